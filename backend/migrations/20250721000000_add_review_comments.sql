@@ -0,0 +1,14 @@
+-- Line-anchored review comments on an attempt's diff
+CREATE TABLE review_comments (
+    id              BLOB PRIMARY KEY,
+    task_attempt_id BLOB NOT NULL,
+    file_path       TEXT NOT NULL,
+    line_number     INTEGER NOT NULL,
+    body            TEXT NOT NULL,
+    resolved        BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_review_comments_task_attempt_id ON review_comments(task_attempt_id);
@@ -0,0 +1,15 @@
+-- Coding agent runs cut short by a dropped network connection. An interruption stays open
+-- until the session is resumed or the attempt is stopped.
+CREATE TABLE network_interruptions (
+    id                   BLOB PRIMARY KEY,
+    task_attempt_id      BLOB NOT NULL,
+    execution_process_id BLOB NOT NULL,
+    error                TEXT NOT NULL,
+    resumed_at           TEXT,
+    abandoned_at         TEXT,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_network_interruptions_task_attempt_id ON network_interruptions(task_attempt_id);
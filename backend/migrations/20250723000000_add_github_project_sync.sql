@@ -0,0 +1,29 @@
+-- Mirroring a project's tasks into a GitHub Projects (v2) board. A link holds the board and
+-- how task statuses map onto its Status field; each synced task has an item on the board.
+CREATE TABLE github_project_links (
+    project_id        BLOB PRIMARY KEY,
+    project_url       TEXT NOT NULL,
+    github_project_id TEXT NOT NULL, -- GraphQL node id of the ProjectV2
+    status_mapping    TEXT NOT NULL DEFAULT '{}', -- JSON object of task status -> option name
+    last_synced_at    TEXT,
+    last_error        TEXT,
+    created_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- No foreign key to tasks: when a task is deleted its row is kept until the sync removes
+-- the item from the board
+CREATE TABLE github_project_items (
+    task_id            BLOB PRIMARY KEY,
+    project_id         BLOB NOT NULL,
+    item_id            TEXT NOT NULL, -- ProjectV2Item node id
+    draft_issue_id     TEXT NOT NULL,
+    synced_title       TEXT NOT NULL,
+    synced_description TEXT,
+    synced_status      TEXT NOT NULL,
+    synced_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_github_project_items_project_id ON github_project_items(project_id);
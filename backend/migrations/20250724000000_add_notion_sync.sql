@@ -0,0 +1,26 @@
+-- Mirroring a project's tasks into a Notion database. A link holds the database and how task
+-- fields map onto its properties; each synced task has a page in the database.
+CREATE TABLE notion_links (
+    project_id       BLOB PRIMARY KEY,
+    database_id      TEXT NOT NULL,
+    property_mapping TEXT NOT NULL DEFAULT '{}', -- JSON NotionPropertyMapping
+    last_synced_at   TEXT,
+    last_error       TEXT,
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- Like github_project_items, rows outlive their task until the sync archives the page
+CREATE TABLE notion_pages (
+    task_id            BLOB PRIMARY KEY,
+    project_id         BLOB NOT NULL,
+    page_id            TEXT NOT NULL,
+    synced_title       TEXT NOT NULL,
+    synced_description TEXT,
+    synced_status      TEXT NOT NULL,
+    synced_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_notion_pages_project_id ON notion_pages(project_id);
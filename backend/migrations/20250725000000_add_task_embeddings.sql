@@ -0,0 +1,10 @@
+-- Embedding vectors of task titles and descriptions, for suggesting similar tasks. A row is
+-- stale once its content hash or model no longer matches and is recomputed on demand.
+CREATE TABLE task_embeddings (
+    task_id      BLOB PRIMARY KEY,
+    model        TEXT NOT NULL,
+    content_hash TEXT NOT NULL,
+    embedding    BLOB NOT NULL, -- little-endian f32s
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
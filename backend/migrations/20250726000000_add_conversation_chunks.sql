@@ -0,0 +1,23 @@
+-- Embedded chunks of normalized coding agent conversations, for semantic search. Processes
+-- are chunked once they finish; indexed_entries records how many of a process's entries are
+-- covered so later indexing only embeds what was added since.
+CREATE TABLE conversation_index_state (
+    execution_process_id BLOB PRIMARY KEY,
+    model                TEXT NOT NULL,
+    indexed_entries      INTEGER NOT NULL,
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE TABLE conversation_chunks (
+    id                   BLOB PRIMARY KEY,
+    execution_process_id BLOB NOT NULL,
+    entry_index          INTEGER NOT NULL, -- first normalized entry in the chunk
+    entry_type           TEXT NOT NULL,    -- JSON NormalizedEntryType of that entry
+    timestamp            TEXT,
+    content              TEXT NOT NULL,
+    embedding            BLOB NOT NULL,    -- little-endian f32s
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_conversation_chunks_execution_process_id ON conversation_chunks(execution_process_id);
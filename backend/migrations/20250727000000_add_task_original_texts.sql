@@ -0,0 +1,9 @@
+-- The title and description a task had before they were rewritten by the summarizer, so a
+-- pasted error dump is never lost. Only the first rewrite is recorded.
+CREATE TABLE task_original_texts (
+    task_id     BLOB PRIMARY KEY,
+    title       TEXT NOT NULL,
+    description TEXT,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
@@ -0,0 +1,19 @@
+-- Free-form labels on tasks, plus labels proposed by the classifiers that run when a task is
+-- created. A suggestion stays pending until the user accepts (it becomes a label) or
+-- dismisses it.
+CREATE TABLE task_labels (
+    task_id    BLOB NOT NULL,
+    name       TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (task_id, name),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE TABLE task_label_suggestions (
+    task_id    BLOB NOT NULL,
+    name       TEXT NOT NULL,
+    reason     TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (task_id, name),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+-- Template for the commits vibe-kanban makes on behalf of the agent (leftover changes after a
+-- run, cleanup script output). NULL falls back to the built-in message.
+ALTER TABLE projects ADD COLUMN commit_message_template TEXT;
@@ -0,0 +1,33 @@
+-- Releases group merged tasks and run the project's release script (tag, build, publish)
+-- from the repository root. The script's output is kept on the release like an execution
+-- process's, and a task counts as released once the release it belongs to succeeds.
+ALTER TABLE projects ADD COLUMN release_script TEXT;
+
+CREATE TABLE releases (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB NOT NULL,
+    version     TEXT NOT NULL,
+    notes       TEXT,
+    status      TEXT NOT NULL DEFAULT 'draft'
+                   CHECK (status IN ('draft', 'running', 'released', 'failed')),
+    stdout      TEXT,
+    stderr      TEXT,
+    exit_code   INTEGER,
+    started_at  TEXT,
+    released_at TEXT,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    UNIQUE (project_id, version),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_releases_project_id ON releases(project_id);
+
+-- A task goes out in at most one release
+CREATE TABLE release_tasks (
+    release_id BLOB NOT NULL,
+    task_id    BLOB NOT NULL UNIQUE,
+    PRIMARY KEY (release_id, task_id),
+    FOREIGN KEY (release_id) REFERENCES releases(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
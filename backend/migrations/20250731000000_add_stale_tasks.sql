@@ -0,0 +1,10 @@
+-- Tasks the stale check found idle in In Progress or In Review for longer than the configured
+-- threshold. A row only counts while the task is still in the status it was flagged in, and
+-- nudged_at keeps the same idle stretch from being nudged twice.
+CREATE TABLE stale_tasks (
+    task_id    BLOB PRIMARY KEY,
+    status     TEXT NOT NULL,
+    idle_since TEXT NOT NULL,
+    nudged_at  TEXT,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
@@ -0,0 +1,22 @@
+-- Files attached to a task (design mockups, error screenshots). The bytes live under the
+-- asset directory; images are handed to executors that can look at them, and each coding
+-- agent run records which attachments it was given.
+CREATE TABLE task_attachments (
+    id           BLOB PRIMARY KEY,
+    task_id      BLOB NOT NULL,
+    file_name    TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size_bytes   INTEGER NOT NULL,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_attachments_task_id ON task_attachments(task_id);
+
+CREATE TABLE execution_process_attachments (
+    execution_process_id BLOB NOT NULL,
+    attachment_id        BLOB NOT NULL,
+    PRIMARY KEY (execution_process_id, attachment_id),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (attachment_id) REFERENCES task_attachments(id) ON DELETE CASCADE
+);
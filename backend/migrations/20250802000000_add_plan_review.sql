@@ -0,0 +1,29 @@
+-- Plan Review: a planning attempt's plan waits here for approval before the same attempt goes
+-- on to implement it.
+
+-- 1. Widen the task status CHECK with the new column, copying existing values across
+ALTER TABLE tasks
+  ADD COLUMN status_new TEXT NOT NULL DEFAULT 'todo'
+    CHECK (status_new IN ('todo','inprogress','planreview','inreview','done','cancelled'));
+
+UPDATE tasks
+  SET status_new = status;
+
+ALTER TABLE tasks DROP COLUMN status;
+
+ALTER TABLE tasks
+  RENAME COLUMN status_new TO status;
+
+-- 2. The plan an attempt produced. It stays pending until approved, and is kept afterwards so
+-- it can be compared against what the implementation actually changed.
+CREATE TABLE attempt_plans (
+    task_attempt_id      BLOB PRIMARY KEY,
+    execution_process_id BLOB NOT NULL,
+    content              TEXT NOT NULL,
+    status               TEXT NOT NULL DEFAULT 'pending'
+                         CHECK (status IN ('pending','approved')),
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    approved_at          TEXT,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+-- Run in an attempt's worktree by the merge queue after rebasing onto the freshly updated
+-- base branch; a non-zero exit keeps the attempt from landing.
+ALTER TABLE projects ADD COLUMN merge_check_script TEXT;
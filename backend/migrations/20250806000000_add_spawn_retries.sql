@@ -0,0 +1,5 @@
+-- Coding agents that fail to start, or exit with an error straight away, are started again
+-- after a backoff. retry_count is how many times this execution has been restarted, and
+-- retry_at is set while it waits for the next try.
+ALTER TABLE execution_processes ADD COLUMN retry_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE execution_processes ADD COLUMN retry_at TEXT;
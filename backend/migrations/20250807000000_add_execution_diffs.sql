@@ -0,0 +1,11 @@
+-- What each coding agent run changed in its worktree. from_commit is HEAD when the run
+-- started; files holds the run's hunks (JSON) once it finished and its changes were committed.
+CREATE TABLE execution_diffs (
+    execution_process_id BLOB PRIMARY KEY,
+    from_commit          TEXT NOT NULL,
+    to_commit            TEXT,
+    files                TEXT,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
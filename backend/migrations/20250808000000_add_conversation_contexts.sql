@@ -0,0 +1,14 @@
+-- Conversations of finished coding agent runs by executors that can't resume a session.
+-- A follow-up replays them to the agent as a condensed transcript instead. entries holds
+-- the run's normalized conversation entries (JSON); prompt is what the run was asked.
+CREATE TABLE conversation_contexts (
+    execution_process_id BLOB PRIMARY KEY,
+    task_attempt_id      BLOB NOT NULL,
+    prompt               TEXT,
+    entries              TEXT NOT NULL DEFAULT '[]',
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_conversation_contexts_task_attempt_id ON conversation_contexts(task_attempt_id);
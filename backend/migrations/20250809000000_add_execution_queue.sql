@@ -0,0 +1,24 @@
+-- Execution queue: attempts started while the concurrency limits were reached wait here, and
+-- their task shows as queued, until a slot frees up.
+
+-- 1. Widen the task status CHECK with the new column, copying existing values across
+ALTER TABLE tasks
+  ADD COLUMN status_new TEXT NOT NULL DEFAULT 'todo'
+    CHECK (status_new IN ('todo','queued','inprogress','planreview','inreview','done','cancelled'));
+
+UPDATE tasks
+  SET status_new = status;
+
+ALTER TABLE tasks DROP COLUMN status;
+
+ALTER TABLE tasks
+  RENAME COLUMN status_new TO status;
+
+-- 2. Queued attempts, dispatched oldest first
+CREATE TABLE execution_queue (
+    task_attempt_id BLOB PRIMARY KEY,
+    project_id      BLOB NOT NULL,
+    queued_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
@@ -0,0 +1,14 @@
+-- Token and cost figures of finished coding agent runs, summed from the usage entries of
+-- their normalized logs. cost_usd is NULL when the executor doesn't report cost.
+CREATE TABLE execution_metrics (
+    execution_process_id BLOB PRIMARY KEY,
+    task_attempt_id      BLOB NOT NULL,
+    input_tokens         INTEGER NOT NULL DEFAULT 0,
+    output_tokens        INTEGER NOT NULL DEFAULT 0,
+    cost_usd             REAL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_execution_metrics_task_attempt_id ON execution_metrics(task_attempt_id);
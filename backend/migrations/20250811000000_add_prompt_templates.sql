@@ -0,0 +1,14 @@
+-- Templates the prompt of a project's coding agents is rendered from. A template with no
+-- executor applies to every executor the project has no template of its own for.
+CREATE TABLE prompt_templates (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB NOT NULL,
+    executor    TEXT,
+    template    TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX idx_prompt_templates_project_executor
+    ON prompt_templates(project_id, COALESCE(executor, ''));
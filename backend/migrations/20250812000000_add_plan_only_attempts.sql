@@ -0,0 +1,2 @@
+-- A plan-only attempt stops at a plan for review; it is only implemented once approved.
+ALTER TABLE task_attempts ADD COLUMN plan_only BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,18 @@
+-- Variables set in the environment of a project's executions. A variable with a task is
+-- only set for that task's executions and overrides the project's variable of the same
+-- key. Secret values are stored encrypted.
+CREATE TABLE environment_variables (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB NOT NULL,
+    task_id     BLOB,
+    key         TEXT NOT NULL,
+    value       TEXT NOT NULL,
+    is_secret   BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX idx_environment_variables_scope_key
+    ON environment_variables(project_id, COALESCE(task_id, x''), key);
@@ -0,0 +1,3 @@
+-- The commit an attempt's worktree was on once a coding agent or cleanup script finished
+-- and its changes were committed, so later runs can start from a known point.
+ALTER TABLE execution_processes ADD COLUMN commit_sha TEXT;
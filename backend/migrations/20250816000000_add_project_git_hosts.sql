@@ -0,0 +1,11 @@
+-- Where a project's pull requests are opened. Projects without a row use GitHub with the
+-- account's token. The token is stored encrypted.
+CREATE TABLE project_git_hosts (
+    project_id  BLOB PRIMARY KEY,
+    kind        TEXT NOT NULL CHECK (kind IN ('github', 'gitlab', 'bitbucket')),
+    base_url    TEXT,
+    token       TEXT,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
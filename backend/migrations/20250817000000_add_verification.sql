@@ -0,0 +1,18 @@
+-- Commands run one per line in an attempt's worktree after its coding agent finishes, to
+-- check the changes build and pass tests before the attempt goes to review.
+ALTER TABLE projects ADD COLUMN verification_script TEXT;
+
+-- The outcome of each verification command, so it can be shown with the agent's output.
+CREATE TABLE execution_verifications (
+    id                   BLOB PRIMARY KEY,
+    execution_process_id BLOB NOT NULL,
+    command              TEXT NOT NULL,
+    passed               BOOLEAN NOT NULL,
+    exit_code            INTEGER,
+    output               TEXT NOT NULL DEFAULT '',
+    duration_ms          INTEGER NOT NULL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_execution_verifications_process ON execution_verifications(execution_process_id);
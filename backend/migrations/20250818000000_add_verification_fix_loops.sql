@@ -0,0 +1,12 @@
+-- An attempt's automatic fixes for failed verification: how many follow-ups were sent and
+-- the prompt made from the latest failure. `pending` marks a follow-up that is due but
+-- hasn't started, so it's still sent after a restart.
+CREATE TABLE verification_fix_loops (
+    task_attempt_id BLOB PRIMARY KEY,
+    fix_count       INTEGER NOT NULL DEFAULT 0,
+    last_failure    TEXT NOT NULL,
+    pending         BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
@@ -0,0 +1,30 @@
+-- What a template sets up for the tasks made from it: the executor their attempts use
+-- unless another is picked, verification commands used instead of the project's, and a
+-- checklist, one item per line.
+ALTER TABLE task_templates ADD COLUMN default_executor TEXT;
+ALTER TABLE task_templates ADD COLUMN verification_script TEXT;
+ALTER TABLE task_templates ADD COLUMN checklist TEXT;
+
+-- The defaults a task was given by the template it was made from
+CREATE TABLE task_defaults (
+    task_id             BLOB PRIMARY KEY,
+    template_id         BLOB,
+    executor            TEXT,
+    verification_script TEXT,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (template_id) REFERENCES task_templates(id) ON DELETE SET NULL
+);
+
+-- A task's checklist, in the order of its template's
+CREATE TABLE task_checklist_items (
+    id           BLOB PRIMARY KEY,
+    task_id      BLOB NOT NULL,
+    position     INTEGER NOT NULL,
+    content      TEXT NOT NULL,
+    completed_at TEXT,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_checklist_items_task_id ON task_checklist_items(task_id, position);
@@ -0,0 +1,20 @@
+-- Subtasks: a task can be split into smaller tasks, by hand or by an agent asked to
+-- decompose it. Deleting a task deletes its subtasks with it.
+ALTER TABLE tasks ADD COLUMN parent_task_id BLOB REFERENCES tasks(id) ON DELETE CASCADE;
+
+CREATE INDEX idx_tasks_parent_task_id ON tasks(parent_task_id);
+
+-- An attempt run to propose a task's subtasks instead of implementing it
+CREATE TABLE task_decompositions (
+    task_attempt_id BLOB PRIMARY KEY,
+    task_id         BLOB NOT NULL,
+    status          TEXT NOT NULL DEFAULT 'pending'
+                    CHECK (status IN ('pending','completed','failed')),
+    subtask_count   INTEGER NOT NULL DEFAULT 0,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    completed_at    TEXT,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_decompositions_task_id ON task_decompositions(task_id);
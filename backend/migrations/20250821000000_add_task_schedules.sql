@@ -0,0 +1,32 @@
+-- Recurring runs: a task with a cron schedule is copied into a fresh subtask and attempt
+-- each time the schedule comes due, and every run is kept in a history.
+CREATE TABLE task_schedules (
+    id              BLOB PRIMARY KEY,
+    task_id         BLOB NOT NULL UNIQUE,
+    cron_expression TEXT NOT NULL,
+    executor        TEXT,
+    enabled         BOOLEAN NOT NULL DEFAULT TRUE,
+    next_run_at     TEXT,
+    last_run_at     TEXT,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE TABLE task_schedule_runs (
+    id              BLOB PRIMARY KEY,
+    schedule_id     BLOB NOT NULL,
+    task_id         BLOB,
+    task_attempt_id BLOB,
+    status          TEXT NOT NULL DEFAULT 'running'
+                    CHECK (status IN ('running','succeeded','failed')),
+    error           TEXT,
+    started_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    finished_at     TEXT,
+    FOREIGN KEY (schedule_id) REFERENCES task_schedules(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE SET NULL,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_task_schedule_runs_schedule_id ON task_schedule_runs(schedule_id, started_at);
+CREATE INDEX idx_task_schedule_runs_task_attempt_id ON task_schedule_runs(task_attempt_id);
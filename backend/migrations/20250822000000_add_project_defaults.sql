@@ -0,0 +1,4 @@
+-- What a project's attempts start from when they don't say: the executor they run and the
+-- branch their worktrees are based on
+ALTER TABLE projects ADD COLUMN default_executor TEXT;
+ALTER TABLE projects ADD COLUMN default_branch TEXT;
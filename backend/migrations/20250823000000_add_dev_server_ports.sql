@@ -0,0 +1,7 @@
+-- The port each dev server was given, so its preview can be proxied to while it runs.
+CREATE TABLE dev_server_ports (
+    execution_process_id BLOB PRIMARY KEY,
+    port                 INTEGER NOT NULL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
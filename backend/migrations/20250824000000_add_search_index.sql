@@ -0,0 +1,39 @@
+-- Full-text search over tasks and coding agent conversations. A task's title and
+-- description are one document, kept in step with the task by triggers; each normalized
+-- conversation entry is a document of its own, added once its process has finished.
+CREATE VIRTUAL TABLE search_documents USING fts5(
+    title,
+    content,
+    task_id UNINDEXED,
+    execution_process_id UNINDEXED,
+    entry_index UNINDEXED,
+    entry_type UNINDEXED,
+    timestamp UNINDEXED,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+-- How many of a process's conversation entries are in the search index
+CREATE TABLE search_index_state (
+    execution_process_id BLOB PRIMARY KEY,
+    indexed_entries      INTEGER NOT NULL,
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE TRIGGER search_documents_task_insert AFTER INSERT ON tasks BEGIN
+    INSERT INTO search_documents (title, content, task_id)
+    VALUES (NEW.title, COALESCE(NEW.description, ''), NEW.id);
+END;
+
+CREATE TRIGGER search_documents_task_update AFTER UPDATE OF title, description ON tasks BEGIN
+    DELETE FROM search_documents WHERE task_id = OLD.id AND execution_process_id IS NULL;
+    INSERT INTO search_documents (title, content, task_id)
+    VALUES (NEW.title, COALESCE(NEW.description, ''), NEW.id);
+END;
+
+CREATE TRIGGER search_documents_task_delete AFTER DELETE ON tasks BEGIN
+    DELETE FROM search_documents WHERE task_id = OLD.id;
+END;
+
+INSERT INTO search_documents (title, content, task_id)
+SELECT title, COALESCE(description, ''), id FROM tasks;
@@ -0,0 +1,29 @@
+-- An append-only record of state transitions, for the activity feed and as an audit trail.
+-- Each event carries a hash over its contents and the previous event's hash, so an edited or
+-- removed event breaks the chain. Events outlive the tasks and projects they are about.
+CREATE TABLE events (
+    id                   INTEGER PRIMARY KEY AUTOINCREMENT,
+    project_id           BLOB NOT NULL,
+    task_id              BLOB,
+    task_attempt_id      BLOB,
+    execution_process_id BLOB,
+    kind                 TEXT NOT NULL
+                         CHECK (kind IN ('task_created','task_status_changed','task_deleted',
+                                         'execution_started','execution_completed',
+                                         'execution_failed','execution_stopped','pr_opened')),
+    actor                TEXT NOT NULL CHECK (actor IN ('user','system')),
+    details              TEXT NOT NULL DEFAULT '{}',
+    created_at           TEXT NOT NULL,
+    prev_hash            TEXT,
+    hash                 TEXT NOT NULL
+);
+
+CREATE INDEX idx_events_project_id ON events(project_id, id);
+
+CREATE TRIGGER events_no_update BEFORE UPDATE ON events BEGIN
+    SELECT RAISE(ABORT, 'events are append-only');
+END;
+
+CREATE TRIGGER events_no_delete BEFORE DELETE ON events BEGIN
+    SELECT RAISE(ABORT, 'events are append-only');
+END;
@@ -0,0 +1,44 @@
+-- Outbound webhooks a project posts to when its attempts finish, fail or wait for approval,
+-- in a JSON shape Slack or Discord incoming webhooks accept.
+CREATE TABLE notification_webhooks (
+    id                BLOB PRIMARY KEY,
+    project_id        BLOB NOT NULL,
+    url               TEXT NOT NULL,
+    format            TEXT NOT NULL CHECK (format IN ('slack', 'discord')),
+    enabled           BOOLEAN NOT NULL DEFAULT TRUE,
+    attempt_succeeded BOOLEAN NOT NULL DEFAULT TRUE,
+    attempt_failed    BOOLEAN NOT NULL DEFAULT TRUE,
+    approval_needed   BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_notification_webhooks_project_id ON notification_webhooks(project_id);
+
+-- Every notification sent, desktop or webhook. Webhook deliveries start out pending and are
+-- retried with backoff until they go through or run out of attempts.
+CREATE TABLE notification_deliveries (
+    id              BLOB PRIMARY KEY,
+    project_id      BLOB NOT NULL,
+    task_attempt_id BLOB,
+    webhook_id      BLOB,
+    channel         TEXT NOT NULL CHECK (channel IN ('desktop', 'webhook')),
+    event           TEXT NOT NULL
+                    CHECK (event IN ('attempt_succeeded', 'attempt_failed', 'approval_needed', 'task_stale')),
+    title           TEXT NOT NULL,
+    message         TEXT NOT NULL,
+    status          TEXT NOT NULL CHECK (status IN ('pending', 'sent', 'failed')),
+    attempts        INTEGER NOT NULL DEFAULT 0,
+    last_error      TEXT,
+    next_attempt_at TEXT,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    sent_at         TEXT,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE SET NULL,
+    FOREIGN KEY (webhook_id) REFERENCES notification_webhooks(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_notification_deliveries_project_id ON notification_deliveries(project_id, created_at);
+CREATE INDEX idx_notification_deliveries_pending ON notification_deliveries(next_attempt_at)
+    WHERE status = 'pending';
@@ -0,0 +1,30 @@
+-- Execution output is stored as the chunks it was appended in, instead of one column that
+-- was rewritten in full on every append. Long runs are trimmed to their beginning and their
+-- latest output; a marker chunk stands in for what was dropped between the two.
+CREATE TABLE execution_log_chunks (
+    id                   INTEGER PRIMARY KEY AUTOINCREMENT, -- Orders the chunks of a stream
+    execution_process_id BLOB NOT NULL,
+    stream               TEXT NOT NULL CHECK (stream IN ('stdout', 'stderr')),
+    content              TEXT NOT NULL,
+    size                 INTEGER NOT NULL,           -- Bytes of content
+    omitted_bytes        INTEGER NOT NULL DEFAULT 0, -- Set on markers: bytes trimmed in their place
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_execution_log_chunks_process ON execution_log_chunks(execution_process_id, stream, id);
+
+-- Existing output becomes each stream's first chunk
+INSERT INTO execution_log_chunks (execution_process_id, stream, content, size)
+SELECT id, 'stdout', stdout, LENGTH(CAST(stdout AS BLOB))
+FROM execution_processes
+WHERE stdout IS NOT NULL AND stdout != '';
+
+INSERT INTO execution_log_chunks (execution_process_id, stream, content, size)
+SELECT id, 'stderr', stderr, LENGTH(CAST(stderr AS BLOB))
+FROM execution_processes
+WHERE stderr IS NOT NULL AND stderr != '';
+
+UPDATE execution_processes
+SET stdout = NULL, stderr = NULL
+WHERE stdout IS NOT NULL OR stderr IS NOT NULL;
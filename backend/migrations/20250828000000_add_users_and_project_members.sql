@@ -0,0 +1,36 @@
+-- Accounts for a server shared by a team. Local accounts sign in with a password; accounts
+-- created through an OIDC provider are matched on the provider's subject instead.
+CREATE TABLE users (
+    id            BLOB PRIMARY KEY,
+    username      TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    display_name  TEXT,
+    password_hash TEXT,
+    oidc_subject  TEXT UNIQUE,
+    is_admin      BOOLEAN NOT NULL DEFAULT FALSE, -- Admin of every project, and manages accounts
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+-- Signed-in sessions. Only a hash of the token is kept; the token itself is the client's.
+CREATE TABLE user_sessions (
+    token_hash TEXT PRIMARY KEY,
+    user_id    BLOB NOT NULL,
+    expires_at TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_user_sessions_user_id ON user_sessions(user_id);
+
+CREATE TABLE project_members (
+    project_id BLOB NOT NULL,
+    user_id    BLOB NOT NULL,
+    role       TEXT NOT NULL CHECK (role IN ('admin', 'member', 'viewer')),
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (project_id, user_id),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_project_members_user_id ON project_members(user_id);
@@ -0,0 +1,30 @@
+-- Personal access tokens for scripts and CI. A token acts as its user, narrowed to its
+-- scopes. Only a hash of the token is kept; revoked tokens stay for the audit trail.
+CREATE TABLE api_tokens (
+    id           BLOB PRIMARY KEY,
+    user_id      BLOB NOT NULL,
+    name         TEXT NOT NULL,
+    token_hash   TEXT NOT NULL UNIQUE,
+    token_prefix TEXT NOT NULL, -- The token's first characters, to tell tokens apart
+    scopes       TEXT NOT NULL DEFAULT '[]', -- JSON array of scopes
+    expires_at   TEXT,
+    last_used_at TEXT,
+    revoked_at   TEXT,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_api_tokens_user_id ON api_tokens(user_id);
+
+-- Every request made with a token, allowed or not
+CREATE TABLE api_token_uses (
+    id         INTEGER PRIMARY KEY AUTOINCREMENT,
+    token_id   BLOB NOT NULL,
+    method     TEXT NOT NULL,
+    path       TEXT NOT NULL,
+    status     INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (token_id) REFERENCES api_tokens(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_api_token_uses_token_id ON api_token_uses(token_id, id);
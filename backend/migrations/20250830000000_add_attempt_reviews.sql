@@ -0,0 +1,43 @@
+-- Review decisions on attempts: approving merges the attempt's changes, rejecting can send
+-- the reviewer's comments back to the agent. Both are recorded in the audit trail.
+
+-- 1. Widen the event kind CHECK with the new column. The append-only triggers are lifted
+--    while the existing kinds are copied across, and put back after.
+DROP TRIGGER events_no_update;
+DROP TRIGGER events_no_delete;
+
+ALTER TABLE events
+  ADD COLUMN kind_new TEXT NOT NULL DEFAULT 'task_created'
+    CHECK (kind_new IN ('task_created','task_status_changed','task_deleted',
+                        'execution_started','execution_completed',
+                        'execution_failed','execution_stopped','pr_opened',
+                        'review_approved','review_rejected'));
+
+UPDATE events
+  SET kind_new = kind;
+
+ALTER TABLE events DROP COLUMN kind;
+
+ALTER TABLE events
+  RENAME COLUMN kind_new TO kind;
+
+CREATE TRIGGER events_no_update BEFORE UPDATE ON events BEGIN
+    SELECT RAISE(ABORT, 'events are append-only');
+END;
+
+CREATE TRIGGER events_no_delete BEFORE DELETE ON events BEGIN
+    SELECT RAISE(ABORT, 'events are append-only');
+END;
+
+-- 2. The decisions, newest last
+CREATE TABLE attempt_reviews (
+    id              BLOB PRIMARY KEY,
+    task_attempt_id BLOB NOT NULL,
+    decision        TEXT NOT NULL CHECK (decision IN ('approved', 'rejected')),
+    reviewer        TEXT, -- The reviewer's username, when sign-in is on
+    comment         TEXT,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_attempt_reviews_task_attempt_id ON attempt_reviews(task_attempt_id);
@@ -0,0 +1,4 @@
+-- The coding agent run whose captured diff a comment was left on; NULL when it is on the
+-- attempt's diff as a whole
+ALTER TABLE review_comments
+    ADD COLUMN execution_process_id BLOB REFERENCES execution_processes(id) ON DELETE SET NULL;
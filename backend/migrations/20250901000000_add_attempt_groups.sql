@@ -0,0 +1,21 @@
+-- Attempts started together on one task with different coding agents, so their changes
+-- can be compared. winner_attempt_id is the one picked as the task's canonical attempt.
+CREATE TABLE attempt_groups (
+    id                BLOB PRIMARY KEY,
+    task_id           BLOB NOT NULL,
+    winner_attempt_id BLOB,
+    decided_at        TEXT,
+    created_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (winner_attempt_id) REFERENCES task_attempts(id) ON DELETE SET NULL
+);
+
+CREATE TABLE attempt_group_members (
+    task_attempt_id BLOB PRIMARY KEY,
+    group_id        BLOB NOT NULL,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE,
+    FOREIGN KEY (group_id) REFERENCES attempt_groups(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_attempt_groups_task_id ON attempt_groups(task_id);
+CREATE INDEX idx_attempt_group_members_group_id ON attempt_group_members(group_id);
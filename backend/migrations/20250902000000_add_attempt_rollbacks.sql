@@ -0,0 +1,45 @@
+-- Rolling an attempt back to what an earlier run left, when a follow-up made things worse.
+-- The commit the attempt was on is kept under a ref, so nothing a run did is lost.
+
+-- 1. Widen the event kind CHECK with the new column. The append-only triggers are lifted
+--    while the existing kinds are copied across, and put back after.
+DROP TRIGGER events_no_update;
+DROP TRIGGER events_no_delete;
+
+ALTER TABLE events
+  ADD COLUMN kind_new TEXT NOT NULL DEFAULT 'task_created'
+    CHECK (kind_new IN ('task_created','task_status_changed','task_deleted',
+                        'execution_started','execution_completed',
+                        'execution_failed','execution_stopped','pr_opened',
+                        'review_approved','review_rejected','attempt_rolled_back'));
+
+UPDATE events
+  SET kind_new = kind;
+
+ALTER TABLE events DROP COLUMN kind;
+
+ALTER TABLE events
+  RENAME COLUMN kind_new TO kind;
+
+CREATE TRIGGER events_no_update BEFORE UPDATE ON events BEGIN
+    SELECT RAISE(ABORT, 'events are append-only');
+END;
+
+CREATE TRIGGER events_no_delete BEFORE DELETE ON events BEGIN
+    SELECT RAISE(ABORT, 'events are append-only');
+END;
+
+-- 2. The rollbacks, newest last
+CREATE TABLE attempt_rollbacks (
+    id                   BLOB PRIMARY KEY,
+    task_attempt_id      BLOB NOT NULL,
+    execution_process_id BLOB,          -- The run whose commit the attempt went back to
+    from_commit          TEXT NOT NULL,
+    to_commit            TEXT NOT NULL,
+    backup_ref           TEXT NOT NULL, -- Keeps from_commit reachable
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_attempt_rollbacks_task_attempt_id ON attempt_rollbacks(task_attempt_id);
@@ -0,0 +1,16 @@
+-- MCP servers handed to the coding agents of a project's attempts, for the agents whose
+-- CLI takes MCP servers on its command line
+CREATE TABLE project_mcp_servers (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB NOT NULL,
+    name        TEXT NOT NULL,
+    command     TEXT NOT NULL,
+    args        TEXT NOT NULL DEFAULT '[]', -- JSON array of arguments
+    env         TEXT NOT NULL DEFAULT '{}', -- JSON object of environment variables
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX idx_project_mcp_servers_project_name
+    ON project_mcp_servers(project_id, name);
@@ -0,0 +1,10 @@
+-- Whether a project's coding agents run sandboxed, overriding the global sandbox setting.
+-- Projects without a row follow the global setting.
+CREATE TABLE project_sandboxes (
+    project_id    BLOB PRIMARY KEY,
+    enabled       BOOLEAN NOT NULL,
+    deny_network  BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
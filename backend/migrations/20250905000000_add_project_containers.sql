@@ -0,0 +1,9 @@
+-- The Docker image a project's coding agents and scripts run in. Projects without a row
+-- run them on the host.
+CREATE TABLE project_containers (
+    project_id  BLOB PRIMARY KEY,
+    image       TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
@@ -0,0 +1,11 @@
+-- The remote worker (a cloud-runner) a project's coding agents and scripts are dispatched
+-- to. Projects without a row run them on this machine.
+CREATE TABLE project_workers (
+    project_id   BLOB PRIMARY KEY,
+    url          TEXT NOT NULL,
+    local_root   TEXT,
+    remote_root  TEXT,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
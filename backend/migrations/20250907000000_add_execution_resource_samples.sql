@@ -0,0 +1,13 @@
+-- CPU and memory use of an execution's process group, sampled while it runs
+CREATE TABLE execution_resource_samples (
+    id                   INTEGER PRIMARY KEY AUTOINCREMENT,
+    execution_process_id BLOB NOT NULL,
+    cpu_percent          REAL NOT NULL,    -- Of one core, since the previous sample
+    cpu_seconds          REAL NOT NULL,    -- CPU time used so far by the processes still running
+    rss_bytes            INTEGER NOT NULL,
+    sampled_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_execution_resource_samples_process
+    ON execution_resource_samples(execution_process_id, id);
@@ -0,0 +1,11 @@
+-- Why a failed execution failed, where its output or its start tells: the agent's CLI
+-- missing, its login rejected, its provider's rate limit or context window, a git conflict,
+-- the network, or the process not starting at all.
+ALTER TABLE execution_processes ADD COLUMN failure_reason TEXT
+    CHECK (failure_reason IN ('missing_binary',
+                              'auth_failure',
+                              'rate_limited',
+                              'context_length_exceeded',
+                              'git_conflict',
+                              'network_error',
+                              'spawn_failed'));
@@ -0,0 +1,11 @@
+-- The pull request title and description generated for a task from one of its attempts,
+-- used when that attempt's pull request is opened. Generating again replaces it.
+CREATE TABLE task_pr_descriptions (
+    task_id         BLOB PRIMARY KEY,
+    task_attempt_id BLOB NOT NULL,
+    title           TEXT NOT NULL,
+    body            TEXT NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
@@ -0,0 +1,4 @@
+-- The model an attempt's agent was asked to run with, e.g. opus or o4-mini, and the one each
+-- coding agent execution actually ran with, so usage can be broken down by model.
+ALTER TABLE task_attempts ADD COLUMN model TEXT;
+ALTER TABLE execution_processes ADD COLUMN model TEXT;
@@ -0,0 +1,28 @@
+-- Named executor setups such as a cheap one for triage and a thorough one for deep work:
+-- the executor, the model it runs with, flags after its CLI's own and variables set for
+-- its runs. Profiles without a project can be used by every project.
+CREATE TABLE executor_profiles (
+    id         BLOB PRIMARY KEY,
+    project_id BLOB,
+    name       TEXT NOT NULL,
+    executor   TEXT NOT NULL,
+    model      TEXT,
+    extra_args TEXT NOT NULL DEFAULT '[]', -- JSON array of flags
+    env        TEXT NOT NULL DEFAULT '{}', -- JSON object of variable names to values
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX idx_executor_profiles_unique_name_project
+ON executor_profiles(project_id, name)
+WHERE project_id IS NOT NULL;
+
+CREATE UNIQUE INDEX idx_executor_profiles_unique_name_global
+ON executor_profiles(name)
+WHERE project_id IS NULL;
+
+-- The profile a task's attempts run with unless they pick an executor, and the profile each
+-- attempt was started with
+ALTER TABLE task_defaults ADD COLUMN executor_profile_id BLOB REFERENCES executor_profiles(id) ON DELETE SET NULL;
+ALTER TABLE task_attempts ADD COLUMN executor_profile_id BLOB REFERENCES executor_profiles(id) ON DELETE SET NULL;
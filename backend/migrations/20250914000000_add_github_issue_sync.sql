@@ -0,0 +1,29 @@
+-- Importing a repository's GitHub issues that carry a label as tasks. A link holds the
+-- repository and label; each imported issue remembers the task it became.
+CREATE TABLE github_issue_links (
+    project_id     BLOB PRIMARY KEY,
+    repo_owner     TEXT NOT NULL,
+    repo_name      TEXT NOT NULL,
+    label          TEXT NOT NULL,
+    last_synced_at TEXT,
+    last_error     TEXT,
+    created_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- No foreign key to tasks: when a task is deleted its row is kept so the issue isn't
+-- imported again
+CREATE TABLE github_issue_tasks (
+    project_id   BLOB NOT NULL,
+    issue_number INTEGER NOT NULL,
+    task_id      BLOB NOT NULL,
+    synced_title TEXT NOT NULL,
+    synced_body  TEXT,
+    issue_closed BOOLEAN NOT NULL DEFAULT FALSE,
+    synced_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (project_id, issue_number),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_github_issue_tasks_task_id ON github_issue_tasks(task_id);
@@ -0,0 +1,27 @@
+-- Jira projects and Linear teams whose issues are imported into a project. Re-syncing only
+-- asks the tracker for issues updated since the last sync.
+CREATE TABLE tracker_imports (
+    project_id     BLOB NOT NULL,
+    tracker        TEXT NOT NULL CHECK (tracker IN ('jira', 'linear')),
+    source_key     TEXT NOT NULL, -- Jira project key or Linear team key
+    last_synced_at TEXT, -- when the last successful sync started
+    last_error     TEXT,
+    created_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (project_id, tracker, source_key),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- The task each imported issue became. No foreign key to tasks: when a task is deleted its
+-- row is kept so the issue isn't imported again.
+CREATE TABLE tracker_issues (
+    project_id          BLOB NOT NULL,
+    tracker             TEXT NOT NULL,
+    issue_id            TEXT NOT NULL, -- the tracker's own id, stable across key changes
+    issue_key           TEXT NOT NULL, -- e.g. ENG-42
+    task_id             BLOB NOT NULL,
+    synced_title        TEXT NOT NULL,
+    synced_description  TEXT,
+    synced_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (project_id, tracker, issue_id),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
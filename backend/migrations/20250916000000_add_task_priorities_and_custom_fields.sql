@@ -0,0 +1,25 @@
+-- Tasks without a priority sort after those with one
+ALTER TABLE tasks ADD COLUMN priority TEXT CHECK (priority IN ('low', 'medium', 'high', 'urgent'));
+
+-- Fields a project's tasks can have values for. A select field's options are a JSON array
+-- of strings.
+CREATE TABLE project_custom_fields (
+    project_id BLOB NOT NULL,
+    name       TEXT NOT NULL,
+    field_type TEXT NOT NULL CHECK (field_type IN ('text', 'number', 'boolean', 'date', 'select')),
+    options    TEXT NOT NULL DEFAULT '[]',
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (project_id, name),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- Values are stored as text in the field type's canonical form: numbers as written,
+-- booleans as 'true' or 'false', dates as YYYY-MM-DD
+CREATE TABLE task_custom_field_values (
+    task_id    BLOB NOT NULL,
+    name       TEXT NOT NULL,
+    value      TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (task_id, name),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
@@ -0,0 +1,21 @@
+-- A project's own board columns. Without any the board shows one column per task status.
+-- A task dropped in a column takes the column's status; conditions is a JSON array of
+-- the workflow states (e.g. 'execution_running', 'verification_failed') that place a task
+-- in the column regardless of its status.
+CREATE TABLE project_board_columns (
+    id         BLOB PRIMARY KEY,
+    project_id BLOB NOT NULL,
+    name       TEXT NOT NULL,
+    position   INTEGER NOT NULL,
+    status     TEXT NOT NULL CHECK (status IN ('todo', 'queued', 'inprogress', 'planreview', 'inreview', 'done', 'cancelled')),
+    wip_limit  INTEGER CHECK (wip_limit IS NULL OR wip_limit > 0),
+    conditions TEXT NOT NULL DEFAULT '[]',
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_project_board_columns_project_id ON project_board_columns(project_id, position);
+
+-- The column a task was moved to by hand. It is cleared when the task's status changes,
+-- so the task follows its workflow again.
+ALTER TABLE tasks ADD COLUMN board_column_id BLOB REFERENCES project_board_columns(id) ON DELETE SET NULL;
@@ -0,0 +1,7 @@
+-- Wall-clock time of a finished execution, pauses included. Kept apart from started_at,
+-- which a retried start resets.
+ALTER TABLE execution_processes ADD COLUMN duration_ms INTEGER;
+
+UPDATE execution_processes
+   SET duration_ms = CAST(ROUND((julianday(completed_at) - julianday(started_at)) * 86400000.0) AS INTEGER)
+ WHERE completed_at IS NOT NULL;
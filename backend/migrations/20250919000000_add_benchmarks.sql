@@ -0,0 +1,53 @@
+-- Benchmark suites: tasks with their own verification commands, run across executors and
+-- profiles to compare how each of them does on the project's own code
+CREATE TABLE benchmark_suites (
+    id         BLOB PRIMARY KEY,
+    project_id BLOB NOT NULL,
+    name       TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE (project_id, name)
+);
+
+CREATE TABLE benchmark_cases (
+    id                  BLOB PRIMARY KEY,
+    suite_id            BLOB NOT NULL,
+    position            INTEGER NOT NULL,
+    title               TEXT NOT NULL,
+    prompt              TEXT NOT NULL,
+    verification_script TEXT NOT NULL,
+    FOREIGN KEY (suite_id) REFERENCES benchmark_suites(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_benchmark_cases_suite_id ON benchmark_cases(suite_id, position);
+
+CREATE TABLE benchmark_runs (
+    id          BLOB PRIMARY KEY,
+    suite_id    BLOB NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    finished_at TEXT,
+    FOREIGN KEY (suite_id) REFERENCES benchmark_suites(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_benchmark_runs_suite_id ON benchmark_runs(suite_id, created_at);
+
+-- One case run by one contestant. The case's title is copied so results outlive edits to
+-- the suite; the task and attempt go when the benchmark's tasks are cleaned up.
+CREATE TABLE benchmark_results (
+    id              BLOB PRIMARY KEY,
+    run_id          BLOB NOT NULL,
+    case_title      TEXT NOT NULL,
+    contestant      TEXT NOT NULL,
+    task_id         BLOB REFERENCES tasks(id) ON DELETE SET NULL,
+    task_attempt_id BLOB REFERENCES task_attempts(id) ON DELETE SET NULL,
+    status          TEXT NOT NULL DEFAULT 'running' CHECK (status IN ('running', 'passed', 'failed')),
+    duration_ms     INTEGER,
+    cost_usd        REAL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    finished_at     TEXT,
+    FOREIGN KEY (run_id) REFERENCES benchmark_runs(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_benchmark_results_run_id ON benchmark_results(run_id);
+CREATE INDEX idx_benchmark_results_task_attempt_id ON benchmark_results(task_attempt_id);
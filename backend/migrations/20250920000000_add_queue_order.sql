@@ -0,0 +1,13 @@
+-- Queue order: an attempt is queued ahead of those whose task has a lower priority, and the
+-- queue can be reordered by hand. A run paused to make room for a more urgent attempt waits
+-- in the queue too, until it's resumed.
+ALTER TABLE execution_queue ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE execution_queue
+  ADD COLUMN paused_process_id BLOB REFERENCES execution_processes(id) ON DELETE CASCADE;
+
+UPDATE execution_queue
+   SET position = (
+     SELECT COUNT(*)
+       FROM execution_queue earlier
+      WHERE earlier.queued_at <= execution_queue.queued_at
+   );
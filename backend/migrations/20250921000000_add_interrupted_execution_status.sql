@@ -0,0 +1,36 @@
+-- A run cut off by the server stopping is 'interrupted'. Its process group is kept so what's
+-- left of it can be killed when the server starts again.
+
+-- 1. Add the replacement column with the wider CHECK
+ALTER TABLE execution_processes
+  ADD COLUMN status_new TEXT NOT NULL DEFAULT 'running'
+    CHECK (status_new IN ('running',
+                          'completed',
+                          'failed',
+                          'killed',
+                          'timedout',
+                          'paused',
+                          'resourcelimitexceeded',
+                          'ratelimited',
+                          'interrupted'));
+
+-- 2. Copy existing values across
+UPDATE execution_processes
+  SET status_new = status;
+
+-- 3. Drop any indexes that mention the old column
+DROP INDEX IF EXISTS idx_execution_processes_status;
+
+-- 4. Remove the old column (requires 3.35+)
+ALTER TABLE execution_processes DROP COLUMN status;
+
+-- 5. Rename the new column back to the canonical name
+ALTER TABLE execution_processes
+  RENAME COLUMN status_new TO status;
+
+-- 6. Re-create the index
+CREATE INDEX idx_execution_processes_status
+        ON execution_processes(status);
+
+-- The group the process leads, by its pid, where it runs on this machine
+ALTER TABLE execution_processes ADD COLUMN process_group INTEGER;
@@ -0,0 +1,11 @@
+-- Where a project's commands get the user's shell environment from, overriding the global
+-- setting. Projects without a row follow the global setting.
+CREATE TABLE project_shell_environments (
+    project_id    BLOB PRIMARY KEY,
+    login_shell   TEXT NOT NULL DEFAULT 'auto'
+                     CHECK (login_shell IN ('auto', 'always', 'never')),
+    env_file      TEXT,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
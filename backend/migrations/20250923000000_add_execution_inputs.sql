@@ -0,0 +1,13 @@
+-- Messages the user sent to a running coding agent on its stdin, so they can be shown in
+-- its conversation where they were sent.
+CREATE TABLE execution_inputs (
+    id                   BLOB PRIMARY KEY,
+    execution_process_id BLOB NOT NULL,
+    content              TEXT NOT NULL,
+    -- How many entries of the run's output there were when it was sent
+    entry_index          INTEGER NOT NULL DEFAULT 0,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_execution_inputs_process ON execution_inputs(execution_process_id);
@@ -0,0 +1,7 @@
+-- Archived tasks are kept but left off the board. Deleted tasks sit in the trash, off the
+-- board and out of search, until they're restored or deleted for good.
+ALTER TABLE tasks ADD COLUMN archived_at TEXT;
+ALTER TABLE tasks ADD COLUMN deleted_at TEXT;
+
+CREATE INDEX idx_tasks_archived_at ON tasks(project_id, archived_at);
+CREATE INDEX idx_tasks_deleted_at ON tasks(project_id, deleted_at);
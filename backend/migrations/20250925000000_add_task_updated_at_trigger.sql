@@ -0,0 +1,8 @@
+-- Clients send a task's updated_at back with an edit to detect edits made in between, so every
+-- change has to move it: updates that leave it as it was, or set it to a CURRENT_TIMESTAMP
+-- within the same second, get a fresh one with subsecond precision.
+CREATE TRIGGER tasks_touch_updated_at AFTER UPDATE ON tasks
+WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE tasks SET updated_at = datetime('now', 'subsec') WHERE id = NEW.id;
+END;
@@ -0,0 +1,12 @@
+-- A follow-up being written for a task, kept between visits until it's sent or discarded.
+-- Quotes (JSON) name conversation entries and diff hunks of the attempt, expanded into the
+-- prompt when it's sent.
+CREATE TABLE follow_up_drafts (
+    task_id         BLOB PRIMARY KEY,
+    task_attempt_id BLOB,
+    prompt          TEXT NOT NULL,
+    quotes          TEXT NOT NULL DEFAULT '[]',
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE SET NULL
+);
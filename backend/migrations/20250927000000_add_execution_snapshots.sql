@@ -0,0 +1,45 @@
+-- The worktree as each coding agent run found it, uncommitted changes included, so one
+-- run's changes can be reverted without losing what the runs before or after it did.
+
+-- 1. Widen the event kind CHECK with the new kind, the same way as for rollbacks
+DROP TRIGGER events_no_update;
+DROP TRIGGER events_no_delete;
+
+ALTER TABLE events
+  ADD COLUMN kind_new TEXT NOT NULL DEFAULT 'task_created'
+    CHECK (kind_new IN ('task_created','task_status_changed','task_deleted',
+                        'execution_started','execution_completed',
+                        'execution_failed','execution_stopped','pr_opened',
+                        'review_approved','review_rejected','attempt_rolled_back',
+                        'execution_reverted'));
+
+UPDATE events
+  SET kind_new = kind;
+
+ALTER TABLE events DROP COLUMN kind;
+
+ALTER TABLE events
+  RENAME COLUMN kind_new TO kind;
+
+CREATE TRIGGER events_no_update BEFORE UPDATE ON events BEGIN
+    SELECT RAISE(ABORT, 'events are append-only');
+END;
+
+CREATE TRIGGER events_no_delete BEFORE DELETE ON events BEGIN
+    SELECT RAISE(ABORT, 'events are append-only');
+END;
+
+-- 2. One snapshot per run, taken before it starts
+CREATE TABLE execution_snapshots (
+    execution_process_id BLOB PRIMARY KEY,
+    task_attempt_id      BLOB NOT NULL,
+    snapshot_commit      TEXT NOT NULL,
+    snapshot_ref         TEXT NOT NULL, -- Keeps snapshot_commit reachable
+    revert_commit        TEXT,          -- The commit undoing the run's changes, once reverted
+    reverted_at          TEXT,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_execution_snapshots_task_attempt_id ON execution_snapshots(task_attempt_id);
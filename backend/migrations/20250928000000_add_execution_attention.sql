@@ -0,0 +1,19 @@
+-- Executions waiting on the user: a dangerous command held for approval, or a plan waiting
+-- to be reviewed. Open until the user answers, and escalated once if they take too long.
+CREATE TABLE execution_attention (
+    id                   BLOB PRIMARY KEY,
+    execution_process_id BLOB NOT NULL,
+    task_attempt_id      BLOB NOT NULL,
+    kind                 TEXT NOT NULL CHECK (kind IN ('command_approval', 'plan_review')),
+    title                TEXT NOT NULL,
+    message              TEXT NOT NULL,
+    requested_at         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    escalated_at         TEXT,
+    resolved_at          TEXT,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_execution_attention_open ON execution_attention(requested_at)
+    WHERE resolved_at IS NULL;
+CREATE INDEX idx_execution_attention_task_attempt_id ON execution_attention(task_attempt_id);
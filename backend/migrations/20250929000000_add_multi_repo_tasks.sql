@@ -0,0 +1,22 @@
+-- Tasks spanning several repositories. A task names the other projects it touches, and each
+-- of its attempts checks all of them out side by side, on the attempt's branch.
+CREATE TABLE task_linked_projects (
+    task_id    BLOB NOT NULL,
+    project_id BLOB NOT NULL, -- Another project than the task's own
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (task_id, project_id),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- The worktree of each linked project an attempt checked out, next to its own
+CREATE TABLE attempt_linked_worktrees (
+    task_attempt_id BLOB NOT NULL,
+    project_id      BLOB NOT NULL,
+    worktree_path   TEXT NOT NULL,
+    base_branch     TEXT NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (task_attempt_id, project_id),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
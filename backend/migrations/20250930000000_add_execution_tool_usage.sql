@@ -0,0 +1,18 @@
+-- What each coding agent run did with its tools, counted from its normalized conversation,
+-- to tell runs that changed code from runs that only talked
+CREATE TABLE execution_tool_usage (
+    execution_process_id BLOB PRIMARY KEY,
+    task_attempt_id      BLOB NOT NULL,
+    files_read           INTEGER NOT NULL DEFAULT 0, -- Distinct paths
+    files_written        INTEGER NOT NULL DEFAULT 0, -- Distinct paths
+    commands_run         INTEGER NOT NULL DEFAULT 0,
+    searches             INTEGER NOT NULL DEFAULT 0,
+    web_fetches          INTEGER NOT NULL DEFAULT 0,
+    other_tools          INTEGER NOT NULL DEFAULT 0,
+    assistant_messages   INTEGER NOT NULL DEFAULT 0,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_execution_tool_usage_task_attempt_id ON execution_tool_usage(task_attempt_id);
@@ -0,0 +1 @@
+ALTER TABLE execution_log_chunks DROP COLUMN received_at;
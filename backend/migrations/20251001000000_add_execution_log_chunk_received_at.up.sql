@@ -0,0 +1,3 @@
+-- When each chunk of output was received from the process, to time the entries normalized
+-- from it. Chunks stored before this have none.
+ALTER TABLE execution_log_chunks ADD COLUMN received_at TEXT;
@@ -0,0 +1 @@
+DROP TABLE task_share_links;
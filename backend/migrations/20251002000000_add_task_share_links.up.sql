@@ -0,0 +1,18 @@
+-- Read-only links to a task's conversations and diffs, for people without access to the
+-- board. Only a hash of the link's token is kept; revoked links stay listed.
+CREATE TABLE task_share_links (
+    id             BLOB PRIMARY KEY,
+    task_id        BLOB NOT NULL,
+    token_hash     TEXT NOT NULL UNIQUE,
+    token_prefix   TEXT NOT NULL, -- The token's first characters, to tell links apart
+    created_by     BLOB,
+    expires_at     TEXT,
+    revoked_at     TEXT,
+    last_viewed_at TEXT,
+    view_count     INTEGER NOT NULL DEFAULT 0,
+    created_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_task_share_links_task_id ON task_share_links(task_id);
@@ -0,0 +1,10 @@
+-- Limits on when and how much a project's coding agents may run. Attempts of a project
+-- over its monthly budget, or outside its allowed windows, wait in the execution queue.
+CREATE TABLE project_guardrails (
+    project_id         BLOB PRIMARY KEY,
+    monthly_budget_usd REAL,
+    allowed_windows    TEXT NOT NULL DEFAULT '[]', -- JSON array of ExecutionWindow
+    created_at         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
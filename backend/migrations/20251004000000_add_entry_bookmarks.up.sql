@@ -0,0 +1,19 @@
+-- Entries of a task's coding agent conversations bookmarked for later: key decisions,
+-- important commands. The entry is kept as it was bookmarked, so it can be shown and
+-- exported without normalizing its run's output again.
+CREATE TABLE entry_bookmarks (
+    id                   BLOB PRIMARY KEY,
+    task_id              BLOB NOT NULL,
+    execution_process_id BLOB NOT NULL,
+    entry_id             TEXT NOT NULL, -- The entry's id among its run's
+    entry                TEXT NOT NULL, -- JSON
+    note                 TEXT,
+    created_by           BLOB,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL,
+    UNIQUE (execution_process_id, entry_id)
+);
+
+CREATE INDEX idx_entry_bookmarks_task_id ON entry_bookmarks(task_id);
@@ -0,0 +1,19 @@
+-- TOTP two-factor authentication for password accounts. The secret has to be kept to check
+-- codes; recovery codes are kept as hashes, like session tokens.
+CREATE TABLE user_two_factor (
+    user_id        BLOB PRIMARY KEY,
+    secret         TEXT NOT NULL,    -- Base32, as authenticator apps take it
+    enabled_at     TEXT,             -- NULL until a code from the app confirms enrollment
+    last_used_step INTEGER,          -- The last code's time step, so a code works only once
+    created_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE TABLE user_recovery_codes (
+    user_id    BLOB NOT NULL,
+    code_hash  TEXT NOT NULL,
+    used_at    TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (user_id, code_hash),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
@@ -0,0 +1,20 @@
+-- Invites to join a project with a role, so project admins can bring people in without a
+-- server admin. Only a hash of the invite's token is kept; each invite is used once.
+CREATE TABLE project_invites (
+    id           BLOB PRIMARY KEY,
+    project_id   BLOB NOT NULL,
+    token_hash   TEXT NOT NULL UNIQUE,
+    token_prefix TEXT NOT NULL, -- The token's first characters, to tell invites apart
+    role         TEXT NOT NULL CHECK (role IN ('admin', 'member', 'viewer')),
+    invited_by   BLOB,
+    expires_at   TEXT NOT NULL,
+    accepted_by  BLOB,
+    accepted_at  TEXT,
+    revoked_at   TEXT,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (invited_by) REFERENCES users(id) ON DELETE SET NULL,
+    FOREIGN KEY (accepted_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_project_invites_project_id ON project_invites(project_id);
@@ -0,0 +1,19 @@
+-- @username mentions in review comments and follow-up prompts, one row per person
+-- mentioned, kept as their inbox until they've read it.
+CREATE TABLE mentions (
+    id              BLOB PRIMARY KEY,
+    user_id         BLOB NOT NULL,
+    task_id         BLOB NOT NULL,
+    task_attempt_id BLOB,
+    source          TEXT NOT NULL CHECK (source IN ('review_comment', 'follow_up')),
+    mentioned_by    BLOB,
+    excerpt         TEXT NOT NULL, -- The start of the text the mention is in
+    read_at         TEXT,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE,
+    FOREIGN KEY (mentioned_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_mentions_user_id ON mentions(user_id, read_at);
@@ -0,0 +1,18 @@
+-- Rhai scripts a project runs when its tasks are created, when its attempts finish and
+-- before they are merged
+CREATE TABLE script_hooks (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB NOT NULL,
+    name        TEXT NOT NULL,
+    event       TEXT NOT NULL
+                   CHECK (event IN ('task_created', 'attempt_finished', 'before_merge')),
+    script      TEXT NOT NULL,
+    enabled     INTEGER NOT NULL DEFAULT 1,
+    last_run_at TEXT,
+    last_error  TEXT, -- Why the last run failed, cleared by one that succeeds
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX idx_script_hooks_project_name ON script_hooks(project_id, name);
@@ -0,0 +1,8 @@
+-- Sign-in rules a project holds its members to. Projects without a row have none.
+CREATE TABLE project_security (
+    project_id          BLOB PRIMARY KEY,
+    require_two_factor  BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
@@ -0,0 +1,24 @@
+-- The merge queue, kept so that merges queued when the server stops are picked up again
+-- when it starts. An attempt is queued at most once at a time.
+CREATE TABLE merge_queue_entries (
+    id            BLOB PRIMARY KEY,
+    project_id    BLOB NOT NULL,
+    task_id       BLOB NOT NULL,
+    attempt_id    BLOB NOT NULL,
+    task_title    TEXT NOT NULL,
+    base_branch   TEXT NOT NULL,
+    status        TEXT NOT NULL DEFAULT 'queued'
+                     CHECK (status IN ('queued', 'rebasing', 'checking', 'merging', 'merged', 'failed')),
+    error         TEXT,
+    check_output  TEXT,
+    merge_commit  TEXT,
+    queued_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    finished_at   TEXT,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_merge_queue_entries_project_id ON merge_queue_entries(project_id);
+CREATE UNIQUE INDEX idx_merge_queue_entries_pending_attempt
+    ON merge_queue_entries(attempt_id) WHERE finished_at IS NULL;
@@ -0,0 +1,1200 @@
+-- The schema of every SQLite migration up to this one's version, for PostgreSQL. Migrations
+-- after it are added to both `migrations/` and `migrations/postgres/`, under the same version.
+
+CREATE TABLE projects (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    git_repo_path TEXT NOT NULL DEFAULT '',
+    setup_script TEXT DEFAULT '',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    dev_script TEXT DEFAULT '',
+    cleanup_script TEXT,
+    commit_message_template TEXT,
+    release_script TEXT,
+    merge_check_script TEXT,
+    verification_script TEXT,
+    default_executor TEXT,
+    default_branch TEXT,
+    UNIQUE (git_repo_path)
+);
+
+CREATE TABLE tasks (
+    id UUID PRIMARY KEY,
+    project_id UUID NOT NULL,
+    title TEXT NOT NULL,
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    parent_task_attempt UUID,
+    timeout_minutes BIGINT,
+    status TEXT NOT NULL DEFAULT 'todo',
+    parent_task_id UUID,
+    priority TEXT,
+    board_column_id UUID,
+    archived_at TIMESTAMPTZ,
+    deleted_at TIMESTAMPTZ,
+    CHECK (status IN ('todo','queued','inprogress','planreview','inreview','done','cancelled')),
+    CHECK (priority IN ('low', 'medium', 'high', 'urgent'))
+);
+
+CREATE TABLE execution_processes (
+    id UUID PRIMARY KEY,
+    task_attempt_id UUID NOT NULL,
+    command TEXT NOT NULL,
+    args TEXT, -- JSON array of arguments
+    working_directory TEXT NOT NULL,
+    stdout TEXT,
+    stderr TEXT,
+    exit_code BIGINT,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    completed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    executor_type TEXT,
+    process_type TEXT NOT NULL DEFAULT 'setupscript',
+    retry_count BIGINT NOT NULL DEFAULT 0,
+    retry_at TIMESTAMPTZ,
+    commit_sha TEXT,
+    failure_reason TEXT,
+    model TEXT,
+    duration_ms BIGINT,
+    status TEXT NOT NULL DEFAULT 'running',
+    process_group BIGINT,
+    CHECK (process_type IN ('setupscript', 'cleanupscript', 'codingagent', 'devserver')),
+    CHECK (failure_reason IN ('missing_binary', 'auth_failure', 'rate_limited', 'context_length_exceeded', 'git_conflict', 'network_error', 'spawn_failed')),
+    CHECK (status IN ('running', 'completed', 'failed', 'killed', 'timedout', 'paused', 'resourcelimitexceeded', 'ratelimited', 'interrupted'))
+);
+
+CREATE TABLE task_attempts (
+    id UUID PRIMARY KEY,
+    task_id UUID NOT NULL,
+    worktree_path TEXT NOT NULL,
+    merge_commit TEXT,
+    executor TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    branch TEXT NOT NULL DEFAULT '',
+    pr_url TEXT,
+    pr_number BIGINT,
+    pr_status TEXT,
+    pr_merged_at TIMESTAMPTZ,
+    base_branch TEXT NOT NULL DEFAULT 'main',
+    worktree_deleted BOOLEAN NOT NULL DEFAULT FALSE,
+    setup_completed_at TIMESTAMPTZ,
+    plan_only BOOLEAN NOT NULL DEFAULT FALSE,
+    model TEXT,
+    executor_profile_id UUID
+);
+
+CREATE TABLE executor_sessions (
+    id UUID PRIMARY KEY,
+    task_attempt_id UUID NOT NULL,
+    execution_process_id UUID NOT NULL,
+    session_id TEXT, -- External session ID from Claude/Amp
+    prompt TEXT, -- The prompt sent to the executor
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    summary TEXT
+);
+
+CREATE TABLE task_templates (
+    id UUID PRIMARY KEY,
+    project_id UUID, -- NULL for global templates
+    title TEXT NOT NULL,
+    description TEXT,
+    template_name TEXT NOT NULL, -- Display name for the template
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    default_executor TEXT,
+    verification_script TEXT,
+    checklist TEXT
+);
+
+CREATE TABLE review_comments (
+    id UUID PRIMARY KEY,
+    task_attempt_id UUID NOT NULL,
+    file_path TEXT NOT NULL,
+    line_number BIGINT NOT NULL,
+    body TEXT NOT NULL,
+    resolved BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    execution_process_id UUID
+);
+
+CREATE TABLE network_interruptions (
+    id UUID PRIMARY KEY,
+    task_attempt_id UUID NOT NULL,
+    execution_process_id UUID NOT NULL,
+    error TEXT NOT NULL,
+    resumed_at TIMESTAMPTZ,
+    abandoned_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE github_project_links (
+    project_id UUID PRIMARY KEY,
+    project_url TEXT NOT NULL,
+    github_project_id TEXT NOT NULL, -- GraphQL node id of the ProjectV2
+    status_mapping TEXT NOT NULL DEFAULT '{}', -- JSON object of task status -> option name
+    last_synced_at TIMESTAMPTZ,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE github_project_items (
+    task_id UUID PRIMARY KEY,
+    project_id UUID NOT NULL,
+    item_id TEXT NOT NULL, -- ProjectV2Item node id
+    draft_issue_id TEXT NOT NULL,
+    synced_title TEXT NOT NULL,
+    synced_description TEXT,
+    synced_status TEXT NOT NULL,
+    synced_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE notion_links (
+    project_id UUID PRIMARY KEY,
+    database_id TEXT NOT NULL,
+    property_mapping TEXT NOT NULL DEFAULT '{}', -- JSON NotionPropertyMapping
+    last_synced_at TIMESTAMPTZ,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE notion_pages (
+    task_id UUID PRIMARY KEY,
+    project_id UUID NOT NULL,
+    page_id TEXT NOT NULL,
+    synced_title TEXT NOT NULL,
+    synced_description TEXT,
+    synced_status TEXT NOT NULL,
+    synced_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE task_embeddings (
+    task_id UUID PRIMARY KEY,
+    model TEXT NOT NULL,
+    content_hash TEXT NOT NULL,
+    embedding BYTEA NOT NULL, -- little-endian f32s
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE conversation_index_state (
+    execution_process_id UUID PRIMARY KEY,
+    model TEXT NOT NULL,
+    indexed_entries BIGINT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE conversation_chunks (
+    id UUID PRIMARY KEY,
+    execution_process_id UUID NOT NULL,
+    entry_index BIGINT NOT NULL, -- first normalized entry in the chunk
+    entry_type TEXT NOT NULL, -- JSON NormalizedEntryType of that entry
+    timestamp TEXT,
+    content TEXT NOT NULL,
+    embedding BYTEA NOT NULL -- little-endian f32s
+);
+
+CREATE TABLE task_original_texts (
+    task_id UUID PRIMARY KEY,
+    title TEXT NOT NULL,
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE task_labels (
+    task_id UUID,
+    name TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    PRIMARY KEY (task_id, name)
+);
+
+CREATE TABLE task_label_suggestions (
+    task_id UUID,
+    name TEXT,
+    reason TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    PRIMARY KEY (task_id, name)
+);
+
+CREATE TABLE releases (
+    id UUID PRIMARY KEY,
+    project_id UUID NOT NULL,
+    version TEXT NOT NULL,
+    notes TEXT,
+    status TEXT NOT NULL DEFAULT 'draft',
+    stdout TEXT,
+    stderr TEXT,
+    exit_code BIGINT,
+    started_at TIMESTAMPTZ,
+    released_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    UNIQUE (project_id, version),
+    CHECK (status IN ('draft', 'running', 'released', 'failed'))
+);
+
+CREATE TABLE release_tasks (
+    release_id UUID,
+    task_id UUID,
+    PRIMARY KEY (release_id, task_id),
+    UNIQUE (task_id)
+);
+
+CREATE TABLE stale_tasks (
+    task_id UUID PRIMARY KEY,
+    status TEXT NOT NULL,
+    idle_since TIMESTAMPTZ NOT NULL,
+    nudged_at TIMESTAMPTZ
+);
+
+CREATE TABLE task_attachments (
+    id UUID PRIMARY KEY,
+    task_id UUID NOT NULL,
+    file_name TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE execution_process_attachments (
+    execution_process_id UUID,
+    attachment_id UUID,
+    PRIMARY KEY (execution_process_id, attachment_id)
+);
+
+CREATE TABLE attempt_plans (
+    task_attempt_id UUID PRIMARY KEY,
+    execution_process_id UUID NOT NULL,
+    content TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    approved_at TIMESTAMPTZ,
+    CHECK (status IN ('pending','approved'))
+);
+
+CREATE TABLE task_dependencies (
+    task_id UUID,
+    blocked_by_task_id UUID,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    PRIMARY KEY (task_id, blocked_by_task_id),
+    CHECK (task_id != blocked_by_task_id)
+);
+
+CREATE TABLE execution_diffs (
+    execution_process_id UUID PRIMARY KEY,
+    from_commit TEXT NOT NULL,
+    to_commit TEXT,
+    files TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE conversation_contexts (
+    execution_process_id UUID PRIMARY KEY,
+    task_attempt_id UUID NOT NULL,
+    prompt TEXT,
+    entries TEXT NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE execution_queue (
+    task_attempt_id UUID PRIMARY KEY,
+    project_id UUID NOT NULL,
+    queued_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    position BIGINT NOT NULL DEFAULT 0,
+    paused_process_id UUID
+);
+
+CREATE TABLE execution_metrics (
+    execution_process_id UUID PRIMARY KEY,
+    task_attempt_id UUID NOT NULL,
+    input_tokens BIGINT NOT NULL DEFAULT 0,
+    output_tokens BIGINT NOT NULL DEFAULT 0,
+    cost_usd DOUBLE PRECISION,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE prompt_templates (
+    id UUID PRIMARY KEY,
+    project_id UUID NOT NULL,
+    executor TEXT,
+    template TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE environment_variables (
+    id UUID PRIMARY KEY,
+    project_id UUID NOT NULL,
+    task_id UUID,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    is_secret BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE project_git_hosts (
+    project_id UUID PRIMARY KEY,
+    kind TEXT NOT NULL,
+    base_url TEXT,
+    token TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    CHECK (kind IN ('github', 'gitlab', 'bitbucket'))
+);
+
+CREATE TABLE execution_verifications (
+    id UUID PRIMARY KEY,
+    execution_process_id UUID NOT NULL,
+    command TEXT NOT NULL,
+    passed BOOLEAN NOT NULL,
+    exit_code BIGINT,
+    output TEXT NOT NULL DEFAULT '',
+    duration_ms BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE verification_fix_loops (
+    task_attempt_id UUID PRIMARY KEY,
+    fix_count BIGINT NOT NULL DEFAULT 0,
+    last_failure TEXT NOT NULL,
+    pending BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE task_defaults (
+    task_id UUID PRIMARY KEY,
+    template_id UUID,
+    executor TEXT,
+    verification_script TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    executor_profile_id UUID
+);
+
+CREATE TABLE task_checklist_items (
+    id UUID PRIMARY KEY,
+    task_id UUID NOT NULL,
+    position BIGINT NOT NULL,
+    content TEXT NOT NULL,
+    completed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE task_decompositions (
+    task_attempt_id UUID PRIMARY KEY,
+    task_id UUID NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    subtask_count BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    completed_at TIMESTAMPTZ,
+    CHECK (status IN ('pending','completed','failed'))
+);
+
+CREATE TABLE task_schedules (
+    id UUID PRIMARY KEY,
+    task_id UUID NOT NULL,
+    cron_expression TEXT NOT NULL,
+    executor TEXT,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    next_run_at TIMESTAMPTZ,
+    last_run_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    UNIQUE (task_id)
+);
+
+CREATE TABLE task_schedule_runs (
+    id UUID PRIMARY KEY,
+    schedule_id UUID NOT NULL,
+    task_id UUID,
+    task_attempt_id UUID,
+    status TEXT NOT NULL DEFAULT 'running',
+    error TEXT,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    finished_at TIMESTAMPTZ,
+    CHECK (status IN ('running','succeeded','failed'))
+);
+
+CREATE TABLE dev_server_ports (
+    execution_process_id UUID PRIMARY KEY,
+    port BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE search_index_state (
+    execution_process_id UUID PRIMARY KEY,
+    indexed_entries BIGINT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE events (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    project_id UUID NOT NULL,
+    task_id UUID,
+    task_attempt_id UUID,
+    execution_process_id UUID,
+    actor TEXT NOT NULL,
+    details TEXT NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL,
+    prev_hash TEXT,
+    hash TEXT NOT NULL,
+    kind TEXT NOT NULL DEFAULT 'task_created',
+    CHECK (actor IN ('user','system')),
+    CHECK (kind IN ('task_created','task_status_changed','task_deleted', 'execution_started','execution_completed', 'execution_failed','execution_stopped','pr_opened', 'review_approved','review_rejected','attempt_rolled_back', 'execution_reverted'))
+);
+
+CREATE TABLE notification_webhooks (
+    id UUID PRIMARY KEY,
+    project_id UUID NOT NULL,
+    url TEXT NOT NULL,
+    format TEXT NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    attempt_succeeded BOOLEAN NOT NULL DEFAULT TRUE,
+    attempt_failed BOOLEAN NOT NULL DEFAULT TRUE,
+    approval_needed BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    CHECK (format IN ('slack', 'discord'))
+);
+
+CREATE TABLE notification_deliveries (
+    id UUID PRIMARY KEY,
+    project_id UUID NOT NULL,
+    task_attempt_id UUID,
+    webhook_id UUID,
+    channel TEXT NOT NULL,
+    event TEXT NOT NULL,
+    title TEXT NOT NULL,
+    message TEXT NOT NULL,
+    status TEXT NOT NULL,
+    attempts BIGINT NOT NULL DEFAULT 0,
+    last_error TEXT,
+    next_attempt_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    sent_at TIMESTAMPTZ,
+    CHECK (channel IN ('desktop', 'webhook')),
+    CHECK (event IN ('attempt_succeeded', 'attempt_failed', 'approval_needed', 'task_stale')),
+    CHECK (status IN ('pending', 'sent', 'failed'))
+);
+
+CREATE TABLE execution_log_chunks (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY, -- Orders the chunks of a stream
+    execution_process_id UUID NOT NULL,
+    stream TEXT NOT NULL,
+    content TEXT NOT NULL,
+    size BIGINT NOT NULL, -- Bytes of content
+    omitted_bytes BIGINT NOT NULL DEFAULT 0, -- Set on markers: bytes trimmed in their place
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    received_at TIMESTAMPTZ,
+    CHECK (stream IN ('stdout', 'stderr'))
+);
+
+CREATE TABLE users (
+    id UUID PRIMARY KEY,
+    username TEXT NOT NULL,
+    display_name TEXT,
+    password_hash TEXT,
+    oidc_subject TEXT,
+    is_admin BOOLEAN NOT NULL DEFAULT FALSE, -- Admin of every project, and manages accounts
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    UNIQUE (oidc_subject),
+    UNIQUE (username)
+);
+
+CREATE TABLE user_sessions (
+    token_hash TEXT PRIMARY KEY,
+    user_id UUID NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE project_members (
+    project_id UUID,
+    user_id UUID,
+    role TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    PRIMARY KEY (project_id, user_id),
+    CHECK (role IN ('admin', 'member', 'viewer'))
+);
+
+CREATE TABLE api_tokens (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL,
+    name TEXT NOT NULL,
+    token_hash TEXT NOT NULL,
+    token_prefix TEXT NOT NULL, -- The token's first characters, to tell tokens apart
+    scopes TEXT NOT NULL DEFAULT '[]', -- JSON array of scopes
+    expires_at TIMESTAMPTZ,
+    last_used_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    UNIQUE (token_hash)
+);
+
+CREATE TABLE api_token_uses (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    token_id UUID NOT NULL,
+    method TEXT NOT NULL,
+    path TEXT NOT NULL,
+    status BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE attempt_reviews (
+    id UUID PRIMARY KEY,
+    task_attempt_id UUID NOT NULL,
+    decision TEXT NOT NULL,
+    reviewer TEXT, -- The reviewer's username, when sign-in is on
+    comment TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    CHECK (decision IN ('approved', 'rejected'))
+);
+
+CREATE TABLE attempt_groups (
+    id UUID PRIMARY KEY,
+    task_id UUID NOT NULL,
+    winner_attempt_id UUID,
+    decided_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE attempt_group_members (
+    task_attempt_id UUID PRIMARY KEY,
+    group_id UUID NOT NULL
+);
+
+CREATE TABLE attempt_rollbacks (
+    id UUID PRIMARY KEY,
+    task_attempt_id UUID NOT NULL,
+    execution_process_id UUID, -- The run whose commit the attempt went back to
+    from_commit TEXT NOT NULL,
+    to_commit TEXT NOT NULL,
+    backup_ref TEXT NOT NULL, -- Keeps from_commit reachable
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE project_mcp_servers (
+    id UUID PRIMARY KEY,
+    project_id UUID NOT NULL,
+    name TEXT NOT NULL,
+    command TEXT NOT NULL,
+    args TEXT NOT NULL DEFAULT '[]', -- JSON array of arguments
+    env TEXT NOT NULL DEFAULT '{}', -- JSON object of environment variables
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE project_sandboxes (
+    project_id UUID PRIMARY KEY,
+    enabled BOOLEAN NOT NULL,
+    deny_network BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE project_containers (
+    project_id UUID PRIMARY KEY,
+    image TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE project_workers (
+    project_id UUID PRIMARY KEY,
+    url TEXT NOT NULL,
+    local_root TEXT,
+    remote_root TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE execution_resource_samples (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    execution_process_id UUID NOT NULL,
+    cpu_percent DOUBLE PRECISION NOT NULL, -- Of one core, since the previous sample
+    cpu_seconds DOUBLE PRECISION NOT NULL, -- CPU time used so far by the processes still running
+    rss_bytes BIGINT NOT NULL,
+    sampled_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE task_pr_descriptions (
+    task_id UUID PRIMARY KEY,
+    task_attempt_id UUID NOT NULL,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE executor_profiles (
+    id UUID PRIMARY KEY,
+    project_id UUID,
+    name TEXT NOT NULL,
+    executor TEXT NOT NULL,
+    model TEXT,
+    extra_args TEXT NOT NULL DEFAULT '[]', -- JSON array of flags
+    env TEXT NOT NULL DEFAULT '{}', -- JSON object of variable names to values
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE github_issue_links (
+    project_id UUID PRIMARY KEY,
+    repo_owner TEXT NOT NULL,
+    repo_name TEXT NOT NULL,
+    label TEXT NOT NULL,
+    last_synced_at TIMESTAMPTZ,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE github_issue_tasks (
+    project_id UUID,
+    issue_number BIGINT,
+    task_id UUID NOT NULL,
+    synced_title TEXT NOT NULL,
+    synced_body TEXT,
+    issue_closed BOOLEAN NOT NULL DEFAULT FALSE,
+    synced_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    PRIMARY KEY (project_id, issue_number)
+);
+
+CREATE TABLE tracker_imports (
+    project_id UUID,
+    tracker TEXT,
+    source_key TEXT, -- Jira project key or Linear team key
+    last_synced_at TIMESTAMPTZ, -- when the last successful sync started
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    PRIMARY KEY (project_id, tracker, source_key),
+    CHECK (tracker IN ('jira', 'linear'))
+);
+
+CREATE TABLE tracker_issues (
+    project_id UUID,
+    tracker TEXT,
+    issue_id TEXT, -- the tracker's own id, stable across key changes
+    issue_key TEXT NOT NULL, -- e.g. ENG-42
+    task_id UUID NOT NULL,
+    synced_title TEXT NOT NULL,
+    synced_description TEXT,
+    synced_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    PRIMARY KEY (project_id, tracker, issue_id)
+);
+
+CREATE TABLE project_custom_fields (
+    project_id UUID,
+    name TEXT,
+    field_type TEXT NOT NULL,
+    options TEXT NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    PRIMARY KEY (project_id, name),
+    CHECK (field_type IN ('text', 'number', 'boolean', 'date', 'select'))
+);
+
+CREATE TABLE task_custom_field_values (
+    task_id UUID,
+    name TEXT,
+    value TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    PRIMARY KEY (task_id, name)
+);
+
+CREATE TABLE project_board_columns (
+    id UUID PRIMARY KEY,
+    project_id UUID NOT NULL,
+    name TEXT NOT NULL,
+    position BIGINT NOT NULL,
+    status TEXT NOT NULL,
+    wip_limit BIGINT,
+    conditions TEXT NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    CHECK (status IN ('todo', 'queued', 'inprogress', 'planreview', 'inreview', 'done', 'cancelled')),
+    CHECK (wip_limit IS NULL OR wip_limit > 0)
+);
+
+CREATE TABLE benchmark_suites (
+    id UUID PRIMARY KEY,
+    project_id UUID NOT NULL,
+    name TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    UNIQUE (project_id, name)
+);
+
+CREATE TABLE benchmark_cases (
+    id UUID PRIMARY KEY,
+    suite_id UUID NOT NULL,
+    position BIGINT NOT NULL,
+    title TEXT NOT NULL,
+    prompt TEXT NOT NULL,
+    verification_script TEXT NOT NULL
+);
+
+CREATE TABLE benchmark_runs (
+    id UUID PRIMARY KEY,
+    suite_id UUID NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    finished_at TIMESTAMPTZ
+);
+
+CREATE TABLE benchmark_results (
+    id UUID PRIMARY KEY,
+    run_id UUID NOT NULL,
+    case_title TEXT NOT NULL,
+    contestant TEXT NOT NULL,
+    task_id UUID,
+    task_attempt_id UUID,
+    status TEXT NOT NULL DEFAULT 'running',
+    duration_ms BIGINT,
+    cost_usd DOUBLE PRECISION,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    finished_at TIMESTAMPTZ,
+    CHECK (status IN ('running', 'passed', 'failed'))
+);
+
+CREATE TABLE project_shell_environments (
+    project_id UUID PRIMARY KEY,
+    login_shell TEXT NOT NULL DEFAULT 'auto',
+    env_file TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    CHECK (login_shell IN ('auto', 'always', 'never'))
+);
+
+CREATE TABLE execution_inputs (
+    id UUID PRIMARY KEY,
+    execution_process_id UUID NOT NULL,
+    content TEXT NOT NULL,
+    entry_index BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE follow_up_drafts (
+    task_id UUID PRIMARY KEY,
+    task_attempt_id UUID,
+    prompt TEXT NOT NULL,
+    quotes TEXT NOT NULL DEFAULT '[]',
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE execution_snapshots (
+    execution_process_id UUID PRIMARY KEY,
+    task_attempt_id UUID NOT NULL,
+    snapshot_commit TEXT NOT NULL,
+    snapshot_ref TEXT NOT NULL, -- Keeps snapshot_commit reachable
+    revert_commit TEXT, -- The commit undoing the run's changes, once reverted
+    reverted_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE execution_attention (
+    id UUID PRIMARY KEY,
+    execution_process_id UUID NOT NULL,
+    task_attempt_id UUID NOT NULL,
+    kind TEXT NOT NULL,
+    title TEXT NOT NULL,
+    message TEXT NOT NULL,
+    requested_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    escalated_at TIMESTAMPTZ,
+    resolved_at TIMESTAMPTZ,
+    CHECK (kind IN ('command_approval', 'plan_review'))
+);
+
+CREATE TABLE task_linked_projects (
+    task_id UUID,
+    project_id UUID, -- Another project than the task's own
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    PRIMARY KEY (task_id, project_id)
+);
+
+CREATE TABLE attempt_linked_worktrees (
+    task_attempt_id UUID,
+    project_id UUID,
+    worktree_path TEXT NOT NULL,
+    base_branch TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    PRIMARY KEY (task_attempt_id, project_id)
+);
+
+CREATE TABLE execution_tool_usage (
+    execution_process_id UUID PRIMARY KEY,
+    task_attempt_id UUID NOT NULL,
+    files_read BIGINT NOT NULL DEFAULT 0, -- Distinct paths
+    files_written BIGINT NOT NULL DEFAULT 0, -- Distinct paths
+    commands_run BIGINT NOT NULL DEFAULT 0,
+    searches BIGINT NOT NULL DEFAULT 0,
+    web_fetches BIGINT NOT NULL DEFAULT 0,
+    other_tools BIGINT NOT NULL DEFAULT 0,
+    assistant_messages BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE task_share_links (
+    id UUID PRIMARY KEY,
+    task_id UUID NOT NULL,
+    token_hash TEXT NOT NULL,
+    token_prefix TEXT NOT NULL, -- The token's first characters, to tell links apart
+    created_by UUID,
+    expires_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    last_viewed_at TIMESTAMPTZ,
+    view_count BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    UNIQUE (token_hash)
+);
+
+CREATE TABLE project_guardrails (
+    project_id UUID PRIMARY KEY,
+    monthly_budget_usd DOUBLE PRECISION,
+    allowed_windows TEXT NOT NULL DEFAULT '[]', -- JSON array of ExecutionWindow
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE entry_bookmarks (
+    id UUID PRIMARY KEY,
+    task_id UUID NOT NULL,
+    execution_process_id UUID NOT NULL,
+    entry_id TEXT NOT NULL, -- The entry's id among its run's
+    entry TEXT NOT NULL, -- JSON
+    note TEXT,
+    created_by UUID,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    UNIQUE (execution_process_id, entry_id)
+);
+
+CREATE TABLE user_two_factor (
+    user_id UUID PRIMARY KEY,
+    secret TEXT NOT NULL, -- Base32, as authenticator apps take it
+    enabled_at TIMESTAMPTZ, -- NULL until a code from the app confirms enrollment
+    last_used_step BIGINT, -- The last code's time step, so a code works only once
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
+
+CREATE TABLE user_recovery_codes (
+    user_id UUID,
+    code_hash TEXT,
+    used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    PRIMARY KEY (user_id, code_hash)
+);
+
+CREATE TABLE project_invites (
+    id UUID PRIMARY KEY,
+    project_id UUID NOT NULL,
+    token_hash TEXT NOT NULL,
+    token_prefix TEXT NOT NULL, -- The token's first characters, to tell invites apart
+    role TEXT NOT NULL,
+    invited_by UUID,
+    expires_at TIMESTAMPTZ NOT NULL,
+    accepted_by UUID,
+    accepted_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    UNIQUE (token_hash),
+    CHECK (role IN ('admin', 'member', 'viewer'))
+);
+
+CREATE TABLE mentions (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL,
+    task_id UUID NOT NULL,
+    task_attempt_id UUID,
+    source TEXT NOT NULL,
+    mentioned_by UUID,
+    excerpt TEXT NOT NULL, -- The start of the text the mention is in
+    read_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    CHECK (source IN ('review_comment', 'follow_up'))
+);
+
+CREATE TABLE script_hooks (
+    id UUID PRIMARY KEY,
+    project_id UUID NOT NULL,
+    name TEXT NOT NULL,
+    event TEXT NOT NULL,
+    script TEXT NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    last_run_at TIMESTAMPTZ,
+    last_error TEXT, -- Why the last run failed, cleared by one that succeeds
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    CHECK (event IN ('task_created', 'attempt_finished', 'before_merge'))
+);
+
+-- Full-text index of tasks and coding agent conversations. Titles weigh twice as much as
+-- other text when ranking.
+CREATE TABLE search_documents (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    title TEXT,
+    content TEXT,
+    task_id UUID,
+    execution_process_id UUID,
+    entry_index BIGINT,
+    entry_type TEXT,
+    timestamp TEXT,
+    document TSVECTOR GENERATED ALWAYS AS (
+        setweight(to_tsvector('simple', COALESCE(title, '')), 'A') ||
+        setweight(to_tsvector('simple', COALESCE(content, '')), 'B')
+    ) STORED
+);
+
+CREATE INDEX idx_search_documents_document ON search_documents USING GIN (document);
+CREATE INDEX idx_search_documents_task_id ON search_documents(task_id);
+
+CREATE FUNCTION search_documents_index_task() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'UPDATE' THEN
+        DELETE FROM search_documents WHERE task_id = OLD.id AND execution_process_id IS NULL;
+    END IF;
+    INSERT INTO search_documents (title, content, task_id)
+    VALUES (NEW.title, COALESCE(NEW.description, ''), NEW.id);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER search_documents_task_insert AFTER INSERT ON tasks
+FOR EACH ROW EXECUTE FUNCTION search_documents_index_task();
+
+CREATE TRIGGER search_documents_task_update AFTER UPDATE OF title, description ON tasks
+FOR EACH ROW EXECUTE FUNCTION search_documents_index_task();
+
+CREATE FUNCTION search_documents_remove_task() RETURNS trigger AS $$
+BEGIN
+    DELETE FROM search_documents WHERE task_id = OLD.id;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER search_documents_task_delete AFTER DELETE ON tasks
+FOR EACH ROW EXECUTE FUNCTION search_documents_remove_task();
+
+-- Updates that don't set updated_at themselves still move it
+CREATE FUNCTION tasks_touch_updated_at() RETURNS trigger AS $$
+BEGIN
+    IF NEW.updated_at = OLD.updated_at THEN
+        NEW.updated_at := statement_timestamp();
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER tasks_touch_updated_at BEFORE UPDATE ON tasks
+FOR EACH ROW EXECUTE FUNCTION tasks_touch_updated_at();
+
+CREATE FUNCTION events_append_only() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'events are append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER events_no_update BEFORE UPDATE ON events
+FOR EACH ROW EXECUTE FUNCTION events_append_only();
+
+CREATE TRIGGER events_no_delete BEFORE DELETE ON events
+FOR EACH ROW EXECUTE FUNCTION events_append_only();
+
+CREATE INDEX idx_tasks_deleted_at ON tasks(project_id, deleted_at);
+CREATE INDEX idx_tasks_archived_at ON tasks(project_id, archived_at);
+CREATE INDEX idx_tasks_parent_task_id ON tasks(parent_task_id);
+CREATE INDEX idx_tasks_parent_task_attempt ON tasks(parent_task_attempt);
+CREATE INDEX idx_execution_processes_status ON execution_processes(status);
+CREATE INDEX idx_execution_processes_type ON execution_processes(process_type);
+CREATE INDEX idx_execution_processes_task_attempt_id ON execution_processes(task_attempt_id);
+CREATE INDEX idx_executor_sessions_session_id ON executor_sessions(session_id);
+CREATE INDEX idx_executor_sessions_execution_process_id ON executor_sessions(execution_process_id);
+CREATE INDEX idx_executor_sessions_task_attempt_id ON executor_sessions(task_attempt_id);
+CREATE UNIQUE INDEX idx_task_templates_unique_name_global ON task_templates(template_name) WHERE project_id IS NULL;
+CREATE UNIQUE INDEX idx_task_templates_unique_name_project ON task_templates(project_id, template_name) WHERE project_id IS NOT NULL;
+CREATE INDEX idx_task_templates_project_id ON task_templates(project_id);
+CREATE INDEX idx_review_comments_task_attempt_id ON review_comments(task_attempt_id);
+CREATE INDEX idx_network_interruptions_task_attempt_id ON network_interruptions(task_attempt_id);
+CREATE INDEX idx_github_project_items_project_id ON github_project_items(project_id);
+CREATE INDEX idx_notion_pages_project_id ON notion_pages(project_id);
+CREATE INDEX idx_conversation_chunks_execution_process_id ON conversation_chunks(execution_process_id);
+CREATE INDEX idx_releases_project_id ON releases(project_id);
+CREATE INDEX idx_task_attachments_task_id ON task_attachments(task_id);
+CREATE INDEX idx_task_dependencies_blocked_by ON task_dependencies(blocked_by_task_id);
+CREATE INDEX idx_conversation_contexts_task_attempt_id ON conversation_contexts(task_attempt_id);
+CREATE INDEX idx_execution_metrics_task_attempt_id ON execution_metrics(task_attempt_id);
+CREATE UNIQUE INDEX idx_prompt_templates_project_executor ON prompt_templates(project_id, COALESCE(executor, ''));
+CREATE UNIQUE INDEX idx_environment_variables_scope_key ON environment_variables(project_id, COALESCE(task_id, '00000000-0000-0000-0000-000000000000'), key);
+CREATE INDEX idx_execution_verifications_process ON execution_verifications(execution_process_id);
+CREATE INDEX idx_task_checklist_items_task_id ON task_checklist_items(task_id, position);
+CREATE INDEX idx_task_decompositions_task_id ON task_decompositions(task_id);
+CREATE INDEX idx_task_schedule_runs_task_attempt_id ON task_schedule_runs(task_attempt_id);
+CREATE INDEX idx_task_schedule_runs_schedule_id ON task_schedule_runs(schedule_id, started_at);
+CREATE INDEX idx_events_project_id ON events(project_id, id);
+CREATE INDEX idx_notification_webhooks_project_id ON notification_webhooks(project_id);
+CREATE INDEX idx_notification_deliveries_pending ON notification_deliveries(next_attempt_at) WHERE status = 'pending';
+CREATE INDEX idx_notification_deliveries_project_id ON notification_deliveries(project_id, created_at);
+CREATE INDEX idx_execution_log_chunks_process ON execution_log_chunks(execution_process_id, stream, id);
+CREATE INDEX idx_user_sessions_user_id ON user_sessions(user_id);
+CREATE INDEX idx_project_members_user_id ON project_members(user_id);
+CREATE INDEX idx_api_tokens_user_id ON api_tokens(user_id);
+CREATE INDEX idx_api_token_uses_token_id ON api_token_uses(token_id, id);
+CREATE INDEX idx_attempt_reviews_task_attempt_id ON attempt_reviews(task_attempt_id);
+CREATE INDEX idx_attempt_groups_task_id ON attempt_groups(task_id);
+CREATE INDEX idx_attempt_group_members_group_id ON attempt_group_members(group_id);
+CREATE INDEX idx_attempt_rollbacks_task_attempt_id ON attempt_rollbacks(task_attempt_id);
+CREATE UNIQUE INDEX idx_project_mcp_servers_project_name ON project_mcp_servers(project_id, name);
+CREATE INDEX idx_execution_resource_samples_process ON execution_resource_samples(execution_process_id, id);
+CREATE UNIQUE INDEX idx_executor_profiles_unique_name_global ON executor_profiles(name) WHERE project_id IS NULL;
+CREATE UNIQUE INDEX idx_executor_profiles_unique_name_project ON executor_profiles(project_id, name) WHERE project_id IS NOT NULL;
+CREATE INDEX idx_github_issue_tasks_task_id ON github_issue_tasks(task_id);
+CREATE INDEX idx_project_board_columns_project_id ON project_board_columns(project_id, position);
+CREATE INDEX idx_benchmark_cases_suite_id ON benchmark_cases(suite_id, position);
+CREATE INDEX idx_benchmark_runs_suite_id ON benchmark_runs(suite_id, created_at);
+CREATE INDEX idx_benchmark_results_task_attempt_id ON benchmark_results(task_attempt_id);
+CREATE INDEX idx_benchmark_results_run_id ON benchmark_results(run_id);
+CREATE INDEX idx_execution_inputs_process ON execution_inputs(execution_process_id);
+CREATE INDEX idx_execution_snapshots_task_attempt_id ON execution_snapshots(task_attempt_id);
+CREATE INDEX idx_execution_attention_task_attempt_id ON execution_attention(task_attempt_id);
+CREATE INDEX idx_execution_attention_open ON execution_attention(requested_at) WHERE resolved_at IS NULL;
+CREATE INDEX idx_execution_tool_usage_task_attempt_id ON execution_tool_usage(task_attempt_id);
+CREATE INDEX idx_task_share_links_task_id ON task_share_links(task_id);
+CREATE INDEX idx_entry_bookmarks_task_id ON entry_bookmarks(task_id);
+CREATE INDEX idx_project_invites_project_id ON project_invites(project_id);
+CREATE INDEX idx_mentions_user_id ON mentions(user_id, read_at);
+CREATE UNIQUE INDEX idx_script_hooks_project_name ON script_hooks(project_id, name);
+
+ALTER TABLE tasks ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE tasks ADD FOREIGN KEY (board_column_id) REFERENCES project_board_columns (id) ON DELETE SET NULL;
+ALTER TABLE tasks ADD FOREIGN KEY (parent_task_id) REFERENCES tasks (id) ON DELETE CASCADE;
+ALTER TABLE tasks ADD FOREIGN KEY (parent_task_attempt) REFERENCES task_attempts (id);
+ALTER TABLE execution_processes ADD FOREIGN KEY (task_attempt_id) REFERENCES task_attempts (id) ON DELETE CASCADE;
+ALTER TABLE task_attempts ADD FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE;
+ALTER TABLE task_attempts ADD FOREIGN KEY (executor_profile_id) REFERENCES executor_profiles (id) ON DELETE SET NULL;
+ALTER TABLE executor_sessions ADD FOREIGN KEY (execution_process_id) REFERENCES execution_processes (id) ON DELETE CASCADE;
+ALTER TABLE executor_sessions ADD FOREIGN KEY (task_attempt_id) REFERENCES task_attempts (id) ON DELETE CASCADE;
+ALTER TABLE task_templates ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE review_comments ADD FOREIGN KEY (task_attempt_id) REFERENCES task_attempts (id) ON DELETE CASCADE;
+ALTER TABLE review_comments ADD FOREIGN KEY (execution_process_id) REFERENCES execution_processes (id) ON DELETE SET NULL;
+ALTER TABLE network_interruptions ADD FOREIGN KEY (execution_process_id) REFERENCES execution_processes (id) ON DELETE CASCADE;
+ALTER TABLE network_interruptions ADD FOREIGN KEY (task_attempt_id) REFERENCES task_attempts (id) ON DELETE CASCADE;
+ALTER TABLE github_project_links ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE github_project_items ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE notion_links ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE notion_pages ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE task_embeddings ADD FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE;
+ALTER TABLE conversation_index_state ADD FOREIGN KEY (execution_process_id) REFERENCES execution_processes (id) ON DELETE CASCADE;
+ALTER TABLE conversation_chunks ADD FOREIGN KEY (execution_process_id) REFERENCES execution_processes (id) ON DELETE CASCADE;
+ALTER TABLE task_original_texts ADD FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE;
+ALTER TABLE task_labels ADD FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE;
+ALTER TABLE task_label_suggestions ADD FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE;
+ALTER TABLE releases ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE release_tasks ADD FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE;
+ALTER TABLE release_tasks ADD FOREIGN KEY (release_id) REFERENCES releases (id) ON DELETE CASCADE;
+ALTER TABLE stale_tasks ADD FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE;
+ALTER TABLE task_attachments ADD FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE;
+ALTER TABLE execution_process_attachments ADD FOREIGN KEY (attachment_id) REFERENCES task_attachments (id) ON DELETE CASCADE;
+ALTER TABLE execution_process_attachments ADD FOREIGN KEY (execution_process_id) REFERENCES execution_processes (id) ON DELETE CASCADE;
+ALTER TABLE attempt_plans ADD FOREIGN KEY (execution_process_id) REFERENCES execution_processes (id) ON DELETE CASCADE;
+ALTER TABLE attempt_plans ADD FOREIGN KEY (task_attempt_id) REFERENCES task_attempts (id) ON DELETE CASCADE;
+ALTER TABLE task_dependencies ADD FOREIGN KEY (blocked_by_task_id) REFERENCES tasks (id) ON DELETE CASCADE;
+ALTER TABLE task_dependencies ADD FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE;
+ALTER TABLE execution_diffs ADD FOREIGN KEY (execution_process_id) REFERENCES execution_processes (id) ON DELETE CASCADE;
+ALTER TABLE conversation_contexts ADD FOREIGN KEY (task_attempt_id) REFERENCES task_attempts (id) ON DELETE CASCADE;
+ALTER TABLE conversation_contexts ADD FOREIGN KEY (execution_process_id) REFERENCES execution_processes (id) ON DELETE CASCADE;
+ALTER TABLE execution_queue ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE execution_queue ADD FOREIGN KEY (task_attempt_id) REFERENCES task_attempts (id) ON DELETE CASCADE;
+ALTER TABLE execution_queue ADD FOREIGN KEY (paused_process_id) REFERENCES execution_processes (id) ON DELETE CASCADE;
+ALTER TABLE execution_metrics ADD FOREIGN KEY (task_attempt_id) REFERENCES task_attempts (id) ON DELETE CASCADE;
+ALTER TABLE execution_metrics ADD FOREIGN KEY (execution_process_id) REFERENCES execution_processes (id) ON DELETE CASCADE;
+ALTER TABLE prompt_templates ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE environment_variables ADD FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE;
+ALTER TABLE environment_variables ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE project_git_hosts ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE execution_verifications ADD FOREIGN KEY (execution_process_id) REFERENCES execution_processes (id) ON DELETE CASCADE;
+ALTER TABLE verification_fix_loops ADD FOREIGN KEY (task_attempt_id) REFERENCES task_attempts (id) ON DELETE CASCADE;
+ALTER TABLE task_defaults ADD FOREIGN KEY (template_id) REFERENCES task_templates (id) ON DELETE SET NULL;
+ALTER TABLE task_defaults ADD FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE;
+ALTER TABLE task_defaults ADD FOREIGN KEY (executor_profile_id) REFERENCES executor_profiles (id) ON DELETE SET NULL;
+ALTER TABLE task_checklist_items ADD FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE;
+ALTER TABLE task_decompositions ADD FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE;
+ALTER TABLE task_decompositions ADD FOREIGN KEY (task_attempt_id) REFERENCES task_attempts (id) ON DELETE CASCADE;
+ALTER TABLE task_schedules ADD FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE;
+ALTER TABLE task_schedule_runs ADD FOREIGN KEY (task_attempt_id) REFERENCES task_attempts (id) ON DELETE SET NULL;
+ALTER TABLE task_schedule_runs ADD FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE SET NULL;
+ALTER TABLE task_schedule_runs ADD FOREIGN KEY (schedule_id) REFERENCES task_schedules (id) ON DELETE CASCADE;
+ALTER TABLE dev_server_ports ADD FOREIGN KEY (execution_process_id) REFERENCES execution_processes (id) ON DELETE CASCADE;
+ALTER TABLE search_index_state ADD FOREIGN KEY (execution_process_id) REFERENCES execution_processes (id) ON DELETE CASCADE;
+ALTER TABLE notification_webhooks ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE notification_deliveries ADD FOREIGN KEY (webhook_id) REFERENCES notification_webhooks (id) ON DELETE SET NULL;
+ALTER TABLE notification_deliveries ADD FOREIGN KEY (task_attempt_id) REFERENCES task_attempts (id) ON DELETE SET NULL;
+ALTER TABLE notification_deliveries ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE execution_log_chunks ADD FOREIGN KEY (execution_process_id) REFERENCES execution_processes (id) ON DELETE CASCADE;
+ALTER TABLE user_sessions ADD FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE;
+ALTER TABLE project_members ADD FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE;
+ALTER TABLE project_members ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE api_tokens ADD FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE;
+ALTER TABLE api_token_uses ADD FOREIGN KEY (token_id) REFERENCES api_tokens (id) ON DELETE CASCADE;
+ALTER TABLE attempt_reviews ADD FOREIGN KEY (task_attempt_id) REFERENCES task_attempts (id) ON DELETE CASCADE;
+ALTER TABLE attempt_groups ADD FOREIGN KEY (winner_attempt_id) REFERENCES task_attempts (id) ON DELETE SET NULL;
+ALTER TABLE attempt_groups ADD FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE;
+ALTER TABLE attempt_group_members ADD FOREIGN KEY (group_id) REFERENCES attempt_groups (id) ON DELETE CASCADE;
+ALTER TABLE attempt_group_members ADD FOREIGN KEY (task_attempt_id) REFERENCES task_attempts (id) ON DELETE CASCADE;
+ALTER TABLE attempt_rollbacks ADD FOREIGN KEY (execution_process_id) REFERENCES execution_processes (id) ON DELETE SET NULL;
+ALTER TABLE attempt_rollbacks ADD FOREIGN KEY (task_attempt_id) REFERENCES task_attempts (id) ON DELETE CASCADE;
+ALTER TABLE project_mcp_servers ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE project_sandboxes ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE project_containers ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE project_workers ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE execution_resource_samples ADD FOREIGN KEY (execution_process_id) REFERENCES execution_processes (id) ON DELETE CASCADE;
+ALTER TABLE task_pr_descriptions ADD FOREIGN KEY (task_attempt_id) REFERENCES task_attempts (id) ON DELETE CASCADE;
+ALTER TABLE task_pr_descriptions ADD FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE;
+ALTER TABLE executor_profiles ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE github_issue_links ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE github_issue_tasks ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE tracker_imports ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE tracker_issues ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE project_custom_fields ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE task_custom_field_values ADD FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE;
+ALTER TABLE project_board_columns ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE benchmark_suites ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE benchmark_cases ADD FOREIGN KEY (suite_id) REFERENCES benchmark_suites (id) ON DELETE CASCADE;
+ALTER TABLE benchmark_runs ADD FOREIGN KEY (suite_id) REFERENCES benchmark_suites (id) ON DELETE CASCADE;
+ALTER TABLE benchmark_results ADD FOREIGN KEY (run_id) REFERENCES benchmark_runs (id) ON DELETE CASCADE;
+ALTER TABLE benchmark_results ADD FOREIGN KEY (task_attempt_id) REFERENCES task_attempts (id) ON DELETE SET NULL;
+ALTER TABLE benchmark_results ADD FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE SET NULL;
+ALTER TABLE project_shell_environments ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE execution_inputs ADD FOREIGN KEY (execution_process_id) REFERENCES execution_processes (id) ON DELETE CASCADE;
+ALTER TABLE follow_up_drafts ADD FOREIGN KEY (task_attempt_id) REFERENCES task_attempts (id) ON DELETE SET NULL;
+ALTER TABLE follow_up_drafts ADD FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE;
+ALTER TABLE execution_snapshots ADD FOREIGN KEY (task_attempt_id) REFERENCES task_attempts (id) ON DELETE CASCADE;
+ALTER TABLE execution_snapshots ADD FOREIGN KEY (execution_process_id) REFERENCES execution_processes (id) ON DELETE CASCADE;
+ALTER TABLE execution_attention ADD FOREIGN KEY (task_attempt_id) REFERENCES task_attempts (id) ON DELETE CASCADE;
+ALTER TABLE execution_attention ADD FOREIGN KEY (execution_process_id) REFERENCES execution_processes (id) ON DELETE CASCADE;
+ALTER TABLE task_linked_projects ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE task_linked_projects ADD FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE;
+ALTER TABLE attempt_linked_worktrees ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE attempt_linked_worktrees ADD FOREIGN KEY (task_attempt_id) REFERENCES task_attempts (id) ON DELETE CASCADE;
+ALTER TABLE execution_tool_usage ADD FOREIGN KEY (task_attempt_id) REFERENCES task_attempts (id) ON DELETE CASCADE;
+ALTER TABLE execution_tool_usage ADD FOREIGN KEY (execution_process_id) REFERENCES execution_processes (id) ON DELETE CASCADE;
+ALTER TABLE task_share_links ADD FOREIGN KEY (created_by) REFERENCES users (id) ON DELETE SET NULL;
+ALTER TABLE task_share_links ADD FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE;
+ALTER TABLE project_guardrails ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE entry_bookmarks ADD FOREIGN KEY (created_by) REFERENCES users (id) ON DELETE SET NULL;
+ALTER TABLE entry_bookmarks ADD FOREIGN KEY (execution_process_id) REFERENCES execution_processes (id) ON DELETE CASCADE;
+ALTER TABLE entry_bookmarks ADD FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE;
+ALTER TABLE user_two_factor ADD FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE;
+ALTER TABLE user_recovery_codes ADD FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE;
+ALTER TABLE project_invites ADD FOREIGN KEY (accepted_by) REFERENCES users (id) ON DELETE SET NULL;
+ALTER TABLE project_invites ADD FOREIGN KEY (invited_by) REFERENCES users (id) ON DELETE SET NULL;
+ALTER TABLE project_invites ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
+ALTER TABLE mentions ADD FOREIGN KEY (mentioned_by) REFERENCES users (id) ON DELETE SET NULL;
+ALTER TABLE mentions ADD FOREIGN KEY (task_attempt_id) REFERENCES task_attempts (id) ON DELETE CASCADE;
+ALTER TABLE mentions ADD FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE;
+ALTER TABLE mentions ADD FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE;
+ALTER TABLE script_hooks ADD FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE;
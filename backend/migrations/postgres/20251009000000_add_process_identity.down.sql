@@ -0,0 +1 @@
+ALTER TABLE execution_processes DROP COLUMN process_identity;
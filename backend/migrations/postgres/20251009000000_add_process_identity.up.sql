@@ -0,0 +1,3 @@
+-- Which process led the process group: the boot it started in and its start time, to tell
+-- it apart after a restart from a later process that was given the same id
+ALTER TABLE execution_processes ADD COLUMN process_identity TEXT;
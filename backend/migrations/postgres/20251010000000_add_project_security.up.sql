@@ -0,0 +1,7 @@
+-- Sign-in rules a project holds its members to. Projects without a row have none.
+CREATE TABLE project_security (
+    project_id UUID PRIMARY KEY REFERENCES projects (id) ON DELETE CASCADE,
+    require_two_factor BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp()
+);
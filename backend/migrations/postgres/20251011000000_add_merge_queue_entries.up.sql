@@ -0,0 +1,21 @@
+-- The merge queue, kept so that merges queued when the server stops are picked up again
+-- when it starts. An attempt is queued at most once at a time.
+CREATE TABLE merge_queue_entries (
+    id UUID PRIMARY KEY,
+    project_id UUID NOT NULL REFERENCES projects (id) ON DELETE CASCADE,
+    task_id UUID NOT NULL REFERENCES tasks (id) ON DELETE CASCADE,
+    attempt_id UUID NOT NULL REFERENCES task_attempts (id) ON DELETE CASCADE,
+    task_title TEXT NOT NULL,
+    base_branch TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'queued',
+    error TEXT,
+    check_output TEXT,
+    merge_commit TEXT,
+    queued_at TIMESTAMPTZ NOT NULL DEFAULT statement_timestamp(),
+    finished_at TIMESTAMPTZ,
+    CHECK (status IN ('queued', 'rebasing', 'checking', 'merging', 'merged', 'failed'))
+);
+
+CREATE INDEX idx_merge_queue_entries_project_id ON merge_queue_entries (project_id);
+CREATE UNIQUE INDEX idx_merge_queue_entries_pending_attempt
+    ON merge_queue_entries (attempt_id) WHERE finished_at IS NULL;
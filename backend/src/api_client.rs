@@ -0,0 +1,343 @@
+//! Typed client for a running server's REST API, used by the `vibe` command line client and
+//! anything else scripting vibe-kanban from Rust. It speaks [`API_VERSION`] of the API and
+//! checks on connecting that the server does too, so a client and server from different
+//! releases fail up front rather than on the first endpoint that changed between them.
+//! Clients in other languages can be generated from `/api/openapi.json` instead; see
+//! `routes::openapi`.
+
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        project::Project,
+        task::TaskWithAttemptStatus,
+        task_attempt::{TaskAttempt, WorktreeDiff},
+        tracker_import::Tracker,
+    },
+    routes::{
+        health::{ServerVersion, API_VERSION},
+        task_attempts::ProcessLogsResponse,
+        tasks::CreatedTask,
+    },
+    services::tracker_import::TrackerSyncResult,
+    utils::port_file_path,
+};
+
+/// The `ApiResponse` envelope every endpoint returns
+#[derive(Debug, Deserialize)]
+struct Envelope<T> {
+    success: bool,
+    data: Option<T>,
+    message: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct NewTask<'a> {
+    project_id: Uuid,
+    title: &'a str,
+    description: Option<String>,
+    parent_task_attempt: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize)]
+struct NewAttempt<'a> {
+    executor: Option<&'a str>,
+    base_branch: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+struct NewTrackerImport<'a> {
+    tracker: Tracker,
+    key: &'a str,
+}
+
+pub struct ApiClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl ApiClient {
+    /// Connect to the server at `server`, or the local one found through the port file it
+    /// writes on startup, and check it speaks this client's API version
+    pub async fn connect(server: Option<String>) -> anyhow::Result<Self> {
+        let base_url = match server {
+            Some(url) => url.trim_end_matches('/').to_string(),
+            None => {
+                let port_file = port_file_path();
+                let port = std::fs::read_to_string(&port_file).with_context(|| {
+                    format!(
+                        "no running server found ({} is missing); start vibe-kanban or pass --server",
+                        port_file.display()
+                    )
+                })?;
+                format!("http://127.0.0.1:{}", port.trim())
+            }
+        };
+        let client = Self {
+            http: reqwest::Client::new(),
+            base_url,
+        };
+
+        let server = client
+            .get::<ServerVersion>("/version")
+            .await
+            .context("failed to check the server's API version")?;
+        if server.api_version != API_VERSION {
+            bail!(
+                "server {} speaks API version {}, but this client speaks {}; use a client from the same release",
+                server.version,
+                server.api_version,
+                API_VERSION
+            );
+        }
+        Ok(client)
+    }
+
+    /// Send a request and unwrap the envelope; `data` is `None` for endpoints returning `()`
+    async fn send<T: DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> anyhow::Result<Option<T>> {
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("failed to reach server at {}", self.base_url))?;
+        let status = response.status();
+        if !status.is_success() {
+            bail!("server returned {}", status);
+        }
+        let envelope: Envelope<T> = response.json().await.context("unexpected response")?;
+        if !envelope.success {
+            bail!(envelope
+                .message
+                .unwrap_or_else(|| "request failed".to_string()));
+        }
+        Ok(envelope.data)
+    }
+
+    /// The URL of an endpoint under this client's version of the API
+    fn url(&self, path: &str) -> String {
+        format!("{}/api/v{}{}", self.base_url, API_VERSION, path)
+    }
+
+    /// GET an endpoint under `/api/v1`
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> anyhow::Result<T> {
+        self.send(self.http.get(self.url(path)))
+            .await?
+            .ok_or_else(|| anyhow!("response had no data"))
+    }
+
+    fn post_request(&self, path: &str, body: &impl Serialize) -> reqwest::RequestBuilder {
+        self.http.post(self.url(path)).json(body)
+    }
+
+    /// POST `body` to an endpoint under `/api/v1`
+    pub async fn post<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+    ) -> anyhow::Result<T> {
+        self.send(self.post_request(path, body))
+            .await?
+            .ok_or_else(|| anyhow!("response had no data"))
+    }
+
+    /// POST to an endpoint that answers with no data on success
+    pub async fn post_unit(&self, path: &str, body: &impl Serialize) -> anyhow::Result<()> {
+        self.send::<serde_json::Value>(self.post_request(path, body))
+            .await
+            .map(|_| ())
+    }
+
+    fn attempts_path(project_id: Uuid, task_id: Uuid) -> String {
+        format!("/projects/{}/tasks/{}/attempts", project_id, task_id)
+    }
+
+    pub async fn projects(&self) -> anyhow::Result<Vec<Project>> {
+        self.get("/projects").await
+    }
+
+    pub async fn tasks(&self, project_id: Uuid) -> anyhow::Result<Vec<TaskWithAttemptStatus>> {
+        self.get(&format!("/projects/{}/tasks", project_id)).await
+    }
+
+    pub async fn create_task(
+        &self,
+        project_id: Uuid,
+        title: &str,
+        description: Option<String>,
+    ) -> anyhow::Result<CreatedTask> {
+        self.post(
+            &format!("/projects/{}/tasks", project_id),
+            &NewTask {
+                project_id,
+                title,
+                description,
+                parent_task_attempt: None,
+            },
+        )
+        .await
+    }
+
+    pub async fn attempts(
+        &self,
+        project_id: Uuid,
+        task_id: Uuid,
+    ) -> anyhow::Result<Vec<TaskAttempt>> {
+        self.get(&Self::attempts_path(project_id, task_id)).await
+    }
+
+    /// Start a new attempt; `None` runs the project's default executor on its default branch
+    pub async fn start_attempt(
+        &self,
+        project_id: Uuid,
+        task_id: Uuid,
+        executor: Option<&str>,
+        base_branch: Option<&str>,
+    ) -> anyhow::Result<TaskAttempt> {
+        self.post(
+            &Self::attempts_path(project_id, task_id),
+            &NewAttempt {
+                executor,
+                base_branch,
+            },
+        )
+        .await
+    }
+
+    /// The normalized conversation of every execution process of the attempt
+    pub async fn logs(
+        &self,
+        project_id: Uuid,
+        task_id: Uuid,
+        attempt_id: Uuid,
+    ) -> anyhow::Result<Vec<ProcessLogsResponse>> {
+        self.get(&format!(
+            "{}/{}/logs",
+            Self::attempts_path(project_id, task_id),
+            attempt_id
+        ))
+        .await
+    }
+
+    /// The attempt's changes against its base branch
+    pub async fn diff(
+        &self,
+        project_id: Uuid,
+        task_id: Uuid,
+        attempt_id: Uuid,
+    ) -> anyhow::Result<WorktreeDiff> {
+        self.get(&format!(
+            "{}/{}/diff",
+            Self::attempts_path(project_id, task_id),
+            attempt_id
+        ))
+        .await
+    }
+
+    pub async fn merge(
+        &self,
+        project_id: Uuid,
+        task_id: Uuid,
+        attempt_id: Uuid,
+    ) -> anyhow::Result<()> {
+        self.post_unit(
+            &format!(
+                "{}/{}/merge",
+                Self::attempts_path(project_id, task_id),
+                attempt_id
+            ),
+            &serde_json::json!({}),
+        )
+        .await
+    }
+
+    /// Import the open issues of a Jira project or Linear team as tasks
+    pub async fn import_tracker(
+        &self,
+        project_id: Uuid,
+        tracker: Tracker,
+        key: &str,
+    ) -> anyhow::Result<TrackerSyncResult> {
+        self.post(
+            &format!("/projects/{}/tasks/import/tracker", project_id),
+            &NewTrackerImport { tracker, key },
+        )
+        .await
+    }
+
+    /// Fetch issues updated since the last import of every source
+    pub async fn sync_trackers(&self, project_id: Uuid) -> anyhow::Result<TrackerSyncResult> {
+        self.post(
+            &format!("/projects/{}/tasks/import/tracker/sync", project_id),
+            &serde_json::json!({}),
+        )
+        .await
+    }
+}
+
+/// Pick the single item whose id starts with `prefix`
+pub fn resolve_by_prefix<'a, T>(
+    items: &'a [T],
+    prefix: &str,
+    id: impl Fn(&T) -> Uuid,
+    kind: &str,
+) -> anyhow::Result<&'a T> {
+    let prefix = prefix.to_lowercase();
+    let mut matches = items
+        .iter()
+        .filter(|item| id(item).to_string().starts_with(&prefix));
+    match (matches.next(), matches.next()) {
+        (Some(item), None) => Ok(item),
+        (None, _) => bail!("no {} matches '{}'", kind, prefix),
+        (Some(_), Some(_)) => bail!("'{}' matches more than one {}", prefix, kind),
+    }
+}
+
+/// Find the project by id or name, or the one whose repository contains `cwd`
+pub fn resolve_project<'a>(
+    projects: &'a [Project],
+    selector: Option<&str>,
+    cwd: &Path,
+) -> anyhow::Result<&'a Project> {
+    if let Some(selector) = selector {
+        if let Some(project) = projects
+            .iter()
+            .find(|p| p.name.eq_ignore_ascii_case(selector))
+        {
+            return Ok(project);
+        }
+        return resolve_by_prefix(projects, selector, |p| p.id, "project");
+    }
+
+    projects
+        .iter()
+        .filter(|p| {
+            let repo = Path::new(&p.git_repo_path);
+            let repo = repo.canonicalize().unwrap_or_else(|_| repo.to_path_buf());
+            cwd.starts_with(repo)
+        })
+        .max_by_key(|p| p.git_repo_path.len())
+        .ok_or_else(|| anyhow!("not inside a known project repository; pass --project"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_by_prefix_needs_a_single_match() {
+        let ids = [
+            Uuid::parse_str("3f2a0000-0000-0000-0000-000000000000").unwrap(),
+            Uuid::parse_str("3f2b0000-0000-0000-0000-000000000000").unwrap(),
+        ];
+        let found = resolve_by_prefix(&ids, "3F2A", |id| *id, "task").unwrap();
+        assert_eq!(*found, ids[0]);
+        assert!(resolve_by_prefix(&ids, "3f2", |id| *id, "task").is_err());
+        assert!(resolve_by_prefix(&ids, "ff", |id| *id, "task").is_err());
+    }
+}
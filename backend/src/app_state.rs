@@ -1,14 +1,43 @@
-use std::{collections::HashMap, path::PathBuf, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
+};
 
+use chrono::{DateTime, Utc};
 use tokio::sync::{Mutex, RwLock as TokioRwLock};
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
 use crate::{
     command_runner,
+    db::DbPool,
+    executor::{Executor, NormalizedConversation, NormalizerState},
     models::Environment,
-    services::{generate_user_id, AnalyticsConfig, AnalyticsService},
+    services::{
+        command_approval::PendingApproval, generate_user_id, merge_queue::MergeQueue,
+        AnalyticsConfig, AnalyticsService, LabelSuggester, ReplayGuard,
+    },
 };
 
+/// Environment variable enabling server-wide dry-run mode
+pub const DRY_RUN_ENV: &str = "VIBE_KANBAN_DRY_RUN";
+
+/// Dry runs play agents back with the mock executor, so they need a build that has it
+fn dry_run_from_env() -> bool {
+    let requested = std::env::var(DRY_RUN_ENV)
+        .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false);
+    if requested && !cfg!(any(test, feature = "mock-executor")) {
+        tracing::warn!(
+            "{} is set, but this build has no mock executor (feature `mock-executor`); ignoring it",
+            DRY_RUN_ENV
+        );
+        return false;
+    }
+    requested
+}
+
 #[derive(Debug)]
 pub enum ExecutionType {
     SetupScript,
@@ -22,21 +51,51 @@ pub struct RunningExecution {
     pub task_attempt_id: Uuid,
     pub _execution_type: ExecutionType,
     pub child: command_runner::CommandProcess,
+    /// Created on the first log poll, then fed only the output added since
+    pub normalizer: Option<NormalizerState>,
+    /// The command the agent is waiting to be allowed or denied, if there is one
+    pub approval: Option<PendingApproval>,
+}
+
+/// An execution the monitor found to have exited, or killed for running past its deadline,
+/// going over its resource limits or being cancelled
+#[derive(Debug)]
+pub struct FinishedExecution {
+    pub execution_id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub success: bool,
+    pub exit_code: Option<i64>,
+    pub timed_out: bool,
+    pub limit_exceeded: bool,
+    /// Stopped as it was being registered, after the stop had looked for it
+    pub cancelled: bool,
 }
 
 #[derive(Debug, Clone)]
 pub struct AppState {
     running_executions: Arc<Mutex<HashMap<Uuid, RunningExecution>>>,
-    pub db_pool: sqlx::SqlitePool,
+    /// Attempts whose verification commands are running after their coding agent finished
+    verifying_attempts: Arc<Mutex<HashSet<Uuid>>>,
+    /// The cancellation token of each execution from before it's spawned until what follows
+    /// it is done: its verification and committing what it changed
+    cancellations: Arc<Mutex<HashMap<Uuid, CancellationToken>>>,
+    pub db_pool: DbPool,
     config: Arc<tokio::sync::RwLock<crate::models::config::Config>>,
     pub analytics: Arc<TokioRwLock<AnalyticsService>>,
     user_id: String,
     pub mode: Environment,
+    pub webhook_replay_guard: Arc<ReplayGuard>,
+    /// Classifiers run on new tasks to propose labels
+    pub label_suggester: Arc<LabelSuggester>,
+    /// Serializes merges into each base branch
+    pub merge_queue: Arc<MergeQueue>,
+    /// Run every coding agent with the mock executor and refuse to merge, push or release
+    pub dry_run: bool,
 }
 
 impl AppState {
     pub async fn new(
-        db_pool: sqlx::SqlitePool,
+        db_pool: DbPool,
         config: Arc<tokio::sync::RwLock<crate::models::config::Config>>,
         mode: Environment,
     ) -> Self {
@@ -51,11 +110,17 @@ impl AppState {
 
         Self {
             running_executions: Arc::new(Mutex::new(HashMap::new())),
+            verifying_attempts: Arc::new(Mutex::new(HashSet::new())),
+            cancellations: Arc::new(Mutex::new(HashMap::new())),
             db_pool,
             config,
             analytics,
             user_id: generate_user_id(),
             mode,
+            webhook_replay_guard: Arc::new(ReplayGuard::default()),
+            label_suggester: Arc::new(LabelSuggester::default()),
+            merge_queue: Arc::new(MergeQueue::default()),
+            dry_run: dry_run_from_env(),
         }
     }
 
@@ -79,54 +144,203 @@ impl AppState {
 
     // Running executions getters
     pub async fn has_running_execution(&self, attempt_id: Uuid) -> bool {
+        if self.verifying_attempts.lock().await.contains(&attempt_id) {
+            return true;
+        }
         let executions = self.running_executions.lock().await;
         executions
             .values()
             .any(|exec| exec.task_attempt_id == attempt_id)
     }
 
-    pub async fn get_running_executions_for_monitor(&self) -> Vec<(Uuid, Uuid, bool, Option<i64>)> {
+    /// Count the attempt as running while its verification commands run
+    pub async fn start_verification(&self, attempt_id: Uuid) {
+        self.verifying_attempts.lock().await.insert(attempt_id);
+    }
+
+    pub async fn finish_verification(&self, attempt_id: Uuid) {
+        self.verifying_attempts.lock().await.remove(&attempt_id);
+    }
+
+    /// Give the execution a cancellation token, before anything is started for it
+    pub async fn register_cancellation(&self, execution_id: Uuid) -> CancellationToken {
+        self.cancellations
+            .lock()
+            .await
+            .entry(execution_id)
+            .or_default()
+            .clone()
+    }
+
+    /// The execution's cancellation token. One no longer registered was cancelled, so that is
+    /// what's handed out for it.
+    pub async fn cancellation(&self, execution_id: Uuid) -> CancellationToken {
+        match self.cancellations.lock().await.get(&execution_id) {
+            Some(token) => token.clone(),
+            None => {
+                let token = CancellationToken::new();
+                token.cancel();
+                token
+            }
+        }
+    }
+
+    /// Forget the execution's cancellation token once nothing is left to run for it
+    pub async fn forget_cancellation(&self, execution_id: Uuid) {
+        self.cancellations.lock().await.remove(&execution_id);
+    }
+
+    /// Cancel whatever still runs for the execution: its process, the command spawning it,
+    /// its verification or the commit of its changes. Returns whether anything did.
+    pub async fn cancel_execution(&self, execution_id: Uuid) -> bool {
+        match self.cancellations.lock().await.remove(&execution_id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub async fn get_running_executions_for_monitor(&self) -> Vec<FinishedExecution> {
         let mut executions = self.running_executions.lock().await;
         let mut completed_executions = Vec::new();
 
         for (execution_id, running_exec) in executions.iter_mut() {
+            let (execution_id, task_attempt_id) = (*execution_id, running_exec.task_attempt_id);
+            let finished =
+                move |success, exit_code, timed_out, limit_exceeded, cancelled| FinishedExecution {
+                    execution_id,
+                    task_attempt_id,
+                    success,
+                    exit_code,
+                    timed_out,
+                    limit_exceeded,
+                    cancelled,
+                };
+            match running_exec.child.kill_if_cancelled().await {
+                Ok(true) => {
+                    tracing::info!("Execution {} was cancelled and killed", execution_id);
+                    completed_executions.push(finished(false, None, false, false, true));
+                    continue;
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    tracing::error!("Failed to kill cancelled execution {}: {}", execution_id, e);
+                }
+            }
             match running_exec.child.try_wait().await {
                 Ok(Some(status)) => {
-                    let success = status.success();
                     let exit_code = status.code().map(|c| c as i64);
-                    completed_executions.push((
-                        *execution_id,
-                        running_exec.task_attempt_id,
-                        success,
+                    let limit_exceeded = match running_exec.child.limit_exceeded().await {
+                        Some(reason) if !status.success() => {
+                            tracing::warn!("Execution {} {}", execution_id, reason);
+                            true
+                        }
+                        _ => false,
+                    };
+                    completed_executions.push(finished(
+                        status.success(),
                         exit_code,
+                        false,
+                        limit_exceeded,
+                        false,
                     ));
                 }
-                Ok(None) => {
-                    // Still running
-                }
+                Ok(None) => match running_exec.child.kill_if_overdue().await {
+                    Ok(true) => {
+                        tracing::warn!("Execution {} timed out and was killed", execution_id);
+                        completed_executions.push(finished(false, None, true, false, false));
+                    }
+                    Ok(false) => match running_exec.child.kill_if_over_limits().await {
+                        Ok(Some(reason)) => {
+                            tracing::warn!("Execution {} {} and was killed", execution_id, reason);
+                            completed_executions.push(finished(false, None, false, true, false));
+                        }
+                        Ok(None) => {
+                            // Still running
+                        }
+                        Err(e) => {
+                            tracing::error!(
+                                "Failed to kill execution {} over its resource limits: {}",
+                                execution_id,
+                                e
+                            );
+                        }
+                    },
+                    Err(e) => {
+                        tracing::error!(
+                            "Failed to kill timed out execution {}: {}",
+                            execution_id,
+                            e
+                        );
+                    }
+                },
                 Err(e) => {
                     tracing::error!("Error checking process status: {}", e);
-                    completed_executions.push((
-                        *execution_id,
-                        running_exec.task_attempt_id,
-                        false,
-                        None,
-                    ));
+                    completed_executions.push(finished(false, None, false, false, false));
                 }
             }
         }
 
         // Remove completed executions from the map
-        for (execution_id, _, _, _) in &completed_executions {
-            executions.remove(execution_id);
+        for finished in &completed_executions {
+            executions.remove(&finished.execution_id);
+            command_runner::close_output_channel(finished.execution_id);
         }
 
         completed_executions
     }
 
+    /// Sample the CPU and memory use of each running execution where it can be measured
+    pub async fn sample_running_resources(&self) -> Vec<(Uuid, command_runner::ResourcePoint)> {
+        let mut executions = self.running_executions.lock().await;
+        let mut points = Vec::new();
+        for (execution_id, execution) in executions.iter_mut() {
+            match execution.child.sample_resources().await {
+                Ok(Some(point)) => points.push((*execution_id, point)),
+                Ok(None) => {}
+                Err(e) => tracing::debug!(
+                    "Failed to sample resources of execution {}: {}",
+                    execution_id,
+                    e
+                ),
+            }
+        }
+        points
+    }
+
+    /// Normalizes a running execution's stdout, reusing the work done on earlier polls and
+    /// timing new entries by `timeline` (see [`NormalizerState::advance_timed`]). Returns
+    /// `None` if the execution isn't running, so there is no state to reuse.
+    pub async fn normalize_running_output(
+        &self,
+        execution_id: Uuid,
+        executor: &dyn Executor,
+        executor_type: &str,
+        stdout: &str,
+        timeline: &[(usize, DateTime<Utc>)],
+        worktree_path: &str,
+    ) -> Option<Result<NormalizedConversation, String>> {
+        let mut executions = self.running_executions.lock().await;
+        let execution = executions.get_mut(&execution_id)?;
+        let state = execution
+            .normalizer
+            .get_or_insert_with(|| NormalizerState::new(worktree_path));
+        match state.advance_timed(executor, stdout, timeline) {
+            Ok(()) => Some(Ok(state.conversation(executor_type))),
+            Err(e) => {
+                // Half-applied chunks would leave the state inconsistent; start over next poll
+                execution.normalizer = None;
+                Some(Err(e))
+            }
+        }
+    }
+
     // Running executions setters
     pub async fn add_running_execution(&self, execution_id: Uuid, execution: RunningExecution) {
         let mut executions = self.running_executions.lock().await;
+        command_runner::open_output_channel(execution_id);
         executions.insert(execution_id, execution);
     }
 
@@ -134,6 +348,9 @@ impl AppState {
         &self,
         execution_id: Uuid,
     ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        // Whatever runs for it but isn't its process stops with the token
+        self.cancel_execution(execution_id).await;
+
         let mut executions = self.running_executions.lock().await;
         let Some(exec) = executions.get_mut(&execution_id) else {
             return Ok(false);
@@ -147,6 +364,108 @@ impl AppState {
 
         // only NOW remove it
         executions.remove(&execution_id);
+        command_runner::close_output_channel(execution_id);
+        // Its secrets stay redacted until the last of its output is stored
+        tokio::spawn(async move {
+            command_runner::wait_for_output_writers(
+                execution_id,
+                crate::execution_monitor::OUTPUT_DRAIN_TIMEOUT,
+            )
+            .await;
+            crate::services::environment_variables::forget_redactions(execution_id);
+        });
+        Ok(true)
+    }
+
+    /// Pause a running execution. Returns `false` if it isn't running.
+    pub async fn pause_running_execution_by_id(
+        &self,
+        execution_id: Uuid,
+    ) -> Result<bool, command_runner::CommandError> {
+        let mut executions = self.running_executions.lock().await;
+        let Some(exec) = executions.get_mut(&execution_id) else {
+            return Ok(false);
+        };
+        exec.child.pause().await?;
+        Ok(true)
+    }
+
+    /// Resume a paused execution. Returns `false` if it isn't running.
+    pub async fn resume_running_execution_by_id(
+        &self,
+        execution_id: Uuid,
+    ) -> Result<bool, command_runner::CommandError> {
+        let mut executions = self.running_executions.lock().await;
+        let Some(exec) = executions.get_mut(&execution_id) else {
+            return Ok(false);
+        };
+        exec.child.resume().await?;
+        Ok(true)
+    }
+
+    /// Write to a running execution's stdin. Returns `false` if it isn't running.
+    pub async fn write_running_execution_stdin(
+        &self,
+        execution_id: Uuid,
+        data: &str,
+    ) -> Result<bool, command_runner::CommandError> {
+        let mut executions = self.running_executions.lock().await;
+        let Some(exec) = executions.get_mut(&execution_id) else {
+            return Ok(false);
+        };
+        exec.child.write_stdin(data).await?;
+        Ok(true)
+    }
+
+    /// Close a running execution's stdin, for CLIs that finish once their input ends.
+    /// Returns `false` if the execution isn't running.
+    pub async fn close_running_execution_stdin(
+        &self,
+        execution_id: Uuid,
+    ) -> Result<bool, command_runner::CommandError> {
+        let mut executions = self.running_executions.lock().await;
+        let Some(exec) = executions.get_mut(&execution_id) else {
+            return Ok(false);
+        };
+        exec.child.close_stdin().await?;
+        Ok(true)
+    }
+
+    /// The command a running execution is waiting to be allowed or denied, if any
+    pub async fn pending_approval(&self, execution_id: Uuid) -> Option<PendingApproval> {
+        let executions = self.running_executions.lock().await;
+        executions.get(&execution_id)?.approval.clone()
+    }
+
+    /// Hold the execution's command for the user. Returns `false` if it isn't running.
+    pub async fn request_approval(&self, approval: PendingApproval) -> bool {
+        let mut executions = self.running_executions.lock().await;
+        match executions.get_mut(&approval.execution_process_id) {
+            Some(exec) => {
+                exec.approval = Some(approval);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Write `reply` to the execution's stdin if `approval_id` is the approval it's waiting
+    /// on, which is then no longer pending. Returns `false` if it isn't.
+    pub async fn answer_approval(
+        &self,
+        execution_id: Uuid,
+        approval_id: Uuid,
+        reply: &str,
+    ) -> Result<bool, command_runner::CommandError> {
+        let mut executions = self.running_executions.lock().await;
+        let Some(exec) = executions.get_mut(&execution_id) else {
+            return Ok(false);
+        };
+        if exec.approval.as_ref().map(|approval| approval.id) != Some(approval_id) {
+            return Ok(false);
+        }
+        exec.child.write_stdin(reply).await?;
+        exec.approval = None;
         Ok(true)
     }
 
@@ -161,6 +480,11 @@ impl AppState {
         config.push_notifications
     }
 
+    pub async fn get_notification_events(&self) -> crate::models::config::NotificationEvents {
+        let config = self.config.read().await;
+        config.notification_events.clone()
+    }
+
     pub async fn get_sound_file(&self) -> crate::models::config::SoundFile {
         let config = self.config.read().await;
         config.sound_file.clone()
@@ -1,45 +1,32 @@
-use std::{collections::HashMap, sync::Arc};
+use std::sync::Arc;
 
 use axum::{
-    body::Body,
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Request, State,
+    },
+    http::{header, StatusCode},
+    middleware::{from_fn_with_state, Next},
     response::{Json, Response},
-    routing::{delete, get, post},
+    routing::get,
     Router,
 };
+use futures_util::{
+    stream::{SplitSink, SplitStream},
+    SinkExt, StreamExt,
+};
 use serde::Serialize;
-use tokio::sync::Mutex;
+use subtle::ConstantTimeEq;
+use tokio::{io::AsyncRead, sync::mpsc};
 use tokio_util::io::ReaderStream;
 use tracing_subscriber::prelude::*;
 use uuid::Uuid;
-use vibe_kanban::command_runner::{CommandProcess, CommandRunner, CommandRunnerArgs};
-
-// Structure to hold process and its streams
-struct ProcessEntry {
-    process: CommandProcess,
-    // Store the actual stdout/stderr streams for direct streaming
-    stdout_stream: Option<Box<dyn tokio::io::AsyncRead + Unpin + Send>>,
-    stderr_stream: Option<Box<dyn tokio::io::AsyncRead + Unpin + Send>>,
-    completed: Arc<Mutex<bool>>,
-}
-
-impl std::fmt::Debug for ProcessEntry {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("ProcessEntry")
-            .field("process", &self.process)
-            .field("stdout_stream", &self.stdout_stream.is_some())
-            .field("stderr_stream", &self.stderr_stream.is_some())
-            .field("completed", &self.completed)
-            .finish()
-    }
-}
-
-// Application state to manage running processes
-#[derive(Clone)]
-struct AppState {
-    processes: Arc<Mutex<HashMap<String, ProcessEntry>>>,
-}
+use vibe_kanban::command_runner::{
+    worker_protocol::{
+        WorkerEvent, WorkerRequest, STDERR_FRAME, STDOUT_FRAME, WORKER_PATH, WORKER_TOKEN_VAR,
+    },
+    CommandProcess, CommandRunner,
+};
 
 // Response type for API responses
 #[derive(Debug, Serialize)]
@@ -57,30 +44,6 @@ impl<T> ApiResponse<T> {
             error: None,
         }
     }
-
-    #[allow(dead_code)]
-    fn error(message: String) -> Self {
-        Self {
-            success: false,
-            data: None,
-            error: Some(message),
-        }
-    }
-}
-
-// Response type for command creation
-#[derive(Debug, Serialize)]
-struct CreateCommandResponse {
-    process_id: String,
-}
-
-// Response type for process status
-#[derive(Debug, Serialize)]
-struct ProcessStatusResponse {
-    process_id: String,
-    running: bool,
-    exit_code: Option<i32>,
-    success: Option<bool>,
 }
 
 #[tokio::main]
@@ -94,20 +57,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    // Create application state
-    let app_state = AppState {
-        processes: Arc::new(Mutex::new(HashMap::new())),
+    // Whoever can reach the runner can run commands on its machine, so it never runs open
+    let token = match std::env::var(WORKER_TOKEN_VAR) {
+        Ok(token) if !token.is_empty() => Arc::<str>::from(token),
+        _ => {
+            tracing::error!(
+                "{} must be set to the token the server connects with",
+                WORKER_TOKEN_VAR
+            );
+            return Err(format!("{} is not set", WORKER_TOKEN_VAR).into());
+        }
     };
 
     // Build router
     let app = Router::new()
-        .route("/health", get(health_check))
-        .route("/commands", post(create_command))
-        .route("/commands/:process_id", delete(kill_command))
-        .route("/commands/:process_id/status", get(get_process_status))
-        .route("/commands/:process_id/stdout", get(get_process_stdout))
-        .route("/commands/:process_id/stderr", get(get_process_stderr))
-        .with_state(app_state);
+        .route(WORKER_PATH, get(worker))
+        .layer(from_fn_with_state(token, require_worker_token))
+        .route("/health", get(health_check));
 
     // Get port from environment or default to 8000
     let port = std::env::var("PORT").unwrap_or_else(|_| "8000".to_string());
@@ -122,280 +88,172 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Only serves requests carrying the worker token as a bearer token
+async fn require_worker_token(
+    State(token): State<Arc<str>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let presented = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or_default();
+    if bool::from(presented.as_bytes().ct_eq(token.as_bytes())) {
+        Ok(next.run(request).await)
+    } else {
+        Err(StatusCode::UNAUTHORIZED)
+    }
+}
+
 // Health check endpoint
 async fn health_check() -> Json<ApiResponse<String>> {
     Json(ApiResponse::success("Cloud Runner is healthy".to_string()))
 }
 
-// Create and start a new command
-async fn create_command(
-    State(state): State<AppState>,
-    Json(request): Json<CommandRunnerArgs>,
-) -> Result<Json<ApiResponse<CreateCommandResponse>>, StatusCode> {
-    tracing::info!("Creating command: {} {:?}", request.command, request.args);
+async fn worker(upgrade: WebSocketUpgrade) -> Response {
+    upgrade.on_upgrade(run_command)
+}
 
-    // Create a local command runner from the request
-    let runner = CommandRunner::from_args(request);
+async fn send_event(sink: &mut SplitSink<WebSocket, Message>, event: &WorkerEvent) -> bool {
+    match serde_json::to_string(event) {
+        Ok(text) => sink.send(Message::Text(text)).await.is_ok(),
+        Err(_) => false,
+    }
+}
 
-    // Start the process
-    let mut process = match runner.start().await {
-        Ok(process) => process,
-        Err(e) => {
-            tracing::error!("Failed to start command: {}", e);
-            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+/// The next request from the server, or None once it has hung up
+async fn next_request(incoming: &mut SplitStream<WebSocket>) -> Option<WorkerRequest> {
+    loop {
+        match incoming.next().await? {
+            Ok(Message::Text(text)) => match serde_json::from_str(&text) {
+                Ok(request) => return Some(request),
+                Err(e) => tracing::warn!("Ignoring unreadable request: {}", e),
+            },
+            Ok(Message::Close(_)) | Err(_) => return None,
+            Ok(_) => {}
         }
-    };
+    }
+}
 
-    // Generate unique process ID
-    let process_id = Uuid::new_v4().to_string();
+/// Run the one command the connection is for, streaming its output back and reporting
+/// its exit. The command is killed if the server hangs up before it exits.
+async fn run_command(socket: WebSocket) {
+    let (mut sink, mut incoming) = socket.split();
 
-    // Create completion flag
-    let completed = Arc::new(Mutex::new(false));
+    let Some(WorkerRequest::Start { args }) = next_request(&mut incoming).await else {
+        return;
+    };
+    tracing::info!("Creating command: {} {:?}", args.command, args.args);
 
-    // Get the streams from the process - we'll store them directly
-    let mut streams = match process.stream().await {
-        Ok(streams) => streams,
+    let started = match CommandRunner::from_args(args).start().await {
+        Ok(mut process) => process.stream().await.map(|streams| (process, streams)),
+        Err(e) => Err(e),
+    };
+    let (mut process, streams) = match started {
+        Ok(started) => started,
         Err(e) => {
-            tracing::error!("Failed to get process streams: {}", e);
-            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            tracing::error!("Failed to start command: {}", e);
+            let message = e.to_string();
+            send_event(&mut sink, &WorkerEvent::Error { message }).await;
+            return;
         }
     };
 
-    // Extract the streams for direct use
-    let stdout_stream = streams.stdout.take();
-    let stderr_stream = streams.stderr.take();
-
-    // Spawn a task to monitor process completion
-    {
-        let process_id_for_completion = process_id.clone();
-        let completed_flag = completed.clone();
-        let processes_ref = state.processes.clone();
-        tokio::spawn(async move {
-            // Wait for the process to complete
-            if let Ok(mut processes) = processes_ref.try_lock() {
-                if let Some(entry) = processes.get_mut(&process_id_for_completion) {
-                    let _ = entry.process.wait().await;
-                    *completed_flag.lock().await = true;
-                    tracing::debug!("Marked process {} as completed", process_id_for_completion);
-                }
-            }
-        });
+    let process_id = Uuid::new_v4().to_string();
+    tracing::info!("Command started with process_id: {}", process_id);
+    if !send_event(&mut sink, &WorkerEvent::Started { process_id }).await {
+        let _ = process.kill().await;
+        return;
     }
 
-    // Create process entry
-    let entry = ProcessEntry {
-        process,
-        stdout_stream,
-        stderr_stream,
-        completed: completed.clone(),
-    };
-
-    // Store the process entry
-    {
-        let mut processes = state.processes.lock().await;
-        processes.insert(process_id.clone(), entry);
+    let (output_tx, mut output_rx) = mpsc::channel(64);
+    for (tag, stream) in [
+        (STDOUT_FRAME, streams.stdout),
+        (STDERR_FRAME, streams.stderr),
+    ] {
+        if let Some(stream) = stream {
+            tokio::spawn(forward_output(tag, stream, output_tx.clone()));
+        }
     }
-
-    tracing::info!("Command started with process_id: {}", process_id);
-
-    Ok(Json(ApiResponse::success(CreateCommandResponse {
-        process_id,
-    })))
-}
-
-// Kill a running command
-async fn kill_command(
-    State(state): State<AppState>,
-    Path(process_id): Path<String>,
-) -> Result<Json<ApiResponse<String>>, StatusCode> {
-    tracing::info!("Killing command with process_id: {}", process_id);
-
-    let mut processes = state.processes.lock().await;
-
-    if let Some(mut entry) = processes.remove(&process_id) {
-        // First check if the process has already finished
-        match entry.process.status().await {
-            Ok(Some(_)) => {
-                // Process already finished, consider kill successful
-                tracing::info!(
-                    "Process {} already completed, kill considered successful",
-                    process_id
-                );
-                Ok(Json(ApiResponse::success(
-                    "Process was already completed".to_string(),
-                )))
-            }
-            Ok(None) => {
-                // Process still running, attempt to kill
-                match entry.process.kill().await {
-                    Ok(()) => {
-                        tracing::info!("Successfully killed process: {}", process_id);
-                        Ok(Json(ApiResponse::success(
-                            "Process killed successfully".to_string(),
-                        )))
-                    }
-                    Err(e) => {
-                        tracing::error!("Failed to kill process {}: {}", process_id, e);
-
-                        // Check if it's a "No such process" error (process finished during kill)
-                        if e.to_string().contains("No such process") {
-                            tracing::info!("Process {} finished during kill attempt", process_id);
-                            Ok(Json(ApiResponse::success(
-                                "Process finished during kill attempt".to_string(),
-                            )))
-                        } else {
-                            Err(StatusCode::INTERNAL_SERVER_ERROR)
-                        }
+    drop(output_tx);
+
+    // Stream the output until both streams end, then wait for the exit
+    let mut output_open = true;
+    let status = loop {
+        tokio::select! {
+            frame = output_rx.recv(), if output_open => match frame {
+                Some(frame) => {
+                    if sink.send(Message::Binary(frame)).await.is_err() {
+                        let _ = process.kill().await;
+                        return;
                     }
                 }
-            }
-            Err(e) => {
-                tracing::error!("Failed to check status for process {}: {}", process_id, e);
-                // Still attempt to kill
-                match entry.process.kill().await {
-                    Ok(()) => {
-                        tracing::info!("Successfully killed process: {}", process_id);
-                        Ok(Json(ApiResponse::success(
-                            "Process killed successfully".to_string(),
-                        )))
-                    }
-                    Err(e) => {
-                        tracing::error!("Failed to kill process {}: {}", process_id, e);
-                        Err(StatusCode::INTERNAL_SERVER_ERROR)
-                    }
+                None => output_open = false,
+            },
+            status = process.wait(), if !output_open => break status,
+            request = next_request(&mut incoming) => match request {
+                Some(request) => handle_request(&mut process, request).await,
+                None => {
+                    tracing::info!("Server hung up, killing the command");
+                    let _ = process.kill().await;
+                    return;
                 }
-            }
+            },
         }
-    } else {
-        tracing::warn!("Process not found: {}", process_id);
-        Err(StatusCode::NOT_FOUND)
-    }
-}
-
-// Get status of a running command
-async fn get_process_status(
-    State(state): State<AppState>,
-    Path(process_id): Path<String>,
-) -> Result<Json<ApiResponse<ProcessStatusResponse>>, StatusCode> {
-    tracing::info!("Getting status for process_id: {}", process_id);
+    };
 
-    let mut processes = state.processes.lock().await;
+    let event = match status {
+        Ok(status) => WorkerEvent::Exited {
+            code: status.code(),
+            success: status.success(),
+        },
+        Err(e) => WorkerEvent::Error {
+            message: e.to_string(),
+        },
+    };
+    send_event(&mut sink, &event).await;
+    let _ = sink.close().await;
+}
 
-    if let Some(entry) = processes.get_mut(&process_id) {
-        match entry.process.status().await {
-            Ok(Some(exit_status)) => {
-                // Process has completed
-                let response = ProcessStatusResponse {
-                    process_id: process_id.clone(),
-                    running: false,
-                    exit_code: exit_status.code(),
-                    success: Some(exit_status.success()),
-                };
-                Ok(Json(ApiResponse::success(response)))
-            }
-            Ok(None) => {
-                // Process is still running
-                let response = ProcessStatusResponse {
-                    process_id: process_id.clone(),
-                    running: true,
-                    exit_code: None,
-                    success: None,
-                };
-                Ok(Json(ApiResponse::success(response)))
-            }
-            Err(e) => {
-                tracing::error!("Failed to get status for process {}: {}", process_id, e);
-                Err(StatusCode::INTERNAL_SERVER_ERROR)
-            }
+async fn handle_request(process: &mut CommandProcess, request: WorkerRequest) {
+    let name = request_name(&request);
+    let result = match request {
+        WorkerRequest::Kill => process.kill().await,
+        WorkerRequest::Stdin { data } => process.write_stdin(&data).await,
+        WorkerRequest::CloseStdin => process.close_stdin().await,
+        WorkerRequest::Start { .. } => {
+            tracing::warn!("Ignoring a second start on a connection already running a command");
+            Ok(())
         }
-    } else {
-        tracing::warn!("Process not found: {}", process_id);
-        Err(StatusCode::NOT_FOUND)
+    };
+    if let Err(e) = result {
+        tracing::error!("Failed to handle {}: {}", name, e);
     }
 }
 
-// Get stdout stream for a running command (direct streaming, no buffering)
-async fn get_process_stdout(
-    State(state): State<AppState>,
-    Path(process_id): Path<String>,
-) -> Result<Response, StatusCode> {
-    tracing::info!(
-        "Starting direct stdout stream for process_id: {}",
-        process_id
-    );
-
-    let mut processes = state.processes.lock().await;
-
-    if let Some(entry) = processes.get_mut(&process_id) {
-        // Take ownership of stdout directly for streaming
-        if let Some(stdout) = entry.stdout_stream.take() {
-            drop(processes); // Release the lock early
-
-            // Convert the AsyncRead (stdout) directly into an HTTP stream
-            let stream = ReaderStream::new(stdout);
-
-            let response = Response::builder()
-                .header("content-type", "application/octet-stream")
-                .header("cache-control", "no-cache")
-                .body(Body::from_stream(stream))
-                .map_err(|e| {
-                    tracing::error!("Failed to build response stream: {}", e);
-                    StatusCode::INTERNAL_SERVER_ERROR
-                })?;
-
-            Ok(response)
-        } else {
-            tracing::error!(
-                "Stdout already taken or unavailable for process {}",
-                process_id
-            );
-            Err(StatusCode::GONE)
-        }
-    } else {
-        tracing::warn!("Process not found for stdout: {}", process_id);
-        Err(StatusCode::NOT_FOUND)
+fn request_name(request: &WorkerRequest) -> &'static str {
+    match request {
+        WorkerRequest::Start { .. } => "start",
+        WorkerRequest::Stdin { .. } => "stdin",
+        WorkerRequest::CloseStdin => "close stdin",
+        WorkerRequest::Kill => "kill",
     }
 }
 
-// Get stderr stream for a running command (direct streaming, no buffering)
-async fn get_process_stderr(
-    State(state): State<AppState>,
-    Path(process_id): Path<String>,
-) -> Result<Response, StatusCode> {
-    tracing::info!(
-        "Starting direct stderr stream for process_id: {}",
-        process_id
-    );
-
-    let mut processes = state.processes.lock().await;
-
-    if let Some(entry) = processes.get_mut(&process_id) {
-        // Take ownership of stderr directly for streaming
-        if let Some(stderr) = entry.stderr_stream.take() {
-            drop(processes); // Release the lock early
-
-            // Convert the AsyncRead (stderr) directly into an HTTP stream
-            let stream = ReaderStream::new(stderr);
-
-            let response = Response::builder()
-                .header("content-type", "application/octet-stream")
-                .header("cache-control", "no-cache")
-                .body(Body::from_stream(stream))
-                .map_err(|e| {
-                    tracing::error!("Failed to build response stream: {}", e);
-                    StatusCode::INTERNAL_SERVER_ERROR
-                })?;
-
-            Ok(response)
-        } else {
-            tracing::error!(
-                "Stderr already taken or unavailable for process {}",
-                process_id
-            );
-            Err(StatusCode::GONE)
+async fn forward_output(
+    tag: u8,
+    stream: Box<dyn AsyncRead + Unpin + Send>,
+    output: mpsc::Sender<Vec<u8>>,
+) {
+    let mut chunks = ReaderStream::new(stream);
+    while let Some(Ok(chunk)) = chunks.next().await {
+        let mut frame = Vec::with_capacity(chunk.len() + 1);
+        frame.push(tag);
+        frame.extend_from_slice(&chunk);
+        if output.send(frame).await.is_err() {
+            break;
         }
-    } else {
-        tracing::warn!("Process not found for stderr: {}", process_id);
-        Err(StatusCode::NOT_FOUND)
     }
 }
@@ -17,6 +17,7 @@ export const EXECUTOR_TYPES: string[] = [
     "aider",
     "codex",
     "aaa",
+    "goose",
 ];
 
 export const EDITOR_TYPES: EditorType[] = [
@@ -39,7 +40,8 @@ export const EXECUTOR_LABELS: Record<string, string> = {
     "sst-opencode": "SST Opencode",
     "aider": "Aider",
     "codex": "Codex",
-    "aaa": "AAA (Assistant Agent)"
+    "aaa": "AAA (Assistant Agent)",
+    "goose": "Goose"
 };
 
 export const EDITOR_LABELS: Record<string, string> = {
@@ -61,6 +63,22 @@ export const MCP_SUPPORTED_EXECUTORS: string[] = [
     "aaa"
 ];
 
+export const MODEL_SUPPORTED_EXECUTORS: string[] = [
+    "claude",
+    "claude-plan",
+    "gemini",
+    "sst-opencode",
+    "aider",
+    "codex",
+    "goose"
+];
+
+export const CONTEXT_REPLAY_EXECUTORS: string[] = [
+    "gemini",
+    "charm-opencode",
+    "aaa"
+];
+
 export const SOUND_FILES: SoundFile[] = [
     "abstract-sound1",
     "abstract-sound2",
@@ -99,6 +117,36 @@ fn generate_types_content() -> String {
         vibe_kanban::models::config::ThemeMode::decl(),
         vibe_kanban::models::config::EditorConfig::decl(),
         vibe_kanban::models::config::GitHubConfig::decl(),
+        vibe_kanban::models::config::WebhookConfig::decl(),
+        vibe_kanban::models::config::NotificationEvents::decl(),
+        vibe_kanban::models::config::AttentionConfig::decl(),
+        vibe_kanban::models::config::AttentionChannels::decl(),
+        vibe_kanban::models::config::SmtpConfig::decl(),
+        vibe_kanban::models::config::SmtpSecurity::decl(),
+        vibe_kanban::models::config::NotionConfig::decl(),
+        vibe_kanban::models::config::JiraConfig::decl(),
+        vibe_kanban::models::config::LinearConfig::decl(),
+        vibe_kanban::models::config::EmbeddingsConfig::decl(),
+        vibe_kanban::models::config::LlmConfig::decl(),
+        vibe_kanban::models::config::PrDescriptionConfig::decl(),
+        vibe_kanban::models::config::StaleTasksConfig::decl(),
+        vibe_kanban::models::config::RunEstimateConfig::decl(),
+        vibe_kanban::models::config::RetryPolicy::decl(),
+        vibe_kanban::models::config::ExecutionLimits::decl(),
+        vibe_kanban::models::config::PreemptionPolicy::decl(),
+        vibe_kanban::models::config::LogRedactionConfig::decl(),
+        vibe_kanban::models::config::LogStorageConfig::decl(),
+        vibe_kanban::models::config::AuthConfig::decl(),
+        vibe_kanban::models::config::OidcConfig::decl(),
+        vibe_kanban::models::config::PostExecutionGitConfig::decl(),
+        vibe_kanban::models::config::VerificationConfig::decl(),
+        vibe_kanban::models::config::ReviewGateConfig::decl(),
+        vibe_kanban::models::config::SandboxConfig::decl(),
+        vibe_kanban::models::config::ResourceLimitsConfig::decl(),
+        vibe_kanban::models::config::ContextBudgetConfig::decl(),
+        vibe_kanban::models::config::ShellEnvironmentConfig::decl(),
+        vibe_kanban::models::config::DatabaseConfig::decl(),
+        vibe_kanban::models::config::LoginShellMode::decl(),
         vibe_kanban::models::config::EditorType::decl(),
         vibe_kanban::models::config::EditorConstants::decl(),
         vibe_kanban::models::config::SoundFile::decl(),
@@ -106,6 +154,16 @@ fn generate_types_content() -> String {
         vibe_kanban::routes::config::ConfigConstants::decl(),
         vibe_kanban::executor::ExecutorConfig::decl(),
         vibe_kanban::executor::ExecutorConstants::decl(),
+        vibe_kanban::executor::ExecutorCapabilities::decl(),
+        vibe_kanban::executor::EnvironmentProblem::decl(),
+        vibe_kanban::services::executor_preflight::EnvironmentReport::decl(),
+        vibe_kanban::services::diagnostics::CheckStatus::decl(),
+        vibe_kanban::services::diagnostics::DiagnosticCheck::decl(),
+        vibe_kanban::services::diagnostics::Diagnostics::decl(),
+        vibe_kanban::executors::jsonl::JsonlFieldMapping::decl(),
+        vibe_kanban::executors::jsonl::JsonlExecutorDefinition::decl(),
+        vibe_kanban::executors::jsonl::JsonlApprovalReplies::decl(),
+        vibe_kanban::executors::acp::AcpAgentDefinition::decl(),
         vibe_kanban::models::project::CreateProject::decl(),
         vibe_kanban::models::project::CreateProjectFromGitHub::decl(),
         vibe_kanban::models::project::Project::decl(),
@@ -116,14 +174,38 @@ fn generate_types_content() -> String {
         vibe_kanban::models::project::GitBranch::decl(),
         vibe_kanban::models::project::CreateBranch::decl(),
         vibe_kanban::models::task::CreateTask::decl(),
+        vibe_kanban::models::task::CreateSubtask::decl(),
         vibe_kanban::models::task::CreateTaskAndStart::decl(),
         vibe_kanban::models::task::TaskStatus::decl(),
+        vibe_kanban::models::task::TaskStatusNames::decl(),
+        vibe_kanban::models::task::TaskPriority::decl(),
         vibe_kanban::models::task::Task::decl(),
         vibe_kanban::models::task::TaskWithAttemptStatus::decl(),
         vibe_kanban::models::task::UpdateTask::decl(),
+        vibe_kanban::models::task::TaskEdit::decl(),
+        vibe_kanban::models::task::TaskTimeoutRequest::decl(),
+        vibe_kanban::models::task::TaskPriorityRequest::decl(),
+        vibe_kanban::models::task::TaskSort::decl(),
+        vibe_kanban::models::task::TaskBoardQuery::decl(),
+        vibe_kanban::models::task::ShelvedTask::decl(),
+        vibe_kanban::models::task_bulk::BulkTaskAction::decl(),
+        vibe_kanban::models::task_bulk::BulkTaskFailure::decl(),
+        vibe_kanban::models::task_bulk::BulkTaskResult::decl(),
         vibe_kanban::models::task_template::TaskTemplate::decl(),
         vibe_kanban::models::task_template::CreateTaskTemplate::decl(),
         vibe_kanban::models::task_template::UpdateTaskTemplate::decl(),
+        vibe_kanban::models::task_template::InstantiateTaskTemplate::decl(),
+        vibe_kanban::models::task_checklist::TaskChecklistItem::decl(),
+        vibe_kanban::models::task_checklist::TaskChecklist::decl(),
+        vibe_kanban::models::task_checklist::UpdateTaskChecklistItem::decl(),
+        vibe_kanban::models::task_decomposition::TaskDecompositionStatus::decl(),
+        vibe_kanban::models::task_decomposition::TaskDecomposition::decl(),
+        vibe_kanban::models::task_decomposition::DecomposeTask::decl(),
+        vibe_kanban::models::task_decomposition::TaskSubtasks::decl(),
+        vibe_kanban::models::task_schedule::TaskSchedule::decl(),
+        vibe_kanban::models::task_schedule::UpsertTaskSchedule::decl(),
+        vibe_kanban::models::task_schedule::TaskScheduleRunStatus::decl(),
+        vibe_kanban::models::task_schedule::TaskScheduleRun::decl(),
         vibe_kanban::models::task_attempt::TaskAttemptStatus::decl(),
         vibe_kanban::models::task_attempt::TaskAttempt::decl(),
         vibe_kanban::models::task_attempt::CreateTaskAttempt::decl(),
@@ -132,21 +214,263 @@ fn generate_types_content() -> String {
         vibe_kanban::routes::filesystem::DirectoryEntry::decl(),
         vibe_kanban::routes::filesystem::DirectoryListResponse::decl(),
         vibe_kanban::routes::auth::DeviceStartResponse::decl(),
+        vibe_kanban::routes::users::AuthStatus::decl(),
+        vibe_kanban::routes::users::LoginRequest::decl(),
+        vibe_kanban::routes::users::LoginResponse::decl(),
+        vibe_kanban::routes::users::ChangePassword::decl(),
         vibe_kanban::services::github_service::RepositoryInfo::decl(),
+        vibe_kanban::models::github_project_sync::GitHubProjectLink::decl(),
+        vibe_kanban::models::github_project_sync::UpsertGitHubProjectLink::decl(),
+        vibe_kanban::services::github_projects::StatusOption::decl(),
+        vibe_kanban::services::github_projects::GitHubProjectBoard::decl(),
+        vibe_kanban::services::github_projects::GitHubProjectSyncResult::decl(),
+        vibe_kanban::routes::github_projects::GitHubProjectLinkResponse::decl(),
+        vibe_kanban::models::github_issue_sync::GitHubIssueLink::decl(),
+        vibe_kanban::models::github_issue_sync::UpsertGitHubIssueLink::decl(),
+        vibe_kanban::services::github_issues::GitHubIssueSyncResult::decl(),
+        vibe_kanban::models::notion_sync::NotionPropertyMapping::decl(),
+        vibe_kanban::models::notion_sync::NotionLink::decl(),
+        vibe_kanban::models::notion_sync::UpsertNotionLink::decl(),
+        vibe_kanban::services::notion_sync::NotionProperty::decl(),
+        vibe_kanban::services::notion_sync::NotionDatabase::decl(),
+        vibe_kanban::services::notion_sync::NotionSyncResult::decl(),
+        vibe_kanban::routes::notion::NotionLinkResponse::decl(),
+        vibe_kanban::services::trello_import::TrelloImportRequest::decl(),
+        vibe_kanban::services::trello_import::TrelloImportResult::decl(),
+        vibe_kanban::models::tracker_import::Tracker::decl(),
+        vibe_kanban::models::tracker_import::TrackerImport::decl(),
+        vibe_kanban::services::tracker_import::TrackerImportRequest::decl(),
+        vibe_kanban::services::tracker_import::TrackerSyncResult::decl(),
+        vibe_kanban::services::project_archive::ProjectArchive::decl(),
+        vibe_kanban::services::project_archive::ArchivedTask::decl(),
+        vibe_kanban::services::project_archive::ArchivedAttempt::decl(),
+        vibe_kanban::services::project_archive::ArchivedExecution::decl(),
+        vibe_kanban::services::project_archive::ProjectImportResult::decl(),
+        vibe_kanban::services::conversation_export::ConversationExportFormat::decl(),
+        vibe_kanban::services::conversation_export::ConversationExportQuery::decl(),
+        vibe_kanban::services::conversation_export::TaskConversations::decl(),
+        vibe_kanban::services::conversation_export::AttemptConversations::decl(),
+        vibe_kanban::services::conversation_export::RunConversation::decl(),
+        vibe_kanban::models::task_share_link::TaskShareLink::decl(),
+        vibe_kanban::models::task_share_link::CreateTaskShareLink::decl(),
+        vibe_kanban::models::task_share_link::CreatedTaskShareLink::decl(),
+        vibe_kanban::services::task_sharing::SharedTask::decl(),
+        vibe_kanban::models::entry_bookmark::EntryBookmark::decl(),
+        vibe_kanban::models::entry_bookmark::CreateEntryBookmark::decl(),
+        vibe_kanban::services::entry_bookmarks::BookmarkExportTarget::decl(),
+        vibe_kanban::services::entry_bookmarks::ExportEntryBookmarks::decl(),
+        vibe_kanban::services::entry_bookmarks::ExportedEntryBookmarks::decl(),
+        vibe_kanban::services::file_mentions::CheckFileMentions::decl(),
+        vibe_kanban::services::file_mentions::FileMentionProblem::decl(),
+        vibe_kanban::services::related_tasks::SimilarTasksQuery::decl(),
+        vibe_kanban::services::related_tasks::SimilarTask::decl(),
+        vibe_kanban::services::related_tasks::DuplicateCandidate::decl(),
+        vibe_kanban::routes::tasks::CreatedTask::decl(),
+        vibe_kanban::routes::tasks::CreateTaskPullRequest::decl(),
+        vibe_kanban::models::task_original_text::TaskOriginalText::decl(),
+        vibe_kanban::models::task_pr_description::TaskPrDescription::decl(),
+        vibe_kanban::models::task_attachment::TaskAttachment::decl(),
+        vibe_kanban::models::task_label::LabelSuggestion::decl(),
+        vibe_kanban::models::task_label::TaskLabels::decl(),
+        vibe_kanban::models::task_label::TaskLabelName::decl(),
+        vibe_kanban::models::task_custom_field::CustomFieldType::decl(),
+        vibe_kanban::models::task_custom_field::CustomField::decl(),
+        vibe_kanban::models::task_custom_field::UpsertCustomField::decl(),
+        vibe_kanban::models::task_custom_field::SetTaskCustomField::decl(),
+        vibe_kanban::models::board_column::BoardCondition::decl(),
+        vibe_kanban::models::board_column::BoardColumn::decl(),
+        vibe_kanban::models::board_column::BoardColumnInput::decl(),
+        vibe_kanban::models::board_column::UpdateBoardColumns::decl(),
+        vibe_kanban::models::board_column::MoveTaskToColumn::decl(),
+        vibe_kanban::models::task_dependency::TaskDependency::decl(),
+        vibe_kanban::models::task_dependency::DependencyTask::decl(),
+        vibe_kanban::models::task_dependency::TaskDependencies::decl(),
+        vibe_kanban::models::task_dependency::TaskDependencyRequest::decl(),
+        vibe_kanban::models::task_dependency::TaskDependencyGraph::decl(),
+        vibe_kanban::services::pr_description::PrDescription::decl(),
+        vibe_kanban::services::changelog::ChangelogFormat::decl(),
+        vibe_kanban::services::changelog::ChangelogQuery::decl(),
+        vibe_kanban::services::changelog::ChangelogSection::decl(),
+        vibe_kanban::services::changelog::ChangelogEntry::decl(),
+        vibe_kanban::services::changelog::Changelog::decl(),
+        vibe_kanban::models::release::ReleaseStatus::decl(),
+        vibe_kanban::models::release::Release::decl(),
+        vibe_kanban::models::release::ReleaseTask::decl(),
+        vibe_kanban::models::release::ReleaseWithTasks::decl(),
+        vibe_kanban::models::release::CreateRelease::decl(),
+        vibe_kanban::models::attempt_plan::AttemptPlanStatus::decl(),
+        vibe_kanban::models::attempt_plan::AttemptPlan::decl(),
+        vibe_kanban::services::plan_review::PlanComparison::decl(),
+        vibe_kanban::services::plan_review::PlanReview::decl(),
+        vibe_kanban::services::run_estimate::EstimateScope::decl(),
+        vibe_kanban::services::run_estimate::RunEstimate::decl(),
+        vibe_kanban::services::worktree_usage::ArtifactDir::decl(),
+        vibe_kanban::services::worktree_usage::WorktreeUsage::decl(),
+        vibe_kanban::models::stale_task::ProjectStaleTask::decl(),
+        vibe_kanban::models::merge_queue_entry::MergeQueueStatus::decl(),
+        vibe_kanban::models::merge_queue_entry::MergeQueueEntry::decl(),
+        vibe_kanban::models::execution_metrics::TaskUsage::decl(),
+        vibe_kanban::models::execution_metrics::ModelUsage::decl(),
+        vibe_kanban::models::execution_metrics::ProjectUsage::decl(),
+        vibe_kanban::models::execution_analytics::ExecutorStats::decl(),
+        vibe_kanban::models::execution_analytics::TaskTime::decl(),
+        vibe_kanban::models::execution_analytics::ProjectAnalytics::decl(),
+        vibe_kanban::models::benchmark::BenchmarkResultStatus::decl(),
+        vibe_kanban::models::benchmark::BenchmarkCase::decl(),
+        vibe_kanban::models::benchmark::BenchmarkSuite::decl(),
+        vibe_kanban::models::benchmark::BenchmarkCaseInput::decl(),
+        vibe_kanban::models::benchmark::UpsertBenchmarkSuite::decl(),
+        vibe_kanban::models::benchmark::BenchmarkContestant::decl(),
+        vibe_kanban::models::benchmark::StartBenchmarkRun::decl(),
+        vibe_kanban::models::benchmark::BenchmarkRun::decl(),
+        vibe_kanban::models::benchmark::BenchmarkResult::decl(),
+        vibe_kanban::models::benchmark::ContestantReport::decl(),
+        vibe_kanban::models::benchmark::BenchmarkReport::decl(),
+        vibe_kanban::models::prompt_template::PromptTemplate::decl(),
+        vibe_kanban::models::prompt_template::UpsertPromptTemplate::decl(),
+        vibe_kanban::models::environment_variable::EnvironmentVariable::decl(),
+        vibe_kanban::models::environment_variable::UpsertEnvironmentVariable::decl(),
+        vibe_kanban::models::project_mcp_server::ProjectMcpServer::decl(),
+        vibe_kanban::models::project_mcp_server::UpsertProjectMcpServer::decl(),
+        vibe_kanban::models::executor_profile::ExecutorProfile::decl(),
+        vibe_kanban::models::executor_profile::UpsertExecutorProfile::decl(),
+        vibe_kanban::models::executor_profile::AssignExecutorProfile::decl(),
+        vibe_kanban::models::task_defaults::SetTaskExecutor::decl(),
+        vibe_kanban::models::project_sandbox::ProjectSandbox::decl(),
+        vibe_kanban::models::project_sandbox::UpsertProjectSandbox::decl(),
+        vibe_kanban::models::project_security::ProjectSecurity::decl(),
+        vibe_kanban::models::project_security::UpsertProjectSecurity::decl(),
+        vibe_kanban::models::project_shell_environment::ProjectShellEnvironment::decl(),
+        vibe_kanban::models::project_shell_environment::UpsertProjectShellEnvironment::decl(),
+        vibe_kanban::models::project_container::ProjectContainer::decl(),
+        vibe_kanban::models::project_container::UpsertProjectContainer::decl(),
+        vibe_kanban::models::project_worker::ProjectWorker::decl(),
+        vibe_kanban::models::project_worker::UpsertProjectWorker::decl(),
+        vibe_kanban::models::project_guardrails::GuardrailHold::decl(),
+        vibe_kanban::models::project_guardrails::ExecutionWindow::decl(),
+        vibe_kanban::models::project_guardrails::ProjectGuardrails::decl(),
+        vibe_kanban::models::project_guardrails::UpsertProjectGuardrails::decl(),
+        vibe_kanban::services::guardrails::GuardrailStatus::decl(),
+        vibe_kanban::models::project_git_host::GitHostKind::decl(),
+        vibe_kanban::models::project_git_host::ProjectGitHost::decl(),
+        vibe_kanban::models::project_git_host::UpsertProjectGitHost::decl(),
+        vibe_kanban::services::repo_analysis::RefreshStats::decl(),
+        vibe_kanban::services::repo_analysis::LanguageStat::decl(),
+        vibe_kanban::services::repo_analysis::RepoAnalysisSummary::decl(),
+        vibe_kanban::services::repo_config::RepoConfig::decl(),
+        vibe_kanban::services::repo_config::EffectiveProjectConfig::decl(),
+        vibe_kanban::services::worktree_usage::ArtifactCleanup::decl(),
         vibe_kanban::routes::task_attempts::ProcessLogsResponse::decl(),
+        vibe_kanban::routes::log_stream::LogStreamMessage::decl(),
+        vibe_kanban::services::command_approval::PendingApproval::decl(),
+        vibe_kanban::services::command_approval::ApprovalDecision::decl(),
+        vibe_kanban::models::execution_input::SendExecutionInput::decl(),
         vibe_kanban::models::task_attempt::DiffChunkType::decl(),
         vibe_kanban::models::task_attempt::DiffChunk::decl(),
         vibe_kanban::models::task_attempt::FileDiff::decl(),
         vibe_kanban::models::task_attempt::WorktreeDiff::decl(),
+        vibe_kanban::models::task_attempt::DiffHunk::decl(),
+        vibe_kanban::models::task_attempt::FileHunks::decl(),
+        vibe_kanban::models::execution_log_chunk::LogStream::decl(),
+        vibe_kanban::models::execution_diff::ExecutionDiff::decl(),
+        vibe_kanban::models::execution_diff::DiffSummary::decl(),
+        vibe_kanban::models::execution_diff::FileDiffSummary::decl(),
+        vibe_kanban::models::execution_resources::ExecutionResourceSample::decl(),
+        vibe_kanban::models::execution_resources::ExecutionResources::decl(),
+        vibe_kanban::services::scoped_follow_up::HunkSelection::decl(),
+        vibe_kanban::services::scoped_follow_up::CreateScopedFollowUp::decl(),
+        vibe_kanban::models::follow_up_draft::DraftQuote::decl(),
+        vibe_kanban::models::follow_up_draft::FollowUpDraft::decl(),
+        vibe_kanban::models::follow_up_draft::SaveFollowUpDraft::decl(),
+        vibe_kanban::models::follow_up_draft::SendFollowUpDraft::decl(),
+        vibe_kanban::models::task_attempt::FileChangeKind::decl(),
+        vibe_kanban::models::task_attempt::FileTreeNode::decl(),
+        vibe_kanban::models::task_attempt::LineChangeKind::decl(),
+        vibe_kanban::models::task_attempt::LineChange::decl(),
+        vibe_kanban::models::task_attempt::FileContent::decl(),
+        vibe_kanban::models::task_attempt::WorktreeStats::decl(),
+        vibe_kanban::models::task_attempt::SharedFileComparison::decl(),
+        vibe_kanban::models::task_attempt::WorktreeComparison::decl(),
+        vibe_kanban::models::execution_verification::ExecutionVerification::decl(),
+        vibe_kanban::models::task_attempt::AttemptRunSummary::decl(),
+        vibe_kanban::models::task_attempt::AttemptComparison::decl(),
+        vibe_kanban::models::review_comment::ReviewComment::decl(),
+        vibe_kanban::models::review_comment::CreateReviewComment::decl(),
+        vibe_kanban::models::review_comment::UpdateReviewComment::decl(),
+        vibe_kanban::models::attempt_review::ReviewDecision::decl(),
+        vibe_kanban::models::attempt_review::AttemptReview::decl(),
+        vibe_kanban::models::attempt_review::ApproveAttempt::decl(),
+        vibe_kanban::models::attempt_review::RejectAttempt::decl(),
+        vibe_kanban::models::attempt_review::AttemptRejection::decl(),
+        vibe_kanban::models::attempt_rollback::AttemptRollback::decl(),
+        vibe_kanban::models::attempt_rollback::RollbackAttempt::decl(),
+        vibe_kanban::models::execution_snapshot::ExecutionSnapshot::decl(),
+        vibe_kanban::models::execution_snapshot::RevertExecution::decl(),
+        vibe_kanban::models::execution_tool_usage::ExecutionToolUsage::decl(),
+        vibe_kanban::models::execution_attention::AttentionKind::decl(),
+        vibe_kanban::models::execution_attention::ExecutionAttention::decl(),
+        vibe_kanban::models::task_linked_project::TaskLinkedProject::decl(),
+        vibe_kanban::models::task_linked_project::SetTaskLinkedProjects::decl(),
+        vibe_kanban::models::attempt_linked_worktree::AttemptLinkedWorktree::decl(),
+        vibe_kanban::models::attempt_linked_worktree::LinkedRepoDiff::decl(),
+        vibe_kanban::models::attempt_group::AttemptGroup::decl(),
+        vibe_kanban::models::attempt_group::CreateParallelAttempts::decl(),
+        vibe_kanban::models::attempt_group::PickAttemptGroupWinner::decl(),
+        vibe_kanban::models::conversation_search::ConversationSearchHit::decl(),
+        vibe_kanban::models::search_document::SearchHitKind::decl(),
+        vibe_kanban::models::search_document::SearchHit::decl(),
+        vibe_kanban::models::search_document::SearchQuery::decl(),
+        vibe_kanban::models::event::EventKind::decl(),
+        vibe_kanban::models::event::EventActor::decl(),
+        vibe_kanban::models::event::Event::decl(),
+        vibe_kanban::models::event::AuditVerification::decl(),
+        vibe_kanban::event_bus::BoardEvent::decl(),
+        vibe_kanban::services::notification_service::NotificationEvent::decl(),
+        vibe_kanban::models::notification_webhook::WebhookFormat::decl(),
+        vibe_kanban::models::notification_webhook::NotificationWebhook::decl(),
+        vibe_kanban::models::notification_webhook::UpsertNotificationWebhook::decl(),
+        vibe_kanban::models::notification_delivery::NotificationChannel::decl(),
+        vibe_kanban::models::notification_delivery::NotificationDeliveryStatus::decl(),
+        vibe_kanban::models::notification_delivery::NotificationDelivery::decl(),
+        vibe_kanban::models::user::User::decl(),
+        vibe_kanban::models::user::CreateUser::decl(),
+        vibe_kanban::models::user::UpdateUser::decl(),
+        vibe_kanban::models::project_member::ProjectRole::decl(),
+        vibe_kanban::models::project_member::ProjectMember::decl(),
+        vibe_kanban::models::project_member::SetProjectMember::decl(),
+        vibe_kanban::models::project_member::ProjectMembership::decl(),
+        vibe_kanban::models::project_invite::ProjectInvite::decl(),
+        vibe_kanban::models::project_invite::CreateProjectInvite::decl(),
+        vibe_kanban::models::project_invite::CreatedProjectInvite::decl(),
+        vibe_kanban::models::project_invite::InviteDetails::decl(),
+        vibe_kanban::models::project_invite::AcceptProjectInvite::decl(),
+        vibe_kanban::models::mention::MentionSource::decl(),
+        vibe_kanban::models::mention::Mention::decl(),
+        vibe_kanban::services::plugins::PluginInfo::decl(),
+        vibe_kanban::models::script_hook::ScriptEvent::decl(),
+        vibe_kanban::models::script_hook::ScriptHook::decl(),
+        vibe_kanban::models::script_hook::UpsertScriptHook::decl(),
+        vibe_kanban::models::api_token::TokenScope::decl(),
+        vibe_kanban::models::api_token::ApiToken::decl(),
+        vibe_kanban::models::api_token::CreateApiToken::decl(),
+        vibe_kanban::models::api_token::CreatedApiToken::decl(),
+        vibe_kanban::models::api_token::ApiTokenUse::decl(),
+        vibe_kanban::models::two_factor::TwoFactorEnrollment::decl(),
+        vibe_kanban::models::two_factor::RecoveryCodes::decl(),
+        vibe_kanban::models::two_factor::TwoFactorCode::decl(),
         vibe_kanban::models::task_attempt::BranchStatus::decl(),
         vibe_kanban::models::task_attempt::ExecutionState::decl(),
         vibe_kanban::models::task_attempt::TaskAttemptState::decl(),
         vibe_kanban::models::execution_process::ExecutionProcess::decl(),
         vibe_kanban::models::execution_process::ExecutionProcessSummary::decl(),
         vibe_kanban::models::execution_process::ExecutionProcessStatus::decl(),
+        vibe_kanban::models::execution_process::FailureReason::decl(),
         vibe_kanban::models::execution_process::ExecutionProcessType::decl(),
         vibe_kanban::models::execution_process::CreateExecutionProcess::decl(),
         vibe_kanban::models::execution_process::UpdateExecutionProcess::decl(),
+        vibe_kanban::models::execution_queue::QueueEntry::decl(),
+        vibe_kanban::models::execution_queue::ReorderQueue::decl(),
+        vibe_kanban::models::dev_server_port::DevServerPort::decl(),
         vibe_kanban::models::executor_session::ExecutorSession::decl(),
         vibe_kanban::models::executor_session::CreateExecutorSession::decl(),
         vibe_kanban::models::executor_session::UpdateExecutorSession::decl(),
@@ -154,6 +478,8 @@ fn generate_types_content() -> String {
         vibe_kanban::executor::NormalizedEntry::decl(),
         vibe_kanban::executor::NormalizedEntryType::decl(),
         vibe_kanban::executor::ActionType::decl(),
+        vibe_kanban::executor::FileLocation::decl(),
+        vibe_kanban::services::entry_limits::TruncatedContent::decl(),
     ];
 
     let body = decls
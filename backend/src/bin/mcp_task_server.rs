@@ -1,9 +1,11 @@
-use std::str::FromStr;
-
 use rmcp::{transport::stdio, ServiceExt};
-use sqlx::{sqlite::SqliteConnectOptions, SqlitePool};
 use tracing_subscriber::{prelude::*, EnvFilter};
-use vibe_kanban::{mcp::task_server::TaskServer, sentry_layer, utils::asset_dir};
+use vibe_kanban::{
+    mcp::task_server::TaskServer,
+    models::config::DatabaseConfig,
+    sentry_layer,
+    utils::{config_path, database},
+};
 
 fn main() -> anyhow::Result<()> {
     let environment = if cfg!(debug_assertions) {
@@ -36,13 +38,8 @@ fn main() -> anyhow::Result<()> {
             tracing::debug!("[MCP] Starting MCP task server...");
 
             // Database connection
-            let database_url = format!(
-                "sqlite://{}",
-                asset_dir().join("db.sqlite").to_string_lossy()
-            );
-
-            let options = SqliteConnectOptions::from_str(&database_url)?.create_if_missing(false);
-            let pool = SqlitePool::connect_with(options).await?;
+            let config = DatabaseConfig::load(&config_path());
+            let pool = database::connect(&config, false).await?;
 
             let service = TaskServer::new(pool)
                 .serve(stdio())
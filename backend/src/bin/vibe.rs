@@ -0,0 +1,568 @@
+use std::{
+    collections::HashMap,
+    io::{IsTerminal, Read, Write},
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail};
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use uuid::Uuid;
+use vibe_kanban::{
+    api_client::{resolve_by_prefix, resolve_project, ApiClient},
+    executor::{NormalizedEntry, NormalizedEntryType},
+    models::{
+        project::Project,
+        task::TaskWithAttemptStatus,
+        task_attempt::{DiffChunkType, TaskAttempt, WorktreeDiff},
+        tracker_import::Tracker,
+    },
+    routes::tasks::CreatedTask,
+    services::tracker_import::TrackerSyncResult,
+};
+
+const LOG_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Command line client for a running vibe-kanban server
+#[derive(Debug, Parser)]
+#[command(name = "vibe", version)]
+struct Cli {
+    /// Server URL; defaults to the port written by the local server
+    #[arg(long, env = "VK_SERVER_URL", global = true)]
+    server: Option<String>,
+
+    /// Project id or name; defaults to the project whose repository contains the current directory
+    #[arg(short, long, env = "VK_PROJECT", global = true)]
+    project: Option<String>,
+
+    /// Print raw JSON instead of human-readable output
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// List projects
+    Projects,
+    /// Manage tasks
+    #[command(subcommand)]
+    Task(TaskCommand),
+    /// Manage attempts of a task
+    #[command(subcommand)]
+    Attempt(AttemptCommand),
+    /// Print the agent conversation of an attempt
+    Logs {
+        /// Task id or unique id prefix
+        task: String,
+        /// Attempt id or prefix; defaults to the latest attempt
+        #[arg(short, long)]
+        attempt: Option<String>,
+        /// Keep printing new entries until the attempt stops running
+        #[arg(short, long)]
+        follow: bool,
+    },
+    /// Print an attempt's changes against its base branch
+    Diff {
+        /// Task id or unique id prefix
+        task: String,
+        /// Attempt id or prefix; defaults to the latest attempt
+        #[arg(short, long)]
+        attempt: Option<String>,
+        /// Only list the changed files with their added and removed line counts
+        #[arg(long)]
+        stat: bool,
+    },
+    /// Merge an attempt's branch into its base branch
+    Merge {
+        /// Task id or unique id prefix
+        task: String,
+        /// Attempt id or prefix; defaults to the latest attempt
+        #[arg(short, long)]
+        attempt: Option<String>,
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Import issues from Jira or Linear as tasks
+    #[command(subcommand)]
+    Import(ImportCommand),
+}
+
+#[derive(Debug, Subcommand)]
+enum TaskCommand {
+    /// List tasks in the project
+    List,
+    /// Create a task
+    #[command(alias = "new")]
+    Create {
+        #[arg(short, long)]
+        title: String,
+        /// Description; pass `-` to read it from stdin
+        #[arg(short, long)]
+        description: Option<String>,
+        /// Start an attempt right away
+        #[arg(short, long)]
+        start: bool,
+        /// Executor for the attempt started with --start
+        #[arg(short, long, requires = "start")]
+        executor: Option<String>,
+    },
+    /// Start a new attempt on a task
+    Run {
+        /// Task id or unique id prefix
+        task: String,
+        /// Executor to run, such as `claude` or `aaa`; defaults to the project's
+        #[arg(short, long)]
+        executor: Option<String>,
+        #[arg(short, long)]
+        base_branch: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum AttemptCommand {
+    /// List attempts of a task
+    List {
+        /// Task id or unique id prefix
+        task: String,
+    },
+    /// Start a new attempt on a task; the same as `task run`
+    Start {
+        /// Task id or unique id prefix
+        task: String,
+        #[arg(short, long)]
+        executor: Option<String>,
+        #[arg(short, long)]
+        base_branch: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum ImportCommand {
+    /// Import the open issues of a Jira project
+    Jira {
+        /// Project key, such as ENG
+        key: String,
+    },
+    /// Import the open issues of a Linear team
+    Linear {
+        /// Team key, such as ENG
+        key: String,
+    },
+    /// Fetch issues updated since the last import of every source
+    Sync,
+}
+
+fn print_json(value: &impl Serialize) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn short_id(id: &Uuid) -> String {
+    id.to_string()[..8].to_string()
+}
+
+fn entry_label(entry_type: &NormalizedEntryType) -> String {
+    match entry_type {
+        NormalizedEntryType::UserMessage => "user".to_string(),
+        NormalizedEntryType::AssistantMessage => "assistant".to_string(),
+        NormalizedEntryType::ToolUse { tool_name, .. } => format!("tool:{}", tool_name),
+        NormalizedEntryType::SystemMessage => "system".to_string(),
+        NormalizedEntryType::ErrorMessage => "error".to_string(),
+        NormalizedEntryType::Thinking => "thinking".to_string(),
+        NormalizedEntryType::Attachment { .. } => "attachment".to_string(),
+        NormalizedEntryType::PlanEntry => "plan".to_string(),
+        NormalizedEntryType::FileEdit { path, .. } => format!("edit:{}", path),
+        NormalizedEntryType::Verification { command, .. } => format!("verify:{}", command),
+    }
+}
+
+fn print_entry(entry: &NormalizedEntry, json: bool) -> anyhow::Result<()> {
+    if json {
+        println!("{}", serde_json::to_string(entry)?);
+    } else {
+        println!("[{}] {}", entry_label(&entry.entry_type), entry.content);
+    }
+    Ok(())
+}
+
+/// Print the diff with a `+`, `-` or space before each line, as `git diff` does
+fn print_diff(diff: &WorktreeDiff, stat: bool) {
+    for file in &diff.files {
+        if stat {
+            let (mut added, mut removed) = (0, 0);
+            for chunk in &file.chunks {
+                let lines = chunk.content.lines().count();
+                match chunk.chunk_type {
+                    DiffChunkType::Insert => added += lines,
+                    DiffChunkType::Delete => removed += lines,
+                    DiffChunkType::Equal => {}
+                }
+            }
+            println!(
+                "{:>5} {:>5}  {}",
+                format!("+{}", added),
+                format!("-{}", removed),
+                file.path
+            );
+            continue;
+        }
+
+        println!("--- {}", file.path);
+        for chunk in &file.chunks {
+            let prefix = match chunk.chunk_type {
+                DiffChunkType::Equal => ' ',
+                DiffChunkType::Insert => '+',
+                DiffChunkType::Delete => '-',
+            };
+            for line in chunk.content.lines() {
+                println!("{}{}", prefix, line);
+            }
+        }
+    }
+}
+
+struct Session<'a> {
+    client: &'a ApiClient,
+    project: &'a Project,
+    json: bool,
+}
+
+impl Session<'_> {
+    async fn tasks(&self) -> anyhow::Result<Vec<TaskWithAttemptStatus>> {
+        self.client.tasks(self.project.id).await
+    }
+
+    async fn task_id(&self, selector: &str) -> anyhow::Result<Uuid> {
+        let tasks = self.tasks().await?;
+        Ok(resolve_by_prefix(&tasks, selector, |t| t.id, "task")?.id)
+    }
+
+    async fn attempt(&self, task_id: Uuid, selector: Option<&str>) -> anyhow::Result<TaskAttempt> {
+        let attempts = self.client.attempts(self.project.id, task_id).await?;
+        match selector {
+            Some(selector) => {
+                Ok(resolve_by_prefix(&attempts, selector, |a| a.id, "attempt")?.clone())
+            }
+            None => attempts
+                .into_iter()
+                .max_by_key(|a| a.created_at)
+                .ok_or_else(|| anyhow!("task has no attempts yet")),
+        }
+    }
+
+    async fn list_tasks(&self) -> anyhow::Result<()> {
+        let tasks = self.tasks().await?;
+        if self.json {
+            return print_json(&tasks);
+        }
+        for task in tasks {
+            let marker = if task.has_in_progress_attempt {
+                "running"
+            } else if task.last_attempt_failed {
+                "failed"
+            } else {
+                ""
+            };
+            println!(
+                "{}  {:<11} {:<8} {}",
+                short_id(&task.id),
+                format!("{:?}", task.status).to_lowercase(),
+                marker,
+                task.title
+            );
+        }
+        Ok(())
+    }
+
+    async fn create_task(
+        &self,
+        title: &str,
+        description: Option<String>,
+        start: bool,
+        executor: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let description = match description.as_deref() {
+            Some("-") => {
+                let mut buf = String::new();
+                std::io::stdin().read_to_string(&mut buf)?;
+                Some(buf.trim_end().to_string())
+            }
+            _ => description,
+        };
+        let CreatedTask {
+            task,
+            possible_duplicates,
+        } = self
+            .client
+            .create_task(self.project.id, title, description)
+            .await?;
+        let attempt = if start {
+            Some(
+                self.client
+                    .start_attempt(self.project.id, task.id, executor, None)
+                    .await?,
+            )
+        } else {
+            None
+        };
+
+        if self.json {
+            return print_json(&serde_json::json!({
+                "task": task,
+                "attempt": attempt,
+                "possible_duplicates": possible_duplicates,
+            }));
+        }
+        println!("Created task {} ({})", short_id(&task.id), task.title);
+        for duplicate in &possible_duplicates {
+            let working = if duplicate.has_in_progress_attempt {
+                ", an agent is on it"
+            } else {
+                ""
+            };
+            eprintln!(
+                "warning: may duplicate {} ({}, {}{})",
+                short_id(&duplicate.id),
+                duplicate.title,
+                format!("{:?}", duplicate.status).to_lowercase(),
+                working
+            );
+        }
+        if let Some(attempt) = attempt {
+            println!(
+                "Started attempt {} on {}",
+                short_id(&attempt.id),
+                attempt.branch
+            );
+        }
+        Ok(())
+    }
+
+    async fn run_task(
+        &self,
+        task: &str,
+        executor: Option<&str>,
+        base_branch: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let task_id = self.task_id(task).await?;
+        let attempt = self
+            .client
+            .start_attempt(self.project.id, task_id, executor, base_branch)
+            .await?;
+        if self.json {
+            return print_json(&attempt);
+        }
+        println!(
+            "Started attempt {} on {}",
+            short_id(&attempt.id),
+            attempt.branch
+        );
+        Ok(())
+    }
+
+    async fn list_attempts(&self, task: &str) -> anyhow::Result<()> {
+        let task_id = self.task_id(task).await?;
+        let attempts = self.client.attempts(self.project.id, task_id).await?;
+        if self.json {
+            return print_json(&attempts);
+        }
+        for attempt in attempts {
+            println!(
+                "{}  {:<10} {:<8} {}",
+                short_id(&attempt.id),
+                attempt.executor.as_deref().unwrap_or("default"),
+                if attempt.merge_commit.is_some() {
+                    "merged"
+                } else {
+                    ""
+                },
+                attempt.branch
+            );
+        }
+        Ok(())
+    }
+
+    async fn logs(&self, task: &str, attempt: Option<&str>, follow: bool) -> anyhow::Result<()> {
+        let task_id = self.task_id(task).await?;
+        let attempt = self.attempt(task_id, attempt).await?;
+
+        // Entries already printed per execution process
+        let mut printed: HashMap<Uuid, usize> = HashMap::new();
+        loop {
+            let processes = self
+                .client
+                .logs(self.project.id, task_id, attempt.id)
+                .await?;
+            for process in &processes {
+                let seen = printed.entry(process.id).or_insert(0);
+                let entries = &process.normalized_conversation.entries;
+                for entry in entries.iter().skip(*seen) {
+                    print_entry(entry, self.json)?;
+                }
+                *seen = (*seen).max(entries.len());
+            }
+            std::io::stdout().flush()?;
+
+            let running = processes.iter().any(|p| p.status.is_live());
+            if !follow || !running {
+                return Ok(());
+            }
+            tokio::time::sleep(LOG_POLL_INTERVAL).await;
+        }
+    }
+
+    async fn diff(&self, task: &str, attempt: Option<&str>, stat: bool) -> anyhow::Result<()> {
+        let task_id = self.task_id(task).await?;
+        let attempt = self.attempt(task_id, attempt).await?;
+        let diff = self
+            .client
+            .diff(self.project.id, task_id, attempt.id)
+            .await?;
+        if self.json {
+            return print_json(&diff);
+        }
+        if diff.files.is_empty() {
+            println!("No changes on {}", attempt.branch);
+            return Ok(());
+        }
+        print_diff(&diff, stat);
+        Ok(())
+    }
+
+    async fn merge(&self, task: &str, attempt: Option<&str>, yes: bool) -> anyhow::Result<()> {
+        let task_id = self.task_id(task).await?;
+        let attempt = self.attempt(task_id, attempt).await?;
+
+        if !yes {
+            if !std::io::stdin().is_terminal() {
+                bail!("refusing to merge without confirmation; pass --yes");
+            }
+            print!(
+                "Merge {} into {}? [y/N] ",
+                attempt.branch, attempt.base_branch
+            );
+            std::io::stdout().flush()?;
+            let mut answer = String::new();
+            std::io::stdin().read_line(&mut answer)?;
+            if !matches!(answer.trim(), "y" | "Y" | "yes") {
+                println!("Aborted");
+                return Ok(());
+            }
+        }
+
+        self.client
+            .merge(self.project.id, task_id, attempt.id)
+            .await?;
+        if self.json {
+            return print_json(&serde_json::json!({ "merged": attempt.id }));
+        }
+        println!("Merged {} into {}", attempt.branch, attempt.base_branch);
+        Ok(())
+    }
+
+    async fn import(&self, command: ImportCommand) -> anyhow::Result<()> {
+        let project_id = self.project.id;
+        let result: TrackerSyncResult = match command {
+            ImportCommand::Jira { key } => {
+                self.client
+                    .import_tracker(project_id, Tracker::Jira, &key)
+                    .await?
+            }
+            ImportCommand::Linear { key } => {
+                self.client
+                    .import_tracker(project_id, Tracker::Linear, &key)
+                    .await?
+            }
+            ImportCommand::Sync => self.client.sync_trackers(project_id).await?,
+        };
+        if self.json {
+            return print_json(&result);
+        }
+        println!(
+            "Imported {} new tasks, updated {}",
+            result.created, result.updated
+        );
+        Ok(())
+    }
+}
+
+async fn run(cli: Cli) -> anyhow::Result<()> {
+    let client = ApiClient::connect(cli.server).await?;
+    let projects = client.projects().await?;
+
+    if let Command::Projects = cli.command {
+        if cli.json {
+            return print_json(&projects);
+        }
+        for project in &projects {
+            println!(
+                "{}  {:<24} {}",
+                short_id(&project.id),
+                project.name,
+                project.git_repo_path
+            );
+        }
+        return Ok(());
+    }
+
+    let cwd = std::env::current_dir()?;
+    let cwd = cwd.canonicalize().unwrap_or(cwd);
+    let project = resolve_project(&projects, cli.project.as_deref(), &cwd)?;
+    let ctx = Session {
+        client: &client,
+        project,
+        json: cli.json,
+    };
+
+    match cli.command {
+        Command::Projects => unreachable!(),
+        Command::Task(TaskCommand::List) => ctx.list_tasks().await,
+        Command::Task(TaskCommand::Create {
+            title,
+            description,
+            start,
+            executor,
+        }) => {
+            ctx.create_task(&title, description, start, executor.as_deref())
+                .await
+        }
+        Command::Task(TaskCommand::Run {
+            task,
+            executor,
+            base_branch,
+        })
+        | Command::Attempt(AttemptCommand::Start {
+            task,
+            executor,
+            base_branch,
+        }) => {
+            ctx.run_task(&task, executor.as_deref(), base_branch.as_deref())
+                .await
+        }
+        Command::Attempt(AttemptCommand::List { task }) => ctx.list_attempts(&task).await,
+        Command::Logs {
+            task,
+            attempt,
+            follow,
+        } => ctx.logs(&task, attempt.as_deref(), follow).await,
+        Command::Diff {
+            task,
+            attempt,
+            stat,
+        } => ctx.diff(&task, attempt.as_deref(), stat).await,
+        Command::Merge { task, attempt, yes } => ctx.merge(&task, attempt.as_deref(), yes).await,
+        Command::Import(command) => ctx.import(command).await,
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    if let Err(e) = run(Cli::parse()).await {
+        eprintln!("vibe: {:#}", e);
+        std::process::exit(1);
+    }
+}
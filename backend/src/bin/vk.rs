@@ -0,0 +1,3 @@
+//! `vk`, the command line client's name before it became `vibe`, kept so scripts that call
+//! it keep working
+include!("vibe.rs");
@@ -1,14 +1,98 @@
+use std::time::{Duration, Instant};
+
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use tokio::io::AsyncRead;
+use tokio_util::sync::CancellationToken;
 
 use crate::models::Environment;
 
+mod capture;
+mod container;
+mod limits;
 mod local;
+mod output;
 mod remote;
+mod sandbox;
+pub mod worker_protocol;
 
+pub use capture::capture_output;
+pub use container::{Container, ContainerCommandExecutor};
+pub use limits::ResourceLimits;
 pub use local::LocalCommandExecutor;
-pub use remote::RemoteCommandExecutor;
+pub use output::{
+    close_output_channel, notify_output, open_output_channel, register_output_writer,
+    subscribe_output, wait_for_output_writers,
+};
+pub use remote::{RemoteCommandExecutor, Worker};
+pub use sandbox::Sandbox;
+
+tokio::task_local! {
+    static SCOPED_ENV: Vec<(String, String)>;
+}
+
+/// Run `future` with `vars` in the environment of every command it starts. A variable the
+/// command sets itself keeps its own value.
+pub async fn with_env<F: std::future::Future>(vars: Vec<(String, String)>, future: F) -> F::Output {
+    SCOPED_ENV.scope(vars, future).await
+}
+
+tokio::task_local! {
+    static SCOPED_SANDBOX: Sandbox;
+}
+
+/// Run `future` with every command it starts confined to `sandbox`. A command that can't be
+/// sandboxed isn't started.
+pub async fn with_sandbox<F: std::future::Future>(sandbox: Sandbox, future: F) -> F::Output {
+    SCOPED_SANDBOX.scope(sandbox, future).await
+}
+
+tokio::task_local! {
+    static SCOPED_CONTAINER: Container;
+}
+
+/// Run `future` with every command it starts run inside `container` instead of on the host.
+/// The container takes the place of any sandbox.
+pub async fn with_container<F: std::future::Future>(container: Container, future: F) -> F::Output {
+    SCOPED_CONTAINER.scope(container, future).await
+}
+
+tokio::task_local! {
+    static SCOPED_WORKER: Worker;
+}
+
+/// Run `future` with every command it starts dispatched to `worker`. The worker takes the
+/// place of any container or sandbox, which are for commands run here.
+pub async fn with_worker<F: std::future::Future>(worker: Worker, future: F) -> F::Output {
+    SCOPED_WORKER.scope(worker, future).await
+}
+
+tokio::task_local! {
+    static SCOPED_LIMITS: ResourceLimits;
+}
+
+/// Run `future` with `limits` on every command it starts that doesn't set its own
+pub async fn with_limits<F: std::future::Future>(limits: ResourceLimits, future: F) -> F::Output {
+    SCOPED_LIMITS.scope(limits, future).await
+}
+
+tokio::task_local! {
+    static SCOPED_CANCELLATION: CancellationToken;
+}
+
+/// Run `future` with every command it starts tied to `token`: none starts once it's
+/// cancelled, and those already running are killed by [`CommandProcess::kill_if_cancelled`]
+pub async fn with_cancellation<F: std::future::Future>(
+    token: CancellationToken,
+    future: F,
+) -> F::Output {
+    SCOPED_CANCELLATION.scope(token, future).await
+}
+
+/// The cancellation token of the execution the caller is running for, if it has one
+pub fn cancellation() -> Option<CancellationToken> {
+    SCOPED_CANCELLATION.try_with(CancellationToken::clone).ok()
+}
 
 // Core trait that defines the interface for command execution
 #[async_trait]
@@ -35,9 +119,51 @@ pub trait ProcessHandle: Send + Sync {
     /// Get streams for stdout and stderr
     async fn stream(&mut self) -> Result<CommandStream, CommandError>;
 
+    /// Suspend the process until it is resumed
+    async fn pause(&mut self) -> Result<(), CommandError> {
+        Err(CommandError::Unsupported { operation: "pause" })
+    }
+
+    /// Continue a paused process
+    async fn resume(&mut self) -> Result<(), CommandError> {
+        Err(CommandError::Unsupported {
+            operation: "resume",
+        })
+    }
+
+    /// Write to the process's stdin, for processes started with it kept open
+    async fn write_stdin(&mut self, _data: &str) -> Result<(), CommandError> {
+        Err(CommandError::Unsupported {
+            operation: "take input",
+        })
+    }
+
+    /// Close the process's stdin, telling it no more input is coming. Stdin that wasn't
+    /// kept open is closed already.
+    async fn close_stdin(&mut self) -> Result<(), CommandError> {
+        Ok(())
+    }
+
+    /// CPU and memory the process and those it started are using, if that can be told
+    /// where it runs
+    async fn resource_usage(&mut self) -> Result<Option<ResourceSample>, CommandError> {
+        Ok(None)
+    }
+
+    /// Why the exited process was ended for going over its resource limits, where what
+    /// it runs on enforces them and ended it
+    async fn limit_exceeded(&mut self) -> Option<String> {
+        None
+    }
+
     /// Get process identifier (for debugging/logging)
     fn process_id(&self) -> String;
 
+    /// The process group it leads on this machine, to find what's left of it after a restart
+    fn process_group(&self) -> Option<u32> {
+        None
+    }
+
     /// Check current status (alias for try_wait for backward compatibility)
     async fn status(&mut self) -> Result<Option<CommandExitStatus>, CommandError> {
         self.try_wait().await
@@ -51,6 +177,11 @@ pub struct CommandRunnerArgs {
     pub working_dir: Option<String>,
     pub env_vars: Vec<(String, String)>,
     pub stdin: Option<String>,
+    /// Leave stdin open after writing `stdin`, so the process can be answered while it runs
+    #[serde(default)]
+    pub keep_stdin_open: bool,
+    #[serde(default)]
+    pub limits: ResourceLimits,
 }
 
 pub struct CommandRunner {
@@ -60,6 +191,8 @@ pub struct CommandRunner {
     working_dir: Option<String>,
     env_vars: Vec<(String, String)>,
     stdin: Option<String>,
+    keep_stdin_open: bool,
+    limits: ResourceLimits,
 }
 impl Default for CommandRunner {
     fn default() -> Self {
@@ -69,12 +202,45 @@ impl Default for CommandRunner {
 
 pub struct CommandProcess {
     handle: Box<dyn ProcessHandle>,
+    deadline: Option<Instant>,
+    /// When the process was paused, if it is
+    paused_at: Option<Instant>,
+    /// The previous resource sample, to tell CPU use since then
+    last_sample: Option<(Instant, ResourceSample)>,
+    limits: ResourceLimits,
+    /// Why a sample went over the limits, once one has
+    over_limit: Option<String>,
+    /// Cancelled when the execution the process runs for is stopped
+    cancellation: CancellationToken,
+}
+
+/// What a process and the processes it started were using at one point
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResourceSample {
+    /// CPU time used so far by those still running
+    pub cpu_seconds: f64,
+    pub rss_bytes: u64,
+}
+
+/// A resource sample with the CPU use since the one before it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResourcePoint {
+    /// Of one core, so a process busy on two cores reads 200
+    pub cpu_percent: f64,
+    pub cpu_seconds: f64,
+    pub rss_bytes: u64,
 }
 
 impl std::fmt::Debug for CommandProcess {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("CommandProcess")
             .field("process_id", &self.handle.process_id())
+            .field("deadline", &self.deadline)
+            .field("paused_at", &self.paused_at)
+            .field("last_sample", &self.last_sample)
+            .field("limits", &self.limits)
+            .field("over_limit", &self.over_limit)
+            .field("cancelled", &self.cancellation.is_cancelled())
             .finish()
     }
 }
@@ -91,7 +257,27 @@ pub enum CommandError {
     KillFailed {
         error: std::io::Error,
     },
+    SignalFailed {
+        signal: &'static str,
+        error: std::io::Error,
+    },
+    /// The process can't be paused, resumed or written to where it runs
+    Unsupported {
+        operation: &'static str,
+    },
+    /// A sandboxed command can't be confined where it runs
+    SandboxUnavailable {
+        reason: String,
+    },
+    /// Docker couldn't provide the container a command runs in
+    ContainerFailed {
+        reason: String,
+    },
     ProcessNotStarted,
+    /// The process's stdin was closed after its prompt, or it stopped reading it
+    InputClosed,
+    /// The execution the command was to run for was stopped before it started
+    Cancelled,
     NoCommandSet,
     IoError {
         error: std::io::Error,
@@ -114,9 +300,27 @@ impl std::fmt::Display for CommandError {
             CommandError::KillFailed { error } => {
                 write!(f, "Failed to kill command: {}", error)
             }
+            CommandError::SignalFailed { signal, error } => {
+                write!(f, "Failed to send {} to command: {}", signal, error)
+            }
+            CommandError::Unsupported { operation } => {
+                write!(f, "This process can't {} on this platform", operation)
+            }
+            CommandError::SandboxUnavailable { reason } => {
+                write!(f, "Can't run the command sandboxed: {}", reason)
+            }
+            CommandError::ContainerFailed { reason } => {
+                write!(f, "Can't run the command in its container: {}", reason)
+            }
             CommandError::ProcessNotStarted => {
                 write!(f, "Process has not been started yet")
             }
+            CommandError::InputClosed => {
+                write!(f, "The process no longer takes input")
+            }
+            CommandError::Cancelled => {
+                write!(f, "The execution was stopped before the command started")
+            }
             CommandError::NoCommandSet => {
                 write!(f, "No command has been set")
             }
@@ -173,6 +377,8 @@ impl CommandRunner {
                 working_dir: None,
                 env_vars: Vec::new(),
                 stdin: None,
+                keep_stdin_open: false,
+                limits: ResourceLimits::default(),
             },
             Environment::Local => CommandRunner {
                 executor: Box::new(LocalCommandExecutor::new()),
@@ -181,6 +387,8 @@ impl CommandRunner {
                 working_dir: None,
                 env_vars: Vec::new(),
                 stdin: None,
+                keep_stdin_open: false,
+                limits: ResourceLimits::default(),
             },
         }
     }
@@ -212,6 +420,12 @@ impl CommandRunner {
         self
     }
 
+    /// Keep stdin open once the prompt is written, for [`CommandProcess::write_stdin`]
+    pub fn keep_stdin_open(&mut self) -> &mut Self {
+        self.keep_stdin_open = true;
+        self
+    }
+
     pub fn working_dir(&mut self, dir: &str) -> &mut Self {
         self.working_dir = Some(dir.to_string());
         self
@@ -222,6 +436,11 @@ impl CommandRunner {
         self
     }
 
+    pub fn limits(&mut self, limits: ResourceLimits) -> &mut Self {
+        self.limits = limits;
+        self
+    }
+
     /// Convert the current CommandRunner state to a CreateCommandRequest
     pub fn to_args(&self) -> Option<CommandRunnerArgs> {
         Some(CommandRunnerArgs {
@@ -230,6 +449,8 @@ impl CommandRunner {
             working_dir: self.working_dir.clone(),
             env_vars: self.env_vars.clone(),
             stdin: self.stdin.clone(),
+            keep_stdin_open: self.keep_stdin_open,
+            limits: self.limits.clone(),
         })
     }
 
@@ -255,18 +476,158 @@ impl CommandRunner {
             runner.stdin(stdin);
         }
 
+        if request.keep_stdin_open {
+            runner.keep_stdin_open();
+        }
+
+        runner.limits(request.limits);
         runner
     }
 
     pub async fn start(&self) -> Result<CommandProcess, CommandError> {
-        let request = self.to_args().ok_or(CommandError::NoCommandSet)?;
-        let handle = self.executor.start(&request).await?;
+        let mut request = self.to_args().ok_or(CommandError::NoCommandSet)?;
+        let cancellation = cancellation().unwrap_or_default();
+        if cancellation.is_cancelled() {
+            return Err(CommandError::Cancelled);
+        }
+        let _ = SCOPED_ENV.try_with(|vars| {
+            for (key, value) in vars {
+                if !request.env_vars.iter().any(|(set, _)| set == key) {
+                    request.env_vars.push((key.clone(), value.clone()));
+                }
+            }
+        });
+        if request.limits.is_unlimited() {
+            if let Ok(limits) = SCOPED_LIMITS.try_with(ResourceLimits::clone) {
+                request.limits = limits;
+            }
+        }
+        let worker = SCOPED_WORKER.try_with(Worker::clone).ok();
+        let container = SCOPED_CONTAINER.try_with(Container::clone).ok();
+        let handle = match (worker, container) {
+            (Some(worker), _) => worker.start(&request).await?,
+            (None, Some(container)) => {
+                ContainerCommandExecutor::new(container)
+                    .start(&request)
+                    .await?
+            }
+            (None, None) => {
+                if let Ok(sandbox) = SCOPED_SANDBOX.try_with(Sandbox::clone) {
+                    sandbox.wrap(&mut request)?;
+                }
+                self.executor.start(&request).await?
+            }
+        };
+
+        Ok(CommandProcess {
+            handle,
+            deadline: None,
+            paused_at: None,
+            last_sample: None,
+            limits: request.limits,
+            over_limit: None,
+            cancellation,
+        })
+    }
+}
 
-        Ok(CommandProcess { handle })
+/// CPU use between two samples `elapsed` apart. Processes that exited in between take
+/// their CPU time with them, which never makes it negative.
+fn cpu_percent_between(
+    previous: &ResourceSample,
+    sample: &ResourceSample,
+    elapsed: Duration,
+) -> f64 {
+    let elapsed = elapsed.as_secs_f64();
+    if elapsed <= 0.0 {
+        return 0.0;
     }
+    (sample.cpu_seconds - previous.cpu_seconds).max(0.0) / elapsed * 100.0
 }
 
 impl CommandProcess {
+    /// Sample the process's CPU and memory use. `None` where it can't be measured.
+    pub async fn sample_resources(&mut self) -> Result<Option<ResourcePoint>, CommandError> {
+        let Some(sample) = self.handle.resource_usage().await? else {
+            return Ok(None);
+        };
+        let now = Instant::now();
+        let cpu_percent = match self.last_sample {
+            Some((at, previous)) => cpu_percent_between(&previous, &sample, now - at),
+            None => 0.0,
+        };
+        self.last_sample = Some((now, sample));
+        if let Some(reason) = self.limits.exceeded_by(&sample) {
+            self.over_limit = Some(reason);
+        }
+        Ok(Some(ResourcePoint {
+            cpu_percent,
+            cpu_seconds: sample.cpu_seconds,
+            rss_bytes: sample.rss_bytes,
+        }))
+    }
+
+    /// Give the process `timeout` from now to finish; see [`CommandProcess::kill_if_overdue`]
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.deadline = Some(Instant::now() + timeout);
+    }
+
+    /// Kill the process if it has run past its deadline. Returns whether it was killed. Time
+    /// spent paused doesn't count towards the deadline.
+    pub async fn kill_if_overdue(&mut self) -> Result<bool, CommandError> {
+        if self.paused_at.is_some() {
+            return Ok(false);
+        }
+        match self.deadline {
+            Some(deadline) if Instant::now() >= deadline => {
+                // The group kill reports an error when its signals already ended the process
+                if let Err(e) = self.kill().await {
+                    if self.try_wait().await?.is_none() {
+                        return Err(e);
+                    }
+                }
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Kill the process if a sample has found it over its limits. Returns why it was
+    /// killed, if it was.
+    pub async fn kill_if_over_limits(&mut self) -> Result<Option<String>, CommandError> {
+        let Some(reason) = self.over_limit.clone() else {
+            return Ok(None);
+        };
+        // The group kill reports an error when its signals already ended the process
+        if let Err(e) = self.kill().await {
+            if self.try_wait().await?.is_none() {
+                return Err(e);
+            }
+        }
+        Ok(Some(reason))
+    }
+
+    /// Kill the process if the execution it runs for has been cancelled. Returns whether it
+    /// was killed.
+    pub async fn kill_if_cancelled(&mut self) -> Result<bool, CommandError> {
+        if !self.cancellation.is_cancelled() {
+            return Ok(false);
+        }
+        // The group kill reports an error when its signals already ended the process
+        if let Err(e) = self.kill().await {
+            if self.try_wait().await?.is_none() {
+                return Err(e);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Why the process, having exited by itself, was ended for going over its limits by
+    /// what it runs on, if it was
+    pub async fn limit_exceeded(&mut self) -> Option<String> {
+        self.handle.limit_exceeded().await
+    }
+
     #[allow(dead_code)]
     pub async fn status(&mut self) -> Result<Option<CommandExitStatus>, CommandError> {
         self.handle.status().await
@@ -280,10 +641,44 @@ impl CommandProcess {
         self.handle.kill().await
     }
 
+    pub fn process_group(&self) -> Option<u32> {
+        self.handle.process_group()
+    }
+
+    /// Suspend the process and everything it started. Pausing a paused process does nothing.
+    pub async fn pause(&mut self) -> Result<(), CommandError> {
+        if self.paused_at.is_none() {
+            self.handle.pause().await?;
+            self.paused_at = Some(Instant::now());
+        }
+        Ok(())
+    }
+
+    /// Continue a paused process, pushing its deadline back by the time it spent paused
+    pub async fn resume(&mut self) -> Result<(), CommandError> {
+        if let Some(paused_at) = self.paused_at {
+            self.handle.resume().await?;
+            self.paused_at = None;
+            if let Some(deadline) = &mut self.deadline {
+                *deadline += paused_at.elapsed();
+            }
+        }
+        Ok(())
+    }
+
     pub async fn stream(&mut self) -> Result<CommandStream, CommandError> {
         self.handle.stream().await
     }
 
+    /// Write to the process's stdin, which it must have been started with kept open
+    pub async fn write_stdin(&mut self, data: &str) -> Result<(), CommandError> {
+        self.handle.write_stdin(data).await
+    }
+
+    pub async fn close_stdin(&mut self) -> Result<(), CommandError> {
+        self.handle.close_stdin().await
+    }
+
     #[allow(dead_code)]
     pub async fn wait(&mut self) -> Result<CommandExitStatus, CommandError> {
         self.handle.wait().await
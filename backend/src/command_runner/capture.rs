@@ -0,0 +1,407 @@
+//! What sits between a process's output pipe and the task storing it. A reader drains the
+//! pipe as fast as the process writes, so a slow database never stalls the process, into a
+//! bounded channel; what doesn't fit spills to a file on disk, and what doesn't fit there
+//! either is dropped. Either way, a process writing faster than its output is stored can't
+//! grow the server's memory. Spilled output is redacted as stored output is, and its file
+//! is only readable by the server's user, in the app's data directory.
+
+use std::{
+    io::SeekFrom,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use bytes::Bytes;
+use futures_util::Stream;
+use tempfile::TempPath;
+use tokio::{
+    fs::File,
+    io::{AsyncRead, AsyncReadExt, AsyncSeekExt, AsyncWriteExt},
+    sync::{
+        mpsc::{
+            self,
+            error::{TryRecvError, TrySendError},
+        },
+        Mutex,
+    },
+};
+use tokio_util::io::StreamReader;
+use uuid::Uuid;
+
+use crate::{
+    metrics,
+    services::{environment_variables, log_redaction},
+    utils::asset_dir,
+};
+
+/// The most read from the pipe at once
+const CHUNK_SIZE: usize = 16 * 1024;
+/// Chunks held in memory per stream, waiting to be stored
+const CHANNEL_CAPACITY: usize = 64;
+/// Bytes a stream may have waiting on disk before more is dropped
+const MAX_SPILL_BYTES: u64 = 256 * 1024 * 1024;
+/// Longest end of a line held back from the spill, waiting for the rest of the line so it
+/// can be redacted whole. A longer line is spilled in pieces.
+const MAX_HELD_LINE_BYTES: usize = 4 * CHUNK_SIZE;
+
+/// Output captured from a pipe, read in the order the process wrote it
+pub type CapturedOutput =
+    StreamReader<std::pin::Pin<Box<dyn Stream<Item = std::io::Result<Bytes>> + Send>>, Bytes>;
+
+/// Start draining `output` of an execution, and return what it drained. The pipe is read
+/// until it closes or what's returned is dropped.
+pub fn capture_output(
+    output: impl AsyncRead + Unpin + Send + 'static,
+    execution_id: Uuid,
+) -> CapturedOutput {
+    let (drain, captured) = capture(
+        output,
+        execution_id,
+        CHANNEL_CAPACITY,
+        MAX_SPILL_BYTES,
+        asset_dir().join("output"),
+    );
+    tokio::spawn(drain);
+    captured
+}
+
+fn capture(
+    output: impl AsyncRead + Unpin + Send + 'static,
+    execution_id: Uuid,
+    capacity: usize,
+    max_spill_bytes: u64,
+    spill_dir: PathBuf,
+) -> (impl std::future::Future<Output = ()> + Send, CapturedOutput) {
+    let (sender, receiver) = mpsc::channel(capacity);
+    let spill = Arc::new(Mutex::new(Spill::new(
+        execution_id,
+        max_spill_bytes,
+        spill_dir,
+    )));
+    let drain = drain(output, sender, spill.clone(), execution_id);
+    (drain, StreamReader::new(Box::pin(chunks(receiver, spill))))
+}
+
+/// Read the pipe into the channel, or the spill once the channel is full. While anything
+/// is waiting in the spill, everything after it goes there too, to keep it in order.
+async fn drain(
+    mut output: impl AsyncRead + Unpin,
+    sender: mpsc::Sender<Bytes>,
+    spill: Arc<Mutex<Spill>>,
+    execution_id: Uuid,
+) {
+    let mut buffer = vec![0; CHUNK_SIZE];
+    loop {
+        let read = match output.read(&mut buffer).await {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) => {
+                tracing::error!("Error reading output of execution {}: {}", execution_id, e);
+                break;
+            }
+        };
+        let chunk = Bytes::copy_from_slice(&buffer[..read]);
+
+        let mut spill = spill.lock().await;
+        if !spill.is_empty() {
+            spill.push(chunk).await;
+            continue;
+        }
+        match sender.try_send(chunk) {
+            Ok(()) => {}
+            Err(TrySendError::Full(chunk)) => spill.push(chunk).await,
+            // Nothing is storing the output any more
+            Err(TrySendError::Closed(_)) => break,
+        }
+    }
+}
+
+/// The chunks the channel holds, then those spilled after them. Both are checked under the
+/// spill's lock, so a chunk can't be spilled between seeing the channel empty and seeing
+/// the spill empty.
+fn chunks(
+    mut receiver: mpsc::Receiver<Bytes>,
+    spill: Arc<Mutex<Spill>>,
+) -> impl Stream<Item = std::io::Result<Bytes>> + Send {
+    async_stream::stream! {
+        loop {
+            let (next, closed) = {
+                let mut spill = spill.lock().await;
+                match receiver.try_recv() {
+                    Ok(chunk) => (Some(chunk), false),
+                    Err(error) if !spill.is_empty() => {
+                        (spill.take().await, error == TryRecvError::Disconnected)
+                    }
+                    Err(TryRecvError::Empty) => (None, false),
+                    Err(TryRecvError::Disconnected) => break,
+                }
+            };
+            match next {
+                Some(chunk) => yield Ok(chunk),
+                // Whatever the pipe gives next goes to the channel, as the spill is empty
+                None if !closed => {
+                    if let Some(chunk) = receiver.recv().await {
+                        yield Ok(chunk);
+                    }
+                }
+                None => {}
+            }
+        }
+    }
+}
+
+/// `output` of the execution redacted as it is when stored
+fn redact(execution_id: Uuid, output: &[u8]) -> Bytes {
+    let output = String::from_utf8_lossy(output);
+    let output = environment_variables::redact(execution_id, &output);
+    Bytes::from(log_redaction::redact(&output).into_owned())
+}
+
+/// Output that didn't fit in the channel, waiting in a file until the channel is drained
+struct Spill {
+    execution_id: Uuid,
+    /// Where the file is created
+    dir: PathBuf,
+    /// Opened when something first spills. Declared before its path, which removes the
+    /// file when dropped, as an open file can't be removed everywhere.
+    file: Option<File>,
+    path: Option<TempPath>,
+    /// The end of the last line pushed, held until the rest of it comes
+    held: Vec<u8>,
+    /// Where the next chunk is written, which is how much the file holds
+    written: u64,
+    /// Where the next chunk is read
+    read: u64,
+    max_bytes: u64,
+    /// Whether dropping output has been logged, which is done once
+    dropped: bool,
+}
+
+impl Spill {
+    fn new(execution_id: Uuid, max_bytes: u64, dir: PathBuf) -> Self {
+        Self {
+            execution_id,
+            dir,
+            file: None,
+            path: None,
+            held: Vec::new(),
+            written: 0,
+            read: 0,
+            max_bytes,
+            dropped: false,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.read == self.written && self.held.is_empty()
+    }
+
+    /// Spill the lines `chunk` completes, redacted. Lines are redacted whole, so a secret
+    /// split between chunks is still found.
+    async fn push(&mut self, chunk: Bytes) {
+        self.held.extend_from_slice(&chunk);
+        let complete = match self.held.iter().rposition(|&byte| byte == b'\n') {
+            Some(end) => end + 1,
+            None if self.held.len() >= MAX_HELD_LINE_BYTES => self.held.len(),
+            None => return,
+        };
+        let lines = redact(self.execution_id, &self.held[..complete]);
+        self.held.drain(..complete);
+
+        let length = lines.len() as u64;
+        if self.written + length > self.max_bytes {
+            self.drop_bytes(length);
+            return;
+        }
+        match self.write(&lines).await {
+            Ok(()) => {
+                self.written += length;
+                metrics::record_output_spilled(length);
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Failed to spill output of execution {}: {}",
+                    self.execution_id,
+                    e
+                );
+                self.drop_bytes(length);
+            }
+        }
+    }
+
+    async fn write(&mut self, chunk: &[u8]) -> std::io::Result<()> {
+        if self.file.is_none() {
+            let (file, path) = create_spill_file(&self.dir, self.execution_id)?.into_parts();
+            self.file = Some(File::from_std(file));
+            self.path = Some(path);
+        }
+        let file = self.file.as_mut().unwrap();
+        file.seek(SeekFrom::Start(self.written)).await?;
+        file.write_all(chunk).await?;
+        file.flush().await
+    }
+
+    /// The oldest spilled chunk, or `None` if the spill couldn't be read, in which case
+    /// what it held is dropped
+    async fn take(&mut self) -> Option<Bytes> {
+        // The end of a line held back when everything before it has been read, which
+        // doesn't wait for the rest of the line to be written
+        if self.read == self.written {
+            let held = std::mem::take(&mut self.held);
+            return Some(redact(self.execution_id, &held));
+        }
+        let length = (self.written - self.read).min(CHUNK_SIZE as u64) as usize;
+        let mut chunk = vec![0; length];
+        let read = match self.file.as_mut() {
+            Some(file) => match file.seek(SeekFrom::Start(self.read)).await {
+                Ok(_) => file.read_exact(&mut chunk).await.map(|_| ()),
+                Err(e) => Err(e),
+            },
+            None => Ok(()),
+        };
+        if let Err(e) = read {
+            tracing::error!(
+                "Failed to read spilled output of execution {}: {}",
+                self.execution_id,
+                e
+            );
+            self.drop_bytes(self.written - self.read);
+            self.rewind().await;
+            return None;
+        }
+
+        self.read += length as u64;
+        if self.is_empty() {
+            self.rewind().await;
+        }
+        Some(Bytes::from(chunk))
+    }
+
+    /// Start over at the beginning of the file, once everything in it has been read
+    async fn rewind(&mut self) {
+        self.written = 0;
+        self.read = 0;
+        if let Some(file) = &self.file {
+            let _ = file.set_len(0).await;
+        }
+    }
+
+    fn drop_bytes(&mut self, length: u64) {
+        if !self.dropped {
+            tracing::warn!(
+                "Execution {} writes output faster than it's stored; dropping what doesn't fit",
+                self.execution_id
+            );
+            self.dropped = true;
+        }
+        metrics::record_output_dropped(length);
+    }
+}
+
+/// A file only the server's user can read, removed when its path is dropped
+fn create_spill_file(dir: &Path, execution_id: Uuid) -> std::io::Result<tempfile::NamedTempFile> {
+    std::fs::create_dir_all(dir)?;
+    // Created with mode 0600 on Unix
+    tempfile::Builder::new()
+        .prefix(&format!("vibe-kanban-output-{}-", execution_id))
+        .tempfile_in(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lines of text, a whole number of them to a chunk
+    fn output(chunks: usize) -> Vec<u8> {
+        (0..chunks * CHUNK_SIZE)
+            .map(|i| match i % 64 {
+                63 => b'\n',
+                column => b'a' + (column % 26) as u8,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_spilled_output_is_read_in_order() {
+        let written = output(10);
+        let (drain, mut captured) = capture(
+            std::io::Cursor::new(written.clone()),
+            Uuid::new_v4(),
+            2,
+            MAX_SPILL_BYTES,
+            std::env::temp_dir(),
+        );
+        // Nothing is read until the pipe is drained, so all but two chunks spill
+        drain.await;
+
+        let mut read = Vec::new();
+        captured.read_to_end(&mut read).await.unwrap();
+        assert_eq!(read, written);
+    }
+
+    #[tokio::test]
+    async fn test_output_that_does_not_fit_is_dropped() {
+        let written = output(8);
+        let (drain, mut captured) = capture(
+            std::io::Cursor::new(written.clone()),
+            Uuid::new_v4(),
+            2,
+            3 * CHUNK_SIZE as u64,
+            std::env::temp_dir(),
+        );
+        drain.await;
+
+        let mut read = Vec::new();
+        captured.read_to_end(&mut read).await.unwrap();
+        assert_eq!(read, written[..5 * CHUNK_SIZE]);
+    }
+
+    #[tokio::test]
+    async fn test_output_read_as_it_is_written() {
+        let (mut pipe, process) = tokio::io::duplex(64);
+        let mut captured = capture_output(process, Uuid::new_v4());
+        pipe.write_all(b"first\n").await.unwrap();
+        let mut line = [0; 6];
+        captured.read_exact(&mut line).await.unwrap();
+        assert_eq!(&line, b"first\n");
+
+        pipe.write_all(b"second\n").await.unwrap();
+        drop(pipe);
+        let mut rest = String::new();
+        captured.read_to_string(&mut rest).await.unwrap();
+        assert_eq!(rest, "second\n");
+    }
+
+    #[tokio::test]
+    async fn test_spilled_output_is_redacted_and_private() {
+        let execution_id = Uuid::new_v4();
+        environment_variables::register_redactions(execution_id, vec!["hunter2".to_string()]);
+        let dir = tempfile::tempdir().unwrap();
+        let mut spill = Spill::new(execution_id, MAX_SPILL_BYTES, dir.path().to_path_buf());
+        // The secret is split between chunks, and the line it's in isn't spilled until it ends
+        spill.push(Bytes::from_static(b"password: hun")).await;
+        assert!(spill.file.is_none());
+        spill.push(Bytes::from_static(b"ter2\nmore")).await;
+
+        let path = spill.path.as_ref().unwrap().to_path_buf();
+        assert!(path.starts_with(dir.path()));
+        let spilled = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(spilled, "password: [REDACTED]\n");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        assert_eq!(
+            spill.take().await.unwrap(),
+            Bytes::from_static(b"password: [REDACTED]\n")
+        );
+        assert_eq!(spill.take().await.unwrap(), Bytes::from_static(b"more"));
+        assert!(spill.is_empty());
+        drop(spill);
+        assert!(!path.exists());
+        environment_variables::forget_redactions(execution_id);
+    }
+}
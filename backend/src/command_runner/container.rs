@@ -0,0 +1,266 @@
+//! Running commands inside a Docker container, through the `docker` CLI
+
+use std::{path::PathBuf, process::Stdio};
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::command_runner::{
+    CommandError, CommandExecutor, CommandExitStatus, CommandRunnerArgs, CommandStream,
+    LocalCommandExecutor, ProcessHandle, ResourceLimits,
+};
+
+/// Seconds `docker stop` waits after SIGTERM before it kills the container
+const STOP_GRACE_SECS: &str = "2";
+
+/// The image commands run in, and the host paths they see, mounted at the same paths so
+/// what the command prints about them holds outside the container too
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Container {
+    pub image: String,
+    pub mounts: Vec<PathBuf>,
+}
+
+impl Container {
+    /// `docker run`'s arguments for running the request's command in a container called
+    /// `name`. The request's variables are passed by name, so their values stay off the
+    /// command line; the `docker` process itself gets them.
+    fn run_args(&self, name: &str, request: &CommandRunnerArgs) -> Vec<String> {
+        let mut args: Vec<String> = ["run", "--rm", "-i", "--init", "--name", name]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+
+        // Files the command writes belong to the user running vibe-kanban, who has no
+        // home directory in the image
+        #[cfg(unix)]
+        {
+            let user = format!("{}:{}", nix::unistd::getuid(), nix::unistd::getgid());
+            args.extend(["--user".to_string(), user]);
+            if !request.env_vars.iter().any(|(key, _)| key == "HOME") {
+                args.extend(["-e".to_string(), "HOME=/tmp".to_string()]);
+            }
+        }
+
+        // The container enforces them, rather than the `docker` client process
+        args.extend(request.limits.docker_args());
+
+        for mount in &self.mounts {
+            let path = mount.to_string_lossy();
+            args.extend(["-v".to_string(), format!("{}:{}", path, path)]);
+        }
+        if let Some(dir) = &request.working_dir {
+            args.extend(["-w".to_string(), dir.clone()]);
+        }
+        for (key, _) in &request.env_vars {
+            args.extend(["-e".to_string(), key.clone()]);
+        }
+
+        args.push(self.image.clone());
+        args.push(request.command.clone());
+        args.extend(request.args.iter().cloned());
+        args
+    }
+}
+
+async fn docker(args: &[&str]) -> Result<std::process::Output, CommandError> {
+    tokio::process::Command::new("docker")
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .map_err(|e| CommandError::ContainerFailed {
+            reason: format!("couldn't run docker: {}", e),
+        })
+}
+
+/// Pull the image unless it's already there
+async fn ensure_image(image: &str) -> Result<(), CommandError> {
+    if docker(&["image", "inspect", image]).await?.status.success() {
+        return Ok(());
+    }
+    tracing::info!("Pulling container image {}", image);
+    let output = docker(&["pull", image]).await?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(CommandError::ContainerFailed {
+            reason: format!(
+                "couldn't pull {}: {}",
+                image,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        })
+    }
+}
+
+/// Starts each command in a fresh container of its image, removed once the command exits
+pub struct ContainerCommandExecutor {
+    container: Container,
+}
+
+impl ContainerCommandExecutor {
+    pub fn new(container: Container) -> Self {
+        Self { container }
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for ContainerCommandExecutor {
+    async fn start(
+        &self,
+        request: &CommandRunnerArgs,
+    ) -> Result<Box<dyn ProcessHandle>, CommandError> {
+        ensure_image(&self.container.image).await?;
+
+        let name = format!("vibe-kanban-{}", Uuid::new_v4());
+        let docker_run = CommandRunnerArgs {
+            command: "docker".to_string(),
+            args: self.container.run_args(&name, request),
+            working_dir: request.working_dir.clone(),
+            env_vars: request.env_vars.clone(),
+            stdin: request.stdin.clone(),
+            // `docker run -i` passes stdin on to the container
+            keep_stdin_open: request.keep_stdin_open,
+            limits: ResourceLimits::default(),
+        };
+        let inner = LocalCommandExecutor::new().start(&docker_run).await?;
+
+        Ok(Box::new(ContainerProcessHandle {
+            inner,
+            name,
+            exited: false,
+        }))
+    }
+}
+
+/// The `docker run` process attached to the container, whose output and exit status are
+/// the command's. The container is stopped through docker, as signalling the client
+/// doesn't reach it.
+pub struct ContainerProcessHandle {
+    inner: Box<dyn ProcessHandle>,
+    name: String,
+    exited: bool,
+}
+
+impl ContainerProcessHandle {
+    async fn container_command(&self, command: &[&str]) -> Result<(), CommandError> {
+        let mut args = command.to_vec();
+        args.push(&self.name);
+        let output = docker(&args).await?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(CommandError::ContainerFailed {
+                reason: format!(
+                    "docker {} {} failed: {}",
+                    command.join(" "),
+                    self.name,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            })
+        }
+    }
+}
+
+#[async_trait]
+impl ProcessHandle for ContainerProcessHandle {
+    async fn try_wait(&mut self) -> Result<Option<CommandExitStatus>, CommandError> {
+        let status = self.inner.try_wait().await?;
+        self.exited |= status.is_some();
+        Ok(status)
+    }
+
+    async fn wait(&mut self) -> Result<CommandExitStatus, CommandError> {
+        let status = self.inner.wait().await?;
+        self.exited = true;
+        Ok(status)
+    }
+
+    async fn kill(&mut self) -> Result<(), CommandError> {
+        if !self.exited {
+            // A container that's already gone is fine; the client is ended below anyway
+            if let Err(e) = self
+                .container_command(&["stop", "--time", STOP_GRACE_SECS])
+                .await
+            {
+                tracing::warn!("{}", e);
+            }
+        }
+        self.inner.kill().await?;
+        self.exited = true;
+        Ok(())
+    }
+
+    async fn stream(&mut self) -> Result<CommandStream, CommandError> {
+        self.inner.stream().await
+    }
+
+    async fn pause(&mut self) -> Result<(), CommandError> {
+        self.container_command(&["pause"]).await
+    }
+
+    async fn resume(&mut self) -> Result<(), CommandError> {
+        self.container_command(&["unpause"]).await
+    }
+
+    async fn write_stdin(&mut self, data: &str) -> Result<(), CommandError> {
+        self.inner.write_stdin(data).await
+    }
+
+    async fn close_stdin(&mut self) -> Result<(), CommandError> {
+        self.inner.close_stdin().await
+    }
+
+    fn process_id(&self) -> String {
+        format!("{} ({})", self.inner.process_id(), self.name)
+    }
+}
+
+/// The container outlives neither the handle nor the execution it belongs to
+impl Drop for ContainerProcessHandle {
+    fn drop(&mut self) {
+        if !self.exited {
+            let _ = std::process::Command::new("docker")
+                .args(["kill", &self.name])
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_args_mount_paths_and_pass_env_by_name() {
+        let container = Container {
+            image: "node:20".to_string(),
+            mounts: vec![PathBuf::from("/work/tree"), PathBuf::from("/repo/.git")],
+        };
+        let request = CommandRunnerArgs {
+            command: "bash".to_string(),
+            args: vec!["-c".to_string(), "npm test".to_string()],
+            working_dir: Some("/work/tree".to_string()),
+            env_vars: vec![("API_KEY".to_string(), "secret".to_string())],
+            stdin: None,
+            keep_stdin_open: false,
+            limits: ResourceLimits {
+                max_rss_bytes: Some(1024 * 1024 * 1024),
+                ..Default::default()
+            },
+        };
+        let args = container.run_args("vibe-kanban-test", &request);
+        let joined = args.join(" ");
+
+        assert!(joined.starts_with("run --rm -i --init --name vibe-kanban-test"));
+        assert!(joined.contains("--memory=1073741824b"));
+        assert!(joined.contains("-v /work/tree:/work/tree -v /repo/.git:/repo/.git"));
+        assert!(joined.contains("-w /work/tree -e API_KEY node:20"));
+        assert!(!joined.contains("secret"));
+        assert_eq!(&args[args.len() - 3..], ["bash", "-c", "npm test"]);
+    }
+}
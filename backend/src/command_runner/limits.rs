@@ -0,0 +1,272 @@
+//! Caps on the memory, CPU and open files of a command and the processes it starts
+
+use serde::{Deserialize, Serialize};
+
+use crate::command_runner::ResourceSample;
+
+/// A cgroup v2 directory commands get cgroups of their own in, delegated to the user running
+/// vibe-kanban with the `memory` and `cpu` controllers enabled for its children. Without
+/// one, memory is capped by sampling and CPU shares become a nice value.
+#[cfg(target_os = "linux")]
+const CGROUP_VAR: &str = "VIBE_KANBAN_CGROUP";
+
+/// The CPU shares of a process nothing limits
+const DEFAULT_CPU_SHARES: u64 = 1024;
+
+const MEGABYTE: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceLimits {
+    /// Resident memory the command and what it starts may use together
+    pub max_rss_bytes: Option<u64>,
+    /// CPU weight against other commands, as cgroup v1's `cpu.shares`: 1024 is an
+    /// unlimited command's, 512 half of that
+    pub cpu_shares: Option<u64>,
+    /// Files each of the processes may have open
+    pub max_open_files: Option<u64>,
+}
+
+impl ResourceLimits {
+    pub fn is_unlimited(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Why the sample breaks the limits, if it does
+    pub fn exceeded_by(&self, sample: &ResourceSample) -> Option<String> {
+        let max = self.max_rss_bytes?;
+        (sample.rss_bytes > max).then(|| {
+            format!(
+                "used {} MB of memory, over its {} MB limit",
+                sample.rss_bytes / MEGABYTE,
+                max / MEGABYTE
+            )
+        })
+    }
+
+    /// `docker run`'s flags for the limits, which the container then enforces
+    pub(crate) fn docker_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(max) = self.max_rss_bytes {
+            args.push(format!("--memory={}b", max));
+            // Swapping would let the command go past the limit instead of ending it
+            args.push(format!("--memory-swap={}b", max));
+        }
+        if let Some(shares) = self.cpu_shares {
+            args.push(format!("--cpu-shares={}", shares));
+        }
+        if let Some(files) = self.max_open_files {
+            args.push(format!("--ulimit=nofile={}:{}", files, files));
+        }
+        args
+    }
+
+    /// Apply the limits to `cmd` as it's spawned: memory and CPU through a cgroup of its own
+    /// where one can be made, open files through `RLIMIT_NOFILE`, and CPU shares below the
+    /// default through niceness where there's no cgroup. The cgroup is kept with the
+    /// process and removed with it.
+    #[cfg(unix)]
+    pub(crate) fn apply(
+        &self,
+        cmd: &mut tokio::process::Command,
+    ) -> Result<Option<Cgroup>, std::io::Error> {
+        use std::os::fd::AsRawFd;
+
+        if self.is_unlimited() {
+            return Ok(None);
+        }
+        let cgroup = Cgroup::create(self)?;
+        let procs_fd = cgroup
+            .as_ref()
+            .and_then(|cgroup| cgroup.procs.as_ref())
+            .map(|procs| procs.as_raw_fd());
+        let nice = match (&cgroup, self.cpu_shares) {
+            (None, Some(shares)) => Some(niceness(shares)),
+            _ => None,
+        };
+        let nofile = self.max_open_files.map(|files| {
+            // Above the hard limit only root may go
+            let mut current = libc::rlimit {
+                rlim_cur: 0,
+                rlim_max: libc::RLIM_INFINITY,
+            };
+            unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut current) };
+            let files = files.min(current.rlim_max);
+            libc::rlimit {
+                rlim_cur: files,
+                rlim_max: files,
+            }
+        });
+
+        // Between fork and exec only async-signal-safe calls, so nothing allocates
+        unsafe {
+            cmd.pre_exec(move || {
+                if let Some(fd) = procs_fd {
+                    // "0" moves the process writing it
+                    if libc::write(fd, b"0".as_ptr().cast(), 1) < 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                }
+                if let Some(limit) = &nofile {
+                    if libc::setrlimit(libc::RLIMIT_NOFILE, limit) != 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                }
+                if let Some(nice) = nice {
+                    libc::setpriority(libc::PRIO_PROCESS, 0, nice);
+                }
+                Ok(())
+            });
+        }
+        Ok(cgroup)
+    }
+}
+
+/// cgroup v2's `cpu.weight` for cgroup v1 CPU shares, converted the way runc does
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn cpu_weight(shares: u64) -> u64 {
+    let shares = shares.clamp(2, 262_144);
+    1 + (shares - 2) * 9_999 / 262_142
+}
+
+/// The nice value standing in for CPU shares when there's no cgroup. Only lowering the
+/// priority needs no privileges, so shares above the default run at the default.
+#[cfg_attr(not(unix), allow(dead_code))]
+fn niceness(shares: u64) -> i32 {
+    let below = DEFAULT_CPU_SHARES.saturating_sub(shares);
+    (below * 19 / DEFAULT_CPU_SHARES) as i32
+}
+
+/// The `oom_kill` count of a cgroup's `memory.events`
+#[cfg_attr(not(unix), allow(dead_code))]
+fn oom_kills(memory_events: &str) -> u64 {
+    memory_events
+        .lines()
+        .find_map(|line| line.strip_prefix("oom_kill "))
+        .and_then(|count| count.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// The cgroup v2 a command was started in, removed once it's dropped
+#[cfg(unix)]
+#[derive(Debug)]
+pub(crate) struct Cgroup {
+    path: std::path::PathBuf,
+    /// Its `cgroup.procs`, open for the command to join it by between fork and exec
+    procs: Option<std::fs::File>,
+}
+
+#[cfg(unix)]
+impl Cgroup {
+    /// A cgroup under `VIBE_KANBAN_CGROUP` with the limits set, if that is set
+    #[cfg(target_os = "linux")]
+    fn create(limits: &ResourceLimits) -> Result<Option<Self>, std::io::Error> {
+        let Some(parent) = std::env::var_os(CGROUP_VAR).filter(|parent| !parent.is_empty()) else {
+            return Ok(None);
+        };
+        let path =
+            std::path::PathBuf::from(parent).join(format!("vibe-kanban-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir(&path)?;
+        let mut cgroup = Cgroup { path, procs: None };
+
+        if let Some(max) = limits.max_rss_bytes {
+            std::fs::write(cgroup.path.join("memory.max"), max.to_string())?;
+            // Not every kernel accounts for swap
+            let _ = std::fs::write(cgroup.path.join("memory.swap.max"), "0");
+        }
+        if let Some(shares) = limits.cpu_shares {
+            std::fs::write(
+                cgroup.path.join("cpu.weight"),
+                cpu_weight(shares).to_string(),
+            )?;
+        }
+        cgroup.procs = Some(
+            std::fs::OpenOptions::new()
+                .write(true)
+                .open(cgroup.path.join("cgroup.procs"))?,
+        );
+        Ok(Some(cgroup))
+    }
+
+    #[cfg(all(unix, not(target_os = "linux")))]
+    fn create(_limits: &ResourceLimits) -> Result<Option<Self>, std::io::Error> {
+        Ok(None)
+    }
+
+    /// Whether the kernel killed one of its processes for going over `memory.max`
+    pub(crate) fn oom_killed(&self) -> bool {
+        std::fs::read_to_string(self.path.join("memory.events"))
+            .map(|events| oom_kills(&events) > 0)
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Cgroup {
+    fn drop(&mut self) {
+        // Fails while processes are left in it, which the process handle kills first
+        let _ = std::fs::remove_dir(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_over_the_limit_is_reported() {
+        let limits = ResourceLimits {
+            max_rss_bytes: Some(512 * MEGABYTE),
+            ..Default::default()
+        };
+        let sample = |rss_bytes| ResourceSample {
+            cpu_seconds: 1.0,
+            rss_bytes,
+        };
+        assert_eq!(limits.exceeded_by(&sample(512 * MEGABYTE)), None);
+        assert_eq!(
+            limits.exceeded_by(&sample(600 * MEGABYTE)).as_deref(),
+            Some("used 600 MB of memory, over its 512 MB limit")
+        );
+        assert_eq!(
+            ResourceLimits::default().exceeded_by(&sample(600 * MEGABYTE)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_limits_become_docker_flags() {
+        let limits = ResourceLimits {
+            max_rss_bytes: Some(MEGABYTE),
+            cpu_shares: Some(512),
+            max_open_files: Some(4096),
+        };
+        assert_eq!(
+            limits.docker_args(),
+            vec![
+                "--memory=1048576b",
+                "--memory-swap=1048576b",
+                "--cpu-shares=512",
+                "--ulimit=nofile=4096:4096",
+            ]
+        );
+        assert!(ResourceLimits::default().docker_args().is_empty());
+    }
+
+    #[test]
+    fn test_cpu_shares_conversions() {
+        assert_eq!(cpu_weight(2), 1);
+        assert_eq!(cpu_weight(1024), 39);
+        assert_eq!(cpu_weight(262_144), 10_000);
+        assert_eq!(niceness(1024), 0);
+        assert_eq!(niceness(4096), 0);
+        assert_eq!(niceness(512), 9);
+        assert_eq!(niceness(0), 19);
+    }
+
+    #[test]
+    fn test_oom_kills_are_read_from_memory_events() {
+        let events = "low 0\nhigh 0\nmax 12\noom 2\noom_kill 1\noom_group_kill 0\n";
+        assert_eq!(oom_kills(events), 1);
+        assert_eq!(oom_kills("low 0\n"), 0);
+    }
+}
@@ -5,13 +5,18 @@ use command_group::{AsyncCommandGroup, AsyncGroupChild};
 #[cfg(unix)]
 use nix::{
     sys::signal::{killpg, Signal},
-    unistd::{getpgid, Pid},
+    unistd::Pid,
+};
+use tokio::{
+    io::AsyncWriteExt,
+    process::{ChildStdin, Command},
 };
-use tokio::process::Command;
 
+#[cfg(unix)]
+use crate::command_runner::limits::Cgroup;
 use crate::command_runner::{
     CommandError, CommandExecutor, CommandExitStatus, CommandRunnerArgs, CommandStream,
-    ProcessHandle,
+    ProcessHandle, ResourceSample,
 };
 
 pub struct LocalCommandExecutor;
@@ -50,42 +55,124 @@ impl CommandExecutor for LocalCommandExecutor {
             cmd.env(key, val);
         }
 
-        let mut child = cmd.group_spawn().map_err(|e| CommandError::SpawnFailed {
+        let spawn_failed = |error| CommandError::SpawnFailed {
             command: format!("{} {}", request.command, request.args.join(" ")),
-            error: e,
-        })?;
+            error,
+        };
+        #[cfg(unix)]
+        let cgroup = request.limits.apply(&mut cmd).map_err(spawn_failed)?;
+        #[cfg(not(unix))]
+        if !request.limits.is_unlimited() {
+            tracing::warn!(
+                "Resource limits aren't applied on this platform; running {} without them",
+                request.command
+            );
+        }
 
-        if let Some(prompt) = &request.stdin {
+        // Its own process group (a job object on Windows), so whatever it starts can be
+        // ended with it
+        let mut child = cmd
+            .group()
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(spawn_failed)?;
+
+        let mut stdin = child.inner().stdin.take();
+        if let (Some(prompt), Some(stdin)) = (&request.stdin, stdin.as_mut()) {
             // Write prompt to stdin safely
-            if let Some(mut stdin) = child.inner().stdin.take() {
-                use tokio::io::AsyncWriteExt;
-                stdin.write_all(prompt.as_bytes()).await?;
+            stdin.write_all(prompt.as_bytes()).await?;
+            stdin.flush().await?;
+        }
+        if !request.keep_stdin_open {
+            if let Some(mut stdin) = stdin.take() {
                 stdin.shutdown().await?;
             }
         }
 
-        Ok(Box::new(LocalProcessHandle::new(child)))
+        let mut handle = LocalProcessHandle::new(child);
+        handle.stdin = stdin;
+        #[cfg(unix)]
+        {
+            handle.cgroup = cgroup;
+        }
+        Ok(Box::new(handle))
     }
 }
 
+/// How long a process group gets to exit after each signal before the next, harsher one
+#[cfg(unix)]
+const KILL_GRACE_PERIOD: Duration = Duration::from_secs(2);
+#[cfg(unix)]
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
 pub struct LocalProcessHandle {
     child: Option<AsyncGroupChild>,
     process_id: String,
+    /// The child was spawned as the leader of its own group, so the group id is its pid.
+    /// Kept from the start, as the leader may exit before the processes it started.
+    process_group: Option<u32>,
+    /// Kept when the process was started to be answered while it runs
+    stdin: Option<ChildStdin>,
+    /// The cgroup its resource limits put it in, if they did
+    #[cfg(unix)]
+    cgroup: Option<Cgroup>,
 }
 
 impl LocalProcessHandle {
     pub fn new(mut child: AsyncGroupChild) -> Self {
-        let process_id = child
-            .inner()
-            .id()
+        let pid = child.inner().id();
+        let process_id = pid
             .map(|id| id.to_string())
             .unwrap_or_else(|| "unknown".to_string());
 
         Self {
             child: Some(child),
             process_id,
+            process_group: pid,
+            stdin: None,
+            #[cfg(unix)]
+            cgroup: None,
+        }
+    }
+
+    /// SIGKILL whatever is left of the group, such as children the leader left running
+    /// when it exited or that ignored the signals it was stopped with
+    #[cfg(unix)]
+    fn kill_stragglers(&self) {
+        if let Some(pgid) = self.process_group {
+            // ESRCH just means the group is already gone
+            let _ = killpg(Pid::from_raw(pgid as i32), Signal::SIGKILL);
         }
     }
+
+    #[cfg(unix)]
+    fn signal_group(&self, signal: Signal) -> Result<(), CommandError> {
+        let pgid = self.process_group.ok_or(CommandError::ProcessNotStarted)?;
+        killpg(Pid::from_raw(pgid as i32), signal).map_err(|e| CommandError::SignalFailed {
+            signal: signal.as_str(),
+            error: std::io::Error::other(e),
+        })
+    }
+}
+
+/// Whether `child` exits within `period`
+#[cfg(unix)]
+async fn exits_within(child: &mut AsyncGroupChild, period: Duration) -> Result<bool, CommandError> {
+    let deadline = tokio::time::Instant::now() + period;
+    loop {
+        if child
+            .inner()
+            .try_wait()
+            .map_err(|e| CommandError::StatusCheckFailed { error: e })?
+            .is_some()
+        {
+            return Ok(true);
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return Ok(false);
+        }
+        tokio::time::sleep(EXIT_POLL_INTERVAL).await;
+    }
 }
 
 #[async_trait]
@@ -120,50 +207,45 @@ impl ProcessHandle for LocalProcessHandle {
     async fn kill(&mut self) -> Result<(), CommandError> {
         match &mut self.child {
             Some(child) => {
+                #[cfg_attr(not(unix), allow(unused_mut))]
+                let mut exited = false;
+
                 // hit the whole process group, not just the leader
                 #[cfg(unix)]
-                {
-                    if let Some(pid) = child.inner().id() {
-                        let pgid = getpgid(Some(Pid::from_raw(pid as i32))).map_err(|e| {
-                            CommandError::KillFailed {
-                                error: std::io::Error::other(e),
-                            }
-                        })?;
-
-                        for sig in [Signal::SIGINT, Signal::SIGTERM, Signal::SIGKILL] {
-                            if let Err(e) = killpg(pgid, sig) {
-                                tracing::warn!(
-                                    "Failed to send signal {:?} to process group {}: {}",
-                                    sig,
-                                    pgid,
-                                    e
-                                );
-                            }
-                            tokio::time::sleep(Duration::from_secs(2)).await;
-                            if child
-                                .inner()
-                                .try_wait()
-                                .map_err(|e| CommandError::StatusCheckFailed { error: e })?
-                                .is_some()
-                            {
-                                break; // gone!
-                            }
+                if let Some(pgid) = self.process_group.map(|pgid| Pid::from_raw(pgid as i32)) {
+                    // A paused group only acts on SIGINT once it runs again
+                    let _ = killpg(pgid, Signal::SIGCONT);
+                    for sig in [Signal::SIGINT, Signal::SIGTERM, Signal::SIGKILL] {
+                        if let Err(e) = killpg(pgid, sig) {
+                            tracing::warn!(
+                                "Failed to send signal {:?} to process group {}: {}",
+                                sig,
+                                pgid,
+                                e
+                            );
+                        }
+                        if exits_within(child, KILL_GRACE_PERIOD).await? {
+                            exited = true;
+                            break; // gone!
                         }
                     }
                 }
 
                 // final fallback – command_group already targets the group
-                child
-                    .kill()
-                    .await
-                    .map_err(|e| CommandError::KillFailed { error: e })?;
-                child
-                    .wait()
-                    .await
-                    .map_err(|e| CommandError::KillFailed { error: e })?; // reap
+                if !exited {
+                    child
+                        .kill()
+                        .await
+                        .map_err(|e| CommandError::KillFailed { error: e })?;
+                    child
+                        .wait()
+                        .await
+                        .map_err(|e| CommandError::KillFailed { error: e })?; // reap
+                }
+                #[cfg(unix)]
+                self.kill_stragglers();
 
-                // Clear the handle after successful kill
-                self.child = None;
+                // The handle is kept, so the exit status can still be read
                 Ok(())
             }
             None => Err(CommandError::ProcessNotStarted),
@@ -184,6 +266,120 @@ impl ProcessHandle for LocalProcessHandle {
     fn process_id(&self) -> String {
         self.process_id.clone()
     }
+
+    fn process_group(&self) -> Option<u32> {
+        self.process_group
+    }
+
+    async fn write_stdin(&mut self, data: &str) -> Result<(), CommandError> {
+        let stdin = self.stdin.as_mut().ok_or(CommandError::InputClosed)?;
+        let written = match stdin.write_all(data.as_bytes()).await {
+            Ok(()) => stdin.flush().await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = written {
+            // The process stopped reading, most likely because it exited
+            self.stdin = None;
+            return Err(if e.kind() == std::io::ErrorKind::BrokenPipe {
+                CommandError::InputClosed
+            } else {
+                CommandError::IoError { error: e }
+            });
+        }
+        Ok(())
+    }
+
+    async fn close_stdin(&mut self) -> Result<(), CommandError> {
+        if let Some(mut stdin) = self.stdin.take() {
+            // A process that stopped reading has closed it already
+            let _ = stdin.shutdown().await;
+        }
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    async fn pause(&mut self) -> Result<(), CommandError> {
+        self.signal_group(Signal::SIGSTOP)
+    }
+
+    #[cfg(unix)]
+    async fn resume(&mut self) -> Result<(), CommandError> {
+        self.signal_group(Signal::SIGCONT)
+    }
+
+    /// Summed over the process group, from `ps`, which reads the same on Linux and macOS
+    #[cfg(unix)]
+    async fn resource_usage(&mut self) -> Result<Option<ResourceSample>, CommandError> {
+        let Some(pgid) = self.process_group else {
+            return Ok(None);
+        };
+        let output = Command::new("ps")
+            .args(["-A", "-o", "pgid=,rss=,time="])
+            .stdin(Stdio::null())
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+        Ok(Some(group_usage(
+            &String::from_utf8_lossy(&output.stdout),
+            pgid,
+        )))
+    }
+
+    #[cfg(unix)]
+    async fn limit_exceeded(&mut self) -> Option<String> {
+        self.cgroup
+            .as_ref()
+            .filter(|cgroup| cgroup.oom_killed())
+            .map(|_| "was killed for going over its memory limit".to_string())
+    }
+}
+
+/// The usage of the processes in group `pgid`, from `ps -o pgid=,rss=,time=` output
+#[cfg_attr(not(unix), allow(dead_code))]
+fn group_usage(ps_output: &str, pgid: u32) -> ResourceSample {
+    let mut sample = ResourceSample {
+        cpu_seconds: 0.0,
+        rss_bytes: 0,
+    };
+    for line in ps_output.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(group), Some(rss_kib), Some(time)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        if group.parse::<u32>().ok() != Some(pgid) {
+            continue;
+        }
+        sample.rss_bytes += rss_kib.parse::<u64>().unwrap_or(0) * 1024;
+        sample.cpu_seconds += parse_cpu_time(time).unwrap_or(0.0);
+    }
+    sample
+}
+
+/// Seconds of a `ps` CPU time: `[DD-]hh:mm:ss` on Linux, `m:ss.cc` on macOS
+#[cfg_attr(not(unix), allow(dead_code))]
+fn parse_cpu_time(time: &str) -> Option<f64> {
+    let (days, clock) = match time.split_once('-') {
+        Some((days, clock)) => (days.parse::<f64>().ok()?, clock),
+        None => (0.0, time),
+    };
+    let mut seconds = 0.0;
+    for part in clock.split(':') {
+        seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(days * 86_400.0 + seconds)
+}
+
+/// The group outlives neither the handle nor the execution it belongs to. On Windows the
+/// job object does this, as it is closed with the handle.
+#[cfg(unix)]
+impl Drop for LocalProcessHandle {
+    fn drop(&mut self) {
+        self.kill_stragglers();
+    }
 }
 
 // Local-specific implementations for shared types
@@ -356,6 +552,47 @@ mod tests {
         assert_eq!(runner_output, test_input);
     }
 
+    #[tokio::test]
+    async fn test_stdin_kept_open() {
+        std::env::set_var("ENVIRONMENT", "local");
+
+        // `head -n 2` exits once it has read the prompt and the answer written after it
+        let mut process = CommandRunner::new()
+            .command("head")
+            .arg("-n")
+            .arg("2")
+            .stdin("prompt\n")
+            .keep_stdin_open()
+            .start()
+            .await
+            .expect("CommandRunner should start head");
+        process
+            .write_stdin("y\n")
+            .await
+            .expect("Should write to the open stdin");
+
+        let mut stream = process.stream().await.expect("Should get stream");
+        let mut stdout_data = Vec::new();
+        if let Some(stdout) = &mut stream.stdout {
+            stdout
+                .read_to_end(&mut stdout_data)
+                .await
+                .expect("Should read stdout");
+        }
+        assert_eq!(String::from_utf8_lossy(&stdout_data), "prompt\ny\n");
+
+        let mut closed = CommandRunner::new()
+            .command("cat")
+            .stdin("prompt\n")
+            .start()
+            .await
+            .expect("CommandRunner should start cat");
+        assert!(matches!(
+            closed.write_stdin("y\n").await,
+            Err(CommandError::InputClosed)
+        ));
+    }
+
     #[tokio::test]
     async fn test_working_directory() {
         // Use pwd command to check working directory
@@ -700,4 +937,219 @@ mod tests {
             "tokio false should exit with code 1"
         );
     }
+
+    #[tokio::test]
+    async fn test_kill_if_overdue() {
+        std::env::set_var("ENVIRONMENT", "local");
+
+        let mut process = CommandRunner::new()
+            .command("sleep")
+            .arg("30")
+            .start()
+            .await
+            .expect("CommandRunner should start sleep");
+        assert!(
+            !process.kill_if_overdue().await.unwrap(),
+            "a process without a deadline is never overdue"
+        );
+
+        process.set_timeout(std::time::Duration::from_secs(60));
+        assert!(!process.kill_if_overdue().await.unwrap());
+
+        process.set_timeout(std::time::Duration::ZERO);
+        assert!(
+            process.kill_if_overdue().await.unwrap(),
+            "a process past its deadline is killed"
+        );
+        let status = process.status().await.expect("Should check final status");
+        assert!(status.is_some(), "killed process should have exited");
+    }
+
+    /// How coding agents are started: a command line run through the platform's shell
+    #[tokio::test]
+    async fn test_shell_command_spawns() {
+        use crate::utils::shell::{get_shell_command, shell_quote};
+
+        std::env::set_var("ENVIRONMENT", "local");
+        let (shell_cmd, shell_arg) = get_shell_command();
+        let mut process = CommandRunner::new()
+            .command(shell_cmd)
+            .arg(shell_arg)
+            .arg(&format!("echo {}", shell_quote("spawned")))
+            .start()
+            .await
+            .expect("CommandRunner should start the shell");
+
+        let mut stream = process.stream().await.expect("Should get stream");
+        let mut stdout_data = Vec::new();
+        if let Some(stdout) = &mut stream.stdout {
+            stdout
+                .read_to_end(&mut stdout_data)
+                .await
+                .expect("Should read stdout");
+        }
+        let output = String::from_utf8(stdout_data).expect("Should be valid UTF-8");
+        // cmd echoes the quotes along with the word
+        assert_eq!(output.trim().trim_matches('"'), "spawned");
+        assert!(process.wait().await.unwrap().success());
+    }
+
+    /// On Windows the process tree lives in a job object, which kill terminates
+    #[cfg(windows)]
+    #[tokio::test]
+    async fn test_windows_kill_ends_the_process_tree() {
+        std::env::set_var("ENVIRONMENT", "local");
+        let mut process = CommandRunner::new()
+            .command("cmd")
+            .arg("/C")
+            .arg("ping -n 30 127.0.0.1 > nul")
+            .start()
+            .await
+            .expect("CommandRunner should start cmd");
+        assert!(process.try_wait().await.unwrap().is_none());
+
+        let started = std::time::Instant::now();
+        process.kill().await.expect("Should kill the job");
+        assert!(process.try_wait().await.unwrap().is_some());
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_pause_and_resume() {
+        std::env::set_var("ENVIRONMENT", "local");
+
+        let mut process = CommandRunner::new()
+            .command("sleep")
+            .arg("30")
+            .start()
+            .await
+            .expect("CommandRunner should start sleep");
+        process.set_timeout(std::time::Duration::ZERO);
+
+        process.pause().await.expect("Should pause the process");
+        process.pause().await.expect("Pausing again does nothing");
+        assert!(
+            !process.kill_if_overdue().await.unwrap(),
+            "a paused process is never overdue"
+        );
+        assert!(process.try_wait().await.unwrap().is_none());
+
+        process.resume().await.expect("Should resume the process");
+        assert!(process.try_wait().await.unwrap().is_none());
+
+        // A stopped process still ends when it is killed
+        process.pause().await.unwrap();
+        process
+            .kill()
+            .await
+            .expect("Should kill the paused process");
+        let status = process.status().await.expect("Should check final status");
+        assert!(status.is_some(), "killed process should have exited");
+    }
+
+    /// Start `script` in `sh`; the first line it prints is the pid of a child it started,
+    /// once that child is set up
+    #[cfg(unix)]
+    async fn start_with_child(script: &str) -> (CommandProcess, u32) {
+        use tokio::io::{AsyncBufReadExt, BufReader};
+
+        std::env::set_var("ENVIRONMENT", "local");
+        let mut process = CommandRunner::new()
+            .command("sh")
+            .arg("-c")
+            .arg(script)
+            .start()
+            .await
+            .expect("CommandRunner should start sh");
+        let stdout = process.stream().await.unwrap().stdout.unwrap();
+        let mut line = String::new();
+        BufReader::new(stdout).read_line(&mut line).await.unwrap();
+        (process, line.trim().parse().expect("script prints a pid"))
+    }
+
+    /// Whether `pid` is a live process; a zombie waiting for init to reap it is not
+    #[cfg(unix)]
+    fn is_alive(pid: u32) -> bool {
+        let output = std::process::Command::new("ps")
+            .args(["-o", "stat=", "-p", &pid.to_string()])
+            .output()
+            .unwrap();
+        let stat = String::from_utf8_lossy(&output.stdout);
+        !stat.trim().is_empty() && !stat.trim().starts_with('Z')
+    }
+
+    #[cfg(unix)]
+    async fn assert_ends(pid: u32, what: &str) {
+        for _ in 0..50 {
+            if !is_alive(pid) {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        panic!("{} is still running", what);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_children_left_behind_end_with_the_handle() {
+        let (mut process, orphan) = start_with_child("sleep 30 & echo $!").await;
+        process.wait().await.expect("sh should exit");
+        assert!(
+            is_alive(orphan),
+            "the child outlives the shell that started it"
+        );
+
+        drop(process);
+        assert_ends(orphan, "the orphaned child").await;
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_kill_ends_children_that_ignore_sigint() {
+        // The leader goes at SIGINT, before the group is sent anything harsher
+        let (mut process, child) =
+            start_with_child("sh -c 'trap \"\" INT TERM; echo $$; exec sleep 30' & exec sleep 30")
+                .await;
+        process.kill().await.expect("Should kill the group");
+        assert_ends(child, "the child ignoring SIGINT").await;
+        assert!(process.try_wait().await.unwrap().is_some());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_cancellation_stops_commands_of_the_execution() {
+        std::env::set_var("ENVIRONMENT", "local");
+        let token = tokio_util::sync::CancellationToken::new();
+        let mut process = with_cancellation(token.clone(), async {
+            CommandRunner::new()
+                .command("sleep")
+                .arg("30")
+                .start()
+                .await
+        })
+        .await
+        .expect("Should start sleep");
+        assert!(!process.kill_if_cancelled().await.unwrap());
+
+        token.cancel();
+        assert!(process.kill_if_cancelled().await.unwrap());
+        assert!(process.try_wait().await.unwrap().is_some());
+
+        let refused = with_cancellation(token, async {
+            CommandRunner::new().command("true").start().await
+        })
+        .await;
+        assert!(matches!(refused, Err(CommandError::Cancelled)));
+    }
+
+    #[test]
+    fn test_group_usage_sums_the_group() {
+        let ps_output = "    1     4100 00:01:02\n  777   204800 1-00:00:01\n  777     1024 0:00.50\n  778     2048 00:00:09\n";
+        let sample = super::group_usage(ps_output, 777);
+        assert_eq!(sample.rss_bytes, (204800 + 1024) * 1024);
+        assert_eq!(sample.cpu_seconds, 86_401.5);
+        assert_eq!(super::parse_cpu_time("00:01:02"), Some(62.0));
+        assert_eq!(super::parse_cpu_time("?"), None);
+    }
 }
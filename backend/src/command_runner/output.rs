@@ -0,0 +1,147 @@
+//! Per-execution broadcast channels announcing that a running execution wrote output, so
+//! log streams can wait for new output instead of polling for it, and a count of the tasks
+//! still storing an execution's output, so its completion isn't handled before they finish
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::event_bus::{self, BoardEvent};
+
+/// Notifications a slow subscriber may fall behind by; it only needs to know that
+/// something changed, so lagging loses nothing
+const CHANNEL_CAPACITY: usize = 16;
+
+lazy_static::lazy_static! {
+    static ref OUTPUT_CHANNELS: Mutex<HashMap<Uuid, broadcast::Sender<()>>> =
+        Mutex::new(HashMap::new());
+    static ref OUTPUT_WRITERS: Mutex<HashMap<Uuid, usize>> = Mutex::new(HashMap::new());
+}
+
+/// How often [`wait_for_output_writers`] checks whether the writers have finished
+const WRITER_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Open the channel for an execution that has started running, and tell the board
+pub fn open_output_channel(execution_id: Uuid) {
+    let mut channels = OUTPUT_CHANNELS.lock().unwrap();
+    channels
+        .entry(execution_id)
+        .or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0);
+    event_bus::publish(BoardEvent::ProcessRunning {
+        execution_process_id: execution_id,
+    });
+}
+
+/// Close an execution's channel once it has stopped. Subscribers see the channel closed.
+pub fn close_output_channel(execution_id: Uuid) {
+    if OUTPUT_CHANNELS
+        .lock()
+        .unwrap()
+        .remove(&execution_id)
+        .is_some()
+    {
+        event_bus::publish(BoardEvent::ProcessStopped {
+            execution_process_id: execution_id,
+        });
+    }
+}
+
+/// Subscribe to an execution's output, or `None` if it isn't running
+pub fn subscribe_output(execution_id: Uuid) -> Option<broadcast::Receiver<()>> {
+    let channels = OUTPUT_CHANNELS.lock().unwrap();
+    channels
+        .get(&execution_id)
+        .map(broadcast::Sender::subscribe)
+}
+
+/// Announce that output was stored for an execution
+pub fn notify_output(execution_id: Uuid) {
+    let channels = OUTPUT_CHANNELS.lock().unwrap();
+    if let Some(sender) = channels.get(&execution_id) {
+        // No subscribers is the usual case
+        let _ = sender.send(());
+    }
+}
+
+/// A task storing an execution's output; it counts as writing until dropped
+#[derive(Debug)]
+pub struct OutputWriter(Uuid);
+
+/// Register a task that will store an execution's output
+pub fn register_output_writer(execution_id: Uuid) -> OutputWriter {
+    *OUTPUT_WRITERS
+        .lock()
+        .unwrap()
+        .entry(execution_id)
+        .or_default() += 1;
+    OutputWriter(execution_id)
+}
+
+impl Drop for OutputWriter {
+    fn drop(&mut self) {
+        let mut writers = OUTPUT_WRITERS.lock().unwrap();
+        if let Some(count) = writers.get_mut(&self.0) {
+            *count -= 1;
+            if *count == 0 {
+                writers.remove(&self.0);
+            }
+        }
+    }
+}
+
+/// Wait until every task storing an execution's output has finished, or `timeout` has
+/// passed; a process that left a child holding its output open never reaches EOF
+pub async fn wait_for_output_writers(execution_id: Uuid, timeout: Duration) {
+    let deadline = Instant::now() + timeout;
+    while OUTPUT_WRITERS.lock().unwrap().contains_key(&execution_id) && Instant::now() < deadline {
+        tokio::time::sleep(WRITER_POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_output_channel_lifecycle() {
+        let execution_id = Uuid::new_v4();
+        assert!(subscribe_output(execution_id).is_none());
+        notify_output(execution_id);
+
+        open_output_channel(execution_id);
+        let mut receiver = subscribe_output(execution_id).unwrap();
+        notify_output(execution_id);
+        assert!(receiver.recv().await.is_ok());
+
+        close_output_channel(execution_id);
+        assert!(matches!(
+            receiver.recv().await,
+            Err(broadcast::error::RecvError::Closed)
+        ));
+        assert!(subscribe_output(execution_id).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_output_writers() {
+        let execution_id = Uuid::new_v4();
+        let first = register_output_writer(execution_id);
+        let second = register_output_writer(execution_id);
+
+        let started = Instant::now();
+        wait_for_output_writers(execution_id, Duration::from_millis(50)).await;
+        assert!(started.elapsed() >= Duration::from_millis(50));
+
+        drop(first);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            drop(second);
+        });
+        wait_for_output_writers(execution_id, Duration::from_secs(10)).await;
+        assert!(!OUTPUT_WRITERS.lock().unwrap().contains_key(&execution_id));
+    }
+}
@@ -1,16 +1,77 @@
-use std::{
-    pin::Pin,
-    task::{Context, Poll},
-};
-
 use async_trait::async_trait;
-use tokio::io::AsyncRead;
+use bytes::Bytes;
+use futures_util::{
+    stream::{SplitSink, SplitStream},
+    SinkExt, StreamExt,
+};
+use tokio::{
+    net::TcpStream,
+    sync::{mpsc, watch},
+};
+use tokio_tungstenite::{
+    tungstenite::{client::IntoClientRequest, http::HeaderValue, Message},
+    MaybeTlsStream, WebSocketStream,
+};
+use tokio_util::io::StreamReader;
 
 use crate::command_runner::{
+    worker_protocol::{
+        WorkerEvent, WorkerRequest, STDERR_FRAME, STDOUT_FRAME, WORKER_PATH, WORKER_TOKEN_VAR,
+    },
     CommandError, CommandExecutor, CommandExitStatus, CommandRunnerArgs, CommandStream,
     ProcessHandle,
 };
 
+type WorkerSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Output received for one of the command's streams
+type OutputSender = mpsc::UnboundedSender<std::io::Result<Bytes>>;
+type OutputReceiver = mpsc::UnboundedReceiver<std::io::Result<Bytes>>;
+
+/// How the command ended, once it has, or why the worker couldn't say
+type ExitReport = Option<Result<CommandExitStatus, String>>;
+
+fn io_error(error: impl std::fmt::Display) -> std::io::Error {
+    std::io::Error::other(error.to_string())
+}
+
+/// A worker machine running `cloud-runner` that a project's commands are dispatched to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Worker {
+    pub url: String,
+    /// This machine's directory holding the worktrees, and where the worker sees it, when
+    /// the paths differ
+    pub path_map: Option<(String, String)>,
+}
+
+impl Worker {
+    /// The request with its working directory as the worker sees it
+    fn map_request(&self, request: &CommandRunnerArgs) -> CommandRunnerArgs {
+        let mut mapped = request.clone();
+        if let (Some((local_root, remote_root)), Some(dir)) = (&self.path_map, &request.working_dir)
+        {
+            if let Ok(rest) = std::path::Path::new(dir).strip_prefix(local_root) {
+                let rest = rest.to_string_lossy().replace('\\', "/");
+                mapped.working_dir = Some(if rest.is_empty() {
+                    remote_root.clone()
+                } else {
+                    format!("{}/{}", remote_root.trim_end_matches('/'), rest)
+                });
+            }
+        }
+        mapped
+    }
+
+    pub async fn start(
+        &self,
+        request: &CommandRunnerArgs,
+    ) -> Result<Box<dyn ProcessHandle>, CommandError> {
+        RemoteCommandExecutor::with_url(self.url.trim_end_matches('/'))
+            .start(&self.map_request(request))
+            .await
+    }
+}
+
 pub struct RemoteCommandExecutor {
     cloud_server_url: String,
 }
@@ -27,6 +88,41 @@ impl RemoteCommandExecutor {
             .unwrap_or_else(|_| "http://localhost:8000".to_string());
         Self { cloud_server_url }
     }
+
+    pub fn with_url(url: &str) -> Self {
+        Self {
+            cloud_server_url: url.to_string(),
+        }
+    }
+
+    /// The worker's WebSocket address, from its HTTP(S) URL
+    fn socket_url(&self) -> String {
+        let url = self.cloud_server_url.trim_end_matches('/');
+        let url = if let Some(rest) = url.strip_prefix("https://") {
+            format!("wss://{}", rest)
+        } else if let Some(rest) = url.strip_prefix("http://") {
+            format!("ws://{}", rest)
+        } else {
+            url.to_string()
+        };
+        format!("{}{}", url, WORKER_PATH)
+    }
+
+    async fn connect(&self) -> Result<WorkerSocket, CommandError> {
+        let mut request = self
+            .socket_url()
+            .into_client_request()
+            .map_err(|e| CommandError::IoError { error: io_error(e) })?;
+        if let Ok(token) = std::env::var(WORKER_TOKEN_VAR) {
+            let value = HeaderValue::from_str(&format!("Bearer {}", token))
+                .map_err(|e| CommandError::IoError { error: io_error(e) })?;
+            request.headers_mut().insert("authorization", value);
+        }
+        let (socket, _) = tokio_tungstenite::connect_async(request)
+            .await
+            .map_err(|e| CommandError::IoError { error: io_error(e) })?;
+        Ok(socket)
+    }
 }
 
 #[async_trait]
@@ -35,349 +131,195 @@ impl CommandExecutor for RemoteCommandExecutor {
         &self,
         request: &CommandRunnerArgs,
     ) -> Result<Box<dyn ProcessHandle>, CommandError> {
-        let client = reqwest::Client::new();
-        let response = client
-            .post(format!("{}/commands", self.cloud_server_url))
-            .json(request)
-            .send()
-            .await
-            .map_err(|e| CommandError::IoError {
-                error: std::io::Error::other(e),
-            })?;
-
-        let result: serde_json::Value =
-            response.json().await.map_err(|e| CommandError::IoError {
-                error: std::io::Error::other(e),
-            })?;
-
-        let process_id =
-            result["data"]["process_id"]
-                .as_str()
+        let (mut sink, mut incoming) = self.connect().await?.split();
+        send_request(
+            &mut sink,
+            &WorkerRequest::Start {
+                args: request.clone(),
+            },
+        )
+        .await
+        .map_err(|error| CommandError::IoError { error })?;
+
+        let process_id = loop {
+            let message = incoming
+                .next()
+                .await
                 .ok_or_else(|| CommandError::IoError {
-                    error: std::io::Error::other(format!(
-                        "Missing process_id in response: {}",
-                        result
-                    )),
-                })?;
-
-        Ok(Box::new(RemoteProcessHandle::new(
-            process_id.to_string(),
-            self.cloud_server_url.clone(),
-        )))
+                    error: io_error("worker closed the connection before starting the command"),
+                })?
+                .map_err(|e| CommandError::IoError { error: io_error(e) })?;
+            let Message::Text(text) = message else {
+                continue;
+            };
+            match serde_json::from_str(&text)
+                .map_err(|e| CommandError::IoError { error: io_error(e) })?
+            {
+                WorkerEvent::Started { process_id } => break process_id,
+                WorkerEvent::Error { message } => {
+                    return Err(CommandError::IoError {
+                        error: io_error(message),
+                    })
+                }
+                WorkerEvent::Exited { .. } => {
+                    return Err(CommandError::IoError {
+                        error: io_error("worker reported an exit before starting the command"),
+                    })
+                }
+            }
+        };
+
+        let (stdout_tx, stdout_rx) = mpsc::unbounded_channel();
+        let (stderr_tx, stderr_rx) = mpsc::unbounded_channel();
+        let (exit_tx, exit_rx) = watch::channel(None);
+        tokio::spawn(read_events(
+            incoming,
+            stdout_tx,
+            stderr_tx,
+            exit_tx,
+            process_id.clone(),
+        ));
+
+        Ok(Box::new(RemoteProcessHandle {
+            process_id,
+            sink,
+            stdout: Some(stdout_rx),
+            stderr: Some(stderr_rx),
+            exit: exit_rx,
+        }))
     }
 }
 
+async fn send_request(
+    sink: &mut SplitSink<WorkerSocket, Message>,
+    request: &WorkerRequest,
+) -> std::io::Result<()> {
+    let text = serde_json::to_string(request).map_err(io_error)?;
+    sink.send(Message::Text(text)).await.map_err(io_error)
+}
+
+/// Route the worker's frames to the command's output streams until it reports the exit.
+/// The streams end when this returns.
+async fn read_events(
+    mut incoming: SplitStream<WorkerSocket>,
+    stdout: OutputSender,
+    stderr: OutputSender,
+    exit: watch::Sender<ExitReport>,
+    process_id: String,
+) {
+    let report = loop {
+        match incoming.next().await {
+            Some(Ok(Message::Binary(frame))) => {
+                let Some((&tag, data)) = frame.split_first() else {
+                    continue;
+                };
+                let stream = match tag {
+                    STDOUT_FRAME => &stdout,
+                    STDERR_FRAME => &stderr,
+                    _ => continue,
+                };
+                // The reader may have been dropped, but the exit still matters
+                let _ = stream.send(Ok(Bytes::copy_from_slice(data)));
+            }
+            Some(Ok(Message::Text(text))) => match serde_json::from_str(&text) {
+                Ok(WorkerEvent::Exited { code, success }) => {
+                    break Ok(CommandExitStatus::from_remote(
+                        code,
+                        success,
+                        Some(process_id),
+                        None,
+                    ))
+                }
+                Ok(WorkerEvent::Error { message }) => break Err(message),
+                Ok(WorkerEvent::Started { .. }) => {}
+                Err(e) => tracing::warn!("Unreadable message from worker: {}", e),
+            },
+            Some(Ok(_)) => {}
+            Some(Err(e)) => break Err(e.to_string()),
+            None => break Err("worker closed the connection before the command exited".into()),
+        }
+    };
+    let _ = exit.send(Some(report));
+}
+
 pub struct RemoteProcessHandle {
     process_id: String,
-    cloud_server_url: String,
+    sink: SplitSink<WorkerSocket, Message>,
+    stdout: Option<OutputReceiver>,
+    stderr: Option<OutputReceiver>,
+    exit: watch::Receiver<ExitReport>,
 }
 
 impl RemoteProcessHandle {
-    pub fn new(process_id: String, cloud_server_url: String) -> Self {
-        Self {
-            process_id,
-            cloud_server_url,
+    fn exit_status(report: &ExitReport) -> Result<Option<CommandExitStatus>, CommandError> {
+        match report {
+            None => Ok(None),
+            Some(Ok(status)) => Ok(Some(status.clone())),
+            Some(Err(message)) => Err(CommandError::StatusCheckFailed {
+                error: io_error(message),
+            }),
         }
     }
 }
 
+/// The command's output as it arrives from the worker
+fn output_reader(receiver: OutputReceiver) -> Box<dyn tokio::io::AsyncRead + Unpin + Send> {
+    let chunks = futures_util::stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|chunk| (chunk, receiver))
+    });
+    Box::new(StreamReader::new(Box::pin(chunks)))
+}
+
 #[async_trait]
 impl ProcessHandle for RemoteProcessHandle {
     async fn try_wait(&mut self) -> Result<Option<CommandExitStatus>, CommandError> {
-        // Make HTTP request to get status from cloud server
-        let client = reqwest::Client::new();
-        let response = client
-            .get(format!(
-                "{}/commands/{}/status",
-                self.cloud_server_url, self.process_id
-            ))
-            .send()
-            .await
-            .map_err(|e| CommandError::StatusCheckFailed {
-                error: std::io::Error::other(e),
-            })?;
-
-        if !response.status().is_success() {
-            if response.status() == reqwest::StatusCode::NOT_FOUND {
-                return Err(CommandError::StatusCheckFailed {
-                    error: std::io::Error::new(std::io::ErrorKind::NotFound, "Process not found"),
-                });
-            } else {
-                return Err(CommandError::StatusCheckFailed {
-                    error: std::io::Error::other("Status check failed"),
-                });
-            }
-        }
-
-        let result: serde_json::Value =
-            response
-                .json()
-                .await
-                .map_err(|e| CommandError::StatusCheckFailed {
-                    error: std::io::Error::other(e),
-                })?;
-
-        let data = result["data"]
-            .as_object()
-            .ok_or_else(|| CommandError::StatusCheckFailed {
-                error: std::io::Error::other("Invalid response format"),
-            })?;
-
-        let running = data["running"].as_bool().unwrap_or(false);
-
-        if running {
-            Ok(None) // Still running
-        } else {
-            // Process completed, extract exit status
-            let exit_code = data["exit_code"].as_i64().map(|c| c as i32);
-            let success = data["success"].as_bool().unwrap_or(false);
-
-            Ok(Some(CommandExitStatus::from_remote(
-                exit_code,
-                success,
-                Some(self.process_id.clone()),
-                None,
-            )))
-        }
+        Self::exit_status(&self.exit.borrow())
     }
 
     async fn wait(&mut self) -> Result<CommandExitStatus, CommandError> {
-        // Poll the status endpoint until process completes
-        loop {
-            let client = reqwest::Client::new();
-            let response = client
-                .get(format!(
-                    "{}/commands/{}/status",
-                    self.cloud_server_url, self.process_id
-                ))
-                .send()
-                .await
-                .map_err(|e| CommandError::StatusCheckFailed {
-                    error: std::io::Error::other(e),
-                })?;
-
-            if !response.status().is_success() {
-                if response.status() == reqwest::StatusCode::NOT_FOUND {
-                    return Err(CommandError::StatusCheckFailed {
-                        error: std::io::Error::new(
-                            std::io::ErrorKind::NotFound,
-                            "Process not found",
-                        ),
-                    });
-                } else {
-                    return Err(CommandError::StatusCheckFailed {
-                        error: std::io::Error::other("Status check failed"),
-                    });
-                }
-            }
-
-            let result: serde_json::Value =
-                response
-                    .json()
-                    .await
-                    .map_err(|e| CommandError::StatusCheckFailed {
-                        error: std::io::Error::other(e),
-                    })?;
-
-            let data =
-                result["data"]
-                    .as_object()
-                    .ok_or_else(|| CommandError::StatusCheckFailed {
-                        error: std::io::Error::other("Invalid response format"),
-                    })?;
-
-            let running = data["running"].as_bool().unwrap_or(false);
-
-            if !running {
-                // Process completed, extract exit status and return
-                let exit_code = data["exit_code"].as_i64().map(|c| c as i32);
-                let success = data["success"].as_bool().unwrap_or(false);
-
-                return Ok(CommandExitStatus::from_remote(
-                    exit_code,
-                    success,
-                    Some(self.process_id.clone()),
-                    None,
-                ));
-            }
-
-            // Wait a bit before polling again
-            tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
-        }
+        let report = self
+            .exit
+            .wait_for(Option::is_some)
+            .await
+            .map_err(|e| CommandError::StatusCheckFailed { error: io_error(e) })?;
+        Self::exit_status(&report)?.ok_or(CommandError::ProcessNotStarted)
     }
 
     async fn kill(&mut self) -> Result<(), CommandError> {
-        let client = reqwest::Client::new();
-        let response = client
-            .delete(format!(
-                "{}/commands/{}",
-                self.cloud_server_url, self.process_id
-            ))
-            .send()
-            .await
-            .map_err(|e| CommandError::KillFailed {
-                error: std::io::Error::other(e),
-            })?;
-
-        if !response.status().is_success() {
-            if response.status() == reqwest::StatusCode::NOT_FOUND {
-                // Process not found, might have already finished - treat as success
-                return Ok(());
-            }
-
-            return Err(CommandError::KillFailed {
-                error: std::io::Error::other(format!(
-                    "Remote kill failed with status: {}",
-                    response.status()
-                )),
-            });
+        if self.exit.borrow().is_some() {
+            return Ok(());
         }
-
-        // Check if server indicates process was already completed
-        if let Ok(result) = response.json::<serde_json::Value>().await {
-            if let Some(data) = result.get("data") {
-                if let Some(message) = data.as_str() {
-                    tracing::info!("Kill result: {}", message);
-                }
-            }
-        }
-
-        Ok(())
+        send_request(&mut self.sink, &WorkerRequest::Kill)
+            .await
+            .map_err(|error| CommandError::KillFailed { error })
     }
 
     async fn stream(&mut self) -> Result<CommandStream, CommandError> {
-        // Create HTTP streams for stdout and stderr concurrently
-        let stdout_url = format!(
-            "{}/commands/{}/stdout",
-            self.cloud_server_url, self.process_id
-        );
-        let stderr_url = format!(
-            "{}/commands/{}/stderr",
-            self.cloud_server_url, self.process_id
-        );
-
-        // Create both streams concurrently using tokio::try_join!
-        let (stdout_result, stderr_result) =
-            tokio::try_join!(HTTPStream::new(stdout_url), HTTPStream::new(stderr_url))?;
-
-        let stdout_stream: Option<Box<dyn AsyncRead + Unpin + Send>> =
-            Some(Box::new(stdout_result) as Box<dyn AsyncRead + Unpin + Send>);
-        let stderr_stream: Option<Box<dyn AsyncRead + Unpin + Send>> =
-            Some(Box::new(stderr_result) as Box<dyn AsyncRead + Unpin + Send>);
-
         Ok(CommandStream {
-            stdout: stdout_stream,
-            stderr: stderr_stream,
+            stdout: self.stdout.take().map(output_reader),
+            stderr: self.stderr.take().map(output_reader),
         })
     }
 
-    fn process_id(&self) -> String {
-        self.process_id.clone()
+    async fn write_stdin(&mut self, data: &str) -> Result<(), CommandError> {
+        send_request(
+            &mut self.sink,
+            &WorkerRequest::Stdin {
+                data: data.to_string(),
+            },
+        )
+        .await
+        .map_err(|error| CommandError::IoError { error })
     }
-}
-
-/// HTTP-based AsyncRead wrapper for true streaming
-pub struct HTTPStream {
-    stream: Pin<Box<dyn futures_util::Stream<Item = Result<Vec<u8>, reqwest::Error>> + Send>>,
-    current_chunk: Vec<u8>,
-    chunk_position: usize,
-    finished: bool,
-}
 
-// HTTPStream needs to be Unpin to work with the AsyncRead trait bounds
-impl Unpin for HTTPStream {}
-
-impl HTTPStream {
-    pub async fn new(url: String) -> Result<Self, CommandError> {
-        let client = reqwest::Client::new();
-        let response = client
-            .get(&url)
-            .send()
+    async fn close_stdin(&mut self) -> Result<(), CommandError> {
+        send_request(&mut self.sink, &WorkerRequest::CloseStdin)
             .await
-            .map_err(|e| CommandError::IoError {
-                error: std::io::Error::other(e),
-            })?;
-
-        if !response.status().is_success() {
-            return Err(CommandError::IoError {
-                error: std::io::Error::other(format!(
-                    "HTTP request failed with status: {}",
-                    response.status()
-                )),
-            });
-        }
-
-        // Use chunk() method to create a stream
-        Ok(Self {
-            stream: Box::pin(futures_util::stream::unfold(
-                response,
-                |mut resp| async move {
-                    match resp.chunk().await {
-                        Ok(Some(chunk)) => Some((Ok(chunk.to_vec()), resp)),
-                        Ok(None) => None,
-                        Err(e) => Some((Err(e), resp)),
-                    }
-                },
-            )),
-            current_chunk: Vec::new(),
-            chunk_position: 0,
-            finished: false,
-        })
+            .map_err(|error| CommandError::IoError { error })
     }
-}
-
-impl AsyncRead for HTTPStream {
-    fn poll_read(
-        mut self: Pin<&mut Self>,
-        cx: &mut Context<'_>,
-        buf: &mut tokio::io::ReadBuf<'_>,
-    ) -> Poll<std::io::Result<()>> {
-        if self.finished {
-            return Poll::Ready(Ok(()));
-        }
 
-        // First, try to read from current chunk if available
-        if self.chunk_position < self.current_chunk.len() {
-            let remaining_in_chunk = self.current_chunk.len() - self.chunk_position;
-            let to_read = std::cmp::min(remaining_in_chunk, buf.remaining());
-
-            let chunk_data =
-                &self.current_chunk[self.chunk_position..self.chunk_position + to_read];
-            buf.put_slice(chunk_data);
-            self.chunk_position += to_read;
-
-            return Poll::Ready(Ok(()));
-        }
-
-        // Current chunk is exhausted, try to get the next chunk
-        match self.stream.as_mut().poll_next(cx) {
-            Poll::Ready(Some(Ok(chunk))) => {
-                if chunk.is_empty() {
-                    // Empty chunk, mark as finished
-                    self.finished = true;
-                    Poll::Ready(Ok(()))
-                } else {
-                    // New chunk available
-                    self.current_chunk = chunk;
-                    self.chunk_position = 0;
-
-                    // Read from the new chunk
-                    let to_read = std::cmp::min(self.current_chunk.len(), buf.remaining());
-                    let chunk_data = &self.current_chunk[..to_read];
-                    buf.put_slice(chunk_data);
-                    self.chunk_position = to_read;
-
-                    Poll::Ready(Ok(()))
-                }
-            }
-            Poll::Ready(Some(Err(e))) => Poll::Ready(Err(std::io::Error::other(e))),
-            Poll::Ready(None) => {
-                // Stream ended
-                self.finished = true;
-                Poll::Ready(Ok(()))
-            }
-            Poll::Pending => Poll::Pending,
-        }
+    fn process_id(&self) -> String {
+        self.process_id.clone()
     }
 }
 
@@ -400,3 +342,54 @@ impl CommandExitStatus {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worker_sees_working_dir_under_its_root() {
+        let worker = Worker {
+            url: "http://build-box:8000".to_string(),
+            path_map: Some((
+                "/home/dev/.vibe-kanban/worktrees".to_string(),
+                "/srv/worktrees/".to_string(),
+            )),
+        };
+        let request = |dir: &str| CommandRunnerArgs {
+            command: "bash".to_string(),
+            args: Vec::new(),
+            working_dir: Some(dir.to_string()),
+            env_vars: Vec::new(),
+            stdin: None,
+            keep_stdin_open: false,
+            limits: Default::default(),
+        };
+        assert_eq!(
+            worker
+                .map_request(&request("/home/dev/.vibe-kanban/worktrees/vk-1234-fix"))
+                .working_dir
+                .as_deref(),
+            Some("/srv/worktrees/vk-1234-fix")
+        );
+        assert_eq!(
+            worker
+                .map_request(&request("/elsewhere/repo"))
+                .working_dir
+                .as_deref(),
+            Some("/elsewhere/repo")
+        );
+    }
+
+    #[test]
+    fn test_worker_socket_url_follows_its_scheme() {
+        assert_eq!(
+            RemoteCommandExecutor::with_url("https://build-box/").socket_url(),
+            "wss://build-box/worker"
+        );
+        assert_eq!(
+            RemoteCommandExecutor::with_url("http://build-box:8000").socket_url(),
+            "ws://build-box:8000/worker"
+        );
+    }
+}
@@ -0,0 +1,148 @@
+//! Confining a command to the paths it's meant to write, with bubblewrap
+
+use std::path::{Path, PathBuf};
+
+use crate::command_runner::{CommandError, CommandRunnerArgs};
+
+/// Under the home directory, which is otherwise hidden: what the agent CLIs keep their
+/// logins, sessions and caches in, left writable
+const AGENT_STATE_PATHS: [&str; 10] = [
+    ".claude",
+    ".claude.json",
+    ".codex",
+    ".gemini",
+    ".config",
+    ".cache",
+    ".npm",
+    ".local/share",
+    ".local/state",
+    ".aider",
+];
+
+/// Under the home directory: where node, and the tools agents call, are usually installed
+/// from, left readable
+const HOME_TOOL_PATHS: [&str; 10] = [
+    ".local/bin",
+    ".nvm",
+    ".volta",
+    ".bun",
+    ".deno",
+    ".cargo",
+    ".rustup",
+    ".pyenv",
+    ".asdf",
+    ".gitconfig",
+];
+
+/// A command is run with the filesystem read-only and the home directory hidden, except
+/// for the agents' own state, `writable` and a fresh `/tmp`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sandbox {
+    pub writable: Vec<PathBuf>,
+    pub deny_network: bool,
+}
+
+impl Sandbox {
+    /// Rewrite the request to run its command inside the sandbox
+    pub fn wrap(&self, request: &mut CommandRunnerArgs) -> Result<(), CommandError> {
+        if !cfg!(target_os = "linux") {
+            return Err(CommandError::SandboxUnavailable {
+                reason: "sandboxing is only supported on Linux".to_string(),
+            });
+        }
+        if crate::services::executor_preflight::find_on_path("bwrap").is_none() {
+            return Err(CommandError::SandboxUnavailable {
+                reason: "bubblewrap (bwrap) isn't installed".to_string(),
+            });
+        }
+
+        let home = dirs::home_dir();
+        let mut args = self.bwrap_args(home.as_deref(), request.working_dir.as_deref());
+        args.push(std::mem::take(&mut request.command));
+        args.append(&mut request.args);
+        request.command = "bwrap".to_string();
+        request.args = args;
+        Ok(())
+    }
+
+    /// bubblewrap's arguments up to and including the `--` before the command. Later
+    /// mounts cover earlier ones, so the writable paths come last.
+    fn bwrap_args(&self, home: Option<&Path>, working_dir: Option<&str>) -> Vec<String> {
+        let mut args: Vec<String> = [
+            "--die-with-parent",
+            "--ro-bind",
+            "/",
+            "/",
+            "--dev",
+            "/dev",
+            "--proc",
+            "/proc",
+            "--tmpfs",
+            "/tmp",
+        ]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+
+        if let Some(home) = home {
+            args.extend(["--tmpfs".to_string(), home.to_string_lossy().to_string()]);
+        }
+        let mut mount = |flag: &str, path: &Path| {
+            let path = path.to_string_lossy().to_string();
+            args.extend([flag.to_string(), path.clone(), path]);
+        };
+        if let Some(home) = home {
+            for path in HOME_TOOL_PATHS {
+                mount("--ro-bind-try", &home.join(path));
+            }
+            for path in AGENT_STATE_PATHS {
+                mount("--bind-try", &home.join(path));
+            }
+        }
+        for path in &self.writable {
+            mount("--bind", path);
+        }
+
+        if self.deny_network {
+            args.push("--unshare-net".to_string());
+        }
+        if let Some(dir) = working_dir {
+            args.extend(["--chdir".to_string(), dir.to_string()]);
+        }
+        args.push("--".to_string());
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bwrap_args_bind_writable_paths_last() {
+        let sandbox = Sandbox {
+            writable: vec![PathBuf::from("/work/tree"), PathBuf::from("/repo/.git")],
+            deny_network: true,
+        };
+        let args = sandbox.bwrap_args(Some(Path::new("/home/dev")), Some("/work/tree"));
+        let args = args.join(" ");
+
+        assert!(args.starts_with("--die-with-parent --ro-bind / / --dev /dev --proc /proc --tmpfs /tmp --tmpfs /home/dev --ro-bind-try /home/dev/.local/bin /home/dev/.local/bin"));
+        assert!(args.contains("--bind-try /home/dev/.claude /home/dev/.claude"));
+        assert!(args.ends_with(
+            "--bind /work/tree /work/tree --bind /repo/.git /repo/.git --unshare-net --chdir /work/tree --"
+        ));
+    }
+
+    #[test]
+    fn test_bwrap_args_keep_network_unless_denied() {
+        let sandbox = Sandbox {
+            writable: vec![PathBuf::from("/work/tree")],
+            deny_network: false,
+        };
+        let args = sandbox.bwrap_args(None, None);
+        assert!(!args.contains(&"--unshare-net".to_string()));
+        assert!(!args.contains(&"--chdir".to_string()));
+        assert_eq!(args.last().map(String::as_str), Some("--"));
+    }
+}
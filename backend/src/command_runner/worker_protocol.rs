@@ -0,0 +1,38 @@
+//! What the server and a `cloud-runner` worker say to each other over the worker's
+//! WebSocket. Each connection runs one command: the server sends [`WorkerRequest::Start`],
+//! the worker answers [`WorkerEvent::Started`], streams the command's output as binary
+//! frames and ends with [`WorkerEvent::Exited`]. Closing the connection kills the command.
+
+use serde::{Deserialize, Serialize};
+
+use crate::command_runner::CommandRunnerArgs;
+
+/// Where the worker accepts connections
+pub const WORKER_PATH: &str = "/worker";
+
+/// Sent as a bearer token when connecting, and required by the runner
+pub const WORKER_TOKEN_VAR: &str = "VIBE_KANBAN_WORKER_TOKEN";
+
+/// First byte of a binary frame carrying the command's stdout
+pub const STDOUT_FRAME: u8 = 1;
+/// First byte of a binary frame carrying the command's stderr
+pub const STDERR_FRAME: u8 = 2;
+
+/// Sent by the server, as JSON text frames
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WorkerRequest {
+    Start { args: CommandRunnerArgs },
+    Stdin { data: String },
+    CloseStdin,
+    Kill,
+}
+
+/// Sent by the worker, as JSON text frames
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WorkerEvent {
+    Started { process_id: String },
+    Exited { code: Option<i32>, success: bool },
+    Error { message: String },
+}
@@ -0,0 +1,401 @@
+//! The app database: SQLite, or PostgreSQL when `database.url` is configured.
+//!
+//! Statements are written once, in SQLite's dialect, with this module's [`query!`],
+//! [`query_as!`] and [`query_scalar!`]. On SQLite they are sqlx's macros of the same names,
+//! checked against the schema at build time. On PostgreSQL the same statement runs as
+//! [`postgres::translate`] rewrites it, with its rows decoded by the output type's
+//! `FromRow`, by column name.
+//!
+//! Enums kept in text columns are declared with `#[sqlx(type_name = "text")]`, which SQLite
+//! ignores and PostgreSQL needs to read and write them as `TEXT`.
+
+pub mod postgres;
+
+use std::future::Future;
+
+use futures_util::future::BoxFuture;
+use sqlx::{
+    postgres::{PgArguments, PgRow},
+    query::{Map, QueryScalar},
+    sqlite::{SqliteArguments, SqliteRow},
+    Decode, Execute, Executor, FromRow, PgPool, Postgres, Row, Sqlite, SqlitePool, Transaction,
+    Type,
+};
+
+#[derive(Debug, Clone)]
+pub enum DbPool {
+    Sqlite(SqlitePool),
+    Postgres(PgPool),
+}
+
+impl DbPool {
+    pub async fn begin(&self) -> Result<DbTransaction, sqlx::Error> {
+        Ok(match self {
+            DbPool::Sqlite(pool) => DbTransaction::Sqlite(pool.begin().await?),
+            DbPool::Postgres(pool) => DbTransaction::Postgres(pool.begin().await?),
+        })
+    }
+
+    /// Begin a transaction that holds the write lock from the start, so what it reads can't
+    /// change before it writes: SQLite's lock on the whole database, or PostgreSQL's on
+    /// `table`
+    pub async fn begin_locked(&self, table: &'static str) -> Result<DbTransaction, sqlx::Error> {
+        match self {
+            DbPool::Sqlite(pool) => Ok(DbTransaction::Sqlite(
+                pool.begin_with("BEGIN IMMEDIATE").await?,
+            )),
+            DbPool::Postgres(pool) => {
+                let mut tx = pool.begin().await?;
+                sqlx::query(&format!("LOCK TABLE {} IN SHARE ROW EXCLUSIVE MODE", table))
+                    .execute(&mut *tx)
+                    .await?;
+                Ok(DbTransaction::Postgres(tx))
+            }
+        }
+    }
+}
+
+pub enum DbTransaction {
+    Sqlite(Transaction<'static, Sqlite>),
+    Postgres(Transaction<'static, Postgres>),
+}
+
+impl DbTransaction {
+    pub async fn commit(self) -> Result<(), sqlx::Error> {
+        match self {
+            DbTransaction::Sqlite(tx) => tx.commit().await,
+            DbTransaction::Postgres(tx) => tx.commit().await,
+        }
+    }
+}
+
+/// Where a statement runs: `pool` or `&mut tx`
+pub enum DbExecutor<'c> {
+    Pool(&'c DbPool),
+    Transaction(&'c mut DbTransaction),
+}
+
+impl<'c> From<&'c DbPool> for DbExecutor<'c> {
+    fn from(pool: &'c DbPool) -> Self {
+        DbExecutor::Pool(pool)
+    }
+}
+
+impl<'c> From<&'c mut DbTransaction> for DbExecutor<'c> {
+    fn from(tx: &'c mut DbTransaction) -> Self {
+        DbExecutor::Transaction(tx)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct QueryResult {
+    rows_affected: u64,
+}
+
+impl QueryResult {
+    pub fn rows_affected(&self) -> u64 {
+        self.rows_affected
+    }
+}
+
+pub type PgQuery<'q> = sqlx::query::Query<'q, Postgres, PgArguments>;
+
+/// A statement run for what it changes, built by [`query!`]
+pub struct Statement<S, P> {
+    sqlite: S,
+    postgres: P,
+}
+
+impl<'q, S, SQ, P> Statement<S, P>
+where
+    S: FnOnce() -> SQ + Send,
+    SQ: Execute<'q, Sqlite> + 'q,
+    P: FnOnce() -> PgQuery<'q> + Send,
+{
+    pub fn new(sqlite: S, postgres: P) -> Self {
+        Self { sqlite, postgres }
+    }
+
+    pub fn execute<'c, 'f>(
+        self,
+        executor: impl Into<DbExecutor<'c>>,
+    ) -> BoxFuture<'f, Result<QueryResult, sqlx::Error>>
+    where
+        'q: 'f,
+        'c: 'f,
+        Self: 'f,
+    {
+        let executor = executor.into();
+        Box::pin(async move {
+            let rows_affected = match executor {
+                DbExecutor::Pool(DbPool::Sqlite(pool)) => {
+                    sqlx::Executor::execute(pool, (self.sqlite)())
+                        .await?
+                        .rows_affected()
+                }
+                DbExecutor::Transaction(DbTransaction::Sqlite(tx)) => {
+                    sqlx::Executor::execute(&mut **tx, (self.sqlite)())
+                        .await?
+                        .rows_affected()
+                }
+                DbExecutor::Pool(DbPool::Postgres(pool)) => {
+                    (self.postgres)().execute(pool).await?.rows_affected()
+                }
+                DbExecutor::Transaction(DbTransaction::Postgres(tx)) => {
+                    (self.postgres)().execute(&mut **tx).await?.rows_affected()
+                }
+            };
+            Ok(QueryResult { rows_affected })
+        })
+    }
+}
+
+/// What sqlx's `query_as!` and `query_scalar!` build, fetched the same way
+pub trait SqliteFetch<'q>: Send {
+    type Output: Send + Unpin;
+
+    fn fetch_all<'e, 'c: 'e, E>(
+        self,
+        executor: E,
+    ) -> impl Future<Output = Result<Vec<Self::Output>, sqlx::Error>> + Send + 'e
+    where
+        'q: 'e,
+        Self: 'e,
+        Self::Output: 'e,
+        E: 'e + Executor<'c, Database = Sqlite>;
+
+    fn fetch_optional<'e, 'c: 'e, E>(
+        self,
+        executor: E,
+    ) -> impl Future<Output = Result<Option<Self::Output>, sqlx::Error>> + Send + 'e
+    where
+        'q: 'e,
+        Self: 'e,
+        Self::Output: 'e,
+        E: 'e + Executor<'c, Database = Sqlite>;
+}
+
+impl<'q, F, O> SqliteFetch<'q> for Map<'q, Sqlite, F, SqliteArguments<'q>>
+where
+    F: FnMut(SqliteRow) -> Result<O, sqlx::Error> + Send,
+    O: Send + Unpin,
+{
+    type Output = O;
+
+    fn fetch_all<'e, 'c: 'e, E>(
+        self,
+        executor: E,
+    ) -> impl Future<Output = Result<Vec<O>, sqlx::Error>> + Send + 'e
+    where
+        'q: 'e,
+        Self: 'e,
+        O: 'e,
+        E: 'e + Executor<'c, Database = Sqlite>,
+    {
+        Map::fetch_all(self, executor)
+    }
+
+    fn fetch_optional<'e, 'c: 'e, E>(
+        self,
+        executor: E,
+    ) -> impl Future<Output = Result<Option<O>, sqlx::Error>> + Send + 'e
+    where
+        'q: 'e,
+        Self: 'e,
+        O: 'e,
+        E: 'e + Executor<'c, Database = Sqlite>,
+    {
+        Map::fetch_optional(self, executor)
+    }
+}
+
+impl<'q, O> SqliteFetch<'q> for QueryScalar<'q, Sqlite, O, SqliteArguments<'q>>
+where
+    O: Send + Unpin,
+    (O,): for<'r> FromRow<'r, SqliteRow>,
+{
+    type Output = O;
+
+    fn fetch_all<'e, 'c: 'e, E>(
+        self,
+        executor: E,
+    ) -> impl Future<Output = Result<Vec<O>, sqlx::Error>> + Send + 'e
+    where
+        'q: 'e,
+        Self: 'e,
+        O: 'e,
+        E: 'e + Executor<'c, Database = Sqlite>,
+    {
+        QueryScalar::fetch_all(self, executor)
+    }
+
+    fn fetch_optional<'e, 'c: 'e, E>(
+        self,
+        executor: E,
+    ) -> impl Future<Output = Result<Option<O>, sqlx::Error>> + Send + 'e
+    where
+        'q: 'e,
+        Self: 'e,
+        O: 'e,
+        E: 'e + Executor<'c, Database = Sqlite>,
+    {
+        QueryScalar::fetch_optional(self, executor)
+    }
+}
+
+/// A statement run for its rows, built by [`query_as!`] or [`query_scalar!`]
+pub struct Fetch<S, P, D> {
+    sqlite: S,
+    postgres: P,
+    decode: D,
+}
+
+impl<'q, S, SQ, O, P, D> Fetch<S, P, D>
+where
+    S: FnOnce() -> SQ + Send,
+    SQ: SqliteFetch<'q, Output = O> + 'q,
+    O: Send + Unpin + 'q,
+    P: FnOnce() -> PgQuery<'q> + Send,
+    D: Fn(&PgRow) -> Result<O, sqlx::Error> + Send,
+{
+    pub fn new(sqlite: S, postgres: P, decode: D) -> Self {
+        Self {
+            sqlite,
+            postgres,
+            decode,
+        }
+    }
+
+    pub fn fetch_all<'c, 'f>(
+        self,
+        executor: impl Into<DbExecutor<'c>>,
+    ) -> BoxFuture<'f, Result<Vec<O>, sqlx::Error>>
+    where
+        'q: 'f,
+        'c: 'f,
+        Self: 'f,
+    {
+        let executor = executor.into();
+        let decode = self.decode;
+        Box::pin(async move {
+            match executor {
+                DbExecutor::Pool(DbPool::Sqlite(pool)) => (self.sqlite)().fetch_all(pool).await,
+                DbExecutor::Transaction(DbTransaction::Sqlite(tx)) => {
+                    (self.sqlite)().fetch_all(&mut **tx).await
+                }
+                DbExecutor::Pool(DbPool::Postgres(pool)) => {
+                    (self.postgres)()
+                        .try_map(move |row: PgRow| decode(&row))
+                        .fetch_all(pool)
+                        .await
+                }
+                DbExecutor::Transaction(DbTransaction::Postgres(tx)) => {
+                    (self.postgres)()
+                        .try_map(move |row: PgRow| decode(&row))
+                        .fetch_all(&mut **tx)
+                        .await
+                }
+            }
+        })
+    }
+
+    pub fn fetch_optional<'c, 'f>(
+        self,
+        executor: impl Into<DbExecutor<'c>>,
+    ) -> BoxFuture<'f, Result<Option<O>, sqlx::Error>>
+    where
+        'q: 'f,
+        'c: 'f,
+        Self: 'f,
+    {
+        let executor = executor.into();
+        let decode = self.decode;
+        Box::pin(async move {
+            match executor {
+                DbExecutor::Pool(DbPool::Sqlite(pool)) => {
+                    (self.sqlite)().fetch_optional(pool).await
+                }
+                DbExecutor::Transaction(DbTransaction::Sqlite(tx)) => {
+                    (self.sqlite)().fetch_optional(&mut **tx).await
+                }
+                DbExecutor::Pool(DbPool::Postgres(pool)) => {
+                    (self.postgres)()
+                        .try_map(move |row: PgRow| decode(&row))
+                        .fetch_optional(pool)
+                        .await
+                }
+                DbExecutor::Transaction(DbTransaction::Postgres(tx)) => {
+                    (self.postgres)()
+                        .try_map(move |row: PgRow| decode(&row))
+                        .fetch_optional(&mut **tx)
+                        .await
+                }
+            }
+        })
+    }
+
+    pub fn fetch_one<'c, 'f>(
+        self,
+        executor: impl Into<DbExecutor<'c>>,
+    ) -> BoxFuture<'f, Result<O, sqlx::Error>>
+    where
+        'q: 'f,
+        'c: 'f,
+        Self: 'f,
+    {
+        let fetch = self.fetch_optional(executor);
+        Box::pin(async move { fetch.await?.ok_or(sqlx::Error::RowNotFound) })
+    }
+}
+
+/// Decode a row of [`query_as!`] on PostgreSQL
+pub fn decode_row<O>(row: &PgRow) -> Result<O, sqlx::Error>
+where
+    O: for<'r> FromRow<'r, PgRow>,
+{
+    O::from_row(row)
+}
+
+/// Decode a row of [`query_scalar!`] on PostgreSQL
+pub fn decode_scalar<O>(row: &PgRow) -> Result<O, sqlx::Error>
+where
+    O: for<'r> Decode<'r, Postgres> + Type<Postgres>,
+{
+    row.try_get(0)
+}
+
+/// [`sqlx::query!`] for either database, for statements whose rows aren't needed
+macro_rules! query {
+    ($sql:literal $(, $arg:expr)* $(,)?) => {
+        $crate::db::Statement::new(
+            || sqlx::query!($sql $(, $arg)*),
+            || sqlx::query($crate::db::postgres::translate($sql)) $(.bind(&$arg))*,
+        )
+    };
+}
+
+/// [`sqlx::query_as!`] for either database. `$out` also has to derive `FromRow`.
+macro_rules! query_as {
+    ($out:path, $sql:literal $(, $arg:expr)* $(,)?) => {
+        $crate::db::Fetch::new(
+            || sqlx::query_as!($out, $sql $(, $arg)*),
+            || sqlx::query($crate::db::postgres::translate($sql)) $(.bind(&$arg))*,
+            $crate::db::decode_row::<$out>,
+        )
+    };
+}
+
+/// [`sqlx::query_scalar!`] for either database
+macro_rules! query_scalar {
+    ($sql:literal $(, $arg:expr)* $(,)?) => {
+        $crate::db::Fetch::new(
+            || sqlx::query_scalar!($sql $(, $arg)*),
+            || sqlx::query($crate::db::postgres::translate($sql)) $(.bind(&$arg))*,
+            $crate::db::decode_scalar,
+        )
+    };
+}
+
+pub(crate) use query;
+pub(crate) use query_as;
+pub(crate) use query_scalar;
@@ -0,0 +1,358 @@
+//! Rewrites the app's statements, written for SQLite, into PostgreSQL's dialect.
+//!
+//! The rewrite covers what the statements use and nothing more:
+//!
+//! - The type overrides of sqlx's macros, as in `id as "id!: Uuid"`, are dropped from column
+//!   aliases.
+//! - `datetime(...)` becomes a `TIMESTAMPTZ`: `'now'` is the statement's start, like SQLite's,
+//!   and modifiers such as `'-24 hours'` are added as intervals. `julianday(...)` becomes days
+//!   since the epoch, which differs from SQLite's only by a constant.
+//! - `json_extract(x, '$.key')` becomes `x::jsonb ->> 'key'`, `char(n)` becomes `chr(n)` and
+//!   `max(a, b)` and `min(a, b)`, SQLite's scalar ones, become `GREATEST` and `LEAST`.
+//! - `INSERT OR IGNORE` becomes `ON CONFLICT DO NOTHING`, `a IS $1` becomes
+//!   `a IS NOT DISTINCT FROM $1` and `CAST(... AS INTEGER)` casts to `BIGINT`, the size of
+//!   SQLite's integers.
+//!
+//! Anything else has to be written so both databases take it: `TRUE` and `FALSE` rather than
+//! `1` and `0` for booleans, `$1` rather than `?` for parameters, a cast to `BIGINT`
+//! around `SUM`s of integers, which PostgreSQL widens to `NUMERIC`, and a real column rather
+//! than `rowid` to break ties in orderings.
+
+use std::{collections::HashMap, sync::Mutex};
+
+lazy_static::lazy_static! {
+    static ref STATEMENTS: Mutex<HashMap<&'static str, &'static str>> = Mutex::new(HashMap::new());
+}
+
+/// `sql` as PostgreSQL runs it. Each statement is rewritten once and kept for the life of the
+/// process, since they are all literals of the app's own.
+pub fn translate(sql: &'static str) -> &'static str {
+    let mut statements = STATEMENTS.lock().unwrap();
+    statements
+        .entry(sql)
+        .or_insert_with(|| Box::leak(rewrite(sql).into_boxed_str()))
+}
+
+/// Rewrite a statement or, for function arguments, part of one
+pub fn rewrite(sql: &str) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let mut rest = sql;
+    let mut ignore_conflicts = false;
+    while let Some(c) = rest.chars().next() {
+        if rest.starts_with("--") {
+            let end = rest.find('\n').unwrap_or(rest.len());
+            out.push_str(&rest[..end]);
+            rest = &rest[end..];
+        } else if c == '\'' {
+            let end = quoted_end(rest);
+            out.push_str(&rest[..end]);
+            rest = &rest[end..];
+        } else if c == '"' {
+            // A column alias, maybe with a type override of the sqlx macros
+            let end = quoted_end(rest);
+            let alias = rest[1..end - 1]
+                .split(['!', '?', ':'])
+                .next()
+                .unwrap_or_default()
+                .trim();
+            out.push('"');
+            out.push_str(alias);
+            out.push('"');
+            rest = &rest[end..];
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let end = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            let word = &rest[..end];
+            let after = &rest[end..];
+            let lower = word.to_ascii_lowercase();
+            let call = after.trim_start().starts_with('(');
+            match lower.as_str() {
+                "datetime" | "julianday" | "json_extract" | "char" | "max" | "min" if call => {
+                    let open = end + (after.len() - after.trim_start().len());
+                    let close = group_end(rest, open);
+                    let args: Vec<String> = split_args(&rest[open + 1..close - 1])
+                        .into_iter()
+                        .map(|arg| rewrite(arg.trim()))
+                        .collect();
+                    out.push_str(&function(word, &args));
+                    rest = &rest[close..];
+                    continue;
+                }
+                "insert" if starts_with_words(after, &["or", "ignore"]).is_some() => {
+                    let skipped = starts_with_words(after, &["or", "ignore"]).unwrap();
+                    out.push_str(word);
+                    ignore_conflicts = true;
+                    rest = &after[skipped..];
+                    continue;
+                }
+                "is" => {
+                    if let Some(skipped) = starts_with_words(after, &["not"]) {
+                        if after[skipped..].trim_start().starts_with('$') {
+                            out.push_str("IS DISTINCT FROM");
+                            rest = &after[skipped..];
+                            continue;
+                        }
+                    } else if after.trim_start().starts_with('$') {
+                        out.push_str("IS NOT DISTINCT FROM");
+                        rest = after;
+                        continue;
+                    }
+                    out.push_str(word);
+                }
+                "as" if starts_with_words(after, &["integer"])
+                    .is_some_and(|skipped| after[skipped..].trim_start().starts_with(')')) =>
+                {
+                    out.push_str("AS BIGINT");
+                    rest = &after[starts_with_words(after, &["integer"]).unwrap()..];
+                    continue;
+                }
+                _ => out.push_str(word),
+            }
+            rest = after;
+        } else {
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    if ignore_conflicts {
+        let clause = " ON CONFLICT DO NOTHING";
+        match find_word(&out, "returning") {
+            Some(at) => out.insert_str(at - 1, clause),
+            None => {
+                let end = out.trim_end().trim_end_matches(';').len();
+                out.insert_str(end, clause);
+            }
+        }
+    }
+    out
+}
+
+/// The PostgreSQL for a call of one of SQLite's functions
+fn function(name: &str, args: &[String]) -> String {
+    match name.to_ascii_lowercase().as_str() {
+        "datetime" => {
+            let mut time = timestamp(&args[0]);
+            for modifier in args[1..].iter().filter(|arg| *arg != "'subsec'") {
+                time = if modifier.starts_with('\'') {
+                    format!("({} + INTERVAL {})", time, modifier)
+                } else {
+                    format!("({} + CAST({} AS INTERVAL))", time, modifier)
+                };
+            }
+            time
+        }
+        "julianday" => format!(
+            "(CAST(EXTRACT(EPOCH FROM {}) AS DOUBLE PRECISION) / 86400.0)",
+            timestamp(&args[0])
+        ),
+        "json_extract" => {
+            let path = args[1].trim_matches('\'').trim_start_matches("$.");
+            if path.contains('.') {
+                format!(
+                    "(CAST({} AS JSONB) #>> '{{{}}}')",
+                    args[0],
+                    path.replace('.', ",")
+                )
+            } else {
+                format!("(CAST({} AS JSONB) ->> '{}')", args[0], path)
+            }
+        }
+        "char" => args
+            .iter()
+            .map(|code| format!("chr({})", code))
+            .collect::<Vec<_>>()
+            .join(" || "),
+        "max" if args.len() > 1 => format!("GREATEST({})", args.join(", ")),
+        "min" if args.len() > 1 => format!("LEAST({})", args.join(", ")),
+        // The aggregates
+        "max" | "min" => format!("{}({})", name, args.join(", ")),
+        _ => unreachable!("{} isn't rewritten", name),
+    }
+}
+
+/// A time argument of `datetime` or `julianday`
+fn timestamp(arg: &str) -> String {
+    if arg.eq_ignore_ascii_case("'now'") {
+        "statement_timestamp()".to_string()
+    } else if arg.starts_with('$') {
+        // A bare parameter, which PostgreSQL can't type from `EXTRACT` alone
+        format!("CAST({} AS TIMESTAMPTZ)", arg)
+    } else {
+        format!("({})", arg)
+    }
+}
+
+/// The length of the quoted string or identifier `s` starts with, quotes included
+fn quoted_end(s: &str) -> usize {
+    let quote = s.as_bytes()[0];
+    let bytes = s.as_bytes();
+    let mut i = 1;
+    while i < bytes.len() {
+        if bytes[i] == quote {
+            // A doubled quote is an escaped one
+            if bytes.get(i + 1) == Some(&quote) {
+                i += 2;
+                continue;
+            }
+            return i + 1;
+        }
+        i += 1;
+    }
+    s.len()
+}
+
+/// The index just past the parenthesis closing the one at `open`
+fn group_end(s: &str, open: usize) -> usize {
+    let mut depth = 0;
+    let mut i = open;
+    while i < s.len() {
+        match s.as_bytes()[i] {
+            b'\'' | b'"' => {
+                i += quoted_end(&s[i..]);
+                continue;
+            }
+            b'(' => depth += 1,
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    s.len()
+}
+
+/// Split a function's arguments at their top-level commas
+fn split_args(s: &str) -> Vec<&str> {
+    let mut args = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    let mut i = 0;
+    while i < s.len() {
+        match s.as_bytes()[i] {
+            b'\'' | b'"' => {
+                i += quoted_end(&s[i..]);
+                continue;
+            }
+            b'(' => depth += 1,
+            b')' => depth -= 1,
+            b',' if depth == 0 => {
+                args.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    args.push(&s[start..]);
+    args
+}
+
+/// If `s` starts with the words, apart from whitespace and case, how much of it they take up
+fn starts_with_words(s: &str, words: &[&str]) -> Option<usize> {
+    let mut at = 0;
+    for word in words {
+        let skipped = s[at..].len() - s[at..].trim_start().len();
+        if skipped == 0 && at > 0 {
+            return None;
+        }
+        at += skipped;
+        let candidate = s.get(at..at + word.len())?;
+        let boundary = s[at + word.len()..]
+            .chars()
+            .next()
+            .is_none_or(|c| !(c.is_ascii_alphanumeric() || c == '_'));
+        if !candidate.eq_ignore_ascii_case(word) || !boundary {
+            return None;
+        }
+        at += word.len();
+    }
+    Some(at)
+}
+
+/// Where `word` first appears in `s` as a word of its own, outside quotes
+fn find_word(s: &str, word: &str) -> Option<usize> {
+    let mut i = 0;
+    while i < s.len() {
+        let c = s.as_bytes()[i];
+        if c == b'\'' || c == b'"' {
+            i += quoted_end(&s[i..]);
+            continue;
+        }
+        let starts_word = i == 0 || !(s.as_bytes()[i - 1].is_ascii_alphanumeric());
+        if starts_word && starts_with_words(&s[i..], &[word]) == Some(word.len()) {
+            return Some(i);
+        }
+        i += 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewrite_drops_type_overrides() {
+        assert_eq!(
+            rewrite(r#"SELECT id as "id!: Uuid", title as "title?", name FROM tasks"#),
+            r#"SELECT id as "id", title as "title", name FROM tasks"#
+        );
+    }
+
+    #[test]
+    fn test_rewrite_times() {
+        assert_eq!(
+            rewrite("UPDATE t SET updated_at = datetime('now', 'subsec') WHERE id = $1"),
+            "UPDATE t SET updated_at = statement_timestamp() WHERE id = $1"
+        );
+        assert_eq!(
+            rewrite("WHERE created_at < datetime('now', '-24 hours') OR at < datetime('now', $2)"),
+            "WHERE created_at < (statement_timestamp() + INTERVAL '-24 hours') OR at < (statement_timestamp() + CAST($2 AS INTERVAL))"
+        );
+        assert_eq!(
+            rewrite("datetime(COALESCE(ep.started_at, t.created_at)) >= datetime($5)"),
+            "(COALESCE(ep.started_at, t.created_at)) >= CAST($5 AS TIMESTAMPTZ)"
+        );
+        assert_eq!(
+            rewrite("CAST(ROUND((julianday($1) - julianday(started_at)) * 86400000.0) AS INTEGER)"),
+            "CAST(ROUND(((CAST(EXTRACT(EPOCH FROM CAST($1 AS TIMESTAMPTZ)) AS DOUBLE PRECISION) / 86400.0) - (CAST(EXTRACT(EPOCH FROM (started_at)) AS DOUBLE PRECISION) / 86400.0)) * 86400000.0) AS BIGINT)"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_functions_and_clauses() {
+        assert_eq!(
+            rewrite("json_extract(e.details, '$.to') = 'done' AND substr(c, 1) = char(10)"),
+            "(CAST(e.details AS JSONB) ->> 'to') = 'done' AND substr(c, 1) = chr(10)"
+        );
+        assert_eq!(
+            rewrite("SELECT MAX(a), max(b, MIN(c)), min(d, e) FROM t"),
+            "SELECT MAX(a), GREATEST(b, MIN(c)), LEAST(d, e) FROM t"
+        );
+        assert_eq!(
+            rewrite("INSERT OR IGNORE INTO links (a, b) VALUES ($1, $2)"),
+            "INSERT INTO links (a, b) VALUES ($1, $2) ON CONFLICT DO NOTHING"
+        );
+        assert_eq!(
+            rewrite("INSERT OR IGNORE INTO links (a) VALUES ($1) RETURNING a"),
+            "INSERT INTO links (a) VALUES ($1) ON CONFLICT DO NOTHING RETURNING a"
+        );
+        assert_eq!(
+            rewrite("WHERE a IS $1 AND b IS NOT $2 AND c IS NULL ORDER BY created_at, id"),
+            "WHERE a IS NOT DISTINCT FROM $1 AND b IS DISTINCT FROM $2 AND c IS NULL ORDER BY created_at, id"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_leaves_strings_alone() {
+        let sql =
+            "SELECT 'datetime(''now'') is rowid' as \"note\" -- julianday(x) isn't called\nFROM t";
+        assert_eq!(rewrite(sql), sql);
+    }
+}
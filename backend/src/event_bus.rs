@@ -0,0 +1,139 @@
+//! Board updates broadcast to open browser tabs, so they stay in sync without polling. Models
+//! publish task and queue changes as they write them, and the command runner publishes when
+//! an execution's process starts and stops running.
+
+use std::sync::OnceLock;
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::models::{
+    execution_process::{ExecutionProcessStatus, ExecutionProcessType},
+    task::TaskStatus,
+};
+
+/// Updates a slow subscriber may fall behind by before it has to resync
+const CHANNEL_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(export)]
+pub enum BoardEvent {
+    TaskCreated {
+        project_id: Uuid,
+        task_id: Uuid,
+    },
+    /// The task's title, description or status was edited
+    TaskUpdated {
+        project_id: Uuid,
+        task_id: Uuid,
+    },
+    TaskStatusChanged {
+        project_id: Uuid,
+        task_id: Uuid,
+        status: TaskStatus,
+    },
+    TaskDeleted {
+        project_id: Uuid,
+        task_id: Uuid,
+    },
+    ExecutionStarted {
+        project_id: Uuid,
+        task_id: Uuid,
+        task_attempt_id: Uuid,
+        execution_process_id: Uuid,
+        process_type: ExecutionProcessType,
+    },
+    ExecutionFinished {
+        project_id: Uuid,
+        task_id: Uuid,
+        task_attempt_id: Uuid,
+        execution_process_id: Uuid,
+        status: ExecutionProcessStatus,
+    },
+    /// The execution's process started running, after any queueing and setup
+    ProcessRunning {
+        execution_process_id: Uuid,
+    },
+    /// The execution's process exited or was killed
+    ProcessStopped {
+        execution_process_id: Uuid,
+    },
+    /// An attempt joined or left the queue waiting for a free slot
+    QueueChanged {
+        project_id: Uuid,
+        task_attempt_id: Uuid,
+        queued: bool,
+    },
+    /// The subscriber fell behind and missed updates; it should reload what it shows
+    Resync,
+}
+
+impl BoardEvent {
+    /// The project the update belongs to, or `None` when it isn't known where it's published
+    pub fn project_id(&self) -> Option<Uuid> {
+        match self {
+            Self::TaskCreated { project_id, .. }
+            | Self::TaskUpdated { project_id, .. }
+            | Self::TaskStatusChanged { project_id, .. }
+            | Self::TaskDeleted { project_id, .. }
+            | Self::ExecutionStarted { project_id, .. }
+            | Self::ExecutionFinished { project_id, .. }
+            | Self::QueueChanged { project_id, .. } => Some(*project_id),
+            Self::ProcessRunning { .. } | Self::ProcessStopped { .. } | Self::Resync => None,
+        }
+    }
+
+    /// The SSE event name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::TaskCreated { .. } => "task_created",
+            Self::TaskUpdated { .. } => "task_updated",
+            Self::TaskStatusChanged { .. } => "task_status_changed",
+            Self::TaskDeleted { .. } => "task_deleted",
+            Self::ExecutionStarted { .. } => "execution_started",
+            Self::ExecutionFinished { .. } => "execution_finished",
+            Self::ProcessRunning { .. } => "process_running",
+            Self::ProcessStopped { .. } => "process_stopped",
+            Self::QueueChanged { .. } => "queue_changed",
+            Self::Resync => "resync",
+        }
+    }
+}
+
+fn channel() -> &'static broadcast::Sender<BoardEvent> {
+    static CHANNEL: OnceLock<broadcast::Sender<BoardEvent>> = OnceLock::new();
+    CHANNEL.get_or_init(|| broadcast::channel(CHANNEL_CAPACITY).0)
+}
+
+pub fn publish(event: BoardEvent) {
+    // No open tabs is the usual case
+    let _ = channel().send(event);
+}
+
+pub fn subscribe() -> broadcast::Receiver<BoardEvent> {
+    channel().subscribe()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_are_tagged_with_their_name() {
+        let event = BoardEvent::TaskStatusChanged {
+            project_id: Uuid::nil(),
+            task_id: Uuid::nil(),
+            status: TaskStatus::InReview,
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], event.name());
+        assert_eq!(json["status"], "inreview");
+        assert_eq!(event.project_id(), Some(Uuid::nil()));
+
+        let json = serde_json::to_value(BoardEvent::Resync).unwrap();
+        assert_eq!(json["type"], BoardEvent::Resync.name());
+    }
+}
@@ -1,17 +1,55 @@
-use git2::Repository;
+use git2::{Repository, StatusOptions};
+use sqlx::FromRow;
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
 use crate::{
-    app_state::AppState,
+    app_state::{AppState, FinishedExecution},
+    command_runner,
+    db::{self, DbPool},
+    executor::{ExecutorConfig, NormalizedEntry, UsageMetrics},
     models::{
-        execution_process::{ExecutionProcess, ExecutionProcessStatus, ExecutionProcessType},
-        task::{Task, TaskStatus},
+        attempt_linked_worktree::AttemptLinkedWorktree,
+        attempt_plan::{AttemptPlan, AttemptPlanStatus},
+        benchmark::BenchmarkResult,
+        conversation_context::ConversationContext,
+        execution_attention::AttentionKind,
+        execution_diff::ExecutionDiff,
+        execution_metrics::ExecutionMetrics,
+        execution_process::{
+            ExecutionProcess, ExecutionProcessStatus, ExecutionProcessType, FailureReason,
+        },
+        execution_resources::ExecutionResources,
+        execution_tool_usage::ExecutionToolUsage,
+        executor_session::ExecutorSession,
+        network_interruption::NetworkInterruption,
+        notification_delivery::NotificationDelivery,
+        project::Project,
+        task::{CreateTask, Task, TaskStatus},
         task_attempt::TaskAttempt,
+        task_decomposition::{TaskDecomposition, TaskDecompositionStatus},
+        task_defaults::TaskDefaults,
+        task_schedule::TaskScheduleRun,
+        verification_fix_loop::VerificationFixLoop,
+    },
+    services::{
+        attention,
+        commit_message::{self, CommitMessageContext, CommitSource, StagedChanges},
+        environment_variables, execution_queue, failure_reasons, git_host, network_recovery,
+        notification_webhooks, plan_review, rate_limits, repo_config, run_summary, spawn_retry,
+        task_decomposition, verification, GitService, NotificationConfig, NotificationEvent,
+        NotificationService, ProcessService,
     },
-    services::{NotificationConfig, NotificationService, ProcessService},
     utils::worktree_manager::WorktreeManager,
 };
 
+/// How long a merged attempt, or one whose task is done, keeps its worktree after its last
+/// activity; other attempts keep theirs for 24 hours
+const FINISHED_WORKTREE_GRACE: &str = "-1 hours";
+
+/// Longest a finished execution's completion waits for its output to be stored
+pub const OUTPUT_DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
 /// Delegation context structure
 #[derive(Debug, serde::Deserialize)]
 struct DelegationContext {
@@ -93,6 +131,12 @@ async fn handle_setup_delegation(app_state: &AppState, delegation_context: Deleg
                 .and_then(|a| a.get("prompt"))
                 .and_then(|p| p.as_str())
                 .unwrap_or("");
+            let executor = params
+                .additional
+                .as_ref()
+                .and_then(|a| a.get("executor"))
+                .and_then(|e| e.as_str())
+                .and_then(|e| e.parse().ok());
 
             ProcessService::start_followup_execution_direct(
                 &app_state.db_pool,
@@ -101,6 +145,7 @@ async fn handle_setup_delegation(app_state: &AppState, delegation_context: Deleg
                 task_id,
                 project_id,
                 prompt,
+                executor,
             )
             .await
             .map(|_| ())
@@ -128,15 +173,17 @@ async fn handle_setup_delegation(app_state: &AppState, delegation_context: Deleg
     }
 }
 
-/// Commit any unstaged changes in the worktree after execution completion
+/// Commit any unstaged changes in the worktree after execution completion. Returns the
+/// commit the worktree is on afterwards. Once `cancellation` is cancelled nothing is staged,
+/// so the changes are either committed whole or left in the worktree as the run left them.
 async fn commit_execution_changes(
     worktree_path: &str,
-    attempt_id: Uuid,
-    summary: Option<&str>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    context: CommitMessageContext,
+    cancellation: &CancellationToken,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
     // Run git operations in a blocking task since git2 is synchronous
     let worktree_path = worktree_path.to_string();
-    let summary = summary.map(|s| s.to_string());
+    let cancellation = cancellation.clone();
     tokio::task::spawn_blocking(move || {
         let worktree_repo = Repository::open(&worktree_path)?;
 
@@ -152,8 +199,9 @@ async fn commit_execution_changes(
                 || flags.contains(git2::Status::WT_DELETED)
         });
 
-        if !has_changes {
-            return Ok::<(), Box<dyn std::error::Error + Send + Sync>>(());
+        if !has_changes || cancellation.is_cancelled() {
+            let head = worktree_repo.head()?.peel_to_commit()?;
+            return Ok::<String, Box<dyn std::error::Error + Send + Sync>>(head.id().to_string());
         }
 
         // Get the current signature for commits
@@ -171,13 +219,10 @@ async fn commit_execution_changes(
         let tree_id = worktree_index.write_tree()?;
         let tree = worktree_repo.find_tree(tree_id)?;
 
-        // Create commit for the changes
-        let commit_message = if let Some(ref summary_msg) = summary {
-            summary_msg.clone()
-        } else {
-            format!("Task attempt {} - Final changes", attempt_id)
-        };
-        worktree_repo.commit(
+        // Describe the commit from what was actually staged
+        let changes = StagedChanges::between(&worktree_repo, &parent_commit.tree()?, &tree)?;
+        let commit_message = commit_message::build(&context, &changes);
+        let commit_id = worktree_repo.commit(
             Some("HEAD"),
             &signature,
             &signature,
@@ -186,28 +231,262 @@ async fn commit_execution_changes(
             &[&parent_commit],
         )?;
 
-        Ok(())
+        Ok(commit_id.to_string())
+    })
+    .await?
+}
+
+/// The commit the worktree is on
+async fn worktree_head(
+    worktree_path: &str,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let worktree_path = worktree_path.to_string();
+    let head = tokio::task::spawn_blocking(move || {
+        let repo = Repository::open(&worktree_path)?;
+        let head = repo.head()?.peel_to_commit()?;
+        Ok::<String, git2::Error>(head.id().to_string())
     })
     .await??;
+    Ok(head)
+}
 
-    Ok(())
+/// Commit what a finished run left in the worktree unless auto-commit is off, note the
+/// commit the worktree ends up on against the execution, and push the attempt's branch
+/// after a successful run if pushing is on. A cancelled execution is neither committed nor
+/// pushed.
+async fn commit_and_push_execution(
+    app_state: &AppState,
+    task_attempt: &TaskAttempt,
+    execution_process_id: Uuid,
+    context: CommitMessageContext,
+    success: bool,
+    cancellation: &CancellationToken,
+) {
+    let settings = app_state
+        .get_config()
+        .read()
+        .await
+        .post_execution_git
+        .clone();
+    let source = context.source;
+    let committed = if settings.auto_commit && !cancellation.is_cancelled() {
+        commit_execution_changes(&task_attempt.worktree_path, context, cancellation).await
+    } else {
+        worktree_head(&task_attempt.worktree_path).await
+    };
+    let commit_sha = match committed {
+        Ok(commit_sha) => commit_sha,
+        Err(e) => {
+            tracing::error!(
+                "Failed to commit {} changes for attempt {}: {}",
+                source.describe(),
+                task_attempt.id,
+                e
+            );
+            return;
+        }
+    };
+    tracing::info!(
+        "Attempt {} is at {} after its {} finished",
+        task_attempt.id,
+        commit_sha,
+        source.describe()
+    );
+    if let Err(e) =
+        ExecutionProcess::set_commit_sha(&app_state.db_pool, execution_process_id, &commit_sha)
+            .await
+    {
+        tracing::error!(
+            "Failed to record the commit of execution {}: {}",
+            execution_process_id,
+            e
+        );
+    }
+
+    if success && settings.push && !cancellation.is_cancelled() {
+        push_attempt_branch(app_state, task_attempt).await;
+    }
 }
 
-/// Check if worktree has uncommitted changes and warn if so
-fn check_uncommitted_changes(worktree_path: &str) {
-    if let Ok(repo) = Repository::open(worktree_path) {
-        if let Ok(statuses) = repo.statuses(None) {
-            // Simplified check: any status entry indicates changes
-            if !statuses.is_empty() {
-                tracing::warn!(
-                    "Deleting worktree {} with uncommitted changes",
-                    worktree_path
+/// Push the attempt's branch to origin with the token of the project's git host
+async fn push_attempt_branch(app_state: &AppState, task_attempt: &TaskAttempt) {
+    if app_state.dry_run {
+        tracing::info!(
+            "Dry run: not pushing branch {} of attempt {}",
+            task_attempt.branch,
+            task_attempt.id
+        );
+        return;
+    }
+    let project = match Task::find_by_id(&app_state.db_pool, task_attempt.task_id).await {
+        Ok(Some(task)) => Project::find_by_id(&app_state.db_pool, task.project_id)
+            .await
+            .ok()
+            .flatten(),
+        _ => None,
+    };
+    let Some(project) = project else {
+        tracing::warn!(
+            "Not pushing branch {} of attempt {}: its project is gone",
+            task_attempt.branch,
+            task_attempt.id
+        );
+        return;
+    };
+    let github = app_state.get_config().read().await.github.clone();
+    let host = match git_host::for_project(
+        &app_state.db_pool,
+        project.id,
+        &project.git_repo_path,
+        &github,
+    )
+    .await
+    {
+        Ok(host) => host,
+        Err(e) => {
+            tracing::warn!(
+                "Not pushing branch {} of attempt {}: {}",
+                task_attempt.branch,
+                task_attempt.id,
+                e
+            );
+            return;
+        }
+    };
+    let (username, token) = host.push_credentials();
+    let (username, token) = (username.to_string(), token.to_string());
+
+    let worktree_path = task_attempt.worktree_path.clone();
+    let branch = task_attempt.branch.clone();
+    let pushed = tokio::task::spawn_blocking(move || {
+        GitService::new(&worktree_path)?.push_with_token(
+            std::path::Path::new(&worktree_path),
+            &branch,
+            &username,
+            &token,
+        )
+    })
+    .await;
+    match pushed {
+        Ok(Ok(())) => tracing::info!(
+            "Pushed branch {} of attempt {}",
+            task_attempt.branch,
+            task_attempt.id
+        ),
+        Ok(Err(e)) => tracing::error!(
+            "Failed to push branch {} of attempt {}: {}",
+            task_attempt.branch,
+            task_attempt.id,
+            e
+        ),
+        Err(e) => tracing::error!("Push of attempt {} panicked: {}", task_attempt.id, e),
+    }
+}
+
+/// Store what a finished coding agent run changed, from the commit it started on to the
+/// worktree's HEAD now that its changes are committed
+async fn capture_execution_diff(pool: &DbPool, execution_process_id: Uuid, worktree_path: &str) {
+    let diff = match ExecutionDiff::find_by_execution_process_id(pool, execution_process_id).await {
+        Ok(Some(diff)) => diff,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::error!(
+                "Failed to load diff of execution {}: {}",
+                execution_process_id,
+                e
+            );
+            return;
+        }
+    };
+
+    let worktree_path = std::path::PathBuf::from(worktree_path);
+    let captured = tokio::task::spawn_blocking(move || {
+        GitService::get_commit_hunks(&worktree_path, &diff.from_commit)
+    })
+    .await;
+    match captured {
+        Ok(Ok((to_commit, files))) => {
+            if let Err(e) =
+                ExecutionDiff::record_finish(pool, execution_process_id, &to_commit, &files).await
+            {
+                tracing::error!(
+                    "Failed to store diff of execution {}: {}",
+                    execution_process_id,
+                    e
                 );
             }
         }
+        Ok(Err(e)) => {
+            tracing::error!("Failed to diff execution {}: {}", execution_process_id, e);
+        }
+        Err(e) => {
+            tracing::error!("Diff of execution {} panicked: {}", execution_process_id, e);
+        }
+    }
+}
+
+/// Store the run's one-line outcome as its session's summary, now that its diff is known
+async fn record_outcome(pool: &DbPool, execution_process_id: Uuid, entries: &[NormalizedEntry]) {
+    let diff = ExecutionDiff::find_by_execution_process_id(pool, execution_process_id)
+        .await
+        .ok()
+        .flatten()
+        .filter(|diff| diff.to_commit.is_some());
+    let Some(outcome) = run_summary::outcome(entries, diff.as_ref().map(|diff| &diff.summary))
+    else {
+        return;
+    };
+    if let Err(e) = ExecutorSession::update_summary(pool, execution_process_id, &outcome).await {
+        tracing::error!(
+            "Failed to store the outcome of execution process {}: {}",
+            execution_process_id,
+            e
+        );
+    }
+}
+
+/// Task and project details for the message of a commit vibe-kanban makes in the worktree
+async fn commit_message_context(
+    app_state: &AppState,
+    task_attempt: &TaskAttempt,
+    source: CommitSource,
+    summary: Option<String>,
+) -> CommitMessageContext {
+    let task = Task::find_by_id(&app_state.db_pool, task_attempt.task_id)
+        .await
+        .ok()
+        .flatten();
+    let template = match &task {
+        Some(task) => Project::find_by_id(&app_state.db_pool, task.project_id)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|project| project.commit_message_template),
+        None => None,
+    };
+    CommitMessageContext {
+        task_id: task_attempt.task_id,
+        attempt_id: task_attempt.id,
+        source,
+        task_title: task.as_ref().map(|t| t.title.clone()),
+        task_description: task.and_then(|t| t.description),
+        summary,
+        template,
     }
 }
 
+/// Whether a worktree has changes deleting it would lose: modified, staged or untracked
+/// files. Ignored files, such as build output, don't count.
+fn has_uncommitted_changes(worktree_path: &str) -> bool {
+    let Ok(repo) = Repository::open(worktree_path) else {
+        return false;
+    };
+    let mut options = StatusOptions::new();
+    options.include_untracked(true).include_ignored(false);
+    repo.statuses(Some(&mut options))
+        .is_ok_and(|statuses| !statuses.is_empty())
+}
+
 /// Delete a single git worktree and its filesystem directory using WorktreeManager
 async fn delete_worktree(
     worktree_path: &str,
@@ -225,8 +504,12 @@ async fn delete_worktree(
         return Ok(());
     }
 
-    // Check for uncommitted changes and warn
-    check_uncommitted_changes(worktree_path);
+    if has_uncommitted_changes(worktree_path) {
+        tracing::warn!(
+            "Deleting worktree {} with uncommitted changes",
+            worktree_path
+        );
+    }
 
     match WorktreeManager::cleanup_worktree(&worktree_path_buf, Some(main_repo_path)).await {
         Ok(_) => {
@@ -247,9 +530,57 @@ async fn delete_worktree(
     }
 }
 
+/// Delete the linked projects' checkouts of a multi-repository attempt, then the workspace
+/// holding them once the attempt's own worktree is gone too
+async fn delete_linked_worktrees(pool: &DbPool, attempt_id: Uuid, worktree_path: &str) {
+    let linked_worktrees =
+        match AttemptLinkedWorktree::find_by_task_attempt_id(pool, attempt_id).await {
+            Ok(linked_worktrees) => linked_worktrees,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to load linked worktrees of attempt {}: {}",
+                    attempt_id,
+                    e
+                );
+                return;
+            }
+        };
+    if linked_worktrees.is_empty() {
+        return;
+    }
+
+    for linked in linked_worktrees {
+        let git_repo_path = match Project::find_by_id(pool, linked.project_id).await {
+            Ok(Some(project)) => project.git_repo_path,
+            Ok(None) => continue,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to load linked project {} of attempt {}: {}",
+                    linked.project_id,
+                    attempt_id,
+                    e
+                );
+                continue;
+            }
+        };
+        if let Err(e) = delete_worktree(&linked.worktree_path, &git_repo_path, attempt_id).await {
+            tracing::error!(
+                "Failed to cleanup linked worktree {}: {}",
+                linked.worktree_path,
+                e
+            );
+        }
+    }
+
+    // Only removed when nothing is left in it
+    if let Some(workspace) = std::path::Path::new(worktree_path).parent() {
+        let _ = std::fs::remove_dir(workspace);
+    }
+}
+
 /// Clean up all worktrees for a specific task (immediate cleanup)
 pub async fn cleanup_task_worktrees(
-    pool: &sqlx::SqlitePool,
+    pool: &DbPool,
     task_id: Uuid,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let task_attempts_with_project =
@@ -270,7 +601,9 @@ pub async fn cleanup_task_worktrees(
     let mut failed_count = 0;
 
     for (attempt_id, worktree_path, git_repo_path) in task_attempts_with_project {
-        if let Err(e) = delete_worktree(&worktree_path, &git_repo_path, attempt_id).await {
+        let result = delete_worktree(&worktree_path, &git_repo_path, attempt_id).await;
+        delete_linked_worktrees(pool, attempt_id, &worktree_path).await;
+        if let Err(e) = result {
             tracing::error!(
                 "Failed to cleanup worktree for attempt {}: {}",
                 attempt_id,
@@ -305,9 +638,77 @@ pub async fn cleanup_task_worktrees(
     Ok(())
 }
 
+/// Delete the worktrees of a task's attempts, except those with uncommitted changes, which
+/// would be lost with them. Returns the attempts whose worktree was kept.
+pub async fn cleanup_clean_task_worktrees(
+    pool: &DbPool,
+    task_id: Uuid,
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    let (dirty, clean): (Vec<_>, Vec<_>) = TaskAttempt::find_by_task_id_with_project(pool, task_id)
+        .await?
+        .into_iter()
+        .partition(|(_, worktree_path, _)| has_uncommitted_changes(worktree_path));
+    delete_attempt_worktrees(pool, clean).await;
+    Ok(dirty
+        .into_iter()
+        .map(|(attempt_id, _, _)| attempt_id)
+        .collect())
+}
+
+/// Delete the worktrees of `attempts` (id, worktree path, repository path) and mark them
+/// deleted. Returns the attempts whose worktree is gone.
+async fn delete_attempt_worktrees(
+    pool: &DbPool,
+    attempts: Vec<(Uuid, String, String)>,
+) -> Vec<Uuid> {
+    let mut deleted = Vec::new();
+    for (attempt_id, worktree_path, git_repo_path) in attempts {
+        let result = delete_worktree(&worktree_path, &git_repo_path, attempt_id).await;
+        delete_linked_worktrees(pool, attempt_id, &worktree_path).await;
+        if let Err(e) = result {
+            tracing::error!("Failed to cleanup worktree {}: {}", attempt_id, e);
+            continue;
+        }
+        // Mark worktree as deleted in database after successful cleanup
+        match TaskAttempt::mark_worktree_deleted(pool, attempt_id).await {
+            Ok(()) => {
+                tracing::info!(
+                    "Successfully marked worktree as deleted for attempt {}",
+                    attempt_id
+                );
+                deleted.push(attempt_id);
+            }
+            Err(e) => tracing::error!(
+                "Failed to mark worktree as deleted in database for attempt {}: {}",
+                attempt_id,
+                e
+            ),
+        }
+    }
+    deleted
+}
+
+/// Delete the worktrees of a project's finished attempts right away, instead of waiting
+/// for the periodic cleanup. Returns the attempts whose worktree was deleted.
+pub async fn prune_finished_worktrees(
+    pool: &DbPool,
+    project_id: Uuid,
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    let finished =
+        TaskAttempt::find_finished_for_cleanup(pool, Some(project_id), "+0 seconds").await?;
+    Ok(delete_attempt_worktrees(pool, finished).await)
+}
+
+#[derive(FromRow)]
+struct ActiveWorktree {
+    id: Uuid,
+    worktree_path: String,
+}
+
 /// Defensively check for externally deleted worktrees and mark them as deleted in the database
-async fn check_externally_deleted_worktrees(pool: &sqlx::SqlitePool) {
-    let active_attempts = match sqlx::query!(
+async fn check_externally_deleted_worktrees(pool: &DbPool) {
+    let active_attempts = match db::query_as!(
+        ActiveWorktree,
         r#"SELECT id as "id!: Uuid", worktree_path FROM task_attempts WHERE worktree_deleted = FALSE"#
     )
     .fetch_all(pool)
@@ -364,7 +765,7 @@ async fn check_externally_deleted_worktrees(pool: &sqlx::SqlitePool) {
 }
 
 /// Find and delete orphaned worktrees that don't correspond to any task attempts
-async fn cleanup_orphaned_worktrees(pool: &sqlx::SqlitePool) {
+async fn cleanup_orphaned_worktrees(pool: &DbPool) {
     // Check if orphan cleanup is disabled via environment variable
     if std::env::var("DISABLE_WORKTREE_ORPHAN_CLEANUP").is_ok() {
         tracing::debug!("Orphan worktree cleanup is disabled via DISABLE_WORKTREE_ORPHAN_CLEANUP environment variable");
@@ -416,15 +817,16 @@ async fn cleanup_orphaned_worktrees(pool: &sqlx::SqlitePool) {
         let worktree_path_str = path.to_string_lossy().to_string();
         checked_count += 1;
 
-        // Check if this worktree path exists in the database
-        let exists_in_db = match sqlx::query!(
-            "SELECT COUNT(*) as count FROM task_attempts WHERE worktree_path = ?",
+        // Check if this worktree path exists in the database, or is the workspace holding the
+        // checkouts of a multi-repository attempt
+        let exists_in_db = match db::query_scalar!(
+            "SELECT COUNT(*) FROM task_attempts WHERE worktree_path = $1 OR substr(worktree_path, 1, length($1) + 1) = $1 || '/'",
             worktree_path_str
         )
         .fetch_one(pool)
         .await
         {
-            Ok(row) => row.count > 0,
+            Ok(count) => count > 0,
             Err(e) => {
                 tracing::error!(
                     "Failed to check database for worktree path {}: {}",
@@ -511,6 +913,159 @@ async fn cleanup_orphaned_worktree_directory(
     Ok(())
 }
 
+/// Record the outcome of every execution that has exited since the last check and
+/// kick off whatever comes next (coding agent after setup, cleanup, finalization)
+pub async fn handle_completed_executions(app_state: &AppState) {
+    let completed_executions = app_state.get_running_executions_for_monitor().await;
+
+    // Handle completed executions
+    for finished in completed_executions {
+        let FinishedExecution {
+            execution_id: execution_process_id,
+            task_attempt_id,
+            success,
+            exit_code,
+            timed_out,
+            limit_exceeded,
+            cancelled,
+        } = finished;
+        let status_text = if success {
+            "completed successfully"
+        } else if cancelled {
+            "was stopped"
+        } else if timed_out {
+            "timed out"
+        } else if limit_exceeded {
+            "went over its resource limits"
+        } else {
+            "failed"
+        };
+        let exit_text = if let Some(code) = exit_code {
+            format!(" with exit code {}", code)
+        } else {
+            String::new()
+        };
+
+        tracing::info!(
+            "Execution {} {}{}",
+            execution_process_id,
+            status_text,
+            exit_text
+        );
+
+        // Its last lines may still be on their way to the database
+        command_runner::wait_for_output_writers(execution_process_id, OUTPUT_DRAIN_TIMEOUT).await;
+        environment_variables::forget_redactions(execution_process_id);
+
+        // Update the execution process record
+        let execution_status = if success {
+            ExecutionProcessStatus::Completed
+        } else if cancelled {
+            ExecutionProcessStatus::Killed
+        } else if timed_out {
+            ExecutionProcessStatus::TimedOut
+        } else if limit_exceeded {
+            ExecutionProcessStatus::ResourceLimitExceeded
+        } else {
+            ExecutionProcessStatus::Failed
+        };
+
+        if let Err(e) = ExecutionProcess::update_completion(
+            &app_state.db_pool,
+            execution_process_id,
+            execution_status,
+            exit_code,
+        )
+        .await
+        {
+            tracing::error!(
+                "Failed to update execution process {} completion: {}",
+                execution_process_id,
+                e
+            );
+        }
+
+        // A stopped execution has nothing follow it, as when it's stopped while running
+        if cancelled {
+            continue;
+        }
+
+        // Get the execution process to determine next steps
+        if let Ok(Some(mut execution_process)) =
+            ExecutionProcess::find_by_id(&app_state.db_pool, execution_process_id).await
+        {
+            if execution_process.status == ExecutionProcessStatus::Failed {
+                execution_process.failure_reason =
+                    record_failure_reason(app_state, &execution_process).await;
+            }
+            match execution_process.process_type {
+                ExecutionProcessType::SetupScript => {
+                    handle_setup_completion(app_state, task_attempt_id, execution_process, success)
+                        .await;
+                }
+                ExecutionProcessType::CleanupScript => {
+                    handle_cleanup_completion(
+                        app_state,
+                        task_attempt_id,
+                        execution_process_id,
+                        execution_process,
+                        success,
+                        exit_code,
+                    )
+                    .await;
+                }
+                ExecutionProcessType::CodingAgent => {
+                    let verifying = handle_coding_agent_completion(
+                        app_state,
+                        task_attempt_id,
+                        execution_process_id,
+                        execution_process,
+                        success,
+                        exit_code,
+                    )
+                    .await;
+                    // Its verification can still be stopped until it's done
+                    if verifying {
+                        continue;
+                    }
+                }
+                ExecutionProcessType::DevServer => {
+                    handle_dev_server_completion(
+                        app_state,
+                        task_attempt_id,
+                        execution_process_id,
+                        execution_process,
+                        success,
+                        exit_code,
+                    )
+                    .await;
+                }
+            }
+        } else {
+            tracing::error!(
+                "Failed to find execution process {} for completion handling",
+                execution_process_id
+            );
+        }
+        app_state.forget_cancellation(execution_process_id).await;
+    }
+}
+
+/// Keep what the running executions are using, for their resource history
+async fn record_resource_samples(app_state: &AppState) {
+    for (execution_id, point) in app_state.sample_running_resources().await {
+        if let Err(e) =
+            ExecutionResources::record_sample(&app_state.db_pool, execution_id, &point).await
+        {
+            tracing::warn!(
+                "Failed to record resource sample of execution {}: {}",
+                execution_id,
+                e
+            );
+        }
+    }
+}
+
 pub async fn execution_monitor(app_state: AppState) {
     let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(5));
     let mut cleanup_interval = tokio::time::interval(tokio::time::Duration::from_secs(1800)); // 30 minutes
@@ -519,105 +1074,21 @@ pub async fn execution_monitor(app_state: AppState) {
         tokio::select! {
             _ = interval.tick() => {
                 // Check for completed processes FIRST to avoid race conditions
-                let completed_executions = app_state.get_running_executions_for_monitor().await;
-
-                // Handle completed executions
-                for (execution_process_id, task_attempt_id, success, exit_code) in completed_executions {
-                    let status_text = if success {
-                        "completed successfully"
-                    } else {
-                        "failed"
-                    };
-                    let exit_text = if let Some(code) = exit_code {
-                        format!(" with exit code {}", code)
-                    } else {
-                        String::new()
-                    };
-
-                    tracing::info!(
-                        "Execution {} {}{}",
-                        execution_process_id,
-                        status_text,
-                        exit_text
-                    );
+                handle_completed_executions(&app_state).await;
 
-                    // Update the execution process record
-                    let execution_status = if success {
-                        ExecutionProcessStatus::Completed
-                    } else {
-                        ExecutionProcessStatus::Failed
-                    };
+                record_resource_samples(&app_state).await;
 
-                    if let Err(e) = ExecutionProcess::update_completion(
-                        &app_state.db_pool,
-                        execution_process_id,
-                        execution_status,
-                        exit_code,
-                    )
-                    .await
-                    {
-                        tracing::error!(
-                            "Failed to update execution process {} completion: {}",
-                            execution_process_id,
-                            e
-                        );
-                    }
+                resume_interrupted_attempts(&app_state).await;
 
-                    // Get the execution process to determine next steps
-                    if let Ok(Some(execution_process)) =
-                        ExecutionProcess::find_by_id(&app_state.db_pool, execution_process_id).await
-                    {
-                        match execution_process.process_type {
-                            ExecutionProcessType::SetupScript => {
-                                handle_setup_completion(
-                                    &app_state,
-                                    task_attempt_id,
-                                    execution_process,
-                                    success,
-                                )
-                                .await;
-                            }
-                            ExecutionProcessType::CleanupScript => {
-                                handle_cleanup_completion(
-                                    &app_state,
-                                    task_attempt_id,
-                                    execution_process_id,
-                                    execution_process,
-                                    success,
-                                    exit_code,
-                                )
-                                .await;
-                            }
-                            ExecutionProcessType::CodingAgent => {
-                                handle_coding_agent_completion(
-                                    &app_state,
-                                    task_attempt_id,
-                                    execution_process_id,
-                                    execution_process,
-                                    success,
-                                    exit_code,
-                                )
-                                .await;
-                            }
-                            ExecutionProcessType::DevServer => {
-                                handle_dev_server_completion(
-                                    &app_state,
-                                    task_attempt_id,
-                                    execution_process_id,
-                                    execution_process,
-                                    success,
-                                    exit_code,
-                                )
-                                .await;
-                            }
-                        }
-                    } else {
-                        tracing::error!(
-                            "Failed to find execution process {} for completion handling",
-                            execution_process_id
-                        );
-                    }
-                }
+                resume_pending_fixes(&app_state).await;
+
+                retry_failed_spawns(&app_state).await;
+
+                retry_rate_limited(&app_state).await;
+
+                stop_finished_tasks_dev_servers(&app_state).await;
+
+                execution_queue::dispatch_queued(&app_state).await;
 
                 // Check for orphaned execution processes AFTER handling completions
                 // Add a small delay to ensure completed processes are properly handled first
@@ -724,24 +1195,25 @@ pub async fn execution_monitor(app_state: AppState) {
                             tracing::debug!("No expired worktrees found");
                         } else {
                             tracing::info!("Found {} expired worktrees to clean up", expired_attempts.len());
-                            for (attempt_id, worktree_path, git_repo_path) in expired_attempts {
-                                if let Err(e) = delete_worktree(&worktree_path, &git_repo_path, attempt_id).await {
-                                    tracing::error!("Failed to cleanup expired worktree {}: {}", attempt_id, e);
-                                } else {
-                                    // Mark worktree as deleted in database after successful cleanup
-                                    if let Err(e) = crate::models::task_attempt::TaskAttempt::mark_worktree_deleted(&app_state.db_pool, attempt_id).await {
-                                        tracing::error!("Failed to mark worktree as deleted in database for attempt {}: {}", attempt_id, e);
-                                    } else {
-                                        tracing::info!("Successfully marked worktree as deleted for attempt {}", attempt_id);
-                                    }
-                                }
-                            }
+                            delete_attempt_worktrees(&app_state.db_pool, expired_attempts).await;
                         }
                     }
                     Err(e) => {
                         tracing::error!("Failed to query expired task attempts: {}", e);
                     }
                 }
+
+                // Finished attempts don't need their worktree for as long
+                match TaskAttempt::find_finished_for_cleanup(&app_state.db_pool, None, FINISHED_WORKTREE_GRACE).await {
+                    Ok(finished_attempts) if !finished_attempts.is_empty() => {
+                        tracing::info!("Found {} worktrees of finished attempts to clean up", finished_attempts.len());
+                        delete_attempt_worktrees(&app_state.db_pool, finished_attempts).await;
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        tracing::error!("Failed to query finished task attempts: {}", e);
+                    }
+                }
             }
         }
     }
@@ -805,8 +1277,31 @@ async fn handle_setup_completion(
         }
     } else {
         // Setup failed, update task status
-
-        // Update task status to InReview since setup failed
+        if let Err(e) = TaskScheduleRun::finish_by_attempt(
+            &app_state.db_pool,
+            task_attempt_id,
+            false,
+            Some("The setup script failed"),
+        )
+        .await
+        {
+            tracing::error!(
+                "Failed to record the scheduled run of attempt {}: {}",
+                task_attempt_id,
+                e
+            );
+        }
+        if let Err(e) =
+            BenchmarkResult::finish_by_attempt(&app_state.db_pool, task_attempt_id).await
+        {
+            tracing::error!(
+                "Failed to record the benchmark result of attempt {}: {}",
+                task_attempt_id,
+                e
+            );
+        }
+
+        // Update task status to InReview since setup failed
         if let Ok(Some(task_attempt)) =
             TaskAttempt::find_by_id(&app_state.db_pool, task_attempt_id).await
         {
@@ -831,6 +1326,8 @@ async fn handle_setup_completion(
 }
 
 /// Handle coding agent completion
+/// Returns whether the run's verification carries on in the background, which forgets the
+/// run's cancellation token once it's done
 async fn handle_coding_agent_completion(
     app_state: &AppState,
     task_attempt_id: Uuid,
@@ -838,117 +1335,970 @@ async fn handle_coding_agent_completion(
     execution_process: ExecutionProcess,
     success: bool,
     exit_code: Option<i64>,
+) -> bool {
+    if !success && defer_rate_limit(app_state, task_attempt_id, &execution_process).await {
+        return false;
+    }
+    if !success && defer_failed_start(app_state, &execution_process).await {
+        return false;
+    }
+    if !success && defer_network_failure(app_state, task_attempt_id, &execution_process).await {
+        return false;
+    }
+    // Stopping the run now aborts what's left of it: the commit, push and verification
+    let cancellation = app_state.cancellation(execution_process_id).await;
+
+    let entries = execution_process
+        .executor_type
+        .as_deref()
+        .and_then(|executor| executor.parse::<ExecutorConfig>().ok())
+        .map(|config| normalized_entries(&config, &execution_process))
+        .unwrap_or_default();
+    // The agent's closing message, for the commit message to draw on
+    let summary = run_summary::final_message(&entries).or_else(|| {
+        execution_process
+            .stdout
+            .as_deref()
+            .and_then(crate::executor::parse_assistant_message_from_logs)
+    });
+
+    if success {
+        record_plan(app_state, task_attempt_id, &execution_process).await;
+    }
+    record_decomposition(app_state, task_attempt_id, &execution_process, success).await;
+    record_conversation_context(app_state, task_attempt_id, &execution_process).await;
+    record_execution_metrics(app_state, task_attempt_id, &execution_process).await;
+    record_tool_usage(app_state, task_attempt_id, &execution_process).await;
+
+    // Note: Notifications and status updates moved to cleanup completion handler
+    // to ensure they only fire after all processing (including cleanup) is complete
+
+    // Get task attempt to access worktree path for committing changes
+    if let Ok(Some(task_attempt)) =
+        TaskAttempt::find_by_id(&app_state.db_pool, task_attempt_id).await
+    {
+        // Commit any unstaged changes after execution completion
+        let context =
+            commit_message_context(app_state, &task_attempt, CommitSource::CodingAgent, summary)
+                .await;
+        commit_and_push_execution(
+            app_state,
+            &task_attempt,
+            execution_process_id,
+            context,
+            success,
+            &cancellation,
+        )
+        .await;
+        capture_execution_diff(
+            &app_state.db_pool,
+            execution_process_id,
+            &task_attempt.worktree_path,
+        )
+        .await;
+        record_outcome(&app_state.db_pool, execution_process_id, &entries).await;
+
+        // Coding agent execution completed
+        tracing::info!(
+            "Task attempt {} set to paused after coding agent completion",
+            task_attempt_id
+        );
+
+        if let Ok(Some(task)) = Task::find_by_id(&app_state.db_pool, task_attempt.task_id).await {
+            let mut project = Project::find_by_id(&app_state.db_pool, task.project_id)
+                .await
+                .ok()
+                .flatten();
+            if let Some(project) = project.as_mut() {
+                if let Err(e) = repo_config::apply(project) {
+                    tracing::warn!(
+                        "Ignoring the repository config of project {}: {}",
+                        project.id,
+                        e
+                    );
+                }
+            }
+            // A task made from a template is verified with the template's commands
+            let task_script = TaskDefaults::find_by_task_id(&app_state.db_pool, task.id)
+                .await
+                .ok()
+                .flatten()
+                .and_then(|defaults| defaults.verification_script);
+            let verification_script = task_script
+                .or_else(|| {
+                    project
+                        .as_ref()
+                        .and_then(|project| project.verification_script.clone())
+                })
+                .filter(|script| !verification::parse_commands(script).is_empty());
+            // A plan waiting for approval hasn't changed anything worth verifying yet, and
+            // a decomposition never does
+            let awaiting_approval =
+                AttemptPlan::find_by_task_attempt_id(&app_state.db_pool, task_attempt_id)
+                    .await
+                    .ok()
+                    .flatten()
+                    .is_some_and(|plan| plan.status == AttemptPlanStatus::Pending);
+            let decomposed =
+                TaskDecomposition::find_by_task_attempt_id(&app_state.db_pool, task_attempt_id)
+                    .await
+                    .ok()
+                    .flatten()
+                    .is_some();
+
+            match verification_script {
+                Some(script)
+                    if success
+                        && !awaiting_approval
+                        && !decomposed
+                        && !cancellation.is_cancelled() =>
+                {
+                    // Verification can take as long as a test suite does, so it runs apart
+                    // from the monitor loop; the attempt counts as running meanwhile
+                    app_state.start_verification(task_attempt_id).await;
+                    let app_state = app_state.clone();
+                    tokio::spawn(async move {
+                        let commands = verification::parse_commands(&script);
+                        let passed = verification::run(
+                            &app_state,
+                            &task,
+                            &task_attempt,
+                            execution_process_id,
+                            &commands,
+                            &cancellation,
+                        )
+                        .await;
+                        // An automatic fix carries the attempt on; its own run is verified
+                        // and cleaned up after in turn
+                        let fixing = !passed
+                            && !cancellation.is_cancelled()
+                            && verification::start_fix(
+                                &app_state,
+                                &task,
+                                &task_attempt,
+                                execution_process_id,
+                            )
+                            .await;
+                        if !fixing {
+                            run_cleanup_or_finalize(
+                                &app_state,
+                                &task_attempt,
+                                &task,
+                                project.as_ref(),
+                                success,
+                                exit_code,
+                                &cancellation,
+                            )
+                            .await;
+                        }
+                        app_state.finish_verification(task_attempt_id).await;
+                        app_state.forget_cancellation(execution_process_id).await;
+                    });
+                    return true;
+                }
+                _ => {
+                    run_cleanup_or_finalize(
+                        app_state,
+                        &task_attempt,
+                        &task,
+                        project.as_ref(),
+                        success,
+                        exit_code,
+                        &cancellation,
+                    )
+                    .await;
+                }
+            }
+        }
+    } else {
+        tracing::error!(
+            "Failed to find task attempt {} for coding agent completion",
+            task_attempt_id
+        );
+    }
+    false
+}
+
+/// Run the project's cleanup script after a coding agent, or if it has none or the agent's
+/// run was cancelled, finalize the task right away
+async fn run_cleanup_or_finalize(
+    app_state: &AppState,
+    task_attempt: &TaskAttempt,
+    task: &Task,
+    project: Option<&Project>,
+    success: bool,
+    exit_code: Option<i64>,
+    cancellation: &CancellationToken,
 ) {
-    // Extract and store assistant message from execution logs
-    let summary = if let Some(stdout) = &execution_process.stdout {
-        if let Some(assistant_message) = crate::executor::parse_assistant_message_from_logs(stdout)
+    let should_run_cleanup = !cancellation.is_cancelled()
+        && project
+            .and_then(|project| project.cleanup_script.as_ref())
+            .is_some_and(|script| !script.trim().is_empty());
+    if !should_run_cleanup {
+        finalize_task_completion(app_state, task_attempt.id, task, success, exit_code).await;
+        return;
+    }
+
+    // Completion is handled by the cleanup completion handler
+    if let Err(e) = ProcessService::run_cleanup_script_if_configured(
+        &app_state.db_pool,
+        app_state,
+        task_attempt.id,
+        task_attempt.task_id,
+        task.project_id,
+    )
+    .await
+    {
+        tracing::error!(
+            "Failed to run cleanup script for attempt {}: {}",
+            task_attempt.id,
+            e
+        );
+        // Even if cleanup fails to start, finalize the task
+        finalize_task_completion(app_state, task_attempt.id, task, success, exit_code).await;
+    }
+}
+
+/// Keep the plan a planning run ended with for review: the plan it presented in its
+/// executor's plan mode, or for a run asked for a plan in its prompt, its final reply. A run
+/// that ended without one leaves nothing to approve, so an older pending plan is dropped.
+async fn record_plan(
+    app_state: &AppState,
+    task_attempt_id: Uuid,
+    execution_process: &ExecutionProcess,
+) {
+    let plans_natively = execution_process
+        .executor_type
+        .as_deref()
+        .and_then(|executor| executor.parse::<ExecutorConfig>().ok())
+        .is_some_and(|config| config.implementation_executor().is_some());
+    let plan = if plans_natively {
+        plan_review::plan_from_process(execution_process)
+    } else {
+        let asks_for_plan = match TaskAttempt::find_by_id(&app_state.db_pool, task_attempt_id).await
         {
-            if let Err(e) = crate::models::executor_session::ExecutorSession::update_summary(
+            Ok(Some(attempt)) => plan_review::asks_for_plan(&app_state.db_pool, &attempt)
+                .await
+                .unwrap_or(false),
+            _ => false,
+        };
+        if !asks_for_plan {
+            return;
+        }
+        plan_review::plan_from_reply(execution_process)
+    };
+
+    let result = match plan {
+        Some(plan) => {
+            AttemptPlan::propose(
                 &app_state.db_pool,
-                execution_process_id,
-                &assistant_message,
+                task_attempt_id,
+                execution_process.id,
+                &plan,
             )
             .await
-            {
+        }
+        None => AttemptPlan::discard_pending(&app_state.db_pool, task_attempt_id)
+            .await
+            .map(|_| ()),
+    };
+    if let Err(e) = result {
+        tracing::error!(
+            "Failed to record the plan of attempt {}: {}",
+            task_attempt_id,
+            e
+        );
+    }
+}
+
+/// Turn the steps a decomposition run proposed into subtasks of its task: the tasks it
+/// handed off, or failing that, the steps of its plan. The steps stand in for the plan, so
+/// the plan is dropped rather than left waiting for approval.
+async fn record_decomposition(
+    app_state: &AppState,
+    task_attempt_id: Uuid,
+    execution_process: &ExecutionProcess,
+    success: bool,
+) {
+    let pool = &app_state.db_pool;
+    let decomposition =
+        match TaskDecomposition::find_by_task_attempt_id(pool, task_attempt_id).await {
+            Ok(Some(decomposition)) if decomposition.status == TaskDecompositionStatus::Pending => {
+                decomposition
+            }
+            Ok(_) => return,
+            Err(e) => {
                 tracing::error!(
-                    "Failed to update summary for execution process {}: {}",
-                    execution_process_id,
+                    "Failed to load the decomposition of attempt {}: {}",
+                    task_attempt_id,
                     e
                 );
-                None
-            } else {
-                tracing::info!(
-                    "Successfully stored summary for execution process {}",
-                    execution_process_id
-                );
-                Some(assistant_message)
+                return;
             }
+        };
+
+    let plan = AttemptPlan::find_by_task_attempt_id(pool, task_attempt_id)
+        .await
+        .ok()
+        .flatten();
+    if let Err(e) = AttemptPlan::discard_pending(pool, task_attempt_id).await {
+        tracing::error!(
+            "Failed to drop the plan of decomposition attempt {}: {}",
+            task_attempt_id,
+            e
+        );
+    }
+
+    let steps = if success {
+        let entries = execution_process
+            .executor_type
+            .as_deref()
+            .and_then(|executor| executor.parse::<ExecutorConfig>().ok())
+            .map(|config| normalized_entries(&config, execution_process))
+            .unwrap_or_default();
+        let handed_off = task_decomposition::subtasks_from_entries(&entries);
+        if handed_off.is_empty() {
+            plan.map(|plan| task_decomposition::subtasks_from_plan(&plan.content))
+                .unwrap_or_default()
         } else {
-            None
+            handed_off
         }
     } else {
-        None
+        Vec::new()
     };
 
-    // Note: Notifications and status updates moved to cleanup completion handler
-    // to ensure they only fire after all processing (including cleanup) is complete
+    let mut created = 0;
+    if let Ok(Some(task)) = Task::find_by_id(pool, decomposition.task_id).await {
+        for step in steps {
+            let subtask = CreateTask {
+                project_id: task.project_id,
+                title: step.title,
+                description: step.description,
+                parent_task_attempt: None,
+                parent_task_id: Some(task.id),
+            };
+            match Task::create(pool, &subtask, Uuid::new_v4()).await {
+                Ok(_) => created += 1,
+                Err(e) => tracing::error!("Failed to create a subtask of task {}: {}", task.id, e),
+            }
+        }
+    }
 
-    // Get task attempt to access worktree path for committing changes
-    if let Ok(Some(task_attempt)) =
-        TaskAttempt::find_by_id(&app_state.db_pool, task_attempt_id).await
+    let status = if created > 0 {
+        TaskDecompositionStatus::Completed
+    } else {
+        TaskDecompositionStatus::Failed
+    };
+    if let Err(e) = TaskDecomposition::finish(pool, task_attempt_id, status, created).await {
+        tracing::error!(
+            "Failed to record the decomposition of attempt {}: {}",
+            task_attempt_id,
+            e
+        );
+    }
+}
+
+/// Keep the run's conversation, so a follow-up that can't resume its session (the executor
+/// has none, or the attempt is handed over to another) can be given it back as a transcript
+async fn record_conversation_context(
+    app_state: &AppState,
+    task_attempt_id: Uuid,
+    execution_process: &ExecutionProcess,
+) {
+    let Some(config) = execution_process
+        .executor_type
+        .as_deref()
+        .and_then(|executor| executor.parse::<ExecutorConfig>().ok())
+    else {
+        return;
+    };
+
+    let entries = normalized_entries(&config, execution_process);
+    let prompt =
+        ExecutorSession::find_by_execution_process_id(&app_state.db_pool, execution_process.id)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|session| session.prompt);
+
+    if let Err(e) = ConversationContext::record(
+        &app_state.db_pool,
+        execution_process.id,
+        task_attempt_id,
+        prompt.as_deref(),
+        &entries,
+    )
+    .await
     {
-        // Commit any unstaged changes after execution completion
-        if let Err(e) = commit_execution_changes(
-            &task_attempt.worktree_path,
+        tracing::error!(
+            "Failed to store the conversation of execution {}: {}",
+            execution_process.id,
+            e
+        );
+    }
+}
+
+/// Store why a failed run failed, where its output tells
+async fn record_failure_reason(
+    app_state: &AppState,
+    execution_process: &ExecutionProcess,
+) -> Option<FailureReason> {
+    let entries = execution_process
+        .executor_type
+        .as_deref()
+        .and_then(|executor| executor.parse::<ExecutorConfig>().ok())
+        .map(|config| normalized_entries(&config, execution_process))
+        .unwrap_or_default();
+    let reason = failure_reasons::detect(
+        execution_process.stdout.as_deref(),
+        execution_process.stderr.as_deref(),
+        &entries,
+    )?;
+
+    tracing::info!(
+        "Execution {} failed: {}",
+        execution_process.id,
+        reason.label()
+    );
+    if let Err(e) =
+        ExecutionProcess::set_failure_reason(&app_state.db_pool, execution_process.id, reason).await
+    {
+        tracing::error!(
+            "Failed to store why execution {} failed: {}",
+            execution_process.id,
+            e
+        );
+    }
+    Some(reason)
+}
+
+/// Store the token and cost figures the run's executor reported, if any
+async fn record_execution_metrics(
+    app_state: &AppState,
+    task_attempt_id: Uuid,
+    execution_process: &ExecutionProcess,
+) {
+    let Some(config) = execution_process
+        .executor_type
+        .as_deref()
+        .and_then(|executor| executor.parse::<ExecutorConfig>().ok())
+    else {
+        return;
+    };
+    let Some(usage) = UsageMetrics::total(&normalized_entries(&config, execution_process)) else {
+        return;
+    };
+
+    if let Err(e) = ExecutionMetrics::record(
+        &app_state.db_pool,
+        execution_process.id,
+        task_attempt_id,
+        &usage,
+    )
+    .await
+    {
+        tracing::error!(
+            "Failed to store the usage of execution {}: {}",
+            execution_process.id,
+            e
+        );
+    }
+}
+
+/// The run's output normalized by its executor
+/// Store what the run did with its tools, so a run that changed nothing stands out
+async fn record_tool_usage(
+    app_state: &AppState,
+    task_attempt_id: Uuid,
+    execution_process: &ExecutionProcess,
+) {
+    let Some(config) = execution_process
+        .executor_type
+        .as_deref()
+        .and_then(|executor| executor.parse::<ExecutorConfig>().ok())
+    else {
+        return;
+    };
+    let usage = ExecutionToolUsage::count(
+        execution_process.id,
+        task_attempt_id,
+        &normalized_entries(&config, execution_process),
+    );
+
+    if let Err(e) = usage.record(&app_state.db_pool).await {
+        tracing::error!(
+            "Failed to store the tool usage of execution {}: {}",
+            execution_process.id,
+            e
+        );
+    }
+}
+
+fn normalized_entries(
+    config: &ExecutorConfig,
+    execution_process: &ExecutionProcess,
+) -> Vec<NormalizedEntry> {
+    let working_dir = std::fs::canonicalize(&execution_process.working_directory)
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|_| execution_process.working_directory.clone());
+    execution_process
+        .stdout
+        .as_deref()
+        .and_then(|stdout| {
+            config
+                .create_executor()
+                .normalize_output(stdout, &working_dir)
+                .ok()
+        })
+        .map(|conversation| conversation.entries)
+        .unwrap_or_default()
+}
+
+/// If a failed run was cut off by the network and its session can be resumed, record the
+/// interruption and leave the attempt waiting for connectivity instead of failing it.
+/// Returns true if the failure was deferred.
+async fn defer_network_failure(
+    app_state: &AppState,
+    task_attempt_id: Uuid,
+    execution_process: &ExecutionProcess,
+) -> bool {
+    let Some(error) = network_recovery::detect_network_failure(
+        execution_process.stdout.as_deref(),
+        execution_process.stderr.as_deref(),
+    ) else {
+        return false;
+    };
+
+    let resumable =
+        ExecutorSession::find_by_execution_process_id(&app_state.db_pool, execution_process.id)
+            .await
+            .ok()
+            .flatten()
+            .is_some_and(|session| session.session_id.is_some());
+    if !resumable {
+        tracing::info!(
+            "Execution {} lost its network connection but has no session to resume",
+            execution_process.id
+        );
+        return false;
+    }
+
+    let previous =
+        NetworkInterruption::find_by_task_attempt_id(&app_state.db_pool, task_attempt_id)
+            .await
+            .unwrap_or_default();
+    if previous.len() >= network_recovery::MAX_NETWORK_RESUMES {
+        tracing::warn!(
+            "Attempt {} lost its network connection {} times, failing it",
             task_attempt_id,
-            summary.as_deref(),
-        )
-        .await
-        {
+            previous.len() + 1
+        );
+        return false;
+    }
+
+    match NetworkInterruption::create(
+        &app_state.db_pool,
+        task_attempt_id,
+        execution_process.id,
+        &error,
+    )
+    .await
+    {
+        Ok(_) => {
+            tracing::warn!(
+                "Execution {} for attempt {} lost its network connection ({}), will resume when connectivity returns",
+                execution_process.id,
+                task_attempt_id,
+                error
+            );
+            true
+        }
+        Err(e) => {
             tracing::error!(
-                "Failed to commit execution changes for attempt {}: {}",
+                "Failed to record network interruption for attempt {}: {}",
                 task_attempt_id,
                 e
             );
-        } else {
-            tracing::info!(
-                "Successfully committed execution changes for attempt {}",
-                task_attempt_id
-            );
+            false
         }
+    }
+}
 
-        // Coding agent execution completed
-        tracing::info!(
-            "Task attempt {} set to paused after coding agent completion",
-            task_attempt_id
+/// A coding agent its provider's rate limit stopped waits for the limit to reset and then
+/// goes on, instead of failing the attempt. Returns true if the failure was deferred.
+async fn defer_rate_limit(
+    app_state: &AppState,
+    task_attempt_id: Uuid,
+    execution_process: &ExecutionProcess,
+) -> bool {
+    if execution_process.failure_reason != Some(FailureReason::RateLimited) {
+        return false;
+    }
+    let previous = ExecutionProcess::count_rate_limited(&app_state.db_pool, task_attempt_id)
+        .await
+        .unwrap_or_default();
+    if previous >= rate_limits::MAX_RATE_LIMIT_RETRIES {
+        tracing::warn!(
+            "Attempt {} was rate limited {} times, failing it",
+            task_attempt_id,
+            previous + 1
         );
+        return false;
+    }
 
-        // Run cleanup script if configured, otherwise immediately finalize task
-        if let Ok(Some(task)) = Task::find_by_id(&app_state.db_pool, task_attempt.task_id).await {
-            // Check if cleanup script should run
-            let should_run_cleanup = if let Ok(Some(project)) =
-                crate::models::project::Project::find_by_id(&app_state.db_pool, task.project_id)
-                    .await
-            {
-                project
-                    .cleanup_script
-                    .as_ref()
-                    .map(|script| !script.trim().is_empty())
-                    .unwrap_or(false)
-            } else {
-                false
+    let now = chrono::Utc::now();
+    let advertised = rate_limits::advertised_retry_after(
+        execution_process.stdout.as_deref(),
+        execution_process.stderr.as_deref(),
+        now,
+    );
+    let wait = rate_limits::cooldown(advertised, previous);
+    let retry_at = now + chrono::Duration::from_std(wait).unwrap_or(chrono::Duration::zero());
+    match ExecutionProcess::schedule_rate_limit_retry(
+        &app_state.db_pool,
+        execution_process.id,
+        retry_at,
+    )
+    .await
+    {
+        Ok(()) => {
+            tracing::warn!(
+                "Execution {} for attempt {} was rate limited, going on in {}s",
+                execution_process.id,
+                task_attempt_id,
+                wait.as_secs()
+            );
+            true
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to schedule rate-limited execution {} to go on: {}",
+                execution_process.id,
+                e
+            );
+            false
+        }
+    }
+}
+
+/// A coding agent that exited with an error as soon as it started is retried after a
+/// backoff instead of failing the attempt, while the configured policy allows
+async fn defer_failed_start(app_state: &AppState, execution_process: &ExecutionProcess) -> bool {
+    if !spawn_retry::failed_on_start(execution_process) {
+        return false;
+    }
+    let reason = match execution_process.exit_code {
+        Some(code) => format!("exited with code {} right after starting", code),
+        None => "exited right after starting".to_string(),
+    };
+    ProcessService::defer_spawn_failure(
+        app_state,
+        execution_process.id,
+        execution_process.retry_count,
+        &reason,
+    )
+    .await
+}
+
+/// Stop the dev servers still running for tasks that are done or cancelled
+pub async fn stop_finished_tasks_dev_servers(app_state: &AppState) {
+    match ExecutionProcess::find_running_dev_servers_of_finished_tasks(&app_state.db_pool).await {
+        Ok(dev_servers) => ProcessService::stop_dev_servers(app_state, dev_servers).await,
+        Err(e) => tracing::error!("Failed to find dev servers of finished tasks: {}", e),
+    }
+}
+
+/// Start again every execution whose retry is due. One that still can't be started
+/// fails its attempt.
+pub async fn retry_failed_spawns(app_state: &AppState) {
+    let due = match ExecutionProcess::find_due_retries(&app_state.db_pool).await {
+        Ok(due) => due,
+        Err(e) => {
+            tracing::error!("Failed to query executions waiting to be retried: {}", e);
+            return;
+        }
+    };
+
+    for process in due {
+        if let Err(e) = ProcessService::retry_coding_agent(app_state, &process).await {
+            tracing::error!(
+                "Execution {} still failed to start after {} retries: {}",
+                process.id,
+                process.retry_count + 1,
+                e
+            );
+            let Ok(Some(task_attempt)) =
+                TaskAttempt::find_by_id(&app_state.db_pool, process.task_attempt_id).await
+            else {
+                continue;
             };
+            if let Ok(Some(task)) = Task::find_by_id(&app_state.db_pool, task_attempt.task_id).await
+            {
+                finalize_task_completion(app_state, task_attempt.id, &task, false, None).await;
+            }
+        }
+    }
+}
+
+/// Let every rate-limited execution whose wait is over go on: in a follow-up where its
+/// session can be resumed, otherwise by starting it again. One that can't fails its attempt.
+pub async fn retry_rate_limited(app_state: &AppState) {
+    let due = match ExecutionProcess::find_due_rate_limit_retries(&app_state.db_pool).await {
+        Ok(due) => due,
+        Err(e) => {
+            tracing::error!("Failed to query rate-limited executions: {}", e);
+            return;
+        }
+    };
 
-            if should_run_cleanup {
-                // Run cleanup script - completion will be handled in cleanup completion handler
+    for process in due {
+        let attempt_id = process.task_attempt_id;
+        let Ok(Some(task_attempt)) = TaskAttempt::find_by_id(&app_state.db_pool, attempt_id).await
+        else {
+            continue;
+        };
+        let Ok(Some(task)) = Task::find_by_id(&app_state.db_pool, task_attempt.task_id).await
+        else {
+            continue;
+        };
+        let resumable =
+            ExecutorSession::find_by_execution_process_id(&app_state.db_pool, process.id)
+                .await
+                .ok()
+                .flatten()
+                .is_some_and(|session| session.session_id.is_some());
+
+        tracing::info!("Rate limit over, going on with attempt {}", attempt_id);
+        let result = if resumable {
+            if let Err(e) = ExecutionProcess::clear_retry(&app_state.db_pool, process.id).await {
+                tracing::error!(
+                    "Failed to take execution {} off the rate limit wait: {}",
+                    process.id,
+                    e
+                );
+                continue;
+            }
+            ProcessService::start_followup_execution(
+                &app_state.db_pool,
+                app_state,
+                attempt_id,
+                task.id,
+                task.project_id,
+                rate_limits::RESUME_PROMPT,
+            )
+            .await
+            .map(|_| ())
+        } else {
+            ProcessService::retry_coding_agent(app_state, &process).await
+        };
+        if let Err(e) = result {
+            tracing::error!(
+                "Failed to go on with attempt {} after its rate limit: {}",
+                attempt_id,
+                e
+            );
+            finalize_task_completion(app_state, attempt_id, &task, false, None).await;
+        }
+    }
+}
+
+/// Resume every session waiting on the network once connectivity is back
+pub async fn resume_interrupted_attempts(app_state: &AppState) {
+    let open = match NetworkInterruption::find_open(&app_state.db_pool).await {
+        Ok(open) => open,
+        Err(e) => {
+            tracing::error!("Failed to query network interruptions: {}", e);
+            return;
+        }
+    };
+    if open.is_empty() || !network_recovery::connectivity_available().await {
+        return;
+    }
+
+    for interruption in open {
+        let attempt_id = interruption.task_attempt_id;
+        let Ok(Some(task_attempt)) = TaskAttempt::find_by_id(&app_state.db_pool, attempt_id).await
+        else {
+            continue;
+        };
+        let Ok(Some(task)) = Task::find_by_id(&app_state.db_pool, task_attempt.task_id).await
+        else {
+            continue;
+        };
+
+        tracing::info!("Connectivity restored, resuming attempt {}", attempt_id);
+        match ProcessService::start_followup_execution(
+            &app_state.db_pool,
+            app_state,
+            attempt_id,
+            task.id,
+            task.project_id,
+            network_recovery::RESUME_PROMPT,
+        )
+        .await
+        {
+            Ok(_) => {
                 if let Err(e) =
-                    crate::services::process_service::ProcessService::run_cleanup_script_if_configured(
-                        &app_state.db_pool,
-                        app_state,
-                        task_attempt_id,
-                        task_attempt.task_id,
-                        task.project_id,
-                    )
-                    .await
+                    NetworkInterruption::mark_resumed(&app_state.db_pool, interruption.id).await
                 {
                     tracing::error!(
-                        "Failed to run cleanup script for attempt {}: {}",
-                        task_attempt_id,
+                        "Failed to mark network interruption {} resumed: {}",
+                        interruption.id,
                         e
                     );
-                    // Even if cleanup fails to start, finalize the task
-                    finalize_task_completion(app_state, task_attempt_id, &task, success, exit_code).await;
                 }
-            } else {
-                // No cleanup script configured, immediately finalize task
-                finalize_task_completion(app_state, task_attempt_id, &task, success, exit_code)
-                    .await;
+            }
+            Err(e) => {
+                // The session can't be picked up again, so the interrupted run stands as a failure
+                tracing::error!("Failed to resume attempt {}: {}", attempt_id, e);
+                let _ =
+                    NetworkInterruption::abandon_for_attempt(&app_state.db_pool, attempt_id).await;
+                finalize_task_completion(app_state, attempt_id, &task, false, None).await;
             }
         }
-    } else {
+    }
+}
+
+/// Send the automatic fixes for failed verification that were due when the server stopped
+pub async fn resume_pending_fixes(app_state: &AppState) {
+    let pending = match VerificationFixLoop::find_pending(&app_state.db_pool).await {
+        Ok(pending) => pending,
+        Err(e) => {
+            tracing::error!("Failed to query pending automatic fixes: {}", e);
+            return;
+        }
+    };
+
+    for fix_loop in pending {
+        let attempt_id = fix_loop.task_attempt_id;
+        // Due fixes of attempts that are still verifying are sent by the verification itself
+        if app_state.has_running_execution(attempt_id).await {
+            continue;
+        }
+        let Ok(Some(task_attempt)) = TaskAttempt::find_by_id(&app_state.db_pool, attempt_id).await
+        else {
+            continue;
+        };
+        let Ok(Some(task)) = Task::find_by_id(&app_state.db_pool, task_attempt.task_id).await
+        else {
+            continue;
+        };
+
+        tracing::info!("Sending the automatic fix due for attempt {}", attempt_id);
+        if !verification::send_fix(app_state, &task, attempt_id, &fix_loop.last_failure).await {
+            finalize_task_completion(app_state, attempt_id, &task, true, None).await;
+        }
+    }
+}
+
+/// Raise the desktop notification for a finished attempt if it is turned on, and queue it
+/// for the project's webhooks. A plan waiting for approval goes through the attention
+/// channels instead.
+async fn notify_completion(
+    app_state: &AppState,
+    task: &crate::models::task::Task,
+    task_attempt: &TaskAttempt,
+    event: NotificationEvent,
+) {
+    let details = format!(
+        "Branch: {}\nExecutor: {}",
+        task_attempt.branch,
+        task_attempt.executor.as_deref().unwrap_or("default")
+    );
+    let (title, message) = match event {
+        NotificationEvent::AttemptSucceeded => (
+            format!("Task Complete: {}", task.title),
+            format!("✅ '{}' completed successfully\n{}", task.title, details),
+        ),
+        NotificationEvent::AttemptFailed => (
+            format!("Task Failed: {}", task.title),
+            format!("❌ '{}' execution failed\n{}", task.title, details),
+        ),
+        NotificationEvent::ApprovalNeeded => (
+            format!("Plan Ready: {}", task.title),
+            format!(
+                "📋 '{}' has a plan waiting for approval\n{}",
+                task.title, details
+            ),
+        ),
+        NotificationEvent::TaskStale => {
+            unreachable!("a finished run is never classified as a stale task")
+        }
+    };
+
+    // A plan waiting for review is routed, and escalated, like any other wait on the user
+    if event == NotificationEvent::ApprovalNeeded {
+        let planning_run =
+            ExecutionProcess::find_by_task_attempt_id(&app_state.db_pool, task_attempt.id)
+                .await
+                .ok()
+                .and_then(|processes| {
+                    processes
+                        .into_iter()
+                        .rev()
+                        .find(|process| process.process_type == ExecutionProcessType::CodingAgent)
+                });
+        if let Some(process) = planning_run {
+            attention::request(
+                app_state,
+                process.id,
+                task,
+                task_attempt.id,
+                AttentionKind::PlanReview,
+                &title,
+                &message,
+            )
+            .await;
+            return;
+        }
+    }
+
+    let sound_enabled = app_state.get_sound_alerts_enabled().await;
+    let push_enabled = app_state.get_push_notifications_enabled().await;
+    if (sound_enabled || push_enabled)
+        && event.is_enabled(&app_state.get_notification_events().await)
+    {
+        let sound_file = app_state.get_sound_file().await;
+        NotificationService::new(NotificationConfig {
+            sound_enabled,
+            push_enabled,
+        })
+        .notify(&title, &message, &sound_file)
+        .await;
+
+        if let Err(e) = NotificationDelivery::record_desktop(
+            &app_state.db_pool,
+            task.project_id,
+            Some(task_attempt.id),
+            event,
+            &title,
+            &message,
+        )
+        .await
+        {
+            tracing::error!(
+                "Failed to log notification for attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+        }
+    }
+
+    // Webhooks have their own per-project toggles, so they don't depend on the desktop settings
+    if let Err(e) = notification_webhooks::queue(
+        &app_state.db_pool,
+        task.project_id,
+        Some(task_attempt.id),
+        event,
+        &title,
+        &message,
+    )
+    .await
+    {
         tracing::error!(
-            "Failed to find task attempt {} for coding agent completion",
-            task_attempt_id
+            "Failed to queue webhook notifications for attempt {}: {}",
+            task_attempt.id,
+            e
         );
     }
 }
@@ -961,44 +2311,47 @@ async fn finalize_task_completion(
     success: bool,
     exit_code: Option<i64>,
 ) {
-    // Send notifications if enabled
-    let sound_enabled = app_state.get_sound_alerts_enabled().await;
-    let push_enabled = app_state.get_push_notifications_enabled().await;
-
-    if sound_enabled || push_enabled {
-        let sound_file = app_state.get_sound_file().await;
-        let notification_config = NotificationConfig {
-            sound_enabled,
-            push_enabled,
-        };
-
-        let notification_service = NotificationService::new(notification_config);
+    // A run that ended with a plan waits for approval instead of going to review
+    let awaiting_approval = success
+        && AttemptPlan::find_by_task_attempt_id(&app_state.db_pool, task_attempt_id)
+            .await
+            .ok()
+            .flatten()
+            .is_some_and(|plan| plan.status == AttemptPlanStatus::Pending);
 
-        // Get task attempt for notification details
-        if let Ok(Some(task_attempt)) =
-            TaskAttempt::find_by_id(&app_state.db_pool, task_attempt_id).await
-        {
-            let title = format!("Task Complete: {}", task.title);
-            let message = if success {
-                format!(
-                    "✅ '{}' completed successfully\nBranch: {}\nExecutor: {}",
-                    task.title,
-                    task_attempt.branch,
-                    task_attempt.executor.as_deref().unwrap_or("default")
-                )
-            } else {
-                format!(
-                    "❌ '{}' execution failed\nBranch: {}\nExecutor: {}",
-                    task.title,
-                    task_attempt.branch,
-                    task_attempt.executor.as_deref().unwrap_or("default")
-                )
-            };
+    if let Ok(Some(task_attempt)) =
+        TaskAttempt::find_by_id(&app_state.db_pool, task_attempt_id).await
+    {
+        let event = NotificationEvent::for_completion(success, awaiting_approval);
+        notify_completion(app_state, task, &task_attempt, event).await;
+    }
 
-            notification_service
-                .notify(&title, &message, &sound_file)
-                .await;
-        }
+    // Close the scheduled run that started the attempt, if it was one
+    let error = (!success).then(|| match exit_code {
+        Some(code) => format!("The coding agent exited with code {}", code),
+        None => "The coding agent failed".to_string(),
+    });
+    if let Err(e) = TaskScheduleRun::finish_by_attempt(
+        &app_state.db_pool,
+        task_attempt_id,
+        success,
+        error.as_deref(),
+    )
+    .await
+    {
+        tracing::error!(
+            "Failed to record the scheduled run of attempt {}: {}",
+            task_attempt_id,
+            e
+        );
+    }
+    // Likewise the benchmark case it ran, once its verification is in
+    if let Err(e) = BenchmarkResult::finish_by_attempt(&app_state.db_pool, task_attempt_id).await {
+        tracing::error!(
+            "Failed to record the benchmark result of attempt {}: {}",
+            task_attempt_id,
+            e
+        );
     }
 
     // Track analytics event
@@ -1015,17 +2368,17 @@ async fn finalize_task_completion(
         )
         .await;
 
-    // Update task status to InReview
-    if let Err(e) = Task::update_status(
-        &app_state.db_pool,
-        task.id,
-        task.project_id,
-        TaskStatus::InReview,
-    )
-    .await
+    let status = if awaiting_approval {
+        TaskStatus::PlanReview
+    } else {
+        TaskStatus::InReview
+    };
+    if let Err(e) =
+        Task::update_status(&app_state.db_pool, task.id, task.project_id, status.clone()).await
     {
         tracing::error!(
-            "Failed to update task status to InReview for completed attempt: {}",
+            "Failed to update task status to {:?} for completed attempt: {}",
+            status,
             e
         );
     }
@@ -1078,26 +2431,20 @@ async fn handle_cleanup_completion(
         if let Ok(Some(task_attempt)) =
             TaskAttempt::find_by_id(&app_state.db_pool, task_attempt_id).await
         {
-            let commit_message = "Cleanup script";
+            let context =
+                commit_message_context(app_state, &task_attempt, CommitSource::CleanupScript, None)
+                    .await;
 
-            if let Err(e) = commit_execution_changes(
-                &task_attempt.worktree_path,
-                task_attempt_id,
-                Some(commit_message),
+            let cancellation = app_state.cancellation(execution_process_id).await;
+            commit_and_push_execution(
+                app_state,
+                &task_attempt,
+                execution_process_id,
+                context,
+                true,
+                &cancellation,
             )
-            .await
-            {
-                tracing::error!(
-                    "Failed to commit changes after cleanup script for attempt {}: {}",
-                    task_attempt_id,
-                    e
-                );
-            } else {
-                tracing::info!(
-                    "Successfully committed changes after cleanup script for attempt {}",
-                    task_attempt_id
-                );
-            }
+            .await;
         } else {
             tracing::error!(
                 "Failed to retrieve task attempt {} for cleanup commit",
@@ -1191,3 +2538,36 @@ async fn handle_dev_server_completion(
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_uncommitted_changes_ignore_ignored_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let path = temp_dir.path().to_str().unwrap();
+        std::fs::write(temp_dir.path().join(".gitignore"), "target/\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new(".gitignore")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+            .unwrap();
+        assert!(!has_uncommitted_changes(path));
+
+        std::fs::create_dir(temp_dir.path().join("target")).unwrap();
+        std::fs::write(temp_dir.path().join("target/out"), "build").unwrap();
+        assert!(!has_uncommitted_changes(path));
+
+        std::fs::write(temp_dir.path().join("notes.md"), "half done").unwrap();
+        assert!(has_uncommitted_changes(path));
+        assert!(!has_uncommitted_changes(
+            temp_dir.path().join("missing").to_str().unwrap()
+        ));
+    }
+}
@@ -1,6 +1,7 @@
 use std::str::FromStr;
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use ts_rs::TS;
@@ -8,15 +9,29 @@ use uuid::Uuid;
 
 use crate::{
     command_runner::{CommandError, CommandProcess, CommandRunner},
+    db::DbPool,
     executors::{
-        AaaExecutor, AiderExecutor, AmpExecutor, CCRExecutor, CharmOpencodeExecutor, ClaudeExecutor,
-        CodexExecutor, EchoExecutor, GeminiExecutor, SetupScriptExecutor, SstOpencodeExecutor,
+        AaaExecutor, AcpExecutor, AiderExecutor, AmpExecutor, CCRExecutor, CharmOpencodeExecutor,
+        ClaudeExecutor, CodexExecutor, EchoExecutor, GeminiExecutor, GooseExecutor, JsonlExecutor,
+        ReplayExecutor, SetupScriptExecutor, SstOpencodeExecutor,
     },
+    models::{
+        config::Config, execution_diff::DiffSummary, execution_process::FailureReason, task::Task,
+        task_attachment::TaskAttachment, task_attempt::TaskAttempt,
+        task_decomposition::TaskDecomposition,
+    },
+    services::{
+        executor_preflight, file_mentions, plan_review, prompt_context::fill_context_variables,
+        prompt_template, task_decomposition,
+    },
+    utils::{shell::shell_quote, terminal},
 };
 
 // Constants for database streaming - fast for near-real-time updates
 const STDOUT_UPDATE_THRESHOLD: usize = 1;
 const BUFFER_SIZE_THRESHOLD: usize = 256;
+/// Stderr held before it's stored even without a pause in it
+const STDERR_FLUSH_BYTES: usize = 64 * 1024;
 
 /// Normalized conversation representation for different executor formats
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -27,13 +42,25 @@ pub struct NormalizedConversation {
     pub executor_type: String,
     pub prompt: Option<String>,
     pub summary: Option<String>,
+    /// What the run changed, once it has finished
+    #[serde(default)]
+    pub diff_summary: Option<DiffSummary>,
 }
 
 /// Individual entry in a normalized conversation
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct NormalizedEntry {
+    /// Identifies the entry among its run's, however often the run's output is normalized
+    /// again: where it came from and its place there. `None` for entries not from a run.
+    #[serde(default)]
+    pub id: Option<String>,
+    /// When the output the entry came from was received, RFC 3339
     pub timestamp: Option<String>,
+    /// For a tool call, how long from its entry to the one after it, which is how long the
+    /// agent spent on it
+    #[serde(default)]
+    pub duration_ms: Option<u32>,
     pub entry_type: NormalizedEntryType,
     pub content: String,
     #[ts(skip)]
@@ -54,6 +81,29 @@ pub enum NormalizedEntryType {
     SystemMessage,
     ErrorMessage,
     Thinking,
+    /// A task attachment the executor was given; the content is the file name
+    Attachment {
+        attachment_id: Uuid,
+        content_type: String,
+    },
+    /// The plan a plan-only run produced for review; the content is the plan
+    PlanEntry,
+    /// One of the project's verification commands, run after the agent finished; the
+    /// content is the end of its output
+    Verification {
+        command: String,
+        passed: bool,
+        /// `None` when the command was stopped or couldn't start
+        exit_code: Option<i32>,
+        duration_ms: u32,
+    },
+    /// What a tool call changed in one file, right after its tool use; the content is the
+    /// path
+    FileEdit {
+        path: String,
+        /// Unified diff of the change
+        diff: String,
+    },
 }
 
 /// Types of tool actions that can be performed
@@ -61,14 +111,397 @@ pub enum NormalizedEntryType {
 #[serde(tag = "action", rename_all = "snake_case")]
 #[ts(export)]
 pub enum ActionType {
-    FileRead { path: String },
-    FileWrite { path: String },
-    CommandRun { command: String },
-    Search { query: String },
-    WebFetch { url: String },
-    TaskCreate { description: String },
-    PlanPresentation { plan: String },
-    Other { description: String },
+    FileRead {
+        path: String,
+        /// Filled in once the conversation is normalized
+        #[serde(default)]
+        location: Option<FileLocation>,
+    },
+    FileWrite {
+        path: String,
+        /// Filled in once the conversation is normalized
+        #[serde(default)]
+        location: Option<FileLocation>,
+    },
+    CommandRun {
+        command: String,
+    },
+    Search {
+        query: String,
+    },
+    WebFetch {
+        url: String,
+    },
+    TaskCreate {
+        description: String,
+    },
+    PlanPresentation {
+        plan: String,
+    },
+    Other {
+        description: String,
+    },
+}
+
+/// Where a file an agent read or wrote is, for opening it from the conversation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct FileLocation {
+    pub absolute_path: String,
+    /// Whether the file is still there; it may have been deleted or renamed since
+    pub exists: bool,
+    /// The file on the project's git host at the execution's commit, for files in the
+    /// worktree of a repository with a known host
+    pub url: Option<String>,
+}
+
+/// Token and cost figures an executor reported for a run, or for one turn of it.
+///
+/// Executors surface them as usage entries: system messages whose `metadata` holds the
+/// figures under a `usage` key. A run's usage is the sum of all its usage entries.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageMetrics {
+    #[serde(default)]
+    pub input_tokens: i64,
+    #[serde(default)]
+    pub output_tokens: i64,
+    #[serde(default)]
+    pub cost_usd: Option<f64>,
+}
+
+lazy_static::lazy_static! {
+    static ref TOKENS_LINE: regex::Regex = regex::Regex::new(
+        r"(?i)^tokens(?: used)?:\s*([\d,]+)\s*(?:input|in)\b[^\d]*([\d,]+)\s*(?:output|out)\b"
+    )
+    .unwrap();
+    static ref COST_LINE: regex::Regex =
+        regex::Regex::new(r"(?i)^(?:total )?cost:\s*\$\s*([\d.]+)").unwrap();
+}
+
+impl UsageMetrics {
+    /// Figures from a plain-text summary line such as `Tokens: 1,234 input, 56 output` or
+    /// `Cost: $0.0123`, as printed by CLIs without structured output
+    pub fn parse_line(line: &str) -> Option<Self> {
+        let line = line.trim();
+        let number = |text: &str| text.replace(',', "").parse::<i64>().ok();
+        if let Some(captures) = TOKENS_LINE.captures(line) {
+            return Some(Self {
+                input_tokens: number(&captures[1])?,
+                output_tokens: number(&captures[2])?,
+                cost_usd: None,
+            });
+        }
+        let captures = COST_LINE.captures(line)?;
+        Some(Self {
+            cost_usd: Some(captures[1].parse().ok()?),
+            ..Default::default()
+        })
+    }
+
+    /// The summed figures of the usage entries among `entries`; `None` if there are none
+    pub fn total(entries: &[NormalizedEntry]) -> Option<Self> {
+        entries
+            .iter()
+            .filter_map(NormalizedEntry::usage_metrics)
+            .reduce(|total, usage| Self {
+                input_tokens: total.input_tokens + usage.input_tokens,
+                output_tokens: total.output_tokens + usage.output_tokens,
+                cost_usd: match (total.cost_usd, usage.cost_usd) {
+                    (Some(a), Some(b)) => Some(a + b),
+                    (a, b) => a.or(b),
+                },
+            })
+    }
+}
+
+impl NormalizedEntry {
+    /// A usage entry carrying `usage`
+    pub fn usage(timestamp: Option<String>, usage: UsageMetrics) -> Self {
+        let mut content = format!(
+            "Tokens: {} input, {} output",
+            usage.input_tokens, usage.output_tokens
+        );
+        if let Some(cost) = usage.cost_usd {
+            content.push_str(&format!(" · ${:.4}", cost));
+        }
+        Self {
+            id: None,
+            timestamp,
+            duration_ms: None,
+            entry_type: NormalizedEntryType::SystemMessage,
+            content,
+            metadata: Some(serde_json::json!({ "usage": usage })),
+        }
+    }
+
+    /// The edit of `path` a tool call made, replacing each `(before, after)` of `edits` in
+    /// turn, or `None` when nothing changed
+    pub fn file_edit(
+        timestamp: Option<String>,
+        path: &str,
+        edits: &[(&str, &str)],
+    ) -> Option<Self> {
+        unified_diff(path, edits).map(|diff| Self::file_patch(timestamp, path, diff))
+    }
+
+    /// The edit of `path` described by the unified diff `diff`
+    pub fn file_patch(timestamp: Option<String>, path: &str, diff: String) -> Self {
+        Self {
+            id: None,
+            timestamp,
+            duration_ms: None,
+            entry_type: NormalizedEntryType::FileEdit {
+                path: path.to_string(),
+                diff,
+            },
+            content: path.to_string(),
+            metadata: None,
+        }
+    }
+
+    /// The figures of a usage entry
+    pub fn usage_metrics(&self) -> Option<UsageMetrics> {
+        if !matches!(self.entry_type, NormalizedEntryType::SystemMessage) {
+            return None;
+        }
+        let usage = self.metadata.as_ref()?.get("usage")?;
+        serde_json::from_value(usage.clone()).ok()
+    }
+}
+
+/// A unified diff of `path` with the hunks of each `(before, after)` replacement, or `None`
+/// when none of them changes anything. Snippets are diffed on their own, so hunk line numbers
+/// count from the start of the snippet rather than the file.
+pub fn unified_diff(path: &str, edits: &[(&str, &str)]) -> Option<String> {
+    // A snippet without a final newline would otherwise be marked as missing one
+    let terminated = |text: &str| {
+        if text.is_empty() || text.ends_with('\n') {
+            text.to_string()
+        } else {
+            format!("{}\n", text)
+        }
+    };
+    let mut hunks = String::new();
+    for (before, after) in edits {
+        let (before, after) = (terminated(before), terminated(after));
+        if before == after {
+            continue;
+        }
+        let mut patch = git2::Patch::from_buffers(
+            before.as_bytes(),
+            Some(std::path::Path::new(path)),
+            after.as_bytes(),
+            Some(std::path::Path::new(path)),
+            None,
+        )
+        .ok()?;
+        let buf = patch.to_buf().ok()?;
+        let text = String::from_utf8_lossy(&buf);
+        // Only the hunks; the file header is written once for all of them
+        if let Some(start) = text.find("\n@@") {
+            hunks.push_str(&text[start + 1..]);
+        }
+    }
+    if hunks.is_empty() {
+        return None;
+    }
+    Some(format!("--- a/{}\n+++ b/{}\n{}", path, path, hunks))
+}
+
+/// Progress of one execution's log normalization, so a running process can be normalized
+/// chunk by chunk instead of re-parsing its whole output on every poll
+#[derive(Debug, Clone, Default)]
+pub struct NormalizerState {
+    pub worktree_path: String,
+    /// Length of the log that has been fed in so far
+    pub consumed: usize,
+    /// Output whose entries aren't final yet: a trailing partial line for line-based
+    /// executors, everything for executors that only normalize whole logs
+    pub buffered: String,
+    pub entries: Vec<NormalizedEntry>,
+    pub session_id: Option<String>,
+    /// When the output each entry first appeared in was received, by entry index
+    pub entry_times: Vec<Option<DateTime<Utc>>>,
+}
+
+/// Most pieces one advance feeds through the executor separately: executors that only
+/// normalize whole logs go over everything buffered for each
+const MAX_TIMED_STEPS: usize = 200;
+
+impl NormalizerState {
+    pub fn new(worktree_path: impl Into<String>) -> Self {
+        Self {
+            worktree_path: worktree_path.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Feeds the part of `log` past what was consumed, through `executor`. Starts over if
+    /// `log` no longer extends what was seen, which only happens if the log was rewritten.
+    pub fn advance(&mut self, executor: &dyn Executor, log: &str) -> Result<(), String> {
+        self.advance_timed(executor, log, &[])
+    }
+
+    /// [`advance`](Self::advance), timing the new entries by `timeline`: where in `log` the
+    /// output received at each time starts (see [`log_storage::timeline`]). The output is fed
+    /// in the pieces it was received in, so each entry gets the time of the piece it first
+    /// appeared after.
+    ///
+    /// [`log_storage::timeline`]: crate::services::log_storage::timeline
+    pub fn advance_timed(
+        &mut self,
+        executor: &dyn Executor,
+        log: &str,
+        timeline: &[(usize, DateTime<Utc>)],
+    ) -> Result<(), String> {
+        if log.get(self.consumed..).is_none() {
+            *self = Self::new(std::mem::take(&mut self.worktree_path));
+        }
+        for (end, received_at) in timed_steps(log, self.consumed, timeline) {
+            executor.normalize_logs_incremental(&log[self.consumed..end], self)?;
+            self.consumed = end;
+            // Entries revised by later output keep the time they first appeared at
+            if self.entries.len() > self.entry_times.len() {
+                self.entry_times.resize(self.entries.len(), received_at);
+            }
+        }
+        Ok(())
+    }
+
+    /// Normalizes the last line of a log that has ended without a newline, which line-based
+    /// executors would otherwise hold back for the rest of it
+    pub fn finish(
+        &mut self,
+        executor: &dyn Executor,
+        timeline: &[(usize, DateTime<Utc>)],
+    ) -> Result<(), String> {
+        if self.buffered.is_empty() || self.buffered.ends_with('\n') {
+            return Ok(());
+        }
+        executor.normalize_logs_incremental("\n", self)?;
+        if self.entries.len() > self.entry_times.len() {
+            let received_at = timeline.last().map(|(_, received_at)| *received_at);
+            self.entry_times.resize(self.entries.len(), received_at);
+        }
+        Ok(())
+    }
+
+    /// Appends `chunk` and splits off the complete lines buffered so far, leaving any
+    /// unterminated line for the next chunk
+    pub fn take_complete_lines(&mut self, chunk: &str) -> Option<String> {
+        self.buffered.push_str(chunk);
+        let end = self.buffered.rfind('\n')? + 1;
+        let rest = self.buffered.split_off(end);
+        Some(std::mem::replace(&mut self.buffered, rest))
+    }
+
+    /// The entries so far, those the executor left untimed given the time they were received
+    pub fn conversation(&self, executor_type: &str) -> NormalizedConversation {
+        let mut entries = self.entries.clone();
+        for (entry, received_at) in entries.iter_mut().zip(&self.entry_times) {
+            if entry.timestamp.is_none() {
+                entry.timestamp = received_at.map(|received_at| received_at.to_rfc3339());
+            }
+        }
+        NormalizedConversation {
+            entries,
+            session_id: self.session_id.clone(),
+            executor_type: executor_type.to_string(),
+            prompt: None,
+            summary: None,
+            diff_summary: None,
+        }
+    }
+}
+
+/// Where to split the part of `log` past `start` for [`NormalizerState::advance_timed`]: the
+/// end of each piece, and when the output it starts with was received
+fn timed_steps(
+    log: &str,
+    start: usize,
+    timeline: &[(usize, DateTime<Utc>)],
+) -> Vec<(usize, Option<DateTime<Utc>>)> {
+    if start >= log.len() {
+        return Vec::new();
+    }
+    let mut ends: Vec<usize> = timeline
+        .iter()
+        .map(|(offset, _)| *offset)
+        .filter(|offset| *offset > start && *offset < log.len() && log.is_char_boundary(*offset))
+        .collect();
+    if ends.len() >= MAX_TIMED_STEPS {
+        let every = ends.len().div_ceil(MAX_TIMED_STEPS);
+        ends = ends.into_iter().step_by(every).collect();
+    }
+    ends.push(log.len());
+
+    let mut piece_start = start;
+    ends.into_iter()
+        .map(|end| {
+            let received = timeline.partition_point(|(offset, _)| *offset <= piece_start);
+            piece_start = end;
+            (end, received.checked_sub(1).map(|i| timeline[i].1))
+        })
+        .collect()
+}
+
+/// Sets how long each timed tool call took: until the next timed entry other than the file
+/// edit it made, or until `ended_at` for the last one
+pub fn time_tool_calls(entries: &mut [NormalizedEntry], ended_at: Option<DateTime<Utc>>) {
+    let times: Vec<Option<DateTime<Utc>>> = entries
+        .iter()
+        .map(|entry| {
+            let timestamp = entry.timestamp.as_deref()?;
+            DateTime::parse_from_rfc3339(timestamp)
+                .ok()
+                .map(|time| time.with_timezone(&Utc))
+        })
+        .collect();
+    for i in 0..entries.len() {
+        let (NormalizedEntryType::ToolUse { .. }, Some(started_at)) =
+            (&entries[i].entry_type, times[i])
+        else {
+            continue;
+        };
+        let next = entries
+            .iter()
+            .zip(&times)
+            .skip(i + 1)
+            .find(|(entry, time)| {
+                time.is_some() && !matches!(entry.entry_type, NormalizedEntryType::FileEdit { .. })
+            })
+            .and_then(|(_, time)| *time);
+        if let Some(finished_at) = next.or(ended_at) {
+            let duration_ms = (finished_at - started_at).num_milliseconds();
+            entries[i].duration_ms = Some(duration_ms.clamp(0, i64::from(u32::MAX)) as u32);
+        }
+    }
+}
+
+/// Identifies entries normalized from a run's stdout by their place among them, which
+/// later output doesn't change
+pub fn identify_output_entries(entries: &mut [NormalizedEntry]) {
+    for (index, entry) in entries.iter_mut().enumerate() {
+        entry.id = Some(format!("stdout-{}", index));
+    }
+}
+
+/// Incremental normalization for executors whose output is one self-contained record per
+/// line: only complete lines are parsed, and their entries are final
+pub fn normalize_complete_lines(
+    executor: &dyn Executor,
+    chunk: &str,
+    state: &mut NormalizerState,
+) -> Result<(), String> {
+    let Some(lines) = state.take_complete_lines(chunk) else {
+        return Ok(());
+    };
+    let normalized = executor.normalize_output(&lines, &state.worktree_path)?;
+    state.entries.extend(normalized.entries);
+    if state.session_id.is_none() {
+        state.session_id = normalized.session_id;
+    }
+    Ok(())
 }
 
 /// Context information for spawn failures to provide comprehensive error details
@@ -156,6 +589,11 @@ pub enum ExecutorError {
     GitError(String),
     InvalidSessionId(String),
     FollowUpNotSupported,
+    /// The pre-flight check found the machine can't run the executor
+    EnvironmentInvalid {
+        executor_type: String,
+        problems: Vec<EnvironmentProblem>,
+    },
 }
 
 impl std::fmt::Display for ExecutorError {
@@ -198,6 +636,16 @@ impl std::fmt::Display for ExecutorError {
             ExecutorError::FollowUpNotSupported => {
                 write!(f, "This executor does not support follow-up sessions")
             }
+            ExecutorError::EnvironmentInvalid {
+                executor_type,
+                problems,
+            } => {
+                write!(f, "{} can't run on this machine:", executor_type)?;
+                for problem in problems {
+                    write!(f, " {} ({})", problem.message, problem.remediation)?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -237,6 +685,13 @@ impl From<crate::models::task_attempt::TaskAttemptError> for ExecutorError {
             crate::models::task_attempt::TaskAttemptError::GitHubService(e) => {
                 ExecutorError::GitError(format!("GitHub service error: {}", e))
             }
+            crate::models::task_attempt::TaskAttemptError::GitHost(e) => {
+                ExecutorError::GitError(e.to_string())
+            }
+            crate::models::task_attempt::TaskAttemptError::Executor(e) => *e,
+            crate::models::task_attempt::TaskAttemptError::BudgetExceeded(e) => {
+                ExecutorError::ContextCollectionFailed(e.to_string())
+            }
         }
     }
 }
@@ -244,17 +699,303 @@ impl From<crate::models::task_attempt::TaskAttemptError> for ExecutorError {
 impl ExecutorError {
     /// Create a new SpawnFailed error with context
     pub fn spawn_failed(error: CommandError, context: SpawnContext) -> Self {
+        crate::metrics::record_spawn_failure(&context.executor_type);
         ExecutorError::SpawnFailed { error, context }
     }
+
+    /// Why the execution this error ended failed
+    pub fn failure_reason(&self) -> FailureReason {
+        match self {
+            ExecutorError::SpawnFailed {
+                error: CommandError::SpawnFailed { error, .. },
+                ..
+            } if error.kind() == std::io::ErrorKind::NotFound => FailureReason::MissingBinary,
+            ExecutorError::EnvironmentInvalid { problems, .. } => {
+                executor_preflight::failure_reason(problems)
+            }
+            _ => FailureReason::SpawnFailed,
+        }
+    }
+}
+
+/// Load the task an agent is started for, with the project context variables its
+/// description uses filled in from the worktree and the files it `@`-mentions appended
+pub async fn load_task_for_prompt(
+    pool: &DbPool,
+    task_id: Uuid,
+    worktree_path: &str,
+) -> Result<Task, ExecutorError> {
+    let mut task = Task::find_by_id(pool, task_id)
+        .await?
+        .ok_or(ExecutorError::TaskNotFound)?;
+    if let Some(description) = &task.description {
+        let description = fill_context(description, worktree_path).await;
+        let files = file_mentions::mentioned_files_context(
+            std::path::Path::new(worktree_path),
+            &description,
+        );
+        task.description = Some(description + files.as_str());
+    }
+    Ok(task)
+}
+
+/// The prompt the project's template gives `task` for a run of `executor`, the executor's
+/// name as in the config. A plan-only run is asked for a plan at the end of it, and a
+/// decomposition run for subtasks. `None` if the project has no template for it and the run
+/// is asked for neither, in which case the executor builds its usual prompt. A run handed
+/// an attempt over from another executor is given the follow-up it was started with instead.
+pub async fn templated_prompt(
+    pool: &DbPool,
+    task: &Task,
+    worktree_path: &str,
+    executor: &str,
+) -> Result<Option<String>, ExecutorError> {
+    if let Ok(Some(prompt)) = RUN_OPTIONS.try_with(|options| options.prompt.clone()) {
+        return Ok(Some(prompt));
+    }
+    let (asks_for_plan, decomposing) =
+        match TaskAttempt::find_by_worktree_path(pool, worktree_path).await? {
+            Some(attempt) => (
+                plan_review::asks_for_plan(pool, &attempt).await?,
+                TaskDecomposition::is_pending(pool, attempt.id).await?,
+            ),
+            None => (false, false),
+        };
+    let prompt = match prompt_template::render_for_task(pool, task, worktree_path, executor).await?
+    {
+        Some(prompt) => prompt,
+        None if asks_for_plan || decomposing => {
+            prompt_template::render_template_for_task(
+                pool,
+                prompt_template::DEFAULT_TEMPLATE,
+                task,
+                worktree_path,
+                executor,
+            )
+            .await?
+        }
+        None => return Ok(None),
+    };
+    let prompt = if decomposing {
+        format!(
+            "{}\n\n{}",
+            prompt.trim_end(),
+            task_decomposition::DECOMPOSITION_PROMPT
+        )
+    } else if asks_for_plan {
+        format!("{}\n\n{}", prompt.trim_end(), plan_review::PLANNING_PROMPT)
+    } else {
+        prompt
+    };
+    Ok(Some(fill_context(&prompt, worktree_path).await))
+}
+
+async fn fill_context(text: &str, worktree_path: &str) -> String {
+    // CI status comes from GitHub, so only then is the token needed
+    let github = text
+        .contains("{{ci_failures}}")
+        .then(|| Config::load(&crate::utils::config_path()).ok())
+        .flatten()
+        .and_then(|config| {
+            let token = config.github.token?;
+            Some((token, config.github.enterprise_url))
+        });
+    fill_context_variables(
+        text,
+        std::path::Path::new(worktree_path),
+        github
+            .as_ref()
+            .map(|(token, url)| (token.as_str(), url.as_deref())),
+    )
+    .await
+}
+
+/// The prompt section pointing an agent at a task's images. `reference` turns a stored file
+/// into whatever the executor's CLI understands as a file reference.
+pub fn image_attachments_prompt(
+    images: &[TaskAttachment],
+    reference: impl Fn(&std::path::Path) -> String,
+) -> String {
+    if images.is_empty() {
+        return String::new();
+    }
+    let lines: Vec<String> = images
+        .iter()
+        .map(|image| format!("- {}", reference(&image.path())))
+        .collect();
+    format!(
+        "\n\nAttached images (look at them before starting):\n{}",
+        lines.join("\n")
+    )
+}
+
+/// The prompt section for executors whose CLI can't take images: where the task's images are
+/// stored, for an agent that can open files
+pub async fn image_paths_prompt(pool: &DbPool, task_id: Uuid) -> Result<String, ExecutorError> {
+    let images = TaskAttachment::find_images_by_task_id(pool, task_id).await?;
+    Ok(image_attachments_prompt(&images, |path| {
+        path.display().to_string()
+    }))
+}
+
+/// What a coding agent is started with on top of its executor's own command line
+#[derive(Debug, Clone, Default)]
+pub struct AgentOptions {
+    pub model: Option<String>,
+    /// Flags after the CLI's own, from the attempt's executor profile
+    pub extra_args: Vec<String>,
+    /// Tokens of the attempt's earlier conversation that executors rebuilding it into a
+    /// follow-up's prompt themselves may use
+    pub context_tokens: Option<usize>,
+    /// What the agent is asked instead of the task, for a follow-up handed over to it
+    pub prompt: Option<String>,
+}
+
+tokio::task_local! {
+    static RUN_OPTIONS: AgentOptions;
+}
+
+/// Run `future` with the coding agents it starts given `options`
+pub async fn with_agent_options<F: std::future::Future>(
+    options: AgentOptions,
+    future: F,
+) -> F::Output {
+    RUN_OPTIONS.scope(options, future).await
+}
+
+/// Whether `model` looks like a model's name, as in "opus", "gpt-4.1" or
+/// "anthropic/claude-sonnet-4". Nothing else is put on an agent's command line, which also
+/// saves quoting it for the shell and plan mode's wrapper script.
+pub fn is_valid_model_name(model: &str) -> bool {
+    !model.is_empty()
+        && model
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | ':' | '/'))
+}
+
+/// `--model` and the model the agent being started was asked to use, then its profile's
+/// extra flags, for executors whose CLI takes them; empty when there are none
+pub fn agent_flags() -> String {
+    RUN_OPTIONS
+        .try_with(|options| {
+            let mut flags = String::new();
+            if let Some(model) = options.model.as_deref().filter(|m| is_valid_model_name(m)) {
+                flags.push_str(" --model ");
+                flags.push_str(model);
+            }
+            for arg in &options.extra_args {
+                flags.push(' ');
+                flags.push_str(&shell_quote(arg));
+            }
+            flags
+        })
+        .unwrap_or_default()
+}
+
+/// The context budget of the agent being started, or the default one from the settings
+pub fn context_budget() -> usize {
+    RUN_OPTIONS
+        .try_with(|options| options.context_tokens)
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| {
+            crate::models::config::ContextBudgetConfig::default().max_tokens as usize
+        })
+}
+
+/// What an executor can do beyond running a task, so callers can offer only what works
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ExecutorCapabilities {
+    /// Can be given a follow-up prompt once a run finishes
+    pub supports_followup: bool,
+    /// A follow-up continues the earlier conversation by itself. Follow-ups of executors
+    /// that can't are given a condensed transcript of the attempt's earlier runs.
+    pub supports_sessions: bool,
+    /// Proposes a plan for approval before changing anything
+    pub supports_plan_mode: bool,
+    /// Takes further messages from the user on stdin while it runs
+    pub supports_interactive: bool,
+    /// Logs record messages and tool calls as structured events rather than plain text
+    pub emits_structured_logs: bool,
+}
+
+/// What an executor needs from the machine before it can start
+#[derive(Debug, Clone)]
+pub struct EnvironmentRequirements {
+    /// Binary that has to be on PATH; it is probed with `--version`
+    pub binary: &'static str,
+    /// How to install the binary when it is missing
+    pub install_hint: &'static str,
+    pub credentials: Option<CredentialRequirement>,
+}
+
+impl EnvironmentRequirements {
+    /// For agents fetched and run through npx
+    pub fn npx() -> Self {
+        Self {
+            binary: "npx",
+            install_hint: "Install Node.js, which comes with npx: https://nodejs.org",
+            credentials: None,
+        }
+    }
+
+    pub fn with_credentials(mut self, credentials: CredentialRequirement) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+}
+
+/// An API key or a login the agent's model provider needs
+#[derive(Debug, Clone)]
+pub struct CredentialRequirement {
+    /// Any one of these being set is enough
+    pub env_vars: &'static [&'static str],
+    /// Written by the CLI's own login, which does as well as a key
+    pub login_file: Option<std::path::PathBuf>,
+    pub hint: &'static str,
+}
+
+/// Something the pre-flight check found missing, with how to fix it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct EnvironmentProblem {
+    pub message: String,
+    pub remediation: String,
 }
 
 /// Trait for coding agents that can execute tasks, normalize logs, and support follow-up sessions
 #[async_trait]
 pub trait Executor: Send + Sync {
+    /// What this executor supports; nothing beyond running a task by default
+    fn capabilities(&self) -> ExecutorCapabilities {
+        ExecutorCapabilities::default()
+    }
+
+    /// What has to be installed and configured for this executor to start, checked before
+    /// each run. `None` for executors that only need a shell.
+    fn environment_requirements(&self) -> Option<EnvironmentRequirements> {
+        None
+    }
+
+    /// The line to write to the CLI's stdin to allow or deny a command it asked to run,
+    /// for executors whose CLI asks before running commands. `None` for those that don't
+    /// ask, which are never paused for approval.
+    fn command_approval_reply(&self, _allow: bool) -> Option<String> {
+        None
+    }
+
+    /// What to write to the CLI's stdin to pass it a message from the user while it runs,
+    /// for executors whose CLI reads stdin for as long as it runs. `None` for those that
+    /// only read the prompt.
+    fn user_message_input(&self, _message: &str) -> Option<String> {
+        None
+    }
+
     /// Spawn the command for a given task attempt
     async fn spawn(
         &self,
-        pool: &sqlx::SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
         worktree_path: &str,
     ) -> Result<CommandProcess, ExecutorError>;
@@ -266,7 +1007,7 @@ pub trait Executor: Send + Sync {
     /// returns an error.
     async fn spawn_followup(
         &self,
-        _pool: &sqlx::SqlitePool,
+        _pool: &DbPool,
         _task_id: Uuid,
         _session_id: &str,
         _prompt: &str,
@@ -287,14 +1028,42 @@ pub trait Executor: Send + Sync {
             executor_type: "unknown".to_string(),
             prompt: None,
             summary: None,
+            diff_summary: None,
         })
     }
 
+    /// Normalize raw output as the process wrote it: it's rendered the way a terminal would
+    /// show it first (see [`terminal::clean_output`]), so `normalize_logs` only sees text
+    fn normalize_output(
+        &self,
+        logs: &str,
+        worktree_path: &str,
+    ) -> Result<NormalizedConversation, String> {
+        self.normalize_logs(&terminal::clean_output(logs), worktree_path)
+    }
+
+    /// Normalize the next chunk of a running execution's logs into `state`
+    ///
+    /// The default re-normalizes everything buffered so far, which is always correct;
+    /// executors whose logs are line-delimited records override it with
+    /// [`normalize_complete_lines`].
+    fn normalize_logs_incremental(
+        &self,
+        chunk: &str,
+        state: &mut NormalizerState,
+    ) -> Result<(), String> {
+        state.buffered.push_str(chunk);
+        let normalized = self.normalize_output(&state.buffered, &state.worktree_path)?;
+        state.entries = normalized.entries;
+        state.session_id = normalized.session_id;
+        Ok(())
+    }
+
     #[allow(clippy::result_large_err)]
     async fn setup_streaming(
         &self,
         child: &mut CommandProcess,
-        pool: &sqlx::SqlitePool,
+        pool: &DbPool,
         attempt_id: Uuid,
         execution_process_id: Uuid,
     ) -> Result<(), ExecutorError> {
@@ -333,7 +1102,7 @@ pub trait Executor: Send + Sync {
     /// Execute the command and stream output to database in real-time
     async fn execute_streaming(
         &self,
-        pool: &sqlx::SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
         attempt_id: Uuid,
         execution_process_id: Uuid,
@@ -348,7 +1117,7 @@ pub trait Executor: Send + Sync {
     #[allow(clippy::too_many_arguments)]
     async fn execute_followup_streaming(
         &self,
-        pool: &sqlx::SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
         attempt_id: Uuid,
         execution_process_id: Uuid,
@@ -381,6 +1150,9 @@ pub enum ExecutorType {
 pub struct FollowUpInfo {
     pub session_id: String,
     pub prompt: String,
+    /// The attempt is handed over from another executor, so rather than resuming a session
+    /// the run starts one of its own, given a transcript of what the attempt did so far
+    pub handover: bool,
 }
 
 /// Configuration for different executor types
@@ -405,6 +1177,24 @@ pub enum ExecutorConfig {
     Aider,
     Codex,
     Aaa,
+    Goose,
+    /// Plays back a recorded log; `normalize_as` names the executor that produced it
+    Replay {
+        recording_path: String,
+        speed: Option<f64>,
+        normalize_as: Option<String>,
+    },
+    /// Scripted executor for tests and dry runs; never calls a real agent
+    #[cfg(any(test, feature = "mock-executor"))]
+    Mock,
+    /// An agent described by a JSON-lines definition of this name in the config
+    Jsonl {
+        name: String,
+    },
+    /// An agent speaking the Agent Client Protocol, started as the config's agent of this name
+    Acp {
+        name: String,
+    },
 }
 
 // Constants for frontend
@@ -431,6 +1221,20 @@ impl FromStr for ExecutorConfig {
             "aider" => Ok(ExecutorConfig::Aider),
             "codex" => Ok(ExecutorConfig::Codex),
             "aaa" => Ok(ExecutorConfig::Aaa),
+            "goose" => Ok(ExecutorConfig::Goose),
+            #[cfg(any(test, feature = "mock-executor"))]
+            "mock" => Ok(ExecutorConfig::Mock),
+            s if s == "replay" || s.starts_with("replay:") => Ok(ExecutorConfig::Replay {
+                recording_path: String::new(),
+                speed: None,
+                normalize_as: s.strip_prefix("replay:").map(|x| x.to_string()),
+            }),
+            s if s.starts_with("jsonl:") => Ok(ExecutorConfig::Jsonl {
+                name: s["jsonl:".len()..].to_string(),
+            }),
+            s if s.starts_with("acp:") => Ok(ExecutorConfig::Acp {
+                name: s["acp:".len()..].to_string(),
+            }),
             "setup-script" => Ok(ExecutorConfig::SetupScript {
                 script: "setup script".to_string(),
             }),
@@ -453,6 +1257,20 @@ impl ExecutorConfig {
             ExecutorConfig::Aider => Box::new(AiderExecutor::new()),
             ExecutorConfig::Codex => Box::new(CodexExecutor::new()),
             ExecutorConfig::Aaa => Box::new(AaaExecutor::new()),
+            ExecutorConfig::Goose => Box::new(GooseExecutor::new()),
+            ExecutorConfig::Replay {
+                recording_path,
+                speed,
+                normalize_as,
+            } => Box::new(ReplayExecutor::new(
+                recording_path.clone(),
+                *speed,
+                normalize_as.clone(),
+            )),
+            #[cfg(any(test, feature = "mock-executor"))]
+            ExecutorConfig::Mock => Box::new(crate::executors::MockExecutor::new()),
+            ExecutorConfig::Jsonl { name } => Box::new(JsonlExecutor::new(name.clone())),
+            ExecutorConfig::Acp { name } => Box::new(AcpExecutor::new(name.clone())),
             ExecutorConfig::SetupScript { script } => {
                 Box::new(SetupScriptExecutor::new(script.clone()))
             }
@@ -493,6 +1311,14 @@ impl ExecutorConfig {
             ExecutorConfig::Aaa => {
                 dirs::home_dir().map(|home| home.join(".assistant").join("config"))
             }
+            ExecutorConfig::Goose => {
+                dirs::home_dir().map(|home| home.join(".config").join("goose").join("config.yaml"))
+            }
+            #[cfg(any(test, feature = "mock-executor"))]
+            ExecutorConfig::Mock => None,
+            ExecutorConfig::Replay { .. } => None,
+            ExecutorConfig::Jsonl { .. } => None,
+            ExecutorConfig::Acp { .. } => None,
             ExecutorConfig::SetupScript { .. } => None,
         }
     }
@@ -511,6 +1337,12 @@ impl ExecutorConfig {
             ExecutorConfig::Aider => None, // Aider doesn't support MCP. https://github.com/Aider-AI/aider/issues/3314
             ExecutorConfig::Codex => None, // Codex uses TOML config, frontend doesn't handle TOML yet
             ExecutorConfig::Aaa => Some(vec!["mcp", "servers"]), // AAA uses MCP servers
+            ExecutorConfig::Goose => None, // Goose uses YAML config, frontend doesn't handle YAML yet
+            #[cfg(any(test, feature = "mock-executor"))]
+            ExecutorConfig::Mock => None,
+            ExecutorConfig::Replay { .. } => None,
+            ExecutorConfig::Jsonl { .. } => None,
+            ExecutorConfig::Acp { .. } => None,
             ExecutorConfig::SetupScript { .. } => None, // Setup scripts don't support MCP
         }
     }
@@ -523,9 +1355,66 @@ impl ExecutorConfig {
                 | ExecutorConfig::Aider
                 | ExecutorConfig::SetupScript { .. }
                 | ExecutorConfig::Codex
+                | ExecutorConfig::Goose
+                | ExecutorConfig::Replay { .. }
+                | ExecutorConfig::Jsonl { .. }
+                | ExecutorConfig::Acp { .. }
+        ) && !self.is_mock()
+    }
+
+    /// Whether this is the scripted test executor
+    pub fn is_mock(&self) -> bool {
+        #[cfg(any(test, feature = "mock-executor"))]
+        if matches!(self, ExecutorConfig::Mock) {
+            return true;
+        }
+        false
+    }
+
+    pub fn capabilities(&self) -> ExecutorCapabilities {
+        self.create_executor().capabilities()
+    }
+
+    /// Whether a follow-up can resume the executor's own session. Follow-ups of executors
+    /// that can't are given a condensed transcript of the attempt's earlier runs instead.
+    pub fn supports_sessions(&self) -> bool {
+        self.capabilities().supports_sessions
+    }
+
+    /// Whether the executor's CLI can be told which model to use. Claude Code Router picks
+    /// the model by its own routing rules.
+    pub fn supports_model(&self) -> bool {
+        matches!(
+            self,
+            ExecutorConfig::Claude
+                | ExecutorConfig::ClaudePlan
+                | ExecutorConfig::Gemini
+                | ExecutorConfig::SstOpencode
+                | ExecutorConfig::Aider
+                | ExecutorConfig::Codex
+                | ExecutorConfig::Goose
         )
     }
 
+    /// For a planning executor, the executor that carries out an approved plan in the same
+    /// session. `None` for executors that implement directly.
+    pub fn implementation_executor(&self) -> Option<ExecutorConfig> {
+        match self {
+            ExecutorConfig::ClaudePlan => Some(ExecutorConfig::Claude),
+            _ => None,
+        }
+    }
+
+    /// The executor that plans with this one's CLI in its native plan mode, the inverse of
+    /// [`Self::implementation_executor`]. `None` for executors without one, which are asked
+    /// for a plan in their prompt instead.
+    pub fn plan_executor(&self) -> Option<ExecutorConfig> {
+        match self {
+            ExecutorConfig::Claude | ExecutorConfig::ClaudePlan => Some(ExecutorConfig::ClaudePlan),
+            _ => None,
+        }
+    }
+
     /// Get the display name for this executor
     pub fn display_name(&self) -> &'static str {
         match self {
@@ -540,6 +1429,12 @@ impl ExecutorConfig {
             ExecutorConfig::Aider => "Aider",
             ExecutorConfig::Codex => "Codex",
             ExecutorConfig::Aaa => "AAA (Assistant Agent)",
+            ExecutorConfig::Goose => "Goose",
+            ExecutorConfig::Replay { .. } => "Replay",
+            #[cfg(any(test, feature = "mock-executor"))]
+            ExecutorConfig::Mock => "Mock (Testing)",
+            ExecutorConfig::Jsonl { .. } => "Custom (JSON lines)",
+            ExecutorConfig::Acp { .. } => "Agent Client Protocol",
             ExecutorConfig::SetupScript { .. } => "Setup Script",
         }
     }
@@ -559,33 +1454,84 @@ impl std::fmt::Display for ExecutorConfig {
             ExecutorConfig::Aider => "aider",
             ExecutorConfig::Codex => "codex",
             ExecutorConfig::Aaa => "aaa",
+            ExecutorConfig::Goose => "goose",
+            #[cfg(any(test, feature = "mock-executor"))]
+            ExecutorConfig::Mock => "mock",
             ExecutorConfig::SetupScript { .. } => "setup-script",
+            // Keep the original executor so stored logs normalize the same way later
+            ExecutorConfig::Replay {
+                normalize_as: Some(original),
+                ..
+            } => return write!(f, "replay:{}", original),
+            ExecutorConfig::Replay { .. } => "replay",
+            ExecutorConfig::Jsonl { name } => return write!(f, "jsonl:{}", name),
+            ExecutorConfig::Acp { name } => return write!(f, "acp:{}", name),
         };
         write!(f, "{}", s)
     }
 }
 
-/// Stream output from a child process to the database
-pub async fn stream_output_to_db(
-    output: impl tokio::io::AsyncRead + Unpin,
-    pool: sqlx::SqlitePool,
+/// Stream output from a child process to the database. The stream counts as an output
+/// writer of the execution from when this is called, not from when the task first runs.
+/// The pipe is drained as it's written, and what the database can't keep up with waits in a
+/// bounded buffer; see [`crate::command_runner::capture_output`].
+pub fn stream_output_to_db(
+    output: impl tokio::io::AsyncRead + Unpin + Send + 'static,
+    pool: DbPool,
     attempt_id: Uuid,
     execution_process_id: Uuid,
     is_stdout: bool,
-) {
-    if is_stdout {
-        stream_stdout_to_db(output, pool, attempt_id, execution_process_id).await;
-    } else {
-        stream_stderr_to_db(output, pool, attempt_id, execution_process_id).await;
+) -> impl std::future::Future<Output = ()> {
+    let writer = crate::command_runner::register_output_writer(execution_process_id);
+    let output = crate::command_runner::capture_output(output, execution_process_id);
+    async move {
+        let _writer = writer;
+        if is_stdout {
+            store_stdout(
+                output,
+                pool,
+                attempt_id,
+                execution_process_id,
+                parse_session_id_from_line,
+            )
+            .await;
+        } else {
+            stream_stderr_to_db(output, pool, attempt_id, execution_process_id).await;
+        }
     }
 }
 
-/// Stream stdout from a child process to the database (immediate updates)
-async fn stream_stdout_to_db(
+/// [`stream_output_to_db`] of stdout for executors whose session id isn't where Claude or
+/// Amp put it; `parse_session_id` is tried on each line until it finds one
+pub fn stream_stdout_to_db_with(
+    output: impl tokio::io::AsyncRead + Unpin + Send + 'static,
+    pool: DbPool,
+    attempt_id: Uuid,
+    execution_process_id: Uuid,
+    parse_session_id: impl Fn(&str) -> Option<String>,
+) -> impl std::future::Future<Output = ()> {
+    let writer = crate::command_runner::register_output_writer(execution_process_id);
+    let output = crate::command_runner::capture_output(output, execution_process_id);
+    async move {
+        let _writer = writer;
+        store_stdout(
+            output,
+            pool,
+            attempt_id,
+            execution_process_id,
+            parse_session_id,
+        )
+        .await;
+    }
+}
+
+/// Store stdout from a child process in the database (immediate updates)
+async fn store_stdout(
     output: impl tokio::io::AsyncRead + Unpin,
-    pool: sqlx::SqlitePool,
+    pool: DbPool,
     attempt_id: Uuid,
     execution_process_id: Uuid,
+    parse_session_id: impl Fn(&str) -> Option<String>,
 ) {
     use crate::models::{execution_process::ExecutionProcess, executor_session::ExecutorSession};
 
@@ -602,7 +1548,7 @@ async fn stream_stdout_to_db(
             Ok(_) => {
                 // Parse session ID from the first JSONL line
                 if !session_id_parsed {
-                    if let Some(external_session_id) = parse_session_id_from_line(&line) {
+                    if let Some(external_session_id) = parse_session_id(&line) {
                         if let Err(e) = ExecutorSession::update_session_id(
                             &pool,
                             execution_process_id,
@@ -675,7 +1621,7 @@ async fn stream_stdout_to_db(
 /// Stream stderr from a child process to the database (buffered with timeout)
 async fn stream_stderr_to_db(
     output: impl tokio::io::AsyncRead + Unpin,
-    pool: sqlx::SqlitePool,
+    pool: DbPool,
     attempt_id: Uuid,
     execution_process_id: Uuid,
 ) {
@@ -699,8 +1645,18 @@ async fn stream_stderr_to_db(
                 break;
             }
             Ok(Ok(_)) => {
-                // Successfully read a line - just accumulate it
                 accumulated_output.push_str(&line);
+                // A process that never pauses still has its stderr stored as it goes
+                if accumulated_output.len() > STDERR_FLUSH_BYTES {
+                    flush_stderr_chunk(
+                        &pool,
+                        execution_process_id,
+                        &accumulated_output,
+                        attempt_id,
+                    )
+                    .await;
+                    accumulated_output.clear();
+                }
             }
             Ok(Err(e)) => {
                 tracing::error!("Error reading stderr for attempt {}: {}", attempt_id, e);
@@ -730,7 +1686,7 @@ async fn stream_stderr_to_db(
 
 /// Flush a chunk of stderr output to the database
 async fn flush_stderr_chunk(
-    pool: &sqlx::SqlitePool,
+    pool: &DbPool,
     execution_process_id: Uuid,
     content: &str,
     attempt_id: Uuid,
@@ -891,6 +1847,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_unified_diff_of_edits() {
+        let diff = unified_diff(
+            "src/lib.rs",
+            &[
+                ("let a = 1;", "let a = 2;"),
+                ("same", "same"),
+                ("", "// new"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            diff,
+            "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1 +1 @@\n-let a = 1;\n+let a = 2;\n@@ -0,0 +1 @@\n+// new\n"
+        );
+        assert_eq!(unified_diff("src/lib.rs", &[("same", "same")]), None);
+    }
+
     #[test]
     fn test_parse_amp_thread_id() {
         let amp_line = r#"{"type":"initial","threadID":"T-286f908a-2cd8-40cc-9490-da689b2f1560"}"#;
@@ -901,6 +1875,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_usage_lines_and_totals() {
+        assert_eq!(
+            UsageMetrics::parse_line("Tokens used: 2,500 in, 120 out"),
+            Some(UsageMetrics {
+                input_tokens: 2500,
+                output_tokens: 120,
+                cost_usd: None,
+            })
+        );
+        assert_eq!(
+            UsageMetrics::parse_line("Total cost: $1.25"),
+            Some(UsageMetrics {
+                cost_usd: Some(1.25),
+                ..Default::default()
+            })
+        );
+        assert_eq!(UsageMetrics::parse_line("Tokens are great"), None);
+
+        let turn = UsageMetrics {
+            input_tokens: 100,
+            output_tokens: 10,
+            cost_usd: None,
+        };
+        let entries = vec![
+            NormalizedEntry::usage(None, turn.clone()),
+            NormalizedEntry::usage(None, turn),
+            NormalizedEntry::usage(
+                None,
+                UsageMetrics {
+                    cost_usd: Some(0.5),
+                    ..Default::default()
+                },
+            ),
+        ];
+        assert_eq!(
+            UsageMetrics::total(&entries),
+            Some(UsageMetrics {
+                input_tokens: 200,
+                output_tokens: 20,
+                cost_usd: Some(0.5),
+            })
+        );
+        assert_eq!(UsageMetrics::total(&[]), None);
+    }
+
     #[test]
     fn test_parse_invalid_json() {
         let invalid_line = "not json at all";
@@ -913,6 +1933,50 @@ mod tests {
         assert_eq!(parse_session_id_from_line(other_json), None);
     }
 
+    #[test]
+    fn test_image_attachments_prompt() {
+        assert_eq!(image_attachments_prompt(&[], |_| unreachable!()), "");
+
+        let image = TaskAttachment {
+            id: Uuid::new_v4(),
+            task_id: Uuid::new_v4(),
+            file_name: "mockup.png".to_string(),
+            content_type: "image/png".to_string(),
+            size_bytes: 1024,
+            created_at: chrono::Utc::now(),
+        };
+        let prompt = image_attachments_prompt(std::slice::from_ref(&image), |path| {
+            format!("@{}", path.display())
+        });
+        assert_eq!(
+            prompt,
+            format!(
+                "\n\nAttached images (look at them before starting):\n- @{}",
+                image.path().display()
+            )
+        );
+        assert!(prompt.ends_with("-mockup.png"));
+    }
+
+    #[test]
+    fn test_executor_capabilities() {
+        let aaa = ExecutorConfig::Aaa.capabilities();
+        assert!(aaa.supports_followup);
+        assert!(!aaa.supports_sessions);
+
+        assert!(ExecutorConfig::ClaudePlan.capabilities().supports_plan_mode);
+        assert!(!ExecutorConfig::Claude.capabilities().supports_plan_mode);
+        assert!(
+            ExecutorConfig::ClaudeCodeRouter
+                .capabilities()
+                .supports_sessions
+        );
+        assert_eq!(
+            ExecutorConfig::Echo.capabilities(),
+            ExecutorCapabilities::default()
+        );
+    }
+
     #[test]
     fn test_parse_empty_line() {
         assert_eq!(parse_session_id_from_line(""), None);
@@ -1087,4 +2151,77 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap().executor_type, "aider");
     }
+
+    #[test]
+    fn test_incremental_normalization_matches_whole_log() {
+        let log = concat!(
+            r#"{"type":"system","subtype":"init","session_id":"abc"}"#,
+            "\n",
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Looking around"}]}}"#,
+            "\n",
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Bash","input":{"command":"ls"}}]}}"#,
+            "\n",
+        );
+        let claude = ClaudeExecutor::new();
+        let whole = claude.normalize_logs(log, "/tmp").unwrap();
+
+        let mut state = NormalizerState::new("/tmp");
+        // Cut inside the second line: it must only be parsed once it is complete
+        let cut = log.find("Looking").unwrap();
+        state.advance(&claude, &log[..cut]).unwrap();
+        let after_first_chunk = state.entries.len();
+        state.advance(&claude, log).unwrap();
+        state.advance(&claude, log).unwrap();
+
+        assert!(after_first_chunk < whole.entries.len());
+        assert_eq!(state.entries.len(), whole.entries.len());
+        assert_eq!(state.session_id.as_deref(), Some("abc"));
+        assert!(state.buffered.is_empty());
+        for (incremental, full) in state.entries.iter().zip(&whole.entries) {
+            assert_eq!(incremental.content, full.content);
+        }
+
+        // Executors without a line-based override fall back to the whole buffer
+        let aider = AiderExecutor::new();
+        let aider_log = r#"{"timestamp":null,"entry_type":{"type":"assistant_message"},"content":"Done","metadata":null}"#;
+        let mut state = NormalizerState::new("/tmp");
+        state.advance(&aider, &aider_log[..20]).unwrap();
+        state.advance(&aider, aider_log).unwrap();
+        assert_eq!(
+            state.entries.len(),
+            aider
+                .normalize_logs(aider_log, "/tmp")
+                .unwrap()
+                .entries
+                .len()
+        );
+    }
+
+    #[test]
+    fn test_entries_are_timed_by_the_output_they_came_from() {
+        let tool_call = r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Bash","input":{"command":"ls"}}]}}"#;
+        let reply = r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Done"}]}}"#;
+        let log = format!("{}\n{}\n", tool_call, reply);
+        let started_at = Utc::now();
+        let replied_at = started_at + chrono::Duration::seconds(3);
+        let timeline = [(0, started_at), (tool_call.len() + 1, replied_at)];
+
+        let claude = ClaudeExecutor::new();
+        let mut state = NormalizerState::new("/tmp");
+        state.advance_timed(&claude, &log, &timeline).unwrap();
+        let mut entries = state.conversation("claude-code").entries;
+        let tool = entries
+            .iter()
+            .position(|entry| matches!(entry.entry_type, NormalizedEntryType::ToolUse { .. }))
+            .unwrap();
+        assert_eq!(entries[tool].timestamp, Some(started_at.to_rfc3339()));
+        assert_eq!(
+            entries.last().unwrap().timestamp,
+            Some(replied_at.to_rfc3339())
+        );
+
+        time_tool_calls(&mut entries, None);
+        assert_eq!(entries[tool].duration_ms, Some(3000));
+        assert_eq!(entries.last().unwrap().duration_ms, None);
+    }
 }
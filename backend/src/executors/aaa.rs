@@ -5,12 +5,12 @@ use uuid::Uuid;
 
 use crate::{
     command_runner::{CommandProcess, CommandRunner},
+    db::DbPool,
     executor::{
-        ActionType, Executor, ExecutorError, NormalizedConversation, NormalizedEntry,
-        NormalizedEntryType,
+        load_task_for_prompt, templated_prompt, ActionType, EnvironmentRequirements, Executor,
+        ExecutorCapabilities, ExecutorError, NormalizedConversation, NormalizedEntry,
+        NormalizedEntryType, UsageMetrics,
     },
-    models::task::Task,
-    utils::shell::get_shell_command,
 };
 
 /// An executor that uses AAA (Assistant Agent) CLI to process tasks
@@ -35,6 +35,7 @@ impl AaaExecutor {
     }
 
     /// Create a new AaaExecutor with custom settings
+    #[allow(dead_code)]
     pub fn with_command(executor_type: String, command: String) -> Self {
         Self {
             executor_type,
@@ -45,18 +46,38 @@ impl AaaExecutor {
 
 #[async_trait]
 impl Executor for AaaExecutor {
+    fn capabilities(&self) -> ExecutorCapabilities {
+        ExecutorCapabilities {
+            supports_followup: true,
+            supports_sessions: false,
+            supports_plan_mode: false,
+            supports_interactive: false,
+            emits_structured_logs: false,
+        }
+    }
+
+    fn environment_requirements(&self) -> Option<EnvironmentRequirements> {
+        Some(EnvironmentRequirements {
+            binary: "aaa",
+            install_hint: "Install the assistant CLI and put `aaa` on PATH",
+            credentials: None,
+        })
+    }
+
     async fn spawn(
         &self,
-        pool: &sqlx::SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
         worktree_path: &str,
     ) -> Result<CommandProcess, ExecutorError> {
         // Get the task to fetch its description
-        let task = Task::find_by_id(pool, task_id)
-            .await?
-            .ok_or(ExecutorError::TaskNotFound)?;
+        let task = load_task_for_prompt(pool, task_id, worktree_path).await?;
 
-        let problem_statement = if let Some(task_description) = task.description {
+        let problem_statement = if let Some(prompt) =
+            templated_prompt(pool, &task, worktree_path, "aaa").await?
+        {
+            prompt
+        } else if let Some(task_description) = task.description {
             format!(
                 "Task: {} - Description: {} - Please help me implement this task in the codebase. Analyze the current code structure and make the necessary changes to fulfill the requirements.",
                 task.title, task_description
@@ -91,7 +112,7 @@ impl Executor for AaaExecutor {
 
     async fn spawn_followup(
         &self,
-        _pool: &sqlx::SqlitePool,
+        _pool: &DbPool,
         _task_id: Uuid,
         _session_id: &str,
         prompt: &str,
@@ -110,10 +131,7 @@ impl Executor for AaaExecutor {
 
         let proc = command.start().await.map_err(|e| {
             crate::executor::SpawnContext::from_command(&command, &self.executor_type)
-                .with_context(format!(
-                    "{} CLI followup execution",
-                    self.executor_type
-                ))
+                .with_context(format!("{} CLI followup execution", self.executor_type))
                 .spawn_error(e)
         })?;
 
@@ -128,34 +146,73 @@ impl Executor for AaaExecutor {
         let mut entries = Vec::new();
         let session_id = None; // AAA doesn't use session IDs like Claude
 
-        for line in logs.lines() {
+        let mut lines = logs.lines().peekable();
+        while let Some(line) = lines.next() {
             let trimmed = line.trim();
             if trimmed.is_empty() {
                 continue;
             }
 
+            // Token and cost summary lines
+            if let Some(usage) = UsageMetrics::parse_line(trimmed) {
+                entries.push(NormalizedEntry::usage(None, usage));
+                continue;
+            }
+
             // AAA outputs are typically plain text, so we'll categorize them based on content
-            let entry_type = if trimmed.starts_with("Error:") || trimmed.starts_with("❌") {
+            let entry_type = if trimmed.starts_with("Error:")
+                || trimmed.starts_with("❌")
+                || trimmed.starts_with("✅")
+                || trimmed.starts_with("🚀")
+                || trimmed.starts_with("📦")
+            {
                 NormalizedEntryType::SystemMessage
-            } else if trimmed.starts_with("✅") || trimmed.starts_with("🚀") || trimmed.starts_with("📦") {
-                NormalizedEntryType::SystemMessage
-            } else if trimmed.starts_with("User input:") || trimmed.starts_with("Enter your message:") {
+            } else if trimmed.starts_with("User input:")
+                || trimmed.starts_with("Enter your message:")
+            {
                 NormalizedEntryType::UserMessage
             } else if self.is_tool_usage(trimmed) {
                 // Detect tool usage patterns
                 let (tool_name, action_type) = self.extract_tool_info(trimmed, worktree_path);
-                NormalizedEntryType::ToolUse { tool_name, action_type }
+                NormalizedEntryType::ToolUse {
+                    tool_name,
+                    action_type,
+                }
             } else {
                 // Default to assistant message for most content
                 NormalizedEntryType::AssistantMessage
             };
 
+            let written_path = match &entry_type {
+                NormalizedEntryType::ToolUse {
+                    action_type: ActionType::FileWrite { path, .. },
+                    ..
+                } => Some(path.clone()),
+                _ => None,
+            };
             entries.push(NormalizedEntry {
+                id: None,
                 timestamp: None,
+                duration_ms: None,
                 entry_type,
                 content: trimmed.to_string(),
                 metadata: None,
             });
+
+            // A write may be followed by the unified diff of what it changed
+            if let Some(path) = written_path {
+                let mut diff = String::new();
+                while let Some(next) = lines.next_if(|next| is_diff_line(next, !diff.is_empty())) {
+                    diff.push_str(next);
+                    diff.push('\n');
+                }
+                if !diff.is_empty() {
+                    if !diff.starts_with("--- ") {
+                        diff = format!("--- a/{}\n+++ b/{}\n{}", path, path, diff);
+                    }
+                    entries.push(NormalizedEntry::file_patch(None, &path, diff));
+                }
+            }
         }
 
         Ok(NormalizedConversation {
@@ -164,43 +221,76 @@ impl Executor for AaaExecutor {
             executor_type: self.executor_type.clone(),
             prompt: None,
             summary: None,
+            diff_summary: None,
         })
     }
 }
 
+/// Whether `line` is part of a unified diff: its file header or first hunk header, or once
+/// `started`, any line of its hunks
+fn is_diff_line(line: &str, started: bool) -> bool {
+    if line.starts_with("--- ") || line.starts_with("@@ ") {
+        return true;
+    }
+    started && (line.starts_with("+++ ") || line.starts_with(['+', '-', ' ', '\\']))
+}
+
 impl AaaExecutor {
     /// Check if a line indicates tool usage
     fn is_tool_usage(&self, line: &str) -> bool {
-        line.contains("Reading file:") ||
-        line.contains("Writing file:") ||
-        line.contains("Running command:") ||
-        line.contains("Searching for:") ||
-        line.contains("Creating task:") ||
-        line.contains("Fetching URL:")
+        line.contains("Reading file:")
+            || line.contains("Writing file:")
+            || line.contains("Running command:")
+            || line.contains("Searching for:")
+            || line.contains("Creating task:")
+            || line.contains("Fetching URL:")
     }
 
     /// Extract tool information from a line
     fn extract_tool_info(&self, line: &str, worktree_path: &str) -> (String, ActionType) {
         if line.contains("Reading file:") {
             let path = self.extract_path_from_line(line, worktree_path);
-            ("file_read".to_string(), ActionType::FileRead { path })
+            (
+                "file_read".to_string(),
+                ActionType::FileRead {
+                    path,
+                    location: None,
+                },
+            )
         } else if line.contains("Writing file:") {
             let path = self.extract_path_from_line(line, worktree_path);
-            ("file_write".to_string(), ActionType::FileWrite { path })
+            (
+                "file_write".to_string(),
+                ActionType::FileWrite {
+                    path,
+                    location: None,
+                },
+            )
         } else if line.contains("Running command:") {
             let command = self.extract_command_from_line(line);
-            ("command_run".to_string(), ActionType::CommandRun { command })
+            (
+                "command_run".to_string(),
+                ActionType::CommandRun { command },
+            )
         } else if line.contains("Searching for:") {
             let query = self.extract_query_from_line(line);
             ("search".to_string(), ActionType::Search { query })
         } else if line.contains("Creating task:") {
             let description = self.extract_description_from_line(line);
-            ("task_create".to_string(), ActionType::TaskCreate { description })
+            (
+                "task_create".to_string(),
+                ActionType::TaskCreate { description },
+            )
         } else if line.contains("Fetching URL:") {
             let url = self.extract_url_from_line(line);
             ("web_fetch".to_string(), ActionType::WebFetch { url })
         } else {
-            ("unknown".to_string(), ActionType::Other { description: line.to_string() })
+            (
+                "unknown".to_string(),
+                ActionType::Other {
+                    description: line.to_string(),
+                },
+            )
         }
     }
 
@@ -299,4 +389,43 @@ mod tests {
         assert!(executor.is_tool_usage("Running command: npm install"));
         assert!(!executor.is_tool_usage("This is just a regular message"));
     }
+
+    #[test]
+    fn test_write_logs_carry_their_diff() {
+        let executor = AaaExecutor::new();
+        let logs = "Writing file: /tmp/wt/src/main.rs\n@@ -1 +1 @@\n-fn main() {}\n+fn main() { run() }\nDone editing.";
+
+        let result = executor.normalize_logs(logs, "/tmp/wt").unwrap();
+
+        assert_eq!(result.entries.len(), 3);
+        match &result.entries[1].entry_type {
+            NormalizedEntryType::FileEdit { path, diff } => {
+                assert_eq!(path, "src/main.rs");
+                assert_eq!(
+                    diff,
+                    "--- a/src/main.rs\n+++ b/src/main.rs\n@@ -1 +1 @@\n-fn main() {}\n+fn main() { run() }\n"
+                );
+            }
+            other => panic!("expected a file edit, got {:?}", other),
+        }
+        assert_eq!(result.entries[2].content, "Done editing.");
+    }
+
+    #[test]
+    fn test_normalize_logs_reports_usage() {
+        let executor = AaaExecutor::new();
+        let logs = "Done editing.\nTokens: 1,200 input, 340 output\nCost: $0.0150";
+
+        let result = executor.normalize_logs(logs, "/tmp").unwrap();
+
+        assert_eq!(result.entries.len(), 3);
+        assert_eq!(
+            UsageMetrics::total(&result.entries),
+            Some(UsageMetrics {
+                input_tokens: 1200,
+                output_tokens: 340,
+                cost_usd: Some(0.015),
+            })
+        );
+    }
 }
@@ -0,0 +1,871 @@
+//! Executor for agents that speak the Agent Client Protocol (ACP): JSON-RPC messages, one per
+//! line, on the agent's stdin and stdout. Several agents speak it natively or through an
+//! adapter, so each is only a command in the config's `acp_agents`, selectable as
+//! `acp:<name>`. The run's stdin starts with the handshake and the session; the prompt and
+//! the answers to the agent's requests are sent by [`acp_session`](crate::services::acp_session)
+//! as the agent gets to them.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, RwLock},
+};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    command_runner::{CommandProcess, CommandRunner},
+    db::DbPool,
+    executor::{
+        image_paths_prompt, load_task_for_prompt, stream_output_to_db, stream_stdout_to_db_with,
+        templated_prompt, ActionType, Executor, ExecutorCapabilities, ExecutorError,
+        NormalizedConversation, NormalizedEntry, NormalizedEntryType, SpawnContext,
+    },
+    models::executor_session::ExecutorSession,
+    services::command_approval::ApprovalReplies,
+    utils::{path::make_path_relative, shell::get_shell_command},
+};
+
+/// The protocol version asked for in the handshake
+const PROTOCOL_VERSION: u64 = 1;
+
+/// Ids of the requests sent to the agent, which its replies carry
+const INITIALIZE_REQUEST_ID: u64 = 0;
+const SESSION_REQUEST_ID: u64 = 1;
+const PROMPT_REQUEST_ID: u64 = 2;
+
+/// JSON-RPC's code for a method the receiver doesn't serve
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// An agent speaking ACP on stdio, selectable as executor `acp:<name>`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AcpAgentDefinition {
+    pub name: String,
+    /// Shell command run in the worktree that starts the agent in ACP mode
+    pub command: String,
+}
+
+/// The agents a new config comes with, each speaking ACP natively or through its adapter
+pub fn default_agents() -> Vec<AcpAgentDefinition> {
+    [
+        ("amp", "npx -y amp-acp"),
+        ("claude", "npx -y @zed-industries/claude-code-acp"),
+        (
+            "gemini",
+            "npx -y @google/gemini-cli@latest --experimental-acp",
+        ),
+        ("opencode", "npx -y opencode-ai@latest acp"),
+    ]
+    .into_iter()
+    .map(|(name, command)| AcpAgentDefinition {
+        name: name.to_string(),
+        command: command.to_string(),
+    })
+    .collect()
+}
+
+/// The prompt of a started run, sent once the agent has set up its session
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingTurn {
+    /// The session being resumed; a new session's id comes with the agent's reply
+    pub session_id: Option<String>,
+    pub prompt: String,
+}
+
+lazy_static::lazy_static! {
+    static ref DEFINITIONS: RwLock<HashMap<String, Arc<AcpAgentDefinition>>> =
+        RwLock::new(HashMap::new());
+    static ref PENDING_TURNS: Mutex<HashMap<Uuid, PendingTurn>> = Mutex::new(HashMap::new());
+}
+
+/// Make the config's agents the ones `acp:<name>` executors resolve to. Called whenever the
+/// config is loaded or saved.
+pub fn register_definitions(definitions: &[AcpAgentDefinition]) {
+    let mut registered = DEFINITIONS.write().unwrap_or_else(|e| e.into_inner());
+    *registered = definitions
+        .iter()
+        .map(|definition| (definition.name.clone(), Arc::new(definition.clone())))
+        .collect();
+}
+
+fn find_definition(name: &str) -> Option<Arc<AcpAgentDefinition>> {
+    let registered = DEFINITIONS.read().unwrap_or_else(|e| e.into_inner());
+    registered.get(name).cloned()
+}
+
+/// The turn the execution's session is waiting for, handed over once
+pub fn take_pending_turn(execution_process_id: Uuid) -> Option<PendingTurn> {
+    PENDING_TURNS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&execution_process_id)
+}
+
+fn set_pending_turn(execution_process_id: Uuid, turn: PendingTurn) {
+    PENDING_TURNS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(execution_process_id, turn);
+}
+
+fn request(id: u64, method: &str, params: Value) -> String {
+    let message = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+    format!("{}\n", message)
+}
+
+fn response(id: &Value, result: Value) -> String {
+    format!(
+        "{}\n",
+        json!({ "jsonrpc": "2.0", "id": id, "result": result })
+    )
+}
+
+fn error_response(id: &Value, code: i64, message: &str) -> String {
+    let error = json!({ "code": code, "message": message });
+    format!(
+        "{}\n",
+        json!({ "jsonrpc": "2.0", "id": id, "error": error })
+    )
+}
+
+/// What a run writes first: the handshake, then a new session in the worktree or the
+/// resumed one loaded
+fn opening_messages(worktree_path: &str, resume: Option<&str>) -> String {
+    let mut messages = request(
+        INITIALIZE_REQUEST_ID,
+        "initialize",
+        json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            // The agent reads and writes files and runs commands itself
+            "clientCapabilities": {
+                "fs": { "readTextFile": false, "writeTextFile": false },
+                "terminal": false
+            },
+        }),
+    );
+    messages.push_str(&match resume {
+        Some(session_id) => request(
+            SESSION_REQUEST_ID,
+            "session/load",
+            json!({ "sessionId": session_id, "cwd": worktree_path, "mcpServers": [] }),
+        ),
+        None => request(
+            SESSION_REQUEST_ID,
+            "session/new",
+            json!({ "cwd": worktree_path, "mcpServers": [] }),
+        ),
+    });
+    messages
+}
+
+/// The request sending `prompt` to the session
+pub fn prompt_request(session_id: &str, prompt: &str) -> String {
+    request(
+        PROMPT_REQUEST_ID,
+        "session/prompt",
+        json!({ "sessionId": session_id, "prompt": [{ "type": "text", "text": prompt }] }),
+    )
+}
+
+/// The id of the new session, from the agent's reply to creating it
+fn new_session_id(line: &str) -> Option<String> {
+    let message: Value = serde_json::from_str(line.trim()).ok()?;
+    if message.get("id").and_then(Value::as_u64) != Some(SESSION_REQUEST_ID) {
+        return None;
+    }
+    message
+        .pointer("/result/sessionId")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// What has to be acted on in a line the agent wrote
+#[derive(Debug, Clone, PartialEq)]
+pub enum AgentMessage {
+    /// The session is set up and waiting for the prompt; a new session's id is given
+    SessionReady { session_id: Option<String> },
+    /// The agent has nothing more to do: the turn ended, or the session couldn't be set up
+    Finished,
+    /// The agent asks before running a tool call, described by `command`
+    PermissionRequest {
+        command: String,
+        replies: ApprovalReplies,
+    },
+    /// A request this side doesn't serve, turned down with `reply`
+    Unsupported { reply: String },
+}
+
+/// What the agent wants from this side, if anything
+pub fn parse_agent_message(line: &str) -> Option<AgentMessage> {
+    let message: Value = serde_json::from_str(line.trim()).ok()?;
+    let id = message.get("id");
+    match (message.get("method").and_then(Value::as_str), id) {
+        (Some("session/request_permission"), Some(id)) => {
+            let params = message.get("params").unwrap_or(&Value::Null);
+            Some(permission_request(id, params))
+        }
+        (Some(method), Some(id)) => Some(AgentMessage::Unsupported {
+            reply: error_response(
+                id,
+                METHOD_NOT_FOUND,
+                &format!("{} is not supported", method),
+            ),
+        }),
+        // Notifications only go into the conversation
+        (Some(_), None) => None,
+        (None, id) => {
+            let failed = message.get("error").is_some();
+            match id?.as_u64()? {
+                SESSION_REQUEST_ID if !failed => Some(AgentMessage::SessionReady {
+                    session_id: message
+                        .pointer("/result/sessionId")
+                        .and_then(Value::as_str)
+                        .map(str::to_string),
+                }),
+                SESSION_REQUEST_ID | PROMPT_REQUEST_ID => Some(AgentMessage::Finished),
+                INITIALIZE_REQUEST_ID if failed => Some(AgentMessage::Finished),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// The command a tool call runs, or its title for tool calls that run none
+fn tool_call_command(tool_call: &Value) -> Option<String> {
+    match tool_call.pointer("/rawInput/command") {
+        Some(Value::String(command)) => Some(command.clone()),
+        Some(Value::Array(parts)) => Some(
+            parts
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        _ => tool_call
+            .get("title")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+    }
+}
+
+fn permission_request(id: &Value, params: &Value) -> AgentMessage {
+    let tool_call = params.get("toolCall").unwrap_or(&Value::Null);
+    let options = params
+        .get("options")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    // The first of `kinds` the agent offers, so nothing is allowed for good by accident
+    let option = |kinds: &[&str]| {
+        kinds.iter().find_map(|kind| {
+            options
+                .iter()
+                .find(|option| option.get("kind").and_then(Value::as_str) == Some(*kind))
+                .and_then(|option| option.get("optionId").cloned())
+        })
+    };
+    let answer = |option_id: Option<Value>| {
+        let outcome = match option_id {
+            Some(option_id) => json!({ "outcome": "selected", "optionId": option_id }),
+            None => json!({ "outcome": "cancelled" }),
+        };
+        response(id, json!({ "outcome": outcome }))
+    };
+
+    AgentMessage::PermissionRequest {
+        command: tool_call_command(tool_call).unwrap_or_else(|| "a tool call".to_string()),
+        replies: ApprovalReplies {
+            allow: answer(option(&["allow_once", "allow_always"])),
+            deny: answer(option(&["reject_once", "reject_always"])),
+        },
+    }
+}
+
+/// The entry a tool call is shown as, from everything the agent said about it so far
+fn tool_call_entry(tool_call: &Value, worktree_path: &str) -> NormalizedEntry {
+    let title = tool_call
+        .get("title")
+        .and_then(Value::as_str)
+        .unwrap_or("Tool call")
+        .to_string();
+    let kind = tool_call
+        .get("kind")
+        .and_then(Value::as_str)
+        .unwrap_or("other");
+    let path = tool_call
+        .pointer("/locations/0/path")
+        .and_then(Value::as_str)
+        .map(|path| make_path_relative(path, worktree_path));
+    let input = |key: &str| {
+        tool_call
+            .get("rawInput")
+            .and_then(|input| input.get(key))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+
+    let (action_type, content) = match (kind, path) {
+        ("read", Some(path)) => (
+            ActionType::FileRead {
+                path: path.clone(),
+                location: None,
+            },
+            format!("`{}`", path),
+        ),
+        ("edit" | "delete" | "move", Some(path)) => (
+            ActionType::FileWrite {
+                path: path.clone(),
+                location: None,
+            },
+            format!("`{}`", path),
+        ),
+        ("execute", _) => {
+            let command = tool_call_command(tool_call).unwrap_or_else(|| title.clone());
+            (
+                ActionType::CommandRun {
+                    command: command.clone(),
+                },
+                format!("`{}`", command),
+            )
+        }
+        ("search", _) => {
+            let query = input("pattern")
+                .or_else(|| input("query"))
+                .unwrap_or_else(|| title.clone());
+            (
+                ActionType::Search {
+                    query: query.clone(),
+                },
+                format!("`{}`", query),
+            )
+        }
+        ("fetch", _) => {
+            let url = input("url").unwrap_or_else(|| title.clone());
+            (ActionType::WebFetch { url: url.clone() }, url)
+        }
+        _ => (
+            ActionType::Other {
+                description: title.clone(),
+            },
+            title,
+        ),
+    };
+    NormalizedEntry {
+        id: None,
+        timestamp: None,
+        duration_ms: None,
+        entry_type: NormalizedEntryType::ToolUse {
+            tool_name: kind.to_string(),
+            action_type,
+        },
+        content,
+        metadata: Some(tool_call.clone()),
+    }
+}
+
+/// The agent's plan, shown like the TODO lists of other agents
+fn plan_entry(update: &Value) -> Option<NormalizedEntry> {
+    let items: Vec<String> = update
+        .get("entries")?
+        .as_array()?
+        .iter()
+        .filter_map(|item| {
+            let content = item.get("content")?.as_str()?;
+            let emoji = match item.get("status").and_then(Value::as_str) {
+                Some("completed") => "✅",
+                Some("in_progress") => "🔄",
+                _ => "⏳",
+            };
+            let priority = item
+                .get("priority")
+                .and_then(Value::as_str)
+                .unwrap_or("medium");
+            Some(format!("{} {} ({})", emoji, content, priority))
+        })
+        .collect();
+    (!items.is_empty()).then(|| NormalizedEntry {
+        id: None,
+        timestamp: None,
+        duration_ms: None,
+        entry_type: NormalizedEntryType::ToolUse {
+            tool_name: "plan".to_string(),
+            action_type: ActionType::Other {
+                description: "Plan".to_string(),
+            },
+        },
+        content: format!("TODO List:\n{}", items.join("\n")),
+        metadata: Some(update.clone()),
+    })
+}
+
+/// Add a streamed chunk of text to the message it continues, or start one
+fn push_chunk(entries: &mut Vec<NormalizedEntry>, entry_type: NormalizedEntryType, text: &str) {
+    let continues = entries.last().is_some_and(|last| {
+        std::mem::discriminant(&last.entry_type) == std::mem::discriminant(&entry_type)
+    });
+    match entries.last_mut() {
+        Some(last) if continues => last.content.push_str(text),
+        _ => entries.push(NormalizedEntry {
+            id: None,
+            timestamp: None,
+            duration_ms: None,
+            entry_type,
+            content: text.to_string(),
+            metadata: None,
+        }),
+    }
+}
+
+/// Turn an ACP log into entries. An agent replays a loaded session before replying to the
+/// load, so only the updates after the session reply are this run's.
+pub fn normalize(logs: &str, worktree_path: &str, executor_type: &str) -> NormalizedConversation {
+    let mut entries = Vec::new();
+    let mut session_id = None;
+    let mut session_ready = false;
+    // Each tool call's entry, and everything said about it so far, by its id
+    let mut tool_calls: HashMap<String, (usize, Value)> = HashMap::new();
+
+    for line in logs.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let Ok(message) = serde_json::from_str::<Value>(trimmed) else {
+            entries.push(NormalizedEntry {
+                id: None,
+                timestamp: None,
+                duration_ms: None,
+                entry_type: NormalizedEntryType::SystemMessage,
+                content: format!("Raw output: {}", trimmed),
+                metadata: None,
+            });
+            continue;
+        };
+
+        if let Some(error) = message.get("error") {
+            let content = error
+                .get("message")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| error.to_string());
+            entries.push(NormalizedEntry {
+                id: None,
+                timestamp: None,
+                duration_ms: None,
+                entry_type: NormalizedEntryType::ErrorMessage,
+                content,
+                metadata: Some(message.clone()),
+            });
+            continue;
+        }
+
+        match message.get("method").and_then(Value::as_str) {
+            None => match message.get("id").and_then(Value::as_u64) {
+                Some(SESSION_REQUEST_ID) => {
+                    session_ready = true;
+                    if let Some(id) = message.pointer("/result/sessionId").and_then(Value::as_str) {
+                        session_id = Some(id.to_string());
+                    }
+                }
+                Some(PROMPT_REQUEST_ID) => {
+                    let stop_reason = message
+                        .pointer("/result/stopReason")
+                        .and_then(Value::as_str)
+                        .unwrap_or("end_turn")
+                        .to_string();
+                    if stop_reason != "end_turn" {
+                        entries.push(NormalizedEntry {
+                            id: None,
+                            timestamp: None,
+                            duration_ms: None,
+                            entry_type: NormalizedEntryType::SystemMessage,
+                            content: format!("Stopped: {}", stop_reason.replace('_', " ")),
+                            metadata: Some(message.clone()),
+                        });
+                    }
+                }
+                _ => {}
+            },
+            Some("session/update") if session_ready => {
+                let Some(update) = message.pointer("/params/update") else {
+                    continue;
+                };
+                let text = update
+                    .pointer("/content/text")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                match update.get("sessionUpdate").and_then(Value::as_str) {
+                    Some("agent_message_chunk") => {
+                        push_chunk(&mut entries, NormalizedEntryType::AssistantMessage, text)
+                    }
+                    Some("agent_thought_chunk") => {
+                        push_chunk(&mut entries, NormalizedEntryType::Thinking, text)
+                    }
+                    Some("tool_call") => {
+                        let id = update
+                            .get("toolCallId")
+                            .and_then(Value::as_str)
+                            .unwrap_or_default()
+                            .to_string();
+                        tool_calls.insert(id, (entries.len(), update.clone()));
+                        entries.push(tool_call_entry(update, worktree_path));
+                    }
+                    Some("tool_call_update") => {
+                        let id = update
+                            .get("toolCallId")
+                            .and_then(Value::as_str)
+                            .unwrap_or_default();
+                        let Some((index, tool_call)) = tool_calls.get_mut(id) else {
+                            continue;
+                        };
+                        if let (Value::Object(known), Value::Object(fields)) =
+                            (&mut *tool_call, update)
+                        {
+                            for (key, value) in fields {
+                                if !value.is_null() {
+                                    known.insert(key.clone(), value.clone());
+                                }
+                            }
+                        }
+                        entries[*index] = tool_call_entry(tool_call, worktree_path);
+                        if update.get("status").and_then(Value::as_str) == Some("failed") {
+                            let title = tool_call
+                                .get("title")
+                                .and_then(Value::as_str)
+                                .unwrap_or("Tool call");
+                            entries.push(NormalizedEntry {
+                                id: None,
+                                timestamp: None,
+                                duration_ms: None,
+                                entry_type: NormalizedEntryType::ErrorMessage,
+                                content: format!("{} failed", title),
+                                metadata: Some(update.clone()),
+                            });
+                        }
+                    }
+                    Some("plan") => entries.extend(plan_entry(update)),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    NormalizedConversation {
+        entries,
+        session_id,
+        executor_type: executor_type.to_string(),
+        prompt: None,
+        summary: None,
+        diff_summary: None,
+    }
+}
+
+/// Runs the ACP agent of the same name from the config
+pub struct AcpExecutor {
+    name: String,
+}
+
+impl AcpExecutor {
+    pub fn new(name: String) -> Self {
+        Self { name }
+    }
+
+    fn executor_type(&self) -> String {
+        format!("acp:{}", self.name)
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn definition(&self) -> Result<Arc<AcpAgentDefinition>, ExecutorError> {
+        find_definition(&self.name).ok_or_else(|| {
+            ExecutorError::ContextCollectionFailed(format!(
+                "No ACP agent named '{}' is configured",
+                self.name
+            ))
+        })
+    }
+
+    async fn task_prompt(
+        &self,
+        pool: &DbPool,
+        task_id: Uuid,
+        worktree_path: &str,
+    ) -> Result<String, ExecutorError> {
+        let task = load_task_for_prompt(pool, task_id, worktree_path).await?;
+        let templated = templated_prompt(pool, &task, worktree_path, &self.executor_type()).await?;
+        let mut prompt = match (templated, &task.description) {
+            (Some(prompt), _) => prompt,
+            (None, Some(description)) => format!(
+                "Task title: {}\nTask description: {}",
+                task.title, description
+            ),
+            (None, None) => format!("Task title: {}", task.title),
+        };
+        prompt.push_str(&image_paths_prompt(pool, task_id).await?);
+        Ok(prompt)
+    }
+
+    async fn start(
+        &self,
+        worktree_path: &str,
+        resume: Option<&str>,
+        task: Option<(Uuid, String)>,
+        context: String,
+    ) -> Result<CommandProcess, ExecutorError> {
+        let definition = self.definition()?;
+        let (shell_cmd, shell_arg) = get_shell_command();
+        let mut command = CommandRunner::new();
+        command
+            .command(shell_cmd)
+            .arg(shell_arg)
+            .arg(&definition.command)
+            .stdin(&opening_messages(worktree_path, resume))
+            .keep_stdin_open()
+            .working_dir(worktree_path);
+
+        command.start().await.map_err(|e| {
+            let mut spawn_context = SpawnContext::from_command(&command, self.executor_type());
+            if let Some((task_id, title)) = task {
+                spawn_context = spawn_context.with_task(task_id, Some(title));
+            }
+            spawn_context.with_context(context).spawn_error(e)
+        })
+    }
+}
+
+#[async_trait]
+impl Executor for AcpExecutor {
+    fn capabilities(&self) -> ExecutorCapabilities {
+        ExecutorCapabilities {
+            supports_followup: true,
+            supports_sessions: true,
+            supports_plan_mode: false,
+            supports_interactive: false,
+            emits_structured_logs: true,
+        }
+    }
+
+    /// Starts the agent and its session; the prompt is sent by `execute_streaming` once the
+    /// session is set up
+    async fn spawn(
+        &self,
+        pool: &DbPool,
+        task_id: Uuid,
+        worktree_path: &str,
+    ) -> Result<CommandProcess, ExecutorError> {
+        let task = load_task_for_prompt(pool, task_id, worktree_path).await?;
+        let context = format!("{} execution for new task", self.executor_type());
+        self.start(worktree_path, None, Some((task_id, task.title)), context)
+            .await
+    }
+
+    /// Starts the agent with the session loaded; agents that can't load sessions end the run
+    /// with an error
+    async fn spawn_followup(
+        &self,
+        _pool: &DbPool,
+        _task_id: Uuid,
+        session_id: &str,
+        _prompt: &str,
+        worktree_path: &str,
+    ) -> Result<CommandProcess, ExecutorError> {
+        let context = format!(
+            "{} follow-up for session {}",
+            self.executor_type(),
+            session_id
+        );
+        self.start(worktree_path, Some(session_id), None, context)
+            .await
+    }
+
+    fn normalize_logs(
+        &self,
+        logs: &str,
+        worktree_path: &str,
+    ) -> Result<NormalizedConversation, String> {
+        Ok(normalize(logs, worktree_path, &self.executor_type()))
+    }
+
+    async fn setup_streaming(
+        &self,
+        child: &mut CommandProcess,
+        pool: &DbPool,
+        attempt_id: Uuid,
+        execution_process_id: Uuid,
+    ) -> Result<(), ExecutorError> {
+        let streams = child
+            .stream()
+            .await
+            .expect("Failed to get stdio from child process");
+        let stdout = streams
+            .stdout
+            .expect("Failed to take stdout from child process");
+        let stderr = streams
+            .stderr
+            .expect("Failed to take stderr from child process");
+
+        tokio::spawn(stream_stdout_to_db_with(
+            stdout,
+            pool.clone(),
+            attempt_id,
+            execution_process_id,
+            new_session_id,
+        ));
+        tokio::spawn(stream_output_to_db(
+            stderr,
+            pool.clone(),
+            attempt_id,
+            execution_process_id,
+            false,
+        ));
+        Ok(())
+    }
+
+    async fn execute_streaming(
+        &self,
+        pool: &DbPool,
+        task_id: Uuid,
+        attempt_id: Uuid,
+        execution_process_id: Uuid,
+        worktree_path: &str,
+    ) -> Result<CommandProcess, ExecutorError> {
+        let prompt = self.task_prompt(pool, task_id, worktree_path).await?;
+        let mut child = self.spawn(pool, task_id, worktree_path).await?;
+        set_pending_turn(
+            execution_process_id,
+            PendingTurn {
+                session_id: None,
+                prompt,
+            },
+        );
+        Self::setup_streaming(self, &mut child, pool, attempt_id, execution_process_id).await?;
+        Ok(child)
+    }
+
+    async fn execute_followup_streaming(
+        &self,
+        pool: &DbPool,
+        task_id: Uuid,
+        attempt_id: Uuid,
+        execution_process_id: Uuid,
+        session_id: &str,
+        prompt: &str,
+        worktree_path: &str,
+    ) -> Result<CommandProcess, ExecutorError> {
+        // A loaded session keeps its id, which the agent's reply doesn't repeat
+        if let Err(e) =
+            ExecutorSession::update_session_id(pool, execution_process_id, session_id).await
+        {
+            tracing::error!(
+                "Failed to update session ID for followup execution process {}: {}",
+                execution_process_id,
+                e
+            );
+        }
+
+        let mut child = self
+            .spawn_followup(pool, task_id, session_id, prompt, worktree_path)
+            .await?;
+        set_pending_turn(
+            execution_process_id,
+            PendingTurn {
+                session_id: Some(session_id.to_string()),
+                prompt: prompt.to_string(),
+            },
+        );
+        Self::setup_streaming(self, &mut child, pool, attempt_id, execution_process_id).await?;
+        Ok(child)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_updates_after_the_session_reply_are_normalized() {
+        let logs = concat!(
+            r#"{"jsonrpc":"2.0","id":0,"result":{"protocolVersion":1}}"#,
+            "\n",
+            // Replayed from the loaded session, before the reply
+            r#"{"jsonrpc":"2.0","method":"session/update","params":{"sessionId":"s-1","update":{"sessionUpdate":"agent_message_chunk","content":{"type":"text","text":"Earlier"}}}}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","id":1,"result":{"sessionId":"s-1"}}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","method":"session/update","params":{"sessionId":"s-1","update":{"sessionUpdate":"agent_message_chunk","content":{"type":"text","text":"Running "}}}}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","method":"session/update","params":{"sessionId":"s-1","update":{"sessionUpdate":"agent_message_chunk","content":{"type":"text","text":"the tests"}}}}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","method":"session/update","params":{"sessionId":"s-1","update":{"sessionUpdate":"tool_call","toolCallId":"t-1","title":"Run tests","kind":"execute","status":"pending"}}}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","method":"session/update","params":{"sessionId":"s-1","update":{"sessionUpdate":"tool_call_update","toolCallId":"t-1","rawInput":{"command":"cargo test"},"status":"failed"}}}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","method":"session/update","params":{"sessionId":"s-1","update":{"sessionUpdate":"tool_call","toolCallId":"t-2","title":"Read main.rs","kind":"read","locations":[{"path":"/repo/src/main.rs"}]}}}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","id":2,"result":{"stopReason":"max_tokens"}}"#,
+            "\n",
+        );
+        let conversation = normalize(logs, "/repo", "acp:example");
+
+        assert_eq!(conversation.session_id.as_deref(), Some("s-1"));
+        let entries = &conversation.entries;
+        assert_eq!(entries.len(), 5);
+        assert_eq!(entries[0].content, "Running the tests");
+        assert!(matches!(
+            &entries[1].entry_type,
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::CommandRun { command },
+                ..
+            } if command == "cargo test"
+        ));
+        assert!(matches!(
+            entries[2].entry_type,
+            NormalizedEntryType::ErrorMessage
+        ));
+        assert!(matches!(
+            &entries[3].entry_type,
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::FileRead { path, .. },
+                ..
+            } if path == "src/main.rs"
+        ));
+        assert_eq!(entries[4].content, "Stopped: max tokens");
+    }
+
+    #[test]
+    fn test_agent_requests_are_answered() {
+        let request = r#"{"jsonrpc":"2.0","id":7,"method":"session/request_permission","params":{"sessionId":"s-1","toolCall":{"toolCallId":"t-1","title":"Delete build","rawInput":{"command":"rm -rf build"}},"options":[{"optionId":"always","name":"Always allow","kind":"allow_always"},{"optionId":"once","name":"Allow","kind":"allow_once"},{"optionId":"no","name":"Reject","kind":"reject_once"}]}}"#;
+        let Some(AgentMessage::PermissionRequest { command, replies }) =
+            parse_agent_message(request)
+        else {
+            panic!("not a permission request");
+        };
+        assert_eq!(command, "rm -rf build");
+        let allow: Value = serde_json::from_str(&replies.allow).unwrap();
+        assert_eq!(allow["id"], 7);
+        assert_eq!(allow["result"]["outcome"]["optionId"], "once");
+        let deny: Value = serde_json::from_str(&replies.deny).unwrap();
+        assert_eq!(deny["result"]["outcome"]["optionId"], "no");
+
+        assert_eq!(
+            parse_agent_message(r#"{"jsonrpc":"2.0","id":1,"result":{"sessionId":"s-1"}}"#),
+            Some(AgentMessage::SessionReady {
+                session_id: Some("s-1".to_string())
+            })
+        );
+        assert_eq!(
+            parse_agent_message(r#"{"jsonrpc":"2.0","id":2,"result":{"stopReason":"end_turn"}}"#),
+            Some(AgentMessage::Finished)
+        );
+        assert!(matches!(
+            parse_agent_message(
+                r#"{"jsonrpc":"2.0","id":8,"method":"fs/read_text_file","params":{}}"#
+            ),
+            Some(AgentMessage::Unsupported { .. })
+        ));
+    }
+}
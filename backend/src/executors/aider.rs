@@ -5,15 +5,17 @@ use uuid::Uuid;
 
 use crate::{
     command_runner::{CommandProcess, CommandRunner},
+    db::DbPool,
     executor::{
-        ActionType, Executor, ExecutorError, NormalizedConversation, NormalizedEntry,
-        NormalizedEntryType,
+        agent_flags, image_paths_prompt, load_task_for_prompt, templated_prompt, ActionType,
+        EnvironmentRequirements, Executor, ExecutorCapabilities, ExecutorError,
+        NormalizedConversation, NormalizedEntry, NormalizedEntryType,
     },
-    models::{
-        execution_process::ExecutionProcess, executor_session::ExecutorSession, task::Task,
-        task_attempt::TaskAttempt,
+    models::{execution_process::ExecutionProcess, executor_session::ExecutorSession},
+    utils::{
+        path::make_path_relative,
+        shell::{get_shell_command, shell_quote},
     },
-    utils::{path::make_path_relative, shell::get_shell_command},
 };
 
 // Sub-modules for utilities
@@ -28,6 +30,8 @@ struct DiffBlockState {
     mode: DiffMode,
     /// Accumulated content for the current diff block
     content: Vec<String>,
+    /// The SEARCH half of the current diff block, once its REPLACE half has started
+    search: Vec<String>,
     /// Start timestamp for the diff block
     start_timestamp: Option<chrono::DateTime<chrono::Utc>>,
     /// Buffered line that might be a file name
@@ -48,6 +52,7 @@ impl Default for DiffBlockState {
         Self {
             mode: DiffMode::None,
             content: Vec::new(),
+            search: Vec::new(),
             start_timestamp: None,
             buffered_line: None,
             current_file: None,
@@ -66,7 +71,7 @@ async fn process_line_for_content(
     session_extracted: &mut bool,
     diff_state: &mut DiffBlockState,
     worktree_path: &str,
-    pool: &sqlx::SqlitePool,
+    pool: &DbPool,
     execution_process_id: uuid::Uuid,
 ) -> Option<Content> {
     if !*session_extracted {
@@ -116,21 +121,23 @@ async fn process_line_for_content(
 
                 diff_state.mode = DiffMode::InSearch;
                 diff_state.content.clear();
+                diff_state.search.clear();
                 diff_state.start_timestamp = Some(chrono::Utc::now());
                 return None; // Don't output individual markers
             }
             "=======" => {
                 if diff_state.mode == DiffMode::InSearch {
                     diff_state.mode = DiffMode::InReplace;
+                    diff_state.search = std::mem::take(&mut diff_state.content);
                     return None; // Don't output individual markers
                 }
             }
             ">>>>>>> REPLACE" => {
                 if diff_state.mode == DiffMode::InReplace {
                     // End of diff block - create atomic edit action
-                    let diff_content = diff_state.content.join("\n");
                     let formatted = format_diff_as_normalized_json(
-                        &diff_content,
+                        &diff_state.search.join("\n"),
+                        &diff_state.content.join("\n"),
                         diff_state.current_file.as_deref(),
                         diff_state.start_timestamp,
                         worktree_path,
@@ -139,6 +146,7 @@ async fn process_line_for_content(
                     // Reset state
                     diff_state.mode = DiffMode::None;
                     diff_state.content.clear();
+                    diff_state.search.clear();
                     diff_state.start_timestamp = None;
                     diff_state.current_file = None;
 
@@ -218,7 +226,7 @@ async fn process_line_for_content(
 pub async fn stream_aider_stdout_stderr_to_db(
     stdout: impl tokio::io::AsyncRead + Unpin + Send + 'static,
     stderr: impl tokio::io::AsyncRead + Unpin + Send + 'static,
-    pool: sqlx::SqlitePool,
+    pool: DbPool,
     attempt_id: Uuid,
     execution_process_id: Uuid,
     worktree_path: String,
@@ -345,9 +353,11 @@ pub async fn stream_aider_stdout_stderr_to_db(
     let _ = tokio::join!(stdout_task, stderr_task);
 }
 
-/// Format diff content as a normalized JSON entry for atomic edit actions
+/// Format a SEARCH/REPLACE block as normalized JSON entries: the atomic edit action, then
+/// the change it makes
 fn format_diff_as_normalized_json(
-    _content: &str,
+    search: &str,
+    replace: &str,
     file_name: Option<&str>,
     start_timestamp: Option<chrono::DateTime<chrono::Utc>>,
     worktree_path: &str,
@@ -361,16 +371,27 @@ fn format_diff_as_normalized_json(
     let path = make_path_relative(&raw_path, worktree_path);
 
     let normalized_entry = NormalizedEntry {
-        timestamp: Some(timestamp_str),
+        id: None,
+        timestamp: Some(timestamp_str.clone()),
+        duration_ms: None,
         entry_type: NormalizedEntryType::ToolUse {
             tool_name: "edit".to_string(),
-            action_type: ActionType::FileWrite { path: path.clone() },
+            action_type: ActionType::FileWrite {
+                path: path.clone(),
+                location: None,
+            },
         },
         content: format!("`{}`", path),
         metadata: None,
     };
 
-    serde_json::to_string(&normalized_entry).unwrap() + "\n"
+    let mut formatted = serde_json::to_string(&normalized_entry).unwrap() + "\n";
+    if let Some(file_edit) =
+        NormalizedEntry::file_edit(Some(timestamp_str), &path, &[(search, replace)])
+    {
+        formatted.push_str(&(serde_json::to_string(&file_edit).unwrap() + "\n"));
+    }
+    formatted
 }
 
 /// Flush any remaining buffered content when stream ends
@@ -417,14 +438,18 @@ pub fn format_aider_content_as_normalized_json(content: &str, _worktree_path: &s
         // Check message type and create appropriate normalized entry
         let normalized_entry = if AiderFilter::is_system_message(trimmed) {
             NormalizedEntry {
+                id: None,
                 timestamp: Some(timestamp_str),
+                duration_ms: None,
                 entry_type: NormalizedEntryType::SystemMessage,
                 content: trimmed.to_string(),
                 metadata: None,
             }
         } else if AiderFilter::is_error(trimmed) {
             NormalizedEntry {
+                id: None,
                 timestamp: Some(timestamp_str),
+                duration_ms: None,
                 entry_type: NormalizedEntryType::ErrorMessage,
                 content: trimmed.to_string(),
                 metadata: None,
@@ -432,7 +457,9 @@ pub fn format_aider_content_as_normalized_json(content: &str, _worktree_path: &s
         } else {
             // Regular assistant message
             NormalizedEntry {
+                id: None,
                 timestamp: Some(timestamp_str),
+                duration_ms: None,
                 entry_type: NormalizedEntryType::AssistantMessage,
                 content: trimmed.to_string(),
                 metadata: None,
@@ -446,6 +473,52 @@ pub fn format_aider_content_as_normalized_json(content: &str, _worktree_path: &s
     results.join("\n") + "\n"
 }
 
+/// Aider's own files (chat and input history, tags cache) all start with this
+const AIDER_FILES_PATTERN: &str = ".aider*";
+
+/// The chat history file of the attempt, kept in its worktree so a follow-up restores the
+/// attempt's own conversation. Aider's files are added to the repository's exclude list
+/// instead of its .gitignore, so they never end up in a commit or the attempt's changes.
+fn prepare_chat_history(worktree_path: &str) -> std::path::PathBuf {
+    if let Err(e) = exclude_aider_files(std::path::Path::new(worktree_path)) {
+        tracing::warn!(
+            "Failed to exclude Aider's files from git in {}: {}",
+            worktree_path,
+            e
+        );
+    }
+    std::path::Path::new(worktree_path).join(".aider.chat.history.md")
+}
+
+fn exclude_aider_files(worktree_path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    let repo = git2::Repository::open(worktree_path)?;
+    // A worktree's git directory points at the main repository's, where info/exclude lives
+    let common_dir = match std::fs::read_to_string(repo.path().join("commondir")) {
+        Ok(common_dir) => repo.path().join(common_dir.trim()),
+        Err(_) => repo.path().to_path_buf(),
+    };
+    let exclude = common_dir.join("info").join("exclude");
+    let current = std::fs::read_to_string(&exclude).unwrap_or_default();
+    if current
+        .lines()
+        .any(|line| line.trim() == AIDER_FILES_PATTERN)
+    {
+        return Ok(());
+    }
+
+    std::fs::create_dir_all(exclude.parent().unwrap_or(worktree_path))?;
+    let separator = if current.is_empty() || current.ends_with('\n') {
+        ""
+    } else {
+        "\n"
+    };
+    std::fs::write(
+        &exclude,
+        format!("{}{}{}\n", current, separator, AIDER_FILES_PATTERN),
+    )?;
+    Ok(())
+}
+
 /// An executor that uses Aider CLI to process tasks
 pub struct AiderExecutor {
     executor_type: String,
@@ -463,77 +536,64 @@ impl AiderExecutor {
     pub fn new() -> Self {
         Self {
             executor_type: "Aider".to_string(),
-            command: "aider . --yes-always --no-show-model-warnings --skip-sanity-check-repo --no-stream --no-fancy-input".to_string(),
+            command: "aider . --yes --no-gitignore --no-show-model-warnings --skip-sanity-check-repo --no-stream --no-fancy-input".to_string(),
         }
     }
 }
 
 #[async_trait]
 impl Executor for AiderExecutor {
+    fn capabilities(&self) -> ExecutorCapabilities {
+        ExecutorCapabilities {
+            supports_followup: true,
+            supports_sessions: true,
+            supports_plan_mode: false,
+            supports_interactive: false,
+            emits_structured_logs: false,
+        }
+    }
+
+    fn environment_requirements(&self) -> Option<EnvironmentRequirements> {
+        Some(EnvironmentRequirements {
+            binary: "aider",
+            install_hint: "Install it with `python -m pip install aider-install && aider-install`",
+            credentials: None,
+        })
+    }
+
     async fn spawn(
         &self,
-        pool: &sqlx::SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
         worktree_path: &str,
     ) -> Result<CommandProcess, ExecutorError> {
         // Get the task to fetch its description
-        let task = Task::find_by_id(pool, task_id)
-            .await?
-            .ok_or(ExecutorError::TaskNotFound)?;
+        let task = load_task_for_prompt(pool, task_id, worktree_path).await?;
 
-        let prompt = if let Some(task_description) = task.description {
-            format!("{}\n{}", task.title, task_description)
-        } else {
-            task.title.to_string()
-        };
-
-        // Create temporary message file
-        let base_dir = TaskAttempt::get_worktree_base_dir();
-        let sessions_dir = base_dir.join("aider").join("aider-messages");
-        if let Err(e) = tokio::fs::create_dir_all(&sessions_dir).await {
-            tracing::warn!(
-                "Failed to create temp message directory {}: {}",
-                sessions_dir.display(),
-                e
-            );
-        }
+        let mut prompt =
+            if let Some(prompt) = templated_prompt(pool, &task, worktree_path, "aider").await? {
+                prompt
+            } else if let Some(task_description) = task.description {
+                format!("{}\n{}", task.title, task_description)
+            } else {
+                task.title.to_string()
+            };
 
-        let message_file = sessions_dir.join(format!("task_{}.md", task_id));
+        // The CLI can't take images, so the agent is pointed at where they are stored
+        prompt.push_str(&image_paths_prompt(pool, task_id).await?);
 
-        // Generate our own session ID and store it in the database immediately
-        let session_id = format!("aider_task_{}", task_id);
-
-        // Create session directory and chat history file for session persistence
-        let session_dir = base_dir.join("aider").join("aider-sessions");
-        if let Err(e) = tokio::fs::create_dir_all(&session_dir).await {
-            tracing::warn!(
-                "Failed to create session directory {}: {}",
-                session_dir.display(),
-                e
-            );
-        }
-        let chat_file = session_dir.join(format!("{}.md", session_id));
+        let chat_file = prepare_chat_history(worktree_path);
 
         // Use shell command for cross-platform compatibility
         let (shell_cmd, shell_arg) = get_shell_command();
         let aider_command = format!(
-            "{} --chat-history-file {} --message-file {}",
+            "{}{} --chat-history-file {} --message {}",
             &self.command,
-            chat_file.to_string_lossy(),
-            message_file.to_string_lossy()
+            agent_flags(),
+            shell_quote(&chat_file.to_string_lossy()),
+            shell_quote(&prompt)
         );
 
-        // Write message file after command is prepared for better error context
-        tokio::fs::write(&message_file, prompt.as_bytes())
-            .await
-            .map_err(|e| {
-                ExecutorError::ContextCollectionFailed(format!(
-                    "Failed to write message file {}: {}",
-                    message_file.display(),
-                    e
-                ))
-            })?;
-
         tracing::debug!("Spawning Aider command: {}", &aider_command);
 
         let mut command = CommandRunner::new();
@@ -551,12 +611,7 @@ impl Executor for AiderExecutor {
                 .spawn_error(e)
         })?;
 
-        tracing::debug!(
-            "Started Aider with message file {} for task {}: {:?}",
-            message_file.display(),
-            task_id,
-            prompt
-        );
+        tracing::debug!("Started Aider for task {}: {:?}", task_id, prompt);
 
         Ok(child)
     }
@@ -564,7 +619,7 @@ impl Executor for AiderExecutor {
     /// Execute with Aider filtering for stdout and stderr
     async fn execute_streaming(
         &self,
-        pool: &sqlx::SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
         attempt_id: Uuid,
         execution_process_id: Uuid,
@@ -642,13 +697,14 @@ impl Executor for AiderExecutor {
             executor_type: "aider".to_string(),
             prompt: None,
             summary: None,
+            diff_summary: None,
         })
     }
 
     /// Execute follow-up with Aider filtering for stdout and stderr
     async fn execute_followup_streaming(
         &self,
-        pool: &sqlx::SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
         attempt_id: Uuid,
         execution_process_id: Uuid,
@@ -706,58 +762,24 @@ impl Executor for AiderExecutor {
 
     async fn spawn_followup(
         &self,
-        _pool: &sqlx::SqlitePool,
+        _pool: &DbPool,
         _task_id: Uuid,
         session_id: &str,
         prompt: &str,
         worktree_path: &str,
     ) -> Result<CommandProcess, ExecutorError> {
-        let base_dir = TaskAttempt::get_worktree_base_dir();
-
-        // Create session directory if it doesn't exist
-        let session_dir = base_dir.join("aider").join("aider-sessions");
-        if let Err(e) = tokio::fs::create_dir_all(&session_dir).await {
-            tracing::warn!(
-                "Failed to create session directory {}: {}",
-                session_dir.display(),
-                e
-            );
-        }
-
-        let chat_file = session_dir.join(format!("{}.md", session_id));
-
-        // Create temporary message file for the followup prompt
-        let sessions_dir = base_dir.join("aider").join("aider-messages");
-        if let Err(e) = tokio::fs::create_dir_all(&sessions_dir).await {
-            tracing::warn!(
-                "Failed to create temp message directory {}: {}",
-                sessions_dir.display(),
-                e
-            );
-        }
-
-        let message_file = sessions_dir.join(format!("followup_{}.md", session_id));
+        let chat_file = prepare_chat_history(worktree_path);
 
         // Use shell command for cross-platform compatibility
         let (shell_cmd, shell_arg) = get_shell_command();
         let aider_command = format!(
-            "{} --restore-chat-history --chat-history-file {} --message-file {}",
+            "{}{} --restore-chat-history --chat-history-file {} --message {}",
             self.command,
-            chat_file.to_string_lossy(),
-            message_file.to_string_lossy()
+            agent_flags(),
+            shell_quote(&chat_file.to_string_lossy()),
+            shell_quote(prompt)
         );
 
-        // Write message file after command is prepared for better error context
-        tokio::fs::write(&message_file, prompt.as_bytes())
-            .await
-            .map_err(|e| {
-                ExecutorError::ContextCollectionFailed(format!(
-                    "Failed to write followup message file {}: {}",
-                    message_file.display(),
-                    e
-                ))
-            })?;
-
         tracing::debug!("Spawning Aider command: {}", &aider_command);
 
         let mut command = CommandRunner::new();
@@ -778,8 +800,7 @@ impl Executor for AiderExecutor {
         })?;
 
         tracing::debug!(
-            "Started Aider followup with message file {} and chat history {} for session {}: {:?}",
-            message_file.display(),
+            "Started Aider followup with chat history {} for session {}: {:?}",
             chat_file.display(),
             session_id,
             prompt
@@ -840,6 +861,32 @@ mod tests {
         assert!(result.entries[3].content.contains("different approach"));
     }
 
+    #[test]
+    fn test_chat_history_stays_out_of_git() {
+        let dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(dir.path()).unwrap();
+        let worktree = dir.path().to_string_lossy().to_string();
+
+        let chat_file = prepare_chat_history(&worktree);
+        prepare_chat_history(&worktree);
+
+        assert_eq!(chat_file, dir.path().join(".aider.chat.history.md"));
+        let exclude = std::fs::read_to_string(dir.path().join(".git/info/exclude")).unwrap();
+        assert_eq!(
+            exclude
+                .lines()
+                .filter(|line| *line == AIDER_FILES_PATTERN)
+                .count(),
+            1
+        );
+
+        std::fs::write(&chat_file, "# aider chat started").unwrap();
+        let repo = git2::Repository::open(dir.path()).unwrap();
+        assert!(repo
+            .status_should_ignore(std::path::Path::new(".aider.chat.history.md"))
+            .unwrap());
+    }
+
     #[test]
     fn test_format_aider_content_as_normalized_json() {
         let content = r#"Main model: anthropic/claude-sonnet-4-20250514
@@ -908,6 +955,37 @@ Let me try a different approach."#;
         }
     }
 
+    #[test]
+    fn test_search_replace_block_carries_its_diff() {
+        let formatted = format_diff_as_normalized_json(
+            "def greet():\n    return 'hi'",
+            "def greet():\n    return 'hello'",
+            Some("/repo/app.py"),
+            None,
+            "/repo",
+        );
+        let entries = AiderExecutor::new()
+            .normalize_logs(&formatted, "/repo")
+            .unwrap()
+            .entries;
+
+        assert_eq!(entries.len(), 2);
+        assert!(matches!(
+            entries[0].entry_type,
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::FileWrite { .. },
+                ..
+            }
+        ));
+        match &entries[1].entry_type {
+            NormalizedEntryType::FileEdit { path, diff } => {
+                assert_eq!(path, "app.py");
+                assert!(diff.contains(" def greet():\n-    return 'hi'\n+    return 'hello'\n"));
+            }
+            other => panic!("expected a file edit, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_normalize_logs_edge_cases() {
         let executor = AiderExecutor::new();
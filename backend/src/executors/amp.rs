@@ -6,12 +6,13 @@ use uuid::Uuid;
 
 use crate::{
     command_runner::{CommandProcess, CommandRunner},
+    db::DbPool,
     executor,
     executor::{
-        ActionType, Executor, ExecutorError, NormalizedConversation, NormalizedEntry,
-        NormalizedEntryType,
+        image_paths_prompt, load_task_for_prompt, normalize_complete_lines, templated_prompt,
+        ActionType, EnvironmentRequirements, Executor, ExecutorCapabilities, ExecutorError,
+        NormalizedConversation, NormalizedEntry, NormalizedEntryType, NormalizerState,
     },
-    models::task::Task,
     utils::shell::get_shell_command,
 };
 
@@ -155,14 +156,18 @@ impl AmpContentItem {
                     _ => return None,
                 };
                 Some(NormalizedEntry {
+                    id: None,
                     timestamp,
+                    duration_ms: None,
                     entry_type,
                     content: text.clone(),
                     metadata: Some(serde_json::to_value(self).unwrap_or(Value::Null)),
                 })
             }
             AmpContentItem::Thinking { thinking } => Some(NormalizedEntry {
+                id: None,
                 timestamp,
+                duration_ms: None,
                 entry_type: NormalizedEntryType::Thinking,
                 content: thinking.clone(),
                 metadata: Some(serde_json::to_value(self).unwrap_or(Value::Null)),
@@ -173,7 +178,9 @@ impl AmpContentItem {
                     executor.generate_concise_content(name, input, &action_type, worktree_path);
 
                 Some(NormalizedEntry {
+                    id: None,
                     timestamp,
+                    duration_ms: None,
                     entry_type: NormalizedEntryType::ToolUse {
                         tool_name: name.clone(),
                         action_type,
@@ -189,33 +196,51 @@ impl AmpContentItem {
 
 #[async_trait]
 impl Executor for AmpExecutor {
+    fn capabilities(&self) -> ExecutorCapabilities {
+        ExecutorCapabilities {
+            supports_followup: true,
+            supports_sessions: true,
+            supports_plan_mode: false,
+            supports_interactive: false,
+            emits_structured_logs: true,
+        }
+    }
+
+    fn environment_requirements(&self) -> Option<EnvironmentRequirements> {
+        Some(EnvironmentRequirements::npx())
+    }
+
     async fn spawn(
         &self,
-        pool: &sqlx::SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
         worktree_path: &str,
     ) -> Result<CommandProcess, ExecutorError> {
         // Get the task to fetch its description
-        let task = Task::find_by_id(pool, task_id)
-            .await?
-            .ok_or(ExecutorError::TaskNotFound)?;
-
-        let prompt = if let Some(task_description) = task.description {
-            format!(
-                r#"project_id: {}
+        let task = load_task_for_prompt(pool, task_id, worktree_path).await?;
+
+        let mut prompt =
+            if let Some(prompt) = templated_prompt(pool, &task, worktree_path, "amp").await? {
+                prompt
+            } else if let Some(task_description) = task.description {
+                format!(
+                    r#"project_id: {}
             
 Task title: {}
 Task description: {}"#,
-                task.project_id, task.title, task_description
-            )
-        } else {
-            format!(
-                r#"project_id: {}
+                    task.project_id, task.title, task_description
+                )
+            } else {
+                format!(
+                    r#"project_id: {}
             
 Task title: {}"#,
-                task.project_id, task.title
-            )
-        };
+                    task.project_id, task.title
+                )
+            };
+
+        // The CLI can't take images, so the agent is pointed at where they are stored
+        prompt.push_str(&image_paths_prompt(pool, task_id).await?);
 
         // Use shell command for cross-platform compatibility
         let (shell_cmd, shell_arg) = get_shell_command();
@@ -242,7 +267,7 @@ Task title: {}"#,
 
     async fn spawn_followup(
         &self,
-        _pool: &sqlx::SqlitePool,
+        _pool: &DbPool,
         _task_id: Uuid,
         session_id: &str,
         prompt: &str,
@@ -295,7 +320,9 @@ Task title: {}"#,
                 Err(_) => {
                     // If line isn't valid JSON, add it as raw text
                     entries.push(NormalizedEntry {
+                        id: None,
                         timestamp: None,
+                        duration_ms: None,
                         entry_type: NormalizedEntryType::SystemMessage,
                         content: format!("Raw output: {}", trimmed),
                         metadata: None,
@@ -324,8 +351,17 @@ Task title: {}"#,
             executor_type: "amp".to_string(),
             prompt: None,
             summary: None,
+            diff_summary: None,
         })
     }
+
+    fn normalize_logs_incremental(
+        &self,
+        chunk: &str,
+        state: &mut NormalizerState,
+    ) -> Result<(), String> {
+        normalize_complete_lines(self, chunk, state)
+    }
 }
 
 impl AmpExecutor {
@@ -356,8 +392,8 @@ impl AmpExecutor {
         worktree_path: &str,
     ) -> String {
         match action_type {
-            ActionType::FileRead { path } => format!("`{}`", path),
-            ActionType::FileWrite { path } => format!("`{}`", path),
+            ActionType::FileRead { path, .. } => format!("`{}`", path),
+            ActionType::FileWrite { path, .. } => format!("`{}`", path),
             ActionType::CommandRun { command } => format!("`{}`", command),
             ActionType::Search { query } => format!("`{}`", query),
             ActionType::WebFetch { url } => format!("`{}`", url),
@@ -508,10 +544,12 @@ impl AmpExecutor {
                 if let Some(path) = input.get("path").and_then(|p| p.as_str()) {
                     ActionType::FileRead {
                         path: self.make_path_relative(path, worktree_path),
+                        location: None,
                     }
                 } else if let Some(file_path) = input.get("file_path").and_then(|p| p.as_str()) {
                     ActionType::FileRead {
                         path: self.make_path_relative(file_path, worktree_path),
+                        location: None,
                     }
                 } else {
                     ActionType::Other {
@@ -523,10 +561,12 @@ impl AmpExecutor {
                 if let Some(path) = input.get("path").and_then(|p| p.as_str()) {
                     ActionType::FileWrite {
                         path: self.make_path_relative(path, worktree_path),
+                        location: None,
                     }
                 } else if let Some(file_path) = input.get("file_path").and_then(|p| p.as_str()) {
                     ActionType::FileWrite {
                         path: self.make_path_relative(file_path, worktree_path),
+                        location: None,
                     }
                 } else {
                     ActionType::Other {
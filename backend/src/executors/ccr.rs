@@ -3,7 +3,11 @@ use uuid::Uuid;
 
 use crate::{
     command_runner::CommandProcess,
-    executor::{Executor, ExecutorError, NormalizedConversation},
+    db::DbPool,
+    executor::{
+        EnvironmentRequirements, Executor, ExecutorCapabilities, ExecutorError,
+        NormalizedConversation,
+    },
     executors::ClaudeExecutor,
 };
 
@@ -28,9 +32,17 @@ impl CCRExecutor {
 
 #[async_trait]
 impl Executor for CCRExecutor {
+    fn capabilities(&self) -> ExecutorCapabilities {
+        self.0.capabilities()
+    }
+
+    fn environment_requirements(&self) -> Option<EnvironmentRequirements> {
+        Some(EnvironmentRequirements::npx())
+    }
+
     async fn spawn(
         &self,
-        pool: &sqlx::SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
         worktree_path: &str,
     ) -> Result<CommandProcess, ExecutorError> {
@@ -39,7 +51,7 @@ impl Executor for CCRExecutor {
 
     async fn spawn_followup(
         &self,
-        pool: &sqlx::SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
         session_id: &str,
         prompt: &str,
@@ -3,8 +3,11 @@ use uuid::Uuid;
 
 use crate::{
     command_runner::{CommandProcess, CommandRunner},
-    executor::{Executor, ExecutorError},
-    models::task::Task,
+    db::DbPool,
+    executor::{
+        image_paths_prompt, load_task_for_prompt, templated_prompt, EnvironmentRequirements,
+        Executor, ExecutorCapabilities, ExecutorError,
+    },
     utils::shell::get_shell_command,
 };
 
@@ -13,18 +16,38 @@ pub struct CharmOpencodeExecutor;
 
 #[async_trait]
 impl Executor for CharmOpencodeExecutor {
+    fn capabilities(&self) -> ExecutorCapabilities {
+        ExecutorCapabilities {
+            supports_followup: true,
+            supports_sessions: false,
+            supports_plan_mode: false,
+            supports_interactive: false,
+            emits_structured_logs: false,
+        }
+    }
+
+    fn environment_requirements(&self) -> Option<EnvironmentRequirements> {
+        Some(EnvironmentRequirements {
+            binary: "opencode",
+            install_hint: "Install it with `go install github.com/opencode-ai/opencode@latest`",
+            credentials: None,
+        })
+    }
+
     async fn spawn(
         &self,
-        pool: &sqlx::SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
         worktree_path: &str,
     ) -> Result<CommandProcess, ExecutorError> {
         // Get the task to fetch its description
-        let task = Task::find_by_id(pool, task_id)
-            .await?
-            .ok_or(ExecutorError::TaskNotFound)?;
+        let task = load_task_for_prompt(pool, task_id, worktree_path).await?;
 
-        let prompt = if let Some(task_description) = task.description {
+        let mut prompt = if let Some(prompt) =
+            templated_prompt(pool, &task, worktree_path, "charm-opencode").await?
+        {
+            prompt
+        } else if let Some(task_description) = task.description {
             format!(
                 r#"project_id: {}
             
@@ -41,6 +64,9 @@ Task title: {}"#,
             )
         };
 
+        // The CLI can't take images, so the agent is pointed at where they are stored
+        prompt.push_str(&image_paths_prompt(pool, task_id).await?);
+
         // Use shell command for cross-platform compatibility
         let (shell_cmd, shell_arg) = get_shell_command();
         let opencode_command = format!(
@@ -67,7 +93,7 @@ Task title: {}"#,
 
     async fn spawn_followup(
         &self,
-        _pool: &sqlx::SqlitePool,
+        _pool: &DbPool,
         _task_id: Uuid,
         _session_id: &str,
         prompt: &str,
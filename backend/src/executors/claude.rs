@@ -5,11 +5,17 @@ use uuid::Uuid;
 
 use crate::{
     command_runner::{CommandProcess, CommandRunner},
+    db::DbPool,
     executor::{
-        ActionType, Executor, ExecutorError, NormalizedConversation, NormalizedEntry,
-        NormalizedEntryType,
+        agent_flags, image_attachments_prompt, load_task_for_prompt, normalize_complete_lines,
+        templated_prompt, ActionType, EnvironmentRequirements, Executor, ExecutorCapabilities,
+        ExecutorError, NormalizedConversation, NormalizedEntry, NormalizedEntryType,
+        NormalizerState, UsageMetrics,
+    },
+    models::{
+        project_mcp_server::{claude_mcp_args, ProjectMcpServer},
+        task_attachment::TaskAttachment,
     },
-    models::task::Task,
     utils::shell::get_shell_command,
 };
 
@@ -41,6 +47,7 @@ exit "$exit_code"
 pub struct ClaudeExecutor {
     executor_type: String,
     command: String,
+    plan_mode: bool,
 }
 
 impl Default for ClaudeExecutor {
@@ -55,15 +62,37 @@ impl ClaudeExecutor {
         Self {
             executor_type: "Claude Code".to_string(),
             command: "npx -y @anthropic-ai/claude-code@latest -p --dangerously-skip-permissions --verbose --output-format=stream-json".to_string(),
+            plan_mode: false,
         }
     }
 
     pub fn new_plan_mode() -> Self {
-        let command = "npx -y @anthropic-ai/claude-code@latest -p --permission-mode=plan --verbose --output-format=stream-json";
-        let script = create_watchkill_script(command);
         Self {
             executor_type: "ClaudePlan".to_string(),
-            command: script,
+            command: "npx -y @anthropic-ai/claude-code@latest -p --permission-mode=plan --verbose --output-format=stream-json".to_string(),
+            plan_mode: true,
+        }
+    }
+
+    /// The command to run, with `extra_args` after the CLI's own. A plan-mode run is
+    /// stopped once the plan is ready.
+    fn command_line(&self, extra_args: &str) -> String {
+        let command = format!("{}{}{}", self.command, agent_flags(), extra_args);
+        if self.plan_mode {
+            create_watchkill_script(&command)
+        } else {
+            command
+        }
+    }
+
+    /// The executor name the project's prompt template is looked up by
+    fn template_executor(&self) -> &'static str {
+        if self.plan_mode {
+            "claude-plan"
+        } else if self.executor_type == "claude-code-router" {
+            "claude-code-router"
+        } else {
+            "claude"
         }
     }
 
@@ -72,24 +101,41 @@ impl ClaudeExecutor {
         Self {
             executor_type,
             command,
+            plan_mode: false,
         }
     }
 }
 
 #[async_trait]
 impl Executor for ClaudeExecutor {
+    fn capabilities(&self) -> ExecutorCapabilities {
+        ExecutorCapabilities {
+            supports_followup: true,
+            supports_sessions: true,
+            supports_plan_mode: self.plan_mode,
+            supports_interactive: false,
+            emits_structured_logs: true,
+        }
+    }
+
+    fn environment_requirements(&self) -> Option<EnvironmentRequirements> {
+        Some(EnvironmentRequirements::npx())
+    }
+
     async fn spawn(
         &self,
-        pool: &sqlx::SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
         worktree_path: &str,
     ) -> Result<CommandProcess, ExecutorError> {
         // Get the task to fetch its description
-        let task = Task::find_by_id(pool, task_id)
-            .await?
-            .ok_or(ExecutorError::TaskNotFound)?;
+        let task = load_task_for_prompt(pool, task_id, worktree_path).await?;
 
-        let prompt = if let Some(task_description) = task.description {
+        let mut prompt = if let Some(prompt) =
+            templated_prompt(pool, &task, worktree_path, self.template_executor()).await?
+        {
+            prompt
+        } else if let Some(task_description) = task.description {
             format!(
                 r#"project_id: {}
             
@@ -105,17 +151,23 @@ Task title: {}"#,
                 task.project_id, task.title
             )
         };
+        // Claude reads images given by absolute path
+        let images = TaskAttachment::find_images_by_task_id(pool, task_id).await?;
+        prompt.push_str(&image_attachments_prompt(&images, |path| {
+            path.display().to_string()
+        }));
 
         // Use shell command for cross-platform compatibility
         let (shell_cmd, shell_arg) = get_shell_command();
         // Pass prompt via stdin instead of command line to avoid shell escaping issues
-        let claude_command = &self.command;
+        let servers = ProjectMcpServer::find_for_task(pool, task_id).await?;
+        let claude_command = self.command_line(&claude_mcp_args(&servers));
 
         let mut command = CommandRunner::new();
         command
             .command(shell_cmd)
             .arg(shell_arg)
-            .arg(claude_command)
+            .arg(&claude_command)
             .stdin(&prompt)
             .working_dir(worktree_path)
             .env("NODE_NO_WARNINGS", "1");
@@ -131,8 +183,8 @@ Task title: {}"#,
 
     async fn spawn_followup(
         &self,
-        _pool: &sqlx::SqlitePool,
-        _task_id: Uuid,
+        pool: &DbPool,
+        task_id: Uuid,
         session_id: &str,
         prompt: &str,
         worktree_path: &str,
@@ -140,16 +192,12 @@ Task title: {}"#,
         // Use shell command for cross-platform compatibility
         let (shell_cmd, shell_arg) = get_shell_command();
 
-        // Determine the command based on whether this is plan mode or not
-        let claude_command = if self.executor_type == "ClaudePlan" {
-            let command = format!(
-                "npx -y @anthropic-ai/claude-code@latest -p --permission-mode=plan --verbose --output-format=stream-json --resume={}",
-                session_id
-            );
-            create_watchkill_script(&command)
-        } else {
-            format!("{} --resume={}", self.command, session_id)
-        };
+        let servers = ProjectMcpServer::find_for_task(pool, task_id).await?;
+        let claude_command = self.command_line(&format!(
+            "{} --resume={}",
+            claude_mcp_args(&servers),
+            session_id
+        ));
 
         let mut command = CommandRunner::new();
         command
@@ -194,7 +242,9 @@ Task title: {}"#,
                 Err(_) => {
                     // If line isn't valid JSON, add it as raw text
                     entries.push(NormalizedEntry {
+                        id: None,
                         timestamp: None,
+                        duration_ms: None,
                         entry_type: NormalizedEntryType::SystemMessage,
                         content: format!("Raw output: {}", trimmed),
                         metadata: None,
@@ -228,7 +278,9 @@ Task title: {}"#,
                                                     .and_then(|t| t.as_str())
                                                 {
                                                     entries.push(NormalizedEntry {
+                                                        id: None,
                                                         timestamp: None,
+                                                        duration_ms: None,
                                                         entry_type:
                                                             NormalizedEntryType::AssistantMessage,
                                                         content: text.to_string(),
@@ -255,9 +307,13 @@ Task title: {}"#,
                                                         &action_type,
                                                         worktree_path,
                                                     );
+                                                    let file_edit =
+                                                        file_edit(tool_name, input, &action_type);
 
                                                     entries.push(NormalizedEntry {
+                                                        id: None,
                                                         timestamp: None,
+                                                        duration_ms: None,
                                                         entry_type: NormalizedEntryType::ToolUse {
                                                             tool_name: tool_name.to_string(),
                                                             action_type,
@@ -265,6 +321,7 @@ Task title: {}"#,
                                                         content,
                                                         metadata: Some(content_item.clone()),
                                                     });
+                                                    entries.extend(file_edit);
                                                 }
                                             }
                                             _ => {}
@@ -288,7 +345,9 @@ Task title: {}"#,
                                                 content_item.get("text").and_then(|t| t.as_str())
                                             {
                                                 entries.push(NormalizedEntry {
+                                                    id: None,
                                                     timestamp: None,
+                                                    duration_ms: None,
                                                     entry_type: NormalizedEntryType::UserMessage,
                                                     content: text.to_string(),
                                                     metadata: Some(content_item.clone()),
@@ -305,7 +364,9 @@ Task title: {}"#,
                         if let Some(subtype) = json.get("subtype").and_then(|s| s.as_str()) {
                             if subtype == "init" {
                                 entries.push(NormalizedEntry {
+                                    id: None,
                                     timestamp: None,
+                                    duration_ms: None,
                                     entry_type: NormalizedEntryType::SystemMessage,
                                     content: format!(
                                         "System initialized with model: {}",
@@ -326,16 +387,20 @@ Task title: {}"#,
             };
 
             // If JSON didn't match expected patterns, add it as unrecognized JSON
-            // Skip JSON with type "result" as requested
+            // The final result only contributes the run's usage
             if !processed {
                 if let Some(msg_type) = json.get("type").and_then(|t| t.as_str()) {
                     if msg_type == "result" {
-                        // Skip result entries
+                        if let Some(usage) = result_usage(&json) {
+                            entries.push(NormalizedEntry::usage(None, usage));
+                        }
                         continue;
                     }
                 }
                 entries.push(NormalizedEntry {
+                    id: None,
                     timestamp: None,
+                    duration_ms: None,
                     entry_type: NormalizedEntryType::SystemMessage,
                     content: format!("Unrecognized JSON: {}", trimmed),
                     metadata: Some(json),
@@ -349,8 +414,45 @@ Task title: {}"#,
             executor_type: self.executor_type.clone(),
             prompt: None,
             summary: None,
+            diff_summary: None,
         })
     }
+
+    fn normalize_logs_incremental(
+        &self,
+        chunk: &str,
+        state: &mut NormalizerState,
+    ) -> Result<(), String> {
+        normalize_complete_lines(self, chunk, state)
+    }
+}
+
+/// The change an editing tool call makes to the file it writes, from the snippets or content
+/// it was given
+fn file_edit(
+    tool_name: &str,
+    input: &serde_json::Value,
+    action_type: &ActionType,
+) -> Option<NormalizedEntry> {
+    fn text<'a>(value: &'a serde_json::Value, key: &str) -> Option<&'a str> {
+        value.get(key).and_then(|v| v.as_str())
+    }
+    let ActionType::FileWrite { path, .. } = action_type else {
+        return None;
+    };
+    let edits = match tool_name.to_lowercase().as_str() {
+        "edit" => vec![(text(input, "old_string")?, text(input, "new_string")?)],
+        "multiedit" => input
+            .get("edits")?
+            .as_array()?
+            .iter()
+            .filter_map(|edit| Some((text(edit, "old_string")?, text(edit, "new_string")?)))
+            .collect(),
+        // What the file held before isn't in the log, so a write shows as all new
+        "write" => vec![("", text(input, "content")?)],
+        _ => return None,
+    };
+    NormalizedEntry::file_edit(None, path, &edits)
 }
 
 impl ClaudeExecutor {
@@ -430,8 +532,8 @@ impl ClaudeExecutor {
         worktree_path: &str,
     ) -> String {
         match action_type {
-            ActionType::FileRead { path } => format!("`{}`", path),
-            ActionType::FileWrite { path } => format!("`{}`", path),
+            ActionType::FileRead { path, .. } => format!("`{}`", path),
+            ActionType::FileWrite { path, .. } => format!("`{}`", path),
             ActionType::CommandRun { command } => format!("`{}`", command),
             ActionType::Search { query } => format!("`{}`", query),
             ActionType::WebFetch { url } => format!("`{}`", url),
@@ -526,6 +628,7 @@ impl ClaudeExecutor {
                 if let Some(file_path) = input.get("file_path").and_then(|p| p.as_str()) {
                     ActionType::FileRead {
                         path: self.make_path_relative(file_path, worktree_path),
+                        location: None,
                     }
                 } else {
                     ActionType::Other {
@@ -537,10 +640,12 @@ impl ClaudeExecutor {
                 if let Some(file_path) = input.get("file_path").and_then(|p| p.as_str()) {
                     ActionType::FileWrite {
                         path: self.make_path_relative(file_path, worktree_path),
+                        location: None,
                     }
                 } else if let Some(path) = input.get("path").and_then(|p| p.as_str()) {
                     ActionType::FileWrite {
                         path: self.make_path_relative(path, worktree_path),
+                        location: None,
                     }
                 } else {
                     ActionType::Other {
@@ -625,6 +730,29 @@ impl ClaudeExecutor {
     }
 }
 
+/// Usage of the run from Claude's final `result` line. Cache reads and writes count as
+/// input tokens.
+fn result_usage(json: &serde_json::Value) -> Option<UsageMetrics> {
+    let usage = json.get("usage");
+    let cost_usd = json.get("total_cost_usd").and_then(|c| c.as_f64());
+    if usage.is_none() && cost_usd.is_none() {
+        return None;
+    }
+    let tokens = |key: &str| {
+        usage
+            .and_then(|u| u.get(key))
+            .and_then(|t| t.as_i64())
+            .unwrap_or(0)
+    };
+    Some(UsageMetrics {
+        input_tokens: tokens("input_tokens")
+            + tokens("cache_creation_input_tokens")
+            + tokens("cache_read_input_tokens"),
+        output_tokens: tokens("output_tokens"),
+        cost_usd,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -655,6 +783,49 @@ mod tests {
             .any(|e| e.content.contains("Unrecognized JSON")));
     }
 
+    #[test]
+    fn test_normalize_logs_reports_result_usage() {
+        let executor = ClaudeExecutor::new();
+        let logs = r#"{"type":"result","subtype":"success","is_error":false,"total_cost_usd":0.0421,"usage":{"input_tokens":12,"cache_creation_input_tokens":300,"cache_read_input_tokens":1000,"output_tokens":250}}"#;
+
+        let result = executor.normalize_logs(logs, "/tmp/test-worktree").unwrap();
+
+        assert_eq!(result.entries.len(), 1);
+        assert_eq!(
+            result.entries[0].usage_metrics(),
+            Some(UsageMetrics {
+                input_tokens: 1312,
+                output_tokens: 250,
+                cost_usd: Some(0.0421),
+            })
+        );
+    }
+
+    #[test]
+    fn test_edit_tool_uses_are_followed_by_their_diff() {
+        let executor = ClaudeExecutor::new();
+        let logs = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"toolu_1","name":"Edit","input":{"file_path":"/tmp/test-worktree/src/main.rs","old_string":"println!(\"hi\");","new_string":"println!(\"hello\");"}}]}}
+{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"toolu_2","name":"Read","input":{"file_path":"/tmp/test-worktree/src/main.rs"}}]}}"#;
+
+        let result = executor.normalize_logs(logs, "/tmp/test-worktree").unwrap();
+
+        assert_eq!(result.entries.len(), 3);
+        match &result.entries[1].entry_type {
+            NormalizedEntryType::FileEdit { path, diff } => {
+                assert_eq!(path, "src/main.rs");
+                assert!(diff.contains("-println!(\"hi\");\n+println!(\"hello\");"));
+            }
+            other => panic!("expected a file edit, got {:?}", other),
+        }
+        assert!(matches!(
+            result.entries[2].entry_type,
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::FileRead { .. },
+                ..
+            }
+        ));
+    }
+
     #[test]
     fn test_make_path_relative() {
         let executor = ClaudeExecutor::new();
@@ -3,6 +3,7 @@ use uuid::Uuid;
 
 use crate::{
     command_runner::{CommandProcess, CommandRunner},
+    db::DbPool,
     executor::{Executor, ExecutorError},
     models::{project::Project, task::Task},
     utils::shell::get_shell_command,
@@ -17,7 +18,7 @@ pub struct CleanupScriptExecutor {
 impl Executor for CleanupScriptExecutor {
     async fn spawn(
         &self,
-        pool: &sqlx::SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
         worktree_path: &str,
     ) -> Result<CommandProcess, ExecutorError> {
@@ -58,7 +59,9 @@ impl Executor for CleanupScriptExecutor {
 
         // Add script command as first entry
         entries.push(crate::executor::NormalizedEntry {
+            id: None,
             timestamp: None,
+            duration_ms: None,
             entry_type: crate::executor::NormalizedEntryType::SystemMessage,
             content: format!("Executing cleanup script:\n{}", self.script),
             metadata: None,
@@ -89,7 +92,9 @@ impl Executor for CleanupScriptExecutor {
                     };
 
                     entries.push(crate::executor::NormalizedEntry {
+                        id: None,
                         timestamp: Some(chrono::Utc::now().to_rfc3339()),
+                        duration_ms: None,
                         entry_type,
                         content: current_chunk.trim().to_string(),
                         metadata: None,
@@ -102,7 +107,9 @@ impl Executor for CleanupScriptExecutor {
             // Add any remaining content
             if !current_chunk.trim().is_empty() {
                 entries.push(crate::executor::NormalizedEntry {
+                    id: None,
                     timestamp: Some(chrono::Utc::now().to_rfc3339()),
+                    duration_ms: None,
                     entry_type: crate::executor::NormalizedEntryType::SystemMessage,
                     content: current_chunk.trim().to_string(),
                     metadata: None,
@@ -116,6 +123,7 @@ impl Executor for CleanupScriptExecutor {
             executor_type: "cleanup-script".to_string(),
             prompt: Some(self.script.clone()),
             summary: None,
+            diff_summary: None,
         })
     }
 }
@@ -8,12 +8,22 @@ use uuid::Uuid;
 
 use crate::{
     command_runner::{CommandProcess, CommandRunner},
+    db::DbPool,
     executor::{
-        ActionType, Executor, ExecutorError, NormalizedConversation, NormalizedEntry,
-        NormalizedEntryType,
+        agent_flags, load_task_for_prompt, normalize_complete_lines, templated_prompt, ActionType,
+        CredentialRequirement, EnvironmentRequirements, Executor, ExecutorCapabilities,
+        ExecutorError, NormalizedConversation, NormalizedEntry, NormalizedEntryType,
+        NormalizerState, UsageMetrics,
+    },
+    models::{
+        executor_session::ExecutorSession,
+        project_mcp_server::{codex_mcp_args, ProjectMcpServer},
+        task_attachment::TaskAttachment,
+    },
+    utils::{
+        path::make_path_relative,
+        shell::{get_shell_command, shell_quote},
     },
-    models::{executor_session::ExecutorSession, task::Task},
-    utils::{path::make_path_relative, shell::get_shell_command},
 };
 
 /// Check if a JSON message looks like a system configuration message
@@ -44,6 +54,25 @@ fn is_system_config_message(json: &Value) -> bool {
     found_config_fields >= 2
 }
 
+/// How much of a failed sandbox command's stderr is kept in its error entry
+const FAILED_COMMAND_STDERR_LINES: usize = 10;
+
+/// The command a sandbox run executed. Codex wraps most commands as `bash -lc <script>`, so
+/// the script is shown on its own.
+fn sandbox_command(args: &[&str]) -> String {
+    match args {
+        [shell, flag, script] if matches!(*shell, "bash" | "sh" | "zsh") && flag.ends_with('c') => {
+            script.to_string()
+        }
+        _ => args.join(" "),
+    }
+}
+
+fn last_lines(text: &str, count: usize) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    lines[lines.len().saturating_sub(count)..].join("\n")
+}
+
 /// Extract session ID from codex stderr output
 fn extract_session_id_from_line(line: &str) -> Option<String> {
     // Look for session_id in the log format:
@@ -113,7 +142,7 @@ fn find_rollout_file_path(session_id: &str) -> Result<PathBuf, String> {
 /// Stream stderr from codex process to extract session_id but don't save stderr to DB
 pub async fn stream_codex_stderr_to_extract_session(
     output: impl tokio::io::AsyncRead + Unpin,
-    pool: sqlx::SqlitePool,
+    pool: DbPool,
     execution_process_id: Uuid,
 ) {
     let mut reader = BufReader::new(output);
@@ -188,37 +217,71 @@ impl CodexExecutor {
 
 #[async_trait]
 impl Executor for CodexExecutor {
+    fn capabilities(&self) -> ExecutorCapabilities {
+        ExecutorCapabilities {
+            supports_followup: true,
+            supports_sessions: true,
+            supports_plan_mode: false,
+            supports_interactive: false,
+            emits_structured_logs: true,
+        }
+    }
+
+    fn environment_requirements(&self) -> Option<EnvironmentRequirements> {
+        Some(
+            EnvironmentRequirements::npx().with_credentials(CredentialRequirement {
+                env_vars: &["OPENAI_API_KEY"],
+                login_file: dirs::home_dir().map(|home| home.join(".codex").join("auth.json")),
+                hint: "Export OPENAI_API_KEY or sign in with `npx @openai/codex login`",
+            }),
+        )
+    }
+
     async fn spawn(
         &self,
-        pool: &sqlx::SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
         worktree_path: &str,
     ) -> Result<CommandProcess, ExecutorError> {
         // Get the task to fetch its description
-        let task = Task::find_by_id(pool, task_id)
-            .await?
-            .ok_or(ExecutorError::TaskNotFound)?;
-
-        let prompt = if let Some(task_description) = task.description {
-            format!(
-                r#"project_id: {}
+        let task = load_task_for_prompt(pool, task_id, worktree_path).await?;
+
+        let prompt =
+            if let Some(prompt) = templated_prompt(pool, &task, worktree_path, "codex").await? {
+                prompt
+            } else if let Some(task_description) = task.description {
+                format!(
+                    r#"project_id: {}
             
 Task title: {}
 Task description: {}"#,
-                task.project_id, task.title, task_description
-            )
-        } else {
-            format!("project_id: {}\n{}", task.project_id, task.title)
-        };
+                    task.project_id, task.title, task_description
+                )
+            } else {
+                format!("project_id: {}\n{}", task.project_id, task.title)
+            };
 
         // Use shell command for cross-platform compatibility
         let (shell_cmd, shell_arg) = get_shell_command();
 
+        let servers = ProjectMcpServer::find_for_task(pool, task_id).await?;
+        let mut codex_command = format!(
+            "{}{}{}",
+            self.command,
+            agent_flags(),
+            codex_mcp_args(&servers)
+        );
+        // `--image` attaches a file to the first message
+        for image in TaskAttachment::find_images_by_task_id(pool, task_id).await? {
+            codex_command.push_str(" --image ");
+            codex_command.push_str(&shell_quote(&image.path().to_string_lossy()));
+        }
+
         let mut command = CommandRunner::new();
         command
             .command(shell_cmd)
             .arg(shell_arg)
-            .arg(&self.command)
+            .arg(&codex_command)
             .stdin(&prompt)
             .working_dir(worktree_path)
             .env("NODE_NO_WARNINGS", "1")
@@ -236,8 +299,8 @@ Task description: {}"#,
 
     async fn spawn_followup(
         &self,
-        _pool: &sqlx::SqlitePool,
-        _task_id: Uuid,
+        pool: &DbPool,
+        task_id: Uuid,
         session_id: &str,
         prompt: &str,
         worktree_path: &str,
@@ -249,9 +312,12 @@ Task description: {}"#,
         // Use shell command for cross-platform compatibility
         let (shell_cmd, shell_arg) = get_shell_command();
 
+        let servers = ProjectMcpServer::find_for_task(pool, task_id).await?;
         let codex_command = format!(
-            "{} -c experimental_resume={}",
+            "{}{}{} -c experimental_resume={}",
             self.command,
+            agent_flags(),
+            codex_mcp_args(&servers),
             rollout_file_path.display()
         );
 
@@ -280,7 +346,7 @@ Task description: {}"#,
     /// Custom streaming setup to handle stderr for session extraction
     async fn execute_streaming(
         &self,
-        pool: &sqlx::SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
         attempt_id: Uuid,
         execution_process_id: Uuid,
@@ -329,7 +395,7 @@ Task description: {}"#,
     /// Custom followup streaming with same stderr handling
     async fn execute_followup_streaming(
         &self,
-        pool: &sqlx::SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
         attempt_id: Uuid,
         execution_process_id: Uuid,
@@ -399,7 +465,9 @@ Task description: {}"#,
                 Err(_) => {
                     // If line isn't valid JSON, add it as raw text
                     entries.push(NormalizedEntry {
+                        id: None,
                         timestamp: None,
+                        duration_ms: None,
                         entry_type: NormalizedEntryType::SystemMessage,
                         content: format!("Raw output: {}", trimmed),
                         metadata: None,
@@ -444,7 +512,9 @@ Task description: {}"#,
                     );
 
                     entries.push(NormalizedEntry {
+                        id: None,
                         timestamp: None,
+                        duration_ms: None,
                         entry_type: NormalizedEntryType::SystemMessage,
                         content,
                         metadata: Some(json.clone()),
@@ -469,7 +539,9 @@ Task description: {}"#,
                         "agent_reasoning" => {
                             if let Some(text) = msg.get("text").and_then(|t| t.as_str()) {
                                 entries.push(NormalizedEntry {
+                                    id: None,
                                     timestamp: None,
+                                    duration_ms: None,
                                     entry_type: NormalizedEntryType::Thinking,
                                     content: text.to_string(),
                                     metadata: Some(json.clone()),
@@ -480,37 +552,28 @@ Task description: {}"#,
                             if let Some(command_array) =
                                 msg.get("command").and_then(|c| c.as_array())
                             {
-                                let command = command_array
+                                let args = command_array
                                     .iter()
                                     .filter_map(|v| v.as_str())
-                                    .collect::<Vec<_>>()
-                                    .join(" ");
+                                    .collect::<Vec<_>>();
 
                                 // Map shell command to bash tool
-                                let (tool_name, action_type) =
-                                    if command_array.first().and_then(|v| v.as_str())
-                                        == Some("bash")
-                                    {
-                                        (
-                                            "bash".to_string(),
-                                            ActionType::CommandRun {
-                                                command: command.clone(),
-                                            },
-                                        )
-                                    } else {
-                                        (
-                                            "shell".to_string(),
-                                            ActionType::CommandRun {
-                                                command: command.clone(),
-                                            },
-                                        )
-                                    };
+                                let tool_name = if args.first() == Some(&"bash") {
+                                    "bash"
+                                } else {
+                                    "shell"
+                                };
+                                let command = sandbox_command(&args);
 
                                 entries.push(NormalizedEntry {
+                                    id: None,
                                     timestamp: None,
+                                    duration_ms: None,
                                     entry_type: NormalizedEntryType::ToolUse {
-                                        tool_name,
-                                        action_type,
+                                        tool_name: tool_name.to_string(),
+                                        action_type: ActionType::CommandRun {
+                                            command: command.clone(),
+                                        },
                                     },
                                     content: format!("`{}`", command),
                                     metadata: Some(json.clone()),
@@ -518,21 +581,58 @@ Task description: {}"#,
                             }
                         }
                         "exec_command_end" => {
-                            // Skip command end entries to avoid duplication
-                            continue;
+                            // The begin entry already shows the command; only a failure adds
+                            // anything
+                            let exit_code =
+                                msg.get("exit_code").and_then(|c| c.as_i64()).unwrap_or(0);
+                            if exit_code != 0 {
+                                let stderr = msg
+                                    .get("stderr")
+                                    .and_then(|s| s.as_str())
+                                    .map(str::trim)
+                                    .unwrap_or_default();
+                                let content = if stderr.is_empty() {
+                                    format!("Command exited with code {}", exit_code)
+                                } else {
+                                    format!(
+                                        "Command exited with code {}:\n{}",
+                                        exit_code,
+                                        last_lines(stderr, FAILED_COMMAND_STDERR_LINES)
+                                    )
+                                };
+                                entries.push(NormalizedEntry {
+                                    id: None,
+                                    timestamp: None,
+                                    duration_ms: None,
+                                    entry_type: NormalizedEntryType::ErrorMessage,
+                                    content,
+                                    metadata: Some(json.clone()),
+                                });
+                            }
                         }
                         "task_complete" => {
                             // Skip task_complete messages as requested
                             continue;
                         }
                         "token_count" => {
-                            // Skip token count entries
-                            continue;
+                            // One turn's usage
+                            let tokens =
+                                |key: &str| msg.get(key).and_then(|t| t.as_i64()).unwrap_or(0);
+                            entries.push(NormalizedEntry::usage(
+                                None,
+                                UsageMetrics {
+                                    input_tokens: tokens("input_tokens"),
+                                    output_tokens: tokens("output_tokens"),
+                                    cost_usd: None,
+                                },
+                            ));
                         }
                         "agent_message" => {
                             if let Some(message) = msg.get("message").and_then(|m| m.as_str()) {
                                 entries.push(NormalizedEntry {
+                                    id: None,
                                     timestamp: None,
+                                    duration_ms: None,
                                     entry_type: NormalizedEntryType::AssistantMessage,
                                     content: message.to_string(),
                                     metadata: Some(json.clone()),
@@ -549,11 +649,14 @@ Task description: {}"#,
                                         make_path_relative(file_path, worktree_path);
 
                                     entries.push(NormalizedEntry {
+                                        id: None,
                                         timestamp: None,
+                                        duration_ms: None,
                                         entry_type: NormalizedEntryType::ToolUse {
                                             tool_name: "edit".to_string(),
                                             action_type: ActionType::FileWrite {
                                                 path: relative_path.clone(),
+                                                location: None,
                                             },
                                         },
                                         content: format!("`{}`", relative_path),
@@ -570,14 +673,18 @@ Task description: {}"#,
                             if let Some(error_message) = msg.get("message").and_then(|m| m.as_str())
                             {
                                 entries.push(NormalizedEntry {
+                                    id: None,
                                     timestamp: None,
+                                    duration_ms: None,
                                     entry_type: NormalizedEntryType::ErrorMessage,
                                     content: error_message.to_string(),
                                     metadata: Some(json.clone()),
                                 });
                             } else {
                                 entries.push(NormalizedEntry {
+                                    id: None,
                                     timestamp: None,
+                                    duration_ms: None,
                                     entry_type: NormalizedEntryType::ErrorMessage,
                                     content: "Unknown error occurred".to_string(),
                                     metadata: Some(json.clone()),
@@ -587,7 +694,9 @@ Task description: {}"#,
                         _ => {
                             // Unknown message type, add as system message
                             entries.push(NormalizedEntry {
+                                id: None,
                                 timestamp: None,
+                                duration_ms: None,
                                 entry_type: NormalizedEntryType::SystemMessage,
                                 content: format!("Unknown message type: {}", msg_type),
                                 metadata: Some(json.clone()),
@@ -598,7 +707,9 @@ Task description: {}"#,
             } else {
                 // JSON without msg field, add as unrecognized
                 entries.push(NormalizedEntry {
+                    id: None,
                     timestamp: None,
+                    duration_ms: None,
                     entry_type: NormalizedEntryType::SystemMessage,
                     content: format!("Unrecognized JSON: {}", trimmed),
                     metadata: Some(json),
@@ -612,8 +723,17 @@ Task description: {}"#,
             executor_type: self.executor_type.clone(),
             prompt: None,
             summary: None,
+            diff_summary: None,
         })
     }
+
+    fn normalize_logs_incremental(
+        &self,
+        chunk: &str,
+        state: &mut NormalizerState,
+    ) -> Result<(), String> {
+        normalize_complete_lines(self, chunk, state)
+    }
 }
 
 #[cfg(test)]
@@ -673,7 +793,7 @@ mod tests {
             assert_eq!(tool_name, "bash");
             assert!(matches!(action_type, ActionType::CommandRun { .. }));
         }
-        assert_eq!(result.entries[1].content, "`bash -lc ls -1`");
+        assert_eq!(result.entries[1].content, "`ls -1`");
     }
 
     #[test]
@@ -700,7 +820,36 @@ mod tests {
     }
 
     #[test]
-    fn test_normalize_logs_token_count_skipped() {
+    fn test_normalize_logs_failed_sandbox_command() {
+        let executor = CodexExecutor::new();
+        let logs = r#"{"id":"1","msg":{"type":"exec_command_begin","call_id":"call_test","command":["bash","-lc","cargo test"],"cwd":"/tmp"}}
+{"id":"1","msg":{"type":"exec_command_end","call_id":"call_test","stdout":"","stderr":"error[E0425]: cannot find value `x`\n","exit_code":101}}
+{"id":"1","msg":{"type":"exec_command_begin","call_id":"call_other","command":["git","status"],"cwd":"/tmp"}}
+{"id":"1","msg":{"type":"exec_command_end","call_id":"call_other","stdout":"clean","stderr":"","exit_code":0}}"#;
+
+        let result = executor.normalize_logs(logs, "/tmp").unwrap();
+
+        assert_eq!(result.entries.len(), 3);
+        assert!(matches!(
+            &result.entries[0].entry_type,
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::CommandRun { command },
+                ..
+            } if command == "cargo test"
+        ));
+        assert!(matches!(
+            result.entries[1].entry_type,
+            NormalizedEntryType::ErrorMessage
+        ));
+        assert_eq!(
+            result.entries[1].content,
+            "Command exited with code 101:\nerror[E0425]: cannot find value `x`"
+        );
+        assert_eq!(result.entries[2].content, "`git status`");
+    }
+
+    #[test]
+    fn test_normalize_logs_token_count_is_usage() {
         let executor = CodexExecutor::new();
         let logs = r#"{"id":"1","msg":{"type":"task_started"}}
 {"id":"1","msg":{"type":"token_count","input_tokens":1674,"cached_input_tokens":1627,"output_tokens":384,"reasoning_output_tokens":384,"total_tokens":2058}}
@@ -708,8 +857,16 @@ mod tests {
 
         let result = executor.normalize_logs(logs, "/tmp").unwrap();
 
-        // Should have: nothing (task_started, task_complete, and token_count all skipped)
-        assert_eq!(result.entries.len(), 0);
+        // Should have: only the usage entry (task_started and task_complete skipped)
+        assert_eq!(result.entries.len(), 1);
+        assert_eq!(
+            result.entries[0].usage_metrics(),
+            Some(UsageMetrics {
+                input_tokens: 1674,
+                output_tokens: 384,
+                cost_usd: None,
+            })
+        );
     }
 
     #[test]
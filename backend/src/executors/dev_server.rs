@@ -3,6 +3,7 @@ use uuid::Uuid;
 
 use crate::{
     command_runner::{CommandProcess, CommandRunner},
+    db::DbPool,
     executor::{Executor, ExecutorError},
     models::{project::Project, task::Task},
     utils::shell::get_shell_command,
@@ -17,7 +18,7 @@ pub struct DevServerExecutor {
 impl Executor for DevServerExecutor {
     async fn spawn(
         &self,
-        pool: &sqlx::SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
         worktree_path: &str,
     ) -> Result<CommandProcess, ExecutorError> {
@@ -3,6 +3,7 @@ use uuid::Uuid;
 
 use crate::{
     command_runner::{CommandProcess, CommandRunner},
+    db::DbPool,
     executor::{Executor, ExecutorError, SpawnContext},
     models::task::Task,
     utils::shell::get_shell_command,
@@ -15,7 +16,7 @@ pub struct EchoExecutor;
 impl Executor for EchoExecutor {
     async fn spawn(
         &self,
-        pool: &sqlx::SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
         _worktree_path: &str,
     ) -> Result<CommandProcess, ExecutorError> {
@@ -0,0 +1,546 @@
+//! Goose executor implementation
+//!
+//! Runs Block's `goose` agent headless with `goose run`. Each attempt gets a named goose
+//! session, so follow-ups resume it with `--resume`. Goose prints plain text, with each
+//! tool call rendered as a `─── tool | extension ───` header followed by its parameters.
+
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    command_runner::{CommandProcess, CommandRunner},
+    db::DbPool,
+    executor::{
+        agent_flags, image_paths_prompt, load_task_for_prompt, templated_prompt, ActionType,
+        CredentialRequirement, EnvironmentRequirements, Executor, ExecutorCapabilities,
+        ExecutorError, NormalizedConversation, NormalizedEntry, NormalizedEntryType,
+    },
+    models::executor_session::ExecutorSession,
+    utils::{
+        path::make_path_relative,
+        shell::{get_shell_command, shell_quote},
+    },
+};
+
+/// An executor that uses the Goose CLI to process tasks
+pub struct GooseExecutor {
+    executor_type: String,
+    command: String,
+}
+
+impl Default for GooseExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GooseExecutor {
+    pub fn new() -> Self {
+        Self {
+            executor_type: "Goose".to_string(),
+            command: "goose run --with-builtin developer".to_string(),
+        }
+    }
+
+    /// The goose session of the attempt working in `worktree_path`, named after the
+    /// worktree so each attempt has its own
+    fn session_name(worktree_path: &str) -> String {
+        let worktree = std::path::Path::new(worktree_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| worktree_path.to_string());
+        let name: String = worktree
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        format!("vibe-kanban-{}", name)
+    }
+
+    async fn start(
+        &self,
+        worktree_path: &str,
+        goose_command: String,
+        context: String,
+        task: Option<(Uuid, String)>,
+    ) -> Result<CommandProcess, ExecutorError> {
+        let (shell_cmd, shell_arg) = get_shell_command();
+        let mut command = CommandRunner::new();
+        command
+            .command(shell_cmd)
+            .arg(shell_arg)
+            .arg(&goose_command)
+            .working_dir(worktree_path)
+            // Plain output, without the interactive spinner and colours
+            .env("NO_COLOR", "1")
+            .env("GOOSE_CLI_MIN_PRIORITY", "0.2");
+
+        command.start().await.map_err(|e| {
+            let mut spawn_context =
+                crate::executor::SpawnContext::from_command(&command, &self.executor_type);
+            if let Some((task_id, title)) = task {
+                spawn_context = spawn_context.with_task(task_id, Some(title));
+            }
+            spawn_context.with_context(context).spawn_error(e)
+        })
+    }
+}
+
+/// Where goose keeps its session files
+fn sessions_dir() -> Option<PathBuf> {
+    if cfg!(windows) {
+        dirs::data_dir().map(|data| {
+            data.join("Block")
+                .join("goose")
+                .join("data")
+                .join("sessions")
+        })
+    } else {
+        dirs::home_dir().map(|home| {
+            home.join(".local")
+                .join("share")
+                .join("goose")
+                .join("sessions")
+        })
+    }
+}
+
+#[async_trait]
+impl Executor for GooseExecutor {
+    fn capabilities(&self) -> ExecutorCapabilities {
+        ExecutorCapabilities {
+            supports_followup: true,
+            supports_sessions: true,
+            supports_plan_mode: false,
+            supports_interactive: false,
+            emits_structured_logs: false,
+        }
+    }
+
+    fn environment_requirements(&self) -> Option<EnvironmentRequirements> {
+        Some(EnvironmentRequirements {
+            binary: "goose",
+            install_hint:
+                "Install it from https://block.github.io/goose/docs/getting-started/installation",
+            credentials: Some(CredentialRequirement {
+                env_vars: &["GOOSE_PROVIDER"],
+                login_file: dirs::home_dir()
+                    .map(|home| home.join(".config").join("goose").join("config.yaml")),
+                hint: "Run `goose configure` to set up a model provider",
+            }),
+        })
+    }
+
+    async fn spawn(
+        &self,
+        pool: &DbPool,
+        task_id: Uuid,
+        worktree_path: &str,
+    ) -> Result<CommandProcess, ExecutorError> {
+        let task = load_task_for_prompt(pool, task_id, worktree_path).await?;
+
+        let mut prompt =
+            if let Some(prompt) = templated_prompt(pool, &task, worktree_path, "goose").await? {
+                prompt
+            } else if let Some(task_description) = task.description {
+                format!(
+                    "project_id: {}\n\nTask title: {}\nTask description: {}",
+                    task.project_id, task.title, task_description
+                )
+            } else {
+                format!(
+                    "project_id: {}\n\nTask title: {}",
+                    task.project_id, task.title
+                )
+            };
+
+        // The CLI can't take images, so the agent is pointed at where they are stored
+        prompt.push_str(&image_paths_prompt(pool, task_id).await?);
+
+        let goose_command = format!(
+            "{}{} --name {} --text {}",
+            self.command,
+            agent_flags(),
+            shell_quote(&Self::session_name(worktree_path)),
+            shell_quote(&prompt)
+        );
+        self.start(
+            worktree_path,
+            goose_command,
+            format!("{} CLI execution for new task", self.executor_type),
+            Some((task_id, task.title.clone())),
+        )
+        .await
+    }
+
+    async fn spawn_followup(
+        &self,
+        _pool: &DbPool,
+        _task_id: Uuid,
+        session_id: &str,
+        prompt: &str,
+        worktree_path: &str,
+    ) -> Result<CommandProcess, ExecutorError> {
+        // A session goose no longer has would silently start over
+        if let Some(dir) = sessions_dir().filter(|dir| dir.is_dir()) {
+            let session_file = dir.join(format!("{}.jsonl", session_id));
+            if !session_file.exists() {
+                return Err(ExecutorError::InvalidSessionId(format!(
+                    "goose has no session file {}",
+                    session_file.display()
+                )));
+            }
+        }
+
+        let goose_command = format!(
+            "{}{} --name {} --resume --text {}",
+            self.command,
+            agent_flags(),
+            shell_quote(session_id),
+            shell_quote(prompt)
+        );
+        self.start(
+            worktree_path,
+            goose_command,
+            format!(
+                "{} CLI followup execution for session {}",
+                self.executor_type, session_id
+            ),
+            None,
+        )
+        .await
+    }
+
+    /// The session is named up front, so it is recorded before goose starts
+    async fn execute_streaming(
+        &self,
+        pool: &DbPool,
+        task_id: Uuid,
+        attempt_id: Uuid,
+        execution_process_id: Uuid,
+        worktree_path: &str,
+    ) -> Result<CommandProcess, ExecutorError> {
+        let session_id = Self::session_name(worktree_path);
+        if let Err(e) =
+            ExecutorSession::update_session_id(pool, execution_process_id, &session_id).await
+        {
+            tracing::error!(
+                "Failed to update session ID for execution process {}: {}",
+                execution_process_id,
+                e
+            );
+        }
+
+        let mut child = self.spawn(pool, task_id, worktree_path).await?;
+        Self::setup_streaming(self, &mut child, pool, attempt_id, execution_process_id).await?;
+        Ok(child)
+    }
+
+    async fn execute_followup_streaming(
+        &self,
+        pool: &DbPool,
+        task_id: Uuid,
+        attempt_id: Uuid,
+        execution_process_id: Uuid,
+        session_id: &str,
+        prompt: &str,
+        worktree_path: &str,
+    ) -> Result<CommandProcess, ExecutorError> {
+        if let Err(e) =
+            ExecutorSession::update_session_id(pool, execution_process_id, session_id).await
+        {
+            tracing::error!(
+                "Failed to update session ID for followup execution process {}: {}",
+                execution_process_id,
+                e
+            );
+        }
+
+        let mut child = self
+            .spawn_followup(pool, task_id, session_id, prompt, worktree_path)
+            .await?;
+        Self::setup_streaming(self, &mut child, pool, attempt_id, execution_process_id).await?;
+        Ok(child)
+    }
+
+    fn normalize_logs(
+        &self,
+        logs: &str,
+        worktree_path: &str,
+    ) -> Result<NormalizedConversation, String> {
+        let mut entries = Vec::new();
+        let mut message: Vec<String> = Vec::new();
+        let mut tool: Option<ToolCall> = None;
+
+        let flush_message = |message: &mut Vec<String>, entries: &mut Vec<NormalizedEntry>| {
+            if !message.is_empty() {
+                entries.push(NormalizedEntry {
+                    id: None,
+                    timestamp: None,
+                    duration_ms: None,
+                    entry_type: NormalizedEntryType::AssistantMessage,
+                    content: message.join("\n"),
+                    metadata: None,
+                });
+                message.clear();
+            }
+        };
+
+        for line in logs.lines() {
+            let line = String::from_utf8(strip_ansi_escapes::strip(line.as_bytes()))
+                .unwrap_or_else(|_| line.to_string());
+            let line = line.trim_end();
+
+            if let Some(call) = ToolCall::parse_header(line) {
+                flush_message(&mut message, &mut entries);
+                if let Some(previous) = tool.replace(call) {
+                    entries.push(previous.into_entry(worktree_path));
+                }
+                continue;
+            }
+
+            if let Some(call) = tool.as_mut() {
+                if line.trim().is_empty() {
+                    entries.push(tool.take().unwrap().into_entry(worktree_path));
+                } else {
+                    call.push_line(line);
+                }
+                continue;
+            }
+
+            if line.trim().is_empty() {
+                flush_message(&mut message, &mut entries);
+            } else if is_session_banner(line) {
+                flush_message(&mut message, &mut entries);
+                entries.push(NormalizedEntry {
+                    id: None,
+                    timestamp: None,
+                    duration_ms: None,
+                    entry_type: NormalizedEntryType::SystemMessage,
+                    content: line.trim().to_string(),
+                    metadata: None,
+                });
+            } else {
+                message.push(line.to_string());
+            }
+        }
+        if let Some(call) = tool {
+            entries.push(call.into_entry(worktree_path));
+        }
+        flush_message(&mut message, &mut entries);
+
+        Ok(NormalizedConversation {
+            entries,
+            session_id: None,
+            executor_type: "goose".to_string(),
+            prompt: None,
+            summary: None,
+            diff_summary: None,
+        })
+    }
+}
+
+/// The lines goose prints when a session starts or resumes
+fn is_session_banner(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.starts_with("starting session |")
+        || trimmed.starts_with("resuming session |")
+        || trimmed.starts_with("session id:")
+        || trimmed.starts_with("logging to ")
+        || trimmed.starts_with("working directory:")
+        || trimmed.starts_with("Closing session.")
+}
+
+/// A tool call as goose renders it: a header naming the tool and its extension, then one
+/// `key: value` line per parameter, with multi-line values indented
+#[derive(Debug)]
+struct ToolCall {
+    tool: String,
+    extension: String,
+    params: Vec<(String, String)>,
+}
+
+impl ToolCall {
+    fn parse_header(line: &str) -> Option<Self> {
+        let inner = line.trim().strip_prefix("─── ")?;
+        let inner = inner.trim_end_matches('─').trim();
+        let (tool, extension) = inner.split_once(" | ")?;
+        Some(Self {
+            tool: tool.trim().to_string(),
+            extension: extension.trim().to_string(),
+            params: Vec::new(),
+        })
+    }
+
+    fn push_line(&mut self, line: &str) {
+        let is_continuation = line.starts_with(char::is_whitespace);
+        match line
+            .split_once(": ")
+            .or_else(|| line.strip_suffix(':').map(|k| (k, "")))
+        {
+            Some((key, value)) if !is_continuation => {
+                self.params.push((key.to_string(), value.to_string()))
+            }
+            _ => {
+                if let Some((_, value)) = self.params.last_mut() {
+                    if !value.is_empty() {
+                        value.push('\n');
+                    }
+                    value.push_str(line.trim());
+                }
+            }
+        }
+    }
+
+    fn param(&self, key: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn path(&self, worktree_path: &str) -> Option<String> {
+        let path = self.param("path")?;
+        let path = match (path.strip_prefix("~/"), dirs::home_dir()) {
+            (Some(rest), Some(home)) => home.join(rest).to_string_lossy().to_string(),
+            _ => path.to_string(),
+        };
+        Some(make_path_relative(&path, worktree_path))
+    }
+
+    fn action_type(&self, worktree_path: &str) -> ActionType {
+        match self.tool.as_str() {
+            "shell" => ActionType::CommandRun {
+                command: self.param("command").unwrap_or_default().to_string(),
+            },
+            "text_editor" => {
+                let path = self.path(worktree_path).unwrap_or_default();
+                match self.param("command") {
+                    Some("view") => ActionType::FileRead {
+                        path,
+                        location: None,
+                    },
+                    _ => ActionType::FileWrite {
+                        path,
+                        location: None,
+                    },
+                }
+            }
+            _ => {
+                if let Some(url) = self.param("url") {
+                    ActionType::WebFetch {
+                        url: url.to_string(),
+                    }
+                } else if let Some(query) = self.param("query") {
+                    ActionType::Search {
+                        query: query.to_string(),
+                    }
+                } else if let Some(path) = self.path(worktree_path) {
+                    ActionType::FileRead {
+                        path,
+                        location: None,
+                    }
+                } else {
+                    ActionType::Other {
+                        description: format!("{} ({})", self.tool, self.extension),
+                    }
+                }
+            }
+        }
+    }
+
+    fn into_entry(self, worktree_path: &str) -> NormalizedEntry {
+        let action_type = self.action_type(worktree_path);
+        let content = match &action_type {
+            ActionType::CommandRun { command } => format!("`{}`", command),
+            ActionType::FileRead { path, .. } | ActionType::FileWrite { path, .. } => {
+                format!("`{}`", path)
+            }
+            ActionType::WebFetch { url } => url.clone(),
+            ActionType::Search { query } => query.clone(),
+            _ => self.tool.clone(),
+        };
+        NormalizedEntry {
+            id: None,
+            timestamp: None,
+            duration_ms: None,
+            entry_type: NormalizedEntryType::ToolUse {
+                tool_name: self.tool,
+                action_type,
+            },
+            content,
+            metadata: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_logs_parses_tool_calls() {
+        let executor = GooseExecutor::new();
+        let logs = "starting session | provider: anthropic model: claude-sonnet-4\n    \
+                    logging to /home/me/.local/share/goose/sessions/vibe-kanban-x.jsonl\n    \
+                    working directory: /tmp/wt\n\
+                    I'll look at the project first.\n\
+                    \n\
+                    ─── shell | developer ──────────────────────────\n\
+                    command: ls -la\n\
+                    \n\
+                    ─── text_editor | developer ──────────────────────────\n\
+                    path: /tmp/wt/src/main.rs\n\
+                    command: write\n\
+                    file_text: fn main() {\n    \
+                    println!(\"hi\");\n\
+                    }\n\
+                    \n\
+                    ─── text_editor | developer ──────────────────────────\n\
+                    path: /tmp/wt/README.md\n\
+                    command: view\n\
+                    \n\
+                    Done, the program now prints hi.\n";
+
+        let result = executor.normalize_logs(logs, "/tmp/wt").unwrap();
+        let actions: Vec<_> = result
+            .entries
+            .iter()
+            .map(|entry| match &entry.entry_type {
+                NormalizedEntryType::ToolUse { action_type, .. } => format!("{:?}", action_type),
+                other => format!("{:?}: {}", other, entry.content),
+            })
+            .collect();
+
+        assert_eq!(
+            actions,
+            vec![
+                "SystemMessage: starting session | provider: anthropic model: claude-sonnet-4"
+                    .to_string(),
+                "SystemMessage: logging to /home/me/.local/share/goose/sessions/vibe-kanban-x.jsonl"
+                    .to_string(),
+                "SystemMessage: working directory: /tmp/wt".to_string(),
+                "AssistantMessage: I'll look at the project first.".to_string(),
+                "CommandRun { command: \"ls -la\" }".to_string(),
+                "FileWrite { path: \"src/main.rs\", location: None }".to_string(),
+                "FileRead { path: \"README.md\", location: None }".to_string(),
+                "AssistantMessage: Done, the program now prints hi.".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_session_name_is_per_worktree() {
+        assert_eq!(
+            GooseExecutor::session_name("/tmp/worktrees/vk-1a2b-fix login"),
+            "vibe-kanban-vk-1a2b-fix-login"
+        );
+    }
+}
@@ -0,0 +1,580 @@
+//! Executor for agent CLIs that print one JSON object per line. Everything specific to the
+//! agent (the command, which record types mean what, where the text and session id live)
+//! comes from a [`JsonlExecutorDefinition`] in the user config, so a new agent can be
+//! integrated without writing a module for it.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    command_runner::{CommandProcess, CommandRunner},
+    db::DbPool,
+    executor::{
+        image_paths_prompt, load_task_for_prompt, normalize_complete_lines, stream_output_to_db,
+        stream_stdout_to_db_with, templated_prompt, ActionType, Executor, ExecutorCapabilities,
+        ExecutorError, NormalizedConversation, NormalizedEntry, NormalizedEntryType,
+        NormalizerState, SpawnContext,
+    },
+    utils::shell::{get_shell_command, shell_quote},
+};
+
+/// Longest tool input echoed into a tool-use entry
+const MAX_TOOL_INPUT_CHARS: usize = 200;
+
+/// Which fields of a record hold what. Paths are dot-separated keys, with numbers
+/// indexing into arrays, e.g. `message.content.0.text`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(default)]
+#[ts(export)]
+pub struct JsonlFieldMapping {
+    /// Field naming the kind of record; its value is looked up in the `*_types` lists
+    pub type_field: String,
+    pub assistant_types: Vec<String>,
+    pub thinking_types: Vec<String>,
+    pub tool_use_types: Vec<String>,
+    pub system_types: Vec<String>,
+    pub error_types: Vec<String>,
+    /// Text of assistant, thinking, system and error records
+    pub content_field: String,
+    pub tool_name_field: String,
+    pub tool_input_field: Option<String>,
+    /// Records of any kind are searched for this until a session id turns up
+    pub session_id_field: Option<String>,
+}
+
+impl Default for JsonlFieldMapping {
+    fn default() -> Self {
+        let types = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+        Self {
+            type_field: "type".to_string(),
+            assistant_types: types(&["assistant", "message"]),
+            thinking_types: types(&["thinking", "reasoning"]),
+            tool_use_types: types(&["tool_use", "tool_call"]),
+            system_types: types(&["system"]),
+            error_types: types(&["error"]),
+            content_field: "content".to_string(),
+            tool_name_field: "name".to_string(),
+            tool_input_field: Some("input".to_string()),
+            session_id_field: Some("session_id".to_string()),
+        }
+    }
+}
+
+/// A JSON-lines agent set up in the config, selectable as executor `jsonl:<name>`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct JsonlExecutorDefinition {
+    pub name: String,
+    /// Shell command run in the worktree; the prompt is written to its stdin
+    pub command: String,
+    /// Command continuing a session, with `{session_id}` where the id goes. Follow-ups
+    /// aren't supported without it.
+    pub follow_up_command: Option<String>,
+    #[serde(default)]
+    pub mapping: JsonlFieldMapping,
+    /// Set for CLIs run in a mode where they wait on stdin before running each command.
+    /// Stdin then stays open after the prompt, for the answers.
+    #[serde(default)]
+    pub approval: Option<JsonlApprovalReplies>,
+    /// Set for CLIs that read messages from the user on stdin while they run: the line
+    /// written for each, with `{message}` where the message goes, escaped as inside a JSON
+    /// string. Stdin then stays open after the prompt.
+    #[serde(default)]
+    pub input_template: Option<String>,
+}
+
+/// The lines a JSON-lines CLI takes on stdin to answer a request to run a command
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct JsonlApprovalReplies {
+    pub allow: String,
+    pub deny: String,
+}
+
+lazy_static::lazy_static! {
+    static ref DEFINITIONS: RwLock<HashMap<String, Arc<JsonlExecutorDefinition>>> =
+        RwLock::new(HashMap::new());
+}
+
+/// Make the config's definitions the ones `jsonl:<name>` executors resolve to. Called
+/// whenever the config is loaded or saved.
+pub fn register_definitions(definitions: &[JsonlExecutorDefinition]) {
+    let mut registered = DEFINITIONS.write().unwrap_or_else(|e| e.into_inner());
+    *registered = definitions
+        .iter()
+        .map(|definition| (definition.name.clone(), Arc::new(definition.clone())))
+        .collect();
+}
+
+fn find_definition(name: &str) -> Option<Arc<JsonlExecutorDefinition>> {
+    let registered = DEFINITIONS.read().unwrap_or_else(|e| e.into_inner());
+    registered.get(name).cloned()
+}
+
+/// The value at a dotted `path`, if every step exists
+fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .filter(|step| !step.is_empty())
+        .try_fold(value, |current, step| match current {
+            Value::Array(items) => step.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => current.get(step),
+        })
+}
+
+/// Text of a field: strings as they are, text blocks joined, anything else as JSON
+fn lookup_text(value: &Value, path: &str) -> Option<String> {
+    let text = match lookup(value, path)? {
+        Value::Null => return None,
+        Value::String(text) => text.clone(),
+        Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|block| match block {
+                Value::String(text) => Some(text.as_str()),
+                _ => block.get("text").and_then(Value::as_str),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        other => other.to_string(),
+    };
+    (!text.trim().is_empty()).then_some(text)
+}
+
+fn lookup_session_id(mapping: &JsonlFieldMapping, line: &str) -> Option<String> {
+    let path = mapping.session_id_field.as_deref()?;
+    let json: Value = serde_json::from_str(line.trim()).ok()?;
+    lookup(&json, path)?.as_str().map(str::to_string)
+}
+
+/// The stdin line passing `message` to a CLI, from its definition's `input_template`
+fn render_input(template: &str, message: &str) -> String {
+    let escaped = serde_json::to_string(message).unwrap_or_default();
+    let escaped = &escaped[1..escaped.len() - 1];
+    format!("{}\n", template.replace("{message}", escaped))
+}
+
+fn tool_use_entry(mapping: &JsonlFieldMapping, json: &Value) -> Option<NormalizedEntry> {
+    let tool_name = lookup_text(json, &mapping.tool_name_field)?;
+    let input = mapping
+        .tool_input_field
+        .as_deref()
+        .and_then(|path| lookup(json, path));
+    let (action_type, content) = match input {
+        Some(input) => match input.get("command").and_then(Value::as_str) {
+            Some(command) => (
+                ActionType::CommandRun {
+                    command: command.to_string(),
+                },
+                format!("`{}`", command),
+            ),
+            None => {
+                let mut summary = input.to_string();
+                if summary.chars().count() > MAX_TOOL_INPUT_CHARS {
+                    summary = summary.chars().take(MAX_TOOL_INPUT_CHARS).collect();
+                    summary.push('…');
+                }
+                (
+                    ActionType::Other {
+                        description: tool_name.clone(),
+                    },
+                    format!("{} {}", tool_name, summary),
+                )
+            }
+        },
+        None => (
+            ActionType::Other {
+                description: tool_name.clone(),
+            },
+            tool_name.clone(),
+        ),
+    };
+    Some(NormalizedEntry {
+        id: None,
+        timestamp: None,
+        duration_ms: None,
+        entry_type: NormalizedEntryType::ToolUse {
+            tool_name,
+            action_type,
+        },
+        content,
+        metadata: Some(json.clone()),
+    })
+}
+
+/// Turn a JSON-lines log into entries according to `mapping`. Lines that aren't JSON are
+/// kept as raw output; records of a type the mapping doesn't mention are skipped.
+pub fn normalize_with_mapping(
+    mapping: &JsonlFieldMapping,
+    logs: &str,
+    executor_type: &str,
+) -> NormalizedConversation {
+    let mut entries = Vec::new();
+    let mut session_id = None;
+
+    for line in logs.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let json: Value = match serde_json::from_str(trimmed) {
+            Ok(json) => json,
+            Err(_) => {
+                entries.push(NormalizedEntry {
+                    id: None,
+                    timestamp: None,
+                    duration_ms: None,
+                    entry_type: NormalizedEntryType::SystemMessage,
+                    content: format!("Raw output: {}", trimmed),
+                    metadata: None,
+                });
+                continue;
+            }
+        };
+
+        if session_id.is_none() {
+            session_id = mapping
+                .session_id_field
+                .as_deref()
+                .and_then(|path| lookup(&json, path))
+                .and_then(Value::as_str)
+                .map(str::to_string);
+        }
+
+        let Some(kind) = lookup(&json, &mapping.type_field).and_then(Value::as_str) else {
+            continue;
+        };
+        let is = |types: &[String]| types.iter().any(|t| t == kind);
+        let entry_type = if is(&mapping.tool_use_types) {
+            entries.extend(tool_use_entry(mapping, &json));
+            continue;
+        } else if is(&mapping.assistant_types) {
+            NormalizedEntryType::AssistantMessage
+        } else if is(&mapping.thinking_types) {
+            NormalizedEntryType::Thinking
+        } else if is(&mapping.system_types) {
+            NormalizedEntryType::SystemMessage
+        } else if is(&mapping.error_types) {
+            NormalizedEntryType::ErrorMessage
+        } else {
+            continue;
+        };
+        if let Some(content) = lookup_text(&json, &mapping.content_field) {
+            entries.push(NormalizedEntry {
+                id: None,
+                timestamp: None,
+                duration_ms: None,
+                entry_type,
+                content,
+                metadata: Some(json),
+            });
+        }
+    }
+
+    NormalizedConversation {
+        entries,
+        session_id,
+        executor_type: executor_type.to_string(),
+        prompt: None,
+        summary: None,
+        diff_summary: None,
+    }
+}
+
+/// Runs the agent described by the config definition of the same name
+pub struct JsonlExecutor {
+    name: String,
+}
+
+impl JsonlExecutor {
+    pub fn new(name: String) -> Self {
+        Self { name }
+    }
+
+    fn executor_type(&self) -> String {
+        format!("jsonl:{}", self.name)
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn definition(&self) -> Result<Arc<JsonlExecutorDefinition>, ExecutorError> {
+        find_definition(&self.name).ok_or_else(|| {
+            ExecutorError::ContextCollectionFailed(format!(
+                "No JSON-lines executor named '{}' is configured",
+                self.name
+            ))
+        })
+    }
+
+    async fn start(
+        &self,
+        definition: &JsonlExecutorDefinition,
+        command_line: &str,
+        prompt: &str,
+        worktree_path: &str,
+        task: Option<(Uuid, String)>,
+        context: String,
+    ) -> Result<CommandProcess, ExecutorError> {
+        let (shell_cmd, shell_arg) = get_shell_command();
+        let mut command = CommandRunner::new();
+        command
+            .command(shell_cmd)
+            .arg(shell_arg)
+            .arg(command_line)
+            .stdin(prompt)
+            .working_dir(worktree_path);
+        if definition.approval.is_some() || definition.input_template.is_some() {
+            command.keep_stdin_open();
+        }
+
+        command.start().await.map_err(|e| {
+            let mut spawn_context = SpawnContext::from_command(&command, self.executor_type());
+            if let Some((task_id, title)) = task {
+                spawn_context = spawn_context.with_task(task_id, Some(title));
+            }
+            spawn_context.with_context(context).spawn_error(e)
+        })
+    }
+}
+
+#[async_trait]
+impl Executor for JsonlExecutor {
+    fn capabilities(&self) -> ExecutorCapabilities {
+        let follow_up = self
+            .definition()
+            .is_ok_and(|definition| definition.follow_up_command.is_some());
+        ExecutorCapabilities {
+            supports_followup: follow_up,
+            supports_sessions: follow_up,
+            supports_plan_mode: false,
+            supports_interactive: self
+                .definition()
+                .is_ok_and(|definition| definition.input_template.is_some()),
+            emits_structured_logs: true,
+        }
+    }
+
+    fn command_approval_reply(&self, allow: bool) -> Option<String> {
+        let approval = self.definition().ok()?.approval.clone()?;
+        let reply = if allow { approval.allow } else { approval.deny };
+        Some(format!("{}\n", reply))
+    }
+
+    fn user_message_input(&self, message: &str) -> Option<String> {
+        let template = self.definition().ok()?.input_template.clone()?;
+        Some(render_input(&template, message))
+    }
+
+    async fn spawn(
+        &self,
+        pool: &DbPool,
+        task_id: Uuid,
+        worktree_path: &str,
+    ) -> Result<CommandProcess, ExecutorError> {
+        let definition = self.definition()?;
+        let task = load_task_for_prompt(pool, task_id, worktree_path).await?;
+        let templated =
+            templated_prompt(pool, &task, worktree_path, &format!("jsonl:{}", self.name)).await?;
+        let mut prompt = match (templated, &task.description) {
+            (Some(prompt), _) => prompt,
+            (None, Some(description)) => format!(
+                "Task title: {}\nTask description: {}",
+                task.title, description
+            ),
+            (None, None) => format!("Task title: {}", task.title),
+        };
+        // There's no telling whether the CLI takes images, so the agent is pointed at where
+        // they are stored
+        prompt.push_str(&image_paths_prompt(pool, task_id).await?);
+
+        let context = format!("{} execution for new task", self.executor_type());
+        self.start(
+            &definition,
+            &definition.command,
+            &prompt,
+            worktree_path,
+            Some((task_id, task.title)),
+            context,
+        )
+        .await
+    }
+
+    async fn spawn_followup(
+        &self,
+        _pool: &DbPool,
+        _task_id: Uuid,
+        session_id: &str,
+        prompt: &str,
+        worktree_path: &str,
+    ) -> Result<CommandProcess, ExecutorError> {
+        let definition = self.definition()?;
+        let Some(template) = &definition.follow_up_command else {
+            return Err(ExecutorError::FollowUpNotSupported);
+        };
+        let command_line = template.replace("{session_id}", &shell_quote(session_id));
+
+        let context = format!(
+            "{} follow-up for session {}",
+            self.executor_type(),
+            session_id
+        );
+        self.start(
+            &definition,
+            &command_line,
+            prompt,
+            worktree_path,
+            None,
+            context,
+        )
+        .await
+    }
+
+    fn normalize_logs(
+        &self,
+        logs: &str,
+        _worktree_path: &str,
+    ) -> Result<NormalizedConversation, String> {
+        // Without the definition the log can still be read, just not interpreted
+        let mapping = find_definition(&self.name)
+            .map(|definition| definition.mapping.clone())
+            .unwrap_or_default();
+        Ok(normalize_with_mapping(
+            &mapping,
+            logs,
+            &self.executor_type(),
+        ))
+    }
+
+    fn normalize_logs_incremental(
+        &self,
+        chunk: &str,
+        state: &mut NormalizerState,
+    ) -> Result<(), String> {
+        normalize_complete_lines(self, chunk, state)
+    }
+
+    async fn setup_streaming(
+        &self,
+        child: &mut CommandProcess,
+        pool: &DbPool,
+        attempt_id: Uuid,
+        execution_process_id: Uuid,
+    ) -> Result<(), ExecutorError> {
+        let streams = child
+            .stream()
+            .await
+            .expect("Failed to get stdio from child process");
+        let stdout = streams
+            .stdout
+            .expect("Failed to take stdout from child process");
+        let stderr = streams
+            .stderr
+            .expect("Failed to take stderr from child process");
+
+        // Session ids are wherever the definition says, not where Claude puts them
+        let mapping = self.definition()?.mapping.clone();
+        tokio::spawn(stream_stdout_to_db_with(
+            stdout,
+            pool.clone(),
+            attempt_id,
+            execution_process_id,
+            move |line| lookup_session_id(&mapping, line),
+        ));
+        tokio::spawn(stream_output_to_db(
+            stderr,
+            pool.clone(),
+            attempt_id,
+            execution_process_id,
+            false,
+        ));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_mapping_normalizes_common_records() {
+        let logs = concat!(
+            r#"{"type":"system","content":"model: example-1","session_id":"s-1"}"#,
+            "\n",
+            r#"{"type":"assistant","content":[{"type":"text","text":"Looking at it"}]}"#,
+            "\n",
+            r#"{"type":"tool_call","name":"shell","input":{"command":"cargo test"}}"#,
+            "\n",
+            r#"{"type":"usage","tokens":10}"#,
+            "\n",
+            "not json\n",
+        );
+        let conversation =
+            normalize_with_mapping(&JsonlFieldMapping::default(), logs, "jsonl:example");
+
+        assert_eq!(conversation.session_id.as_deref(), Some("s-1"));
+        assert_eq!(conversation.executor_type, "jsonl:example");
+        let entries = &conversation.entries;
+        assert_eq!(entries.len(), 4);
+        assert!(matches!(
+            entries[0].entry_type,
+            NormalizedEntryType::SystemMessage
+        ));
+        assert_eq!(entries[1].content, "Looking at it");
+        match &entries[2].entry_type {
+            NormalizedEntryType::ToolUse {
+                tool_name,
+                action_type: ActionType::CommandRun { command },
+            } => {
+                assert_eq!(tool_name, "shell");
+                assert_eq!(command, "cargo test");
+            }
+            other => panic!("expected a command tool use, got {:?}", other),
+        }
+        assert_eq!(entries[3].content, "Raw output: not json");
+    }
+
+    #[test]
+    fn test_custom_mapping_with_nested_paths() {
+        let mapping: JsonlFieldMapping = serde_json::from_value(serde_json::json!({
+            "type_field": "event.kind",
+            "assistant_types": ["reply"],
+            "tool_use_types": ["invoke"],
+            "content_field": "event.body.0",
+            "tool_name_field": "event.tool",
+            "tool_input_field": null,
+            "session_id_field": "meta.thread",
+        }))
+        .unwrap();
+        let logs = concat!(
+            r#"{"meta":{"thread":"t-9"},"event":{"kind":"reply","body":["Done"]}}"#,
+            "\n",
+            r#"{"event":{"kind":"invoke","tool":"search"}}"#,
+        );
+        let conversation = normalize_with_mapping(&mapping, logs, "jsonl:custom");
+
+        assert_eq!(conversation.session_id.as_deref(), Some("t-9"));
+        assert_eq!(conversation.entries.len(), 2);
+        assert_eq!(conversation.entries[0].content, "Done");
+        assert_eq!(conversation.entries[1].content, "search");
+        assert_eq!(
+            lookup_session_id(&mapping, r#"{"meta":{"thread":"t-9"}}"#).as_deref(),
+            Some("t-9")
+        );
+        // Unset fields keep their defaults
+        assert_eq!(mapping.error_types, vec!["error".to_string()]);
+    }
+
+    #[test]
+    fn test_render_input_escapes_the_message_onto_one_line() {
+        let template = r#"{"type":"user","content":"{message}"}"#;
+        assert_eq!(
+            render_input(template, "Use \"tabs\"\nnot spaces"),
+            "{\"type\":\"user\",\"content\":\"Use \\\"tabs\\\"\\nnot spaces\"}\n"
+        );
+    }
+}
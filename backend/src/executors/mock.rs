@@ -0,0 +1,424 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    command_runner::{CommandProcess, CommandRunner},
+    db::DbPool,
+    executor::{
+        ActionType, Executor, ExecutorCapabilities, ExecutorError, NormalizedConversation,
+        NormalizedEntry, NormalizedEntryType, SpawnContext,
+    },
+    models::task::Task,
+    utils::shell::shell_quote,
+};
+
+/// Fence used to embed a mock script inside a task description
+const SCRIPT_FENCE: &str = "```mock-script";
+
+/// One scripted action performed by the mock agent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MockStep {
+    Message {
+        content: String,
+    },
+    Thinking {
+        content: String,
+    },
+    /// Write `content` to `path` (relative to the worktree)
+    FileEdit {
+        path: String,
+        content: String,
+    },
+    /// Report a command run; the command itself is never executed
+    Command {
+        command: String,
+        #[serde(default)]
+        output: Option<String>,
+    },
+    Error {
+        content: String,
+    },
+    /// Raw line written to stderr
+    Stderr {
+        content: String,
+    },
+    Sleep {
+        ms: u64,
+    },
+}
+
+/// Scripted sequence the mock agent plays back before exiting
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MockScript {
+    pub steps: Vec<MockStep>,
+    #[serde(default)]
+    pub exit_code: i32,
+}
+
+impl MockScript {
+    /// Script used when the task does not embed one
+    pub fn default_for_task(title: &str) -> Self {
+        Self {
+            steps: vec![
+                MockStep::Message {
+                    content: format!("Working on: {}", title),
+                },
+                MockStep::FileEdit {
+                    path: "MOCK_CHANGES.md".to_string(),
+                    content: format!("# {}\n\nChanges made by the mock executor.\n", title),
+                },
+                MockStep::Message {
+                    content: "Done.".to_string(),
+                },
+            ],
+            exit_code: 0,
+        }
+    }
+
+    /// Parse a script from a task description: either the whole description is
+    /// JSON, or it contains a ```mock-script fenced block.
+    pub fn from_description(description: &str) -> Option<Self> {
+        let trimmed = description.trim();
+        if trimmed.starts_with('{') {
+            return serde_json::from_str(trimmed).ok();
+        }
+
+        let start = trimmed.find(SCRIPT_FENCE)? + SCRIPT_FENCE.len();
+        let end = trimmed[start..].find("```")? + start;
+        serde_json::from_str(trimmed[start..end].trim()).ok()
+    }
+
+    /// Render the script as a POSIX shell program emitting one JSON event per line
+    pub fn to_shell_script(&self, session_id: &str, prompt: Option<&str>) -> String {
+        let mut lines = vec!["set -e".to_string()];
+        let emit = |lines: &mut Vec<String>, event: serde_json::Value| {
+            lines.push(format!(
+                "printf '%s\\n' {}",
+                shell_quote(&event.to_string())
+            ));
+        };
+
+        emit(
+            &mut lines,
+            serde_json::json!({ "type": "session", "session_id": session_id }),
+        );
+        if let Some(prompt) = prompt {
+            emit(
+                &mut lines,
+                serde_json::json!({ "type": "user", "content": prompt }),
+            );
+        }
+
+        for step in &self.steps {
+            match step {
+                MockStep::FileEdit { path, content } => {
+                    let quoted = shell_quote(path);
+                    lines.push(format!("mkdir -p \"$(dirname {})\"", quoted));
+                    lines.push(format!("printf '%s' {} > {}", shell_quote(content), quoted));
+                    emit(
+                        &mut lines,
+                        serde_json::json!({ "type": "file_edit", "path": path }),
+                    );
+                }
+                MockStep::Stderr { content } => {
+                    lines.push(format!("printf '%s\\n' {} >&2", shell_quote(content)));
+                }
+                MockStep::Sleep { ms } => {
+                    lines.push(format!("sleep {}.{:03}", ms / 1000, ms % 1000));
+                }
+                other => emit(
+                    &mut lines,
+                    serde_json::to_value(other).unwrap_or(serde_json::Value::Null),
+                ),
+            }
+        }
+
+        lines.push(format!("exit {}", self.exit_code));
+        lines.join("\n")
+    }
+}
+
+/// Deterministic executor that plays back a [`MockScript`] instead of calling an agent CLI.
+/// Used by integration tests, local development and server-wide dry runs.
+pub struct MockExecutor {
+    script: Option<MockScript>,
+}
+
+impl Default for MockExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockExecutor {
+    /// Read the script from each task's description, falling back to a default script
+    pub fn new() -> Self {
+        Self { script: None }
+    }
+
+    /// Always play back the given script
+    #[allow(dead_code)]
+    pub fn with_script(script: MockScript) -> Self {
+        Self {
+            script: Some(script),
+        }
+    }
+
+    fn resolve_script(&self, task: &Task) -> MockScript {
+        self.script
+            .clone()
+            .or_else(|| {
+                task.description
+                    .as_deref()
+                    .and_then(MockScript::from_description)
+            })
+            .unwrap_or_else(|| MockScript::default_for_task(&task.title))
+    }
+
+    async fn run_script(
+        &self,
+        pool: &DbPool,
+        task_id: Uuid,
+        session_id: &str,
+        prompt: Option<&str>,
+        worktree_path: &str,
+    ) -> Result<CommandProcess, ExecutorError> {
+        let task = Task::find_by_id(pool, task_id)
+            .await?
+            .ok_or(ExecutorError::TaskNotFound)?;
+
+        let script = self
+            .resolve_script(&task)
+            .to_shell_script(session_id, prompt);
+
+        let mut command = CommandRunner::new();
+        command
+            .command("sh")
+            .arg("-c")
+            .arg(&script)
+            .working_dir(worktree_path);
+
+        command.start().await.map_err(|e| {
+            SpawnContext::from_command(&command, "Mock")
+                .with_task(task_id, Some(task.title.clone()))
+                .with_context("Mock executor script")
+                .spawn_error(e)
+        })
+    }
+}
+
+#[async_trait]
+impl Executor for MockExecutor {
+    fn capabilities(&self) -> ExecutorCapabilities {
+        ExecutorCapabilities {
+            supports_followup: true,
+            supports_sessions: true,
+            supports_plan_mode: false,
+            supports_interactive: false,
+            emits_structured_logs: true,
+        }
+    }
+
+    async fn spawn(
+        &self,
+        pool: &DbPool,
+        task_id: Uuid,
+        worktree_path: &str,
+    ) -> Result<CommandProcess, ExecutorError> {
+        let session_id = format!("mock-{}", task_id);
+        self.run_script(pool, task_id, &session_id, None, worktree_path)
+            .await
+    }
+
+    async fn spawn_followup(
+        &self,
+        pool: &DbPool,
+        task_id: Uuid,
+        session_id: &str,
+        prompt: &str,
+        worktree_path: &str,
+    ) -> Result<CommandProcess, ExecutorError> {
+        self.run_script(pool, task_id, session_id, Some(prompt), worktree_path)
+            .await
+    }
+
+    fn normalize_logs(
+        &self,
+        logs: &str,
+        _worktree_path: &str,
+    ) -> Result<NormalizedConversation, String> {
+        let mut entries = Vec::new();
+        let mut session_id = None;
+        let mut prompt = None;
+
+        for line in logs.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let event: serde_json::Value = match serde_json::from_str(line) {
+                Ok(v) => v,
+                Err(_) => {
+                    entries.push(NormalizedEntry {
+                        id: None,
+                        timestamp: None,
+                        duration_ms: None,
+                        entry_type: NormalizedEntryType::SystemMessage,
+                        content: line.to_string(),
+                        metadata: None,
+                    });
+                    continue;
+                }
+            };
+            let field = |name: &str| {
+                event
+                    .get(name)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string()
+            };
+
+            let (entry_type, content) = match event.get("type").and_then(|t| t.as_str()) {
+                Some("session") => {
+                    session_id = Some(field("session_id"));
+                    continue;
+                }
+                Some("user") => {
+                    prompt.get_or_insert_with(|| field("content"));
+                    (NormalizedEntryType::UserMessage, field("content"))
+                }
+                Some("message") => (NormalizedEntryType::AssistantMessage, field("content")),
+                Some("thinking") => (NormalizedEntryType::Thinking, field("content")),
+                Some("error") => (NormalizedEntryType::ErrorMessage, field("content")),
+                Some("file_edit") => {
+                    let path = field("path");
+                    (
+                        NormalizedEntryType::ToolUse {
+                            tool_name: "edit".to_string(),
+                            action_type: ActionType::FileWrite {
+                                path: path.clone(),
+                                location: None,
+                            },
+                        },
+                        format!("`{}`", path),
+                    )
+                }
+                Some("command") => {
+                    let command = field("command");
+                    (
+                        NormalizedEntryType::ToolUse {
+                            tool_name: "bash".to_string(),
+                            action_type: ActionType::CommandRun {
+                                command: command.clone(),
+                            },
+                        },
+                        format!("`{}`", command),
+                    )
+                }
+                _ => (NormalizedEntryType::SystemMessage, line.to_string()),
+            };
+
+            entries.push(NormalizedEntry {
+                id: None,
+                timestamp: None,
+                duration_ms: None,
+                entry_type,
+                content,
+                metadata: Some(event.clone()),
+            });
+        }
+
+        Ok(NormalizedConversation {
+            entries,
+            session_id,
+            executor_type: "mock".to_string(),
+            prompt,
+            summary: None,
+            diff_summary: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_script() -> MockScript {
+        MockScript {
+            steps: vec![
+                MockStep::Message {
+                    content: "Let's fix it's bug".to_string(),
+                },
+                MockStep::FileEdit {
+                    path: "src/lib.rs".to_string(),
+                    content: "fn main() {}\n".to_string(),
+                },
+                MockStep::Command {
+                    command: "cargo test".to_string(),
+                    output: Some("ok".to_string()),
+                },
+                MockStep::Stderr {
+                    content: "warning: noisy".to_string(),
+                },
+            ],
+            exit_code: 3,
+        }
+    }
+
+    #[test]
+    fn test_script_from_description() {
+        let json = serde_json::to_string(&sample_script()).unwrap();
+        assert_eq!(MockScript::from_description(&json), Some(sample_script()));
+
+        let fenced = format!("Fix the bug\n\n```mock-script\n{}\n```\n", json);
+        assert_eq!(MockScript::from_description(&fenced), Some(sample_script()));
+
+        assert_eq!(MockScript::from_description("Just a normal task"), None);
+    }
+
+    #[test]
+    fn test_shell_script_runs_and_normalizes() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = sample_script().to_shell_script("mock-session", Some("follow up"));
+
+        let output = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(&script)
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+
+        assert_eq!(output.status.code(), Some(3));
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("src/lib.rs")).unwrap(),
+            "fn main() {}\n"
+        );
+        assert_eq!(String::from_utf8_lossy(&output.stderr), "warning: noisy\n");
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let conversation = MockExecutor::new()
+            .normalize_logs(&stdout, dir.path().to_str().unwrap())
+            .unwrap();
+
+        assert_eq!(conversation.session_id.as_deref(), Some("mock-session"));
+        assert_eq!(conversation.prompt.as_deref(), Some("follow up"));
+        assert_eq!(conversation.entries.len(), 4);
+        assert!(matches!(
+            conversation.entries[0].entry_type,
+            NormalizedEntryType::UserMessage
+        ));
+        assert_eq!(conversation.entries[1].content, "Let's fix it's bug");
+        assert!(matches!(
+            &conversation.entries[2].entry_type,
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::FileWrite { path, .. },
+                ..
+            } if path == "src/lib.rs"
+        ));
+        assert!(matches!(
+            &conversation.entries[3].entry_type,
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::CommandRun { command },
+                ..
+            } if command == "cargo test"
+        ));
+    }
+}
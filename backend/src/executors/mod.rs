@@ -1,4 +1,5 @@
 pub mod aaa;
+pub mod acp;
 pub mod aider;
 pub mod amp;
 pub mod ccr;
@@ -9,10 +10,16 @@ pub mod codex;
 pub mod dev_server;
 pub mod echo;
 pub mod gemini;
+pub mod goose;
+pub mod jsonl;
+#[cfg(any(test, feature = "mock-executor"))]
+pub mod mock;
+pub mod replay;
 pub mod setup_script;
 pub mod sst_opencode;
 
 pub use aaa::AaaExecutor;
+pub use acp::AcpExecutor;
 pub use aider::AiderExecutor;
 pub use amp::AmpExecutor;
 pub use ccr::CCRExecutor;
@@ -23,5 +30,10 @@ pub use codex::CodexExecutor;
 pub use dev_server::DevServerExecutor;
 pub use echo::EchoExecutor;
 pub use gemini::GeminiExecutor;
+pub use goose::GooseExecutor;
+pub use jsonl::JsonlExecutor;
+#[cfg(any(test, feature = "mock-executor"))]
+pub use mock::MockExecutor;
+pub use replay::ReplayExecutor;
 pub use setup_script::SetupScriptExecutor;
 pub use sst_opencode::SstOpencodeExecutor;
@@ -0,0 +1,236 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    command_runner::{CommandProcess, CommandRunner},
+    db::DbPool,
+    executor::{
+        Executor, ExecutorConfig, ExecutorError, NormalizedConversation, NormalizedEntry,
+        NormalizedEntryType, SpawnContext,
+    },
+    utils::shell::shell_quote,
+};
+
+/// Delay between lines when a recording carries no timing information
+const UNTIMED_LINE_INTERVAL_MS: u64 = 20;
+
+/// A single captured output line in a timed recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedLine {
+    /// Milliseconds since the start of the original run
+    pub t_ms: u64,
+    #[serde(default)]
+    pub stream: RecordedStream,
+    pub line: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordedStream {
+    #[default]
+    Stdout,
+    Stderr,
+}
+
+/// Parse a recording. Files where every line is a [`RecordedLine`] keep their
+/// original timing; anything else is treated as a raw stdout log.
+pub fn parse_recording(content: &str) -> Vec<RecordedLine> {
+    let timed: Option<Vec<RecordedLine>> = content
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| serde_json::from_str::<RecordedLine>(l).ok())
+        .collect();
+
+    match timed {
+        Some(lines) if !lines.is_empty() => lines,
+        _ => content
+            .lines()
+            .enumerate()
+            .map(|(i, line)| RecordedLine {
+                t_ms: i as u64 * UNTIMED_LINE_INTERVAL_MS,
+                stream: RecordedStream::Stdout,
+                line: line.to_string(),
+            })
+            .collect(),
+    }
+}
+
+/// Build a shell script that re-emits the recording, scaling gaps by `speed`
+pub fn replay_script(lines: &[RecordedLine], speed: f64) -> String {
+    let speed = if speed > 0.0 { speed } else { 1.0 };
+    let mut script = Vec::with_capacity(lines.len() * 2);
+    let mut last_ms = 0u64;
+
+    for recorded in lines {
+        let gap_ms = (recorded.t_ms.saturating_sub(last_ms) as f64 / speed).round() as u64;
+        if gap_ms > 0 {
+            script.push(format!("sleep {}.{:03}", gap_ms / 1000, gap_ms % 1000));
+        }
+        last_ms = last_ms.max(recorded.t_ms);
+
+        let redirect = match recorded.stream {
+            RecordedStream::Stdout => "",
+            RecordedStream::Stderr => " >&2",
+        };
+        script.push(format!(
+            "printf '%s\\n' {}{}",
+            shell_quote(&recorded.line),
+            redirect
+        ));
+    }
+
+    script.join("\n")
+}
+
+/// Replays a recorded agent log as if the agent were running, for demos, UI work
+/// and reproducing normalization bugs from user-submitted logs
+pub struct ReplayExecutor {
+    recording_path: String,
+    speed: f64,
+    normalize_as: Option<String>,
+}
+
+impl ReplayExecutor {
+    pub fn new(recording_path: String, speed: Option<f64>, normalize_as: Option<String>) -> Self {
+        Self {
+            recording_path,
+            speed: speed.unwrap_or(1.0),
+            normalize_as,
+        }
+    }
+}
+
+#[async_trait]
+impl Executor for ReplayExecutor {
+    async fn spawn(
+        &self,
+        _pool: &DbPool,
+        task_id: Uuid,
+        worktree_path: &str,
+    ) -> Result<CommandProcess, ExecutorError> {
+        let content = tokio::fs::read_to_string(&self.recording_path)
+            .await
+            .map_err(|e| {
+                ExecutorError::ContextCollectionFailed(format!(
+                    "Failed to read recording {}: {}",
+                    self.recording_path, e
+                ))
+            })?;
+        let lines = parse_recording(&content);
+
+        // Large recordings would overflow the argument limit, so replay from a file
+        // that removes itself once playback finishes
+        let script_path = std::env::temp_dir().join(format!("vibe-kanban-replay-{}.sh", task_id));
+        let script = format!(
+            "trap 'rm -f {path}' EXIT\n{body}\n",
+            path = shell_quote(&script_path.to_string_lossy()),
+            body = replay_script(&lines, self.speed)
+        );
+        tokio::fs::write(&script_path, script).await.map_err(|e| {
+            ExecutorError::ContextCollectionFailed(format!("Failed to write replay script: {}", e))
+        })?;
+
+        let mut command = CommandRunner::new();
+        command
+            .command("sh")
+            .arg(&script_path.to_string_lossy())
+            .working_dir(worktree_path);
+
+        command.start().await.map_err(|e| {
+            SpawnContext::from_command(&command, "Replay")
+                .with_task(task_id, None)
+                .with_context(format!("Replaying {}", self.recording_path))
+                .spawn_error(e)
+        })
+    }
+
+    fn normalize_logs(
+        &self,
+        logs: &str,
+        worktree_path: &str,
+    ) -> Result<NormalizedConversation, String> {
+        // Normalize with the original executor so its parser bugs reproduce faithfully
+        if let Some(original) = &self.normalize_as {
+            let config = ExecutorConfig::from_str(original)?;
+            return config.create_executor().normalize_logs(logs, worktree_path);
+        }
+
+        Ok(NormalizedConversation {
+            entries: logs
+                .lines()
+                .filter(|l| !l.trim().is_empty())
+                .map(|line| NormalizedEntry {
+                    id: None,
+                    timestamp: None,
+                    duration_ms: None,
+                    entry_type: NormalizedEntryType::SystemMessage,
+                    content: line.to_string(),
+                    metadata: None,
+                })
+                .collect(),
+            session_id: None,
+            executor_type: "replay".to_string(),
+            prompt: None,
+            summary: None,
+            diff_summary: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timed_recording() {
+        let content = r#"{"t_ms":0,"line":"{\"type\":\"system\"}"}
+{"t_ms":1500,"stream":"stderr","line":"warning"}
+"#;
+        let lines = parse_recording(content);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].stream, RecordedStream::Stdout);
+        assert_eq!(lines[0].line, r#"{"type":"system"}"#);
+        assert_eq!(lines[1].t_ms, 1500);
+        assert_eq!(lines[1].stream, RecordedStream::Stderr);
+    }
+
+    #[test]
+    fn test_parse_raw_log_falls_back_to_fixed_interval() {
+        let lines = parse_recording("first\n{\"not\":\"a recording\"}\nthird\n");
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[2].t_ms, 2 * UNTIMED_LINE_INTERVAL_MS);
+        assert_eq!(lines[1].line, "{\"not\":\"a recording\"}");
+    }
+
+    #[test]
+    fn test_replay_script_scales_timing() {
+        let lines = vec![
+            RecordedLine {
+                t_ms: 0,
+                stream: RecordedStream::Stdout,
+                line: "it's".to_string(),
+            },
+            RecordedLine {
+                t_ms: 2000,
+                stream: RecordedStream::Stderr,
+                line: "err".to_string(),
+            },
+        ];
+
+        assert_eq!(
+            replay_script(&lines, 4.0),
+            "printf '%s\\n' 'it'\\''s'\nsleep 0.500\nprintf '%s\\n' 'err' >&2"
+        );
+
+        let output = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(replay_script(&lines, 100.0))
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "it's\n");
+        assert_eq!(String::from_utf8_lossy(&output.stderr), "err\n");
+    }
+}
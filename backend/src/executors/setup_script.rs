@@ -3,6 +3,7 @@ use uuid::Uuid;
 
 use crate::{
     command_runner::{CommandProcess, CommandRunner},
+    db::DbPool,
     executor::{Executor, ExecutorError},
     models::{project::Project, task::Task},
     utils::shell::get_shell_command,
@@ -23,7 +24,7 @@ impl SetupScriptExecutor {
 impl Executor for SetupScriptExecutor {
     async fn spawn(
         &self,
-        pool: &sqlx::SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
         worktree_path: &str,
     ) -> Result<CommandProcess, ExecutorError> {
@@ -64,7 +65,9 @@ impl Executor for SetupScriptExecutor {
 
         // Add script command as first entry
         entries.push(crate::executor::NormalizedEntry {
+            id: None,
             timestamp: None,
+            duration_ms: None,
             entry_type: crate::executor::NormalizedEntryType::SystemMessage,
             content: format!("Executing setup script:\n{}", self.script),
             metadata: None,
@@ -95,7 +98,9 @@ impl Executor for SetupScriptExecutor {
                     };
 
                     entries.push(crate::executor::NormalizedEntry {
+                        id: None,
                         timestamp: Some(chrono::Utc::now().to_rfc3339()),
+                        duration_ms: None,
                         entry_type,
                         content: current_chunk.trim().to_string(),
                         metadata: None,
@@ -108,7 +113,9 @@ impl Executor for SetupScriptExecutor {
             // Add any remaining content
             if !current_chunk.trim().is_empty() {
                 entries.push(crate::executor::NormalizedEntry {
+                    id: None,
                     timestamp: Some(chrono::Utc::now().to_rfc3339()),
+                    duration_ms: None,
                     entry_type: crate::executor::NormalizedEntryType::SystemMessage,
                     content: current_chunk.trim().to_string(),
                     metadata: None,
@@ -122,6 +129,7 @@ impl Executor for SetupScriptExecutor {
             executor_type: "setup-script".to_string(),
             prompt: Some(self.script.clone()),
             summary: None,
+            diff_summary: None,
         })
     }
 }
@@ -5,8 +5,13 @@ use uuid::Uuid;
 
 use crate::{
     command_runner::{CommandProcess, CommandRunner},
-    executor::{Executor, ExecutorError, NormalizedConversation, NormalizedEntry},
-    models::{execution_process::ExecutionProcess, executor_session::ExecutorSession, task::Task},
+    db::DbPool,
+    executor::{
+        agent_flags, image_paths_prompt, load_task_for_prompt, templated_prompt,
+        EnvironmentRequirements, Executor, ExecutorCapabilities, ExecutorError,
+        NormalizedConversation, NormalizedEntry,
+    },
+    models::{execution_process::ExecutionProcess, executor_session::ExecutorSession},
     utils::shell::get_shell_command,
 };
 
@@ -29,7 +34,7 @@ async fn process_line_for_content(
     line: &str,
     session_extracted: &mut bool,
     worktree_path: &str,
-    pool: &sqlx::SqlitePool,
+    pool: &DbPool,
     execution_process_id: uuid::Uuid,
 ) -> Option<Content> {
     if !*session_extracted {
@@ -80,7 +85,7 @@ async fn process_line_for_content(
 /// Stream stderr from OpenCode process with filtering to separate clean output from noise
 pub async fn stream_opencode_stderr_to_db(
     output: impl tokio::io::AsyncRead + Unpin,
-    pool: sqlx::SqlitePool,
+    pool: DbPool,
     attempt_id: Uuid,
     execution_process_id: Uuid,
     worktree_path: String,
@@ -236,18 +241,34 @@ impl SstOpencodeExecutor {
 
 #[async_trait]
 impl Executor for SstOpencodeExecutor {
+    fn capabilities(&self) -> ExecutorCapabilities {
+        ExecutorCapabilities {
+            supports_followup: true,
+            supports_sessions: true,
+            supports_plan_mode: false,
+            supports_interactive: false,
+            emits_structured_logs: true,
+        }
+    }
+
+    fn environment_requirements(&self) -> Option<EnvironmentRequirements> {
+        Some(EnvironmentRequirements::npx())
+    }
+
     async fn spawn(
         &self,
-        pool: &sqlx::SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
         worktree_path: &str,
     ) -> Result<CommandProcess, ExecutorError> {
         // Get the task to fetch its description
-        let task = Task::find_by_id(pool, task_id)
-            .await?
-            .ok_or(ExecutorError::TaskNotFound)?;
+        let task = load_task_for_prompt(pool, task_id, worktree_path).await?;
 
-        let prompt = if let Some(task_description) = task.description {
+        let mut prompt = if let Some(prompt) =
+            templated_prompt(pool, &task, worktree_path, "sst-opencode").await?
+        {
+            prompt
+        } else if let Some(task_description) = task.description {
             format!(
                 r#"project_id: {}
             
@@ -264,15 +285,18 @@ Task title: {}"#,
             )
         };
 
+        // The CLI can't take images, so the agent is pointed at where they are stored
+        prompt.push_str(&image_paths_prompt(pool, task_id).await?);
+
         // Use shell command for cross-platform compatibility
         let (shell_cmd, shell_arg) = get_shell_command();
-        let opencode_command = &self.command;
+        let opencode_command = format!("{}{}", self.command, agent_flags());
 
         let mut command = CommandRunner::new();
         command
             .command(shell_cmd)
             .arg(shell_arg)
-            .arg(opencode_command)
+            .arg(&opencode_command)
             .stdin(&prompt)
             .working_dir(worktree_path)
             .env("NODE_NO_WARNINGS", "1");
@@ -290,7 +314,7 @@ Task title: {}"#,
     /// Execute with OpenCode filtering for stderr
     async fn execute_streaming(
         &self,
-        pool: &sqlx::SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
         attempt_id: Uuid,
         execution_process_id: Uuid,
@@ -347,13 +371,14 @@ Task title: {}"#,
             executor_type: "sst-opencode".to_string(),
             prompt: None,
             summary: None,
+            diff_summary: None,
         })
     }
 
     /// Execute follow-up with OpenCode filtering for stderr
     async fn execute_followup_streaming(
         &self,
-        pool: &sqlx::SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
         attempt_id: Uuid,
         execution_process_id: Uuid,
@@ -391,7 +416,7 @@ Task title: {}"#,
 
     async fn spawn_followup(
         &self,
-        _pool: &sqlx::SqlitePool,
+        _pool: &DbPool,
         _task_id: Uuid,
         session_id: &str,
         prompt: &str,
@@ -399,7 +424,8 @@ Task title: {}"#,
     ) -> Result<CommandProcess, ExecutorError> {
         // Use shell command for cross-platform compatibility
         let (shell_cmd, shell_arg) = get_shell_command();
-        let opencode_command = format!("{} --session {}", self.command, session_id);
+        let opencode_command =
+            format!("{}{} --session {}", self.command, agent_flags(), session_id);
 
         let mut command = CommandRunner::new();
         command
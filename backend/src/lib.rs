@@ -2,16 +2,23 @@ use rust_embed::RustEmbed;
 use sentry_tracing::{EventFilter, SentryLayer};
 use tracing::Level;
 
+pub mod api_client;
 pub mod app_state;
 pub mod command_runner;
+pub mod db;
+pub mod event_bus;
 pub mod execution_monitor;
 pub mod executor;
 pub mod executors;
 pub mod mcp;
+pub mod metrics;
 pub mod middleware;
 pub mod models;
 pub mod routes;
 pub mod services;
+#[cfg(test)]
+mod test_harness;
+pub mod tui;
 pub mod utils;
 
 #[derive(RustEmbed)]
@@ -1,15 +1,14 @@
-use std::{str::FromStr, sync::Arc};
+use std::{net::SocketAddr, sync::Arc};
 
 use axum::{
     body::Body,
     http::{header, HeaderValue, StatusCode},
-    middleware::from_fn_with_state,
+    middleware::{from_fn, from_fn_with_state},
     response::{IntoResponse, Json as ResponseJson, Response},
     routing::{get, post},
-    Json, Router,
+    Extension, Json, Router,
 };
 use sentry_tower::NewSentryLayer;
-use sqlx::{sqlite::SqliteConnectOptions, SqlitePool};
 use strip_ansi_escapes::strip;
 use tokio::sync::RwLock;
 use tower_http::cors::CorsLayer;
@@ -18,28 +17,41 @@ use vibe_kanban::{sentry_layer, Assets, ScriptAssets, SoundAssets};
 
 mod app_state;
 mod command_runner;
+mod db;
+mod event_bus;
 mod execution_monitor;
 mod executor;
 mod executors;
 mod mcp;
+mod metrics;
 mod middleware;
 mod models;
 mod routes;
 mod services;
+mod tui;
 mod utils;
 
 use app_state::AppState;
 use execution_monitor::execution_monitor;
 use middleware::{
-    load_execution_process_simple_middleware, load_project_middleware,
-    load_task_attempt_middleware, load_task_middleware, load_task_template_middleware,
+    access::require_access_middleware, load_execution_process_simple_middleware,
+    load_project_middleware, load_task_attachment_middleware, load_task_attempt_middleware,
+    load_task_middleware, load_task_template_middleware,
 };
-use models::{ApiResponse, Config, Environment};
+use models::{config::DatabaseConfig, release::Release, ApiResponse, Config, Environment};
 use routes::{
-    auth, config, filesystem, github, health, projects, stream, task_attempts, task_templates,
-    tasks,
+    api_tokens, attention, auth, benchmarks, board_columns, config, custom_fields, entry_bookmarks,
+    environment_variables, events, execution_queue, executor_profiles, filesystem, git_host,
+    github, github_issues, github_projects, health, mentions, notifications, notion, openapi,
+    project_container, project_guardrails, project_mcp_servers, project_sandbox, project_security,
+    project_shell_environment, project_worker, projects, releases, script_hooks, search,
+    share_links, stream, task_attachments, task_attempts, task_templates, tasks, users, webhooks,
+};
+use services::{
+    AttentionEscalationService, GitHubIssueSyncService, GitHubProjectSyncService,
+    NotificationDeliveryService, NotionSyncService, PrMonitorService, ScriptHookService,
+    StaleTaskService, TaskSchedulerService,
 };
-use services::PrMonitorService;
 
 async fn echo_handler(
     Json(payload): Json<serde_json::Value>,
@@ -139,15 +151,20 @@ fn main() -> anyhow::Result<()> {
     sentry::configure_scope(|scope| {
         scope.set_tag("source", "server");
     });
+    let command = std::env::args().nth(1);
+    let tui_mode = command.as_deref() == Some("tui");
     tokio::runtime::Builder::new_multi_thread()
         .enable_all()
         .build()
         .unwrap()
         .block_on(async {
-            tracing_subscriber::registry()
-                .with(tracing_subscriber::fmt::layer().with_filter(LevelFilter::INFO))
-                .with(sentry_layer())
-                .init();
+            // The TUI owns the terminal, so it runs without the stdout log layer
+            if !tui_mode {
+                tracing_subscriber::registry()
+                    .with(tracing_subscriber::fmt::layer().with_filter(LevelFilter::INFO))
+                    .with(sentry_layer())
+                    .init();
+            }
 
             // Create asset directory if it doesn't exist
             if !utils::asset_dir().exists() {
@@ -155,18 +172,41 @@ fn main() -> anyhow::Result<()> {
             }
 
             // Database connection
-            let database_url = format!(
-                "sqlite://{}",
-                utils::asset_dir().join("db.sqlite").to_string_lossy()
-            );
+            let database = DatabaseConfig::load(&utils::config_path());
+            let pool = utils::database::connect(&database, true).await?;
+            match command.as_deref() {
+                Some("--migrate-dry-run") => {
+                    println!("{}", services::migrations::plan(&pool).await?.summary());
+                    return Ok(());
+                }
+                Some("--migrate-down") => {
+                    match services::migrations::downgrade(&pool).await? {
+                        Some(version) => println!("Rolled back migration {}", version),
+                        None => println!("No migrations have been applied"),
+                    }
+                    return Ok(());
+                }
+                _ => services::migrations::upgrade(&pool).await?,
+            }
+
+            if tui_mode {
+                return tui::run(pool).await;
+            }
 
-            let options = SqliteConnectOptions::from_str(&database_url)?.create_if_missing(true);
-            let pool = SqlitePool::connect_with(options).await?;
-            sqlx::migrate!("./migrations").run(&pool).await?;
+            // No release script survives a restart
+            match Release::fail_interrupted(&pool).await {
+                Ok(0) => {}
+                Ok(count) => tracing::warn!("Marked {} interrupted release(s) as failed", count),
+                Err(e) => tracing::error!("Failed to clean up interrupted releases: {}", e),
+            }
 
             // Load configuration
             let config_path = utils::config_path();
             let config = Config::load(&config_path)?;
+            executors::jsonl::register_definitions(&config.jsonl_executors);
+            executors::acp::register_definitions(&config.acp_agents);
+            services::log_redaction::configure(&config.log_redaction);
+            services::log_storage::configure(&config.log_storage);
             let config_arc = Arc::new(RwLock::new(config));
 
             let env = std::env::var("ENVIRONMENT")
@@ -179,8 +219,16 @@ fn main() -> anyhow::Result<()> {
 
             app_state.update_sentry_scope().await;
 
+            if app_state.dry_run {
+                tracing::warn!("Dry-run mode enabled: coding agents use the mock executor; merges, pushes and releases are disabled");
+            }
+
             // Track session start event
             app_state.track_analytics_event("session_start", None).await;
+            // Runs the last server was running when it stopped
+            services::execution_recovery::recover(&app_state).await;
+            // Merges the last server had queued when it stopped
+            services::merge_queue::recover(&app_state).await;
             // Start background task to check for init status and spawn processes
             let state_clone = app_state.clone();
             tokio::spawn(async move {
@@ -195,9 +243,84 @@ fn main() -> anyhow::Result<()> {
                 pr_monitor.start_with_config(config_for_monitor).await;
             });
 
+            // Start GitHub Projects sync service
+            let github_project_sync = GitHubProjectSyncService::new(pool.clone());
+            let config_for_sync = config_arc.clone();
+
+            tokio::spawn(async move {
+                github_project_sync.start_with_config(config_for_sync).await;
+            });
+
+            // Start GitHub issue sync service
+            let github_issue_sync = GitHubIssueSyncService::new(pool.clone());
+            let config_for_issues = config_arc.clone();
+
+            tokio::spawn(async move {
+                github_issue_sync.start_with_config(config_for_issues).await;
+            });
+
+            // Start Notion sync service
+            let notion_sync = NotionSyncService::new(pool.clone());
+            let config_for_notion = config_arc.clone();
+
+            tokio::spawn(async move {
+                notion_sync.start_with_config(config_for_notion).await;
+            });
+
+            // Start stale task check
+            let stale_tasks = StaleTaskService::new(pool.clone());
+            let config_for_stale_tasks = config_arc.clone();
+
+            tokio::spawn(async move {
+                stale_tasks.start_with_config(config_for_stale_tasks).await;
+            });
+
+            // Start notification webhook deliveries
+            let notification_delivery = NotificationDeliveryService::new(pool.clone());
+            let config_for_notifications = config_arc.clone();
+
+            tokio::spawn(async move {
+                notification_delivery
+                    .start_with_config(config_for_notifications)
+                    .await;
+            });
+
+            // Start scheduled task runs
+            let task_scheduler = TaskSchedulerService::new(app_state.clone());
+
+            tokio::spawn(async move {
+                task_scheduler.start().await;
+            });
+
+            // Start escalating executions left waiting on the user
+            let attention_escalation = AttentionEscalationService::new(app_state.clone());
+
+            tokio::spawn(async move {
+                attention_escalation.start().await;
+            });
+
+            // Start running project script hooks on task and attempt events
+            let script_hooks = ScriptHookService::new(app_state.clone());
+
+            tokio::spawn(async move {
+                script_hooks.start().await;
+            });
+
+            // Load plugins and start handing them events
+            services::plugins::install(services::plugins::PluginHost::load_dir(
+                &utils::asset_dir().join("plugins"),
+            ));
+
+            tokio::spawn(async move {
+                services::plugins::deliver_events().await;
+            });
+
             // Public routes (no auth required)
             let public_routes = Router::new()
-                .route("/api/health", get(health::health_check))
+                .nest("/api", health::health_router())
+                .nest(&format!("/api/v{}", health::API_VERSION), health::health_router())
+                .route("/api/openapi.json", get(openapi::get_openapi))
+                .route("/metrics", get(health::get_metrics))
                 .route("/api/echo", post(echo_handler));
 
             // Create routers with different middleware layers
@@ -206,10 +329,26 @@ fn main() -> anyhow::Result<()> {
                 .merge(filesystem::filesystem_router())
                 .merge(config::config_router())
                 .merge(auth::auth_router())
+                .merge(users::accounts_router())
+                .merge(api_tokens::api_tokens_router())
+                .merge(mentions::mentions_router())
+                .merge(webhooks::webhooks_router())
+                .merge(search::search_router())
+                .merge(share_links::shared_router())
+                .merge(events::events_router())
+                .merge(execution_queue::execution_queue_router())
+                .merge(attention::attention_router())
+                .merge(notifications::notification_webhooks_router())
+                .merge(executor_profiles::executor_profiles_router())
+                .merge(routes::mcp::mcp_router())
+                .merge(routes::plugins::plugins_router())
                 .route("/sounds/:filename", get(serve_sound_file))
                 .merge(
                     Router::new()
                         .route("/execution-processes/:process_id", get(task_attempts::get_execution_process))
+                        .route("/execution-processes/:process_id/diff", get(task_attempts::get_execution_process_diff))
+                        .route("/execution-processes/:process_id/resources", get(task_attempts::get_execution_process_resources))
+                        .route("/execution-processes/:process_id/tool-usage", get(task_attempts::get_execution_process_tool_usage))
                         .route_layer(from_fn_with_state(app_state.clone(), load_execution_process_simple_middleware))
                 );
 
@@ -229,6 +368,7 @@ fn main() -> anyhow::Result<()> {
                                 .put(task_templates::update_template)
                                 .delete(task_templates::delete_template),
                         )
+                        .route("/templates/:template_id/tasks", post(task_templates::instantiate_template))
                         .route_layer(from_fn_with_state(app_state.clone(), load_task_template_middleware))
                 );
 
@@ -236,14 +376,52 @@ fn main() -> anyhow::Result<()> {
             let project_routes = Router::new()
                 .merge(projects::projects_base_router())
                 .merge(projects::projects_with_id_router()
-                    .layer(from_fn_with_state(app_state.clone(), load_project_middleware)));
+                    .merge(github_projects::github_projects_router())
+                    .merge(github_issues::github_issues_router())
+                    .merge(notion::notion_router())
+                    .merge(environment_variables::project_environment_router())
+                    .merge(custom_fields::project_custom_fields_router())
+                    .merge(board_columns::project_board_columns_router())
+                    .merge(project_mcp_servers::project_mcp_servers_router())
+                    .merge(script_hooks::project_script_hooks_router())
+                    .merge(project_sandbox::project_sandbox_router())
+                    .merge(project_security::project_security_router())
+                    .merge(project_shell_environment::project_shell_environment_router())
+                    .merge(project_container::project_container_router())
+                    .merge(project_worker::project_worker_router())
+                    .merge(project_guardrails::project_guardrails_router())
+                    .merge(entry_bookmarks::project_bookmarks_router())
+                    .merge(git_host::git_host_router())
+                    .merge(events::project_events_router())
+                    .merge(notifications::project_notifications_router())
+                    .merge(executor_profiles::project_executor_profiles_router())
+                    .merge(users::project_members_router())
+                    .layer(from_fn_with_state(app_state.clone(), load_project_middleware)))
+                .merge(releases::releases_router(app_state.clone()))
+                .merge(benchmarks::benchmarks_router(app_state.clone()));
 
             // Task routes with appropriate middleware
             let task_routes = Router::new()
                 .merge(tasks::tasks_project_router()
                     .layer(from_fn_with_state(app_state.clone(), load_project_middleware)))
                 .merge(tasks::tasks_with_id_router()
-                    .layer(from_fn_with_state(app_state.clone(), load_task_middleware)));
+                    .layer(from_fn_with_state(app_state.clone(), load_task_middleware)))
+                .merge(task_attachments::task_attachments_list_router()
+                    .layer(from_fn_with_state(app_state.clone(), load_task_middleware)))
+                .merge(environment_variables::task_environment_router()
+                    .layer(from_fn_with_state(app_state.clone(), load_task_middleware)))
+                .merge(custom_fields::task_custom_fields_router()
+                    .layer(from_fn_with_state(app_state.clone(), load_task_middleware)))
+                .merge(board_columns::task_board_column_router()
+                    .layer(from_fn_with_state(app_state.clone(), load_task_middleware)))
+                .merge(share_links::task_share_links_router()
+                    .layer(from_fn_with_state(app_state.clone(), load_task_middleware)))
+                .merge(share_links::share_link_with_id_router())
+                .merge(entry_bookmarks::task_bookmarks_router()
+                    .layer(from_fn_with_state(app_state.clone(), load_task_middleware)))
+                .merge(entry_bookmarks::bookmark_with_id_router())
+                .merge(task_attachments::task_attachments_with_id_router()
+                    .layer(from_fn_with_state(app_state.clone(), load_task_attachment_middleware)));
 
             // Task attempt routes with appropriate middleware
             let task_attempt_routes = Router::new()
@@ -265,13 +443,17 @@ fn main() -> anyhow::Result<()> {
                 tracing::info!("GitHub repository routes enabled (cloud mode)");
             }
 
-            // All routes (no auth required)
+            // With sign-in on, requests are checked against what the signed-in user may do
+            let api_routes = api_routes
+                .layer(from_fn(events::user_actor_middleware))
+                .layer(from_fn_with_state(app_state.clone(), require_access_middleware))
+                .layer(from_fn_with_state(app_state.clone(), auth::sentry_user_context_middleware));
+
+            // Served under the versioned prefix for external clients, and unversioned for the
+            // frontend shipped with this server
             let app_routes = Router::new()
-                .nest(
-                    "/api",
-                    api_routes
-                        .layer(from_fn_with_state(app_state.clone(), auth::sentry_user_context_middleware)),
-                );
+                .nest(&format!("/api/v{}", health::API_VERSION), api_routes.clone())
+                .nest("/api", api_routes);
 
             let app = Router::new()
                 .merge(public_routes)
@@ -300,9 +482,18 @@ fn main() -> anyhow::Result<()> {
             let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
             let listener = tokio::net::TcpListener::bind(format!("{host}:{port}")).await?;
             let actual_port = listener.local_addr()?.port(); // get → 53427 (example)
+            // Terminals only open for requests to the server's own hosts
+            let app = app.layer(Extension(routes::terminal::ListenAddress(listener.local_addr()?)));
 
             tracing::info!("Server running on http://{host}:{actual_port}");
 
+            let port_file = utils::port_file_path();
+            if let Err(e) = std::fs::create_dir_all(port_file.parent().unwrap())
+                .and_then(|_| std::fs::write(&port_file, actual_port.to_string()))
+            {
+                tracing::warn!("Failed to write port file {}: {}", port_file.display(), e);
+            }
+
             if !cfg!(debug_assertions) {
                 tracing::info!("Opening browser...");
                 if let Err(e) = utils::open_browser(&format!("http://127.0.0.1:{actual_port}")).await {
@@ -310,7 +501,8 @@ fn main() -> anyhow::Result<()> {
                 }
             }
 
-            axum::serve(listener, app).await?;
+            // Client addresses are what sign-in attempts are throttled by, besides accounts
+            axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
 
             Ok(())
         })
@@ -1 +1,2 @@
+pub mod sse;
 pub mod task_server;
@@ -0,0 +1,110 @@
+//! MCP over server-sent events, for agents that reach vibe-kanban over HTTP instead of
+//! starting the stdio server. A client opens the event stream, is told where to post its
+//! messages, and gets the task server's replies on the stream.
+
+use std::collections::HashMap;
+
+use rmcp::{
+    model::{ClientJsonRpcMessage, ServerJsonRpcMessage},
+    transport::Transport,
+    RoleServer, ServiceExt,
+};
+use tokio::sync::{mpsc, RwLock};
+use uuid::Uuid;
+
+use super::task_server::TaskServer;
+use crate::db::DbPool;
+
+/// How many messages can wait in either direction before the sender waits
+const CHANNEL_CAPACITY: usize = 64;
+
+lazy_static::lazy_static! {
+    /// Where to hand each open session's incoming messages
+    static ref SESSIONS: RwLock<HashMap<Uuid, mpsc::Sender<ClientJsonRpcMessage>>> =
+        RwLock::new(HashMap::new());
+}
+
+/// A task server's side of a session: messages posted by the client come in, replies go
+/// out on the client's event stream
+struct ChannelTransport {
+    to_client: mpsc::Sender<ServerJsonRpcMessage>,
+    from_client: mpsc::Receiver<ClientJsonRpcMessage>,
+}
+
+impl Transport<RoleServer> for ChannelTransport {
+    type Error = std::io::Error;
+
+    fn send(
+        &mut self,
+        item: ServerJsonRpcMessage,
+    ) -> impl std::future::Future<Output = Result<(), Self::Error>> + Send + 'static {
+        let to_client = self.to_client.clone();
+        async move {
+            to_client.send(item).await.map_err(|_| {
+                std::io::Error::new(
+                    std::io::ErrorKind::BrokenPipe,
+                    "the client stopped listening",
+                )
+            })
+        }
+    }
+
+    async fn receive(&mut self) -> Option<ClientJsonRpcMessage> {
+        self.from_client.recv().await
+    }
+
+    async fn close(&mut self) -> Result<(), Self::Error> {
+        self.from_client.close();
+        Ok(())
+    }
+}
+
+/// An open session, its messages to be streamed to the client
+pub struct Session {
+    pub id: Uuid,
+    pub messages: mpsc::Receiver<ServerJsonRpcMessage>,
+}
+
+/// Start a session served by its own task server. It ends once the client stops
+/// listening.
+pub async fn open_session(pool: DbPool) -> Session {
+    let id = Uuid::new_v4();
+    let (to_client, messages) = mpsc::channel(CHANNEL_CAPACITY);
+    let (from_client_tx, from_client) = mpsc::channel(CHANNEL_CAPACITY);
+    SESSIONS.write().await.insert(id, from_client_tx);
+
+    let listening = to_client.clone();
+    tokio::spawn(async move {
+        listening.closed().await;
+        // Dropping the sender ends the task server's receive loop
+        SESSIONS.write().await.remove(&id);
+    });
+
+    let transport = ChannelTransport {
+        to_client,
+        from_client,
+    };
+    tokio::spawn(async move {
+        match TaskServer::new(pool).serve(transport).await {
+            Ok(service) => {
+                if let Err(e) = service.waiting().await {
+                    tracing::warn!("MCP session {} ended with an error: {:?}", id, e);
+                }
+            }
+            Err(e) => tracing::error!("Failed to start MCP session {}: {:?}", id, e),
+        }
+        SESSIONS.write().await.remove(&id);
+    });
+
+    Session { id, messages }
+}
+
+/// Hand a client's message to its session, returning false if there's no such session
+/// open
+pub async fn deliver(session_id: Uuid, message: ClientJsonRpcMessage) -> bool {
+    let sender = match SESSIONS.read().await.get(&session_id) {
+        Some(sender) => sender.clone(),
+        None => return false,
+    };
+    sender.send(message).await.is_ok()
+}
@@ -9,12 +9,15 @@ use rmcp::{
 };
 use serde::{Deserialize, Serialize};
 use serde_json;
-use sqlx::SqlitePool;
 use uuid::Uuid;
 
-use crate::models::{
-    project::Project,
-    task::{CreateTask, Task, TaskStatus},
+use crate::{
+    db::DbPool,
+    models::{
+        project::Project,
+        task::{CreateTask, Task, TaskStatus},
+    },
+    services::{related_tasks::find_duplicate_candidates, repo_analysis},
 };
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -32,6 +35,22 @@ pub struct CreateTaskResponse {
     pub success: bool,
     pub task_id: String,
     pub message: String,
+    #[schemars(
+        description = "Open tasks with a near-identical title that may already cover this work"
+    )]
+    pub possible_duplicates: Vec<PossibleDuplicate>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct PossibleDuplicate {
+    #[schemars(description = "The unique identifier of the existing task")]
+    pub id: String,
+    #[schemars(description = "The title of the existing task")]
+    pub title: String,
+    #[schemars(description = "Current status of the existing task")]
+    pub status: String,
+    #[schemars(description = "Whether an agent is working on the existing task right now")]
+    pub has_in_progress_attempt: bool,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -66,7 +85,7 @@ pub struct ListTasksRequest {
     #[schemars(description = "The ID of the project to list tasks from")]
     pub project_id: String,
     #[schemars(
-        description = "Optional status filter: 'todo', 'inprogress', 'inreview', 'done', 'cancelled'"
+        description = "Optional status filter: 'todo', 'queued', 'inprogress', 'planreview', 'inreview', 'done', 'cancelled'"
     )]
     pub status: Option<String>,
     #[schemars(description = "Maximum number of tasks to return (default: 50)")]
@@ -114,7 +133,9 @@ pub struct ListTasksFilters {
 fn parse_task_status(status_str: &str) -> Option<TaskStatus> {
     match status_str.to_lowercase().as_str() {
         "todo" => Some(TaskStatus::Todo),
+        "queued" => Some(TaskStatus::Queued),
         "inprogress" | "in-progress" | "in_progress" => Some(TaskStatus::InProgress),
+        "planreview" | "plan-review" | "plan_review" => Some(TaskStatus::PlanReview),
         "inreview" | "in-review" | "in_review" => Some(TaskStatus::InReview),
         "done" | "completed" => Some(TaskStatus::Done),
         "cancelled" | "canceled" => Some(TaskStatus::Cancelled),
@@ -125,7 +146,9 @@ fn parse_task_status(status_str: &str) -> Option<TaskStatus> {
 fn task_status_to_string(status: &TaskStatus) -> String {
     match status {
         TaskStatus::Todo => "todo".to_string(),
+        TaskStatus::Queued => "queued".to_string(),
         TaskStatus::InProgress => "in-progress".to_string(),
+        TaskStatus::PlanReview => "plan-review".to_string(),
         TaskStatus::InReview => "in-review".to_string(),
         TaskStatus::Done => "done".to_string(),
         TaskStatus::Cancelled => "cancelled".to_string(),
@@ -142,7 +165,9 @@ pub struct UpdateTaskRequest {
     pub title: Option<String>,
     #[schemars(description = "New description for the task")]
     pub description: Option<String>,
-    #[schemars(description = "New status: 'todo', 'inprogress', 'inreview', 'done', 'cancelled'")]
+    #[schemars(
+        description = "New status: 'todo', 'queued', 'inprogress', 'planreview', 'inreview', 'done', 'cancelled'"
+    )]
     pub status: Option<String>,
 }
 
@@ -191,15 +216,69 @@ pub struct GetTaskResponse {
     pub project_name: Option<String>,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetRepoMapRequest {
+    #[schemars(description = "The ID of the project whose repository to map")]
+    pub project_id: String,
+    #[schemars(description = "Only list files under this path, e.g. `src/routes/`")]
+    pub path_prefix: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetBoardRequest {
+    #[schemars(description = "The ID of the project whose board to show")]
+    pub project_id: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct BoardTask {
+    #[schemars(description = "The unique identifier of the task")]
+    pub id: String,
+    #[schemars(description = "The title of the task")]
+    pub title: String,
+    #[schemars(description = "Whether an agent is working on the task right now")]
+    pub has_in_progress_attempt: bool,
+    #[schemars(description = "Whether the last execution attempt failed")]
+    pub last_attempt_failed: bool,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct BoardColumn {
+    #[schemars(description = "The status the column holds")]
+    pub status: String,
+    pub count: usize,
+    pub tasks: Vec<BoardTask>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct GetBoardResponse {
+    pub success: bool,
+    pub project_id: String,
+    pub project_name: String,
+    #[schemars(description = "Every column of the board in order, empty ones included")]
+    pub columns: Vec<BoardColumn>,
+}
+
+/// The board's columns, left to right
+const BOARD_COLUMNS: [TaskStatus; 7] = [
+    TaskStatus::Todo,
+    TaskStatus::Queued,
+    TaskStatus::InProgress,
+    TaskStatus::PlanReview,
+    TaskStatus::InReview,
+    TaskStatus::Done,
+    TaskStatus::Cancelled,
+];
+
 #[derive(Debug, Clone)]
 pub struct TaskServer {
-    pub pool: SqlitePool,
+    pub pool: DbPool,
     tool_router: ToolRouter<TaskServer>,
 }
 
 impl TaskServer {
     #[allow(dead_code)]
-    pub fn new(pool: SqlitePool) -> Self {
+    pub fn new(pool: DbPool) -> Self {
         Self {
             pool,
             tool_router: Self::tool_router(),
@@ -264,20 +343,51 @@ impl TaskServer {
             Ok(true) => {}
         }
 
+        // Title similarity only: the MCP server has no embeddings provider
+        let possible_duplicates: Vec<PossibleDuplicate> = find_duplicate_candidates(
+            &self.pool,
+            None,
+            project_uuid,
+            &title,
+            description.as_deref(),
+        )
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to look for duplicate tasks: {}", e);
+            Vec::new()
+        })
+        .into_iter()
+        .map(|candidate| PossibleDuplicate {
+            id: candidate.id.to_string(),
+            title: candidate.title,
+            status: task_status_to_string(&candidate.status),
+            has_in_progress_attempt: candidate.has_in_progress_attempt,
+        })
+        .collect();
+
         let task_id = Uuid::new_v4();
         let create_task_data = CreateTask {
             project_id: project_uuid,
             title: title.clone(),
             description: description.clone(),
             parent_task_attempt: None,
+            parent_task_id: None,
         };
 
         match Task::create(&self.pool, &create_task_data, task_id).await {
             Ok(_task) => {
+                let message = if possible_duplicates.is_empty() {
+                    "Task created successfully".to_string()
+                } else {
+                    "Task created, but open tasks with a similar title exist; check them \
+                     before starting work on this one"
+                        .to_string()
+                };
                 let success_response = CreateTaskResponse {
                     success: true,
                     task_id: task_id.to_string(),
-                    message: "Task created successfully".to_string(),
+                    message,
+                    possible_duplicates,
                 };
                 Ok(CallToolResult::success(vec![Content::text(
                     serde_json::to_string_pretty(&success_response)
@@ -379,7 +489,7 @@ impl TaskServer {
                 None => {
                     let error_response = serde_json::json!({
                         "success": false,
-                        "error": "Invalid status filter. Valid values: 'todo', 'inprogress', 'inreview', 'done', 'cancelled'",
+                        "error": "Invalid status filter. Valid values: 'todo', 'queued', 'inprogress', 'planreview', 'inreview', 'done', 'cancelled'",
                         "provided_status": status_str
                     });
                     return Ok(CallToolResult::error(vec![Content::text(
@@ -533,7 +643,7 @@ impl TaskServer {
                 None => {
                     let error_response = serde_json::json!({
                         "success": false,
-                        "error": "Invalid status. Valid values: 'todo', 'inprogress', 'inreview', 'done', 'cancelled'",
+                        "error": "Invalid status. Valid values: 'todo', 'queued', 'inprogress', 'planreview', 'inreview', 'done', 'cancelled'",
                         "provided_status": status_str
                     });
                     return Ok(CallToolResult::error(vec![Content::text(
@@ -661,13 +771,13 @@ impl TaskServer {
 
         match Task::exists(&self.pool, task_uuid, project_uuid).await {
             Ok(true) => {
-                // Delete the task
-                match Task::delete(&self.pool, task_uuid, project_uuid).await {
-                    Ok(rows_affected) => {
-                        if rows_affected > 0 {
+                // Move the task to the trash, where the user can restore it
+                match Task::trash(&self.pool, task_uuid, project_uuid).await {
+                    Ok(trashed) => {
+                        if trashed {
                             let response = DeleteTaskResponse {
                                 success: true,
-                                message: "Task deleted successfully".to_string(),
+                                message: "Task moved to the trash".to_string(),
                                 deleted_task_id: Some(task_id),
                             };
                             Ok(CallToolResult::success(vec![Content::text(
@@ -802,6 +912,173 @@ impl TaskServer {
             }
         }
     }
+
+    #[tool(
+        description = "Get a map of the project's repository: languages, and every tracked file with the functions, types and classes it defines. Cheaper than listing and searching the codebase yourself. `project_id` is required!"
+    )]
+    async fn get_repo_map(
+        &self,
+        Parameters(GetRepoMapRequest {
+            project_id,
+            path_prefix,
+        }): Parameters<GetRepoMapRequest>,
+    ) -> Result<CallToolResult, RmcpError> {
+        let project_uuid = match Uuid::parse_str(&project_id) {
+            Ok(uuid) => uuid,
+            Err(_) => {
+                let error_response = serde_json::json!({
+                    "success": false,
+                    "error": "Invalid project ID format"
+                });
+                return Ok(CallToolResult::error(vec![Content::text(
+                    serde_json::to_string_pretty(&error_response).unwrap(),
+                )]));
+            }
+        };
+
+        let project = match Project::find_by_id(&self.pool, project_uuid).await {
+            Ok(Some(project)) => project,
+            Ok(None) => {
+                let error_response = serde_json::json!({
+                    "success": false,
+                    "error": "Project not found"
+                });
+                return Ok(CallToolResult::error(vec![Content::text(
+                    serde_json::to_string_pretty(&error_response).unwrap(),
+                )]));
+            }
+            Err(e) => {
+                let error_response = serde_json::json!({
+                    "success": false,
+                    "error": "Failed to retrieve project",
+                    "details": e.to_string()
+                });
+                return Ok(CallToolResult::error(vec![Content::text(
+                    serde_json::to_string_pretty(&error_response).unwrap(),
+                )]));
+            }
+        };
+
+        let repo_path = std::path::PathBuf::from(&project.git_repo_path);
+        let analysis =
+            tokio::task::spawn_blocking(move || repo_analysis::load_or_refresh(&repo_path)).await;
+        match analysis {
+            Ok(Ok((analysis, _))) => Ok(CallToolResult::success(vec![Content::text(
+                analysis.render_map(path_prefix.as_deref(), repo_analysis::DEFAULT_MAP_CHARS),
+            )])),
+            Ok(Err(e)) => {
+                let error_response = serde_json::json!({
+                    "success": false,
+                    "error": "Failed to analyze the repository",
+                    "details": e.to_string()
+                });
+                Ok(CallToolResult::error(vec![Content::text(
+                    serde_json::to_string_pretty(&error_response).unwrap(),
+                )]))
+            }
+            Err(e) => {
+                let error_response = serde_json::json!({
+                    "success": false,
+                    "error": "Failed to analyze the repository",
+                    "details": e.to_string()
+                });
+                Ok(CallToolResult::error(vec![Content::text(
+                    serde_json::to_string_pretty(&error_response).unwrap(),
+                )]))
+            }
+        }
+    }
+
+    #[tool(
+        description = "Get the state of a project's kanban board: every column in order with the tasks in it, and whether an agent is working on each. `project_id` is required!"
+    )]
+    async fn get_board(
+        &self,
+        Parameters(GetBoardRequest { project_id }): Parameters<GetBoardRequest>,
+    ) -> Result<CallToolResult, RmcpError> {
+        let project_uuid = match Uuid::parse_str(&project_id) {
+            Ok(uuid) => uuid,
+            Err(_) => {
+                let error_response = serde_json::json!({
+                    "success": false,
+                    "error": "Invalid project ID format"
+                });
+                return Ok(CallToolResult::error(vec![Content::text(
+                    serde_json::to_string_pretty(&error_response).unwrap(),
+                )]));
+            }
+        };
+
+        let project = match Project::find_by_id(&self.pool, project_uuid).await {
+            Ok(Some(project)) => project,
+            Ok(None) => {
+                let error_response = serde_json::json!({
+                    "success": false,
+                    "error": "Project not found"
+                });
+                return Ok(CallToolResult::error(vec![Content::text(
+                    serde_json::to_string_pretty(&error_response).unwrap(),
+                )]));
+            }
+            Err(e) => {
+                let error_response = serde_json::json!({
+                    "success": false,
+                    "error": "Failed to retrieve project",
+                    "details": e.to_string()
+                });
+                return Ok(CallToolResult::error(vec![Content::text(
+                    serde_json::to_string_pretty(&error_response).unwrap(),
+                )]));
+            }
+        };
+
+        let tasks =
+            match Task::find_by_project_id_with_attempt_status(&self.pool, project_uuid).await {
+                Ok(tasks) => tasks,
+                Err(e) => {
+                    let error_response = serde_json::json!({
+                        "success": false,
+                        "error": "Failed to retrieve tasks",
+                        "details": e.to_string()
+                    });
+                    return Ok(CallToolResult::error(vec![Content::text(
+                        serde_json::to_string_pretty(&error_response).unwrap(),
+                    )]));
+                }
+            };
+
+        let columns = BOARD_COLUMNS
+            .iter()
+            .map(|status| {
+                let tasks: Vec<BoardTask> = tasks
+                    .iter()
+                    .filter(|task| &task.status == status)
+                    .map(|task| BoardTask {
+                        id: task.id.to_string(),
+                        title: task.title.clone(),
+                        has_in_progress_attempt: task.has_in_progress_attempt,
+                        last_attempt_failed: task.last_attempt_failed,
+                    })
+                    .collect();
+                BoardColumn {
+                    status: task_status_to_string(status),
+                    count: tasks.len(),
+                    tasks,
+                }
+            })
+            .collect();
+
+        let response = GetBoardResponse {
+            success: true,
+            project_id,
+            project_name: project.name,
+            columns,
+        };
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&response)
+                .unwrap_or_else(|_| "Failed to serialize the board".to_string()),
+        )]))
+    }
 }
 
 #[tool_handler]
@@ -816,7 +1093,7 @@ impl ServerHandler for TaskServer {
                 name: "vibe-kanban".to_string(),
                 version: "1.0.0".to_string(),
             },
-            instructions: Some("A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. This should be provided to you. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project`. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'get_task', 'update_task', 'delete_task'. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids.".to_string()),
+            instructions: Some("A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. This should be provided to you. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project`. Call `get_board` to see every column of a project's board at once, and mark a task done with `update_task` and status 'done'. TOOLS: 'list_projects', 'get_board', 'list_tasks', 'create_task', 'get_task', 'update_task', 'delete_task', 'get_repo_map'. Make sure to pass `project_id` or `task_id` where required. You can use list tools to get the available ids.".to_string()),
         }
     }
 }
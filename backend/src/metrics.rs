@@ -0,0 +1,277 @@
+//! Operational metrics in the Prometheus text format, served at `/metrics`. Failures and
+//! output that couldn't keep up with storage are counted in memory from the time the
+//! server started; everything else is read from the database on each scrape, so it carries
+//! over restarts.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use crate::{
+    db::DbPool,
+    models::{
+        execution_metrics::ExecutionMetrics, execution_process::ExecutionProcess,
+        execution_queue::QueuedAttempt,
+    },
+};
+
+/// Every process type gets a running gauge, so alerts see a zero rather than no series
+const PROCESS_TYPES: [&str; 4] = ["setupscript", "cleanupscript", "codingagent", "devserver"];
+
+lazy_static::lazy_static! {
+    static ref SPAWN_FAILURES: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
+    static ref NORMALIZATION_ERRORS: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
+}
+
+static OUTPUT_SPILLED_BYTES: AtomicU64 = AtomicU64::new(0);
+static OUTPUT_DROPPED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// Count an executor process that couldn't be spawned
+pub fn record_spawn_failure(executor_type: &str) {
+    increment(&SPAWN_FAILURES, executor_type);
+}
+
+/// Count an execution's output that its executor failed to normalize
+pub fn record_normalization_error(executor_type: &str) {
+    increment(&NORMALIZATION_ERRORS, executor_type);
+}
+
+/// Count process output that came faster than it was stored and waited on disk
+pub fn record_output_spilled(bytes: u64) {
+    OUTPUT_SPILLED_BYTES.fetch_add(bytes, Ordering::Relaxed);
+}
+
+/// Count process output that came faster than it was stored and didn't fit on disk either
+pub fn record_output_dropped(bytes: u64) {
+    OUTPUT_DROPPED_BYTES.fetch_add(bytes, Ordering::Relaxed);
+}
+
+fn increment(counter: &Mutex<BTreeMap<String, u64>>, executor_type: &str) {
+    *counter
+        .lock()
+        .unwrap()
+        .entry(executor_type.to_string())
+        .or_default() += 1;
+}
+
+fn snapshot(counter: &Mutex<BTreeMap<String, u64>>) -> BTreeMap<String, u64> {
+    counter.lock().unwrap().clone()
+}
+
+/// Builds up a scrape, one metric family at a time
+#[derive(Default)]
+struct Exposition {
+    out: String,
+}
+
+impl Exposition {
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.out, "# HELP {} {}", name, help);
+        let _ = writeln!(self.out, "# TYPE {} {}", name, kind);
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.out.push_str(name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+                .collect();
+            let _ = write!(self.out, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.out, " {}", value);
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// The current metrics, ready to be served to Prometheus
+pub async fn render(pool: &DbPool) -> Result<String, sqlx::Error> {
+    let running = ExecutionProcess::summarize_running(pool).await?;
+    let finished = ExecutionProcess::summarize_finished(pool).await?;
+    let queued = QueuedAttempt::count(pool).await?;
+    let usage = ExecutionMetrics::usage_by_executor(pool).await?;
+    let mut metrics = Exposition::default();
+
+    metrics.family(
+        "vibe_kanban_running_executions",
+        "gauge",
+        "Executions running now, by process type",
+    );
+    for process_type in PROCESS_TYPES {
+        let count = running
+            .iter()
+            .find(|r| r.process_type == process_type)
+            .map_or(0, |r| r.count);
+        metrics.sample(
+            "vibe_kanban_running_executions",
+            &[("process_type", process_type)],
+            count as f64,
+        );
+    }
+    metrics.family(
+        "vibe_kanban_running_execution_age_seconds",
+        "gauge",
+        "How long the longest-running execution of each type has been running",
+    );
+    for r in &running {
+        metrics.sample(
+            "vibe_kanban_running_execution_age_seconds",
+            &[("process_type", &r.process_type)],
+            r.oldest_seconds,
+        );
+    }
+    metrics.family(
+        "vibe_kanban_running_execution_idle_seconds",
+        "gauge",
+        "How long the quietest running execution of each type has gone without output",
+    );
+    for r in &running {
+        metrics.sample(
+            "vibe_kanban_running_execution_idle_seconds",
+            &[("process_type", &r.process_type)],
+            r.idle_seconds,
+        );
+    }
+
+    metrics.family(
+        "vibe_kanban_queued_attempts",
+        "gauge",
+        "Attempts waiting for a slot under the concurrency limits",
+    );
+    metrics.sample("vibe_kanban_queued_attempts", &[], queued as f64);
+
+    metrics.family(
+        "vibe_kanban_spawn_failures_total",
+        "counter",
+        "Executor processes that failed to spawn since the server started",
+    );
+    for (executor, count) in snapshot(&SPAWN_FAILURES) {
+        metrics.sample(
+            "vibe_kanban_spawn_failures_total",
+            &[("executor", &executor)],
+            count as f64,
+        );
+    }
+    metrics.family(
+        "vibe_kanban_normalization_errors_total",
+        "counter",
+        "Execution logs their executor failed to normalize since the server started",
+    );
+    for (executor, count) in snapshot(&NORMALIZATION_ERRORS) {
+        metrics.sample(
+            "vibe_kanban_normalization_errors_total",
+            &[("executor", &executor)],
+            count as f64,
+        );
+    }
+
+    metrics.family(
+        "vibe_kanban_output_spilled_bytes_total",
+        "counter",
+        "Process output spilled to disk to wait for storage since the server started",
+    );
+    metrics.sample(
+        "vibe_kanban_output_spilled_bytes_total",
+        &[],
+        OUTPUT_SPILLED_BYTES.load(Ordering::Relaxed) as f64,
+    );
+    metrics.family(
+        "vibe_kanban_output_dropped_bytes_total",
+        "counter",
+        "Process output dropped for outrunning storage since the server started",
+    );
+    metrics.sample(
+        "vibe_kanban_output_dropped_bytes_total",
+        &[],
+        OUTPUT_DROPPED_BYTES.load(Ordering::Relaxed) as f64,
+    );
+
+    // The average duration is the rate of the sum over the rate of the count
+    metrics.family(
+        "vibe_kanban_execution_duration_seconds",
+        "summary",
+        "How long finished executions ran, by process type and executor",
+    );
+    for f in &finished {
+        let labels = [
+            ("process_type", f.process_type.as_str()),
+            ("executor", f.executor_type.as_str()),
+        ];
+        metrics.sample(
+            "vibe_kanban_execution_duration_seconds_sum",
+            &labels,
+            f.total_seconds,
+        );
+        metrics.sample(
+            "vibe_kanban_execution_duration_seconds_count",
+            &labels,
+            f.count as f64,
+        );
+    }
+
+    metrics.family(
+        "vibe_kanban_tokens_total",
+        "counter",
+        "Tokens used by coding agent runs that reported usage",
+    );
+    for u in &usage {
+        for (direction, tokens) in [("input", u.input_tokens), ("output", u.output_tokens)] {
+            metrics.sample(
+                "vibe_kanban_tokens_total",
+                &[("executor", &u.executor_type), ("direction", direction)],
+                tokens as f64,
+            );
+        }
+    }
+    metrics.family(
+        "vibe_kanban_cost_usd_total",
+        "counter",
+        "Cost in US dollars of coding agent runs that reported it",
+    );
+    for u in &usage {
+        if let Some(cost_usd) = u.cost_usd {
+            metrics.sample(
+                "vibe_kanban_cost_usd_total",
+                &[("executor", &u.executor_type)],
+                cost_usd,
+            );
+        }
+    }
+
+    Ok(metrics.out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_samples_are_written_in_the_text_format() {
+        let mut metrics = Exposition::default();
+        metrics.family("vibe_kanban_example", "gauge", "An example");
+        metrics.sample("vibe_kanban_example", &[], 2.0);
+        metrics.sample(
+            "vibe_kanban_example",
+            &[("executor", "say \"hi\"\\\n"), ("kind", "input")],
+            0.5,
+        );
+        assert_eq!(
+            metrics.out,
+            "# HELP vibe_kanban_example An example\n\
+             # TYPE vibe_kanban_example gauge\n\
+             vibe_kanban_example 2\n\
+             vibe_kanban_example{executor=\"say \\\"hi\\\"\\\\\\n\",kind=\"input\"} 0.5\n"
+        );
+    }
+}
@@ -0,0 +1,507 @@
+//! Who may do what once sign-in is turned on. Every API request but signing in and the
+//! signed webhooks needs a session or a personal access token. What it may then do is
+//! decided by the user's role in the project the request is about, which comes from its
+//! path, and reading needs a lower role than changing anything. Requests that aren't about
+//! one project are left to admins, apart from a few everyone needs to load the app. Projects
+//! that require two-factor authentication turn away password accounts without it. A token
+//! is further held to its scopes, and each request made with one is recorded.
+
+use axum::{
+    extract::{OriginalUri, Request, State},
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    models::{
+        api_token::{ApiToken, TokenScope},
+        execution_process::ExecutionProcess,
+        notification_webhook::NotificationWebhook,
+        project_member::ProjectRole,
+        task_attempt::TaskAttempt,
+        task_template::TaskTemplate,
+        user::User,
+    },
+    services::{accounts, two_factor},
+};
+
+/// Project settings only project admins change
+const ADMIN_SECTIONS: [&str; 15] = [
+    "members",
+    "environment",
+    "mcp-servers",
+    "sandbox",
+    "security",
+    "container",
+    "worker",
+    "guardrails",
+    "git-host",
+    "notification-webhooks",
+    "notion",
+    "github-project",
+    "prompt-templates",
+    "worktrees",
+    "script-hooks",
+];
+
+/// What a request is about, to find its project by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    Project(Uuid),
+    ExecutionProcess(Uuid),
+    TaskAttempt(Uuid),
+    /// Templates without a project are anyone's to read and admins' to change
+    Template(Uuid),
+    NotificationWebhook(Uuid),
+    /// A feed across projects, narrowed to one by its `project_id` query parameter. Only
+    /// admins read it across all of them.
+    ProjectQuery,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Requirement {
+    Public,
+    SignedIn,
+    Admin,
+    Role(Scope, ProjectRole),
+}
+
+fn is_read(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+fn project_role(read: bool, rest: &[&str]) -> ProjectRole {
+    match rest {
+        // The terminal is a shell in the worktree, so opening it is no mere read
+        ["tasks", _, "attempts", _, "terminal"] => ProjectRole::Member,
+        // Pending invites are who may still join, and with what role
+        ["invites", ..] => ProjectRole::Admin,
+        _ if read => ProjectRole::Viewer,
+        // Approving an attempt merges it past the review gate
+        ["tasks", _, "attempts", _, "review", ..] => ProjectRole::Admin,
+        // Editing or deleting the project itself
+        [] => ProjectRole::Admin,
+        [section, ..] if ADMIN_SECTIONS.contains(section) => ProjectRole::Admin,
+        _ => ProjectRole::Member,
+    }
+}
+
+/// The path's segments below `/api`
+fn segments(path: &str) -> Vec<&str> {
+    let segments: Vec<&str> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    match segments.as_slice() {
+        ["api", rest @ ..] => rest.to_vec(),
+        _ => segments,
+    }
+}
+
+/// What a request to `path`, below `/api`, needs
+pub fn requirement(method: &Method, path: &str) -> Requirement {
+    let segments = segments(path);
+    let read = is_read(method);
+    let change = |role| if read { ProjectRole::Viewer } else { role };
+    let scoped = |id: &str, scope: fn(Uuid) -> Scope, role| match Uuid::parse_str(id) {
+        Ok(id) => Requirement::Role(scope(id), role),
+        Err(_) => Requirement::Admin,
+    };
+
+    match segments.as_slice() {
+        // Signs the server itself into GitHub
+        ["auth", "github", ..] => Requirement::Admin,
+        ["auth", ..] | ["webhooks", ..] | ["sounds", ..] => Requirement::Public,
+        // The link's token is what grants access
+        ["shared", _] if read => Requirement::Public,
+        ["projects"] if read => Requirement::SignedIn,
+        ["projects", "from-github"] => Requirement::Admin,
+        ["projects", id, rest @ ..] => scoped(id, Scope::Project, project_role(read, rest)),
+        ["execution-processes", id, ..] => {
+            scoped(id, Scope::ExecutionProcess, change(ProjectRole::Member))
+        }
+        ["attempts", id, ..] => scoped(id, Scope::TaskAttempt, change(ProjectRole::Member)),
+        ["templates"] | ["templates", "global"] if read => Requirement::SignedIn,
+        ["templates", id, ..] => scoped(id, Scope::Template, change(ProjectRole::Member)),
+        ["notification-webhooks", id, ..] => {
+            scoped(id, Scope::NotificationWebhook, change(ProjectRole::Admin))
+        }
+        ["events"] | ["events", "stream"] | ["search"] if read => {
+            Requirement::Role(Scope::ProjectQuery, ProjectRole::Viewer)
+        }
+        ["config"] | ["config", "constants"] if read => Requirement::SignedIn,
+        ["tokens", ..] | ["mentions", ..] => Requirement::SignedIn,
+        // The MCP tools reach every project's board
+        ["mcp", ..] => Requirement::Admin,
+        _ => Requirement::Admin,
+    }
+}
+
+/// The scopes a personal access token needs to make a request, on top of what
+/// [`requirement`] asks of its user. `None` for requests tokens can't make at all.
+pub fn token_scopes(method: &Method, path: &str) -> Option<&'static [TokenScope]> {
+    let segments = segments(path);
+    let read = is_read(method);
+    match segments.as_slice() {
+        ["projects"] if read => Some(&[]),
+        // The terminal is for people
+        ["projects", _, "tasks", _, "attempts", _, "terminal"] => None,
+        ["projects", ..] | ["attempts", ..] | ["execution-processes", ..] if read => {
+            Some(&[TokenScope::ReadLogs])
+        }
+        ["projects", _, "tasks"] if *method == Method::POST => Some(&[TokenScope::CreateTask]),
+        ["projects", _, "tasks", "create-and-start"] => {
+            Some(&[TokenScope::CreateTask, TokenScope::TriggerExecution])
+        }
+        ["projects", _, "tasks", _, "attempts"]
+        | ["projects", _, "tasks", _, "attempts", "parallel"]
+        | ["projects", _, "tasks", _, "attempts", _, "follow-up"] => {
+            Some(&[TokenScope::TriggerExecution])
+        }
+        // An orchestrating agent managing the board over MCP
+        ["mcp", ..] => Some(&[TokenScope::CreateTask]),
+        _ => None,
+    }
+}
+
+fn query_project_id(query: Option<&str>) -> Option<Uuid> {
+    query?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(name, _)| *name == "project_id")
+        .and_then(|(_, value)| Uuid::parse_str(value).ok())
+}
+
+/// The project a scope belongs to: `None` if what it names doesn't exist, `Some(None)` if
+/// it isn't any one project's
+async fn resolve_project(
+    app_state: &AppState,
+    scope: Scope,
+    query: Option<&str>,
+) -> Result<Option<Option<Uuid>>, sqlx::Error> {
+    let pool = &app_state.db_pool;
+    Ok(match scope {
+        Scope::Project(id) => Some(Some(id)),
+        Scope::ExecutionProcess(id) => ExecutionProcess::find_project_id(pool, id).await?.map(Some),
+        Scope::TaskAttempt(id) => TaskAttempt::find_project_id(pool, id).await?.map(Some),
+        Scope::Template(id) => TaskTemplate::find_by_id(pool, id)
+            .await?
+            .map(|template| template.project_id),
+        Scope::NotificationWebhook(id) => NotificationWebhook::find_by_id(pool, id)
+            .await?
+            .map(|webhook| Some(webhook.project_id)),
+        Scope::ProjectQuery => Some(query_project_id(query)),
+    })
+}
+
+/// Whether the user may make a request with this requirement
+async fn check(
+    app_state: &AppState,
+    user: &User,
+    requirement: Requirement,
+    query: Option<&str>,
+) -> Result<(), StatusCode> {
+    let internal_error = |e: sqlx::Error| {
+        tracing::error!("Failed to check access: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    let allowed = match requirement {
+        Requirement::Public | Requirement::SignedIn => true,
+        Requirement::Admin => user.is_admin,
+        Requirement::Role(scope, role) => {
+            match resolve_project(app_state, scope, query)
+                .await
+                .map_err(internal_error)?
+            {
+                None => return Err(StatusCode::NOT_FOUND),
+                Some(Some(project_id)) => {
+                    let pool = &app_state.db_pool;
+                    let granted = accounts::project_role(pool, user, project_id)
+                        .await
+                        .map_err(internal_error)?
+                        .is_some_and(|granted| granted >= role);
+                    // Setting up two-factor authentication is under /auth, which is public
+                    granted
+                        && !two_factor::enrollment_required_for(pool, user, project_id)
+                            .await
+                            .map_err(internal_error)?
+                }
+                // Global templates are anyone's to read
+                Some(None) if matches!(scope, Scope::Template(_)) => {
+                    user.is_admin || role == ProjectRole::Viewer
+                }
+                // A feed across every project, so across any that require two-factor
+                // authentication too
+                Some(None) => {
+                    user.is_admin
+                        && !two_factor::enrollment_required(&app_state.db_pool, user)
+                            .await
+                            .map_err(internal_error)?
+                }
+            }
+        }
+    };
+    if allowed {
+        Ok(())
+    } else {
+        Err(StatusCode::FORBIDDEN)
+    }
+}
+
+/// Turn requests away that the signed-in user, or the lack of one, may not make. Passes the
+/// user on to handlers as an extension. Does nothing while sign-in is off.
+pub async fn require_access_middleware(
+    State(app_state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let auth = app_state.get_config().read().await.auth.clone();
+    if !auth.enabled {
+        return Ok(next.run(request).await);
+    }
+    let requirement = requirement(request.method(), request.uri().path());
+    if requirement == Requirement::Public {
+        return Ok(next.run(request).await);
+    }
+
+    let pool = &app_state.db_pool;
+    let internal_error = |e: sqlx::Error| {
+        tracing::error!("Failed to check access: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    let (user, api_token) = match accounts::session_token(request.headers()) {
+        Some(token) if accounts::is_api_token(&token) => {
+            let (api_token, user) = accounts::api_token_user(pool, &token)
+                .await
+                .map_err(internal_error)?
+                .ok_or(StatusCode::UNAUTHORIZED)?;
+            (user, Some(api_token))
+        }
+        _ => {
+            let user = accounts::current_user(pool, request.headers())
+                .await
+                .map_err(internal_error)?
+                .ok_or(StatusCode::UNAUTHORIZED)?;
+            (user, None)
+        }
+    };
+
+    let checked = match &api_token {
+        Some(api_token)
+            if !token_scopes(request.method(), request.uri().path())
+                .is_some_and(|scopes| api_token.allows(scopes)) =>
+        {
+            Err(StatusCode::FORBIDDEN)
+        }
+        _ => check(&app_state, &user, requirement, request.uri().query()).await,
+    };
+
+    let method = request.method().to_string();
+    let path = match request.extensions().get::<OriginalUri>() {
+        Some(OriginalUri(uri)) => uri.path().to_string(),
+        None => request.uri().path().to_string(),
+    };
+    let result = match checked {
+        Ok(()) => {
+            request.extensions_mut().insert(user);
+            Ok(next.run(request).await)
+        }
+        Err(status) => Err(status),
+    };
+
+    if let Some(api_token) = api_token {
+        let status = match &result {
+            Ok(response) => response.status(),
+            Err(status) => *status,
+        };
+        if let Err(e) =
+            ApiToken::record_use(pool, api_token.id, &method, &path, status.as_u16()).await
+        {
+            tracing::error!("Failed to record use of API token {}: {}", api_token.id, e);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROJECT: &str = "00000000-0000-0000-0000-000000000001";
+
+    fn project(role: ProjectRole) -> Requirement {
+        Requirement::Role(Scope::Project(Uuid::parse_str(PROJECT).unwrap()), role)
+    }
+
+    #[test]
+    fn test_viewers_read_but_members_change_tasks_and_run_agents() {
+        let tasks = format!("/projects/{}/tasks", PROJECT);
+        assert_eq!(
+            requirement(&Method::GET, &tasks),
+            project(ProjectRole::Viewer)
+        );
+        assert_eq!(
+            requirement(&Method::POST, &format!("{}/create-and-start", tasks)),
+            project(ProjectRole::Member)
+        );
+        let task = format!("{}/{}", tasks, Uuid::new_v4());
+        assert_eq!(
+            requirement(&Method::DELETE, &task),
+            project(ProjectRole::Member)
+        );
+        let attempt = format!("{}/attempts/{}", task, Uuid::new_v4());
+        assert_eq!(
+            requirement(&Method::GET, &format!("{}/logs", attempt)),
+            project(ProjectRole::Viewer)
+        );
+        assert_eq!(
+            requirement(&Method::GET, &format!("{}/terminal", attempt)),
+            project(ProjectRole::Member)
+        );
+    }
+
+    #[test]
+    fn test_project_settings_are_for_project_admins() {
+        let path = format!("/api/projects/{}", PROJECT);
+        assert_eq!(
+            requirement(&Method::GET, &path),
+            project(ProjectRole::Viewer)
+        );
+        assert_eq!(
+            requirement(&Method::DELETE, &path),
+            project(ProjectRole::Admin)
+        );
+        assert_eq!(
+            requirement(
+                &Method::PUT,
+                &format!("{}/members/{}", path, Uuid::new_v4())
+            ),
+            project(ProjectRole::Admin)
+        );
+        assert_eq!(
+            requirement(&Method::GET, &format!("{}/members", path)),
+            project(ProjectRole::Viewer)
+        );
+        // Unlike members, pending invites aren't for viewers to read
+        assert_eq!(
+            requirement(&Method::GET, &format!("{}/invites", path)),
+            project(ProjectRole::Admin)
+        );
+        assert_eq!(
+            requirement(&Method::GET, "/api/auth/invites/vki_abc"),
+            Requirement::Public
+        );
+        // Hooks run with the server's access to the project, whoever triggers them
+        assert_eq!(
+            requirement(&Method::PUT, &format!("{}/script-hooks", path)),
+            project(ProjectRole::Admin)
+        );
+        let review = format!("{}/tasks/{}/attempts/{}/review", path, PROJECT, PROJECT);
+        assert_eq!(
+            requirement(&Method::POST, &format!("{}/approve", review)),
+            project(ProjectRole::Admin)
+        );
+        assert_eq!(
+            requirement(&Method::GET, &format!("{}s", review)),
+            project(ProjectRole::Viewer)
+        );
+    }
+
+    #[test]
+    fn test_requests_outside_projects() {
+        assert_eq!(
+            requirement(&Method::POST, "/auth/login"),
+            Requirement::Public
+        );
+        assert_eq!(
+            requirement(&Method::POST, "/webhooks/github"),
+            Requirement::Public
+        );
+        assert_eq!(
+            requirement(&Method::GET, "/api/shared/vks_0123456789abcdef"),
+            Requirement::Public
+        );
+        assert_eq!(
+            requirement(&Method::DELETE, "/shared/vks_0123456789abcdef"),
+            Requirement::Admin
+        );
+        assert_eq!(
+            requirement(&Method::POST, "/auth/github/device/start"),
+            Requirement::Admin
+        );
+        assert_eq!(
+            requirement(&Method::GET, "/projects"),
+            Requirement::SignedIn
+        );
+        assert_eq!(requirement(&Method::POST, "/projects"), Requirement::Admin);
+        assert_eq!(requirement(&Method::GET, "/config"), Requirement::SignedIn);
+        assert_eq!(requirement(&Method::POST, "/config"), Requirement::Admin);
+        assert_eq!(
+            requirement(&Method::POST, "/mentions/read"),
+            Requirement::SignedIn
+        );
+        assert_eq!(
+            requirement(&Method::GET, "/filesystem/list"),
+            Requirement::Admin
+        );
+        assert_eq!(
+            requirement(&Method::GET, "/search"),
+            Requirement::Role(Scope::ProjectQuery, ProjectRole::Viewer)
+        );
+        assert_eq!(
+            requirement(&Method::GET, "/execution-processes/not-a-uuid"),
+            Requirement::Admin
+        );
+    }
+
+    #[test]
+    fn test_tokens_are_held_to_their_scopes() {
+        let tasks = format!("/projects/{}/tasks", PROJECT);
+        assert_eq!(
+            token_scopes(&Method::POST, &tasks),
+            Some(&[TokenScope::CreateTask][..])
+        );
+        assert_eq!(
+            token_scopes(&Method::POST, &format!("{}/create-and-start", tasks)),
+            Some(&[TokenScope::CreateTask, TokenScope::TriggerExecution][..])
+        );
+        let attempts = format!("{}/{}/attempts", tasks, Uuid::new_v4());
+        assert_eq!(
+            token_scopes(&Method::POST, &attempts),
+            Some(&[TokenScope::TriggerExecution][..])
+        );
+        let attempt = format!("{}/{}", attempts, Uuid::new_v4());
+        assert_eq!(
+            token_scopes(&Method::GET, &format!("{}/logs", attempt)),
+            Some(&[TokenScope::ReadLogs][..])
+        );
+        assert_eq!(token_scopes(&Method::GET, "/api/projects"), Some(&[][..]));
+        assert_eq!(
+            token_scopes(&Method::POST, "/mcp/message"),
+            Some(&[TokenScope::CreateTask][..])
+        );
+
+        // Tokens can't change what they aren't for, or manage tokens
+        assert_eq!(token_scopes(&Method::DELETE, &tasks), None);
+        assert_eq!(
+            token_scopes(&Method::GET, &format!("{}/terminal", attempt)),
+            None
+        );
+        assert_eq!(token_scopes(&Method::POST, "/tokens"), None);
+        assert_eq!(token_scopes(&Method::GET, "/config"), None);
+    }
+
+    #[test]
+    fn test_feeds_are_narrowed_by_their_project_id() {
+        let id = Uuid::new_v4();
+        assert_eq!(
+            query_project_id(Some(&format!("limit=5&project_id={}", id))),
+            Some(id)
+        );
+        assert_eq!(query_project_id(Some("limit=5")), None);
+        assert_eq!(query_project_id(None), None);
+    }
+}
@@ -1,3 +1,4 @@
+pub mod access;
 pub mod model_loaders;
 
 pub use model_loaders::*;
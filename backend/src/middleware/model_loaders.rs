@@ -9,8 +9,9 @@ use uuid::Uuid;
 use crate::{
     app_state::AppState,
     models::{
-        execution_process::ExecutionProcess, project::Project, task::Task,
-        task_attempt::TaskAttempt, task_template::TaskTemplate,
+        benchmark::BenchmarkSuite, execution_process::ExecutionProcess, project::Project,
+        release::Release, task::Task, task_attachment::TaskAttachment, task_attempt::TaskAttempt,
+        task_template::TaskTemplate,
     },
 };
 
@@ -90,6 +91,142 @@ pub async fn load_task_middleware(
     Ok(next.run(request).await)
 }
 
+/// Middleware that loads and injects Project, Task and TaskAttachment based on project_id, task_id and attachment_id path parameters
+pub async fn load_task_attachment_middleware(
+    State(app_state): State<AppState>,
+    Path((project_id, task_id, attachment_id)): Path<(Uuid, Uuid, Uuid)>,
+    request: axum::extract::Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let project = match Project::find_by_id(&app_state.db_pool, project_id).await {
+        Ok(Some(project)) => project,
+        Ok(None) => {
+            tracing::warn!("Project {} not found", project_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch project {}: {}", project_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let task = match Task::find_by_id_and_project_id(&app_state.db_pool, task_id, project_id).await
+    {
+        Ok(Some(task)) => task,
+        Ok(None) => {
+            tracing::warn!("Task {} not found in project {}", task_id, project_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch task {}: {}", task_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let attachment =
+        match TaskAttachment::find_by_id_and_task_id(&app_state.db_pool, attachment_id, task_id)
+            .await
+        {
+            Ok(Some(attachment)) => attachment,
+            Ok(None) => {
+                tracing::warn!("Attachment {} not found on task {}", attachment_id, task_id);
+                return Err(StatusCode::NOT_FOUND);
+            }
+            Err(e) => {
+                tracing::error!("Failed to fetch attachment {}: {}", attachment_id, e);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        };
+
+    let mut request = request;
+    request.extensions_mut().insert(project);
+    request.extensions_mut().insert(task);
+    request.extensions_mut().insert(attachment);
+
+    Ok(next.run(request).await)
+}
+
+/// Middleware that loads and injects Project and Release based on project_id and release_id path parameters
+pub async fn load_release_middleware(
+    State(app_state): State<AppState>,
+    Path((project_id, release_id)): Path<(Uuid, Uuid)>,
+    request: axum::extract::Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let project = match Project::find_by_id(&app_state.db_pool, project_id).await {
+        Ok(Some(project)) => project,
+        Ok(None) => {
+            tracing::warn!("Project {} not found", project_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch project {}: {}", project_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    // Load the release and validate it belongs to the project
+    let release = match Release::find_by_id(&app_state.db_pool, release_id).await {
+        Ok(Some(release)) if release.project_id == project_id => release,
+        Ok(_) => {
+            tracing::warn!("Release {} not found in project {}", release_id, project_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch release {}: {}", release_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let mut request = request;
+    request.extensions_mut().insert(project);
+    request.extensions_mut().insert(release);
+
+    Ok(next.run(request).await)
+}
+
+/// Middleware that loads and injects Project and BenchmarkSuite based on project_id and suite_id path parameters
+pub async fn load_benchmark_suite_middleware(
+    State(app_state): State<AppState>,
+    Path((project_id, suite_id)): Path<(Uuid, Uuid)>,
+    request: axum::extract::Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let project = match Project::find_by_id(&app_state.db_pool, project_id).await {
+        Ok(Some(project)) => project,
+        Ok(None) => {
+            tracing::warn!("Project {} not found", project_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch project {}: {}", project_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let suite = match BenchmarkSuite::find_by_id(&app_state.db_pool, suite_id).await {
+        Ok(Some(suite)) if suite.project_id == project_id => suite,
+        Ok(_) => {
+            tracing::warn!(
+                "Benchmark suite {} not found in project {}",
+                suite_id,
+                project_id
+            );
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch benchmark suite {}: {}", suite_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let mut request = request;
+    request.extensions_mut().insert(project);
+    request.extensions_mut().insert(suite);
+
+    Ok(next.run(request).await)
+}
+
 /// Middleware that loads and injects Project, Task, and TaskAttempt based on project_id, task_id, and attempt_id path parameters
 pub async fn load_task_attempt_middleware(
     State(app_state): State<AppState>,
@@ -0,0 +1,246 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// What a token may be used for, on top of what its user may do
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "kebab-case")]
+#[ts(export)]
+pub enum TokenScope {
+    /// Create tasks on the boards of the user's projects
+    CreateTask,
+    /// Read boards, attempts and their logs
+    ReadLogs,
+    /// Start attempts and send follow-ups to coding agents
+    TriggerExecution,
+}
+
+/// A personal access token. The token itself is shown once, when it's created.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ApiToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    /// The token's first characters, to tell tokens apart
+    pub token_prefix: String,
+    pub scopes: Vec<TokenScope>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct ApiTokenRow {
+    id: Uuid,
+    user_id: Uuid,
+    name: String,
+    token_prefix: String,
+    scopes: String,
+    expires_at: Option<DateTime<Utc>>,
+    last_used_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<ApiTokenRow> for ApiToken {
+    fn from(row: ApiTokenRow) -> Self {
+        Self {
+            id: row.id,
+            user_id: row.user_id,
+            name: row.name,
+            token_prefix: row.token_prefix,
+            scopes: serde_json::from_str(&row.scopes).unwrap_or_default(),
+            expires_at: row.expires_at,
+            last_used_at: row.last_used_at,
+            revoked_at: row.revoked_at,
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateApiToken {
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    /// Days until the token stops working; `None` keeps it working until it's revoked
+    pub expires_in_days: Option<u32>,
+}
+
+/// A token just created, with the secret to send as a bearer token. It can't be shown
+/// again.
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct CreatedApiToken {
+    pub token: ApiToken,
+    pub secret: String,
+}
+
+/// A request made with a token, and the status it was answered with
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct ApiTokenUse {
+    pub id: i64,
+    pub token_id: Uuid,
+    pub method: String,
+    pub path: String,
+    pub status: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ApiToken {
+    pub fn allows(&self, scopes: &[TokenScope]) -> bool {
+        scopes.iter().all(|scope| self.scopes.contains(scope))
+    }
+
+    pub async fn find_all(pool: &DbPool) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = db::query_as!(
+            ApiTokenRow,
+            r#"SELECT id as "id!: Uuid", user_id as "user_id!: Uuid", name, token_prefix, scopes, expires_at as "expires_at: DateTime<Utc>", last_used_at as "last_used_at: DateTime<Utc>", revoked_at as "revoked_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM api_tokens
+               ORDER BY created_at DESC"#
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(ApiToken::from).collect())
+    }
+
+    pub async fn find_by_user_id(pool: &DbPool, user_id: Uuid) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = db::query_as!(
+            ApiTokenRow,
+            r#"SELECT id as "id!: Uuid", user_id as "user_id!: Uuid", name, token_prefix, scopes, expires_at as "expires_at: DateTime<Utc>", last_used_at as "last_used_at: DateTime<Utc>", revoked_at as "revoked_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM api_tokens
+               WHERE user_id = $1
+               ORDER BY created_at DESC"#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(ApiToken::from).collect())
+    }
+
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        let row = db::query_as!(
+            ApiTokenRow,
+            r#"SELECT id as "id!: Uuid", user_id as "user_id!: Uuid", name, token_prefix, scopes, expires_at as "expires_at: DateTime<Utc>", last_used_at as "last_used_at: DateTime<Utc>", revoked_at as "revoked_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM api_tokens
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(ApiToken::from))
+    }
+
+    /// The token with this hash, unless it has been revoked or has expired
+    pub async fn find_active_by_hash(
+        pool: &DbPool,
+        token_hash: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let row = db::query_as!(
+            ApiTokenRow,
+            r#"SELECT id as "id!: Uuid", user_id as "user_id!: Uuid", name, token_prefix, scopes, expires_at as "expires_at: DateTime<Utc>", last_used_at as "last_used_at: DateTime<Utc>", revoked_at as "revoked_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM api_tokens
+               WHERE token_hash = $1
+               AND revoked_at IS NULL
+               AND (expires_at IS NULL OR expires_at > $2)"#,
+            token_hash,
+            now
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(ApiToken::from))
+    }
+
+    pub async fn create(
+        pool: &DbPool,
+        user_id: Uuid,
+        data: &CreateApiToken,
+        token_hash: &str,
+        token_prefix: &str,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let scopes = serde_json::to_string(&data.scopes).unwrap_or_else(|_| "[]".to_string());
+        let row = db::query_as!(
+            ApiTokenRow,
+            r#"INSERT INTO api_tokens (id, user_id, name, token_hash, token_prefix, scopes, expires_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               RETURNING id as "id!: Uuid", user_id as "user_id!: Uuid", name, token_prefix, scopes, expires_at as "expires_at: DateTime<Utc>", last_used_at as "last_used_at: DateTime<Utc>", revoked_at as "revoked_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            user_id,
+            data.name,
+            token_hash,
+            token_prefix,
+            scopes,
+            expires_at
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(row.into())
+    }
+
+    /// Stop the token from working. Its record and uses are kept.
+    pub async fn revoke(pool: &DbPool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = db::query!(
+            "UPDATE api_tokens SET revoked_at = datetime('now', 'subsec') WHERE id = $1 AND revoked_at IS NULL",
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn record_use(
+        pool: &DbPool,
+        id: Uuid,
+        method: &str,
+        path: &str,
+        status: u16,
+    ) -> Result<(), sqlx::Error> {
+        let status = i64::from(status);
+        db::query!(
+            "INSERT INTO api_token_uses (token_id, method, path, status) VALUES ($1, $2, $3, $4)",
+            id,
+            method,
+            path,
+            status
+        )
+        .execute(pool)
+        .await?;
+        db::query!(
+            "UPDATE api_tokens SET last_used_at = datetime('now', 'subsec') WHERE id = $1",
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The token's most recent uses, newest first
+    pub async fn find_uses(
+        pool: &DbPool,
+        id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<ApiTokenUse>, sqlx::Error> {
+        db::query_as!(
+            ApiTokenUse,
+            r#"SELECT id as "id!: i64", token_id as "token_id!: Uuid", method, path, status, created_at as "created_at!: DateTime<Utc>"
+               FROM api_token_uses
+               WHERE token_id = $1
+               ORDER BY id DESC
+               LIMIT $2"#,
+            id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// How many coding agents can work on a task side by side
+pub const MIN_PARALLEL_ATTEMPTS: usize = 2;
+pub const MAX_PARALLEL_ATTEMPTS: usize = 3;
+
+/// Attempts started together on one task, each with its own coding agent and worktree,
+/// until one of them is picked as the task's canonical attempt
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct AttemptGroup {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub attempt_ids: Vec<Uuid>,
+    /// The attempt picked to carry on with; `None` until one is
+    pub winner_attempt_id: Option<Uuid>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(FromRow)]
+struct AttemptGroupRow {
+    id: Uuid,
+    task_id: Uuid,
+    winner_attempt_id: Option<Uuid>,
+    decided_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateParallelAttempts {
+    /// The coding agents to run, one attempt each
+    pub executors: Vec<String>,
+    pub base_branch: Option<String>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct PickAttemptGroupWinner {
+    pub attempt_id: Uuid,
+}
+
+/// Why the executors can't run side by side, if they can't
+pub fn parallel_executors_error(executors: &[String]) -> Option<String> {
+    if !(MIN_PARALLEL_ATTEMPTS..=MAX_PARALLEL_ATTEMPTS).contains(&executors.len()) {
+        return Some(format!(
+            "Pick {} to {} coding agents to run side by side",
+            MIN_PARALLEL_ATTEMPTS, MAX_PARALLEL_ATTEMPTS
+        ));
+    }
+    let distinct: HashSet<&str> = executors.iter().map(String::as_str).collect();
+    if distinct.len() != executors.len() {
+        return Some("Each coding agent can only run once in a comparison".to_string());
+    }
+    None
+}
+
+impl AttemptGroup {
+    async fn with_members(pool: &DbPool, row: AttemptGroupRow) -> Result<Self, sqlx::Error> {
+        let attempt_ids = db::query_scalar!(
+            r#"SELECT m.task_attempt_id as "task_attempt_id!: Uuid"
+               FROM attempt_group_members m
+               JOIN task_attempts ta ON ta.id = m.task_attempt_id
+               WHERE m.group_id = $1
+               ORDER BY ta.created_at ASC"#,
+            row.id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(Self {
+            id: row.id,
+            task_id: row.task_id,
+            attempt_ids,
+            winner_attempt_id: row.winner_attempt_id,
+            decided_at: row.decided_at,
+            created_at: row.created_at,
+        })
+    }
+
+    pub async fn create(
+        pool: &DbPool,
+        task_id: Uuid,
+        attempt_ids: &[Uuid],
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let mut tx = pool.begin().await?;
+        db::query!(
+            "INSERT INTO attempt_groups (id, task_id) VALUES ($1, $2)",
+            id,
+            task_id
+        )
+        .execute(&mut tx)
+        .await?;
+        for attempt_id in attempt_ids {
+            db::query!(
+                "INSERT INTO attempt_group_members (task_attempt_id, group_id) VALUES ($1, $2)",
+                attempt_id,
+                id
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        let row = db::query_as!(
+            AttemptGroupRow,
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", winner_attempt_id as "winner_attempt_id: Uuid", decided_at as "decided_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM attempt_groups
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        match row {
+            Some(row) => Ok(Some(Self::with_members(pool, row).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// The task's groups, newest first
+    pub async fn find_by_task_id(pool: &DbPool, task_id: Uuid) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = db::query_as!(
+            AttemptGroupRow,
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", winner_attempt_id as "winner_attempt_id: Uuid", decided_at as "decided_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM attempt_groups
+               WHERE task_id = $1
+               ORDER BY created_at DESC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await?;
+        let mut groups = Vec::with_capacity(rows.len());
+        for row in rows {
+            groups.push(Self::with_members(pool, row).await?);
+        }
+        Ok(groups)
+    }
+
+    /// The group the attempt was started in, if it was started alongside others
+    pub async fn find_by_attempt_id(
+        pool: &DbPool,
+        attempt_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let group_id = db::query_scalar!(
+            r#"SELECT group_id as "group_id!: Uuid" FROM attempt_group_members WHERE task_attempt_id = $1"#,
+            attempt_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        match group_id {
+            Some(group_id) => Self::find_by_id(pool, group_id).await,
+            None => Ok(None),
+        }
+    }
+
+    pub async fn set_winner(
+        pool: &DbPool,
+        id: Uuid,
+        attempt_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        db::query!(
+            "UPDATE attempt_groups SET winner_attempt_id = $1, decided_at = datetime('now', 'subsec') WHERE id = $2",
+            attempt_id,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    /// The attempts that lost out to the winner
+    pub fn losers(&self) -> impl Iterator<Item = Uuid> + '_ {
+        self.attempt_ids
+            .iter()
+            .copied()
+            .filter(move |id| Some(*id) != self.winner_attempt_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn executors(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parallel_executors_must_be_two_or_three_different_agents() {
+        assert!(parallel_executors_error(&executors(&["claude"])).is_some());
+        assert!(parallel_executors_error(&executors(&["claude", "claude"])).is_some());
+        assert!(
+            parallel_executors_error(&executors(&["claude", "amp", "gemini", "echo"])).is_some()
+        );
+        assert_eq!(
+            parallel_executors_error(&executors(&["claude", "amp"])),
+            None
+        );
+        assert_eq!(
+            parallel_executors_error(&executors(&["claude", "amp", "gemini"])),
+            None
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task_attempt::WorktreeDiff;
+use crate::db::{self, DbPool};
+
+/// The checkout of a linked project an attempt of a multi-repository task works in, on the
+/// same branch as the attempt's own worktree
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct AttemptLinkedWorktree {
+    pub task_attempt_id: Uuid,
+    pub project_id: Uuid,
+    pub worktree_path: String,
+    pub base_branch: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A linked project's changes in an attempt, kept apart from those of the attempt's own
+/// repository
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct LinkedRepoDiff {
+    pub project_id: Uuid,
+    pub project_name: String,
+    pub diff: WorktreeDiff,
+}
+
+impl AttemptLinkedWorktree {
+    pub async fn find_by_task_attempt_id(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            AttemptLinkedWorktree,
+            r#"SELECT task_attempt_id as "task_attempt_id!: Uuid", project_id as "project_id!: Uuid", worktree_path, base_branch, created_at as "created_at!: DateTime<Utc>"
+               FROM attempt_linked_worktrees
+               WHERE task_attempt_id = $1
+               ORDER BY created_at ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+        project_id: Uuid,
+        worktree_path: &str,
+        base_branch: &str,
+    ) -> Result<Self, sqlx::Error> {
+        db::query_as!(
+            AttemptLinkedWorktree,
+            r#"INSERT INTO attempt_linked_worktrees (task_attempt_id, project_id, worktree_path, base_branch)
+               VALUES ($1, $2, $3, $4)
+               RETURNING task_attempt_id as "task_attempt_id!: Uuid", project_id as "project_id!: Uuid", worktree_path, base_branch, created_at as "created_at!: DateTime<Utc>""#,
+            task_attempt_id,
+            project_id,
+            worktree_path,
+            base_branch
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
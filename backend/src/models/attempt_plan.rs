@@ -0,0 +1,113 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum AttemptPlanStatus {
+    Pending,
+    Approved,
+}
+
+/// The plan a planning attempt produced, kept after approval so the implementation can be
+/// compared against it
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AttemptPlan {
+    pub task_attempt_id: Uuid,
+    /// The planning run the plan came from
+    pub execution_process_id: Uuid,
+    pub content: String,
+    pub status: AttemptPlanStatus,
+    pub created_at: DateTime<Utc>,
+    pub approved_at: Option<DateTime<Utc>>,
+}
+
+impl AttemptPlan {
+    pub async fn find_by_task_attempt_id(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            AttemptPlan,
+            r#"SELECT task_attempt_id as "task_attempt_id!: Uuid", execution_process_id as "execution_process_id!: Uuid", content, status as "status!: AttemptPlanStatus", created_at as "created_at!: DateTime<Utc>", approved_at as "approved_at: DateTime<Utc>"
+               FROM attempt_plans
+               WHERE task_attempt_id = $1"#,
+            task_attempt_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn is_approved(pool: &DbPool, task_attempt_id: Uuid) -> Result<bool, sqlx::Error> {
+        Ok(Self::find_by_task_attempt_id(pool, task_attempt_id)
+            .await?
+            .is_some_and(|plan| plan.status == AttemptPlanStatus::Approved))
+    }
+
+    /// Store the latest plan of an attempt for review. A plan that was already approved is
+    /// left alone.
+    pub async fn propose(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+        execution_process_id: Uuid,
+        content: &str,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            r#"INSERT INTO attempt_plans (task_attempt_id, execution_process_id, content)
+               VALUES ($1, $2, $3)
+               ON CONFLICT(task_attempt_id) DO UPDATE SET
+                   execution_process_id = excluded.execution_process_id,
+                   content = excluded.content,
+                   created_at = datetime('now', 'subsec')
+               WHERE attempt_plans.status = 'pending'"#,
+            task_attempt_id,
+            execution_process_id,
+            content
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Drop a pending plan, e.g. when a later planning run ended without one
+    pub async fn discard_pending(pool: &DbPool, task_attempt_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = db::query!(
+            "DELETE FROM attempt_plans WHERE task_attempt_id = $1 AND status = 'pending'",
+            task_attempt_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Returns false if there was no pending plan to approve
+    pub async fn approve(pool: &DbPool, task_attempt_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = db::query!(
+            r#"UPDATE attempt_plans
+               SET status = 'approved', approved_at = datetime('now', 'subsec')
+               WHERE task_attempt_id = $1 AND status = 'pending'"#,
+            task_attempt_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Put an approved plan back up for review, used when its implementation run couldn't start
+    pub async fn reopen(pool: &DbPool, task_attempt_id: Uuid) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE attempt_plans SET status = 'pending', approved_at = NULL WHERE task_attempt_id = $1",
+            task_attempt_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
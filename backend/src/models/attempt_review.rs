@@ -0,0 +1,169 @@
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::{
+    execution_diff::ExecutionDiff,
+    review_comment::{compile_follow_up_prompt, ReviewComment},
+};
+use crate::db::{self, DbPool};
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum ReviewDecision {
+    Approved,
+    Rejected,
+}
+
+/// A reviewer's decision on an attempt's changes
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct AttemptReview {
+    pub id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub decision: ReviewDecision,
+    /// The reviewer's username, when sign-in is on
+    pub reviewer: Option<String>,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct ApproveAttempt {
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct RejectAttempt {
+    /// What's wrong with the changes
+    pub comment: Option<String>,
+    /// Send the comment, and the attempt's open review comments, to the agent as a
+    /// follow-up
+    pub follow_up: bool,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct AttemptRejection {
+    pub review: AttemptReview,
+    /// The attempt the follow-up runs in, when one was started
+    pub follow_up_attempt_id: Option<Uuid>,
+}
+
+impl AttemptReview {
+    pub async fn find_by_task_attempt_id(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            AttemptReview,
+            r#"SELECT id as "id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", decision as "decision!: ReviewDecision", reviewer, comment, created_at as "created_at!: DateTime<Utc>"
+               FROM attempt_reviews
+               WHERE task_attempt_id = $1
+               ORDER BY created_at ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+        decision: ReviewDecision,
+        reviewer: Option<&str>,
+        comment: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        db::query_as!(
+            AttemptReview,
+            r#"INSERT INTO attempt_reviews (id, task_attempt_id, decision, reviewer, comment)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", decision as "decision!: ReviewDecision", reviewer, comment, created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            task_attempt_id,
+            decision,
+            reviewer,
+            comment
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
+
+/// The follow-up prompt for a rejection: the reviewer's comment, then the open review
+/// comments with their code. `None` when there's nothing to send.
+pub fn rejection_prompt(
+    comment: Option<&str>,
+    comments: &[ReviewComment],
+    worktree_path: &Path,
+    run_diffs: &[ExecutionDiff],
+) -> Option<String> {
+    let comment = comment.map(str::trim).filter(|comment| !comment.is_empty());
+    let has_open_comments = comments.iter().any(|c| !c.resolved);
+
+    let mut prompt = match comment {
+        Some(comment) => format!("A reviewer rejected your changes:\n\n{}\n", comment),
+        None if has_open_comments => String::from("A reviewer rejected your changes.\n"),
+        None => return None,
+    };
+    if has_open_comments {
+        prompt.push('\n');
+        prompt.push_str(&compile_follow_up_prompt(
+            comments,
+            worktree_path,
+            run_diffs,
+        ));
+    }
+    Some(prompt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(body: &str, resolved: bool) -> ReviewComment {
+        ReviewComment {
+            id: Uuid::new_v4(),
+            task_attempt_id: Uuid::new_v4(),
+            execution_process_id: None,
+            file_path: "missing.rs".to_string(),
+            line_number: 1,
+            body: body.to_string(),
+            resolved,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_rejection_prompt_leads_with_the_reviewer_comment() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(rejection_prompt(None, &[], dir.path(), &[]), None);
+        assert_eq!(
+            rejection_prompt(Some("  "), &[comment("Done", true)], dir.path(), &[]),
+            None
+        );
+        assert_eq!(
+            rejection_prompt(Some("Tests are missing"), &[], dir.path(), &[]).as_deref(),
+            Some("A reviewer rejected your changes:\n\nTests are missing\n")
+        );
+        assert_eq!(
+            rejection_prompt(None, &[comment("Handle the error", false)], dir.path(), &[])
+                .as_deref(),
+            Some(
+                "A reviewer rejected your changes.\n\
+                 \nPlease address the following review comments:\n\
+                 \n1. missing.rs:1\nHandle the error\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::execution_process::{ExecutionProcessStatus, ExecutionProcessSummary};
+use crate::db::{self, DbPool};
+
+/// An attempt's branch going back to what an earlier run left
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct AttemptRollback {
+    pub id: Uuid,
+    pub task_attempt_id: Uuid,
+    /// The run whose commit the attempt went back to
+    pub execution_process_id: Option<Uuid>,
+    pub from_commit: String,
+    pub to_commit: String,
+    /// The ref keeping `from_commit`, so a later rollback can return to it
+    pub backup_ref: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct RollbackAttempt {
+    /// The run to go back to; the attempt's branch is reset to the commit it left
+    pub execution_process_id: Uuid,
+}
+
+/// The ref a rollback keeps the attempt's previous commit under
+pub fn backup_ref(task_attempt_id: Uuid, rollback_id: Uuid) -> String {
+    format!(
+        "refs/vibe-kanban/rollbacks/{}/{}",
+        task_attempt_id, rollback_id
+    )
+}
+
+/// The commit to go back to for a run of the attempt, or why it can't be gone back to
+pub fn rollback_target(
+    processes: &[ExecutionProcessSummary],
+    execution_process_id: Uuid,
+) -> Result<&str, &'static str> {
+    let process = processes
+        .iter()
+        .find(|process| process.id == execution_process_id)
+        .ok_or("That run isn't part of this attempt")?;
+    if matches!(process.status, ExecutionProcessStatus::Running) {
+        return Err("That run hasn't finished yet");
+    }
+    process
+        .commit_sha
+        .as_deref()
+        .ok_or("That run didn't leave a commit to go back to")
+}
+
+impl AttemptRollback {
+    pub async fn find_by_task_attempt_id(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            AttemptRollback,
+            r#"SELECT id as "id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", execution_process_id as "execution_process_id: Uuid", from_commit, to_commit, backup_ref, created_at as "created_at!: DateTime<Utc>"
+               FROM attempt_rollbacks
+               WHERE task_attempt_id = $1
+               ORDER BY created_at ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &DbPool,
+        id: Uuid,
+        task_attempt_id: Uuid,
+        execution_process_id: Uuid,
+        from_commit: &str,
+        to_commit: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let backup_ref = backup_ref(task_attempt_id, id);
+        db::query_as!(
+            AttemptRollback,
+            r#"INSERT INTO attempt_rollbacks (id, task_attempt_id, execution_process_id, from_commit, to_commit, backup_ref)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", execution_process_id as "execution_process_id: Uuid", from_commit, to_commit, backup_ref, created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            task_attempt_id,
+            execution_process_id,
+            from_commit,
+            to_commit,
+            backup_ref
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
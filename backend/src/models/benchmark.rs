@@ -0,0 +1,583 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::{
+    execution_analytics::median, execution_process::ExecutionProcessStatus,
+    execution_verification::ExecutionVerification,
+};
+use crate::db::{self, DbPool, DbTransaction};
+
+/// Label added to the tasks a benchmark run creates
+pub const BENCHMARK_LABEL: &str = "benchmark";
+
+/// How many executors and profiles one run can compare
+pub const MAX_BENCHMARK_CONTESTANTS: usize = 5;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum BenchmarkResultStatus {
+    Running,
+    Passed,
+    Failed,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct BenchmarkCase {
+    pub id: Uuid,
+    pub suite_id: Uuid,
+    pub position: i64,
+    pub title: String,
+    /// What the coding agent is asked to do
+    pub prompt: String,
+    /// Commands that must all pass after the agent, one per line
+    pub verification_script: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct BenchmarkSuite {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    pub cases: Vec<BenchmarkCase>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(FromRow)]
+struct BenchmarkSuiteRow {
+    id: Uuid,
+    project_id: Uuid,
+    name: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+/// The latest run of a case's coding agent
+#[derive(FromRow)]
+struct LastRun {
+    id: Uuid,
+    status: ExecutionProcessStatus,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct BenchmarkCaseInput {
+    pub title: String,
+    pub prompt: String,
+    pub verification_script: String,
+}
+
+/// A suite's name and its cases, in the order they run
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertBenchmarkSuite {
+    pub name: String,
+    pub cases: Vec<BenchmarkCaseInput>,
+}
+
+/// An executor, or a profile, to run every case of a suite with
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export)]
+pub struct BenchmarkContestant {
+    pub executor: Option<String>,
+    pub executor_profile_id: Option<Uuid>,
+    /// The model to run the executor with, when it's not a profile
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct StartBenchmarkRun {
+    pub contestants: Vec<BenchmarkContestant>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct BenchmarkRun {
+    pub id: Uuid,
+    pub suite_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    /// Set once every result is in
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// How one contestant did on one case
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct BenchmarkResult {
+    pub id: Uuid,
+    pub run_id: Uuid,
+    pub case_title: String,
+    /// The profile's name, or the executor with its model
+    pub contestant: String,
+    pub task_id: Option<Uuid>,
+    pub task_attempt_id: Option<Uuid>,
+    pub status: BenchmarkResultStatus,
+    /// Wall-clock time of the attempt's coding agent runs, follow-up fixes included
+    pub duration_ms: Option<i64>,
+    /// `None` when the agent reported no cost
+    pub cost_usd: Option<f64>,
+    pub created_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// The results of one contestant across a run's cases
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ContestantReport {
+    pub contestant: String,
+    pub cases: i64,
+    pub passed: i64,
+    pub failed: i64,
+    pub running: i64,
+    /// Passed cases out of the finished ones; `None` before any finished
+    pub pass_rate: Option<f64>,
+    pub median_duration_ms: Option<i64>,
+    pub total_duration_ms: i64,
+    pub cost_usd: Option<f64>,
+}
+
+/// A run's results, with the contestants that passed the most first
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct BenchmarkReport {
+    pub run: BenchmarkRun,
+    pub contestants: Vec<ContestantReport>,
+    pub results: Vec<BenchmarkResult>,
+}
+
+impl BenchmarkReport {
+    pub fn new(run: BenchmarkRun, results: Vec<BenchmarkResult>) -> Self {
+        let mut by_contestant: BTreeMap<&str, Vec<&BenchmarkResult>> = BTreeMap::new();
+        for result in &results {
+            by_contestant
+                .entry(&result.contestant)
+                .or_default()
+                .push(result);
+        }
+        let mut contestants: Vec<ContestantReport> = by_contestant
+            .into_iter()
+            .map(|(contestant, results)| {
+                let count = |status: BenchmarkResultStatus| {
+                    results.iter().filter(|r| r.status == status).count() as i64
+                };
+                let (passed, failed) = (
+                    count(BenchmarkResultStatus::Passed),
+                    count(BenchmarkResultStatus::Failed),
+                );
+                let durations: Vec<i64> = results.iter().filter_map(|r| r.duration_ms).collect();
+                ContestantReport {
+                    contestant: contestant.to_string(),
+                    cases: results.len() as i64,
+                    passed,
+                    failed,
+                    running: count(BenchmarkResultStatus::Running),
+                    pass_rate: (passed + failed > 0)
+                        .then(|| passed as f64 / (passed + failed) as f64),
+                    total_duration_ms: durations.iter().sum(),
+                    median_duration_ms: median(durations),
+                    cost_usd: results
+                        .iter()
+                        .filter_map(|r| r.cost_usd)
+                        .reduce(|a, b| a + b),
+                }
+            })
+            .collect();
+        contestants.sort_by(|a, b| {
+            b.passed
+                .cmp(&a.passed)
+                .then(a.total_duration_ms.cmp(&b.total_duration_ms))
+        });
+        Self {
+            run,
+            contestants,
+            results,
+        }
+    }
+}
+
+/// Whether a case passed: its agent's last run completed and every verification command
+/// run after it passed
+pub fn case_passed(
+    last_run: Option<ExecutionProcessStatus>,
+    verifications: &[ExecutionVerification],
+) -> bool {
+    last_run == Some(ExecutionProcessStatus::Completed)
+        && !verifications.is_empty()
+        && verifications.iter().all(|v| v.passed)
+}
+
+impl BenchmarkSuite {
+    async fn with_cases(pool: &DbPool, row: BenchmarkSuiteRow) -> Result<Self, sqlx::Error> {
+        let cases = db::query_as!(
+            BenchmarkCase,
+            r#"SELECT id as "id!: Uuid", suite_id as "suite_id!: Uuid", position, title, prompt, verification_script
+               FROM benchmark_cases
+               WHERE suite_id = $1
+               ORDER BY position"#,
+            row.id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(Self {
+            id: row.id,
+            project_id: row.project_id,
+            name: row.name,
+            cases,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = db::query_as!(
+            BenchmarkSuiteRow,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", name, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM benchmark_suites
+               WHERE project_id = $1
+               ORDER BY name"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        let mut suites = Vec::with_capacity(rows.len());
+        for row in rows {
+            suites.push(Self::with_cases(pool, row).await?);
+        }
+        Ok(suites)
+    }
+
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        let row = db::query_as!(
+            BenchmarkSuiteRow,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", name, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM benchmark_suites
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        match row {
+            Some(row) => Ok(Some(Self::with_cases(pool, row).await?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn insert_cases(
+        tx: &mut DbTransaction,
+        suite_id: Uuid,
+        cases: &[BenchmarkCaseInput],
+    ) -> Result<(), sqlx::Error> {
+        for (position, case) in cases.iter().enumerate() {
+            let id = Uuid::new_v4();
+            let position = position as i64;
+            db::query!(
+                "INSERT INTO benchmark_cases (id, suite_id, position, title, prompt, verification_script) VALUES ($1, $2, $3, $4, $5, $6)",
+                id,
+                suite_id,
+                position,
+                case.title,
+                case.prompt,
+                case.verification_script
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn create(
+        pool: &DbPool,
+        project_id: Uuid,
+        data: &UpsertBenchmarkSuite,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let mut tx = pool.begin().await?;
+        db::query!(
+            "INSERT INTO benchmark_suites (id, project_id, name) VALUES ($1, $2, $3)",
+            id,
+            project_id,
+            data.name
+        )
+        .execute(&mut tx)
+        .await?;
+        Self::insert_cases(&mut tx, id, &data.cases).await?;
+        tx.commit().await?;
+
+        Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    /// Rename the suite and replace its cases. Results of earlier runs are kept.
+    pub async fn update(
+        pool: &DbPool,
+        id: Uuid,
+        data: &UpsertBenchmarkSuite,
+    ) -> Result<Self, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        db::query!(
+            "UPDATE benchmark_suites SET name = $2, updated_at = datetime('now', 'subsec') WHERE id = $1",
+            id,
+            data.name
+        )
+        .execute(&mut tx)
+        .await?;
+        db::query!("DELETE FROM benchmark_cases WHERE suite_id = $1", id)
+            .execute(&mut tx)
+            .await?;
+        Self::insert_cases(&mut tx, id, &data.cases).await?;
+        tx.commit().await?;
+
+        Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    pub async fn delete(pool: &DbPool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = db::query!("DELETE FROM benchmark_suites WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl BenchmarkRun {
+    /// The suite's runs, newest first
+    pub async fn find_by_suite_id(pool: &DbPool, suite_id: Uuid) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            BenchmarkRun,
+            r#"SELECT id as "id!: Uuid", suite_id as "suite_id!: Uuid", created_at as "created_at!: DateTime<Utc>", finished_at as "finished_at: DateTime<Utc>"
+               FROM benchmark_runs
+               WHERE suite_id = $1
+               ORDER BY created_at DESC"#,
+            suite_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(pool: &DbPool, suite_id: Uuid) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        db::query_as!(
+            BenchmarkRun,
+            r#"INSERT INTO benchmark_runs (id, suite_id) VALUES ($1, $2)
+               RETURNING id as "id!: Uuid", suite_id as "suite_id!: Uuid", created_at as "created_at!: DateTime<Utc>", finished_at as "finished_at: DateTime<Utc>""#,
+            id,
+            suite_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Mark the run finished once none of its results is still running
+    pub async fn finish_if_done(pool: &DbPool, id: Uuid) -> Result<(), sqlx::Error> {
+        db::query!(
+            r#"UPDATE benchmark_runs
+               SET finished_at = datetime('now', 'subsec')
+               WHERE id = $1 AND finished_at IS NULL
+                 AND NOT EXISTS (SELECT 1 FROM benchmark_results WHERE run_id = $1 AND status = 'running')"#,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn report(pool: &DbPool, run: BenchmarkRun) -> Result<BenchmarkReport, sqlx::Error> {
+        let results = BenchmarkResult::find_by_run_id(pool, run.id).await?;
+        Ok(BenchmarkReport::new(run, results))
+    }
+}
+
+impl BenchmarkResult {
+    pub async fn find_by_run_id(pool: &DbPool, run_id: Uuid) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            BenchmarkResult,
+            r#"SELECT id as "id!: Uuid", run_id as "run_id!: Uuid", case_title, contestant, task_id as "task_id: Uuid", task_attempt_id as "task_attempt_id: Uuid", status as "status!: BenchmarkResultStatus", duration_ms, cost_usd, created_at as "created_at!: DateTime<Utc>", finished_at as "finished_at: DateTime<Utc>"
+               FROM benchmark_results
+               WHERE run_id = $1
+               ORDER BY created_at ASC"#,
+            run_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// A result for a case that couldn't start is recorded as failed straight away
+    pub async fn create(
+        pool: &DbPool,
+        run_id: Uuid,
+        case_title: &str,
+        contestant: &str,
+        task_id: Uuid,
+        task_attempt_id: Option<Uuid>,
+    ) -> Result<(), sqlx::Error> {
+        let id = Uuid::new_v4();
+        let status = if task_attempt_id.is_some() {
+            BenchmarkResultStatus::Running
+        } else {
+            BenchmarkResultStatus::Failed
+        };
+        db::query!(
+            r#"INSERT INTO benchmark_results (id, run_id, case_title, contestant, task_id, task_attempt_id, status, finished_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, CASE WHEN $7 = 'running' THEN NULL ELSE datetime('now', 'subsec') END)"#,
+            id,
+            run_id,
+            case_title,
+            contestant,
+            task_id,
+            task_attempt_id,
+            status
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record how the attempt did, if a benchmark started it, and finish its run when it was
+    /// the last one still going
+    pub async fn finish_by_attempt(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        let Some(run_id) = db::query_scalar!(
+            r#"SELECT run_id as "run_id!: Uuid" FROM benchmark_results
+               WHERE task_attempt_id = $1 AND status = 'running'"#,
+            task_attempt_id
+        )
+        .fetch_optional(pool)
+        .await?
+        else {
+            return Ok(());
+        };
+
+        let last_run = db::query_as!(
+            LastRun,
+            r#"SELECT id as "id!: Uuid", status as "status!: ExecutionProcessStatus"
+               FROM execution_processes
+               WHERE task_attempt_id = $1 AND process_type = 'codingagent'
+               ORDER BY started_at DESC
+               LIMIT 1"#,
+            task_attempt_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        let verifications = match &last_run {
+            Some(run) => ExecutionVerification::find_by_execution_process_id(pool, run.id).await?,
+            None => Vec::new(),
+        };
+        let status = if case_passed(last_run.map(|run| run.status), &verifications) {
+            BenchmarkResultStatus::Passed
+        } else {
+            BenchmarkResultStatus::Failed
+        };
+
+        db::query!(
+            r#"UPDATE benchmark_results
+               SET status = $2,
+                   duration_ms = (SELECT SUM(duration_ms) FROM execution_processes
+                                   WHERE task_attempt_id = $1 AND process_type = 'codingagent'),
+                   cost_usd = (SELECT SUM(cost_usd) FROM execution_metrics WHERE task_attempt_id = $1),
+                   finished_at = datetime('now', 'subsec')
+               WHERE task_attempt_id = $1 AND status = 'running'"#,
+            task_attempt_id,
+            status
+        )
+        .execute(pool)
+        .await?;
+
+        BenchmarkRun::finish_if_done(pool, run_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(contestant: &str, status: BenchmarkResultStatus, ms: i64) -> BenchmarkResult {
+        BenchmarkResult {
+            id: Uuid::new_v4(),
+            run_id: Uuid::nil(),
+            case_title: "Add a flag".to_string(),
+            contestant: contestant.to_string(),
+            task_id: None,
+            task_attempt_id: None,
+            status,
+            duration_ms: (status != BenchmarkResultStatus::Running).then_some(ms),
+            cost_usd: Some(0.25),
+            created_at: Utc::now(),
+            finished_at: None,
+        }
+    }
+
+    #[test]
+    fn test_report_ranks_contestants_by_cases_passed() {
+        let run = BenchmarkRun {
+            id: Uuid::nil(),
+            suite_id: Uuid::nil(),
+            created_at: Utc::now(),
+            finished_at: None,
+        };
+        let report = BenchmarkReport::new(
+            run,
+            vec![
+                result("amp", BenchmarkResultStatus::Failed, 1_000),
+                result("amp", BenchmarkResultStatus::Passed, 3_000),
+                result("claude", BenchmarkResultStatus::Passed, 2_000),
+                result("claude", BenchmarkResultStatus::Passed, 4_000),
+                result("claude", BenchmarkResultStatus::Running, 0),
+            ],
+        );
+
+        let claude = &report.contestants[0];
+        assert_eq!(claude.contestant, "claude");
+        assert_eq!((claude.passed, claude.failed, claude.running), (2, 0, 1));
+        assert_eq!(claude.pass_rate, Some(1.0));
+        assert_eq!(claude.total_duration_ms, 6_000);
+        assert_eq!(claude.cost_usd, Some(0.75));
+
+        let amp = &report.contestants[1];
+        assert_eq!(amp.pass_rate, Some(0.5));
+        assert_eq!(amp.median_duration_ms, Some(2_000));
+    }
+
+    #[test]
+    fn test_case_needs_completed_run_and_passing_verification() {
+        let verification = |passed: bool| ExecutionVerification {
+            id: Uuid::new_v4(),
+            execution_process_id: Uuid::nil(),
+            command: "cargo test".to_string(),
+            passed,
+            exit_code: Some(if passed { 0 } else { 101 }),
+            output: String::new(),
+            duration_ms: 10,
+            created_at: Utc::now(),
+        };
+        let completed = || Some(ExecutionProcessStatus::Completed);
+
+        assert!(case_passed(completed(), &[verification(true)]));
+        assert!(!case_passed(
+            completed(),
+            &[verification(true), verification(false)]
+        ));
+        // A run that never got verified didn't pass
+        assert!(!case_passed(completed(), &[]));
+        assert!(!case_passed(
+            Some(ExecutionProcessStatus::Failed),
+            &[verification(true)]
+        ));
+    }
+}
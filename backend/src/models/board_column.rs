@@ -0,0 +1,318 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::{Task, TaskStatus, TaskWithAttemptStatus};
+use crate::db::{self, DbPool};
+
+/// A workflow state that places a task in a column whatever its status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum BoardCondition {
+    /// A coding agent or one of the project's scripts is running for the task
+    ExecutionRunning,
+    /// The task's latest run failed, was stopped or timed out
+    ExecutionFailed,
+    /// A verification command failed after the task's latest coding agent run
+    VerificationFailed,
+    /// The task's latest pull request is open
+    PrOpen,
+    /// One of the task's attempts was merged
+    Merged,
+}
+
+impl BoardCondition {
+    pub fn matches(&self, task: &TaskWithAttemptStatus) -> bool {
+        match self {
+            BoardCondition::ExecutionRunning => task.has_in_progress_attempt,
+            BoardCondition::ExecutionFailed => task.last_attempt_failed,
+            BoardCondition::VerificationFailed => task.verification_failed,
+            BoardCondition::PrOpen => task.pr_status.as_deref() == Some("open"),
+            BoardCondition::Merged => task.has_merged_attempt,
+        }
+    }
+}
+
+/// A column of a project's own board
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct BoardColumn {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    /// Columns are shown from the lowest position up
+    pub position: i64,
+    /// The status a task moved into the column gets
+    pub status: TaskStatus,
+    /// How many tasks the column holds before moving more in is refused
+    pub wip_limit: Option<i64>,
+    /// Tasks in any of these states show in the column, checked in column order
+    pub conditions: Vec<BoardCondition>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct BoardColumnInput {
+    /// Unset for a new column
+    pub id: Option<Uuid>,
+    pub name: String,
+    pub status: TaskStatus,
+    pub wip_limit: Option<i64>,
+    #[serde(default)]
+    pub conditions: Vec<BoardCondition>,
+}
+
+/// The project's columns in board order. An empty list goes back to a column per status.
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpdateBoardColumns {
+    pub columns: Vec<BoardColumnInput>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct MoveTaskToColumn {
+    pub column_id: Uuid,
+}
+
+#[derive(FromRow)]
+struct BoardColumnRow {
+    id: Uuid,
+    project_id: Uuid,
+    name: String,
+    position: i64,
+    status: TaskStatus,
+    wip_limit: Option<i64>,
+    conditions: String,
+    created_at: DateTime<Utc>,
+}
+
+impl From<BoardColumnRow> for BoardColumn {
+    fn from(row: BoardColumnRow) -> Self {
+        Self {
+            id: row.id,
+            project_id: row.project_id,
+            name: row.name,
+            position: row.position,
+            status: row.status,
+            wip_limit: row.wip_limit,
+            conditions: serde_json::from_str(&row.conditions).unwrap_or_default(),
+            created_at: row.created_at,
+        }
+    }
+}
+
+/// The column a task shows in: the one it was moved to by hand, else the first whose
+/// conditions it meets, else the first for its status. A status no column is for falls
+/// into the first column.
+pub fn place(columns: &[BoardColumn], task: &TaskWithAttemptStatus) -> Option<Uuid> {
+    let pinned = task
+        .board_column_id
+        .filter(|id| columns.iter().any(|column| column.id == *id));
+    pinned
+        .or_else(|| {
+            columns
+                .iter()
+                .find(|column| column.conditions.iter().any(|c| c.matches(task)))
+                .map(|column| column.id)
+        })
+        .or_else(|| {
+            columns
+                .iter()
+                .find(|column| column.status == task.status)
+                .map(|column| column.id)
+        })
+        .or_else(|| columns.first().map(|column| column.id))
+}
+
+impl BoardColumn {
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = db::query_as!(
+            BoardColumnRow,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", name, position, status as "status!: TaskStatus", wip_limit, conditions, created_at as "created_at!: DateTime<Utc>"
+               FROM project_board_columns
+               WHERE project_id = $1
+               ORDER BY position"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(Self::from).collect())
+    }
+
+    /// Make `columns` the project's board, in that order. Tasks moved by hand into a column
+    /// whose status changed take the new status and stay in it; those in a removed column
+    /// are placed by their status and conditions again.
+    pub async fn replace(
+        pool: &DbPool,
+        project_id: Uuid,
+        columns: &[BoardColumnInput],
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let existing = Self::find_by_project_id(pool, project_id).await?;
+        let mut kept = Vec::new();
+        let mut restatused = Vec::new();
+
+        let mut tx = pool.begin().await?;
+        for (position, column) in columns.iter().enumerate() {
+            let position = position as i64;
+            let conditions =
+                serde_json::to_string(&column.conditions).unwrap_or_else(|_| "[]".to_string());
+            let previous = column
+                .id
+                .and_then(|id| existing.iter().find(|existing| existing.id == id));
+            match previous {
+                Some(previous) => {
+                    db::query!(
+                        "UPDATE project_board_columns SET name = $3, position = $4, status = $5, wip_limit = $6, conditions = $7 WHERE id = $1 AND project_id = $2",
+                        previous.id,
+                        project_id,
+                        column.name,
+                        position,
+                        column.status,
+                        column.wip_limit,
+                        conditions
+                    )
+                    .execute(&mut tx)
+                    .await?;
+                    if previous.status != column.status {
+                        restatused.push((previous.id, column.status.clone()));
+                    }
+                    kept.push(previous.id);
+                }
+                None => {
+                    let id = Uuid::new_v4();
+                    db::query!(
+                        "INSERT INTO project_board_columns (id, project_id, name, position, status, wip_limit, conditions) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+                        id,
+                        project_id,
+                        column.name,
+                        position,
+                        column.status,
+                        column.wip_limit,
+                        conditions
+                    )
+                    .execute(&mut tx)
+                    .await?;
+                    kept.push(id);
+                }
+            }
+        }
+        for removed in existing.iter().filter(|column| !kept.contains(&column.id)) {
+            db::query!(
+                "UPDATE tasks SET board_column_id = NULL WHERE board_column_id = $1",
+                removed.id
+            )
+            .execute(&mut tx)
+            .await?;
+            db::query!(
+                "DELETE FROM project_board_columns WHERE id = $1",
+                removed.id
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        for (column_id, status) in restatused {
+            let task_ids = db::query_scalar!(
+                r#"SELECT id as "id!: Uuid" FROM tasks WHERE board_column_id = $1"#,
+                column_id
+            )
+            .fetch_all(pool)
+            .await?;
+            for task_id in task_ids {
+                // Changing the status unpins the task, so it's moved back in after
+                Task::update_status(pool, task_id, project_id, status.clone()).await?;
+                Task::set_board_column(pool, task_id, project_id, Some(column_id)).await?;
+            }
+        }
+
+        Self::find_by_project_id(pool, project_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn column(name: &str, status: TaskStatus, conditions: &[BoardCondition]) -> BoardColumn {
+        BoardColumn {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            name: name.to_string(),
+            position: 0,
+            status,
+            wip_limit: None,
+            conditions: conditions.to_vec(),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn task(status: TaskStatus) -> TaskWithAttemptStatus {
+        TaskWithAttemptStatus {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            title: "Fix login".to_string(),
+            description: None,
+            status,
+            parent_task_attempt: None,
+            parent_task_id: None,
+            timeout_minutes: None,
+            priority: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            labels: Vec::new(),
+            custom_fields: HashMap::new(),
+            board_column_id: None,
+            has_in_progress_attempt: false,
+            has_merged_attempt: false,
+            last_attempt_failed: false,
+            verification_failed: false,
+            latest_attempt_executor: None,
+            stale_since: None,
+            queue_position: None,
+            pr_url: None,
+            pr_number: None,
+            pr_status: None,
+            latest_summary: None,
+        }
+    }
+
+    #[test]
+    fn test_place_by_pin_then_conditions_then_status() {
+        let columns = vec![
+            column("Backlog", TaskStatus::Todo, &[]),
+            column(
+                "Needs Fix",
+                TaskStatus::Todo,
+                &[BoardCondition::VerificationFailed],
+            ),
+            column("Review", TaskStatus::InReview, &[]),
+        ];
+
+        let mut reviewed = task(TaskStatus::InReview);
+        assert_eq!(place(&columns, &reviewed), Some(columns[2].id));
+
+        reviewed.verification_failed = true;
+        assert_eq!(place(&columns, &reviewed), Some(columns[1].id));
+
+        reviewed.board_column_id = Some(columns[2].id);
+        assert_eq!(place(&columns, &reviewed), Some(columns[2].id));
+
+        // A pin to a column that's gone is ignored, and statuses without a column fall
+        // into the first one
+        let mut done = task(TaskStatus::Done);
+        done.board_column_id = Some(Uuid::new_v4());
+        assert_eq!(place(&columns, &done), Some(columns[0].id));
+        assert_eq!(place(&[], &done), None);
+    }
+}
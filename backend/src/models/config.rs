@@ -1,9 +1,21 @@
-use std::{path::PathBuf, str::FromStr};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
-use crate::executor::ExecutorConfig;
+use crate::{
+    command_runner::ResourceLimits,
+    executor::ExecutorConfig,
+    executors::{
+        acp::{self, AcpAgentDefinition},
+        jsonl::JsonlExecutorDefinition,
+    },
+    utils::shell,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
 #[ts(export)]
@@ -65,11 +77,50 @@ pub struct Config {
     pub sound_alerts: bool,
     pub sound_file: SoundFile,
     pub push_notifications: bool,
+    pub notification_events: NotificationEvents,
+    pub attention: AttentionConfig,
     pub editor: EditorConfig,
     pub github: GitHubConfig,
     pub analytics_enabled: Option<bool>,
     pub environment: EnvironmentInfo,
     pub workspace_dir: Option<String>,
+    pub webhooks: WebhookConfig,
+    pub notion: NotionConfig,
+    pub jira: JiraConfig,
+    pub linear: LinearConfig,
+    pub embeddings: EmbeddingsConfig,
+    pub llm: LlmConfig,
+    /// Propose labels for new tasks from their wording and the paths they mention
+    pub suggest_labels: bool,
+    pub pr_description: PrDescriptionConfig,
+    pub stale_tasks: StaleTasksConfig,
+    pub run_estimate: RunEstimateConfig,
+    /// Agents run through the generic JSON-lines executor, selectable as `jsonl:<name>`
+    pub jsonl_executors: Vec<JsonlExecutorDefinition>,
+    /// Agents speaking the Agent Client Protocol, selectable as `acp:<name>`
+    pub acp_agents: Vec<AcpAgentDefinition>,
+    /// The model each executor runs with when an attempt doesn't pick one, keyed by the
+    /// executor as in `claude` or `codex`. Executors left out use their CLI's default.
+    pub executor_models: HashMap<String, String>,
+    /// Minutes a coding agent or script may run before it's killed, unless its task sets
+    /// its own limit. `None` lets executions run indefinitely.
+    pub execution_timeout_minutes: Option<u32>,
+    pub executor_retry: RetryPolicy,
+    pub execution_limits: ExecutionLimits,
+    /// Resume coding agents cut off by the server stopping as follow-ups when it starts again,
+    /// rather than leaving their tasks for review
+    pub resume_interrupted_runs: bool,
+    pub log_redaction: LogRedactionConfig,
+    pub log_storage: LogStorageConfig,
+    pub auth: AuthConfig,
+    pub post_execution_git: PostExecutionGitConfig,
+    pub verification: VerificationConfig,
+    pub review_gate: ReviewGateConfig,
+    pub sandbox: SandboxConfig,
+    pub resource_limits: ResourceLimitsConfig,
+    pub context_budget: ContextBudgetConfig,
+    pub shell_environment: ShellEnvironmentConfig,
+    pub database: DatabaseConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -104,6 +155,422 @@ pub struct GitHubConfig {
     pub enterprise_url: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WebhookConfig {
+    /// Shared secrets, newest first. The first one signs outgoing payloads.
+    pub secrets: Vec<String>,
+    pub timestamp_tolerance_secs: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct NotionConfig {
+    /// Internal integration secret; databases must be shared with the integration
+    pub token: Option<String>,
+}
+
+/// Credentials for importing issues from Jira Cloud
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct JiraConfig {
+    /// Site URL such as https://acme.atlassian.net
+    pub base_url: Option<String>,
+    /// Email of the account the API token belongs to
+    pub email: Option<String>,
+    /// API token from id.atlassian.com/manage-profile/security/api-tokens
+    pub api_token: Option<String>,
+}
+
+/// Credentials for importing issues from Linear
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LinearConfig {
+    /// Personal API key from Linear's security settings
+    pub api_key: Option<String>,
+}
+
+/// OpenAI-compatible embeddings provider used to find similar tasks
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct EmbeddingsConfig {
+    /// API base such as https://api.openai.com/v1 or http://localhost:11434/v1 for Ollama;
+    /// embeddings are off when unset
+    pub base_url: Option<String>,
+    pub model: String,
+    pub api_key: Option<String>,
+}
+
+/// OpenAI-compatible chat model for small text jobs like tidying up pasted task descriptions
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LlmConfig {
+    /// API base of a `/chat/completions` endpoint; these features are off when unset
+    pub base_url: Option<String>,
+    pub model: String,
+    pub api_key: Option<String>,
+}
+
+/// When tasks sitting in In Progress or In Review without activity get flagged
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct StaleTasksConfig {
+    pub enabled: bool,
+    pub in_progress_hours: u32,
+    pub in_review_hours: u32,
+    /// Archive stale tasks that stay idle this many days, removing their worktrees unless
+    /// they have uncommitted changes; `None` leaves them on the board
+    pub archive_after_days: Option<u32>,
+}
+
+/// The optional estimate shown before an attempt starts, and when it needs a confirmation
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct RunEstimateConfig {
+    pub enabled: bool,
+    pub confirm_above_tokens: u32,
+    pub confirm_above_files: u32,
+}
+
+/// How often a coding agent that fails to start, or exits with an error right away, is
+/// started again before the attempt fails
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct RetryPolicy {
+    /// Starts in total, counting the first; `1` turns retries off
+    pub max_attempts: u32,
+    /// Wait before the first retry, doubled for each one after it
+    pub backoff_seconds: u32,
+}
+
+/// How many attempts may run at once. Attempts started beyond a limit are queued until one
+/// of the running attempts finishes; `None` leaves that limit off.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ExecutionLimits {
+    pub max_running: Option<u32>,
+    /// Applies to each project on its own
+    pub max_running_per_project: Option<u32>,
+    pub preemption: PreemptionPolicy,
+}
+
+/// What a queued attempt does about lower priority runs holding the slots it waits for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum PreemptionPolicy {
+    /// Wait for a slot to free up
+    #[default]
+    Never,
+    /// Pause the lowest priority coding agent in its way and queue that attempt again, to
+    /// be resumed once there's a slot for it
+    PauseLowerPriority,
+}
+
+/// What is redacted from execution output before it's stored: credentials in the shape of
+/// known API keys, tokens and AWS keys, on top of the execution's own secrets
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LogRedactionConfig {
+    pub enabled: bool,
+    /// Also redact long tokens that look random, which no pattern knows the shape of
+    pub detect_high_entropy: bool,
+    /// Regexes redacted on top of the built-in ones. Of a pattern with a group named
+    /// `secret`, only that group is replaced.
+    pub extra_patterns: Vec<String>,
+}
+
+/// How much of each execution's output is kept in the database. Past the limit, a stream
+/// keeps its beginning and its latest output, and what was between is dropped.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LogStorageConfig {
+    /// Limit on each of an execution's stdout and stderr; `None` keeps all of it
+    pub max_stream_kb: Option<u32>,
+    /// How much of the limit goes to the beginning of the output
+    pub head_kb: u32,
+    /// Also write every execution's full output to files in the app's data directory
+    pub offload_full_logs: bool,
+}
+
+/// Sign-in for a server shared by a team. Off, everyone who can reach the server can do
+/// everything, as on a single-user machine.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AuthConfig {
+    pub enabled: bool,
+    /// How long a sign-in lasts
+    pub session_hours: u32,
+    /// How long refreshing can keep a session going, counted from sign-in. Past it, the
+    /// user signs in again.
+    pub max_session_days: u32,
+    pub oidc: OidcConfig,
+}
+
+/// An OpenID Connect provider users can sign in with, next to local passwords. Accounts are
+/// created on first sign-in, without access to any project until an admin grants it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct OidcConfig {
+    /// Such as https://accounts.google.com; OIDC sign-in is off when unset
+    pub issuer_url: Option<String>,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    /// Where the provider sends users back to: this server's `/api/auth/oidc/callback`
+    pub redirect_url: Option<String>,
+}
+
+/// What happens to an attempt's branch once a coding agent, or the cleanup script after
+/// it, finishes
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PostExecutionGitConfig {
+    /// Commit what the run left uncommitted, with the project's commit message template
+    pub auto_commit: bool,
+    /// Push the branch to origin after a successful run, using the GitHub token
+    pub push: bool,
+}
+
+/// Whether an attempt's changes need a reviewer's approval before they're merged. Approving
+/// merges them; rejecting can send the reviewer's comments back to the agent.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ReviewGateConfig {
+    /// Merging, directly or through the merge queue, is refused until the attempt is
+    /// approved
+    pub require_approval: bool,
+    /// Push the base branch to origin once an approved attempt is merged into it
+    pub push_on_approve: bool,
+}
+
+/// Running coding agents under bubblewrap on Linux, so they can write to their worktree and
+/// their own settings but nothing else. A project's own setting takes the place of
+/// `enabled` and `deny_network`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SandboxConfig {
+    pub enabled: bool,
+    /// Cut the agents off from the network too; most need it to reach their model
+    pub deny_network: bool,
+    /// The agents to sandbox, as in `executor.type`; empty sandboxes every coding agent
+    pub executors: Vec<String>,
+}
+
+/// Caps on each coding agent and script, together with whatever it starts. Memory and CPU
+/// shares go through a cgroup where `VIBE_KANBAN_CGROUP` names one; otherwise memory is
+/// checked as runs are sampled. `None` leaves a resource unlimited.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ResourceLimitsConfig {
+    /// Megabytes of resident memory a run is killed for going over
+    pub max_memory_mb: Option<u32>,
+    /// CPU weight against other runs and processes; 1024 is the default, 512 half of it
+    pub cpu_shares: Option<u32>,
+    /// Files each process of a run may have open
+    pub max_open_files: Option<u32>,
+}
+
+/// How many tokens of an attempt's earlier conversation a follow-up's prompt may carry, for
+/// executors that are given it back rather than resuming their session. Older turns are
+/// summarized and the most recent tool calls kept as they were until it fits.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ContextBudgetConfig {
+    pub max_tokens: u32,
+    /// Budgets for particular executors, keyed as in `claude` or `gemini`
+    pub executors: HashMap<String, u32>,
+}
+
+/// The environment executor commands start from, for a server started from a desktop
+/// launcher that never ran the user's shell profile. Not used for commands run on a worker
+/// or in a container.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ShellEnvironmentConfig {
+    pub login_shell: LoginShellMode,
+    /// A file of `KEY=value` lines loaded on top of the login shell's environment. `~` is the
+    /// home directory, and a relative path is from the attempt's worktree.
+    pub env_file: Option<String>,
+}
+
+/// Where the app keeps its data. Changing it takes a restart, and nothing is copied from one
+/// database to the other.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DatabaseConfig {
+    /// A `postgres://` URL to keep the data in PostgreSQL. `None` keeps it in SQLite, in the
+    /// app's data directory.
+    pub url: Option<String>,
+}
+
+impl DatabaseConfig {
+    /// The database section of the config file, read without loading or saving the rest, for
+    /// processes other than the server, which owns the file
+    pub fn load(config_path: &Path) -> Self {
+        std::fs::read_to_string(config_path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .and_then(|config| serde_json::from_value(config.get("database")?.clone()).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// When commands get the environment of the user's login shell. Windows programs get the
+/// user's environment however they're started, so there it's never loaded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS, sqlx::Type)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum LoginShellMode {
+    /// When the server wasn't started from a terminal
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl LoginShellMode {
+    pub fn applies(self) -> bool {
+        match self {
+            _ if cfg!(windows) => false,
+            LoginShellMode::Auto => !shell::started_from_terminal(),
+            LoginShellMode::Always => true,
+            LoginShellMode::Never => false,
+        }
+    }
+}
+
+impl ShellEnvironmentConfig {
+    /// Where the env file is, for commands run in `worktree_path`
+    pub fn env_file_path(&self, worktree_path: &Path) -> Option<PathBuf> {
+        let env_file = self.env_file.as_deref().map(str::trim)?;
+        if env_file.is_empty() {
+            return None;
+        }
+        let path = match env_file.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir()?.join(rest),
+            None => PathBuf::from(env_file),
+        };
+        Some(worktree_path.join(path))
+    }
+
+    /// The variables commands run in `worktree_path` start with
+    pub async fn bootstrap(&self, worktree_path: &Path) -> Vec<(String, String)> {
+        shell::bootstrap_environment(
+            self.login_shell.applies(),
+            self.env_file_path(worktree_path).as_deref(),
+        )
+        .await
+    }
+}
+
+impl ContextBudgetConfig {
+    pub fn max_tokens_for(&self, executor: &str) -> usize {
+        self.executors
+            .get(executor)
+            .copied()
+            .unwrap_or(self.max_tokens) as usize
+    }
+}
+
+impl ResourceLimitsConfig {
+    pub fn to_limits(&self) -> ResourceLimits {
+        ResourceLimits {
+            max_rss_bytes: self.max_memory_mb.map(|mb| u64::from(mb) * 1024 * 1024),
+            cpu_shares: self.cpu_shares.map(u64::from),
+            max_open_files: self.max_open_files.map(u64::from),
+        }
+    }
+}
+
+/// How a project's verification commands are run once a coding agent finishes
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct VerificationConfig {
+    /// Label the task `needs-follow-up` when a command fails, and drop the label once they
+    /// all pass again
+    pub follow_up_on_failure: bool,
+    /// Seconds each command may run before it's stopped and counted as failed
+    pub timeout_secs: u32,
+    /// Follow-ups sent to the agent with the failing output, asking it to fix what failed,
+    /// before the attempt goes to review anyway; `0` turns them off
+    pub auto_fix_attempts: u32,
+}
+
+/// How the body of a pull request created from an attempt is put together
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PrDescriptionConfig {
+    /// Markdown with `{{task_title}}`, `{{task_description}}`, `{{summary}}`, `{{changes}}`,
+    /// `{{validation}}`, `{{branch}}` and `{{base_branch}}`; headings over empty sections are
+    /// left out
+    pub template: String,
+    /// Summarize the agent's messages with the language model instead of quoting its last one
+    pub summarize_with_llm: bool,
+}
+
+/// Which events raise sound and desktop notifications
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct NotificationEvents {
+    pub attempt_succeeded: bool,
+    pub attempt_failed: bool,
+    /// A dangerous command or a finished plan is waiting to be approved
+    pub approval_needed: bool,
+    /// A task went stale in In Progress or In Review
+    pub task_stale: bool,
+}
+
+/// Where executions waiting on the user (a held command, a plan to review) are announced,
+/// and who hears about it again when nobody answers
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AttentionConfig {
+    /// Used as soon as an execution starts waiting
+    pub notify: AttentionChannels,
+    /// Minutes an execution may wait before it's announced through `escalate_to`; `None`
+    /// never escalates
+    pub escalate_after_minutes: Option<u32>,
+    pub escalate_to: AttentionChannels,
+    pub smtp: SmtpConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AttentionChannels {
+    /// Sound and push notifications, as turned on above
+    pub desktop: bool,
+    /// The project's webhooks that want approval notifications
+    pub webhook: bool,
+    /// Mail to `smtp.to`
+    pub email: bool,
+}
+
+/// The mail server attention emails are sent through
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SmtpConfig {
+    /// Empty until a server is set up, which keeps email from being sent
+    pub host: String,
+    pub port: u16,
+    pub security: SmtpSecurity,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// TLS from the start, usually on port 465
+    Tls,
+    /// Upgraded to TLS after connecting, usually on port 587
+    Starttls,
+    None,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 #[serde(rename_all = "lowercase")]
@@ -218,6 +685,8 @@ impl Default for Config {
             sound_alerts: true,
             sound_file: SoundFile::AbstractSound4,
             push_notifications: true,
+            notification_events: NotificationEvents::default(),
+            attention: AttentionConfig::default(),
             editor: EditorConfig::default(),
             github: GitHubConfig::default(),
             analytics_enabled: None,
@@ -228,6 +697,152 @@ impl Default for Config {
                 bitness: info.bitness().to_string(),
             },
             workspace_dir: None,
+            webhooks: WebhookConfig::default(),
+            notion: NotionConfig::default(),
+            jira: JiraConfig::default(),
+            linear: LinearConfig::default(),
+            embeddings: EmbeddingsConfig::default(),
+            llm: LlmConfig::default(),
+            suggest_labels: true,
+            pr_description: PrDescriptionConfig::default(),
+            stale_tasks: StaleTasksConfig::default(),
+            run_estimate: RunEstimateConfig::default(),
+            jsonl_executors: Vec::new(),
+            acp_agents: acp::default_agents(),
+            executor_models: HashMap::new(),
+            execution_timeout_minutes: None,
+            executor_retry: RetryPolicy::default(),
+            execution_limits: ExecutionLimits::default(),
+            resume_interrupted_runs: false,
+            log_redaction: LogRedactionConfig::default(),
+            log_storage: LogStorageConfig::default(),
+            auth: AuthConfig::default(),
+            post_execution_git: PostExecutionGitConfig::default(),
+            verification: VerificationConfig::default(),
+            review_gate: ReviewGateConfig::default(),
+            sandbox: SandboxConfig::default(),
+            resource_limits: ResourceLimitsConfig::default(),
+            context_budget: ContextBudgetConfig::default(),
+            shell_environment: ShellEnvironmentConfig::default(),
+            database: DatabaseConfig::default(),
+        }
+    }
+}
+
+impl Default for StaleTasksConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            in_progress_hours: 24,
+            in_review_hours: 72,
+            archive_after_days: None,
+        }
+    }
+}
+
+impl Default for RunEstimateConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            confirm_above_tokens: 500_000,
+            confirm_above_files: 20,
+        }
+    }
+}
+
+impl Default for ContextBudgetConfig {
+    fn default() -> Self {
+        Self {
+            max_tokens: 4_000,
+            executors: HashMap::new(),
+        }
+    }
+}
+
+impl Default for LogRedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            detect_high_entropy: true,
+            extra_patterns: Vec::new(),
+        }
+    }
+}
+
+impl Default for LogStorageConfig {
+    fn default() -> Self {
+        Self {
+            max_stream_kb: Some(8 * 1024),
+            head_kb: 1024,
+            offload_full_logs: false,
+        }
+    }
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            session_hours: 24 * 7,
+            max_session_days: 30,
+            oidc: OidcConfig::default(),
+        }
+    }
+}
+
+impl Default for PostExecutionGitConfig {
+    fn default() -> Self {
+        Self {
+            auto_commit: true,
+            push: false,
+        }
+    }
+}
+
+impl Default for VerificationConfig {
+    fn default() -> Self {
+        Self {
+            follow_up_on_failure: true,
+            timeout_secs: 600,
+            auto_fix_attempts: 0,
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            backoff_seconds: 10,
+        }
+    }
+}
+
+impl Default for PrDescriptionConfig {
+    fn default() -> Self {
+        Self {
+            template: crate::services::pr_description::DEFAULT_TEMPLATE.to_string(),
+            summarize_with_llm: true,
+        }
+    }
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        Self {
+            base_url: None,
+            model: "gpt-4o-mini".to_string(),
+            api_key: None,
+        }
+    }
+}
+
+impl Default for EmbeddingsConfig {
+    fn default() -> Self {
+        Self {
+            base_url: None,
+            model: "text-embedding-3-small".to_string(),
+            api_key: None,
         }
     }
 }
@@ -254,6 +869,67 @@ impl Default for GitHubConfig {
     }
 }
 
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            secrets: Vec::new(),
+            timestamp_tolerance_secs: 300,
+        }
+    }
+}
+
+impl Default for NotificationEvents {
+    fn default() -> Self {
+        Self {
+            attempt_succeeded: true,
+            attempt_failed: true,
+            approval_needed: true,
+            task_stale: true,
+        }
+    }
+}
+
+impl Default for AttentionConfig {
+    fn default() -> Self {
+        Self {
+            notify: AttentionChannels {
+                desktop: true,
+                webhook: true,
+                email: false,
+            },
+            escalate_after_minutes: Some(15),
+            escalate_to: AttentionChannels {
+                desktop: true,
+                webhook: false,
+                email: true,
+            },
+            smtp: SmtpConfig::default(),
+        }
+    }
+}
+
+impl Default for SmtpConfig {
+    fn default() -> Self {
+        Self {
+            host: String::new(),
+            port: 587,
+            security: SmtpSecurity::Starttls,
+            username: None,
+            password: None,
+            from: String::new(),
+            to: Vec::new(),
+        }
+    }
+}
+
+impl WebhookConfig {
+    pub fn signer(&self) -> crate::services::WebhookSigner {
+        crate::services::WebhookSigner::new(self.secrets.clone()).with_tolerance(
+            std::time::Duration::from_secs(self.timestamp_tolerance_secs),
+        )
+    }
+}
+
 impl EditorConfig {
     pub fn get_command(&self) -> Vec<String> {
         match &self.editor_type {
@@ -427,6 +1103,21 @@ impl Config {
         Ok(())
     }
 
+    /// The config with its credentials cleared, for users who aren't admins
+    pub fn without_secrets(&self) -> Self {
+        let mut config = self.clone();
+        config.github.pat = None;
+        config.github.token = None;
+        config.webhooks.secrets.clear();
+        config.notion.token = None;
+        config.jira.api_token = None;
+        config.linear.api_key = None;
+        config.embeddings.api_key = None;
+        config.llm.api_key = None;
+        config.auth.oidc.client_secret = None;
+        config
+    }
+
     pub fn save(&self, config_path: &PathBuf) -> anyhow::Result<()> {
         let content = serde_json::to_string_pretty(self)?;
         std::fs::write(config_path, content)?;
@@ -0,0 +1,158 @@
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::{
+    db::{self, DbPool},
+    executor::NormalizedEntryType,
+    services::embeddings::{decode_vector, encode_vector},
+};
+
+/// How far a process's conversation has been chunked and with which model
+#[derive(Debug, Clone, FromRow)]
+pub struct ConversationIndexState {
+    pub execution_process_id: Uuid,
+    pub model: String,
+    pub indexed_entries: i64,
+}
+
+/// A run of consecutive conversation entries embedded together
+#[derive(Debug, Clone)]
+pub struct ConversationChunk {
+    pub execution_process_id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub entry_index: i64,
+    pub entry_type: NormalizedEntryType,
+    pub timestamp: Option<String>,
+    pub content: String,
+    pub embedding: Vec<f32>,
+}
+
+/// A chunk ready to be stored, before it is tied to a process
+#[derive(Debug, Clone)]
+pub struct NewConversationChunk {
+    pub entry_index: usize,
+    pub entry_type: NormalizedEntryType,
+    pub timestamp: Option<String>,
+    pub content: String,
+    pub embedding: Vec<f32>,
+}
+
+impl ConversationIndexState {
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            ConversationIndexState,
+            r#"SELECT s.execution_process_id as "execution_process_id!: Uuid", s.model, s.indexed_entries
+               FROM conversation_index_state s
+               JOIN execution_processes ep ON ep.id = s.execution_process_id
+               JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE t.project_id = $1"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+#[derive(FromRow)]
+struct ConversationChunkRow {
+    execution_process_id: Uuid,
+    task_attempt_id: Uuid,
+    entry_index: i64,
+    entry_type: String,
+    timestamp: Option<String>,
+    content: String,
+    embedding: Vec<u8>,
+}
+
+impl ConversationChunk {
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = db::query_as!(
+            ConversationChunkRow,
+            r#"SELECT c.execution_process_id as "execution_process_id!: Uuid", ep.task_attempt_id as "task_attempt_id!: Uuid", c.entry_index, c.entry_type, c.timestamp, c.content, c.embedding
+               FROM conversation_chunks c
+               JOIN execution_processes ep ON ep.id = c.execution_process_id
+               JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE t.project_id = $1"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                Some(Self {
+                    execution_process_id: row.execution_process_id,
+                    task_attempt_id: row.task_attempt_id,
+                    entry_index: row.entry_index,
+                    entry_type: serde_json::from_str(&row.entry_type).ok()?,
+                    timestamp: row.timestamp,
+                    content: row.content,
+                    embedding: decode_vector(&row.embedding),
+                })
+            })
+            .collect())
+    }
+
+    /// Store newly embedded chunks of a process and advance its index state. With `reset`,
+    /// chunks from an earlier model are dropped first since their vectors aren't comparable.
+    pub async fn append(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+        model: &str,
+        reset: bool,
+        chunks: &[NewConversationChunk],
+        indexed_entries: usize,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        if reset {
+            db::query!(
+                "DELETE FROM conversation_chunks WHERE execution_process_id = $1",
+                execution_process_id
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+        for chunk in chunks {
+            let id = Uuid::new_v4();
+            let entry_index = chunk.entry_index as i64;
+            let entry_type = serde_json::to_string(&chunk.entry_type).unwrap_or_default();
+            let embedding = encode_vector(&chunk.embedding);
+            db::query!(
+                r#"INSERT INTO conversation_chunks (id, execution_process_id, entry_index, entry_type, timestamp, content, embedding)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
+                id,
+                execution_process_id,
+                entry_index,
+                entry_type,
+                chunk.timestamp,
+                chunk.content,
+                embedding
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+        let indexed_entries = indexed_entries as i64;
+        db::query!(
+            r#"INSERT INTO conversation_index_state (execution_process_id, model, indexed_entries)
+               VALUES ($1, $2, $3)
+               ON CONFLICT(execution_process_id) DO UPDATE SET
+                   model = excluded.model,
+                   indexed_entries = excluded.indexed_entries,
+                   updated_at = datetime('now', 'subsec')"#,
+            execution_process_id,
+            model,
+            indexed_entries
+        )
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await
+    }
+}
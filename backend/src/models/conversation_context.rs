@@ -0,0 +1,73 @@
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::{
+    db::{self, DbPool},
+    executor::NormalizedEntry,
+};
+
+/// The conversation of a finished coding agent run, kept so follow-ups that can't resume its
+/// session (of executors without sessions, or of another executor) can be given it back as
+/// a transcript
+#[derive(Debug, Clone)]
+pub struct ConversationContext {
+    pub prompt: Option<String>,
+    pub entries: Vec<NormalizedEntry>,
+}
+
+#[derive(FromRow)]
+struct ConversationContextRow {
+    prompt: Option<String>,
+    entries: String,
+}
+
+impl From<ConversationContextRow> for ConversationContext {
+    fn from(row: ConversationContextRow) -> Self {
+        Self {
+            prompt: row.prompt,
+            entries: serde_json::from_str(&row.entries).unwrap_or_default(),
+        }
+    }
+}
+
+impl ConversationContext {
+    pub async fn record(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+        task_attempt_id: Uuid,
+        prompt: Option<&str>,
+        entries: &[NormalizedEntry],
+    ) -> Result<(), sqlx::Error> {
+        let entries = serde_json::to_string(entries).unwrap_or_else(|_| "[]".to_string());
+        db::query!(
+            "INSERT INTO conversation_contexts (execution_process_id, task_attempt_id, prompt, entries)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT(execution_process_id) DO UPDATE SET prompt = excluded.prompt, entries = excluded.entries",
+            execution_process_id,
+            task_attempt_id,
+            prompt,
+            entries
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The attempt's recorded conversations, oldest first
+    pub async fn find_by_task_attempt_id(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = db::query_as!(
+            ConversationContextRow,
+            r#"SELECT prompt, entries
+               FROM conversation_contexts
+               WHERE task_attempt_id = $1
+               ORDER BY created_at ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(Self::from).collect())
+    }
+}
@@ -0,0 +1,265 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    db::{self, DbPool},
+    executor::{ActionType, NormalizedEntry, NormalizedEntryType},
+};
+
+/// Characters of context kept on either side of the first match in a snippet
+const SNIPPET_CONTEXT_CHARS: usize = 80;
+
+/// Filler words dropped from natural-language queries before scoring
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "did", "do", "does", "for", "how", "in", "is", "it", "of", "on", "or",
+    "the", "to", "was", "we", "what", "when", "where", "which", "who", "why", "with",
+];
+
+/// One ranked conversation entry, pointing back at the attempt and process it came from
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ConversationSearchHit {
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub attempt_id: Uuid,
+    pub execution_process_id: Uuid,
+    /// Index into the process's normalized conversation entries
+    pub entry_index: usize,
+    pub entry_type: NormalizedEntryType,
+    pub timestamp: Option<String>,
+    pub snippet: String,
+    pub score: f64,
+}
+
+/// The task an attempt belongs to, for labelling search hits
+#[derive(Debug, Clone, FromRow)]
+pub struct AttemptTaskRef {
+    pub attempt_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+}
+
+impl AttemptTaskRef {
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            AttemptTaskRef,
+            r#"SELECT ta.id as "attempt_id!: Uuid", t.id as "task_id!: Uuid", t.title as "task_title!"
+               FROM task_attempts ta
+               JOIN tasks t ON ta.task_id = t.id
+               WHERE t.project_id = $1"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+/// A parsed search query: the full phrase plus its meaningful terms
+#[derive(Debug)]
+pub struct ConversationQuery {
+    phrase: String,
+    terms: Vec<String>,
+}
+
+impl ConversationQuery {
+    /// Returns `None` if the query has nothing left to search for
+    pub fn parse(query: &str) -> Option<Self> {
+        let phrase = query.trim().to_lowercase();
+        let mut seen = HashSet::new();
+        let terms: Vec<String> = phrase
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .filter(|t| t.len() > 1 && !STOP_WORDS.contains(t))
+            .filter(|t| seen.insert(t.to_string()))
+            .map(str::to_string)
+            .collect();
+        if terms.is_empty() {
+            return None;
+        }
+        Some(Self { phrase, terms })
+    }
+
+    /// Score an entry against the query, returning the score and a snippet around the best match
+    pub fn score_entry(&self, entry: &NormalizedEntry) -> Option<(f64, String)> {
+        let text = searchable_text(entry);
+        let lower = text.to_lowercase();
+
+        let mut score = 0.0;
+        let mut matched_terms = 0;
+        let mut first_match: Option<usize> = None;
+        for term in &self.terms {
+            let mut occurrences = 0;
+            for (pos, _) in lower.match_indices(term.as_str()) {
+                occurrences += 1;
+                first_match = Some(first_match.map_or(pos, |p| p.min(pos)));
+            }
+            if occurrences > 0 {
+                matched_terms += 1;
+                // Diminishing returns for repeated mentions
+                score += 1.0 + (occurrences as f64).ln();
+            }
+        }
+        if matched_terms == 0 {
+            return None;
+        }
+
+        // Entries matching more of the query rank well above ones matching a single word
+        let coverage = matched_terms as f64 / self.terms.len() as f64;
+        score *= coverage * coverage;
+
+        let phrase_pos = lower.find(&self.phrase);
+        if phrase_pos.is_some() {
+            score *= 2.0;
+        }
+        score *= entry_type_weight(&entry.entry_type);
+
+        let anchor = phrase_pos.or(first_match).unwrap_or(0);
+        Some((score, snippet(&text, &lower, anchor)))
+    }
+}
+
+/// Content plus any paths or commands carried by a tool use, so "which attempt touched
+/// auth.rs" matches the edit itself
+pub fn searchable_text(entry: &NormalizedEntry) -> String {
+    let detail = match &entry.entry_type {
+        NormalizedEntryType::ToolUse { action_type, .. } => match action_type {
+            ActionType::FileRead { path, .. } | ActionType::FileWrite { path, .. } => Some(path),
+            ActionType::CommandRun { command } => Some(command),
+            ActionType::Search { query } => Some(query),
+            ActionType::WebFetch { url } => Some(url),
+            ActionType::TaskCreate { description } | ActionType::Other { description } => {
+                Some(description)
+            }
+            ActionType::PlanPresentation { plan } => Some(plan),
+        },
+        _ => None,
+    };
+    match detail {
+        Some(detail) if !entry.content.contains(detail.as_str()) => {
+            format!("{}\n{}", entry.content, detail)
+        }
+        _ => entry.content.clone(),
+    }
+}
+
+fn entry_type_weight(entry_type: &NormalizedEntryType) -> f64 {
+    match entry_type {
+        NormalizedEntryType::ToolUse {
+            action_type: ActionType::FileWrite { .. },
+            ..
+        } => 1.5,
+        NormalizedEntryType::UserMessage
+        | NormalizedEntryType::AssistantMessage
+        | NormalizedEntryType::PlanEntry => 1.2,
+        NormalizedEntryType::ToolUse { .. }
+        | NormalizedEntryType::FileEdit { .. }
+        | NormalizedEntryType::Thinking => 1.0,
+        NormalizedEntryType::SystemMessage
+        | NormalizedEntryType::ErrorMessage
+        | NormalizedEntryType::Attachment { .. }
+        | NormalizedEntryType::Verification { .. } => 0.8,
+    }
+}
+
+/// Cut a window of `text` around byte offset `anchor` of its lowercased form.
+/// Lowercasing can change byte lengths, so the window is located by character count.
+fn snippet(text: &str, lower: &str, anchor: usize) -> String {
+    let anchor_chars = lower
+        .char_indices()
+        .take_while(|(i, _)| *i < anchor)
+        .count();
+    let total = text.chars().count();
+    let start = anchor_chars.saturating_sub(SNIPPET_CONTEXT_CHARS);
+    let end = (anchor_chars + SNIPPET_CONTEXT_CHARS).min(total);
+
+    let window: String = text.chars().skip(start).take(end - start).collect();
+    let window = window.split_whitespace().collect::<Vec<_>>().join(" ");
+    format!(
+        "{}{}{}",
+        if start > 0 { "…" } else { "" },
+        window,
+        if end < total { "…" } else { "" }
+    )
+}
+
+/// Rank hits best-first, breaking ties by most recent, and keep the top `limit`
+pub fn rank_hits(mut hits: Vec<ConversationSearchHit>, limit: usize) -> Vec<ConversationSearchHit> {
+    hits.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| b.timestamp.cmp(&a.timestamp))
+    });
+    hits.truncate(limit);
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            id: None,
+            timestamp: None,
+            duration_ms: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_scoring_prefers_full_coverage_and_edits() {
+        let query =
+            ConversationQuery::parse("which attempt modified the auth middleware?").unwrap();
+        assert_eq!(
+            query.terms,
+            vec!["attempt", "modified", "auth", "middleware"]
+        );
+
+        let edit = entry(
+            NormalizedEntryType::ToolUse {
+                tool_name: "edit".to_string(),
+                action_type: ActionType::FileWrite {
+                    path: "src/auth/middleware.rs".to_string(),
+                    location: None,
+                },
+            },
+            "Modified the auth middleware",
+        );
+        let chat = entry(
+            NormalizedEntryType::AssistantMessage,
+            "The auth flow looks fine to me",
+        );
+        let unrelated = entry(NormalizedEntryType::AssistantMessage, "Added a retry loop");
+
+        let (edit_score, edit_snippet) = query.score_entry(&edit).unwrap();
+        let (chat_score, _) = query.score_entry(&chat).unwrap();
+        assert!(edit_score > chat_score);
+        assert!(edit_snippet.contains("src/auth/middleware.rs"));
+        assert!(query.score_entry(&unrelated).is_none());
+    }
+
+    #[test]
+    fn test_snippet_is_centered_on_match() {
+        let query = ConversationQuery::parse("retry strategy").unwrap();
+        let long = format!(
+            "{} we settled on an exponential retry strategy {}",
+            "x ".repeat(100),
+            "ü ".repeat(100)
+        );
+        let (_, snippet) = query
+            .score_entry(&entry(NormalizedEntryType::UserMessage, &long))
+            .unwrap();
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        assert!(snippet.contains("retry strategy"));
+        assert!(ConversationQuery::parse("the of ?").is_none());
+    }
+}
@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// The port a dev server was told to listen on through `PORT`, which its preview is proxied to
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DevServerPort {
+    pub execution_process_id: Uuid,
+    pub port: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl DevServerPort {
+    pub async fn create(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+        port: u16,
+    ) -> Result<Self, sqlx::Error> {
+        let port = port as i64;
+        db::query_as!(
+            DevServerPort,
+            r#"INSERT INTO dev_server_ports (execution_process_id, port)
+               VALUES ($1, $2)
+               RETURNING execution_process_id as "execution_process_id!: Uuid", port, created_at as "created_at!: DateTime<Utc>""#,
+            execution_process_id,
+            port
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// The port of the attempt's dev server, if one is running
+    pub async fn find_running_by_task_attempt_id(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            DevServerPort,
+            r#"SELECT dsp.execution_process_id as "execution_process_id!: Uuid", dsp.port, dsp.created_at as "created_at!: DateTime<Utc>"
+               FROM dev_server_ports dsp
+               JOIN execution_processes ep ON dsp.execution_process_id = ep.id
+               WHERE ep.task_attempt_id = $1
+               AND ep.process_type = 'devserver'
+               AND ep.status IN ('running', 'paused')
+               ORDER BY ep.created_at DESC
+               LIMIT 1"#,
+            task_attempt_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+}
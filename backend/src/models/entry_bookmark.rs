@@ -0,0 +1,168 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    db::{self, DbPool},
+    executor::NormalizedEntry,
+};
+
+/// An entry of a task's coding agent conversation bookmarked for later, as it was when it
+/// was bookmarked
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct EntryBookmark {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    /// The run whose conversation the entry is in
+    pub execution_process_id: Uuid,
+    /// The entry's id among the run's
+    pub entry_id: String,
+    pub entry: NormalizedEntry,
+    pub note: Option<String>,
+    /// Who bookmarked the entry, when sign-in is turned on
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct EntryBookmarkRow {
+    id: Uuid,
+    task_id: Uuid,
+    task_title: String,
+    execution_process_id: Uuid,
+    entry_id: String,
+    entry: String,
+    note: Option<String>,
+    created_by: Option<Uuid>,
+    created_at: DateTime<Utc>,
+}
+
+impl EntryBookmarkRow {
+    /// `None` for an entry kept by a version whose entries this one can't read
+    fn into_bookmark(self) -> Option<EntryBookmark> {
+        Some(EntryBookmark {
+            id: self.id,
+            task_id: self.task_id,
+            task_title: self.task_title,
+            execution_process_id: self.execution_process_id,
+            entry_id: self.entry_id,
+            entry: serde_json::from_str(&self.entry).ok()?,
+            note: self.note,
+            created_by: self.created_by,
+            created_at: self.created_at,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateEntryBookmark {
+    pub execution_process_id: Uuid,
+    /// The entry's `id` in the run's normalized conversation
+    pub entry_id: String,
+    pub note: Option<String>,
+}
+
+impl EntryBookmark {
+    pub async fn find_by_task_id(pool: &DbPool, task_id: Uuid) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = db::query_as!(
+            EntryBookmarkRow,
+            r#"SELECT b.id as "id!: Uuid", b.task_id as "task_id!: Uuid", t.title as task_title, b.execution_process_id as "execution_process_id!: Uuid", b.entry_id, b.entry, b.note, b.created_by as "created_by: Uuid", b.created_at as "created_at!: DateTime<Utc>"
+               FROM entry_bookmarks b
+               JOIN tasks t ON t.id = b.task_id
+               WHERE b.task_id = $1
+               ORDER BY b.created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(EntryBookmarkRow::into_bookmark)
+            .collect())
+    }
+
+    /// Every bookmark on the project's tasks, newest first
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = db::query_as!(
+            EntryBookmarkRow,
+            r#"SELECT b.id as "id!: Uuid", b.task_id as "task_id!: Uuid", t.title as task_title, b.execution_process_id as "execution_process_id!: Uuid", b.entry_id, b.entry, b.note, b.created_by as "created_by: Uuid", b.created_at as "created_at!: DateTime<Utc>"
+               FROM entry_bookmarks b
+               JOIN tasks t ON t.id = b.task_id
+               WHERE t.project_id = $1
+               ORDER BY b.created_at DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(EntryBookmarkRow::into_bookmark)
+            .collect())
+    }
+
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        let row = db::query_as!(
+            EntryBookmarkRow,
+            r#"SELECT b.id as "id!: Uuid", b.task_id as "task_id!: Uuid", t.title as task_title, b.execution_process_id as "execution_process_id!: Uuid", b.entry_id, b.entry, b.note, b.created_by as "created_by: Uuid", b.created_at as "created_at!: DateTime<Utc>"
+               FROM entry_bookmarks b
+               JOIN tasks t ON t.id = b.task_id
+               WHERE b.id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.and_then(EntryBookmarkRow::into_bookmark))
+    }
+
+    /// Bookmark `entry`. Bookmarking an entry again updates its note and the entry as kept.
+    pub async fn create(
+        pool: &DbPool,
+        task_id: Uuid,
+        entry: &NormalizedEntry,
+        data: &CreateEntryBookmark,
+        created_by: Option<Uuid>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let entry_json = serde_json::to_string(entry).unwrap_or_default();
+        let note = data
+            .note
+            .as_deref()
+            .map(str::trim)
+            .filter(|note| !note.is_empty());
+        let id = db::query_scalar!(
+            r#"INSERT INTO entry_bookmarks (id, task_id, execution_process_id, entry_id, entry, note, created_by)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               ON CONFLICT (execution_process_id, entry_id) DO UPDATE SET
+                   entry = excluded.entry,
+                   note = excluded.note
+               RETURNING id as "id!: Uuid""#,
+            id,
+            task_id,
+            data.execution_process_id,
+            data.entry_id,
+            entry_json,
+            note,
+            created_by
+        )
+        .fetch_one(pool)
+        .await?;
+        Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    pub async fn delete(pool: &DbPool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = db::query!("DELETE FROM entry_bookmarks WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// A variable set in the environment of a project's executions, or of one task's
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct EnvironmentVariable {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    /// Task whose executions alone get this variable; `None` for every task of the project
+    pub task_id: Option<Uuid>,
+    pub key: String,
+    /// Encrypted for a secret, and left out when a secret is sent back
+    pub value: String,
+    pub is_secret: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertEnvironmentVariable {
+    pub key: String,
+    pub value: String,
+    #[serde(default)]
+    pub is_secret: bool,
+}
+
+impl EnvironmentVariable {
+    /// The variables set for `task_id`'s executions alone, or for all of the project's
+    /// when `task_id` is `None`
+    pub async fn find_by_scope(
+        pool: &DbPool,
+        project_id: Uuid,
+        task_id: Option<Uuid>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            EnvironmentVariable,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", task_id as "task_id: Uuid", key, value, is_secret as "is_secret!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM environment_variables
+               WHERE project_id = $1 AND task_id IS $2
+               ORDER BY key ASC"#,
+            project_id,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Every variable a task's executions get: the project's, then the task's own, which
+    /// come later so they override the project's of the same key
+    pub async fn find_for_task(
+        pool: &DbPool,
+        project_id: Uuid,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            EnvironmentVariable,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", task_id as "task_id: Uuid", key, value, is_secret as "is_secret!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM environment_variables
+               WHERE project_id = $1 AND (task_id IS NULL OR task_id = $2)
+               ORDER BY task_id IS NOT NULL, key ASC"#,
+            project_id,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Set `key` in the scope to `value`, as it is to be stored, replacing any value it had
+    pub async fn upsert(
+        pool: &DbPool,
+        project_id: Uuid,
+        task_id: Option<Uuid>,
+        key: &str,
+        value: &str,
+        is_secret: bool,
+    ) -> Result<Self, sqlx::Error> {
+        let updated = db::query!(
+            "UPDATE environment_variables SET value = $4, is_secret = $5, updated_at = datetime('now', 'subsec')
+             WHERE project_id = $1 AND task_id IS $2 AND key = $3",
+            project_id,
+            task_id,
+            key,
+            value,
+            is_secret
+        )
+        .execute(pool)
+        .await?;
+        if updated.rows_affected() == 0 {
+            let id = Uuid::new_v4();
+            db::query!(
+                "INSERT INTO environment_variables (id, project_id, task_id, key, value, is_secret) VALUES ($1, $2, $3, $4, $5, $6)",
+                id,
+                project_id,
+                task_id,
+                key,
+                value,
+                is_secret
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        db::query_as!(
+            EnvironmentVariable,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", task_id as "task_id: Uuid", key, value, is_secret as "is_secret!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM environment_variables
+               WHERE project_id = $1 AND task_id IS $2 AND key = $3"#,
+            project_id,
+            task_id,
+            key
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Remove `key` from the scope. Returns whether it was set.
+    pub async fn delete(
+        pool: &DbPool,
+        project_id: Uuid,
+        task_id: Option<Uuid>,
+        key: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = db::query!(
+            "DELETE FROM environment_variables WHERE project_id = $1 AND task_id IS $2 AND key = $3",
+            project_id,
+            task_id,
+            key
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The variable as the API sends it back, without a secret's value
+    pub fn without_secret(mut self) -> Self {
+        if self.is_secret {
+            self.value = String::new();
+        }
+        self
+    }
+}
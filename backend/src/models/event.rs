@@ -0,0 +1,402 @@
+use std::sync::OnceLock;
+
+use chrono::{DateTime, SubsecRound, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, Type};
+use tokio::sync::{broadcast, Mutex};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// Events kept for subscribers that fall behind before they start missing some
+const CHANNEL_CAPACITY: usize = 256;
+
+tokio::task_local! {
+    static ACTOR: EventActor;
+}
+
+/// Keeps two events from being chained onto the same previous one
+static RECORD_LOCK: Mutex<()> = Mutex::const_new(());
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum EventKind {
+    TaskCreated,
+    TaskStatusChanged,
+    TaskDeleted,
+    ExecutionStarted,
+    ExecutionCompleted,
+    ExecutionFailed,
+    ExecutionStopped,
+    PrOpened,
+    ReviewApproved,
+    ReviewRejected,
+    AttemptRolledBack,
+    ExecutionReverted,
+}
+
+/// Who caused an event: the user through the API, or the server on its own, e.g. the
+/// execution monitor or a scheduled run
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum EventActor {
+    User,
+    System,
+}
+
+/// A state transition in a project, as shown in its activity feed
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct Event {
+    pub id: i64,
+    pub project_id: Uuid,
+    pub task_id: Option<Uuid>,
+    pub task_attempt_id: Option<Uuid>,
+    pub execution_process_id: Option<Uuid>,
+    pub kind: EventKind,
+    pub actor: EventActor,
+    /// What changed, e.g. the statuses a task moved between
+    #[ts(type = "unknown")]
+    pub details: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    pub prev_hash: Option<String>,
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct EventRow {
+    id: i64,
+    project_id: Uuid,
+    task_id: Option<Uuid>,
+    task_attempt_id: Option<Uuid>,
+    execution_process_id: Option<Uuid>,
+    kind: EventKind,
+    actor: EventActor,
+    details: String,
+    created_at: DateTime<Utc>,
+    prev_hash: Option<String>,
+    hash: String,
+}
+
+impl From<EventRow> for Event {
+    fn from(row: EventRow) -> Self {
+        Self {
+            id: row.id,
+            project_id: row.project_id,
+            task_id: row.task_id,
+            task_attempt_id: row.task_attempt_id,
+            execution_process_id: row.execution_process_id,
+            kind: row.kind,
+            actor: row.actor,
+            details: serde_json::from_str(&row.details).unwrap_or_default(),
+            created_at: row.created_at,
+            prev_hash: row.prev_hash,
+            hash: row.hash,
+        }
+    }
+}
+
+/// An event about to be recorded
+/// The task and project an attempt belongs to
+#[derive(FromRow)]
+struct AttemptOwner {
+    task_id: Uuid,
+    project_id: Uuid,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewEvent {
+    pub project_id: Uuid,
+    pub task_id: Option<Uuid>,
+    pub task_attempt_id: Option<Uuid>,
+    pub execution_process_id: Option<Uuid>,
+    pub kind: EventKind,
+    pub details: serde_json::Value,
+}
+
+/// The outcome of checking the audit trail's hash chain
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct AuditVerification {
+    pub valid: bool,
+    pub checked: i64,
+    /// The first event whose hash doesn't match its contents or the event before it
+    pub first_invalid_event_id: Option<i64>,
+}
+
+/// Run `future` with the events it records attributed to `actor`. Events recorded outside
+/// of one are the system's.
+pub async fn with_actor<F: std::future::Future>(actor: EventActor, future: F) -> F::Output {
+    ACTOR.scope(actor, future).await
+}
+
+fn current_actor() -> EventActor {
+    ACTOR.try_with(|actor| *actor).unwrap_or(EventActor::System)
+}
+
+fn channel() -> &'static broadcast::Sender<Event> {
+    static CHANNEL: OnceLock<broadcast::Sender<Event>> = OnceLock::new();
+    CHANNEL.get_or_init(|| broadcast::channel(CHANNEL_CAPACITY).0)
+}
+
+/// Events as they are recorded, across all projects
+pub fn subscribe() -> broadcast::Receiver<Event> {
+    channel().subscribe()
+}
+
+fn kind_name(kind: EventKind) -> String {
+    serde_json::to_value(kind)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn actor_name(actor: EventActor) -> &'static str {
+    match actor {
+        EventActor::User => "user",
+        EventActor::System => "system",
+    }
+}
+
+/// The hash chaining an event onto the one before it
+#[allow(clippy::too_many_arguments)]
+fn chain_hash(
+    prev_hash: Option<&str>,
+    project_id: Uuid,
+    task_id: Option<Uuid>,
+    task_attempt_id: Option<Uuid>,
+    execution_process_id: Option<Uuid>,
+    kind: EventKind,
+    actor: EventActor,
+    details: &str,
+    created_at: DateTime<Utc>,
+) -> String {
+    let optional = |id: Option<Uuid>| id.map(|id| id.to_string()).unwrap_or_default();
+    let fields = [
+        prev_hash.unwrap_or_default().to_string(),
+        project_id.to_string(),
+        optional(task_id),
+        optional(task_attempt_id),
+        optional(execution_process_id),
+        kind_name(kind),
+        actor_name(actor).to_string(),
+        details.to_string(),
+        created_at.to_rfc3339(),
+    ];
+    hex::encode(Sha256::digest(fields.join("\n").as_bytes()))
+}
+
+fn row_hash(row: &EventRow) -> String {
+    chain_hash(
+        row.prev_hash.as_deref(),
+        row.project_id,
+        row.task_id,
+        row.task_attempt_id,
+        row.execution_process_id,
+        row.kind,
+        row.actor,
+        &row.details,
+        row.created_at,
+    )
+}
+
+impl Event {
+    /// Append an event to the audit trail and announce it to subscribers
+    pub async fn record(pool: &DbPool, event: NewEvent) -> Result<Self, sqlx::Error> {
+        let _guard = RECORD_LOCK.lock().await;
+
+        let actor = current_actor();
+        let details = event.details.to_string();
+        // To the microsecond, as PostgreSQL keeps it, so the hash checks out once read back
+        let created_at = Utc::now().trunc_subsecs(6);
+        let prev_hash = db::query_scalar!(r#"SELECT hash FROM events ORDER BY id DESC LIMIT 1"#)
+            .fetch_optional(pool)
+            .await?;
+        let hash = chain_hash(
+            prev_hash.as_deref(),
+            event.project_id,
+            event.task_id,
+            event.task_attempt_id,
+            event.execution_process_id,
+            event.kind,
+            actor,
+            &details,
+            created_at,
+        );
+
+        let row = db::query_as!(
+            EventRow,
+            r#"INSERT INTO events (project_id, task_id, task_attempt_id, execution_process_id, kind, actor, details, created_at, prev_hash, hash)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+               RETURNING id as "id!: i64", project_id as "project_id!: Uuid", task_id as "task_id: Uuid", task_attempt_id as "task_attempt_id: Uuid", execution_process_id as "execution_process_id: Uuid", kind as "kind!: EventKind", actor as "actor!: EventActor", details, created_at as "created_at!: DateTime<Utc>", prev_hash, hash"#,
+            event.project_id,
+            event.task_id,
+            event.task_attempt_id,
+            event.execution_process_id,
+            event.kind,
+            actor,
+            details,
+            created_at,
+            prev_hash,
+            hash
+        )
+        .fetch_one(pool)
+        .await?;
+
+        let event = Event::from(row);
+        // Nobody listening isn't an error
+        let _ = channel().send(event.clone());
+        Ok(event)
+    }
+
+    /// Record an event about an attempt, or one of its processes, under the attempt's task
+    pub async fn record_for_attempt(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+        execution_process_id: Option<Uuid>,
+        kind: EventKind,
+        details: serde_json::Value,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let Some(owner) = db::query_as!(
+            AttemptOwner,
+            r#"SELECT ta.task_id as "task_id!: Uuid", t.project_id as "project_id!: Uuid"
+               FROM task_attempts ta
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE ta.id = $1"#,
+            task_attempt_id
+        )
+        .fetch_optional(pool)
+        .await?
+        else {
+            return Ok(None);
+        };
+        Self::record(
+            pool,
+            NewEvent {
+                project_id: owner.project_id,
+                task_id: Some(owner.task_id),
+                task_attempt_id: Some(task_attempt_id),
+                execution_process_id,
+                kind,
+                details,
+            },
+        )
+        .await
+        .map(Some)
+    }
+
+    /// A page of events, newest first, from one project or from all of them
+    pub async fn find_page(
+        pool: &DbPool,
+        project_id: Option<Uuid>,
+        before_id: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = db::query_as!(
+            EventRow,
+            r#"SELECT id as "id!: i64", project_id as "project_id!: Uuid", task_id as "task_id: Uuid", task_attempt_id as "task_attempt_id: Uuid", execution_process_id as "execution_process_id: Uuid", kind as "kind!: EventKind", actor as "actor!: EventActor", details, created_at as "created_at!: DateTime<Utc>", prev_hash, hash
+               FROM events
+               WHERE ($1 IS NULL OR project_id = $1)
+               AND ($2 IS NULL OR id < $2)
+               ORDER BY id DESC
+               LIMIT $3"#,
+            project_id,
+            before_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(Event::from).collect())
+    }
+
+    /// Events recorded after `after_id`, oldest first, for catching a stream up
+    pub async fn find_after(
+        pool: &DbPool,
+        project_id: Option<Uuid>,
+        after_id: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = db::query_as!(
+            EventRow,
+            r#"SELECT id as "id!: i64", project_id as "project_id!: Uuid", task_id as "task_id: Uuid", task_attempt_id as "task_attempt_id: Uuid", execution_process_id as "execution_process_id: Uuid", kind as "kind!: EventKind", actor as "actor!: EventActor", details, created_at as "created_at!: DateTime<Utc>", prev_hash, hash
+               FROM events
+               WHERE ($1 IS NULL OR project_id = $1) AND id > $2
+               ORDER BY id ASC"#,
+            project_id,
+            after_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(Event::from).collect())
+    }
+
+    /// Walk the whole trail checking that every event still hashes to what was recorded and
+    /// follows on from the event before it
+    pub async fn verify_chain(pool: &DbPool) -> Result<AuditVerification, sqlx::Error> {
+        let rows = db::query_as!(
+            EventRow,
+            r#"SELECT id as "id!: i64", project_id as "project_id!: Uuid", task_id as "task_id: Uuid", task_attempt_id as "task_attempt_id: Uuid", execution_process_id as "execution_process_id: Uuid", kind as "kind!: EventKind", actor as "actor!: EventActor", details, created_at as "created_at!: DateTime<Utc>", prev_hash, hash
+               FROM events
+               ORDER BY id ASC"#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut prev_hash: Option<String> = None;
+        let mut checked = 0;
+        for row in rows {
+            checked += 1;
+            if row.prev_hash != prev_hash || row_hash(&row) != row.hash {
+                return Ok(AuditVerification {
+                    valid: false,
+                    checked,
+                    first_invalid_event_id: Some(row.id),
+                });
+            }
+            prev_hash = Some(row.hash);
+        }
+        Ok(AuditVerification {
+            valid: true,
+            checked,
+            first_invalid_event_id: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_hash_covers_contents_and_previous_event() {
+        let project_id = Uuid::new_v4();
+        let created_at = Utc::now();
+        let hash = |prev: Option<&str>, details: &str| {
+            chain_hash(
+                prev,
+                project_id,
+                None,
+                None,
+                None,
+                EventKind::TaskCreated,
+                EventActor::User,
+                details,
+                created_at,
+            )
+        };
+
+        let first = hash(None, r#"{"title":"a"}"#);
+        assert_eq!(first, hash(None, r#"{"title":"a"}"#));
+        assert_ne!(first, hash(None, r#"{"title":"b"}"#));
+        assert_ne!(first, hash(Some("0"), r#"{"title":"a"}"#));
+        assert_eq!(
+            kind_name(EventKind::TaskStatusChanged),
+            "task_status_changed"
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+};
+
+use serde::Serialize;
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::execution_process::ExecutionProcessStatus;
+use crate::db::{self, DbPool};
+
+/// How the runs of one executor went in a project
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ExecutorStats {
+    pub executor: String,
+    pub runs: i64,
+    pub completed: i64,
+    /// Runs that failed, timed out or hit a limit
+    pub failed: i64,
+    /// Runs stopped by the user or cut off by a restart, which count neither way towards the
+    /// success rate
+    pub killed: i64,
+    /// Completed runs out of the finished ones that weren't killed; `None` before any
+    pub success_rate: Option<f64>,
+    pub median_duration_ms: Option<i64>,
+    pub total_duration_ms: i64,
+    pub attempts: i64,
+    pub merged_attempts: i64,
+    /// Coding agent runs after the first in an attempt
+    pub follow_ups_per_attempt: f64,
+}
+
+/// The time coding agents spent on a task, over all its attempts
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct TaskTime {
+    pub task_id: Uuid,
+    pub title: String,
+    pub agent_time_ms: i64,
+    pub runs: i64,
+    pub follow_ups: i64,
+}
+
+/// Time and outcome aggregates of a project's coding agent runs, with the tasks that took
+/// the most agent time first
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ProjectAnalytics {
+    pub tasks_done: i64,
+    /// From a task's creation to it last being moved to done
+    pub median_time_to_done_secs: Option<i64>,
+    /// Over the tasks with at least one run
+    pub follow_ups_per_task: Option<f64>,
+    pub agent_time_ms: i64,
+    pub executors: Vec<ExecutorStats>,
+    pub tasks: Vec<TaskTime>,
+}
+
+/// One coding agent run of the project
+#[derive(Debug, Clone, FromRow)]
+pub struct AgentRun {
+    pub task_id: Uuid,
+    pub title: String,
+    pub task_attempt_id: Uuid,
+    pub executor_type: Option<String>,
+    pub status: ExecutionProcessStatus,
+    pub duration_ms: Option<i64>,
+    pub merged: bool,
+}
+
+/// The middle value, or the mean of the two middle ones
+pub fn median(mut values: Vec<i64>) -> Option<i64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2
+    } else {
+        values[mid]
+    })
+}
+
+impl ProjectAnalytics {
+    /// Aggregate `runs`, in the order they started, and the seconds each done task took to
+    /// get there (`None` for those done before their moves were recorded)
+    pub fn new(runs: &[AgentRun], done_secs: &[Option<i64>]) -> Self {
+        let mut executors: BTreeMap<String, Vec<&AgentRun>> = BTreeMap::new();
+        let mut tasks: Vec<TaskTime> = Vec::new();
+        let mut attempts_by_task: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        for run in runs {
+            executors
+                .entry(
+                    run.executor_type
+                        .clone()
+                        .unwrap_or_else(|| "unknown".to_string()),
+                )
+                .or_default()
+                .push(run);
+
+            let attempts = attempts_by_task.entry(run.task_id).or_default();
+            let follow_up = attempts.contains(&run.task_attempt_id);
+            if !follow_up {
+                attempts.push(run.task_attempt_id);
+            }
+            match tasks.iter_mut().find(|task| task.task_id == run.task_id) {
+                Some(task) => {
+                    task.agent_time_ms += run.duration_ms.unwrap_or(0);
+                    task.runs += 1;
+                    task.follow_ups += follow_up as i64;
+                }
+                None => tasks.push(TaskTime {
+                    task_id: run.task_id,
+                    title: run.title.clone(),
+                    agent_time_ms: run.duration_ms.unwrap_or(0),
+                    runs: 1,
+                    follow_ups: 0,
+                }),
+            }
+        }
+
+        let executors = executors
+            .into_iter()
+            .map(|(executor, runs)| {
+                let count = |matches: fn(&ExecutionProcessStatus) -> bool| {
+                    runs.iter().filter(|run| matches(&run.status)).count() as i64
+                };
+                let completed = count(|s| matches!(s, ExecutionProcessStatus::Completed));
+                let killed = count(|s| {
+                    matches!(
+                        s,
+                        ExecutionProcessStatus::Killed | ExecutionProcessStatus::Interrupted
+                    )
+                });
+                let failed = count(|s| {
+                    !s.is_live()
+                        && !matches!(
+                            s,
+                            ExecutionProcessStatus::Completed
+                                | ExecutionProcessStatus::Killed
+                                | ExecutionProcessStatus::Interrupted
+                        )
+                });
+                let mut attempts: Vec<(Uuid, bool)> = Vec::new();
+                for run in &runs {
+                    if !attempts.iter().any(|(id, _)| *id == run.task_attempt_id) {
+                        attempts.push((run.task_attempt_id, run.merged));
+                    }
+                }
+                let durations: Vec<i64> = runs.iter().filter_map(|run| run.duration_ms).collect();
+                ExecutorStats {
+                    executor,
+                    runs: runs.len() as i64,
+                    completed,
+                    failed,
+                    killed,
+                    success_rate: (completed + failed > 0)
+                        .then(|| completed as f64 / (completed + failed) as f64),
+                    total_duration_ms: durations.iter().sum(),
+                    median_duration_ms: median(durations),
+                    attempts: attempts.len() as i64,
+                    merged_attempts: attempts.iter().filter(|(_, merged)| *merged).count() as i64,
+                    follow_ups_per_attempt: (runs.len() - attempts.len()) as f64
+                        / attempts.len() as f64,
+                }
+            })
+            .collect();
+
+        let follow_ups_per_task = (!tasks.is_empty()).then(|| {
+            tasks.iter().map(|task| task.follow_ups).sum::<i64>() as f64 / tasks.len() as f64
+        });
+        tasks.sort_by_key(|task| Reverse(task.agent_time_ms));
+        Self {
+            tasks_done: done_secs.len() as i64,
+            median_time_to_done_secs: median(done_secs.iter().flatten().copied().collect()),
+            follow_ups_per_task,
+            agent_time_ms: tasks.iter().map(|task| task.agent_time_ms).sum(),
+            executors,
+            tasks,
+        }
+    }
+
+    pub async fn for_project(pool: &DbPool, project_id: Uuid) -> Result<Self, sqlx::Error> {
+        let runs = db::query_as!(
+            AgentRun,
+            r#"SELECT t.id as "task_id!: Uuid",
+                      t.title,
+                      ta.id as "task_attempt_id!: Uuid",
+                      ep.executor_type,
+                      ep.status as "status!: ExecutionProcessStatus",
+                      ep.duration_ms,
+                      ta.merge_commit IS NOT NULL as "merged!: bool"
+               FROM execution_processes ep
+               JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE t.project_id = $1 AND ep.process_type = 'codingagent'
+               ORDER BY ep.started_at"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let done_secs = db::query_scalar!(
+            r#"SELECT CAST(ROUND((julianday(MAX(e.created_at)) - julianday(t.created_at)) * 86400.0) AS INTEGER) as "secs: i64"
+               FROM tasks t
+               LEFT JOIN events e ON e.task_id = t.id
+                    AND e.kind = 'task_status_changed'
+                    AND json_extract(e.details, '$.to') = 'done'
+               WHERE t.project_id = $1 AND t.status = 'done'
+               GROUP BY t.id"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(Self::new(&runs, &done_secs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(
+        task_id: Uuid,
+        task_attempt_id: Uuid,
+        executor: &str,
+        status: ExecutionProcessStatus,
+        duration_ms: i64,
+    ) -> AgentRun {
+        AgentRun {
+            task_id,
+            title: "Fix login".to_string(),
+            task_attempt_id,
+            executor_type: Some(executor.to_string()),
+            status,
+            duration_ms: Some(duration_ms),
+            merged: false,
+        }
+    }
+
+    #[test]
+    fn test_aggregates_runs_per_executor_and_task() {
+        let (first_task, second_task) = (Uuid::new_v4(), Uuid::new_v4());
+        let (first_attempt, second_attempt) = (Uuid::new_v4(), Uuid::new_v4());
+        let runs = vec![
+            run(
+                first_task,
+                first_attempt,
+                "claude",
+                ExecutionProcessStatus::Failed,
+                1_000,
+            ),
+            run(
+                first_task,
+                first_attempt,
+                "claude",
+                ExecutionProcessStatus::Completed,
+                3_000,
+            ),
+            run(
+                first_task,
+                first_attempt,
+                "claude",
+                ExecutionProcessStatus::Killed,
+                500,
+            ),
+            run(
+                second_task,
+                second_attempt,
+                "amp",
+                ExecutionProcessStatus::Completed,
+                10_000,
+            ),
+        ];
+
+        let analytics = ProjectAnalytics::new(&runs, &[Some(60), None, Some(180), Some(100)]);
+        assert_eq!(analytics.tasks_done, 4);
+        assert_eq!(analytics.median_time_to_done_secs, Some(100));
+        assert_eq!(analytics.agent_time_ms, 14_500);
+        assert_eq!(analytics.follow_ups_per_task, Some(1.0));
+        assert_eq!(analytics.tasks[0].task_id, second_task);
+
+        let claude = &analytics.executors[1];
+        assert_eq!(claude.executor, "claude");
+        assert_eq!((claude.completed, claude.failed, claude.killed), (1, 1, 1));
+        assert_eq!(claude.success_rate, Some(0.5));
+        assert_eq!(claude.median_duration_ms, Some(1_000));
+        assert_eq!(claude.follow_ups_per_attempt, 2.0);
+    }
+}
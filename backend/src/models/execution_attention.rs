@@ -0,0 +1,146 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// What an execution is waiting on the user for
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum AttentionKind {
+    /// A dangerous command is held until the user allows or denies it
+    CommandApproval,
+    /// A planning run finished and its plan is waiting to be approved
+    PlanReview,
+}
+
+/// An execution waiting on the user, open until they answer
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct ExecutionAttention {
+    pub id: Uuid,
+    pub execution_process_id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub kind: AttentionKind,
+    pub title: String,
+    pub message: String,
+    pub requested_at: DateTime<Utc>,
+    /// When the user was notified again for taking too long to answer
+    pub escalated_at: Option<DateTime<Utc>>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+impl ExecutionAttention {
+    pub async fn create(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+        task_attempt_id: Uuid,
+        kind: AttentionKind,
+        title: &str,
+        message: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        db::query_as!(
+            ExecutionAttention,
+            r#"INSERT INTO execution_attention (id, execution_process_id, task_attempt_id, kind, title, message)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid", execution_process_id as "execution_process_id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", kind as "kind!: AttentionKind", title, message, requested_at as "requested_at!: DateTime<Utc>", escalated_at as "escalated_at: DateTime<Utc>", resolved_at as "resolved_at: DateTime<Utc>""#,
+            id,
+            execution_process_id,
+            task_attempt_id,
+            kind,
+            title,
+            message
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Everything still waiting on the user, oldest first
+    pub async fn find_open(pool: &DbPool) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            ExecutionAttention,
+            r#"SELECT id as "id!: Uuid", execution_process_id as "execution_process_id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", kind as "kind!: AttentionKind", title, message, requested_at as "requested_at!: DateTime<Utc>", escalated_at as "escalated_at: DateTime<Utc>", resolved_at as "resolved_at: DateTime<Utc>"
+               FROM execution_attention
+               WHERE resolved_at IS NULL
+               ORDER BY requested_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_open_by_task_attempt_id(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            ExecutionAttention,
+            r#"SELECT id as "id!: Uuid", execution_process_id as "execution_process_id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", kind as "kind!: AttentionKind", title, message, requested_at as "requested_at!: DateTime<Utc>", escalated_at as "escalated_at: DateTime<Utc>", resolved_at as "resolved_at: DateTime<Utc>"
+               FROM execution_attention
+               WHERE task_attempt_id = $1 AND resolved_at IS NULL
+               ORDER BY requested_at ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Open attention requested at or before `requested_before` that hasn't been escalated
+    pub async fn find_unescalated(
+        pool: &DbPool,
+        requested_before: DateTime<Utc>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            ExecutionAttention,
+            r#"SELECT id as "id!: Uuid", execution_process_id as "execution_process_id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", kind as "kind!: AttentionKind", title, message, requested_at as "requested_at!: DateTime<Utc>", escalated_at as "escalated_at: DateTime<Utc>", resolved_at as "resolved_at: DateTime<Utc>"
+               FROM execution_attention
+               WHERE resolved_at IS NULL AND escalated_at IS NULL AND requested_at <= $1
+               ORDER BY requested_at ASC"#,
+            requested_before
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn mark_escalated(pool: &DbPool, id: Uuid) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE execution_attention SET escalated_at = datetime('now', 'subsec') WHERE id = $1",
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn resolve(pool: &DbPool, id: Uuid) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE execution_attention SET resolved_at = datetime('now', 'subsec')
+             WHERE id = $1 AND resolved_at IS NULL",
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Close what the attempt was waiting on the user for of `kind`, once they've answered
+    pub async fn resolve_for_task_attempt(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+        kind: AttentionKind,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE execution_attention SET resolved_at = datetime('now', 'subsec')
+             WHERE task_attempt_id = $1 AND kind = $2 AND resolved_at IS NULL",
+            task_attempt_id,
+            kind
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
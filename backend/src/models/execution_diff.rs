@@ -0,0 +1,244 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task_attempt::{DiffChunkType, DiffHunk, FileHunks};
+use crate::db::{self, DbPool};
+
+/// What one coding agent run changed in its worktree, hunk by hunk
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ExecutionDiff {
+    pub execution_process_id: Uuid,
+    /// HEAD of the worktree when the run started
+    pub from_commit: String,
+    /// HEAD once the run finished and its changes were committed; `None` while it runs
+    pub to_commit: Option<String>,
+    pub files: Vec<FileHunks>,
+    pub summary: DiffSummary,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Line counts of a diff, overall and per file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DiffSummary {
+    pub additions: u32,
+    pub deletions: u32,
+    pub files: Vec<FileDiffSummary>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct FileDiffSummary {
+    pub path: String,
+    pub additions: u32,
+    pub deletions: u32,
+}
+
+impl DiffSummary {
+    pub fn from_files(files: &[FileHunks]) -> Self {
+        let files: Vec<FileDiffSummary> = files
+            .iter()
+            .map(|file| {
+                let lines = file.hunks.iter().flat_map(|hunk| &hunk.lines);
+                let (mut additions, mut deletions) = (0, 0);
+                for line in lines {
+                    match line.chunk_type {
+                        DiffChunkType::Insert => additions += 1,
+                        DiffChunkType::Delete => deletions += 1,
+                        DiffChunkType::Equal => {}
+                    }
+                }
+                FileDiffSummary {
+                    path: file.path.clone(),
+                    additions,
+                    deletions,
+                }
+            })
+            .collect();
+        Self {
+            additions: files.iter().map(|file| file.additions).sum(),
+            deletions: files.iter().map(|file| file.deletions).sum(),
+            files,
+        }
+    }
+}
+
+#[derive(FromRow)]
+struct ExecutionDiffRow {
+    execution_process_id: Uuid,
+    from_commit: String,
+    to_commit: Option<String>,
+    files: Option<String>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<ExecutionDiffRow> for ExecutionDiff {
+    fn from(row: ExecutionDiffRow) -> Self {
+        let files: Vec<FileHunks> = row
+            .files
+            .and_then(|files| serde_json::from_str(&files).ok())
+            .unwrap_or_default();
+        Self {
+            execution_process_id: row.execution_process_id,
+            from_commit: row.from_commit,
+            to_commit: row.to_commit,
+            summary: DiffSummary::from_files(&files),
+            files,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+impl ExecutionDiff {
+    /// The hunk of `path` that shows line `line_number` of the run's version of the file
+    pub fn hunk_at(&self, path: &str, line_number: i64) -> Option<&DiffHunk> {
+        let file = self.files.iter().find(|file| file.path == path)?;
+        file.hunks.iter().find(|hunk| {
+            let start = i64::from(hunk.new_start);
+            let shown = hunk
+                .lines
+                .iter()
+                .filter(|chunk| !matches!(chunk.chunk_type, DiffChunkType::Delete))
+                .count() as i64;
+            (start..start + shown).contains(&line_number)
+        })
+    }
+
+    /// Remember the commit a run starts from, so its changes can be told apart from
+    /// earlier runs' once it finishes
+    pub async fn record_start(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+        from_commit: &str,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            "INSERT INTO execution_diffs (execution_process_id, from_commit) VALUES ($1, $2)
+             ON CONFLICT(execution_process_id) DO NOTHING",
+            execution_process_id,
+            from_commit
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn record_finish(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+        to_commit: &str,
+        files: &[FileHunks],
+    ) -> Result<(), sqlx::Error> {
+        let files = serde_json::to_string(files).unwrap_or_else(|_| "[]".to_string());
+        db::query!(
+            "UPDATE execution_diffs SET to_commit = $1, files = $2, updated_at = datetime('now', 'subsec')
+             WHERE execution_process_id = $3",
+            to_commit,
+            files,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let row = db::query_as!(
+            ExecutionDiffRow,
+            r#"SELECT execution_process_id as "execution_process_id!: Uuid", from_commit, to_commit, files, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_diffs
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(Self::from))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::task_attempt::DiffChunk;
+
+    fn line(chunk_type: DiffChunkType) -> DiffChunk {
+        DiffChunk {
+            chunk_type,
+            content: "x\n".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_summary_counts_lines_per_file() {
+        let files = vec![
+            FileHunks {
+                path: "a.rs".to_string(),
+                hunks: vec![DiffHunk {
+                    index: 0,
+                    header: "@@ -1,2 +1,3 @@".to_string(),
+                    old_start: 1,
+                    new_start: 1,
+                    lines: vec![
+                        line(DiffChunkType::Equal),
+                        line(DiffChunkType::Delete),
+                        line(DiffChunkType::Insert),
+                        line(DiffChunkType::Insert),
+                    ],
+                }],
+            },
+            FileHunks {
+                path: "b.rs".to_string(),
+                hunks: vec![],
+            },
+        ];
+
+        let summary = DiffSummary::from_files(&files);
+        assert_eq!(summary.additions, 2);
+        assert_eq!(summary.deletions, 1);
+        assert_eq!(summary.files.len(), 2);
+        assert_eq!(summary.files[1].additions, 0);
+    }
+
+    #[test]
+    fn test_hunk_at_finds_lines_of_the_new_file() {
+        let hunk = |index, new_start| DiffHunk {
+            index,
+            header: String::new(),
+            old_start: new_start,
+            new_start,
+            lines: vec![
+                line(DiffChunkType::Equal),
+                line(DiffChunkType::Delete),
+                line(DiffChunkType::Insert),
+            ],
+        };
+        let diff = ExecutionDiff::from(ExecutionDiffRow {
+            execution_process_id: Uuid::new_v4(),
+            from_commit: "a".to_string(),
+            to_commit: Some("b".to_string()),
+            files: Some(
+                serde_json::to_string(&[FileHunks {
+                    path: "a.rs".to_string(),
+                    hunks: vec![hunk(0, 3), hunk(1, 20)],
+                }])
+                .unwrap(),
+            ),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        });
+
+        assert_eq!(diff.hunk_at("a.rs", 4).map(|hunk| hunk.index), Some(0));
+        assert_eq!(diff.hunk_at("a.rs", 20).map(|hunk| hunk.index), Some(1));
+        assert!(diff.hunk_at("a.rs", 5).is_none());
+        assert!(diff.hunk_at("b.rs", 3).is_none());
+    }
+}
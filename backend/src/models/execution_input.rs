@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// A message the user sent to a running coding agent
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ExecutionInput {
+    pub id: Uuid,
+    pub execution_process_id: Uuid,
+    pub content: String,
+    pub entry_index: i64, // The entries of the run's output there were when it was sent
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct SendExecutionInput {
+    pub content: String,
+}
+
+impl ExecutionInput {
+    pub async fn create(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+        content: &str,
+        entry_index: i64,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        db::query_as!(
+            ExecutionInput,
+            r#"INSERT INTO execution_inputs (id, execution_process_id, content, entry_index)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid", execution_process_id as "execution_process_id!: Uuid", content, entry_index as "entry_index!: i64", created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            execution_process_id,
+            content,
+            entry_index
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// The messages sent to a run, in the order they were sent
+    pub async fn find_by_execution_process_id(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            ExecutionInput,
+            r#"SELECT id as "id!: Uuid", execution_process_id as "execution_process_id!: Uuid", content, entry_index as "entry_index!: i64", created_at as "created_at!: DateTime<Utc>"
+               FROM execution_inputs
+               WHERE execution_process_id = $1
+               ORDER BY created_at ASC"#,
+            execution_process_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
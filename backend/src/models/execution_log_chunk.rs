@@ -0,0 +1,189 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, Hash, TS)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+/// A piece of an execution's output, in the order it was appended. A marker has no content
+/// of its own and stands in for the chunks trimmed from the middle of a long log.
+#[derive(Debug, Clone, FromRow)]
+pub struct ExecutionLogChunk {
+    pub content: String,
+    pub size: i64,
+    pub omitted_bytes: i64,
+}
+
+/// What trimming needs to know of a chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromRow)]
+pub struct ChunkSize {
+    pub id: i64,
+    pub size: i64,
+    pub omitted_bytes: i64,
+}
+
+/// What timing a stream's entries needs to know of a chunk: where it falls in the stream as
+/// it is read back, and when it was received
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromRow)]
+pub struct ChunkTiming {
+    pub size: i64,
+    pub omitted_bytes: i64,
+    pub ends_with_newline: bool,
+    pub received_at: Option<DateTime<Utc>>,
+}
+
+impl ExecutionLogChunk {
+    pub fn is_marker(&self) -> bool {
+        self.omitted_bytes > 0
+    }
+
+    pub async fn append(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+        stream: LogStream,
+        content: &str,
+        received_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        let size = content.len() as i64;
+        db::query!(
+            "INSERT INTO execution_log_chunks (execution_process_id, stream, content, size, received_at) VALUES ($1, $2, $3, $4, $5)",
+            execution_process_id,
+            stream,
+            content,
+            size,
+            received_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The stream's chunks, in order
+    pub async fn find_by_execution_process_id(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+        stream: LogStream,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            ExecutionLogChunk,
+            r#"SELECT content, size, omitted_bytes
+               FROM execution_log_chunks
+               WHERE execution_process_id = $1 AND stream = $2
+               ORDER BY id ASC"#,
+            execution_process_id,
+            stream
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Bytes of output the stream keeps, not counting what was trimmed
+    pub async fn total_size(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+        stream: LogStream,
+    ) -> Result<i64, sqlx::Error> {
+        db::query_scalar!(
+            r#"SELECT CAST(COALESCE(SUM(size), 0) AS BIGINT) as "total!: i64"
+               FROM execution_log_chunks
+               WHERE execution_process_id = $1 AND stream = $2"#,
+            execution_process_id,
+            stream
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// The timing of the stream's chunks, in order
+    pub async fn find_timings(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+        stream: LogStream,
+    ) -> Result<Vec<ChunkTiming>, sqlx::Error> {
+        db::query_as!(
+            ChunkTiming,
+            r#"SELECT size, omitted_bytes, substr(content, length(content)) = char(10) as "ends_with_newline!: bool", received_at as "received_at: DateTime<Utc>"
+               FROM execution_log_chunks
+               WHERE execution_process_id = $1 AND stream = $2
+               ORDER BY id ASC"#,
+            execution_process_id,
+            stream
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_sizes(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+        stream: LogStream,
+    ) -> Result<Vec<ChunkSize>, sqlx::Error> {
+        db::query_as!(
+            ChunkSize,
+            r#"SELECT id as "id!: i64", size, omitted_bytes
+               FROM execution_log_chunks
+               WHERE execution_process_id = $1 AND stream = $2
+               ORDER BY id ASC"#,
+            execution_process_id,
+            stream
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Replace the stream's chunks from `first_id` to `last_id` with a marker for
+    /// `omitted_bytes`, in their place
+    pub async fn replace_with_marker(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+        stream: LogStream,
+        first_id: i64,
+        last_id: i64,
+        omitted_bytes: i64,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        db::query!(
+            "DELETE FROM execution_log_chunks WHERE execution_process_id = $1 AND stream = $2 AND id BETWEEN $3 AND $4",
+            execution_process_id,
+            stream,
+            first_id,
+            last_id
+        )
+        .execute(&mut tx)
+        .await?;
+        // The freed id keeps the marker between the head and the tail
+        db::query!(
+            "INSERT INTO execution_log_chunks (id, execution_process_id, stream, content, size, omitted_bytes) VALUES ($1, $2, $3, '', 0, $4)",
+            first_id,
+            execution_process_id,
+            stream,
+            omitted_bytes
+        )
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await
+    }
+
+    pub async fn delete_by_execution_process_id(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            "DELETE FROM execution_log_chunks WHERE execution_process_id = $1",
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,208 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    db::{self, DbPool},
+    executor::UsageMetrics,
+};
+
+/// Token and cost totals of a task's coding agent runs
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct TaskUsage {
+    pub task_id: Uuid,
+    pub title: String,
+    /// Runs that reported usage
+    pub runs: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    /// `None` if none of the runs reported cost
+    pub cost_usd: Option<f64>,
+}
+
+/// Token and cost totals of the runs that used one model
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct ModelUsage {
+    /// `None` for runs that left the model to the agent
+    pub model: Option<String>,
+    pub runs: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cost_usd: Option<f64>,
+}
+
+/// Token and cost totals of a project, with the tasks that used the most first
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ProjectUsage {
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cost_usd: Option<f64>,
+    pub tasks: Vec<TaskUsage>,
+    pub models: Vec<ModelUsage>,
+}
+
+impl ProjectUsage {
+    pub fn new(tasks: Vec<TaskUsage>, models: Vec<ModelUsage>) -> Self {
+        let cost_usd = tasks
+            .iter()
+            .filter_map(|task| task.cost_usd)
+            .reduce(|a, b| a + b);
+        Self {
+            input_tokens: tasks.iter().map(|task| task.input_tokens).sum(),
+            output_tokens: tasks.iter().map(|task| task.output_tokens).sum(),
+            cost_usd,
+            tasks,
+            models,
+        }
+    }
+}
+
+/// Token and cost totals of an executor's runs, across projects
+#[derive(Debug, Clone, FromRow)]
+pub struct ExecutorUsage {
+    pub executor_type: String,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cost_usd: Option<f64>,
+}
+
+/// Token and cost totals of one attempt's coding agent runs
+#[derive(Debug, Clone, FromRow, Default)]
+pub struct AttemptUsage {
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cost_usd: Option<f64>,
+}
+
+pub struct ExecutionMetrics;
+
+impl ExecutionMetrics {
+    pub async fn record(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+        task_attempt_id: Uuid,
+        usage: &UsageMetrics,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            "INSERT INTO execution_metrics (execution_process_id, task_attempt_id, input_tokens, output_tokens, cost_usd)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT(execution_process_id) DO UPDATE SET input_tokens = excluded.input_tokens, output_tokens = excluded.output_tokens, cost_usd = excluded.cost_usd",
+            execution_process_id,
+            task_attempt_id,
+            usage.input_tokens,
+            usage.output_tokens,
+            usage.cost_usd
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Usage of the attempt's runs; zero when none reported any
+    pub async fn usage_by_attempt(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+    ) -> Result<AttemptUsage, sqlx::Error> {
+        db::query_as!(
+            AttemptUsage,
+            r#"SELECT CAST(COALESCE(SUM(input_tokens), 0) AS BIGINT) as "input_tokens!: i64",
+                      CAST(COALESCE(SUM(output_tokens), 0) AS BIGINT) as "output_tokens!: i64",
+                      SUM(cost_usd) as "cost_usd: f64"
+               FROM execution_metrics
+               WHERE task_attempt_id = $1"#,
+            task_attempt_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Usage of each of the project's tasks that has any, most expensive first
+    pub async fn usage_by_task(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<TaskUsage>, sqlx::Error> {
+        db::query_as!(
+            TaskUsage,
+            r#"SELECT t.id as "task_id!: Uuid",
+                      t.title as "title!",
+                      COUNT(em.execution_process_id) as "runs!: i64",
+                      CAST(COALESCE(SUM(em.input_tokens), 0) AS BIGINT) as "input_tokens!: i64",
+                      CAST(COALESCE(SUM(em.output_tokens), 0) AS BIGINT) as "output_tokens!: i64",
+                      SUM(em.cost_usd) as "cost_usd: f64"
+               FROM execution_metrics em
+               JOIN task_attempts ta ON ta.id = em.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE t.project_id = $1
+               GROUP BY t.id
+               ORDER BY SUM(em.cost_usd) DESC, SUM(em.input_tokens + em.output_tokens) DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Usage of the project's runs per model they ran with, most expensive first
+    pub async fn usage_by_model(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<ModelUsage>, sqlx::Error> {
+        db::query_as!(
+            ModelUsage,
+            r#"SELECT ep.model,
+                      COUNT(em.execution_process_id) as "runs!: i64",
+                      CAST(COALESCE(SUM(em.input_tokens), 0) AS BIGINT) as "input_tokens!: i64",
+                      CAST(COALESCE(SUM(em.output_tokens), 0) AS BIGINT) as "output_tokens!: i64",
+                      SUM(em.cost_usd) as "cost_usd: f64"
+               FROM execution_metrics em
+               JOIN execution_processes ep ON ep.id = em.execution_process_id
+               JOIN task_attempts ta ON ta.id = em.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE t.project_id = $1
+               GROUP BY ep.model
+               ORDER BY SUM(em.cost_usd) DESC, SUM(em.input_tokens + em.output_tokens) DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// What the project's runs recorded since `since` cost, counting runs that didn't
+    /// report cost as free
+    pub async fn project_cost_since(
+        pool: &DbPool,
+        project_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> Result<f64, sqlx::Error> {
+        db::query_scalar!(
+            r#"SELECT COALESCE(SUM(em.cost_usd), 0.0) as "cost_usd!: f64"
+               FROM execution_metrics em
+               JOIN task_attempts ta ON ta.id = em.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE t.project_id = $1 AND datetime(em.created_at) >= datetime($2)"#,
+            project_id,
+            since
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn usage_by_executor(pool: &DbPool) -> Result<Vec<ExecutorUsage>, sqlx::Error> {
+        db::query_as!(
+            ExecutorUsage,
+            r#"SELECT COALESCE(ep.executor_type, 'unknown') as "executor_type!",
+                      CAST(COALESCE(SUM(em.input_tokens), 0) AS BIGINT) as "input_tokens!: i64",
+                      CAST(COALESCE(SUM(em.output_tokens), 0) AS BIGINT) as "output_tokens!: i64",
+                      SUM(em.cost_usd) as "cost_usd: f64"
+               FROM execution_metrics em
+               JOIN execution_processes ep ON ep.id = em.execution_process_id
+               GROUP BY ep.executor_type"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
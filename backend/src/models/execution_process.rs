@@ -1,10 +1,20 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize, Serializer};
-use sqlx::{FromRow, SqlitePool, Type};
+use sqlx::{FromRow, Type};
 use ts_rs::TS;
 use uuid::Uuid;
 
-use crate::app_state::ExecutionType;
+use super::{
+    event::{Event, EventKind},
+    execution_log_chunk::LogStream,
+};
+use crate::{
+    app_state::ExecutionType,
+    db::{self, DbPool},
+    event_bus::{self, BoardEvent},
+    services::log_storage,
+};
 
 /// Filter out stderr boundary markers from output
 fn filter_stderr_boundary_markers(stderr: &Option<String>) -> Option<String> {
@@ -22,8 +32,8 @@ where
     filtered.serialize(serializer)
 }
 
-#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS)]
-#[sqlx(type_name = "execution_process_status", rename_all = "lowercase")]
+#[derive(Debug, Clone, Type, Serialize, Deserialize, JsonSchema, PartialEq, TS)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
 #[ts(export)]
 pub enum ExecutionProcessStatus {
@@ -31,10 +41,90 @@ pub enum ExecutionProcessStatus {
     Completed,
     Failed,
     Killed,
+    /// Killed by the execution monitor for running past its deadline
+    TimedOut,
+    /// Stopped with SIGSTOP until it is resumed; still counts as running
+    Paused,
+    /// Killed for going over the memory it may use
+    ResourceLimitExceeded,
+    /// Stopped by its provider's rate limit; goes on at `retry_at` unless that was cancelled
+    RateLimited,
+    /// Cut off by the server stopping while it ran
+    Interrupted,
 }
 
-#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS)]
-#[sqlx(type_name = "execution_process_type", rename_all = "lowercase")]
+impl ExecutionProcessStatus {
+    /// Whether the process is still alive, running or paused
+    pub fn is_live(&self) -> bool {
+        matches!(self, Self::Running | Self::Paused)
+    }
+}
+
+/// Why a failed execution failed, as far as its output or its start tells
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum FailureReason {
+    /// The agent's CLI, or a command it needed, isn't installed
+    MissingBinary,
+    /// The model provider rejected the agent's API key or login
+    AuthFailure,
+    /// The model provider turned requests away for going over its rate or usage limits
+    RateLimited,
+    /// The conversation outgrew the model's context window
+    ContextLengthExceeded,
+    /// A git merge or rebase stopped on conflicts
+    GitConflict,
+    /// The connection to the model provider dropped
+    NetworkError,
+    /// The process couldn't be started
+    SpawnFailed,
+}
+
+impl FailureReason {
+    pub fn label(self) -> &'static str {
+        match self {
+            FailureReason::MissingBinary => "Missing binary",
+            FailureReason::AuthFailure => "Authentication failed",
+            FailureReason::RateLimited => "Rate limited",
+            FailureReason::ContextLengthExceeded => "Context length exceeded",
+            FailureReason::GitConflict => "Git conflict",
+            FailureReason::NetworkError => "Network error",
+            FailureReason::SpawnFailed => "Couldn't start",
+        }
+    }
+
+    /// What to do about it
+    pub fn remediation(self) -> &'static str {
+        match self {
+            FailureReason::MissingBinary => {
+                "Install the agent's CLI where vibe-kanban can find it on PATH, or choose another agent in Settings."
+            }
+            FailureReason::AuthFailure => {
+                "Log in to the agent's CLI again, or check the API key in the project's environment variables."
+            }
+            FailureReason::RateLimited => {
+                "Wait for the provider's limit to reset, then send a follow-up to carry on."
+            }
+            FailureReason::ContextLengthExceeded => {
+                "Start a new attempt, or split the task into smaller ones, so the conversation fits."
+            }
+            FailureReason::GitConflict => {
+                "Resolve the conflicts in the attempt's worktree, or rebase the attempt onto its base branch again."
+            }
+            FailureReason::NetworkError => {
+                "Check this machine's connection to the provider, then send a follow-up to carry on."
+            }
+            FailureReason::SpawnFailed => {
+                "Check the agent's or script's command and the attempt's worktree, then try again."
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Type, Serialize, Deserialize, JsonSchema, PartialEq, TS)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
 #[ts(export)]
 pub enum ExecutionProcessType {
@@ -81,8 +171,20 @@ pub struct ExecutionProcess {
     #[serde(serialize_with = "serialize_filtered_stderr")]
     pub stderr: Option<String>,
     pub exit_code: Option<i64>,
+    /// How many times a failed start has been retried
+    pub retry_count: i64,
+    /// Set while a failed start waits to be retried
+    pub retry_at: Option<DateTime<Utc>>,
+    /// The worktree's commit once the run finished and its changes were committed
+    pub commit_sha: Option<String>,
+    /// Why the run failed, where that could be told
+    pub failure_reason: Option<FailureReason>,
+    /// The model a coding agent was told to run with; `None` leaves it to the agent
+    pub model: Option<String>,
     pub started_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
+    /// Wall-clock milliseconds from start to finish, pauses included
+    pub duration_ms: Option<i64>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -119,16 +221,83 @@ pub struct ExecutionProcessSummary {
     pub args: Option<String>, // JSON array of arguments
     pub working_directory: String,
     pub exit_code: Option<i64>,
+    /// How many times a failed start has been retried
+    pub retry_count: i64,
+    /// Set while a failed start waits to be retried
+    pub retry_at: Option<DateTime<Utc>>,
+    /// The worktree's commit once the run finished and its changes were committed
+    pub commit_sha: Option<String>,
+    /// Why the run failed, where that could be told
+    pub failure_reason: Option<FailureReason>,
+    /// The model a coding agent was told to run with; `None` leaves it to the agent
+    pub model: Option<String>,
     pub started_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
+    /// Wall-clock milliseconds from start to finish, pauses included
+    pub duration_ms: Option<i64>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Running executions of one type
+#[derive(Debug, Clone, FromRow)]
+pub struct RunningExecutions {
+    pub process_type: String,
+    pub count: i64,
+    /// Seconds since the longest-running of them started
+    pub oldest_seconds: f64,
+    /// Seconds since the quietest of them last wrote any output
+    pub idle_seconds: f64,
+}
+
+/// Finished executions of one type and executor, with how long they ran altogether
+#[derive(Debug, Clone, FromRow)]
+pub struct FinishedExecutions {
+    pub process_type: String,
+    /// Empty for scripts
+    pub executor_type: String,
+    pub count: i64,
+    pub total_seconds: f64,
+}
+
+/// The process group a local process leads, and which process led it, to tell it apart
+/// from a later group given the same id
+#[derive(Debug, Clone, FromRow)]
+pub struct ProcessGroup {
+    pub process_group: i64,
+    pub process_identity: Option<String>,
+}
+
+/// The attempt and type of a process whose status was just updated
+#[derive(FromRow)]
+struct FinishedProcess {
+    task_attempt_id: Uuid,
+    process_type: ExecutionProcessType,
+}
+
 impl ExecutionProcess {
+    /// Fill in the process's output from where it is stored
+    async fn load_output(mut self, pool: &DbPool) -> Result<Self, sqlx::Error> {
+        if let Some(stdout) = log_storage::read(pool, self.id, LogStream::Stdout).await? {
+            self.stdout = Some(stdout);
+        }
+        if let Some(stderr) = log_storage::read(pool, self.id, LogStream::Stderr).await? {
+            self.stderr = Some(stderr);
+        }
+        Ok(self)
+    }
+
+    async fn load_outputs(pool: &DbPool, processes: Vec<Self>) -> Result<Vec<Self>, sqlx::Error> {
+        let mut loaded = Vec::with_capacity(processes.len());
+        for process in processes {
+            loaded.push(process.load_output(pool).await?);
+        }
+        Ok(loaded)
+    }
+
     /// Find execution process by ID
-    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
-        sqlx::query_as!(
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        let process = db::query_as!(
             ExecutionProcess,
             r#"SELECT 
                 id as "id!: Uuid", 
@@ -142,8 +311,14 @@ impl ExecutionProcess {
                 stdout, 
                 stderr, 
                 exit_code,
+                retry_count,
+                retry_at as "retry_at?: DateTime<Utc>",
+                commit_sha,
+                failure_reason as "failure_reason?: FailureReason",
+                model,
                 started_at as "started_at!: DateTime<Utc>",
                 completed_at as "completed_at?: DateTime<Utc>",
+                duration_ms,
                 created_at as "created_at!: DateTime<Utc>", 
                 updated_at as "updated_at!: DateTime<Utc>"
                FROM execution_processes 
@@ -151,15 +326,19 @@ impl ExecutionProcess {
             id
         )
         .fetch_optional(pool)
-        .await
+        .await?;
+        match process {
+            Some(process) => Ok(Some(process.load_output(pool).await?)),
+            None => Ok(None),
+        }
     }
 
     /// Find all execution processes for a task attempt
     pub async fn find_by_task_attempt_id(
-        pool: &SqlitePool,
+        pool: &DbPool,
         task_attempt_id: Uuid,
     ) -> Result<Vec<Self>, sqlx::Error> {
-        sqlx::query_as!(
+        let processes = db::query_as!(
             ExecutionProcess,
             r#"SELECT 
                 id as "id!: Uuid", 
@@ -173,8 +352,14 @@ impl ExecutionProcess {
                 stdout, 
                 stderr, 
                 exit_code,
+                retry_count,
+                retry_at as "retry_at?: DateTime<Utc>",
+                commit_sha,
+                failure_reason as "failure_reason?: FailureReason",
+                model,
                 started_at as "started_at!: DateTime<Utc>",
                 completed_at as "completed_at?: DateTime<Utc>",
+                duration_ms,
                 created_at as "created_at!: DateTime<Utc>", 
                 updated_at as "updated_at!: DateTime<Utc>"
                FROM execution_processes 
@@ -183,15 +368,16 @@ impl ExecutionProcess {
             task_attempt_id
         )
         .fetch_all(pool)
-        .await
+        .await?;
+        Self::load_outputs(pool, processes).await
     }
 
     /// Find execution process summaries for a task attempt (excluding stdio)
     pub async fn find_summaries_by_task_attempt_id(
-        pool: &SqlitePool,
+        pool: &DbPool,
         task_attempt_id: Uuid,
     ) -> Result<Vec<ExecutionProcessSummary>, sqlx::Error> {
-        sqlx::query_as!(
+        db::query_as!(
             ExecutionProcessSummary,
             r#"SELECT 
                 id as "id!: Uuid", 
@@ -203,8 +389,14 @@ impl ExecutionProcess {
                 args, 
                 working_directory, 
                 exit_code,
+                retry_count,
+                retry_at as "retry_at?: DateTime<Utc>",
+                commit_sha,
+                failure_reason as "failure_reason?: FailureReason",
+                model,
                 started_at as "started_at!: DateTime<Utc>",
                 completed_at as "completed_at?: DateTime<Utc>",
+                duration_ms,
                 created_at as "created_at!: DateTime<Utc>", 
                 updated_at as "updated_at!: DateTime<Utc>"
                FROM execution_processes 
@@ -216,9 +408,90 @@ impl ExecutionProcess {
         .await
     }
 
+    /// Find every coding agent process across all attempts of a project
+    pub async fn find_coding_agents_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let processes = db::query_as!(
+            ExecutionProcess,
+            r#"SELECT 
+                ep.id as "id!: Uuid", 
+                ep.task_attempt_id as "task_attempt_id!: Uuid", 
+                ep.process_type as "process_type!: ExecutionProcessType",
+                ep.executor_type,
+                ep.status as "status!: ExecutionProcessStatus",
+                ep.command, 
+                ep.args, 
+                ep.working_directory, 
+                ep.stdout, 
+                ep.stderr, 
+                ep.exit_code,
+                ep.retry_count,
+                ep.retry_at as "retry_at?: DateTime<Utc>",
+                ep.commit_sha,
+                ep.failure_reason as "failure_reason?: FailureReason",
+                ep.model,
+                ep.started_at as "started_at!: DateTime<Utc>",
+                ep.completed_at as "completed_at?: DateTime<Utc>",
+                ep.duration_ms,
+                ep.created_at as "created_at!: DateTime<Utc>", 
+                ep.updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_processes ep
+               JOIN task_attempts ta ON ep.task_attempt_id = ta.id
+               JOIN tasks t ON ta.task_id = t.id
+               WHERE t.project_id = $1 AND ep.process_type = 'codingagent'
+               ORDER BY ep.created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Self::load_outputs(pool, processes).await
+    }
+
+    /// Find finished coding agent processes whose conversations aren't in the search index yet
+    pub async fn find_coding_agents_missing_from_search_index(
+        pool: &DbPool,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let processes = db::query_as!(
+            ExecutionProcess,
+            r#"SELECT 
+                ep.id as "id!: Uuid", 
+                ep.task_attempt_id as "task_attempt_id!: Uuid", 
+                ep.process_type as "process_type!: ExecutionProcessType",
+                ep.executor_type,
+                ep.status as "status!: ExecutionProcessStatus",
+                ep.command, 
+                ep.args, 
+                ep.working_directory, 
+                ep.stdout, 
+                ep.stderr, 
+                ep.exit_code,
+                ep.retry_count,
+                ep.retry_at as "retry_at?: DateTime<Utc>",
+                ep.commit_sha,
+                ep.failure_reason as "failure_reason?: FailureReason",
+                ep.model,
+                ep.started_at as "started_at!: DateTime<Utc>",
+                ep.completed_at as "completed_at?: DateTime<Utc>",
+                ep.duration_ms,
+                ep.created_at as "created_at!: DateTime<Utc>", 
+                ep.updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_processes ep
+               LEFT JOIN search_index_state s ON s.execution_process_id = ep.id
+               WHERE ep.process_type = 'codingagent'
+               AND ep.status NOT IN ('running', 'paused')
+               AND s.execution_process_id IS NULL
+               ORDER BY ep.created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await?;
+        Self::load_outputs(pool, processes).await
+    }
+
     /// Find running execution processes
-    pub async fn find_running(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
-        sqlx::query_as!(
+    pub async fn find_running(pool: &DbPool) -> Result<Vec<Self>, sqlx::Error> {
+        let processes = db::query_as!(
             ExecutionProcess,
             r#"SELECT 
                 id as "id!: Uuid", 
@@ -232,24 +505,75 @@ impl ExecutionProcess {
                 stdout, 
                 stderr, 
                 exit_code,
+                retry_count,
+                retry_at as "retry_at?: DateTime<Utc>",
+                commit_sha,
+                failure_reason as "failure_reason?: FailureReason",
+                model,
                 started_at as "started_at!: DateTime<Utc>",
                 completed_at as "completed_at?: DateTime<Utc>",
+                duration_ms,
                 created_at as "created_at!: DateTime<Utc>", 
                 updated_at as "updated_at!: DateTime<Utc>"
                FROM execution_processes 
-               WHERE status = 'running' 
+               WHERE status IN ('running', 'paused')
                ORDER BY created_at ASC"#
         )
         .fetch_all(pool)
+        .await?;
+        Self::load_outputs(pool, processes).await
+    }
+
+    /// The project the execution ran for
+    pub async fn find_project_id(pool: &DbPool, id: Uuid) -> Result<Option<Uuid>, sqlx::Error> {
+        db::query_scalar!(
+            r#"SELECT t.project_id as "project_id!: Uuid"
+               FROM execution_processes ep
+               JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE ep.id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn summarize_running(pool: &DbPool) -> Result<Vec<RunningExecutions>, sqlx::Error> {
+        db::query_as!(
+            RunningExecutions,
+            r#"SELECT process_type as "process_type!",
+                      COUNT(*) as "count!: i64",
+                      MAX((julianday('now') - julianday(started_at)) * 86400.0) as "oldest_seconds!: f64",
+                      MAX((julianday('now') - julianday(updated_at)) * 86400.0) as "idle_seconds!: f64"
+               FROM execution_processes
+               WHERE status = 'running'
+               GROUP BY process_type"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn summarize_finished(pool: &DbPool) -> Result<Vec<FinishedExecutions>, sqlx::Error> {
+        db::query_as!(
+            FinishedExecutions,
+            r#"SELECT process_type as "process_type!",
+                      COALESCE(executor_type, '') as "executor_type!",
+                      COUNT(*) as "count!: i64",
+                      COALESCE(SUM((julianday(completed_at) - julianday(started_at)) * 86400.0), 0.0) as "total_seconds!: f64"
+               FROM execution_processes
+               WHERE completed_at IS NOT NULL
+               GROUP BY process_type, executor_type"#
+        )
+        .fetch_all(pool)
         .await
     }
 
     /// Find running dev servers for a specific project
     pub async fn find_running_dev_servers_by_project(
-        pool: &SqlitePool,
+        pool: &DbPool,
         project_id: Uuid,
     ) -> Result<Vec<Self>, sqlx::Error> {
-        sqlx::query_as!(
+        let processes = db::query_as!(
             ExecutionProcess,
             r#"SELECT 
                 ep.id as "id!: Uuid", 
@@ -263,32 +587,121 @@ impl ExecutionProcess {
                 ep.stdout, 
                 ep.stderr, 
                 ep.exit_code,
+                ep.retry_count,
+                ep.retry_at as "retry_at?: DateTime<Utc>",
+                ep.commit_sha,
+                ep.failure_reason as "failure_reason?: FailureReason",
+                ep.model,
                 ep.started_at as "started_at!: DateTime<Utc>",
                 ep.completed_at as "completed_at?: DateTime<Utc>",
+                ep.duration_ms,
                 ep.created_at as "created_at!: DateTime<Utc>", 
                 ep.updated_at as "updated_at!: DateTime<Utc>"
                FROM execution_processes ep
                JOIN task_attempts ta ON ep.task_attempt_id = ta.id
                JOIN tasks t ON ta.task_id = t.id
-               WHERE ep.status = 'running' 
+               WHERE ep.status IN ('running', 'paused')
                AND ep.process_type = 'devserver'
                AND t.project_id = $1
                ORDER BY ep.created_at ASC"#,
             project_id
         )
         .fetch_all(pool)
-        .await
+        .await?;
+        Self::load_outputs(pool, processes).await
+    }
+
+    /// Find the running dev servers of a task attempt
+    pub async fn find_running_dev_servers_by_task_attempt_id(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let processes = db::query_as!(
+            ExecutionProcess,
+            r#"SELECT 
+                ep.id as "id!: Uuid", 
+                ep.task_attempt_id as "task_attempt_id!: Uuid", 
+                ep.process_type as "process_type!: ExecutionProcessType",
+                ep.executor_type,
+                ep.status as "status!: ExecutionProcessStatus",
+                ep.command, 
+                ep.args, 
+                ep.working_directory, 
+                ep.stdout, 
+                ep.stderr, 
+                ep.exit_code,
+                ep.retry_count,
+                ep.retry_at as "retry_at?: DateTime<Utc>",
+                ep.commit_sha,
+                ep.failure_reason as "failure_reason?: FailureReason",
+                ep.model,
+                ep.started_at as "started_at!: DateTime<Utc>",
+                ep.completed_at as "completed_at?: DateTime<Utc>",
+                ep.duration_ms,
+                ep.created_at as "created_at!: DateTime<Utc>", 
+                ep.updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_processes ep
+               WHERE ep.status IN ('running', 'paused')
+               AND ep.process_type = 'devserver'
+               AND ep.task_attempt_id = $1
+               ORDER BY ep.created_at ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Self::load_outputs(pool, processes).await
+    }
+
+    /// Find running dev servers whose task is done or cancelled
+    pub async fn find_running_dev_servers_of_finished_tasks(
+        pool: &DbPool,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let processes = db::query_as!(
+            ExecutionProcess,
+            r#"SELECT 
+                ep.id as "id!: Uuid", 
+                ep.task_attempt_id as "task_attempt_id!: Uuid", 
+                ep.process_type as "process_type!: ExecutionProcessType",
+                ep.executor_type,
+                ep.status as "status!: ExecutionProcessStatus",
+                ep.command, 
+                ep.args, 
+                ep.working_directory, 
+                ep.stdout, 
+                ep.stderr, 
+                ep.exit_code,
+                ep.retry_count,
+                ep.retry_at as "retry_at?: DateTime<Utc>",
+                ep.commit_sha,
+                ep.failure_reason as "failure_reason?: FailureReason",
+                ep.model,
+                ep.started_at as "started_at!: DateTime<Utc>",
+                ep.completed_at as "completed_at?: DateTime<Utc>",
+                ep.duration_ms,
+                ep.created_at as "created_at!: DateTime<Utc>", 
+                ep.updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_processes ep
+               JOIN task_attempts ta ON ep.task_attempt_id = ta.id
+               JOIN tasks t ON ta.task_id = t.id
+               WHERE ep.status IN ('running', 'paused')
+               AND ep.process_type = 'devserver'
+               AND t.status IN ('done', 'cancelled')
+               ORDER BY ep.created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await?;
+        Self::load_outputs(pool, processes).await
     }
 
     /// Create a new execution process
     pub async fn create(
-        pool: &SqlitePool,
+        pool: &DbPool,
         data: &CreateExecutionProcess,
         process_id: Uuid,
     ) -> Result<Self, sqlx::Error> {
         let now = Utc::now();
 
-        sqlx::query_as!(
+        let process = db::query_as!(
             ExecutionProcess,
             r#"INSERT INTO execution_processes (
                 id, task_attempt_id, process_type, executor_type, status, command, args, 
@@ -308,8 +721,14 @@ impl ExecutionProcess {
                 stdout, 
                 stderr, 
                 exit_code,
+                retry_count,
+                retry_at as "retry_at?: DateTime<Utc>",
+                commit_sha,
+                failure_reason as "failure_reason?: FailureReason",
+                model,
                 started_at as "started_at!: DateTime<Utc>",
                 completed_at as "completed_at?: DateTime<Utc>",
+                duration_ms,
                 created_at as "created_at!: DateTime<Utc>", 
                 updated_at as "updated_at!: DateTime<Utc>""#,
             process_id,
@@ -329,12 +748,172 @@ impl ExecutionProcess {
             now                    // updated_at
         )
         .fetch_one(pool)
+        .await?;
+
+        let event = Event::record_for_attempt(
+            pool,
+            process.task_attempt_id,
+            Some(process.id),
+            EventKind::ExecutionStarted,
+            serde_json::json!({
+                "process_type": process.process_type,
+                "executor_type": process.executor_type,
+            }),
+        )
+        .await?;
+        if let Some(Event {
+            project_id,
+            task_id: Some(task_id),
+            ..
+        }) = event
+        {
+            event_bus::publish(BoardEvent::ExecutionStarted {
+                project_id,
+                task_id,
+                task_attempt_id: process.task_attempt_id,
+                execution_process_id: process.id,
+                process_type: process.process_type.clone(),
+            });
+        }
+        Ok(process)
+    }
+
+    /// Re-create a process from another machine's export under `task_attempt_id`, with a new
+    /// id. A process that was still alive when it was exported is recorded as killed.
+    pub async fn create_imported(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+        process: &ExecutionProcess,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let status = if process.status.is_live() {
+            ExecutionProcessStatus::Killed
+        } else {
+            process.status.clone()
+        };
+        let completed_at = process.completed_at.or(Some(process.updated_at));
+        let duration_ms = process.duration_ms.or_else(|| {
+            completed_at.map(|completed_at| (completed_at - process.started_at).num_milliseconds())
+        });
+        let imported = db::query_as!(
+            ExecutionProcess,
+            r#"INSERT INTO execution_processes (
+                id, task_attempt_id, process_type, executor_type, status, command, args,
+                working_directory, exit_code, commit_sha, failure_reason, model, started_at,
+                completed_at, duration_ms, created_at, updated_at
+               )
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+               RETURNING
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                process_type as "process_type!: ExecutionProcessType",
+                executor_type,
+                status as "status!: ExecutionProcessStatus",
+                command,
+                args,
+                working_directory,
+                stdout,
+                stderr,
+                exit_code,
+                retry_count,
+                retry_at as "retry_at?: DateTime<Utc>",
+                commit_sha,
+                failure_reason as "failure_reason?: FailureReason",
+                model,
+                started_at as "started_at!: DateTime<Utc>",
+                completed_at as "completed_at?: DateTime<Utc>",
+                duration_ms,
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            task_attempt_id,
+            process.process_type,
+            process.executor_type,
+            status,
+            process.command,
+            process.args,
+            process.working_directory,
+            process.exit_code,
+            process.commit_sha,
+            process.failure_reason,
+            process.model,
+            process.started_at,
+            completed_at,
+            duration_ms,
+            process.created_at,
+            process.updated_at
+        )
+        .fetch_one(pool)
+        .await?;
+
+        for (stream, output) in [
+            (LogStream::Stdout, &process.stdout),
+            (LogStream::Stderr, &process.stderr),
+        ] {
+            if let Some(output) = output.as_deref().filter(|output| !output.is_empty()) {
+                // When the imported output was received isn't known
+                log_storage::append(pool, id, stream, output, None).await?;
+            }
+        }
+        imported.load_output(pool).await
+    }
+
+    /// Mark a process that is still alive as paused, or as running again. A process that
+    /// has finished meanwhile keeps its final status.
+    pub async fn set_paused(pool: &DbPool, id: Uuid, paused: bool) -> Result<(), sqlx::Error> {
+        let status = if paused {
+            ExecutionProcessStatus::Paused
+        } else {
+            ExecutionProcessStatus::Running
+        };
+        db::query!(
+            r#"UPDATE execution_processes
+               SET status = $1, updated_at = datetime('now')
+               WHERE id = $2 AND status IN ('running', 'paused')"#,
+            status,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Keep the process group a local process leads, to find what's left of it after a restart
+    pub async fn set_process_group(
+        pool: &DbPool,
+        id: Uuid,
+        process_group: i64,
+        process_identity: Option<String>,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE execution_processes SET process_group = $1, process_identity = $2 WHERE id = $3",
+            process_group,
+            process_identity,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_process_group(
+        pool: &DbPool,
+        id: Uuid,
+    ) -> Result<Option<ProcessGroup>, sqlx::Error> {
+        db::query_as!(
+            ProcessGroup,
+            r#"SELECT process_group as "process_group!: i64", process_identity
+               FROM execution_processes
+               WHERE id = $1 AND process_group IS NOT NULL"#,
+            id
+        )
+        .fetch_optional(pool)
         .await
     }
 
     /// Update execution process status and completion info
     pub async fn update_completion(
-        pool: &SqlitePool,
+        pool: &DbPool,
         id: Uuid,
         status: ExecutionProcessStatus,
         exit_code: Option<i64>,
@@ -345,58 +924,347 @@ impl ExecutionProcess {
             Some(Utc::now())
         };
 
-        sqlx::query!(
+        let updated = db::query_as!(
+            FinishedProcess,
             r#"UPDATE execution_processes 
-               SET status = $1, exit_code = $2, completed_at = $3, updated_at = datetime('now') 
-               WHERE id = $4"#,
+               SET status = $1, exit_code = $2, completed_at = $3,
+                   duration_ms = CAST(ROUND((julianday($3) - julianday(started_at)) * 86400000.0) AS INTEGER),
+                   updated_at = datetime('now')
+               WHERE id = $4
+               RETURNING task_attempt_id as "task_attempt_id!: Uuid", process_type as "process_type!: ExecutionProcessType""#,
             status,
             exit_code,
             completed_at,
             id
         )
+        .fetch_optional(pool)
+        .await?;
+        if !status.is_live() {
+            log_storage::forget(id);
+        }
+
+        let kind = match status {
+            ExecutionProcessStatus::Completed => EventKind::ExecutionCompleted,
+            ExecutionProcessStatus::Failed
+            | ExecutionProcessStatus::TimedOut
+            | ExecutionProcessStatus::ResourceLimitExceeded
+            | ExecutionProcessStatus::RateLimited => EventKind::ExecutionFailed,
+            ExecutionProcessStatus::Killed | ExecutionProcessStatus::Interrupted => {
+                EventKind::ExecutionStopped
+            }
+            ExecutionProcessStatus::Running | ExecutionProcessStatus::Paused => return Ok(()),
+        };
+        if let Some(updated) = updated {
+            let event = Event::record_for_attempt(
+                pool,
+                updated.task_attempt_id,
+                Some(id),
+                kind,
+                serde_json::json!({
+                    "process_type": updated.process_type,
+                    "status": status,
+                    "exit_code": exit_code,
+                }),
+            )
+            .await?;
+            if let Some(Event {
+                project_id,
+                task_id: Some(task_id),
+                ..
+            }) = event
+            {
+                event_bus::publish(BoardEvent::ExecutionFinished {
+                    project_id,
+                    task_id,
+                    task_attempt_id: updated.task_attempt_id,
+                    execution_process_id: id,
+                    status,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Note the commit the worktree was left on once the run's changes were committed
+    pub async fn set_commit_sha(
+        pool: &DbPool,
+        id: Uuid,
+        commit_sha: &str,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE execution_processes SET commit_sha = $1, updated_at = datetime('now') WHERE id = $2",
+            commit_sha,
+            id
+        )
         .execute(pool)
         .await?;
+        Ok(())
+    }
+
+    /// Note why the run failed
+    pub async fn set_failure_reason(
+        pool: &DbPool,
+        id: Uuid,
+        failure_reason: FailureReason,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE execution_processes SET failure_reason = $1, updated_at = datetime('now') WHERE id = $2",
+            failure_reason,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
 
+    /// Note the model the coding agent was told to run with
+    pub async fn set_model(pool: &DbPool, id: Uuid, model: &str) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE execution_processes SET model = $1, updated_at = datetime('now') WHERE id = $2",
+            model,
+            id
+        )
+        .execute(pool)
+        .await?;
         Ok(())
     }
 
-    /// Append to stdout for this execution process (for streaming updates)
+    /// Record the execution as failed and waiting to be started again at `retry_at`
+    pub async fn schedule_retry(
+        pool: &DbPool,
+        id: Uuid,
+        retry_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        let completed_at = Utc::now();
+        db::query!(
+            r#"UPDATE execution_processes
+               SET status = 'failed', completed_at = $1, retry_at = $2,
+                   duration_ms = CAST(ROUND((julianday($1) - julianday(started_at)) * 86400000.0) AS INTEGER),
+                   updated_at = datetime('now')
+               WHERE id = $3"#,
+            completed_at,
+            retry_at,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Failed executions whose retry is due
+    pub async fn find_due_retries(pool: &DbPool) -> Result<Vec<Self>, sqlx::Error> {
+        let now = Utc::now();
+        let processes = db::query_as!(
+            ExecutionProcess,
+            r#"SELECT
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                process_type as "process_type!: ExecutionProcessType",
+                executor_type,
+                status as "status!: ExecutionProcessStatus",
+                command,
+                args,
+                working_directory,
+                stdout,
+                stderr,
+                exit_code,
+                retry_count,
+                retry_at as "retry_at?: DateTime<Utc>",
+                commit_sha,
+                failure_reason as "failure_reason?: FailureReason",
+                model,
+                started_at as "started_at!: DateTime<Utc>",
+                completed_at as "completed_at?: DateTime<Utc>",
+                duration_ms,
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_processes
+               WHERE status = 'failed' AND retry_at IS NOT NULL AND retry_at <= $1
+               ORDER BY retry_at ASC"#,
+            now
+        )
+        .fetch_all(pool)
+        .await?;
+        Self::load_outputs(pool, processes).await
+    }
+
+    /// Record a failed execution as rate limited, going on at `retry_at`
+    pub async fn schedule_rate_limit_retry(
+        pool: &DbPool,
+        id: Uuid,
+        retry_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            r#"UPDATE execution_processes
+               SET status = 'ratelimited', retry_at = $1, updated_at = datetime('now')
+               WHERE id = $2"#,
+            retry_at,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Rate-limited executions whose provider's limit should have reset
+    pub async fn find_due_rate_limit_retries(pool: &DbPool) -> Result<Vec<Self>, sqlx::Error> {
+        let now = Utc::now();
+        let processes = db::query_as!(
+            ExecutionProcess,
+            r#"SELECT
+                id as "id!: Uuid",
+                task_attempt_id as "task_attempt_id!: Uuid",
+                process_type as "process_type!: ExecutionProcessType",
+                executor_type,
+                status as "status!: ExecutionProcessStatus",
+                command,
+                args,
+                working_directory,
+                stdout,
+                stderr,
+                exit_code,
+                retry_count,
+                retry_at as "retry_at?: DateTime<Utc>",
+                commit_sha,
+                failure_reason as "failure_reason?: FailureReason",
+                model,
+                started_at as "started_at!: DateTime<Utc>",
+                completed_at as "completed_at?: DateTime<Utc>",
+                duration_ms,
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_processes
+               WHERE status = 'ratelimited' AND retry_at IS NOT NULL AND retry_at <= $1
+               ORDER BY retry_at ASC"#,
+            now
+        )
+        .fetch_all(pool)
+        .await?;
+        Self::load_outputs(pool, processes).await
+    }
+
+    /// Executors with rate-limited runs still waiting for their provider's limit to reset
+    pub async fn find_cooling_down_executors(pool: &DbPool) -> Result<Vec<String>, sqlx::Error> {
+        let now = Utc::now();
+        db::query_scalar!(
+            r#"SELECT DISTINCT executor_type as "executor_type!"
+               FROM execution_processes
+               WHERE status = 'ratelimited' AND executor_type IS NOT NULL AND retry_at > $1"#,
+            now
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// How many of an attempt's runs its provider's rate limit has stopped
+    pub async fn count_rate_limited(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+    ) -> Result<i64, sqlx::Error> {
+        db::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64" FROM execution_processes
+               WHERE task_attempt_id = $1 AND status = 'ratelimited'"#,
+            task_attempt_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Take a rate-limited execution off the wait once it has gone on in a follow-up
+    pub async fn clear_retry(pool: &DbPool, id: Uuid) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE execution_processes SET retry_at = NULL WHERE id = $1",
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Reset a failed execution for its next start, dropping the failed run's output
+    pub async fn restart_for_retry(pool: &DbPool, id: Uuid) -> Result<(), sqlx::Error> {
+        let started_at = Utc::now();
+        db::query!(
+            r#"UPDATE execution_processes
+               SET status = 'running', retry_count = retry_count + 1, retry_at = NULL,
+                   stdout = NULL, stderr = NULL, exit_code = NULL, failure_reason = NULL,
+                   started_at = $1,
+                   completed_at = NULL, duration_ms = NULL, updated_at = datetime('now')
+               WHERE id = $2"#,
+            started_at,
+            id
+        )
+        .execute(pool)
+        .await?;
+        log_storage::clear(pool, id).await?;
+
+        Ok(())
+    }
+
+    /// Drop the pending retries of an attempt's executions, leaving them failed. Returns
+    /// how many were cancelled.
+    pub async fn cancel_retries_for_attempt(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = db::query!(
+            "UPDATE execution_processes SET retry_at = NULL WHERE task_attempt_id = $1 AND retry_at IS NOT NULL",
+            task_attempt_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Append to stdout for this execution process (for streaming updates), as received now
     pub async fn append_stdout(
-        pool: &SqlitePool,
+        pool: &DbPool,
         id: Uuid,
         stdout_append: &str,
     ) -> Result<(), sqlx::Error> {
-        sqlx::query!(
-            "UPDATE execution_processes SET stdout = COALESCE(stdout, '') || $1, updated_at = datetime('now') WHERE id = $2",
-            stdout_append,
+        let stdout_append = crate::services::environment_variables::redact(id, stdout_append);
+        let stdout_append = crate::services::log_redaction::redact(&stdout_append);
+        let stdout_append = stdout_append.as_ref();
+        log_storage::append(pool, id, LogStream::Stdout, stdout_append, Some(Utc::now())).await?;
+        db::query!(
+            "UPDATE execution_processes SET updated_at = datetime('now') WHERE id = $1",
             id
         )
         .execute(pool)
         .await?;
+        crate::command_runner::notify_output(id);
 
         Ok(())
     }
 
-    /// Append to stderr for this execution process (for streaming updates)
+    /// Append to stderr for this execution process (for streaming updates), as received now
     pub async fn append_stderr(
-        pool: &SqlitePool,
+        pool: &DbPool,
         id: Uuid,
         stderr_append: &str,
     ) -> Result<(), sqlx::Error> {
-        sqlx::query!(
-            "UPDATE execution_processes SET stderr = COALESCE(stderr, '') || $1, updated_at = datetime('now') WHERE id = $2",
-            stderr_append,
+        let stderr_append = crate::services::environment_variables::redact(id, stderr_append);
+        let stderr_append = crate::services::log_redaction::redact(&stderr_append);
+        let stderr_append = stderr_append.as_ref();
+        log_storage::append(pool, id, LogStream::Stderr, stderr_append, Some(Utc::now())).await?;
+        db::query!(
+            "UPDATE execution_processes SET updated_at = datetime('now') WHERE id = $1",
             id
         )
         .execute(pool)
         .await?;
+        crate::command_runner::notify_output(id);
 
         Ok(())
     }
 
     /// Append to both stdout and stderr for this execution process
     pub async fn append_output(
-        pool: &SqlitePool,
+        pool: &DbPool,
         id: Uuid,
         stdout_append: Option<&str>,
         stderr_append: Option<&str>,
@@ -415,10 +1283,10 @@ impl ExecutionProcess {
     /// Delete execution processes for a task attempt (cleanup)
     #[allow(dead_code)]
     pub async fn delete_by_task_attempt_id(
-        pool: &SqlitePool,
+        pool: &DbPool,
         task_attempt_id: Uuid,
     ) -> Result<(), sqlx::Error> {
-        sqlx::query!(
+        db::query!(
             "DELETE FROM execution_processes WHERE task_attempt_id = $1",
             task_attempt_id
         )
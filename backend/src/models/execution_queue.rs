@@ -0,0 +1,333 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::{project_guardrails::GuardrailHold, task::TaskPriority};
+use crate::{
+    db::{self, DbPool},
+    event_bus::{self, BoardEvent},
+};
+
+/// An attempt waiting for a slot under the concurrency limits
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct QueuedAttempt {
+    pub task_attempt_id: Uuid,
+    pub project_id: Uuid,
+    pub executor: Option<String>,
+    pub priority: Option<TaskPriority>,
+    /// The coding agent paused to make room for a more urgent attempt, to be resumed rather
+    /// than started
+    pub paused_process_id: Option<Uuid>,
+}
+
+/// A queued attempt as the queue is shown, in the order it's dispatched
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct QueueEntry {
+    pub task_attempt_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub project_id: Uuid,
+    pub project_name: String,
+    pub priority: Option<TaskPriority>,
+    pub executor: Option<String>,
+    /// Paused to make room for a more urgent attempt
+    pub paused: bool,
+    pub queued_at: DateTime<Utc>,
+    /// Why its project's guardrails hold it back, if they do; filled in by
+    /// `services::guardrails`, not stored
+    pub hold: Option<GuardrailHold>,
+}
+
+#[derive(FromRow)]
+struct QueueEntryRow {
+    task_attempt_id: Uuid,
+    task_id: Uuid,
+    task_title: String,
+    project_id: Uuid,
+    project_name: String,
+    priority: Option<TaskPriority>,
+    executor: Option<String>,
+    paused: bool,
+    queued_at: DateTime<Utc>,
+}
+
+/// Where an attempt is queued, for reordering
+#[derive(FromRow)]
+struct QueueSlot {
+    task_attempt_id: Uuid,
+    project_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct ReorderQueue {
+    /// Queued attempts in the order they should go first; the rest keep their order after
+    pub task_attempt_ids: Vec<Uuid>,
+}
+
+/// A coding agent that could be paused to make room for a more urgent attempt
+#[derive(Debug, Clone, FromRow)]
+pub struct RunningAgent {
+    pub execution_process_id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub priority: Option<TaskPriority>,
+}
+
+/// How many attempts of a project are running
+#[derive(Debug, Clone, FromRow)]
+pub struct RunningAttempts {
+    pub project_id: Uuid,
+    pub count: i64,
+}
+
+impl QueuedAttempt {
+    /// Queue an attempt ahead of the first one whose task has a lower priority
+    pub async fn enqueue(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+        project_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        Self::insert(pool, task_attempt_id, project_id, None).await
+    }
+
+    /// Queue the attempt of a coding agent that was paused to make room, to be resumed
+    pub async fn enqueue_paused(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+        project_id: Uuid,
+        execution_process_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        Self::insert(
+            pool,
+            task_attempt_id,
+            project_id,
+            Some(execution_process_id),
+        )
+        .await
+    }
+
+    async fn insert(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+        project_id: Uuid,
+        paused_process_id: Option<Uuid>,
+    ) -> Result<(), sqlx::Error> {
+        // Take the write lock up front: a read transaction upgraded to a write one fails with
+        // SQLITE_BUSY right away, without waiting, when another connection wrote meanwhile
+        let mut tx = pool.begin_locked("execution_queue").await?;
+        let queued = db::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64" FROM execution_queue WHERE task_attempt_id = $1"#,
+            task_attempt_id
+        )
+        .fetch_one(&mut tx)
+        .await?;
+        if queued > 0 {
+            return Ok(());
+        }
+
+        let position = db::query_scalar!(
+            r#"SELECT COALESCE(
+                 (SELECT MIN(q.position)
+                    FROM execution_queue q
+                    JOIN task_attempts qa ON qa.id = q.task_attempt_id
+                    JOIN tasks qt ON qt.id = qa.task_id
+                   WHERE (CASE qt.priority WHEN 'low' THEN 0 WHEN 'high' THEN 2 WHEN 'urgent' THEN 3 ELSE 1 END)
+                       < (SELECT CASE t.priority WHEN 'low' THEN 0 WHEN 'high' THEN 2 WHEN 'urgent' THEN 3 ELSE 1 END
+                            FROM task_attempts ta
+                            JOIN tasks t ON t.id = ta.task_id
+                           WHERE ta.id = $1)),
+                 (SELECT COALESCE(MAX(position), 0) + 1 FROM execution_queue)
+               ) as "position!: i64""#,
+            task_attempt_id
+        )
+        .fetch_one(&mut tx)
+        .await?;
+        db::query!(
+            "UPDATE execution_queue SET position = position + 1 WHERE position >= $1",
+            position
+        )
+        .execute(&mut tx)
+        .await?;
+        db::query!(
+            "INSERT INTO execution_queue (task_attempt_id, project_id, position, paused_process_id)
+             VALUES ($1, $2, $3, $4)",
+            task_attempt_id,
+            project_id,
+            position,
+            paused_process_id
+        )
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await?;
+
+        event_bus::publish(BoardEvent::QueueChanged {
+            project_id,
+            task_attempt_id,
+            queued: true,
+        });
+        Ok(())
+    }
+
+    /// Move `task_attempt_ids` to the front of the queue in that order. Ids that aren't
+    /// queued are skipped.
+    pub async fn reorder(pool: &DbPool, task_attempt_ids: &[Uuid]) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin_locked("execution_queue").await?;
+        let queued = db::query_as!(
+            QueueSlot,
+            r#"SELECT task_attempt_id as "task_attempt_id!: Uuid", project_id as "project_id!: Uuid"
+               FROM execution_queue
+               ORDER BY position, queued_at"#
+        )
+        .fetch_all(&mut tx)
+        .await?;
+        let moved = task_attempt_ids
+            .iter()
+            .filter_map(|id| queued.iter().find(|row| row.task_attempt_id == *id));
+        let rest = queued
+            .iter()
+            .filter(|row| !task_attempt_ids.contains(&row.task_attempt_id));
+        let order: Vec<_> = moved.chain(rest).collect();
+        for (position, row) in (1_i64..).zip(&order) {
+            db::query!(
+                "UPDATE execution_queue SET position = $1 WHERE task_attempt_id = $2",
+                position,
+                row.task_attempt_id
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        for row in order {
+            event_bus::publish(BoardEvent::QueueChanged {
+                project_id: row.project_id,
+                task_attempt_id: row.task_attempt_id,
+                queued: true,
+            });
+        }
+        Ok(())
+    }
+
+    /// Take an attempt off the queue. Returns whether it was queued.
+    pub async fn remove(pool: &DbPool, task_attempt_id: Uuid) -> Result<bool, sqlx::Error> {
+        let project_id = db::query_scalar!(
+            r#"DELETE FROM execution_queue WHERE task_attempt_id = $1
+               RETURNING project_id as "project_id!: Uuid""#,
+            task_attempt_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        let Some(project_id) = project_id else {
+            return Ok(false);
+        };
+        event_bus::publish(BoardEvent::QueueChanged {
+            project_id,
+            task_attempt_id,
+            queued: false,
+        });
+        Ok(true)
+    }
+
+    /// Every queued attempt, in the order they're dispatched
+    pub async fn find_all(pool: &DbPool) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            QueuedAttempt,
+            r#"SELECT q.task_attempt_id as "task_attempt_id!: Uuid",
+                      q.project_id as "project_id!: Uuid",
+                      ta.executor,
+                      t.priority as "priority: TaskPriority",
+                      q.paused_process_id as "paused_process_id: Uuid"
+               FROM execution_queue q
+               JOIN task_attempts ta ON ta.id = q.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               ORDER BY q.position, q.queued_at"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_entries(pool: &DbPool) -> Result<Vec<QueueEntry>, sqlx::Error> {
+        let rows = db::query_as!(
+            QueueEntryRow,
+            r#"SELECT q.task_attempt_id as "task_attempt_id!: Uuid",
+                      t.id as "task_id!: Uuid",
+                      t.title as task_title,
+                      q.project_id as "project_id!: Uuid",
+                      p.name as project_name,
+                      t.priority as "priority: TaskPriority",
+                      ta.executor,
+                      q.paused_process_id IS NOT NULL as "paused!: bool",
+                      q.queued_at as "queued_at!: DateTime<Utc>"
+               FROM execution_queue q
+               JOIN task_attempts ta ON ta.id = q.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               JOIN projects p ON p.id = q.project_id
+               ORDER BY q.position, q.queued_at"#
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| QueueEntry {
+                task_attempt_id: row.task_attempt_id,
+                task_id: row.task_id,
+                task_title: row.task_title,
+                project_id: row.project_id,
+                project_name: row.project_name,
+                priority: row.priority,
+                executor: row.executor,
+                paused: row.paused,
+                queued_at: row.queued_at,
+                hold: None,
+            })
+            .collect())
+    }
+
+    pub async fn count(pool: &DbPool) -> Result<i64, sqlx::Error> {
+        db::query_scalar!(r#"SELECT COUNT(*) as "count!: i64" FROM execution_queue"#)
+            .fetch_one(pool)
+            .await
+    }
+
+    /// Attempts with a setup script, coding agent or cleanup script running, per project.
+    /// Those paused to make room are in the queue again and don't count.
+    pub async fn count_running(pool: &DbPool) -> Result<Vec<RunningAttempts>, sqlx::Error> {
+        db::query_as!(
+            RunningAttempts,
+            r#"SELECT t.project_id as "project_id!: Uuid", COUNT(DISTINCT ta.id) as "count!: i64"
+               FROM task_attempts ta
+               JOIN tasks t ON t.id = ta.task_id
+               JOIN execution_processes ep ON ep.task_attempt_id = ta.id
+               WHERE ep.status IN ('running', 'paused')
+                 AND ep.process_type IN ('setupscript','cleanupscript','codingagent')
+                 AND NOT EXISTS (SELECT 1 FROM execution_queue q WHERE q.task_attempt_id = ta.id)
+               GROUP BY t.project_id"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Coding agents running now, and not paused, most recently started first
+    pub async fn find_preemptible(pool: &DbPool) -> Result<Vec<RunningAgent>, sqlx::Error> {
+        db::query_as!(
+            RunningAgent,
+            r#"SELECT ep.id as "execution_process_id!: Uuid",
+                      ta.id as "task_attempt_id!: Uuid",
+                      t.id as "task_id!: Uuid",
+                      t.project_id as "project_id!: Uuid",
+                      t.priority as "priority: TaskPriority"
+               FROM execution_processes ep
+               JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE ep.status = 'running' AND ep.process_type = 'codingagent'
+               ORDER BY ep.started_at DESC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
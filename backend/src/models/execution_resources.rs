@@ -0,0 +1,98 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::execution_process::ExecutionProcess;
+use crate::{
+    command_runner::ResourcePoint,
+    db::{self, DbPool},
+};
+
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct ExecutionResourceSample {
+    /// Of one core, since the previous sample; a run busy on two cores reads 200
+    pub cpu_percent: f64,
+    pub rss_bytes: i64,
+    pub sampled_at: DateTime<Utc>,
+}
+
+/// The CPU and memory an execution used, sampled every few seconds while it ran
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ExecutionResources {
+    pub execution_process_id: Uuid,
+    pub peak_rss_bytes: i64,
+    pub peak_cpu_percent: f64,
+    /// CPU time at the last sample; what processes that exited between samples used is
+    /// missing
+    pub cpu_seconds: f64,
+    /// From start to finish, or until now while it runs
+    pub wall_seconds: f64,
+    pub samples: Vec<ExecutionResourceSample>,
+}
+
+impl ExecutionResources {
+    pub async fn record_sample(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+        point: &ResourcePoint,
+    ) -> Result<(), sqlx::Error> {
+        let rss_bytes = point.rss_bytes as i64;
+        db::query!(
+            "INSERT INTO execution_resource_samples (execution_process_id, cpu_percent, cpu_seconds, rss_bytes)
+             VALUES ($1, $2, $3, $4)",
+            execution_process_id,
+            point.cpu_percent,
+            point.cpu_seconds,
+            rss_bytes
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The execution's samples and peaks; `None` if it was never sampled, as where it ran
+    /// doesn't tell
+    pub async fn find_for_execution(
+        pool: &DbPool,
+        process: &ExecutionProcess,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let samples = db::query_as!(
+            ExecutionResourceSample,
+            r#"SELECT cpu_percent as "cpu_percent!: f64", rss_bytes as "rss_bytes!: i64", sampled_at as "sampled_at!: DateTime<Utc>"
+               FROM execution_resource_samples
+               WHERE execution_process_id = $1
+               ORDER BY id ASC"#,
+            process.id
+        )
+        .fetch_all(pool)
+        .await?;
+        if samples.is_empty() {
+            return Ok(None);
+        }
+        let cpu_seconds = db::query_scalar!(
+            r#"SELECT cpu_seconds as "cpu_seconds!: f64"
+               FROM execution_resource_samples
+               WHERE execution_process_id = $1
+               ORDER BY id DESC
+               LIMIT 1"#,
+            process.id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        let finished_at = process.completed_at.unwrap_or_else(Utc::now);
+        Ok(Some(Self {
+            execution_process_id: process.id,
+            peak_rss_bytes: samples.iter().map(|s| s.rss_bytes).max().unwrap_or(0),
+            peak_cpu_percent: samples.iter().map(|s| s.cpu_percent).fold(0.0, f64::max),
+            cpu_seconds,
+            wall_seconds: (finished_at - process.started_at).num_milliseconds().max(0) as f64
+                / 1000.0,
+            samples,
+        }))
+    }
+}
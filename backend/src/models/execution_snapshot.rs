@@ -0,0 +1,110 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// The worktree as a coding agent run found it, uncommitted changes included, kept so the
+/// run's own changes can be reverted later
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct ExecutionSnapshot {
+    pub execution_process_id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub snapshot_commit: String,
+    /// The ref keeping `snapshot_commit`, which is on no branch
+    pub snapshot_ref: String,
+    /// The commit that took the run's changes back out, once it was reverted
+    pub revert_commit: Option<String>,
+    pub reverted_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct RevertExecution {
+    /// The run whose changes to undo; what the other runs changed is kept
+    pub execution_process_id: Uuid,
+}
+
+/// The ref a run's snapshot is kept under
+pub fn snapshot_ref(task_attempt_id: Uuid, execution_process_id: Uuid) -> String {
+    format!(
+        "refs/vibe-kanban/snapshots/{}/{}",
+        task_attempt_id, execution_process_id
+    )
+}
+
+impl ExecutionSnapshot {
+    pub async fn find_by_task_attempt_id(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            ExecutionSnapshot,
+            r#"SELECT execution_process_id as "execution_process_id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", snapshot_commit, snapshot_ref, revert_commit, reverted_at as "reverted_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM execution_snapshots
+               WHERE task_attempt_id = $1
+               ORDER BY created_at ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            ExecutionSnapshot,
+            r#"SELECT execution_process_id as "execution_process_id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", snapshot_commit, snapshot_ref, revert_commit, reverted_at as "reverted_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM execution_snapshots
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+        task_attempt_id: Uuid,
+        snapshot_commit: &str,
+        snapshot_ref: &str,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            "INSERT INTO execution_snapshots (execution_process_id, task_attempt_id, snapshot_commit, snapshot_ref)
+             VALUES ($1, $2, $3, $4)",
+            execution_process_id,
+            task_attempt_id,
+            snapshot_commit,
+            snapshot_ref
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Note that the run's changes were taken back out by `revert_commit`
+    pub async fn mark_reverted(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+        revert_commit: &str,
+    ) -> Result<Self, sqlx::Error> {
+        db::query_as!(
+            ExecutionSnapshot,
+            r#"UPDATE execution_snapshots
+               SET revert_commit = $1, reverted_at = datetime('now', 'subsec')
+               WHERE execution_process_id = $2
+               RETURNING execution_process_id as "execution_process_id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", snapshot_commit, snapshot_ref, revert_commit, reverted_at as "reverted_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>""#,
+            revert_commit,
+            execution_process_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    db::{self, DbPool},
+    executor::{ActionType, NormalizedEntry, NormalizedEntryType},
+};
+
+/// What a coding agent run did with its tools, counted from its conversation once it ended
+#[derive(Debug, Clone, FromRow, Default, PartialEq, Serialize, TS)]
+#[ts(export)]
+pub struct ExecutionToolUsage {
+    pub execution_process_id: Uuid,
+    pub task_attempt_id: Uuid,
+    /// Distinct files, however many times each was read
+    pub files_read: i64,
+    /// Distinct files, however many times each was written; zero for a run that only talked
+    pub files_written: i64,
+    pub commands_run: i64,
+    pub searches: i64,
+    pub web_fetches: i64,
+    /// Delegated tasks, presented plans and tools the executor doesn't classify
+    pub other_tools: i64,
+    pub assistant_messages: i64,
+}
+
+impl ExecutionToolUsage {
+    /// Count the tool uses among the run's `entries`
+    pub fn count(
+        execution_process_id: Uuid,
+        task_attempt_id: Uuid,
+        entries: &[NormalizedEntry],
+    ) -> Self {
+        let mut usage = Self {
+            execution_process_id,
+            task_attempt_id,
+            ..Default::default()
+        };
+        let mut read = HashSet::new();
+        let mut written = HashSet::new();
+        for entry in entries {
+            match &entry.entry_type {
+                NormalizedEntryType::ToolUse { action_type, .. } => match action_type {
+                    ActionType::FileRead { path, .. } => {
+                        read.insert(path.as_str());
+                    }
+                    ActionType::FileWrite { path, .. } => {
+                        written.insert(path.as_str());
+                    }
+                    ActionType::CommandRun { .. } => usage.commands_run += 1,
+                    ActionType::Search { .. } => usage.searches += 1,
+                    ActionType::WebFetch { .. } => usage.web_fetches += 1,
+                    ActionType::TaskCreate { .. }
+                    | ActionType::PlanPresentation { .. }
+                    | ActionType::Other { .. } => usage.other_tools += 1,
+                },
+                NormalizedEntryType::AssistantMessage => usage.assistant_messages += 1,
+                _ => {}
+            }
+        }
+        usage.files_read = read.len() as i64;
+        usage.files_written = written.len() as i64;
+        usage
+    }
+
+    pub async fn record(&self, pool: &DbPool) -> Result<(), sqlx::Error> {
+        db::query!(
+            "INSERT INTO execution_tool_usage (execution_process_id, task_attempt_id, files_read, files_written, commands_run, searches, web_fetches, other_tools, assistant_messages)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+             ON CONFLICT(execution_process_id) DO UPDATE SET files_read = excluded.files_read, files_written = excluded.files_written, commands_run = excluded.commands_run, searches = excluded.searches, web_fetches = excluded.web_fetches, other_tools = excluded.other_tools, assistant_messages = excluded.assistant_messages",
+            self.execution_process_id,
+            self.task_attempt_id,
+            self.files_read,
+            self.files_written,
+            self.commands_run,
+            self.searches,
+            self.web_fetches,
+            self.other_tools,
+            self.assistant_messages
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            ExecutionToolUsage,
+            r#"SELECT execution_process_id as "execution_process_id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", files_read, files_written, commands_run, searches, web_fetches, other_tools, assistant_messages
+               FROM execution_tool_usage
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The tool usage of each of the attempt's runs that ended, oldest first
+    pub async fn find_by_task_attempt_id(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            ExecutionToolUsage,
+            r#"SELECT execution_process_id as "execution_process_id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", files_read, files_written, commands_run, searches, web_fetches, other_tools, assistant_messages
+               FROM execution_tool_usage
+               WHERE task_attempt_id = $1
+               ORDER BY created_at ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool_use(action_type: ActionType) -> NormalizedEntry {
+        NormalizedEntry {
+            id: None,
+            timestamp: None,
+            duration_ms: None,
+            entry_type: NormalizedEntryType::ToolUse {
+                tool_name: "tool".to_string(),
+                action_type,
+            },
+            content: String::new(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_files_are_counted_once_and_tools_by_kind() {
+        let entries = vec![
+            tool_use(ActionType::FileRead {
+                path: "src/main.rs".to_string(),
+                location: None,
+            }),
+            tool_use(ActionType::FileRead {
+                path: "src/main.rs".to_string(),
+                location: None,
+            }),
+            tool_use(ActionType::FileWrite {
+                path: "src/lib.rs".to_string(),
+                location: None,
+            }),
+            tool_use(ActionType::CommandRun {
+                command: "cargo test".to_string(),
+            }),
+            tool_use(ActionType::Other {
+                description: "todo".to_string(),
+            }),
+            NormalizedEntry {
+                id: None,
+                timestamp: None,
+                duration_ms: None,
+                entry_type: NormalizedEntryType::AssistantMessage,
+                content: "Done".to_string(),
+                metadata: None,
+            },
+        ];
+        let usage = ExecutionToolUsage::count(Uuid::nil(), Uuid::nil(), &entries);
+        assert_eq!(usage.files_read, 1);
+        assert_eq!(usage.files_written, 1);
+        assert_eq!(usage.commands_run, 1);
+        assert_eq!(usage.searches, 0);
+        assert_eq!(usage.other_tools, 1);
+        assert_eq!(usage.assistant_messages, 1);
+    }
+}
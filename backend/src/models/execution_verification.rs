@@ -0,0 +1,67 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// The outcome of one of the project's verification commands, run after a coding agent
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ExecutionVerification {
+    pub id: Uuid,
+    pub execution_process_id: Uuid, // The coding agent run that was verified
+    pub command: String,
+    pub passed: bool,
+    pub exit_code: Option<i64>, // None when the command was stopped or couldn't start
+    pub output: String,         // The end of stdout and stderr, redacted
+    pub duration_ms: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ExecutionVerification {
+    pub async fn create(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+        command: &str,
+        passed: bool,
+        exit_code: Option<i64>,
+        output: &str,
+        duration_ms: i64,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        db::query_as!(
+            ExecutionVerification,
+            r#"INSERT INTO execution_verifications (id, execution_process_id, command, passed, exit_code, output, duration_ms)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               RETURNING id as "id!: Uuid", execution_process_id as "execution_process_id!: Uuid", command, passed as "passed!: bool", exit_code, output, duration_ms as "duration_ms!: i64", created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            execution_process_id,
+            command,
+            passed,
+            exit_code,
+            output,
+            duration_ms
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// The results for a run, in the order the commands ran
+    pub async fn find_by_execution_process_id(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            ExecutionVerification,
+            r#"SELECT id as "id!: Uuid", execution_process_id as "execution_process_id!: Uuid", command, passed as "passed!: bool", exit_code, output, duration_ms as "duration_ms!: i64", created_at as "created_at!: DateTime<Utc>"
+               FROM execution_verifications
+               WHERE execution_process_id = $1
+               ORDER BY created_at ASC"#,
+            execution_process_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,286 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    db::{self, DbPool},
+    executor::{is_valid_model_name, ExecutorConfig},
+    services::environment_variables,
+};
+
+/// A named way of running a coding agent, such as a cheap one for triage and a thorough one
+/// for deep work, that attempts and tasks can be given instead of an executor
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ExecutorProfile {
+    pub id: Uuid,
+    pub project_id: Option<Uuid>, // None for profiles every project can use
+    pub name: String,
+    /// The executor it runs, such as `claude` or `jsonl:<name>`
+    pub executor: String,
+    /// Used unless an attempt picks its own model
+    pub model: Option<String>,
+    /// Flags put after the executor's CLI's own
+    pub extra_args: Vec<String>,
+    /// Variables set for its runs. Secrets belong in the project's environment variables,
+    /// which are stored encrypted and redacted from logs.
+    pub env: BTreeMap<String, String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct ExecutorProfileRow {
+    id: Uuid,
+    project_id: Option<Uuid>,
+    name: String,
+    executor: String,
+    model: Option<String>,
+    extra_args: String,
+    env: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<ExecutorProfileRow> for ExecutorProfile {
+    fn from(row: ExecutorProfileRow) -> Self {
+        Self {
+            id: row.id,
+            project_id: row.project_id,
+            name: row.name,
+            executor: row.executor,
+            model: row.model,
+            extra_args: serde_json::from_str(&row.extra_args).unwrap_or_default(),
+            env: serde_json::from_str(&row.env).unwrap_or_default(),
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertExecutorProfile {
+    /// Letters, digits, `-` and `_`, unique among the profiles of its scope
+    pub name: String,
+    pub executor: String,
+    pub model: Option<String>,
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+}
+
+/// The profile a task's attempts run with unless they pick an executor; `None` takes it away
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct AssignExecutorProfile {
+    pub executor_profile_id: Option<Uuid>,
+}
+
+/// Why the profile can't be saved, if it can't
+pub fn executor_profile_error(profile: &UpsertExecutorProfile) -> Option<String> {
+    let valid_name = !profile.name.is_empty()
+        && profile
+            .name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid_name {
+        return Some(format!(
+            "\"{}\" isn't a valid profile name: use letters, digits, - and _",
+            profile.name
+        ));
+    }
+    let config = match profile.executor.parse::<ExecutorConfig>() {
+        Ok(ExecutorConfig::SetupScript { .. }) | Err(_) => {
+            return Some(format!("Unknown executor: {}", profile.executor));
+        }
+        Ok(config) => config,
+    };
+    if let Some(model) = profile.model.as_deref().map(str::trim) {
+        if !model.is_empty() && !config.supports_model() {
+            return Some(format!("{} can't be given a model", config.display_name()));
+        }
+        if !model.is_empty() && !is_valid_model_name(model) {
+            return Some(format!("\"{}\" isn't a model name", model));
+        }
+    }
+    if !profile.extra_args.is_empty() && !config.supports_model() {
+        return Some(format!(
+            "{} can't be given extra flags",
+            config.display_name()
+        ));
+    }
+    if let Some(name) = profile
+        .env
+        .keys()
+        .find(|name| !environment_variables::is_valid_name(name))
+    {
+        return Some(format!("\"{}\" isn't a valid variable name", name));
+    }
+    None
+}
+
+impl ExecutorProfile {
+    /// Whether attempts in the project may run with the profile
+    pub fn is_available_in(&self, project_id: Uuid) -> bool {
+        self.project_id.is_none() || self.project_id == Some(project_id)
+    }
+
+    /// The model, trimmed, if the profile sets one
+    pub fn model(&self) -> Option<String> {
+        self.model
+            .as_deref()
+            .map(str::trim)
+            .filter(|model| !model.is_empty())
+            .map(str::to_string)
+    }
+
+    /// The profiles every project can use
+    pub async fn find_global(pool: &DbPool) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = db::query_as!(
+            ExecutorProfileRow,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id?: Uuid", name, executor, model, extra_args, env, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM executor_profiles
+               WHERE project_id IS NULL
+               ORDER BY name ASC"#
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(ExecutorProfile::from).collect())
+    }
+
+    /// The profiles the project's attempts can use: its own first, then the global ones
+    pub async fn find_available(pool: &DbPool, project_id: Uuid) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = db::query_as!(
+            ExecutorProfileRow,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id?: Uuid", name, executor, model, extra_args, env, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM executor_profiles
+               WHERE project_id = $1 OR project_id IS NULL
+               ORDER BY project_id IS NULL, name ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(ExecutorProfile::from).collect())
+    }
+
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        let row = db::query_as!(
+            ExecutorProfileRow,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id?: Uuid", name, executor, model, extra_args, env, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM executor_profiles
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(ExecutorProfile::from))
+    }
+
+    /// Create a profile in the project, or a global one without a project
+    pub async fn create(
+        pool: &DbPool,
+        project_id: Option<Uuid>,
+        data: &UpsertExecutorProfile,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let extra_args =
+            serde_json::to_string(&data.extra_args).unwrap_or_else(|_| "[]".to_string());
+        let env = serde_json::to_string(&data.env).unwrap_or_else(|_| "{}".to_string());
+        let row = db::query_as!(
+            ExecutorProfileRow,
+            r#"INSERT INTO executor_profiles (id, project_id, name, executor, model, extra_args, env)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               RETURNING id as "id!: Uuid", project_id as "project_id?: Uuid", name, executor, model, extra_args, env, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            data.name,
+            data.executor,
+            data.model,
+            extra_args,
+            env
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(row.into())
+    }
+
+    pub async fn update(
+        pool: &DbPool,
+        id: Uuid,
+        data: &UpsertExecutorProfile,
+    ) -> Result<Self, sqlx::Error> {
+        let extra_args =
+            serde_json::to_string(&data.extra_args).unwrap_or_else(|_| "[]".to_string());
+        let env = serde_json::to_string(&data.env).unwrap_or_else(|_| "{}".to_string());
+        let row = db::query_as!(
+            ExecutorProfileRow,
+            r#"UPDATE executor_profiles
+               SET name = $2, executor = $3, model = $4, extra_args = $5, env = $6, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", project_id as "project_id?: Uuid", name, executor, model, extra_args, env, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.name,
+            data.executor,
+            data.model,
+            extra_args,
+            env
+        )
+        .fetch_optional(pool)
+        .await?;
+        row.map(ExecutorProfile::from)
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    /// Remove the profile. Tasks and attempts that used it go back to their executor.
+    pub async fn delete(pool: &DbPool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = db::query!("DELETE FROM executor_profiles WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(executor: &str, model: Option<&str>, extra_args: &[&str]) -> UpsertExecutorProfile {
+        UpsertExecutorProfile {
+            name: "deep-work".to_string(),
+            executor: executor.to_string(),
+            model: model.map(str::to_string),
+            extra_args: extra_args.iter().map(|arg| arg.to_string()).collect(),
+            env: BTreeMap::from([("MAX_THINKING_TOKENS".to_string(), "32000".to_string())]),
+        }
+    }
+
+    #[test]
+    fn test_profiles_are_checked() {
+        assert_eq!(
+            executor_profile_error(&profile("claude", Some("opus"), &["--verbose"])),
+            None
+        );
+        assert_eq!(
+            executor_profile_error(&profile("amp", Some(" "), &[])),
+            None
+        );
+        assert!(executor_profile_error(&profile("amp", Some("gpt-5"), &[])).is_some());
+        assert!(executor_profile_error(&profile("amp", None, &["--verbose"])).is_some());
+        assert!(executor_profile_error(&profile("claude", Some("opus; rm"), &[])).is_some());
+        assert!(executor_profile_error(&profile("setup-script", None, &[])).is_some());
+        assert!(executor_profile_error(&profile("cursor", None, &[])).is_some());
+
+        let mut unnamed = profile("claude", None, &[]);
+        unnamed.name = "deep work".to_string();
+        assert!(executor_profile_error(&unnamed).is_some());
+        let mut bad_env = profile("claude", None, &[]);
+        bad_env.env.insert("1ST".to_string(), "x".to_string());
+        assert!(executor_profile_error(&bad_env).is_some());
+    }
+}
@@ -1,9 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::FromRow;
 use ts_rs::TS;
 use uuid::Uuid;
 
+use crate::db::{self, DbPool};
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct ExecutorSession {
@@ -12,7 +14,7 @@ pub struct ExecutorSession {
     pub execution_process_id: Uuid,
     pub session_id: Option<String>, // External session ID from Claude/Amp
     pub prompt: Option<String>,     // The prompt sent to the executor
-    pub summary: Option<String>,    // Final assistant message/summary
+    pub summary: Option<String>,    // One-line outcome of the finished run
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -37,8 +39,8 @@ pub struct UpdateExecutorSession {
 impl ExecutorSession {
     /// Find executor session by ID
     #[allow(dead_code)]
-    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
-        sqlx::query_as!(
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
             ExecutorSession,
             r#"SELECT 
                 id as "id!: Uuid", 
@@ -59,10 +61,10 @@ impl ExecutorSession {
 
     /// Find executor session by execution process ID
     pub async fn find_by_execution_process_id(
-        pool: &SqlitePool,
+        pool: &DbPool,
         execution_process_id: Uuid,
     ) -> Result<Option<Self>, sqlx::Error> {
-        sqlx::query_as!(
+        db::query_as!(
             ExecutorSession,
             r#"SELECT
                 id as "id!: Uuid",
@@ -84,10 +86,10 @@ impl ExecutorSession {
     /// Find all executor sessions for a task attempt
     #[allow(dead_code)]
     pub async fn find_by_task_attempt_id(
-        pool: &SqlitePool,
+        pool: &DbPool,
         task_attempt_id: Uuid,
     ) -> Result<Vec<Self>, sqlx::Error> {
-        sqlx::query_as!(
+        db::query_as!(
             ExecutorSession,
             r#"SELECT 
                 id as "id!: Uuid", 
@@ -109,7 +111,7 @@ impl ExecutorSession {
 
     /// Create a new executor session
     pub async fn create(
-        pool: &SqlitePool,
+        pool: &DbPool,
         data: &CreateExecutorSession,
         session_id: Uuid,
     ) -> Result<Self, sqlx::Error> {
@@ -120,7 +122,7 @@ impl ExecutorSession {
             session_id, data.task_attempt_id, data.execution_process_id
         );
 
-        sqlx::query_as!(
+        db::query_as!(
             ExecutorSession,
             r#"INSERT INTO executor_sessions (
                 id, task_attempt_id, execution_process_id, session_id, prompt, summary,
@@ -151,11 +153,11 @@ impl ExecutorSession {
 
     /// Update executor session with external session ID
     pub async fn update_session_id(
-        pool: &SqlitePool,
+        pool: &DbPool,
         execution_process_id: Uuid,
         external_session_id: &str,
     ) -> Result<(), sqlx::Error> {
-        sqlx::query!(
+        db::query!(
             r#"UPDATE executor_sessions
                SET session_id = $1, updated_at = datetime('now')
                WHERE execution_process_id = $2"#,
@@ -170,12 +172,8 @@ impl ExecutorSession {
 
     /// Update executor session prompt
     #[allow(dead_code)]
-    pub async fn update_prompt(
-        pool: &SqlitePool,
-        id: Uuid,
-        prompt: &str,
-    ) -> Result<(), sqlx::Error> {
-        sqlx::query!(
+    pub async fn update_prompt(pool: &DbPool, id: Uuid, prompt: &str) -> Result<(), sqlx::Error> {
+        db::query!(
             r#"UPDATE executor_sessions 
                SET prompt = $1, updated_at = datetime('now') 
                WHERE id = $2"#,
@@ -190,11 +188,11 @@ impl ExecutorSession {
 
     /// Update executor session summary
     pub async fn update_summary(
-        pool: &SqlitePool,
+        pool: &DbPool,
         execution_process_id: Uuid,
         summary: &str,
     ) -> Result<(), sqlx::Error> {
-        sqlx::query!(
+        db::query!(
             r#"UPDATE executor_sessions 
                SET summary = $1, updated_at = datetime('now') 
                WHERE execution_process_id = $2"#,
@@ -210,10 +208,10 @@ impl ExecutorSession {
     /// Delete executor sessions for a task attempt (cleanup)
     #[allow(dead_code)]
     pub async fn delete_by_task_attempt_id(
-        pool: &SqlitePool,
+        pool: &DbPool,
         task_attempt_id: Uuid,
     ) -> Result<(), sqlx::Error> {
-        sqlx::query!(
+        db::query!(
             "DELETE FROM executor_sessions WHERE task_attempt_id = $1",
             task_attempt_id
         )
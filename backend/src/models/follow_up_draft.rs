@@ -0,0 +1,323 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    db::{self, DbPool},
+    executor::{NormalizedConversation, NormalizedEntry, NormalizedEntryType},
+    models::task_attempt::FileHunks,
+    services::scoped_follow_up::unified_hunk,
+};
+
+/// Part of an attempt the draft refers to, expanded into the prompt when it's sent so the
+/// user doesn't have to paste it in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(export)]
+pub enum DraftQuote {
+    /// An entry of a run's normalized conversation, by its index in the entries
+    Entry {
+        execution_process_id: Uuid,
+        entry_index: u32,
+    },
+    /// A hunk of the attempt's diff, by its index in the file's hunk listing
+    Hunk { path: String, hunk_index: u32 },
+}
+
+/// A follow-up being written for a task, kept until it's sent or discarded
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct FollowUpDraft {
+    pub task_id: Uuid,
+    /// The attempt the quotes were picked from
+    pub task_attempt_id: Option<Uuid>,
+    pub prompt: String,
+    pub quotes: Vec<DraftQuote>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct FollowUpDraftRow {
+    task_id: Uuid,
+    task_attempt_id: Option<Uuid>,
+    prompt: String,
+    quotes: String,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<FollowUpDraftRow> for FollowUpDraft {
+    fn from(row: FollowUpDraftRow) -> Self {
+        Self {
+            task_id: row.task_id,
+            task_attempt_id: row.task_attempt_id,
+            prompt: row.prompt,
+            quotes: serde_json::from_str(&row.quotes).unwrap_or_default(),
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct SaveFollowUpDraft {
+    pub task_attempt_id: Option<Uuid>,
+    pub prompt: String,
+    #[serde(default)]
+    pub quotes: Vec<DraftQuote>,
+}
+
+#[derive(Debug, Default, Deserialize, TS)]
+#[ts(export)]
+pub struct SendFollowUpDraft {
+    /// Hand the attempt over to this executor instead of continuing the current one's session
+    pub executor: Option<String>,
+}
+
+impl FollowUpDraft {
+    pub async fn find_by_task_id(
+        pool: &DbPool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let row = db::query_as!(
+            FollowUpDraftRow,
+            r#"SELECT task_id as "task_id!: Uuid", task_attempt_id as "task_attempt_id: Uuid", prompt, quotes, updated_at as "updated_at!: DateTime<Utc>"
+               FROM follow_up_drafts
+               WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(Self::from))
+    }
+
+    /// Keep the draft, replacing the task's earlier one
+    pub async fn save(
+        pool: &DbPool,
+        task_id: Uuid,
+        data: &SaveFollowUpDraft,
+    ) -> Result<Self, sqlx::Error> {
+        let quotes = serde_json::to_string(&data.quotes).unwrap_or_else(|_| "[]".to_string());
+        let row = db::query_as!(
+            FollowUpDraftRow,
+            r#"INSERT INTO follow_up_drafts (task_id, task_attempt_id, prompt, quotes)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT(task_id) DO UPDATE SET
+                   task_attempt_id = excluded.task_attempt_id,
+                   prompt = excluded.prompt,
+                   quotes = excluded.quotes,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING task_id as "task_id!: Uuid", task_attempt_id as "task_attempt_id: Uuid", prompt, quotes, updated_at as "updated_at!: DateTime<Utc>""#,
+            task_id,
+            data.task_attempt_id,
+            data.prompt,
+            quotes
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(row.into())
+    }
+
+    pub async fn delete(pool: &DbPool, task_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = db::query!("DELETE FROM follow_up_drafts WHERE task_id = $1", task_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The runs whose conversation entries the draft quotes
+    pub fn quoted_processes(&self) -> Vec<Uuid> {
+        let mut ids: Vec<Uuid> = self
+            .quotes
+            .iter()
+            .filter_map(|quote| match quote {
+                DraftQuote::Entry {
+                    execution_process_id,
+                    ..
+                } => Some(*execution_process_id),
+                DraftQuote::Hunk { .. } => None,
+            })
+            .collect();
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+
+    /// The prompt to send, with every quote expanded from the runs' `conversations` and the
+    /// attempt's diff `files`. `Err` with why when a quote no longer matches them.
+    pub fn compile_prompt(
+        &self,
+        conversations: &HashMap<Uuid, NormalizedConversation>,
+        files: &[FileHunks],
+    ) -> Result<String, String> {
+        let instruction = self.prompt.trim();
+        if instruction.is_empty() {
+            return Err("Write what the agent should do".to_string());
+        }
+        if self.quotes.is_empty() {
+            return Ok(instruction.to_string());
+        }
+
+        let mut sections = Vec::new();
+        for quote in &self.quotes {
+            match quote {
+                DraftQuote::Entry {
+                    execution_process_id,
+                    entry_index,
+                } => {
+                    let entry = conversations
+                        .get(execution_process_id)
+                        .and_then(|conversation| conversation.entries.get(*entry_index as usize))
+                        .ok_or_else(|| {
+                            "A quoted message is no longer in the conversation".to_string()
+                        })?;
+                    sections.push(quoted_entry(entry));
+                }
+                DraftQuote::Hunk { path, hunk_index } => {
+                    let hunk = files
+                        .iter()
+                        .find(|file| &file.path == path)
+                        .and_then(|file| file.hunks.iter().find(|h| h.index == *hunk_index))
+                        .ok_or_else(|| {
+                            format!(
+                                "{} has no hunk {}; the diff may have changed since it was quoted",
+                                path, hunk_index
+                            )
+                        })?;
+                    sections.push(format!(
+                        "### {}\n```diff\n{}```\n",
+                        path,
+                        unified_hunk(hunk)
+                    ));
+                }
+            }
+        }
+
+        Ok(format!(
+            "{}\n\nIt refers to these parts of this attempt:\n\n{}",
+            instruction,
+            sections.join("\n")
+        ))
+    }
+}
+
+/// The entry as a section of the prompt, headed by who or what it came from
+fn quoted_entry(entry: &NormalizedEntry) -> String {
+    let heading = match &entry.entry_type {
+        NormalizedEntryType::UserMessage => "My message".to_string(),
+        NormalizedEntryType::AssistantMessage => "Your message".to_string(),
+        NormalizedEntryType::ToolUse { tool_name, .. } => format!("Your {} call", tool_name),
+        NormalizedEntryType::SystemMessage => "System message".to_string(),
+        NormalizedEntryType::ErrorMessage => "Error".to_string(),
+        NormalizedEntryType::Thinking => "Your reasoning".to_string(),
+        NormalizedEntryType::Attachment { .. } => "Attachment".to_string(),
+        NormalizedEntryType::PlanEntry => "Your plan".to_string(),
+        NormalizedEntryType::Verification { command, .. } => format!("Output of `{}`", command),
+        NormalizedEntryType::FileEdit { path, diff } => {
+            return format!(
+                "### Your edit to {}\n```diff\n{}\n```\n",
+                path,
+                diff.trim_end()
+            );
+        }
+    };
+    let quoted: Vec<String> = entry
+        .content
+        .trim_end()
+        .lines()
+        .map(|line| format!("> {}", line).trim_end().to_string())
+        .collect();
+    format!("### {}\n{}\n", heading, quoted.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::task_attempt::{DiffChunk, DiffChunkType, DiffHunk};
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            id: None,
+            timestamp: None,
+            duration_ms: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    fn draft(quotes: Vec<DraftQuote>) -> FollowUpDraft {
+        FollowUpDraft {
+            task_id: Uuid::new_v4(),
+            task_attempt_id: None,
+            prompt: " This is wrong, the retry should back off ".to_string(),
+            quotes,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_quotes_are_expanded_into_the_prompt() {
+        let process_id = Uuid::new_v4();
+        let conversations = HashMap::from([(
+            process_id,
+            NormalizedConversation {
+                entries: vec![
+                    entry(NormalizedEntryType::UserMessage, "Add retries"),
+                    entry(
+                        NormalizedEntryType::AssistantMessage,
+                        "I'll retry right away.\n\nDone.",
+                    ),
+                ],
+                session_id: None,
+                executor_type: "claude".to_string(),
+                prompt: None,
+                summary: None,
+                diff_summary: None,
+            },
+        )]);
+        let files = vec![FileHunks {
+            path: "src/retry.rs".to_string(),
+            hunks: vec![DiffHunk {
+                index: 0,
+                header: "@@ -1,1 +1,1 @@".to_string(),
+                old_start: 1,
+                new_start: 1,
+                lines: vec![DiffChunk {
+                    chunk_type: DiffChunkType::Insert,
+                    content: "retry();\n".to_string(),
+                }],
+            }],
+        }];
+
+        let prompt = draft(vec![
+            DraftQuote::Entry {
+                execution_process_id: process_id,
+                entry_index: 1,
+            },
+            DraftQuote::Hunk {
+                path: "src/retry.rs".to_string(),
+                hunk_index: 0,
+            },
+        ])
+        .compile_prompt(&conversations, &files)
+        .unwrap();
+        assert!(prompt.starts_with("This is wrong, the retry should back off\n\n"));
+        assert!(prompt.contains("### Your message\n> I'll retry right away.\n>\n> Done.\n"));
+        assert!(prompt.contains("### src/retry.rs\n```diff\n@@ -1,1 +1,1 @@\n+retry();\n```"));
+        assert!(!prompt.contains("Add retries"));
+
+        let missing = draft(vec![DraftQuote::Entry {
+            execution_process_id: process_id,
+            entry_index: 5,
+        }]);
+        assert!(missing.compile_prompt(&conversations, &files).is_err());
+        assert_eq!(
+            draft(vec![]).compile_prompt(&conversations, &files),
+            Ok("This is wrong, the retry should back off".to_string())
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct GitHubIssueLink {
+    pub project_id: Uuid,
+    pub repo_owner: String,
+    pub repo_name: String,
+    /// Only issues with this label are imported
+    pub label: String,
+    pub last_synced_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertGitHubIssueLink {
+    pub label: String,
+}
+
+/// An imported issue and the task it became, with what was last copied from it
+#[derive(Debug, Clone, FromRow)]
+pub struct GitHubIssueTask {
+    pub issue_number: i64,
+    pub task_id: Uuid,
+    pub synced_title: String,
+    pub synced_body: Option<String>,
+    pub issue_closed: bool,
+}
+
+impl GitHubIssueLink {
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            GitHubIssueLink,
+            r#"SELECT project_id as "project_id!: Uuid", repo_owner, repo_name, label, last_synced_at as "last_synced_at?: DateTime<Utc>", last_error, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM github_issue_links
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_all(pool: &DbPool) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            GitHubIssueLink,
+            r#"SELECT project_id as "project_id!: Uuid", repo_owner, repo_name, label, last_synced_at as "last_synced_at?: DateTime<Utc>", last_error, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM github_issue_links"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Whether the link imports from the repository GitHub names `full_name` (owner/name)
+    pub fn is_for_repo(&self, full_name: &str) -> bool {
+        full_name.eq_ignore_ascii_case(&format!("{}/{}", self.repo_owner, self.repo_name))
+    }
+
+    /// Link the project to a repository's issues, replacing any previous link. Issues
+    /// imported from a different repository are forgotten, since their numbers mean nothing
+    /// in the new one.
+    pub async fn upsert(
+        pool: &DbPool,
+        project_id: Uuid,
+        repo_owner: &str,
+        repo_name: &str,
+        label: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        db::query!(
+            "DELETE FROM github_issue_tasks WHERE project_id = $1 AND EXISTS (SELECT 1 FROM github_issue_links WHERE project_id = $1 AND (repo_owner != $2 OR repo_name != $3))",
+            project_id,
+            repo_owner,
+            repo_name
+        )
+        .execute(&mut tx)
+        .await?;
+        let link = db::query_as!(
+            GitHubIssueLink,
+            r#"INSERT INTO github_issue_links (project_id, repo_owner, repo_name, label)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT(project_id) DO UPDATE SET
+                   repo_owner = excluded.repo_owner,
+                   repo_name = excluded.repo_name,
+                   label = excluded.label,
+                   last_error = NULL,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid", repo_owner, repo_name, label, last_synced_at as "last_synced_at?: DateTime<Utc>", last_error, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            repo_owner,
+            repo_name,
+            label
+        )
+        .fetch_one(&mut tx)
+        .await?;
+        tx.commit().await?;
+        Ok(link)
+    }
+
+    /// Unlink the project; imported tasks stay, but stop following their issues
+    pub async fn delete(pool: &DbPool, project_id: Uuid) -> Result<u64, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        db::query!(
+            "DELETE FROM github_issue_tasks WHERE project_id = $1",
+            project_id
+        )
+        .execute(&mut tx)
+        .await?;
+        let result = db::query!(
+            "DELETE FROM github_issue_links WHERE project_id = $1",
+            project_id
+        )
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn record_sync(
+        pool: &DbPool,
+        project_id: Uuid,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE github_issue_links SET last_synced_at = datetime('now', 'subsec'), last_error = $2 WHERE project_id = $1",
+            project_id,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+impl GitHubIssueTask {
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            GitHubIssueTask,
+            r#"SELECT issue_number, task_id as "task_id!: Uuid", synced_title, synced_body, issue_closed as "issue_closed!: bool"
+               FROM github_issue_tasks
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &DbPool,
+        project_id: Uuid,
+        issue_number: i64,
+        task_id: Uuid,
+        title: &str,
+        body: Option<&str>,
+        issue_closed: bool,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            r#"INSERT INTO github_issue_tasks (project_id, issue_number, task_id, synced_title, synced_body, issue_closed)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               ON CONFLICT(project_id, issue_number) DO UPDATE SET
+                   task_id = excluded.task_id,
+                   synced_title = excluded.synced_title,
+                   synced_body = excluded.synced_body,
+                   issue_closed = excluded.issue_closed,
+                   synced_at = datetime('now', 'subsec')"#,
+            project_id,
+            issue_number,
+            task_id,
+            title,
+            body,
+            issue_closed
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,233 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::{TaskStatus, TaskStatusNames};
+use crate::db::{self, DbPool};
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct GitHubProjectLink {
+    pub project_id: Uuid,
+    pub project_url: String,
+    pub github_project_id: String,
+    pub status_mapping: TaskStatusNames,
+    pub last_synced_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertGitHubProjectLink {
+    /// e.g. https://github.com/orgs/acme/projects/5 or https://github.com/users/me/projects/2
+    pub project_url: String,
+    #[serde(default)]
+    pub status_mapping: TaskStatusNames,
+}
+
+/// The board item a task is mirrored to, with what was last written to it
+#[derive(Debug, Clone, FromRow)]
+pub struct GitHubProjectItem {
+    pub task_id: Uuid,
+    pub item_id: String,
+    pub draft_issue_id: String,
+    pub synced_title: String,
+    pub synced_description: Option<String>,
+    pub synced_status: TaskStatus,
+}
+
+#[derive(FromRow)]
+struct LinkRow {
+    project_id: Uuid,
+    project_url: String,
+    github_project_id: String,
+    status_mapping: String,
+    last_synced_at: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<LinkRow> for GitHubProjectLink {
+    fn from(row: LinkRow) -> Self {
+        Self {
+            project_id: row.project_id,
+            project_url: row.project_url,
+            github_project_id: row.github_project_id,
+            status_mapping: serde_json::from_str(&row.status_mapping).unwrap_or_default(),
+            last_synced_at: row.last_synced_at,
+            last_error: row.last_error,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+impl GitHubProjectLink {
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let row = db::query_as!(
+            LinkRow,
+            r#"SELECT project_id as "project_id!: Uuid", project_url, github_project_id, status_mapping, last_synced_at as "last_synced_at?: DateTime<Utc>", last_error, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM github_project_links
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(Into::into))
+    }
+
+    pub async fn find_all(pool: &DbPool) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = db::query_as!(
+            LinkRow,
+            r#"SELECT project_id as "project_id!: Uuid", project_url, github_project_id, status_mapping, last_synced_at as "last_synced_at?: DateTime<Utc>", last_error, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM github_project_links"#
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Link the project to a board, replacing any previous link. Items synced to a different
+    /// board are forgotten so tasks are added to the new one.
+    pub async fn upsert(
+        pool: &DbPool,
+        project_id: Uuid,
+        project_url: &str,
+        github_project_id: &str,
+        status_mapping: &TaskStatusNames,
+    ) -> Result<Self, sqlx::Error> {
+        let mapping = serde_json::to_string(status_mapping).unwrap_or_else(|_| "{}".to_string());
+        let mut tx = pool.begin().await?;
+        db::query!(
+            "DELETE FROM github_project_items WHERE project_id = $1 AND EXISTS (SELECT 1 FROM github_project_links WHERE project_id = $1 AND github_project_id != $2)",
+            project_id,
+            github_project_id
+        )
+        .execute(&mut tx)
+        .await?;
+        let row = db::query_as!(
+            LinkRow,
+            r#"INSERT INTO github_project_links (project_id, project_url, github_project_id, status_mapping)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT(project_id) DO UPDATE SET
+                   project_url = excluded.project_url,
+                   github_project_id = excluded.github_project_id,
+                   status_mapping = excluded.status_mapping,
+                   last_error = NULL,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid", project_url, github_project_id, status_mapping, last_synced_at as "last_synced_at?: DateTime<Utc>", last_error, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            project_url,
+            github_project_id,
+            mapping
+        )
+        .fetch_one(&mut tx)
+        .await?;
+        tx.commit().await?;
+        Ok(row.into())
+    }
+
+    /// Unlink the project; the board keeps its items but they stop updating
+    pub async fn delete(pool: &DbPool, project_id: Uuid) -> Result<u64, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        db::query!(
+            "DELETE FROM github_project_items WHERE project_id = $1",
+            project_id
+        )
+        .execute(&mut tx)
+        .await?;
+        let result = db::query!(
+            "DELETE FROM github_project_links WHERE project_id = $1",
+            project_id
+        )
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn record_sync(
+        pool: &DbPool,
+        project_id: Uuid,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE github_project_links SET last_synced_at = datetime('now', 'subsec'), last_error = $2 WHERE project_id = $1",
+            project_id,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+impl GitHubProjectItem {
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            GitHubProjectItem,
+            r#"SELECT task_id as "task_id!: Uuid", item_id, draft_issue_id, synced_title, synced_description, synced_status as "synced_status!: TaskStatus"
+               FROM github_project_items
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn upsert(
+        pool: &DbPool,
+        task_id: Uuid,
+        project_id: Uuid,
+        item_id: &str,
+        draft_issue_id: &str,
+        title: &str,
+        description: Option<&str>,
+        status: &TaskStatus,
+    ) -> Result<(), sqlx::Error> {
+        let status = status.clone();
+        db::query!(
+            r#"INSERT INTO github_project_items (task_id, project_id, item_id, draft_issue_id, synced_title, synced_description, synced_status)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               ON CONFLICT(task_id) DO UPDATE SET
+                   item_id = excluded.item_id,
+                   draft_issue_id = excluded.draft_issue_id,
+                   synced_title = excluded.synced_title,
+                   synced_description = excluded.synced_description,
+                   synced_status = excluded.synced_status,
+                   synced_at = datetime('now', 'subsec')"#,
+            task_id,
+            project_id,
+            item_id,
+            draft_issue_id,
+            title,
+            description,
+            status
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &DbPool, task_id: Uuid) -> Result<(), sqlx::Error> {
+        db::query!(
+            "DELETE FROM github_project_items WHERE task_id = $1",
+            task_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
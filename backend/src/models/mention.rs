@@ -0,0 +1,111 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// Where someone was mentioned
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum MentionSource {
+    ReviewComment,
+    FollowUp,
+}
+
+/// A user @mentioned on a task, as it shows in their inbox
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct Mention {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub project_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub task_attempt_id: Option<Uuid>,
+    pub source: MentionSource,
+    /// Who wrote the mention, when sign-in is turned on
+    pub mentioned_by: Option<Uuid>,
+    pub mentioned_by_username: Option<String>,
+    /// The start of the comment or prompt the mention is in
+    pub excerpt: String,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Mention {
+    /// The user's mentions, newest first
+    pub async fn find_by_user_id(
+        pool: &DbPool,
+        user_id: Uuid,
+        unread_only: bool,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            Mention,
+            r#"SELECT m.id as "id!: Uuid", m.user_id as "user_id!: Uuid", t.project_id as "project_id!: Uuid", m.task_id as "task_id!: Uuid", t.title as task_title, m.task_attempt_id as "task_attempt_id: Uuid", m.source as "source!: MentionSource", m.mentioned_by as "mentioned_by: Uuid", u.username as "mentioned_by_username?", m.excerpt, m.read_at as "read_at: DateTime<Utc>", m.created_at as "created_at!: DateTime<Utc>"
+               FROM mentions m
+               JOIN tasks t ON t.id = m.task_id
+               LEFT JOIN users u ON u.id = m.mentioned_by
+               WHERE m.user_id = $1
+               AND ($2 = FALSE OR m.read_at IS NULL)
+               ORDER BY m.created_at DESC"#,
+            user_id,
+            unread_only
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &DbPool,
+        user_id: Uuid,
+        task_id: Uuid,
+        task_attempt_id: Option<Uuid>,
+        source: MentionSource,
+        mentioned_by: Option<Uuid>,
+        excerpt: &str,
+    ) -> Result<Uuid, sqlx::Error> {
+        let id = Uuid::new_v4();
+        db::query!(
+            r#"INSERT INTO mentions (id, user_id, task_id, task_attempt_id, source, mentioned_by, excerpt)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
+            id,
+            user_id,
+            task_id,
+            task_attempt_id,
+            source,
+            mentioned_by,
+            excerpt
+        )
+        .execute(pool)
+        .await?;
+        Ok(id)
+    }
+
+    /// Mark one of the user's mentions read
+    pub async fn mark_read(pool: &DbPool, user_id: Uuid, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = db::query!(
+            r#"UPDATE mentions SET read_at = datetime('now', 'subsec')
+               WHERE id = $1 AND user_id = $2 AND read_at IS NULL"#,
+            id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn mark_all_read(pool: &DbPool, user_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = db::query!(
+            r#"UPDATE mentions SET read_at = datetime('now', 'subsec')
+               WHERE user_id = $1 AND read_at IS NULL"#,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
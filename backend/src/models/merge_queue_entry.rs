@@ -0,0 +1,176 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+#[derive(Debug, Clone, Copy, Type, PartialEq, Eq, Serialize, TS)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum MergeQueueStatus {
+    Queued,
+    Rebasing,
+    Checking,
+    Merging,
+    Merged,
+    Failed,
+}
+
+impl MergeQueueStatus {
+    pub fn is_finished(self) -> bool {
+        matches!(self, MergeQueueStatus::Merged | MergeQueueStatus::Failed)
+    }
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct MergeQueueEntry {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub task_id: Uuid,
+    pub attempt_id: Uuid,
+    pub task_title: String,
+    pub base_branch: String,
+    pub status: MergeQueueStatus,
+    pub error: Option<String>,
+    /// Tail of the merge check script's output when it failed
+    pub check_output: Option<String>,
+    pub merge_commit: Option<String>,
+    pub queued_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl MergeQueueEntry {
+    /// Queue the attempt. Fails with a unique violation while it is queued already.
+    pub async fn create(
+        pool: &DbPool,
+        project_id: Uuid,
+        task_id: Uuid,
+        attempt_id: Uuid,
+        task_title: &str,
+        base_branch: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        db::query_as!(
+            MergeQueueEntry,
+            r#"INSERT INTO merge_queue_entries (id, project_id, task_id, attempt_id, task_title, base_branch)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", task_id as "task_id!: Uuid", attempt_id as "attempt_id!: Uuid", task_title, base_branch, status as "status!: MergeQueueStatus", error, check_output, merge_commit, queued_at as "queued_at!: DateTime<Utc>", finished_at as "finished_at: DateTime<Utc>""#,
+            id,
+            project_id,
+            task_id,
+            attempt_id,
+            task_title,
+            base_branch
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// The project's entries in the order they were queued
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            MergeQueueEntry,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", task_id as "task_id!: Uuid", attempt_id as "attempt_id!: Uuid", task_title, base_branch, status as "status!: MergeQueueStatus", error, check_output, merge_commit, queued_at as "queued_at!: DateTime<Utc>", finished_at as "finished_at: DateTime<Utc>"
+               FROM merge_queue_entries
+               WHERE project_id = $1
+               ORDER BY queued_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Entries of every project that haven't finished, in the order they were queued
+    pub async fn find_pending(pool: &DbPool) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            MergeQueueEntry,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", task_id as "task_id!: Uuid", attempt_id as "attempt_id!: Uuid", task_title, base_branch, status as "status!: MergeQueueStatus", error, check_output, merge_commit, queued_at as "queued_at!: DateTime<Utc>", finished_at as "finished_at: DateTime<Utc>"
+               FROM merge_queue_entries
+               WHERE finished_at IS NULL
+               ORDER BY queued_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn set_status(
+        pool: &DbPool,
+        id: Uuid,
+        status: MergeQueueStatus,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE merge_queue_entries SET status = $2 WHERE id = $1",
+            id,
+            status
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn fail(
+        pool: &DbPool,
+        id: Uuid,
+        error: &str,
+        check_output: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            r#"UPDATE merge_queue_entries
+               SET status = 'failed', error = $2, check_output = $3, finished_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            error,
+            check_output
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn mark_merged(
+        pool: &DbPool,
+        id: Uuid,
+        merge_commit: &str,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            r#"UPDATE merge_queue_entries
+               SET status = 'merged', merge_commit = $2, finished_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            merge_commit
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Drop the project's finished entries beyond the `keep` that finished last
+    pub async fn prune_finished(
+        pool: &DbPool,
+        project_id: Uuid,
+        keep: i64,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            r#"DELETE FROM merge_queue_entries
+               WHERE project_id = $1 AND finished_at IS NOT NULL
+                 AND id NOT IN (
+                     SELECT id FROM merge_queue_entries
+                     WHERE project_id = $1 AND finished_at IS NOT NULL
+                     ORDER BY finished_at DESC
+                     LIMIT $2
+                 )"#,
+            project_id,
+            keep
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
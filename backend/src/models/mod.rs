@@ -1,12 +1,82 @@
 pub mod api_response;
+pub mod api_token;
+pub mod attempt_group;
+pub mod attempt_linked_worktree;
+pub mod attempt_plan;
+pub mod attempt_review;
+pub mod attempt_rollback;
+pub mod benchmark;
+pub mod board_column;
 pub mod config;
+pub mod conversation_chunk;
+pub mod conversation_context;
+pub mod conversation_search;
+pub mod dev_server_port;
+pub mod entry_bookmark;
+pub mod environment_variable;
+pub mod event;
+pub mod execution_analytics;
+pub mod execution_attention;
+pub mod execution_diff;
+pub mod execution_input;
+pub mod execution_log_chunk;
+pub mod execution_metrics;
 pub mod execution_process;
+pub mod execution_queue;
+pub mod execution_resources;
+pub mod execution_snapshot;
+pub mod execution_tool_usage;
+pub mod execution_verification;
+pub mod executor_profile;
 pub mod executor_session;
+pub mod follow_up_draft;
+pub mod github_issue_sync;
+pub mod github_project_sync;
+pub mod mention;
+pub mod merge_queue_entry;
+pub mod network_interruption;
+pub mod notification_delivery;
+pub mod notification_webhook;
+pub mod notion_sync;
 pub mod project;
+pub mod project_container;
+pub mod project_git_host;
+pub mod project_guardrails;
+pub mod project_invite;
+pub mod project_mcp_server;
+pub mod project_member;
+pub mod project_sandbox;
+pub mod project_security;
+pub mod project_shell_environment;
+pub mod project_worker;
+pub mod prompt_template;
+pub mod release;
+pub mod review_comment;
+pub mod script_hook;
+pub mod search_document;
+pub mod stale_task;
 pub mod task;
+pub mod task_attachment;
 pub mod task_attempt;
+pub mod task_bulk;
+pub mod task_checklist;
+pub mod task_custom_field;
+pub mod task_decomposition;
+pub mod task_defaults;
+pub mod task_dependency;
+pub mod task_embedding;
+pub mod task_label;
+pub mod task_linked_project;
+pub mod task_original_text;
+pub mod task_pr_description;
+pub mod task_schedule;
+pub mod task_share_link;
 
 pub mod task_template;
+pub mod tracker_import;
+pub mod two_factor;
+pub mod user;
+pub mod verification_fix_loop;
 
 pub use api_response::ApiResponse;
 pub use config::{Config, Environment};
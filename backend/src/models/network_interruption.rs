@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// A coding agent run that stopped because the network dropped, waiting to be resumed
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct NetworkInterruption {
+    pub id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub execution_process_id: Uuid, // The run that was cut short
+    pub error: String,              // Output that identified the failure as a network error
+    pub resumed_at: Option<DateTime<Utc>>,
+    pub abandoned_at: Option<DateTime<Utc>>, // Stopped by the user before connectivity returned
+    pub created_at: DateTime<Utc>,
+}
+
+impl NetworkInterruption {
+    pub fn is_open(&self) -> bool {
+        self.resumed_at.is_none() && self.abandoned_at.is_none()
+    }
+
+    /// The note shown at the end of the interrupted run's conversation
+    pub fn conversation_note(&self) -> String {
+        let status = if let Some(resumed_at) = self.resumed_at {
+            format!(
+                "Connectivity returned and the session was resumed at {}.",
+                resumed_at.format("%H:%M:%S UTC")
+            )
+        } else if self.abandoned_at.is_some() {
+            "The attempt was stopped before connectivity returned.".to_string()
+        } else {
+            "Retrying: the session will resume once connectivity returns.".to_string()
+        };
+        format!("Network connection lost ({}). {}", self.error, status)
+    }
+
+    pub async fn create(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+        execution_process_id: Uuid,
+        error: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        db::query_as!(
+            NetworkInterruption,
+            r#"INSERT INTO network_interruptions (id, task_attempt_id, execution_process_id, error)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", execution_process_id as "execution_process_id!: Uuid", error, resumed_at as "resumed_at?: DateTime<Utc>", abandoned_at as "abandoned_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            task_attempt_id,
+            execution_process_id,
+            error
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_task_attempt_id(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            NetworkInterruption,
+            r#"SELECT id as "id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", execution_process_id as "execution_process_id!: Uuid", error, resumed_at as "resumed_at?: DateTime<Utc>", abandoned_at as "abandoned_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM network_interruptions
+               WHERE task_attempt_id = $1
+               ORDER BY created_at ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            NetworkInterruption,
+            r#"SELECT id as "id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", execution_process_id as "execution_process_id!: Uuid", error, resumed_at as "resumed_at?: DateTime<Utc>", abandoned_at as "abandoned_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM network_interruptions
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Interruptions across all attempts that are still waiting for the network
+    pub async fn find_open(pool: &DbPool) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            NetworkInterruption,
+            r#"SELECT id as "id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", execution_process_id as "execution_process_id!: Uuid", error, resumed_at as "resumed_at?: DateTime<Utc>", abandoned_at as "abandoned_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM network_interruptions
+               WHERE resumed_at IS NULL AND abandoned_at IS NULL
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn mark_resumed(pool: &DbPool, id: Uuid) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE network_interruptions SET resumed_at = datetime('now', 'subsec') WHERE id = $1",
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Give up on any open interruption for the attempt, e.g. when the user stops it
+    pub async fn abandon_for_attempt(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = db::query!(
+            "UPDATE network_interruptions SET abandoned_at = datetime('now', 'subsec') WHERE task_attempt_id = $1 AND resumed_at IS NULL AND abandoned_at IS NULL",
+            task_attempt_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,174 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    db::{self, DbPool},
+    services::NotificationEvent,
+};
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum NotificationChannel {
+    Desktop,
+    Webhook,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum NotificationDeliveryStatus {
+    Pending,
+    Sent,
+    Failed,
+}
+
+/// A notification sent, or still being sent, for a project
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct NotificationDelivery {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub task_attempt_id: Option<Uuid>,
+    pub webhook_id: Option<Uuid>, // None for desktop notifications and once the webhook is removed
+    pub channel: NotificationChannel,
+    pub event: NotificationEvent,
+    pub title: String,
+    pub message: String,
+    pub status: NotificationDeliveryStatus,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub next_attempt_at: Option<DateTime<Utc>>, // Set while a webhook delivery is pending
+    pub created_at: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+}
+
+impl NotificationDelivery {
+    /// Log a desktop notification, which is shown once and never retried
+    pub async fn record_desktop(
+        pool: &DbPool,
+        project_id: Uuid,
+        task_attempt_id: Option<Uuid>,
+        event: NotificationEvent,
+        title: &str,
+        message: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        db::query_as!(
+            NotificationDelivery,
+            r#"INSERT INTO notification_deliveries (id, project_id, task_attempt_id, channel, event, title, message, status, attempts, sent_at)
+               VALUES ($1, $2, $3, 'desktop', $4, $5, $6, 'sent', 1, datetime('now', 'subsec'))
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", task_attempt_id as "task_attempt_id: Uuid", webhook_id as "webhook_id: Uuid", channel as "channel!: NotificationChannel", event as "event!: NotificationEvent", title, message, status as "status!: NotificationDeliveryStatus", attempts, last_error, next_attempt_at as "next_attempt_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", sent_at as "sent_at: DateTime<Utc>""#,
+            id,
+            project_id,
+            task_attempt_id,
+            event,
+            title,
+            message
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Queue a webhook delivery, due straight away
+    pub async fn queue_webhook(
+        pool: &DbPool,
+        project_id: Uuid,
+        task_attempt_id: Option<Uuid>,
+        webhook_id: Uuid,
+        event: NotificationEvent,
+        title: &str,
+        message: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let now = Utc::now();
+        db::query_as!(
+            NotificationDelivery,
+            r#"INSERT INTO notification_deliveries (id, project_id, task_attempt_id, webhook_id, channel, event, title, message, status, next_attempt_at)
+               VALUES ($1, $2, $3, $4, 'webhook', $5, $6, $7, 'pending', $8)
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", task_attempt_id as "task_attempt_id: Uuid", webhook_id as "webhook_id: Uuid", channel as "channel!: NotificationChannel", event as "event!: NotificationEvent", title, message, status as "status!: NotificationDeliveryStatus", attempts, last_error, next_attempt_at as "next_attempt_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", sent_at as "sent_at: DateTime<Utc>""#,
+            id,
+            project_id,
+            task_attempt_id,
+            webhook_id,
+            event,
+            title,
+            message,
+            now
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Pending webhook deliveries whose next attempt is due at `now`
+    pub async fn find_due(pool: &DbPool, now: DateTime<Utc>) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            NotificationDelivery,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", task_attempt_id as "task_attempt_id: Uuid", webhook_id as "webhook_id: Uuid", channel as "channel!: NotificationChannel", event as "event!: NotificationEvent", title, message, status as "status!: NotificationDeliveryStatus", attempts, last_error, next_attempt_at as "next_attempt_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", sent_at as "sent_at: DateTime<Utc>"
+               FROM notification_deliveries
+               WHERE status = 'pending' AND next_attempt_at <= $1
+               ORDER BY next_attempt_at ASC"#,
+            now
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The project's notifications, newest first
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            NotificationDelivery,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", task_attempt_id as "task_attempt_id: Uuid", webhook_id as "webhook_id: Uuid", channel as "channel!: NotificationChannel", event as "event!: NotificationEvent", title, message, status as "status!: NotificationDeliveryStatus", attempts, last_error, next_attempt_at as "next_attempt_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", sent_at as "sent_at: DateTime<Utc>"
+               FROM notification_deliveries
+               WHERE project_id = $1
+               ORDER BY created_at DESC
+               LIMIT $2"#,
+            project_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn mark_sent(pool: &DbPool, id: Uuid) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE notification_deliveries
+             SET status = 'sent', attempts = attempts + 1, last_error = NULL, next_attempt_at = NULL, sent_at = datetime('now', 'subsec')
+             WHERE id = $1",
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record a failed attempt. The delivery is retried at `retry_at`, or given up on if `None`.
+    pub async fn record_failure(
+        pool: &DbPool,
+        id: Uuid,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE notification_deliveries
+             SET status = CASE WHEN $3 IS NULL THEN 'failed' ELSE 'pending' END,
+                 attempts = attempts + 1, last_error = $2, next_attempt_at = $3
+             WHERE id = $1",
+            id,
+            error,
+            retry_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
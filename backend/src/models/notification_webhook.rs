@@ -0,0 +1,153 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    db::{self, DbPool},
+    services::NotificationEvent,
+};
+
+/// The JSON shape a webhook expects: Slack's `text` or Discord's `content`
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum WebhookFormat {
+    Slack,
+    Discord,
+}
+
+/// An incoming webhook the project posts to when one of its attempts finishes, fails or
+/// waits for approval
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct NotificationWebhook {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub url: String,
+    pub format: WebhookFormat,
+    pub enabled: bool,
+    pub attempt_succeeded: bool,
+    pub attempt_failed: bool,
+    pub approval_needed: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertNotificationWebhook {
+    pub url: String,
+    pub format: WebhookFormat,
+    pub enabled: Option<bool>,
+    pub attempt_succeeded: Option<bool>,
+    pub attempt_failed: Option<bool>,
+    pub approval_needed: Option<bool>,
+}
+
+impl NotificationWebhook {
+    /// Whether the webhook wants to hear about `event`
+    pub fn is_subscribed(&self, event: NotificationEvent) -> bool {
+        self.enabled
+            && match event {
+                NotificationEvent::AttemptSucceeded => self.attempt_succeeded,
+                NotificationEvent::AttemptFailed => self.attempt_failed,
+                NotificationEvent::ApprovalNeeded => self.approval_needed,
+                NotificationEvent::TaskStale => false,
+            }
+    }
+
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            NotificationWebhook,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", url, format as "format!: WebhookFormat", enabled as "enabled!: bool", attempt_succeeded as "attempt_succeeded!: bool", attempt_failed as "attempt_failed!: bool", approval_needed as "approval_needed!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM notification_webhooks
+               WHERE project_id = $1
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            NotificationWebhook,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", url, format as "format!: WebhookFormat", enabled as "enabled!: bool", attempt_succeeded as "attempt_succeeded!: bool", attempt_failed as "attempt_failed!: bool", approval_needed as "approval_needed!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM notification_webhooks
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &DbPool,
+        project_id: Uuid,
+        data: &UpsertNotificationWebhook,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let enabled = data.enabled.unwrap_or(true);
+        let attempt_succeeded = data.attempt_succeeded.unwrap_or(true);
+        let attempt_failed = data.attempt_failed.unwrap_or(true);
+        let approval_needed = data.approval_needed.unwrap_or(true);
+        db::query_as!(
+            NotificationWebhook,
+            r#"INSERT INTO notification_webhooks (id, project_id, url, format, enabled, attempt_succeeded, attempt_failed, approval_needed)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", url, format as "format!: WebhookFormat", enabled as "enabled!: bool", attempt_succeeded as "attempt_succeeded!: bool", attempt_failed as "attempt_failed!: bool", approval_needed as "approval_needed!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            data.url,
+            data.format,
+            enabled,
+            attempt_succeeded,
+            attempt_failed,
+            approval_needed
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Replace the webhook's settings; toggles left out keep their current value
+    pub async fn update(
+        pool: &DbPool,
+        id: Uuid,
+        data: &UpsertNotificationWebhook,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            NotificationWebhook,
+            r#"UPDATE notification_webhooks
+               SET url = $2, format = $3,
+                   enabled = COALESCE($4, enabled),
+                   attempt_succeeded = COALESCE($5, attempt_succeeded),
+                   attempt_failed = COALESCE($6, attempt_failed),
+                   approval_needed = COALESCE($7, approval_needed),
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", url, format as "format!: WebhookFormat", enabled as "enabled!: bool", attempt_succeeded as "attempt_succeeded!: bool", attempt_failed as "attempt_failed!: bool", approval_needed as "approval_needed!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.url,
+            data.format,
+            data.enabled,
+            data.attempt_succeeded,
+            data.attempt_failed,
+            data.approval_needed
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &DbPool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = db::query!("DELETE FROM notification_webhooks WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,229 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::{TaskStatus, TaskStatusNames};
+use crate::db::{self, DbPool};
+
+/// Which database properties task fields are written to
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[serde(default)]
+#[ts(export)]
+pub struct NotionPropertyMapping {
+    /// Title property; defaults to the database's title column
+    pub title: Option<String>,
+    /// A status or select property; defaults to one named "Status"
+    pub status: Option<String>,
+    /// A text property for the task description; descriptions aren't synced when unset
+    pub description: Option<String>,
+    pub status_names: TaskStatusNames,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct NotionLink {
+    pub project_id: Uuid,
+    pub database_id: String,
+    pub property_mapping: NotionPropertyMapping,
+    pub last_synced_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertNotionLink {
+    /// Database URL or id
+    pub database: String,
+    #[serde(default)]
+    pub property_mapping: NotionPropertyMapping,
+}
+
+/// The page a task is mirrored to, with what was last written to it
+#[derive(Debug, Clone, FromRow)]
+pub struct NotionPage {
+    pub task_id: Uuid,
+    pub page_id: String,
+    pub synced_title: String,
+    pub synced_description: Option<String>,
+    pub synced_status: TaskStatus,
+    pub synced_at: DateTime<Utc>,
+}
+
+#[derive(FromRow)]
+struct LinkRow {
+    project_id: Uuid,
+    database_id: String,
+    property_mapping: String,
+    last_synced_at: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<LinkRow> for NotionLink {
+    fn from(row: LinkRow) -> Self {
+        Self {
+            project_id: row.project_id,
+            database_id: row.database_id,
+            property_mapping: serde_json::from_str(&row.property_mapping).unwrap_or_default(),
+            last_synced_at: row.last_synced_at,
+            last_error: row.last_error,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+impl NotionLink {
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let row = db::query_as!(
+            LinkRow,
+            r#"SELECT project_id as "project_id!: Uuid", database_id, property_mapping, last_synced_at as "last_synced_at?: DateTime<Utc>", last_error, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM notion_links
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(Into::into))
+    }
+
+    pub async fn find_all(pool: &DbPool) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = db::query_as!(
+            LinkRow,
+            r#"SELECT project_id as "project_id!: Uuid", database_id, property_mapping, last_synced_at as "last_synced_at?: DateTime<Utc>", last_error, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM notion_links"#
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Link the project to a database, replacing any previous link. Pages in a different
+    /// database are forgotten so tasks are added to the new one; pages synced before the
+    /// link's `updated_at` are rewritten in full on the next sync.
+    pub async fn upsert(
+        pool: &DbPool,
+        project_id: Uuid,
+        database_id: &str,
+        property_mapping: &NotionPropertyMapping,
+    ) -> Result<Self, sqlx::Error> {
+        let mapping = serde_json::to_string(property_mapping).unwrap_or_else(|_| "{}".to_string());
+        let mut tx = pool.begin().await?;
+        db::query!(
+            "DELETE FROM notion_pages WHERE project_id = $1 AND EXISTS (SELECT 1 FROM notion_links WHERE project_id = $1 AND database_id != $2)",
+            project_id,
+            database_id
+        )
+        .execute(&mut tx)
+        .await?;
+        let row = db::query_as!(
+            LinkRow,
+            r#"INSERT INTO notion_links (project_id, database_id, property_mapping)
+               VALUES ($1, $2, $3)
+               ON CONFLICT(project_id) DO UPDATE SET
+                   database_id = excluded.database_id,
+                   property_mapping = excluded.property_mapping,
+                   last_error = NULL,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid", database_id, property_mapping, last_synced_at as "last_synced_at?: DateTime<Utc>", last_error, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            database_id,
+            mapping
+        )
+        .fetch_one(&mut tx)
+        .await?;
+        tx.commit().await?;
+        Ok(row.into())
+    }
+
+    /// Unlink the project; the database keeps its pages but they stop updating
+    pub async fn delete(pool: &DbPool, project_id: Uuid) -> Result<u64, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        db::query!("DELETE FROM notion_pages WHERE project_id = $1", project_id)
+            .execute(&mut tx)
+            .await?;
+        let result = db::query!("DELETE FROM notion_links WHERE project_id = $1", project_id)
+            .execute(&mut tx)
+            .await?;
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn record_sync(
+        pool: &DbPool,
+        project_id: Uuid,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE notion_links SET last_synced_at = datetime('now', 'subsec'), last_error = $2 WHERE project_id = $1",
+            project_id,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+impl NotionPage {
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            NotionPage,
+            r#"SELECT task_id as "task_id!: Uuid", page_id, synced_title, synced_description, synced_status as "synced_status!: TaskStatus", synced_at as "synced_at!: DateTime<Utc>"
+               FROM notion_pages
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &DbPool,
+        task_id: Uuid,
+        project_id: Uuid,
+        page_id: &str,
+        title: &str,
+        description: Option<&str>,
+        status: &TaskStatus,
+    ) -> Result<(), sqlx::Error> {
+        let status = status.clone();
+        db::query!(
+            r#"INSERT INTO notion_pages (task_id, project_id, page_id, synced_title, synced_description, synced_status)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               ON CONFLICT(task_id) DO UPDATE SET
+                   page_id = excluded.page_id,
+                   synced_title = excluded.synced_title,
+                   synced_description = excluded.synced_description,
+                   synced_status = excluded.synced_status,
+                   synced_at = datetime('now', 'subsec')"#,
+            task_id,
+            project_id,
+            page_id,
+            title,
+            description,
+            status
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &DbPool, task_id: Uuid) -> Result<(), sqlx::Error> {
+        db::query!("DELETE FROM notion_pages WHERE task_id = $1", task_id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+}
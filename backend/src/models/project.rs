@@ -1,11 +1,14 @@
 use chrono::{DateTime, Utc};
 use git2::{BranchType, Repository};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::FromRow;
 use ts_rs::TS;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+use crate::db::{self, DbPool};
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, JsonSchema, TS)]
 #[ts(export)]
 pub struct Project {
     pub id: Uuid,
@@ -14,6 +17,20 @@ pub struct Project {
     pub setup_script: Option<String>,
     pub dev_script: Option<String>,
     pub cleanup_script: Option<String>,
+    /// Used for commits vibe-kanban makes in attempt worktrees; see
+    /// `services::commit_message` for the placeholders
+    pub commit_message_template: Option<String>,
+    /// Run from the repository root to tag, build and publish a release
+    pub release_script: Option<String>,
+    /// Run in an attempt's worktree by the merge queue before the attempt lands
+    pub merge_check_script: Option<String>,
+    /// Commands run one per line in an attempt's worktree after its coding agent finishes;
+    /// see `services::verification`
+    pub verification_script: Option<String>,
+    /// Executor for attempts started without one, ahead of the configured executor
+    pub default_executor: Option<String>,
+    /// Branch attempts are based on when they don't name one, instead of the current branch
+    pub default_branch: Option<String>,
 
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
@@ -21,7 +38,7 @@ pub struct Project {
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize, TS)]
+#[derive(Debug, Deserialize, JsonSchema, TS)]
 #[ts(export)]
 pub struct CreateProject {
     pub name: String,
@@ -30,6 +47,12 @@ pub struct CreateProject {
     pub setup_script: Option<String>,
     pub dev_script: Option<String>,
     pub cleanup_script: Option<String>,
+    pub commit_message_template: Option<String>,
+    pub release_script: Option<String>,
+    pub merge_check_script: Option<String>,
+    pub verification_script: Option<String>,
+    pub default_executor: Option<String>,
+    pub default_branch: Option<String>,
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -40,6 +63,12 @@ pub struct UpdateProject {
     pub setup_script: Option<String>,
     pub dev_script: Option<String>,
     pub cleanup_script: Option<String>,
+    pub commit_message_template: Option<String>,
+    pub release_script: Option<String>,
+    pub merge_check_script: Option<String>,
+    pub verification_script: Option<String>,
+    pub default_executor: Option<String>,
+    pub default_branch: Option<String>,
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -51,6 +80,12 @@ pub struct CreateProjectFromGitHub {
     pub setup_script: Option<String>,
     pub dev_script: Option<String>,
     pub cleanup_script: Option<String>,
+    pub commit_message_template: Option<String>,
+    pub release_script: Option<String>,
+    pub merge_check_script: Option<String>,
+    pub verification_script: Option<String>,
+    pub default_executor: Option<String>,
+    pub default_branch: Option<String>,
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -62,6 +97,12 @@ pub struct ProjectWithBranch {
     pub setup_script: Option<String>,
     pub dev_script: Option<String>,
     pub cleanup_script: Option<String>,
+    pub commit_message_template: Option<String>,
+    pub release_script: Option<String>,
+    pub merge_check_script: Option<String>,
+    pub verification_script: Option<String>,
+    pub default_executor: Option<String>,
+    pub default_branch: Option<String>,
     pub current_branch: Option<String>,
 
     #[ts(type = "Date")]
@@ -104,19 +145,19 @@ pub struct CreateBranch {
 }
 
 impl Project {
-    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
-        sqlx::query_as!(
+    pub async fn find_all(pool: &DbPool) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
             Project,
-            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects ORDER BY created_at DESC"#
+            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, commit_message_template, release_script, merge_check_script, verification_script, default_executor, default_branch, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects ORDER BY created_at DESC"#
         )
         .fetch_all(pool)
         .await
     }
 
-    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
-        sqlx::query_as!(
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
             Project,
-            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE id = $1"#,
+            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, commit_message_template, release_script, merge_check_script, verification_script, default_executor, default_branch, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE id = $1"#,
             id
         )
         .fetch_optional(pool)
@@ -124,12 +165,12 @@ impl Project {
     }
 
     pub async fn find_by_git_repo_path(
-        pool: &SqlitePool,
+        pool: &DbPool,
         git_repo_path: &str,
     ) -> Result<Option<Self>, sqlx::Error> {
-        sqlx::query_as!(
+        db::query_as!(
             Project,
-            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE git_repo_path = $1"#,
+            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, commit_message_template, release_script, merge_check_script, verification_script, default_executor, default_branch, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE git_repo_path = $1"#,
             git_repo_path
         )
         .fetch_optional(pool)
@@ -137,13 +178,13 @@ impl Project {
     }
 
     pub async fn find_by_git_repo_path_excluding_id(
-        pool: &SqlitePool,
+        pool: &DbPool,
         git_repo_path: &str,
         exclude_id: Uuid,
     ) -> Result<Option<Self>, sqlx::Error> {
-        sqlx::query_as!(
+        db::query_as!(
             Project,
-            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE git_repo_path = $1 AND id != $2"#,
+            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, commit_message_template, release_script, merge_check_script, verification_script, default_executor, default_branch, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE git_repo_path = $1 AND id != $2"#,
             git_repo_path,
             exclude_id
         )
@@ -152,56 +193,75 @@ impl Project {
     }
 
     pub async fn create(
-        pool: &SqlitePool,
+        pool: &DbPool,
         data: &CreateProject,
         project_id: Uuid,
     ) -> Result<Self, sqlx::Error> {
-        sqlx::query_as!(
+        db::query_as!(
             Project,
-            r#"INSERT INTO projects (id, name, git_repo_path, setup_script, dev_script, cleanup_script) VALUES ($1, $2, $3, $4, $5, $6) RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"INSERT INTO projects (id, name, git_repo_path, setup_script, dev_script, cleanup_script, commit_message_template, release_script, merge_check_script, verification_script, default_executor, default_branch) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12) RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, commit_message_template, release_script, merge_check_script, verification_script, default_executor, default_branch, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             project_id,
             data.name,
             data.git_repo_path,
             data.setup_script,
             data.dev_script,
-            data.cleanup_script
+            data.cleanup_script,
+            data.commit_message_template,
+            data.release_script,
+            data.merge_check_script,
+            data.verification_script,
+            data.default_executor,
+            data.default_branch
         )
         .fetch_one(pool)
         .await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn update(
-        pool: &SqlitePool,
+        pool: &DbPool,
         id: Uuid,
         name: String,
         git_repo_path: String,
         setup_script: Option<String>,
         dev_script: Option<String>,
         cleanup_script: Option<String>,
+        commit_message_template: Option<String>,
+        release_script: Option<String>,
+        merge_check_script: Option<String>,
+        verification_script: Option<String>,
+        default_executor: Option<String>,
+        default_branch: Option<String>,
     ) -> Result<Self, sqlx::Error> {
-        sqlx::query_as!(
+        db::query_as!(
             Project,
-            r#"UPDATE projects SET name = $2, git_repo_path = $3, setup_script = $4, dev_script = $5, cleanup_script = $6 WHERE id = $1 RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"UPDATE projects SET name = $2, git_repo_path = $3, setup_script = $4, dev_script = $5, cleanup_script = $6, commit_message_template = $7, release_script = $8, merge_check_script = $9, verification_script = $10, default_executor = $11, default_branch = $12 WHERE id = $1 RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, commit_message_template, release_script, merge_check_script, verification_script, default_executor, default_branch, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             name,
             git_repo_path,
             setup_script,
             dev_script,
-            cleanup_script
+            cleanup_script,
+            commit_message_template,
+            release_script,
+            merge_check_script,
+            verification_script,
+            default_executor,
+            default_branch
         )
         .fetch_one(pool)
         .await
     }
 
-    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
-        let result = sqlx::query!("DELETE FROM projects WHERE id = $1", id)
+    pub async fn delete(pool: &DbPool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = db::query!("DELETE FROM projects WHERE id = $1", id)
             .execute(pool)
             .await?;
         Ok(result.rows_affected())
     }
 
-    pub async fn exists(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
-        let result = sqlx::query!(
+    pub async fn exists(pool: &DbPool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let count = db::query_scalar!(
             r#"
                 SELECT COUNT(*) as "count!: i64"
                 FROM projects
@@ -212,7 +272,7 @@ impl Project {
         .fetch_one(pool)
         .await?;
 
-        Ok(result.count > 0)
+        Ok(count > 0)
     }
 
     pub fn get_current_branch(&self) -> Result<String, git2::Error> {
@@ -236,6 +296,12 @@ impl Project {
             setup_script: self.setup_script,
             dev_script: self.dev_script,
             cleanup_script: self.cleanup_script,
+            commit_message_template: self.commit_message_template,
+            release_script: self.release_script,
+            merge_check_script: self.merge_check_script,
+            verification_script: self.verification_script,
+            default_executor: self.default_executor,
+            default_branch: self.default_branch,
             current_branch,
             created_at: self.created_at,
             updated_at: self.updated_at,
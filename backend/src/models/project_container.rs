@@ -0,0 +1,94 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// The Docker image a project's coding agents and scripts run in, with the attempt's
+/// worktree mounted
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct ProjectContainer {
+    pub project_id: Uuid,
+    /// As `docker run` takes it, e.g. `node:20` or `ghcr.io/acme/toolchain:1.4`
+    pub image: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertProjectContainer {
+    pub image: String,
+}
+
+/// Why the image can't be saved, if it can't
+pub fn container_image_error(image: &str) -> Option<String> {
+    if image.trim().is_empty() {
+        return Some("Name the Docker image to run in".to_string());
+    }
+    if image.chars().any(char::is_whitespace) || image.starts_with('-') {
+        return Some(format!("\"{}\" isn't a Docker image name", image));
+    }
+    None
+}
+
+impl ProjectContainer {
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            ProjectContainer,
+            r#"SELECT project_id as "project_id!: Uuid", image, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_containers
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(pool: &DbPool, project_id: Uuid, image: &str) -> Result<Self, sqlx::Error> {
+        db::query_as!(
+            ProjectContainer,
+            r#"INSERT INTO project_containers (project_id, image)
+               VALUES ($1, $2)
+               ON CONFLICT (project_id) DO UPDATE SET
+                   image = excluded.image,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid", image, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            image
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Go back to running on the host. Returns whether the project had an image.
+    pub async fn delete(pool: &DbPool, project_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = db::query!(
+            "DELETE FROM project_containers WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_names_are_checked() {
+        assert_eq!(container_image_error("node:20"), None);
+        assert_eq!(container_image_error("ghcr.io/acme/toolchain:1.4"), None);
+        assert!(container_image_error(" ").is_some());
+        assert!(container_image_error("node:20 --privileged").is_some());
+        assert!(container_image_error("--privileged").is_some());
+    }
+}
@@ -0,0 +1,119 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum GitHostKind {
+    GitHub,
+    GitLab,
+    Bitbucket,
+}
+
+impl GitHostKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            GitHostKind::GitHub => "GitHub",
+            GitHostKind::GitLab => "GitLab",
+            GitHostKind::Bitbucket => "Bitbucket",
+        }
+    }
+}
+
+/// Where a project's pull requests are opened, when that isn't GitHub with the account's
+/// own token
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProjectGitHost {
+    pub project_id: Uuid,
+    pub kind: GitHostKind,
+    /// The instance's URL, for self-hosted GitLab and GitHub Enterprise. `None` means the
+    /// host the remote points at, or the cloud API for Bitbucket.
+    pub base_url: Option<String>,
+    /// Access token, encrypted. Never sent back; `has_token` tells whether there is one.
+    #[serde(skip)]
+    #[ts(skip)]
+    pub token: Option<String>,
+    pub has_token: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertProjectGitHost {
+    pub kind: GitHostKind,
+    pub base_url: Option<String>,
+    /// A new token; `None` or empty keeps the one saved
+    pub token: Option<String>,
+}
+
+impl ProjectGitHost {
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            ProjectGitHost,
+            r#"SELECT project_id as "project_id!: Uuid", kind as "kind!: GitHostKind", base_url, token, token IS NOT NULL as "has_token!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_git_hosts
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Set the project's host. The token is stored as given, so it must already be
+    /// encrypted; `None` keeps the one saved.
+    pub async fn upsert(
+        pool: &DbPool,
+        project_id: Uuid,
+        kind: GitHostKind,
+        base_url: Option<&str>,
+        token: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        let updated = db::query!(
+            "UPDATE project_git_hosts SET kind = $2, base_url = $3, token = COALESCE($4, token), updated_at = datetime('now', 'subsec')
+             WHERE project_id = $1",
+            project_id,
+            kind,
+            base_url,
+            token
+        )
+        .execute(pool)
+        .await?;
+        if updated.rows_affected() == 0 {
+            db::query!(
+                "INSERT INTO project_git_hosts (project_id, kind, base_url, token) VALUES ($1, $2, $3, $4)",
+                project_id,
+                kind,
+                base_url,
+                token
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        Self::find_by_project_id(pool, project_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    /// Go back to GitHub with the account's token. Returns whether a host was set.
+    pub async fn delete(pool: &DbPool, project_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = db::query!(
+            "DELETE FROM project_git_hosts WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
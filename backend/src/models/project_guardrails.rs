@@ -0,0 +1,249 @@
+use chrono::{DateTime, Datelike, NaiveDateTime, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// Why a project's attempts wait in the execution queue however many slots are free
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum GuardrailHold {
+    /// The project's runs this month have cost its monthly budget
+    BudgetExceeded,
+    /// It's outside every window the project's agents may start in
+    OutsideWorkingHours,
+}
+
+/// Times of day a project's agents may start in, in the server's local time. Agents
+/// running when a window closes carry on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ExecutionWindow {
+    /// `HH:MM`
+    pub start: String,
+    /// `HH:MM`, before `start` for a window running past midnight
+    pub end: String,
+    /// Only windows starting Monday to Friday
+    #[serde(default)]
+    pub weekdays_only: bool,
+}
+
+fn parse_time(time: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(time.trim(), "%H:%M").ok()
+}
+
+impl ExecutionWindow {
+    pub fn contains(&self, at: NaiveDateTime) -> bool {
+        let (Some(start), Some(end)) = (parse_time(&self.start), parse_time(&self.end)) else {
+            return false;
+        };
+        let time = at.time();
+        let (inside, started_on) = if start <= end {
+            (start <= time && time < end, at.date())
+        } else if time >= start {
+            (true, at.date())
+        } else {
+            // In the part after midnight of the window that started the day before
+            (time < end, at.date().pred_opt().unwrap_or(at.date()))
+        };
+        inside
+            && !(self.weekdays_only && matches!(started_on.weekday(), Weekday::Sat | Weekday::Sun))
+    }
+}
+
+/// A project's monthly budget and the windows its agents may start in
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ProjectGuardrails {
+    pub project_id: Uuid,
+    /// In US dollars, counted from the first of the month in the server's local time
+    pub monthly_budget_usd: Option<f64>,
+    /// Empty lets agents start any time
+    pub allowed_windows: Vec<ExecutionWindow>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct ProjectGuardrailsRow {
+    project_id: Uuid,
+    monthly_budget_usd: Option<f64>,
+    allowed_windows: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<ProjectGuardrailsRow> for ProjectGuardrails {
+    fn from(row: ProjectGuardrailsRow) -> Self {
+        Self {
+            project_id: row.project_id,
+            monthly_budget_usd: row.monthly_budget_usd,
+            allowed_windows: serde_json::from_str(&row.allowed_windows).unwrap_or_default(),
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertProjectGuardrails {
+    pub monthly_budget_usd: Option<f64>,
+    #[serde(default)]
+    pub allowed_windows: Vec<ExecutionWindow>,
+}
+
+/// Why the guardrails can't be saved, if they can't
+pub fn guardrails_error(guardrails: &UpsertProjectGuardrails) -> Option<String> {
+    if guardrails
+        .monthly_budget_usd
+        .is_some_and(|budget| !budget.is_finite() || budget < 0.0)
+    {
+        return Some("The monthly budget can't be negative".to_string());
+    }
+    for window in &guardrails.allowed_windows {
+        let (Some(start), Some(end)) = (parse_time(&window.start), parse_time(&window.end)) else {
+            return Some(format!(
+                "'{}' to '{}' isn't a window; give times as HH:MM",
+                window.start, window.end
+            ));
+        };
+        if start == end {
+            return Some(format!(
+                "The window starting at {} ends when it starts",
+                window.start
+            ));
+        }
+    }
+    None
+}
+
+impl ProjectGuardrails {
+    /// Whether agents may start at `at`, in the server's local time
+    pub fn allows_starting_at(&self, at: NaiveDateTime) -> bool {
+        self.allowed_windows.is_empty()
+            || self
+                .allowed_windows
+                .iter()
+                .any(|window| window.contains(at))
+    }
+
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let row = db::query_as!(
+            ProjectGuardrailsRow,
+            r#"SELECT project_id as "project_id!: Uuid", monthly_budget_usd, allowed_windows, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_guardrails
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(ProjectGuardrails::from))
+    }
+
+    pub async fn upsert(
+        pool: &DbPool,
+        project_id: Uuid,
+        data: &UpsertProjectGuardrails,
+    ) -> Result<Self, sqlx::Error> {
+        let windows: Vec<ExecutionWindow> = data
+            .allowed_windows
+            .iter()
+            .map(|window| ExecutionWindow {
+                start: window.start.trim().to_string(),
+                end: window.end.trim().to_string(),
+                weekdays_only: window.weekdays_only,
+            })
+            .collect();
+        let allowed_windows = serde_json::to_string(&windows).unwrap_or_else(|_| "[]".to_string());
+        let row = db::query_as!(
+            ProjectGuardrailsRow,
+            r#"INSERT INTO project_guardrails (project_id, monthly_budget_usd, allowed_windows)
+               VALUES ($1, $2, $3)
+               ON CONFLICT (project_id) DO UPDATE SET
+                   monthly_budget_usd = excluded.monthly_budget_usd,
+                   allowed_windows = excluded.allowed_windows,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid", monthly_budget_usd, allowed_windows, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            data.monthly_budget_usd,
+            allowed_windows
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(row.into())
+    }
+
+    /// Lift the project's guardrails. Returns whether it had any.
+    pub async fn delete(pool: &DbPool, project_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = db::query!(
+            "DELETE FROM project_guardrails WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn window(start: &str, end: &str, weekdays_only: bool) -> ExecutionWindow {
+        ExecutionWindow {
+            start: start.to_string(),
+            end: end.to_string(),
+            weekdays_only,
+        }
+    }
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        // 2025-09-01 is a Monday
+        NaiveDate::from_ymd_opt(2025, 9, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_windows_contain_their_times() {
+        let office = window("09:00", "18:00", true);
+        assert!(office.contains(at(1, 9, 0)));
+        assert!(!office.contains(at(1, 18, 0)));
+        assert!(!office.contains(at(6, 10, 0)));
+
+        // Friday night's window runs into Saturday; Sunday night's doesn't count
+        let night = window("22:00", "06:00", true);
+        assert!(night.contains(at(5, 23, 0)));
+        assert!(night.contains(at(6, 5, 59)));
+        assert!(!night.contains(at(7, 23, 0)));
+        assert!(!night.contains(at(8, 3, 0)));
+        assert!(night.contains(at(9, 3, 0)));
+        assert!(!night.contains(at(9, 12, 0)));
+    }
+
+    #[test]
+    fn test_guardrails_are_checked() {
+        let upsert = |budget: Option<f64>, windows: Vec<ExecutionWindow>| UpsertProjectGuardrails {
+            monthly_budget_usd: budget,
+            allowed_windows: windows,
+        };
+        assert_eq!(
+            guardrails_error(&upsert(Some(50.0), vec![window("09:00", "18:00", false)])),
+            None
+        );
+        assert_eq!(guardrails_error(&upsert(Some(0.0), vec![])), None);
+        assert!(guardrails_error(&upsert(Some(-1.0), vec![])).is_some());
+        assert!(guardrails_error(&upsert(None, vec![window("9am", "18:00", false)])).is_some());
+        assert!(guardrails_error(&upsert(None, vec![window("09:00", "09:00", false)])).is_some());
+    }
+}
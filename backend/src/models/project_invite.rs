@@ -0,0 +1,160 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::project_member::ProjectRole;
+use crate::db::{self, DbPool};
+
+/// An invite to join a project with a role. The token in the invite link is shown once,
+/// when it's created, and works once.
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct ProjectInvite {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    /// The token's first characters, to tell invites apart
+    pub token_prefix: String,
+    pub role: ProjectRole,
+    pub invited_by: Option<Uuid>,
+    pub expires_at: DateTime<Utc>,
+    pub accepted_by: Option<Uuid>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateProjectInvite {
+    pub role: ProjectRole,
+    /// Days until the invite stops working; a week when left out
+    pub expires_in_days: Option<u32>,
+}
+
+/// An invite just created, with the token to put in its link. It can't be shown again.
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct CreatedProjectInvite {
+    pub invite: ProjectInvite,
+    pub token: String,
+}
+
+/// What an invite link shows before it's accepted
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct InviteDetails {
+    pub project_id: Uuid,
+    pub project_name: String,
+    pub role: ProjectRole,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Accept an invite as the signed-in user, or, without a session, as a new account
+#[derive(Debug, Default, Deserialize, TS)]
+#[ts(export)]
+pub struct AcceptProjectInvite {
+    pub username: Option<String>,
+    pub display_name: Option<String>,
+    pub password: Option<String>,
+}
+
+impl ProjectInvite {
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            ProjectInvite,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", token_prefix, role as "role!: ProjectRole", invited_by as "invited_by: Uuid", expires_at as "expires_at!: DateTime<Utc>", accepted_by as "accepted_by: Uuid", accepted_at as "accepted_at: DateTime<Utc>", revoked_at as "revoked_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM project_invites
+               WHERE project_id = $1
+               ORDER BY created_at DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The invite with this hash, unless it was used, revoked or has expired
+    pub async fn find_pending_by_hash(
+        pool: &DbPool,
+        token_hash: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            ProjectInvite,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", token_prefix, role as "role!: ProjectRole", invited_by as "invited_by: Uuid", expires_at as "expires_at!: DateTime<Utc>", accepted_by as "accepted_by: Uuid", accepted_at as "accepted_at: DateTime<Utc>", revoked_at as "revoked_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM project_invites
+               WHERE token_hash = $1
+               AND accepted_at IS NULL
+               AND revoked_at IS NULL
+               AND expires_at > $2"#,
+            token_hash,
+            now
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &DbPool,
+        project_id: Uuid,
+        role: ProjectRole,
+        invited_by: Option<Uuid>,
+        token_hash: &str,
+        token_prefix: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        db::query_as!(
+            ProjectInvite,
+            r#"INSERT INTO project_invites (id, project_id, token_hash, token_prefix, role, invited_by, expires_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", token_prefix, role as "role!: ProjectRole", invited_by as "invited_by: Uuid", expires_at as "expires_at!: DateTime<Utc>", accepted_by as "accepted_by: Uuid", accepted_at as "accepted_at: DateTime<Utc>", revoked_at as "revoked_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            token_hash,
+            token_prefix,
+            role,
+            invited_by,
+            expires_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Mark the invite used by the user. Returns whether it was still pending, so that two
+    /// people can't both use it and an invite that expired since it was looked up can't be.
+    pub async fn accept(
+        pool: &DbPool,
+        id: Uuid,
+        user_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = db::query!(
+            r#"UPDATE project_invites SET accepted_by = $2, accepted_at = datetime('now', 'subsec')
+               WHERE id = $1 AND accepted_at IS NULL AND revoked_at IS NULL AND expires_at > $3"#,
+            id,
+            user_id,
+            now
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Stop the invite from working. It stays listed.
+    pub async fn revoke(pool: &DbPool, project_id: Uuid, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = db::query!(
+            r#"UPDATE project_invites SET revoked_at = datetime('now', 'subsec')
+               WHERE id = $1 AND project_id = $2 AND accepted_at IS NULL AND revoked_at IS NULL"#,
+            id,
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,259 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    db::{self, DbPool},
+    utils::shell::shell_quote,
+};
+
+/// An MCP server the coding agents of a project's attempts are started with
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ProjectMcpServer {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    pub command: String,
+    pub args: Vec<String>,
+    pub env: BTreeMap<String, String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct ProjectMcpServerRow {
+    id: Uuid,
+    project_id: Uuid,
+    name: String,
+    command: String,
+    args: String,
+    env: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<ProjectMcpServerRow> for ProjectMcpServer {
+    fn from(row: ProjectMcpServerRow) -> Self {
+        Self {
+            id: row.id,
+            project_id: row.project_id,
+            name: row.name,
+            command: row.command,
+            args: serde_json::from_str(&row.args).unwrap_or_default(),
+            env: serde_json::from_str(&row.env).unwrap_or_default(),
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertProjectMcpServer {
+    /// Letters, digits, `-` and `_`; a server of the same name is replaced
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+}
+
+/// Why the server can't be saved, if it can't
+pub fn mcp_server_error(server: &UpsertProjectMcpServer) -> Option<String> {
+    let valid_name = !server.name.is_empty()
+        && server
+            .name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid_name {
+        return Some(format!(
+            "\"{}\" isn't a valid MCP server name: use letters, digits, - and _",
+            server.name
+        ));
+    }
+    if server.command.trim().is_empty() {
+        return Some("An MCP server needs a command to start it".to_string());
+    }
+    None
+}
+
+/// The servers as Claude Code's `--mcp-config` flag, appended to its command line
+pub fn claude_mcp_args(servers: &[ProjectMcpServer]) -> String {
+    if servers.is_empty() {
+        return String::new();
+    }
+    let config = serde_json::json!({
+        "mcpServers": servers
+            .iter()
+            .map(|server| {
+                (
+                    server.name.clone(),
+                    serde_json::json!({
+                        "command": server.command,
+                        "args": server.args,
+                        "env": server.env,
+                    }),
+                )
+            })
+            .collect::<serde_json::Map<_, _>>()
+    });
+    format!(" --mcp-config {}", shell_quote(&config.to_string()))
+}
+
+/// The servers as Codex `-c mcp_servers.<name>...` overrides, appended to its command line.
+/// The values are TOML, which JSON strings and arrays also are.
+pub fn codex_mcp_args(servers: &[ProjectMcpServer]) -> String {
+    let toml_string = |value: &str| serde_json::Value::from(value).to_string();
+    let mut args = String::new();
+    for server in servers {
+        let prefix = format!("mcp_servers.{}", server.name);
+        let env = server
+            .env
+            .iter()
+            .map(|(key, value)| format!("{}={}", toml_string(key), toml_string(value)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        for setting in [
+            format!("{}.command={}", prefix, toml_string(&server.command)),
+            format!(
+                "{}.args={}",
+                prefix,
+                serde_json::Value::from(server.args.clone())
+            ),
+            format!("{}.env={{{}}}", prefix, env),
+        ] {
+            args.push_str(" -c ");
+            args.push_str(&shell_quote(&setting));
+        }
+    }
+    args
+}
+
+impl ProjectMcpServer {
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = db::query_as!(
+            ProjectMcpServerRow,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", name, command, args, env, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_mcp_servers
+               WHERE project_id = $1
+               ORDER BY name ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(ProjectMcpServer::from).collect())
+    }
+
+    /// The servers of the task's project
+    pub async fn find_for_task(pool: &DbPool, task_id: Uuid) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = db::query_as!(
+            ProjectMcpServerRow,
+            r#"SELECT s.id as "id!: Uuid", s.project_id as "project_id!: Uuid", s.name, s.command, s.args, s.env, s.created_at as "created_at!: DateTime<Utc>", s.updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_mcp_servers s
+               JOIN tasks t ON t.project_id = s.project_id
+               WHERE t.id = $1
+               ORDER BY s.name ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(ProjectMcpServer::from).collect())
+    }
+
+    /// Save the server under its name, replacing the project's server of that name
+    pub async fn upsert(
+        pool: &DbPool,
+        project_id: Uuid,
+        data: &UpsertProjectMcpServer,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let args = serde_json::to_string(&data.args).unwrap_or_else(|_| "[]".to_string());
+        let env = serde_json::to_string(&data.env).unwrap_or_else(|_| "{}".to_string());
+        let row = db::query_as!(
+            ProjectMcpServerRow,
+            r#"INSERT INTO project_mcp_servers (id, project_id, name, command, args, env)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               ON CONFLICT (project_id, name) DO UPDATE SET
+                   command = excluded.command,
+                   args = excluded.args,
+                   env = excluded.env,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", name, command, args, env, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            data.name,
+            data.command,
+            args,
+            env
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(row.into())
+    }
+
+    /// Remove the project's server of that name. Returns whether there was one.
+    pub async fn delete(pool: &DbPool, project_id: Uuid, name: &str) -> Result<bool, sqlx::Error> {
+        let result = db::query!(
+            "DELETE FROM project_mcp_servers WHERE project_id = $1 AND name = $2",
+            project_id,
+            name
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(name: &str) -> ProjectMcpServer {
+        ProjectMcpServer {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            name: name.to_string(),
+            command: "npx".to_string(),
+            args: vec!["-y".to_string(), "@acme/mcp-server".to_string()],
+            env: BTreeMap::from([("ACME_TOKEN".to_string(), "it's secret".to_string())]),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_servers_become_agent_flags() {
+        assert_eq!(claude_mcp_args(&[]), "");
+        assert_eq!(
+            claude_mcp_args(&[server("acme")]),
+            r#" --mcp-config '{"mcpServers":{"acme":{"args":["-y","@acme/mcp-server"],"command":"npx","env":{"ACME_TOKEN":"it'\''s secret"}}}}'"#
+        );
+        assert_eq!(
+            codex_mcp_args(&[server("acme")]),
+            r#" -c 'mcp_servers.acme.command="npx"' -c 'mcp_servers.acme.args=["-y","@acme/mcp-server"]' -c 'mcp_servers.acme.env={"ACME_TOKEN"="it'\''s secret"}'"#
+        );
+    }
+
+    #[test]
+    fn test_server_names_are_checked() {
+        let upsert = |name: &str, command: &str| UpsertProjectMcpServer {
+            name: name.to_string(),
+            command: command.to_string(),
+            args: Vec::new(),
+            env: BTreeMap::new(),
+        };
+        assert_eq!(mcp_server_error(&upsert("acme_docs-2", "npx")), None);
+        assert!(mcp_server_error(&upsert("acme docs", "npx")).is_some());
+        assert!(mcp_server_error(&upsert("acme.docs", "npx")).is_some());
+        assert!(mcp_server_error(&upsert("acme", " ")).is_some());
+    }
+}
@@ -0,0 +1,129 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// What a user may do in a project. Each role can do everything the ones before it can.
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, TS)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum ProjectRole {
+    /// Reads the board, conversations and logs
+    Viewer,
+    /// Also edits and deletes tasks and starts, follows up on and stops attempts
+    Member,
+    /// Also changes the project's settings and who has access to it
+    Admin,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProjectMember {
+    pub project_id: Uuid,
+    pub user_id: Uuid,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub role: ProjectRole,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Give a user a role in the project, or change the one they have
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct SetProjectMember {
+    pub username: String,
+    pub role: ProjectRole,
+}
+
+/// A project the signed-in user can see, and what they may do in it
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct ProjectMembership {
+    pub project_id: Uuid,
+    pub role: ProjectRole,
+}
+
+impl ProjectMember {
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            ProjectMember,
+            r#"SELECT pm.project_id as "project_id!: Uuid", pm.user_id as "user_id!: Uuid", u.username, u.display_name, pm.role as "role!: ProjectRole", pm.created_at as "created_at!: DateTime<Utc>"
+               FROM project_members pm
+               JOIN users u ON u.id = pm.user_id
+               WHERE pm.project_id = $1
+               ORDER BY u.username ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_role(
+        pool: &DbPool,
+        project_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<ProjectRole>, sqlx::Error> {
+        db::query_scalar!(
+            r#"SELECT role as "role!: ProjectRole" FROM project_members WHERE project_id = $1 AND user_id = $2"#,
+            project_id,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_memberships(
+        pool: &DbPool,
+        user_id: Uuid,
+    ) -> Result<Vec<ProjectMembership>, sqlx::Error> {
+        db::query_as!(
+            ProjectMembership,
+            r#"SELECT project_id as "project_id!: Uuid", role as "role!: ProjectRole"
+               FROM project_members
+               WHERE user_id = $1"#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &DbPool,
+        project_id: Uuid,
+        user_id: Uuid,
+        role: ProjectRole,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            "INSERT INTO project_members (project_id, user_id, role) VALUES ($1, $2, $3)
+             ON CONFLICT(project_id, user_id) DO UPDATE SET role = excluded.role, updated_at = datetime('now', 'subsec')",
+            project_id,
+            user_id,
+            role
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(
+        pool: &DbPool,
+        project_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = db::query!(
+            "DELETE FROM project_members WHERE project_id = $1 AND user_id = $2",
+            project_id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
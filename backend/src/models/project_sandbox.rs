@@ -0,0 +1,77 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// Whether a project's coding agents run sandboxed, in place of the global setting
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct ProjectSandbox {
+    pub project_id: Uuid,
+    pub enabled: bool,
+    /// Cut the agents off from the network too
+    pub deny_network: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertProjectSandbox {
+    pub enabled: bool,
+    #[serde(default)]
+    pub deny_network: bool,
+}
+
+impl ProjectSandbox {
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            ProjectSandbox,
+            r#"SELECT project_id as "project_id!: Uuid", enabled as "enabled!: bool", deny_network as "deny_network!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_sandboxes
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &DbPool,
+        project_id: Uuid,
+        data: &UpsertProjectSandbox,
+    ) -> Result<Self, sqlx::Error> {
+        db::query_as!(
+            ProjectSandbox,
+            r#"INSERT INTO project_sandboxes (project_id, enabled, deny_network)
+               VALUES ($1, $2, $3)
+               ON CONFLICT (project_id) DO UPDATE SET
+                   enabled = excluded.enabled,
+                   deny_network = excluded.deny_network,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid", enabled as "enabled!: bool", deny_network as "deny_network!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            data.enabled,
+            data.deny_network
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Go back to the global setting. Returns whether the project had its own.
+    pub async fn delete(pool: &DbPool, project_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = db::query!(
+            "DELETE FROM project_sandboxes WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// Sign-in rules a project holds the people who reach it to
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct ProjectSecurity {
+    pub project_id: Uuid,
+    /// Password accounts can't reach the project until they turn on two-factor
+    /// authentication. Accounts that sign in through OIDC are left to the provider.
+    pub require_two_factor: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertProjectSecurity {
+    pub require_two_factor: bool,
+}
+
+impl ProjectSecurity {
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            ProjectSecurity,
+            r#"SELECT project_id as "project_id!: Uuid", require_two_factor as "require_two_factor!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_security
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Whether any project the user reaches requires two-factor authentication: any at
+    /// all for admins, who reach every project, and those they are a member of otherwise
+    pub async fn requires_two_factor_of(
+        pool: &DbPool,
+        user_id: Uuid,
+        is_admin: bool,
+    ) -> Result<bool, sqlx::Error> {
+        let count = db::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64"
+               FROM project_security ps
+               WHERE ps.require_two_factor
+                 AND ($2 OR EXISTS (
+                     SELECT 1 FROM project_members pm
+                     WHERE pm.project_id = ps.project_id AND pm.user_id = $1
+                 ))"#,
+            user_id,
+            is_admin
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(count > 0)
+    }
+
+    pub async fn upsert(
+        pool: &DbPool,
+        project_id: Uuid,
+        data: &UpsertProjectSecurity,
+    ) -> Result<Self, sqlx::Error> {
+        db::query_as!(
+            ProjectSecurity,
+            r#"INSERT INTO project_security (project_id, require_two_factor)
+               VALUES ($1, $2)
+               ON CONFLICT (project_id) DO UPDATE SET
+                   require_two_factor = excluded.require_two_factor,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid", require_two_factor as "require_two_factor!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            data.require_two_factor
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Drop the project's rules. Returns whether it had any.
+    pub async fn delete(pool: &DbPool, project_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = db::query!(
+            "DELETE FROM project_security WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,93 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::config::{LoginShellMode, ShellEnvironmentConfig};
+use crate::db::{self, DbPool};
+
+/// Where a project's commands get the user's shell environment from, in place of the
+/// global setting
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct ProjectShellEnvironment {
+    pub project_id: Uuid,
+    pub login_shell: LoginShellMode,
+    /// Relative to the attempt's worktree, so it can be a file in the repository
+    pub env_file: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertProjectShellEnvironment {
+    pub login_shell: LoginShellMode,
+    #[serde(default)]
+    pub env_file: Option<String>,
+}
+
+impl From<ProjectShellEnvironment> for ShellEnvironmentConfig {
+    fn from(project: ProjectShellEnvironment) -> Self {
+        ShellEnvironmentConfig {
+            login_shell: project.login_shell,
+            env_file: project.env_file,
+        }
+    }
+}
+
+impl ProjectShellEnvironment {
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            ProjectShellEnvironment,
+            r#"SELECT project_id as "project_id!: Uuid", login_shell as "login_shell!: LoginShellMode", env_file, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_shell_environments
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &DbPool,
+        project_id: Uuid,
+        data: &UpsertProjectShellEnvironment,
+    ) -> Result<Self, sqlx::Error> {
+        let env_file = data
+            .env_file
+            .as_deref()
+            .map(str::trim)
+            .filter(|env_file| !env_file.is_empty());
+        db::query_as!(
+            ProjectShellEnvironment,
+            r#"INSERT INTO project_shell_environments (project_id, login_shell, env_file)
+               VALUES ($1, $2, $3)
+               ON CONFLICT (project_id) DO UPDATE SET
+                   login_shell = excluded.login_shell,
+                   env_file = excluded.env_file,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid", login_shell as "login_shell!: LoginShellMode", env_file, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            data.login_shell,
+            env_file
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Go back to the global setting. Returns whether the project had its own.
+    pub async fn delete(pool: &DbPool, project_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = db::query!(
+            "DELETE FROM project_shell_environments WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,143 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    command_runner::Worker,
+    db::{self, DbPool},
+};
+
+/// The worker machine a project's coding agents and scripts run on, through the
+/// `cloud-runner` it serves
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct ProjectWorker {
+    pub project_id: Uuid,
+    /// The runner's address, e.g. `http://build-box:8000`
+    pub url: String,
+    /// The directory here holding the attempts' worktrees, when the worker sees it at
+    /// `remote_root` rather than at the same path
+    pub local_root: Option<String>,
+    pub remote_root: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertProjectWorker {
+    pub url: String,
+    pub local_root: Option<String>,
+    pub remote_root: Option<String>,
+}
+
+/// Why the worker can't be saved, if it can't
+pub fn worker_error(worker: &UpsertProjectWorker) -> Option<String> {
+    let url = worker.url.trim();
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Some("The worker's URL must start with http:// or https://".to_string());
+    }
+    let set = |root: &Option<String>| root.as_deref().is_some_and(|root| !root.trim().is_empty());
+    if set(&worker.local_root) != set(&worker.remote_root) {
+        return Some("Set both the local and the worker's directory, or neither".to_string());
+    }
+    None
+}
+
+impl From<ProjectWorker> for Worker {
+    fn from(worker: ProjectWorker) -> Self {
+        Worker {
+            url: worker.url,
+            path_map: worker.local_root.zip(worker.remote_root),
+        }
+    }
+}
+
+impl ProjectWorker {
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            ProjectWorker,
+            r#"SELECT project_id as "project_id!: Uuid", url, local_root, remote_root, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_workers
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &DbPool,
+        project_id: Uuid,
+        data: &UpsertProjectWorker,
+    ) -> Result<Self, sqlx::Error> {
+        let url = data.url.trim();
+        let local_root = data
+            .local_root
+            .as_deref()
+            .map(str::trim)
+            .filter(|root| !root.is_empty());
+        let remote_root = data
+            .remote_root
+            .as_deref()
+            .map(str::trim)
+            .filter(|root| !root.is_empty());
+        db::query_as!(
+            ProjectWorker,
+            r#"INSERT INTO project_workers (project_id, url, local_root, remote_root)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT (project_id) DO UPDATE SET
+                   url = excluded.url,
+                   local_root = excluded.local_root,
+                   remote_root = excluded.remote_root,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid", url, local_root, remote_root, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            url,
+            local_root,
+            remote_root
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Go back to running on this machine. Returns whether the project had a worker.
+    pub async fn delete(pool: &DbPool, project_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = db::query!(
+            "DELETE FROM project_workers WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workers_are_checked() {
+        let upsert = |url: &str, local: Option<&str>, remote: Option<&str>| UpsertProjectWorker {
+            url: url.to_string(),
+            local_root: local.map(str::to_string),
+            remote_root: remote.map(str::to_string),
+        };
+        assert_eq!(
+            worker_error(&upsert("http://build-box:8000", None, None)),
+            None
+        );
+        assert_eq!(
+            worker_error(&upsert("https://build-box", Some("/a"), Some("/b"))),
+            None
+        );
+        assert!(worker_error(&upsert("build-box:8000", None, None)).is_some());
+        assert!(worker_error(&upsert("http://build-box", Some("/a"), Some(" "))).is_some());
+    }
+}
@@ -0,0 +1,122 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// The template a project's coding agent prompt is rendered from
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PromptTemplate {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    /// Executor this template is for, such as `claude`; `None` for every executor
+    pub executor: Option<String>,
+    pub template: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertPromptTemplate {
+    pub executor: Option<String>,
+    pub template: String,
+}
+
+impl PromptTemplate {
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            PromptTemplate,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", executor, template, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM prompt_templates
+               WHERE project_id = $1
+               ORDER BY executor IS NOT NULL, executor ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The template a run of `executor` in the project uses: the executor's own, otherwise
+    /// the project's template for every executor
+    pub async fn find_for_executor(
+        pool: &DbPool,
+        project_id: Uuid,
+        executor: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            PromptTemplate,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", executor, template, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM prompt_templates
+               WHERE project_id = $1 AND (executor = $2 OR executor IS NULL)
+               ORDER BY executor IS NULL
+               LIMIT 1"#,
+            project_id,
+            executor
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Set the project's template for `executor`, replacing any it had
+    pub async fn upsert(
+        pool: &DbPool,
+        project_id: Uuid,
+        data: &UpsertPromptTemplate,
+    ) -> Result<Self, sqlx::Error> {
+        let updated = db::query!(
+            "UPDATE prompt_templates SET template = $3, updated_at = datetime('now', 'subsec')
+             WHERE project_id = $1 AND executor IS $2",
+            project_id,
+            data.executor,
+            data.template
+        )
+        .execute(pool)
+        .await?;
+        if updated.rows_affected() == 0 {
+            let id = Uuid::new_v4();
+            db::query!(
+                "INSERT INTO prompt_templates (id, project_id, executor, template) VALUES ($1, $2, $3, $4)",
+                id,
+                project_id,
+                data.executor,
+                data.template
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        db::query_as!(
+            PromptTemplate,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", executor, template, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM prompt_templates
+               WHERE project_id = $1 AND executor IS $2"#,
+            project_id,
+            data.executor
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Remove the project's template for `executor`. Returns whether there was one.
+    pub async fn delete(
+        pool: &DbPool,
+        project_id: Uuid,
+        executor: Option<&str>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = db::query!(
+            "DELETE FROM prompt_templates WHERE project_id = $1 AND executor IS $2",
+            project_id,
+            executor
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
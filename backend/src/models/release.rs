@@ -0,0 +1,247 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum ReleaseStatus {
+    Draft,
+    Running,
+    Released,
+    Failed,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Release {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub version: String,
+    pub notes: Option<String>,
+    pub status: ReleaseStatus,
+    pub exit_code: Option<i64>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub released_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct ReleaseTask {
+    pub task_id: Uuid,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ReleaseWithTasks {
+    pub release: Release,
+    pub tasks: Vec<ReleaseTask>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateRelease {
+    pub version: String,
+    pub notes: Option<String>,
+    /// Tasks to include; every merged task not yet in a release when omitted
+    pub task_ids: Option<Vec<Uuid>>,
+}
+
+/// What the release script printed
+#[derive(Debug, Clone, FromRow, Default)]
+pub struct ReleaseOutput {
+    pub stdout: Option<String>,
+    pub stderr: Option<String>,
+}
+
+impl Release {
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            Release,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", version, notes, status as "status!: ReleaseStatus", exit_code, started_at as "started_at: DateTime<Utc>", released_at as "released_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM releases
+               WHERE project_id = $1
+               ORDER BY created_at DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            Release,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", version, notes, status as "status!: ReleaseStatus", exit_code, started_at as "started_at: DateTime<Utc>", released_at as "released_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM releases
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_tasks(pool: &DbPool, id: Uuid) -> Result<Vec<ReleaseTask>, sqlx::Error> {
+        db::query_as!(
+            ReleaseTask,
+            r#"SELECT t.id as "task_id!: Uuid", t.title
+               FROM release_tasks rt
+               JOIN tasks t ON rt.task_id = t.id
+               WHERE rt.release_id = $1
+               ORDER BY t.created_at ASC"#,
+            id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_output(pool: &DbPool, id: Uuid) -> Result<ReleaseOutput, sqlx::Error> {
+        let output = db::query_as!(
+            ReleaseOutput,
+            "SELECT stdout, stderr FROM releases WHERE id = $1",
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(output.unwrap_or_default())
+    }
+
+    /// Tasks of the project that are already part of some release
+    pub async fn find_released_task_ids(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        db::query_scalar!(
+            r#"SELECT rt.task_id as "task_id!: Uuid"
+               FROM release_tasks rt
+               JOIN releases r ON rt.release_id = r.id
+               WHERE r.project_id = $1"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &DbPool,
+        project_id: Uuid,
+        version: &str,
+        notes: Option<&str>,
+        task_ids: &[Uuid],
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let mut tx = pool.begin().await?;
+        let release = db::query_as!(
+            Release,
+            r#"INSERT INTO releases (id, project_id, version, notes)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", version, notes, status as "status!: ReleaseStatus", exit_code, started_at as "started_at: DateTime<Utc>", released_at as "released_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            version,
+            notes
+        )
+        .fetch_one(&mut tx)
+        .await?;
+        for task_id in task_ids {
+            db::query!(
+                "INSERT INTO release_tasks (release_id, task_id) VALUES ($1, $2)",
+                id,
+                task_id
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(release)
+    }
+
+    /// Clear the previous run's output and mark the release as running
+    pub async fn mark_running(pool: &DbPool, id: Uuid) -> Result<(), sqlx::Error> {
+        let status = ReleaseStatus::Running;
+        db::query!(
+            r#"UPDATE releases
+               SET status = $2, stdout = NULL, stderr = NULL, exit_code = NULL,
+                   started_at = datetime('now', 'subsec'), updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn finish(
+        pool: &DbPool,
+        id: Uuid,
+        succeeded: bool,
+        exit_code: Option<i64>,
+    ) -> Result<(), sqlx::Error> {
+        let (status, released_at) = if succeeded {
+            (ReleaseStatus::Released, Some(Utc::now()))
+        } else {
+            (ReleaseStatus::Failed, None)
+        };
+        db::query!(
+            r#"UPDATE releases
+               SET status = $2, exit_code = $3, released_at = $4, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status,
+            exit_code,
+            released_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Releases still marked as running when the server starts lost their script
+    pub async fn fail_interrupted(pool: &DbPool) -> Result<u64, sqlx::Error> {
+        let result = db::query!(
+            "UPDATE releases SET status = 'failed', updated_at = datetime('now', 'subsec') WHERE status = 'running'"
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn append_output(
+        pool: &DbPool,
+        id: Uuid,
+        stdout_append: Option<&str>,
+        stderr_append: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            r#"UPDATE releases
+               SET stdout = CASE WHEN $2 IS NULL THEN stdout ELSE COALESCE(stdout, '') || $2 END,
+                   stderr = CASE WHEN $3 IS NULL THEN stderr ELSE COALESCE(stderr, '') || $3 END,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            stdout_append,
+            stderr_append
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &DbPool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = db::query!("DELETE FROM releases WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
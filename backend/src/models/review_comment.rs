@@ -0,0 +1,314 @@
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::execution_diff::ExecutionDiff;
+use crate::{
+    db::{self, DbPool},
+    services::scoped_follow_up::unified_hunk,
+};
+
+/// Lines of code shown on either side of a commented line in the follow-up prompt
+const PROMPT_CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ReviewComment {
+    pub id: Uuid,
+    pub task_attempt_id: Uuid,
+    /// The coding agent run whose captured diff the comment is on; `None` for the
+    /// attempt's diff as a whole
+    pub execution_process_id: Option<Uuid>,
+    pub file_path: String,
+    pub line_number: i64, // 1-based line in the attempt's version of the file
+    pub body: String,
+    pub resolved: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateReviewComment {
+    /// Comment on this run's captured diff rather than the attempt's current one
+    pub execution_process_id: Option<Uuid>,
+    pub file_path: String,
+    pub line_number: i64,
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpdateReviewComment {
+    pub body: Option<String>,
+    pub resolved: Option<bool>,
+}
+
+impl ReviewComment {
+    pub async fn find_by_task_attempt_id(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            ReviewComment,
+            r#"SELECT id as "id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", execution_process_id as "execution_process_id: Uuid", file_path, line_number, body, resolved as "resolved!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM review_comments
+               WHERE task_attempt_id = $1
+               ORDER BY file_path ASC, line_number ASC, created_at ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            ReviewComment,
+            r#"SELECT id as "id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", execution_process_id as "execution_process_id: Uuid", file_path, line_number, body, resolved as "resolved!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM review_comments
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+        data: &CreateReviewComment,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        db::query_as!(
+            ReviewComment,
+            r#"INSERT INTO review_comments (id, task_attempt_id, execution_process_id, file_path, line_number, body)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", execution_process_id as "execution_process_id: Uuid", file_path, line_number, body, resolved as "resolved!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            task_attempt_id,
+            data.execution_process_id,
+            data.file_path,
+            data.line_number,
+            data.body
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &DbPool,
+        id: Uuid,
+        data: &UpdateReviewComment,
+    ) -> Result<Self, sqlx::Error> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+
+        let body = data.body.as_ref().unwrap_or(&existing.body);
+        let resolved = data.resolved.unwrap_or(existing.resolved);
+
+        db::query_as!(
+            ReviewComment,
+            r#"UPDATE review_comments
+               SET body = $2, resolved = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", execution_process_id as "execution_process_id: Uuid", file_path, line_number, body, resolved as "resolved!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            body,
+            resolved
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Mark every open comment on an attempt as resolved
+    pub async fn resolve_all(pool: &DbPool, task_attempt_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = db::query!(
+            "UPDATE review_comments SET resolved = TRUE, updated_at = datetime('now', 'subsec') WHERE task_attempt_id = $1 AND resolved = FALSE",
+            task_attempt_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// The captured diffs of the runs the open comments were left on
+    pub async fn run_diffs(
+        pool: &DbPool,
+        comments: &[ReviewComment],
+    ) -> Result<Vec<ExecutionDiff>, sqlx::Error> {
+        let mut diffs: Vec<ExecutionDiff> = Vec::new();
+        let runs = comments
+            .iter()
+            .filter(|c| !c.resolved)
+            .filter_map(|c| c.execution_process_id);
+        for run in runs {
+            if diffs.iter().any(|diff| diff.execution_process_id == run) {
+                continue;
+            }
+            if let Some(diff) = ExecutionDiff::find_by_execution_process_id(pool, run).await? {
+                diffs.push(diff);
+            }
+        }
+        Ok(diffs)
+    }
+
+    pub async fn delete(pool: &DbPool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = db::query!("DELETE FROM review_comments WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+/// Build a follow-up prompt asking the agent to address the open comments, quoting the
+/// surrounding code from the worktree so each comment can be understood on its own.
+/// Comments on a run's captured diff quote the hunk they were left on instead.
+pub fn compile_follow_up_prompt(
+    comments: &[ReviewComment],
+    worktree_path: &Path,
+    run_diffs: &[ExecutionDiff],
+) -> String {
+    let mut prompt = String::from("Please address the following review comments:\n");
+
+    for (index, comment) in comments.iter().filter(|c| !c.resolved).enumerate() {
+        prompt.push_str(&format!(
+            "\n{}. {}:{}\n{}\n",
+            index + 1,
+            comment.file_path,
+            comment.line_number,
+            comment.body.trim()
+        ));
+
+        let hunk = comment.execution_process_id.and_then(|run| {
+            run_diffs
+                .iter()
+                .find(|diff| diff.execution_process_id == run)?
+                .hunk_at(&comment.file_path, comment.line_number)
+        });
+        if let Some(hunk) = hunk {
+            prompt.push_str(&format!("```diff\n{}```\n", unified_hunk(hunk)));
+        } else if let Some(snippet) = code_context(worktree_path, comment) {
+            prompt.push_str(&format!("```\n{}```\n", snippet));
+        }
+    }
+
+    prompt
+}
+
+fn code_context(worktree_path: &Path, comment: &ReviewComment) -> Option<String> {
+    let content = std::fs::read_to_string(worktree_path.join(&comment.file_path)).ok()?;
+    let lines: Vec<&str> = content.lines().collect();
+    let target = usize::try_from(comment.line_number).ok()?.checked_sub(1)?;
+    if target >= lines.len() {
+        return None;
+    }
+
+    let start = target.saturating_sub(PROMPT_CONTEXT_LINES);
+    let end = (target + PROMPT_CONTEXT_LINES + 1).min(lines.len());
+    let width = end.to_string().len();
+
+    let mut snippet = String::new();
+    for (i, line) in lines.iter().enumerate().take(end).skip(start) {
+        let marker = if i == target { '>' } else { ' ' };
+        snippet.push_str(&format!("{} {:>width$} | {}\n", marker, i + 1, line));
+    }
+    Some(snippet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(path: &str, line: i64, body: &str, resolved: bool) -> ReviewComment {
+        ReviewComment {
+            id: Uuid::new_v4(),
+            task_attempt_id: Uuid::new_v4(),
+            execution_process_id: None,
+            file_path: path.to_string(),
+            line_number: line,
+            body: body.to_string(),
+            resolved,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_follow_up_prompt_quotes_code() {
+        let dir = tempfile::tempdir().unwrap();
+        let content: String = (1..=10).map(|i| format!("line {}\n", i)).collect();
+        std::fs::write(dir.path().join("main.rs"), content).unwrap();
+
+        let prompt = compile_follow_up_prompt(
+            &[
+                comment("main.rs", 2, "Rename this", false),
+                comment("main.rs", 9, "Already fixed", true),
+                comment("missing.rs", 1, "File was removed", false),
+            ],
+            dir.path(),
+            &[],
+        );
+
+        assert_eq!(
+            prompt,
+            "Please address the following review comments:\n\
+             \n1. main.rs:2\nRename this\n\
+             ```\n  1 | line 1\n> 2 | line 2\n  3 | line 3\n  4 | line 4\n  5 | line 5\n```\n\
+             \n2. missing.rs:1\nFile was removed\n"
+        );
+    }
+
+    #[test]
+    fn test_comments_on_a_run_quote_its_captured_hunk() {
+        use crate::models::{
+            execution_diff::DiffSummary,
+            task_attempt::{DiffChunk, DiffChunkType, DiffHunk, FileHunks},
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("main.rs"), "rewritten since\n").unwrap();
+        let run = Uuid::new_v4();
+        let diff = ExecutionDiff {
+            execution_process_id: run,
+            from_commit: "a".to_string(),
+            to_commit: Some("b".to_string()),
+            files: vec![FileHunks {
+                path: "main.rs".to_string(),
+                hunks: vec![DiffHunk {
+                    index: 0,
+                    header: "@@ -1 +1 @@".to_string(),
+                    old_start: 1,
+                    new_start: 1,
+                    lines: vec![
+                        DiffChunk {
+                            chunk_type: DiffChunkType::Delete,
+                            content: "old\n".to_string(),
+                        },
+                        DiffChunk {
+                            chunk_type: DiffChunkType::Insert,
+                            content: "new\n".to_string(),
+                        },
+                    ],
+                }],
+            }],
+            summary: DiffSummary::default(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let mut on_run = comment("main.rs", 1, "Why this?", false);
+        on_run.execution_process_id = Some(run);
+
+        let prompt = compile_follow_up_prompt(&[on_run], dir.path(), &[diff]);
+        assert_eq!(
+            prompt,
+            "Please address the following review comments:\n\
+             \n1. main.rs:1\nWhy this?\n\
+             ```diff\n@@ -1 +1 @@\n-old\n+new\n```\n"
+        );
+    }
+}
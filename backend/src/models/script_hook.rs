@@ -0,0 +1,157 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// When a script hook runs
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum ScriptEvent {
+    TaskCreated,
+    /// An attempt's coding agent finished, whether it succeeded or failed
+    AttemptFinished,
+    /// An attempt is about to be merged; the script may stop it
+    BeforeMerge,
+}
+
+/// A Rhai script the project runs on one of its lifecycle events (see
+/// [`crate::services::script_hooks`])
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct ScriptHook {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    pub event: ScriptEvent,
+    pub script: String,
+    pub enabled: bool,
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// Why the last run failed; `None` when it succeeded
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertScriptHook {
+    /// Letters, digits, `-` and `_`; a hook of the same name is replaced
+    pub name: String,
+    pub event: ScriptEvent,
+    pub script: String,
+    pub enabled: Option<bool>,
+}
+
+/// Why the hook's name can't be saved, if it can't
+pub fn script_hook_name_error(name: &str) -> Option<String> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    (!valid).then(|| {
+        format!(
+            "\"{}\" isn't a valid script hook name: use letters, digits, - and _",
+            name
+        )
+    })
+}
+
+impl ScriptHook {
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            ScriptHook,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", name, event as "event!: ScriptEvent", script, enabled as "enabled!: bool", last_run_at as "last_run_at: DateTime<Utc>", last_error, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM script_hooks
+               WHERE project_id = $1
+               ORDER BY name ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The project's enabled hooks for `event`, in name order, which is the order they run in
+    pub async fn find_enabled(
+        pool: &DbPool,
+        project_id: Uuid,
+        event: ScriptEvent,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            ScriptHook,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", name, event as "event!: ScriptEvent", script, enabled as "enabled!: bool", last_run_at as "last_run_at: DateTime<Utc>", last_error, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM script_hooks
+               WHERE project_id = $1 AND event = $2 AND enabled = TRUE
+               ORDER BY name ASC"#,
+            project_id,
+            event
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Save the hook under its name, replacing the project's hook of that name
+    pub async fn upsert(
+        pool: &DbPool,
+        project_id: Uuid,
+        data: &UpsertScriptHook,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let enabled = data.enabled.unwrap_or(true);
+        db::query_as!(
+            ScriptHook,
+            r#"INSERT INTO script_hooks (id, project_id, name, event, script, enabled)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               ON CONFLICT (project_id, name) DO UPDATE SET
+                   event = excluded.event,
+                   script = excluded.script,
+                   enabled = excluded.enabled,
+                   last_error = NULL,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", name, event as "event!: ScriptEvent", script, enabled as "enabled!: bool", last_run_at as "last_run_at: DateTime<Utc>", last_error, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            data.name,
+            data.event,
+            data.script,
+            enabled
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Note a run of the hook and why it failed, if it did
+    pub async fn record_run(
+        pool: &DbPool,
+        id: Uuid,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE script_hooks SET last_run_at = datetime('now', 'subsec'), last_error = $2 WHERE id = $1",
+            id,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Remove the project's hook of that name. Returns whether there was one.
+    pub async fn delete(pool: &DbPool, project_id: Uuid, name: &str) -> Result<bool, sqlx::Error> {
+        let result = db::query!(
+            "DELETE FROM script_hooks WHERE project_id = $1 AND name = $2",
+            project_id,
+            name
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
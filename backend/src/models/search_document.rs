@@ -0,0 +1,264 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::TaskStatus;
+use crate::{
+    db::{self, DbPool},
+    executor::NormalizedEntryType,
+};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum SearchHitKind {
+    /// The task's title or description
+    Task,
+    /// An entry of one of the task's coding agent conversations
+    Conversation,
+}
+
+/// A task or conversation entry matching a full-text search, best matches first
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct SearchHit {
+    pub kind: SearchHitKind,
+    pub project_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub task_status: TaskStatus,
+    pub attempt_id: Option<Uuid>,
+    pub execution_process_id: Option<Uuid>,
+    /// Index into the process's normalized conversation entries
+    pub entry_index: Option<i64>,
+    pub entry_type: Option<NormalizedEntryType>,
+    pub timestamp: Option<String>,
+    pub snippet: String,
+    /// Higher is better
+    pub score: f64,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct SearchQuery {
+    pub q: String,
+    pub project_id: Option<Uuid>,
+    pub status: Option<TaskStatus>,
+    pub executor: Option<String>,
+    /// RFC 3339 timestamp or `YYYY-MM-DD`, inclusive
+    pub from: Option<String>,
+    /// RFC 3339 timestamp or `YYYY-MM-DD`, inclusive
+    pub to: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// Narrows a search. Dates apply to when the task was created, or to when the process of a
+/// conversation entry started.
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilters {
+    pub project_id: Option<Uuid>,
+    pub status: Option<TaskStatus>,
+    /// Conversations run with the executor, and tasks with an attempt run with it
+    pub executor: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// A conversation entry ready to be indexed
+#[derive(Debug, Clone)]
+pub struct NewConversationDocument {
+    pub entry_index: usize,
+    pub entry_type: NormalizedEntryType,
+    pub timestamp: Option<String>,
+    pub content: String,
+}
+
+/// A row of [`SearchDocument::search`]
+#[derive(FromRow)]
+struct SearchRow {
+    project_id: Uuid,
+    task_id: Uuid,
+    task_title: String,
+    task_status: TaskStatus,
+    attempt_id: Option<Uuid>,
+    execution_process_id: Option<Uuid>,
+    entry_index: Option<i64>,
+    entry_type: Option<String>,
+    timestamp: Option<String>,
+    snippet: String,
+    score: f64,
+}
+
+/// Stores documents in the `search_documents` full-text index. Task documents are kept up to
+/// date by triggers; only conversations are added from here.
+pub struct SearchDocument;
+
+impl SearchDocument {
+    /// Add a finished process's conversation entries, recording how many it has so the
+    /// process isn't indexed again
+    pub async fn add_conversation_entries(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+        documents: &[NewConversationDocument],
+        indexed_entries: usize,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        for document in documents {
+            let entry_index = document.entry_index as i64;
+            let entry_type = serde_json::to_string(&document.entry_type).unwrap_or_default();
+            db::query!(
+                r#"INSERT INTO search_documents (content, task_id, execution_process_id, entry_index, entry_type, timestamp)
+                   SELECT $1, ta.task_id, ep.id, $3, $4, $5
+                   FROM execution_processes ep
+                   JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+                   WHERE ep.id = $2"#,
+                document.content,
+                execution_process_id,
+                entry_index,
+                entry_type,
+                document.timestamp
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+        let indexed_entries = indexed_entries as i64;
+        db::query!(
+            r#"INSERT INTO search_index_state (execution_process_id, indexed_entries)
+               VALUES ($1, $2)
+               ON CONFLICT(execution_process_id) DO UPDATE SET
+                   indexed_entries = excluded.indexed_entries,
+                   updated_at = datetime('now', 'subsec')"#,
+            execution_process_id,
+            indexed_entries
+        )
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await
+    }
+
+    /// Run an FTS5 `query` against the index, or the same query through PostgreSQL's
+    /// `websearch_to_tsquery`. Titles weigh twice as much as other text.
+    pub async fn search(
+        pool: &DbPool,
+        query: &str,
+        filters: &SearchFilters,
+        limit: i64,
+    ) -> Result<Vec<SearchHit>, sqlx::Error> {
+        let rows = match pool {
+            DbPool::Sqlite(pool) => {
+                sqlx::query_as!(
+                    SearchRow,
+                r#"SELECT
+                    t.project_id as "project_id!: Uuid",
+                    t.id as "task_id!: Uuid",
+                    t.title as "task_title!",
+                    t.status as "task_status!: TaskStatus",
+                    ep.task_attempt_id as "attempt_id?: Uuid",
+                    search_documents.execution_process_id as "execution_process_id: Uuid",
+                    search_documents.entry_index as "entry_index: i64",
+                    search_documents.entry_type as "entry_type: String",
+                    search_documents.timestamp as "timestamp: String",
+                    snippet(search_documents, -1, '', '', '…', 24) as "snippet!: String",
+                    -bm25(search_documents, 2.0, 1.0) as "score!: f64"
+                   FROM search_documents
+                   JOIN tasks t ON t.id = search_documents.task_id
+                   LEFT JOIN execution_processes ep ON ep.id = search_documents.execution_process_id
+                   LEFT JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+                   WHERE search_documents MATCH $1
+                   AND t.deleted_at IS NULL
+                   AND ($2 IS NULL OR t.project_id = $2)
+                   AND ($3 IS NULL OR t.status = $3)
+                   AND ($4 IS NULL OR ta.executor = $4 OR (ta.id IS NULL AND EXISTS (
+                       SELECT 1 FROM task_attempts a WHERE a.task_id = t.id AND a.executor = $4
+                   )))
+                   AND ($5 IS NULL OR datetime(COALESCE(ep.started_at, t.created_at)) >= datetime($5))
+                   AND ($6 IS NULL OR datetime(COALESCE(ep.started_at, t.created_at)) <= datetime($6))
+                   ORDER BY bm25(search_documents, 2.0, 1.0)
+                   LIMIT $7"#,
+                query,
+                filters.project_id,
+                filters.status,
+                filters.executor,
+                filters.from,
+                filters.to,
+                limit
+            )
+                .fetch_all(pool)
+                .await?
+            }
+            DbPool::Postgres(pool) => {
+                sqlx::query_as::<_, SearchRow>(
+                    r#"SELECT
+                        t.project_id,
+                        t.id as task_id,
+                        t.title as task_title,
+                        t.status as task_status,
+                        ep.task_attempt_id as attempt_id,
+                        d.execution_process_id,
+                        d.entry_index,
+                        d.entry_type,
+                        d.timestamp,
+                        ts_headline(
+                            'simple',
+                            CASE WHEN to_tsvector('simple', COALESCE(d.content, '')) @@ q
+                                THEN d.content ELSE COALESCE(d.title, '') END,
+                            q,
+                            'StartSel="", StopSel="", MaxWords=24, MinWords=12, MaxFragments=1, FragmentDelimiter="…"'
+                        ) as snippet,
+                        CAST(ts_rank('{0, 0, 0.5, 1}', d.document, q) AS DOUBLE PRECISION) as score
+                       FROM search_documents d
+                       CROSS JOIN websearch_to_tsquery('simple', $1) q
+                       JOIN tasks t ON t.id = d.task_id
+                       LEFT JOIN execution_processes ep ON ep.id = d.execution_process_id
+                       LEFT JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+                       WHERE d.document @@ q
+                       AND t.deleted_at IS NULL
+                       AND ($2::uuid IS NULL OR t.project_id = $2)
+                       AND ($3::text IS NULL OR t.status = $3)
+                       AND ($4::text IS NULL OR ta.executor = $4 OR (ta.id IS NULL AND EXISTS (
+                           SELECT 1 FROM task_attempts a WHERE a.task_id = t.id AND a.executor = $4
+                       )))
+                       AND ($5::timestamptz IS NULL OR COALESCE(ep.started_at, t.created_at) >= $5)
+                       AND ($6::timestamptz IS NULL OR COALESCE(ep.started_at, t.created_at) <= $6)
+                       ORDER BY score DESC
+                       LIMIT $7"#,
+                )
+                .bind(query)
+                .bind(filters.project_id)
+                .bind(filters.status.clone())
+                .bind(&filters.executor)
+                .bind(filters.from)
+                .bind(filters.to)
+                .bind(limit)
+                .fetch_all(pool)
+                .await?
+            }
+        };
+
+        Ok(rows
+            .into_iter()
+            .map(|row| SearchHit {
+                kind: if row.execution_process_id.is_some() {
+                    SearchHitKind::Conversation
+                } else {
+                    SearchHitKind::Task
+                },
+                project_id: row.project_id,
+                task_id: row.task_id,
+                task_title: row.task_title,
+                task_status: row.task_status,
+                attempt_id: row.attempt_id,
+                execution_process_id: row.execution_process_id,
+                entry_index: row.entry_index,
+                entry_type: row
+                    .entry_type
+                    .and_then(|entry_type| serde_json::from_str(&entry_type).ok()),
+                timestamp: row.timestamp,
+                snippet: row.snippet,
+                score: row.score,
+            })
+            .collect())
+    }
+}
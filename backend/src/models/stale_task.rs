@@ -0,0 +1,192 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::TaskStatus;
+use crate::db::{self, DbPool};
+
+/// A task in In Progress, Plan Review or In Review with nothing running, and when it last saw activity
+#[derive(Debug, Clone)]
+pub struct IdleTask {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub title: String,
+    pub status: TaskStatus,
+    pub last_activity: DateTime<Utc>,
+}
+
+/// A task the stale check has flagged
+#[derive(Debug, Clone, FromRow)]
+pub struct StaleTask {
+    pub task_id: Uuid,
+    pub status: TaskStatus,
+    pub idle_since: DateTime<Utc>,
+    pub nudged_at: Option<DateTime<Utc>>,
+}
+
+/// A task of the project flagged stale in the column it's in now
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct ProjectStaleTask {
+    pub task_id: Uuid,
+    pub title: String,
+    pub status: TaskStatus,
+    /// When the task last saw activity
+    pub idle_since: DateTime<Utc>,
+    /// When the user was nudged about it, if they have been yet
+    pub nudged_at: Option<DateTime<Utc>>,
+}
+
+#[derive(FromRow)]
+struct IdleTaskRow {
+    id: Uuid,
+    project_id: Uuid,
+    title: String,
+    status: TaskStatus,
+    updated_at: DateTime<Utc>,
+    attempt_updated_at: Option<DateTime<Utc>>,
+    process_updated_at: Option<DateTime<Utc>>,
+}
+
+impl IdleTask {
+    /// Activity is the latest update to the task, any of its attempts, or their processes
+    pub async fn find_all(pool: &DbPool) -> Result<Vec<Self>, sqlx::Error> {
+        let records = db::query_as!(
+            IdleTaskRow,
+            r#"SELECT
+  t.id                  AS "id!: Uuid",
+  t.project_id          AS "project_id!: Uuid",
+  t.title,
+  t.status              AS "status!: TaskStatus",
+  t.updated_at          AS "updated_at!: DateTime<Utc>",
+  ( SELECT MAX(ta.updated_at)
+      FROM task_attempts ta
+     WHERE ta.task_id = t.id
+  )                     AS "attempt_updated_at: DateTime<Utc>",
+  ( SELECT MAX(ep.updated_at)
+      FROM task_attempts ta
+      JOIN execution_processes ep ON ep.task_attempt_id = ta.id
+     WHERE ta.task_id = t.id
+  )                     AS "process_updated_at: DateTime<Utc>"
+FROM tasks t
+WHERE t.status IN ('inprogress', 'planreview', 'inreview')
+  AND t.archived_at IS NULL
+  AND t.deleted_at IS NULL
+  AND NOT EXISTS (
+    SELECT 1
+      FROM task_attempts ta
+      JOIN execution_processes ep ON ep.task_attempt_id = ta.id
+     WHERE ta.task_id = t.id
+       AND ep.status = 'running'
+  )"#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|rec| {
+                let last_activity = [rec.attempt_updated_at, rec.process_updated_at]
+                    .into_iter()
+                    .flatten()
+                    .fold(rec.updated_at, DateTime::max);
+                IdleTask {
+                    id: rec.id,
+                    project_id: rec.project_id,
+                    title: rec.title,
+                    status: rec.status,
+                    last_activity,
+                }
+            })
+            .collect())
+    }
+}
+
+impl StaleTask {
+    pub async fn find_all(pool: &DbPool) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            StaleTask,
+            r#"SELECT task_id as "task_id!: Uuid", status as "status!: TaskStatus", idle_since as "idle_since!: DateTime<Utc>", nudged_at as "nudged_at: DateTime<Utc>"
+               FROM stale_tasks"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The project's flagged tasks that are still on the board, idle longest first
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<ProjectStaleTask>, sqlx::Error> {
+        db::query_as!(
+            ProjectStaleTask,
+            r#"SELECT st.task_id as "task_id!: Uuid", t.title, st.status as "status!: TaskStatus", st.idle_since as "idle_since!: DateTime<Utc>", st.nudged_at as "nudged_at: DateTime<Utc>"
+               FROM stale_tasks st
+               JOIN tasks t ON t.id = st.task_id
+               WHERE t.project_id = $1
+                 AND st.status = t.status
+                 AND t.archived_at IS NULL
+                 AND t.deleted_at IS NULL
+               ORDER BY st.idle_since ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Flag a task, starting over (not yet nudged) when its status or idle stretch changed
+    pub async fn flag(
+        pool: &DbPool,
+        task_id: Uuid,
+        status: &TaskStatus,
+        idle_since: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            r#"INSERT INTO stale_tasks (task_id, status, idle_since)
+               VALUES ($1, $2, $3)
+               ON CONFLICT(task_id) DO UPDATE SET
+                   status = excluded.status,
+                   idle_since = excluded.idle_since,
+                   nudged_at = CASE
+                       WHEN stale_tasks.status = excluded.status
+                        AND stale_tasks.idle_since = excluded.idle_since
+                       THEN stale_tasks.nudged_at
+                   END"#,
+            task_id,
+            status,
+            idle_since
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn mark_nudged(pool: &DbPool, task_ids: &[Uuid]) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        let mut tx = pool.begin().await?;
+        for task_id in task_ids {
+            db::query!(
+                "UPDATE stale_tasks SET nudged_at = $2 WHERE task_id = $1",
+                task_id,
+                now
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    pub async fn unflag(pool: &DbPool, task_id: Uuid) -> Result<(), sqlx::Error> {
+        db::query!("DELETE FROM stale_tasks WHERE task_id = $1", task_id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn unflag_all(pool: &DbPool) -> Result<u64, sqlx::Error> {
+        let result = db::query!("DELETE FROM stale_tasks").execute(pool).await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -1,22 +1,138 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::HashMap,
+};
+
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool, Type};
+use sqlx::{FromRow, Type};
 use ts_rs::TS;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS)]
-#[sqlx(type_name = "task_status", rename_all = "lowercase")]
+use super::{
+    board_column::{self, BoardColumn},
+    event::{Event, EventKind, NewEvent},
+    task_custom_field::TaskCustomFields,
+    task_label::{normalize_label, TaskLabels},
+};
+use crate::{
+    db::{self, DbPool},
+    event_bus::{self, BoardEvent},
+};
+
+#[derive(Debug, Clone, Type, Serialize, Deserialize, JsonSchema, PartialEq, TS)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
 #[ts(export)]
 pub enum TaskStatus {
     Todo,
+    /// An attempt was started while the concurrency limits were reached and is waiting for a
+    /// slot
+    Queued,
     InProgress,
+    /// A planning attempt's plan is waiting to be approved
+    PlanReview,
     InReview,
     Done,
     Cancelled,
 }
 
-#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+/// Declared from least to most pressing, so comparisons order by urgency
+#[derive(
+    Debug, Clone, Copy, Type, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord, TS,
+)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum TaskPriority {
+    Low,
+    Medium,
+    High,
+    Urgent,
+}
+
+impl TaskPriority {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskPriority::Low => "low",
+            TaskPriority::Medium => "medium",
+            TaskPriority::High => "high",
+            TaskPriority::Urgent => "urgent",
+        }
+    }
+
+    /// Where a task stands when attempts compete for a slot; tasks without a priority count
+    /// as medium
+    pub fn rank(priority: Option<Self>) -> u8 {
+        match priority {
+            Some(TaskPriority::Low) => 0,
+            None | Some(TaskPriority::Medium) => 1,
+            Some(TaskPriority::High) => 2,
+            Some(TaskPriority::Urgent) => 3,
+        }
+    }
+}
+
+/// A name per task status, used when mirroring tasks into another tool whose columns or
+/// status options are named differently. Unset entries fall back to common names.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TaskStatusNames {
+    pub todo: Option<String>,
+    pub queued: Option<String>,
+    pub inprogress: Option<String>,
+    pub planreview: Option<String>,
+    pub inreview: Option<String>,
+    pub done: Option<String>,
+    pub cancelled: Option<String>,
+}
+
+impl TaskStatusNames {
+    pub fn get(&self, status: &TaskStatus) -> Option<&str> {
+        match status {
+            TaskStatus::Todo => self.todo.as_deref(),
+            TaskStatus::Queued => self.queued.as_deref(),
+            TaskStatus::InProgress => self.inprogress.as_deref(),
+            TaskStatus::PlanReview => self.planreview.as_deref(),
+            TaskStatus::InReview => self.inreview.as_deref(),
+            TaskStatus::Done => self.done.as_deref(),
+            TaskStatus::Cancelled => self.cancelled.as_deref(),
+        }
+    }
+
+    /// Names tried, case-insensitively, when no name is set for a status. They cover the
+    /// defaults of GitHub Projects and Notion boards.
+    fn fallback_names(status: &TaskStatus) -> &'static [&'static str] {
+        match status {
+            TaskStatus::Todo => &["todo", "to do", "not started", "backlog"],
+            // Boards without a queue column show queued tasks as started
+            TaskStatus::Queued => &["queued", "in progress", "doing"],
+            TaskStatus::InProgress => &["in progress", "doing"],
+            // Boards without a planning column show plans under review with the rest
+            TaskStatus::PlanReview => &["plan review", "planning", "in review", "review"],
+            TaskStatus::InReview => &["in review", "review"],
+            TaskStatus::Done => &["done", "complete", "completed"],
+            TaskStatus::Cancelled => &["cancelled", "canceled", "won't do"],
+        }
+    }
+
+    /// Pick the option a task in `status` should show from those the other tool offers
+    pub fn resolve<'a>(&self, status: &TaskStatus, options: &[&'a str]) -> Option<&'a str> {
+        let find = |name: &str| {
+            options
+                .iter()
+                .copied()
+                .find(|o| o.trim().eq_ignore_ascii_case(name.trim()))
+        };
+        match self.get(status) {
+            Some(name) => find(name),
+            None => Self::fallback_names(status).iter().find_map(|n| find(n)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, JsonSchema, TS)]
 #[ts(export)]
 pub struct Task {
     pub id: Uuid,
@@ -25,11 +141,15 @@ pub struct Task {
     pub description: Option<String>,
     pub status: TaskStatus,
     pub parent_task_attempt: Option<Uuid>, // Foreign key to parent TaskAttempt
+    pub parent_task_id: Option<Uuid>,      // Foreign key to the Task this is a subtask of
+    /// Overrides the configured execution timeout for this task's attempts
+    pub timeout_minutes: Option<i64>,
+    pub priority: Option<TaskPriority>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, TS)]
 #[ts(export)]
 pub struct TaskWithAttemptStatus {
     pub id: Uuid,
@@ -38,21 +158,101 @@ pub struct TaskWithAttemptStatus {
     pub description: Option<String>,
     pub status: TaskStatus,
     pub parent_task_attempt: Option<Uuid>,
+    pub parent_task_id: Option<Uuid>,
+    pub timeout_minutes: Option<i64>,
+    pub priority: Option<TaskPriority>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub labels: Vec<String>,
+    /// Values of the project's custom fields, keyed by field name
+    pub custom_fields: HashMap<String, String>,
+    /// The column the task shows in when the project has its own board columns
+    pub board_column_id: Option<Uuid>,
     pub has_in_progress_attempt: bool,
     pub has_merged_attempt: bool,
     pub last_attempt_failed: bool,
+    /// A verification command failed after the latest coding agent run
+    pub verification_failed: bool,
     pub latest_attempt_executor: Option<String>,
+    /// Set while the stale task check has this task flagged in its current column
+    pub stale_since: Option<DateTime<Utc>>,
+    /// Place of the task's attempt in the execution queue; 1 is next
+    pub queue_position: Option<i64>,
+    /// Pull request of the latest attempt that opened one
+    pub pr_url: Option<String>,
+    pub pr_number: Option<i64>,
+    /// open, closed or merged
+    pub pr_status: Option<String>,
+    /// One-line outcome of the latest coding agent run that finished with one
+    pub latest_summary: Option<String>,
 }
 
-#[derive(Debug, Deserialize, TS)]
+/// A row of [`Task::find_by_project_id_with_attempt_status`], before its labels and custom
+/// fields are added
+#[derive(FromRow)]
+struct TaskBoardRow {
+    id: Uuid,
+    project_id: Uuid,
+    title: String,
+    description: Option<String>,
+    status: TaskStatus,
+    parent_task_attempt: Option<Uuid>,
+    parent_task_id: Option<Uuid>,
+    timeout_minutes: Option<i64>,
+    priority: Option<TaskPriority>,
+    board_column_id: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    has_in_progress_attempt: bool,
+    has_merged_attempt: bool,
+    last_attempt_failed: bool,
+    verification_failed: bool,
+    latest_attempt_executor: Option<String>,
+    stale_since: Option<DateTime<Utc>>,
+    queue_position: Option<i64>,
+    pr_url: Option<String>,
+    pr_number: Option<i64>,
+    pr_status: Option<String>,
+    latest_summary: Option<String>,
+}
+
+/// What [`Task::delete`] needs of the row it deleted
+#[derive(FromRow)]
+struct DeletedTask {
+    title: String,
+    trashed: bool,
+}
+
+/// A task off the board: archived, or in the trash
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct ShelvedTask {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub title: String,
+    pub status: TaskStatus,
+    pub priority: Option<TaskPriority>,
+    pub archived_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema, TS)]
 #[ts(export)]
 pub struct CreateTask {
     pub project_id: Uuid,
     pub title: String,
     pub description: Option<String>,
     pub parent_task_attempt: Option<Uuid>,
+    pub parent_task_id: Option<Uuid>,
+}
+
+/// A subtask, created in its parent's project
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateSubtask {
+    pub title: String,
+    pub description: Option<String>,
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -63,6 +263,8 @@ pub struct CreateTaskAndStart {
     pub description: Option<String>,
     pub parent_task_attempt: Option<Uuid>,
     pub executor: Option<crate::executor::ExecutorConfig>,
+    /// The model to run the agent with, as its CLI names it
+    pub model: Option<String>,
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -72,14 +274,202 @@ pub struct UpdateTask {
     pub description: Option<String>,
     pub status: Option<TaskStatus>,
     pub parent_task_attempt: Option<Uuid>,
+    /// The task's `updated_at` when the edit started. The update is refused when the task has
+    /// changed since, unless `base` shows the changes were to other fields.
+    pub expected_updated_at: Option<DateTime<Utc>>,
+    /// The task as the edit started from, so changes made since to other fields are kept
+    pub base: Option<TaskEdit>,
+}
+
+/// The fields of a task an edit writes
+#[derive(Debug, Clone, PartialEq, Deserialize, TS)]
+#[ts(export)]
+pub struct TaskEdit {
+    pub title: String,
+    pub description: Option<String>,
+    pub status: TaskStatus,
+    pub parent_task_attempt: Option<Uuid>,
+}
+
+impl UpdateTask {
+    /// The fields to write over `current`. With `base`, the task as the edit started from,
+    /// fields the edit didn't change keep their current values, and a field changed both by
+    /// the edit and by someone else since is a conflict; `Err` names those fields.
+    pub fn merge(
+        &self,
+        current: &Task,
+        base: Option<&TaskEdit>,
+    ) -> Result<TaskEdit, Vec<&'static str>> {
+        fn field<T: Clone + PartialEq>(
+            name: &'static str,
+            edited: Option<&T>,
+            current: &T,
+            base: Option<&T>,
+            conflicts: &mut Vec<&'static str>,
+        ) -> T {
+            match (edited, base) {
+                (None, _) => current.clone(),
+                (Some(edited), None) => edited.clone(),
+                (Some(edited), Some(base)) if edited == base => current.clone(),
+                (Some(edited), Some(base)) => {
+                    if current != base && current != edited {
+                        conflicts.push(name);
+                    }
+                    edited.clone()
+                }
+            }
+        }
+
+        let mut conflicts = Vec::new();
+        let edit = TaskEdit {
+            title: field(
+                "title",
+                self.title.as_ref(),
+                &current.title,
+                base.map(|b| &b.title),
+                &mut conflicts,
+            ),
+            // Leaving the description out keeps it, so it can't be cleared through an edit
+            description: field(
+                "description",
+                self.description.as_ref().map(|_| &self.description),
+                &current.description,
+                base.map(|b| &b.description),
+                &mut conflicts,
+            ),
+            status: field(
+                "status",
+                self.status.as_ref(),
+                &current.status,
+                base.map(|b| &b.status),
+                &mut conflicts,
+            ),
+            parent_task_attempt: field(
+                "parent task attempt",
+                self.parent_task_attempt
+                    .as_ref()
+                    .map(|_| &self.parent_task_attempt),
+                &current.parent_task_attempt,
+                base.map(|b| &b.parent_task_attempt),
+                &mut conflicts,
+            ),
+        };
+        if conflicts.is_empty() {
+            Ok(edit)
+        } else {
+            Err(conflicts)
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct TaskPriorityRequest {
+    pub priority: Option<TaskPriority>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum TaskSort {
+    /// Newest first
+    #[default]
+    Created,
+    /// Most recently changed first
+    Updated,
+    /// Most pressing first, tasks without a priority last
+    Priority,
+    Title,
+    /// By the custom field named in `sort_field`, tasks without a value last
+    Field,
+}
+
+/// Filters and ordering for a project's board
+#[derive(Debug, Default, Deserialize, TS)]
+#[ts(export)]
+pub struct TaskBoardQuery {
+    pub label: Option<String>,
+    pub priority: Option<TaskPriority>,
+    /// A custom field value to match, as `name:value`
+    pub field: Option<String>,
+    #[serde(default)]
+    pub sort: TaskSort,
+    pub sort_field: Option<String>,
+}
+
+impl TaskBoardQuery {
+    /// Custom field values compare as numbers when both are, and case-insensitively
+    /// otherwise, which also orders dates
+    fn compare_values(a: &str, b: &str) -> Ordering {
+        match (a.parse::<f64>(), b.parse::<f64>()) {
+            (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+            _ => a.to_lowercase().cmp(&b.to_lowercase()),
+        }
+    }
+
+    pub fn apply(&self, tasks: Vec<TaskWithAttemptStatus>) -> Vec<TaskWithAttemptStatus> {
+        let label = self.label.as_deref().and_then(normalize_label);
+        let field = self
+            .field
+            .as_deref()
+            .and_then(|field| field.split_once(':'));
+        let mut tasks: Vec<_> = tasks
+            .into_iter()
+            .filter(|task| {
+                label
+                    .as_ref()
+                    .is_none_or(|label| task.labels.contains(label))
+            })
+            .filter(|task| {
+                self.priority
+                    .is_none_or(|priority| task.priority == Some(priority))
+            })
+            .filter(|task| {
+                field.is_none_or(|(name, value)| {
+                    task.custom_fields
+                        .get(name.trim())
+                        .is_some_and(|v| v.eq_ignore_ascii_case(value.trim()))
+                })
+            })
+            .collect();
+
+        match self.sort {
+            // Already in this order
+            TaskSort::Created => {}
+            TaskSort::Updated => tasks.sort_by_key(|task| Reverse(task.updated_at)),
+            TaskSort::Priority => tasks.sort_by_key(|task| Reverse(task.priority)),
+            TaskSort::Title => tasks.sort_by_key(|task| task.title.to_lowercase()),
+            TaskSort::Field => {
+                let name = self.sort_field.as_deref().unwrap_or_default().trim();
+                tasks.sort_by(|a, b| {
+                    match (a.custom_fields.get(name), b.custom_fields.get(name)) {
+                        (Some(a), Some(b)) => Self::compare_values(a, b),
+                        (Some(_), None) => Ordering::Less,
+                        (None, Some(_)) => Ordering::Greater,
+                        (None, None) => Ordering::Equal,
+                    }
+                });
+            }
+        }
+        tasks
+    }
+}
+
+/// `None` falls back to the configured default; `0` lets the task's executions run
+/// without a limit
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct TaskTimeoutRequest {
+    pub timeout_minutes: Option<i64>,
 }
 
 impl Task {
     pub async fn find_by_project_id_with_attempt_status(
-        pool: &SqlitePool,
+        pool: &DbPool,
         project_id: Uuid,
     ) -> Result<Vec<TaskWithAttemptStatus>, sqlx::Error> {
-        let records = sqlx::query!(
+        let records = db::query_as!(
+            TaskBoardRow,
             r#"SELECT
   t.id                            AS "id!: Uuid",
   t.project_id                    AS "project_id!: Uuid",
@@ -87,6 +477,10 @@ impl Task {
   t.description,
   t.status                        AS "status!: TaskStatus",
   t.parent_task_attempt           AS "parent_task_attempt: Uuid",
+  t.parent_task_id                AS "parent_task_id: Uuid",
+  t.timeout_minutes,
+  t.priority                      AS "priority: TaskPriority",
+  t.board_column_id               AS "board_column_id: Uuid",
   t.created_at                    AS "created_at!: DateTime<Utc>",
   t.updated_at                    AS "updated_at!: DateTime<Utc>",
 
@@ -96,10 +490,10 @@ impl Task {
       JOIN execution_processes ep
         ON ep.task_attempt_id = ta.id
      WHERE ta.task_id       = t.id
-       AND ep.status        = 'running'
+       AND ep.status       IN ('running','paused')
        AND ep.process_type IN ('setupscript','cleanupscript','codingagent')
      LIMIT 1
-  ) THEN 1 ELSE 0 END            AS "has_in_progress_attempt!: i64",
+  ) THEN TRUE ELSE FALSE END            AS "has_in_progress_attempt!: bool",
 
   CASE WHEN EXISTS (
     SELECT 1
@@ -107,7 +501,7 @@ impl Task {
      WHERE ta.task_id       = t.id
        AND ta.merge_commit IS NOT NULL
      LIMIT 1
-  ) THEN 1 ELSE 0 END            AS "has_merged_attempt!: i64",
+  ) THEN TRUE ELSE FALSE END            AS "has_merged_attempt!: bool",
 
   CASE WHEN (
     SELECT ep.status
@@ -118,24 +512,99 @@ impl Task {
      AND ep.process_type IN ('setupscript','cleanupscript','codingagent')
      ORDER BY ep.created_at DESC
      LIMIT 1
-  ) IN ('failed','killed') THEN 1 ELSE 0 END
-                                 AS "last_attempt_failed!: i64",
+  ) IN ('failed','killed','timedout','resourcelimitexceeded') THEN TRUE ELSE FALSE END
+                                 AS "last_attempt_failed!: bool",
+
+  CASE WHEN EXISTS (
+    SELECT 1
+      FROM execution_verifications ev
+     WHERE ev.passed = FALSE
+       AND ev.execution_process_id = (
+         SELECT ep.id
+           FROM task_attempts ta
+           JOIN execution_processes ep
+             ON ep.task_attempt_id = ta.id
+          WHERE ta.task_id       = t.id
+            AND ep.process_type  = 'codingagent'
+          ORDER BY ep.created_at DESC
+          LIMIT 1
+       )
+  ) THEN TRUE ELSE FALSE END            AS "verification_failed!: bool",
 
   ( SELECT ta.executor
       FROM task_attempts ta
      WHERE ta.task_id = t.id
      ORDER BY ta.created_at DESC
      LIMIT 1
-  )                               AS "latest_attempt_executor"
+  )                               AS "latest_attempt_executor",
+
+  ( SELECT st.idle_since
+      FROM stale_tasks st
+     WHERE st.task_id = t.id
+       AND st.status  = t.status
+  )                               AS "stale_since?: DateTime<Utc>",
+
+  ( SELECT ( SELECT COUNT(*)
+               FROM execution_queue ahead
+              WHERE ahead.position <= q.position )
+      FROM execution_queue q
+      JOIN task_attempts ta
+        ON ta.id = q.task_attempt_id
+     WHERE ta.task_id = t.id
+     ORDER BY q.position
+     LIMIT 1
+  )                               AS "queue_position: i64",
+
+  ( SELECT ta.pr_url
+      FROM task_attempts ta
+     WHERE ta.task_id = t.id
+       AND ta.pr_url IS NOT NULL
+     ORDER BY ta.created_at DESC
+     LIMIT 1
+  )                               AS "pr_url",
+
+  ( SELECT ta.pr_number
+      FROM task_attempts ta
+     WHERE ta.task_id = t.id
+       AND ta.pr_url IS NOT NULL
+     ORDER BY ta.created_at DESC
+     LIMIT 1
+  )                               AS "pr_number: i64",
+
+  ( SELECT ta.pr_status
+      FROM task_attempts ta
+     WHERE ta.task_id = t.id
+       AND ta.pr_url IS NOT NULL
+     ORDER BY ta.created_at DESC
+     LIMIT 1
+  )                               AS "pr_status",
+
+  ( SELECT es.summary
+      FROM task_attempts ta
+      JOIN execution_processes ep
+        ON ep.task_attempt_id = ta.id
+      JOIN executor_sessions es
+        ON es.execution_process_id = ep.id
+     WHERE ta.task_id       = t.id
+       AND ep.process_type  = 'codingagent'
+       AND es.summary IS NOT NULL
+     ORDER BY ep.created_at DESC
+     LIMIT 1
+  )                               AS "latest_summary"
 
 FROM tasks t
 WHERE t.project_id = $1
+  AND t.archived_at IS NULL
+  AND t.deleted_at IS NULL
 ORDER BY t.created_at DESC"#,
             project_id
         )
         .fetch_all(pool)
         .await?;
 
+        let mut labels = TaskLabels::find_labels_by_project_id(pool, project_id).await?;
+        let mut custom_fields = TaskCustomFields::find_by_project_id(pool, project_id).await?;
+        let columns = BoardColumn::find_by_project_id(pool, project_id).await?;
         let tasks = records
             .into_iter()
             .map(|rec| TaskWithAttemptStatus {
@@ -145,22 +614,56 @@ ORDER BY t.created_at DESC"#,
                 description: rec.description,
                 status: rec.status,
                 parent_task_attempt: rec.parent_task_attempt,
+                parent_task_id: rec.parent_task_id,
+                timeout_minutes: rec.timeout_minutes,
+                priority: rec.priority,
                 created_at: rec.created_at,
                 updated_at: rec.updated_at,
-                has_in_progress_attempt: rec.has_in_progress_attempt != 0,
-                has_merged_attempt: rec.has_merged_attempt != 0,
-                last_attempt_failed: rec.last_attempt_failed != 0,
+                labels: labels.remove(&rec.id).unwrap_or_default(),
+                custom_fields: custom_fields.remove(&rec.id).unwrap_or_default(),
+                board_column_id: rec.board_column_id,
+                has_in_progress_attempt: rec.has_in_progress_attempt,
+                has_merged_attempt: rec.has_merged_attempt,
+                last_attempt_failed: rec.last_attempt_failed,
+                verification_failed: rec.verification_failed,
                 latest_attempt_executor: rec.latest_attempt_executor,
+                stale_since: rec.stale_since,
+                queue_position: rec.queue_position,
+                pr_url: rec.pr_url,
+                pr_number: rec.pr_number,
+                pr_status: rec.pr_status,
+                latest_summary: rec.latest_summary,
+            })
+            .map(|mut task| {
+                task.board_column_id = board_column::place(&columns, &task);
+                task
             })
             .collect();
 
         Ok(tasks)
     }
 
-    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
-        sqlx::query_as!(
+    /// Every task of the project, oldest first
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            Task,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", parent_task_id as "parent_task_id: Uuid", timeout_minutes, priority as "priority: TaskPriority", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM tasks
+               WHERE project_id = $1
+               ORDER BY created_at ASC, id ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", parent_task_id as "parent_task_id: Uuid", timeout_minutes, priority as "priority: TaskPriority", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks 
                WHERE id = $1"#,
             id
@@ -170,13 +673,13 @@ ORDER BY t.created_at DESC"#,
     }
 
     pub async fn find_by_id_and_project_id(
-        pool: &SqlitePool,
+        pool: &DbPool,
         id: Uuid,
         project_id: Uuid,
     ) -> Result<Option<Self>, sqlx::Error> {
-        sqlx::query_as!(
+        db::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", parent_task_id as "parent_task_id: Uuid", timeout_minutes, priority as "priority: TaskPriority", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks 
                WHERE id = $1 AND project_id = $2"#,
             id,
@@ -186,29 +689,65 @@ ORDER BY t.created_at DESC"#,
         .await
     }
 
+    /// The subtasks of a task, oldest first so they read in the order they were proposed
+    pub async fn find_subtasks(
+        pool: &DbPool,
+        parent_task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            Task,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", parent_task_id as "parent_task_id: Uuid", timeout_minutes, priority as "priority: TaskPriority", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM tasks 
+               WHERE parent_task_id = $1
+               ORDER BY created_at ASC, id ASC"#,
+            parent_task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     pub async fn create(
-        pool: &SqlitePool,
+        pool: &DbPool,
         data: &CreateTask,
         task_id: Uuid,
     ) -> Result<Self, sqlx::Error> {
-        sqlx::query_as!(
+        let task = db::query_as!(
             Task,
-            r#"INSERT INTO tasks (id, project_id, title, description, status, parent_task_attempt) 
-               VALUES ($1, $2, $3, $4, $5, $6) 
-               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"INSERT INTO tasks (id, project_id, title, description, status, parent_task_attempt, parent_task_id) 
+               VALUES ($1, $2, $3, $4, $5, $6, $7) 
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", parent_task_id as "parent_task_id: Uuid", timeout_minutes, priority as "priority: TaskPriority", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             task_id,
             data.project_id,
             data.title,
             data.description,
-            TaskStatus::Todo as TaskStatus,
-            data.parent_task_attempt
+            TaskStatus::Todo,
+            data.parent_task_attempt,
+            data.parent_task_id
         )
         .fetch_one(pool)
-        .await
+        .await?;
+
+        Event::record(
+            pool,
+            NewEvent {
+                project_id: task.project_id,
+                task_id: Some(task.id),
+                task_attempt_id: None,
+                execution_process_id: None,
+                kind: EventKind::TaskCreated,
+                details: serde_json::json!({ "title": task.title }),
+            },
+        )
+        .await?;
+        event_bus::publish(BoardEvent::TaskCreated {
+            project_id: task.project_id,
+            task_id: task.id,
+        });
+        Ok(task)
     }
 
     pub async fn update(
-        pool: &SqlitePool,
+        pool: &DbPool,
         id: Uuid,
         project_id: Uuid,
         title: String,
@@ -216,59 +755,407 @@ ORDER BY t.created_at DESC"#,
         status: TaskStatus,
         parent_task_attempt: Option<Uuid>,
     ) -> Result<Self, sqlx::Error> {
-        let status_value = status as TaskStatus;
-        sqlx::query_as!(
+        let edit = TaskEdit {
+            title,
+            description,
+            status,
+            parent_task_attempt,
+        };
+        Self::write_edit(pool, id, project_id, edit, None)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    /// Write `edit` only if the task's `updated_at` is still `expected_updated_at`. `None`
+    /// when it isn't, because the task changed since it was read.
+    pub async fn update_unless_changed(
+        pool: &DbPool,
+        id: Uuid,
+        project_id: Uuid,
+        edit: TaskEdit,
+        expected_updated_at: DateTime<Utc>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        Self::write_edit(pool, id, project_id, edit, Some(expected_updated_at)).await
+    }
+
+    async fn write_edit(
+        pool: &DbPool,
+        id: Uuid,
+        project_id: Uuid,
+        edit: TaskEdit,
+        expected_updated_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let previous = Self::find_status(pool, id).await?;
+        let status_value = edit.status as TaskStatus;
+        // Both sides go through datetime() since stored and bound timestamps are formatted
+        // differently
+        let task = db::query_as!(
             Task,
             r#"UPDATE tasks 
-               SET title = $3, description = $4, status = $5, parent_task_attempt = $6 
-               WHERE id = $1 AND project_id = $2 
-               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+               SET title = $3, description = $4, status = $5, parent_task_attempt = $6,
+                   board_column_id = CASE WHEN status = $5 THEN board_column_id END,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1 AND project_id = $2
+                 AND ($7 IS NULL OR datetime(updated_at, 'subsec') = datetime($7, 'subsec'))
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", parent_task_id as "parent_task_id: Uuid", timeout_minutes, priority as "priority: TaskPriority", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             project_id,
-            title,
-            description,
+            edit.title,
+            edit.description,
             status_value,
-            parent_task_attempt
+            edit.parent_task_attempt,
+            expected_updated_at
         )
-        .fetch_one(pool)
-        .await
+        .fetch_optional(pool)
+        .await?;
+        let Some(task) = task else {
+            return Ok(None);
+        };
+
+        if let Some(previous) = previous {
+            Self::record_status_change(pool, id, project_id, previous, task.status.clone()).await?;
+        }
+        event_bus::publish(BoardEvent::TaskUpdated {
+            project_id,
+            task_id: id,
+        });
+        Ok(Some(task))
     }
 
     pub async fn update_status(
-        pool: &SqlitePool,
+        pool: &DbPool,
         id: Uuid,
         project_id: Uuid,
         status: TaskStatus,
     ) -> Result<(), sqlx::Error> {
-        let status_value = status as TaskStatus;
-        sqlx::query!(
-            "UPDATE tasks SET status = $3, updated_at = CURRENT_TIMESTAMP WHERE id = $1 AND project_id = $2",
+        let previous = Self::find_status(pool, id).await?;
+        let status_value = status.clone() as TaskStatus;
+        db::query!(
+            "UPDATE tasks SET status = $3, board_column_id = CASE WHEN status = $3 THEN board_column_id END, updated_at = CURRENT_TIMESTAMP WHERE id = $1 AND project_id = $2",
             id,
             project_id,
             status_value
         )
         .execute(pool)
         .await?;
+
+        if let Some(previous) = previous {
+            Self::record_status_change(pool, id, project_id, previous, status).await?;
+        }
+        Ok(())
+    }
+
+    async fn find_status(pool: &DbPool, id: Uuid) -> Result<Option<TaskStatus>, sqlx::Error> {
+        db::query_scalar!(
+            r#"SELECT status as "status!: TaskStatus" FROM tasks WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Add a status change to the activity feed, unless the status stayed the same
+    async fn record_status_change(
+        pool: &DbPool,
+        id: Uuid,
+        project_id: Uuid,
+        from: TaskStatus,
+        to: TaskStatus,
+    ) -> Result<(), sqlx::Error> {
+        if from == to {
+            return Ok(());
+        }
+        Event::record(
+            pool,
+            NewEvent {
+                project_id,
+                task_id: Some(id),
+                task_attempt_id: None,
+                execution_process_id: None,
+                kind: EventKind::TaskStatusChanged,
+                details: serde_json::json!({ "from": from, "to": to }),
+            },
+        )
+        .await?;
+        event_bus::publish(BoardEvent::TaskStatusChanged {
+            project_id,
+            task_id: id,
+            status: to,
+        });
         Ok(())
     }
 
-    pub async fn delete(pool: &SqlitePool, id: Uuid, project_id: Uuid) -> Result<u64, sqlx::Error> {
-        let result = sqlx::query!(
-            "DELETE FROM tasks WHERE id = $1 AND project_id = $2",
+    pub async fn set_timeout_minutes(
+        pool: &DbPool,
+        id: Uuid,
+        project_id: Uuid,
+        timeout_minutes: Option<i64>,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE tasks SET timeout_minutes = $3, updated_at = CURRENT_TIMESTAMP WHERE id = $1 AND project_id = $2",
             id,
-            project_id
+            project_id,
+            timeout_minutes
         )
         .execute(pool)
         .await?;
-        Ok(result.rows_affected())
+        Ok(())
     }
 
-    pub async fn exists(
-        pool: &SqlitePool,
+    pub async fn set_priority(
+        pool: &DbPool,
         id: Uuid,
         project_id: Uuid,
-    ) -> Result<bool, sqlx::Error> {
-        let result = sqlx::query!(
+        priority: Option<TaskPriority>,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE tasks SET priority = $3, updated_at = CURRENT_TIMESTAMP WHERE id = $1 AND project_id = $2",
+            id,
+            project_id,
+            priority
+        )
+        .execute(pool)
+        .await?;
+        event_bus::publish(BoardEvent::TaskUpdated {
+            project_id,
+            task_id: id,
+        });
+        Ok(())
+    }
+
+    /// Keep the task in a board column, or let its status and the column conditions place
+    /// it again
+    pub async fn set_board_column(
+        pool: &DbPool,
+        id: Uuid,
+        project_id: Uuid,
+        column_id: Option<Uuid>,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE tasks SET board_column_id = $3, updated_at = CURRENT_TIMESTAMP WHERE id = $1 AND project_id = $2",
+            id,
+            project_id,
+            column_id
+        )
+        .execute(pool)
+        .await?;
+        event_bus::publish(BoardEvent::TaskUpdated {
+            project_id,
+            task_id: id,
+        });
+        Ok(())
+    }
+
+    /// Delete the task for good. One already in the trash had its deletion recorded when
+    /// it went there.
+    pub async fn delete(pool: &DbPool, id: Uuid, project_id: Uuid) -> Result<u64, sqlx::Error> {
+        let deleted = db::query_as!(
+            DeletedTask,
+            r#"DELETE FROM tasks WHERE id = $1 AND project_id = $2
+               RETURNING title, deleted_at IS NOT NULL as "trashed!: bool""#,
+            id,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        let Some(deleted) = deleted else {
+            return Ok(0);
+        };
+        if deleted.trashed {
+            return Ok(1);
+        }
+        let title = deleted.title;
+
+        Event::record(
+            pool,
+            NewEvent {
+                project_id,
+                task_id: Some(id),
+                task_attempt_id: None,
+                execution_process_id: None,
+                kind: EventKind::TaskDeleted,
+                details: serde_json::json!({ "title": title }),
+            },
+        )
+        .await?;
+        event_bus::publish(BoardEvent::TaskDeleted {
+            project_id,
+            task_id: id,
+        });
+        Ok(1)
+    }
+
+    /// Take the task off the board, keeping it. Returns `false` if it was already archived
+    /// or is in the trash.
+    pub async fn archive(pool: &DbPool, id: Uuid, project_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = db::query!(
+            "UPDATE tasks SET archived_at = CURRENT_TIMESTAMP
+             WHERE id = $1 AND project_id = $2 AND archived_at IS NULL AND deleted_at IS NULL",
+            id,
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+        event_bus::publish(BoardEvent::TaskUpdated {
+            project_id,
+            task_id: id,
+        });
+        Ok(true)
+    }
+
+    /// Archive every done task of the project unchanged since `cutoff`, returning their ids
+    pub async fn archive_done_before(
+        pool: &DbPool,
+        project_id: Uuid,
+        cutoff: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        let ids = db::query_scalar!(
+            r#"UPDATE tasks SET archived_at = CURRENT_TIMESTAMP
+               WHERE project_id = $1
+                 AND status = 'done'
+                 AND archived_at IS NULL
+                 AND deleted_at IS NULL
+                 AND datetime(updated_at) < datetime($2)
+               RETURNING id as "id!: Uuid""#,
+            project_id,
+            cutoff
+        )
+        .fetch_all(pool)
+        .await?;
+        for id in &ids {
+            event_bus::publish(BoardEvent::TaskUpdated {
+                project_id,
+                task_id: *id,
+            });
+        }
+        Ok(ids)
+    }
+
+    /// Put an archived task back on the board. Returns `false` if it wasn't archived.
+    pub async fn unarchive(pool: &DbPool, id: Uuid, project_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = db::query!(
+            "UPDATE tasks SET archived_at = NULL
+             WHERE id = $1 AND project_id = $2 AND archived_at IS NOT NULL",
+            id,
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+        event_bus::publish(BoardEvent::TaskUpdated {
+            project_id,
+            task_id: id,
+        });
+        Ok(true)
+    }
+
+    /// Move the task to the trash, from where it can be restored. Returns `false` if it was
+    /// already there.
+    pub async fn trash(pool: &DbPool, id: Uuid, project_id: Uuid) -> Result<bool, sqlx::Error> {
+        let title = db::query_scalar!(
+            "UPDATE tasks SET deleted_at = CURRENT_TIMESTAMP
+             WHERE id = $1 AND project_id = $2 AND deleted_at IS NULL
+             RETURNING title",
+            id,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        let Some(title) = title else {
+            return Ok(false);
+        };
+
+        Event::record(
+            pool,
+            NewEvent {
+                project_id,
+                task_id: Some(id),
+                task_attempt_id: None,
+                execution_process_id: None,
+                kind: EventKind::TaskDeleted,
+                details: serde_json::json!({ "title": title, "trashed": true }),
+            },
+        )
+        .await?;
+        event_bus::publish(BoardEvent::TaskDeleted {
+            project_id,
+            task_id: id,
+        });
+        Ok(true)
+    }
+
+    /// Take the task out of the trash. Returns `false` if it wasn't there.
+    pub async fn restore(pool: &DbPool, id: Uuid, project_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = db::query!(
+            "UPDATE tasks SET deleted_at = NULL
+             WHERE id = $1 AND project_id = $2 AND deleted_at IS NOT NULL",
+            id,
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+        event_bus::publish(BoardEvent::TaskUpdated {
+            project_id,
+            task_id: id,
+        });
+        Ok(true)
+    }
+
+    /// Whether the task is in the trash
+    pub async fn is_trashed(pool: &DbPool, id: Uuid) -> Result<bool, sqlx::Error> {
+        db::query_scalar!(
+            r#"SELECT deleted_at IS NOT NULL as "trashed!: bool" FROM tasks WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+        .map(|trashed| trashed.unwrap_or(false))
+    }
+
+    /// The project's archived tasks that aren't in the trash, most recently archived first
+    pub async fn find_archived(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<ShelvedTask>, sqlx::Error> {
+        db::query_as!(
+            ShelvedTask,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, status as "status!: TaskStatus", priority as "priority: TaskPriority", archived_at as "archived_at: DateTime<Utc>", deleted_at as "deleted_at: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM tasks
+               WHERE project_id = $1 AND archived_at IS NOT NULL AND deleted_at IS NULL
+               ORDER BY archived_at DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The project's tasks in the trash, most recently deleted first
+    pub async fn find_trashed(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<ShelvedTask>, sqlx::Error> {
+        db::query_as!(
+            ShelvedTask,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, status as "status!: TaskStatus", priority as "priority: TaskPriority", archived_at as "archived_at: DateTime<Utc>", deleted_at as "deleted_at: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM tasks
+               WHERE project_id = $1 AND deleted_at IS NOT NULL
+               ORDER BY deleted_at DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn exists(pool: &DbPool, id: Uuid, project_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = db::query_scalar!(
             "SELECT id as \"id!: Uuid\" FROM tasks WHERE id = $1 AND project_id = $2",
             id,
             project_id
@@ -279,14 +1166,14 @@ ORDER BY t.created_at DESC"#,
     }
 
     pub async fn find_related_tasks_by_attempt_id(
-        pool: &SqlitePool,
+        pool: &DbPool,
         attempt_id: Uuid,
         project_id: Uuid,
     ) -> Result<Vec<Self>, sqlx::Error> {
         // Find both children and parent for this attempt
-        sqlx::query_as!(
+        db::query_as!(
             Task,
-            r#"SELECT DISTINCT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.parent_task_attempt as "parent_task_attempt: Uuid", t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT DISTINCT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.parent_task_attempt as "parent_task_attempt: Uuid", t.parent_task_id as "parent_task_id: Uuid", t.timeout_minutes, t.priority as "priority: TaskPriority", t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks t
                WHERE (
                    -- Find children: tasks that have this attempt as parent
@@ -311,3 +1198,135 @@ ORDER BY t.created_at DESC"#,
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(
+        title: &str,
+        priority: Option<TaskPriority>,
+        points: Option<&str>,
+    ) -> TaskWithAttemptStatus {
+        TaskWithAttemptStatus {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            title: title.to_string(),
+            description: None,
+            status: TaskStatus::Todo,
+            parent_task_attempt: None,
+            parent_task_id: None,
+            timeout_minutes: None,
+            priority,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            labels: vec!["backend".to_string()],
+            custom_fields: points
+                .map(|points| HashMap::from([("points".to_string(), points.to_string())]))
+                .unwrap_or_default(),
+            board_column_id: None,
+            has_in_progress_attempt: false,
+            has_merged_attempt: false,
+            last_attempt_failed: false,
+            verification_failed: false,
+            latest_attempt_executor: None,
+            stale_since: None,
+            queue_position: None,
+            pr_url: None,
+            pr_number: None,
+            pr_status: None,
+            latest_summary: None,
+        }
+    }
+
+    fn titles(tasks: &[TaskWithAttemptStatus]) -> Vec<&str> {
+        tasks.iter().map(|task| task.title.as_str()).collect()
+    }
+
+    #[test]
+    fn test_board_query_filters_and_sorts() {
+        let tasks = || {
+            vec![
+                task("a", Some(TaskPriority::Low), Some("8")),
+                task("b", None, Some("13")),
+                task("c", Some(TaskPriority::Urgent), None),
+                task("d", Some(TaskPriority::High), Some("2")),
+            ]
+        };
+
+        let by_priority = TaskBoardQuery {
+            sort: TaskSort::Priority,
+            ..Default::default()
+        };
+        assert_eq!(titles(&by_priority.apply(tasks())), ["c", "d", "a", "b"]);
+
+        let by_points = TaskBoardQuery {
+            sort: TaskSort::Field,
+            sort_field: Some("points".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(titles(&by_points.apply(tasks())), ["d", "a", "b", "c"]);
+
+        let filtered = TaskBoardQuery {
+            label: Some(" Backend ".to_string()),
+            priority: Some(TaskPriority::High),
+            field: Some("points:2".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(titles(&filtered.apply(tasks())), ["d"]);
+
+        let other_label = TaskBoardQuery {
+            label: Some("frontend".to_string()),
+            ..Default::default()
+        };
+        assert!(other_label.apply(tasks()).is_empty());
+    }
+
+    #[test]
+    fn test_merge_keeps_changes_to_other_fields_and_reports_overlaps() {
+        let base = TaskEdit {
+            title: "Fix login".to_string(),
+            description: Some("Safari only".to_string()),
+            status: TaskStatus::Todo,
+            parent_task_attempt: None,
+        };
+        // Since the edit started, someone else moved the task along
+        let current = Task {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            title: base.title.clone(),
+            description: base.description.clone(),
+            status: TaskStatus::InProgress,
+            parent_task_attempt: None,
+            parent_task_id: None,
+            timeout_minutes: None,
+            priority: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        // The form sends every field, but only the title changed
+        let retitled = UpdateTask {
+            title: Some("Fix login on Safari".to_string()),
+            description: base.description.clone(),
+            status: Some(TaskStatus::Todo),
+            parent_task_attempt: None,
+            expected_updated_at: None,
+            base: None,
+        };
+        let edit = retitled.merge(&current, Some(&base)).unwrap();
+        assert_eq!(edit.title, "Fix login on Safari");
+        assert_eq!(edit.status, TaskStatus::InProgress);
+
+        let moved = UpdateTask {
+            status: Some(TaskStatus::Done),
+            ..retitled
+        };
+        assert_eq!(moved.merge(&current, Some(&base)), Err(vec!["status"]));
+        // Without a base the edit wins
+        assert_eq!(
+            moved.merge(&current, None).unwrap().status,
+            TaskStatus::Done
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    db::{self, DbPool},
+    utils::asset_dir,
+};
+
+/// Larger files are almost always video or archives, which no executor can use
+pub const MAX_ATTACHMENT_BYTES: usize = 10 * 1024 * 1024;
+
+#[derive(Debug)]
+pub enum TaskAttachmentError {
+    Database(sqlx::Error),
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for TaskAttachmentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TaskAttachmentError::Database(e) => write!(f, "Database error: {}", e),
+            TaskAttachmentError::Io(e) => write!(f, "Failed to store attachment: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for TaskAttachmentError {}
+
+impl From<sqlx::Error> for TaskAttachmentError {
+    fn from(err: sqlx::Error) -> Self {
+        TaskAttachmentError::Database(err)
+    }
+}
+
+impl From<std::io::Error> for TaskAttachmentError {
+    fn from(err: std::io::Error) -> Self {
+        TaskAttachmentError::Io(err)
+    }
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct TaskAttachment {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TaskAttachment {
+    pub fn is_image(&self) -> bool {
+        self.content_type.starts_with("image/")
+    }
+
+    /// Where a task's attachments are stored; outside the worktree so they never end up in
+    /// a commit
+    pub fn task_dir(task_id: Uuid) -> PathBuf {
+        asset_dir().join("attachments").join(task_id.to_string())
+    }
+
+    /// The stored file keeps the original name so executors see something meaningful
+    pub fn path(&self) -> PathBuf {
+        Self::task_dir(self.task_id).join(format!(
+            "{}-{}",
+            &self.id.simple().to_string()[..8],
+            self.file_name
+        ))
+    }
+
+    pub async fn find_by_task_id(pool: &DbPool, task_id: Uuid) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            TaskAttachment,
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", file_name, content_type, size_bytes, created_at as "created_at!: DateTime<Utc>"
+               FROM task_attachments
+               WHERE task_id = $1
+               ORDER BY created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id_and_task_id(
+        pool: &DbPool,
+        id: Uuid,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            TaskAttachment,
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", file_name, content_type, size_bytes, created_at as "created_at!: DateTime<Utc>"
+               FROM task_attachments
+               WHERE id = $1 AND task_id = $2"#,
+            id,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The attachments a coding agent run was given
+    pub async fn find_by_execution_process_id(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            TaskAttachment,
+            r#"SELECT a.id as "id!: Uuid", a.task_id as "task_id!: Uuid", a.file_name, a.content_type, a.size_bytes, a.created_at as "created_at!: DateTime<Utc>"
+               FROM task_attachments a
+               JOIN execution_process_attachments epa ON epa.attachment_id = a.id
+               WHERE epa.execution_process_id = $1
+               ORDER BY a.created_at ASC"#,
+            execution_process_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &DbPool,
+        task_id: Uuid,
+        file_name: &str,
+        content_type: &str,
+        bytes: &[u8],
+    ) -> Result<Self, TaskAttachmentError> {
+        let id = Uuid::new_v4();
+        let size_bytes = bytes.len() as i64;
+        let attachment = db::query_as!(
+            TaskAttachment,
+            r#"INSERT INTO task_attachments (id, task_id, file_name, content_type, size_bytes)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", file_name, content_type, size_bytes, created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            task_id,
+            file_name,
+            content_type,
+            size_bytes
+        )
+        .fetch_one(pool)
+        .await?;
+
+        let stored = async {
+            tokio::fs::create_dir_all(Self::task_dir(task_id)).await?;
+            tokio::fs::write(attachment.path(), bytes).await
+        };
+        if let Err(e) = stored.await {
+            Self::delete(pool, id).await?;
+            return Err(e.into());
+        }
+        Ok(attachment)
+    }
+
+    pub async fn delete(pool: &DbPool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = db::query!("DELETE FROM task_attachments WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Record that a coding agent run was handed these attachments
+    pub async fn record_for_execution_process(
+        pool: &DbPool,
+        execution_process_id: Uuid,
+        attachments: &[TaskAttachment],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        for attachment in attachments {
+            db::query!(
+                "INSERT OR IGNORE INTO execution_process_attachments (execution_process_id, attachment_id) VALUES ($1, $2)",
+                execution_process_id,
+                attachment.id
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    /// The images of a task whose files are present, for executors that can look at them
+    pub async fn find_images_by_task_id(
+        pool: &DbPool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        Ok(Self::find_by_task_id(pool, task_id)
+            .await?
+            .into_iter()
+            .filter(|attachment| attachment.is_image() && attachment.path().is_file())
+            .collect())
+    }
+}
+
+/// Keep only the last path component and characters that are safe in a file name
+pub fn sanitize_file_name(name: &str) -> Option<String> {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let cleaned: String = base
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let cleaned = cleaned.trim_start_matches('.');
+    if cleaned.is_empty() {
+        None
+    } else {
+        Some(cleaned.chars().take(100).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_file_name() {
+        assert_eq!(
+            sanitize_file_name("../../etc/login screen.png").as_deref(),
+            Some("login_screen.png")
+        );
+        assert_eq!(
+            sanitize_file_name("C:\\Users\\me\\mock-up_v2.jpg").as_deref(),
+            Some("mock-up_v2.jpg")
+        );
+        assert_eq!(sanitize_file_name(".."), None);
+        assert_eq!(sanitize_file_name(""), None);
+    }
+}
@@ -1,17 +1,38 @@
-use std::path::Path;
+use std::{collections::HashMap, path::Path};
 
 use chrono::{DateTime, Utc};
 use git2::{BranchType, Error as GitError, Repository};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool, Type};
+use sqlx::{FromRow, Type};
 use tracing::info;
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::{project::Project, task::Task};
-use crate::services::{
-    CreatePrRequest, GitHubRepoInfo, GitHubService, GitHubServiceError, GitService,
-    GitServiceError, ProcessService,
+use super::{
+    attempt_linked_worktree::{AttemptLinkedWorktree, LinkedRepoDiff},
+    attempt_rollback::{backup_ref, rollback_target, AttemptRollback},
+    event::{Event, EventKind},
+    execution_metrics::ExecutionMetrics,
+    execution_process::{ExecutionProcess, ExecutionProcessStatus, ExecutionProcessType},
+    execution_snapshot::ExecutionSnapshot,
+    execution_verification::ExecutionVerification,
+    network_interruption::NetworkInterruption,
+    project::Project,
+    task::Task,
+    task_linked_project::TaskLinkedProject,
+};
+use crate::{
+    db::{self, DbPool},
+    executor::{ExecutorCapabilities, ExecutorConfig, ExecutorError},
+    services::{
+        git_host::{GitHost, GitHostError},
+        git_service::RebaseOutcome,
+        guardrails::BudgetExceeded,
+        repo_config, CreatePrRequest, GitHubServiceError, GitService, GitServiceError,
+        ProcessService,
+    },
+    utils::worktree_manager::WorktreeManager,
 };
 
 // Constants for git diff operations
@@ -24,10 +45,14 @@ pub enum TaskAttemptError {
     Git(GitError),
     GitService(GitServiceError),
     GitHubService(GitHubServiceError),
+    GitHost(GitHostError),
     TaskNotFound,
     ProjectNotFound,
     ValidationError(String),
     BranchNotFound(String),
+    Executor(Box<ExecutorError>),
+    /// The project's monthly budget is spent, so no agent is started for it
+    BudgetExceeded(BudgetExceeded),
 }
 
 impl std::fmt::Display for TaskAttemptError {
@@ -37,10 +62,13 @@ impl std::fmt::Display for TaskAttemptError {
             TaskAttemptError::Git(e) => write!(f, "Git error: {}", e),
             TaskAttemptError::GitService(e) => write!(f, "Git service error: {}", e),
             TaskAttemptError::GitHubService(e) => write!(f, "GitHub service error: {}", e),
+            TaskAttemptError::GitHost(e) => write!(f, "{}", e),
             TaskAttemptError::TaskNotFound => write!(f, "Task not found"),
             TaskAttemptError::ProjectNotFound => write!(f, "Project not found"),
             TaskAttemptError::ValidationError(e) => write!(f, "Validation error: {}", e),
             TaskAttemptError::BranchNotFound(branch) => write!(f, "Branch '{}' not found", branch),
+            TaskAttemptError::Executor(e) => write!(f, "{}", e),
+            TaskAttemptError::BudgetExceeded(e) => write!(f, "{}", e),
         }
     }
 }
@@ -71,8 +99,14 @@ impl From<GitHubServiceError> for TaskAttemptError {
     }
 }
 
+impl From<GitHostError> for TaskAttemptError {
+    fn from(err: GitHostError) -> Self {
+        TaskAttemptError::GitHost(err)
+    }
+}
+
 #[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS)]
-#[sqlx(type_name = "task_attempt_status", rename_all = "lowercase")]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
 #[ts(export)]
 pub enum TaskAttemptStatus {
@@ -84,7 +118,7 @@ pub enum TaskAttemptStatus {
     ExecutorFailed,
 }
 
-#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, JsonSchema, TS)]
 #[ts(export)]
 pub struct TaskAttempt {
     pub id: Uuid,
@@ -100,15 +134,26 @@ pub struct TaskAttempt {
     pub pr_merged_at: Option<DateTime<Utc>>, // When PR was merged
     pub worktree_deleted: bool,    // Flag indicating if worktree has been cleaned up
     pub setup_completed_at: Option<DateTime<Utc>>, // When setup script was last completed
+    pub plan_only: bool,           // Stops at a plan for review before implementing
+    /// The model the agent is asked to run with; `None` leaves it to the config's default
+    /// for the executor, or the CLI's own
+    pub model: Option<String>,
+    /// The executor profile it was started with, whose flags and variables its runs get
+    pub executor_profile_id: Option<Uuid>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize, TS)]
+#[derive(Debug, Deserialize, JsonSchema, TS)]
 #[ts(export)]
 pub struct CreateTaskAttempt {
     pub executor: Option<String>, // Optional executor name (defaults to "echo")
     pub base_branch: Option<String>, // Optional base branch to checkout (defaults to current HEAD)
+    pub plan_only: Option<bool>,  // Only plan, and implement once the plan is approved
+    /// The model to run the agent with, as its CLI names it (e.g. "opus", "o4-mini")
+    pub model: Option<String>,
+    /// A profile to run with, which picks the executor
+    pub executor_profile_id: Option<Uuid>,
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -117,25 +162,36 @@ pub struct UpdateTaskAttempt {
     // Currently no updateable fields, but keeping struct for API compatibility
 }
 
-/// GitHub PR creation parameters
+/// An attempt whose worktree may be cleaned up
+#[derive(FromRow)]
+struct CleanupCandidate {
+    attempt_id: Uuid,
+    worktree_path: String,
+    git_repo_path: String,
+}
+
+/// Pull request creation parameters
 pub struct CreatePrParams<'a> {
     pub attempt_id: Uuid,
     pub task_id: Uuid,
     pub project_id: Uuid,
-    pub github_token: &'a str,
+    /// Where the pull request is opened and the branch pushed
+    pub host: &'a dyn GitHost,
     pub title: &'a str,
     pub body: Option<&'a str>,
     pub base_branch: Option<&'a str>,
-    pub enterprise_url: Option<&'a str>,
 }
 
 #[derive(Debug, Deserialize, TS)]
 #[ts(export)]
 pub struct CreateFollowUpAttempt {
     pub prompt: String,
+    /// Hand the attempt over to this executor instead of continuing the current one's session
+    #[serde(default)]
+    pub executor: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, TS)]
 #[ts(export)]
 pub enum DiffChunkType {
     Equal,
@@ -143,26 +199,156 @@ pub enum DiffChunkType {
     Delete,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, TS)]
 #[ts(export)]
 pub struct DiffChunk {
     pub chunk_type: DiffChunkType,
     pub content: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, TS)]
 #[ts(export)]
 pub struct FileDiff {
     pub path: String,
     pub chunks: Vec<DiffChunk>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, TS)]
 #[ts(export)]
 pub struct WorktreeDiff {
     pub files: Vec<FileDiff>,
 }
 
+/// One hunk of an attempt's diff, as git reports it, so a selection can name it by index
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DiffHunk {
+    pub index: u32,
+    /// The `@@ -a,b +c,d @@` line, including any function context git finds
+    pub header: String,
+    pub old_start: u32,
+    pub new_start: u32,
+    pub lines: Vec<DiffChunk>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct FileHunks {
+    pub path: String,
+    pub hunks: Vec<DiffHunk>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum FileChangeKind {
+    Added,
+    Modified,
+    Deleted,
+}
+
+/// A file or directory in an attempt's worktree, marked with its change against the base branch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct FileTreeNode {
+    pub name: String,
+    pub path: String,
+    pub is_dir: bool,
+    pub change: Option<FileChangeKind>,
+    /// True when this node or anything beneath it has changed
+    pub has_changes: bool,
+    pub children: Vec<FileTreeNode>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum LineChangeKind {
+    Added,
+    Modified,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LineChange {
+    /// 1-based line number in the returned content
+    pub line: usize,
+    pub kind: LineChangeKind,
+}
+
+/// Contents of a single file from an attempt, with line changes against the base branch.
+/// `content` is omitted for binary files and files over the size limit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct FileContent {
+    pub path: String,
+    pub size: usize,
+    pub is_binary: bool,
+    pub too_large: bool,
+    pub content: Option<String>,
+    pub line_changes: Vec<LineChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WorktreeStats {
+    pub files_changed: usize,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+}
+
+/// A file changed by both attempts, with the diff from the left version to the right one
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SharedFileComparison {
+    pub path: String,
+    pub left_change: FileChangeKind,
+    pub right_change: FileChangeKind,
+    pub identical: bool,
+    pub left_to_right: Vec<DiffChunk>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WorktreeComparison {
+    pub left: WorktreeStats,
+    pub right: WorktreeStats,
+    pub only_left: Vec<String>,
+    pub only_right: Vec<String>,
+    pub shared: Vec<SharedFileComparison>,
+}
+
+/// How one attempt's run went, for side-by-side comparison
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AttemptRunSummary {
+    pub attempt_id: Uuid,
+    pub executor: Option<String>,
+    pub branch: String,
+    pub merged: bool,
+    /// Status of the most recent coding agent run
+    pub agent_status: Option<ExecutionProcessStatus>,
+    /// Total wall-clock time spent in coding agent runs
+    pub agent_duration_ms: Option<i64>,
+    /// Whether every verification command passed after the latest agent run; `None` when
+    /// it wasn't verified
+    pub verification_passed: Option<bool>,
+    /// The verification commands run after the latest agent run, in the order they ran
+    pub verifications: Vec<ExecutionVerification>,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    /// `None` when none of the agent runs reported cost
+    pub cost_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AttemptComparison {
+    pub left: AttemptRunSummary,
+    pub right: AttemptRunSummary,
+    pub changes: WorktreeComparison,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct BranchStatus {
@@ -173,6 +359,8 @@ pub struct BranchStatus {
     pub merged: bool,
     pub has_uncommitted_changes: bool,
     pub base_branch_name: String,
+    /// Files that would conflict if the branch were rebased onto its base now
+    pub conflicted_files: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -187,6 +375,9 @@ pub enum ExecutionState {
     CodingAgentComplete,
     CodingAgentFailed,
     CodingAgentStopped,
+    /// The agent lost its network connection or hit its provider's rate limit and is waiting
+    /// to go on
+    CodingAgentRetrying,
     Complete,
 }
 
@@ -198,6 +389,8 @@ pub struct TaskAttemptState {
     pub has_setup_script: bool,
     pub setup_process_id: Option<String>,
     pub coding_agent_process_id: Option<String>,
+    /// What the attempt's executor supports; `None` if it isn't known
+    pub capabilities: Option<ExecutorCapabilities>,
 }
 
 /// Context data for resume operations (simplified)
@@ -207,6 +400,29 @@ pub struct AttemptResumeContext {
     pub cumulative_diffs: String,
 }
 
+/// An attempt whose changes landed, by a local merge or through its PR
+#[derive(Debug, Clone, FromRow)]
+pub struct MergedAttempt {
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub merge_commit: Option<String>,
+    pub pr_url: Option<String>,
+    pub pr_number: Option<i64>,
+    pub pr_merged_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// An attempt whose worktree hasn't been cleaned up yet
+#[derive(Debug, Clone, FromRow)]
+pub struct AttemptWorktree {
+    pub attempt_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub branch: String,
+    pub worktree_path: String,
+    pub updated_at: DateTime<Utc>,
+}
+
 #[derive(Debug)]
 pub struct TaskAttemptContext {
     pub task_attempt: TaskAttempt,
@@ -215,15 +431,27 @@ pub struct TaskAttemptContext {
 }
 
 impl TaskAttempt {
+    /// The executor the attempt's latest coding agent run used, falling back to the one it
+    /// was created with
+    pub fn current_executor(&self, processes: &[ExecutionProcess]) -> Option<ExecutorConfig> {
+        processes
+            .iter()
+            .rev()
+            .find(|p| matches!(p.process_type, ExecutionProcessType::CodingAgent))
+            .and_then(|p| p.executor_type.as_deref())
+            .or(self.executor.as_deref())
+            .and_then(|executor| executor.parse().ok())
+    }
+
     /// Load task attempt with full validation - ensures task_attempt belongs to task and task belongs to project
     pub async fn load_context(
-        pool: &SqlitePool,
+        pool: &DbPool,
         attempt_id: Uuid,
         task_id: Uuid,
         project_id: Uuid,
     ) -> Result<TaskAttemptContext, TaskAttemptError> {
         // Single query with JOIN validation to ensure proper relationships
-        let task_attempt = sqlx::query_as!(
+        let task_attempt = db::query_as!(
             TaskAttempt,
             r#"SELECT  ta.id                AS "id!: Uuid",
                        ta.task_id           AS "task_id!: Uuid",
@@ -238,6 +466,9 @@ impl TaskAttempt {
                        ta.pr_merged_at      AS "pr_merged_at: DateTime<Utc>",
                        ta.worktree_deleted  AS "worktree_deleted!: bool",
                        ta.setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       ta.plan_only         AS "plan_only!: bool",
+                       ta.model,
+                       ta.executor_profile_id AS "executor_profile_id?: Uuid",
                        ta.created_at        AS "created_at!: DateTime<Utc>",
                        ta.updated_at        AS "updated_at!: DateTime<Utc>"
                FROM    task_attempts ta
@@ -269,12 +500,9 @@ impl TaskAttempt {
     }
 
     /// Helper function to mark a worktree as deleted in the database
-    pub async fn mark_worktree_deleted(
-        pool: &SqlitePool,
-        attempt_id: Uuid,
-    ) -> Result<(), sqlx::Error> {
-        sqlx::query!(
-            "UPDATE task_attempts SET worktree_deleted = TRUE, updated_at = datetime('now') WHERE id = ?",
+    pub async fn mark_worktree_deleted(pool: &DbPool, attempt_id: Uuid) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE task_attempts SET worktree_deleted = TRUE, updated_at = datetime('now') WHERE id = $1",
             attempt_id
         )
         .execute(pool)
@@ -302,8 +530,54 @@ impl TaskAttempt {
         }
     }
 
-    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
-        sqlx::query_as!(
+    pub async fn find_merged_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<MergedAttempt>, sqlx::Error> {
+        db::query_as!(
+            MergedAttempt,
+            r#"SELECT ta.task_id      AS "task_id!: Uuid",
+                      t.title         AS task_title,
+                      ta.merge_commit,
+                      ta.pr_url,
+                      ta.pr_number,
+                      ta.pr_merged_at AS "pr_merged_at: DateTime<Utc>",
+                      ta.updated_at   AS "updated_at!: DateTime<Utc>"
+               FROM task_attempts ta
+               JOIN tasks t ON ta.task_id = t.id
+               WHERE t.project_id = $1
+                 AND (ta.merge_commit IS NOT NULL OR ta.pr_status = 'merged')
+               ORDER BY ta.updated_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_worktrees_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<AttemptWorktree>, sqlx::Error> {
+        db::query_as!(
+            AttemptWorktree,
+            r#"SELECT ta.id            AS "attempt_id!: Uuid",
+                      ta.task_id       AS "task_id!: Uuid",
+                      t.title          AS task_title,
+                      ta.branch,
+                      ta.worktree_path,
+                      ta.updated_at    AS "updated_at!: DateTime<Utc>"
+               FROM task_attempts ta
+               JOIN tasks t ON ta.task_id = t.id
+               WHERE t.project_id = $1 AND ta.worktree_deleted = FALSE
+               ORDER BY ta.updated_at DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
             TaskAttempt,
             r#"SELECT  id                AS "id!: Uuid",
                        task_id           AS "task_id!: Uuid",
@@ -318,6 +592,9 @@ impl TaskAttempt {
                        pr_merged_at      AS "pr_merged_at: DateTime<Utc>",
                        worktree_deleted  AS "worktree_deleted!: bool",
                        setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       plan_only         AS "plan_only!: bool",
+                       model,
+                       executor_profile_id AS "executor_profile_id?: Uuid",
                        created_at        AS "created_at!: DateTime<Utc>",
                        updated_at        AS "updated_at!: DateTime<Utc>"
                FROM    task_attempts
@@ -328,11 +605,56 @@ impl TaskAttempt {
         .await
     }
 
-    pub async fn find_by_task_id(
-        pool: &SqlitePool,
-        task_id: Uuid,
-    ) -> Result<Vec<Self>, sqlx::Error> {
-        sqlx::query_as!(
+    /// The project the attempt's task belongs to
+    pub async fn find_project_id(pool: &DbPool, id: Uuid) -> Result<Option<Uuid>, sqlx::Error> {
+        db::query_scalar!(
+            r#"SELECT t.project_id as "project_id!: Uuid"
+               FROM task_attempts ta
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE ta.id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The attempt working in `worktree_path`, the most recent if the path was reused
+    pub async fn find_by_worktree_path(
+        pool: &DbPool,
+        worktree_path: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            TaskAttempt,
+            r#"SELECT  id                AS "id!: Uuid",
+                       task_id           AS "task_id!: Uuid",
+                       worktree_path,
+                       branch,
+                       merge_commit,
+                       base_branch,
+                       executor,
+                       pr_url,
+                       pr_number,
+                       pr_status,
+                       pr_merged_at      AS "pr_merged_at: DateTime<Utc>",
+                       worktree_deleted  AS "worktree_deleted!: bool",
+                       setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       plan_only         AS "plan_only!: bool",
+                       model,
+                       executor_profile_id AS "executor_profile_id?: Uuid",
+                       created_at        AS "created_at!: DateTime<Utc>",
+                       updated_at        AS "updated_at!: DateTime<Utc>"
+               FROM    task_attempts
+               WHERE   worktree_path = $1
+               ORDER BY created_at DESC
+               LIMIT 1"#,
+            worktree_path
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_task_id(pool: &DbPool, task_id: Uuid) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
             TaskAttempt,
             r#"SELECT  id                AS "id!: Uuid",
                        task_id           AS "task_id!: Uuid",
@@ -347,6 +669,9 @@ impl TaskAttempt {
                        pr_merged_at      AS "pr_merged_at: DateTime<Utc>",
                        worktree_deleted  AS "worktree_deleted!: bool",
                        setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       plan_only         AS "plan_only!: bool",
+                       model,
+                       executor_profile_id AS "executor_profile_id?: Uuid",
                        created_at        AS "created_at!: DateTime<Utc>",
                        updated_at        AS "updated_at!: DateTime<Utc>"
                FROM    task_attempts
@@ -360,12 +685,13 @@ impl TaskAttempt {
 
     /// Find task attempts by task_id with project git repo path for cleanup operations
     pub async fn find_by_task_id_with_project(
-        pool: &SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
     ) -> Result<Vec<(Uuid, String, String)>, sqlx::Error> {
-        let records = sqlx::query!(
+        let records = db::query_as!(
+            CleanupCandidate,
             r#"
-            SELECT ta.id as "attempt_id!: Uuid", ta.worktree_path, p.git_repo_path as "git_repo_path!"
+            SELECT ta.id as "attempt_id!: Uuid", ta.worktree_path as "worktree_path!", p.git_repo_path as "git_repo_path!"
             FROM task_attempts ta
             JOIN tasks t ON ta.task_id = t.id
             JOIN projects p ON t.project_id = p.id
@@ -386,11 +712,12 @@ impl TaskAttempt {
     /// Activity includes: execution completion, task attempt updates (including worktree recreation),
     /// and any attempts that are currently in progress
     pub async fn find_expired_for_cleanup(
-        pool: &SqlitePool,
+        pool: &DbPool,
     ) -> Result<Vec<(Uuid, String, String)>, sqlx::Error> {
-        let records = sqlx::query!(
+        let records = db::query_as!(
+            CleanupCandidate,
             r#"
-            SELECT ta.id as "attempt_id!: Uuid", ta.worktree_path, p.git_repo_path as "git_repo_path!"
+            SELECT ta.id as "attempt_id!: Uuid", ta.worktree_path as "worktree_path!", p.git_repo_path as "git_repo_path!"
             FROM task_attempts ta
             LEFT JOIN execution_processes ep ON ta.id = ep.task_attempt_id AND ep.completed_at IS NOT NULL
             JOIN tasks t ON ta.task_id = t.id
@@ -424,15 +751,56 @@ impl TaskAttempt {
 
         Ok(records
             .into_iter()
-            .filter_map(|r| {
-                r.worktree_path
-                    .map(|path| (r.attempt_id, path, r.git_repo_path))
-            })
+            .map(|r| (r.attempt_id, r.worktree_path, r.git_repo_path))
+            .collect())
+    }
+
+    /// Find attempts whose work is finished (merged, or their task is done) and that have
+    /// been idle for longer than `idle_for`, a SQLite time modifier such as `'-1 hours'`.
+    /// Attempts with a running process are left alone. `None` searches every project.
+    pub async fn find_finished_for_cleanup(
+        pool: &DbPool,
+        project_id: Option<Uuid>,
+        idle_for: &str,
+    ) -> Result<Vec<(Uuid, String, String)>, sqlx::Error> {
+        let records = db::query_as!(
+            CleanupCandidate,
+            r#"
+            SELECT ta.id as "attempt_id!: Uuid", ta.worktree_path as "worktree_path!", p.git_repo_path as "git_repo_path!"
+            FROM task_attempts ta
+            JOIN tasks t ON ta.task_id = t.id
+            JOIN projects p ON t.project_id = p.id
+            WHERE ta.worktree_deleted = FALSE
+                AND ($1 IS NULL OR t.project_id = $1)
+                AND (ta.merge_commit IS NOT NULL OR ta.pr_status = 'merged' OR t.status = 'done')
+                AND NOT EXISTS (
+                    SELECT 1 FROM execution_processes ep
+                    WHERE ep.task_attempt_id = ta.id AND ep.completed_at IS NULL
+                )
+                AND datetime('now', $2) >= MAX(
+                    datetime(ta.updated_at),
+                    COALESCE(
+                        (SELECT MAX(datetime(ep.completed_at)) FROM execution_processes ep
+                         WHERE ep.task_attempt_id = ta.id),
+                        datetime(ta.updated_at)
+                    )
+                )
+            ORDER BY ta.updated_at ASC
+            "#,
+            project_id,
+            idle_for
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|r| (r.attempt_id, r.worktree_path, r.git_repo_path))
             .collect())
     }
 
     pub async fn create(
-        pool: &SqlitePool,
+        pool: &DbPool,
         data: &CreateTaskAttempt,
         task_id: Uuid,
     ) -> Result<Self, TaskAttemptError> {
@@ -452,55 +820,178 @@ impl TaskAttempt {
             task_title_id
         );
 
-        // Generate worktree path using vibe-kanban specific directory
-        let worktree_path = Self::get_worktree_base_dir().join(&task_attempt_branch);
-        let worktree_path_str = worktree_path.to_string_lossy().to_string();
-
-        // Then get the project using the project_id
-        let project = Project::find_by_id(pool, task.project_id)
+        // Then get the project using the project_id, with the settings its repository's
+        // `.vibe-kanban.toml` fills in
+        let mut project = Project::find_by_id(pool, task.project_id)
             .await?
             .ok_or(TaskAttemptError::ProjectNotFound)?;
+        let repo_config =
+            repo_config::apply(&mut project).map_err(TaskAttemptError::ValidationError)?;
+
+        // Generate worktree path using vibe-kanban specific directory, unless the repository
+        // names its own. A task spanning several repositories gets a workspace there instead,
+        // with a checkout of each side by side, its own project's first.
+        let linked_projects: Vec<TaskLinkedProject> =
+            TaskLinkedProject::find_by_task_id(pool, task_id)
+                .await?
+                .into_iter()
+                .filter(|linked| linked.project_id != project.id)
+                .collect();
+        let workspace_path = repo_config::worktree_base_dir(&project, repo_config.as_ref())
+            .join(&task_attempt_branch);
+        let repo_paths: Vec<&str> = std::iter::once(project.git_repo_path.as_str())
+            .chain(
+                linked_projects
+                    .iter()
+                    .map(|linked| linked.git_repo_path.as_str()),
+            )
+            .collect();
+        let checkout_names = WorktreeManager::workspace_checkout_names(&repo_paths);
+        let worktree_path = if linked_projects.is_empty() {
+            workspace_path.clone()
+        } else {
+            workspace_path.join(&checkout_names[0])
+        };
+        let worktree_path_str = worktree_path.to_string_lossy().to_string();
 
         // Create GitService instance
         let git_service = GitService::new(&project.git_repo_path)?;
 
-        // Determine the resolved base branch name first
-        let resolved_base_branch = if let Some(ref base_branch) = data.base_branch {
+        // Determine the resolved base branch name first; the project's default branch is
+        // used when the attempt doesn't name one
+        let base_branch = data
+            .base_branch
+            .clone()
+            .or_else(|| project.default_branch.clone());
+        let resolved_base_branch = if let Some(ref base_branch) = base_branch {
             base_branch.clone()
         } else {
             // Default to current HEAD branch name or "main"
             git_service.get_default_branch_name()?
         };
 
+        // An executor with a plan mode of its own plans in it; the others are prompted for a
+        // plan instead. The project's default executor runs attempts that don't pick one.
+        let plan_only = data.plan_only.unwrap_or(false);
+        let executor = match data
+            .executor
+            .as_deref()
+            .or(project.default_executor.as_deref())
+        {
+            Some(executor) if plan_only => Some(
+                executor
+                    .parse::<ExecutorConfig>()
+                    .ok()
+                    .and_then(|config| config.plan_executor())
+                    .map(|config| config.to_string())
+                    .unwrap_or_else(|| executor.to_string()),
+            ),
+            executor => executor.map(|executor| executor.to_string()),
+        };
+
+        let model = data
+            .model
+            .as_deref()
+            .map(str::trim)
+            .filter(|model| !model.is_empty());
+
         // Create the worktree using GitService
         git_service.create_worktree(
             &task_attempt_branch,
             &worktree_path,
-            data.base_branch.as_deref(),
+            base_branch.as_deref(),
         )?;
 
+        // Then the linked projects' checkouts, each on the same branch from its own default
+        let mut linked_checkouts = Vec::new();
+        for (linked, name) in linked_projects.iter().zip(checkout_names.iter().skip(1)) {
+            let linked_git_service = GitService::new(&linked.git_repo_path)?;
+            let linked_base_branch = match &linked.default_branch {
+                Some(branch) => branch.clone(),
+                None => linked_git_service.get_default_branch_name()?,
+            };
+            let linked_worktree_path = workspace_path.join(name);
+            linked_git_service.create_worktree(
+                &task_attempt_branch,
+                &linked_worktree_path,
+                Some(&linked_base_branch),
+            )?;
+            linked_checkouts.push((
+                linked.project_id,
+                linked_worktree_path.to_string_lossy().to_string(),
+                linked_base_branch,
+            ));
+        }
+
         // Insert the record into the database
-        Ok(sqlx::query_as!(
+        let task_attempt = db::query_as!(
             TaskAttempt,
-            r#"INSERT INTO task_attempts (id, task_id, worktree_path, branch, base_branch, merge_commit, executor, pr_url, pr_number, pr_status, pr_merged_at, worktree_deleted, setup_completed_at)
-               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
-               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", worktree_path, branch, base_branch, merge_commit, executor, pr_url, pr_number, pr_status, pr_merged_at as "pr_merged_at: DateTime<Utc>", worktree_deleted as "worktree_deleted!: bool", setup_completed_at as "setup_completed_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"INSERT INTO task_attempts (id, task_id, worktree_path, branch, base_branch, merge_commit, executor, pr_url, pr_number, pr_status, pr_merged_at, worktree_deleted, setup_completed_at, plan_only, model, executor_profile_id)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", worktree_path, branch, base_branch, merge_commit, executor, pr_url, pr_number, pr_status, pr_merged_at as "pr_merged_at: DateTime<Utc>", worktree_deleted as "worktree_deleted!: bool", setup_completed_at as "setup_completed_at: DateTime<Utc>", plan_only as "plan_only!: bool", model, executor_profile_id as "executor_profile_id?: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             attempt_id,
             task_id,
             worktree_path_str,
             task_attempt_branch,
             resolved_base_branch,
             Option::<String>::None, // merge_commit is always None during creation
-            data.executor,
+            executor,
             Option::<String>::None, // pr_url is None during creation
             Option::<i64>::None, // pr_number is None during creation
             Option::<String>::None, // pr_status is None during creation
             Option::<DateTime<Utc>>::None, // pr_merged_at is None during creation
             false, // worktree_deleted is false during creation
-            Option::<DateTime<Utc>>::None // setup_completed_at is None during creation
+            Option::<DateTime<Utc>>::None, // setup_completed_at is None during creation
+            plan_only,
+            model,
+            data.executor_profile_id
         )
         .fetch_one(pool)
-        .await?)
+        .await?;
+
+        for (project_id, linked_worktree_path, linked_base_branch) in linked_checkouts {
+            AttemptLinkedWorktree::create(
+                pool,
+                task_attempt.id,
+                project_id,
+                &linked_worktree_path,
+                &linked_base_branch,
+            )
+            .await?;
+        }
+        Ok(task_attempt)
+    }
+
+    /// Re-create an attempt from another machine's export under `task_id`, with a new id. It
+    /// has no worktree; one is made from its branch if the attempt is picked up again.
+    pub async fn create_imported(
+        pool: &DbPool,
+        task_id: Uuid,
+        attempt: &TaskAttempt,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        db::query_as!(
+            TaskAttempt,
+            r#"INSERT INTO task_attempts (id, task_id, worktree_path, branch, base_branch, merge_commit, executor, pr_url, pr_number, pr_status, pr_merged_at, worktree_deleted, plan_only, model, created_at, updated_at)
+               VALUES ($1, $2, '', $3, $4, $5, $6, $7, $8, $9, $10, TRUE, $11, $12, $13, $14)
+               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", worktree_path, branch, base_branch, merge_commit, executor, pr_url, pr_number, pr_status, pr_merged_at as "pr_merged_at: DateTime<Utc>", worktree_deleted as "worktree_deleted!: bool", setup_completed_at as "setup_completed_at: DateTime<Utc>", plan_only as "plan_only!: bool", model, executor_profile_id as "executor_profile_id?: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            task_id,
+            attempt.branch,
+            attempt.base_branch,
+            attempt.merge_commit,
+            attempt.executor,
+            attempt.pr_url,
+            attempt.pr_number,
+            attempt.pr_status,
+            attempt.pr_merged_at,
+            attempt.plan_only,
+            attempt.model,
+            attempt.created_at,
+            attempt.updated_at
+        )
+        .fetch_one(pool)
+        .await
     }
 
     /// Perform the actual merge operation using GitService
@@ -542,18 +1033,24 @@ impl TaskAttempt {
         main_repo_path: &str,
         new_base_branch: Option<String>,
         old_base_branch: String,
-    ) -> Result<String, TaskAttemptError> {
+        leave_conflicts: bool,
+    ) -> Result<RebaseOutcome, TaskAttemptError> {
         let git_service = GitService::new(main_repo_path)?;
         let worktree_path = Path::new(worktree_path);
 
         git_service
-            .rebase_branch(worktree_path, new_base_branch.as_deref(), &old_base_branch)
+            .rebase_branch(
+                worktree_path,
+                new_base_branch.as_deref(),
+                &old_base_branch,
+                leave_conflicts,
+            )
             .map_err(TaskAttemptError::from)
     }
 
     /// Merge the worktree changes back to the main repository
     pub async fn merge_changes(
-        pool: &SqlitePool,
+        pool: &DbPool,
         attempt_id: Uuid,
         task_id: Uuid,
         project_id: Uuid,
@@ -577,7 +1074,7 @@ impl TaskAttempt {
         )?;
 
         // Update the task attempt with the merge commit
-        sqlx::query!(
+        db::query!(
             "UPDATE task_attempts SET merge_commit = $1, updated_at = datetime('now') WHERE id = $2",
             merge_commit_id,
             attempt_id
@@ -588,9 +1085,106 @@ impl TaskAttempt {
         Ok(merge_commit_id)
     }
 
+    /// Reset the attempt's branch to the commit one of its earlier runs left, e.g. when a
+    /// follow-up made things worse. The commit it was on stays reachable under a backup ref.
+    pub async fn rollback_to(
+        pool: &DbPool,
+        attempt_id: Uuid,
+        task_id: Uuid,
+        project_id: Uuid,
+        execution_process_id: Uuid,
+    ) -> Result<AttemptRollback, TaskAttemptError> {
+        let ctx = TaskAttempt::load_context(pool, attempt_id, task_id, project_id).await?;
+        if ctx.task_attempt.merge_commit.is_some() {
+            return Err(TaskAttemptError::ValidationError(
+                "This attempt has already been merged".to_string(),
+            ));
+        }
+
+        let processes =
+            ExecutionProcess::find_summaries_by_task_attempt_id(pool, attempt_id).await?;
+        let to_commit = rollback_target(&processes, execution_process_id)
+            .map_err(|reason| TaskAttemptError::ValidationError(reason.to_string()))?
+            .to_string();
+
+        let worktree_path =
+            Self::ensure_worktree_exists(pool, attempt_id, project_id, "rollback").await?;
+        let rollback_id = Uuid::new_v4();
+        let from_commit = GitService::new(&ctx.project.git_repo_path)?.reset_worktree(
+            Path::new(&worktree_path),
+            &to_commit,
+            &backup_ref(attempt_id, rollback_id),
+        )?;
+
+        Ok(AttemptRollback::create(
+            pool,
+            rollback_id,
+            attempt_id,
+            execution_process_id,
+            &from_commit,
+            &to_commit,
+        )
+        .await?)
+    }
+
+    /// Take what one of the attempt's runs changed back out of its branch, from the snapshot
+    /// taken before the run to the commit it left, keeping what the other runs changed. The
+    /// revert is a commit of its own, so it can be reverted in turn.
+    pub async fn revert_execution(
+        pool: &DbPool,
+        attempt_id: Uuid,
+        task_id: Uuid,
+        project_id: Uuid,
+        execution_process_id: Uuid,
+    ) -> Result<ExecutionSnapshot, TaskAttemptError> {
+        let ctx = TaskAttempt::load_context(pool, attempt_id, task_id, project_id).await?;
+        if ctx.task_attempt.merge_commit.is_some() {
+            return Err(TaskAttemptError::ValidationError(
+                "This attempt has already been merged".to_string(),
+            ));
+        }
+
+        let invalid = |reason: &str| TaskAttemptError::ValidationError(reason.to_string());
+        let process = ExecutionProcess::find_by_id(pool, execution_process_id)
+            .await?
+            .filter(|process| process.task_attempt_id == attempt_id)
+            .ok_or_else(|| invalid("That run isn't part of this attempt"))?;
+        if matches!(process.status, ExecutionProcessStatus::Running) {
+            return Err(invalid("That run hasn't finished yet"));
+        }
+        let snapshot = ExecutionSnapshot::find_by_execution_process_id(pool, process.id)
+            .await?
+            .ok_or_else(|| invalid("There's no snapshot from before that run to revert to"))?;
+        if snapshot.reverted_at.is_some() {
+            return Err(invalid("That run has already been reverted"));
+        }
+        let run_commit = process
+            .commit_sha
+            .as_deref()
+            .ok_or_else(|| invalid("That run didn't leave a commit to revert"))?;
+
+        let worktree_path =
+            Self::ensure_worktree_exists(pool, attempt_id, project_id, "revert").await?;
+        let message = format!(
+            "Revert the changes of run {}\n\nUndoes {}..{} of the {} run.",
+            process.id,
+            &snapshot.snapshot_commit[..snapshot.snapshot_commit.len().min(12)],
+            &run_commit[..run_commit.len().min(12)],
+            process.executor_type.as_deref().unwrap_or("agent"),
+        );
+        let revert_commit = GitService::new(&ctx.project.git_repo_path)?.revert_changes(
+            Path::new(&worktree_path),
+            &snapshot.snapshot_commit,
+            run_commit,
+            &message,
+        )?;
+
+        Ok(ExecutionSnapshot::mark_reverted(pool, process.id, &revert_commit).await?)
+    }
+
     /// Start the execution flow for a task attempt (setup script + executor)
     pub async fn start_execution(
-        pool: &SqlitePool,
+        pool: &DbPool,
         app_state: &crate::app_state::AppState,
         attempt_id: Uuid,
         task_id: Uuid,
@@ -601,7 +1195,7 @@ impl TaskAttempt {
 
     /// Start a dev server for this task attempt
     pub async fn start_dev_server(
-        pool: &SqlitePool,
+        pool: &DbPool,
         app_state: &crate::app_state::AppState,
         attempt_id: Uuid,
         task_id: Uuid,
@@ -613,7 +1207,7 @@ impl TaskAttempt {
     /// Start a follow-up execution using the same executor type as the first process
     /// Returns the attempt_id that was actually used (always the original attempt_id for session continuity)
     pub async fn start_followup_execution(
-        pool: &SqlitePool,
+        pool: &DbPool,
         app_state: &crate::app_state::AppState,
         attempt_id: Uuid,
         task_id: Uuid,
@@ -626,9 +1220,26 @@ impl TaskAttempt {
         .await
     }
 
+    /// Start a follow-up, handed over to `executor` when it's another than the attempt's
+    #[allow(clippy::too_many_arguments)]
+    pub async fn start_followup_execution_with_executor(
+        pool: &DbPool,
+        app_state: &crate::app_state::AppState,
+        attempt_id: Uuid,
+        task_id: Uuid,
+        project_id: Uuid,
+        prompt: &str,
+        executor: Option<ExecutorConfig>,
+    ) -> Result<Uuid, TaskAttemptError> {
+        ProcessService::start_followup_execution_with_executor(
+            pool, app_state, attempt_id, task_id, project_id, prompt, executor,
+        )
+        .await
+    }
+
     /// Ensure worktree exists, recreating from branch if needed (cold task support)
     pub async fn ensure_worktree_exists(
-        pool: &SqlitePool,
+        pool: &DbPool,
         attempt_id: Uuid,
         project_id: Uuid,
         context: &str,
@@ -652,7 +1263,7 @@ impl TaskAttempt {
             Self::recreate_worktree_from_branch(pool, &task_attempt, project_id).await?;
 
         // Update database with new path, reset worktree_deleted flag, and clear setup completion
-        sqlx::query!(
+        db::query!(
             "UPDATE task_attempts SET worktree_path = $1, worktree_deleted = FALSE, setup_completed_at = NULL, updated_at = datetime('now') WHERE id = $2",
             new_worktree_path,
             attempt_id
@@ -663,9 +1274,74 @@ impl TaskAttempt {
         Ok(new_worktree_path)
     }
 
+    /// The directory a coding agent of the attempt works in: its worktree, or for a
+    /// multi-repository task the workspace holding it and the linked projects' checkouts, which
+    /// are recreated if they were cleaned up
+    pub async fn agent_workspace(
+        pool: &DbPool,
+        attempt_id: Uuid,
+        worktree_path: &str,
+    ) -> Result<String, TaskAttemptError> {
+        let linked_worktrees =
+            AttemptLinkedWorktree::find_by_task_attempt_id(pool, attempt_id).await?;
+        if linked_worktrees.is_empty() {
+            return Ok(worktree_path.to_string());
+        }
+        let Some(workspace) = std::path::Path::new(worktree_path).parent() else {
+            return Ok(worktree_path.to_string());
+        };
+        let task_attempt = TaskAttempt::find_by_id(pool, attempt_id)
+            .await?
+            .ok_or(TaskAttemptError::TaskNotFound)?;
+
+        let mut checkouts = Vec::new();
+        for linked in linked_worktrees {
+            let project = Project::find_by_id(pool, linked.project_id)
+                .await?
+                .ok_or(TaskAttemptError::ProjectNotFound)?;
+            checkouts.push((
+                project.git_repo_path,
+                task_attempt.branch.clone(),
+                std::path::PathBuf::from(linked.worktree_path),
+            ));
+        }
+        WorktreeManager::ensure_workspace_exists(checkouts).await?;
+        Ok(workspace.to_string_lossy().to_string())
+    }
+
+    /// The changes of each linked project of a multi-repository attempt, against the branch
+    /// its checkout started from. Only the attempt's own repository is merged; the linked
+    /// ones keep their changes on a branch of the same name.
+    pub async fn get_linked_diffs(
+        pool: &DbPool,
+        attempt_id: Uuid,
+    ) -> Result<Vec<LinkedRepoDiff>, TaskAttemptError> {
+        let mut diffs = Vec::new();
+        for linked in AttemptLinkedWorktree::find_by_task_attempt_id(pool, attempt_id).await? {
+            let Some(project) = Project::find_by_id(pool, linked.project_id).await? else {
+                continue;
+            };
+            let worktree_path = std::path::Path::new(&linked.worktree_path);
+            if !worktree_path.exists() {
+                continue;
+            }
+            let diff = GitService::new(&project.git_repo_path)?.get_enhanced_diff(
+                worktree_path,
+                None,
+                &linked.base_branch,
+            )?;
+            diffs.push(LinkedRepoDiff {
+                project_id: project.id,
+                project_name: project.name,
+                diff,
+            });
+        }
+        Ok(diffs)
+    }
+
     /// Recreate a worktree from an existing branch (for cold task support)
     pub async fn recreate_worktree_from_branch(
-        pool: &SqlitePool,
+        pool: &DbPool,
         task_attempt: &TaskAttempt,
         project_id: Uuid,
     ) -> Result<String, TaskAttemptError> {
@@ -689,7 +1365,7 @@ impl TaskAttempt {
 
     /// Get the git diff between the base commit and the current committed worktree state
     pub async fn get_diff(
-        pool: &SqlitePool,
+        pool: &DbPool,
         attempt_id: Uuid,
         task_id: Uuid,
         project_id: Uuid,
@@ -725,9 +1401,158 @@ impl TaskAttempt {
         }
     }
 
+    /// Get the worktree's directory tree with change markers against the base branch
+    pub async fn get_file_tree(
+        pool: &DbPool,
+        attempt_id: Uuid,
+        task_id: Uuid,
+        project_id: Uuid,
+    ) -> Result<Vec<FileTreeNode>, TaskAttemptError> {
+        let ctx = TaskAttempt::load_context(pool, attempt_id, task_id, project_id).await?;
+        let worktree_path =
+            Self::ensure_worktree_exists(pool, attempt_id, project_id, "file tree").await?;
+
+        let git_service = GitService::new(&ctx.project.git_repo_path)?;
+        git_service
+            .get_file_tree(Path::new(&worktree_path), &ctx.task_attempt.base_branch)
+            .map_err(TaskAttemptError::from)
+    }
+
+    /// The attempt's changes against its base branch, hunk by hunk
+    pub async fn get_diff_hunks(
+        pool: &DbPool,
+        attempt_id: Uuid,
+        task_id: Uuid,
+        project_id: Uuid,
+    ) -> Result<Vec<FileHunks>, TaskAttemptError> {
+        let ctx = TaskAttempt::load_context(pool, attempt_id, task_id, project_id).await?;
+        let worktree_path =
+            Self::ensure_worktree_exists(pool, attempt_id, project_id, "diff hunks").await?;
+
+        let git_service = GitService::new(&ctx.project.git_repo_path)?;
+        git_service
+            .get_worktree_hunks(Path::new(&worktree_path), &ctx.task_attempt.base_branch)
+            .map_err(TaskAttemptError::from)
+    }
+
+    /// The files an attempt changed against its base branch, committed or not
+    pub async fn get_worktree_changes(
+        pool: &DbPool,
+        attempt_id: Uuid,
+        task_id: Uuid,
+        project_id: Uuid,
+    ) -> Result<HashMap<String, FileChangeKind>, TaskAttemptError> {
+        let ctx = TaskAttempt::load_context(pool, attempt_id, task_id, project_id).await?;
+        let worktree_path =
+            Self::ensure_worktree_exists(pool, attempt_id, project_id, "worktree changes").await?;
+
+        let git_service = GitService::new(&ctx.project.git_repo_path)?;
+        git_service
+            .get_worktree_changes(Path::new(&worktree_path), &ctx.task_attempt.base_branch)
+            .map_err(TaskAttemptError::from)
+    }
+
+    /// Read a file from the worktree, or from `commit` when given, annotated against the base branch
+    pub async fn get_file_content(
+        pool: &DbPool,
+        attempt_id: Uuid,
+        task_id: Uuid,
+        project_id: Uuid,
+        path: &str,
+        commit: Option<&str>,
+    ) -> Result<FileContent, TaskAttemptError> {
+        let ctx = TaskAttempt::load_context(pool, attempt_id, task_id, project_id).await?;
+        let worktree_path =
+            Self::ensure_worktree_exists(pool, attempt_id, project_id, "file content").await?;
+
+        let git_service = GitService::new(&ctx.project.git_repo_path)?;
+        git_service
+            .get_file_content(
+                Path::new(&worktree_path),
+                &ctx.task_attempt.base_branch,
+                path,
+                commit,
+            )
+            .map_err(TaskAttemptError::from)
+    }
+
+    /// Compare two attempts of the same task
+    pub async fn compare(
+        pool: &DbPool,
+        left_id: Uuid,
+        right_id: Uuid,
+        task_id: Uuid,
+        project_id: Uuid,
+    ) -> Result<AttemptComparison, TaskAttemptError> {
+        let left = TaskAttempt::load_context(pool, left_id, task_id, project_id).await?;
+        let right = TaskAttempt::load_context(pool, right_id, task_id, project_id).await?;
+        if left.task_attempt.base_branch != right.task_attempt.base_branch {
+            return Err(TaskAttemptError::ValidationError(format!(
+                "Attempts are based on different branches ({} and {})",
+                left.task_attempt.base_branch, right.task_attempt.base_branch
+            )));
+        }
+
+        let left_path = Self::ensure_worktree_exists(pool, left_id, project_id, "compare").await?;
+        let right_path =
+            Self::ensure_worktree_exists(pool, right_id, project_id, "compare").await?;
+        let changes = GitService::new(&left.project.git_repo_path)?.compare_worktrees(
+            Path::new(&left_path),
+            Path::new(&right_path),
+            &left.task_attempt.base_branch,
+        )?;
+
+        Ok(AttemptComparison {
+            left: Self::run_summary(pool, &left.task_attempt).await?,
+            right: Self::run_summary(pool, &right.task_attempt).await?,
+            changes,
+        })
+    }
+
+    async fn run_summary(
+        pool: &DbPool,
+        attempt: &TaskAttempt,
+    ) -> Result<AttemptRunSummary, TaskAttemptError> {
+        let agent_runs: Vec<_> =
+            ExecutionProcess::find_summaries_by_task_attempt_id(pool, attempt.id)
+                .await?
+                .into_iter()
+                .filter(|p| p.process_type == ExecutionProcessType::CodingAgent)
+                .collect();
+
+        let durations: Vec<i64> = agent_runs
+            .iter()
+            .filter_map(|p| {
+                p.completed_at
+                    .map(|done| (done - p.started_at).num_milliseconds())
+            })
+            .collect();
+
+        let verifications = match agent_runs.last() {
+            Some(run) => ExecutionVerification::find_by_execution_process_id(pool, run.id).await?,
+            None => Vec::new(),
+        };
+        let usage = ExecutionMetrics::usage_by_attempt(pool, attempt.id).await?;
+
+        Ok(AttemptRunSummary {
+            attempt_id: attempt.id,
+            executor: attempt.executor.clone(),
+            branch: attempt.branch.clone(),
+            merged: attempt.merge_commit.is_some(),
+            agent_status: agent_runs.last().map(|p| p.status.clone()),
+            agent_duration_ms: (!durations.is_empty()).then(|| durations.iter().sum()),
+            verification_passed: (!verifications.is_empty())
+                .then(|| verifications.iter().all(|v| v.passed)),
+            verifications,
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            cost_usd: usage.cost_usd,
+        })
+    }
+
     /// Get the branch status for this task attempt
     pub async fn get_branch_status(
-        pool: &SqlitePool,
+        pool: &DbPool,
         attempt_id: Uuid,
         task_id: Uuid,
         project_id: Uuid,
@@ -765,17 +1590,24 @@ impl TaskAttempt {
         }
 
         // Calculate ahead/behind counts using the stored base branch
-        let (commits_ahead, commits_behind) =
-            if let Ok(base_branch) = main_repo.find_branch(&base_branch_name, BranchType::Local) {
-                if let Some(base_oid) = base_branch.get().target() {
-                    main_repo.graph_ahead_behind(attempt_oid, base_oid)?
-                } else {
-                    (0, 0) // Base branch has no commits
-                }
-            } else {
-                // Base branch doesn't exist, assume no relationship
-                (0, 0)
-            };
+        let base_oid = main_repo
+            .find_branch(&base_branch_name, BranchType::Local)
+            .ok()
+            .and_then(|base_branch| base_branch.get().target());
+        let (commits_ahead, commits_behind) = match base_oid {
+            Some(base_oid) => main_repo.graph_ahead_behind(attempt_oid, base_oid)?,
+            // Base branch doesn't exist or has no commits, assume no relationship
+            None => (0, 0),
+        };
+
+        // Only a branch that has diverged from its base can conflict with it
+        let conflicted_files = match base_oid {
+            Some(base_oid) if commits_ahead > 0 && commits_behind > 0 => {
+                GitService::new(&ctx.project.git_repo_path)?
+                    .conflicting_files(attempt_oid, base_oid)?
+            }
+            _ => Vec::new(),
+        };
 
         // ── detect any uncommitted / untracked changes ───────────────────────────────
         let repo_for_status = Repository::open(&ctx.project.git_repo_path)?;
@@ -800,17 +1632,21 @@ impl TaskAttempt {
             merged: ctx.task_attempt.merge_commit.is_some(),
             has_uncommitted_changes,
             base_branch_name,
+            conflicted_files,
         })
     }
 
-    /// Rebase the worktree branch onto specified base branch (or current HEAD if none specified)
+    /// Rebase the worktree branch onto specified base branch (or current HEAD if none specified).
+    /// With `leave_conflicts`, changes that conflict with the base are left in the worktree
+    /// with conflict markers instead of failing the rebase.
     pub async fn rebase_attempt(
-        pool: &SqlitePool,
+        pool: &DbPool,
         attempt_id: Uuid,
         task_id: Uuid,
         project_id: Uuid,
         new_base_branch: Option<String>,
-    ) -> Result<String, TaskAttemptError> {
+        leave_conflicts: bool,
+    ) -> Result<RebaseOutcome, TaskAttemptError> {
         // Load context with full validation
         let ctx = TaskAttempt::load_context(pool, attempt_id, task_id, project_id).await?;
 
@@ -822,11 +1658,12 @@ impl TaskAttempt {
         let worktree_path =
             Self::ensure_worktree_exists(pool, attempt_id, project_id, "rebase").await?;
 
-        let new_base_commit = Self::perform_rebase_operation(
+        let outcome = Self::perform_rebase_operation(
             &worktree_path,
             &ctx.project.git_repo_path,
             effective_base_branch.clone(),
             ctx.task_attempt.base_branch.clone(),
+            leave_conflicts,
         )?;
 
         // Update the database with the new base branch if it was changed
@@ -839,7 +1676,7 @@ impl TaskAttempt {
                     new_base_branch
                 };
 
-                sqlx::query!(
+                db::query!(
                     "UPDATE task_attempts SET base_branch = $1, updated_at = datetime('now') WHERE id = $2",
                     db_branch_name,
                     attempt_id
@@ -849,12 +1686,12 @@ impl TaskAttempt {
             }
         }
 
-        Ok(new_base_commit)
+        Ok(outcome)
     }
 
     /// Delete a file from the worktree and commit the change
     pub async fn delete_file(
-        pool: &SqlitePool,
+        pool: &DbPool,
         attempt_id: Uuid,
         task_id: Uuid,
         project_id: Uuid,
@@ -877,9 +1714,9 @@ impl TaskAttempt {
         Ok(commit_id)
     }
 
-    /// Create a GitHub PR for this task attempt
-    pub async fn create_github_pr(
-        pool: &SqlitePool,
+    /// Push this task attempt's branch and open a pull request for it on the project's host
+    pub async fn create_pr(
+        pool: &DbPool,
         params: CreatePrParams<'_>,
     ) -> Result<String, TaskAttemptError> {
         // Load context with full validation
@@ -888,33 +1725,23 @@ impl TaskAttempt {
                 .await?;
 
         // Ensure worktree exists (recreate if needed for cold task support)
-        let worktree_path =
-            Self::ensure_worktree_exists(pool, params.attempt_id, params.project_id, "GitHub PR")
-                .await?;
-
-        // Create GitHub service instance
-        let github_service = if let Some(enterprise_url) = params.enterprise_url {
-            GitHubService::new_with_base_url(params.github_token, enterprise_url)?
-        } else {
-            GitHubService::new(params.github_token)?
-        };
-
-        // Use GitService to get the remote URL, then create GitHubRepoInfo
-        let git_service = GitService::new(&ctx.project.git_repo_path)?;
-        let (owner, repo_name) = git_service
-            .get_github_repo_info()
-            .map_err(|e| TaskAttemptError::ValidationError(e.to_string()))?;
-        let repo_info = GitHubRepoInfo { owner, repo_name };
+        let worktree_path = Self::ensure_worktree_exists(
+            pool,
+            params.attempt_id,
+            params.project_id,
+            "pull request",
+        )
+        .await?;
 
-        // Push the branch to GitHub first
-        Self::push_branch_to_github(
-            &ctx.project.git_repo_path,
-            &worktree_path,
+        // Push the branch first
+        let (username, token) = params.host.push_credentials();
+        GitService::new(&ctx.project.git_repo_path)?.push_with_token(
+            Path::new(&worktree_path),
             &ctx.task_attempt.branch,
-            params.github_token,
+            username,
+            token,
         )?;
 
-        // Create the PR using GitHub service
         let pr_request = CreatePrRequest {
             title: params.title.to_string(),
             body: params.body.map(|s| s.to_string()),
@@ -922,10 +1749,10 @@ impl TaskAttempt {
             base_branch: params.base_branch.unwrap_or("main").to_string(),
         };
 
-        let pr_info = github_service.create_pr(&repo_info, &pr_request).await?;
+        let pr_info = params.host.create_pull_request(&pr_request).await?;
 
         // Update the task attempt with PR information
-        sqlx::query!(
+        db::query!(
             "UPDATE task_attempts SET pr_url = $1, pr_number = $2, pr_status = $3, updated_at = datetime('now') WHERE id = $4",
             pr_info.url,
             pr_info.number,
@@ -935,32 +1762,27 @@ impl TaskAttempt {
         .execute(pool)
         .await?;
 
-        Ok(pr_info.url)
-    }
+        Event::record_for_attempt(
+            pool,
+            params.attempt_id,
+            None,
+            EventKind::PrOpened,
+            serde_json::json!({ "pr_url": pr_info.url, "pr_number": pr_info.number }),
+        )
+        .await?;
 
-    /// Push the branch to GitHub remote
-    fn push_branch_to_github(
-        git_repo_path: &str,
-        worktree_path: &str,
-        branch_name: &str,
-        github_token: &str,
-    ) -> Result<(), TaskAttemptError> {
-        // Use GitService to push to GitHub
-        let git_service = GitService::new(git_repo_path)?;
-        git_service
-            .push_to_github(Path::new(worktree_path), branch_name, github_token)
-            .map_err(TaskAttemptError::from)
+        Ok(pr_info.url)
     }
 
     /// Update PR status and merge commit
     pub async fn update_pr_status(
-        pool: &SqlitePool,
+        pool: &DbPool,
         attempt_id: Uuid,
         status: &str,
         merged_at: Option<DateTime<Utc>>,
         merge_commit_sha: Option<&str>,
     ) -> Result<(), sqlx::Error> {
-        sqlx::query!(
+        db::query!(
             "UPDATE task_attempts SET pr_status = $1, pr_merged_at = $2, merge_commit = $3, updated_at = datetime('now') WHERE id = $4",
             status,
             merged_at,
@@ -973,15 +1795,40 @@ impl TaskAttempt {
         Ok(())
     }
 
+    /// Carry the attempt on with `executor`, dropping the model and profile that were picked
+    /// for the executor before it
+    pub async fn hand_over(
+        pool: &DbPool,
+        attempt_id: Uuid,
+        executor: &str,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE task_attempts SET executor = $1, model = NULL, executor_profile_id = NULL, updated_at = datetime('now') WHERE id = $2",
+            executor,
+            attempt_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
     /// Get the current execution state for a task attempt
     pub async fn get_execution_state(
-        pool: &SqlitePool,
+        pool: &DbPool,
         attempt_id: Uuid,
         task_id: Uuid,
         project_id: Uuid,
     ) -> Result<TaskAttemptState, TaskAttemptError> {
         // Load context with full validation
-        let ctx = TaskAttempt::load_context(pool, attempt_id, task_id, project_id).await?;
+        let mut ctx = TaskAttempt::load_context(pool, attempt_id, task_id, project_id).await?;
+        if let Err(e) = repo_config::apply(&mut ctx.project) {
+            tracing::warn!(
+                "Ignoring the repository config of project {}: {}",
+                project_id,
+                e
+            );
+        }
 
         let has_setup_script = ctx
             .project
@@ -1015,22 +1862,34 @@ impl TaskAttempt {
         // Determine execution state based on processes
         let execution_state = if let Some(setup) = setup_process {
             match setup.status {
-                crate::models::execution_process::ExecutionProcessStatus::Running => {
+                crate::models::execution_process::ExecutionProcessStatus::Running
+                | crate::models::execution_process::ExecutionProcessStatus::Paused => {
                     ExecutionState::SetupRunning
                 }
                 crate::models::execution_process::ExecutionProcessStatus::Completed => {
                     if let Some(agent) = coding_agent_process {
                         match agent.status {
-                            crate::models::execution_process::ExecutionProcessStatus::Running => {
+                            crate::models::execution_process::ExecutionProcessStatus::Running
+                            | crate::models::execution_process::ExecutionProcessStatus::Paused => {
                                 ExecutionState::CodingAgentRunning
                             }
                             crate::models::execution_process::ExecutionProcessStatus::Completed => {
                                 ExecutionState::CodingAgentComplete
                             }
-                            crate::models::execution_process::ExecutionProcessStatus::Failed => {
+                            crate::models::execution_process::ExecutionProcessStatus::Failed
+                            | crate::models::execution_process::ExecutionProcessStatus::TimedOut
+                            | crate::models::execution_process::ExecutionProcessStatus::ResourceLimitExceeded => {
                                 ExecutionState::CodingAgentFailed
                             }
-                            crate::models::execution_process::ExecutionProcessStatus::Killed => {
+                            crate::models::execution_process::ExecutionProcessStatus::RateLimited => {
+                                if agent.retry_at.is_some() {
+                                    ExecutionState::CodingAgentRetrying
+                                } else {
+                                    ExecutionState::CodingAgentFailed
+                                }
+                            }
+                            crate::models::execution_process::ExecutionProcessStatus::Killed
+                            | crate::models::execution_process::ExecutionProcessStatus::Interrupted => {
                                 ExecutionState::CodingAgentStopped
                             }
                         }
@@ -1038,26 +1897,41 @@ impl TaskAttempt {
                         ExecutionState::SetupComplete
                     }
                 }
-                crate::models::execution_process::ExecutionProcessStatus::Failed => {
+                crate::models::execution_process::ExecutionProcessStatus::Failed
+                | crate::models::execution_process::ExecutionProcessStatus::TimedOut
+                | crate::models::execution_process::ExecutionProcessStatus::ResourceLimitExceeded
+                | crate::models::execution_process::ExecutionProcessStatus::RateLimited => {
                     ExecutionState::SetupFailed
                 }
-                crate::models::execution_process::ExecutionProcessStatus::Killed => {
+                crate::models::execution_process::ExecutionProcessStatus::Killed
+                | crate::models::execution_process::ExecutionProcessStatus::Interrupted => {
                     ExecutionState::SetupStopped
                 }
             }
         } else if let Some(agent) = coding_agent_process {
             // No setup script, only coding agent
             match agent.status {
-                crate::models::execution_process::ExecutionProcessStatus::Running => {
+                crate::models::execution_process::ExecutionProcessStatus::Running
+                | crate::models::execution_process::ExecutionProcessStatus::Paused => {
                     ExecutionState::CodingAgentRunning
                 }
                 crate::models::execution_process::ExecutionProcessStatus::Completed => {
                     ExecutionState::CodingAgentComplete
                 }
-                crate::models::execution_process::ExecutionProcessStatus::Failed => {
+                crate::models::execution_process::ExecutionProcessStatus::Failed
+                | crate::models::execution_process::ExecutionProcessStatus::TimedOut
+                | crate::models::execution_process::ExecutionProcessStatus::ResourceLimitExceeded => {
                     ExecutionState::CodingAgentFailed
                 }
-                crate::models::execution_process::ExecutionProcessStatus::Killed => {
+                crate::models::execution_process::ExecutionProcessStatus::RateLimited => {
+                    if agent.retry_at.is_some() {
+                        ExecutionState::CodingAgentRetrying
+                    } else {
+                        ExecutionState::CodingAgentFailed
+                    }
+                }
+                crate::models::execution_process::ExecutionProcessStatus::Killed
+                | crate::models::execution_process::ExecutionProcessStatus::Interrupted => {
                     ExecutionState::CodingAgentStopped
                 }
             }
@@ -1066,6 +1940,16 @@ impl TaskAttempt {
             ExecutionState::NotStarted
         };
 
+        let waiting_on_network = NetworkInterruption::find_by_task_attempt_id(pool, attempt_id)
+            .await?
+            .iter()
+            .any(|i| i.is_open());
+        let execution_state = if waiting_on_network {
+            ExecutionState::CodingAgentRetrying
+        } else {
+            execution_state
+        };
+
         // Check if there are any changes (quick diff check)
         let has_changes = match Self::get_diff(pool, attempt_id, task_id, project_id).await {
             Ok(diff) => !diff.files.is_empty(),
@@ -1078,12 +1962,16 @@ impl TaskAttempt {
             has_setup_script,
             setup_process_id: setup_process.map(|p| p.id.to_string()),
             coding_agent_process_id: coding_agent_process.map(|p| p.id.to_string()),
+            capabilities: ctx
+                .task_attempt
+                .current_executor(&processes)
+                .map(|executor| executor.capabilities()),
         })
     }
 
     /// Check if setup script has been completed for this worktree
     pub async fn is_setup_completed(
-        pool: &SqlitePool,
+        pool: &DbPool,
         attempt_id: Uuid,
     ) -> Result<bool, TaskAttemptError> {
         let task_attempt = Self::find_by_id(pool, attempt_id)
@@ -1095,11 +1983,11 @@ impl TaskAttempt {
 
     /// Mark setup script as completed for this worktree
     pub async fn mark_setup_completed(
-        pool: &SqlitePool,
+        pool: &DbPool,
         attempt_id: Uuid,
     ) -> Result<(), TaskAttemptError> {
-        sqlx::query!(
-            "UPDATE task_attempts SET setup_completed_at = datetime('now'), updated_at = datetime('now') WHERE id = ?",
+        db::query!(
+            "UPDATE task_attempts SET setup_completed_at = datetime('now'), updated_at = datetime('now') WHERE id = $1",
             attempt_id
         )
         .execute(pool)
@@ -1110,7 +1998,7 @@ impl TaskAttempt {
 
     /// Get execution history from current attempt only (simplified)
     pub async fn get_attempt_execution_history(
-        pool: &SqlitePool,
+        pool: &DbPool,
         attempt_id: Uuid,
     ) -> Result<String, TaskAttemptError> {
         // Get all coding agent processes for this attempt
@@ -1146,7 +2034,7 @@ impl TaskAttempt {
 
     /// Get diff between base_branch and current attempt (simplified)
     pub async fn get_attempt_diff(
-        pool: &SqlitePool,
+        pool: &DbPool,
         attempt_id: Uuid,
         project_id: Uuid,
     ) -> Result<String, TaskAttemptError> {
@@ -1199,7 +2087,7 @@ impl TaskAttempt {
 
     /// Get comprehensive resume context for Gemini followup execution (simplified)
     pub async fn get_attempt_resume_context(
-        pool: &SqlitePool,
+        pool: &DbPool,
         attempt_id: Uuid,
         _task_id: Uuid,
         project_id: Uuid,
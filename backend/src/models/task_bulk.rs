@@ -0,0 +1,122 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::TaskStatus;
+
+/// How long a done task is left unchanged before archiving done tasks takes it
+pub const DEFAULT_ARCHIVE_DONE_AFTER_DAYS: u32 = 30;
+
+/// Something done to many of a project's tasks at once
+#[derive(Debug, Deserialize, TS)]
+#[serde(tag = "action", rename_all = "snake_case")]
+#[ts(export)]
+pub enum BulkTaskAction {
+    Archive {
+        task_ids: Vec<Uuid>,
+    },
+    /// Archive every done task left unchanged for `older_than_days`, 30 unless given
+    ArchiveDone {
+        older_than_days: Option<u32>,
+    },
+    Unarchive {
+        task_ids: Vec<Uuid>,
+    },
+    /// Move the tasks to the trash
+    Delete {
+        task_ids: Vec<Uuid>,
+    },
+    /// Take the tasks out of the trash
+    Restore {
+        task_ids: Vec<Uuid>,
+    },
+    /// Move the tasks to one of the project's board columns, or when it has none of its
+    /// own, to a status
+    Move {
+        task_ids: Vec<Uuid>,
+        column_id: Option<Uuid>,
+        status: Option<TaskStatus>,
+    },
+    /// Start a new attempt of each task with another coding agent
+    Rerun {
+        task_ids: Vec<Uuid>,
+        executor: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct BulkTaskFailure {
+    pub task_id: Uuid,
+    pub error: String,
+}
+
+/// What came of a bulk action, task by task
+#[derive(Debug, Clone, Default, Serialize, TS)]
+#[ts(export)]
+pub struct BulkTaskResult {
+    pub succeeded: Vec<Uuid>,
+    pub failed: Vec<BulkTaskFailure>,
+}
+
+impl BulkTaskAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Archive { .. } => "archive",
+            Self::ArchiveDone { .. } => "archive_done",
+            Self::Unarchive { .. } => "unarchive",
+            Self::Delete { .. } => "delete",
+            Self::Restore { .. } => "restore",
+            Self::Move { .. } => "move",
+            Self::Rerun { .. } => "rerun",
+        }
+    }
+}
+
+impl BulkTaskResult {
+    pub fn fail(&mut self, task_id: Uuid, error: impl Into<String>) {
+        self.failed.push(BulkTaskFailure {
+            task_id,
+            error: error.into(),
+        });
+    }
+}
+
+/// Done tasks unchanged since the returned time are archived by `ArchiveDone`
+pub fn archive_done_cutoff(now: DateTime<Utc>, older_than_days: Option<u32>) -> DateTime<Utc> {
+    let days = older_than_days.unwrap_or(DEFAULT_ARCHIVE_DONE_AFTER_DAYS);
+    now - Duration::days(i64::from(days))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_actions_are_tagged_and_archive_done_defaults_to_thirty_days() {
+        let action: BulkTaskAction =
+            serde_json::from_str(r#"{"action":"archive_done","older_than_days":null}"#).unwrap();
+        let BulkTaskAction::ArchiveDone { older_than_days } = action else {
+            panic!("expected archive_done");
+        };
+        let now = Utc::now();
+        assert_eq!(
+            archive_done_cutoff(now, older_than_days),
+            now - Duration::days(30)
+        );
+        assert_eq!(archive_done_cutoff(now, Some(0)), now);
+
+        let action: BulkTaskAction = serde_json::from_str(
+            r#"{"action":"move","task_ids":[],"column_id":null,"status":"done"}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            action,
+            BulkTaskAction::Move {
+                status: Some(TaskStatus::Done),
+                ..
+            }
+        ));
+    }
+}
@@ -0,0 +1,104 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TaskChecklistItem {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub position: i64,
+    pub content: String,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A task's checklist with how far along it is
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct TaskChecklist {
+    pub items: Vec<TaskChecklistItem>,
+    pub completed: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpdateTaskChecklistItem {
+    pub item_id: Uuid,
+    pub completed: bool,
+}
+
+impl TaskChecklist {
+    pub async fn find_by_task_id(pool: &DbPool, task_id: Uuid) -> Result<Self, sqlx::Error> {
+        let items = db::query_as!(
+            TaskChecklistItem,
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", position, content, completed_at as "completed_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM task_checklist_items
+               WHERE task_id = $1
+               ORDER BY position ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await?;
+        let completed = items
+            .iter()
+            .filter(|item| item.completed_at.is_some())
+            .count();
+        Ok(Self {
+            total: items.len(),
+            completed,
+            items,
+        })
+    }
+
+    /// Give a task its checklist, in the order given
+    pub async fn create(pool: &DbPool, task_id: Uuid, items: &[&str]) -> Result<(), sqlx::Error> {
+        for (position, content) in items.iter().enumerate() {
+            let id = Uuid::new_v4();
+            let position = position as i64;
+            db::query!(
+                "INSERT INTO task_checklist_items (id, task_id, position, content) VALUES ($1, $2, $3, $4)",
+                id,
+                task_id,
+                position,
+                content
+            )
+            .execute(pool)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Tick an item off or untick it. Returns false if the task has no such item.
+    pub async fn set_completed(
+        pool: &DbPool,
+        task_id: Uuid,
+        item_id: Uuid,
+        completed: bool,
+    ) -> Result<bool, sqlx::Error> {
+        let result = if completed {
+            db::query!(
+                "UPDATE task_checklist_items SET completed_at = COALESCE(completed_at, datetime('now', 'subsec'))
+                 WHERE id = $1 AND task_id = $2",
+                item_id,
+                task_id
+            )
+            .execute(pool)
+            .await?
+        } else {
+            db::query!(
+                "UPDATE task_checklist_items SET completed_at = NULL WHERE id = $1 AND task_id = $2",
+                item_id,
+                task_id
+            )
+            .execute(pool)
+            .await?
+        };
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,362 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+const MAX_FIELD_NAME_CHARS: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum CustomFieldType {
+    Text,
+    Number,
+    Boolean,
+    /// A day, as YYYY-MM-DD
+    Date,
+    /// One of the field's options
+    Select,
+}
+
+/// A field every task of a project can have a value for
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct CustomField {
+    pub project_id: Uuid,
+    /// Lowercase letters, digits and underscores; prompt templates use the value as
+    /// `{{fields.<name>}}`
+    pub name: String,
+    pub field_type: CustomFieldType,
+    /// The values a select field allows
+    pub options: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertCustomField {
+    pub name: String,
+    pub field_type: CustomFieldType,
+    #[serde(default)]
+    pub options: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct SetTaskCustomField {
+    pub name: String,
+    /// An empty value clears the field
+    pub value: String,
+}
+
+#[derive(FromRow)]
+struct CustomFieldRow {
+    project_id: Uuid,
+    name: String,
+    field_type: CustomFieldType,
+    options: String,
+    created_at: DateTime<Utc>,
+}
+
+impl From<CustomFieldRow> for CustomField {
+    fn from(row: CustomFieldRow) -> Self {
+        Self {
+            project_id: row.project_id,
+            name: row.name,
+            field_type: row.field_type,
+            options: serde_json::from_str(&row.options).unwrap_or_default(),
+            created_at: row.created_at,
+        }
+    }
+}
+
+/// Field names go into template tags and query strings, so they are kept to lowercase
+/// identifiers; spaces and dashes become underscores
+pub fn normalize_field_name(name: &str) -> Option<String> {
+    let name = name.trim().to_lowercase().replace([' ', '-'], "_");
+    let valid = name.chars().count() <= MAX_FIELD_NAME_CHARS
+        && name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    valid.then_some(name)
+}
+
+impl CustomField {
+    /// `value` in the form it is stored in, or why the field can't hold it
+    pub fn parse_value(&self, value: &str) -> Result<String, String> {
+        let value = value.trim();
+        match self.field_type {
+            CustomFieldType::Text => Ok(value.to_string()),
+            CustomFieldType::Number => value
+                .parse::<f64>()
+                .ok()
+                .filter(|number| number.is_finite())
+                .map(|_| value.to_string())
+                .ok_or_else(|| format!("{} must be a number", self.name)),
+            CustomFieldType::Boolean => match value.to_lowercase().as_str() {
+                "true" | "yes" => Ok("true".to_string()),
+                "false" | "no" => Ok("false".to_string()),
+                _ => Err(format!("{} must be true or false", self.name)),
+            },
+            CustomFieldType::Date => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(|date| date.format("%Y-%m-%d").to_string())
+                .map_err(|_| format!("{} must be a date such as 2025-09-30", self.name)),
+            CustomFieldType::Select => self
+                .options
+                .iter()
+                .find(|option| option.eq_ignore_ascii_case(value))
+                .cloned()
+                .ok_or_else(|| {
+                    format!("{} must be one of: {}", self.name, self.options.join(", "))
+                }),
+        }
+    }
+
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = db::query_as!(
+            CustomFieldRow,
+            r#"SELECT project_id as "project_id!: Uuid", name, field_type as "field_type!: CustomFieldType", options, created_at as "created_at!: DateTime<Utc>"
+               FROM project_custom_fields
+               WHERE project_id = $1
+               ORDER BY created_at, name"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(Self::from).collect())
+    }
+
+    pub async fn find(
+        pool: &DbPool,
+        project_id: Uuid,
+        name: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let row = db::query_as!(
+            CustomFieldRow,
+            r#"SELECT project_id as "project_id!: Uuid", name, field_type as "field_type!: CustomFieldType", options, created_at as "created_at!: DateTime<Utc>"
+               FROM project_custom_fields
+               WHERE project_id = $1 AND name = $2"#,
+            project_id,
+            name
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(Self::from))
+    }
+
+    /// Define a field, or change an existing one's type or options. Values the changed
+    /// field can no longer hold are dropped.
+    pub async fn upsert(
+        pool: &DbPool,
+        project_id: Uuid,
+        name: &str,
+        field_type: CustomFieldType,
+        options: &[String],
+    ) -> Result<Self, sqlx::Error> {
+        let options_json = serde_json::to_string(options).unwrap_or_else(|_| "[]".to_string());
+        let field: Self = db::query_as!(
+            CustomFieldRow,
+            r#"INSERT INTO project_custom_fields (project_id, name, field_type, options)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT(project_id, name) DO UPDATE SET
+                   field_type = excluded.field_type,
+                   options = excluded.options
+               RETURNING project_id as "project_id!: Uuid", name, field_type as "field_type!: CustomFieldType", options, created_at as "created_at!: DateTime<Utc>""#,
+            project_id,
+            name,
+            field_type,
+            options_json
+        )
+        .fetch_one(pool)
+        .await?
+        .into();
+
+        let values = db::query_as!(
+            TaskCustomFieldValue,
+            r#"SELECT v.task_id as "task_id!: Uuid", v.name, v.value
+               FROM task_custom_field_values v
+               JOIN tasks t ON v.task_id = t.id
+               WHERE t.project_id = $1 AND v.name = $2"#,
+            project_id,
+            name
+        )
+        .fetch_all(pool)
+        .await?;
+        for value in values {
+            match field.parse_value(&value.value) {
+                Ok(parsed) if parsed == value.value => {}
+                Ok(parsed) => TaskCustomFields::set(pool, value.task_id, name, &parsed).await?,
+                Err(_) => TaskCustomFields::clear(pool, value.task_id, name).await?,
+            }
+        }
+        Ok(field)
+    }
+
+    /// Remove the field along with its values; `false` if the project has no such field
+    pub async fn delete(pool: &DbPool, project_id: Uuid, name: &str) -> Result<bool, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        db::query!(
+            "DELETE FROM task_custom_field_values WHERE name = $2 AND task_id IN (SELECT id FROM tasks WHERE project_id = $1)",
+            project_id,
+            name
+        )
+        .execute(&mut tx)
+        .await?;
+        let result = db::query!(
+            "DELETE FROM project_custom_fields WHERE project_id = $1 AND name = $2",
+            project_id,
+            name
+        )
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// The custom field values of tasks, keyed by field name
+pub struct TaskCustomFields;
+
+#[derive(FromRow)]
+struct TaskCustomFieldValue {
+    task_id: Uuid,
+    name: String,
+    value: String,
+}
+
+impl TaskCustomFields {
+    pub async fn find_by_task_id(
+        pool: &DbPool,
+        task_id: Uuid,
+    ) -> Result<HashMap<String, String>, sqlx::Error> {
+        let rows = db::query_as!(
+            TaskCustomFieldValue,
+            r#"SELECT task_id as "task_id!: Uuid", name, value FROM task_custom_field_values WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(|row| (row.name, row.value)).collect())
+    }
+
+    /// Values of every task in the project, keyed by task
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<HashMap<Uuid, HashMap<String, String>>, sqlx::Error> {
+        let rows = db::query_as!(
+            TaskCustomFieldValue,
+            r#"SELECT v.task_id as "task_id!: Uuid", v.name, v.value
+               FROM task_custom_field_values v
+               JOIN tasks t ON v.task_id = t.id
+               WHERE t.project_id = $1"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        let mut values: HashMap<Uuid, HashMap<String, String>> = HashMap::new();
+        for row in rows {
+            values
+                .entry(row.task_id)
+                .or_default()
+                .insert(row.name, row.value);
+        }
+        Ok(values)
+    }
+
+    /// Store a value already checked with [`CustomField::parse_value`]
+    pub async fn set(
+        pool: &DbPool,
+        task_id: Uuid,
+        name: &str,
+        value: &str,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            r#"INSERT INTO task_custom_field_values (task_id, name, value)
+               VALUES ($1, $2, $3)
+               ON CONFLICT(task_id, name) DO UPDATE SET
+                   value = excluded.value,
+                   updated_at = datetime('now', 'subsec')"#,
+            task_id,
+            name,
+            value
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn clear(pool: &DbPool, task_id: Uuid, name: &str) -> Result<(), sqlx::Error> {
+        db::query!(
+            "DELETE FROM task_custom_field_values WHERE task_id = $1 AND name = $2",
+            task_id,
+            name
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(field_type: CustomFieldType, options: &[&str]) -> CustomField {
+        CustomField {
+            project_id: Uuid::new_v4(),
+            name: "severity".to_string(),
+            field_type,
+            options: options.iter().map(|o| o.to_string()).collect(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_normalize_field_name() {
+        assert_eq!(
+            normalize_field_name(" Story Points "),
+            Some("story_points".to_string())
+        );
+        assert_eq!(
+            normalize_field_name("due-date"),
+            Some("due_date".to_string())
+        );
+        assert_eq!(normalize_field_name("2fast"), None);
+        assert_eq!(normalize_field_name("cost ($)"), None);
+        assert_eq!(normalize_field_name(""), None);
+    }
+
+    #[test]
+    fn test_parse_value_by_type() {
+        let number = field(CustomFieldType::Number, &[]);
+        assert_eq!(number.parse_value(" 3.5 "), Ok("3.5".to_string()));
+        assert!(number.parse_value("three").is_err());
+        assert!(number.parse_value("NaN").is_err());
+
+        let boolean = field(CustomFieldType::Boolean, &[]);
+        assert_eq!(boolean.parse_value("Yes"), Ok("true".to_string()));
+        assert!(boolean.parse_value("maybe").is_err());
+
+        let date = field(CustomFieldType::Date, &[]);
+        assert_eq!(date.parse_value("2025-09-30"), Ok("2025-09-30".to_string()));
+        assert!(date.parse_value("30/09/2025").is_err());
+
+        let select = field(CustomFieldType::Select, &["Minor", "Major"]);
+        assert_eq!(select.parse_value("major"), Ok("Major".to_string()));
+        assert_eq!(
+            select.parse_value("blocker"),
+            Err("severity must be one of: Minor, Major".to_string())
+        );
+    }
+}
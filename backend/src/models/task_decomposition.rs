@@ -0,0 +1,125 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::Task;
+use crate::db::{self, DbPool};
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum TaskDecompositionStatus {
+    Pending,
+    Completed,
+    /// The run failed or proposed no steps
+    Failed,
+}
+
+/// A planning attempt run to split a task into subtasks rather than to implement it
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TaskDecomposition {
+    pub task_attempt_id: Uuid,
+    pub task_id: Uuid,
+    pub status: TaskDecompositionStatus,
+    pub subtask_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// What to decompose a task with; the configured executor if none is given
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct DecomposeTask {
+    pub executor: Option<String>,
+}
+
+/// A task's subtasks and how its latest decomposition went
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct TaskSubtasks {
+    pub subtasks: Vec<Task>,
+    pub decomposition: Option<TaskDecomposition>,
+}
+
+impl TaskDecomposition {
+    pub async fn find_by_task_attempt_id(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            TaskDecomposition,
+            r#"SELECT task_attempt_id as "task_attempt_id!: Uuid", task_id as "task_id!: Uuid", status as "status!: TaskDecompositionStatus", subtask_count, created_at as "created_at!: DateTime<Utc>", completed_at as "completed_at: DateTime<Utc>"
+               FROM task_decompositions
+               WHERE task_attempt_id = $1"#,
+            task_attempt_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The task's most recent decomposition
+    pub async fn find_latest_by_task_id(
+        pool: &DbPool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            TaskDecomposition,
+            r#"SELECT task_attempt_id as "task_attempt_id!: Uuid", task_id as "task_id!: Uuid", status as "status!: TaskDecompositionStatus", subtask_count, created_at as "created_at!: DateTime<Utc>", completed_at as "completed_at: DateTime<Utc>"
+               FROM task_decompositions
+               WHERE task_id = $1
+               ORDER BY created_at DESC
+               LIMIT 1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Whether runs of the attempt are to propose subtasks
+    pub async fn is_pending(pool: &DbPool, task_attempt_id: Uuid) -> Result<bool, sqlx::Error> {
+        Ok(Self::find_by_task_attempt_id(pool, task_attempt_id)
+            .await?
+            .is_some_and(|decomposition| decomposition.status == TaskDecompositionStatus::Pending))
+    }
+
+    pub async fn create(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+        task_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        db::query_as!(
+            TaskDecomposition,
+            r#"INSERT INTO task_decompositions (task_attempt_id, task_id)
+               VALUES ($1, $2)
+               RETURNING task_attempt_id as "task_attempt_id!: Uuid", task_id as "task_id!: Uuid", status as "status!: TaskDecompositionStatus", subtask_count, created_at as "created_at!: DateTime<Utc>", completed_at as "completed_at: DateTime<Utc>""#,
+            task_attempt_id,
+            task_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Close a pending decomposition with the number of subtasks it created
+    pub async fn finish(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+        status: TaskDecompositionStatus,
+        subtask_count: i64,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            r#"UPDATE task_decompositions
+               SET status = $2, subtask_count = $3, completed_at = datetime('now', 'subsec')
+               WHERE task_attempt_id = $1 AND status = 'pending'"#,
+            task_attempt_id,
+            status,
+            subtask_count
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// What a task made from a template was given by it, and the executor profile it was assigned
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct TaskDefaults {
+    pub task_id: Uuid,
+    pub template_id: Option<Uuid>, // None once the template is deleted
+    pub executor: Option<String>,  // Used by attempts that don't pick an executor
+    pub verification_script: Option<String>, // Used instead of the project's
+    /// Used by attempts that pick neither an executor nor a profile, over `executor`
+    pub executor_profile_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The executor a task's attempts start with unless they pick one; `None` leaves it to the
+/// task's template or the project
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct SetTaskExecutor {
+    pub executor: Option<String>,
+}
+
+impl TaskDefaults {
+    pub async fn find_by_task_id(
+        pool: &DbPool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            TaskDefaults,
+            r#"SELECT task_id as "task_id!: Uuid", template_id as "template_id?: Uuid", executor, verification_script, executor_profile_id as "executor_profile_id?: Uuid", created_at as "created_at!: DateTime<Utc>"
+               FROM task_defaults
+               WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &DbPool,
+        task_id: Uuid,
+        template_id: Uuid,
+        executor: Option<&str>,
+        verification_script: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        db::query_as!(
+            TaskDefaults,
+            r#"INSERT INTO task_defaults (task_id, template_id, executor, verification_script)
+               VALUES ($1, $2, $3, $4)
+               RETURNING task_id as "task_id!: Uuid", template_id as "template_id?: Uuid", executor, verification_script, executor_profile_id as "executor_profile_id?: Uuid", created_at as "created_at!: DateTime<Utc>""#,
+            task_id,
+            template_id,
+            executor,
+            verification_script
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Verify the task's attempts with `verification_script` instead of the project's commands
+    pub async fn set_verification_script(
+        pool: &DbPool,
+        task_id: Uuid,
+        verification_script: &str,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            r#"INSERT INTO task_defaults (task_id, verification_script)
+               VALUES ($1, $2)
+               ON CONFLICT (task_id) DO UPDATE SET verification_script = excluded.verification_script"#,
+            task_id,
+            verification_script
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Start the task's attempts with `executor` from now on, or clear it with `None`. Setting
+    /// one drops the task's profile, which would otherwise still win over it.
+    pub async fn set_executor(
+        pool: &DbPool,
+        task_id: Uuid,
+        executor: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            r#"INSERT INTO task_defaults (task_id, executor)
+               VALUES ($1, $2)
+               ON CONFLICT (task_id) DO UPDATE SET
+                   executor = excluded.executor,
+                   executor_profile_id = CASE WHEN excluded.executor IS NULL THEN task_defaults.executor_profile_id END"#,
+            task_id,
+            executor
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Assign the task a profile for its attempts, or take it away with `None`
+    pub async fn set_executor_profile(
+        pool: &DbPool,
+        task_id: Uuid,
+        executor_profile_id: Option<Uuid>,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            r#"INSERT INTO task_defaults (task_id, executor_profile_id)
+               VALUES ($1, $2)
+               ON CONFLICT (task_id) DO UPDATE SET executor_profile_id = excluded.executor_profile_id"#,
+            task_id,
+            executor_profile_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,323 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::{Task, TaskStatus};
+use crate::db::{self, DbPool};
+
+/// `task_id` can't start until `blocked_by_task_id` is done
+#[derive(Debug, Clone, FromRow, PartialEq, Serialize, TS)]
+#[ts(export)]
+pub struct TaskDependency {
+    pub task_id: Uuid,
+    pub blocked_by_task_id: Uuid,
+}
+
+/// The other end of a dependency, as shown next to a task
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct DependencyTask {
+    pub id: Uuid,
+    pub title: String,
+    pub status: TaskStatus,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct TaskDependencies {
+    pub blocked_by: Vec<DependencyTask>,
+    pub blocks: Vec<DependencyTask>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct TaskDependencyRequest {
+    pub blocked_by_task_id: Uuid,
+}
+
+/// Every task of a project with blockers ahead of the tasks they block, plus the edges
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct TaskDependencyGraph {
+    pub order: Vec<Uuid>,
+    pub edges: Vec<TaskDependency>,
+}
+
+#[derive(Debug)]
+pub enum TaskDependencyError {
+    Database(sqlx::Error),
+    SelfDependency,
+    BlockerNotFound,
+    Cycle,
+}
+
+impl std::fmt::Display for TaskDependencyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TaskDependencyError::Database(e) => write!(f, "Database error: {}", e),
+            TaskDependencyError::SelfDependency => write!(f, "A task can't block itself"),
+            TaskDependencyError::BlockerNotFound => {
+                write!(f, "The blocking task isn't part of this project")
+            }
+            TaskDependencyError::Cycle => write!(
+                f,
+                "That task already depends on this one, so the dependency would be circular"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TaskDependencyError {}
+
+impl From<sqlx::Error> for TaskDependencyError {
+    fn from(err: sqlx::Error) -> Self {
+        TaskDependencyError::Database(err)
+    }
+}
+
+impl TaskDependencies {
+    pub async fn find_by_task_id(pool: &DbPool, task_id: Uuid) -> Result<Self, sqlx::Error> {
+        let blocked_by = db::query_as!(
+            DependencyTask,
+            r#"SELECT t.id as "id!: Uuid", t.title, t.status as "status!: TaskStatus"
+               FROM task_dependencies d
+               JOIN tasks t ON d.blocked_by_task_id = t.id
+               WHERE d.task_id = $1
+               ORDER BY t.created_at"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await?;
+        let blocks = db::query_as!(
+            DependencyTask,
+            r#"SELECT t.id as "id!: Uuid", t.title, t.status as "status!: TaskStatus"
+               FROM task_dependencies d
+               JOIN tasks t ON d.task_id = t.id
+               WHERE d.blocked_by_task_id = $1
+               ORDER BY t.created_at"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(Self { blocked_by, blocks })
+    }
+}
+
+impl TaskDependencyGraph {
+    pub async fn for_project(pool: &DbPool, project_id: Uuid) -> Result<Self, sqlx::Error> {
+        let task_ids = db::query_scalar!(
+            r#"SELECT id as "id!: Uuid" FROM tasks WHERE project_id = $1 ORDER BY created_at"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        let edges = TaskDependency::find_by_project_id(pool, project_id).await?;
+        Ok(Self {
+            order: topological_order(&task_ids, &edges),
+            edges,
+        })
+    }
+}
+
+impl TaskDependency {
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            TaskDependency,
+            r#"SELECT d.task_id as "task_id!: Uuid", d.blocked_by_task_id as "blocked_by_task_id!: Uuid"
+               FROM task_dependencies d
+               JOIN tasks t ON d.task_id = t.id
+               WHERE t.project_id = $1
+               ORDER BY d.created_at"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Blockers of `task_id` that aren't done yet; the task may only start when this is empty
+    pub async fn unfinished_blockers(
+        pool: &DbPool,
+        task_id: Uuid,
+    ) -> Result<Vec<DependencyTask>, sqlx::Error> {
+        db::query_as!(
+            DependencyTask,
+            r#"SELECT t.id as "id!: Uuid", t.title, t.status as "status!: TaskStatus"
+               FROM task_dependencies d
+               JOIN tasks t ON d.blocked_by_task_id = t.id
+               WHERE d.task_id = $1 AND t.status != 'done' AND t.deleted_at IS NULL
+               ORDER BY t.created_at"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Record that `task` is blocked by `blocked_by_task_id`, a task of the same project
+    pub async fn add(
+        pool: &DbPool,
+        task: &Task,
+        blocked_by_task_id: Uuid,
+    ) -> Result<(), TaskDependencyError> {
+        if task.id == blocked_by_task_id {
+            return Err(TaskDependencyError::SelfDependency);
+        }
+        if Task::find_by_id_and_project_id(pool, blocked_by_task_id, task.project_id)
+            .await?
+            .is_none()
+        {
+            return Err(TaskDependencyError::BlockerNotFound);
+        }
+        let edges = Self::find_by_project_id(pool, task.project_id).await?;
+        if would_create_cycle(&edges, task.id, blocked_by_task_id) {
+            return Err(TaskDependencyError::Cycle);
+        }
+        db::query!(
+            r#"INSERT INTO task_dependencies (task_id, blocked_by_task_id) VALUES ($1, $2)
+               ON CONFLICT(task_id, blocked_by_task_id) DO NOTHING"#,
+            task.id,
+            blocked_by_task_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn remove(
+        pool: &DbPool,
+        task_id: Uuid,
+        blocked_by_task_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = db::query!(
+            "DELETE FROM task_dependencies WHERE task_id = $1 AND blocked_by_task_id = $2",
+            task_id,
+            blocked_by_task_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+/// Why a task with these unfinished blockers can't start yet
+pub fn blocked_message(blockers: &[DependencyTask]) -> String {
+    let titles: Vec<&str> = blockers.iter().map(|task| task.title.as_str()).collect();
+    format!("Blocked by unfinished tasks: {}", titles.join(", "))
+}
+
+/// Whether `task_id` blocked by `blocked_by` closes a loop, i.e. the blocker already
+/// (transitively) waits on the task
+pub fn would_create_cycle(edges: &[TaskDependency], task_id: Uuid, blocked_by: Uuid) -> bool {
+    let mut blockers: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for edge in edges {
+        blockers
+            .entry(edge.task_id)
+            .or_default()
+            .push(edge.blocked_by_task_id);
+    }
+    let mut seen = HashSet::new();
+    let mut stack = vec![blocked_by];
+    while let Some(current) = stack.pop() {
+        if current == task_id {
+            return true;
+        }
+        if seen.insert(current) {
+            stack.extend(blockers.get(&current).into_iter().flatten());
+        }
+    }
+    false
+}
+
+/// `task_ids` reordered so every blocker comes before the tasks it blocks. Otherwise the
+/// input order is kept, and tasks caught in a cycle (which `add` refuses to create) go last.
+pub fn topological_order(task_ids: &[Uuid], edges: &[TaskDependency]) -> Vec<Uuid> {
+    let position: HashMap<Uuid, usize> = task_ids
+        .iter()
+        .enumerate()
+        .map(|(i, id)| (*id, i))
+        .collect();
+    let mut blocked_count = vec![0usize; task_ids.len()];
+    let mut unblocks: Vec<Vec<usize>> = vec![Vec::new(); task_ids.len()];
+    for edge in edges {
+        if let (Some(&task), Some(&blocker)) = (
+            position.get(&edge.task_id),
+            position.get(&edge.blocked_by_task_id),
+        ) {
+            blocked_count[task] += 1;
+            unblocks[blocker].push(task);
+        }
+    }
+
+    let mut ready: BinaryHeap<Reverse<usize>> = (0..task_ids.len())
+        .filter(|&i| blocked_count[i] == 0)
+        .map(Reverse)
+        .collect();
+    let mut placed = vec![false; task_ids.len()];
+    let mut order = Vec::with_capacity(task_ids.len());
+    while let Some(Reverse(i)) = ready.pop() {
+        placed[i] = true;
+        order.push(task_ids[i]);
+        for &task in &unblocks[i] {
+            blocked_count[task] -= 1;
+            if blocked_count[task] == 0 {
+                ready.push(Reverse(task));
+            }
+        }
+    }
+    order.extend(
+        (0..task_ids.len())
+            .filter(|&i| !placed[i])
+            .map(|i| task_ids[i]),
+    );
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge(task_id: Uuid, blocked_by_task_id: Uuid) -> TaskDependency {
+        TaskDependency {
+            task_id,
+            blocked_by_task_id,
+        }
+    }
+
+    #[test]
+    fn test_topological_order_puts_blockers_first() {
+        let ids: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        // 0 waits on 2, 2 waits on 3; 1 is independent
+        let edges = vec![edge(ids[0], ids[2]), edge(ids[2], ids[3])];
+        assert_eq!(
+            topological_order(&ids, &edges),
+            vec![ids[1], ids[3], ids[2], ids[0]]
+        );
+        assert_eq!(topological_order(&ids, &[]), ids);
+
+        // A cycle can't be ordered, but its tasks aren't dropped
+        let cyclic = vec![edge(ids[0], ids[1]), edge(ids[1], ids[0])];
+        assert_eq!(
+            topological_order(&ids, &cyclic),
+            vec![ids[2], ids[3], ids[0], ids[1]]
+        );
+    }
+
+    #[test]
+    fn test_would_create_cycle() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        // a waits on b, b waits on c
+        let edges = vec![edge(a, b), edge(b, c)];
+        assert!(would_create_cycle(&edges, c, a));
+        assert!(would_create_cycle(&edges, b, a));
+        assert!(!would_create_cycle(&edges, a, c));
+        assert!(!would_create_cycle(&[], a, b));
+    }
+}
@@ -0,0 +1,90 @@
+use sha2::{Digest, Sha256};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::{
+    db::{self, DbPool},
+    services::embeddings::{decode_vector, encode_vector},
+};
+
+/// Cached embedding of a task's title and description
+#[derive(Debug, Clone)]
+pub struct TaskEmbedding {
+    pub task_id: Uuid,
+    pub model: String,
+    pub content_hash: String,
+    pub embedding: Vec<f32>,
+}
+
+#[derive(FromRow)]
+struct TaskEmbeddingRow {
+    task_id: Uuid,
+    model: String,
+    content_hash: String,
+    embedding: Vec<u8>,
+}
+
+impl TaskEmbedding {
+    /// The text a task is embedded as
+    pub fn content(title: &str, description: Option<&str>) -> String {
+        match description.map(str::trim).filter(|d| !d.is_empty()) {
+            Some(description) => format!("{}\n\n{}", title.trim(), description),
+            None => title.trim().to_string(),
+        }
+    }
+
+    pub fn content_hash(content: &str) -> String {
+        hex::encode(Sha256::digest(content.as_bytes()))
+    }
+
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = db::query_as!(
+            TaskEmbeddingRow,
+            r#"SELECT e.task_id as "task_id!: Uuid", e.model, e.content_hash, e.embedding
+               FROM task_embeddings e
+               JOIN tasks t ON t.id = e.task_id
+               WHERE t.project_id = $1"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| Self {
+                task_id: row.task_id,
+                model: row.model,
+                content_hash: row.content_hash,
+                embedding: decode_vector(&row.embedding),
+            })
+            .collect())
+    }
+
+    pub async fn upsert(
+        pool: &DbPool,
+        task_id: Uuid,
+        model: &str,
+        content_hash: &str,
+        embedding: &[f32],
+    ) -> Result<(), sqlx::Error> {
+        let embedding = encode_vector(embedding);
+        db::query!(
+            r#"INSERT INTO task_embeddings (task_id, model, content_hash, embedding)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT(task_id) DO UPDATE SET
+                   model = excluded.model,
+                   content_hash = excluded.content_hash,
+                   embedding = excluded.embedding,
+                   created_at = datetime('now', 'subsec')"#,
+            task_id,
+            model,
+            content_hash,
+            embedding
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+const MAX_LABEL_CHARS: usize = 50;
+
+/// A label proposed for a task, with why it was proposed
+#[derive(Debug, Clone, FromRow, PartialEq, Serialize, TS)]
+#[ts(export)]
+pub struct LabelSuggestion {
+    pub name: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct TaskLabels {
+    pub labels: Vec<String>,
+    pub suggestions: Vec<LabelSuggestion>,
+}
+
+#[derive(FromRow)]
+struct TaskLabelRow {
+    task_id: Uuid,
+    name: String,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct TaskLabelName {
+    pub name: String,
+}
+
+/// Labels are compared case-insensitively, so they're stored lowercased
+pub fn normalize_label(name: &str) -> Option<String> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() || name.chars().count() > MAX_LABEL_CHARS {
+        return None;
+    }
+    Some(name)
+}
+
+impl TaskLabels {
+    pub async fn find_by_task_id(pool: &DbPool, task_id: Uuid) -> Result<Self, sqlx::Error> {
+        let labels = db::query_scalar!(
+            "SELECT name FROM task_labels WHERE task_id = $1 ORDER BY name",
+            task_id
+        )
+        .fetch_all(pool)
+        .await?;
+        let suggestions = db::query_as!(
+            LabelSuggestion,
+            "SELECT name, reason FROM task_label_suggestions WHERE task_id = $1 ORDER BY created_at, name",
+            task_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(Self {
+            labels,
+            suggestions,
+        })
+    }
+
+    /// Applied labels of every task in the project, keyed by task
+    pub async fn find_labels_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<HashMap<Uuid, Vec<String>>, sqlx::Error> {
+        let rows = db::query_as!(
+            TaskLabelRow,
+            r#"SELECT l.task_id as "task_id!: Uuid", l.name
+               FROM task_labels l
+               JOIN tasks t ON l.task_id = t.id
+               WHERE t.project_id = $1
+               ORDER BY l.name"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        let mut labels: HashMap<Uuid, Vec<String>> = HashMap::new();
+        for row in rows {
+            labels.entry(row.task_id).or_default().push(row.name);
+        }
+        Ok(labels)
+    }
+
+    /// Apply a label, settling any pending suggestion for it
+    pub async fn add(pool: &DbPool, task_id: Uuid, name: &str) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        db::query!(
+            "INSERT INTO task_labels (task_id, name) VALUES ($1, $2) ON CONFLICT(task_id, name) DO NOTHING",
+            task_id,
+            name
+        )
+        .execute(&mut tx)
+        .await?;
+        db::query!(
+            "DELETE FROM task_label_suggestions WHERE task_id = $1 AND name = $2",
+            task_id,
+            name
+        )
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await
+    }
+
+    pub async fn remove(pool: &DbPool, task_id: Uuid, name: &str) -> Result<u64, sqlx::Error> {
+        let result = db::query!(
+            "DELETE FROM task_labels WHERE task_id = $1 AND name = $2",
+            task_id,
+            name
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Store suggestions, skipping labels the task already has
+    pub async fn suggest(
+        pool: &DbPool,
+        task_id: Uuid,
+        suggestions: &[LabelSuggestion],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        for suggestion in suggestions {
+            db::query!(
+                r#"INSERT INTO task_label_suggestions (task_id, name, reason)
+                   SELECT $1, $2, $3
+                   WHERE NOT EXISTS (SELECT 1 FROM task_labels WHERE task_id = $1 AND name = $2)
+                   ON CONFLICT(task_id, name) DO NOTHING"#,
+                task_id,
+                suggestion.name,
+                suggestion.reason
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    pub async fn dismiss_suggestion(
+        pool: &DbPool,
+        task_id: Uuid,
+        name: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = db::query!(
+            "DELETE FROM task_label_suggestions WHERE task_id = $1 AND name = $2",
+            task_id,
+            name
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// Another project a task spans, checked out next to the task's own by each of its attempts
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct TaskLinkedProject {
+    pub project_id: Uuid,
+    pub name: String,
+    pub git_repo_path: String,
+    /// What attempts branch from in this project
+    pub default_branch: Option<String>,
+}
+
+/// The projects a task spans besides its own; an empty list makes it a single-repository
+/// task again
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct SetTaskLinkedProjects {
+    pub project_ids: Vec<Uuid>,
+}
+
+impl TaskLinkedProject {
+    pub async fn find_by_task_id(pool: &DbPool, task_id: Uuid) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            TaskLinkedProject,
+            r#"SELECT p.id as "project_id!: Uuid", p.name, p.git_repo_path, p.default_branch
+               FROM task_linked_projects tlp
+               JOIN projects p ON p.id = tlp.project_id
+               WHERE tlp.task_id = $1
+               ORDER BY tlp.created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Make `project_ids` the projects the task spans besides its own. Attempts already
+    /// started keep the checkouts they have.
+    pub async fn replace(
+        pool: &DbPool,
+        task_id: Uuid,
+        project_ids: &[Uuid],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        db::query!(
+            "DELETE FROM task_linked_projects WHERE task_id = $1",
+            task_id
+        )
+        .execute(&mut tx)
+        .await?;
+        for project_id in project_ids {
+            db::query!(
+                "INSERT OR IGNORE INTO task_linked_projects (task_id, project_id) VALUES ($1, $2)",
+                task_id,
+                project_id
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+}
@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// A task's title and description from before the summarizer rewrote them
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct TaskOriginalText {
+    pub task_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TaskOriginalText {
+    pub async fn find_by_task_id(
+        pool: &DbPool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            TaskOriginalText,
+            r#"SELECT task_id as "task_id!: Uuid", title, description, created_at as "created_at!: DateTime<Utc>"
+               FROM task_original_texts
+               WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Record the text unless an earlier original is already kept; summarizing twice must
+    /// not replace the pasted text with the first summary
+    pub async fn save_if_absent(
+        pool: &DbPool,
+        task_id: Uuid,
+        title: &str,
+        description: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            "INSERT INTO task_original_texts (task_id, title, description) VALUES ($1, $2, $3) ON CONFLICT(task_id) DO NOTHING",
+            task_id,
+            title,
+            description
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
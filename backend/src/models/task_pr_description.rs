@@ -0,0 +1,84 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// The pull request title and description generated for a task from one of its attempts
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct TaskPrDescription {
+    pub task_id: Uuid,
+    /// The attempt whose diff and conversation it describes
+    pub task_attempt_id: Uuid,
+    pub title: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TaskPrDescription {
+    pub async fn find_by_task_id(
+        pool: &DbPool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            TaskPrDescription,
+            r#"SELECT task_id as "task_id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", title, body, created_at as "created_at!: DateTime<Utc>"
+               FROM task_pr_descriptions
+               WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The task's description if it was generated from this attempt
+    pub async fn find_for_attempt(
+        pool: &DbPool,
+        task_id: Uuid,
+        task_attempt_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        Ok(Self::find_by_task_id(pool, task_id)
+            .await?
+            .filter(|description| description.task_attempt_id == task_attempt_id))
+    }
+
+    /// Keep a newly generated description, replacing the task's earlier one
+    pub async fn save(
+        pool: &DbPool,
+        task_id: Uuid,
+        task_attempt_id: Uuid,
+        title: &str,
+        body: &str,
+    ) -> Result<Self, sqlx::Error> {
+        db::query_as!(
+            TaskPrDescription,
+            r#"INSERT INTO task_pr_descriptions (task_id, task_attempt_id, title, body)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT(task_id) DO UPDATE SET
+                   task_attempt_id = excluded.task_attempt_id,
+                   title = excluded.title,
+                   body = excluded.body,
+                   created_at = datetime('now', 'subsec')
+               RETURNING task_id as "task_id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", title, body, created_at as "created_at!: DateTime<Utc>""#,
+            task_id,
+            task_attempt_id,
+            title,
+            body
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &DbPool, task_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = db::query!(
+            "DELETE FROM task_pr_descriptions WHERE task_id = $1",
+            task_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,251 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// A cron schedule on which a task is run again, each time as a fresh subtask of it
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TaskSchedule {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub cron_expression: String,
+    pub executor: Option<String>, // The task's template executor or the configured one if None
+    pub enabled: bool,
+    pub next_run_at: Option<DateTime<Utc>>, // None once the expression has no more runs
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpsertTaskSchedule {
+    pub cron_expression: String,
+    pub executor: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum TaskScheduleRunStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// One time a schedule came due, and how the run it started went
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TaskScheduleRun {
+    pub id: Uuid,
+    pub schedule_id: Uuid,
+    pub task_id: Option<Uuid>, // The copy of the task; None once it is deleted
+    pub task_attempt_id: Option<Uuid>, // None if the run failed before its attempt was made
+    pub status: TaskScheduleRunStatus,
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl TaskSchedule {
+    pub async fn find_by_task_id(
+        pool: &DbPool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", cron_expression, executor, enabled as "enabled!: bool", next_run_at as "next_run_at: DateTime<Utc>", last_run_at as "last_run_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Enabled schedules with a run due at some point. Those of archived tasks and tasks in
+    /// the trash wait for the task to be back on the board.
+    pub async fn find_enabled(pool: &DbPool) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", cron_expression, executor, enabled as "enabled!: bool", next_run_at as "next_run_at: DateTime<Utc>", last_run_at as "last_run_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE enabled = TRUE AND next_run_at IS NOT NULL
+                 AND task_id IN (SELECT id FROM tasks WHERE archived_at IS NULL AND deleted_at IS NULL)"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Set the task's schedule, replacing the one it had
+    pub async fn upsert(
+        pool: &DbPool,
+        task_id: Uuid,
+        data: &UpsertTaskSchedule,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let enabled = data.enabled.unwrap_or(true);
+        db::query_as!(
+            TaskSchedule,
+            r#"INSERT INTO task_schedules (id, task_id, cron_expression, executor, enabled, next_run_at)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               ON CONFLICT(task_id) DO UPDATE SET
+                   cron_expression = excluded.cron_expression,
+                   executor = excluded.executor,
+                   enabled = excluded.enabled,
+                   next_run_at = excluded.next_run_at,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", cron_expression, executor, enabled as "enabled!: bool", next_run_at as "next_run_at: DateTime<Utc>", last_run_at as "last_run_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            task_id,
+            data.cron_expression,
+            data.executor,
+            enabled,
+            next_run_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &DbPool, task_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = db::query!("DELETE FROM task_schedules WHERE task_id = $1", task_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Move the schedule on past a run that came due at `fired_at`
+    pub async fn record_fired(
+        pool: &DbPool,
+        id: Uuid,
+        fired_at: DateTime<Utc>,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            r#"UPDATE task_schedules
+               SET last_run_at = $2, next_run_at = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            fired_at,
+            next_run_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+impl TaskScheduleRun {
+    /// The schedule's runs, newest first
+    pub async fn find_by_schedule_id(
+        pool: &DbPool,
+        schedule_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            TaskScheduleRun,
+            r#"SELECT id as "id!: Uuid", schedule_id as "schedule_id!: Uuid", task_id as "task_id: Uuid", task_attempt_id as "task_attempt_id: Uuid", status as "status!: TaskScheduleRunStatus", error, started_at as "started_at!: DateTime<Utc>", finished_at as "finished_at: DateTime<Utc>"
+               FROM task_schedule_runs
+               WHERE schedule_id = $1
+               ORDER BY started_at DESC, id DESC"#,
+            schedule_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The schedule's runs that haven't finished
+    pub async fn find_running_by_schedule_id(
+        pool: &DbPool,
+        schedule_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            TaskScheduleRun,
+            r#"SELECT id as "id!: Uuid", schedule_id as "schedule_id!: Uuid", task_id as "task_id: Uuid", task_attempt_id as "task_attempt_id: Uuid", status as "status!: TaskScheduleRunStatus", error, started_at as "started_at!: DateTime<Utc>", finished_at as "finished_at: DateTime<Utc>"
+               FROM task_schedule_runs
+               WHERE schedule_id = $1 AND status = 'running'"#,
+            schedule_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &DbPool,
+        schedule_id: Uuid,
+        task_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        db::query_as!(
+            TaskScheduleRun,
+            r#"INSERT INTO task_schedule_runs (id, schedule_id, task_id)
+               VALUES ($1, $2, $3)
+               RETURNING id as "id!: Uuid", schedule_id as "schedule_id!: Uuid", task_id as "task_id: Uuid", task_attempt_id as "task_attempt_id: Uuid", status as "status!: TaskScheduleRunStatus", error, started_at as "started_at!: DateTime<Utc>", finished_at as "finished_at: DateTime<Utc>""#,
+            id,
+            schedule_id,
+            task_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn set_attempt(
+        pool: &DbPool,
+        id: Uuid,
+        task_attempt_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE task_schedule_runs SET task_attempt_id = $2 WHERE id = $1",
+            id,
+            task_attempt_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Close a run that is still running as failed
+    pub async fn fail(pool: &DbPool, id: Uuid, error: &str) -> Result<(), sqlx::Error> {
+        db::query!(
+            r#"UPDATE task_schedule_runs
+               SET status = 'failed', error = $2, finished_at = datetime('now', 'subsec')
+               WHERE id = $1 AND status = 'running'"#,
+            id,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Close the run that started the attempt, if one did, with how the attempt ended
+    pub async fn finish_by_attempt(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+        success: bool,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        let status = if success {
+            TaskScheduleRunStatus::Succeeded
+        } else {
+            TaskScheduleRunStatus::Failed
+        };
+        db::query!(
+            r#"UPDATE task_schedule_runs
+               SET status = $2, error = $3, finished_at = datetime('now', 'subsec')
+               WHERE task_attempt_id = $1 AND status = 'running'"#,
+            task_attempt_id,
+            status,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
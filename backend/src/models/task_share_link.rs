@@ -0,0 +1,133 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// A read-only link to a task's conversations and diffs. The token in the link is shown
+/// once, when it's created.
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct TaskShareLink {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    /// The token's first characters, to tell links apart
+    pub token_prefix: String,
+    /// Who created the link, when sign-in is turned on
+    pub created_by: Option<Uuid>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub last_viewed_at: Option<DateTime<Utc>>,
+    pub view_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateTaskShareLink {
+    /// Days until the link stops working; `None` keeps it working until it's revoked
+    pub expires_in_days: Option<u32>,
+}
+
+/// A link just created, with the token to put in its URL. It can't be shown again.
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct CreatedTaskShareLink {
+    pub link: TaskShareLink,
+    pub token: String,
+}
+
+impl TaskShareLink {
+    pub async fn find_by_task_id(pool: &DbPool, task_id: Uuid) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            TaskShareLink,
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", token_prefix, created_by as "created_by: Uuid", expires_at as "expires_at: DateTime<Utc>", revoked_at as "revoked_at: DateTime<Utc>", last_viewed_at as "last_viewed_at: DateTime<Utc>", view_count, created_at as "created_at!: DateTime<Utc>"
+               FROM task_share_links
+               WHERE task_id = $1
+               ORDER BY created_at DESC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            TaskShareLink,
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", token_prefix, created_by as "created_by: Uuid", expires_at as "expires_at: DateTime<Utc>", revoked_at as "revoked_at: DateTime<Utc>", last_viewed_at as "last_viewed_at: DateTime<Utc>", view_count, created_at as "created_at!: DateTime<Utc>"
+               FROM task_share_links
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The link with this hash, unless it has been revoked or has expired
+    pub async fn find_active_by_hash(
+        pool: &DbPool,
+        token_hash: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            TaskShareLink,
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", token_prefix, created_by as "created_by: Uuid", expires_at as "expires_at: DateTime<Utc>", revoked_at as "revoked_at: DateTime<Utc>", last_viewed_at as "last_viewed_at: DateTime<Utc>", view_count, created_at as "created_at!: DateTime<Utc>"
+               FROM task_share_links
+               WHERE token_hash = $1
+               AND revoked_at IS NULL
+               AND (expires_at IS NULL OR expires_at > $2)"#,
+            token_hash,
+            now
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &DbPool,
+        task_id: Uuid,
+        created_by: Option<Uuid>,
+        token_hash: &str,
+        token_prefix: &str,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        db::query_as!(
+            TaskShareLink,
+            r#"INSERT INTO task_share_links (id, task_id, token_hash, token_prefix, created_by, expires_at)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", token_prefix, created_by as "created_by: Uuid", expires_at as "expires_at: DateTime<Utc>", revoked_at as "revoked_at: DateTime<Utc>", last_viewed_at as "last_viewed_at: DateTime<Utc>", view_count, created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            task_id,
+            token_hash,
+            token_prefix,
+            created_by,
+            expires_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Stop the link from working. It stays listed.
+    pub async fn revoke(pool: &DbPool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = db::query!(
+            "UPDATE task_share_links SET revoked_at = datetime('now', 'subsec') WHERE id = $1 AND revoked_at IS NULL",
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn record_view(pool: &DbPool, id: Uuid) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE task_share_links SET last_viewed_at = datetime('now', 'subsec'), view_count = view_count + 1 WHERE id = $1",
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -1,9 +1,21 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::FromRow;
 use ts_rs::TS;
 use uuid::Uuid;
 
+use super::{
+    task::{CreateTask, Task},
+    task_checklist::TaskChecklist,
+    task_defaults::TaskDefaults,
+};
+use crate::{
+    db::{self, DbPool},
+    services::prompt_template,
+};
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct TaskTemplate {
@@ -12,6 +24,9 @@ pub struct TaskTemplate {
     pub title: String,
     pub description: Option<String>,
     pub template_name: String,
+    pub default_executor: Option<String>, // Used by attempts that don't pick an executor
+    pub verification_script: Option<String>, // Used instead of the project's
+    pub checklist: Option<String>,        // One item per line
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -23,6 +38,9 @@ pub struct CreateTaskTemplate {
     pub title: String,
     pub description: Option<String>,
     pub template_name: String,
+    pub default_executor: Option<String>,
+    pub verification_script: Option<String>,
+    pub checklist: Option<String>,
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -31,13 +49,99 @@ pub struct UpdateTaskTemplate {
     pub title: Option<String>,
     pub description: Option<String>,
     pub template_name: Option<String>,
+    pub default_executor: Option<String>,
+    pub verification_script: Option<String>,
+    pub checklist: Option<String>,
+}
+
+/// A new task from a template. `{{name}}` placeholders in its title and description are
+/// filled in from `variables`.
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct InstantiateTaskTemplate {
+    pub project_id: Uuid,
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    pub parent_task_attempt: Option<Uuid>,
 }
 
 impl TaskTemplate {
-    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
-        sqlx::query_as!(
+    /// The template's checklist items, skipping blank lines
+    pub fn checklist_items(&self) -> Vec<&str> {
+        self.checklist
+            .as_deref()
+            .unwrap_or_default()
+            .lines()
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .collect()
+    }
+
+    /// The placeholders of the title and description `variables` has no value for
+    pub fn missing_variables(&self, variables: &HashMap<String, String>) -> Vec<String> {
+        let values = Self::values(variables);
+        let mut missing = prompt_template::missing_variables(&self.title, &values);
+        if let Some(description) = &self.description {
+            for name in prompt_template::missing_variables(description, &values) {
+                if !missing.contains(&name) {
+                    missing.push(name);
+                }
+            }
+        }
+        missing
+    }
+
+    fn values(variables: &HashMap<String, String>) -> Vec<(&str, String)> {
+        variables
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.clone()))
+            .collect()
+    }
+
+    /// Create a task from the template, with its defaults and checklist
+    pub async fn instantiate(
+        &self,
+        pool: &DbPool,
+        data: &InstantiateTaskTemplate,
+        task_id: Uuid,
+    ) -> Result<Task, sqlx::Error> {
+        let values = Self::values(&data.variables);
+        let description = self
+            .description
+            .as_deref()
+            .map(|description| prompt_template::render(description, &values))
+            .filter(|description| !description.trim().is_empty());
+        let task = Task::create(
+            pool,
+            &CreateTask {
+                project_id: data.project_id,
+                title: prompt_template::render(&self.title, &values),
+                description,
+                parent_task_attempt: data.parent_task_attempt,
+                parent_task_id: None,
+            },
+            task_id,
+        )
+        .await?;
+
+        let executor = self
+            .default_executor
+            .as_deref()
+            .map(str::trim)
+            .filter(|executor| !executor.is_empty());
+        let verification_script = self
+            .verification_script
+            .as_deref()
+            .filter(|script| !script.trim().is_empty());
+        TaskDefaults::create(pool, task.id, self.id, executor, verification_script).await?;
+        TaskChecklist::create(pool, task.id, &self.checklist_items()).await?;
+        Ok(task)
+    }
+
+    pub async fn find_all(pool: &DbPool) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
             TaskTemplate,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id?: Uuid", title, description, template_name, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id?: Uuid", title, description, template_name, default_executor, verification_script, checklist, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM task_templates 
                ORDER BY project_id IS NULL DESC, template_name ASC"#
         )
@@ -46,25 +150,26 @@ impl TaskTemplate {
     }
 
     pub async fn find_by_project_id(
-        pool: &SqlitePool,
+        pool: &DbPool,
         project_id: Option<Uuid>,
     ) -> Result<Vec<Self>, sqlx::Error> {
         if let Some(pid) = project_id {
             // Return only project-specific templates
-            sqlx::query_as::<_, TaskTemplate>(
-                r#"SELECT id, project_id, title, description, template_name, created_at, updated_at
+            db::query_as!(
+                TaskTemplate,
+                r#"SELECT id as "id!: Uuid", project_id as "project_id?: Uuid", title, description, template_name, default_executor, verification_script, checklist, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                    FROM task_templates 
-                   WHERE project_id = ?
+                   WHERE project_id = $1
                    ORDER BY template_name ASC"#,
+                pid
             )
-            .bind(pid)
             .fetch_all(pool)
             .await
         } else {
             // Return only global templates
-            sqlx::query_as!(
+            db::query_as!(
                 TaskTemplate,
-                r#"SELECT id as "id!: Uuid", project_id as "project_id?: Uuid", title, description, template_name, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+                r#"SELECT id as "id!: Uuid", project_id as "project_id?: Uuid", title, description, template_name, default_executor, verification_script, checklist, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                    FROM task_templates 
                    WHERE project_id IS NULL
                    ORDER BY template_name ASC"#
@@ -74,10 +179,10 @@ impl TaskTemplate {
         }
     }
 
-    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
-        sqlx::query_as!(
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
             TaskTemplate,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id?: Uuid", title, description, template_name, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id?: Uuid", title, description, template_name, default_executor, verification_script, checklist, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM task_templates 
                WHERE id = $1"#,
             id
@@ -86,25 +191,28 @@ impl TaskTemplate {
         .await
     }
 
-    pub async fn create(pool: &SqlitePool, data: &CreateTaskTemplate) -> Result<Self, sqlx::Error> {
+    pub async fn create(pool: &DbPool, data: &CreateTaskTemplate) -> Result<Self, sqlx::Error> {
         let id = Uuid::new_v4();
-        sqlx::query_as!(
+        db::query_as!(
             TaskTemplate,
-            r#"INSERT INTO task_templates (id, project_id, title, description, template_name) 
-               VALUES ($1, $2, $3, $4, $5) 
-               RETURNING id as "id!: Uuid", project_id as "project_id?: Uuid", title, description, template_name, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"INSERT INTO task_templates (id, project_id, title, description, template_name, default_executor, verification_script, checklist) 
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8) 
+               RETURNING id as "id!: Uuid", project_id as "project_id?: Uuid", title, description, template_name, default_executor, verification_script, checklist, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             data.project_id,
             data.title,
             data.description,
-            data.template_name
+            data.template_name,
+            data.default_executor,
+            data.verification_script,
+            data.checklist
         )
         .fetch_one(pool)
         .await
     }
 
     pub async fn update(
-        pool: &SqlitePool,
+        pool: &DbPool,
         id: Uuid,
         data: &UpdateTaskTemplate,
     ) -> Result<Self, sqlx::Error> {
@@ -120,24 +228,36 @@ impl TaskTemplate {
             .template_name
             .as_ref()
             .unwrap_or(&existing.template_name);
+        let default_executor = data
+            .default_executor
+            .as_ref()
+            .or(existing.default_executor.as_ref());
+        let verification_script = data
+            .verification_script
+            .as_ref()
+            .or(existing.verification_script.as_ref());
+        let checklist = data.checklist.as_ref().or(existing.checklist.as_ref());
 
-        sqlx::query_as!(
+        db::query_as!(
             TaskTemplate,
             r#"UPDATE task_templates 
-               SET title = $2, description = $3, template_name = $4, updated_at = datetime('now', 'subsec')
+               SET title = $2, description = $3, template_name = $4, default_executor = $5, verification_script = $6, checklist = $7, updated_at = datetime('now', 'subsec')
                WHERE id = $1 
-               RETURNING id as "id!: Uuid", project_id as "project_id?: Uuid", title, description, template_name, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+               RETURNING id as "id!: Uuid", project_id as "project_id?: Uuid", title, description, template_name, default_executor, verification_script, checklist, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             title,
             description,
-            template_name
+            template_name,
+            default_executor,
+            verification_script,
+            checklist
         )
         .fetch_one(pool)
         .await
     }
 
-    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
-        let result = sqlx::query!("DELETE FROM task_templates WHERE id = $1", id)
+    pub async fn delete(pool: &DbPool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = db::query!("DELETE FROM task_templates WHERE id = $1", id)
             .execute(pool)
             .await?;
         Ok(result.rows_affected())
@@ -0,0 +1,163 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// An issue tracker tasks can be imported from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Type, Serialize, Deserialize, JsonSchema, TS)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum Tracker {
+    Jira,
+    Linear,
+}
+
+impl Tracker {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Tracker::Jira => "Jira",
+            Tracker::Linear => "Linear",
+        }
+    }
+}
+
+/// A Jira project or Linear team whose issues are imported into a project
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+#[ts(export)]
+pub struct TrackerImport {
+    pub project_id: Uuid,
+    pub tracker: Tracker,
+    /// Jira project key or Linear team key, such as `ENG`
+    pub source_key: String,
+    /// When the last successful sync started; the next one only fetches issues updated since
+    pub last_synced_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An imported issue and the task it became, with what was last copied from it
+#[derive(Debug, Clone, FromRow)]
+pub struct TrackerIssue {
+    pub issue_id: String,
+    pub task_id: Uuid,
+    pub synced_title: String,
+    pub synced_description: Option<String>,
+}
+
+impl TrackerImport {
+    pub async fn find_by_project_id(
+        pool: &DbPool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            TrackerImport,
+            r#"SELECT project_id as "project_id!: Uuid", tracker as "tracker!: Tracker", source_key, last_synced_at as "last_synced_at?: DateTime<Utc>", last_error, created_at as "created_at!: DateTime<Utc>"
+               FROM tracker_imports
+               WHERE project_id = $1
+               ORDER BY created_at"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Start importing from a source; importing it again keeps its sync history
+    pub async fn find_or_create(
+        pool: &DbPool,
+        project_id: Uuid,
+        tracker: Tracker,
+        source_key: &str,
+    ) -> Result<Self, sqlx::Error> {
+        db::query_as!(
+            TrackerImport,
+            r#"INSERT INTO tracker_imports (project_id, tracker, source_key)
+               VALUES ($1, $2, $3)
+               ON CONFLICT(project_id, tracker, source_key) DO UPDATE SET source_key = excluded.source_key
+               RETURNING project_id as "project_id!: Uuid", tracker as "tracker!: Tracker", source_key, last_synced_at as "last_synced_at?: DateTime<Utc>", last_error, created_at as "created_at!: DateTime<Utc>""#,
+            project_id,
+            tracker,
+            source_key
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Record how a sync went; `started_at` only moves forward when it succeeded
+    pub async fn record_sync(
+        pool: &DbPool,
+        project_id: Uuid,
+        tracker: Tracker,
+        source_key: &str,
+        started_at: Option<DateTime<Utc>>,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE tracker_imports SET last_synced_at = COALESCE($4, last_synced_at), last_error = $5 WHERE project_id = $1 AND tracker = $2 AND source_key = $3",
+            project_id,
+            tracker,
+            source_key,
+            started_at,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+impl TrackerIssue {
+    pub async fn find_by_tracker(
+        pool: &DbPool,
+        project_id: Uuid,
+        tracker: Tracker,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            TrackerIssue,
+            r#"SELECT issue_id, task_id as "task_id!: Uuid", synced_title, synced_description
+               FROM tracker_issues
+               WHERE project_id = $1 AND tracker = $2"#,
+            project_id,
+            tracker
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn upsert(
+        pool: &DbPool,
+        project_id: Uuid,
+        tracker: Tracker,
+        issue_id: &str,
+        issue_key: &str,
+        task_id: Uuid,
+        title: &str,
+        description: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            r#"INSERT INTO tracker_issues (project_id, tracker, issue_id, issue_key, task_id, synced_title, synced_description)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               ON CONFLICT(project_id, tracker, issue_id) DO UPDATE SET
+                   issue_key = excluded.issue_key,
+                   task_id = excluded.task_id,
+                   synced_title = excluded.synced_title,
+                   synced_description = excluded.synced_description,
+                   synced_at = datetime('now', 'subsec')"#,
+            project_id,
+            tracker,
+            issue_id,
+            issue_key,
+            task_id,
+            title,
+            description
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
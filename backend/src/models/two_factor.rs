@@ -0,0 +1,165 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// An account's TOTP secret. Enrollment is pending until a code from the authenticator app
+/// confirms it; only then is the code asked for at sign-in.
+#[derive(Debug, Clone, FromRow)]
+pub struct TwoFactor {
+    /// Base32, as authenticator apps take it
+    pub secret: String,
+    pub enabled_at: Option<DateTime<Utc>>,
+}
+
+/// A new TOTP secret for the user to add to their authenticator app, by hand or by scanning
+/// `otpauth_url` as a QR code
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct TwoFactorEnrollment {
+    pub secret: String,
+    pub otpauth_url: String,
+}
+
+/// One-time codes that sign in in place of the authenticator app. Shown once.
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct RecoveryCodes {
+    pub codes: Vec<String>,
+}
+
+/// A code from the authenticator app, or a recovery code
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct TwoFactorCode {
+    pub code: String,
+}
+
+impl TwoFactor {
+    pub async fn find_by_user_id(
+        pool: &DbPool,
+        user_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            TwoFactor,
+            r#"SELECT secret, enabled_at as "enabled_at: DateTime<Utc>"
+               FROM user_two_factor
+               WHERE user_id = $1"#,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Whether the account asks for a code at sign-in
+    pub async fn is_enabled(pool: &DbPool, user_id: Uuid) -> Result<bool, sqlx::Error> {
+        Ok(Self::find_by_user_id(pool, user_id)
+            .await?
+            .is_some_and(|two_factor| two_factor.enabled_at.is_some()))
+    }
+
+    /// Start enrolling with a new secret, replacing any enrollment still pending
+    pub async fn start_enrollment(
+        pool: &DbPool,
+        user_id: Uuid,
+        secret: &str,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            r#"INSERT INTO user_two_factor (user_id, secret)
+               VALUES ($1, $2)
+               ON CONFLICT(user_id) DO UPDATE SET
+                   secret = excluded.secret,
+                   enabled_at = NULL,
+                   last_used_step = NULL,
+                   created_at = datetime('now', 'subsec')"#,
+            user_id,
+            secret
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn enable(pool: &DbPool, user_id: Uuid, step: i64) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE user_two_factor SET enabled_at = datetime('now', 'subsec'), last_used_step = $2 WHERE user_id = $1",
+            user_id,
+            step
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Accept a code of the time step, unless that or a later step's code was accepted
+    /// already. Returns whether it was accepted.
+    pub async fn use_step(pool: &DbPool, user_id: Uuid, step: i64) -> Result<bool, sqlx::Error> {
+        let result = db::query!(
+            r#"UPDATE user_two_factor SET last_used_step = $2
+               WHERE user_id = $1 AND (last_used_step IS NULL OR last_used_step < $2)"#,
+            user_id,
+            step
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Turn two-factor authentication off for the account, recovery codes included
+    pub async fn delete(pool: &DbPool, user_id: Uuid) -> Result<u64, sqlx::Error> {
+        db::query!(
+            "DELETE FROM user_recovery_codes WHERE user_id = $1",
+            user_id
+        )
+        .execute(pool)
+        .await?;
+        let result = db::query!("DELETE FROM user_two_factor WHERE user_id = $1", user_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn replace_recovery_codes(
+        pool: &DbPool,
+        user_id: Uuid,
+        code_hashes: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        db::query!(
+            "DELETE FROM user_recovery_codes WHERE user_id = $1",
+            user_id
+        )
+        .execute(&mut tx)
+        .await?;
+        for code_hash in code_hashes {
+            db::query!(
+                "INSERT INTO user_recovery_codes (user_id, code_hash) VALUES ($1, $2)",
+                user_id,
+                code_hash
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Use up the recovery code. Returns whether it was one of the account's unused codes.
+    pub async fn use_recovery_code(
+        pool: &DbPool,
+        user_id: Uuid,
+        code_hash: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = db::query!(
+            r#"UPDATE user_recovery_codes SET used_at = datetime('now', 'subsec')
+               WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL"#,
+            user_id,
+            code_hash
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,280 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// An account on a server with sign-in turned on. The password hash never leaves the model.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct User {
+    pub id: Uuid,
+    pub username: String,
+    pub display_name: Option<String>,
+    /// Admin of every project, and manages accounts and server settings
+    pub is_admin: bool,
+    /// Signed in through the OIDC provider rather than with a password
+    pub is_oidc: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateUser {
+    pub username: String,
+    pub display_name: Option<String>,
+    pub password: String,
+    pub is_admin: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpdateUser {
+    pub display_name: Option<String>,
+    pub is_admin: Option<bool>,
+    /// A new password; signs the account out everywhere
+    pub password: Option<String>,
+}
+
+impl User {
+    pub async fn count(pool: &DbPool) -> Result<i64, sqlx::Error> {
+        db::query_scalar!(r#"SELECT COUNT(*) as "count!: i64" FROM users"#)
+            .fetch_one(pool)
+            .await
+    }
+
+    pub async fn count_admins(pool: &DbPool) -> Result<i64, sqlx::Error> {
+        db::query_scalar!(r#"SELECT COUNT(*) as "count!: i64" FROM users WHERE is_admin = TRUE"#)
+            .fetch_one(pool)
+            .await
+    }
+
+    pub async fn find_all(pool: &DbPool) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            User,
+            r#"SELECT id as "id!: Uuid", username, display_name, is_admin as "is_admin!: bool", oidc_subject IS NOT NULL as "is_oidc!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM users
+               ORDER BY username ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            User,
+            r#"SELECT id as "id!: Uuid", username, display_name, is_admin as "is_admin!: bool", oidc_subject IS NOT NULL as "is_oidc!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM users
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_username(
+        pool: &DbPool,
+        username: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            User,
+            r#"SELECT id as "id!: Uuid", username, display_name, is_admin as "is_admin!: bool", oidc_subject IS NOT NULL as "is_oidc!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM users
+               WHERE username = $1"#,
+            username
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_oidc_subject(
+        pool: &DbPool,
+        subject: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            User,
+            r#"SELECT id as "id!: Uuid", username, display_name, is_admin as "is_admin!: bool", oidc_subject IS NOT NULL as "is_oidc!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM users
+               WHERE oidc_subject = $1"#,
+            subject
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The account's password hash, if it has a password
+    pub async fn find_password_hash(
+        pool: &DbPool,
+        id: Uuid,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let hash = db::query_scalar!("SELECT password_hash FROM users WHERE id = $1", id)
+            .fetch_optional(pool)
+            .await?;
+        Ok(hash.flatten())
+    }
+
+    /// Create an account that signs in with a password, or through OIDC when `oidc_subject`
+    /// is set
+    pub async fn create(
+        pool: &DbPool,
+        username: &str,
+        display_name: Option<&str>,
+        password_hash: Option<&str>,
+        oidc_subject: Option<&str>,
+        is_admin: bool,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        db::query_as!(
+            User,
+            r#"INSERT INTO users (id, username, display_name, password_hash, oidc_subject, is_admin)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid", username, display_name, is_admin as "is_admin!: bool", oidc_subject IS NOT NULL as "is_oidc!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            username,
+            display_name,
+            password_hash,
+            oidc_subject,
+            is_admin
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Change what was given; fields left out keep their current value
+    pub async fn update(
+        pool: &DbPool,
+        id: Uuid,
+        display_name: Option<&str>,
+        is_admin: Option<bool>,
+        password_hash: Option<&str>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            User,
+            r#"UPDATE users
+               SET display_name = COALESCE($2, display_name),
+                   is_admin = COALESCE($3, is_admin),
+                   password_hash = COALESCE($4, password_hash),
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", username, display_name, is_admin as "is_admin!: bool", oidc_subject IS NOT NULL as "is_oidc!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            display_name,
+            is_admin,
+            password_hash
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &DbPool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = db::query!("DELETE FROM users WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+/// A signed-in session, found by the hash of its token
+pub struct UserSession;
+
+impl UserSession {
+    pub async fn create(
+        pool: &DbPool,
+        token_hash: &str,
+        user_id: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        db::query!(
+            "INSERT INTO user_sessions (token_hash, user_id, expires_at) VALUES ($1, $2, $3)",
+            token_hash,
+            user_id,
+            expires_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The account signed in with the session, unless it has expired
+    pub async fn find_user(
+        pool: &DbPool,
+        token_hash: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<User>, sqlx::Error> {
+        db::query_as!(
+            User,
+            r#"SELECT u.id as "id!: Uuid", u.username, u.display_name, u.is_admin as "is_admin!: bool", u.oidc_subject IS NOT NULL as "is_oidc!: bool", u.created_at as "created_at!: DateTime<Utc>", u.updated_at as "updated_at!: DateTime<Utc>"
+               FROM user_sessions s
+               JOIN users u ON u.id = s.user_id
+               WHERE s.token_hash = $1 AND s.expires_at > $2"#,
+            token_hash,
+            now
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// When the session was signed in, unless it has expired
+    pub async fn find_signed_in_at(
+        pool: &DbPool,
+        token_hash: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+        db::query_scalar!(
+            r#"SELECT created_at as "created_at!: DateTime<Utc>"
+               FROM user_sessions
+               WHERE token_hash = $1 AND expires_at > $2"#,
+            token_hash,
+            now
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Give the session a new token and expiry, keeping when it was signed in. Returns
+    /// whether the session still existed.
+    pub async fn rotate(
+        pool: &DbPool,
+        token_hash: &str,
+        new_token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = db::query!(
+            "UPDATE user_sessions SET token_hash = $2, expires_at = $3 WHERE token_hash = $1",
+            token_hash,
+            new_token_hash,
+            expires_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn delete(pool: &DbPool, token_hash: &str) -> Result<(), sqlx::Error> {
+        db::query!(
+            "DELETE FROM user_sessions WHERE token_hash = $1",
+            token_hash
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Sign the account out everywhere, returning how many sessions ended
+    pub async fn delete_by_user_id(pool: &DbPool, user_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = db::query!("DELETE FROM user_sessions WHERE user_id = $1", user_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete_expired(pool: &DbPool, now: DateTime<Utc>) -> Result<(), sqlx::Error> {
+        db::query!("DELETE FROM user_sessions WHERE expires_at <= $1", now)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::db::{self, DbPool};
+
+/// Follow-ups sent to an attempt's agent to fix what its verification commands reported
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct VerificationFixLoop {
+    pub task_attempt_id: Uuid,
+    pub fix_count: i64,       // Follow-ups started so far
+    pub last_failure: String, // The prompt made from the latest failed verification
+    pub pending: bool,        // A follow-up is due for `last_failure` but hasn't started
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl VerificationFixLoop {
+    pub async fn find_by_task_attempt_id(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        db::query_as!(
+            VerificationFixLoop,
+            r#"SELECT task_attempt_id as "task_attempt_id!: Uuid", fix_count, last_failure, pending as "pending!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM verification_fix_loops
+               WHERE task_attempt_id = $1"#,
+            task_attempt_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Loops across all attempts with a follow-up that is due
+    pub async fn find_pending(pool: &DbPool) -> Result<Vec<Self>, sqlx::Error> {
+        db::query_as!(
+            VerificationFixLoop,
+            r#"SELECT task_attempt_id as "task_attempt_id!: Uuid", fix_count, last_failure, pending as "pending!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM verification_fix_loops
+               WHERE pending = TRUE
+               ORDER BY updated_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Record a failure a follow-up is due for, keeping the count of earlier ones
+    pub async fn record_failure(
+        pool: &DbPool,
+        task_attempt_id: Uuid,
+        failure: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let updated = db::query!(
+            "UPDATE verification_fix_loops SET last_failure = $2, pending = TRUE, updated_at = datetime('now', 'subsec')
+             WHERE task_attempt_id = $1",
+            task_attempt_id,
+            failure
+        )
+        .execute(pool)
+        .await?;
+        if updated.rows_affected() == 0 {
+            db::query!(
+                "INSERT INTO verification_fix_loops (task_attempt_id, last_failure, pending) VALUES ($1, $2, TRUE)",
+                task_attempt_id,
+                failure
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        Self::find_by_task_attempt_id(pool, task_attempt_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    /// The due follow-up started
+    pub async fn mark_started(pool: &DbPool, task_attempt_id: Uuid) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE verification_fix_loops SET fix_count = fix_count + 1, pending = FALSE, updated_at = datetime('now', 'subsec')
+             WHERE task_attempt_id = $1",
+            task_attempt_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Give up on the due follow-up, e.g. when it couldn't start
+    pub async fn cancel(pool: &DbPool, task_attempt_id: Uuid) -> Result<(), sqlx::Error> {
+        db::query!(
+            "UPDATE verification_fix_loops SET pending = FALSE, updated_at = datetime('now', 'subsec')
+             WHERE task_attempt_id = $1",
+            task_attempt_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Forget the attempt's fixes once its verification passes
+    pub async fn delete(pool: &DbPool, task_attempt_id: Uuid) -> Result<(), sqlx::Error> {
+        db::query!(
+            "DELETE FROM verification_fix_loops WHERE task_attempt_id = $1",
+            task_attempt_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+    routing::{delete, get, post},
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    models::{
+        api_token::{ApiToken, ApiTokenUse, CreateApiToken, CreatedApiToken},
+        user::User,
+        ApiResponse,
+    },
+    services::accounts,
+};
+
+const DEFAULT_USES_LIMIT: i64 = 100;
+const MAX_USES_LIMIT: i64 = 1000;
+const SIGN_IN_NEEDED: &str = "API tokens act as an account, so they need sign-in turned on";
+
+#[derive(Debug, Deserialize)]
+pub struct TokensQuery {
+    /// Every user's tokens, for admins
+    pub all: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TokenUsesQuery {
+    pub limit: Option<i64>,
+}
+
+/// The token, if the user may see and revoke it: their own, or any for admins
+async fn find_token(
+    app_state: &AppState,
+    user: &User,
+    token_id: Uuid,
+) -> Result<ApiToken, StatusCode> {
+    match ApiToken::find_by_id(&app_state.db_pool, token_id).await {
+        Ok(Some(token)) if token.user_id == user.id || user.is_admin => Ok(token),
+        Ok(_) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Failed to load API token {}: {}", token_id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn get_tokens(
+    State(app_state): State<AppState>,
+    user: Option<Extension<User>>,
+    Query(query): Query<TokensQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApiToken>>>, StatusCode> {
+    let Some(Extension(user)) = user else {
+        return Ok(ResponseJson(ApiResponse::error(SIGN_IN_NEEDED)));
+    };
+    let tokens = if query.all.unwrap_or(false) && user.is_admin {
+        ApiToken::find_all(&app_state.db_pool).await
+    } else {
+        ApiToken::find_by_user_id(&app_state.db_pool, user.id).await
+    };
+    match tokens {
+        Ok(tokens) => Ok(ResponseJson(ApiResponse::success(tokens))),
+        Err(e) => {
+            tracing::error!("Failed to load API tokens: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn create_token(
+    State(app_state): State<AppState>,
+    user: Option<Extension<User>>,
+    Json(payload): Json<CreateApiToken>,
+) -> Result<ResponseJson<ApiResponse<CreatedApiToken>>, StatusCode> {
+    let Some(Extension(user)) = user else {
+        return Ok(ResponseJson(ApiResponse::error(SIGN_IN_NEEDED)));
+    };
+    if payload.name.trim().is_empty() {
+        return Ok(ResponseJson(ApiResponse::error("Tokens need a name")));
+    }
+    if payload.scopes.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Tokens need at least one scope",
+        )));
+    }
+
+    match accounts::create_api_token(&app_state.db_pool, &user, &payload).await {
+        Ok(created) => {
+            tracing::info!(
+                "{} created API token {} ({})",
+                user.username,
+                created.token.id,
+                created.token.name
+            );
+            Ok(ResponseJson(ApiResponse::success(created)))
+        }
+        Err(e) => {
+            tracing::error!("Failed to create API token: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// DELETE /tokens/:token_id: revoke the token. It stays listed, with its uses.
+pub async fn revoke_token(
+    Path(token_id): Path<Uuid>,
+    State(app_state): State<AppState>,
+    user: Option<Extension<User>>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    let Some(Extension(user)) = user else {
+        return Ok(ResponseJson(ApiResponse::error(SIGN_IN_NEEDED)));
+    };
+    let token = find_token(&app_state, &user, token_id).await?;
+    match ApiToken::revoke(&app_state.db_pool, token.id).await {
+        Ok(_) => {
+            tracing::info!("{} revoked API token {}", user.username, token.id);
+            Ok(ResponseJson(ApiResponse::success(())))
+        }
+        Err(e) => {
+            tracing::error!("Failed to revoke API token {}: {}", token.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// POST /tokens/:token_id/rotate: replace the token with a new secret and revoke the old
+/// one, for a token that may have leaked. The new secret is shown once.
+pub async fn rotate_token(
+    Path(token_id): Path<Uuid>,
+    State(app_state): State<AppState>,
+    user: Option<Extension<User>>,
+) -> Result<ResponseJson<ApiResponse<CreatedApiToken>>, StatusCode> {
+    let Some(Extension(user)) = user else {
+        return Ok(ResponseJson(ApiResponse::error(SIGN_IN_NEEDED)));
+    };
+    let token = find_token(&app_state, &user, token_id).await?;
+    if token.revoked_at.is_some() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Revoked tokens can't be rotated",
+        )));
+    }
+    match accounts::rotate_api_token(&app_state.db_pool, &token).await {
+        Ok(rotated) => {
+            tracing::info!(
+                "{} rotated API token {} into {}",
+                user.username,
+                token.id,
+                rotated.token.id
+            );
+            Ok(ResponseJson(ApiResponse::success(rotated)))
+        }
+        Err(e) => {
+            tracing::error!("Failed to rotate API token {}: {}", token.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// The requests made with the token, newest first
+pub async fn get_token_uses(
+    Path(token_id): Path<Uuid>,
+    State(app_state): State<AppState>,
+    user: Option<Extension<User>>,
+    Query(query): Query<TokenUsesQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApiTokenUse>>>, StatusCode> {
+    let Some(Extension(user)) = user else {
+        return Ok(ResponseJson(ApiResponse::error(SIGN_IN_NEEDED)));
+    };
+    let token = find_token(&app_state, &user, token_id).await?;
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_USES_LIMIT)
+        .clamp(1, MAX_USES_LIMIT);
+    match ApiToken::find_uses(&app_state.db_pool, token.id, limit).await {
+        Ok(uses) => Ok(ResponseJson(ApiResponse::success(uses))),
+        Err(e) => {
+            tracing::error!("Failed to load uses of API token {}: {}", token.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub fn api_tokens_router() -> Router<AppState> {
+    Router::new()
+        .route("/tokens", get(get_tokens).post(create_token))
+        .route("/tokens/:token_id", delete(revoke_token))
+        .route("/tokens/:token_id/rotate", post(rotate_token))
+        .route("/tokens/:token_id/uses", get(get_token_uses))
+}
@@ -0,0 +1,178 @@
+use axum::{extract::State, http::StatusCode, response::Json as ResponseJson, Extension, Json};
+use uuid::Uuid;
+
+use super::task_attempts::{launch_attempt, prepare_attempt};
+use crate::{
+    app_state::AppState,
+    models::{
+        attempt_group::{
+            parallel_executors_error, AttemptGroup, CreateParallelAttempts, PickAttemptGroupWinner,
+        },
+        execution_process::{ExecutionProcess, ExecutionProcessStatus},
+        project::Project,
+        task::Task,
+        task_attempt::CreateTaskAttempt,
+        task_dependency::{blocked_message, TaskDependency},
+        ApiResponse,
+    },
+};
+
+/// Stop whatever an attempt that lost out still has running
+async fn stop_attempt(app_state: &AppState, attempt_id: Uuid) {
+    let processes =
+        match ExecutionProcess::find_by_task_attempt_id(&app_state.db_pool, attempt_id).await {
+            Ok(processes) => processes,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to fetch execution processes for attempt {}: {}",
+                    attempt_id,
+                    e
+                );
+                return;
+            }
+        };
+    for process in processes {
+        match app_state.stop_running_execution_by_id(process.id).await {
+            Ok(true) => {
+                if let Err(e) = ExecutionProcess::update_completion(
+                    &app_state.db_pool,
+                    process.id,
+                    ExecutionProcessStatus::Killed,
+                    None,
+                )
+                .await
+                {
+                    tracing::error!("Failed to update execution process status: {}", e);
+                }
+            }
+            Ok(false) => {}
+            Err(e) => {
+                tracing::error!("Failed to stop execution process {}: {}", process.id, e);
+            }
+        }
+    }
+}
+
+pub async fn get_attempt_groups(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<AttemptGroup>>>, StatusCode> {
+    match AttemptGroup::find_by_task_id(&app_state.db_pool, task.id).await {
+        Ok(groups) => Ok(ResponseJson(ApiResponse::success(groups))),
+        Err(e) => {
+            tracing::error!("Failed to fetch attempt groups of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Start one attempt per executor, side by side, each in its own worktree
+pub async fn create_parallel_attempts(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<CreateParallelAttempts>,
+) -> Result<ResponseJson<ApiResponse<AttemptGroup>>, StatusCode> {
+    if let Some(message) = parallel_executors_error(&payload.executors) {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+    match TaskDependency::unfinished_blockers(&app_state.db_pool, task.id).await {
+        Ok(blockers) if !blockers.is_empty() => {
+            return Ok(ResponseJson(ApiResponse::error(&blocked_message(
+                &blockers,
+            ))));
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::error!("Failed to check blockers of task {}: {}", task.id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    // Check every agent can run before starting any of them
+    let mut attempts = Vec::with_capacity(payload.executors.len());
+    for executor in &payload.executors {
+        let mut attempt = CreateTaskAttempt {
+            executor: Some(executor.clone()),
+            base_branch: payload.base_branch.clone(),
+            plan_only: None,
+            model: None,
+            executor_profile_id: None,
+        };
+        if let Some(message) = prepare_attempt(&app_state, &project, &task, &mut attempt).await? {
+            return Ok(ResponseJson(ApiResponse::error(&format!(
+                "{}: {}",
+                executor, message
+            ))));
+        }
+        attempts.push(attempt);
+    }
+
+    let mut attempt_ids = Vec::with_capacity(attempts.len());
+    for attempt in &attempts {
+        attempt_ids.push(
+            launch_attempt(&app_state, &project, &task, attempt)
+                .await?
+                .id,
+        );
+    }
+
+    match AttemptGroup::create(&app_state.db_pool, task.id, &attempt_ids).await {
+        Ok(group) => Ok(ResponseJson(ApiResponse::success(group))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to group parallel attempts of task {}: {}",
+                task.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Keep one attempt of a group as the task's canonical attempt, stopping the others
+pub async fn pick_attempt_group_winner(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<PickAttemptGroupWinner>,
+) -> Result<ResponseJson<ApiResponse<AttemptGroup>>, StatusCode> {
+    let group = match AttemptGroup::find_by_attempt_id(&app_state.db_pool, payload.attempt_id).await
+    {
+        Ok(Some(group)) if group.task_id == task.id => group,
+        Ok(_) => {
+            return Ok(ResponseJson(ApiResponse::error(
+                "That attempt wasn't started alongside others on this task",
+            )))
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch the group of attempt {}: {}",
+                payload.attempt_id,
+                e
+            );
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let group = AttemptGroup::set_winner(&app_state.db_pool, group.id, payload.attempt_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to pick the winner of group {}: {}", group.id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    for loser in group.losers() {
+        stop_attempt(&app_state, loser).await;
+    }
+
+    app_state
+        .track_analytics_event(
+            "attempt_group_winner_picked",
+            Some(serde_json::json!({
+                "task_id": task.id.to_string(),
+                "attempts": group.attempt_ids.len(),
+            })),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(group)))
+}
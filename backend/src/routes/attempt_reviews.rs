@@ -0,0 +1,264 @@
+use axum::{extract::State, http::StatusCode, response::Json as ResponseJson, Extension, Json};
+
+use super::task_attempts::merge_attempt;
+use crate::{
+    app_state::AppState,
+    models::{
+        attempt_review::{
+            rejection_prompt, ApproveAttempt, AttemptRejection, AttemptReview, RejectAttempt,
+            ReviewDecision,
+        },
+        event::{Event, EventKind},
+        project::Project,
+        review_comment::ReviewComment,
+        task::{Task, TaskStatus},
+        task_attempt::TaskAttempt,
+        user::User,
+        ApiResponse,
+    },
+    services::{git_host, GitService},
+};
+
+fn internal_error(
+    action: &str,
+    task_attempt: &TaskAttempt,
+    e: impl std::fmt::Display,
+) -> StatusCode {
+    tracing::error!(
+        "Failed to {} for attempt {}: {}",
+        action,
+        task_attempt.id,
+        e
+    );
+    StatusCode::INTERNAL_SERVER_ERROR
+}
+
+/// Why the attempt can't be reviewed yet, if it can't
+async fn not_reviewable(app_state: &AppState, task_attempt: &TaskAttempt) -> Option<&'static str> {
+    if task_attempt.merge_commit.is_some() {
+        Some("This attempt has already been merged")
+    } else if app_state.has_running_execution(task_attempt.id).await {
+        Some("The attempt is still running; review it once it finishes")
+    } else {
+        None
+    }
+}
+
+/// Push the base branch an approved attempt was merged into
+async fn push_base_branch(
+    app_state: &AppState,
+    project: &Project,
+    branch: &str,
+) -> Result<(), String> {
+    let github = app_state.get_config().read().await.github.clone();
+    let host = git_host::for_project(
+        &app_state.db_pool,
+        project.id,
+        &project.git_repo_path,
+        &github,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    let (username, token) = host.push_credentials();
+    let (username, token) = (username.to_string(), token.to_string());
+
+    let repo_path = project.git_repo_path.clone();
+    let branch = branch.to_string();
+    tokio::task::spawn_blocking(move || {
+        GitService::new(&repo_path)?.push_with_token(
+            std::path::Path::new(&repo_path),
+            &branch,
+            &username,
+            &token,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+pub async fn get_attempt_reviews(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<AttemptReview>>>, StatusCode> {
+    match AttemptReview::find_by_task_attempt_id(&app_state.db_pool, task_attempt.id).await {
+        Ok(reviews) => Ok(ResponseJson(ApiResponse::success(reviews))),
+        Err(e) => Err(internal_error("load reviews", &task_attempt, e)),
+    }
+}
+
+/// Approve the attempt's changes and merge them, pushing the base branch when configured
+pub async fn approve_attempt(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+    user: Option<Extension<User>>,
+    Json(payload): Json<ApproveAttempt>,
+) -> Result<ResponseJson<ApiResponse<AttemptReview>>, StatusCode> {
+    if app_state.dry_run {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Merging is disabled in dry-run mode",
+        )));
+    }
+    if let Some(reason) = not_reviewable(&app_state, &task_attempt).await {
+        return Ok(ResponseJson(ApiResponse::error(reason)));
+    }
+
+    let merge_commit = match merge_attempt(&app_state, &project, &task, &task_attempt).await? {
+        Ok(merge_commit) => merge_commit,
+        Err(message) => return Ok(ResponseJson(ApiResponse::error(&message))),
+    };
+
+    let pool = &app_state.db_pool;
+    let reviewer = user.map(|Extension(user)| user.username);
+    let review = AttemptReview::create(
+        pool,
+        task_attempt.id,
+        ReviewDecision::Approved,
+        reviewer.as_deref(),
+        payload.comment.as_deref(),
+    )
+    .await
+    .map_err(|e| internal_error("record the approval", &task_attempt, e))?;
+
+    let pushed = if app_state
+        .get_config()
+        .read()
+        .await
+        .review_gate
+        .push_on_approve
+    {
+        let pushed = push_base_branch(&app_state, &project, &task_attempt.base_branch).await;
+        if let Err(e) = &pushed {
+            tracing::error!(
+                "Failed to push {} after approving attempt {}: {}",
+                task_attempt.base_branch,
+                task_attempt.id,
+                e
+            );
+        }
+        Some(pushed.is_ok())
+    } else {
+        None
+    };
+
+    Event::record_for_attempt(
+        pool,
+        task_attempt.id,
+        None,
+        EventKind::ReviewApproved,
+        serde_json::json!({
+            "reviewer": review.reviewer,
+            "comment": review.comment,
+            "merge_commit": merge_commit,
+            "pushed": pushed,
+        }),
+    )
+    .await
+    .map_err(|e| internal_error("record the approval event", &task_attempt, e))?;
+
+    Ok(ResponseJson(ApiResponse::success(review)))
+}
+
+/// Reject the attempt's changes. With `follow_up`, the reviewer's comment and the open review
+/// comments go to the agent as a follow-up; otherwise the task goes back to the to-do column.
+pub async fn reject_attempt(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+    user: Option<Extension<User>>,
+    Json(payload): Json<RejectAttempt>,
+) -> Result<ResponseJson<ApiResponse<AttemptRejection>>, StatusCode> {
+    if let Some(reason) = not_reviewable(&app_state, &task_attempt).await {
+        return Ok(ResponseJson(ApiResponse::error(reason)));
+    }
+    let pool = &app_state.db_pool;
+
+    let prompt =
+        if payload.follow_up {
+            let comments = ReviewComment::find_by_task_attempt_id(pool, task_attempt.id)
+                .await
+                .map_err(|e| internal_error("load review comments", &task_attempt, e))?;
+            let worktree_path =
+                TaskAttempt::ensure_worktree_exists(pool, task_attempt.id, project.id, "review")
+                    .await
+                    .map_err(|e| internal_error("prepare the worktree", &task_attempt, e))?;
+            let run_diffs = ReviewComment::run_diffs(pool, &comments)
+                .await
+                .map_err(|e| internal_error("load run diffs", &task_attempt, e))?;
+            match rejection_prompt(
+                payload.comment.as_deref(),
+                &comments,
+                std::path::Path::new(&worktree_path),
+                &run_diffs,
+            ) {
+                Some(prompt) => Some(prompt),
+                None => return Ok(ResponseJson(ApiResponse::error(
+                    "Say what to change, in a comment or review comments, to send it to the agent",
+                ))),
+            }
+        } else {
+            None
+        };
+
+    let reviewer = user.map(|Extension(user)| user.username);
+    let review = AttemptReview::create(
+        pool,
+        task_attempt.id,
+        ReviewDecision::Rejected,
+        reviewer.as_deref(),
+        payload.comment.as_deref(),
+    )
+    .await
+    .map_err(|e| internal_error("record the rejection", &task_attempt, e))?;
+
+    let follow_up_attempt_id = match prompt {
+        Some(prompt) => {
+            let attempt_id = TaskAttempt::start_followup_execution(
+                pool,
+                &app_state,
+                task_attempt.id,
+                task.id,
+                project.id,
+                &prompt,
+            )
+            .await
+            .map_err(|e| internal_error("start the review follow-up", &task_attempt, e))?;
+            if let Err(e) = ReviewComment::resolve_all(pool, task_attempt.id).await {
+                tracing::error!(
+                    "Failed to resolve review comments for attempt {}: {}",
+                    task_attempt.id,
+                    e
+                );
+            }
+            Some(attempt_id)
+        }
+        None => {
+            Task::update_status(pool, task.id, project.id, TaskStatus::Todo)
+                .await
+                .map_err(|e| internal_error("move the task back to do", &task_attempt, e))?;
+            None
+        }
+    };
+
+    Event::record_for_attempt(
+        pool,
+        task_attempt.id,
+        None,
+        EventKind::ReviewRejected,
+        serde_json::json!({
+            "reviewer": review.reviewer,
+            "comment": review.comment,
+            "follow_up_attempt_id": follow_up_attempt_id,
+        }),
+    )
+    .await
+    .map_err(|e| internal_error("record the rejection event", &task_attempt, e))?;
+
+    Ok(ResponseJson(ApiResponse::success(AttemptRejection {
+        review,
+        follow_up_attempt_id,
+    })))
+}
@@ -0,0 +1,94 @@
+use axum::{extract::State, http::StatusCode, response::Json as ResponseJson, Extension, Json};
+
+use crate::{
+    app_state::AppState,
+    models::{
+        attempt_rollback::{AttemptRollback, RollbackAttempt},
+        event::{Event, EventKind},
+        project::Project,
+        task::Task,
+        task_attempt::{TaskAttempt, TaskAttemptError},
+        ApiResponse,
+    },
+    services::GitServiceError,
+};
+
+pub async fn get_attempt_rollbacks(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<AttemptRollback>>>, StatusCode> {
+    match AttemptRollback::find_by_task_attempt_id(&app_state.db_pool, task_attempt.id).await {
+        Ok(rollbacks) => Ok(ResponseJson(ApiResponse::success(rollbacks))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to load rollbacks for attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Put the attempt's branch back to what one of its earlier runs left
+pub async fn rollback_attempt(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<RollbackAttempt>,
+) -> Result<ResponseJson<ApiResponse<AttemptRollback>>, StatusCode> {
+    if app_state.has_running_execution(task_attempt.id).await {
+        return Ok(ResponseJson(ApiResponse::error(
+            "The attempt is still running; roll it back once it finishes",
+        )));
+    }
+
+    let pool = &app_state.db_pool;
+    let rollback = match TaskAttempt::rollback_to(
+        pool,
+        task_attempt.id,
+        task.id,
+        project.id,
+        payload.execution_process_id,
+    )
+    .await
+    {
+        Ok(rollback) => rollback,
+        Err(TaskAttemptError::ValidationError(message)) => {
+            return Ok(ResponseJson(ApiResponse::error(&message)));
+        }
+        Err(TaskAttemptError::GitService(GitServiceError::WorktreeDirty(files))) => {
+            return Ok(ResponseJson(ApiResponse::error(&format!(
+                "Commit or discard the worktree's uncommitted changes first: {}",
+                files
+            ))));
+        }
+        Err(e) => {
+            tracing::error!("Failed to roll back attempt {}: {}", task_attempt.id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    if let Err(e) = Event::record_for_attempt(
+        pool,
+        task_attempt.id,
+        rollback.execution_process_id,
+        EventKind::AttemptRolledBack,
+        serde_json::json!({
+            "from_commit": rollback.from_commit,
+            "to_commit": rollback.to_commit,
+            "backup_ref": rollback.backup_ref,
+        }),
+    )
+    .await
+    {
+        tracing::error!(
+            "Failed to record the rollback of attempt {}: {}",
+            task_attempt.id,
+            e
+        );
+    }
+
+    Ok(ResponseJson(ApiResponse::success(rollback)))
+}
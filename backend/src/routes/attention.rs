@@ -0,0 +1,25 @@
+use axum::{
+    extract::State, http::StatusCode, response::Json as ResponseJson, routing::get, Router,
+};
+
+use crate::{
+    app_state::AppState,
+    models::{execution_attention::ExecutionAttention, ApiResponse},
+};
+
+/// Every execution waiting on the user, oldest first
+pub async fn get_open_attention(
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<ExecutionAttention>>>, StatusCode> {
+    match ExecutionAttention::find_open(&app_state.db_pool).await {
+        Ok(attention) => Ok(ResponseJson(ApiResponse::success(attention))),
+        Err(e) => {
+            tracing::error!("Failed to load executions waiting on the user: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub fn attention_router() -> Router<AppState> {
+    Router::new().route("/attention", get(get_open_attention))
+}
@@ -38,7 +38,10 @@ struct DevicePollRequest {
 async fn device_start() -> ResponseJson<ApiResponse<DeviceStartResponse>> {
     let client_id = option_env!("GITHUB_CLIENT_ID").unwrap_or("Ov23li9bxz3kKfPOIsGm");
 
-    let params = [("client_id", client_id), ("scope", "user:email,repo")];
+    let params = [
+        ("client_id", client_id),
+        ("scope", "user:email,repo,project"),
+    ];
     let client = reqwest::Client::new();
     let res = client
         .post("https://github.com/login/device/code")
@@ -0,0 +1,374 @@
+use std::collections::HashSet;
+
+use axum::{
+    extract::State, http::StatusCode, middleware::from_fn_with_state,
+    response::Json as ResponseJson, routing::get, Extension, Json, Router,
+};
+use uuid::Uuid;
+
+use super::task_attempts::{launch_attempt, prepare_attempt};
+use crate::{
+    app_state::AppState,
+    middleware::{load_benchmark_suite_middleware, load_project_middleware},
+    models::{
+        benchmark::{
+            BenchmarkContestant, BenchmarkReport, BenchmarkResult, BenchmarkRun, BenchmarkSuite,
+            StartBenchmarkRun, UpsertBenchmarkSuite, BENCHMARK_LABEL, MAX_BENCHMARK_CONTESTANTS,
+        },
+        executor_profile::ExecutorProfile,
+        project::Project,
+        task::{CreateTask, Task},
+        task_attempt::CreateTaskAttempt,
+        task_defaults::TaskDefaults,
+        task_label::TaskLabels,
+        ApiResponse,
+    },
+    services::verification,
+};
+
+type BenchmarkResponse<T> = Result<ResponseJson<ApiResponse<T>>, StatusCode>;
+
+/// Why the suite can't be saved, if it can't
+fn suite_error(suite: &UpsertBenchmarkSuite) -> Option<String> {
+    if suite.name.trim().is_empty() {
+        return Some("The suite needs a name".to_string());
+    }
+    if suite.cases.is_empty() {
+        return Some("Add at least one case to the suite".to_string());
+    }
+    for case in &suite.cases {
+        if case.title.trim().is_empty() || case.prompt.trim().is_empty() {
+            return Some("Every case needs a title and a prompt".to_string());
+        }
+        if verification::parse_commands(&case.verification_script).is_empty() {
+            return Some(format!(
+                "{} needs a verification command to tell whether it passed",
+                case.title.trim()
+            ));
+        }
+    }
+    None
+}
+
+fn trimmed(mut suite: UpsertBenchmarkSuite) -> UpsertBenchmarkSuite {
+    suite.name = suite.name.trim().to_string();
+    for case in &mut suite.cases {
+        case.title = case.title.trim().to_string();
+    }
+    suite
+}
+
+pub async fn get_benchmark_suites(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> BenchmarkResponse<Vec<BenchmarkSuite>> {
+    match BenchmarkSuite::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(suites) => Ok(ResponseJson(ApiResponse::success(suites))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch benchmark suites of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn create_benchmark_suite(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpsertBenchmarkSuite>,
+) -> BenchmarkResponse<BenchmarkSuite> {
+    if let Some(message) = suite_error(&payload) {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+    let payload = trimmed(payload);
+    match BenchmarkSuite::create(&app_state.db_pool, project.id, &payload).await {
+        Ok(suite) => Ok(ResponseJson(ApiResponse::success(suite))),
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Ok(ResponseJson(
+            ApiResponse::error(&format!("A suite named {} already exists", payload.name)),
+        )),
+        Err(e) => {
+            tracing::error!(
+                "Failed to create benchmark suite for project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn get_benchmark_suite(
+    Extension(suite): Extension<BenchmarkSuite>,
+) -> BenchmarkResponse<BenchmarkSuite> {
+    Ok(ResponseJson(ApiResponse::success(suite)))
+}
+
+pub async fn update_benchmark_suite(
+    Extension(suite): Extension<BenchmarkSuite>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpsertBenchmarkSuite>,
+) -> BenchmarkResponse<BenchmarkSuite> {
+    if let Some(message) = suite_error(&payload) {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+    let payload = trimmed(payload);
+    match BenchmarkSuite::update(&app_state.db_pool, suite.id, &payload).await {
+        Ok(suite) => Ok(ResponseJson(ApiResponse::success(suite))),
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Ok(ResponseJson(
+            ApiResponse::error(&format!("A suite named {} already exists", payload.name)),
+        )),
+        Err(e) => {
+            tracing::error!("Failed to update benchmark suite {}: {}", suite.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Delete the suite and its runs. The tasks its runs created stay on the board.
+pub async fn delete_benchmark_suite(
+    Extension(suite): Extension<BenchmarkSuite>,
+    State(app_state): State<AppState>,
+) -> BenchmarkResponse<()> {
+    match BenchmarkSuite::delete(&app_state.db_pool, suite.id).await {
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => {
+            tracing::error!("Failed to delete benchmark suite {}: {}", suite.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// The suite's runs with their results, newest first
+pub async fn get_benchmark_runs(
+    Extension(suite): Extension<BenchmarkSuite>,
+    State(app_state): State<AppState>,
+) -> BenchmarkResponse<Vec<BenchmarkReport>> {
+    let reports = async {
+        let mut reports = Vec::new();
+        for run in BenchmarkRun::find_by_suite_id(&app_state.db_pool, suite.id).await? {
+            reports.push(BenchmarkRun::report(&app_state.db_pool, run).await?);
+        }
+        Ok::<_, sqlx::Error>(reports)
+    };
+    match reports.await {
+        Ok(reports) => Ok(ResponseJson(ApiResponse::success(reports))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch runs of benchmark suite {}: {}",
+                suite.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// What a contestant is called in the report, or why it can't run
+async fn contestant_label(
+    app_state: &AppState,
+    project: &Project,
+    contestant: &BenchmarkContestant,
+) -> Result<Result<String, String>, StatusCode> {
+    let model = contestant
+        .model
+        .as_deref()
+        .map(str::trim)
+        .filter(|model| !model.is_empty());
+    match (&contestant.executor, contestant.executor_profile_id) {
+        (Some(executor), None) => Ok(Ok(match model {
+            Some(model) => format!("{}/{}", executor, model),
+            None => executor.clone(),
+        })),
+        (None, Some(profile_id)) => {
+            match ExecutorProfile::find_by_id(&app_state.db_pool, profile_id).await {
+                Ok(Some(profile)) if profile.is_available_in(project.id) => Ok(Ok(profile.name)),
+                Ok(_) => Ok(Err("Executor profile not found".to_string())),
+                Err(e) => {
+                    tracing::error!("Failed to load executor profile {}: {}", profile_id, e);
+                    Err(StatusCode::INTERNAL_SERVER_ERROR)
+                }
+            }
+        }
+        _ => Ok(Err(
+            "Give each contestant either an executor or a profile".to_string()
+        )),
+    }
+}
+
+/// Create a task per case and start an attempt at it for every contestant, side by side in
+/// their own worktrees. Each attempt is verified with its case's commands, and its result
+/// recorded once it finishes.
+pub async fn start_benchmark_run(
+    Extension(project): Extension<Project>,
+    Extension(suite): Extension<BenchmarkSuite>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<StartBenchmarkRun>,
+) -> BenchmarkResponse<BenchmarkReport> {
+    let pool = &app_state.db_pool;
+    if payload.contestants.is_empty() || payload.contestants.len() > MAX_BENCHMARK_CONTESTANTS {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "Pick 1 to {} executors or profiles to compare",
+            MAX_BENCHMARK_CONTESTANTS
+        ))));
+    }
+    if suite.cases.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error("The suite has no cases")));
+    }
+
+    let mut labels = Vec::with_capacity(payload.contestants.len());
+    for contestant in &payload.contestants {
+        match contestant_label(&app_state, &project, contestant).await? {
+            Ok(label) => labels.push(label),
+            Err(message) => return Ok(ResponseJson(ApiResponse::error(&message))),
+        }
+    }
+    if labels.iter().collect::<HashSet<_>>().len() != labels.len() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Each executor or profile can only run once in a benchmark",
+        )));
+    }
+
+    let mut tasks = Vec::with_capacity(suite.cases.len());
+    let created = async {
+        for case in &suite.cases {
+            let task = Task::create(
+                pool,
+                &CreateTask {
+                    project_id: project.id,
+                    title: case.title.clone(),
+                    description: Some(case.prompt.clone()),
+                    parent_task_attempt: None,
+                    parent_task_id: None,
+                },
+                Uuid::new_v4(),
+            )
+            .await?;
+            TaskLabels::add(pool, task.id, BENCHMARK_LABEL).await?;
+            TaskDefaults::set_verification_script(pool, task.id, &case.verification_script).await?;
+            tasks.push(task);
+        }
+        Ok::<_, sqlx::Error>(())
+    };
+    if let Err(e) = created.await {
+        tracing::error!(
+            "Failed to create the tasks of benchmark suite {}: {}",
+            suite.id,
+            e
+        );
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    // Check every contestant can run before starting any of them
+    let mut attempts = Vec::with_capacity(payload.contestants.len());
+    for (contestant, label) in payload.contestants.iter().zip(&labels) {
+        let mut attempt = CreateTaskAttempt {
+            executor: contestant.executor.clone(),
+            base_branch: None,
+            plan_only: None,
+            model: contestant.model.clone(),
+            executor_profile_id: contestant.executor_profile_id,
+        };
+        if let Some(message) =
+            prepare_attempt(&app_state, &project, &tasks[0], &mut attempt).await?
+        {
+            for task in &tasks {
+                if let Err(e) = Task::delete(pool, task.id, project.id).await {
+                    tracing::error!("Failed to delete benchmark task {}: {}", task.id, e);
+                }
+            }
+            return Ok(ResponseJson(ApiResponse::error(&format!(
+                "{}: {}",
+                label, message
+            ))));
+        }
+        attempts.push(attempt);
+    }
+
+    let run = match BenchmarkRun::create(pool, suite.id).await {
+        Ok(run) => run,
+        Err(e) => {
+            tracing::error!(
+                "Failed to create a run of benchmark suite {}: {}",
+                suite.id,
+                e
+            );
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    for (case, task) in suite.cases.iter().zip(&tasks) {
+        for (attempt, label) in attempts.iter().zip(&labels) {
+            // An attempt that couldn't be created counts as failed
+            let attempt_id = launch_attempt(&app_state, &project, task, attempt)
+                .await
+                .ok()
+                .map(|attempt| attempt.id);
+            if let Err(e) =
+                BenchmarkResult::create(pool, run.id, &case.title, label, task.id, attempt_id).await
+            {
+                tracing::error!(
+                    "Failed to record a result of benchmark run {}: {}",
+                    run.id,
+                    e
+                );
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        }
+    }
+    if let Err(e) = BenchmarkRun::finish_if_done(pool, run.id).await {
+        tracing::error!("Failed to update benchmark run {}: {}", run.id, e);
+    }
+
+    app_state
+        .track_analytics_event(
+            "benchmark_started",
+            Some(serde_json::json!({
+                "project_id": project.id.to_string(),
+                "cases": suite.cases.len(),
+                "contestants": labels.len(),
+            })),
+        )
+        .await;
+
+    match BenchmarkRun::report(pool, run).await {
+        Ok(report) => Ok(ResponseJson(ApiResponse::success(report))),
+        Err(e) => {
+            tracing::error!("Failed to load benchmark run of suite {}: {}", suite.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub fn benchmarks_router(app_state: AppState) -> Router<AppState> {
+    Router::new()
+        .merge(
+            Router::new()
+                .route(
+                    "/projects/:project_id/benchmarks",
+                    get(get_benchmark_suites).post(create_benchmark_suite),
+                )
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    load_project_middleware,
+                )),
+        )
+        .merge(
+            Router::new()
+                .route(
+                    "/projects/:project_id/benchmarks/:suite_id",
+                    get(get_benchmark_suite)
+                        .put(update_benchmark_suite)
+                        .delete(delete_benchmark_suite),
+                )
+                .route(
+                    "/projects/:project_id/benchmarks/:suite_id/runs",
+                    get(get_benchmark_runs).post(start_benchmark_run),
+                )
+                .route_layer(from_fn_with_state(
+                    app_state,
+                    load_benchmark_suite_middleware,
+                )),
+        )
+}
@@ -0,0 +1,172 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::Json as ResponseJson,
+    routing::{get, put},
+    Extension, Json, Router,
+};
+
+use crate::{
+    app_state::AppState,
+    models::{
+        board_column::{BoardColumn, MoveTaskToColumn, UpdateBoardColumns},
+        project::Project,
+        task::Task,
+        ApiResponse,
+    },
+};
+
+type ColumnResponse<T> = Result<ResponseJson<ApiResponse<T>>, StatusCode>;
+
+/// The project's own board columns in order; empty when the board has a column per status
+pub async fn get_board_columns(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> ColumnResponse<Vec<BoardColumn>> {
+    match BoardColumn::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(columns) => Ok(ResponseJson(ApiResponse::success(columns))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to load board columns of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Replace the project's columns, moving the tasks placed in edited or removed ones
+pub async fn update_board_columns(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpdateBoardColumns>,
+) -> ColumnResponse<Vec<BoardColumn>> {
+    let mut names: Vec<String> = Vec::new();
+    for column in &payload.columns {
+        let name = column.name.trim();
+        if name.is_empty() {
+            return Ok(ResponseJson(ApiResponse::error(
+                "Every column needs a name",
+            )));
+        }
+        if names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+            return Ok(ResponseJson(ApiResponse::error(&format!(
+                "There are two columns named {}",
+                name
+            ))));
+        }
+        if column.wip_limit.is_some_and(|limit| limit < 1) {
+            return Ok(ResponseJson(ApiResponse::error(
+                "A WIP limit must be at least 1",
+            )));
+        }
+        names.push(name.to_string());
+    }
+    let columns: Vec<_> = payload
+        .columns
+        .into_iter()
+        .map(|mut column| {
+            column.name = column.name.trim().to_string();
+            column
+        })
+        .collect();
+
+    match BoardColumn::replace(&app_state.db_pool, project.id, &columns).await {
+        Ok(columns) => Ok(ResponseJson(ApiResponse::success(columns))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to save board columns of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Move the task into a column, giving it the column's status. Refused when the column is
+/// at its WIP limit.
+pub async fn move_task_to_column(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<MoveTaskToColumn>,
+) -> ColumnResponse<Task> {
+    let pool = &app_state.db_pool;
+    let columns = match BoardColumn::find_by_project_id(pool, task.project_id).await {
+        Ok(columns) => columns,
+        Err(e) => {
+            tracing::error!(
+                "Failed to load board columns of project {}: {}",
+                task.project_id,
+                e
+            );
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    let Some(column) = columns.iter().find(|c| c.id == payload.column_id) else {
+        return Ok(ResponseJson(ApiResponse::error(
+            "The project's board has no such column",
+        )));
+    };
+
+    if let Some(limit) = column.wip_limit {
+        let board = match Task::find_by_project_id_with_attempt_status(pool, task.project_id).await
+        {
+            Ok(board) => board,
+            Err(e) => {
+                tracing::error!("Failed to load board of project {}: {}", task.project_id, e);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        };
+        let in_column = board
+            .iter()
+            .filter(|t| t.id != task.id && t.board_column_id == Some(column.id))
+            .count();
+        if in_column as i64 >= limit {
+            return Ok(ResponseJson(ApiResponse::error(&format!(
+                "{} is at its WIP limit of {}",
+                column.name, limit
+            ))));
+        }
+    }
+
+    // The status goes first: changing it unpins the task from any earlier column
+    if let Err(e) = Task::update_status(pool, task.id, task.project_id, column.status.clone()).await
+    {
+        tracing::error!("Failed to update status of task {}: {}", task.id, e);
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    if let Err(e) = Task::set_board_column(pool, task.id, task.project_id, Some(column.id)).await {
+        tracing::error!(
+            "Failed to move task {} to column {}: {}",
+            task.id,
+            column.id,
+            e
+        );
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    match Task::find_by_id(pool, task.id).await {
+        Ok(Some(task)) => Ok(ResponseJson(ApiResponse::success(task))),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Failed to reload task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub fn project_board_columns_router() -> Router<AppState> {
+    Router::new().route(
+        "/projects/:id/board/columns",
+        get(get_board_columns).put(update_board_columns),
+    )
+}
+
+pub fn task_board_column_router() -> Router<AppState> {
+    Router::new().route(
+        "/projects/:project_id/tasks/:task_id/column",
+        put(move_task_to_column),
+    )
+}
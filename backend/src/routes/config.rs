@@ -4,7 +4,7 @@ use axum::{
     extract::{Query, State},
     response::Json as ResponseJson,
     routing::{get, post},
-    Json, Router,
+    Extension, Json, Router,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -14,10 +14,16 @@ use ts_rs::TS;
 use crate::{
     app_state::AppState,
     executor::ExecutorConfig,
+    executors::{acp, jsonl},
     models::{
         config::{Config, EditorConstants, SoundConstants},
+        user::User,
         ApiResponse, Environment,
     },
+    services::{
+        executor_preflight::{self, EnvironmentReport},
+        log_redaction, log_storage,
+    },
     utils,
 };
 
@@ -26,12 +32,23 @@ pub fn config_router() -> Router<AppState> {
         .route("/config", get(get_config))
         .route("/config", post(update_config))
         .route("/config/constants", get(get_config_constants))
+        .route(
+            "/config/executor-environment",
+            get(get_executor_environment),
+        )
         .route("/mcp-servers", get(get_mcp_servers))
         .route("/mcp-servers", post(update_mcp_servers))
 }
 
-async fn get_config(State(app_state): State<AppState>) -> ResponseJson<ApiResponse<Config>> {
+async fn get_config(
+    State(app_state): State<AppState>,
+    user: Option<Extension<User>>,
+) -> ResponseJson<ApiResponse<Config>> {
     let mut config = app_state.get_config().read().await.clone();
+    // Tokens and secrets are for the admins who set them
+    if user.is_some_and(|Extension(user)| !user.is_admin) {
+        config = config.without_secrets();
+    }
 
     // Update environment info dynamically
     let info = os_info::get();
@@ -47,10 +64,22 @@ async fn update_config(
     State(app_state): State<AppState>,
     Json(new_config): Json<Config>,
 ) -> ResponseJson<ApiResponse<Config>> {
+    let invalid_patterns = log_redaction::invalid_patterns(&new_config.log_redaction);
+    if !invalid_patterns.is_empty() {
+        return ResponseJson(ApiResponse::error(&format!(
+            "Invalid redaction pattern {}",
+            invalid_patterns.join("; ")
+        )));
+    }
+
     let config_path = utils::config_path();
 
     match new_config.save(&config_path) {
         Ok(_) => {
+            jsonl::register_definitions(&new_config.jsonl_executors);
+            acp::register_definitions(&new_config.acp_agents);
+            log_redaction::configure(&new_config.log_redaction);
+            log_storage::configure(&new_config.log_storage);
             let mut config = app_state.get_config().write().await;
             *config = new_config.clone();
             drop(config);
@@ -71,6 +100,7 @@ pub struct ConfigConstants {
     pub editor: EditorConstants,
     pub sound: SoundConstants,
     pub mode: Environment,
+    pub dry_run: bool,
 }
 
 async fn get_config_constants(
@@ -80,11 +110,35 @@ async fn get_config_constants(
         editor: EditorConstants::new(),
         sound: SoundConstants::new(),
         mode: app_state.mode,
+        dry_run: app_state.dry_run,
     };
 
     ResponseJson(ApiResponse::success(constants))
 }
 
+#[derive(Debug, Deserialize)]
+struct ExecutorQuery {
+    executor: Option<String>,
+}
+
+/// Whether the machine can run an executor, the configured one by default
+async fn get_executor_environment(
+    State(app_state): State<AppState>,
+    Query(query): Query<ExecutorQuery>,
+) -> ResponseJson<ApiResponse<EnvironmentReport>> {
+    let executor_config = match query.executor {
+        Some(executor) => match executor.parse::<ExecutorConfig>() {
+            Ok(config) => config,
+            Err(message) => return ResponseJson(ApiResponse::error(&message)),
+        },
+        None => app_state.get_config().read().await.executor.clone(),
+    };
+
+    ResponseJson(ApiResponse::success(
+        executor_preflight::check(&executor_config).await,
+    ))
+}
+
 #[derive(Debug, Deserialize)]
 struct McpServerQuery {
     executor: Option<String>,
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+    routing::get,
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    models::{
+        project::Project,
+        task::Task,
+        task_custom_field::{
+            normalize_field_name, CustomField, CustomFieldType, SetTaskCustomField,
+            TaskCustomFields, UpsertCustomField,
+        },
+        ApiResponse,
+    },
+};
+
+const INVALID_NAME: &str =
+    "Field names start with a letter and use only letters, digits and underscores, up to 40 characters";
+
+#[derive(Debug, Deserialize)]
+pub struct CustomFieldQuery {
+    pub name: String,
+}
+
+type FieldResponse<T> = Result<ResponseJson<ApiResponse<T>>, StatusCode>;
+
+/// The fields the project's tasks can have values for
+pub async fn get_custom_fields(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> FieldResponse<Vec<CustomField>> {
+    match CustomField::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(fields) => Ok(ResponseJson(ApiResponse::success(fields))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to load custom fields of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Define a field, or change the type or options of the one with the same name
+pub async fn upsert_custom_field(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpsertCustomField>,
+) -> FieldResponse<CustomField> {
+    let Some(name) = normalize_field_name(&payload.name) else {
+        return Ok(ResponseJson(ApiResponse::error(INVALID_NAME)));
+    };
+    let mut options: Vec<String> = Vec::new();
+    for option in payload.options.iter().map(|o| o.trim()) {
+        if !option.is_empty() && !options.iter().any(|o| o.eq_ignore_ascii_case(option)) {
+            options.push(option.to_string());
+        }
+    }
+    if payload.field_type == CustomFieldType::Select && options.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "A select field needs at least one option",
+        )));
+    }
+
+    match CustomField::upsert(
+        &app_state.db_pool,
+        project.id,
+        &name,
+        payload.field_type,
+        &options,
+    )
+    .await
+    {
+        Ok(field) => Ok(ResponseJson(ApiResponse::success(field))),
+        Err(e) => {
+            tracing::error!("Failed to save custom field {}: {}", name, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Remove a field and every task's value for it
+pub async fn delete_custom_field(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Query(query): Query<CustomFieldQuery>,
+) -> FieldResponse<()> {
+    match CustomField::delete(&app_state.db_pool, project.id, &query.name).await {
+        Ok(true) => Ok(ResponseJson(ApiResponse::success(()))),
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Failed to delete custom field {}: {}", query.name, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn get_task_custom_fields(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> FieldResponse<HashMap<String, String>> {
+    match TaskCustomFields::find_by_task_id(&app_state.db_pool, task.id).await {
+        Ok(values) => Ok(ResponseJson(ApiResponse::success(values))),
+        Err(e) => {
+            tracing::error!("Failed to load custom fields of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Set one of the task's field values, checked against the field's type
+pub async fn set_task_custom_field(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<SetTaskCustomField>,
+) -> FieldResponse<HashMap<String, String>> {
+    let field = match CustomField::find(&app_state.db_pool, task.project_id, &payload.name).await {
+        Ok(Some(field)) => field,
+        Ok(None) => {
+            return Ok(ResponseJson(ApiResponse::error(&format!(
+                "The project has no field named {}",
+                payload.name
+            ))))
+        }
+        Err(e) => {
+            tracing::error!("Failed to load custom field {}: {}", payload.name, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let saved = if payload.value.trim().is_empty() {
+        TaskCustomFields::clear(&app_state.db_pool, task.id, &field.name).await
+    } else {
+        match field.parse_value(&payload.value) {
+            Ok(value) => {
+                TaskCustomFields::set(&app_state.db_pool, task.id, &field.name, &value).await
+            }
+            Err(message) => return Ok(ResponseJson(ApiResponse::error(&message))),
+        }
+    };
+    if let Err(e) = saved {
+        tracing::error!(
+            "Failed to set custom field {} of task {}: {}",
+            field.name,
+            task.id,
+            e
+        );
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    get_task_custom_fields(Extension(task), State(app_state)).await
+}
+
+pub fn project_custom_fields_router() -> Router<AppState> {
+    Router::new().route(
+        "/projects/:id/custom-fields",
+        get(get_custom_fields)
+            .put(upsert_custom_field)
+            .delete(delete_custom_field),
+    )
+}
+
+pub fn task_custom_fields_router() -> Router<AppState> {
+    Router::new().route(
+        "/projects/:project_id/tasks/:task_id/custom-fields",
+        get(get_task_custom_fields).put(set_task_custom_field),
+    )
+}
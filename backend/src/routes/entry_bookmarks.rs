@@ -0,0 +1,165 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+    routing::{delete, get, post},
+    Extension, Json, Router,
+};
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    models::{
+        entry_bookmark::{CreateEntryBookmark, EntryBookmark},
+        project::Project,
+        task::Task,
+        user::User,
+        ApiResponse,
+    },
+    services::entry_bookmarks::{
+        self, BookmarkExportError, ExportEntryBookmarks, ExportedEntryBookmarks,
+    },
+};
+
+pub async fn get_task_bookmarks(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<EntryBookmark>>>, StatusCode> {
+    match EntryBookmark::find_by_task_id(&app_state.db_pool, task.id).await {
+        Ok(bookmarks) => Ok(ResponseJson(ApiResponse::success(bookmarks))),
+        Err(e) => {
+            tracing::error!("Failed to load bookmarks of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn get_project_bookmarks(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<EntryBookmark>>>, StatusCode> {
+    match EntryBookmark::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(bookmarks) => Ok(ResponseJson(ApiResponse::success(bookmarks))),
+        Err(e) => {
+            tracing::error!("Failed to load bookmarks of project {}: {}", project.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Bookmark an entry of one of the task's runs, keeping it as it is now
+pub async fn create_bookmark(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    user: Option<Extension<User>>,
+    Json(payload): Json<CreateEntryBookmark>,
+) -> Result<ResponseJson<ApiResponse<EntryBookmark>>, StatusCode> {
+    let pool = &app_state.db_pool;
+    let entry = match entry_bookmarks::find_entry(
+        pool,
+        task.id,
+        payload.execution_process_id,
+        &payload.entry_id,
+    )
+    .await
+    {
+        Ok(Some(entry)) => entry,
+        Ok(None) => {
+            return Ok(ResponseJson(ApiResponse::error(
+                "That entry isn't in a conversation of this task",
+            )))
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to load entry {} of execution process {}: {}",
+                payload.entry_id,
+                payload.execution_process_id,
+                e
+            );
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let created_by = user.map(|Extension(user)| user.id);
+    match EntryBookmark::create(pool, task.id, &entry, &payload, created_by).await {
+        Ok(bookmark) => Ok(ResponseJson(ApiResponse::success(bookmark))),
+        Err(e) => {
+            tracing::error!("Failed to bookmark an entry of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// The path has one id more than the task loader takes, so the bookmark is checked against
+/// the project and task here
+pub async fn delete_bookmark(
+    Path((project_id, task_id, bookmark_id)): Path<(Uuid, Uuid, Uuid)>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    let pool = &app_state.db_pool;
+    let found = match EntryBookmark::find_by_id(pool, bookmark_id).await {
+        Ok(Some(bookmark)) if bookmark.task_id == task_id => {
+            Task::find_by_id_and_project_id(pool, task_id, project_id).await
+        }
+        Ok(_) => Ok(None),
+        Err(e) => Err(e),
+    };
+    match found {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Failed to load bookmark {}: {}", bookmark_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+    match EntryBookmark::delete(pool, bookmark_id).await {
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => {
+            tracing::error!("Failed to delete bookmark {}: {}", bookmark_id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Export the task's bookmarks into its description or a comment on its pull request
+pub async fn export_bookmarks(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<ExportEntryBookmarks>,
+) -> Result<ResponseJson<ApiResponse<ExportedEntryBookmarks>>, StatusCode> {
+    let github = app_state.get_config().read().await.github.clone();
+    match entry_bookmarks::export(&app_state.db_pool, &github, &project, &task, &payload).await {
+        Ok(exported) => Ok(ResponseJson(ApiResponse::success(exported))),
+        Err(BookmarkExportError::Database(e)) => {
+            tracing::error!("Failed to export bookmarks of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+        Err(e) => Ok(ResponseJson(ApiResponse::error(&e.to_string()))),
+    }
+}
+
+/// Under the task loader
+pub fn task_bookmarks_router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/projects/:project_id/tasks/:task_id/bookmarks",
+            get(get_task_bookmarks).post(create_bookmark),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/bookmarks/export",
+            post(export_bookmarks),
+        )
+}
+
+pub fn bookmark_with_id_router() -> Router<AppState> {
+    Router::new().route(
+        "/projects/:project_id/tasks/:task_id/bookmarks/:bookmark_id",
+        delete(delete_bookmark),
+    )
+}
+
+/// Under the project loader
+pub fn project_bookmarks_router() -> Router<AppState> {
+    Router::new().route("/projects/:id/bookmarks", get(get_project_bookmarks))
+}
@@ -0,0 +1,160 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+    routing::get,
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    models::{
+        environment_variable::{EnvironmentVariable, UpsertEnvironmentVariable},
+        project::Project,
+        task::Task,
+        ApiResponse,
+    },
+    services::environment_variables::{self, EnvironmentError},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct EnvironmentVariableQuery {
+    pub key: String,
+}
+
+type ScopeResponse<T> = Result<ResponseJson<ApiResponse<T>>, StatusCode>;
+
+async fn list(
+    app_state: &AppState,
+    project_id: Uuid,
+    task_id: Option<Uuid>,
+) -> ScopeResponse<Vec<EnvironmentVariable>> {
+    match EnvironmentVariable::find_by_scope(&app_state.db_pool, project_id, task_id).await {
+        Ok(variables) => Ok(ResponseJson(ApiResponse::success(
+            variables
+                .into_iter()
+                .map(EnvironmentVariable::without_secret)
+                .collect(),
+        ))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch environment variables of project {}: {}",
+                project_id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+async fn upsert(
+    app_state: &AppState,
+    project_id: Uuid,
+    task_id: Option<Uuid>,
+    payload: &UpsertEnvironmentVariable,
+) -> ScopeResponse<EnvironmentVariable> {
+    match environment_variables::save(&app_state.db_pool, project_id, task_id, payload).await {
+        Ok(variable) => Ok(ResponseJson(ApiResponse::success(
+            variable.without_secret(),
+        ))),
+        Err(EnvironmentError::Database(e)) => {
+            tracing::error!(
+                "Failed to save environment variable {} of project {}: {}",
+                payload.key,
+                project_id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+        Err(e) => Ok(ResponseJson(ApiResponse::error(&e.to_string()))),
+    }
+}
+
+async fn delete(
+    app_state: &AppState,
+    project_id: Uuid,
+    task_id: Option<Uuid>,
+    key: &str,
+) -> ScopeResponse<()> {
+    match EnvironmentVariable::delete(&app_state.db_pool, project_id, task_id, key).await {
+        Ok(true) => Ok(ResponseJson(ApiResponse::success(()))),
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!(
+                "Failed to delete environment variable {} of project {}: {}",
+                key,
+                project_id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// The variables every task of the project gets; secrets come without their values
+pub async fn get_project_environment(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> ScopeResponse<Vec<EnvironmentVariable>> {
+    list(&app_state, project.id, None).await
+}
+
+pub async fn upsert_project_environment_variable(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpsertEnvironmentVariable>,
+) -> ScopeResponse<EnvironmentVariable> {
+    upsert(&app_state, project.id, None, &payload).await
+}
+
+pub async fn delete_project_environment_variable(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Query(query): Query<EnvironmentVariableQuery>,
+) -> ScopeResponse<()> {
+    delete(&app_state, project.id, None, &query.key).await
+}
+
+/// The variables the task gets on top of its project's, overriding those of the same key
+pub async fn get_task_environment(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> ScopeResponse<Vec<EnvironmentVariable>> {
+    list(&app_state, task.project_id, Some(task.id)).await
+}
+
+pub async fn upsert_task_environment_variable(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpsertEnvironmentVariable>,
+) -> ScopeResponse<EnvironmentVariable> {
+    upsert(&app_state, task.project_id, Some(task.id), &payload).await
+}
+
+pub async fn delete_task_environment_variable(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Query(query): Query<EnvironmentVariableQuery>,
+) -> ScopeResponse<()> {
+    delete(&app_state, task.project_id, Some(task.id), &query.key).await
+}
+
+pub fn project_environment_router() -> Router<AppState> {
+    Router::new().route(
+        "/projects/:id/environment",
+        get(get_project_environment)
+            .put(upsert_project_environment_variable)
+            .delete(delete_project_environment_variable),
+    )
+}
+
+pub fn task_environment_router() -> Router<AppState> {
+    Router::new().route(
+        "/projects/:project_id/tasks/:task_id/environment",
+        get(get_task_environment)
+            .put(upsert_task_environment_variable)
+            .delete(delete_task_environment_variable),
+    )
+}
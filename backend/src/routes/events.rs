@@ -0,0 +1,248 @@
+use axum::{
+    extract::{Query, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{
+        sse::{Event as SseEvent, KeepAlive, Sse},
+        IntoResponse, Json as ResponseJson, Response,
+    },
+    routing::get,
+    Extension, Router,
+};
+use futures_util::stream::Stream;
+use serde::Deserialize;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    event_bus::{self, BoardEvent},
+    models::{
+        event::{self, AuditVerification, Event, EventActor},
+        project::Project,
+        ApiResponse,
+    },
+};
+
+const DEFAULT_EVENTS_LIMIT: i64 = 50;
+const MAX_EVENTS_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct EventsQuery {
+    pub project_id: Option<Uuid>,
+    /// Only events older than this one, for paging back through the feed
+    pub before_id: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EventStreamQuery {
+    pub project_id: Option<Uuid>,
+    /// Resume after this event; events recorded since are sent before live ones
+    pub since_id: Option<i64>,
+}
+
+/// Attribute the events recorded while handling an API request to the user
+pub async fn user_actor_middleware(req: Request, next: Next) -> Response {
+    event::with_actor(EventActor::User, next.run(req)).await
+}
+
+async fn find_events(
+    app_state: &AppState,
+    project_id: Option<Uuid>,
+    before_id: Option<i64>,
+    limit: Option<i64>,
+) -> Result<ResponseJson<ApiResponse<Vec<Event>>>, StatusCode> {
+    let limit = limit
+        .unwrap_or(DEFAULT_EVENTS_LIMIT)
+        .clamp(1, MAX_EVENTS_LIMIT);
+    match Event::find_page(&app_state.db_pool, project_id, before_id, limit).await {
+        Ok(events) => Ok(ResponseJson(ApiResponse::success(events))),
+        Err(e) => {
+            tracing::error!("Failed to load events: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+fn wants_event_stream(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/event-stream"))
+}
+
+/// The audit trail across projects, newest first. Asked for `text/event-stream`, as
+/// `EventSource` does, it streams live board updates instead.
+pub async fn get_events(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<EventsQuery>,
+) -> Result<Response, StatusCode> {
+    if wants_event_stream(&headers) {
+        return Ok(board_updates(query.project_id).into_response());
+    }
+    find_events(&app_state, query.project_id, query.before_id, query.limit)
+        .await
+        .map(IntoResponse::into_response)
+}
+
+fn board_sse_event(event: &BoardEvent) -> SseEvent {
+    SseEvent::default()
+        .event(event.name())
+        .json_data(event)
+        .unwrap_or_default()
+}
+
+/// Board updates for one project, or all of them, as they are published. Updates that
+/// aren't tied to a project are sent to every subscriber.
+fn board_updates(
+    project_id: Option<Uuid>,
+) -> Sse<impl Stream<Item = Result<SseEvent, axum::Error>>> {
+    let stream = async_stream::stream! {
+        let mut receiver = event_bus::subscribe();
+        loop {
+            match receiver.recv().await {
+                Ok(event) => {
+                    if let (Some(wanted), Some(project_id)) = (project_id, event.project_id()) {
+                        if wanted != project_id {
+                            continue;
+                        }
+                    }
+                    yield Ok(board_sse_event(&event));
+                }
+                // Missed updates can't be replayed; the tab reloads what it shows instead
+                Err(broadcast::error::RecvError::Lagged(_)) => {
+                    yield Ok(board_sse_event(&BoardEvent::Resync));
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    };
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// The project's activity feed, newest first
+pub async fn get_project_events(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Query(query): Query<EventsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<Event>>>, StatusCode> {
+    find_events(&app_state, Some(project.id), query.before_id, query.limit).await
+}
+
+/// Check that no event in the audit trail was edited or removed
+pub async fn verify_events(
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<AuditVerification>>, StatusCode> {
+    match Event::verify_chain(&app_state.db_pool).await {
+        Ok(verification) => {
+            if !verification.valid {
+                tracing::warn!(
+                    "Audit trail fails verification at event {:?}",
+                    verification.first_invalid_event_id
+                );
+            }
+            Ok(ResponseJson(ApiResponse::success(verification)))
+        }
+        Err(e) => {
+            tracing::error!("Failed to verify the audit trail: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+fn sse_event(event: &Event) -> SseEvent {
+    SseEvent::default()
+        .id(event.id.to_string())
+        .json_data(event)
+        .unwrap_or_default()
+}
+
+/// Events as they are recorded, after catching up on the ones since `since_id`
+fn event_stream(
+    app_state: AppState,
+    project_id: Option<Uuid>,
+    since_id: Option<i64>,
+) -> impl Stream<Item = Result<SseEvent, axum::Error>> {
+    async_stream::stream! {
+        // Subscribed before catching up, so nothing recorded in between is missed
+        let mut receiver = event::subscribe();
+        let mut last_id = since_id;
+
+        if let Some(since_id) = since_id {
+            match Event::find_after(&app_state.db_pool, project_id, since_id).await {
+                Ok(events) => {
+                    for event in events {
+                        last_id = Some(event.id);
+                        yield Ok(sse_event(&event));
+                    }
+                }
+                Err(e) => tracing::error!("Failed to catch up on events: {}", e),
+            }
+        }
+
+        loop {
+            match receiver.recv().await {
+                Ok(event) => {
+                    if project_id.is_some_and(|id| id != event.project_id) {
+                        continue;
+                    }
+                    if last_id.is_some_and(|last_id| event.id <= last_id) {
+                        continue;
+                    }
+                    last_id = Some(event.id);
+                    yield Ok(sse_event(&event));
+                }
+                // Fell behind the channel: send what was missed from the database
+                Err(broadcast::error::RecvError::Lagged(_)) => {
+                    let Some(after_id) = last_id else {
+                        continue;
+                    };
+                    match Event::find_after(&app_state.db_pool, project_id, after_id).await {
+                        Ok(events) => {
+                            for event in events {
+                                last_id = Some(event.id);
+                                yield Ok(sse_event(&event));
+                            }
+                        }
+                        Err(e) => tracing::error!("Failed to catch up on events: {}", e),
+                    }
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }
+}
+
+/// GET /api/events/stream?project_id=...&since_id=123
+pub async fn stream_events(
+    State(app_state): State<AppState>,
+    Query(query): Query<EventStreamQuery>,
+) -> Sse<impl Stream<Item = Result<SseEvent, axum::Error>>> {
+    Sse::new(event_stream(app_state, query.project_id, query.since_id))
+        .keep_alive(KeepAlive::default())
+}
+
+/// GET /api/projects/:id/events/stream?since_id=123
+pub async fn stream_project_events(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Query(query): Query<EventStreamQuery>,
+) -> Sse<impl Stream<Item = Result<SseEvent, axum::Error>>> {
+    Sse::new(event_stream(app_state, Some(project.id), query.since_id))
+        .keep_alive(KeepAlive::default())
+}
+
+pub fn events_router() -> Router<AppState> {
+    Router::new()
+        .route("/events", get(get_events))
+        .route("/events/verify", get(verify_events))
+        .route("/events/stream", get(stream_events))
+}
+
+pub fn project_events_router() -> Router<AppState> {
+    Router::new()
+        .route("/projects/:id/events", get(get_project_events))
+        .route("/projects/:id/events/stream", get(stream_project_events))
+}
@@ -0,0 +1,44 @@
+use axum::{
+    extract::State, http::StatusCode, response::Json as ResponseJson, routing::get, Json, Router,
+};
+
+use crate::{
+    app_state::AppState,
+    models::{
+        execution_queue::{QueueEntry, QueuedAttempt, ReorderQueue},
+        ApiResponse,
+    },
+    services::guardrails,
+};
+
+/// The attempts waiting for a slot, in the order they're started, with those their
+/// project's guardrails hold back marked
+pub async fn get_execution_queue(
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<QueueEntry>>>, StatusCode> {
+    match guardrails::queue_entries(&app_state.db_pool).await {
+        Ok(entries) => Ok(ResponseJson(ApiResponse::success(entries))),
+        Err(e) => {
+            tracing::error!("Failed to load the execution queue: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn reorder_execution_queue(
+    State(app_state): State<AppState>,
+    Json(payload): Json<ReorderQueue>,
+) -> Result<ResponseJson<ApiResponse<Vec<QueueEntry>>>, StatusCode> {
+    if let Err(e) = QueuedAttempt::reorder(&app_state.db_pool, &payload.task_attempt_ids).await {
+        tracing::error!("Failed to reorder the execution queue: {}", e);
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    get_execution_queue(State(app_state)).await
+}
+
+pub fn execution_queue_router() -> Router<AppState> {
+    Router::new().route(
+        "/execution-queue",
+        get(get_execution_queue).put(reorder_execution_queue),
+    )
+}
@@ -0,0 +1,105 @@
+use axum::{extract::State, http::StatusCode, response::Json as ResponseJson, Extension, Json};
+
+use crate::{
+    app_state::AppState,
+    models::{
+        event::{Event, EventKind},
+        execution_snapshot::{ExecutionSnapshot, RevertExecution},
+        project::Project,
+        task::Task,
+        task_attempt::{TaskAttempt, TaskAttemptError},
+        ApiResponse,
+    },
+    services::GitServiceError,
+};
+
+/// The snapshots taken before each of the attempt's runs, showing which runs were reverted
+pub async fn get_execution_snapshots(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<ExecutionSnapshot>>>, StatusCode> {
+    match ExecutionSnapshot::find_by_task_attempt_id(&app_state.db_pool, task_attempt.id).await {
+        Ok(snapshots) => Ok(ResponseJson(ApiResponse::success(snapshots))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to load snapshots for attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Undo the changes of one of the attempt's runs, keeping what the others changed
+pub async fn revert_execution(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<RevertExecution>,
+) -> Result<ResponseJson<ApiResponse<ExecutionSnapshot>>, StatusCode> {
+    if app_state.has_running_execution(task_attempt.id).await {
+        return Ok(ResponseJson(ApiResponse::error(
+            "The attempt is still running; revert the run once it finishes",
+        )));
+    }
+
+    let pool = &app_state.db_pool;
+    let snapshot = match TaskAttempt::revert_execution(
+        pool,
+        task_attempt.id,
+        task.id,
+        project.id,
+        payload.execution_process_id,
+    )
+    .await
+    {
+        Ok(snapshot) => snapshot,
+        Err(TaskAttemptError::ValidationError(message)) => {
+            return Ok(ResponseJson(ApiResponse::error(&message)));
+        }
+        Err(TaskAttemptError::GitService(GitServiceError::WorktreeDirty(files))) => {
+            return Ok(ResponseJson(ApiResponse::error(&format!(
+                "Commit or discard the worktree's uncommitted changes first: {}",
+                files
+            ))));
+        }
+        Err(TaskAttemptError::GitService(GitServiceError::MergeConflicts(files))) => {
+            return Ok(ResponseJson(ApiResponse::error(&format!(
+                "Later changes conflict with reverting that run: {}",
+                files
+            ))));
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to revert execution {} of attempt {}: {}",
+                payload.execution_process_id,
+                task_attempt.id,
+                e
+            );
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    if let Err(e) = Event::record_for_attempt(
+        pool,
+        task_attempt.id,
+        Some(snapshot.execution_process_id),
+        EventKind::ExecutionReverted,
+        serde_json::json!({
+            "snapshot_commit": snapshot.snapshot_commit,
+            "revert_commit": snapshot.revert_commit,
+        }),
+    )
+    .await
+    {
+        tracing::error!(
+            "Failed to record the revert of execution {}: {}",
+            snapshot.execution_process_id,
+            e
+        );
+    }
+
+    Ok(ResponseJson(ApiResponse::success(snapshot)))
+}
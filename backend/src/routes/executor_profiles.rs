@@ -0,0 +1,151 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+    routing::{get, put},
+    Extension, Json, Router,
+};
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    models::{
+        executor_profile::{executor_profile_error, ExecutorProfile, UpsertExecutorProfile},
+        project::Project,
+        ApiResponse,
+    },
+};
+
+const DUPLICATE_NAME: &str = "A profile with this name already exists in this scope";
+
+fn is_duplicate_name(e: &sqlx::Error) -> bool {
+    e.to_string().contains("UNIQUE constraint failed")
+}
+
+/// The profiles the project's attempts can run with, its own before the global ones
+pub async fn get_project_executor_profiles(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<ExecutorProfile>>>, StatusCode> {
+    match ExecutorProfile::find_available(&app_state.db_pool, project.id).await {
+        Ok(profiles) => Ok(ResponseJson(ApiResponse::success(profiles))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to load executor profiles of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn create_project_executor_profile(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpsertExecutorProfile>,
+) -> Result<ResponseJson<ApiResponse<ExecutorProfile>>, StatusCode> {
+    create_profile(&app_state, Some(project.id), payload).await
+}
+
+pub async fn get_global_executor_profiles(
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<ExecutorProfile>>>, StatusCode> {
+    match ExecutorProfile::find_global(&app_state.db_pool).await {
+        Ok(profiles) => Ok(ResponseJson(ApiResponse::success(profiles))),
+        Err(e) => {
+            tracing::error!("Failed to load global executor profiles: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn create_global_executor_profile(
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpsertExecutorProfile>,
+) -> Result<ResponseJson<ApiResponse<ExecutorProfile>>, StatusCode> {
+    create_profile(&app_state, None, payload).await
+}
+
+async fn create_profile(
+    app_state: &AppState,
+    project_id: Option<Uuid>,
+    payload: UpsertExecutorProfile,
+) -> Result<ResponseJson<ApiResponse<ExecutorProfile>>, StatusCode> {
+    if let Some(message) = executor_profile_error(&payload) {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+
+    match ExecutorProfile::create(&app_state.db_pool, project_id, &payload).await {
+        Ok(profile) => {
+            app_state
+                .track_analytics_event(
+                    "executor_profile_created",
+                    Some(serde_json::json!({
+                        "executor": profile.executor,
+                        "global": profile.project_id.is_none(),
+                    })),
+                )
+                .await;
+            Ok(ResponseJson(ApiResponse::success(profile)))
+        }
+        Err(e) if is_duplicate_name(&e) => Ok(ResponseJson(ApiResponse::error(DUPLICATE_NAME))),
+        Err(e) => {
+            tracing::error!("Failed to create executor profile {}: {}", payload.name, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn update_executor_profile(
+    Path(profile_id): Path<Uuid>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpsertExecutorProfile>,
+) -> Result<ResponseJson<ApiResponse<ExecutorProfile>>, StatusCode> {
+    if let Some(message) = executor_profile_error(&payload) {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+
+    match ExecutorProfile::update(&app_state.db_pool, profile_id, &payload).await {
+        Ok(profile) => Ok(ResponseJson(ApiResponse::success(profile))),
+        Err(sqlx::Error::RowNotFound) => Err(StatusCode::NOT_FOUND),
+        Err(e) if is_duplicate_name(&e) => Ok(ResponseJson(ApiResponse::error(DUPLICATE_NAME))),
+        Err(e) => {
+            tracing::error!("Failed to update executor profile {}: {}", profile_id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn delete_executor_profile(
+    Path(profile_id): Path<Uuid>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match ExecutorProfile::delete(&app_state.db_pool, profile_id).await {
+        Ok(0) => Err(StatusCode::NOT_FOUND),
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => {
+            tracing::error!("Failed to delete executor profile {}: {}", profile_id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub fn project_executor_profiles_router() -> Router<AppState> {
+    Router::new().route(
+        "/projects/:id/executor-profiles",
+        get(get_project_executor_profiles).post(create_project_executor_profile),
+    )
+}
+
+pub fn executor_profiles_router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/executor-profiles",
+            get(get_global_executor_profiles).post(create_global_executor_profile),
+        )
+        .route(
+            "/executor-profiles/:profile_id",
+            put(update_executor_profile).delete(delete_executor_profile),
+        )
+}
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+
+use axum::{extract::State, http::StatusCode, response::Json as ResponseJson, Extension, Json};
+
+use super::task_attempts::{
+    followup_handover, normalize_process_logs, notify_follow_up_mentions, FollowUpResponse,
+};
+use crate::{
+    app_state::AppState,
+    models::{
+        execution_process::ExecutionProcess,
+        follow_up_draft::{DraftQuote, FollowUpDraft, SaveFollowUpDraft, SendFollowUpDraft},
+        project::Project,
+        task::Task,
+        task_attempt::{TaskAttempt, TaskAttemptError},
+        user::User,
+        ApiResponse,
+    },
+};
+
+pub async fn get_follow_up_draft(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Option<FollowUpDraft>>>, StatusCode> {
+    match FollowUpDraft::find_by_task_id(&app_state.db_pool, task.id).await {
+        Ok(draft) => Ok(ResponseJson(ApiResponse::success(draft))),
+        Err(e) => {
+            tracing::error!("Failed to fetch follow-up draft of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn save_follow_up_draft(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<SaveFollowUpDraft>,
+) -> Result<ResponseJson<ApiResponse<FollowUpDraft>>, StatusCode> {
+    if let Some(attempt_id) = payload.task_attempt_id {
+        match TaskAttempt::find_by_id(&app_state.db_pool, attempt_id).await {
+            Ok(Some(attempt)) if attempt.task_id == task.id => {}
+            Ok(_) => {
+                return Ok(ResponseJson(ApiResponse::error(
+                    "The attempt isn't one of this task's",
+                )))
+            }
+            Err(e) => {
+                tracing::error!("Failed to fetch task attempt {}: {}", attempt_id, e);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        }
+    } else if !payload.quotes.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Quotes need the attempt they were picked from",
+        )));
+    }
+
+    match FollowUpDraft::save(&app_state.db_pool, task.id, &payload).await {
+        Ok(draft) => Ok(ResponseJson(ApiResponse::success(draft))),
+        Err(e) => {
+            tracing::error!("Failed to save follow-up draft of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn delete_follow_up_draft(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match FollowUpDraft::delete(&app_state.db_pool, task.id).await {
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to delete follow-up draft of task {}: {}",
+                task.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// The task's draft and the prompt it expands to for `task_attempt`. The inner `Err` says
+/// why it can't be sent to that attempt as it is.
+async fn compile_draft(
+    app_state: &AppState,
+    project: &Project,
+    task: &Task,
+    task_attempt: &TaskAttempt,
+) -> Result<Result<(FollowUpDraft, String), String>, StatusCode> {
+    let db_error = |e: sqlx::Error| {
+        tracing::error!("Failed to load follow-up draft of task {}: {}", task.id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+
+    let Some(draft) = FollowUpDraft::find_by_task_id(&app_state.db_pool, task.id)
+        .await
+        .map_err(db_error)?
+    else {
+        return Ok(Err("There's no draft for this task".to_string()));
+    };
+    if !draft.quotes.is_empty() && draft.task_attempt_id != Some(task_attempt.id) {
+        return Ok(Err(
+            "The draft quotes another attempt; remove its quotes to send it to this one"
+                .to_string(),
+        ));
+    }
+
+    let mut conversations = HashMap::new();
+    for process_id in draft.quoted_processes() {
+        match ExecutionProcess::find_by_id(&app_state.db_pool, process_id)
+            .await
+            .map_err(db_error)?
+        {
+            Some(process) if process.task_attempt_id == task_attempt.id => {
+                let conversation = normalize_process_logs(&app_state.db_pool, &process).await;
+                conversations.insert(process_id, conversation);
+            }
+            _ => {
+                return Ok(Err(
+                    "A quoted run is no longer part of the attempt".to_string()
+                ))
+            }
+        }
+    }
+
+    let quotes_hunks = draft
+        .quotes
+        .iter()
+        .any(|quote| matches!(quote, DraftQuote::Hunk { .. }));
+    let files = if quotes_hunks {
+        match TaskAttempt::get_diff_hunks(&app_state.db_pool, task_attempt.id, task.id, project.id)
+            .await
+        {
+            Ok(files) => files,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to get diff hunks for task attempt {}: {}",
+                    task_attempt.id,
+                    e
+                );
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        }
+    } else {
+        Vec::new()
+    };
+
+    Ok(draft
+        .compile_prompt(&conversations, &files)
+        .map(|prompt| (draft, prompt)))
+}
+
+/// The prompt the draft would be sent as, with its quotes expanded
+pub async fn preview_follow_up_draft(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<String>>, StatusCode> {
+    match compile_draft(&app_state, &project, &task, &task_attempt).await? {
+        Ok((_, prompt)) => Ok(ResponseJson(ApiResponse::success(prompt))),
+        Err(message) => Ok(ResponseJson(ApiResponse::error(&message))),
+    }
+}
+
+/// Send the draft as a follow-up to the attempt, with its quotes expanded, and discard it.
+/// The body may hand the attempt over to another executor with it.
+pub async fn send_follow_up_draft(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+    user: Option<Extension<User>>,
+    request_body: Option<Json<SendFollowUpDraft>>,
+) -> Result<ResponseJson<ApiResponse<FollowUpResponse>>, StatusCode> {
+    let payload = request_body.map(|Json(body)| body).unwrap_or_default();
+    let handover = match followup_handover(
+        &app_state.db_pool,
+        &task_attempt,
+        payload.executor.as_deref(),
+    )
+    .await
+    {
+        Ok(handover) => handover,
+        Err(message) => return Ok(ResponseJson(ApiResponse::error(&message))),
+    };
+    let (draft, prompt) = match compile_draft(&app_state, &project, &task, &task_attempt).await? {
+        Ok(compiled) => compiled,
+        Err(message) => return Ok(ResponseJson(ApiResponse::error(&message))),
+    };
+
+    let actual_attempt_id = match TaskAttempt::start_followup_execution_with_executor(
+        &app_state.db_pool,
+        &app_state,
+        task_attempt.id,
+        task.id,
+        project.id,
+        &prompt,
+        handover,
+    )
+    .await
+    {
+        Ok(id) => id,
+        Err(TaskAttemptError::Executor(e)) => {
+            return Ok(ResponseJson(ApiResponse::error(&e.to_string())))
+        }
+        Err(TaskAttemptError::BudgetExceeded(e)) => {
+            return Ok(ResponseJson(ApiResponse::error(&e.to_string())))
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to send follow-up draft to attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    if let Err(e) = FollowUpDraft::delete(&app_state.db_pool, task.id).await {
+        tracing::error!("Failed to discard sent draft of task {}: {}", task.id, e);
+    }
+    notify_follow_up_mentions(
+        &app_state.db_pool,
+        &task,
+        actual_attempt_id,
+        user.as_ref().map(|Extension(user)| user),
+        &draft.prompt,
+    )
+    .await;
+
+    Ok(ResponseJson(ApiResponse::success(FollowUpResponse {
+        message: if draft.quotes.is_empty() {
+            "Follow-up sent".to_string()
+        } else {
+            format!("Follow-up sent with {} quote(s)", draft.quotes.len())
+        },
+        actual_attempt_id,
+        created_new_attempt: actual_attempt_id != task_attempt.id,
+    })))
+}
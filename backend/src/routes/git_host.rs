@@ -0,0 +1,77 @@
+use axum::{
+    extract::State, http::StatusCode, response::Json as ResponseJson, routing::get, Extension,
+    Json, Router,
+};
+
+use crate::{
+    app_state::AppState,
+    models::{
+        project::Project,
+        project_git_host::{ProjectGitHost, UpsertProjectGitHost},
+        ApiResponse,
+    },
+    services::git_host::{self, GitHostError},
+};
+
+/// The project's host, or `None` when it uses GitHub with the account's token
+pub async fn get_git_host(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectGitHost>>>, StatusCode> {
+    match ProjectGitHost::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(host) => Ok(ResponseJson(ApiResponse::success(host))),
+        Err(e) => {
+            tracing::error!("Failed to load git host of project {}: {}", project.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn upsert_git_host(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpsertProjectGitHost>,
+) -> Result<ResponseJson<ApiResponse<ProjectGitHost>>, StatusCode> {
+    match git_host::save(&app_state.db_pool, project.id, &payload).await {
+        Ok(host) => {
+            app_state
+                .track_analytics_event(
+                    "git_host_configured",
+                    Some(serde_json::json!({
+                        "project_id": project.id.to_string(),
+                        "kind": host.kind,
+                        "self_hosted": host.base_url.is_some(),
+                    })),
+                )
+                .await;
+            Ok(ResponseJson(ApiResponse::success(host)))
+        }
+        Err(GitHostError::Database(e)) => {
+            tracing::error!("Failed to save git host of project {}: {}", project.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+        Err(e) => Ok(ResponseJson(ApiResponse::error(&e.to_string()))),
+    }
+}
+
+pub async fn delete_git_host(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match ProjectGitHost::delete(&app_state.db_pool, project.id).await {
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => {
+            tracing::error!("Failed to delete git host of project {}: {}", project.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub fn git_host_router() -> Router<AppState> {
+    Router::new().route(
+        "/projects/:id/git-host",
+        get(get_git_host)
+            .put(upsert_git_host)
+            .delete(delete_git_host),
+    )
+}
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use axum::{
     extract::{Query, State},
     http::StatusCode,
@@ -13,9 +15,11 @@ use crate::{
         project::{CreateProject, CreateProjectFromGitHub, Project},
         ApiResponse,
     },
+    routes::projects::invalid_default_executor,
     services::{
         git_service::GitService,
         github_service::{GitHubService, RepositoryInfo},
+        repo_config::RepoConfig,
         GitHubServiceError,
     },
 };
@@ -105,6 +109,10 @@ pub async fn create_project_from_github(
 
     tracing::debug!("Creating project '{}' from GitHub repository", payload.name);
 
+    if let Some(message) = invalid_default_executor(&payload.default_executor) {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+
     // Get workspace path
     let workspace_path = match app_state.get_workspace_path().await {
         Ok(path) => path,
@@ -145,8 +153,25 @@ pub async fn create_project_from_github(
         config.github.token.clone()
     };
 
+    // Borrow objects from a clone of the same repository that's already in the workspace
+    let reference = match Project::find_all(&app_state.db_pool).await {
+        Ok(projects) => projects
+            .into_iter()
+            .map(|project| PathBuf::from(project.git_repo_path))
+            .find(|path| GitService::new(path).is_ok_and(|git| git.has_origin(&payload.clone_url))),
+        Err(e) => {
+            tracing::error!("Failed to fetch projects: {}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
     // Clone the repository
-    match GitService::clone_repository(&payload.clone_url, &target_path, github_token.as_deref()) {
+    match GitService::clone_repository(
+        &payload.clone_url,
+        &target_path,
+        github_token.as_deref(),
+        reference.as_deref(),
+    ) {
         Ok(_) => {
             tracing::info!(
                 "Successfully cloned repository {} to {}",
@@ -163,6 +188,14 @@ pub async fn create_project_from_github(
         }
     }
 
+    // A `.vibe-kanban.toml` checked into the repository has to be valid to register it
+    if let Err(message) = RepoConfig::load(&target_path) {
+        if let Err(cleanup_err) = std::fs::remove_dir_all(&target_path) {
+            tracing::error!("Failed to cleanup cloned repository: {}", cleanup_err);
+        }
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+
     // Create project record in database
     let has_setup_script = payload.setup_script.is_some();
     let has_dev_script = payload.dev_script.is_some();
@@ -173,6 +206,12 @@ pub async fn create_project_from_github(
         setup_script: payload.setup_script,
         dev_script: payload.dev_script,
         cleanup_script: payload.cleanup_script,
+        commit_message_template: payload.commit_message_template,
+        release_script: payload.release_script,
+        merge_check_script: payload.merge_check_script,
+        verification_script: payload.verification_script,
+        default_executor: payload.default_executor,
+        default_branch: payload.default_branch,
     };
 
     let project_id = Uuid::new_v4();
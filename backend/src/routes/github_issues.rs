@@ -0,0 +1,153 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::Json as ResponseJson,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+
+use crate::{
+    app_state::AppState,
+    models::{
+        github_issue_sync::{GitHubIssueLink, UpsertGitHubIssueLink},
+        project::Project,
+        ApiResponse,
+    },
+    services::{
+        github_issues::{sync_project, GitHubIssueSyncResult, GitHubIssuesClient},
+        GitService,
+    },
+};
+
+const TOKEN_MISSING: &str = "Sign in with GitHub or add a personal access token first.";
+
+async fn issues_client(app_state: &AppState) -> Result<GitHubIssuesClient, String> {
+    match GitHubIssuesClient::from_config(&app_state.get_config().read().await.github) {
+        Some(client) => client.map_err(|e| e.to_string()),
+        None => Err(TOKEN_MISSING.to_string()),
+    }
+}
+
+pub async fn get_github_issue_link(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Option<GitHubIssueLink>>>, StatusCode> {
+    match GitHubIssueLink::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(link) => Ok(ResponseJson(ApiResponse::success(link))),
+        Err(e) => {
+            tracing::error!("Failed to load GitHub issue link for {}: {}", project.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Import issues with the label from the repository the project's `origin` points at
+pub async fn upsert_github_issue_link(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpsertGitHubIssueLink>,
+) -> Result<ResponseJson<ApiResponse<GitHubIssueLink>>, StatusCode> {
+    let label = payload.label.trim();
+    if label.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Choose the label of the issues to import",
+        )));
+    }
+    let repo = GitService::new(&project.git_repo_path).and_then(|git| git.get_github_repo_info());
+    let (repo_owner, repo_name) = match repo {
+        Ok(repo) => repo,
+        Err(e) => {
+            tracing::warn!(
+                "Failed to find the GitHub repository of project {}: {}",
+                project.id,
+                e
+            );
+            return Ok(ResponseJson(ApiResponse::error(
+                "The project's origin remote isn't a GitHub repository",
+            )));
+        }
+    };
+
+    match GitHubIssueLink::upsert(
+        &app_state.db_pool,
+        project.id,
+        &repo_owner,
+        &repo_name,
+        label,
+    )
+    .await
+    {
+        Ok(link) => {
+            app_state
+                .track_analytics_event(
+                    "github_issues_linked",
+                    Some(serde_json::json!({ "project_id": project.id.to_string() })),
+                )
+                .await;
+            Ok(ResponseJson(ApiResponse::success(link)))
+        }
+        Err(e) => {
+            tracing::error!("Failed to save GitHub issue link: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn delete_github_issue_link(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match GitHubIssueLink::delete(&app_state.db_pool, project.id).await {
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => {
+            tracing::error!("Failed to delete GitHub issue link: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Sync now instead of waiting for the background service or a webhook
+pub async fn sync_github_issues(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<GitHubIssueSyncResult>>, StatusCode> {
+    let link = match GitHubIssueLink::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(Some(link)) => link,
+        Ok(None) => {
+            return Ok(ResponseJson(ApiResponse::error(
+                "This project doesn't import GitHub issues",
+            )))
+        }
+        Err(e) => {
+            tracing::error!("Failed to load GitHub issue link: {}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    let client = match issues_client(&app_state).await {
+        Ok(client) => client,
+        Err(e) => return Ok(ResponseJson(ApiResponse::error(&e))),
+    };
+
+    let outcome = sync_project(&app_state.db_pool, &client, &link).await;
+    let error = outcome.as_ref().err().map(|e| e.to_string());
+    if let Err(e) =
+        GitHubIssueLink::record_sync(&app_state.db_pool, project.id, error.as_deref()).await
+    {
+        tracing::error!("Failed to record GitHub issue sync: {}", e);
+    }
+    match outcome {
+        Ok(result) => Ok(ResponseJson(ApiResponse::success(result))),
+        Err(_) => Ok(ResponseJson(ApiResponse::error(&error.unwrap_or_default()))),
+    }
+}
+
+pub fn github_issues_router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/projects/:id/github-issues",
+            get(get_github_issue_link)
+                .put(upsert_github_issue_link)
+                .delete(delete_github_issue_link),
+        )
+        .route("/projects/:id/github-issues/sync", post(sync_github_issues))
+}
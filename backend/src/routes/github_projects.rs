@@ -0,0 +1,192 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::Json as ResponseJson,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::{
+    app_state::AppState,
+    models::{
+        github_project_sync::{GitHubProjectLink, UpsertGitHubProjectLink},
+        project::Project,
+        ApiResponse,
+    },
+    services::{
+        github_projects::{
+            sync_project, GitHubProjectBoard, GitHubProjectSyncResult, GitHubProjectsClient,
+            ProjectUrl,
+        },
+        GitHubServiceError,
+    },
+};
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct GitHubProjectLinkResponse {
+    pub link: GitHubProjectLink,
+    pub board: GitHubProjectBoard,
+}
+
+const TOKEN_MISSING: &str = "Sign in with GitHub or add a personal access token first.";
+const TOKEN_REJECTED: &str =
+    "GitHub refused the token. Sign in with GitHub again so it includes the project scope.";
+
+async fn projects_client(app_state: &AppState) -> Result<GitHubProjectsClient, String> {
+    let token = {
+        let config = app_state.get_config().read().await;
+        config
+            .github
+            .pat
+            .clone()
+            .or_else(|| config.github.token.clone())
+    };
+    let token = token.ok_or_else(|| TOKEN_MISSING.to_string())?;
+    GitHubProjectsClient::new(&token).map_err(|e| e.to_string())
+}
+
+fn error_message(e: &GitHubServiceError) -> String {
+    match e {
+        GitHubServiceError::TokenInvalid => TOKEN_REJECTED.to_string(),
+        e => e.to_string(),
+    }
+}
+
+pub async fn get_github_project_link(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Option<GitHubProjectLink>>>, StatusCode> {
+    match GitHubProjectLink::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(link) => Ok(ResponseJson(ApiResponse::success(link))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to load GitHub project link for {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Link the project to a board, checking that the board exists and the token can reach it
+pub async fn upsert_github_project_link(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpsertGitHubProjectLink>,
+) -> Result<ResponseJson<ApiResponse<GitHubProjectLinkResponse>>, StatusCode> {
+    let Some(url) = ProjectUrl::parse(&payload.project_url) else {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Expected a project URL like https://github.com/orgs/<org>/projects/<number>",
+        )));
+    };
+    let client = match projects_client(&app_state).await {
+        Ok(client) => client,
+        Err(e) => return Ok(ResponseJson(ApiResponse::error(&e))),
+    };
+    let board = match client.find_board(&url).await {
+        Ok(board) => board,
+        Err(e) => {
+            tracing::warn!(
+                "Failed to look up GitHub project {}: {}",
+                payload.project_url,
+                e
+            );
+            return Ok(ResponseJson(ApiResponse::error(&error_message(&e))));
+        }
+    };
+
+    match GitHubProjectLink::upsert(
+        &app_state.db_pool,
+        project.id,
+        payload.project_url.trim(),
+        &board.id,
+        &payload.status_mapping,
+    )
+    .await
+    {
+        Ok(link) => {
+            app_state
+                .track_analytics_event(
+                    "github_project_linked",
+                    Some(serde_json::json!({
+                        "project_id": project.id.to_string(),
+                        "has_status_field": board.status_field_id.is_some(),
+                    })),
+                )
+                .await;
+            Ok(ResponseJson(ApiResponse::success(
+                GitHubProjectLinkResponse { link, board },
+            )))
+        }
+        Err(e) => {
+            tracing::error!("Failed to save GitHub project link: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn delete_github_project_link(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match GitHubProjectLink::delete(&app_state.db_pool, project.id).await {
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => {
+            tracing::error!("Failed to delete GitHub project link: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Sync now instead of waiting for the background service
+pub async fn sync_github_project(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<GitHubProjectSyncResult>>, StatusCode> {
+    let link = match GitHubProjectLink::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(Some(link)) => link,
+        Ok(None) => {
+            return Ok(ResponseJson(ApiResponse::error(
+                "This project is not linked to a GitHub project",
+            )))
+        }
+        Err(e) => {
+            tracing::error!("Failed to load GitHub project link: {}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    let client = match projects_client(&app_state).await {
+        Ok(client) => client,
+        Err(e) => return Ok(ResponseJson(ApiResponse::error(&e))),
+    };
+
+    let outcome = sync_project(&app_state.db_pool, &client, &link).await;
+    let error = outcome.as_ref().err().map(error_message);
+    if let Err(e) =
+        GitHubProjectLink::record_sync(&app_state.db_pool, project.id, error.as_deref()).await
+    {
+        tracing::error!("Failed to record GitHub Projects sync: {}", e);
+    }
+    match outcome {
+        Ok(result) => Ok(ResponseJson(ApiResponse::success(result))),
+        Err(_) => Ok(ResponseJson(ApiResponse::error(&error.unwrap_or_default()))),
+    }
+}
+
+pub fn github_projects_router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/projects/:id/github-project",
+            get(get_github_project_link)
+                .put(upsert_github_project_link)
+                .delete(delete_github_project_link),
+        )
+        .route(
+            "/projects/:id/github-project/sync",
+            post(sync_github_project),
+        )
+}
@@ -1,7 +1,86 @@
-use axum::response::Json;
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+    routing::get,
+    Router,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
-use crate::models::ApiResponse;
+use crate::{
+    app_state::AppState,
+    metrics,
+    models::ApiResponse,
+    services::diagnostics::{self, CheckStatus, Diagnostics},
+};
 
-pub async fn health_check() -> Json<ApiResponse<String>> {
-    Json(ApiResponse::success("OK".to_string()))
+/// Version of the REST API. Bumped when an endpoint changes in a way that breaks existing
+/// clients, so `api_client` can refuse to talk to a server it doesn't understand.
+pub const API_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ServerVersion {
+    /// The vibe-kanban release
+    pub version: String,
+    pub api_version: u32,
+}
+
+/// GET /api/health, the checks cheap enough for a supervisor to poll. Answers 503 when one
+/// of them failed, so a plain HTTP probe sees it too.
+pub async fn health_check(
+    State(app_state): State<AppState>,
+) -> (StatusCode, Json<ApiResponse<Diagnostics>>) {
+    report(diagnostics::quick_checks(&app_state.db_pool).await)
+}
+
+/// GET /api/diagnostics, every check including the configured executors' versions
+pub async fn get_diagnostics(
+    State(app_state): State<AppState>,
+) -> (StatusCode, Json<ApiResponse<Diagnostics>>) {
+    let default_executor = app_state.get_config().read().await.executor.clone();
+    report(diagnostics::full_checks(&app_state.db_pool, &default_executor).await)
+}
+
+fn report(diagnostics: Diagnostics) -> (StatusCode, Json<ApiResponse<Diagnostics>>) {
+    let status = if diagnostics.status == CheckStatus::Error {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+    (status, Json(ApiResponse::success(diagnostics)))
+}
+
+/// GET /api/version, so clients can check they speak the server's API
+pub async fn get_version() -> Json<ApiResponse<ServerVersion>> {
+    Json(ApiResponse::success(ServerVersion {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        api_version: API_VERSION,
+    }))
+}
+
+/// GET /metrics, in the Prometheus text format
+pub async fn get_metrics(State(app_state): State<AppState>) -> Result<Response, StatusCode> {
+    match metrics::render(&app_state.db_pool).await {
+        Ok(body) => Ok((
+            [(
+                header::CONTENT_TYPE,
+                "text/plain; version=0.0.4; charset=utf-8",
+            )],
+            body,
+        )
+            .into_response()),
+        Err(e) => {
+            tracing::error!("Failed to collect metrics: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// The checks and the version, public and served under both `/api` and `/api/v1`
+pub fn health_router() -> Router<AppState> {
+    Router::new()
+        .route("/health", get(health_check))
+        .route("/diagnostics", get(get_diagnostics))
+        .route("/version", get(get_version))
 }
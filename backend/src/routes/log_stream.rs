@@ -0,0 +1,220 @@
+use std::{collections::HashMap, time::Duration};
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    response::Response,
+    Extension,
+};
+use futures_util::future::select_all;
+use serde::Serialize;
+use tokio::sync::broadcast;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task_attempts::{normalize_live_process_logs, ProcessLogsResponse};
+use crate::{
+    app_state::AppState,
+    command_runner,
+    executor::NormalizedEntry,
+    models::{
+        execution_process::{ExecutionProcess, ExecutionProcessStatus},
+        task_attempt::TaskAttempt,
+    },
+    services::command_approval::PendingApproval,
+};
+
+/// How often the attempt is checked without new output, to pick up processes that start
+/// and statuses that change
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Sent as JSON text frames on the attempt log stream
+#[derive(Debug, Serialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(export)]
+pub enum LogStreamMessage {
+    /// A process's whole log: sent when the process is first seen, when its status
+    /// changes, and when its entries no longer just extend what was sent
+    Snapshot { process: ProcessLogsResponse },
+    /// Entries added to the end of a process's log since the last message for it
+    Entries {
+        process_id: Uuid,
+        entries: Vec<NormalizedEntry>,
+    },
+    /// The command a process is waiting to be allowed or denied, sent when it starts
+    /// waiting and again with `None` once it's been answered
+    Approval {
+        process_id: Uuid,
+        approval: Option<PendingApproval>,
+    },
+}
+
+/// What a client has been sent for one process
+struct SentLog {
+    status: ExecutionProcessStatus,
+    output_len: usize,
+    /// Type and content of each entry; timestamps of stderr entries change on every
+    /// normalization, so they can't tell whether an entry is new
+    entries: Vec<(String, String)>,
+}
+
+fn entry_key(entry: &NormalizedEntry) -> (String, String) {
+    (
+        serde_json::to_string(&entry.entry_type).unwrap_or_default(),
+        entry.content.clone(),
+    )
+}
+
+fn output_len(process: &ExecutionProcess) -> usize {
+    process.stdout.as_ref().map_or(0, String::len) + process.stderr.as_ref().map_or(0, String::len)
+}
+
+/// Upgrade to a WebSocket that pushes the attempt's normalized logs as they're produced:
+/// a snapshot of every process first, then the entries each running process adds
+pub async fn stream_attempt_logs(
+    ws: WebSocketUpgrade,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Response {
+    ws.on_upgrade(move |socket| run_log_stream(socket, app_state, task_attempt.id))
+}
+
+async fn run_log_stream(mut socket: WebSocket, app_state: AppState, attempt_id: Uuid) {
+    let mut sent: HashMap<Uuid, SentLog> = HashMap::new();
+    // The approval each process was last sent as waiting on
+    let mut sent_approvals: HashMap<Uuid, Option<Uuid>> = HashMap::new();
+    let mut subscriptions: HashMap<Uuid, broadcast::Receiver<()>> = HashMap::new();
+
+    loop {
+        let processes =
+            match ExecutionProcess::find_by_task_attempt_id(&app_state.db_pool, attempt_id).await {
+                Ok(processes) => processes,
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to load execution processes for log stream of attempt {}: {}",
+                        attempt_id,
+                        e
+                    );
+                    break;
+                }
+            };
+
+        for process in processes {
+            let process_id = process.id;
+            if process.status.is_live() && !subscriptions.contains_key(&process_id) {
+                if let Some(receiver) = command_runner::subscribe_output(process_id) {
+                    subscriptions.insert(process_id, receiver);
+                }
+            }
+            let log = next_message(&app_state, &mut sent, process).await;
+            let approval = approval_message(&app_state, &mut sent_approvals, process_id).await;
+            for message in [log, approval].into_iter().flatten() {
+                let text = match serde_json::to_string(&message) {
+                    Ok(text) => text,
+                    Err(e) => {
+                        tracing::error!("Failed to serialize log stream message: {}", e);
+                        continue;
+                    }
+                };
+                if socket.send(Message::Text(text)).await.is_err() {
+                    return;
+                }
+            }
+        }
+
+        tokio::select! {
+            () = output_written(&mut subscriptions) => {}
+            () = tokio::time::sleep(IDLE_CHECK_INTERVAL) => {}
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    let _ = socket.close().await;
+}
+
+/// The message bringing the client up to date on `process`, if it's missing anything
+async fn next_message(
+    app_state: &AppState,
+    sent: &mut HashMap<Uuid, SentLog>,
+    process: ExecutionProcess,
+) -> Option<LogStreamMessage> {
+    let output_len = output_len(&process);
+    if let Some(previous) = sent.get(&process.id) {
+        if previous.status == process.status && previous.output_len == output_len {
+            return None;
+        }
+    }
+
+    let conversation = normalize_live_process_logs(app_state, &process).await;
+    let entries: Vec<_> = conversation.entries.iter().map(entry_key).collect();
+    let previous = sent.insert(
+        process.id,
+        SentLog {
+            status: process.status.clone(),
+            output_len,
+            entries: entries.clone(),
+        },
+    );
+
+    match previous {
+        Some(previous)
+            if previous.status == process.status && entries.starts_with(&previous.entries) =>
+        {
+            if entries.len() == previous.entries.len() {
+                return None;
+            }
+            Some(LogStreamMessage::Entries {
+                process_id: process.id,
+                entries: conversation.entries[previous.entries.len()..].to_vec(),
+            })
+        }
+        _ => Some(LogStreamMessage::Snapshot {
+            process: ProcessLogsResponse {
+                id: process.id,
+                process_type: process.process_type,
+                command: process.command,
+                executor_type: process.executor_type,
+                status: process.status,
+                normalized_conversation: conversation,
+            },
+        }),
+    }
+}
+
+/// The process's pending approval, if it changed since the client was last told
+async fn approval_message(
+    app_state: &AppState,
+    sent_approvals: &mut HashMap<Uuid, Option<Uuid>>,
+    process_id: Uuid,
+) -> Option<LogStreamMessage> {
+    let approval = app_state.pending_approval(process_id).await;
+    let approval_id = approval.as_ref().map(|approval| approval.id);
+    let previous = sent_approvals.insert(process_id, approval_id);
+    if previous.flatten() == approval_id {
+        return None;
+    }
+    Some(LogStreamMessage::Approval {
+        process_id,
+        approval,
+    })
+}
+
+/// Wait until one of the subscribed executions writes output. Channels of executions that
+/// stopped are dropped, which also wakes the stream so it sends their final status.
+async fn output_written(subscriptions: &mut HashMap<Uuid, broadcast::Receiver<()>>) {
+    if subscriptions.is_empty() {
+        return std::future::pending().await;
+    }
+    let waits = subscriptions
+        .iter_mut()
+        .map(|(id, receiver)| Box::pin(async move { (*id, receiver.recv().await) }));
+    let ((id, result), _, _) = select_all(waits).await;
+    if let Err(broadcast::error::RecvError::Closed) = result {
+        subscriptions.remove(&id);
+    }
+}
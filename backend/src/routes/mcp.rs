@@ -0,0 +1,56 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::sse::{Event as SseEvent, KeepAlive, Sse},
+    routing::{get, post},
+    Json, Router,
+};
+use futures_util::stream::Stream;
+use rmcp::model::ClientJsonRpcMessage;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{app_state::AppState, mcp::sse};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpMessageQuery {
+    session_id: Uuid,
+}
+
+/// GET /api/mcp/sse
+///
+/// Opens an MCP session. The first event names the endpoint to post the session's
+/// messages to; the task server's replies follow as `message` events.
+pub async fn mcp_sse(
+    State(app_state): State<AppState>,
+) -> Sse<impl Stream<Item = Result<SseEvent, axum::Error>>> {
+    let mut session = sse::open_session(app_state.db_pool.clone()).await;
+    let stream = async_stream::stream! {
+        yield Ok(SseEvent::default()
+            .event("endpoint")
+            .data(format!("/api/mcp/message?sessionId={}", session.id)));
+        while let Some(message) = session.messages.recv().await {
+            yield SseEvent::default().event("message").json_data(&message);
+        }
+    };
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// POST /api/mcp/message?sessionId=...
+pub async fn mcp_message(
+    Query(query): Query<McpMessageQuery>,
+    Json(message): Json<ClientJsonRpcMessage>,
+) -> StatusCode {
+    if sse::deliver(query.session_id, message).await {
+        StatusCode::ACCEPTED
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
+pub fn mcp_router() -> Router<AppState> {
+    Router::new()
+        .route("/mcp/sse", get(mcp_sse))
+        .route("/mcp/message", post(mcp_message))
+}
@@ -0,0 +1,79 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+    routing::{get, post},
+    Extension, Router,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    models::{mention::Mention, user::User, ApiResponse},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct MentionsQuery {
+    pub unread: Option<bool>,
+}
+
+/// The signed-in user's mentions. Without sign-in there is no one to mention, so the
+/// inbox is empty.
+pub async fn get_mentions(
+    State(app_state): State<AppState>,
+    user: Option<Extension<User>>,
+    Query(query): Query<MentionsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<Mention>>>, StatusCode> {
+    let Some(Extension(user)) = user else {
+        return Ok(ResponseJson(ApiResponse::success(Vec::new())));
+    };
+    match Mention::find_by_user_id(&app_state.db_pool, user.id, query.unread.unwrap_or(false)).await
+    {
+        Ok(mentions) => Ok(ResponseJson(ApiResponse::success(mentions))),
+        Err(e) => {
+            tracing::error!("Failed to load mentions of user {}: {}", user.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn mark_mention_read(
+    Path(mention_id): Path<Uuid>,
+    State(app_state): State<AppState>,
+    user: Option<Extension<User>>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    let Some(Extension(user)) = user else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+    match Mention::mark_read(&app_state.db_pool, user.id, mention_id).await {
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => {
+            tracing::error!("Failed to mark mention {} read: {}", mention_id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn mark_all_mentions_read(
+    State(app_state): State<AppState>,
+    user: Option<Extension<User>>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    let Some(Extension(user)) = user else {
+        return Ok(ResponseJson(ApiResponse::success(())));
+    };
+    match Mention::mark_all_read(&app_state.db_pool, user.id).await {
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => {
+            tracing::error!("Failed to mark mentions of user {} read: {}", user.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub fn mentions_router() -> Router<AppState> {
+    Router::new()
+        .route("/mentions", get(get_mentions))
+        .route("/mentions/read", post(mark_all_mentions_read))
+        .route("/mentions/:mention_id/read", post(mark_mention_read))
+}
@@ -1,10 +1,52 @@
+pub mod api_tokens;
+pub mod attempt_groups;
+pub mod attempt_reviews;
+pub mod attempt_rollbacks;
+pub mod attention;
 pub mod auth;
+pub mod benchmarks;
+pub mod board_columns;
 pub mod config;
+pub mod custom_fields;
+pub mod entry_bookmarks;
+pub mod environment_variables;
+pub mod events;
+pub mod execution_queue;
+pub mod execution_snapshots;
+pub mod executor_profiles;
 pub mod filesystem;
+pub mod follow_up_drafts;
+pub mod git_host;
 pub mod github;
+pub mod github_issues;
+pub mod github_projects;
 pub mod health;
+pub mod log_stream;
+pub mod mcp;
+pub mod mentions;
+pub mod notifications;
+pub mod notion;
+pub mod openapi;
+pub mod plugins;
+pub mod project_container;
+pub mod project_guardrails;
+pub mod project_mcp_servers;
+pub mod project_sandbox;
+pub mod project_security;
+pub mod project_shell_environment;
+pub mod project_worker;
 pub mod projects;
+pub mod releases;
+pub mod review_comments;
+pub mod script_hooks;
+pub mod search;
+pub mod share_links;
 pub mod stream;
+pub mod task_attachments;
 pub mod task_attempts;
+pub mod task_bulk;
 pub mod task_templates;
 pub mod tasks;
+pub mod terminal;
+pub mod users;
+pub mod webhooks;
@@ -0,0 +1,165 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+    routing::{get, put},
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    models::{
+        notification_delivery::NotificationDelivery,
+        notification_webhook::{NotificationWebhook, UpsertNotificationWebhook},
+        project::Project,
+        ApiResponse,
+    },
+};
+
+const DEFAULT_LOG_LIMIT: i64 = 100;
+const MAX_LOG_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct NotificationLogQuery {
+    pub limit: Option<i64>,
+}
+
+fn validate_url(url: &str) -> Result<(), String> {
+    match reqwest::Url::parse(url) {
+        Ok(url) if url.scheme() == "https" || url.scheme() == "http" => Ok(()),
+        Ok(_) => Err("Webhook URL must use http or https".to_string()),
+        Err(e) => Err(format!("Invalid webhook URL: {}", e)),
+    }
+}
+
+pub async fn get_notification_webhooks(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<NotificationWebhook>>>, StatusCode> {
+    match NotificationWebhook::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(webhooks) => Ok(ResponseJson(ApiResponse::success(webhooks))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to load notification webhooks of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn create_notification_webhook(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpsertNotificationWebhook>,
+) -> Result<ResponseJson<ApiResponse<NotificationWebhook>>, StatusCode> {
+    if let Err(e) = validate_url(&payload.url) {
+        return Ok(ResponseJson(ApiResponse::error(&e)));
+    }
+
+    match NotificationWebhook::create(&app_state.db_pool, project.id, &payload).await {
+        Ok(webhook) => {
+            app_state
+                .track_analytics_event(
+                    "notification_webhook_created",
+                    Some(serde_json::json!({
+                        "project_id": project.id.to_string(),
+                        "format": webhook.format,
+                    })),
+                )
+                .await;
+            Ok(ResponseJson(ApiResponse::success(webhook)))
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to create notification webhook for project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn update_notification_webhook(
+    Path(webhook_id): Path<Uuid>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpsertNotificationWebhook>,
+) -> Result<ResponseJson<ApiResponse<NotificationWebhook>>, StatusCode> {
+    if let Err(e) = validate_url(&payload.url) {
+        return Ok(ResponseJson(ApiResponse::error(&e)));
+    }
+
+    match NotificationWebhook::update(&app_state.db_pool, webhook_id, &payload).await {
+        Ok(Some(webhook)) => Ok(ResponseJson(ApiResponse::success(webhook))),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!(
+                "Failed to update notification webhook {}: {}",
+                webhook_id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn delete_notification_webhook(
+    Path(webhook_id): Path<Uuid>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match NotificationWebhook::delete(&app_state.db_pool, webhook_id).await {
+        Ok(0) => Err(StatusCode::NOT_FOUND),
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to delete notification webhook {}: {}",
+                webhook_id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// The notifications sent for the project, desktop and webhook, newest first
+pub async fn get_notification_log(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Query(query): Query<NotificationLogQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<NotificationDelivery>>>, StatusCode> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_LOG_LIMIT)
+        .clamp(1, MAX_LOG_LIMIT);
+    match NotificationDelivery::find_by_project_id(&app_state.db_pool, project.id, limit).await {
+        Ok(deliveries) => Ok(ResponseJson(ApiResponse::success(deliveries))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to load notifications of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub fn project_notifications_router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/projects/:id/notification-webhooks",
+            get(get_notification_webhooks).post(create_notification_webhook),
+        )
+        .route("/projects/:id/notifications", get(get_notification_log))
+}
+
+pub fn notification_webhooks_router() -> Router<AppState> {
+    Router::new().route(
+        "/notification-webhooks/:webhook_id",
+        put(update_notification_webhook).delete(delete_notification_webhook),
+    )
+}
@@ -0,0 +1,165 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::Json as ResponseJson,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::{
+    app_state::AppState,
+    models::{
+        notion_sync::{NotionLink, UpsertNotionLink},
+        project::Project,
+        ApiResponse,
+    },
+    services::notion_sync::{
+        parse_database_id, sync_project, NotionClient, NotionDatabase, NotionSyncResult,
+    },
+};
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct NotionLinkResponse {
+    pub link: NotionLink,
+    pub database: NotionDatabase,
+}
+
+const TOKEN_MISSING: &str = "Add a Notion integration token in Settings first.";
+
+async fn notion_client(app_state: &AppState) -> Result<NotionClient, String> {
+    let token = app_state.get_config().read().await.notion.token.clone();
+    token
+        .map(|token| NotionClient::new(&token))
+        .ok_or_else(|| TOKEN_MISSING.to_string())
+}
+
+pub async fn get_notion_link(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Option<NotionLink>>>, StatusCode> {
+    match NotionLink::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(link) => Ok(ResponseJson(ApiResponse::success(link))),
+        Err(e) => {
+            tracing::error!("Failed to load Notion link for {}: {}", project.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Link the project to a database, checking that the integration can see it and that the
+/// property mapping fits its schema
+pub async fn upsert_notion_link(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpsertNotionLink>,
+) -> Result<ResponseJson<ApiResponse<NotionLinkResponse>>, StatusCode> {
+    let Some(database_id) = parse_database_id(&payload.database) else {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Expected a Notion database URL or id",
+        )));
+    };
+    let client = match notion_client(&app_state).await {
+        Ok(client) => client,
+        Err(e) => return Ok(ResponseJson(ApiResponse::error(&e))),
+    };
+    let database = match client.get_database(&database_id).await {
+        Ok(database) => database,
+        Err(e) => {
+            tracing::warn!("Failed to look up Notion database {}: {}", database_id, e);
+            return Ok(ResponseJson(ApiResponse::error(&e.to_string())));
+        }
+    };
+    if let Err(e) = database.validate(&payload.property_mapping) {
+        return Ok(ResponseJson(ApiResponse::error(&e.to_string())));
+    }
+
+    match NotionLink::upsert(
+        &app_state.db_pool,
+        project.id,
+        &database.id,
+        &payload.property_mapping,
+    )
+    .await
+    {
+        Ok(link) => {
+            app_state
+                .track_analytics_event(
+                    "notion_database_linked",
+                    Some(serde_json::json!({
+                        "project_id": project.id.to_string(),
+                        "syncs_description": link.property_mapping.description.is_some(),
+                    })),
+                )
+                .await;
+            Ok(ResponseJson(ApiResponse::success(NotionLinkResponse {
+                link,
+                database,
+            })))
+        }
+        Err(e) => {
+            tracing::error!("Failed to save Notion link: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn delete_notion_link(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match NotionLink::delete(&app_state.db_pool, project.id).await {
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => {
+            tracing::error!("Failed to delete Notion link: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Sync now instead of waiting for the background service
+pub async fn sync_notion(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<NotionSyncResult>>, StatusCode> {
+    let link = match NotionLink::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(Some(link)) => link,
+        Ok(None) => {
+            return Ok(ResponseJson(ApiResponse::error(
+                "This project is not linked to a Notion database",
+            )))
+        }
+        Err(e) => {
+            tracing::error!("Failed to load Notion link: {}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    let client = match notion_client(&app_state).await {
+        Ok(client) => client,
+        Err(e) => return Ok(ResponseJson(ApiResponse::error(&e))),
+    };
+
+    let outcome = sync_project(&app_state.db_pool, &client, &link).await;
+    let error = outcome.as_ref().err().map(|e| e.to_string());
+    if let Err(e) = NotionLink::record_sync(&app_state.db_pool, project.id, error.as_deref()).await
+    {
+        tracing::error!("Failed to record Notion sync: {}", e);
+    }
+    match outcome {
+        Ok(result) => Ok(ResponseJson(ApiResponse::success(result))),
+        Err(_) => Ok(ResponseJson(ApiResponse::error(&error.unwrap_or_default()))),
+    }
+}
+
+pub fn notion_router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/projects/:id/notion",
+            get(get_notion_link)
+                .put(upsert_notion_link)
+                .delete(delete_notion_link),
+        )
+        .route("/projects/:id/notion/sync", post(sync_notion))
+}
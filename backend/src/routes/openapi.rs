@@ -0,0 +1,333 @@
+//! The OpenAPI document of the REST API, served at `/api/openapi.json` so integrations can
+//! generate typed clients instead of reading the handlers. Each operation is added with its
+//! handler, and its request and response schemas are derived with `schemars` from the
+//! handler's own signature, so changing what a handler takes or answers changes the
+//! document with it. A test checks every documented path is still routed.
+//!
+//! It covers what scripting clients need, as the `vibe` client uses it: projects, tasks,
+//! attempts with their logs, diff and merge, and tracker imports, all under `/api/v1`. The
+//! rest of the API isn't described.
+
+use std::{collections::BTreeMap, future::Future};
+
+use axum::{http::StatusCode, response::Json};
+use schemars::{
+    gen::{SchemaGenerator, SchemaSettings},
+    JsonSchema,
+};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    models::ApiResponse,
+    routes::{
+        health::{self, API_VERSION},
+        projects, task_attempts, tasks,
+    },
+};
+
+const PROJECT: &str = "/projects/{project_id}";
+const TASK: &str = "/projects/{project_id}/tasks/{task_id}";
+const ATTEMPT: &str = "/projects/{project_id}/tasks/{task_id}/attempts/{attempt_id}";
+
+/// GET /api/openapi.json
+pub async fn get_openapi() -> Json<Value> {
+    Json(document())
+}
+
+pub fn document() -> Value {
+    let mut doc = ApiDoc::new();
+
+    doc.get(
+        "/version",
+        "The server's release and the API version it speaks",
+        health::get_version,
+    );
+    doc.get(
+        "/health",
+        "Database, migrations, disk space and git checks; 503 when one failed",
+        health::health_check,
+    );
+    doc.get(
+        "/diagnostics",
+        "The health checks plus whether each configured executor can run",
+        health::get_diagnostics,
+    );
+
+    doc.get("/projects", "List projects", projects::get_projects);
+    doc.post_json("/projects", "Register a project", projects::create_project);
+    doc.get(PROJECT, "Get a project", projects::get_project);
+
+    let tasks = format!("{}/tasks", PROJECT);
+    doc.get(&tasks, "List the project's tasks", tasks::get_project_tasks);
+    doc.post_json(&tasks, "Create a task", tasks::create_task);
+    doc.get(TASK, "Get a task", tasks::get_task);
+    doc.post_json(
+        &format!("{}/tasks/import/tracker", PROJECT),
+        "Import the open issues of a Jira project or Linear team as tasks",
+        tasks::import_from_tracker,
+    );
+    doc.post(
+        &format!("{}/tasks/import/tracker/sync", PROJECT),
+        "Fetch the issues updated since the last import of every tracker source",
+        tasks::sync_tracker_imports,
+    );
+
+    let attempts = format!("{}/attempts", TASK);
+    doc.get(
+        &attempts,
+        "List the task's attempts",
+        task_attempts::get_task_attempts,
+    );
+    doc.post_json(
+        &attempts,
+        "Start an attempt",
+        task_attempts::create_task_attempt,
+    );
+    doc.get(
+        &format!("{}/logs", ATTEMPT),
+        "The normalized conversation of every execution process of the attempt",
+        task_attempts::get_task_attempt_all_logs,
+    );
+    doc.get(
+        &format!("{}/diff", ATTEMPT),
+        "The attempt's changes against its base branch",
+        task_attempts::get_task_attempt_diff,
+    );
+    doc.post(
+        &format!("{}/merge", ATTEMPT),
+        "Merge the attempt into its base branch",
+        task_attempts::merge_task_attempt,
+    );
+
+    doc.finish()
+}
+
+/// What a handler answers with inside the `ApiResponse` envelope, read off its return type
+pub trait Answer {
+    type Data;
+}
+
+impl<T> Answer for Json<ApiResponse<T>> {
+    type Data = T;
+}
+
+impl<T> Answer for Result<Json<ApiResponse<T>>, StatusCode> {
+    type Data = T;
+}
+
+impl<T> Answer for (StatusCode, Json<ApiResponse<T>>) {
+    type Data = T;
+}
+
+/// An async handler answering with an [`Answer`]. `Args` are its extractors, which tells
+/// apart the implementations for each number of them.
+pub trait Handler<Args> {
+    type Data;
+}
+
+/// A handler whose last extractor is its JSON request body
+pub trait JsonHandler<Args> {
+    type Body;
+}
+
+macro_rules! impl_handler {
+    ($($arg:ident),*) => {
+        impl<F, Fut, $($arg,)*> Handler<($($arg,)*)> for F
+        where
+            F: Fn($($arg),*) -> Fut,
+            Fut: Future,
+            Fut::Output: Answer,
+        {
+            type Data = <Fut::Output as Answer>::Data;
+        }
+
+        impl<F, Fut, $($arg,)* B> JsonHandler<($($arg,)* Json<B>,)> for F
+        where
+            F: Fn($($arg,)* Json<B>) -> Fut,
+        {
+            type Body = B;
+        }
+    };
+}
+
+impl_handler!();
+impl_handler!(T1);
+impl_handler!(T1, T2);
+impl_handler!(T1, T2, T3);
+impl_handler!(T1, T2, T3, T4);
+impl_handler!(T1, T2, T3, T4, T5);
+
+/// Collects operations, and the schemas they refer to as components
+struct ApiDoc {
+    generator: SchemaGenerator,
+    paths: BTreeMap<String, Map<String, Value>>,
+}
+
+impl ApiDoc {
+    fn new() -> Self {
+        Self {
+            generator: SchemaSettings::openapi3().into_generator(),
+            paths: BTreeMap::new(),
+        }
+    }
+
+    /// A reference to `T`'s schema, or `None` for `()`
+    fn schema<T: JsonSchema>(&mut self) -> Option<Value> {
+        if T::schema_name() == <()>::schema_name() {
+            return None;
+        }
+        serde_json::to_value(self.generator.subschema_for::<T>()).ok()
+    }
+
+    fn get<H: Handler<Args>, Args>(&mut self, path: &str, summary: &str, _handler: H)
+    where
+        H::Data: JsonSchema,
+    {
+        let response = self.schema::<H::Data>();
+        self.add("get", path, summary, None, response);
+    }
+
+    /// A POST without a request body
+    fn post<H: Handler<Args>, Args>(&mut self, path: &str, summary: &str, _handler: H)
+    where
+        H::Data: JsonSchema,
+    {
+        let response = self.schema::<H::Data>();
+        self.add("post", path, summary, None, response);
+    }
+
+    fn post_json<H, Args, BodyArgs>(&mut self, path: &str, summary: &str, _handler: H)
+    where
+        H: Handler<Args> + JsonHandler<BodyArgs>,
+        <H as Handler<Args>>::Data: JsonSchema,
+        <H as JsonHandler<BodyArgs>>::Body: JsonSchema,
+    {
+        let request = self.schema::<<H as JsonHandler<BodyArgs>>::Body>();
+        let response = self.schema::<<H as Handler<Args>>::Data>();
+        self.add("post", path, summary, request, response);
+    }
+
+    fn add(
+        &mut self,
+        method: &str,
+        path: &str,
+        summary: &str,
+        request: Option<Value>,
+        response: Option<Value>,
+    ) {
+        let mut operation = json!({
+            "summary": summary,
+            "parameters": path_parameters(path),
+            "responses": {
+                "200": {
+                    "description": "The `ApiResponse` envelope; `success` is false with a \
+                                    `message` when the request can't be done",
+                    "content": { "application/json": { "schema": envelope(response) } },
+                },
+                "500": { "description": "The server failed to handle the request" },
+            },
+        });
+        if let Some(request) = request {
+            operation["requestBody"] = json!({
+                "required": true,
+                "content": { "application/json": { "schema": request } },
+            });
+        }
+        self.paths
+            .entry(path.to_string())
+            .or_default()
+            .insert(method.to_string(), operation);
+    }
+
+    fn finish(mut self) -> Value {
+        json!({
+            "openapi": "3.0.3",
+            "info": {
+                "title": "vibe-kanban",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "servers": [{ "url": format!("/api/v{}", API_VERSION) }],
+            "paths": self.paths,
+            "components": { "schemas": self.generator.take_definitions() },
+        })
+    }
+}
+
+/// Every `{name}` segment of `path`, all of them ids
+fn path_parameters(path: &str) -> Vec<Value> {
+    path.split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+        .map(|name| {
+            json!({
+                "name": name,
+                "in": "path",
+                "required": true,
+                "schema": { "type": "string", "format": "uuid" },
+            })
+        })
+        .collect()
+}
+
+/// The `ApiResponse` every endpoint answers with, around `data`
+fn envelope(data: Option<Value>) -> Value {
+    let mut properties = json!({
+        "success": { "type": "boolean" },
+        "message": { "type": "string", "nullable": true },
+    });
+    if let Some(data) = data {
+        properties["data"] = data;
+    }
+    json!({
+        "type": "object",
+        "required": ["success"],
+        "properties": properties,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect_refs<'a>(value: &'a Value, refs: &mut Vec<&'a str>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(reference)) = map.get("$ref") {
+                    refs.push(reference);
+                }
+                map.values().for_each(|v| collect_refs(v, refs));
+            }
+            Value::Array(items) => items.iter().for_each(|v| collect_refs(v, refs)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_document_describes_operations_and_resolves_every_schema() {
+        let doc = document();
+
+        let create_task = &doc["paths"]["/projects/{project_id}/tasks"]["post"];
+        assert_eq!(create_task["parameters"].as_array().unwrap().len(), 1);
+        assert!(create_task["requestBody"].is_object());
+        let merge = &doc["paths"][format!("{}/merge", ATTEMPT)]["post"];
+        assert_eq!(merge["parameters"].as_array().unwrap().len(), 3);
+        assert!(
+            merge["responses"]["200"]["content"]["application/json"]["schema"]["properties"]
+                .get("data")
+                .is_none()
+        );
+
+        let mut refs = Vec::new();
+        collect_refs(&doc, &mut refs);
+        assert!(!refs.is_empty());
+        for reference in refs {
+            let name = reference
+                .strip_prefix("#/components/schemas/")
+                .unwrap_or_else(|| panic!("unexpected reference {}", reference));
+            assert!(
+                doc["components"]["schemas"].get(name).is_some(),
+                "{} isn't defined",
+                name
+            );
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use axum::{response::Json as ResponseJson, routing::get, Router};
+
+use crate::{
+    app_state::AppState,
+    models::ApiResponse,
+    services::plugins::{self, PluginInfo},
+};
+
+/// The plugins the server loaded at startup
+pub async fn get_plugins() -> ResponseJson<ApiResponse<Vec<PluginInfo>>> {
+    ResponseJson(ApiResponse::success(plugins::host().plugins()))
+}
+
+pub fn plugins_router() -> Router<AppState> {
+    Router::new().route("/plugins", get(get_plugins))
+}
@@ -0,0 +1,71 @@
+use axum::{
+    extract::State, http::StatusCode, response::Json as ResponseJson, routing::get, Extension,
+    Json, Router,
+};
+
+use crate::{
+    app_state::AppState,
+    models::{
+        project::Project,
+        project_container::{container_image_error, ProjectContainer, UpsertProjectContainer},
+        ApiResponse,
+    },
+};
+
+/// The image the project's agents and scripts run in, or `None` when they run on the host
+pub async fn get_project_container(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectContainer>>>, StatusCode> {
+    match ProjectContainer::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(container) => Ok(ResponseJson(ApiResponse::success(container))),
+        Err(e) => {
+            tracing::error!("Failed to load container of project {}: {}", project.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn upsert_project_container(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpsertProjectContainer>,
+) -> Result<ResponseJson<ApiResponse<ProjectContainer>>, StatusCode> {
+    let image = payload.image.trim();
+    if let Some(message) = container_image_error(image) {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+    match ProjectContainer::upsert(&app_state.db_pool, project.id, image).await {
+        Ok(container) => Ok(ResponseJson(ApiResponse::success(container))),
+        Err(e) => {
+            tracing::error!("Failed to save container of project {}: {}", project.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn delete_project_container(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match ProjectContainer::delete(&app_state.db_pool, project.id).await {
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to delete container of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub fn project_container_router() -> Router<AppState> {
+    Router::new().route(
+        "/projects/:id/container",
+        get(get_project_container)
+            .put(upsert_project_container)
+            .delete(delete_project_container),
+    )
+}
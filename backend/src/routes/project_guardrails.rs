@@ -0,0 +1,70 @@
+use axum::{
+    extract::State, http::StatusCode, response::Json as ResponseJson, routing::get, Extension,
+    Json, Router,
+};
+
+use crate::{
+    app_state::AppState,
+    models::{
+        project::Project,
+        project_guardrails::{guardrails_error, ProjectGuardrails, UpsertProjectGuardrails},
+        ApiResponse,
+    },
+    services::guardrails::{self, GuardrailStatus},
+};
+
+/// The project's budget and working hours, with what it's spent this month and whether its
+/// attempts are held back now
+pub async fn get_project_guardrails(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<GuardrailStatus>>, StatusCode> {
+    match guardrails::status(&app_state.db_pool, project.id).await {
+        Ok(status) => Ok(ResponseJson(ApiResponse::success(status))),
+        Err(e) => {
+            tracing::error!("Failed to load guardrails of project {}: {}", project.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn upsert_project_guardrails(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpsertProjectGuardrails>,
+) -> Result<ResponseJson<ApiResponse<GuardrailStatus>>, StatusCode> {
+    if let Some(message) = guardrails_error(&payload) {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+    if let Err(e) = ProjectGuardrails::upsert(&app_state.db_pool, project.id, &payload).await {
+        tracing::error!("Failed to save guardrails of project {}: {}", project.id, e);
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    get_project_guardrails(Extension(project), State(app_state)).await
+}
+
+pub async fn delete_project_guardrails(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match ProjectGuardrails::delete(&app_state.db_pool, project.id).await {
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to delete guardrails of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub fn project_guardrails_router() -> Router<AppState> {
+    Router::new().route(
+        "/projects/:id/guardrails",
+        get(get_project_guardrails)
+            .put(upsert_project_guardrails)
+            .delete(delete_project_guardrails),
+    )
+}
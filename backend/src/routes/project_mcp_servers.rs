@@ -0,0 +1,91 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+    routing::get,
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    models::{
+        project::Project,
+        project_mcp_server::{mcp_server_error, ProjectMcpServer, UpsertProjectMcpServer},
+        ApiResponse,
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct McpServerNameQuery {
+    pub name: String,
+}
+
+/// The MCP servers the project's coding agents are started with
+pub async fn get_project_mcp_servers(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectMcpServer>>>, StatusCode> {
+    match ProjectMcpServer::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(servers) => Ok(ResponseJson(ApiResponse::success(servers))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch MCP servers of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn upsert_project_mcp_server(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpsertProjectMcpServer>,
+) -> Result<ResponseJson<ApiResponse<ProjectMcpServer>>, StatusCode> {
+    if let Some(message) = mcp_server_error(&payload) {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+    match ProjectMcpServer::upsert(&app_state.db_pool, project.id, &payload).await {
+        Ok(server) => Ok(ResponseJson(ApiResponse::success(server))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to save MCP server {} of project {}: {}",
+                payload.name,
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn delete_project_mcp_server(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Query(query): Query<McpServerNameQuery>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match ProjectMcpServer::delete(&app_state.db_pool, project.id, &query.name).await {
+        Ok(true) => Ok(ResponseJson(ApiResponse::success(()))),
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!(
+                "Failed to delete MCP server {} of project {}: {}",
+                query.name,
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub fn project_mcp_servers_router() -> Router<AppState> {
+    Router::new().route(
+        "/projects/:id/mcp-servers",
+        get(get_project_mcp_servers)
+            .put(upsert_project_mcp_server)
+            .delete(delete_project_mcp_server),
+    )
+}
@@ -0,0 +1,75 @@
+use axum::{
+    extract::State, http::StatusCode, response::Json as ResponseJson, routing::get, Extension,
+    Json, Router,
+};
+
+use crate::{
+    app_state::AppState,
+    models::{
+        project::Project,
+        project_sandbox::{ProjectSandbox, UpsertProjectSandbox},
+        ApiResponse,
+    },
+};
+
+/// The project's sandbox setting, or `None` when it follows the global one
+pub async fn get_project_sandbox(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectSandbox>>>, StatusCode> {
+    match ProjectSandbox::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(sandbox) => Ok(ResponseJson(ApiResponse::success(sandbox))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to load sandbox setting of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn upsert_project_sandbox(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpsertProjectSandbox>,
+) -> Result<ResponseJson<ApiResponse<ProjectSandbox>>, StatusCode> {
+    match ProjectSandbox::upsert(&app_state.db_pool, project.id, &payload).await {
+        Ok(sandbox) => Ok(ResponseJson(ApiResponse::success(sandbox))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to save sandbox setting of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn delete_project_sandbox(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match ProjectSandbox::delete(&app_state.db_pool, project.id).await {
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to delete sandbox setting of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub fn project_sandbox_router() -> Router<AppState> {
+    Router::new().route(
+        "/projects/:id/sandbox",
+        get(get_project_sandbox)
+            .put(upsert_project_sandbox)
+            .delete(delete_project_sandbox),
+    )
+}
@@ -0,0 +1,75 @@
+use axum::{
+    extract::State, http::StatusCode, response::Json as ResponseJson, routing::get, Extension,
+    Json, Router,
+};
+
+use crate::{
+    app_state::AppState,
+    models::{
+        project::Project,
+        project_security::{ProjectSecurity, UpsertProjectSecurity},
+        ApiResponse,
+    },
+};
+
+/// The project's sign-in rules, or `None` when it has none
+pub async fn get_project_security(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectSecurity>>>, StatusCode> {
+    match ProjectSecurity::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(security) => Ok(ResponseJson(ApiResponse::success(security))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to load sign-in rules of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn upsert_project_security(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpsertProjectSecurity>,
+) -> Result<ResponseJson<ApiResponse<ProjectSecurity>>, StatusCode> {
+    match ProjectSecurity::upsert(&app_state.db_pool, project.id, &payload).await {
+        Ok(security) => Ok(ResponseJson(ApiResponse::success(security))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to save sign-in rules of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn delete_project_security(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match ProjectSecurity::delete(&app_state.db_pool, project.id).await {
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to delete sign-in rules of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub fn project_security_router() -> Router<AppState> {
+    Router::new().route(
+        "/projects/:id/security",
+        get(get_project_security)
+            .put(upsert_project_security)
+            .delete(delete_project_security),
+    )
+}
@@ -0,0 +1,75 @@
+use axum::{
+    extract::State, http::StatusCode, response::Json as ResponseJson, routing::get, Extension,
+    Json, Router,
+};
+
+use crate::{
+    app_state::AppState,
+    models::{
+        project::Project,
+        project_shell_environment::{ProjectShellEnvironment, UpsertProjectShellEnvironment},
+        ApiResponse,
+    },
+};
+
+/// The project's shell environment setting, or `None` when it follows the global one
+pub async fn get_project_shell_environment(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectShellEnvironment>>>, StatusCode> {
+    match ProjectShellEnvironment::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(shell_environment) => Ok(ResponseJson(ApiResponse::success(shell_environment))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to load shell environment setting of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn upsert_project_shell_environment(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpsertProjectShellEnvironment>,
+) -> Result<ResponseJson<ApiResponse<ProjectShellEnvironment>>, StatusCode> {
+    match ProjectShellEnvironment::upsert(&app_state.db_pool, project.id, &payload).await {
+        Ok(shell_environment) => Ok(ResponseJson(ApiResponse::success(shell_environment))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to save shell environment setting of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn delete_project_shell_environment(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match ProjectShellEnvironment::delete(&app_state.db_pool, project.id).await {
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to delete shell environment setting of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub fn project_shell_environment_router() -> Router<AppState> {
+    Router::new().route(
+        "/projects/:id/shell-environment",
+        get(get_project_shell_environment)
+            .put(upsert_project_shell_environment)
+            .delete(delete_project_shell_environment),
+    )
+}
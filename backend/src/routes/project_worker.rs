@@ -0,0 +1,66 @@
+use axum::{
+    extract::State, http::StatusCode, response::Json as ResponseJson, routing::get, Extension,
+    Json, Router,
+};
+
+use crate::{
+    app_state::AppState,
+    models::{
+        project::Project,
+        project_worker::{worker_error, ProjectWorker, UpsertProjectWorker},
+        ApiResponse,
+    },
+};
+
+/// The worker the project's agents and scripts run on, or `None` when they run here
+pub async fn get_project_worker(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectWorker>>>, StatusCode> {
+    match ProjectWorker::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(worker) => Ok(ResponseJson(ApiResponse::success(worker))),
+        Err(e) => {
+            tracing::error!("Failed to load worker of project {}: {}", project.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn upsert_project_worker(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpsertProjectWorker>,
+) -> Result<ResponseJson<ApiResponse<ProjectWorker>>, StatusCode> {
+    if let Some(message) = worker_error(&payload) {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+    match ProjectWorker::upsert(&app_state.db_pool, project.id, &payload).await {
+        Ok(worker) => Ok(ResponseJson(ApiResponse::success(worker))),
+        Err(e) => {
+            tracing::error!("Failed to save worker of project {}: {}", project.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn delete_project_worker(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match ProjectWorker::delete(&app_state.db_pool, project.id).await {
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => {
+            tracing::error!("Failed to delete worker of project {}: {}", project.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub fn project_worker_router() -> Router<AppState> {
+    Router::new().route(
+        "/projects/:id/worker",
+        get(get_project_worker)
+            .put(upsert_project_worker)
+            .delete(delete_project_worker),
+    )
+}
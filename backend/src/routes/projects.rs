@@ -1,29 +1,72 @@
 use std::collections::HashMap;
 
 use axum::{
-    extract::{Query, State},
-    http::StatusCode,
-    response::Json as ResponseJson,
+    extract::{DefaultBodyLimit, Query, State},
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Json as ResponseJson, Response},
     routing::get,
     Extension, Json, Router,
 };
+use serde::Deserialize;
 use uuid::Uuid;
 
 use crate::{
     app_state::AppState,
+    execution_monitor,
+    executor::ExecutorConfig,
     models::{
+        conversation_search::{
+            rank_hits, AttemptTaskRef, ConversationQuery, ConversationSearchHit,
+        },
+        execution_analytics::ProjectAnalytics,
+        execution_metrics::{ExecutionMetrics, ProjectUsage},
+        execution_process::ExecutionProcess,
+        merge_queue_entry::MergeQueueEntry,
         project::{
             CreateBranch, CreateProject, GitBranch, Project, ProjectWithBranch, SearchMatchType,
             SearchResult, UpdateProject,
         },
+        project_member::ProjectMember,
+        prompt_template::{PromptTemplate, UpsertPromptTemplate},
+        stale_task::{ProjectStaleTask, StaleTask},
+        task_attempt::TaskAttempt,
+        user::User,
         ApiResponse,
     },
+    services::{
+        changelog::{self, Changelog, ChangelogError, ChangelogQuery},
+        conversation_index::semantic_search,
+        embeddings::EmbeddingsClient,
+        file_mentions::{self, CheckFileMentions, FileMentionProblem},
+        merge_queue,
+        project_archive::{self, ProjectArchive, ProjectArchiveError, ProjectImportResult},
+        prompt_template,
+        repo_analysis::{self, RepoAnalysisSummary},
+        repo_config::{self, EffectiveProjectConfig, RepoConfig},
+        worktree_usage::{self, WorktreeUsage},
+    },
 };
 
+/// Every project, or with sign-in on, the ones the user is a member of
 pub async fn get_projects(
     State(app_state): State<AppState>,
+    user: Option<Extension<User>>,
 ) -> Result<ResponseJson<ApiResponse<Vec<Project>>>, StatusCode> {
-    match Project::find_all(&app_state.db_pool).await {
+    let projects = match user {
+        Some(Extension(user)) if !user.is_admin => {
+            match ProjectMember::find_memberships(&app_state.db_pool, user.id).await {
+                Ok(memberships) => Project::find_all(&app_state.db_pool).await.map(|projects| {
+                    projects
+                        .into_iter()
+                        .filter(|project| memberships.iter().any(|m| m.project_id == project.id))
+                        .collect()
+                }),
+                Err(e) => Err(e),
+            }
+        }
+        _ => Project::find_all(&app_state.db_pool).await,
+    };
+    match projects {
         Ok(projects) => Ok(ResponseJson(ApiResponse::success(projects))),
         Err(e) => {
             tracing::error!("Failed to fetch projects: {}", e);
@@ -101,6 +144,10 @@ pub async fn create_project(
 
     tracing::debug!("Creating project '{}'", payload.name);
 
+    if let Some(message) = invalid_default_executor(&payload.default_executor) {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+
     // Check if git repo path is already used by another project
     match Project::find_by_git_repo_path(&app_state.db_pool, &payload.git_repo_path).await {
         Ok(Some(_)) => {
@@ -181,6 +228,11 @@ pub async fn create_project(
         }
     }
 
+    // A `.vibe-kanban.toml` checked into the repository has to be valid to register it
+    if let Err(message) = RepoConfig::load(path) {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+
     match Project::create(&app_state.db_pool, &payload, id).await {
         Ok(project) => {
             // Track project creation event
@@ -205,11 +257,24 @@ pub async fn create_project(
     }
 }
 
+/// Why a project's default executor can't be used, if it can't
+pub(crate) fn invalid_default_executor(executor: &Option<String>) -> Option<String> {
+    let executor = executor.as_deref()?;
+    executor
+        .parse::<ExecutorConfig>()
+        .err()
+        .map(|e| format!("Invalid default executor: {}", e))
+}
+
 pub async fn update_project(
     Extension(existing_project): Extension<Project>,
     State(app_state): State<AppState>,
     Json(payload): Json<UpdateProject>,
 ) -> Result<ResponseJson<ApiResponse<Project>>, StatusCode> {
+    if let Some(message) = invalid_default_executor(&payload.default_executor) {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+
     // If git_repo_path is being changed, check if the new path is already used by another project
     if let Some(new_git_repo_path) = &payload.git_repo_path {
         if new_git_repo_path != &existing_project.git_repo_path {
@@ -245,9 +310,20 @@ pub async fn update_project(
         setup_script,
         dev_script,
         cleanup_script,
+        commit_message_template,
+        release_script,
+        merge_check_script,
+        verification_script,
+        default_executor,
+        default_branch,
     } = payload;
 
     let name = name.unwrap_or(existing_project.name);
+    if let Some(git_repo_path) = &git_repo_path {
+        if let Err(message) = RepoConfig::load(std::path::Path::new(git_repo_path)) {
+            return Ok(ResponseJson(ApiResponse::error(&message)));
+        }
+    }
     let git_repo_path = git_repo_path.unwrap_or(existing_project.git_repo_path);
 
     match Project::update(
@@ -258,6 +334,12 @@ pub async fn update_project(
         setup_script,
         dev_script,
         cleanup_script,
+        commit_message_template,
+        release_script,
+        merge_check_script,
+        verification_script,
+        default_executor,
+        default_branch,
     )
     .await
     {
@@ -379,6 +461,128 @@ pub async fn search_project_files(
     }
 }
 
+/// The `@` file mentions of a task description being written that don't name a file of the
+/// repository
+pub async fn check_file_mentions(
+    Extension(project): Extension<Project>,
+    Json(payload): Json<CheckFileMentions>,
+) -> Result<ResponseJson<ApiResponse<Vec<FileMentionProblem>>>, StatusCode> {
+    Ok(ResponseJson(ApiResponse::success(
+        file_mentions::check_mentions(std::path::Path::new(&project.git_repo_path), &payload.text),
+    )))
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConversationSearchMode {
+    #[default]
+    Keyword,
+    /// Embedding similarity, for questions that don't share words with the conversation
+    Semantic,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConversationSearchQuery {
+    pub q: String,
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub mode: ConversationSearchMode,
+}
+
+const DEFAULT_CONVERSATION_SEARCH_LIMIT: usize = 20;
+const MAX_CONVERSATION_SEARCH_LIMIT: usize = 100;
+
+/// Search the normalized conversations of every coding agent run in the project
+pub async fn search_project_conversations(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Query(params): Query<ConversationSearchQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ConversationSearchHit>>>, StatusCode> {
+    let Some(query) = ConversationQuery::parse(&params.q) else {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Query parameter 'q' must contain at least one search term",
+        )));
+    };
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_CONVERSATION_SEARCH_LIMIT)
+        .clamp(1, MAX_CONVERSATION_SEARCH_LIMIT);
+
+    if let ConversationSearchMode::Semantic = params.mode {
+        let client = {
+            let config = app_state.get_config().read().await;
+            EmbeddingsClient::from_config(&config.embeddings)
+        };
+        let Some(client) = client else {
+            return Ok(ResponseJson(ApiResponse::error(
+                "Semantic search needs an embeddings provider. Configure one in Settings.",
+            )));
+        };
+        return match semantic_search(&app_state.db_pool, &client, project.id, &params.q, limit)
+            .await
+        {
+            Ok(hits) => Ok(ResponseJson(ApiResponse::success(hits))),
+            Err(e) => {
+                tracing::warn!(
+                    "Semantic conversation search in project {} failed: {}",
+                    project.id,
+                    e
+                );
+                Ok(ResponseJson(ApiResponse::error(&e.to_string())))
+            }
+        };
+    }
+
+    let attempts: HashMap<Uuid, AttemptTaskRef> =
+        match AttemptTaskRef::find_by_project_id(&app_state.db_pool, project.id).await {
+            Ok(refs) => refs.into_iter().map(|r| (r.attempt_id, r)).collect(),
+            Err(e) => {
+                tracing::error!("Failed to fetch attempts for project {}: {}", project.id, e);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        };
+    let processes =
+        match ExecutionProcess::find_coding_agents_by_project_id(&app_state.db_pool, project.id)
+            .await
+        {
+            Ok(processes) => processes,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to fetch execution processes for project {}: {}",
+                    project.id,
+                    e
+                );
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        };
+
+    let mut hits = Vec::new();
+    for process in processes {
+        let Some(attempt) = attempts.get(&process.task_attempt_id) else {
+            continue;
+        };
+        let conversation =
+            super::task_attempts::normalize_process_logs(&app_state.db_pool, &process).await;
+        for (entry_index, entry) in conversation.entries.iter().enumerate() {
+            if let Some((score, snippet)) = query.score_entry(entry) {
+                hits.push(ConversationSearchHit {
+                    task_id: attempt.task_id,
+                    task_title: attempt.task_title.clone(),
+                    attempt_id: attempt.attempt_id,
+                    execution_process_id: process.id,
+                    entry_index,
+                    entry_type: entry.entry_type.clone(),
+                    timestamp: entry.timestamp.clone(),
+                    snippet,
+                    score,
+                });
+            }
+        }
+    }
+
+    Ok(ResponseJson(ApiResponse::success(rank_hits(hits, limit))))
+}
+
 async fn search_files_in_repo(
     repo_path: &str,
     query: &str,
@@ -478,6 +682,378 @@ async fn search_files_in_repo(
     Ok(results)
 }
 
+/// Release notes from the tasks merged in a date range or since a tag
+pub async fn get_project_changelog(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Query(query): Query<ChangelogQuery>,
+) -> Result<ResponseJson<ApiResponse<Changelog>>, StatusCode> {
+    match changelog::compile(&app_state.db_pool, &project, &query).await {
+        Ok(changelog) => Ok(ResponseJson(ApiResponse::success(changelog))),
+        Err(ChangelogError::Database(e)) => {
+            tracing::error!(
+                "Failed to compile changelog for project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+        Err(e) => Ok(ResponseJson(ApiResponse::error(&e.to_string()))),
+    }
+}
+
+/// Disk usage of every attempt worktree in the project that hasn't been cleaned up
+pub async fn get_project_worktree_usage(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<WorktreeUsage>>>, StatusCode> {
+    let worktrees =
+        match TaskAttempt::find_worktrees_by_project_id(&app_state.db_pool, project.id).await {
+            Ok(worktrees) => worktrees,
+            Err(e) => {
+                tracing::error!("Failed to fetch worktrees of project {}: {}", project.id, e);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        };
+
+    let measured = tokio::task::spawn_blocking(move || {
+        let mut usages: Vec<WorktreeUsage> = worktrees
+            .into_iter()
+            .map(|worktree| {
+                let path = std::path::Path::new(&worktree.worktree_path);
+                let exists = path.is_dir();
+                let usage = if exists {
+                    worktree_usage::measure(path)
+                } else {
+                    Default::default()
+                };
+                WorktreeUsage {
+                    attempt_id: worktree.attempt_id,
+                    task_id: worktree.task_id,
+                    task_title: worktree.task_title,
+                    branch: worktree.branch,
+                    worktree_path: worktree.worktree_path,
+                    exists,
+                    total_bytes: usage.total_bytes,
+                    artifact_bytes: usage.artifact_bytes(),
+                    artifacts: usage.artifacts,
+                    updated_at: worktree.updated_at,
+                }
+            })
+            .collect();
+        usages.sort_by_key(|usage| std::cmp::Reverse(usage.total_bytes));
+        usages
+    })
+    .await;
+
+    match measured {
+        Ok(usages) => Ok(ResponseJson(ApiResponse::success(usages))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to measure worktrees of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Delete the worktrees of the project's merged attempts and attempts of done tasks now,
+/// returning the attempts whose worktree was deleted
+pub async fn prune_project_worktrees(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<Uuid>>>, StatusCode> {
+    match execution_monitor::prune_finished_worktrees(&app_state.db_pool, project.id).await {
+        Ok(pruned) => Ok(ResponseJson(ApiResponse::success(pruned))),
+        Err(e) => {
+            tracing::error!("Failed to prune worktrees of project {}: {}", project.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// The project's tasks the stale check has flagged, idle longest first
+pub async fn get_project_stale_tasks(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectStaleTask>>>, StatusCode> {
+    match StaleTask::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(stale) => Ok(ResponseJson(ApiResponse::success(stale))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch stale tasks of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Pending merges in the order they will land, then the most recently finished ones
+pub async fn get_project_merge_queue(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<MergeQueueEntry>>>, StatusCode> {
+    match merge_queue::for_project(&app_state.db_pool, project.id).await {
+        Ok(entries) => Ok(ResponseJson(ApiResponse::success(entries))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch the merge queue of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Token and cost totals of the project's coding agent runs, per task and per model
+pub async fn get_project_usage(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<ProjectUsage>>, StatusCode> {
+    let usage = async {
+        let tasks = ExecutionMetrics::usage_by_task(&app_state.db_pool, project.id).await?;
+        let models = ExecutionMetrics::usage_by_model(&app_state.db_pool, project.id).await?;
+        Ok::<_, sqlx::Error>(ProjectUsage::new(tasks, models))
+    };
+    match usage.await {
+        Ok(usage) => Ok(ResponseJson(ApiResponse::success(usage))),
+        Err(e) => {
+            tracing::error!("Failed to fetch usage of project {}: {}", project.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Agent time, time to done and executor outcomes of the project's tasks
+pub async fn get_project_analytics(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<ProjectAnalytics>>, StatusCode> {
+    match ProjectAnalytics::for_project(&app_state.db_pool, project.id).await {
+        Ok(analytics) => Ok(ResponseJson(ApiResponse::success(analytics))),
+        Err(e) => {
+            tracing::error!("Failed to fetch analytics of project {}: {}", project.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn get_project_prompt_templates(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<PromptTemplate>>>, StatusCode> {
+    match PromptTemplate::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(templates) => Ok(ResponseJson(ApiResponse::success(templates))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch prompt templates of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Set the project's prompt template for an executor, or for every executor without one
+pub async fn upsert_project_prompt_template(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpsertPromptTemplate>,
+) -> Result<ResponseJson<ApiResponse<PromptTemplate>>, StatusCode> {
+    if let Some(executor) = &payload.executor {
+        if let Err(message) = executor.parse::<ExecutorConfig>() {
+            return Ok(ResponseJson(ApiResponse::error(&message)));
+        }
+    }
+    if payload.template.trim().is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "The prompt template can't be empty",
+        )));
+    }
+    if let Err(message) = prompt_template::validate(&payload.template) {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+
+    match PromptTemplate::upsert(&app_state.db_pool, project.id, &payload).await {
+        Ok(template) => Ok(ResponseJson(ApiResponse::success(template))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to save prompt template of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PromptTemplateQuery {
+    pub executor: Option<String>,
+}
+
+pub async fn delete_project_prompt_template(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Query(query): Query<PromptTemplateQuery>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match PromptTemplate::delete(&app_state.db_pool, project.id, query.executor.as_deref()).await {
+        Ok(true) => Ok(ResponseJson(ApiResponse::success(()))),
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!(
+                "Failed to delete prompt template of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Files, languages and symbols of the project's repository, refreshed from its index
+pub async fn get_project_analysis(
+    Extension(project): Extension<Project>,
+) -> Result<ResponseJson<ApiResponse<RepoAnalysisSummary>>, StatusCode> {
+    let repo_path = std::path::PathBuf::from(&project.git_repo_path);
+    match tokio::task::spawn_blocking(move || repo_analysis::load_or_refresh(&repo_path)).await {
+        Ok(Ok((analysis, stats))) => {
+            Ok(ResponseJson(ApiResponse::success(analysis.summary(stats))))
+        }
+        Ok(Err(e)) => {
+            tracing::error!("Failed to analyze project {}: {}", project.id, e);
+            Ok(ResponseJson(ApiResponse::error(&e.to_string())))
+        }
+        Err(e) => {
+            tracing::error!("Analysis of project {} panicked: {}", project.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// The settings the project's runs use: its own, with the gaps filled from its repository's
+/// `.vibe-kanban.toml`
+pub async fn get_project_effective_config(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<EffectiveProjectConfig>>, StatusCode> {
+    let prompt_template =
+        match PromptTemplate::find_by_project_id(&app_state.db_pool, project.id).await {
+            Ok(templates) => templates
+                .into_iter()
+                .find(|template| template.executor.is_none())
+                .map(|template| template.template),
+            Err(e) => {
+                tracing::error!(
+                    "Failed to fetch prompt templates of project {}: {}",
+                    project.id,
+                    e
+                );
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        };
+    match repo_config::effective(&project, prompt_template) {
+        Ok(config) => Ok(ResponseJson(ApiResponse::success(config))),
+        Err(message) => Ok(ResponseJson(ApiResponse::error(&message))),
+    }
+}
+
+/// Archives carry every execution's conversation, and logs too when asked for
+const ARCHIVE_MAX_BYTES: usize = 256 * 1024 * 1024;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ProjectExportQuery {
+    #[serde(default)]
+    pub include_logs: bool,
+}
+
+/// The project's tasks, attempts, executions, conversations and templates as a JSON archive
+/// download
+pub async fn export_project(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Query(query): Query<ProjectExportQuery>,
+) -> Result<Response, StatusCode> {
+    let archive =
+        match project_archive::export(&app_state.db_pool, &project, query.include_logs).await {
+            Ok(archive) => archive,
+            Err(e) => {
+                tracing::error!("Failed to export project {}: {}", project.id, e);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        };
+
+    app_state
+        .track_analytics_event(
+            "project_exported",
+            Some(serde_json::json!({
+                "project_id": project.id.to_string(),
+                "tasks": archive.tasks.len(),
+                "include_logs": query.include_logs,
+            })),
+        )
+        .await;
+
+    let file_name: String = project
+        .name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let disposition = format!("attachment; filename=\"{}.vibe-kanban.json\"", file_name);
+    let mut response = ResponseJson(archive).into_response();
+    if let Ok(value) = HeaderValue::from_str(&disposition) {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_DISPOSITION, value);
+    }
+    Ok(response)
+}
+
+/// Re-create an exported project's tasks, attempts, executions and templates in this
+/// project, with new ids
+pub async fn import_project(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(archive): Json<ProjectArchive>,
+) -> Result<ResponseJson<ApiResponse<ProjectImportResult>>, StatusCode> {
+    match project_archive::import(&app_state.db_pool, project.id, &archive).await {
+        Ok(result) => {
+            app_state
+                .track_analytics_event(
+                    "project_imported",
+                    Some(serde_json::json!({
+                        "project_id": project.id.to_string(),
+                        "tasks_created": result.tasks_created,
+                        "executions_created": result.executions_created,
+                    })),
+                )
+                .await;
+            Ok(ResponseJson(ApiResponse::success(result)))
+        }
+        Err(ProjectArchiveError::Database(e)) => {
+            tracing::error!(
+                "Failed to import archive into project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+        Err(e) => Ok(ResponseJson(ApiResponse::error(&e.to_string()))),
+    }
+}
+
 pub fn projects_base_router() -> Router<AppState> {
     Router::new().route("/projects", get(get_projects).post(create_project))
 }
@@ -496,5 +1072,36 @@ pub fn projects_with_id_router() -> Router<AppState> {
             get(get_project_branches).post(create_project_branch),
         )
         .route("/projects/:id/search", get(search_project_files))
+        .route("/projects/:id/file-mentions", post(check_file_mentions))
+        .route(
+            "/projects/:id/conversations/search",
+            get(search_project_conversations),
+        )
+        .route("/projects/:id/changelog", get(get_project_changelog))
+        .route("/projects/:id/worktrees", get(get_project_worktree_usage))
+        .route(
+            "/projects/:id/worktrees/prune",
+            post(prune_project_worktrees),
+        )
+        .route("/projects/:id/stale", get(get_project_stale_tasks))
+        .route("/projects/:id/merge-queue", get(get_project_merge_queue))
+        .route("/projects/:id/usage", get(get_project_usage))
+        .route("/projects/:id/analytics", get(get_project_analytics))
+        .route(
+            "/projects/:id/prompt-templates",
+            get(get_project_prompt_templates)
+                .put(upsert_project_prompt_template)
+                .delete(delete_project_prompt_template),
+        )
+        .route("/projects/:id/analysis", get(get_project_analysis))
+        .route(
+            "/projects/:id/effective-config",
+            get(get_project_effective_config),
+        )
         .route("/projects/:id/open-editor", post(open_project_in_editor))
+        .route("/projects/:id/export", get(export_project))
+        .route(
+            "/projects/:id/import",
+            post(import_project).layer(DefaultBodyLimit::max(ARCHIVE_MAX_BYTES)),
+        )
 }
@@ -0,0 +1,252 @@
+use std::collections::HashSet;
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+
+use crate::{
+    app_state::AppState,
+    executor::NormalizedConversation,
+    middleware::{load_project_middleware, load_release_middleware},
+    models::{
+        project::Project,
+        release::{CreateRelease, Release, ReleaseStatus, ReleaseWithTasks},
+        task::Task,
+        ApiResponse,
+    },
+    services::releases::{self, ReleaseError},
+};
+
+pub async fn get_releases(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<Release>>>, StatusCode> {
+    match Release::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(releases) => Ok(ResponseJson(ApiResponse::success(releases))),
+        Err(e) => {
+            tracing::error!("Failed to fetch releases for project {}: {}", project.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn create_release(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<CreateRelease>,
+) -> Result<ResponseJson<ApiResponse<ReleaseWithTasks>>, StatusCode> {
+    let pool = &app_state.db_pool;
+    let version = payload.version.trim();
+    if version.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error("Version cannot be empty")));
+    }
+    let notes = payload
+        .notes
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty());
+
+    let task_ids = match payload.task_ids {
+        Some(task_ids) => {
+            let released: HashSet<_> = Release::find_released_task_ids(pool, project.id)
+                .await
+                .map_err(|e| {
+                    tracing::error!(
+                        "Failed to fetch released tasks for project {}: {}",
+                        project.id,
+                        e
+                    );
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?
+                .into_iter()
+                .collect();
+            for task_id in &task_ids {
+                match Task::find_by_id_and_project_id(pool, *task_id, project.id).await {
+                    Ok(Some(_)) if released.contains(task_id) => {
+                        return Ok(ResponseJson(ApiResponse::error(&format!(
+                            "Task {} is already part of a release",
+                            task_id
+                        ))));
+                    }
+                    Ok(Some(_)) => {}
+                    Ok(None) => {
+                        return Ok(ResponseJson(ApiResponse::error(&format!(
+                            "Task {} is not in this project",
+                            task_id
+                        ))));
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to fetch task {}: {}", task_id, e);
+                        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+                    }
+                }
+            }
+            let mut seen = HashSet::new();
+            task_ids.into_iter().filter(|id| seen.insert(*id)).collect()
+        }
+        None => releases::unreleased_task_ids(pool, project.id)
+            .await
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to fetch unreleased tasks for project {}: {}",
+                    project.id,
+                    e
+                );
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?,
+    };
+    if task_ids.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "There are no merged tasks to release",
+        )));
+    }
+
+    let release = match Release::create(pool, project.id, version, notes, &task_ids).await {
+        Ok(release) => release,
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            return Ok(ResponseJson(ApiResponse::error(&format!(
+                "Release {} already exists",
+                version
+            ))));
+        }
+        Err(e) => {
+            tracing::error!("Failed to create release for project {}: {}", project.id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    with_tasks(&app_state, release).await
+}
+
+pub async fn get_release(
+    Extension(release): Extension<Release>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<ReleaseWithTasks>>, StatusCode> {
+    with_tasks(&app_state, release).await
+}
+
+async fn with_tasks(
+    app_state: &AppState,
+    release: Release,
+) -> Result<ResponseJson<ApiResponse<ReleaseWithTasks>>, StatusCode> {
+    match Release::find_tasks(&app_state.db_pool, release.id).await {
+        Ok(tasks) => Ok(ResponseJson(ApiResponse::success(ReleaseWithTasks {
+            release,
+            tasks,
+        }))),
+        Err(e) => {
+            tracing::error!("Failed to fetch tasks of release {}: {}", release.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn run_release(
+    Extension(project): Extension<Project>,
+    Extension(release): Extension<Release>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Release>>, StatusCode> {
+    if app_state.dry_run {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Running releases is disabled in dry-run mode",
+        )));
+    }
+    let pool = &app_state.db_pool;
+    match releases::start(pool.clone(), &project, &release).await {
+        Ok(()) => {}
+        Err(ReleaseError::Database(e)) => {
+            tracing::error!("Failed to start release {}: {}", release.id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+        Err(e) => return Ok(ResponseJson(ApiResponse::error(&e.to_string()))),
+    }
+
+    match Release::find_by_id(pool, release.id).await {
+        Ok(Some(release)) => Ok(ResponseJson(ApiResponse::success(release))),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Failed to fetch release {}: {}", release.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn get_release_conversation(
+    Extension(project): Extension<Project>,
+    Extension(release): Extension<Release>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<NormalizedConversation>>, StatusCode> {
+    match Release::find_output(&app_state.db_pool, release.id).await {
+        Ok(output) => Ok(ResponseJson(ApiResponse::success(releases::conversation(
+            project.release_script.as_deref(),
+            &release,
+            &output,
+        )))),
+        Err(e) => {
+            tracing::error!("Failed to fetch output of release {}: {}", release.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn delete_release(
+    Extension(release): Extension<Release>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match release.status {
+        ReleaseStatus::Running => {
+            return Ok(ResponseJson(ApiResponse::error(
+                "Wait for the release script to finish before deleting the release",
+            )));
+        }
+        ReleaseStatus::Released => {
+            return Ok(ResponseJson(ApiResponse::error(
+                "Releases that went out can't be deleted",
+            )));
+        }
+        ReleaseStatus::Draft | ReleaseStatus::Failed => {}
+    }
+
+    match Release::delete(&app_state.db_pool, release.id).await {
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => {
+            tracing::error!("Failed to delete release {}: {}", release.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub fn releases_router(app_state: AppState) -> Router<AppState> {
+    Router::new()
+        .merge(
+            Router::new()
+                .route(
+                    "/projects/:project_id/releases",
+                    get(get_releases).post(create_release),
+                )
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    load_project_middleware,
+                )),
+        )
+        .merge(
+            Router::new()
+                .route(
+                    "/projects/:project_id/releases/:release_id",
+                    get(get_release).delete(delete_release),
+                )
+                .route(
+                    "/projects/:project_id/releases/:release_id/run",
+                    post(run_release),
+                )
+                .route(
+                    "/projects/:project_id/releases/:release_id/conversation",
+                    get(get_release_conversation),
+                )
+                .route_layer(from_fn_with_state(app_state, load_release_middleware)),
+        )
+}
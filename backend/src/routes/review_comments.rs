@@ -0,0 +1,302 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use super::task_attempts::FollowUpResponse;
+use crate::{
+    app_state::AppState,
+    models::{
+        execution_diff::ExecutionDiff,
+        execution_process::ExecutionProcess,
+        mention::MentionSource,
+        project::Project,
+        review_comment::{
+            compile_follow_up_prompt, CreateReviewComment, ReviewComment, UpdateReviewComment,
+        },
+        task::Task,
+        task_attempt::TaskAttempt,
+        user::User,
+        ApiResponse,
+    },
+    services::mentions,
+};
+
+pub async fn get_review_comments(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<ReviewComment>>>, StatusCode> {
+    match ReviewComment::find_by_task_attempt_id(&app_state.db_pool, task_attempt.id).await {
+        Ok(comments) => Ok(ResponseJson(ApiResponse::success(comments))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch review comments for attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Why a comment can't go on the run's captured diff, if it can't: the run must belong to
+/// the attempt and have changed the commented line
+async fn run_anchor_error(
+    app_state: &AppState,
+    task_attempt: &TaskAttempt,
+    payload: &CreateReviewComment,
+) -> Result<Option<String>, StatusCode> {
+    let Some(run) = payload.execution_process_id else {
+        return Ok(None);
+    };
+    let db_error = |e: sqlx::Error| {
+        tracing::error!("Failed to fetch diff of execution process {}: {}", run, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+
+    match ExecutionProcess::find_by_id(&app_state.db_pool, run)
+        .await
+        .map_err(db_error)?
+    {
+        Some(process) if process.task_attempt_id == task_attempt.id => {}
+        _ => return Ok(Some("That run isn't part of this attempt".to_string())),
+    }
+    let Some(diff) = ExecutionDiff::find_by_execution_process_id(&app_state.db_pool, run)
+        .await
+        .map_err(db_error)?
+    else {
+        return Ok(Some("No diff was captured for that run".to_string()));
+    };
+    if diff
+        .hunk_at(&payload.file_path, payload.line_number)
+        .is_none()
+    {
+        return Ok(Some(format!(
+            "That run's diff doesn't show line {} of {}",
+            payload.line_number, payload.file_path
+        )));
+    }
+    Ok(None)
+}
+
+/// Tell the people @mentioned in a comment, skipping those it mentioned before an edit
+async fn notify_mentions(
+    app_state: &AppState,
+    task: &Task,
+    task_attempt: &TaskAttempt,
+    user: Option<&User>,
+    body: &str,
+    previous: Option<&str>,
+) {
+    if let Err(e) = mentions::notify(
+        &app_state.db_pool,
+        task,
+        Some(task_attempt.id),
+        MentionSource::ReviewComment,
+        user,
+        body,
+        previous,
+    )
+    .await
+    {
+        tracing::error!(
+            "Failed to notify mentions in a comment on attempt {}: {}",
+            task_attempt.id,
+            e
+        );
+    }
+}
+
+pub async fn create_review_comment(
+    Extension(task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+    user: Option<Extension<User>>,
+    Json(payload): Json<CreateReviewComment>,
+) -> Result<ResponseJson<ApiResponse<ReviewComment>>, StatusCode> {
+    if payload.body.trim().is_empty() {
+        return Ok(ResponseJson(ApiResponse::error("Comment cannot be empty")));
+    }
+    if payload.line_number < 1 {
+        return Ok(ResponseJson(ApiResponse::error("Line numbers start at 1")));
+    }
+    if let Some(message) = run_anchor_error(&app_state, &task_attempt, &payload).await? {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+
+    match ReviewComment::create(&app_state.db_pool, task_attempt.id, &payload).await {
+        Ok(comment) => {
+            let user = user.as_ref().map(|Extension(user)| user);
+            notify_mentions(&app_state, &task, &task_attempt, user, &comment.body, None).await;
+            Ok(ResponseJson(ApiResponse::success(comment)))
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to create review comment on attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Load a comment, making sure it belongs to the attempt in the URL
+async fn find_attempt_comment(
+    app_state: &AppState,
+    task_attempt: &TaskAttempt,
+    comment_id: Uuid,
+) -> Result<ReviewComment, StatusCode> {
+    match ReviewComment::find_by_id(&app_state.db_pool, comment_id).await {
+        Ok(Some(comment)) if comment.task_attempt_id == task_attempt.id => Ok(comment),
+        Ok(_) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Failed to fetch review comment {}: {}", comment_id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn update_review_comment(
+    Extension(task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+    user: Option<Extension<User>>,
+    Path((_project_id, _task_id, _attempt_id, comment_id)): Path<(Uuid, Uuid, Uuid, Uuid)>,
+    Json(payload): Json<UpdateReviewComment>,
+) -> Result<ResponseJson<ApiResponse<ReviewComment>>, StatusCode> {
+    let previous = find_attempt_comment(&app_state, &task_attempt, comment_id).await?;
+
+    match ReviewComment::update(&app_state.db_pool, comment_id, &payload).await {
+        Ok(comment) => {
+            if payload.body.is_some() {
+                let user = user.as_ref().map(|Extension(user)| user);
+                notify_mentions(
+                    &app_state,
+                    &task,
+                    &task_attempt,
+                    user,
+                    &comment.body,
+                    Some(&previous.body),
+                )
+                .await;
+            }
+            Ok(ResponseJson(ApiResponse::success(comment)))
+        }
+        Err(e) => {
+            tracing::error!("Failed to update review comment {}: {}", comment_id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn delete_review_comment(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+    Path((_project_id, _task_id, _attempt_id, comment_id)): Path<(Uuid, Uuid, Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    find_attempt_comment(&app_state, &task_attempt, comment_id).await?;
+
+    match ReviewComment::delete(&app_state.db_pool, comment_id).await {
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => {
+            tracing::error!("Failed to delete review comment {}: {}", comment_id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Turn every open comment into a single follow-up prompt, start the follow-up and
+/// resolve the comments that were sent
+pub async fn send_review_comments_as_follow_up(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<FollowUpResponse>>, StatusCode> {
+    let comments = ReviewComment::find_by_task_attempt_id(&app_state.db_pool, task_attempt.id)
+        .await
+        .map_err(|e| {
+            tracing::error!(
+                "Failed to fetch review comments for attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if comments.iter().all(|c| c.resolved) {
+        return Ok(ResponseJson(ApiResponse::error(
+            "There are no open review comments",
+        )));
+    }
+
+    let worktree_path = TaskAttempt::ensure_worktree_exists(
+        &app_state.db_pool,
+        task_attempt.id,
+        project.id,
+        "review comments",
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(
+            "Failed to prepare worktree for attempt {}: {}",
+            task_attempt.id,
+            e
+        );
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let run_diffs = ReviewComment::run_diffs(&app_state.db_pool, &comments)
+        .await
+        .map_err(|e| {
+            tracing::error!(
+                "Failed to fetch run diffs for attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    let prompt =
+        compile_follow_up_prompt(&comments, std::path::Path::new(&worktree_path), &run_diffs);
+
+    let actual_attempt_id = match TaskAttempt::start_followup_execution(
+        &app_state.db_pool,
+        &app_state,
+        task_attempt.id,
+        task.id,
+        project.id,
+        &prompt,
+    )
+    .await
+    {
+        Ok(id) => id,
+        Err(e) => {
+            tracing::error!(
+                "Failed to start review follow-up for attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    if let Err(e) = ReviewComment::resolve_all(&app_state.db_pool, task_attempt.id).await {
+        tracing::error!(
+            "Failed to resolve review comments for attempt {}: {}",
+            task_attempt.id,
+            e
+        );
+    }
+
+    let created_new_attempt = actual_attempt_id != task_attempt.id;
+    Ok(ResponseJson(ApiResponse::success(FollowUpResponse {
+        message: format!(
+            "Sent {} review comments to the agent",
+            comments.iter().filter(|c| !c.resolved).count()
+        ),
+        actual_attempt_id,
+        created_new_attempt,
+    })))
+}
@@ -0,0 +1,98 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+    routing::get,
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    models::{
+        project::Project,
+        script_hook::{script_hook_name_error, ScriptHook, UpsertScriptHook},
+        ApiResponse,
+    },
+    services::script_hooks,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct ScriptHookNameQuery {
+    pub name: String,
+}
+
+/// The scripts the project runs on its lifecycle events
+pub async fn get_script_hooks(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<ScriptHook>>>, StatusCode> {
+    match ScriptHook::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(hooks) => Ok(ResponseJson(ApiResponse::success(hooks))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch script hooks of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn upsert_script_hook(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpsertScriptHook>,
+) -> Result<ResponseJson<ApiResponse<ScriptHook>>, StatusCode> {
+    if let Some(message) = script_hook_name_error(&payload.name) {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+    if let Some(e) = script_hooks::compile_error(&payload.script) {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "The script doesn't compile: {}",
+            e
+        ))));
+    }
+    match ScriptHook::upsert(&app_state.db_pool, project.id, &payload).await {
+        Ok(hook) => Ok(ResponseJson(ApiResponse::success(hook))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to save script hook {} of project {}: {}",
+                payload.name,
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn delete_script_hook(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Query(query): Query<ScriptHookNameQuery>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match ScriptHook::delete(&app_state.db_pool, project.id, &query.name).await {
+        Ok(true) => Ok(ResponseJson(ApiResponse::success(()))),
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!(
+                "Failed to delete script hook {} of project {}: {}",
+                query.name,
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub fn project_script_hooks_router() -> Router<AppState> {
+    Router::new().route(
+        "/projects/:id/script-hooks",
+        get(get_script_hooks)
+            .put(upsert_script_hook)
+            .delete(delete_script_hook),
+    )
+}
@@ -0,0 +1,74 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+    routing::get,
+    Router,
+};
+
+use crate::{
+    app_state::AppState,
+    models::{
+        search_document::{SearchFilters, SearchHit, SearchQuery},
+        ApiResponse,
+    },
+    services::search::{self, parse_date},
+};
+
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+const MAX_SEARCH_LIMIT: usize = 200;
+
+/// Search task titles, descriptions and coding agent conversations across projects
+pub async fn search_all(
+    State(app_state): State<AppState>,
+    Query(params): Query<SearchQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<SearchHit>>>, StatusCode> {
+    if search::fts_query(&params.q).is_none() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Query parameter 'q' must contain at least one search term",
+        )));
+    }
+    let from = match params
+        .from
+        .as_deref()
+        .map(|v| parse_date(v, false))
+        .transpose()
+    {
+        Ok(from) => from,
+        Err(e) => return Ok(ResponseJson(ApiResponse::error(&e))),
+    };
+    let to = match params
+        .to
+        .as_deref()
+        .map(|v| parse_date(v, true))
+        .transpose()
+    {
+        Ok(to) => to,
+        Err(e) => return Ok(ResponseJson(ApiResponse::error(&e))),
+    };
+    let filters = SearchFilters {
+        project_id: params.project_id,
+        status: params.status,
+        executor: params
+            .executor
+            .filter(|executor| !executor.trim().is_empty()),
+        from,
+        to,
+    };
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+
+    match search::search(&app_state.db_pool, &params.q, &filters, limit).await {
+        Ok(hits) => Ok(ResponseJson(ApiResponse::success(hits))),
+        Err(e) => {
+            tracing::error!("Failed to search for `{}`: {}", params.q, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub fn search_router() -> Router<AppState> {
+    Router::new().route("/search", get(search_all))
+}
@@ -0,0 +1,124 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+    routing::{delete, get},
+    Extension, Json, Router,
+};
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    models::{
+        task::Task,
+        task_share_link::{CreateTaskShareLink, CreatedTaskShareLink, TaskShareLink},
+        user::User,
+        ApiResponse,
+    },
+    services::task_sharing::{self, SharedTask},
+};
+
+pub async fn get_share_links(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskShareLink>>>, StatusCode> {
+    match TaskShareLink::find_by_task_id(&app_state.db_pool, task.id).await {
+        Ok(links) => Ok(ResponseJson(ApiResponse::success(links))),
+        Err(e) => {
+            tracing::error!("Failed to load share links of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn create_share_link(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    user: Option<Extension<User>>,
+    Json(payload): Json<CreateTaskShareLink>,
+) -> Result<ResponseJson<ApiResponse<CreatedTaskShareLink>>, StatusCode> {
+    let created_by = user.map(|Extension(user)| user.id);
+    match task_sharing::create(&app_state.db_pool, task.id, created_by, &payload).await {
+        Ok(created) => {
+            tracing::info!(
+                "Created share link {} for task {}",
+                created.link.id,
+                task.id
+            );
+            Ok(ResponseJson(ApiResponse::success(created)))
+        }
+        Err(e) => {
+            tracing::error!("Failed to create a share link for task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Revoke the link. It stays listed. The path has one id more than the task loader takes,
+/// so the link is checked against the project and task here.
+pub async fn revoke_share_link(
+    Path((project_id, task_id, link_id)): Path<(Uuid, Uuid, Uuid)>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    let pool = &app_state.db_pool;
+    let found = match TaskShareLink::find_by_id(pool, link_id).await {
+        Ok(Some(link)) if link.task_id == task_id => {
+            Task::find_by_id_and_project_id(pool, task_id, project_id).await
+        }
+        Ok(_) => Ok(None),
+        Err(e) => Err(e),
+    };
+    match found {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Failed to load share link {}: {}", link_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+    match TaskShareLink::revoke(pool, link_id).await {
+        Ok(_) => {
+            tracing::info!("Revoked share link {} of task {}", link_id, task_id);
+            Ok(ResponseJson(ApiResponse::success(())))
+        }
+        Err(e) => {
+            tracing::error!("Failed to revoke share link {}: {}", link_id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// GET /shared/:token: what the link shows, to anyone holding it. A link that doesn't
+/// exist, was revoked or has expired is a 404 alike, so they can't be told apart.
+pub async fn get_shared_task(
+    Path(token): Path<String>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<SharedTask>>, StatusCode> {
+    match task_sharing::shared_task(&app_state.db_pool, &token).await {
+        Ok(Some(shared)) => Ok(ResponseJson(ApiResponse::success(shared))),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Failed to load a shared task: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Under the task loader
+pub fn task_share_links_router() -> Router<AppState> {
+    Router::new().route(
+        "/projects/:project_id/tasks/:task_id/share-links",
+        get(get_share_links).post(create_share_link),
+    )
+}
+
+pub fn share_link_with_id_router() -> Router<AppState> {
+    Router::new().route(
+        "/projects/:project_id/tasks/:task_id/share-links/:link_id",
+        delete(revoke_share_link),
+    )
+}
+
+pub fn shared_router() -> Router<AppState> {
+    Router::new().route("/shared/:token", get(get_shared_task))
+}
@@ -0,0 +1,150 @@
+use axum::{
+    body::{Body, Bytes},
+    extract::{DefaultBodyLimit, Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{Json as ResponseJson, Response},
+    routing::get,
+    Extension, Router,
+};
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    models::{
+        task::Task,
+        task_attachment::{sanitize_file_name, TaskAttachment, MAX_ATTACHMENT_BYTES},
+        ApiResponse,
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct UploadAttachmentQuery {
+    pub file_name: String,
+}
+
+pub async fn get_task_attachments(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskAttachment>>>, StatusCode> {
+    match TaskAttachment::find_by_task_id(&app_state.db_pool, task.id).await {
+        Ok(attachments) => Ok(ResponseJson(ApiResponse::success(attachments))),
+        Err(e) => {
+            tracing::error!("Failed to fetch attachments of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// The body is the raw file; its type comes from the Content-Type header, or the file
+/// extension when the client sends none
+pub async fn upload_task_attachment(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Query(query): Query<UploadAttachmentQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<ResponseJson<ApiResponse<TaskAttachment>>, StatusCode> {
+    let Some(file_name) = sanitize_file_name(&query.file_name) else {
+        return Ok(ResponseJson(ApiResponse::error("A file name is required")));
+    };
+    if body.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error("The file is empty")));
+    }
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| {
+            value
+                .split(';')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        })
+        .filter(|value| !value.is_empty() && value != "application/octet-stream")
+        .unwrap_or_else(|| {
+            mime_guess::from_path(&file_name)
+                .first_or_octet_stream()
+                .to_string()
+        });
+
+    match TaskAttachment::create(
+        &app_state.db_pool,
+        task.id,
+        &file_name,
+        &content_type,
+        &body,
+    )
+    .await
+    {
+        Ok(attachment) => Ok(ResponseJson(ApiResponse::success(attachment))),
+        Err(e) => {
+            tracing::error!("Failed to attach {} to task {}: {}", file_name, task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn get_task_attachment_content(
+    Extension(attachment): Extension<TaskAttachment>,
+) -> Result<Response, StatusCode> {
+    let bytes = match tokio::fs::read(attachment.path()).await {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            tracing::warn!("File of attachment {} is missing", attachment.id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to read attachment {}: {}", attachment.id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    let content_type = HeaderValue::from_str(&attachment.content_type)
+        .unwrap_or_else(|_| HeaderValue::from_static("application/octet-stream"));
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, content_type)
+        .body(Body::from(bytes))
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+pub async fn delete_task_attachment(
+    Extension(attachment): Extension<TaskAttachment>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    if let Err(e) = TaskAttachment::delete(&app_state.db_pool, attachment.id).await {
+        tracing::error!("Failed to delete attachment {}: {}", attachment.id, e);
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    if let Err(e) = tokio::fs::remove_file(attachment.path()).await {
+        tracing::warn!(
+            "Failed to remove the file of attachment {}: {}",
+            attachment.id,
+            e
+        );
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn task_attachments_list_router() -> Router<AppState> {
+    Router::new().route(
+        "/projects/:project_id/tasks/:task_id/attachments",
+        get(get_task_attachments)
+            .post(upload_task_attachment)
+            .layer(DefaultBodyLimit::max(MAX_ATTACHMENT_BYTES)),
+    )
+}
+
+pub fn task_attachments_with_id_router() -> Router<AppState> {
+    use axum::routing::delete;
+
+    Router::new()
+        .route(
+            "/projects/:project_id/tasks/:task_id/attachments/:attachment_id",
+            delete(delete_task_attachment),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attachments/:attachment_id/content",
+            get(get_task_attachment_content),
+        )
+}
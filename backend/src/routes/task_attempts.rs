@@ -1,40 +1,89 @@
 use axum::{
-    extract::{Query, State},
-    http::StatusCode,
+    extract::{Path, Query, Request, State},
+    http::{header, StatusCode},
     middleware::from_fn_with_state,
-    response::Json as ResponseJson,
+    response::{IntoResponse, Json as ResponseJson, Response},
     routing::get,
     Extension, Json, Router,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use sqlx::SqlitePool;
 use ts_rs::TS;
 use uuid::Uuid;
 
 use crate::{
     app_state::AppState,
+    command_runner::{self, CommandError},
+    db::DbPool,
     executor::{
-        ActionType, ExecutorConfig, NormalizedConversation, NormalizedEntry, NormalizedEntryType,
+        identify_output_entries, time_tool_calls, ExecutorConfig, NormalizedConversation,
+        NormalizedEntry, NormalizedEntryType, NormalizerState,
     },
+    metrics,
     middleware::{load_execution_process_with_context_middleware, load_task_attempt_middleware},
     models::{
+        attempt_linked_worktree::LinkedRepoDiff,
+        attempt_plan::{AttemptPlan, AttemptPlanStatus},
         config::Config,
+        dev_server_port::DevServerPort,
+        execution_attention::{AttentionKind, ExecutionAttention},
+        execution_diff::ExecutionDiff,
+        execution_input::{ExecutionInput, SendExecutionInput},
+        execution_log_chunk::LogStream,
         execution_process::{
             ExecutionProcess, ExecutionProcessStatus, ExecutionProcessSummary, ExecutionProcessType,
         },
+        execution_queue::QueuedAttempt,
+        execution_resources::ExecutionResources,
+        execution_tool_usage::ExecutionToolUsage,
+        execution_verification::ExecutionVerification,
+        executor_profile::ExecutorProfile,
+        mention::MentionSource,
+        merge_queue_entry::MergeQueueEntry,
+        network_interruption::NetworkInterruption,
         project::Project,
+        project_git_host::GitHostKind,
         task::{Task, TaskStatus},
+        task_attachment::TaskAttachment,
         task_attempt::{
-            BranchStatus, CreateFollowUpAttempt, CreatePrParams, CreateTaskAttempt, TaskAttempt,
+            AttemptComparison, BranchStatus, CreateFollowUpAttempt, CreatePrParams,
+            CreateTaskAttempt, FileContent, FileHunks, FileTreeNode, TaskAttempt, TaskAttemptError,
             TaskAttemptState, WorktreeDiff,
         },
+        task_defaults::TaskDefaults,
+        task_dependency::{blocked_message, TaskDependency},
+        task_pr_description::TaskPrDescription,
+        user::User,
         ApiResponse,
     },
+    services::{
+        attention,
+        command_approval::{ApprovalDecision, PendingApproval},
+        dev_server_proxy, entry_limits, executor_preflight, file_locations,
+        git_host::{self, GitHostError},
+        guardrails, log_redaction, log_storage, mentions,
+        merge_queue::{self, MergeQueueError},
+        plan_review::{self, PlanReview},
+        plugins::{self, Validation},
+        pr_description::{self, PrDescription},
+        repo_config,
+        scoped_follow_up::{compile_scoped_prompt, CreateScopedFollowUp},
+        script_hooks,
+        worktree_usage::{self, ArtifactCleanup},
+        GitServiceError, ProcessService,
+    },
 };
 
+pub(crate) const REVIEW_REQUIRED: &str =
+    "Merging needs a reviewer's approval; approve the attempt to merge it";
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct RebaseTaskAttemptRequest {
     pub new_base_branch: Option<String>,
+    /// Rebase even when changes conflict with the base, leaving conflict markers in the
+    /// worktree, and start a follow-up asking the agent to resolve them
+    #[serde(default)]
+    pub resolve_conflicts: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -51,7 +100,7 @@ pub struct FollowUpResponse {
     pub created_new_attempt: bool,
 }
 
-#[derive(Debug, Serialize, TS)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, TS)]
 #[ts(export)]
 pub struct ProcessLogsResponse {
     pub id: Uuid,
@@ -59,13 +108,119 @@ pub struct ProcessLogsResponse {
     pub command: String,
     pub executor_type: Option<String>,
     pub status: ExecutionProcessStatus,
+    // Entries are described in shared/types.ts rather than the OpenAPI document
+    #[schemars(with = "serde_json::Value")]
     pub normalized_conversation: NormalizedConversation,
 }
 
 // Helper to normalize logs for a process (extracted from get_execution_process_normalized_logs)
-async fn normalize_process_logs(
-    db_pool: &SqlitePool,
+pub(crate) async fn normalize_process_logs(
+    db_pool: &DbPool,
+    process: &ExecutionProcess,
+) -> NormalizedConversation {
+    normalize_process_logs_with(db_pool, process, None, true).await
+}
+
+/// Like [`normalize_process_logs`], with entries too large to render in full
+pub(crate) async fn normalize_full_process_logs(
+    db_pool: &DbPool,
+    process: &ExecutionProcess,
+) -> NormalizedConversation {
+    normalize_process_logs_with(db_pool, process, None, false).await
+}
+
+/// Like [`normalize_process_logs`], but running processes reuse their normalizer state in
+/// `app_state`, so repeated polls only parse output added since the last one
+pub(crate) async fn normalize_live_process_logs(
+    app_state: &AppState,
+    process: &ExecutionProcess,
+) -> NormalizedConversation {
+    normalize_process_logs_with(&app_state.db_pool, process, Some(app_state), true).await
+}
+
+/// The entries of the process's stdout, normalized by its executor and timed by when their
+/// output was received. `None` when the executor isn't one this version knows.
+async fn normalize_stdout(
+    db_pool: &DbPool,
+    process: &ExecutionProcess,
+    executor_session: Option<&crate::models::executor_session::ExecutorSession>,
+    live: Option<&AppState>,
+) -> Option<Vec<NormalizedEntry>> {
+    let mut stdout_entries = Vec::new();
+    if let Some(stdout) = &process.stdout {
+        if !stdout.trim().is_empty() {
+            let executor_type = process.executor_type.as_deref().unwrap_or("unknown");
+            let executor_config = if process.process_type == ExecutionProcessType::SetupScript {
+                ExecutorConfig::SetupScript {
+                    script: executor_session
+                        .and_then(|s| s.prompt.clone())
+                        .unwrap_or_else(|| "setup script".to_string()),
+                }
+            } else {
+                match executor_type.to_string().parse() {
+                    Ok(config) => config,
+                    Err(_) => return None,
+                }
+            };
+            let executor = executor_config.create_executor();
+            let working_dir_path = match std::fs::canonicalize(&process.working_directory) {
+                Ok(canonical_path) => canonical_path.to_string_lossy().to_string(),
+                Err(_) => process.working_directory.clone(),
+            };
+            let timeline = log_storage::timeline(db_pool, process.id, LogStream::Stdout)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Failed to load the timing of process {}: {}", process.id, e);
+                    Vec::new()
+                });
+            // A trimmed log changes in the middle, so it can't be followed incrementally; it
+            // is short enough to normalize in full instead
+            let incremental = match live {
+                Some(app_state) if !log_storage::was_trimmed(process.id, LogStream::Stdout) => {
+                    app_state
+                        .normalize_running_output(
+                            process.id,
+                            executor.as_ref(),
+                            executor_type,
+                            stdout,
+                            &timeline,
+                            &working_dir_path,
+                        )
+                        .await
+                }
+                _ => None,
+            };
+            let normalized = incremental.unwrap_or_else(|| {
+                // Output stored before receive times were recorded can only be normalized whole
+                if timeline.is_empty() {
+                    return executor.normalize_output(stdout, &working_dir_path);
+                }
+                let mut state = NormalizerState::new(working_dir_path.as_str());
+                state.advance_timed(executor.as_ref(), stdout, &timeline)?;
+                state.finish(executor.as_ref(), &timeline)?;
+                Ok(state.conversation(executor_type))
+            });
+            match normalized {
+                Ok(normalized) => {
+                    stdout_entries =
+                        plugins::normalize(executor_type.to_string(), normalized.entries).await;
+                    time_tool_calls(&mut stdout_entries, process.completed_at);
+                    identify_output_entries(&mut stdout_entries);
+                }
+                Err(_) => metrics::record_normalization_error(executor_type),
+            }
+        }
+    }
+    Some(stdout_entries)
+}
+
+/// `limit` cuts down entries too large to render (see [`entry_limits`]); the conversation
+/// is the same otherwise, so an entry's index finds its full content in the unlimited one
+async fn normalize_process_logs_with(
+    db_pool: &DbPool,
     process: &ExecutionProcess,
+    live: Option<&AppState>,
+    limit: bool,
 ) -> NormalizedConversation {
     use crate::models::{
         execution_process::ExecutionProcessType, executor_session::ExecutorSession,
@@ -96,46 +251,54 @@ async fn normalize_process_logs(
                 .unwrap_or("unknown".to_string()),
             prompt: executor_session.as_ref().and_then(|s| s.prompt.clone()),
             summary: executor_session.as_ref().and_then(|s| s.summary.clone()),
+            diff_summary: None,
         };
     }
 
     // Parse stdout as JSONL using executor normalization
-    let mut stdout_entries = Vec::new();
-    if let Some(stdout) = &process.stdout {
-        if !stdout.trim().is_empty() {
-            let executor_type = process.executor_type.as_deref().unwrap_or("unknown");
-            let executor_config = if process.process_type == ExecutionProcessType::SetupScript {
-                ExecutorConfig::SetupScript {
-                    script: executor_session
-                        .as_ref()
-                        .and_then(|s| s.prompt.clone())
-                        .unwrap_or_else(|| "setup script".to_string()),
-                }
-            } else {
-                match executor_type.to_string().parse() {
-                    Ok(config) => config,
-                    Err(_) => {
-                        return NormalizedConversation {
-                            entries: vec![],
-                            session_id: None,
-                            executor_type: executor_type.to_string(),
-                            prompt: executor_session.as_ref().and_then(|s| s.prompt.clone()),
-                            summary: executor_session.as_ref().and_then(|s| s.summary.clone()),
-                        };
-                    }
-                }
-            };
-            let executor = executor_config.create_executor();
-            let working_dir_path = match std::fs::canonicalize(&process.working_directory) {
-                Ok(canonical_path) => canonical_path.to_string_lossy().to_string(),
-                Err(_) => process.working_directory.clone(),
+    let Some(mut stdout_entries) =
+        normalize_stdout(db_pool, process, executor_session.as_ref(), live).await
+    else {
+        let executor_type = process.executor_type.as_deref().unwrap_or("unknown");
+        return NormalizedConversation {
+            entries: vec![],
+            session_id: None,
+            executor_type: executor_type.to_string(),
+            prompt: executor_session.as_ref().and_then(|s| s.prompt.clone()),
+            summary: executor_session.as_ref().and_then(|s| s.summary.clone()),
+            diff_summary: None,
+        };
+    };
+    // Messages sent while it ran go after the output there was when they were sent
+    if let Ok(inputs) = ExecutionInput::find_by_execution_process_id(db_pool, process.id).await {
+        for input in inputs.into_iter().rev() {
+            let index = usize::try_from(input.entry_index)
+                .unwrap_or(0)
+                .min(stdout_entries.len());
+            let timestamp = match index {
+                0 => Some(input.created_at.to_rfc3339()),
+                _ => stdout_entries[index - 1].timestamp.clone(),
             };
-            if let Ok(normalized) = executor.normalize_logs(stdout, &working_dir_path) {
-                stdout_entries = normalized.entries;
-            }
+            stdout_entries.insert(
+                index,
+                NormalizedEntry {
+                    id: Some(format!("input-{}", input.id)),
+                    timestamp,
+                    duration_ms: None,
+                    entry_type: NormalizedEntryType::UserMessage,
+                    content: input.content,
+                    metadata: None,
+                },
+            );
         }
     }
-    // Parse stderr chunks separated by boundary markers
+    // Parse stderr chunks separated by boundary markers. Setup scripts write their progress
+    // there (package managers do), so theirs is shown as ordinary output.
+    let stderr_entry_type = if process.process_type == ExecutionProcessType::SetupScript {
+        NormalizedEntryType::SystemMessage
+    } else {
+        NormalizedEntryType::ErrorMessage
+    };
     let mut stderr_entries = Vec::new();
     if let Some(stderr) = &process.stderr {
         let trimmed = stderr.trim();
@@ -147,8 +310,10 @@ async fn normalize_process_logs(
                     let filtered_content = chunk_trimmed.replace("---STDERR_CHUNK_BOUNDARY---", "");
                     if !filtered_content.trim().is_empty() {
                         stderr_entries.push(NormalizedEntry {
+                            id: Some(format!("stderr-{}", stderr_entries.len())),
                             timestamp: Some(chrono::Utc::now().to_rfc3339()),
-                            entry_type: NormalizedEntryType::ErrorMessage,
+                            duration_ms: None,
+                            entry_type: stderr_entry_type.clone(),
                             content: filtered_content.trim().to_string(),
                             metadata: None,
                         });
@@ -157,9 +322,196 @@ async fn normalize_process_logs(
             }
         }
     }
+    // The other entries are identified by where they came from too, so each keeps its id
+    // however the entries are sorted
     let mut all_entries = Vec::new();
+    if process.process_type == ExecutionProcessType::CodingAgent {
+        if let Ok(attachments) =
+            TaskAttachment::find_by_execution_process_id(db_pool, process.id).await
+        {
+            all_entries.extend(attachments.into_iter().map(|attachment| NormalizedEntry {
+                id: Some(format!("attachment-{}", attachment.id)),
+                timestamp: Some(process.started_at.to_rfc3339()),
+                duration_ms: None,
+                entry_type: NormalizedEntryType::Attachment {
+                    attachment_id: attachment.id,
+                    content_type: attachment.content_type,
+                },
+                content: attachment.file_name,
+                metadata: None,
+            }));
+        }
+    }
     all_entries.extend(stdout_entries);
     all_entries.extend(stderr_entries);
+    log_redaction::redact_entries(&mut all_entries);
+    if limit {
+        entry_limits::limit_entries(&mut all_entries);
+    }
+    if let Ok(Some(interruption)) =
+        NetworkInterruption::find_by_execution_process_id(db_pool, process.id).await
+    {
+        all_entries.push(NormalizedEntry {
+            id: Some("interruption".to_string()),
+            timestamp: Some(interruption.created_at.to_rfc3339()),
+            duration_ms: None,
+            entry_type: NormalizedEntryType::SystemMessage,
+            content: interruption.conversation_note(),
+            metadata: None,
+        });
+    }
+    // A plan mode's plan is already in the logs as the tool use that presented it
+    let plans_natively = process
+        .executor_type
+        .as_deref()
+        .and_then(|executor| executor.parse::<ExecutorConfig>().ok())
+        .is_some_and(|config| config.implementation_executor().is_some());
+    if let Ok(Some(plan)) =
+        AttemptPlan::find_by_task_attempt_id(db_pool, process.task_attempt_id).await
+    {
+        if plan.execution_process_id == process.id && !plans_natively {
+            all_entries.push(NormalizedEntry {
+                id: Some("plan".to_string()),
+                timestamp: Some(plan.created_at.to_rfc3339()),
+                duration_ms: None,
+                entry_type: NormalizedEntryType::PlanEntry,
+                content: plan.content,
+                metadata: Some(serde_json::json!({ "status": plan.status })),
+            });
+        }
+    }
+    if let Ok(verifications) =
+        ExecutionVerification::find_by_execution_process_id(db_pool, process.id).await
+    {
+        all_entries.extend(
+            verifications
+                .into_iter()
+                .map(|verification| NormalizedEntry {
+                    id: Some(format!("verification-{}", verification.id)),
+                    timestamp: Some(verification.created_at.to_rfc3339()),
+                    duration_ms: None,
+                    entry_type: NormalizedEntryType::Verification {
+                        command: verification.command,
+                        passed: verification.passed,
+                        exit_code: verification.exit_code.map(|code| code as i32),
+                        duration_ms: u32::try_from(verification.duration_ms).unwrap_or(u32::MAX),
+                    },
+                    content: verification.output,
+                    metadata: None,
+                }),
+        );
+    }
+    if process.status == ExecutionProcessStatus::TimedOut {
+        let stopped_at = process.completed_at.unwrap_or(process.updated_at);
+        let minutes = (stopped_at - process.started_at).num_minutes();
+        all_entries.push(NormalizedEntry {
+            id: Some("timed-out".to_string()),
+            timestamp: Some(stopped_at.to_rfc3339()),
+            duration_ms: None,
+            entry_type: NormalizedEntryType::SystemMessage,
+            content: format!(
+                "Stopped after running for {} minute{}, past its timeout. Raise the task's timeout or the default in Settings to give it longer.",
+                minutes,
+                if minutes == 1 { "" } else { "s" }
+            ),
+            metadata: None,
+        });
+    }
+    if process.status == ExecutionProcessStatus::ResourceLimitExceeded {
+        all_entries.push(NormalizedEntry {
+            id: Some("resource-limit".to_string()),
+            timestamp: Some(
+                process
+                    .completed_at
+                    .unwrap_or(process.updated_at)
+                    .to_rfc3339(),
+            ),
+            duration_ms: None,
+            entry_type: NormalizedEntryType::SystemMessage,
+            content: "Stopped for going over its memory limit. Raise the resource limits in Settings to give it more room.".to_string(),
+            metadata: None,
+        });
+    }
+    // A rate-limited run still waiting to go on says so below instead
+    if let Some(reason) = process
+        .failure_reason
+        .filter(|_| process.retry_at.is_none())
+    {
+        all_entries.push(NormalizedEntry {
+            id: Some("failure".to_string()),
+            timestamp: Some(
+                process
+                    .completed_at
+                    .unwrap_or(process.updated_at)
+                    .to_rfc3339(),
+            ),
+            duration_ms: None,
+            entry_type: NormalizedEntryType::SystemMessage,
+            content: format!("{}. {}", reason.label(), reason.remediation()),
+            metadata: Some(serde_json::json!({ "failure_reason": reason })),
+        });
+    }
+    if process.process_type == ExecutionProcessType::SetupScript
+        && process.status == ExecutionProcessStatus::Failed
+    {
+        all_entries.push(NormalizedEntry {
+            id: Some("setup-failed".to_string()),
+            timestamp: Some(
+                process
+                    .completed_at
+                    .unwrap_or(process.updated_at)
+                    .to_rfc3339(),
+            ),
+            duration_ms: None,
+            entry_type: NormalizedEntryType::ErrorMessage,
+            content: match process.exit_code {
+                Some(code) => format!(
+                    "The setup script exited with code {}, so the coding agent was not started.",
+                    code
+                ),
+                None => "The setup script failed, so the coding agent was not started.".to_string(),
+            },
+            metadata: None,
+        });
+    }
+    if process.retry_count > 0 {
+        all_entries.push(NormalizedEntry {
+            id: Some("retried".to_string()),
+            timestamp: Some(process.started_at.to_rfc3339()),
+            duration_ms: None,
+            entry_type: NormalizedEntryType::SystemMessage,
+            content: format!(
+                "Started again after failing to start (retry {}).",
+                process.retry_count
+            ),
+            metadata: None,
+        });
+    }
+    if let Some(retry_at) = process.retry_at {
+        all_entries.push(NormalizedEntry {
+            id: Some("retry".to_string()),
+            timestamp: Some(
+                process
+                    .completed_at
+                    .unwrap_or(process.updated_at)
+                    .to_rfc3339(),
+            ),
+            duration_ms: None,
+            entry_type: NormalizedEntryType::SystemMessage,
+            content: if process.status == ExecutionProcessStatus::RateLimited {
+                format!(
+                    "Rate limited by the provider. Going on at {} once the limit resets.",
+                    retry_at.format("%H:%M:%S UTC")
+                )
+            } else {
+                format!(
+                    "Failed to start. Trying again at {}.",
+                    retry_at.format("%H:%M:%S UTC")
+                )
+            },
+            metadata: None,
+        });
+    }
     all_entries.sort_by(|a, b| match (&a.timestamp, &b.timestamp) {
         (Some(a_ts), Some(b_ts)) => a_ts.cmp(b_ts),
         (Some(_), None) => std::cmp::Ordering::Less,
@@ -174,12 +526,44 @@ async fn normalize_process_logs(
             .clone()
             .unwrap_or("unknown".to_string())
     };
+    let diff = ExecutionDiff::find_by_execution_process_id(db_pool, process.id)
+        .await
+        .ok()
+        .flatten();
+    // Files are linked at the commit the run left, or while it runs the one it started from
+    let commit = process
+        .commit_sha
+        .clone()
+        .or_else(|| diff.as_ref().map(|diff| diff.from_commit.clone()));
+    let web = match TaskAttempt::find_project_id(db_pool, process.task_attempt_id).await {
+        Ok(Some(project_id)) if commit.is_some() => {
+            git_host::repository_web(db_pool, project_id, &process.working_directory)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Failed to look up the repository's git host: {}", e);
+                    None
+                })
+        }
+        _ => None,
+    };
+    let worktree_path = std::fs::canonicalize(&process.working_directory)
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|_| process.working_directory.clone());
+    file_locations::resolve(
+        &mut all_entries,
+        &worktree_path,
+        web.as_ref().zip(commit.as_deref()),
+    );
+    let diff_summary = diff
+        .filter(|diff| diff.to_commit.is_some())
+        .map(|diff| diff.summary);
     NormalizedConversation {
         entries: all_entries,
         session_id: None,
         executor_type,
         prompt: executor_session.as_ref().and_then(|s| s.prompt.clone()),
         summary: executor_session.as_ref().and_then(|s| s.summary.clone()),
+        diff_summary,
     }
 }
 
@@ -203,7 +587,7 @@ pub async fn get_task_attempt_all_logs(
     // For each process, normalize logs
     let mut result = Vec::new();
     for process in processes {
-        let normalized_conversation = normalize_process_logs(&app_state.db_pool, &process).await;
+        let normalized_conversation = normalize_live_process_logs(&app_state, &process).await;
         result.push(ProcessLogsResponse {
             id: process.id,
             process_type: process.process_type.clone(),
@@ -230,57 +614,213 @@ pub async fn get_task_attempts(
     }
 }
 
-pub async fn create_task_attempt(
-    Extension(_project): Extension<Project>,
+#[derive(Debug, Deserialize)]
+pub struct CompareAttemptsQuery {
+    pub left: Uuid,
+    pub right: Uuid,
+}
+
+pub async fn compare_task_attempts(
+    Extension(project): Extension<Project>,
     Extension(task): Extension<Task>,
     State(app_state): State<AppState>,
-    Json(payload): Json<CreateTaskAttempt>,
-) -> Result<ResponseJson<ApiResponse<TaskAttempt>>, StatusCode> {
-    let executor_string = payload.executor.as_ref().map(|exec| exec.to_string());
+    Query(query): Query<CompareAttemptsQuery>,
+) -> Result<ResponseJson<ApiResponse<AttemptComparison>>, StatusCode> {
+    match TaskAttempt::compare(
+        &app_state.db_pool,
+        query.left,
+        query.right,
+        task.id,
+        project.id,
+    )
+    .await
+    {
+        Ok(comparison) => Ok(ResponseJson(ApiResponse::success(comparison))),
+        Err(e @ (TaskAttemptError::TaskNotFound | TaskAttemptError::ValidationError(_))) => {
+            Ok(ResponseJson(ApiResponse::error(&e.to_string())))
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to compare attempts {} and {} of task {}: {}",
+                query.left,
+                query.right,
+                task.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
 
-    match TaskAttempt::create(&app_state.db_pool, &payload, task.id).await {
-        Ok(attempt) => {
-            app_state
-                .track_analytics_event(
-                    "task_attempt_started",
-                    Some(serde_json::json!({
-                        "task_id": task.id.to_string(),
-                        "executor_type": executor_string.as_deref().unwrap_or("default"),
-                        "attempt_id": attempt.id.to_string(),
-                    })),
-                )
-                .await;
+/// Fill in the executor the attempt runs with when it names none, and check the project's
+/// budget isn't spent, its repository config is valid and this machine can run the
+/// executor. `Ok(Some(message))` when it can't start.
+pub(crate) async fn prepare_attempt(
+    app_state: &AppState,
+    project: &Project,
+    task: &Task,
+    payload: &mut CreateTaskAttempt,
+) -> Result<Option<String>, StatusCode> {
+    match guardrails::budget_exceeded(&app_state.db_pool, project.id).await {
+        Ok(Some(exceeded)) => return Ok(Some(exceeded.to_string())),
+        Ok(None) => {}
+        Err(e) => {
+            tracing::error!(
+                "Failed to check the budget of project {}: {}",
+                project.id,
+                e
+            );
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+    // A task assigned a profile runs with it unless told otherwise, a task made from a
+    // template with the template's executor, and any other task with its project's
+    if payload.executor.is_none() && payload.executor_profile_id.is_none() {
+        match TaskDefaults::find_by_task_id(&app_state.db_pool, task.id).await {
+            Ok(Some(defaults)) => {
+                payload.executor_profile_id = defaults.executor_profile_id;
+                payload.executor = defaults.executor;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                tracing::error!("Failed to load defaults of task {}: {}", task.id, e);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        }
+    }
+    if let Some(profile_id) = payload.executor_profile_id {
+        match ExecutorProfile::find_by_id(&app_state.db_pool, profile_id).await {
+            Ok(Some(profile)) if profile.is_available_in(project.id) => {
+                payload.executor = Some(profile.executor);
+            }
+            Ok(_) => return Ok(Some("Executor profile not found".to_string())),
+            Err(e) => {
+                tracing::error!("Failed to load executor profile {}: {}", profile_id, e);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        }
+    }
+    // The repository's `.vibe-kanban.toml` is read on every start, so a broken one is
+    // reported before anything runs
+    let mut project = project.clone();
+    if let Err(message) = repo_config::apply(&mut project) {
+        return Ok(Some(message));
+    }
+    if payload.executor.is_none() {
+        payload.executor = project.default_executor;
+    }
 
-            // Start execution asynchronously (don't block the response)
-            let app_state_clone = app_state.clone();
-            let attempt_id = attempt.id;
-            let task_id = task.id;
-            let project_id = _project.id;
-            tokio::spawn(async move {
-                if let Err(e) = TaskAttempt::start_execution(
-                    &app_state_clone.db_pool,
-                    &app_state_clone,
-                    attempt_id,
-                    task_id,
-                    project_id,
-                )
-                .await
-                {
-                    tracing::error!(
-                        "Failed to start execution for task attempt {}: {}",
-                        attempt_id,
-                        e
-                    );
-                }
-            });
+    // Refuse up front an agent this machine can't run, rather than failing once started
+    let executor_config = payload
+        .executor
+        .as_deref()
+        .and_then(|executor| executor.parse::<ExecutorConfig>().ok());
+
+    if let Some(model) = payload.model.as_deref().map(str::trim) {
+        let config = match &executor_config {
+            Some(config) => config.clone(),
+            None => app_state.get_config().read().await.executor.clone(),
+        };
+        if !model.is_empty() {
+            if !config.supports_model() {
+                return Ok(Some(format!(
+                    "{} can't be given a model",
+                    config.display_name()
+                )));
+            }
+            if !crate::executor::is_valid_model_name(model) {
+                return Ok(Some(format!(
+                    "\"{}\" isn't a model name; use letters, digits and . - _ : /",
+                    model
+                )));
+            }
+        }
+    }
 
-            Ok(ResponseJson(ApiResponse::success(attempt)))
+    if let (Some(config), false) = (executor_config, app_state.dry_run) {
+        if let Err(e) = executor_preflight::validate(&config).await {
+            return Ok(Some(e.to_string()));
         }
-        Err(e) => {
+    }
+    Ok(None)
+}
+
+/// Create the attempt and start it in the background
+pub(crate) async fn launch_attempt(
+    app_state: &AppState,
+    project: &Project,
+    task: &Task,
+    payload: &CreateTaskAttempt,
+) -> Result<TaskAttempt, StatusCode> {
+    let attempt = TaskAttempt::create(&app_state.db_pool, payload, task.id)
+        .await
+        .map_err(|e| {
             tracing::error!("Failed to create task attempt: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    app_state
+        .track_analytics_event(
+            "task_attempt_started",
+            Some(serde_json::json!({
+                "task_id": task.id.to_string(),
+                "executor_type": payload.executor.as_deref().unwrap_or("default"),
+                "attempt_id": attempt.id.to_string(),
+                "plan_only": attempt.plan_only,
+            })),
+        )
+        .await;
+
+    // Start execution asynchronously (don't block the response)
+    let app_state_clone = app_state.clone();
+    let attempt_id = attempt.id;
+    let task_id = task.id;
+    let project_id = project.id;
+    tokio::spawn(async move {
+        if let Err(e) = TaskAttempt::start_execution(
+            &app_state_clone.db_pool,
+            &app_state_clone,
+            attempt_id,
+            task_id,
+            project_id,
+        )
+        .await
+        {
+            tracing::error!(
+                "Failed to start execution for task attempt {}: {}",
+                attempt_id,
+                e
+            );
+        }
+    });
+
+    Ok(attempt)
+}
+
+pub async fn create_task_attempt(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Json(mut payload): Json<CreateTaskAttempt>,
+) -> Result<ResponseJson<ApiResponse<TaskAttempt>>, StatusCode> {
+    match TaskDependency::unfinished_blockers(&app_state.db_pool, task.id).await {
+        Ok(blockers) if !blockers.is_empty() => {
+            return Ok(ResponseJson(ApiResponse::error(&blocked_message(
+                &blockers,
+            ))));
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::error!("Failed to check blockers of task {}: {}", task.id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
         }
     }
+
+    if let Some(message) = prepare_attempt(&app_state, &project, &task, &mut payload).await? {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+    let attempt = launch_attempt(&app_state, &project, &task, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(attempt)))
 }
 
 pub async fn get_task_attempt_diff(
@@ -302,80 +842,307 @@ pub async fn get_task_attempt_diff(
     }
 }
 
-#[axum::debug_handler]
-pub async fn merge_task_attempt(
-    Extension(project): Extension<Project>,
-    Extension(task): Extension<Task>,
+/// The changes of each linked project of a multi-repository attempt, kept apart from those
+/// of its own repository
+pub async fn get_task_attempt_linked_diffs(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(app_state): State<AppState>,
-) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
-    match TaskAttempt::merge_changes(&app_state.db_pool, task_attempt.id, task.id, project.id).await
-    {
-        Ok(_) => {
-            // Update task status to Done
-            if let Err(e) = Task::update_status(
-                &app_state.db_pool,
-                task.id,
-                project.id,
-                crate::models::task::TaskStatus::Done,
-            )
-            .await
-            {
-                tracing::error!("Failed to update task status to Done after merge: {}", e);
-                return Err(StatusCode::INTERNAL_SERVER_ERROR);
-            }
-
-            // Track task attempt merged event
-            app_state
-                .track_analytics_event(
-                    "task_attempt_merged",
-                    Some(serde_json::json!({
-                        "task_id": task.id.to_string(),
-                        "project_id": project.id.to_string(),
-                        "attempt_id": task_attempt.id.to_string(),
-                    })),
-                )
-                .await;
-
-            Ok(ResponseJson(ApiResponse::success(())))
-        }
+) -> Result<ResponseJson<ApiResponse<Vec<LinkedRepoDiff>>>, StatusCode> {
+    match TaskAttempt::get_linked_diffs(&app_state.db_pool, task_attempt.id).await {
+        Ok(diffs) => Ok(ResponseJson(ApiResponse::success(diffs))),
         Err(e) => {
-            tracing::error!("Failed to merge task attempt {}: {}", task_attempt.id, e);
-            Ok(ResponseJson(ApiResponse::error(&format!(
-                "Failed to merge: {}",
+            tracing::error!(
+                "Failed to get linked diffs for task attempt {}: {}",
+                task_attempt.id,
                 e
-            ))))
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
 }
 
-pub async fn create_github_pr(
+pub async fn get_task_attempt_diff_hunks(
     Extension(project): Extension<Project>,
     Extension(task): Extension<Task>,
     Extension(task_attempt): Extension<TaskAttempt>,
     State(app_state): State<AppState>,
-    Json(request): Json<CreateGitHubPRRequest>,
-) -> Result<ResponseJson<ApiResponse<String>>, StatusCode> {
-    // Load the user's GitHub configuration
-    let config = match Config::load(&crate::utils::config_path()) {
-        Ok(config) => config,
+) -> Result<ResponseJson<ApiResponse<Vec<FileHunks>>>, StatusCode> {
+    match TaskAttempt::get_diff_hunks(&app_state.db_pool, task_attempt.id, task.id, project.id)
+        .await
+    {
+        Ok(hunks) => Ok(ResponseJson(ApiResponse::success(hunks))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to get diff hunks for task attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn get_task_attempt_files(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<FileTreeNode>>>, StatusCode> {
+    match TaskAttempt::get_file_tree(&app_state.db_pool, task_attempt.id, task.id, project.id).await
+    {
+        Ok(tree) => Ok(ResponseJson(ApiResponse::success(tree))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to get file tree for task attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FileContentQuery {
+    pub path: String,
+    /// Read the file as of this commit instead of the working tree
+    pub commit: Option<String>,
+}
+
+pub async fn get_task_attempt_file_content(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+    Query(query): Query<FileContentQuery>,
+) -> Result<ResponseJson<ApiResponse<FileContent>>, StatusCode> {
+    match TaskAttempt::get_file_content(
+        &app_state.db_pool,
+        task_attempt.id,
+        task.id,
+        project.id,
+        &query.path,
+        query.commit.as_deref(),
+    )
+    .await
+    {
+        Ok(content) => Ok(ResponseJson(ApiResponse::success(content))),
+        Err(TaskAttemptError::GitService(GitServiceError::InvalidPath(message))) => {
+            Ok(ResponseJson(ApiResponse::error(&message)))
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to read {} for task attempt {}: {}",
+                query.path,
+                task_attempt.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Merge the attempt's branch into its base branch and mark the task done. Returns the merge
+/// commit, or why the merge failed.
+pub(crate) async fn merge_attempt(
+    app_state: &AppState,
+    project: &Project,
+    task: &Task,
+    task_attempt: &TaskAttempt,
+) -> Result<Result<String, String>, StatusCode> {
+    if let Err(reason) = plugins::validate(Validation::Merge {
+        project_id: project.id,
+        task_id: task.id,
+        task_attempt_id: task_attempt.id,
+        task_title: task.title.clone(),
+        branch: task_attempt.branch.clone(),
+        base_branch: task_attempt.base_branch.clone(),
+    })
+    .await
+    {
+        return Ok(Err(reason));
+    }
+    if let Some(reason) = script_hooks::before_merge(app_state, task, task_attempt).await {
+        return Ok(Err(reason));
+    }
+
+    // Wait for queued merges into the same base branch instead of landing between them
+    let lane = app_state
+        .merge_queue
+        .lane(project.id, &task_attempt.base_branch);
+    let _turn = lane.lock().await;
+
+    match TaskAttempt::merge_changes(&app_state.db_pool, task_attempt.id, task.id, project.id).await
+    {
+        Ok(merge_commit) => {
+            // Update task status to Done
+            if let Err(e) = Task::update_status(
+                &app_state.db_pool,
+                task.id,
+                project.id,
+                crate::models::task::TaskStatus::Done,
+            )
+            .await
+            {
+                tracing::error!("Failed to update task status to Done after merge: {}", e);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+
+            // Track task attempt merged event
+            app_state
+                .track_analytics_event(
+                    "task_attempt_merged",
+                    Some(serde_json::json!({
+                        "task_id": task.id.to_string(),
+                        "project_id": project.id.to_string(),
+                        "attempt_id": task_attempt.id.to_string(),
+                    })),
+                )
+                .await;
+
+            Ok(Ok(merge_commit))
+        }
+        Err(e) => {
+            tracing::error!("Failed to merge task attempt {}: {}", task_attempt.id, e);
+            Ok(Err(format!("Failed to merge: {}", e)))
+        }
+    }
+}
+
+#[axum::debug_handler]
+pub async fn merge_task_attempt(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    if app_state.dry_run {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Merging is disabled in dry-run mode",
+        )));
+    }
+    if app_state
+        .get_config()
+        .read()
+        .await
+        .review_gate
+        .require_approval
+    {
+        return Ok(ResponseJson(ApiResponse::error(REVIEW_REQUIRED)));
+    }
+
+    match merge_attempt(&app_state, &project, &task, &task_attempt).await? {
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(message) => Ok(ResponseJson(ApiResponse::error(&message))),
+    }
+}
+
+/// Queue the attempt to be rebased, checked and merged after the merges ahead of it
+pub async fn queue_task_attempt_merge(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<MergeQueueEntry>>, StatusCode> {
+    if app_state.dry_run {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Merging is disabled in dry-run mode",
+        )));
+    }
+    if app_state
+        .get_config()
+        .read()
+        .await
+        .review_gate
+        .require_approval
+    {
+        return Ok(ResponseJson(ApiResponse::error(REVIEW_REQUIRED)));
+    }
+    if task_attempt.merge_commit.is_some() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "This attempt has already been merged",
+        )));
+    }
+    if app_state.has_running_execution(task_attempt.id).await {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Stop the attempt's running processes before queueing it to merge",
+        )));
+    }
+
+    let entry = match merge_queue::enqueue(&app_state.db_pool, &project, &task, &task_attempt).await
+    {
+        Ok(entry) => entry,
+        Err(MergeQueueError::Database(e)) => {
+            tracing::error!(
+                "Failed to queue task attempt {} to merge: {}",
+                task_attempt.id,
+                e
+            );
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+        Err(e) => return Ok(ResponseJson(ApiResponse::error(&e.to_string()))),
+    };
+    tokio::spawn(merge_queue::process(
+        app_state.clone(),
+        project,
+        entry.clone(),
+    ));
+    Ok(ResponseJson(ApiResponse::success(entry)))
+}
+
+pub async fn create_github_pr(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+    Json(request): Json<CreateGitHubPRRequest>,
+) -> Result<ResponseJson<ApiResponse<String>>, StatusCode> {
+    open_pull_request(&app_state, &project, &task, &task_attempt, request).await
+}
+
+/// Push the attempt's branch and open a pull request for it, recording it on the attempt.
+/// Returns the pull request's URL.
+pub(crate) async fn open_pull_request(
+    app_state: &AppState,
+    project: &Project,
+    task: &Task,
+    task_attempt: &TaskAttempt,
+    request: CreateGitHubPRRequest,
+) -> Result<ResponseJson<ApiResponse<String>>, StatusCode> {
+    if app_state.dry_run {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Creating pull requests is disabled in dry-run mode",
+        )));
+    }
+
+    // Load the user's GitHub configuration
+    let config = match Config::load(&crate::utils::config_path()) {
+        Ok(config) => config,
         Err(e) => {
             tracing::error!("Failed to load config: {}", e);
             return Err(StatusCode::INTERNAL_SERVER_ERROR);
         }
     };
 
-    let github_token = match config.github.token {
-        Some(token) => token,
-        None => {
-            return Ok(ResponseJson(ApiResponse::error(
-                "GitHub authentication not configured. Please sign in with GitHub.",
-            )));
+    let host = match git_host::for_project(
+        &app_state.db_pool,
+        project.id,
+        &project.git_repo_path,
+        &config.github,
+    )
+    .await
+    {
+        Ok(host) => host,
+        Err(GitHostError::Database(e)) => {
+            tracing::error!("Failed to load git host of project {}: {}", project.id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
         }
+        Err(e) => return Ok(ResponseJson(ApiResponse::error(&e.to_string()))),
     };
+    let on_github = host.kind() == GitHostKind::GitHub;
 
     // Get the task attempt to access the stored base branch
-    let attempt = &task_attempt;
+    let attempt = task_attempt;
 
     let base_branch = request.base_branch.unwrap_or_else(|| {
         // Use the stored base branch from the task attempt as the default
@@ -386,21 +1153,45 @@ pub async fn create_github_pr(
             config
                 .github
                 .default_pr_base
+                .clone()
                 .unwrap_or_else(|| "main".to_string())
         }
     });
 
-    match TaskAttempt::create_github_pr(
+    // An empty body gets the same generated description the dialog offers
+    let body = match request.body.filter(|body| !body.trim().is_empty()) {
+        Some(body) => Some(body),
+        None => match pr_description::generate(
+            &app_state.db_pool,
+            &config,
+            task_attempt.id,
+            task.id,
+            project.id,
+        )
+        .await
+        {
+            Ok(description) => Some(description.body),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to generate PR description for attempt {}: {}",
+                    task_attempt.id,
+                    e
+                );
+                None
+            }
+        },
+    };
+
+    match TaskAttempt::create_pr(
         &app_state.db_pool,
         CreatePrParams {
             attempt_id: task_attempt.id,
             task_id: task.id,
             project_id: project.id,
-            github_token: &config.github.pat.unwrap_or(github_token),
+            host: host.as_ref(),
             title: &request.title,
-            body: request.body.as_deref(),
+            body: body.as_deref(),
             base_branch: Some(&base_branch),
-            enterprise_url: config.github.enterprise_url.as_deref(),
         },
     )
     .await
@@ -413,6 +1204,7 @@ pub async fn create_github_pr(
                         "task_id": task.id.to_string(),
                         "project_id": project.id.to_string(),
                         "attempt_id": task_attempt.id.to_string(),
+                        "git_host": host.kind(),
                     })),
                 )
                 .await;
@@ -421,30 +1213,32 @@ pub async fn create_github_pr(
         }
         Err(e) => {
             tracing::error!(
-                "Failed to create GitHub PR for attempt {}: {}",
+                "Failed to create {} pull request for attempt {}: {}",
+                host.kind().name(),
                 task_attempt.id,
                 e
             );
+            // The GitHub errors the frontend knows how to help with
             let message = match &e {
-                crate::models::task_attempt::TaskAttemptError::GitHubService(
-                    crate::services::GitHubServiceError::TokenInvalid,
-                ) => Some("github_token_invalid".to_string()),
-                crate::models::task_attempt::TaskAttemptError::GitService(
-                    crate::services::git_service::GitServiceError::Git(err),
-                ) if err
-                    .message()
-                    .contains("too many redirects or authentication replays") =>
+                TaskAttemptError::GitHost(GitHostError::TokenInvalid(GitHostKind::GitHub)) => {
+                    Some("github_token_invalid".to_string())
+                }
+                TaskAttemptError::GitService(GitServiceError::Git(err))
+                    if on_github
+                        && err
+                            .message()
+                            .contains("too many redirects or authentication replays") =>
                 {
                     Some("insufficient_github_permissions".to_string()) // PAT is invalid
                 }
-                crate::models::task_attempt::TaskAttemptError::GitService(
-                    crate::services::git_service::GitServiceError::Git(err),
-                ) if err.message().contains("status code: 403") => {
+                TaskAttemptError::GitService(GitServiceError::Git(err))
+                    if on_github && err.message().contains("status code: 403") =>
+                {
                     Some("insufficient_github_permissions".to_string())
                 }
-                crate::models::task_attempt::TaskAttemptError::GitService(
-                    crate::services::git_service::GitServiceError::Git(err),
-                ) if err.message().contains("status code: 404") => {
+                TaskAttemptError::GitService(GitServiceError::Git(err))
+                    if on_github && err.message().contains("status code: 404") =>
+                {
                     Some("github_repo_not_found_or_no_access".to_string())
                 }
                 _ => Some(format!("Failed to create PR: {}", e)),
@@ -456,6 +1250,51 @@ pub async fn create_github_pr(
     }
 }
 
+pub async fn get_pr_description(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<PrDescription>>, StatusCode> {
+    match TaskPrDescription::find_for_attempt(&app_state.db_pool, task.id, task_attempt.id).await {
+        Ok(Some(generated)) => {
+            return Ok(ResponseJson(ApiResponse::success(PrDescription {
+                title: generated.title,
+                body: generated.body,
+            })));
+        }
+        Ok(None) => {}
+        Err(e) => {
+            tracing::error!("Failed to load PR description of task {}: {}", task.id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    let config = app_state.get_config().read().await.clone();
+    match pr_description::generate(
+        &app_state.db_pool,
+        &config,
+        task_attempt.id,
+        task.id,
+        project.id,
+    )
+    .await
+    {
+        Ok(description) => Ok(ResponseJson(ApiResponse::success(description))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to generate PR description for attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            Ok(ResponseJson(ApiResponse::error(&format!(
+                "Failed to generate PR description: {}",
+                e
+            ))))
+        }
+    }
+}
+
 #[derive(serde::Deserialize)]
 pub struct OpenEditorRequest {
     editor_type: Option<String>,
@@ -559,25 +1398,82 @@ pub async fn rebase_task_attempt(
     request_body: Option<Json<RebaseTaskAttemptRequest>>,
 ) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
     // Extract new base branch from request body if provided
-    let new_base_branch = request_body.and_then(|body| body.new_base_branch.clone());
+    let (new_base_branch, resolve_conflicts) = match request_body {
+        Some(Json(body)) => (body.new_base_branch, body.resolve_conflicts),
+        None => (None, false),
+    };
+
+    if resolve_conflicts {
+        if let Some(message) = followup_unsupported(&app_state.db_pool, &task_attempt).await {
+            return Ok(ResponseJson(ApiResponse::error(&message)));
+        }
+    }
 
-    match TaskAttempt::rebase_attempt(
+    let outcome = match TaskAttempt::rebase_attempt(
         &app_state.db_pool,
         task_attempt.id,
         task.id,
         project.id,
-        new_base_branch,
+        new_base_branch.clone(),
+        resolve_conflicts,
     )
     .await
     {
-        Ok(_new_base_commit) => Ok(ResponseJson(ApiResponse::success(()))),
+        Ok(outcome) => outcome,
         Err(e) => {
             tracing::error!("Failed to rebase task attempt {}: {}", task_attempt.id, e);
-            Ok(ResponseJson(ApiResponse::error(&e.to_string())))
+            return Ok(ResponseJson(ApiResponse::error(&e.to_string())));
+        }
+    };
+
+    if outcome.conflicted_files.is_empty() {
+        return Ok(ResponseJson(ApiResponse::success(())));
+    }
+
+    let base_branch = new_base_branch.unwrap_or(task_attempt.base_branch);
+    let prompt = conflict_resolution_prompt(&base_branch, &outcome.conflicted_files);
+    match TaskAttempt::start_followup_execution(
+        &app_state.db_pool,
+        &app_state,
+        task_attempt.id,
+        task.id,
+        project.id,
+        &prompt,
+    )
+    .await
+    {
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to start resolving the rebase conflicts of task attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            Ok(ResponseJson(ApiResponse::error(&format!(
+                "Rebased with conflicts in {}, but the agent couldn't be started to resolve them: {}",
+                outcome.conflicted_files.join(", "),
+                e
+            ))))
         }
     }
 }
 
+/// Asks the agent to resolve the conflict markers a rebase left in its worktree
+fn conflict_resolution_prompt(base_branch: &str, conflicted_files: &[String]) -> String {
+    let files: Vec<String> = conflicted_files
+        .iter()
+        .map(|file| format!("- {}", file))
+        .collect();
+    format!(
+        "Your changes were rebased onto the latest `{}`, which changed the same lines as you \
+         in these files:\n\n{}\n\nEach conflict is marked with `<<<<<<<`, `=======` and \
+         `>>>>>>>`. Resolve every conflict so both your changes and the new base work \
+         together, remove the markers, and make sure the project still builds.",
+        base_branch,
+        files.join("\n")
+    )
+}
+
 pub async fn get_task_attempt_execution_processes(
     Extension(_project): Extension<Project>,
     Extension(_task): Extension<Task>,
@@ -605,35 +1501,120 @@ pub async fn get_execution_process(
     Ok(ResponseJson(ApiResponse::success(execution_process)))
 }
 
-#[axum::debug_handler]
-pub async fn stop_all_execution_processes(
-    Extension(_project): Extension<Project>,
-    Extension(_task): Extension<Task>,
-    Extension(task_attempt): Extension<TaskAttempt>,
+/// What a coding agent run changed, captured when it finished
+pub async fn get_execution_process_diff(
+    Extension(execution_process): Extension<ExecutionProcess>,
     State(app_state): State<AppState>,
-) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
-    // Get all execution processes for the task attempt
-    let processes = match ExecutionProcess::find_by_task_attempt_id(
-        &app_state.db_pool,
-        task_attempt.id,
-    )
-    .await
+) -> Result<ResponseJson<ApiResponse<ExecutionDiff>>, StatusCode> {
+    match ExecutionDiff::find_by_execution_process_id(&app_state.db_pool, execution_process.id)
+        .await
     {
-        Ok(processes) => processes,
+        Ok(Some(diff)) => Ok(ResponseJson(ApiResponse::success(diff))),
+        Ok(None) => Ok(ResponseJson(ApiResponse::error(
+            "No diff was captured for this execution",
+        ))),
         Err(e) => {
             tracing::error!(
-                "Failed to fetch execution processes for attempt {}: {}",
-                task_attempt.id,
+                "Failed to fetch diff of execution process {}: {}",
+                execution_process.id,
                 e
             );
-            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
-    };
-
-    let mut stopped_count = 0;
-    let mut errors = Vec::new();
+    }
+}
 
-    // Stop all running processes
+/// The CPU and memory the execution used, sampled while it ran
+pub async fn get_execution_process_resources(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<ExecutionResources>>, StatusCode> {
+    match ExecutionResources::find_for_execution(&app_state.db_pool, &execution_process).await {
+        Ok(Some(resources)) => Ok(ResponseJson(ApiResponse::success(resources))),
+        Ok(None) => Ok(ResponseJson(ApiResponse::error(
+            "No resource usage was sampled for this execution",
+        ))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch resource usage of execution process {}: {}",
+                execution_process.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// What the run did with its tools: files read and written, commands run, searches and fetches
+pub async fn get_execution_process_tool_usage(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<ExecutionToolUsage>>, StatusCode> {
+    match ExecutionToolUsage::find_by_execution_process_id(&app_state.db_pool, execution_process.id)
+        .await
+    {
+        Ok(Some(usage)) => Ok(ResponseJson(ApiResponse::success(usage))),
+        Ok(None) => Ok(ResponseJson(ApiResponse::error(
+            "No tool usage was recorded for this execution",
+        ))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch tool usage of execution process {}: {}",
+                execution_process.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// The tool usage of each of the attempt's coding agent runs that ended
+pub async fn get_task_attempt_tool_usage(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<ExecutionToolUsage>>>, StatusCode> {
+    match ExecutionToolUsage::find_by_task_attempt_id(&app_state.db_pool, task_attempt.id).await {
+        Ok(usage) => Ok(ResponseJson(ApiResponse::success(usage))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch tool usage of task attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+#[axum::debug_handler]
+pub async fn stop_all_execution_processes(
+    Extension(_project): Extension<Project>,
+    Extension(_task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    // Get all execution processes for the task attempt
+    let processes = match ExecutionProcess::find_by_task_attempt_id(
+        &app_state.db_pool,
+        task_attempt.id,
+    )
+    .await
+    {
+        Ok(processes) => processes,
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch execution processes for attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let mut stopped_count = 0;
+    let mut errors = Vec::new();
+
+    // Stop all running processes
     for process in processes {
         match app_state.stop_running_execution_by_id(process.id).await {
             Ok(true) => {
@@ -664,6 +1645,71 @@ pub async fn stop_all_execution_processes(
         }
     }
 
+    // An attempt waiting on the network or on a retry has nothing running, but stopping it
+    // should still cancel what is pending
+    let cancelled_retries =
+        match ExecutionProcess::cancel_retries_for_attempt(&app_state.db_pool, task_attempt.id)
+            .await
+        {
+            Ok(cancelled) => cancelled,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to cancel start retries for attempt {}: {}",
+                    task_attempt.id,
+                    e
+                );
+                errors.push("Failed to cancel pending start retry".to_string());
+                0
+            }
+        };
+    match NetworkInterruption::abandon_for_attempt(&app_state.db_pool, task_attempt.id)
+        .await
+        .map(|abandoned| abandoned + cancelled_retries)
+    {
+        Ok(0) => {}
+        Ok(_) => {
+            if let Err(e) = Task::update_status(
+                &app_state.db_pool,
+                _task.id,
+                _project.id,
+                TaskStatus::InReview,
+            )
+            .await
+            {
+                tracing::error!("Failed to update task status after stopping retry: {}", e);
+            }
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to cancel network retry for attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            errors.push("Failed to cancel pending network retry".to_string());
+        }
+    }
+
+    // A queued attempt hasn't started yet, so stopping it just takes it off the queue
+    match QueuedAttempt::remove(&app_state.db_pool, task_attempt.id).await {
+        Ok(false) => {}
+        Ok(true) => {
+            if let Err(e) =
+                Task::update_status(&app_state.db_pool, _task.id, _project.id, TaskStatus::Todo)
+                    .await
+            {
+                tracing::error!("Failed to update task status after unqueueing: {}", e);
+            }
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to take attempt {} off the queue: {}",
+                task_attempt.id,
+                e
+            );
+            errors.push("Failed to take the attempt off the queue".to_string());
+        }
+    }
+
     if !errors.is_empty() {
         return Ok(ResponseJson(ApiResponse::error(&format!(
             "Stopped {} processes, but encountered errors: {}",
@@ -725,6 +1771,301 @@ pub async fn stop_execution_process(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// Suspend a running process with everything it started, or continue a paused one
+async fn set_execution_process_paused(
+    app_state: &AppState,
+    execution_process: &ExecutionProcess,
+    paused: bool,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    let (expected, action) = if paused {
+        (ExecutionProcessStatus::Running, "pause")
+    } else {
+        (ExecutionProcessStatus::Paused, "resume")
+    };
+    if execution_process.status != expected {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "Only a {} process can be {}d",
+            if paused { "running" } else { "paused" },
+            action
+        ))));
+    }
+
+    let result = if paused {
+        app_state
+            .pause_running_execution_by_id(execution_process.id)
+            .await
+    } else {
+        app_state
+            .resume_running_execution_by_id(execution_process.id)
+            .await
+    };
+    match result {
+        Ok(true) => {}
+        Ok(false) => {
+            return Ok(ResponseJson(ApiResponse::error(
+                "The process has already finished",
+            )));
+        }
+        Err(e @ CommandError::Unsupported { .. }) => {
+            return Ok(ResponseJson(ApiResponse::error(&e.to_string())));
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to {} execution process {}: {}",
+                action,
+                execution_process.id,
+                e
+            );
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    if let Err(e) =
+        ExecutionProcess::set_paused(&app_state.db_pool, execution_process.id, paused).await
+    {
+        tracing::error!("Failed to update execution process status: {}", e);
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn pause_execution_process(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    set_execution_process_paused(&app_state, &execution_process, true).await
+}
+
+pub async fn resume_execution_process(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    set_execution_process_paused(&app_state, &execution_process, false).await
+}
+
+/// The command the process is waiting to be allowed or denied, if it is
+pub async fn get_execution_process_approval(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Option<PendingApproval>>>, StatusCode> {
+    let approval = app_state.pending_approval(execution_process.id).await;
+    Ok(ResponseJson(ApiResponse::success(approval)))
+}
+
+/// Allow or deny the command the process is waiting on, by writing the answer to its CLI
+pub async fn answer_execution_process_approval(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(app_state): State<AppState>,
+    Json(decision): Json<ApprovalDecision>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    // Requests that came with their own answers are answered with those
+    let own_reply = app_state
+        .pending_approval(execution_process.id)
+        .await
+        .filter(|approval| approval.id == decision.approval_id)
+        .and_then(|approval| approval.replies)
+        .map(|replies| {
+            if decision.allow {
+                replies.allow
+            } else {
+                replies.deny
+            }
+        });
+    let reply = own_reply.or_else(|| {
+        execution_process
+            .executor_type
+            .as_deref()
+            .and_then(|executor_type| executor_type.parse::<ExecutorConfig>().ok())
+            .and_then(|config| {
+                config
+                    .create_executor()
+                    .command_approval_reply(decision.allow)
+            })
+    });
+    let Some(reply) = reply else {
+        return Ok(ResponseJson(ApiResponse::error(
+            "This process doesn't ask before running commands",
+        )));
+    };
+
+    match app_state
+        .answer_approval(execution_process.id, decision.approval_id, &reply)
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => {
+            return Ok(ResponseJson(ApiResponse::error(
+                "The command is no longer waiting for an answer",
+            )));
+        }
+        Err(e @ CommandError::InputClosed) => {
+            return Ok(ResponseJson(ApiResponse::error(&e.to_string())));
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to answer the approval of execution process {}: {}",
+                execution_process.id,
+                e
+            );
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+    attention::resolve(
+        &app_state,
+        execution_process.task_attempt_id,
+        AttentionKind::CommandApproval,
+    )
+    .await;
+    // Wakes the approval watch, to answer the commands after it, and the log streams
+    command_runner::notify_output(execution_process.id);
+
+    app_state
+        .track_analytics_event(
+            "command_approval_answered",
+            Some(serde_json::json!({ "allowed": decision.allow })),
+        )
+        .await;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Pass a message from the user to the running process on its stdin, for executors whose
+/// CLI reads stdin for as long as it runs. It shows in the conversation where it was sent.
+pub async fn send_execution_process_input(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<SendExecutionInput>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    let content = payload.content.trim();
+    if content.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error("The message is empty")));
+    }
+    if execution_process.status != ExecutionProcessStatus::Running {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Only a running process can be sent messages",
+        )));
+    }
+    let input = execution_process
+        .executor_type
+        .as_deref()
+        .and_then(|executor_type| executor_type.parse::<ExecutorConfig>().ok())
+        .and_then(|config| config.create_executor().user_message_input(content));
+    let Some(input) = input else {
+        return Ok(ResponseJson(ApiResponse::error(
+            "This process doesn't take messages while it runs",
+        )));
+    };
+
+    // Where it goes in the conversation, taken before the agent can answer it
+    let entry_index = normalize_stdout(
+        &app_state.db_pool,
+        &execution_process,
+        None,
+        Some(&app_state),
+    )
+    .await
+    .map_or(0, |entries| entries.len());
+    match app_state
+        .write_running_execution_stdin(execution_process.id, &input)
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => {
+            return Ok(ResponseJson(ApiResponse::error(
+                "The process has already finished",
+            )));
+        }
+        Err(e @ CommandError::InputClosed) => {
+            return Ok(ResponseJson(ApiResponse::error(&e.to_string())));
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to write to the stdin of execution process {}: {}",
+                execution_process.id,
+                e
+            );
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+    if let Err(e) = ExecutionInput::create(
+        &app_state.db_pool,
+        execution_process.id,
+        content,
+        entry_index as i64,
+    )
+    .await
+    {
+        tracing::error!(
+            "Failed to record the message sent to execution process {}: {}",
+            execution_process.id,
+            e
+        );
+    }
+    // Wakes the log streams, which send the message to their clients
+    command_runner::notify_output(execution_process.id);
+
+    app_state
+        .track_analytics_event("execution_input_sent", None)
+        .await;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RawLogQuery {
+    pub stream: LogStream,
+}
+
+/// The full content of one entry of the process's conversation, as plain text; the logs
+/// only show the start of entries that are too large
+pub async fn get_execution_process_entry_content(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    Path((_project_id, _task_id, _attempt_id, _process_id, entry_index)): Path<(
+        Uuid,
+        Uuid,
+        Uuid,
+        Uuid,
+        usize,
+    )>,
+    State(app_state): State<AppState>,
+) -> Result<Response, StatusCode> {
+    let conversation = normalize_full_process_logs(&app_state.db_pool, &execution_process).await;
+    let Some(entry) = conversation.entries.into_iter().nth(entry_index) else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+    Ok((
+        [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+        entry.content,
+    )
+        .into_response())
+}
+
+/// One of the process's output streams as plain text: the full output when it was written
+/// to disk, or what the database kept of it
+pub async fn get_execution_process_raw_log(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    Query(query): Query<RawLogQuery>,
+) -> Result<Response, StatusCode> {
+    let path = log_storage::full_log_path(execution_process.id, query.stream);
+    let log = match tokio::fs::read_to_string(&path).await {
+        Ok(log) => log,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let stored = match query.stream {
+                LogStream::Stdout => execution_process.stdout,
+                LogStream::Stderr => execution_process.stderr,
+            };
+            stored.unwrap_or_default()
+        }
+        Err(e) => {
+            tracing::error!("Failed to read {}: {}", path.display(), e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    let log = match query.stream {
+        LogStream::Stdout => log,
+        LogStream::Stderr => log.replace("---STDERR_CHUNK_BOUNDARY---\n", ""),
+    };
+    Ok(([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], log).into_response())
+}
+
 #[derive(serde::Deserialize)]
 pub struct DeleteFileQuery {
     file_path: String,
@@ -760,44 +2101,280 @@ pub async fn delete_task_attempt_file(
     }
 }
 
+/// Delete the build output and installed dependencies in the attempt's worktree, keeping
+/// its files and git state
+pub async fn clean_attempt_artifacts(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<ArtifactCleanup>>, StatusCode> {
+    if task_attempt.worktree_deleted {
+        return Ok(ResponseJson(ApiResponse::error(
+            "The worktree of this attempt has already been cleaned up",
+        )));
+    }
+
+    match ExecutionProcess::find_by_task_attempt_id(&app_state.db_pool, task_attempt.id).await {
+        Ok(processes) => {
+            if processes.iter().any(|process| process.status.is_live()) {
+                return Ok(ResponseJson(ApiResponse::error(
+                    "Stop the attempt's running processes before cleaning its worktree",
+                )));
+            }
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch execution processes for attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    let worktree_path = task_attempt.worktree_path.clone();
+    let cleanup = tokio::task::spawn_blocking(move || {
+        worktree_usage::clean_artifacts(std::path::Path::new(&worktree_path))
+    })
+    .await;
+
+    match cleanup {
+        Ok(Ok(cleanup)) => {
+            tracing::info!(
+                "Freed {} bytes of build artifacts in worktree of attempt {}",
+                cleanup.freed_bytes,
+                task_attempt.id
+            );
+            Ok(ResponseJson(ApiResponse::success(cleanup)))
+        }
+        Ok(Err(e)) => {
+            tracing::warn!(
+                "Failed to clean build artifacts of attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            Ok(ResponseJson(ApiResponse::error(&format!(
+                "Failed to clean build artifacts: {}",
+                e
+            ))))
+        }
+        Err(e) => {
+            tracing::error!(
+                "Artifact cleanup of attempt {} panicked: {}",
+                task_attempt.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Why the attempt can't take a follow-up, if its executor doesn't support them
+pub(crate) async fn followup_unsupported(
+    pool: &DbPool,
+    task_attempt: &TaskAttempt,
+) -> Option<String> {
+    let processes = ExecutionProcess::find_by_task_attempt_id(pool, task_attempt.id)
+        .await
+        .ok()?;
+    let executor = task_attempt.current_executor(&processes)?;
+    (!executor.capabilities().supports_followup)
+        .then(|| format!("{} doesn't support follow-ups", executor.display_name()))
+}
+
+/// The executor a follow-up hands the attempt over to, if it asks for another than the one
+/// the attempt runs with. `Err` with why the follow-up can't be sent: the executor is
+/// unknown or, with the attempt's own, that one doesn't take follow-ups.
+pub(crate) async fn followup_handover(
+    pool: &DbPool,
+    task_attempt: &TaskAttempt,
+    executor: Option<&str>,
+) -> Result<Option<ExecutorConfig>, String> {
+    let target = executor
+        .map(|executor| executor.parse::<ExecutorConfig>())
+        .transpose()?;
+    if let Some(target) = target {
+        let processes = ExecutionProcess::find_by_task_attempt_id(pool, task_attempt.id)
+            .await
+            .unwrap_or_default();
+        let current = task_attempt.current_executor(&processes);
+        if current.map(|current| current.to_string()) != Some(target.to_string()) {
+            return Ok(Some(target));
+        }
+    }
+    match followup_unsupported(pool, task_attempt).await {
+        Some(message) => Err(message),
+        None => Ok(None),
+    }
+}
+
+/// Tell the people @mentioned in a follow-up prompt that was sent to the attempt
+pub(crate) async fn notify_follow_up_mentions(
+    pool: &DbPool,
+    task: &Task,
+    task_attempt_id: Uuid,
+    user: Option<&User>,
+    prompt: &str,
+) {
+    if let Err(e) = mentions::notify(
+        pool,
+        task,
+        Some(task_attempt_id),
+        MentionSource::FollowUp,
+        user,
+        prompt,
+        None,
+    )
+    .await
+    {
+        tracing::error!(
+            "Failed to notify mentions in a follow-up to attempt {}: {}",
+            task_attempt_id,
+            e
+        );
+    }
+}
+
 pub async fn create_followup_attempt(
     Extension(project): Extension<Project>,
     Extension(task): Extension<Task>,
     Extension(task_attempt): Extension<TaskAttempt>,
     State(app_state): State<AppState>,
+    user: Option<Extension<User>>,
     Json(payload): Json<CreateFollowUpAttempt>,
 ) -> Result<ResponseJson<ApiResponse<FollowUpResponse>>, StatusCode> {
+    let handover = match followup_handover(
+        &app_state.db_pool,
+        &task_attempt,
+        payload.executor.as_deref(),
+    )
+    .await
+    {
+        Ok(handover) => handover,
+        Err(message) => return Ok(ResponseJson(ApiResponse::error(&message))),
+    };
+    let handed_over_to = handover.as_ref().map(|executor| executor.display_name());
+
     // Start follow-up execution synchronously to catch errors
-    match TaskAttempt::start_followup_execution(
+    match TaskAttempt::start_followup_execution_with_executor(
         &app_state.db_pool,
         &app_state,
         task_attempt.id,
         task.id,
         project.id,
         &payload.prompt,
+        handover,
     )
     .await
     {
         Ok(actual_attempt_id) => {
+            notify_follow_up_mentions(
+                &app_state.db_pool,
+                &task,
+                actual_attempt_id,
+                user.as_ref().map(|Extension(user)| user),
+                &payload.prompt,
+            )
+            .await;
             let created_new_attempt = actual_attempt_id != task_attempt.id;
             let message = if created_new_attempt {
                 format!(
                     "Follow-up execution started on new attempt {} (original worktree was deleted)",
                     actual_attempt_id
                 )
+            } else if let Some(executor) = handed_over_to {
+                format!("Follow-up handed over to {}", executor)
             } else {
                 "Follow-up execution started successfully".to_string()
             };
 
             Ok(ResponseJson(ApiResponse::success(FollowUpResponse {
-                message: message.clone(),
+                message: message.clone(),
+                actual_attempt_id,
+                created_new_attempt,
+            })))
+        }
+        Err(TaskAttemptError::Executor(e)) => Ok(ResponseJson(ApiResponse::error(&e.to_string()))),
+        Err(TaskAttemptError::BudgetExceeded(e)) => {
+            Ok(ResponseJson(ApiResponse::error(&e.to_string())))
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to start follow-up execution for task attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// A follow-up that only shows the agent the files and hunks the user selected
+pub async fn create_scoped_followup(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+    user: Option<Extension<User>>,
+    Json(payload): Json<CreateScopedFollowUp>,
+) -> Result<ResponseJson<ApiResponse<FollowUpResponse>>, StatusCode> {
+    if let Some(message) = followup_unsupported(&app_state.db_pool, &task_attempt).await {
+        return Ok(ResponseJson(ApiResponse::error(&message)));
+    }
+
+    let files =
+        match TaskAttempt::get_diff_hunks(&app_state.db_pool, task_attempt.id, task.id, project.id)
+            .await
+        {
+            Ok(files) => files,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to get diff hunks for task attempt {}: {}",
+                    task_attempt.id,
+                    e
+                );
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        };
+    let prompt = match compile_scoped_prompt(&payload, &files) {
+        Ok(prompt) => prompt,
+        Err(message) => return Ok(ResponseJson(ApiResponse::error(&message))),
+    };
+
+    match TaskAttempt::start_followup_execution(
+        &app_state.db_pool,
+        &app_state,
+        task_attempt.id,
+        task.id,
+        project.id,
+        &prompt,
+    )
+    .await
+    {
+        Ok(actual_attempt_id) => {
+            notify_follow_up_mentions(
+                &app_state.db_pool,
+                &task,
+                actual_attempt_id,
+                user.as_ref().map(|Extension(user)| user),
+                &payload.prompt,
+            )
+            .await;
+            Ok(ResponseJson(ApiResponse::success(FollowUpResponse {
+                message: format!(
+                    "Follow-up started on {} selected file(s)",
+                    payload.selections.len()
+                ),
                 actual_attempt_id,
-                created_new_attempt,
+                created_new_attempt: actual_attempt_id != task_attempt.id,
             })))
         }
+        Err(TaskAttemptError::Executor(e)) => Ok(ResponseJson(ApiResponse::error(&e.to_string()))),
+        Err(TaskAttemptError::BudgetExceeded(e)) => {
+            Ok(ResponseJson(ApiResponse::error(&e.to_string())))
+        }
         Err(e) => {
             tracing::error!(
-                "Failed to start follow-up execution for task attempt {}: {}",
+                "Failed to start scoped follow-up for task attempt {}: {}",
                 task_attempt.id,
                 e
             );
@@ -828,34 +2405,7 @@ pub async fn start_dev_server(
             }
         };
 
-    for dev_server in existing_dev_servers {
-        tracing::info!(
-            "Stopping existing dev server {} for project {}",
-            dev_server.id,
-            project.id
-        );
-
-        // Stop the running process
-        if let Err(e) = app_state.stop_running_execution_by_id(dev_server.id).await {
-            tracing::error!("Failed to stop dev server {}: {}", dev_server.id, e);
-        } else {
-            // Update the execution process status in the database
-            if let Err(e) = ExecutionProcess::update_completion(
-                &app_state.db_pool,
-                dev_server.id,
-                crate::models::execution_process::ExecutionProcessStatus::Killed,
-                None,
-            )
-            .await
-            {
-                tracing::error!(
-                    "Failed to update dev server {} status: {}",
-                    dev_server.id,
-                    e
-                );
-            }
-        }
-    }
+    ProcessService::stop_dev_servers(&app_state, existing_dev_servers).await;
 
     // Start dev server execution
     match TaskAttempt::start_dev_server(
@@ -879,6 +2429,100 @@ pub async fn start_dev_server(
     }
 }
 
+/// The port of the attempt's running dev server, if it has one
+pub async fn get_dev_server(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Option<DevServerPort>>>, StatusCode> {
+    match DevServerPort::find_running_by_task_attempt_id(&app_state.db_pool, task_attempt.id).await
+    {
+        Ok(port) => Ok(ResponseJson(ApiResponse::success(port))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to load dev server of task attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn stop_dev_server(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    let dev_servers = match ExecutionProcess::find_running_dev_servers_by_task_attempt_id(
+        &app_state.db_pool,
+        task_attempt.id,
+    )
+    .await
+    {
+        Ok(dev_servers) => dev_servers,
+        Err(e) => {
+            tracing::error!(
+                "Failed to find running dev servers for task attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    if dev_servers.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "No dev server is running for this attempt",
+        )));
+    }
+
+    ProcessService::stop_dev_servers(&app_state, dev_servers).await;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Forward a request to the attempt's running dev server
+async fn proxy_to_dev_server(
+    app_state: &AppState,
+    task_attempt: &TaskAttempt,
+    path: &str,
+    request: Request,
+) -> Response {
+    match DevServerPort::find_running_by_task_attempt_id(&app_state.db_pool, task_attempt.id).await
+    {
+        Ok(Some(dev_server)) => {
+            dev_server_proxy::forward(dev_server.port as u16, path, request).await
+        }
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            "No dev server is running for this attempt",
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!(
+                "Failed to load dev server of task attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+pub async fn proxy_dev_server_root(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+    request: Request,
+) -> Response {
+    proxy_to_dev_server(&app_state, &task_attempt, "", request).await
+}
+
+pub async fn proxy_dev_server(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+    Path((_project_id, _task_id, _attempt_id, path)): Path<(Uuid, Uuid, Uuid, String)>,
+    request: Request,
+) -> Response {
+    proxy_to_dev_server(&app_state, &task_attempt, &path, request).await
+}
+
 pub async fn get_task_attempt_execution_state(
     Extension(project): Extension<Project>,
     Extension(task): Extension<Task>,
@@ -901,130 +2545,188 @@ pub async fn get_task_attempt_execution_state(
     }
 }
 
-/// Find plan content with context by searching through multiple processes in the same attempt
-async fn find_plan_content_with_context(
-    pool: &SqlitePool,
+/// The plan up for review, falling back to the logs for attempts planned before plans
+/// were stored
+async fn find_attempt_plan(
+    pool: &DbPool,
     attempt_id: Uuid,
-) -> Result<String, StatusCode> {
-    // Get all execution processes for this attempt
-    let execution_processes =
-        match ExecutionProcess::find_by_task_attempt_id(pool, attempt_id).await {
-            Ok(processes) => processes,
-            Err(e) => {
-                tracing::error!(
-                    "Failed to fetch execution processes for attempt {}: {}",
-                    attempt_id,
-                    e
-                );
-                return Err(StatusCode::INTERNAL_SERVER_ERROR);
-            }
-        };
+) -> Result<Option<AttemptPlan>, sqlx::Error> {
+    if let Some(plan) = AttemptPlan::find_by_task_attempt_id(pool, attempt_id).await? {
+        return Ok(Some(plan));
+    }
 
-    // Look for claudeplan processes (most recent first)
-    for claudeplan_process in execution_processes
+    let processes = ExecutionProcess::find_by_task_attempt_id(pool, attempt_id).await?;
+    let Some((process_id, content)) = processes
         .iter()
         .rev()
-        .filter(|p| p.executor_type.as_deref() == Some("claude-plan"))
-    {
-        if let Some(stdout) = &claudeplan_process.stdout {
-            if !stdout.trim().is_empty() {
-                // Create executor and normalize logs
-                let executor_config = ExecutorConfig::ClaudePlan;
-                let executor = executor_config.create_executor();
-
-                // Use working directory for normalization
-                let working_dir_path =
-                    match std::fs::canonicalize(&claudeplan_process.working_directory) {
-                        Ok(canonical_path) => canonical_path.to_string_lossy().to_string(),
-                        Err(_) => claudeplan_process.working_directory.clone(),
-                    };
-
-                // Normalize logs and extract plan content
-                match executor.normalize_logs(stdout, &working_dir_path) {
-                    Ok(normalized_conversation) => {
-                        // Search for plan content in the normalized conversation
-                        if let Some(plan_content) = normalized_conversation
-                            .entries
-                            .iter()
-                            .rev()
-                            .find_map(|entry| {
-                                if let NormalizedEntryType::ToolUse {
-                                    action_type: ActionType::PlanPresentation { plan },
-                                    ..
-                                } = &entry.entry_type
-                                {
-                                    Some(plan.clone())
-                                } else {
-                                    None
-                                }
-                            })
-                        {
-                            return Ok(plan_content);
-                        }
-                    }
-                    Err(_) => {
-                        continue;
-                    }
-                }
+        .find_map(|process| plan_review::plan_from_process(process).map(|plan| (process.id, plan)))
+    else {
+        return Ok(None);
+    };
+    AttemptPlan::propose(pool, attempt_id, process_id, &content).await?;
+    AttemptPlan::find_by_task_attempt_id(pool, attempt_id).await
+}
+
+pub async fn get_attempt_plan(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Option<PlanReview>>>, StatusCode> {
+    let plan = match find_attempt_plan(&app_state.db_pool, task_attempt.id).await {
+        Ok(Some(plan)) => plan,
+        Ok(None) => return Ok(ResponseJson(ApiResponse::success(None))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to load the plan of attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let comparison = if plan.status == AttemptPlanStatus::Approved {
+        match TaskAttempt::get_worktree_changes(
+            &app_state.db_pool,
+            task_attempt.id,
+            task.id,
+            project.id,
+        )
+        .await
+        {
+            Ok(changes) => Some(plan_review::compare_plan(&plan.content, &changes)),
+            Err(e) => {
+                // The plan is still worth showing when the worktree can't be read
+                tracing::warn!(
+                    "Failed to compare the plan of attempt {} with its changes: {}",
+                    task_attempt.id,
+                    e
+                );
+                None
             }
         }
-    }
+    } else {
+        None
+    };
 
-    tracing::error!(
-        "No claudeplan content found in any process in attempt {}",
-        attempt_id
-    );
-    Err(StatusCode::NOT_FOUND)
+    Ok(ResponseJson(ApiResponse::success(Some(PlanReview {
+        plan,
+        comparison,
+    }))))
 }
 
+/// Approve the attempt's plan and have the same session implement it
 pub async fn approve_plan(
     Extension(project): Extension<Project>,
     Extension(task): Extension<Task>,
     Extension(task_attempt): Extension<TaskAttempt>,
     State(app_state): State<AppState>,
 ) -> Result<ResponseJson<ApiResponse<FollowUpResponse>>, StatusCode> {
-    let current_task = &task;
+    let pool = &app_state.db_pool;
 
-    // Find plan content with context across the task hierarchy
-    let plan_content = find_plan_content_with_context(&app_state.db_pool, task_attempt.id).await?;
-
-    use crate::models::task::CreateTask;
-    let new_task_id = Uuid::new_v4();
-    let create_task_data = CreateTask {
-        project_id: project.id,
-        title: format!("Execute Plan: {}", current_task.title),
-        description: Some(plan_content),
-        parent_task_attempt: Some(task_attempt.id),
-    };
+    match find_attempt_plan(pool, task_attempt.id).await {
+        Ok(Some(plan)) if plan.status == AttemptPlanStatus::Approved => {
+            return Ok(ResponseJson(ApiResponse::error(
+                "This plan has already been approved",
+            )));
+        }
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return Ok(ResponseJson(ApiResponse::error(
+                "This attempt has no plan to approve",
+            )));
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to load the plan of attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
 
-    let new_task = match Task::create(&app_state.db_pool, &create_task_data, new_task_id).await {
-        Ok(task) => task,
+    let running = match ExecutionProcess::find_by_task_attempt_id(pool, task_attempt.id).await {
+        Ok(processes) => processes.iter().any(|process| {
+            process.status.is_live() && process.process_type == ExecutionProcessType::CodingAgent
+        }),
         Err(e) => {
-            tracing::error!("Failed to create new task: {}", e);
+            tracing::error!(
+                "Failed to fetch execution processes for attempt {}: {}",
+                task_attempt.id,
+                e
+            );
             return Err(StatusCode::INTERNAL_SERVER_ERROR);
         }
     };
+    if running {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Wait for the attempt to finish before approving its plan",
+        )));
+    }
 
-    // Mark original task as completed since it now has children
-    if let Err(e) =
-        Task::update_status(&app_state.db_pool, task.id, project.id, TaskStatus::Done).await
-    {
-        tracing::error!("Failed to update original task status to Done: {}", e);
+    if let Err(e) = AttemptPlan::approve(pool, task_attempt.id).await {
+        tracing::error!(
+            "Failed to approve the plan of attempt {}: {}",
+            task_attempt.id,
+            e
+        );
         return Err(StatusCode::INTERNAL_SERVER_ERROR);
-    } else {
-        tracing::info!(
-            "Original task {} marked as Done after plan approval (has children)",
-            task.id
+    }
+    attention::resolve(&app_state, task_attempt.id, AttentionKind::PlanReview).await;
+
+    if let Err(e) = TaskAttempt::start_followup_execution(
+        pool,
+        &app_state,
+        task_attempt.id,
+        task.id,
+        project.id,
+        plan_review::IMPLEMENTATION_PROMPT,
+    )
+    .await
+    {
+        tracing::error!(
+            "Failed to start implementing the plan of attempt {}: {}",
+            task_attempt.id,
+            e
         );
+        // Leave the plan waiting so approving can be retried
+        let _ = AttemptPlan::reopen(pool, task_attempt.id).await;
+        let _ = Task::update_status(pool, task.id, project.id, TaskStatus::PlanReview).await;
+        if let TaskAttemptError::Executor(e) = e {
+            return Ok(ResponseJson(ApiResponse::error(&e.to_string())));
+        }
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
     }
 
     Ok(ResponseJson(ApiResponse::success(FollowUpResponse {
-        message: format!("Plan approved and new task created: {}", new_task.title),
-        actual_attempt_id: new_task_id, // Return the new task ID
-        created_new_attempt: true,
+        message: "Plan approved, implementation started".to_string(),
+        actual_attempt_id: task_attempt.id,
+        created_new_attempt: false,
     })))
 }
 
+/// What the attempt's executions are waiting on the user for, if anything
+pub async fn get_task_attempt_attention(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<ExecutionAttention>>>, StatusCode> {
+    match ExecutionAttention::find_open_by_task_attempt_id(&app_state.db_pool, task_attempt.id)
+        .await
+    {
+        Ok(attention) => Ok(ResponseJson(ApiResponse::success(attention))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to load the attention of attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
 pub async fn get_task_attempt_details(
     Extension(task_attempt): Extension<TaskAttempt>,
 ) -> Result<ResponseJson<ApiResponse<TaskAttempt>>, StatusCode> {
@@ -1052,24 +2754,119 @@ pub async fn get_task_attempt_children(
 }
 
 pub fn task_attempts_list_router(_state: AppState) -> Router<AppState> {
-    Router::new().route(
-        "/projects/:project_id/tasks/:task_id/attempts",
-        get(get_task_attempts).post(create_task_attempt),
-    )
+    Router::new()
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts",
+            get(get_task_attempts).post(create_task_attempt),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/compare",
+            get(compare_task_attempts),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/parallel",
+            axum::routing::post(super::attempt_groups::create_parallel_attempts),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempt-groups",
+            get(super::attempt_groups::get_attempt_groups),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempt-groups/winner",
+            axum::routing::post(super::attempt_groups::pick_attempt_group_winner),
+        )
 }
 
 pub fn task_attempts_with_id_router(_state: AppState) -> Router<AppState> {
-    use axum::routing::post;
+    use axum::routing::{any, post};
 
     Router::new()
         .route(
             "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/diff",
             get(get_task_attempt_diff),
         )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/linked-diffs",
+            get(get_task_attempt_linked_diffs),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/diff/hunks",
+            get(get_task_attempt_diff_hunks),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/files",
+            get(get_task_attempt_files),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/file",
+            get(get_task_attempt_file_content),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/comments",
+            get(super::review_comments::get_review_comments)
+                .post(super::review_comments::create_review_comment),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/comments/follow-up",
+            post(super::review_comments::send_review_comments_as_follow_up),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/follow-up-draft/preview",
+            get(super::follow_up_drafts::preview_follow_up_draft),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/follow-up-draft/send",
+            post(super::follow_up_drafts::send_follow_up_draft),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/comments/:comment_id",
+            axum::routing::put(super::review_comments::update_review_comment)
+                .delete(super::review_comments::delete_review_comment),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/reviews",
+            get(super::attempt_reviews::get_attempt_reviews),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/review/approve",
+            post(super::attempt_reviews::approve_attempt),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/review/reject",
+            post(super::attempt_reviews::reject_attempt),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/rollbacks",
+            get(super::attempt_rollbacks::get_attempt_rollbacks),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/rollback",
+            post(super::attempt_rollbacks::rollback_attempt),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/tool-usage",
+            get(get_task_attempt_tool_usage),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/execution-snapshots",
+            get(super::execution_snapshots::get_execution_snapshots),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/revert-execution",
+            post(super::execution_snapshots::revert_execution),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/terminal",
+            get(super::terminal::attach_terminal),
+        )
         .route(
             "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/merge",
             post(merge_task_attempt),
         )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/merge-queue",
+            post(queue_task_attempt_merge),
+        )
         .route(
             "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/branch-status",
             get(get_task_attempt_branch_status),
@@ -1086,10 +2883,18 @@ pub fn task_attempts_with_id_router(_state: AppState) -> Router<AppState> {
             "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/delete-file",
             post(delete_task_attempt_file),
         )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/clean-artifacts",
+            post(clean_attempt_artifacts),
+        )
         .route(
             "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/create-pr",
             post(create_github_pr),
         )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/pr-description",
+            get(get_pr_description),
+        )
         .route(
             "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/execution-processes",
             get(get_task_attempt_execution_processes),
@@ -1104,28 +2909,88 @@ pub fn task_attempts_with_id_router(_state: AppState) -> Router<AppState> {
                     "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/execution-processes/:process_id/stop",
                     post(stop_execution_process),
                 )
+                .route(
+                    "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/execution-processes/:process_id/pause",
+                    post(pause_execution_process),
+                )
+                .route(
+                    "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/execution-processes/:process_id/resume",
+                    post(resume_execution_process),
+                )
+                .route(
+                    "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/execution-processes/:process_id/approval",
+                    get(get_execution_process_approval).post(answer_execution_process_approval),
+                )
+                .route(
+                    "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/execution-processes/:process_id/input",
+                    post(send_execution_process_input),
+                )
+                .route(
+                    "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/execution-processes/:process_id/raw-log",
+                    get(get_execution_process_raw_log),
+                )
+                .route(
+                    "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/execution-processes/:process_id/entries/:entry_index",
+                    get(get_execution_process_entry_content),
+                )
                 .route_layer(from_fn_with_state(_state.clone(), load_execution_process_with_context_middleware))
         )
         .route(
             "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/logs",
             get(get_task_attempt_all_logs),
         )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/logs/stream",
+            get(super::log_stream::stream_attempt_logs),
+        )
         .route(
             "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/follow-up",
             post(create_followup_attempt),
         )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/follow-up/scoped",
+            post(create_scoped_followup),
+        )
         .route(
             "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/start-dev-server",
             post(start_dev_server),
         )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/dev-server",
+            get(get_dev_server),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/dev-server/stop",
+            post(stop_dev_server),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/dev-server/proxy",
+            any(proxy_dev_server_root),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/dev-server/proxy/",
+            any(proxy_dev_server_root),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/dev-server/proxy/*path",
+            any(proxy_dev_server),
+        )
         .route(
             "/projects/:project_id/tasks/:task_id/attempts/:attempt_id",
             get(get_task_attempt_execution_state),
         )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/plan",
+            get(get_attempt_plan),
+        )
         .route(
             "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/approve-plan",
             post(approve_plan),
         )
+        .route(
+            "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/attention",
+            get(get_task_attempt_attention),
+        )
         .route(
             "/projects/:project_id/tasks/:task_id/attempts/:attempt_id/children",
             get(get_task_attempt_children),
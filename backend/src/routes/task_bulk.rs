@@ -0,0 +1,291 @@
+use axum::{extract::State, http::StatusCode, response::Json as ResponseJson, Extension, Json};
+use chrono::Utc;
+use uuid::Uuid;
+
+use super::{
+    task_attempts::{launch_attempt, prepare_attempt},
+    tasks::stop_task_dev_servers,
+};
+use crate::{
+    app_state::AppState,
+    executor::ExecutorConfig,
+    models::{
+        board_column::BoardColumn,
+        project::Project,
+        task::{Task, TaskStatus},
+        task_attempt::CreateTaskAttempt,
+        task_bulk::{archive_done_cutoff, BulkTaskAction, BulkTaskResult},
+        task_dependency::{blocked_message, TaskDependency},
+        ApiResponse,
+    },
+};
+
+type BulkResponse = Result<ResponseJson<ApiResponse<BulkTaskResult>>, StatusCode>;
+
+/// Where a bulk move puts the tasks
+enum MoveTarget {
+    Column(BoardColumn),
+    Status(TaskStatus),
+}
+
+/// Archive, delete, restore, move or run again many of the project's tasks at once. Tasks
+/// are handled one by one, so one failing doesn't stop the rest.
+pub async fn apply_bulk_task_action(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(action): Json<BulkTaskAction>,
+) -> BulkResponse {
+    let pool = &app_state.db_pool;
+    let mut result = BulkTaskResult::default();
+    let action_name = action.name();
+
+    match action {
+        BulkTaskAction::ArchiveDone { older_than_days } => {
+            let cutoff = archive_done_cutoff(Utc::now(), older_than_days);
+            match Task::archive_done_before(pool, project.id, cutoff).await {
+                Ok(ids) => result.succeeded = ids,
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to archive done tasks of project {}: {}",
+                        project.id,
+                        e
+                    );
+                    return Err(StatusCode::INTERNAL_SERVER_ERROR);
+                }
+            }
+        }
+        BulkTaskAction::Archive { task_ids } => {
+            for task in load_tasks(&app_state, &project, &task_ids, &mut result).await? {
+                match Task::archive(pool, task.id, project.id).await {
+                    Ok(true) => result.succeeded.push(task.id),
+                    Ok(false) => result.fail(task.id, "Already archived or in the trash"),
+                    Err(e) => {
+                        tracing::error!("Failed to archive task {}: {}", task.id, e);
+                        result.fail(task.id, "Failed to archive");
+                    }
+                }
+            }
+        }
+        BulkTaskAction::Unarchive { task_ids } => {
+            for task in load_tasks(&app_state, &project, &task_ids, &mut result).await? {
+                match Task::unarchive(pool, task.id, project.id).await {
+                    Ok(true) => result.succeeded.push(task.id),
+                    Ok(false) => result.fail(task.id, "Not archived"),
+                    Err(e) => {
+                        tracing::error!("Failed to unarchive task {}: {}", task.id, e);
+                        result.fail(task.id, "Failed to unarchive");
+                    }
+                }
+            }
+        }
+        BulkTaskAction::Delete { task_ids } => {
+            for task in load_tasks(&app_state, &project, &task_ids, &mut result).await? {
+                stop_task_dev_servers(&app_state, task.id).await;
+                match Task::trash(pool, task.id, project.id).await {
+                    Ok(true) => result.succeeded.push(task.id),
+                    Ok(false) => result.fail(task.id, "Already in the trash"),
+                    Err(e) => {
+                        tracing::error!("Failed to move task {} to the trash: {}", task.id, e);
+                        result.fail(task.id, "Failed to delete");
+                    }
+                }
+            }
+        }
+        BulkTaskAction::Restore { task_ids } => {
+            for task in load_tasks(&app_state, &project, &task_ids, &mut result).await? {
+                match Task::restore(pool, task.id, project.id).await {
+                    Ok(true) => result.succeeded.push(task.id),
+                    Ok(false) => result.fail(task.id, "Not in the trash"),
+                    Err(e) => {
+                        tracing::error!("Failed to restore task {}: {}", task.id, e);
+                        result.fail(task.id, "Failed to restore");
+                    }
+                }
+            }
+        }
+        BulkTaskAction::Move {
+            task_ids,
+            column_id,
+            status,
+        } => {
+            let target = match (column_id, status) {
+                (Some(column_id), _) => {
+                    let columns = BoardColumn::find_by_project_id(pool, project.id)
+                        .await
+                        .map_err(|e| {
+                            tracing::error!(
+                                "Failed to load board columns of project {}: {}",
+                                project.id,
+                                e
+                            );
+                            StatusCode::INTERNAL_SERVER_ERROR
+                        })?;
+                    let Some(column) = columns.into_iter().find(|c| c.id == column_id) else {
+                        return Ok(ResponseJson(ApiResponse::error(
+                            "The project's board has no such column",
+                        )));
+                    };
+                    if let Some(message) =
+                        wip_limit_error(&app_state, &project, &column, &task_ids).await?
+                    {
+                        return Ok(ResponseJson(ApiResponse::error(&message)));
+                    }
+                    MoveTarget::Column(column)
+                }
+                (None, Some(status)) => MoveTarget::Status(status),
+                (None, None) => {
+                    return Ok(ResponseJson(ApiResponse::error(
+                        "Name the column or status to move the tasks to",
+                    )));
+                }
+            };
+            for task in load_tasks(&app_state, &project, &task_ids, &mut result).await? {
+                match move_task(&app_state, &task, &target).await {
+                    Ok(()) => result.succeeded.push(task.id),
+                    Err(e) => {
+                        tracing::error!("Failed to move task {}: {}", task.id, e);
+                        result.fail(task.id, "Failed to move");
+                    }
+                }
+            }
+        }
+        BulkTaskAction::Rerun { task_ids, executor } => {
+            if executor.parse::<ExecutorConfig>().is_err() {
+                return Ok(ResponseJson(ApiResponse::error(&format!(
+                    "Unknown executor: {}",
+                    executor
+                ))));
+            }
+            for task in load_tasks(&app_state, &project, &task_ids, &mut result).await? {
+                match rerun_task(&app_state, &project, &task, &executor).await {
+                    Ok(None) => result.succeeded.push(task.id),
+                    Ok(Some(message)) => result.fail(task.id, message),
+                    Err(_) => result.fail(task.id, "Failed to start an attempt"),
+                }
+            }
+        }
+    }
+
+    app_state
+        .track_analytics_event(
+            "tasks_bulk_action",
+            Some(serde_json::json!({
+                "action": action_name,
+                "succeeded": result.succeeded.len(),
+                "failed": result.failed.len(),
+            })),
+        )
+        .await;
+    Ok(ResponseJson(ApiResponse::success(result)))
+}
+
+/// The named tasks of the project, in order. Those that aren't its tasks fail.
+async fn load_tasks(
+    app_state: &AppState,
+    project: &Project,
+    task_ids: &[Uuid],
+    result: &mut BulkTaskResult,
+) -> Result<Vec<Task>, StatusCode> {
+    let mut tasks = Vec::with_capacity(task_ids.len());
+    for task_id in task_ids {
+        match Task::find_by_id_and_project_id(&app_state.db_pool, *task_id, project.id).await {
+            Ok(Some(task)) => tasks.push(task),
+            Ok(None) => result.fail(*task_id, "Task not found"),
+            Err(e) => {
+                tracing::error!("Failed to load task {}: {}", task_id, e);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        }
+    }
+    Ok(tasks)
+}
+
+/// Why the tasks can't all go in the column, if its WIP limit leaves too little room
+async fn wip_limit_error(
+    app_state: &AppState,
+    project: &Project,
+    column: &BoardColumn,
+    task_ids: &[Uuid],
+) -> Result<Option<String>, StatusCode> {
+    let Some(limit) = column.wip_limit else {
+        return Ok(None);
+    };
+    let board = Task::find_by_project_id_with_attempt_status(&app_state.db_pool, project.id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to load board of project {}: {}", project.id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    let staying = board
+        .iter()
+        .filter(|t| t.board_column_id == Some(column.id) && !task_ids.contains(&t.id))
+        .count();
+    let moving = board.iter().filter(|t| task_ids.contains(&t.id)).count();
+    if (staying + moving) as i64 > limit {
+        return Ok(Some(format!(
+            "Moving {} task{} would take {} past its WIP limit of {}",
+            moving,
+            if moving == 1 { "" } else { "s" },
+            column.name,
+            limit
+        )));
+    }
+    Ok(None)
+}
+
+async fn move_task(
+    app_state: &AppState,
+    task: &Task,
+    target: &MoveTarget,
+) -> Result<(), sqlx::Error> {
+    let pool = &app_state.db_pool;
+    match target {
+        // The status goes first: changing it unpins the task from any earlier column
+        MoveTarget::Column(column) => {
+            Task::update_status(pool, task.id, task.project_id, column.status.clone()).await?;
+            Task::set_board_column(pool, task.id, task.project_id, Some(column.id)).await
+        }
+        MoveTarget::Status(status) => {
+            Task::update_status(pool, task.id, task.project_id, status.clone()).await
+        }
+    }
+}
+
+/// Start a new attempt of the task with `executor`. `Ok(Some(message))` when it can't run.
+async fn rerun_task(
+    app_state: &AppState,
+    project: &Project,
+    task: &Task,
+    executor: &str,
+) -> Result<Option<String>, StatusCode> {
+    let pool = &app_state.db_pool;
+    match Task::is_trashed(pool, task.id).await {
+        Ok(true) => return Ok(Some("The task is in the trash".to_string())),
+        Ok(false) => {}
+        Err(e) => {
+            tracing::error!("Failed to check whether task {} is trashed: {}", task.id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+    match TaskDependency::unfinished_blockers(pool, task.id).await {
+        Ok(blockers) if !blockers.is_empty() => return Ok(Some(blocked_message(&blockers))),
+        Ok(_) => {}
+        Err(e) => {
+            tracing::error!("Failed to check blockers of task {}: {}", task.id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    let mut attempt = CreateTaskAttempt {
+        executor: Some(executor.to_string()),
+        base_branch: None,
+        plan_only: None,
+        model: None,
+        executor_profile_id: None,
+    };
+    if let Some(message) = prepare_attempt(app_state, project, task, &mut attempt).await? {
+        return Ok(Some(message));
+    }
+    launch_attempt(app_state, project, task, &attempt).await?;
+    Ok(None)
+}
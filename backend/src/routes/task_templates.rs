@@ -8,12 +8,35 @@ use uuid::Uuid;
 
 use crate::{
     app_state::AppState,
+    executor::ExecutorConfig,
     models::{
         api_response::ApiResponse,
-        task_template::{CreateTaskTemplate, TaskTemplate, UpdateTaskTemplate},
+        project::Project,
+        task_template::{
+            CreateTaskTemplate, InstantiateTaskTemplate, TaskTemplate, UpdateTaskTemplate,
+        },
     },
 };
 
+/// Reject a default executor that isn't one this build knows
+fn validate_default_executor(
+    executor: Option<&str>,
+) -> Result<(), (StatusCode, Json<ApiResponse<()>>)> {
+    match executor
+        .map(str::trim)
+        .filter(|executor| !executor.is_empty())
+    {
+        Some(executor) if executor.parse::<ExecutorConfig>().is_err() => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(&format!(
+                "Unknown executor: {}",
+                executor
+            ))),
+        )),
+        _ => Ok(()),
+    }
+}
+
 pub async fn list_templates(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<()>>)> {
@@ -70,6 +93,7 @@ pub async fn create_template(
     State(state): State<AppState>,
     Json(payload): Json<CreateTaskTemplate>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<()>>)> {
+    validate_default_executor(payload.default_executor.as_deref())?;
     match TaskTemplate::create(&state.db_pool, &payload).await {
         Ok(template) => Ok((StatusCode::CREATED, Json(ApiResponse::success(template)))),
         Err(e) => {
@@ -98,6 +122,7 @@ pub async fn update_template(
     State(state): State<AppState>,
     Json(payload): Json<UpdateTaskTemplate>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<()>>)> {
+    validate_default_executor(payload.default_executor.as_deref())?;
     match TaskTemplate::update(&state.db_pool, template.id, &payload).await {
         Ok(template) => Ok(Json(ApiResponse::success(template))),
         Err(e) => {
@@ -145,3 +170,82 @@ pub async fn delete_template(
         )),
     }
 }
+
+/// Create a task in a project from the template: its placeholders filled in, and the
+/// template's checklist, executor and verification commands given to the task
+pub async fn instantiate_template(
+    Extension(template): Extension<TaskTemplate>,
+    State(state): State<AppState>,
+    Json(payload): Json<InstantiateTaskTemplate>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<()>>)> {
+    if template
+        .project_id
+        .is_some_and(|project_id| project_id != payload.project_id)
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(
+                "The template belongs to another project",
+            )),
+        ));
+    }
+    match Project::exists(&state.db_pool, payload.project_id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::error("Project not found")),
+            ))
+        }
+        Err(e) => {
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(&format!(
+                    "Failed to fetch project: {}",
+                    e
+                ))),
+            ))
+        }
+    }
+    let missing = template.missing_variables(&payload.variables);
+    if !missing.is_empty() {
+        let names: Vec<_> = missing
+            .iter()
+            .map(|name| format!("{{{{{}}}}}", name))
+            .collect();
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(&format!(
+                "Missing values for {}",
+                names.join(", ")
+            ))),
+        ));
+    }
+
+    match template
+        .instantiate(&state.db_pool, &payload, Uuid::new_v4())
+        .await
+    {
+        Ok(task) => {
+            state
+                .track_analytics_event(
+                    "task_created_from_template",
+                    Some(serde_json::json!({
+                        "task_id": task.id.to_string(),
+                        "project_id": task.project_id.to_string(),
+                        "template_id": template.id.to_string(),
+                        "checklist_items": template.checklist_items().len(),
+                    })),
+                )
+                .await;
+            Ok((StatusCode::CREATED, Json(ApiResponse::success(task))))
+        }
+        Err(e) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(&format!(
+                "Failed to create task from template: {}",
+                e
+            ))),
+        )),
+    }
+}
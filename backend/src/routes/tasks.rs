@@ -1,26 +1,89 @@
 use axum::{
-    extract::State, http::StatusCode, response::Json as ResponseJson, routing::get, Extension,
-    Json, Router,
+    extract::{DefaultBodyLimit, Query, State},
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Json as ResponseJson, Response},
+    routing::get,
+    Extension, Json, Router,
 };
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 use uuid::Uuid;
 
 use crate::{
     app_state::AppState,
     execution_monitor,
+    executor::ExecutorConfig,
     models::{
+        execution_process::ExecutionProcess,
+        executor_profile::{AssignExecutorProfile, ExecutorProfile},
         project::Project,
-        task::{CreateTask, CreateTaskAndStart, Task, TaskWithAttemptStatus, UpdateTask},
+        task::{
+            CreateSubtask, CreateTask, CreateTaskAndStart, ShelvedTask, Task, TaskBoardQuery,
+            TaskPriorityRequest, TaskStatus, TaskTimeoutRequest, TaskWithAttemptStatus, UpdateTask,
+        },
+        task_attachment::TaskAttachment,
         task_attempt::{CreateTaskAttempt, TaskAttempt},
+        task_checklist::{TaskChecklist, UpdateTaskChecklistItem},
+        task_decomposition::{
+            DecomposeTask, TaskDecomposition, TaskDecompositionStatus, TaskSubtasks,
+        },
+        task_defaults::{SetTaskExecutor, TaskDefaults},
+        task_dependency::{
+            TaskDependencies, TaskDependency, TaskDependencyError, TaskDependencyGraph,
+            TaskDependencyRequest,
+        },
+        task_label::{normalize_label, TaskLabelName, TaskLabels},
+        task_linked_project::{SetTaskLinkedProjects, TaskLinkedProject},
+        task_original_text::TaskOriginalText,
+        task_pr_description::TaskPrDescription,
+        task_schedule::{TaskSchedule, TaskScheduleRun, UpsertTaskSchedule},
+        tracker_import::TrackerImport,
         ApiResponse,
     },
+    routes::task_attempts,
+    services::{
+        conversation_export::{self, ConversationExportFormat, ConversationExportQuery},
+        embeddings::EmbeddingsClient,
+        executor_preflight,
+        label_suggestions::ClassificationInput,
+        llm::LlmClient,
+        plugins::{self, Validation},
+        pr_description,
+        related_tasks::{
+            find_duplicate_candidates, find_similar_tasks, DuplicateCandidate, SimilarTask,
+            SimilarTasksQuery,
+        },
+        run_estimate::{self, RepoProfile, RunEstimate},
+        task_summary,
+        tracker_import::{self, TrackerImportRequest, TrackerSyncResult},
+        trello_import::PlannedTask,
+        ProcessService, TrelloBoard, TrelloImportRequest, TrelloImportResult,
+    },
+    utils::cron::CronSchedule,
 };
 
+const TRELLO_EXPORT_MAX_BYTES: usize = 64 * 1024 * 1024;
+
+/// A newly created task, plus the open tasks it may duplicate so clients can warn before
+/// the same work is handed to a second agent
+#[derive(Debug, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct CreatedTask {
+    #[serde(flatten)]
+    pub task: Task,
+    pub possible_duplicates: Vec<DuplicateCandidate>,
+}
+
+/// The project's board, optionally filtered by label, priority or a custom field value and
+/// sorted by something other than creation
 pub async fn get_project_tasks(
     Extension(project): Extension<Project>,
     State(app_state): State<AppState>,
+    Query(query): Query<TaskBoardQuery>,
 ) -> Result<ResponseJson<ApiResponse<Vec<TaskWithAttemptStatus>>>, StatusCode> {
     match Task::find_by_project_id_with_attempt_status(&app_state.db_pool, project.id).await {
-        Ok(tasks) => Ok(ResponseJson(ApiResponse::success(tasks))),
+        Ok(tasks) => Ok(ResponseJson(ApiResponse::success(query.apply(tasks)))),
         Err(e) => {
             tracing::error!("Failed to fetch tasks for project {}: {}", project.id, e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
@@ -34,11 +97,64 @@ pub async fn get_task(
     Ok(ResponseJson(ApiResponse::success(task)))
 }
 
+/// Task-creation hook: store what the configured classifiers suggest as pending labels
+async fn suggest_labels(app_state: &AppState, task: &Task) {
+    if !app_state.get_config().read().await.suggest_labels {
+        return;
+    }
+    let input = ClassificationInput {
+        title: &task.title,
+        description: task.description.as_deref(),
+    };
+    let suggestions = app_state.label_suggester.suggest(&input).await;
+    if suggestions.is_empty() {
+        return;
+    }
+    if let Err(e) = TaskLabels::suggest(&app_state.db_pool, task.id, &suggestions).await {
+        tracing::error!(
+            "Failed to store label suggestions for task {}: {}",
+            task.id,
+            e
+        );
+    }
+}
+
+/// Runs before the task is inserted so it can't match itself. Never fails the creation: a
+/// lookup error just means no candidates.
+async fn possible_duplicates(
+    app_state: &AppState,
+    project_id: Uuid,
+    title: &str,
+    description: Option<&str>,
+) -> Vec<DuplicateCandidate> {
+    let client = {
+        let config = app_state.get_config().read().await;
+        EmbeddingsClient::from_config(&config.embeddings)
+    };
+    find_duplicate_candidates(
+        &app_state.db_pool,
+        client.as_ref(),
+        project_id,
+        title,
+        description,
+    )
+    .await
+    .unwrap_or_else(|e| {
+        tracing::error!(
+            "Failed to look for duplicates of '{}' in project {}: {}",
+            title,
+            project_id,
+            e
+        );
+        Vec::new()
+    })
+}
+
 pub async fn create_task(
     Extension(project): Extension<Project>,
     State(app_state): State<AppState>,
     Json(mut payload): Json<CreateTask>,
-) -> Result<ResponseJson<ApiResponse<Task>>, StatusCode> {
+) -> Result<ResponseJson<ApiResponse<CreatedTask>>, StatusCode> {
     let id = Uuid::new_v4();
 
     // Ensure the project_id in the payload matches the project from middleware
@@ -50,8 +166,28 @@ pub async fn create_task(
         project.id
     );
 
+    if let Err(reason) = plugins::validate(Validation::TaskCreate {
+        project_id: project.id,
+        title: payload.title.clone(),
+        description: payload.description.clone(),
+    })
+    .await
+    {
+        return Ok(ResponseJson(ApiResponse::error(&reason)));
+    }
+
+    let possible_duplicates = possible_duplicates(
+        &app_state,
+        project.id,
+        &payload.title,
+        payload.description.as_deref(),
+    )
+    .await;
+
     match Task::create(&app_state.db_pool, &payload, id).await {
         Ok(task) => {
+            suggest_labels(&app_state, &task).await;
+
             // Track task creation event
             app_state
                 .track_analytics_event(
@@ -60,11 +196,15 @@ pub async fn create_task(
                     "task_id": task.id.to_string(),
                     "project_id": project.id.to_string(),
                     "has_description": task.description.is_some(),
+                    "possible_duplicates": possible_duplicates.len(),
                     })),
                 )
                 .await;
 
-            Ok(ResponseJson(ApiResponse::success(task)))
+            Ok(ResponseJson(ApiResponse::success(CreatedTask {
+                task,
+                possible_duplicates,
+            })))
         }
         Err(e) => {
             tracing::error!("Failed to create task: {}", e);
@@ -77,7 +217,7 @@ pub async fn create_task_and_start(
     Extension(project): Extension<Project>,
     State(app_state): State<AppState>,
     Json(mut payload): Json<CreateTaskAndStart>,
-) -> Result<ResponseJson<ApiResponse<Task>>, StatusCode> {
+) -> Result<ResponseJson<ApiResponse<CreatedTask>>, StatusCode> {
     let task_id = Uuid::new_v4();
 
     // Ensure the project_id in the payload matches the project from middleware
@@ -89,12 +229,31 @@ pub async fn create_task_and_start(
         project.id
     );
 
+    if let Err(reason) = plugins::validate(Validation::TaskCreate {
+        project_id: project.id,
+        title: payload.title.clone(),
+        description: payload.description.clone(),
+    })
+    .await
+    {
+        return Ok(ResponseJson(ApiResponse::error(&reason)));
+    }
+
+    let possible_duplicates = possible_duplicates(
+        &app_state,
+        project.id,
+        &payload.title,
+        payload.description.as_deref(),
+    )
+    .await;
+
     // Create the task first
     let create_task_payload = CreateTask {
         project_id: payload.project_id,
         title: payload.title.clone(),
         description: payload.description.clone(),
         parent_task_attempt: payload.parent_task_attempt,
+        parent_task_id: None,
     };
     let task = match Task::create(&app_state.db_pool, &create_task_payload, task_id).await {
         Ok(task) => task,
@@ -103,12 +262,16 @@ pub async fn create_task_and_start(
             return Err(StatusCode::INTERNAL_SERVER_ERROR);
         }
     };
+    suggest_labels(&app_state, &task).await;
 
     // Create task attempt
     let executor_string = payload.executor.as_ref().map(|exec| exec.to_string());
     let attempt_payload = CreateTaskAttempt {
         executor: executor_string.clone(),
         base_branch: None, // Not supported in task creation endpoint, only in task attempts
+        plan_only: None,
+        model: payload.model.clone(),
+        executor_profile_id: None,
     };
 
     match TaskAttempt::create(&app_state.db_pool, &attempt_payload, task_id).await {
@@ -120,6 +283,7 @@ pub async fn create_task_and_start(
                         "task_id": task.id.to_string(),
                         "project_id": project.id.to_string(),
                         "has_description": task.description.is_some(),
+                        "possible_duplicates": possible_duplicates.len(),
                     })),
                 )
                 .await;
@@ -156,7 +320,10 @@ pub async fn create_task_and_start(
                 }
             });
 
-            Ok(ResponseJson(ApiResponse::success(task)))
+            Ok(ResponseJson(ApiResponse::success(CreatedTask {
+                task,
+                possible_duplicates,
+            })))
         }
         Err(e) => {
             tracing::error!("Failed to create task attempt: {}", e);
@@ -165,113 +332,1607 @@ pub async fn create_task_and_start(
     }
 }
 
+/// Edit the task. With `expected_updated_at`, an edit made to a task that has changed since is
+/// refused with 409, unless `base` shows the changes were to other fields, in which case both
+/// are kept.
 pub async fn update_task(
     Extension(project): Extension<Project>,
     Extension(existing_task): Extension<Task>,
     State(app_state): State<AppState>,
     Json(payload): Json<UpdateTask>,
+) -> Result<Response, StatusCode> {
+    let conflict = |message: String| {
+        (
+            StatusCode::CONFLICT,
+            ResponseJson(ApiResponse::<()>::error(&message)),
+        )
+            .into_response()
+    };
+
+    let base = match payload.expected_updated_at {
+        Some(expected) if expected != existing_task.updated_at => match &payload.base {
+            Some(base) => Some(base),
+            None => {
+                return Ok(conflict(
+                    "This task was changed since you opened it. Reload it and try again."
+                        .to_string(),
+                ))
+            }
+        },
+        _ => None,
+    };
+    let edit = match payload.merge(&existing_task, base) {
+        Ok(edit) => edit,
+        Err(fields) => {
+            return Ok(conflict(format!(
+                "Someone else changed the {} of this task since you opened it. Reload it and try again.",
+                fields.join(" and ")
+            )))
+        }
+    };
+
+    let updated = if payload.expected_updated_at.is_some() {
+        // Merged against the task as it is now, so written only if it still is
+        Task::update_unless_changed(
+            &app_state.db_pool,
+            existing_task.id,
+            project.id,
+            edit,
+            existing_task.updated_at,
+        )
+        .await
+    } else {
+        Task::update(
+            &app_state.db_pool,
+            existing_task.id,
+            project.id,
+            edit.title,
+            edit.description,
+            edit.status,
+            edit.parent_task_attempt,
+        )
+        .await
+        .map(Some)
+    };
+
+    match updated {
+        Ok(Some(task)) => Ok(ResponseJson(ApiResponse::success(task)).into_response()),
+        Ok(None) => Ok(conflict(
+            "This task changed while saving. Reload it and try again.".to_string(),
+        )),
+        Err(e) => {
+            tracing::error!("Failed to update task: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Set or clear the task's own execution timeout
+pub async fn set_task_timeout(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<TaskTimeoutRequest>,
 ) -> Result<ResponseJson<ApiResponse<Task>>, StatusCode> {
-    // Use existing values if not provided in update
-    let title = payload.title.unwrap_or(existing_task.title);
-    let description = payload.description.or(existing_task.description);
-    let status = payload.status.unwrap_or(existing_task.status);
-    let parent_task_attempt = payload
-        .parent_task_attempt
-        .or(existing_task.parent_task_attempt);
+    if matches!(payload.timeout_minutes, Some(minutes) if minutes < 0) {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Timeout can't be negative",
+        )));
+    }
 
-    match Task::update(
+    if let Err(e) = Task::set_timeout_minutes(
         &app_state.db_pool,
-        existing_task.id,
+        task.id,
         project.id,
-        title,
-        description,
-        status,
-        parent_task_attempt,
+        payload.timeout_minutes,
     )
     .await
     {
-        Ok(task) => Ok(ResponseJson(ApiResponse::success(task))),
+        tracing::error!("Failed to set timeout of task {}: {}", task.id, e);
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    match Task::find_by_id(&app_state.db_pool, task.id).await {
+        Ok(Some(task)) => Ok(ResponseJson(ApiResponse::success(task))),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
         Err(e) => {
-            tracing::error!("Failed to update task: {}", e);
+            tracing::error!("Failed to reload task {}: {}", task.id, e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
 }
 
-pub async fn delete_task(
+/// Set or clear the task's priority
+pub async fn set_task_priority(
     Extension(project): Extension<Project>,
     Extension(task): Extension<Task>,
     State(app_state): State<AppState>,
-) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
-    // Clean up all worktrees for this task before deletion
-    if let Err(e) = execution_monitor::cleanup_task_worktrees(&app_state.db_pool, task.id).await {
-        tracing::error!("Failed to cleanup worktrees for task {}: {}", task.id, e);
-        // Continue with deletion even if cleanup fails
+    Json(payload): Json<TaskPriorityRequest>,
+) -> Result<ResponseJson<ApiResponse<Task>>, StatusCode> {
+    if let Err(e) =
+        Task::set_priority(&app_state.db_pool, task.id, project.id, payload.priority).await
+    {
+        tracing::error!("Failed to set priority of task {}: {}", task.id, e);
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
     }
 
-    // Clean up all executor sessions for this task before deletion
-    match TaskAttempt::find_by_task_id(&app_state.db_pool, task.id).await {
-        Ok(task_attempts) => {
-            for attempt in task_attempts {
-                if let Err(e) =
-                    crate::models::executor_session::ExecutorSession::delete_by_task_attempt_id(
-                        &app_state.db_pool,
-                        attempt.id,
-                    )
-                    .await
-                {
-                    tracing::error!(
-                        "Failed to cleanup executor sessions for task attempt {}: {}",
-                        attempt.id,
-                        e
-                    );
-                    // Continue with deletion even if session cleanup fails
-                } else {
-                    tracing::debug!(
-                        "Cleaned up executor sessions for task attempt {}",
-                        attempt.id
-                    );
+    match Task::find_by_id(&app_state.db_pool, task.id).await {
+        Ok(Some(task)) => Ok(ResponseJson(ApiResponse::success(task))),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Failed to reload task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// The profile the task's attempts run with unless they pick an executor
+pub async fn get_task_executor_profile(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Option<ExecutorProfile>>>, StatusCode> {
+    let profile = async {
+        let Some(profile_id) = TaskDefaults::find_by_task_id(&app_state.db_pool, task.id)
+            .await?
+            .and_then(|defaults| defaults.executor_profile_id)
+        else {
+            return Ok(None);
+        };
+        ExecutorProfile::find_by_id(&app_state.db_pool, profile_id).await
+    };
+    match profile.await {
+        Ok(profile) => Ok(ResponseJson(ApiResponse::success(profile))),
+        Err(e) => {
+            tracing::error!("Failed to load executor profile of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn assign_task_executor_profile(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<AssignExecutorProfile>,
+) -> Result<ResponseJson<ApiResponse<Option<ExecutorProfile>>>, StatusCode> {
+    let profile = match payload.executor_profile_id {
+        Some(profile_id) => {
+            match ExecutorProfile::find_by_id(&app_state.db_pool, profile_id).await {
+                Ok(Some(profile)) if profile.is_available_in(project.id) => Some(profile),
+                Ok(_) => {
+                    return Ok(ResponseJson(ApiResponse::error(
+                        "Executor profile not found",
+                    )))
+                }
+                Err(e) => {
+                    tracing::error!("Failed to load executor profile {}: {}", profile_id, e);
+                    return Err(StatusCode::INTERNAL_SERVER_ERROR);
                 }
             }
         }
+        None => None,
+    };
+
+    match TaskDefaults::set_executor_profile(
+        &app_state.db_pool,
+        task.id,
+        payload.executor_profile_id,
+    )
+    .await
+    {
+        Ok(()) => Ok(ResponseJson(ApiResponse::success(profile))),
         Err(e) => {
-            tracing::error!("Failed to get task attempts for session cleanup: {}", e);
-            // Continue with deletion even if we can't get task attempts
+            tracing::error!(
+                "Failed to assign executor profile to task {}: {}",
+                task.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
+}
 
-    match Task::delete(&app_state.db_pool, task.id, project.id).await {
-        Ok(rows_affected) => {
-            if rows_affected == 0 {
-                Err(StatusCode::NOT_FOUND)
-            } else {
-                Ok(ResponseJson(ApiResponse::success(())))
+/// The executor the task's attempts start with unless they pick one
+pub async fn get_task_executor(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Option<String>>>, StatusCode> {
+    match TaskDefaults::find_by_task_id(&app_state.db_pool, task.id).await {
+        Ok(defaults) => Ok(ResponseJson(ApiResponse::success(
+            defaults.and_then(|defaults| defaults.executor),
+        ))),
+        Err(e) => {
+            tracing::error!("Failed to load executor of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Change the executor the task's next attempts start with. Attempts already made keep
+/// theirs; a follow-up can hand one over to another executor.
+pub async fn set_task_executor(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<SetTaskExecutor>,
+) -> Result<ResponseJson<ApiResponse<Option<String>>>, StatusCode> {
+    if let Some(executor) = &payload.executor {
+        if let Err(e) = executor.parse::<ExecutorConfig>() {
+            return Ok(ResponseJson(ApiResponse::error(&e)));
+        }
+    }
+
+    match TaskDefaults::set_executor(&app_state.db_pool, task.id, payload.executor.as_deref()).await
+    {
+        Ok(()) => Ok(ResponseJson(ApiResponse::success(payload.executor))),
+        Err(e) => {
+            tracing::error!("Failed to set executor of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// The other projects the task spans, checked out next to its own by each of its attempts
+pub async fn get_task_linked_projects(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskLinkedProject>>>, StatusCode> {
+    match TaskLinkedProject::find_by_task_id(&app_state.db_pool, task.id).await {
+        Ok(linked_projects) => Ok(ResponseJson(ApiResponse::success(linked_projects))),
+        Err(e) => {
+            tracing::error!("Failed to load linked projects of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Change the other projects the task spans. Attempts started from now on check them out;
+/// those already made keep their workspace.
+pub async fn set_task_linked_projects(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<SetTaskLinkedProjects>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskLinkedProject>>>, StatusCode> {
+    for project_id in &payload.project_ids {
+        if *project_id == task.project_id {
+            return Ok(ResponseJson(ApiResponse::error(
+                "A task can't be linked to its own project",
+            )));
+        }
+        match Project::exists(&app_state.db_pool, *project_id).await {
+            Ok(true) => {}
+            Ok(false) => {
+                return Ok(ResponseJson(ApiResponse::error(&format!(
+                    "Project {} not found",
+                    project_id
+                ))))
+            }
+            Err(e) => {
+                tracing::error!("Failed to check project {}: {}", project_id, e);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        }
+    }
+
+    if let Err(e) =
+        TaskLinkedProject::replace(&app_state.db_pool, task.id, &payload.project_ids).await
+    {
+        tracing::error!("Failed to set linked projects of task {}: {}", task.id, e);
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    get_task_linked_projects(Extension(task), State(app_state)).await
+}
+
+/// Replace the task's title and description with a generated summary, keeping the
+/// original text
+pub async fn summarize_task(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Task>>, StatusCode> {
+    let client = {
+        let config = app_state.get_config().read().await;
+        LlmClient::from_config(&config.llm)
+    };
+    let Some(client) = client else {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Summarizing needs a language model. Configure one in Settings.",
+        )));
+    };
+
+    let summary =
+        match task_summary::summarize(&client, &task.title, task.description.as_deref()).await {
+            Ok(summary) => summary,
+            Err(e) => {
+                tracing::warn!("Failed to summarize task {}: {}", task.id, e);
+                return Ok(ResponseJson(ApiResponse::error(&e.to_string())));
             }
+        };
+
+    if let Err(e) = TaskOriginalText::save_if_absent(
+        &app_state.db_pool,
+        task.id,
+        &task.title,
+        task.description.as_deref(),
+    )
+    .await
+    {
+        tracing::error!("Failed to keep original text of task {}: {}", task.id, e);
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    match Task::update(
+        &app_state.db_pool,
+        task.id,
+        project.id,
+        summary.title,
+        Some(summary.description).filter(|d| !d.is_empty()),
+        task.status,
+        task.parent_task_attempt,
+    )
+    .await
+    {
+        Ok(task) => {
+            app_state
+                .track_analytics_event(
+                    "task_summarized",
+                    Some(serde_json::json!({
+                        "task_id": task.id.to_string(),
+                        "project_id": project.id.to_string(),
+                    })),
+                )
+                .await;
+            Ok(ResponseJson(ApiResponse::success(task)))
         }
         Err(e) => {
-            tracing::error!("Failed to delete task: {}", e);
+            tracing::error!("Failed to update summarized task: {}", e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
 }
 
-pub fn tasks_project_router() -> Router<AppState> {
-    use axum::routing::post;
+pub async fn get_task_original_text(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Option<TaskOriginalText>>>, StatusCode> {
+    match TaskOriginalText::find_by_task_id(&app_state.db_pool, task.id).await {
+        Ok(original) => Ok(ResponseJson(ApiResponse::success(original))),
+        Err(e) => {
+            tracing::error!("Failed to load original text of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
 
-    Router::new()
-        .route(
-            "/projects/:project_id/tasks",
-            get(get_project_tasks).post(create_task),
-        )
-        .route(
-            "/projects/:project_id/tasks/create-and-start",
-            post(create_task_and_start),
-        )
+pub async fn get_task_pr_description(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Option<TaskPrDescription>>>, StatusCode> {
+    match TaskPrDescription::find_by_task_id(&app_state.db_pool, task.id).await {
+        Ok(description) => Ok(ResponseJson(ApiResponse::success(description))),
+        Err(e) => {
+            tracing::error!("Failed to load PR description of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
 }
 
-pub fn tasks_with_id_router() -> Router<AppState> {
-    Router::new().route(
-        "/projects/:project_id/tasks/:task_id",
-        get(get_task).put(update_task).delete(delete_task),
+#[derive(Debug, Deserialize)]
+pub struct GeneratePrDescriptionQuery {
+    /// The attempt to describe; the latest one when left out
+    pub attempt_id: Option<Uuid>,
+}
+
+/// Have the language model write a pull request title and description from an attempt's
+/// diff and conversations, and keep it for when that attempt's pull request opens
+pub async fn generate_task_pr_description(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Query(query): Query<GeneratePrDescriptionQuery>,
+) -> Result<ResponseJson<ApiResponse<TaskPrDescription>>, StatusCode> {
+    let client = {
+        let config = app_state.get_config().read().await;
+        LlmClient::from_config(&config.llm)
+    };
+    let Some(client) = client else {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Generating a PR description needs a language model. Configure one in Settings.",
+        )));
+    };
+
+    let attempt = match TaskAttempt::find_by_task_id(&app_state.db_pool, task.id).await {
+        Ok(attempts) => attempts
+            .into_iter()
+            .find(|attempt| query.attempt_id.is_none_or(|id| id == attempt.id)),
+        Err(e) => {
+            tracing::error!("Failed to fetch attempts of task {}: {}", task.id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    let Some(attempt) = attempt else {
+        return Ok(ResponseJson(ApiResponse::error(
+            "No attempt of the task to describe",
+        )));
+    };
+
+    let description =
+        match pr_description::generate_with_llm(&app_state.db_pool, &client, &task, &attempt).await
+        {
+            Ok(description) => description,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to generate PR description of task {}: {}",
+                    task.id,
+                    e
+                );
+                return Ok(ResponseJson(ApiResponse::error(&e.to_string())));
+            }
+        };
+
+    match TaskPrDescription::save(
+        &app_state.db_pool,
+        task.id,
+        attempt.id,
+        &description.title,
+        &description.body,
     )
+    .await
+    {
+        Ok(saved) => {
+            app_state
+                .track_analytics_event(
+                    "pr_description_generated",
+                    Some(serde_json::json!({
+                        "task_id": task.id.to_string(),
+                        "attempt_id": attempt.id.to_string(),
+                    })),
+                )
+                .await;
+            Ok(ResponseJson(ApiResponse::success(saved)))
+        }
+        Err(e) => {
+            tracing::error!("Failed to save PR description of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn delete_task_pr_description(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match TaskPrDescription::delete(&app_state.db_pool, task.id).await {
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => {
+            tracing::error!("Failed to delete PR description of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Predict the cost and reach of an attempt at the task, so an oversized run can be
+/// confirmed before it starts
+pub async fn estimate_task_run(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<RunEstimate>>, StatusCode> {
+    let (estimate_config, client) = {
+        let config = app_state.get_config().read().await;
+        (
+            config.run_estimate.clone(),
+            LlmClient::from_config(&config.llm),
+        )
+    };
+
+    let repo_path = project.git_repo_path.clone();
+    let profile = match tokio::task::spawn_blocking(move || {
+        RepoProfile::scan(std::path::Path::new(&repo_path))
+    })
+    .await
+    {
+        Ok(profile) => profile,
+        Err(e) => {
+            tracing::error!("Failed to scan repository of project {}: {}", project.id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let estimate = run_estimate::estimate(
+        &estimate_config,
+        client.as_ref(),
+        &profile,
+        &task.title,
+        task.description.as_deref(),
+    )
+    .await;
+    Ok(ResponseJson(ApiResponse::success(estimate)))
+}
+
+pub async fn get_task_labels(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<TaskLabels>>, StatusCode> {
+    match TaskLabels::find_by_task_id(&app_state.db_pool, task.id).await {
+        Ok(labels) => Ok(ResponseJson(ApiResponse::success(labels))),
+        Err(e) => {
+            tracing::error!("Failed to load labels of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Label a task; this is also how a suggestion is accepted
+pub async fn add_task_label(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<TaskLabelName>,
+) -> Result<ResponseJson<ApiResponse<TaskLabels>>, StatusCode> {
+    let Some(name) = normalize_label(&payload.name) else {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Labels must be between 1 and 50 characters",
+        )));
+    };
+    if let Err(e) = TaskLabels::add(&app_state.db_pool, task.id, &name).await {
+        tracing::error!("Failed to label task {}: {}", task.id, e);
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    get_task_labels(Extension(task), State(app_state)).await
+}
+
+pub async fn remove_task_label(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<TaskLabelName>,
+) -> Result<ResponseJson<ApiResponse<TaskLabels>>, StatusCode> {
+    let name = normalize_label(&payload.name).unwrap_or_default();
+    if let Err(e) = TaskLabels::remove(&app_state.db_pool, task.id, &name).await {
+        tracing::error!("Failed to remove label from task {}: {}", task.id, e);
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    get_task_labels(Extension(task), State(app_state)).await
+}
+
+pub async fn get_task_checklist(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<TaskChecklist>>, StatusCode> {
+    match TaskChecklist::find_by_task_id(&app_state.db_pool, task.id).await {
+        Ok(checklist) => Ok(ResponseJson(ApiResponse::success(checklist))),
+        Err(e) => {
+            tracing::error!("Failed to load checklist of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Tick a checklist item off, or untick it
+pub async fn update_task_checklist_item(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpdateTaskChecklistItem>,
+) -> Result<ResponseJson<ApiResponse<TaskChecklist>>, StatusCode> {
+    match TaskChecklist::set_completed(
+        &app_state.db_pool,
+        task.id,
+        payload.item_id,
+        payload.completed,
+    )
+    .await
+    {
+        Ok(true) => get_task_checklist(Extension(task), State(app_state)).await,
+        Ok(false) => Ok(ResponseJson(ApiResponse::error(
+            "The task has no such checklist item",
+        ))),
+        Err(e) => {
+            tracing::error!("Failed to update checklist of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn get_task_subtasks(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<TaskSubtasks>>, StatusCode> {
+    let subtasks = match Task::find_subtasks(&app_state.db_pool, task.id).await {
+        Ok(subtasks) => subtasks,
+        Err(e) => {
+            tracing::error!("Failed to load subtasks of task {}: {}", task.id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    match TaskDecomposition::find_latest_by_task_id(&app_state.db_pool, task.id).await {
+        Ok(decomposition) => Ok(ResponseJson(ApiResponse::success(TaskSubtasks {
+            subtasks,
+            decomposition,
+        }))),
+        Err(e) => {
+            tracing::error!("Failed to load decomposition of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn create_subtask(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<CreateSubtask>,
+) -> Result<ResponseJson<ApiResponse<Task>>, StatusCode> {
+    if payload.title.trim().is_empty() {
+        return Ok(ResponseJson(ApiResponse::error("A subtask needs a title")));
+    }
+    let subtask = CreateTask {
+        project_id: task.project_id,
+        title: payload.title.trim().to_string(),
+        description: payload.description,
+        parent_task_attempt: None,
+        parent_task_id: Some(task.id),
+    };
+    if let Err(reason) = plugins::validate(Validation::TaskCreate {
+        project_id: subtask.project_id,
+        title: subtask.title.clone(),
+        description: subtask.description.clone(),
+    })
+    .await
+    {
+        return Ok(ResponseJson(ApiResponse::error(&reason)));
+    }
+    match Task::create(&app_state.db_pool, &subtask, Uuid::new_v4()).await {
+        Ok(subtask) => {
+            app_state
+                .track_analytics_event(
+                    "subtask_created",
+                    Some(serde_json::json!({
+                        "task_id": subtask.id.to_string(),
+                        "parent_task_id": task.id.to_string(),
+                        "project_id": task.project_id.to_string(),
+                    })),
+                )
+                .await;
+            Ok(ResponseJson(ApiResponse::success(subtask)))
+        }
+        Err(e) => {
+            tracing::error!("Failed to create a subtask of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Start a planning attempt that proposes subtasks for the task instead of implementing
+/// it; the subtasks are created once its run finishes
+pub async fn decompose_task(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<DecomposeTask>,
+) -> Result<ResponseJson<ApiResponse<TaskAttempt>>, StatusCode> {
+    match TaskDecomposition::find_latest_by_task_id(&app_state.db_pool, task.id).await {
+        Ok(Some(decomposition)) if decomposition.status == TaskDecompositionStatus::Pending => {
+            return Ok(ResponseJson(ApiResponse::error(
+                "The task is already being decomposed",
+            )));
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::error!("Failed to load decomposition of task {}: {}", task.id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    // Decomposed with the template's executor too, for a task made from a template
+    let executor = match payload.executor {
+        Some(executor) => Some(executor),
+        None => match TaskDefaults::find_by_task_id(&app_state.db_pool, task.id).await {
+            Ok(defaults) => defaults.and_then(|defaults| defaults.executor),
+            Err(e) => {
+                tracing::error!("Failed to load defaults of task {}: {}", task.id, e);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        },
+    };
+    let executor_config = executor
+        .as_deref()
+        .and_then(|executor| executor.parse::<ExecutorConfig>().ok());
+    if let (Some(config), false) = (executor_config, app_state.dry_run) {
+        if let Err(e) = executor_preflight::validate(&config).await {
+            return Ok(ResponseJson(ApiResponse::error(&e.to_string())));
+        }
+    }
+
+    let attempt_payload = CreateTaskAttempt {
+        executor: executor.clone(),
+        base_branch: None,
+        plan_only: Some(true),
+        model: None,
+        executor_profile_id: None,
+    };
+    let attempt = match TaskAttempt::create(&app_state.db_pool, &attempt_payload, task.id).await {
+        Ok(attempt) => attempt,
+        Err(e) => {
+            tracing::error!("Failed to create decomposition attempt: {}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    // Recorded before the run starts, which is when its prompt is built
+    if let Err(e) = TaskDecomposition::create(&app_state.db_pool, attempt.id, task.id).await {
+        tracing::error!("Failed to record decomposition of task {}: {}", task.id, e);
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    app_state
+        .track_analytics_event(
+            "task_decomposition_started",
+            Some(serde_json::json!({
+                "task_id": task.id.to_string(),
+                "executor_type": executor.as_deref().unwrap_or("default"),
+                "attempt_id": attempt.id.to_string(),
+            })),
+        )
+        .await;
+
+    let app_state_clone = app_state.clone();
+    let attempt_id = attempt.id;
+    let task_id = task.id;
+    let project_id = project.id;
+    tokio::spawn(async move {
+        if let Err(e) = TaskAttempt::start_execution(
+            &app_state_clone.db_pool,
+            &app_state_clone,
+            attempt_id,
+            task_id,
+            project_id,
+        )
+        .await
+        {
+            tracing::error!(
+                "Failed to start execution for task attempt {}: {}",
+                attempt_id,
+                e
+            );
+        }
+    });
+
+    Ok(ResponseJson(ApiResponse::success(attempt)))
+}
+
+pub async fn get_task_schedule(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Option<TaskSchedule>>>, StatusCode> {
+    match TaskSchedule::find_by_task_id(&app_state.db_pool, task.id).await {
+        Ok(schedule) => Ok(ResponseJson(ApiResponse::success(schedule))),
+        Err(e) => {
+            tracing::error!("Failed to load schedule of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Run the task on a cron schedule, each run as a new subtask with an attempt of its own
+pub async fn upsert_task_schedule(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpsertTaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, StatusCode> {
+    let cron = match payload.cron_expression.parse::<CronSchedule>() {
+        Ok(cron) => cron,
+        Err(e) => {
+            return Ok(ResponseJson(ApiResponse::error(&format!(
+                "Invalid cron expression: {}",
+                e
+            ))))
+        }
+    };
+    if let Some(executor) = &payload.executor {
+        if let Err(e) = executor.parse::<ExecutorConfig>() {
+            return Ok(ResponseJson(ApiResponse::error(&e)));
+        }
+    }
+    let Some(next_run_at) = cron.next_after(chrono::Utc::now()) else {
+        return Ok(ResponseJson(ApiResponse::error(
+            "The cron expression never comes due",
+        )));
+    };
+
+    match TaskSchedule::upsert(&app_state.db_pool, task.id, &payload, Some(next_run_at)).await {
+        Ok(schedule) => {
+            app_state
+                .track_analytics_event(
+                    "task_schedule_saved",
+                    Some(serde_json::json!({
+                        "task_id": task.id.to_string(),
+                        "cron_expression": schedule.cron_expression,
+                        "enabled": schedule.enabled,
+                    })),
+                )
+                .await;
+            Ok(ResponseJson(ApiResponse::success(schedule)))
+        }
+        Err(e) => {
+            tracing::error!("Failed to save schedule of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn delete_task_schedule(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match TaskSchedule::delete(&app_state.db_pool, task.id).await {
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => {
+            tracing::error!("Failed to delete schedule of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// The task's scheduled runs, newest first
+pub async fn get_task_schedule_runs(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskScheduleRun>>>, StatusCode> {
+    let runs = match TaskSchedule::find_by_task_id(&app_state.db_pool, task.id).await {
+        Ok(Some(schedule)) => {
+            TaskScheduleRun::find_by_schedule_id(&app_state.db_pool, schedule.id).await
+        }
+        Ok(None) => Ok(Vec::new()),
+        Err(e) => Err(e),
+    };
+    match runs {
+        Ok(runs) => Ok(ResponseJson(ApiResponse::success(runs))),
+        Err(e) => {
+            tracing::error!("Failed to load scheduled runs of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn dismiss_label_suggestion(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<TaskLabelName>,
+) -> Result<ResponseJson<ApiResponse<TaskLabels>>, StatusCode> {
+    let name = normalize_label(&payload.name).unwrap_or_default();
+    if let Err(e) = TaskLabels::dismiss_suggestion(&app_state.db_pool, task.id, &name).await {
+        tracing::error!(
+            "Failed to dismiss label suggestion for task {}: {}",
+            task.id,
+            e
+        );
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    get_task_labels(Extension(task), State(app_state)).await
+}
+
+pub async fn get_task_dependencies(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<TaskDependencies>>, StatusCode> {
+    match TaskDependencies::find_by_task_id(&app_state.db_pool, task.id).await {
+        Ok(dependencies) => Ok(ResponseJson(ApiResponse::success(dependencies))),
+        Err(e) => {
+            tracing::error!("Failed to load dependencies of task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Mark the task as blocked by another task of the project
+pub async fn add_task_dependency(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<TaskDependencyRequest>,
+) -> Result<ResponseJson<ApiResponse<TaskDependencies>>, StatusCode> {
+    match TaskDependency::add(&app_state.db_pool, &task, payload.blocked_by_task_id).await {
+        Ok(()) => {}
+        Err(TaskDependencyError::Database(e)) => {
+            tracing::error!("Failed to add dependency to task {}: {}", task.id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+        Err(e) => return Ok(ResponseJson(ApiResponse::error(&e.to_string()))),
+    }
+    get_task_dependencies(Extension(task), State(app_state)).await
+}
+
+pub async fn remove_task_dependency(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<TaskDependencyRequest>,
+) -> Result<ResponseJson<ApiResponse<TaskDependencies>>, StatusCode> {
+    if let Err(e) =
+        TaskDependency::remove(&app_state.db_pool, task.id, payload.blocked_by_task_id).await
+    {
+        tracing::error!("Failed to remove dependency from task {}: {}", task.id, e);
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    get_task_dependencies(Extension(task), State(app_state)).await
+}
+
+/// The project's tasks in an order where blockers come first, with the edges between them
+pub async fn get_task_dependency_graph(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<TaskDependencyGraph>>, StatusCode> {
+    match TaskDependencyGraph::for_project(&app_state.db_pool, project.id).await {
+        Ok(graph) => Ok(ResponseJson(ApiResponse::success(graph))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to load dependency graph of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteTaskQuery {
+    /// Delete the task for good rather than move it to the trash
+    #[serde(default)]
+    pub permanent: bool,
+}
+
+/// Stop the dev servers of the task's attempts, which have no use once it's off the board
+pub(crate) async fn stop_task_dev_servers(app_state: &AppState, task_id: Uuid) {
+    let task_attempts = match TaskAttempt::find_by_task_id(&app_state.db_pool, task_id).await {
+        Ok(task_attempts) => task_attempts,
+        Err(e) => {
+            tracing::error!("Failed to get task attempts of task {}: {}", task_id, e);
+            return;
+        }
+    };
+    for attempt in task_attempts {
+        match ExecutionProcess::find_running_dev_servers_by_task_attempt_id(
+            &app_state.db_pool,
+            attempt.id,
+        )
+        .await
+        {
+            Ok(dev_servers) => ProcessService::stop_dev_servers(app_state, dev_servers).await,
+            Err(e) => tracing::error!(
+                "Failed to find dev servers of task attempt {}: {}",
+                attempt.id,
+                e
+            ),
+        }
+    }
+}
+
+/// Move the task to the trash, or with `permanent` delete it and everything of it for good
+pub async fn delete_task(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Query(query): Query<DeleteTaskQuery>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    if !query.permanent {
+        stop_task_dev_servers(&app_state, task.id).await;
+        return match Task::trash(&app_state.db_pool, task.id, project.id).await {
+            Ok(true) => Ok(ResponseJson(ApiResponse::success(()))),
+            Ok(false) => Ok(ResponseJson(ApiResponse::error(
+                "The task is already in the trash",
+            ))),
+            Err(e) => {
+                tracing::error!("Failed to move task {} to the trash: {}", task.id, e);
+                Err(StatusCode::INTERNAL_SERVER_ERROR)
+            }
+        };
+    }
+
+    // Clean up all worktrees for this task before deletion
+    if let Err(e) = execution_monitor::cleanup_task_worktrees(&app_state.db_pool, task.id).await {
+        tracing::error!("Failed to cleanup worktrees for task {}: {}", task.id, e);
+        // Continue with deletion even if cleanup fails
+    }
+
+    stop_task_dev_servers(&app_state, task.id).await;
+
+    // Clean up all executor sessions for this task before deletion
+    match TaskAttempt::find_by_task_id(&app_state.db_pool, task.id).await {
+        Ok(task_attempts) => {
+            for attempt in task_attempts {
+                if let Err(e) =
+                    crate::models::executor_session::ExecutorSession::delete_by_task_attempt_id(
+                        &app_state.db_pool,
+                        attempt.id,
+                    )
+                    .await
+                {
+                    tracing::error!(
+                        "Failed to cleanup executor sessions for task attempt {}: {}",
+                        attempt.id,
+                        e
+                    );
+                    // Continue with deletion even if session cleanup fails
+                } else {
+                    tracing::debug!(
+                        "Cleaned up executor sessions for task attempt {}",
+                        attempt.id
+                    );
+                }
+            }
+        }
+        Err(e) => {
+            tracing::error!("Failed to get task attempts for session cleanup: {}", e);
+            // Continue with deletion even if we can't get task attempts
+        }
+    }
+
+    match Task::delete(&app_state.db_pool, task.id, project.id).await {
+        Ok(rows_affected) => {
+            if rows_affected == 0 {
+                Err(StatusCode::NOT_FOUND)
+            } else {
+                // The attachment rows went with the task; their files are ours to remove
+                match tokio::fs::remove_dir_all(TaskAttachment::task_dir(task.id)).await {
+                    Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                        tracing::warn!("Failed to remove attachments of task {}: {}", task.id, e)
+                    }
+                    _ => {}
+                }
+                Ok(ResponseJson(ApiResponse::success(())))
+            }
+        }
+        Err(e) => {
+            tracing::error!("Failed to delete task: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Take the task off the board, keeping it and its attempts
+pub async fn archive_task(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match Task::archive(&app_state.db_pool, task.id, project.id).await {
+        Ok(true) => Ok(ResponseJson(ApiResponse::success(()))),
+        Ok(false) => Ok(ResponseJson(ApiResponse::error(
+            "The task is already archived or in the trash",
+        ))),
+        Err(e) => {
+            tracing::error!("Failed to archive task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn unarchive_task(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match Task::unarchive(&app_state.db_pool, task.id, project.id).await {
+        Ok(true) => Ok(ResponseJson(ApiResponse::success(()))),
+        Ok(false) => Ok(ResponseJson(ApiResponse::error("The task isn't archived"))),
+        Err(e) => {
+            tracing::error!("Failed to unarchive task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Take the task out of the trash, back where it was
+pub async fn restore_task(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match Task::restore(&app_state.db_pool, task.id, project.id).await {
+        Ok(true) => Ok(ResponseJson(ApiResponse::success(()))),
+        Ok(false) => Ok(ResponseJson(ApiResponse::error(
+            "The task isn't in the trash",
+        ))),
+        Err(e) => {
+            tracing::error!("Failed to restore task {}: {}", task.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn get_archived_tasks(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<ShelvedTask>>>, StatusCode> {
+    match Task::find_archived(&app_state.db_pool, project.id).await {
+        Ok(tasks) => Ok(ResponseJson(ApiResponse::success(tasks))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch archived tasks of project {}: {}",
+                project.id,
+                e
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn get_trashed_tasks(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<ShelvedTask>>>, StatusCode> {
+    match Task::find_trashed(&app_state.db_pool, project.id).await {
+        Ok(tasks) => Ok(ResponseJson(ApiResponse::success(tasks))),
+        Err(e) => {
+            tracing::error!("Failed to fetch the trash of project {}: {}", project.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn import_trello_board(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<TrelloImportRequest>,
+) -> Result<ResponseJson<ApiResponse<TrelloImportResult>>, StatusCode> {
+    let board = match TrelloBoard::load(payload).await {
+        Ok(board) => board,
+        Err(e) => {
+            tracing::warn!("Trello import into project {} failed: {}", project.id, e);
+            return Ok(ResponseJson(ApiResponse::error(&e.to_string())));
+        }
+    };
+
+    let mut result = TrelloImportResult {
+        board_name: board.name.clone(),
+        tasks_created: 0,
+        subtasks_created: 0,
+    };
+    // Checklist items become subtasks of their card's task
+    for planned in board.plan() {
+        let parent = match create_imported_task(&app_state, project.id, &planned, None).await {
+            Ok(parent) => parent,
+            Err(e) => {
+                tracing::error!("Failed to create task imported from Trello: {}", e);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        };
+        for subtask in &planned.subtasks {
+            if let Err(e) =
+                create_imported_task(&app_state, project.id, subtask, Some(parent.id)).await
+            {
+                tracing::error!("Failed to create task imported from Trello: {}", e);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        }
+        result.tasks_created += 1;
+        result.subtasks_created += planned.subtasks.len();
+    }
+
+    app_state
+        .track_analytics_event(
+            "trello_board_imported",
+            Some(serde_json::json!({
+                "project_id": project.id.to_string(),
+                "tasks_created": result.tasks_created,
+                "subtasks_created": result.subtasks_created,
+            })),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(result)))
+}
+
+/// Import a Jira project's or Linear team's open issues, or those updated since it was last
+/// imported
+pub async fn import_from_tracker(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<TrackerImportRequest>,
+) -> Result<ResponseJson<ApiResponse<TrackerSyncResult>>, StatusCode> {
+    let key = payload.key.trim();
+    if key.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Enter the Jira project key or Linear team key to import",
+        )));
+    }
+    let source =
+        match TrackerImport::find_or_create(&app_state.db_pool, project.id, payload.tracker, key)
+            .await
+        {
+            Ok(source) => source,
+            Err(e) => {
+                tracing::error!("Failed to save tracker import: {}", e);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        };
+
+    let config = app_state.get_config().read().await.clone();
+    match tracker_import::sync_source(&app_state.db_pool, &config, &source).await {
+        Ok(result) => {
+            app_state
+                .track_analytics_event(
+                    "tracker_issues_imported",
+                    Some(serde_json::json!({
+                        "project_id": project.id.to_string(),
+                        "tracker": payload.tracker,
+                        "tasks_created": result.created,
+                    })),
+                )
+                .await;
+            Ok(ResponseJson(ApiResponse::success(result)))
+        }
+        Err(e) => {
+            tracing::warn!(
+                "{} import into project {} failed: {}",
+                payload.tracker.display_name(),
+                project.id,
+                e
+            );
+            Ok(ResponseJson(ApiResponse::error(&e.to_string())))
+        }
+    }
+}
+
+/// The Jira projects and Linear teams the project imports from
+pub async fn get_tracker_imports(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<TrackerImport>>>, StatusCode> {
+    match TrackerImport::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(sources) => Ok(ResponseJson(ApiResponse::success(sources))),
+        Err(e) => {
+            tracing::error!("Failed to load tracker imports for {}: {}", project.id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Re-sync every source the project imports from, fetching only what changed since
+pub async fn sync_tracker_imports(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<TrackerSyncResult>>, StatusCode> {
+    let sources = match TrackerImport::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(sources) => sources,
+        Err(e) => {
+            tracing::error!("Failed to load tracker imports for {}: {}", project.id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    if sources.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Nothing has been imported from Jira or Linear yet",
+        )));
+    }
+
+    let config = app_state.get_config().read().await.clone();
+    let mut total = TrackerSyncResult::default();
+    let mut errors = Vec::new();
+    for source in &sources {
+        match tracker_import::sync_source(&app_state.db_pool, &config, source).await {
+            Ok(result) => {
+                total.created += result.created;
+                total.updated += result.updated;
+            }
+            Err(e) => errors.push(format!("{}: {}", source.source_key, e)),
+        }
+    }
+    if errors.is_empty() {
+        Ok(ResponseJson(ApiResponse::success(total)))
+    } else {
+        Ok(ResponseJson(ApiResponse::error(&errors.join("; "))))
+    }
+}
+
+async fn create_imported_task(
+    app_state: &AppState,
+    project_id: Uuid,
+    planned: &PlannedTask,
+    parent_task_id: Option<Uuid>,
+) -> Result<Task, sqlx::Error> {
+    let task = Task::create(
+        &app_state.db_pool,
+        &CreateTask {
+            project_id,
+            title: planned.title.clone(),
+            description: planned.description.clone(),
+            parent_task_attempt: None,
+            parent_task_id,
+        },
+        Uuid::new_v4(),
+    )
+    .await?;
+    if planned.status != TaskStatus::Todo {
+        Task::update_status(
+            &app_state.db_pool,
+            task.id,
+            project_id,
+            planned.status.clone(),
+        )
+        .await?;
+    }
+    Ok(task)
+}
+
+/// Existing tasks resembling a draft, for the create dialog. Empty when no embeddings
+/// provider is configured.
+pub async fn find_similar_project_tasks(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<SimilarTasksQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<SimilarTask>>>, StatusCode> {
+    let client = {
+        let config = app_state.get_config().read().await;
+        EmbeddingsClient::from_config(&config.embeddings)
+    };
+    let Some(client) = client else {
+        return Ok(ResponseJson(ApiResponse::success(Vec::new())));
+    };
+
+    match find_similar_tasks(&app_state.db_pool, &client, project.id, &payload).await {
+        Ok(similar) => Ok(ResponseJson(ApiResponse::success(similar))),
+        Err(e) => {
+            tracing::warn!(
+                "Similar task lookup for project {} failed: {}",
+                project.id,
+                e
+            );
+            Ok(ResponseJson(ApiResponse::error(&e.to_string())))
+        }
+    }
+}
+
+pub fn tasks_project_router() -> Router<AppState> {
+    use axum::routing::post;
+
+    Router::new()
+        .route(
+            "/projects/:project_id/tasks",
+            get(get_project_tasks).post(create_task),
+        )
+        .route(
+            "/projects/:project_id/tasks/create-and-start",
+            post(create_task_and_start),
+        )
+        .route(
+            "/projects/:project_id/tasks/similar",
+            post(find_similar_project_tasks),
+        )
+        .route(
+            "/projects/:project_id/tasks/dependency-graph",
+            get(get_task_dependency_graph),
+        )
+        .route(
+            "/projects/:project_id/tasks/archived",
+            get(get_archived_tasks),
+        )
+        .route("/projects/:project_id/tasks/trash", get(get_trashed_tasks))
+        .route(
+            "/projects/:project_id/tasks/bulk",
+            post(super::task_bulk::apply_bulk_task_action),
+        )
+        .route(
+            "/projects/:project_id/tasks/import/trello",
+            // Exports carry the board's whole action history and easily pass the 2MB default
+            post(import_trello_board).layer(DefaultBodyLimit::max(TRELLO_EXPORT_MAX_BYTES)),
+        )
+        .route(
+            "/projects/:project_id/tasks/import/tracker",
+            get(get_tracker_imports).post(import_from_tracker),
+        )
+        .route(
+            "/projects/:project_id/tasks/import/tracker/sync",
+            post(sync_tracker_imports),
+        )
+}
+
+#[derive(Debug, Default, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateTaskPullRequest {
+    /// Defaults to the base branch of the attempt the pull request is for
+    pub base_branch: Option<String>,
+}
+
+/// Open a pull request for the task's latest attempt, titled after the task and with its
+/// description as the body. Returns the pull request's URL; its state shows on the board.
+pub async fn create_task_pr(
+    Extension(project): Extension<Project>,
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Json(request): Json<CreateTaskPullRequest>,
+) -> Result<ResponseJson<ApiResponse<String>>, StatusCode> {
+    let attempt = match TaskAttempt::find_by_task_id(&app_state.db_pool, task.id).await {
+        Ok(attempts) => attempts.into_iter().next(),
+        Err(e) => {
+            tracing::error!("Failed to fetch attempts of task {}: {}", task.id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    let Some(attempt) = attempt else {
+        return Ok(ResponseJson(ApiResponse::error(
+            "The task has no attempt to open a pull request for",
+        )));
+    };
+    if let (Some(pr_url), Some("open")) = (&attempt.pr_url, attempt.pr_status.as_deref()) {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "The task already has an open pull request: {}",
+            pr_url
+        ))));
+    }
+
+    // A description generated from this attempt beats the task's own text
+    let generated =
+        match TaskPrDescription::find_for_attempt(&app_state.db_pool, task.id, attempt.id).await {
+            Ok(generated) => generated,
+            Err(e) => {
+                tracing::error!("Failed to load PR description of task {}: {}", task.id, e);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        };
+    let request = match generated {
+        Some(generated) => task_attempts::CreateGitHubPRRequest {
+            title: generated.title,
+            body: Some(generated.body),
+            base_branch: request.base_branch,
+        },
+        None => task_attempts::CreateGitHubPRRequest {
+            title: task.title.clone(),
+            body: task.description.clone(),
+            base_branch: request.base_branch,
+        },
+    };
+    task_attempts::open_pull_request(&app_state, &project, &task, &attempt, request).await
+}
+
+/// The task's coding agent conversations, across all its attempts, as a Markdown or JSON
+/// download
+pub async fn export_task_conversations(
+    Extension(task): Extension<Task>,
+    State(app_state): State<AppState>,
+    Query(query): Query<ConversationExportQuery>,
+) -> Result<Response, StatusCode> {
+    let export = match conversation_export::export(&app_state.db_pool, &task).await {
+        Ok(export) => export,
+        Err(e) => {
+            tracing::error!("Failed to export conversations of task {}: {}", task.id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let (mut response, extension) = match query.format {
+        ConversationExportFormat::Markdown => (
+            (
+                [(header::CONTENT_TYPE, "text/markdown; charset=utf-8")],
+                conversation_export::render_markdown(&export),
+            )
+                .into_response(),
+            "md",
+        ),
+        ConversationExportFormat::Json => (ResponseJson(export).into_response(), "json"),
+    };
+    let file_name: String = task
+        .title
+        .chars()
+        .take(60)
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let disposition = format!(
+        "attachment; filename=\"{}.conversation.{}\"",
+        file_name, extension
+    );
+    if let Ok(value) = HeaderValue::from_str(&disposition) {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_DISPOSITION, value);
+    }
+    Ok(response)
+}
+
+pub fn tasks_with_id_router() -> Router<AppState> {
+    use axum::routing::{delete, post, put};
+
+    Router::new()
+        .route(
+            "/projects/:project_id/tasks/:task_id",
+            get(get_task).put(update_task).delete(delete_task),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/archive",
+            post(archive_task).delete(unarchive_task),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/follow-up-draft",
+            get(super::follow_up_drafts::get_follow_up_draft)
+                .put(super::follow_up_drafts::save_follow_up_draft)
+                .delete(super::follow_up_drafts::delete_follow_up_draft),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/restore",
+            post(restore_task),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/timeout",
+            put(set_task_timeout),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/priority",
+            put(set_task_priority),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/executor",
+            get(get_task_executor).put(set_task_executor),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/executor-profile",
+            get(get_task_executor_profile).put(assign_task_executor_profile),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/linked-projects",
+            get(get_task_linked_projects).put(set_task_linked_projects),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/summarize",
+            post(summarize_task),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/create-pr",
+            post(create_task_pr),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/original",
+            get(get_task_original_text),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/pr-description",
+            get(get_task_pr_description)
+                .post(generate_task_pr_description)
+                .delete(delete_task_pr_description),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/conversations/export",
+            get(export_task_conversations),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/estimate",
+            get(estimate_task_run),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/labels",
+            get(get_task_labels)
+                .post(add_task_label)
+                .delete(remove_task_label),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/checklist",
+            get(get_task_checklist).put(update_task_checklist_item),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/subtasks",
+            get(get_task_subtasks).post(create_subtask),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/decompose",
+            post(decompose_task),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/schedule",
+            get(get_task_schedule)
+                .put(upsert_task_schedule)
+                .delete(delete_task_schedule),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/schedule/runs",
+            get(get_task_schedule_runs),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/label-suggestions",
+            delete(dismiss_label_suggestion),
+        )
+        .route(
+            "/projects/:project_id/tasks/:task_id/dependencies",
+            get(get_task_dependencies)
+                .post(add_task_dependency)
+                .delete(remove_task_dependency),
+        )
 }
@@ -0,0 +1,301 @@
+use std::net::{IpAddr, SocketAddr};
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    http::{header, uri::Authority, HeaderMap, StatusCode, Uri},
+    response::Response,
+    Extension,
+};
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    models::{project::Project, task_attempt::TaskAttempt},
+};
+
+const DEFAULT_COLS: u16 = 80;
+const DEFAULT_ROWS: u16 = 24;
+
+#[derive(Debug, Deserialize)]
+pub struct TerminalQuery {
+    pub cols: Option<u16>,
+    pub rows: Option<u16>,
+}
+
+/// Control messages sent by the client as text frames; binary frames are raw keystrokes
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum TerminalClientMessage {
+    Input { data: String },
+    Resize { cols: u16, rows: u16 },
+}
+
+/// Where the server listens, added to every request once it is bound
+#[derive(Debug, Clone, Copy)]
+pub struct ListenAddress(pub SocketAddr);
+
+/// More `Host`s the server is reached by, such as its name on the LAN or behind a proxy
+pub const ALLOWED_HOSTS_VAR: &str = "VIBE_KANBAN_ALLOWED_HOSTS";
+
+fn allowed_hosts() -> Vec<String> {
+    std::env::var(ALLOWED_HOSTS_VAR)
+        .unwrap_or_default()
+        .split(',')
+        .map(|host| host.trim().to_ascii_lowercase())
+        .filter(|host| !host.is_empty())
+        .collect()
+}
+
+fn is_loopback(host: &str) -> bool {
+    matches!(host, "localhost" | "127.0.0.1" | "[::1]")
+}
+
+/// Whether `host`, a `Host` header, names this server: on its own port, a loopback address
+/// or the address it's bound to (any address if bound to all of them), or else one of the
+/// allowed hosts. Names pointed at it by anyone else, as DNS rebinding does, are refused.
+fn is_own_host(host: &str, listen: SocketAddr, allowed: &[String]) -> bool {
+    let host = host.to_ascii_lowercase();
+    if allowed.contains(&host) {
+        return true;
+    }
+    let Ok(authority) = host.parse::<Authority>() else {
+        return false;
+    };
+    if authority.port_u16() != Some(listen.port()) {
+        return false;
+    }
+    let name = authority.host();
+    let address = name
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+        .ok();
+    is_loopback(name)
+        || match address {
+            Some(address) => listen.ip().is_unspecified() || address == listen.ip(),
+            None => false,
+        }
+}
+
+/// Whether a WebSocket upgrade comes from the app itself. Browsers always send `Origin` on
+/// WebSocket handshakes and CORS doesn't apply to them, so without this any page the user
+/// visits could open a shell. The request has to be for one of the server's own hosts and
+/// from that same origin, or from a loopback address as served by the dev frontend when
+/// the server is reached over loopback too. Requests without an origin are refused.
+fn is_own_origin(headers: &HeaderMap, listen: SocketAddr, allowed: &[String]) -> bool {
+    let Some(host) = headers
+        .get(header::HOST)
+        .and_then(|host| host.to_str().ok())
+        .filter(|host| is_own_host(host, listen, allowed))
+    else {
+        return false;
+    };
+    let Some(origin) = headers
+        .get(header::ORIGIN)
+        .and_then(|origin| origin.to_str().ok())
+        .and_then(|origin| origin.parse::<Uri>().ok())
+    else {
+        return false;
+    };
+    let Some(authority) = origin.authority() else {
+        return false;
+    };
+    host.eq_ignore_ascii_case(authority.as_str())
+        || (is_loopback(authority.host())
+            && host
+                .parse::<Authority>()
+                .is_ok_and(|host| is_loopback(host.host())))
+}
+
+/// Upgrade to a WebSocket running an interactive shell in the attempt's worktree.
+/// Shell output is sent back as binary frames.
+pub async fn attach_terminal(
+    ws: WebSocketUpgrade,
+    headers: HeaderMap,
+    Extension(ListenAddress(listen)): Extension<ListenAddress>,
+    Extension(project): Extension<Project>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(app_state): State<AppState>,
+    Query(query): Query<TerminalQuery>,
+) -> Result<Response, StatusCode> {
+    if cfg!(not(unix)) {
+        return Err(StatusCode::NOT_IMPLEMENTED);
+    }
+    if !is_own_origin(&headers, listen, &allowed_hosts()) {
+        tracing::warn!(
+            "Refused terminal for attempt {} from origin {:?} to host {:?}",
+            task_attempt.id,
+            headers.get(header::ORIGIN),
+            headers.get(header::HOST)
+        );
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let worktree_path = TaskAttempt::ensure_worktree_exists(
+        &app_state.db_pool,
+        task_attempt.id,
+        project.id,
+        "terminal",
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(
+            "Failed to prepare worktree for terminal on attempt {}: {}",
+            task_attempt.id,
+            e
+        );
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let cols = query.cols.unwrap_or(DEFAULT_COLS);
+    let rows = query.rows.unwrap_or(DEFAULT_ROWS);
+    Ok(ws.on_upgrade(move |socket| run_terminal(socket, worktree_path, cols, rows)))
+}
+
+#[cfg(unix)]
+async fn run_terminal(mut socket: WebSocket, worktree_path: String, cols: u16, rows: u16) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use crate::utils::pty::PtyShell;
+
+    let mut shell = match PtyShell::spawn(std::path::Path::new(&worktree_path), cols, rows) {
+        Ok(shell) => shell,
+        Err(e) => {
+            tracing::error!("Failed to start terminal in {}: {}", worktree_path, e);
+            let _ = socket
+                .send(Message::Text(format!("Failed to start shell: {}", e)))
+                .await;
+            return;
+        }
+    };
+    let (mut reader, mut writer) = match shell.io() {
+        Ok(io) => io,
+        Err(e) => {
+            tracing::error!("Failed to open terminal io: {}", e);
+            return;
+        }
+    };
+
+    let mut buf = vec![0u8; 8192];
+    loop {
+        tokio::select! {
+            read = reader.read(&mut buf) => match read {
+                // EIO on the master side means the shell exited
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    if socket.send(Message::Binary(buf[..n].to_vec())).await.is_err() {
+                        break;
+                    }
+                }
+            },
+            message = socket.recv() => {
+                let input = match message {
+                    Some(Ok(Message::Binary(data))) => data,
+                    Some(Ok(Message::Text(text))) => {
+                        match serde_json::from_str::<TerminalClientMessage>(&text) {
+                            Ok(TerminalClientMessage::Input { data }) => data.into_bytes(),
+                            Ok(TerminalClientMessage::Resize { cols, rows }) => {
+                                if let Err(e) = shell.resize(cols, rows) {
+                                    tracing::warn!("Failed to resize terminal: {}", e);
+                                }
+                                continue;
+                            }
+                            Err(_) => text.into_bytes(),
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                    Some(Ok(_)) => continue,
+                };
+                if writer.write_all(&input).await.is_err() || writer.flush().await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+
+    let _ = shell.child.kill().await;
+    let _ = socket.close().await;
+}
+
+#[cfg(not(unix))]
+async fn run_terminal(_socket: WebSocket, _worktree_path: String, _cols: u16, _rows: u16) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCAL: &str = "127.0.0.1:3001";
+
+    fn headers(origin: Option<&str>, host: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::HOST, host.parse().unwrap());
+        if let Some(origin) = origin {
+            headers.insert(header::ORIGIN, origin.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_only_the_servers_own_hosts_are_accepted() {
+        let local: SocketAddr = LOCAL.parse().unwrap();
+        let everywhere: SocketAddr = "0.0.0.0:3001".parse().unwrap();
+        let allowed = vec!["kanban.lan:3001".to_string()];
+
+        assert!(is_own_host("localhost:3001", local, &[]));
+        assert!(is_own_host("[::1]:3001", local, &[]));
+        assert!(is_own_host("127.0.0.1:3001", local, &[]));
+        assert!(!is_own_host("localhost:4000", local, &[]));
+        assert!(!is_own_host("192.168.1.5:3001", local, &[]));
+        assert!(is_own_host("192.168.1.5:3001", everywhere, &[]));
+
+        // Names only as allowed, so a rebound name of the attacker's is refused
+        assert!(!is_own_host("evil.example:3001", everywhere, &[]));
+        assert!(is_own_host("Kanban.lan:3001", everywhere, &allowed));
+        assert!(!is_own_host("kanban.lan", everywhere, &allowed));
+    }
+
+    #[test]
+    fn test_only_the_apps_own_origin_may_open_a_terminal() {
+        let local: SocketAddr = LOCAL.parse().unwrap();
+        let everywhere: SocketAddr = "0.0.0.0:3001".parse().unwrap();
+        let allowed = vec!["kanban.lan:3001".to_string()];
+
+        assert!(is_own_origin(
+            &headers(Some("http://kanban.lan:3001"), "kanban.lan:3001"),
+            everywhere,
+            &allowed
+        ));
+        // The dev frontend proxies to the server from its own port
+        assert!(is_own_origin(
+            &headers(Some("http://localhost:3000"), "localhost:3001"),
+            local,
+            &[]
+        ));
+
+        assert!(!is_own_origin(&headers(None, LOCAL), local, &[]));
+        assert!(!is_own_origin(
+            &headers(Some("https://evil.example"), LOCAL),
+            local,
+            &[]
+        ));
+        assert!(!is_own_origin(
+            &headers(Some("http://evil.example:3001"), "evil.example:3001"),
+            everywhere,
+            &[]
+        ));
+        assert!(!is_own_origin(
+            &headers(Some("http://localhost:3000"), "kanban.lan:3001"),
+            everywhere,
+            &allowed
+        ));
+        assert!(!is_own_origin(
+            &headers(Some("http://kanban.lan:4000"), "kanban.lan:3001"),
+            everywhere,
+            &allowed
+        ));
+        assert!(!is_own_origin(&headers(Some("null"), LOCAL), local, &[]));
+    }
+}
@@ -0,0 +1,969 @@
+use std::net::SocketAddr;
+
+use axum::{
+    extract::{ConnectInfo, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Json as ResponseJson, Redirect, Response},
+    routing::{delete, get, post, put},
+    Extension, Json, Router,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    models::{
+        project::Project,
+        project_invite::{
+            AcceptProjectInvite, CreateProjectInvite, CreatedProjectInvite, InviteDetails,
+            ProjectInvite,
+        },
+        project_member::{ProjectMember, ProjectMembership, SetProjectMember},
+        two_factor::{RecoveryCodes, TwoFactor, TwoFactorCode, TwoFactorEnrollment},
+        user::{CreateUser, UpdateUser, User, UserSession},
+        ApiResponse,
+    },
+    services::{
+        accounts,
+        login_throttle::{self, Subject},
+        oidc::{self, OidcIdentity},
+        project_invites, two_factor,
+    },
+};
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct AuthStatus {
+    pub auth_enabled: bool,
+    pub oidc_enabled: bool,
+    /// No account exists yet, so the first one can be registered and becomes an admin
+    pub needs_setup: bool,
+    pub user: Option<User>,
+    pub memberships: Vec<ProjectMembership>,
+    /// The signed-in user asks for a code from their authenticator app at sign-in
+    pub two_factor_enabled: bool,
+    /// A project the signed-in user reaches requires two-factor authentication and they
+    /// haven't turned it on yet, so that project turns them away until they have
+    pub two_factor_required: bool,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct LoginRequest {
+    pub username: String,
+    pub password: String,
+    /// A code from the authenticator app, or a recovery code, for accounts with two-factor
+    /// authentication turned on
+    pub code: Option<String>,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct LoginResponse {
+    pub user: User,
+    /// The session token, for API clients that send it as a bearer token rather than
+    /// keeping the cookie
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct ChangePassword {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OidcCallbackQuery {
+    pub code: String,
+    pub state: String,
+}
+
+fn internal_error(action: &str, e: sqlx::Error) -> StatusCode {
+    tracing::error!("Failed to {}: {}", action, e);
+    StatusCode::INTERNAL_SERVER_ERROR
+}
+
+async fn session_hours(app_state: &AppState) -> u32 {
+    app_state.get_config().read().await.auth.session_hours
+}
+
+/// The signed-in user, or 401 for requests without a live session
+async fn signed_in_user(app_state: &AppState, headers: &HeaderMap) -> Result<User, StatusCode> {
+    accounts::current_user(&app_state.db_pool, headers)
+        .await
+        .map_err(|e| internal_error("load the signed-in user", e))?
+        .ok_or(StatusCode::UNAUTHORIZED)
+}
+
+/// Start a session for the user and answer with it, setting the session cookie
+async fn signed_in(app_state: &AppState, user: User) -> Result<Response, StatusCode> {
+    let hours = session_hours(app_state).await;
+    let token = accounts::start_session(&app_state.db_pool, user.id, hours)
+        .await
+        .map_err(|e| internal_error("start a session", e))?;
+    Ok((
+        [(header::SET_COOKIE, accounts::session_cookie(&token, hours))],
+        ResponseJson(ApiResponse::success(LoginResponse { user, token })),
+    )
+        .into_response())
+}
+
+/// GET /auth/status: whether sign-in is on, and who is signed in
+pub async fn get_auth_status(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<ResponseJson<ApiResponse<AuthStatus>>, StatusCode> {
+    let auth = app_state.get_config().read().await.auth.clone();
+    let pool = &app_state.db_pool;
+    let needs_setup = User::count(pool)
+        .await
+        .map_err(|e| internal_error("count users", e))?
+        == 0;
+    let user = accounts::current_user(pool, &headers)
+        .await
+        .map_err(|e| internal_error("load the signed-in user", e))?;
+    let (memberships, two_factor_enabled, two_factor_required) = match &user {
+        Some(user) => (
+            ProjectMember::find_memberships(pool, user.id)
+                .await
+                .map_err(|e| internal_error("load project memberships", e))?,
+            TwoFactor::is_enabled(pool, user.id)
+                .await
+                .map_err(|e| internal_error("look up two-factor authentication", e))?,
+            two_factor::enrollment_required(pool, user)
+                .await
+                .map_err(|e| internal_error("look up two-factor authentication", e))?,
+        ),
+        None => (Vec::new(), false, false),
+    };
+    Ok(ResponseJson(ApiResponse::success(AuthStatus {
+        auth_enabled: auth.enabled,
+        oidc_enabled: oidc::is_configured(&auth.oidc),
+        needs_setup,
+        user,
+        memberships,
+        two_factor_enabled,
+        two_factor_required,
+    })))
+}
+
+/// POST /auth/register: create the first account, an admin. Later accounts are created by
+/// admins.
+pub async fn register(
+    State(app_state): State<AppState>,
+    Json(payload): Json<CreateUser>,
+) -> Result<Response, StatusCode> {
+    let pool = &app_state.db_pool;
+    if User::count(pool)
+        .await
+        .map_err(|e| internal_error("count users", e))?
+        > 0
+    {
+        return Ok(
+            ResponseJson(ApiResponse::<()>::error("An account already exists")).into_response(),
+        );
+    }
+    if let Err(e) = accounts::validate_username(&payload.username)
+        .and_then(|_| accounts::validate_password(&payload.password))
+    {
+        return Ok(ResponseJson(ApiResponse::<()>::error(&e)).into_response());
+    }
+
+    let user = User::create(
+        pool,
+        &payload.username,
+        payload.display_name.as_deref(),
+        Some(&accounts::hash_password(&payload.password)),
+        None,
+        true,
+    )
+    .await
+    .map_err(|e| internal_error("create the first account", e))?;
+    tracing::info!("Created the first account, {}", user.username);
+    signed_in(&app_state, user).await
+}
+
+/// Turn the request away if the subjects failed too often lately
+fn locked_out<T>(subjects: &[Subject]) -> Option<ResponseJson<ApiResponse<T>>> {
+    login_throttle::retry_after(subjects).map(|wait| {
+        ResponseJson(ApiResponse::error(&login_throttle::locked_out_message(
+            wait,
+        )))
+    })
+}
+
+/// POST /auth/login. Locked out for a while after too many wrong passwords or codes for the
+/// account, or from the client's address.
+pub async fn login(
+    State(app_state): State<AppState>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    Json(payload): Json<LoginRequest>,
+) -> Result<Response, StatusCode> {
+    let subjects = [
+        Subject::account(&payload.username),
+        Subject::Address(client.ip()),
+    ];
+    if let Some(response) = locked_out::<()>(&subjects) {
+        return Ok(response.into_response());
+    }
+    let pool = &app_state.db_pool;
+    let user = User::find_by_username(pool, &payload.username)
+        .await
+        .map_err(|e| internal_error("look up the account", e))?;
+    let hash = match &user {
+        Some(user) => User::find_password_hash(pool, user.id)
+            .await
+            .map_err(|e| internal_error("look up the account", e))?,
+        None => None,
+    };
+    let user = match (user, hash) {
+        (Some(user), Some(hash)) if accounts::verify_password(&payload.password, &hash) => user,
+        _ => {
+            login_throttle::record_failure(&subjects);
+            return Ok(
+                ResponseJson(ApiResponse::<()>::error("Wrong username or password"))
+                    .into_response(),
+            );
+        }
+    };
+
+    if TwoFactor::is_enabled(pool, user.id)
+        .await
+        .map_err(|e| internal_error("look up two-factor authentication", e))?
+    {
+        let Some(code) = payload
+            .code
+            .as_deref()
+            .filter(|code| !code.trim().is_empty())
+        else {
+            return Ok(ResponseJson(ApiResponse::<()>::error(
+                "Enter the code from your authenticator app, or a recovery code",
+            ))
+            .into_response());
+        };
+        if let Some(response) = locked_out::<()>(&[Subject::TwoFactor(user.id)]) {
+            return Ok(response.into_response());
+        }
+        // Counted against the user's codes by `verify` itself
+        if !two_factor::verify(pool, user.id, code, Utc::now())
+            .await
+            .map_err(|e| internal_error("check the two-factor code", e))?
+        {
+            login_throttle::record_failure(&subjects);
+            return Ok(
+                ResponseJson(ApiResponse::<()>::error("Wrong two-factor code")).into_response(),
+            );
+        }
+    }
+    login_throttle::record_success(&subjects[..1]);
+    signed_in(&app_state, user).await
+}
+
+/// POST /auth/logout
+pub async fn logout(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    if let Some(token) = accounts::session_token(&headers) {
+        accounts::end_session(&app_state.db_pool, &token)
+            .await
+            .map_err(|e| internal_error("end the session", e))?;
+    }
+    Ok((
+        [(header::SET_COOKIE, accounts::cleared_session_cookie())],
+        ResponseJson(ApiResponse::success(())),
+    )
+        .into_response())
+}
+
+/// POST /auth/refresh: swap the session's token for a new one with a later expiry. Refused
+/// once the session reaches `max_session_days` after sign-in.
+pub async fn refresh_session(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let user = signed_in_user(&app_state, &headers).await?;
+    let Some(token) = accounts::session_token(&headers) else {
+        return Err(StatusCode::UNAUTHORIZED);
+    };
+    let auth = app_state.get_config().read().await.auth.clone();
+    let refreshed = accounts::refresh_session(
+        &app_state.db_pool,
+        &token,
+        auth.session_hours,
+        auth.max_session_days,
+    )
+    .await
+    .map_err(|e| internal_error("refresh the session", e))?;
+    match refreshed {
+        Some(token) => Ok((
+            [(
+                header::SET_COOKIE,
+                accounts::session_cookie(&token, auth.session_hours),
+            )],
+            ResponseJson(ApiResponse::success(LoginResponse { user, token })),
+        )
+            .into_response()),
+        None => Ok(ResponseJson(ApiResponse::<()>::error(
+            "The session can't be refreshed any longer; sign in again",
+        ))
+        .into_response()),
+    }
+}
+
+/// POST /auth/logout-all: end every session of the signed-in user, this one included
+pub async fn logout_all(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let user = signed_in_user(&app_state, &headers).await?;
+    let ended = UserSession::delete_by_user_id(&app_state.db_pool, user.id)
+        .await
+        .map_err(|e| internal_error("end the account's sessions", e))?;
+    tracing::info!("{} signed out of {} sessions", user.username, ended);
+    Ok((
+        [(header::SET_COOKIE, accounts::cleared_session_cookie())],
+        ResponseJson(ApiResponse::success(())),
+    )
+        .into_response())
+}
+
+/// POST /auth/two-factor/enroll: a new TOTP secret for the signed-in user's authenticator
+/// app. Nothing changes at sign-in until a code from the app confirms it.
+pub async fn start_two_factor(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<ResponseJson<ApiResponse<TwoFactorEnrollment>>, StatusCode> {
+    let user = signed_in_user(&app_state, &headers).await?;
+    let pool = &app_state.db_pool;
+    if user.is_oidc {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Accounts that sign in through OIDC use the provider's two-factor authentication",
+        )));
+    }
+    if TwoFactor::is_enabled(pool, user.id)
+        .await
+        .map_err(|e| internal_error("look up two-factor authentication", e))?
+    {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Two-factor authentication is already on",
+        )));
+    }
+    match two_factor::start_enrollment(pool, &user).await {
+        Ok(enrollment) => Ok(ResponseJson(ApiResponse::success(enrollment))),
+        Err(e) => Err(internal_error("start two-factor enrollment", e)),
+    }
+}
+
+/// POST /auth/two-factor/confirm: turn two-factor authentication on with a code from the
+/// app. Answers with the recovery codes, which aren't shown again.
+pub async fn confirm_two_factor(
+    State(app_state): State<AppState>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(payload): Json<TwoFactorCode>,
+) -> Result<ResponseJson<ApiResponse<RecoveryCodes>>, StatusCode> {
+    let user = signed_in_user(&app_state, &headers).await?;
+    let subjects = [Subject::TwoFactor(user.id), Subject::Address(client.ip())];
+    if let Some(response) = locked_out(&subjects) {
+        return Ok(response);
+    }
+    match two_factor::confirm_enrollment(&app_state.db_pool, user.id, &payload.code, Utc::now())
+        .await
+    {
+        Ok(Some(codes)) => {
+            login_throttle::record_success(&subjects[..1]);
+            tracing::info!("{} turned on two-factor authentication", user.username);
+            Ok(ResponseJson(ApiResponse::success(codes)))
+        }
+        Ok(None) => {
+            login_throttle::record_failure(&subjects);
+            Ok(ResponseJson(ApiResponse::error(
+                "Wrong code, or no enrollment was started",
+            )))
+        }
+        Err(e) => Err(internal_error("confirm two-factor enrollment", e)),
+    }
+}
+
+/// Check a code the signed-in user gives to change their two-factor settings, counting
+/// wrong ones against them and their address like at sign-in. The answer to refuse with
+/// if the code won't do.
+async fn refuse_two_factor_code<T>(
+    app_state: &AppState,
+    user: &User,
+    client: SocketAddr,
+    code: &str,
+) -> Result<Option<ResponseJson<ApiResponse<T>>>, StatusCode> {
+    let subjects = [Subject::TwoFactor(user.id), Subject::Address(client.ip())];
+    if let Some(response) = locked_out(&subjects) {
+        return Ok(Some(response));
+    }
+    // Counted against the user's codes by `verify` itself
+    if !two_factor::verify(&app_state.db_pool, user.id, code, Utc::now())
+        .await
+        .map_err(|e| internal_error("check the two-factor code", e))?
+    {
+        login_throttle::record_failure(&subjects[1..]);
+        return Ok(Some(ResponseJson(ApiResponse::error(
+            "Wrong two-factor code",
+        ))));
+    }
+    Ok(None)
+}
+
+/// POST /auth/two-factor/recovery-codes: new recovery codes in place of the old ones, for a
+/// code from the app or one of the old recovery codes
+pub async fn replace_recovery_codes(
+    State(app_state): State<AppState>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(payload): Json<TwoFactorCode>,
+) -> Result<ResponseJson<ApiResponse<RecoveryCodes>>, StatusCode> {
+    let user = signed_in_user(&app_state, &headers).await?;
+    if let Some(response) = refuse_two_factor_code(&app_state, &user, client, &payload.code).await?
+    {
+        return Ok(response);
+    }
+    let pool = &app_state.db_pool;
+    match two_factor::replace_recovery_codes(pool, user.id).await {
+        Ok(codes) => Ok(ResponseJson(ApiResponse::success(codes))),
+        Err(e) => Err(internal_error("replace the recovery codes", e)),
+    }
+}
+
+/// POST /auth/two-factor/disable: turn two-factor authentication off, for a code from the
+/// app or a recovery code. Refused while a project the user reaches requires it.
+pub async fn disable_two_factor(
+    State(app_state): State<AppState>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(payload): Json<TwoFactorCode>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    let user = signed_in_user(&app_state, &headers).await?;
+    let pool = &app_state.db_pool;
+    if two_factor::required_by_projects(pool, &user)
+        .await
+        .map_err(|e| internal_error("look up two-factor authentication", e))?
+    {
+        return Ok(ResponseJson(ApiResponse::error(
+            "A project you belong to requires two-factor authentication",
+        )));
+    }
+    if let Some(response) = refuse_two_factor_code(&app_state, &user, client, &payload.code).await?
+    {
+        return Ok(response);
+    }
+    TwoFactor::delete(pool, user.id)
+        .await
+        .map_err(|e| internal_error("turn off two-factor authentication", e))?;
+    tracing::info!("{} turned off two-factor authentication", user.username);
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// POST /auth/password: change the signed-in user's own password. Signs them out
+/// everywhere else.
+pub async fn change_password(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<ChangePassword>,
+) -> Result<Response, StatusCode> {
+    let pool = &app_state.db_pool;
+    let user = signed_in_user(&app_state, &headers).await?;
+    let hash = User::find_password_hash(pool, user.id)
+        .await
+        .map_err(|e| internal_error("look up the account", e))?;
+    if !hash.is_some_and(|hash| accounts::verify_password(&payload.current_password, &hash)) {
+        return Ok(
+            ResponseJson(ApiResponse::<()>::error("The current password is wrong")).into_response(),
+        );
+    }
+    if let Err(e) = accounts::validate_password(&payload.new_password) {
+        return Ok(ResponseJson(ApiResponse::<()>::error(&e)).into_response());
+    }
+
+    let hash = accounts::hash_password(&payload.new_password);
+    let user = User::update(pool, user.id, None, None, Some(&hash))
+        .await
+        .map_err(|e| internal_error("change the password", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    UserSession::delete_by_user_id(pool, user.id)
+        .await
+        .map_err(|e| internal_error("end the account's sessions", e))?;
+    signed_in(&app_state, user).await
+}
+
+/// GET /auth/oidc/login: send the browser to the OIDC provider to sign in
+pub async fn oidc_login(State(app_state): State<AppState>) -> Response {
+    let config = app_state.get_config().read().await.auth.oidc.clone();
+    match oidc::authorization_url(&config).await {
+        Ok(url) => Redirect::to(&url).into_response(),
+        Err(e) => {
+            tracing::warn!("Failed to start OIDC sign-in: {}", e);
+            (StatusCode::BAD_GATEWAY, e.to_string()).into_response()
+        }
+    }
+}
+
+/// A username for a new account that no other account has
+async fn free_username(app_state: &AppState, wanted: &str) -> Result<String, sqlx::Error> {
+    let mut username = wanted.to_string();
+    let mut suffix = 2;
+    while User::find_by_username(&app_state.db_pool, &username)
+        .await?
+        .is_some()
+    {
+        username = format!("{}-{}", wanted, suffix);
+        suffix += 1;
+    }
+    Ok(username)
+}
+
+/// The account the identity signs in to, created on its first sign-in. The first account on
+/// the server is an admin; later ones get access to projects from an admin.
+async fn oidc_account(app_state: &AppState, identity: &OidcIdentity) -> Result<User, sqlx::Error> {
+    let pool = &app_state.db_pool;
+    if let Some(user) = User::find_by_oidc_subject(pool, &identity.subject).await? {
+        return Ok(user);
+    }
+    let is_admin = User::count(pool).await? == 0;
+    let username = free_username(app_state, &identity.username()).await?;
+    let user = User::create(
+        pool,
+        &username,
+        identity.name.as_deref(),
+        None,
+        Some(&identity.subject),
+        is_admin,
+    )
+    .await?;
+    tracing::info!(
+        "Created account {} on its first OIDC sign-in",
+        user.username
+    );
+    Ok(user)
+}
+
+/// GET /auth/oidc/callback: where the provider sends the browser back to
+pub async fn oidc_callback(
+    State(app_state): State<AppState>,
+    Query(query): Query<OidcCallbackQuery>,
+) -> Result<Response, StatusCode> {
+    let config = app_state.get_config().read().await.auth.oidc.clone();
+    let identity = match oidc::complete(&config, &query.code, &query.state).await {
+        Ok(identity) => identity,
+        Err(e) => {
+            tracing::warn!("OIDC sign-in failed: {}", e);
+            return Ok((StatusCode::BAD_REQUEST, e.to_string()).into_response());
+        }
+    };
+    let user = oidc_account(&app_state, &identity)
+        .await
+        .map_err(|e| internal_error("find or create the OIDC account", e))?;
+
+    let hours = session_hours(&app_state).await;
+    let token = accounts::start_session(&app_state.db_pool, user.id, hours)
+        .await
+        .map_err(|e| internal_error("start a session", e))?;
+    Ok((
+        [(header::SET_COOKIE, accounts::session_cookie(&token, hours))],
+        Redirect::to("/"),
+    )
+        .into_response())
+}
+
+pub async fn get_users(
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<User>>>, StatusCode> {
+    match User::find_all(&app_state.db_pool).await {
+        Ok(users) => Ok(ResponseJson(ApiResponse::success(users))),
+        Err(e) => Err(internal_error("load users", e)),
+    }
+}
+
+pub async fn create_user(
+    State(app_state): State<AppState>,
+    Json(payload): Json<CreateUser>,
+) -> Result<ResponseJson<ApiResponse<User>>, StatusCode> {
+    if let Err(e) = accounts::validate_username(&payload.username)
+        .and_then(|_| accounts::validate_password(&payload.password))
+    {
+        return Ok(ResponseJson(ApiResponse::error(&e)));
+    }
+    let pool = &app_state.db_pool;
+    match User::find_by_username(pool, &payload.username).await {
+        Ok(Some(_)) => {
+            return Ok(ResponseJson(ApiResponse::error(
+                "An account with that username already exists",
+            )))
+        }
+        Ok(None) => {}
+        Err(e) => return Err(internal_error("look up the username", e)),
+    }
+
+    match User::create(
+        pool,
+        &payload.username,
+        payload.display_name.as_deref(),
+        Some(&accounts::hash_password(&payload.password)),
+        None,
+        payload.is_admin.unwrap_or(false),
+    )
+    .await
+    {
+        Ok(user) => Ok(ResponseJson(ApiResponse::success(user))),
+        Err(e) => Err(internal_error("create the account", e)),
+    }
+}
+
+/// Whether `user_id` is the only admin left, who mustn't be removed or demoted
+async fn is_last_admin(app_state: &AppState, user_id: Uuid) -> Result<bool, sqlx::Error> {
+    let pool = &app_state.db_pool;
+    let is_admin = User::find_by_id(pool, user_id)
+        .await?
+        .is_some_and(|user| user.is_admin);
+    Ok(is_admin && User::count_admins(pool).await? <= 1)
+}
+
+pub async fn update_user(
+    Path(user_id): Path<Uuid>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<UpdateUser>,
+) -> Result<ResponseJson<ApiResponse<User>>, StatusCode> {
+    if let Some(password) = &payload.password {
+        if let Err(e) = accounts::validate_password(password) {
+            return Ok(ResponseJson(ApiResponse::error(&e)));
+        }
+    }
+    if payload.is_admin == Some(false)
+        && is_last_admin(&app_state, user_id)
+            .await
+            .map_err(|e| internal_error("count admins", e))?
+    {
+        return Ok(ResponseJson(ApiResponse::error(
+            "The last admin can't be demoted",
+        )));
+    }
+
+    let pool = &app_state.db_pool;
+    let password_hash = payload.password.as_deref().map(accounts::hash_password);
+    let user = match User::update(
+        pool,
+        user_id,
+        payload.display_name.as_deref(),
+        payload.is_admin,
+        password_hash.as_deref(),
+    )
+    .await
+    {
+        Ok(Some(user)) => user,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(e) => return Err(internal_error("update the account", e)),
+    };
+    if password_hash.is_some() {
+        UserSession::delete_by_user_id(pool, user_id)
+            .await
+            .map_err(|e| internal_error("end the account's sessions", e))?;
+    }
+    Ok(ResponseJson(ApiResponse::success(user)))
+}
+
+pub async fn delete_user(
+    Path(user_id): Path<Uuid>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    if is_last_admin(&app_state, user_id)
+        .await
+        .map_err(|e| internal_error("count admins", e))?
+    {
+        return Ok(ResponseJson(ApiResponse::error(
+            "The last admin can't be deleted",
+        )));
+    }
+    match User::delete(&app_state.db_pool, user_id).await {
+        Ok(0) => Err(StatusCode::NOT_FOUND),
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => Err(internal_error("delete the account", e)),
+    }
+}
+
+/// DELETE /users/:user_id/sessions: sign the account out everywhere, such as when one of
+/// its sessions has leaked
+pub async fn end_user_sessions(
+    Path(user_id): Path<Uuid>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match UserSession::delete_by_user_id(&app_state.db_pool, user_id).await {
+        Ok(ended) => {
+            tracing::info!("Ended {} sessions of account {}", ended, user_id);
+            Ok(ResponseJson(ApiResponse::success(())))
+        }
+        Err(e) => Err(internal_error("end the account's sessions", e)),
+    }
+}
+
+/// DELETE /users/:user_id/two-factor: turn two-factor authentication off for an account
+/// that lost its authenticator app and recovery codes. Where the server requires it, the
+/// user sets it up again on their next sign-in.
+pub async fn reset_user_two_factor(
+    Path(user_id): Path<Uuid>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match TwoFactor::delete(&app_state.db_pool, user_id).await {
+        Ok(0) => Err(StatusCode::NOT_FOUND),
+        Ok(_) => {
+            tracing::info!("Reset two-factor authentication of account {}", user_id);
+            Ok(ResponseJson(ApiResponse::success(())))
+        }
+        Err(e) => Err(internal_error("reset two-factor authentication", e)),
+    }
+}
+
+pub async fn get_project_members(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectMember>>>, StatusCode> {
+    match ProjectMember::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(members) => Ok(ResponseJson(ApiResponse::success(members))),
+        Err(e) => Err(internal_error("load project members", e)),
+    }
+}
+
+/// PUT /projects/:id/members: give a user a role in the project, or change it. Answers with
+/// the project's members.
+pub async fn set_project_member(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    Json(payload): Json<SetProjectMember>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectMember>>>, StatusCode> {
+    let pool = &app_state.db_pool;
+    let user = match User::find_by_username(pool, &payload.username).await {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            return Ok(ResponseJson(ApiResponse::error(&format!(
+                "No account is named {}",
+                payload.username
+            ))))
+        }
+        Err(e) => return Err(internal_error("look up the username", e)),
+    };
+    ProjectMember::upsert(pool, project.id, user.id, payload.role)
+        .await
+        .map_err(|e| internal_error("set the project member", e))?;
+    get_project_members(Extension(project), State(app_state)).await
+}
+
+pub async fn delete_project_member(
+    Path((project_id, user_id)): Path<(Uuid, Uuid)>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match ProjectMember::delete(&app_state.db_pool, project_id, user_id).await {
+        Ok(0) => Err(StatusCode::NOT_FOUND),
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => Err(internal_error("remove the project member", e)),
+    }
+}
+
+pub async fn get_project_invites(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectInvite>>>, StatusCode> {
+    match ProjectInvite::find_by_project_id(&app_state.db_pool, project.id).await {
+        Ok(invites) => Ok(ResponseJson(ApiResponse::success(invites))),
+        Err(e) => Err(internal_error("load project invites", e)),
+    }
+}
+
+/// POST /projects/:id/invites: an invite link to join the project with a role
+pub async fn create_project_invite(
+    Extension(project): Extension<Project>,
+    State(app_state): State<AppState>,
+    user: Option<Extension<User>>,
+    Json(payload): Json<CreateProjectInvite>,
+) -> Result<ResponseJson<ApiResponse<CreatedProjectInvite>>, StatusCode> {
+    let invited_by = user.map(|Extension(user)| user.id);
+    match project_invites::create(&app_state.db_pool, project.id, invited_by, &payload).await {
+        Ok(created) => {
+            tracing::info!(
+                "Created invite {} to project {} as {:?}",
+                created.invite.id,
+                project.id,
+                created.invite.role
+            );
+            Ok(ResponseJson(ApiResponse::success(created)))
+        }
+        Err(e) => Err(internal_error("create the project invite", e)),
+    }
+}
+
+pub async fn revoke_project_invite(
+    Path((project_id, invite_id)): Path<(Uuid, Uuid)>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    match ProjectInvite::revoke(&app_state.db_pool, project_id, invite_id).await {
+        Ok(0) => Err(StatusCode::NOT_FOUND),
+        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(e) => Err(internal_error("revoke the project invite", e)),
+    }
+}
+
+const INVITE_UNUSABLE: &str = "This invite has expired, was revoked or was used already";
+
+/// GET /auth/invites/:token: the project and role an invite link is for
+pub async fn get_invite(
+    Path(token): Path<String>,
+    State(app_state): State<AppState>,
+) -> Result<ResponseJson<ApiResponse<InviteDetails>>, StatusCode> {
+    let pool = &app_state.db_pool;
+    let Some(invite) = project_invites::find_pending(pool, &token)
+        .await
+        .map_err(|e| internal_error("look up the invite", e))?
+    else {
+        return Ok(ResponseJson(ApiResponse::error(INVITE_UNUSABLE)));
+    };
+    let project = Project::find_by_id(pool, invite.project_id)
+        .await
+        .map_err(|e| internal_error("load the invite's project", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    Ok(ResponseJson(ApiResponse::success(InviteDetails {
+        project_id: project.id,
+        project_name: project.name,
+        role: invite.role,
+        expires_at: invite.expires_at,
+    })))
+}
+
+/// POST /auth/invites/:token/accept: join the invite's project as the signed-in user or,
+/// without a session, as a new account with the username and password given, which is then
+/// signed in
+pub async fn accept_invite(
+    Path(token): Path<String>,
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<AcceptProjectInvite>,
+) -> Result<Response, StatusCode> {
+    let pool = &app_state.db_pool;
+    let Some(invite) = project_invites::find_pending(pool, &token)
+        .await
+        .map_err(|e| internal_error("look up the invite", e))?
+    else {
+        return Ok(ResponseJson(ApiResponse::<()>::error(INVITE_UNUSABLE)).into_response());
+    };
+
+    let signed_in_user = accounts::current_user(pool, &headers)
+        .await
+        .map_err(|e| internal_error("load the signed-in user", e))?;
+    let (user, new_account) = match signed_in_user {
+        Some(user) => (user, false),
+        None => {
+            let (Some(username), Some(password)) = (&payload.username, &payload.password) else {
+                return Ok(ResponseJson(ApiResponse::<()>::error(
+                    "Sign in, or choose a username and password for a new account",
+                ))
+                .into_response());
+            };
+            if let Err(e) = accounts::validate_username(username)
+                .and_then(|_| accounts::validate_password(password))
+            {
+                return Ok(ResponseJson(ApiResponse::<()>::error(&e)).into_response());
+            }
+            if User::find_by_username(pool, username)
+                .await
+                .map_err(|e| internal_error("look up the username", e))?
+                .is_some()
+            {
+                return Ok(ResponseJson(ApiResponse::<()>::error(
+                    "An account with that username already exists; sign in to accept",
+                ))
+                .into_response());
+            }
+            let user = User::create(
+                pool,
+                username,
+                payload.display_name.as_deref(),
+                Some(&accounts::hash_password(password)),
+                None,
+                false,
+            )
+            .await
+            .map_err(|e| internal_error("create the account", e))?;
+            (user, true)
+        }
+    };
+
+    let Some(membership) = project_invites::accept(pool, &invite, user.id)
+        .await
+        .map_err(|e| internal_error("accept the invite", e))?
+    else {
+        return Ok(ResponseJson(ApiResponse::<()>::error(INVITE_UNUSABLE)).into_response());
+    };
+    tracing::info!(
+        "{} joined project {} as {:?} through invite {}",
+        user.username,
+        membership.project_id,
+        membership.role,
+        invite.id
+    );
+    let response = ResponseJson(ApiResponse::success(membership));
+    if !new_account {
+        return Ok(response.into_response());
+    }
+    let hours = session_hours(&app_state).await;
+    let token = accounts::start_session(pool, user.id, hours)
+        .await
+        .map_err(|e| internal_error("start a session", e))?;
+    Ok((
+        [(header::SET_COOKIE, accounts::session_cookie(&token, hours))],
+        response,
+    )
+        .into_response())
+}
+
+pub fn accounts_router() -> Router<AppState> {
+    Router::new()
+        .route("/auth/status", get(get_auth_status))
+        .route("/auth/register", post(register))
+        .route("/auth/login", post(login))
+        .route("/auth/logout", post(logout))
+        .route("/auth/logout-all", post(logout_all))
+        .route("/auth/refresh", post(refresh_session))
+        .route("/auth/password", post(change_password))
+        .route("/auth/two-factor/enroll", post(start_two_factor))
+        .route("/auth/two-factor/confirm", post(confirm_two_factor))
+        .route(
+            "/auth/two-factor/recovery-codes",
+            post(replace_recovery_codes),
+        )
+        .route("/auth/two-factor/disable", post(disable_two_factor))
+        .route("/auth/invites/:token", get(get_invite))
+        .route("/auth/invites/:token/accept", post(accept_invite))
+        .route("/auth/oidc/login", get(oidc_login))
+        .route("/auth/oidc/callback", get(oidc_callback))
+        .route("/users", get(get_users).post(create_user))
+        .route("/users/:user_id", put(update_user).delete(delete_user))
+        .route("/users/:user_id/sessions", delete(end_user_sessions))
+        .route("/users/:user_id/two-factor", delete(reset_user_two_factor))
+        .route(
+            "/projects/:id/members/:user_id",
+            delete(delete_project_member),
+        )
+        .route(
+            "/projects/:id/invites/:invite_id",
+            delete(revoke_project_invite),
+        )
+}
+
+pub fn project_members_router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/projects/:id/members",
+            get(get_project_members).put(set_project_member),
+        )
+        .route(
+            "/projects/:id/invites",
+            get(get_project_invites).post(create_project_invite),
+        )
+}
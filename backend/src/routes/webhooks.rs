@@ -0,0 +1,191 @@
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::Json as ResponseJson,
+    routing::post,
+    Extension, Json, Router,
+};
+
+use crate::{
+    app_state::AppState,
+    models::{project::Project, task::CreateTask, ApiResponse},
+    routes::tasks::{self, CreatedTask},
+    services::{
+        github_issues::{self, GitHubIssuesClient},
+        webhook_signing::{DELIVERY_HEADER, SIGNATURE_HEADER},
+        PrMonitorService, WebhookSignatureError,
+    },
+};
+
+pub fn webhooks_router() -> Router<AppState> {
+    Router::new()
+        .route("/webhooks/github", post(github_webhook))
+        .route("/webhooks/gitlab", post(gitlab_webhook))
+        .route("/webhooks/tasks", post(task_webhook))
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+fn rejection_status(err: &WebhookSignatureError) -> StatusCode {
+    match err {
+        // Without a secret the receiver is effectively disabled
+        WebhookSignatureError::NoSecretConfigured => StatusCode::NOT_FOUND,
+        WebhookSignatureError::Replayed(_) => StatusCode::CONFLICT,
+        _ => StatusCode::UNAUTHORIZED,
+    }
+}
+
+/// Receive a GitHub webhook, verifying `X-Hub-Signature-256` and rejecting replayed deliveries
+pub async fn github_webhook(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    let signer = app_state.get_config().read().await.webhooks.signer();
+
+    let verified = signer
+        .verify_github(header(&headers, "x-hub-signature-256"), &body)
+        .and_then(|_| match header(&headers, "x-github-delivery") {
+            Some(delivery_id) => app_state.webhook_replay_guard.check_and_record(delivery_id),
+            None => Err(WebhookSignatureError::MalformedSignature(
+                "missing X-GitHub-Delivery header".to_string(),
+            )),
+        });
+    if let Err(e) = verified {
+        tracing::warn!("Rejected GitHub webhook: {}", e);
+        return Err(rejection_status(&e));
+    }
+
+    let event = header(&headers, "x-github-event").unwrap_or("unknown");
+    tracing::info!("Received GitHub webhook event: {}", event);
+
+    match event {
+        "pull_request" => refresh_pull_requests(&app_state).await,
+        "issues" => sync_issues(&app_state, &body).await,
+        _ => {}
+    }
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Sync the projects importing issues from the repository the event came from
+async fn sync_issues(app_state: &AppState, body: &[u8]) {
+    let full_name = serde_json::from_slice::<serde_json::Value>(body)
+        .ok()
+        .and_then(|payload| {
+            payload["repository"]["full_name"]
+                .as_str()
+                .map(str::to_string)
+        });
+    let Some(full_name) = full_name else {
+        tracing::warn!("GitHub issues webhook without a repository");
+        return;
+    };
+    let client = match GitHubIssuesClient::from_config(&app_state.get_config().read().await.github)
+    {
+        Some(Ok(client)) => client,
+        Some(Err(e)) => {
+            tracing::error!("Failed to sync issues after webhook: {}", e);
+            return;
+        }
+        None => return,
+    };
+    if let Err(e) = github_issues::sync_repository(&app_state.db_pool, &client, &full_name).await {
+        tracing::error!(
+            "Failed to sync issues of {} after webhook: {}",
+            full_name,
+            e
+        );
+    }
+}
+
+/// Refresh tracked PRs right away instead of waiting for the next poll
+async fn refresh_pull_requests(app_state: &AppState) {
+    let github = app_state.get_config().read().await.github.clone();
+    let pr_monitor = PrMonitorService::new(app_state.db_pool.clone());
+    if let Err(e) = pr_monitor.check_all_open_prs(&github).await {
+        tracing::error!("Failed to refresh PRs after webhook: {}", e);
+    }
+}
+
+/// Receive a GitLab webhook, verifying `X-Gitlab-Token` and rejecting replayed deliveries
+pub async fn gitlab_webhook(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<ResponseJson<ApiResponse<()>>, StatusCode> {
+    let signer = app_state.get_config().read().await.webhooks.signer();
+
+    let verified = signer
+        .verify_gitlab(header(&headers, "x-gitlab-token"))
+        .and_then(|_| match header(&headers, "x-gitlab-event-uuid") {
+            Some(event_id) => app_state.webhook_replay_guard.check_and_record(event_id),
+            None => Err(WebhookSignatureError::MalformedSignature(
+                "missing X-Gitlab-Event-UUID header".to_string(),
+            )),
+        });
+    if let Err(e) = verified {
+        tracing::warn!("Rejected GitLab webhook: {}", e);
+        return Err(rejection_status(&e));
+    }
+
+    let event = header(&headers, "x-gitlab-event").unwrap_or("unknown");
+    tracing::info!("Received GitLab webhook event: {}", event);
+
+    if event == "Merge Request Hook" {
+        refresh_pull_requests(&app_state).await;
+    }
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Create a task from a request signed the way our own outgoing webhooks are: a
+/// timestamped `X-Vibe-Kanban-Signature` over the body, which is rejected once it falls
+/// outside the configured window, and an `X-Vibe-Kanban-Delivery` id that can't be reused
+pub async fn task_webhook(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<ResponseJson<ApiResponse<CreatedTask>>, StatusCode> {
+    let signer = app_state.get_config().read().await.webhooks.signer();
+
+    let verified = signer
+        .verify_timestamped(
+            header(&headers, SIGNATURE_HEADER),
+            &body,
+            chrono::Utc::now().timestamp(),
+        )
+        .and_then(|_| match header(&headers, DELIVERY_HEADER) {
+            Some(delivery_id) => app_state.webhook_replay_guard.check_and_record(delivery_id),
+            None => Err(WebhookSignatureError::MalformedSignature(
+                "missing X-Vibe-Kanban-Delivery header".to_string(),
+            )),
+        });
+    if let Err(e) = verified {
+        tracing::warn!("Rejected task webhook: {}", e);
+        return Err(rejection_status(&e));
+    }
+
+    let payload: CreateTask = match serde_json::from_slice(&body) {
+        Ok(payload) => payload,
+        Err(e) => {
+            return Ok(ResponseJson(ApiResponse::error(&format!(
+                "Invalid task: {}",
+                e
+            ))))
+        }
+    };
+    let project = match Project::find_by_id(&app_state.db_pool, payload.project_id).await {
+        Ok(Some(project)) => project,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Failed to fetch project {}: {}", payload.project_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    tracing::info!("Creating task '{}' from webhook", payload.title);
+
+    tasks::create_task(Extension(project), State(app_state), Json(payload)).await
+}
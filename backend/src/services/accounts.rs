@@ -0,0 +1,347 @@
+//! Accounts and sessions for a server with sign-in turned on. Passwords are kept as salted
+//! PBKDF2 hashes. A session is a random token the browser holds in a cookie, or an API
+//! client sends as a bearer token; the database only has its hash. So do personal access
+//! tokens, which scripts send as a bearer token and which are told apart by their prefix.
+
+use std::num::NonZeroU32;
+
+use axum::http::{header, HeaderMap};
+use chrono::{Duration, Utc};
+use ring::{
+    pbkdf2,
+    rand::{SecureRandom, SystemRandom},
+};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    models::{
+        api_token::{ApiToken, CreateApiToken, CreatedApiToken},
+        project_member::{ProjectMember, ProjectRole},
+        user::{User, UserSession},
+    },
+};
+
+pub const SESSION_COOKIE: &str = "vk_session";
+pub const API_TOKEN_PREFIX: &str = "vkp_";
+/// How much of a personal access token is kept in the clear to tell it apart
+const API_TOKEN_SHOWN_LENGTH: usize = 12;
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+const PBKDF2_ITERATIONS: u32 = 600_000;
+const HASH_SCHEME: &str = "pbkdf2-sha256";
+
+pub(crate) fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .expect("the system random number generator failed");
+    bytes
+}
+
+/// A random token for a session or a sign-in in progress
+pub fn random_token() -> String {
+    hex::encode(random_bytes::<32>())
+}
+
+fn hash_with_iterations(password: &str, iterations: NonZeroU32) -> String {
+    let salt = random_bytes::<16>();
+    let mut hash = [0u8; 32];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        &salt,
+        password.as_bytes(),
+        &mut hash,
+    );
+    format!(
+        "{}${}${}${}",
+        HASH_SCHEME,
+        iterations,
+        hex::encode(salt),
+        hex::encode(hash)
+    )
+}
+
+pub fn hash_password(password: &str) -> String {
+    hash_with_iterations(password, NonZeroU32::new(PBKDF2_ITERATIONS).unwrap())
+}
+
+/// Whether `password` is the one `stored` was hashed from. Hashes made with a different
+/// number of iterations still verify, so the count can be raised later.
+pub fn verify_password(password: &str, stored: &str) -> bool {
+    let mut parts = stored.split('$');
+    let (Some(HASH_SCHEME), Some(iterations), Some(salt), Some(hash), None) = (
+        parts.next(),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+    ) else {
+        return false;
+    };
+    let (Some(iterations), Ok(salt), Ok(hash)) = (
+        iterations.parse().ok().and_then(NonZeroU32::new),
+        hex::decode(salt),
+        hex::decode(hash),
+    ) else {
+        return false;
+    };
+    pbkdf2::verify(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        &salt,
+        password.as_bytes(),
+        &hash,
+    )
+    .is_ok()
+}
+
+pub fn validate_username(username: &str) -> Result<(), String> {
+    let valid = !username.is_empty()
+        && username.len() <= 64
+        && username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '@'));
+    if valid {
+        Ok(())
+    } else {
+        Err("Usernames are 1 to 64 letters, digits and . _ - @".to_string())
+    }
+}
+
+pub fn validate_password(password: &str) -> Result<(), String> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(format!(
+            "Passwords need at least {} characters",
+            MIN_PASSWORD_LENGTH
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn token_hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Sign the user in, returning the session's token
+pub async fn start_session(
+    pool: &DbPool,
+    user_id: Uuid,
+    session_hours: u32,
+) -> Result<String, sqlx::Error> {
+    let now = Utc::now();
+    UserSession::delete_expired(pool, now).await?;
+    let token = random_token();
+    let expires_at = now + Duration::hours(i64::from(session_hours.max(1)));
+    UserSession::create(pool, &token_hash(&token), user_id, expires_at).await?;
+    Ok(token)
+}
+
+/// Swap the session's token for a new one that lasts another `session_hours`, but no longer
+/// than `max_session_days` after sign-in. `None` once the session has expired or reached
+/// that limit; the user signs in again then.
+pub async fn refresh_session(
+    pool: &DbPool,
+    token: &str,
+    session_hours: u32,
+    max_session_days: u32,
+) -> Result<Option<String>, sqlx::Error> {
+    let now = Utc::now();
+    let hash = token_hash(token);
+    let Some(signed_in_at) = UserSession::find_signed_in_at(pool, &hash, now).await? else {
+        return Ok(None);
+    };
+    let limit = signed_in_at + Duration::days(i64::from(max_session_days.max(1)));
+    if limit <= now {
+        return Ok(None);
+    }
+    let expires_at = (now + Duration::hours(i64::from(session_hours.max(1)))).min(limit);
+    let new_token = random_token();
+    if !UserSession::rotate(pool, &hash, &token_hash(&new_token), expires_at).await? {
+        return Ok(None);
+    }
+    Ok(Some(new_token))
+}
+
+pub async fn end_session(pool: &DbPool, token: &str) -> Result<(), sqlx::Error> {
+    UserSession::delete(pool, &token_hash(token)).await
+}
+
+/// The session token a request carries, as a bearer token or in the session cookie
+pub fn session_token(headers: &HeaderMap) -> Option<String> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if let Some(token) = bearer {
+        return Some(token.trim().to_string());
+    }
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|cookies| cookies.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(name, _)| *name == SESSION_COOKIE)
+        .map(|(_, token)| token.to_string())
+}
+
+/// The account signed in on the request, if it carries a session that hasn't expired
+pub async fn current_user(pool: &DbPool, headers: &HeaderMap) -> Result<Option<User>, sqlx::Error> {
+    let Some(token) = session_token(headers) else {
+        return Ok(None);
+    };
+    UserSession::find_user(pool, &token_hash(&token), Utc::now()).await
+}
+
+/// Whether a bearer token is a personal access token rather than a session
+pub fn is_api_token(token: &str) -> bool {
+    token.starts_with(API_TOKEN_PREFIX)
+}
+
+pub async fn create_api_token(
+    pool: &DbPool,
+    user: &User,
+    data: &CreateApiToken,
+) -> Result<CreatedApiToken, sqlx::Error> {
+    let secret = format!("{}{}", API_TOKEN_PREFIX, random_token());
+    let expires_at = data
+        .expires_in_days
+        .map(|days| Utc::now() + Duration::days(i64::from(days.max(1))));
+    let token = ApiToken::create(
+        pool,
+        user.id,
+        data,
+        &token_hash(&secret),
+        &secret[..API_TOKEN_SHOWN_LENGTH],
+        expires_at,
+    )
+    .await?;
+    Ok(CreatedApiToken { token, secret })
+}
+
+/// Replace the token with a new secret of the same name and scopes, lasting as long as the
+/// old one was meant to, and revoke the old one
+pub async fn rotate_api_token(
+    pool: &DbPool,
+    token: &ApiToken,
+) -> Result<CreatedApiToken, sqlx::Error> {
+    let secret = format!("{}{}", API_TOKEN_PREFIX, random_token());
+    let expires_at = token
+        .expires_at
+        .map(|expires_at| Utc::now() + (expires_at - token.created_at));
+    let data = CreateApiToken {
+        name: token.name.clone(),
+        scopes: token.scopes.clone(),
+        expires_in_days: None,
+    };
+    let rotated = ApiToken::create(
+        pool,
+        token.user_id,
+        &data,
+        &token_hash(&secret),
+        &secret[..API_TOKEN_SHOWN_LENGTH],
+        expires_at,
+    )
+    .await?;
+    ApiToken::revoke(pool, token.id).await?;
+    Ok(CreatedApiToken {
+        token: rotated,
+        secret,
+    })
+}
+
+/// The personal access token and the account it acts as, unless the token was revoked or
+/// has expired
+pub async fn api_token_user(
+    pool: &DbPool,
+    token: &str,
+) -> Result<Option<(ApiToken, User)>, sqlx::Error> {
+    let Some(token) = ApiToken::find_active_by_hash(pool, &token_hash(token), Utc::now()).await?
+    else {
+        return Ok(None);
+    };
+    Ok(User::find_by_id(pool, token.user_id)
+        .await?
+        .map(|user| (token, user)))
+}
+
+/// `Set-Cookie` value that keeps the session in the browser
+pub fn session_cookie(token: &str, session_hours: u32) -> String {
+    format!(
+        "{}={}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}",
+        SESSION_COOKIE,
+        token,
+        u64::from(session_hours.max(1)) * 3600
+    )
+}
+
+/// `Set-Cookie` value that removes the session from the browser
+pub fn cleared_session_cookie() -> String {
+    format!(
+        "{}=; Path=/; HttpOnly; SameSite=Lax; Max-Age=0",
+        SESSION_COOKIE
+    )
+}
+
+/// What the user may do in the project: anything for admins, otherwise what their
+/// membership allows, and nothing without one
+pub async fn project_role(
+    pool: &DbPool,
+    user: &User,
+    project_id: Uuid,
+) -> Result<Option<ProjectRole>, sqlx::Error> {
+    if user.is_admin {
+        return Ok(Some(ProjectRole::Admin));
+    }
+    ProjectMember::find_role(pool, project_id, user.id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn test_passwords_verify_against_their_hash_only() {
+        let stored = hash_with_iterations("correct horse", NonZeroU32::new(1_000).unwrap());
+        assert!(stored.starts_with("pbkdf2-sha256$1000$"));
+        assert!(verify_password("correct horse", &stored));
+        assert!(!verify_password("correct horsE", &stored));
+        assert!(!verify_password("correct horse", "plaintext"));
+        assert_ne!(
+            stored,
+            hash_with_iterations("correct horse", NonZeroU32::new(1_000).unwrap()),
+            "salted"
+        );
+    }
+
+    #[test]
+    fn test_session_token_comes_from_bearer_or_cookie() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(session_token(&headers), None);
+
+        headers.insert(
+            header::COOKIE,
+            HeaderValue::from_static("theme=dark; vk_session=abc123"),
+        );
+        assert_eq!(session_token(&headers).as_deref(), Some("abc123"));
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer def456"),
+        );
+        assert_eq!(session_token(&headers).as_deref(), Some("def456"));
+    }
+
+    #[test]
+    fn test_usernames_and_passwords_are_validated() {
+        assert!(validate_username("ada.lovelace@example.com").is_ok());
+        assert!(validate_username("").is_err());
+        assert!(validate_username("with space").is_err());
+        assert!(validate_password("short").is_err());
+        assert!(validate_password("long enough").is_ok());
+    }
+}
@@ -0,0 +1,150 @@
+//! Carrying a run of an agent that speaks the Agent Client Protocol through its turn: the
+//! prompt is sent once the agent has set up the session, the agent's requests to run tool
+//! calls are answered (the dangerous ones once the user has, as in [`command_approval`]),
+//! and the agent's stdin is closed when the turn is over so it exits.
+
+use std::time::Duration;
+
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    command_runner,
+    executors::acp::{self, AgentMessage},
+    models::execution_process::ExecutionProcess,
+    services::command_approval,
+};
+
+/// How often the output is checked without being told it changed, for output written before
+/// the session subscribed to it
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Answer what the agent of a running ACP execution writes until its turn is over. Ends with
+/// the execution.
+pub async fn drive(app_state: AppState, execution_process_id: Uuid, task_id: Uuid) {
+    let Some(turn) = acp::take_pending_turn(execution_process_id) else {
+        return;
+    };
+    let Some(mut output) = command_runner::subscribe_output(execution_process_id) else {
+        return;
+    };
+    let mut prompt = Some(turn.prompt);
+    // Bytes of stdout acted on so far, always up to the end of a line
+    let mut handled = 0;
+
+    loop {
+        // The agent waits on the request held for the user, and only one is held at a time
+        if app_state
+            .pending_approval(execution_process_id)
+            .await
+            .is_none()
+        {
+            let stdout = match ExecutionProcess::find_by_id(
+                &app_state.db_pool,
+                execution_process_id,
+            )
+            .await
+            {
+                Ok(Some(process)) => process.stdout.unwrap_or_default(),
+                Ok(None) => return,
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to load execution {} to answer its agent: {}",
+                        execution_process_id,
+                        e
+                    );
+                    String::new()
+                }
+            };
+            let end = stdout.rfind('\n').map_or(0, |newline| newline + 1);
+            let lines = stdout.get(handled..end).unwrap_or_default();
+            for line in lines.split_inclusive('\n') {
+                handled += line.len();
+                let Some(message) = acp::parse_agent_message(line) else {
+                    continue;
+                };
+                let reply = match message {
+                    AgentMessage::SessionReady { session_id } => {
+                        match (
+                            session_id.or_else(|| turn.session_id.clone()),
+                            prompt.take(),
+                        ) {
+                            (Some(session_id), Some(prompt)) => {
+                                acp::prompt_request(&session_id, &prompt)
+                            }
+                            _ => {
+                                tracing::warn!(
+                                    "The agent of execution {} set up a session without an id",
+                                    execution_process_id
+                                );
+                                close_stdin(&app_state, execution_process_id).await;
+                                return;
+                            }
+                        }
+                    }
+                    AgentMessage::Finished => {
+                        close_stdin(&app_state, execution_process_id).await;
+                        return;
+                    }
+                    AgentMessage::PermissionRequest { command, replies } => {
+                        match command_approval::dangerous_reason(&command) {
+                            Some(reason) => {
+                                command_approval::request_approval(
+                                    &app_state,
+                                    execution_process_id,
+                                    task_id,
+                                    command,
+                                    reason,
+                                    Some(replies),
+                                )
+                                .await;
+                                break;
+                            }
+                            None => replies.allow,
+                        }
+                    }
+                    AgentMessage::Unsupported { reply } => reply,
+                };
+                match app_state
+                    .write_running_execution_stdin(execution_process_id, &reply)
+                    .await
+                {
+                    Ok(true) => {}
+                    Ok(false) => return,
+                    Err(e) => {
+                        tracing::warn!(
+                            "Failed to answer the agent of execution {}: {}",
+                            execution_process_id,
+                            e
+                        );
+                        return;
+                    }
+                }
+            }
+        }
+
+        tokio::select! {
+            result = output.recv() => {
+                if let Err(broadcast::error::RecvError::Closed) = result {
+                    return;
+                }
+            }
+            () = tokio::time::sleep(IDLE_CHECK_INTERVAL) => {}
+        }
+    }
+}
+
+/// Tell the agent no more is coming, which ends it
+async fn close_stdin(app_state: &AppState, execution_process_id: Uuid) {
+    if let Err(e) = app_state
+        .close_running_execution_stdin(execution_process_id)
+        .await
+    {
+        tracing::warn!(
+            "Failed to close the input of execution {}: {}",
+            execution_process_id,
+            e
+        );
+    }
+}
@@ -0,0 +1,324 @@
+//! Executions waiting on the user: a dangerous command held for approval, or a plan waiting
+//! to be reviewed. Each one is recorded as open attention and announced through the channels
+//! the user picked (desktop, the project's webhooks, email). If nobody answers within the
+//! configured time it is announced once more through the escalation channels.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use lettre::{
+    message::Mailbox, transport::smtp::authentication::Credentials, AsyncSmtpTransport,
+    AsyncTransport, Message, Tokio1Executor,
+};
+use tokio::time::interval;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    models::{
+        attempt_plan::{AttemptPlan, AttemptPlanStatus},
+        config::{AttentionChannels, Config, SmtpConfig, SmtpSecurity},
+        execution_attention::{AttentionKind, ExecutionAttention},
+        notification_delivery::NotificationDelivery,
+        task::Task,
+        task_attempt::TaskAttempt,
+    },
+    services::{notification_webhooks, NotificationConfig, NotificationEvent, NotificationService},
+};
+
+const SMTP_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Record that the execution is waiting on the user and announce it
+pub async fn request(
+    app_state: &AppState,
+    execution_process_id: Uuid,
+    task: &Task,
+    task_attempt_id: Uuid,
+    kind: AttentionKind,
+    title: &str,
+    message: &str,
+) {
+    if let Err(e) = ExecutionAttention::create(
+        &app_state.db_pool,
+        execution_process_id,
+        task_attempt_id,
+        kind,
+        title,
+        message,
+    )
+    .await
+    {
+        error!(
+            "Failed to record that execution {} needs attention: {}",
+            execution_process_id, e
+        );
+    }
+
+    let config = app_state.get_config().read().await.clone();
+    announce(
+        app_state,
+        &config,
+        &config.attention.notify,
+        task.project_id,
+        task_attempt_id,
+        title,
+        message,
+    )
+    .await;
+}
+
+/// Close what the attempt was waiting on the user for, now that they've answered
+pub async fn resolve(app_state: &AppState, task_attempt_id: Uuid, kind: AttentionKind) {
+    if let Err(e) =
+        ExecutionAttention::resolve_for_task_attempt(&app_state.db_pool, task_attempt_id, kind)
+            .await
+    {
+        error!(
+            "Failed to resolve the attention of attempt {}: {}",
+            task_attempt_id, e
+        );
+    }
+}
+
+async fn announce(
+    app_state: &AppState,
+    config: &Config,
+    channels: &AttentionChannels,
+    project_id: Uuid,
+    task_attempt_id: Uuid,
+    title: &str,
+    message: &str,
+) {
+    let event = NotificationEvent::ApprovalNeeded;
+    if channels.desktop
+        && (config.sound_alerts || config.push_notifications)
+        && event.is_enabled(&config.notification_events)
+    {
+        NotificationService::new(NotificationConfig {
+            sound_enabled: config.sound_alerts,
+            push_enabled: config.push_notifications,
+        })
+        .notify(title, message, &config.sound_file)
+        .await;
+
+        if let Err(e) = NotificationDelivery::record_desktop(
+            &app_state.db_pool,
+            project_id,
+            Some(task_attempt_id),
+            event,
+            title,
+            message,
+        )
+        .await
+        {
+            error!(
+                "Failed to log notification for attempt {}: {}",
+                task_attempt_id, e
+            );
+        }
+    }
+
+    if channels.webhook {
+        if let Err(e) = notification_webhooks::queue(
+            &app_state.db_pool,
+            project_id,
+            Some(task_attempt_id),
+            event,
+            title,
+            message,
+        )
+        .await
+        {
+            error!(
+                "Failed to queue webhook notifications for attempt {}: {}",
+                task_attempt_id, e
+            );
+        }
+    }
+
+    if channels.email {
+        if config.attention.smtp.host.is_empty() {
+            warn!(
+                "Not emailing that attempt {} needs attention: no SMTP server is set up",
+                task_attempt_id
+            );
+        } else if let Err(e) = send_email(&config.attention.smtp, title, message).await {
+            error!(
+                "Failed to email that attempt {} needs attention: {}",
+                task_attempt_id, e
+            );
+        }
+    }
+}
+
+async fn send_email(smtp: &SmtpConfig, subject: &str, body: &str) -> Result<(), String> {
+    let from: Mailbox = smtp
+        .from
+        .parse()
+        .map_err(|e| format!("Invalid sender '{}': {}", smtp.from, e))?;
+    let mut builder = Message::builder().from(from).subject(subject);
+    for to in &smtp.to {
+        let to: Mailbox = to
+            .parse()
+            .map_err(|e| format!("Invalid recipient '{}': {}", to, e))?;
+        builder = builder.to(to);
+    }
+    if smtp.to.is_empty() {
+        return Err("No recipients are set up".to_string());
+    }
+    let email = builder.body(body.to_string()).map_err(|e| e.to_string())?;
+
+    let mut transport = match smtp.security {
+        SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&smtp.host),
+        SmtpSecurity::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&smtp.host),
+        SmtpSecurity::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+            &smtp.host,
+        )),
+    }
+    .map_err(|e| e.to_string())?
+    .port(smtp.port)
+    .timeout(Some(SMTP_TIMEOUT));
+    if let (Some(username), Some(password)) = (&smtp.username, &smtp.password) {
+        transport = transport.credentials(Credentials::new(username.clone(), password.clone()));
+    }
+
+    transport
+        .build()
+        .send(email)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// The announcement sent again for attention nobody answered
+pub fn escalation_message(attention: &ExecutionAttention, now: DateTime<Utc>) -> (String, String) {
+    let minutes = (now - attention.requested_at).num_minutes();
+    (
+        format!("Still Waiting: {}", attention.title),
+        format!(
+            "{}\nNobody has answered for {} minute{}",
+            attention.message,
+            minutes,
+            if minutes == 1 { "" } else { "s" }
+        ),
+    )
+}
+
+pub struct AttentionEscalationService {
+    app_state: AppState,
+    poll_interval: Duration,
+}
+
+impl AttentionEscalationService {
+    pub fn new(app_state: AppState) -> Self {
+        Self {
+            app_state,
+            poll_interval: Duration::from_secs(60),
+        }
+    }
+
+    pub async fn start(&self) {
+        info!(
+            "Starting attention escalation with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.escalate_due(Utc::now()).await {
+                error!("Error escalating executions waiting on the user: {}", e);
+            }
+        }
+    }
+
+    /// Announce again, through the escalation channels, every open attention that has waited
+    /// too long at `now`, returning how many were. Attention nobody needs to answer anymore is
+    /// closed instead.
+    pub async fn escalate_due(&self, now: DateTime<Utc>) -> Result<usize, sqlx::Error> {
+        let pool = &self.app_state.db_pool;
+        let config = self.app_state.get_config().read().await.clone();
+        let Some(minutes) = config.attention.escalate_after_minutes else {
+            return Ok(0);
+        };
+        let requested_before = now - chrono::Duration::minutes(i64::from(minutes));
+
+        let mut escalated = 0;
+        for attention in ExecutionAttention::find_unescalated(pool, requested_before).await? {
+            if !self.still_waiting(&attention).await? {
+                ExecutionAttention::resolve(pool, attention.id).await?;
+                continue;
+            }
+            let Some(task_attempt) =
+                TaskAttempt::find_by_id(pool, attention.task_attempt_id).await?
+            else {
+                continue;
+            };
+            let Some(task) = Task::find_by_id(pool, task_attempt.task_id).await? else {
+                continue;
+            };
+
+            let (title, message) = escalation_message(&attention, now);
+            announce(
+                &self.app_state,
+                &config,
+                &config.attention.escalate_to,
+                task.project_id,
+                task_attempt.id,
+                &title,
+                &message,
+            )
+            .await;
+            ExecutionAttention::mark_escalated(pool, attention.id).await?;
+            escalated += 1;
+        }
+        Ok(escalated)
+    }
+
+    /// Whether the execution is still waiting on what the attention was opened for, which it
+    /// isn't once the command was answered, the execution ended or the plan was decided
+    async fn still_waiting(&self, attention: &ExecutionAttention) -> Result<bool, sqlx::Error> {
+        match attention.kind {
+            AttentionKind::CommandApproval => Ok(self
+                .app_state
+                .pending_approval(attention.execution_process_id)
+                .await
+                .is_some()),
+            AttentionKind::PlanReview => Ok(AttemptPlan::find_by_task_attempt_id(
+                &self.app_state.db_pool,
+                attention.task_attempt_id,
+            )
+            .await?
+            .is_some_and(|plan| plan.status == AttemptPlanStatus::Pending)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escalation_message_says_how_long_it_waited() {
+        let requested_at = Utc::now();
+        let attention = ExecutionAttention {
+            id: Uuid::new_v4(),
+            execution_process_id: Uuid::new_v4(),
+            task_attempt_id: Uuid::new_v4(),
+            kind: AttentionKind::CommandApproval,
+            title: "Approval Needed: Fix login".to_string(),
+            message: "⚠️ 'Fix login' wants to run `sudo make install`, which runs as root"
+                .to_string(),
+            requested_at,
+            escalated_at: None,
+            resolved_at: None,
+        };
+
+        let (title, message) =
+            escalation_message(&attention, requested_at + chrono::Duration::minutes(20));
+        assert_eq!(title, "Still Waiting: Approval Needed: Fix login");
+        assert!(message.starts_with(&attention.message));
+        assert!(message.ends_with("Nobody has answered for 20 minutes"));
+    }
+}
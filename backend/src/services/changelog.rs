@@ -0,0 +1,471 @@
+//! Release notes compiled from the board: every task whose attempt was merged in a window,
+//! grouped by its labels into changelog sections.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    models::{
+        project::Project,
+        task_attempt::{MergedAttempt, TaskAttempt},
+        task_label::TaskLabels,
+    },
+    services::{GitService, GitServiceError},
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum ChangelogFormat {
+    /// Headings named after the kind of change
+    #[default]
+    Markdown,
+    /// https://keepachangelog.com sections under a version heading
+    KeepAChangelog,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[ts(export)]
+pub struct ChangelogQuery {
+    /// RFC 3339 timestamp or `YYYY-MM-DD`, inclusive
+    pub from: Option<String>,
+    /// RFC 3339 timestamp or `YYYY-MM-DD`, inclusive
+    pub to: Option<String>,
+    /// Only tasks merged after this tag
+    pub since_tag: Option<String>,
+    /// Heading for the keep-a-changelog format; "Unreleased" when omitted
+    pub version: Option<String>,
+    #[serde(default)]
+    pub format: ChangelogFormat,
+}
+
+/// Keep-a-changelog's sections, in the order it lists them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum ChangelogSection {
+    Added,
+    Changed,
+    Deprecated,
+    Removed,
+    Fixed,
+    Security,
+}
+
+/// Labels that put a task in a section, most specific first
+const SECTION_LABELS: &[(ChangelogSection, &[&str])] = &[
+    (ChangelogSection::Security, &["security"]),
+    (ChangelogSection::Fixed, &["bug", "fix", "bugfix"]),
+    (ChangelogSection::Removed, &["removal", "removed"]),
+    (ChangelogSection::Deprecated, &["deprecation", "deprecated"]),
+    (ChangelogSection::Added, &["feature", "enhancement"]),
+];
+
+impl ChangelogSection {
+    fn for_labels(labels: &[String]) -> Self {
+        SECTION_LABELS
+            .iter()
+            .find(|(_, names)| labels.iter().any(|l| names.contains(&l.as_str())))
+            .map(|(section, _)| *section)
+            .unwrap_or(ChangelogSection::Changed)
+    }
+
+    fn keep_a_changelog_heading(&self) -> &'static str {
+        match self {
+            ChangelogSection::Added => "Added",
+            ChangelogSection::Changed => "Changed",
+            ChangelogSection::Deprecated => "Deprecated",
+            ChangelogSection::Removed => "Removed",
+            ChangelogSection::Fixed => "Fixed",
+            ChangelogSection::Security => "Security",
+        }
+    }
+
+    fn markdown_heading(&self) -> &'static str {
+        match self {
+            ChangelogSection::Added => "Features",
+            ChangelogSection::Changed => "Other changes",
+            ChangelogSection::Deprecated => "Deprecations",
+            ChangelogSection::Removed => "Removals",
+            ChangelogSection::Fixed => "Bug fixes",
+            ChangelogSection::Security => "Security",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ChangelogEntry {
+    pub task_id: Uuid,
+    pub title: String,
+    pub labels: Vec<String>,
+    pub section: ChangelogSection,
+    pub merged_at: DateTime<Utc>,
+    pub pr_url: Option<String>,
+    pub pr_number: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct Changelog {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub entries: Vec<ChangelogEntry>,
+    /// The entries rendered in the requested format
+    pub content: String,
+}
+
+#[derive(Debug)]
+pub enum ChangelogError {
+    InvalidDate(String),
+    Git(GitServiceError),
+    Database(sqlx::Error),
+}
+
+impl std::fmt::Display for ChangelogError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangelogError::InvalidDate(value) => write!(
+                f,
+                "Invalid date '{}', expected YYYY-MM-DD or an RFC 3339 timestamp",
+                value
+            ),
+            ChangelogError::Git(e) => write!(f, "{}", e),
+            ChangelogError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ChangelogError {}
+
+impl From<GitServiceError> for ChangelogError {
+    fn from(err: GitServiceError) -> Self {
+        ChangelogError::Git(err)
+    }
+}
+
+impl From<sqlx::Error> for ChangelogError {
+    fn from(err: sqlx::Error) -> Self {
+        ChangelogError::Database(err)
+    }
+}
+
+pub async fn compile(
+    pool: &DbPool,
+    project: &Project,
+    query: &ChangelogQuery,
+) -> Result<Changelog, ChangelogError> {
+    let to = query
+        .to
+        .as_deref()
+        .map(|v| parse_bound(v, true))
+        .transpose()?;
+    let requested_from = query
+        .from
+        .as_deref()
+        .map(|v| parse_bound(v, false))
+        .transpose()?;
+
+    let git = GitService::new(&project.git_repo_path)?;
+    let tag = match query.since_tag.as_deref().map(str::trim) {
+        Some(tag) if !tag.is_empty() => Some(git.resolve_tag(tag)?),
+        _ => None,
+    };
+    // Without the merge commit at hand, fall back on the tag's date
+    let from = match (&tag, requested_from) {
+        (Some((_, tagged_at)), Some(from)) => Some(from.max(*tagged_at)),
+        (Some((_, tagged_at)), None) => Some(*tagged_at),
+        (None, from) => from,
+    };
+    let not_before = |bound: Option<DateTime<Utc>>, time: DateTime<Utc>| match bound {
+        Some(bound) => bound <= time,
+        None => true,
+    };
+
+    let mut labels = TaskLabels::find_labels_by_project_id(pool, project.id).await?;
+    // A task merged through several attempts appears once, at its latest merge
+    let mut latest: HashMap<Uuid, (MergedAttempt, DateTime<Utc>)> = HashMap::new();
+    for attempt in TaskAttempt::find_merged_by_project_id(pool, project.id).await? {
+        let merged_at = attempt
+            .pr_merged_at
+            .or_else(|| {
+                attempt
+                    .merge_commit
+                    .as_deref()
+                    .and_then(|c| git.commit_time(c))
+            })
+            .unwrap_or(attempt.updated_at);
+
+        let after_start = match (&tag, attempt.merge_commit.as_deref()) {
+            (Some((tag_commit, _)), Some(merge_commit)) => {
+                match git.contains_commit(tag_commit, merge_commit) {
+                    Some(in_tag) => !in_tag && not_before(requested_from, merged_at),
+                    None => not_before(from, merged_at),
+                }
+            }
+            _ => not_before(from, merged_at),
+        };
+        let before_end = match to {
+            Some(to) => merged_at <= to,
+            None => true,
+        };
+        if !(after_start && before_end) {
+            continue;
+        }
+        match latest.get(&attempt.task_id) {
+            Some((_, seen)) if *seen >= merged_at => {}
+            _ => {
+                latest.insert(attempt.task_id, (attempt, merged_at));
+            }
+        }
+    }
+
+    let mut entries: Vec<ChangelogEntry> = latest
+        .into_values()
+        .map(|(attempt, merged_at)| {
+            let task_labels = labels.remove(&attempt.task_id).unwrap_or_default();
+            ChangelogEntry {
+                task_id: attempt.task_id,
+                title: attempt.task_title,
+                section: ChangelogSection::for_labels(&task_labels),
+                labels: task_labels,
+                merged_at,
+                pr_url: attempt.pr_url,
+                pr_number: attempt.pr_number,
+            }
+        })
+        .collect();
+    entries.sort_by_key(|entry| entry.merged_at);
+
+    let content = match query.format {
+        ChangelogFormat::Markdown => render_markdown(&entries, from, to),
+        ChangelogFormat::KeepAChangelog => {
+            render_keep_a_changelog(&entries, query.version.as_deref(), to)
+        }
+    };
+    Ok(Changelog {
+        from,
+        to,
+        entries,
+        content,
+    })
+}
+
+/// A bare date covers the whole day, so `to` moves to its last second
+fn parse_bound(value: &str, end_of_day: bool) -> Result<DateTime<Utc>, ChangelogError> {
+    let value = value.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| ChangelogError::InvalidDate(value.to_string()))?;
+    let time = if end_of_day {
+        date.and_hms_opt(23, 59, 59)
+    } else {
+        date.and_hms_opt(0, 0, 0)
+    };
+    time.map(|t| t.and_utc())
+        .ok_or_else(|| ChangelogError::InvalidDate(value.to_string()))
+}
+
+fn entry_line(entry: &ChangelogEntry) -> String {
+    match (&entry.pr_url, entry.pr_number) {
+        (Some(url), Some(number)) => format!("- {} ([#{}]({}))", entry.title, number, url),
+        (Some(url), None) => format!("- {} ({})", entry.title, url),
+        _ => format!("- {}", entry.title),
+    }
+}
+
+fn sections(entries: &[ChangelogEntry]) -> Vec<(ChangelogSection, Vec<&ChangelogEntry>)> {
+    let mut grouped: Vec<(ChangelogSection, Vec<&ChangelogEntry>)> = Vec::new();
+    for entry in entries {
+        match grouped.iter_mut().find(|(s, _)| *s == entry.section) {
+            Some((_, group)) => group.push(entry),
+            None => grouped.push((entry.section, vec![entry])),
+        }
+    }
+    grouped.sort_by_key(|(section, _)| *section);
+    grouped
+}
+
+fn render_markdown(
+    entries: &[ChangelogEntry],
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> String {
+    let mut out = String::from("# Changelog\n");
+    let range = match (from, to) {
+        (Some(from), Some(to)) => Some(format!("{} to {}", day(from), day(to))),
+        (Some(from), None) => Some(format!("Since {}", day(from))),
+        (None, Some(to)) => Some(format!("Up to {}", day(to))),
+        (None, None) => None,
+    };
+    if let Some(range) = range {
+        out.push_str(&format!("\n_{}_\n", range));
+    }
+    if entries.is_empty() {
+        out.push_str("\nNo merged tasks.\n");
+        return out;
+    }
+    // Markdown readers care about features first and the catch-all last
+    let mut grouped = sections(entries);
+    grouped.sort_by_key(|(section, _)| match section {
+        ChangelogSection::Added => 0,
+        ChangelogSection::Fixed => 1,
+        ChangelogSection::Security => 2,
+        ChangelogSection::Deprecated => 3,
+        ChangelogSection::Removed => 4,
+        ChangelogSection::Changed => 5,
+    });
+    for (section, group) in grouped {
+        out.push_str(&format!("\n## {}\n\n", section.markdown_heading()));
+        for entry in group {
+            out.push_str(&entry_line(entry));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn render_keep_a_changelog(
+    entries: &[ChangelogEntry],
+    version: Option<&str>,
+    to: Option<DateTime<Utc>>,
+) -> String {
+    let version = version
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .unwrap_or("Unreleased");
+    let mut out = match (version, to) {
+        ("Unreleased", _) => "## [Unreleased]\n".to_string(),
+        (version, Some(to)) => format!("## [{}] - {}\n", version, day(to)),
+        (version, None) => format!("## [{}] - {}\n", version, day(Utc::now())),
+    };
+    for (section, group) in sections(entries) {
+        out.push_str(&format!("\n### {}\n\n", section.keep_a_changelog_heading()));
+        for entry in group {
+            out.push_str(&entry_line(entry));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn day(time: DateTime<Utc>) -> String {
+    time.format("%Y-%m-%d").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(title: &str, labels: &[&str], pr: Option<i64>) -> ChangelogEntry {
+        let labels: Vec<String> = labels.iter().map(|l| l.to_string()).collect();
+        ChangelogEntry {
+            task_id: Uuid::new_v4(),
+            title: title.to_string(),
+            section: ChangelogSection::for_labels(&labels),
+            labels,
+            merged_at: Utc::now(),
+            pr_url: pr.map(|n| format!("https://github.com/o/r/pull/{}", n)),
+            pr_number: pr,
+        }
+    }
+
+    fn entries() -> Vec<ChangelogEntry> {
+        vec![
+            entry("Tidy config loading", &["refactor"], None),
+            entry("Fix session panic", &["area:backend", "bug"], Some(12)),
+            entry("Add dark mode", &["feature"], None),
+        ]
+    }
+
+    #[test]
+    fn test_sections_from_labels() {
+        let sections: Vec<ChangelogSection> = entries().iter().map(|e| e.section).collect();
+        assert_eq!(
+            sections,
+            vec![
+                ChangelogSection::Changed,
+                ChangelogSection::Fixed,
+                ChangelogSection::Added
+            ]
+        );
+        // A security fix is listed under security, not fixes
+        assert_eq!(
+            ChangelogSection::for_labels(&["bug".to_string(), "security".to_string()]),
+            ChangelogSection::Security
+        );
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let from = parse_bound("2025-07-01", false).unwrap();
+        let to = parse_bound("2025-07-31", true).unwrap();
+        assert_eq!(
+            render_markdown(&entries(), Some(from), Some(to)),
+            "# Changelog
+
+_2025-07-01 to 2025-07-31_
+
+## Features
+
+- Add dark mode
+
+## Bug fixes
+
+- Fix session panic ([#12](https://github.com/o/r/pull/12))
+
+## Other changes
+
+- Tidy config loading
+"
+        );
+    }
+
+    #[test]
+    fn test_render_keep_a_changelog() {
+        let to = parse_bound("2025-07-31", true).unwrap();
+        assert_eq!(
+            render_keep_a_changelog(&entries(), Some("1.4.0"), Some(to)),
+            "## [1.4.0] - 2025-07-31
+
+### Added
+
+- Add dark mode
+
+### Changed
+
+- Tidy config loading
+
+### Fixed
+
+- Fix session panic ([#12](https://github.com/o/r/pull/12))
+"
+        );
+        assert!(render_keep_a_changelog(&[], None, None).starts_with("## [Unreleased]\n"));
+    }
+
+    #[test]
+    fn test_parse_bound() {
+        assert_eq!(
+            parse_bound("2025-07-31", true).unwrap().to_rfc3339(),
+            "2025-07-31T23:59:59+00:00"
+        );
+        assert_eq!(
+            parse_bound("2025-07-31T10:00:00+02:00", true)
+                .unwrap()
+                .to_rfc3339(),
+            "2025-07-31T08:00:00+00:00"
+        );
+        assert!(matches!(
+            parse_bound("last week", false),
+            Err(ChangelogError::InvalidDate(_))
+        ));
+    }
+}
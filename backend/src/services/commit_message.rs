@@ -0,0 +1,267 @@
+//! Messages for the commits vibe-kanban makes itself in attempt worktrees: the changes an agent
+//! left uncommitted when its run ended, and whatever the cleanup script changed. Projects can
+//! override the layout with their own template.
+//!
+//! Placeholders: `{{task_title}}`, `{{task_description}}`, `{{summary}}`, `{{changes}}`,
+//! `{{stats}}`, `{{source}}`, `{{task_id}}` and `{{attempt_id}}`.
+
+use git2::{Delta, Repository, Tree};
+use uuid::Uuid;
+
+use crate::{models::task_attempt::FileChangeKind, utils::text::fill_placeholders};
+
+pub const DEFAULT_TEMPLATE: &str = "{{task_title}}
+
+{{summary}}
+
+{{stats}}
+
+{{changes}}
+
+Task: {{task_id}}";
+
+const MAX_SUBJECT_CHARS: usize = 72;
+const MAX_SUMMARY_CHARS: usize = 1500;
+const MAX_LISTED_FILES: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitSource {
+    CodingAgent,
+    CleanupScript,
+}
+
+impl CommitSource {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            CommitSource::CodingAgent => "coding agent",
+            CommitSource::CleanupScript => "cleanup script",
+        }
+    }
+}
+
+/// What the commit is for; built before the changes are staged
+#[derive(Debug, Clone)]
+pub struct CommitMessageContext {
+    pub task_id: Uuid,
+    pub attempt_id: Uuid,
+    pub source: CommitSource,
+    pub task_title: Option<String>,
+    pub task_description: Option<String>,
+    /// The agent's closing message, if it left one
+    pub summary: Option<String>,
+    pub template: Option<String>,
+}
+
+/// The staged changes a commit is about to record
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StagedChanges {
+    pub files: Vec<(String, FileChangeKind)>,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+}
+
+impl StagedChanges {
+    pub fn between(repo: &Repository, parent: &Tree, staged: &Tree) -> Result<Self, git2::Error> {
+        let diff = repo.diff_tree_to_tree(Some(parent), Some(staged), None)?;
+        let stats = diff.stats()?;
+        let mut files = Vec::new();
+        for delta in diff.deltas() {
+            let change = match delta.status() {
+                Delta::Added | Delta::Copied | Delta::Renamed => FileChangeKind::Added,
+                Delta::Deleted => FileChangeKind::Deleted,
+                Delta::Modified | Delta::Typechange => FileChangeKind::Modified,
+                _ => continue,
+            };
+            if let Some(path) = delta.new_file().path().or(delta.old_file().path()) {
+                files.push((path.to_string_lossy().to_string(), change));
+            }
+        }
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(Self {
+            files,
+            lines_added: stats.insertions(),
+            lines_removed: stats.deletions(),
+        })
+    }
+}
+
+pub fn build(context: &CommitMessageContext, changes: &StagedChanges) -> String {
+    let template = context
+        .template
+        .as_deref()
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or(DEFAULT_TEMPLATE);
+
+    let summary = match context.source {
+        CommitSource::CodingAgent => context
+            .summary
+            .as_deref()
+            .map(|s| clip(s.trim(), MAX_SUMMARY_CHARS))
+            .unwrap_or_default(),
+        CommitSource::CleanupScript => "Changes made by the cleanup script.".to_string(),
+    };
+    let values = [
+        ("task_title", context.task_title.clone().unwrap_or_default()),
+        (
+            "task_description",
+            context.task_description.clone().unwrap_or_default(),
+        ),
+        ("summary", summary),
+        ("changes", changes_list(changes)),
+        ("stats", stats_line(changes)),
+        ("source", context.source.describe().to_string()),
+        ("task_id", context.task_id.to_string()),
+        ("attempt_id", context.attempt_id.to_string()),
+    ];
+
+    let message = tidy(&fill_placeholders(template, &values));
+    if message.is_empty() {
+        return format!(
+            "Task attempt {} - {} changes",
+            context.attempt_id,
+            context.source.describe()
+        );
+    }
+    message
+}
+
+fn stats_line(changes: &StagedChanges) -> String {
+    let files = changes.files.len();
+    format!(
+        "{} file{} changed, {} insertion{}(+), {} deletion{}(-)",
+        files,
+        if files == 1 { "" } else { "s" },
+        changes.lines_added,
+        if changes.lines_added == 1 { "" } else { "s" },
+        changes.lines_removed,
+        if changes.lines_removed == 1 { "" } else { "s" },
+    )
+}
+
+fn changes_list(changes: &StagedChanges) -> String {
+    let mut lines: Vec<String> = changes
+        .files
+        .iter()
+        .take(MAX_LISTED_FILES)
+        .map(|(path, change)| {
+            let marker = match change {
+                FileChangeKind::Added => 'A',
+                FileChangeKind::Modified => 'M',
+                FileChangeKind::Deleted => 'D',
+            };
+            format!("{} {}", marker, path)
+        })
+        .collect();
+    if changes.files.len() > MAX_LISTED_FILES {
+        lines.push(format!(
+            "… and {} more",
+            changes.files.len() - MAX_LISTED_FILES
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Keep a short subject line and no runs of blank lines from empty placeholders
+fn tidy(message: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for line in message.lines().map(str::trim_end) {
+        let previous_blank = match lines.last() {
+            Some(previous) => previous.is_empty(),
+            None => true,
+        };
+        if line.is_empty() && previous_blank {
+            continue;
+        }
+        lines.push(line.to_string());
+    }
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    if let Some(subject) = lines.first_mut() {
+        *subject = clip(subject.trim(), MAX_SUBJECT_CHARS);
+    }
+    lines.join("\n")
+}
+
+fn clip(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let clipped: String = text.chars().take(max_chars - 1).collect();
+    format!("{}…", clipped.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(source: CommitSource, template: Option<&str>) -> CommitMessageContext {
+        CommitMessageContext {
+            task_id: Uuid::nil(),
+            attempt_id: Uuid::nil(),
+            source,
+            task_title: Some("Fix session panic".to_string()),
+            task_description: None,
+            summary: Some("Handled a missing session store.\n".to_string()),
+            template: template.map(str::to_string),
+        }
+    }
+
+    fn changes() -> StagedChanges {
+        StagedChanges {
+            files: vec![
+                ("src/session.rs".to_string(), FileChangeKind::Modified),
+                ("tests/session.rs".to_string(), FileChangeKind::Added),
+            ],
+            lines_added: 12,
+            lines_removed: 1,
+        }
+    }
+
+    #[test]
+    fn test_default_message() {
+        assert_eq!(
+            build(&context(CommitSource::CodingAgent, None), &changes()),
+            format!(
+                "Fix session panic
+
+Handled a missing session store.
+
+2 files changed, 12 insertions(+), 1 deletion(-)
+
+M src/session.rs
+A tests/session.rs
+
+Task: {}",
+                Uuid::nil()
+            )
+        );
+    }
+
+    #[test]
+    fn test_project_template() {
+        let message = build(
+            &context(
+                CommitSource::CleanupScript,
+                Some("chore: {{source}} for \"{{task_title}}\"\n\n{{task_description}}\n\nAttempt: {{attempt_id}}"),
+            ),
+            &changes(),
+        );
+        assert_eq!(
+            message,
+            format!(
+                "chore: cleanup script for \"Fix session panic\"\n\nAttempt: {}",
+                Uuid::nil()
+            )
+        );
+    }
+
+    #[test]
+    fn test_long_subject_is_clipped() {
+        let mut context = context(CommitSource::CodingAgent, Some("{{task_title}}"));
+        context.task_title = Some("word ".repeat(30));
+        let message = build(&context, &StagedChanges::default());
+        assert_eq!(message.chars().count(), MAX_SUBJECT_CHARS);
+        assert!(message.ends_with('…'));
+    }
+}
@@ -0,0 +1,399 @@
+//! A task's coding agent conversations, across all its attempts, as Markdown for pasting
+//! into a pull request or as JSON for keeping outside vibe-kanban
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    executor::{ActionType, NormalizedConversation, NormalizedEntry, NormalizedEntryType},
+    models::{
+        execution_diff::ExecutionDiff,
+        execution_process::{ExecutionProcess, ExecutionProcessStatus, ExecutionProcessType},
+        task::Task,
+        task_attempt::{FileHunks, TaskAttempt},
+    },
+    routes::task_attempts::normalize_process_logs,
+    services::scoped_follow_up::unified_hunk,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum ConversationExportFormat {
+    #[default]
+    Markdown,
+    Json,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[ts(export)]
+pub struct ConversationExportQuery {
+    #[serde(default)]
+    pub format: ConversationExportFormat,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TaskConversations {
+    pub task_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub exported_at: DateTime<Utc>,
+    /// Oldest first
+    pub attempts: Vec<AttemptConversations>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AttemptConversations {
+    pub attempt_id: Uuid,
+    pub branch: String,
+    pub executor: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// The attempt's coding agent runs, its first run and then each follow-up
+    pub runs: Vec<RunConversation>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct RunConversation {
+    pub execution_process_id: Uuid,
+    pub status: ExecutionProcessStatus,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub conversation: NormalizedConversation,
+    /// What the run changed, if it finished with its changes captured
+    pub diff: Option<Vec<FileHunks>>,
+}
+
+/// Every coding agent conversation of the task, with what each run changed
+pub async fn export(pool: &DbPool, task: &Task) -> Result<TaskConversations, sqlx::Error> {
+    let mut attempts = Vec::new();
+    for attempt in TaskAttempt::find_by_task_id(pool, task.id)
+        .await?
+        .into_iter()
+        .rev()
+    {
+        let mut runs = Vec::new();
+        for process in ExecutionProcess::find_by_task_attempt_id(pool, attempt.id).await? {
+            if process.process_type != ExecutionProcessType::CodingAgent {
+                continue;
+            }
+            let diff = ExecutionDiff::find_by_execution_process_id(pool, process.id)
+                .await?
+                .filter(|diff| diff.to_commit.is_some())
+                .map(|diff| diff.files);
+            runs.push(RunConversation {
+                execution_process_id: process.id,
+                status: process.status.clone(),
+                started_at: process.started_at,
+                completed_at: process.completed_at,
+                conversation: normalize_process_logs(pool, &process).await,
+                diff,
+            });
+        }
+        attempts.push(AttemptConversations {
+            attempt_id: attempt.id,
+            branch: attempt.branch,
+            executor: attempt.executor,
+            created_at: attempt.created_at,
+            runs,
+        });
+    }
+
+    Ok(TaskConversations {
+        task_id: task.id,
+        title: task.title.clone(),
+        description: task.description.clone(),
+        exported_at: Utc::now(),
+        attempts,
+    })
+}
+
+/// A code fence longer than any run of backticks in `content`, so the content can't end it
+fn fence(content: &str) -> String {
+    let mut longest = 0;
+    let mut run = 0;
+    for c in content.chars() {
+        run = if c == '`' { run + 1 } else { 0 };
+        longest = longest.max(run);
+    }
+    "`".repeat(longest.max(2) + 1)
+}
+
+pub(crate) fn fenced(info: &str, content: &str) -> String {
+    let fence = fence(content);
+    format!(
+        "{}{}\n{}\n{}\n",
+        fence,
+        info,
+        content.trim_end_matches('\n'),
+        fence
+    )
+}
+
+fn action_line(tool_name: &str, action: &ActionType, content: &str) -> String {
+    let target = match action {
+        ActionType::FileRead { path, .. } | ActionType::FileWrite { path, .. } => {
+            format!("`{}`", path)
+        }
+        ActionType::CommandRun { command } => format!("`{}`", command),
+        ActionType::Search { query } => format!("`{}`", query),
+        ActionType::WebFetch { url } => url.clone(),
+        ActionType::TaskCreate { description } => description.clone(),
+        ActionType::PlanPresentation { .. } | ActionType::Other { .. } => content.to_string(),
+    };
+    format!("- **{}** {}\n", tool_name, target)
+}
+
+pub(crate) fn render_entry(entry: &NormalizedEntry) -> String {
+    match &entry.entry_type {
+        NormalizedEntryType::UserMessage => format!("**User**\n\n{}\n", entry.content),
+        NormalizedEntryType::AssistantMessage => format!("**Assistant**\n\n{}\n", entry.content),
+        NormalizedEntryType::ToolUse {
+            tool_name,
+            action_type: ActionType::PlanPresentation { plan },
+        } => format!("**{}**\n\n{}\n", tool_name, plan),
+        NormalizedEntryType::ToolUse {
+            tool_name,
+            action_type,
+        } => action_line(tool_name, action_type, &entry.content),
+        NormalizedEntryType::SystemMessage => format!("_{}_\n", entry.content.trim()),
+        NormalizedEntryType::ErrorMessage => format!("> **Error:** {}\n", entry.content),
+        NormalizedEntryType::Thinking => format!(
+            "<details><summary>Thinking</summary>\n\n{}\n\n</details>\n",
+            entry.content
+        ),
+        NormalizedEntryType::Attachment { .. } => format!("_Attached {}_\n", entry.content),
+        NormalizedEntryType::PlanEntry => format!("**Plan**\n\n{}\n", entry.content),
+        NormalizedEntryType::FileEdit { diff, .. } => format!("```diff\n{}```\n", diff),
+        NormalizedEntryType::Verification {
+            command,
+            passed,
+            exit_code,
+            duration_ms,
+        } => {
+            let result = match (passed, exit_code) {
+                (true, _) => "passed".to_string(),
+                (false, Some(code)) => format!("failed with exit code {}", code),
+                (false, None) => "didn't finish".to_string(),
+            };
+            let mut text = format!(
+                "**Verification** `{}` {} in {:.1}s\n",
+                command,
+                result,
+                f64::from(*duration_ms) / 1000.0
+            );
+            if !entry.content.trim().is_empty() {
+                text.push('\n');
+                text.push_str(&fenced("", &entry.content));
+            }
+            text
+        }
+    }
+}
+
+fn render_diff(files: &[FileHunks]) -> String {
+    let mut diff = String::new();
+    for file in files {
+        diff.push_str(&format!("--- a/{0}\n+++ b/{0}\n", file.path));
+        for hunk in &file.hunks {
+            diff.push_str(&unified_hunk(hunk));
+        }
+    }
+    fenced("diff", &diff)
+}
+
+fn render_run(index: usize, run: &RunConversation) -> String {
+    let status = serde_json::to_value(&run.status)
+        .ok()
+        .and_then(|status| status.as_str().map(str::to_string))
+        .unwrap_or_default();
+    let mut out = format!(
+        "### Run {} ({}, {})\n\n",
+        index + 1,
+        run.conversation.executor_type,
+        status
+    );
+
+    // Executors that don't echo the prompt only have it with their session
+    let echoes_prompt = run
+        .conversation
+        .entries
+        .iter()
+        .any(|entry| matches!(entry.entry_type, NormalizedEntryType::UserMessage));
+    if let Some(prompt) = run.conversation.prompt.as_ref().filter(|_| !echoes_prompt) {
+        out.push_str(&format!("**User**\n\n{}\n\n", prompt));
+    }
+    for entry in &run.conversation.entries {
+        out.push_str(&render_entry(entry));
+        out.push('\n');
+    }
+
+    if let Some(files) = run.diff.as_ref().filter(|files| !files.is_empty()) {
+        let heading = match &run.conversation.diff_summary {
+            Some(summary) => format!(
+                "#### Changes (+{} -{} in {} files)\n\n",
+                summary.additions,
+                summary.deletions,
+                summary.files.len()
+            ),
+            None => "#### Changes\n\n".to_string(),
+        };
+        out.push_str(&heading);
+        out.push_str(&render_diff(files));
+        out.push('\n');
+    }
+    out
+}
+
+/// The conversations as Markdown: a section per attempt, and in it one per run with its
+/// messages, tool calls and diff
+pub fn render_markdown(export: &TaskConversations) -> String {
+    let mut out = format!("# {}\n\n", export.title);
+    if let Some(description) = export
+        .description
+        .as_deref()
+        .filter(|description| !description.trim().is_empty())
+    {
+        out.push_str(description.trim_end());
+        out.push_str("\n\n");
+    }
+    if export.attempts.is_empty() {
+        out.push_str("_No attempts yet._\n");
+        return out;
+    }
+
+    for (index, attempt) in export.attempts.iter().enumerate() {
+        out.push_str(&format!(
+            "## Attempt {} on `{}`\n\n_Started {}{}_\n\n",
+            index + 1,
+            attempt.branch,
+            attempt.created_at.format("%Y-%m-%d %H:%M UTC"),
+            attempt
+                .executor
+                .as_deref()
+                .map(|executor| format!(" with {}", executor))
+                .unwrap_or_default()
+        ));
+        if attempt.runs.is_empty() {
+            out.push_str("_No coding agent runs._\n\n");
+        }
+        for (index, run) in attempt.runs.iter().enumerate() {
+            out.push_str(&render_run(index, run));
+        }
+    }
+    format!("{}\n", out.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::task_attempt::{DiffChunk, DiffChunkType, DiffHunk};
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            id: None,
+            timestamp: None,
+            duration_ms: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let started_at = DateTime::parse_from_rfc3339("2025-09-01T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let export = TaskConversations {
+            task_id: Uuid::new_v4(),
+            title: "Fix login".to_string(),
+            description: Some("The form rejects valid emails".to_string()),
+            exported_at: started_at,
+            attempts: vec![AttemptConversations {
+                attempt_id: Uuid::new_v4(),
+                branch: "vk-fix-login".to_string(),
+                executor: Some("claude".to_string()),
+                created_at: started_at,
+                runs: vec![RunConversation {
+                    execution_process_id: Uuid::new_v4(),
+                    status: ExecutionProcessStatus::Completed,
+                    started_at,
+                    completed_at: None,
+                    conversation: NormalizedConversation {
+                        entries: vec![
+                            entry(
+                                NormalizedEntryType::ToolUse {
+                                    tool_name: "Bash".to_string(),
+                                    action_type: ActionType::CommandRun {
+                                        command: "npm test".to_string(),
+                                    },
+                                },
+                                "npm test",
+                            ),
+                            entry(NormalizedEntryType::AssistantMessage, "Fixed the regex."),
+                        ],
+                        session_id: None,
+                        executor_type: "claude".to_string(),
+                        prompt: Some("Fix login".to_string()),
+                        summary: None,
+                        diff_summary: None,
+                    },
+                    diff: Some(vec![FileHunks {
+                        path: "src/login.ts".to_string(),
+                        hunks: vec![DiffHunk {
+                            index: 0,
+                            header: "@@ -1,1 +1,1 @@".to_string(),
+                            old_start: 1,
+                            new_start: 1,
+                            lines: vec![
+                                DiffChunk {
+                                    chunk_type: DiffChunkType::Delete,
+                                    content: "const re = /a/;".to_string(),
+                                },
+                                DiffChunk {
+                                    chunk_type: DiffChunkType::Insert,
+                                    content: "const re = /b/;".to_string(),
+                                },
+                            ],
+                        }],
+                    }]),
+                }],
+            }],
+        };
+
+        assert_eq!(
+            render_markdown(&export),
+            "# Fix login\n\n\
+             The form rejects valid emails\n\n\
+             ## Attempt 1 on `vk-fix-login`\n\n\
+             _Started 2025-09-01 10:00 UTC with claude_\n\n\
+             ### Run 1 (claude, completed)\n\n\
+             **User**\n\nFix login\n\n\
+             - **Bash** `npm test`\n\n\
+             **Assistant**\n\nFixed the regex.\n\n\
+             #### Changes\n\n\
+             ```diff\n\
+             --- a/src/login.ts\n\
+             +++ b/src/login.ts\n\
+             @@ -1,1 +1,1 @@\n\
+             -const re = /a/;\n\
+             +const re = /b/;\n\
+             ```\n"
+        );
+    }
+
+    #[test]
+    fn test_fences_outlast_backticks_in_the_content() {
+        assert_eq!(fence("plain"), "```");
+        assert_eq!(
+            fenced("", "```rust\nfn f() {}\n```"),
+            "````\n```rust\nfn f() {}\n```\n````\n"
+        );
+    }
+}
@@ -0,0 +1,277 @@
+//! Semantic search over coding agent conversations. Finished processes are split into
+//! chunks of consecutive normalized entries, embedded, and stored; each search first
+//! embeds whatever was added since the last one, then ranks chunks by similarity to the
+//! query.
+
+use std::collections::HashMap;
+
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    executor::NormalizedEntry,
+    models::{
+        conversation_chunk::{ConversationChunk, ConversationIndexState, NewConversationChunk},
+        conversation_search::{rank_hits, searchable_text, AttemptTaskRef, ConversationSearchHit},
+        execution_process::ExecutionProcess,
+    },
+    routes::task_attempts::normalize_process_logs,
+    services::embeddings::{cosine_similarity, EmbeddingsClient, EmbeddingsError},
+};
+
+/// Roughly a few paragraphs, small enough that one topic dominates a chunk's vector
+const CHUNK_CHARS: usize = 1200;
+/// Semantic scores are compressed compared to keyword ones; chunks below this are noise
+const MIN_SIMILARITY: f32 = 0.3;
+const SNIPPET_CHARS: usize = 160;
+
+#[derive(Debug)]
+pub enum ConversationIndexError {
+    Database(sqlx::Error),
+    Embeddings(EmbeddingsError),
+}
+
+impl std::fmt::Display for ConversationIndexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConversationIndexError::Database(e) => write!(f, "Database error: {}", e),
+            ConversationIndexError::Embeddings(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ConversationIndexError {}
+
+impl From<sqlx::Error> for ConversationIndexError {
+    fn from(e: sqlx::Error) -> Self {
+        ConversationIndexError::Database(e)
+    }
+}
+
+impl From<EmbeddingsError> for ConversationIndexError {
+    fn from(e: EmbeddingsError) -> Self {
+        ConversationIndexError::Embeddings(e)
+    }
+}
+
+/// Keeps concurrent searches from embedding the same new entries twice
+static INDEX_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// A chunk's text and the entry it starts at, before embedding
+#[derive(Debug, Clone, PartialEq)]
+struct PendingChunk {
+    entry_index: usize,
+    content: String,
+}
+
+/// Group entries from `start` onwards into chunks of up to `CHUNK_CHARS`. An entry longer
+/// than that gets chunks of its own.
+fn chunk_entries(entries: &[NormalizedEntry], start: usize) -> Vec<PendingChunk> {
+    let mut chunks = Vec::new();
+    let mut current: Option<PendingChunk> = None;
+
+    for (index, entry) in entries.iter().enumerate().skip(start) {
+        let text = searchable_text(entry);
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let length = text.chars().count();
+
+        if let Some(chunk) = &mut current {
+            if chunk.content.chars().count() + 1 + length <= CHUNK_CHARS {
+                chunk.content.push('\n');
+                chunk.content.push_str(text);
+                continue;
+            }
+            chunks.extend(current.take());
+        }
+
+        if length <= CHUNK_CHARS {
+            current = Some(PendingChunk {
+                entry_index: index,
+                content: text.to_string(),
+            });
+        } else {
+            let chars: Vec<char> = text.chars().collect();
+            chunks.extend(chars.chunks(CHUNK_CHARS).map(|piece| PendingChunk {
+                entry_index: index,
+                content: piece.iter().collect(),
+            }));
+        }
+    }
+    chunks.extend(current);
+    chunks
+}
+
+/// Embed new conversation entries of every finished coding agent process in the project
+pub async fn index_project(
+    pool: &DbPool,
+    client: &EmbeddingsClient,
+    project_id: Uuid,
+) -> Result<(), ConversationIndexError> {
+    let _guard = INDEX_LOCK.lock().await;
+
+    let states: HashMap<Uuid, ConversationIndexState> =
+        ConversationIndexState::find_by_project_id(pool, project_id)
+            .await?
+            .into_iter()
+            .map(|state| (state.execution_process_id, state))
+            .collect();
+    let processes = ExecutionProcess::find_coding_agents_by_project_id(pool, project_id).await?;
+
+    for process in processes {
+        // Entries of a running process are still being written
+        if process.status.is_live() {
+            continue;
+        }
+        let state = states.get(&process.id);
+        let reset = state.is_some_and(|s| s.model != client.model());
+        let start = match state {
+            Some(state) if !reset => state.indexed_entries as usize,
+            _ => 0,
+        };
+
+        let conversation = normalize_process_logs(pool, &process).await;
+        if conversation.entries.len() <= start && state.is_some() && !reset {
+            continue;
+        }
+        let pending = chunk_entries(&conversation.entries, start);
+        let contents: Vec<String> = pending.iter().map(|c| c.content.clone()).collect();
+        let vectors = if contents.is_empty() {
+            Vec::new()
+        } else {
+            client.embed(&contents).await?
+        };
+
+        let chunks: Vec<NewConversationChunk> = pending
+            .into_iter()
+            .zip(vectors)
+            .map(|(chunk, embedding)| {
+                let entry = &conversation.entries[chunk.entry_index];
+                NewConversationChunk {
+                    entry_index: chunk.entry_index,
+                    entry_type: entry.entry_type.clone(),
+                    timestamp: entry.timestamp.clone(),
+                    content: chunk.content,
+                    embedding,
+                }
+            })
+            .collect();
+        ConversationChunk::append(
+            pool,
+            process.id,
+            client.model(),
+            reset,
+            &chunks,
+            conversation.entries.len(),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Rank indexed chunks against a natural-language query
+pub async fn semantic_search(
+    pool: &DbPool,
+    client: &EmbeddingsClient,
+    project_id: Uuid,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<ConversationSearchHit>, ConversationIndexError> {
+    index_project(pool, client, project_id).await?;
+
+    let attempts: HashMap<Uuid, AttemptTaskRef> =
+        AttemptTaskRef::find_by_project_id(pool, project_id)
+            .await?
+            .into_iter()
+            .map(|r| (r.attempt_id, r))
+            .collect();
+    let query_vector = client
+        .embed(&[query.trim().to_string()])
+        .await?
+        .pop()
+        .ok_or(EmbeddingsError::InvalidResponse)?;
+
+    let hits = ConversationChunk::find_by_project_id(pool, project_id)
+        .await?
+        .into_iter()
+        .filter_map(|chunk| {
+            let score = cosine_similarity(&query_vector, &chunk.embedding);
+            if score < MIN_SIMILARITY {
+                return None;
+            }
+            let attempt = attempts.get(&chunk.task_attempt_id)?;
+            Some(ConversationSearchHit {
+                task_id: attempt.task_id,
+                task_title: attempt.task_title.clone(),
+                attempt_id: attempt.attempt_id,
+                execution_process_id: chunk.execution_process_id,
+                entry_index: chunk.entry_index as usize,
+                entry_type: chunk.entry_type,
+                timestamp: chunk.timestamp,
+                snippet: snippet(&chunk.content),
+                score: score as f64,
+            })
+        })
+        .collect();
+    Ok(rank_hits(hits, limit))
+}
+
+fn snippet(content: &str) -> String {
+    let collapsed = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.chars().count() <= SNIPPET_CHARS {
+        return collapsed;
+    }
+    let cut: String = collapsed.chars().take(SNIPPET_CHARS).collect();
+    format!("{}…", cut)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::NormalizedEntryType;
+
+    fn message(content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            id: None,
+            timestamp: None,
+            duration_ms: None,
+            entry_type: NormalizedEntryType::AssistantMessage,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_chunk_entries_groups_and_splits() {
+        let long = "x".repeat(CHUNK_CHARS + 10);
+        let entries = vec![
+            message("first"),
+            message("second"),
+            message("   "),
+            message(&long),
+            message("after"),
+        ];
+
+        let chunks = chunk_entries(&entries, 0);
+        assert_eq!(chunks.len(), 4);
+        assert_eq!(
+            chunks[0],
+            PendingChunk {
+                entry_index: 0,
+                content: "first\nsecond".to_string()
+            }
+        );
+        assert_eq!(chunks[1].entry_index, 3);
+        assert_eq!(chunks[1].content.len(), CHUNK_CHARS);
+        assert_eq!(chunks[2].content.len(), 10);
+        assert_eq!(chunks[3].entry_index, 4);
+
+        // Incremental indexing only looks at entries past what was already covered
+        let chunks = chunk_entries(&entries, 4);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].content, "after");
+    }
+}
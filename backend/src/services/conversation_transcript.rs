@@ -0,0 +1,370 @@
+//! Follow-ups for executors that can't resume a session, and for attempts handed over to
+//! another executor. The conversation of each of the attempt's finished runs is kept, and
+//! the follow-up prompt is prefixed with a transcript of them, so the agent starts out
+//! knowing what was already done. The transcript is compacted to the executor's token
+//! budget: the most recent turns are kept as they were, tool calls included, and the ones
+//! before them summarized run by run.
+
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    executor::{ActionType, NormalizedEntry, NormalizedEntryType},
+    models::conversation_context::ConversationContext,
+    utils::tokens::{count_tokens, truncate_to_tokens},
+};
+
+const MAX_MESSAGE_CHARS: usize = 1_500;
+const MAX_TOOL_CHARS: usize = 500;
+/// Once older turns have to be summarized, a quarter of the budget is kept for them
+const SUMMARY_SHARE: usize = 4;
+const MAX_SUMMARY_QUOTE_CHARS: usize = 300;
+
+/// `prompt` prefixed with the transcript of the attempt's earlier runs, in at most
+/// `max_tokens`. Falls back to the bare prompt when there is nothing to replay or the
+/// conversations can't be loaded.
+pub async fn prompt_with_transcript(
+    pool: &DbPool,
+    attempt_id: Uuid,
+    prompt: &str,
+    max_tokens: usize,
+) -> String {
+    let contexts = match ConversationContext::find_by_task_attempt_id(pool, attempt_id).await {
+        Ok(contexts) => contexts,
+        Err(e) => {
+            tracing::warn!(
+                "Failed to load earlier conversations of attempt {}, following up without them: {}",
+                attempt_id,
+                e
+            );
+            return prompt.to_string();
+        }
+    };
+    match compress_transcript(&contexts, max_tokens) {
+        Some(transcript) => format!(
+            "This continues earlier work on this task. A condensed transcript of it:\n\n\
+             {}\n\nFollow-up request:\n\n{}",
+            transcript, prompt
+        ),
+        None => prompt.to_string(),
+    }
+}
+
+/// A line of the transcript, from one of the runs' entries or, without one, its prompt
+struct Turn<'a> {
+    run: usize,
+    entry: Option<&'a NormalizedEntry>,
+    text: String,
+    tokens: usize,
+}
+
+/// The runs' conversations as plain text of at most about `max_tokens`, with thinking and
+/// system output dropped and long messages and tool calls clipped. `None` if there is
+/// nothing to replay.
+pub fn compress_transcript(contexts: &[ConversationContext], max_tokens: usize) -> Option<String> {
+    let mut turns: Vec<Turn> = contexts
+        .iter()
+        .enumerate()
+        .flat_map(|(run, context)| run_turns(run, context))
+        .collect();
+    if turns.is_empty() {
+        return None;
+    }
+
+    // Keep the most recent turns as they are, leaving room to summarize the rest
+    let total: usize = turns.iter().map(|turn| turn.tokens).sum();
+    let verbatim_budget = if total <= max_tokens {
+        max_tokens
+    } else {
+        max_tokens - max_tokens / SUMMARY_SHARE
+    };
+    let mut first_kept = turns.len();
+    let mut used = 0;
+    while first_kept > 0 && used + turns[first_kept - 1].tokens <= verbatim_budget {
+        first_kept -= 1;
+        used += turns[first_kept].tokens;
+    }
+    if first_kept == turns.len() {
+        // Not even the last turn fits whole; keep its start
+        let last = turns.last_mut()?;
+        last.text = format!("{}…", truncate_to_tokens(&last.text, verbatim_budget));
+        last.tokens = count_tokens(&last.text);
+        first_kept -= 1;
+        used = last.tokens;
+    }
+
+    // Then summaries of the older turns, the most recent runs first
+    let mut summaries = vec![None; contexts.len()];
+    for run in (0..=turns[first_kept].run).rev() {
+        let older: Vec<&Turn> = turns[..first_kept]
+            .iter()
+            .filter(|turn| turn.run == run)
+            .collect();
+        let Some(summary) = summarize(contexts[run].prompt.as_deref(), &older) else {
+            continue;
+        };
+        let tokens = count_tokens(&summary);
+        if used + tokens > max_tokens {
+            break;
+        }
+        used += tokens;
+        summaries[run] = Some(summary);
+    }
+
+    let mut runs = Vec::new();
+    let mut omitted = 0;
+    for (run, summary) in summaries.into_iter().enumerate() {
+        let lines: Vec<String> = summary
+            .into_iter()
+            .chain(
+                turns[first_kept..]
+                    .iter()
+                    .filter(|turn| turn.run == run)
+                    .map(|turn| turn.text.clone()),
+            )
+            .collect();
+        if !lines.is_empty() {
+            runs.push(lines.join("\n"));
+        } else if turns.iter().any(|turn| turn.run == run) {
+            omitted += 1;
+        }
+    }
+    let mut transcript = runs.join("\n\n");
+    if omitted > 0 {
+        transcript = format!("({} earlier runs left out)\n\n{}", omitted, transcript);
+    }
+    Some(transcript)
+}
+
+fn run_turns(run: usize, context: &ConversationContext) -> Vec<Turn<'_>> {
+    let prompt = context
+        .prompt
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty());
+    let mut turns = Vec::new();
+    if let Some(prompt) = prompt {
+        turns.push((None, format!("User: {}", clip(prompt, MAX_MESSAGE_CHARS))));
+    }
+    turns.extend(
+        context
+            .entries
+            .iter()
+            .filter_map(|entry| Some((Some(entry), compress_entry(entry, prompt)?))),
+    );
+    turns
+        .into_iter()
+        .map(|(entry, text)| Turn {
+            run,
+            entry,
+            tokens: count_tokens(&text),
+            text,
+        })
+        .collect()
+}
+
+/// One line on what `turns` of a run did: what was asked, the files it changed, the commands
+/// it ran and what it said last. `None` if they did nothing worth mentioning.
+fn summarize(prompt: Option<&str>, turns: &[&Turn]) -> Option<String> {
+    let mut asked = None;
+    let mut edited: Vec<&str> = Vec::new();
+    let mut commands: Vec<&str> = Vec::new();
+    let (mut reads, mut errors, mut failed_checks) = (0, 0, 0);
+    let mut last_said = None;
+    for turn in turns {
+        let Some(entry) = turn.entry else {
+            asked = prompt;
+            continue;
+        };
+        match &entry.entry_type {
+            NormalizedEntryType::UserMessage => {
+                asked.get_or_insert(entry.content.as_str());
+            }
+            NormalizedEntryType::AssistantMessage | NormalizedEntryType::PlanEntry => {
+                last_said = Some(entry.content.as_str());
+            }
+            NormalizedEntryType::ToolUse { action_type, .. } => match action_type {
+                ActionType::FileWrite { path, .. } if !edited.contains(&path.as_str()) => {
+                    edited.push(path)
+                }
+                ActionType::CommandRun { command } => commands.push(command),
+                ActionType::FileRead { .. } | ActionType::Search { .. } => reads += 1,
+                _ => {}
+            },
+            NormalizedEntryType::ErrorMessage => errors += 1,
+            NormalizedEntryType::Verification { passed: false, .. } => failed_checks += 1,
+            _ => {}
+        }
+    }
+
+    let quote = |text: &str| format!("\"{}\"", clip(text.trim(), MAX_SUMMARY_QUOTE_CHARS));
+    let mut parts = Vec::new();
+    if let Some(asked) = asked {
+        parts.push(format!("asked {}", quote(asked)));
+    }
+    if reads > 0 {
+        parts.push(format!("read or searched {} times", reads));
+    }
+    if !edited.is_empty() {
+        parts.push(clip(
+            &format!("edited {}", edited.join(", ")),
+            MAX_TOOL_CHARS,
+        ));
+    }
+    if !commands.is_empty() {
+        parts.push(clip(
+            &format!("ran {}", commands.join(", ")),
+            MAX_TOOL_CHARS,
+        ));
+    }
+    if errors > 0 {
+        parts.push(format!("hit {} errors", errors));
+    }
+    if failed_checks > 0 {
+        parts.push(format!("failed {} verification commands", failed_checks));
+    }
+    if let Some(said) = last_said {
+        parts.push(format!("then said {}", quote(said)));
+    }
+    (!parts.is_empty()).then(|| format!("Summary of earlier turns: {}", parts.join("; ")))
+}
+
+fn compress_entry(entry: &NormalizedEntry, prompt: Option<&str>) -> Option<String> {
+    let content = entry.content.trim();
+    if content.is_empty() {
+        return None;
+    }
+    match &entry.entry_type {
+        // The executor echoing the run's prompt back
+        NormalizedEntryType::UserMessage if Some(content) == prompt => None,
+        NormalizedEntryType::UserMessage => {
+            Some(format!("User: {}", clip(content, MAX_MESSAGE_CHARS)))
+        }
+        NormalizedEntryType::AssistantMessage => {
+            Some(format!("Assistant: {}", clip(content, MAX_MESSAGE_CHARS)))
+        }
+        NormalizedEntryType::ToolUse { tool_name, .. } => Some(format!(
+            "Tool ({}): {}",
+            tool_name,
+            clip(content, MAX_TOOL_CHARS)
+        )),
+        NormalizedEntryType::ErrorMessage => {
+            Some(format!("Error: {}", clip(content, MAX_TOOL_CHARS)))
+        }
+        NormalizedEntryType::PlanEntry => {
+            Some(format!("Plan: {}", clip(content, MAX_MESSAGE_CHARS)))
+        }
+        NormalizedEntryType::Verification {
+            command, passed, ..
+        } => Some(format!(
+            "Verification: {} {}",
+            command,
+            if *passed { "passed" } else { "failed" }
+        )),
+        // The tool use before it already says which file was edited
+        NormalizedEntryType::Thinking
+        | NormalizedEntryType::SystemMessage
+        | NormalizedEntryType::Attachment { .. }
+        | NormalizedEntryType::FileEdit { .. } => None,
+    }
+}
+
+fn clip(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let clipped: String = text.chars().take(max_chars - 1).collect();
+    format!("{}…", clipped.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            id: None,
+            timestamp: None,
+            duration_ms: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    fn context(prompt: &str, entries: Vec<NormalizedEntry>) -> ConversationContext {
+        ConversationContext {
+            prompt: Some(prompt.to_string()),
+            entries,
+        }
+    }
+
+    #[test]
+    fn test_transcript_keeps_messages_and_tool_calls() {
+        let contexts = vec![context(
+            "Add a login page",
+            vec![
+                entry(NormalizedEntryType::UserMessage, "Add a login page"),
+                entry(NormalizedEntryType::Thinking, "Where do pages live?"),
+                entry(
+                    NormalizedEntryType::ToolUse {
+                        tool_name: "write".to_string(),
+                        action_type: ActionType::FileWrite {
+                            path: "src/login.tsx".to_string(),
+                            location: None,
+                        },
+                    },
+                    "write src/login.tsx",
+                ),
+                entry(NormalizedEntryType::AssistantMessage, "Added the page."),
+            ],
+        )];
+
+        let transcript = compress_transcript(&contexts, 4_000).unwrap();
+        assert_eq!(
+            transcript,
+            "User: Add a login page\nTool (write): write src/login.tsx\nAssistant: Added the page."
+        );
+    }
+
+    #[test]
+    fn test_transcript_summarizes_older_runs_over_budget() {
+        let long = "x".repeat(MAX_MESSAGE_CHARS);
+        let contexts: Vec<_> = (0..20)
+            .map(|i| {
+                context(
+                    &format!("Run {}", i),
+                    vec![
+                        entry(
+                            NormalizedEntryType::ToolUse {
+                                tool_name: "bash".to_string(),
+                                action_type: ActionType::CommandRun {
+                                    command: "cargo test".to_string(),
+                                },
+                            },
+                            "cargo test",
+                        ),
+                        entry(NormalizedEntryType::AssistantMessage, &long),
+                    ],
+                )
+            })
+            .collect();
+
+        let transcript = compress_transcript(&contexts, 2_000).unwrap();
+        assert!(count_tokens(&transcript) <= 2_000 + 10);
+        assert!(transcript.starts_with("(9 earlier runs left out)"));
+        assert!(transcript.contains("Summary of earlier turns: asked \"Run 16\"; ran cargo test"));
+        assert!(!transcript.contains("\"Run 0\""));
+        assert!(transcript.contains("User: Run 19\nTool (bash): cargo test\n"));
+    }
+
+    #[test]
+    fn test_empty_transcript_is_none() {
+        assert!(compress_transcript(&[], 4_000).is_none());
+        let contexts = vec![ConversationContext {
+            prompt: None,
+            entries: vec![entry(NormalizedEntryType::Thinking, "hmm")],
+        }];
+        assert!(compress_transcript(&contexts, 4_000).is_none());
+    }
+}
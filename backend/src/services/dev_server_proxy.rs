@@ -0,0 +1,128 @@
+//! Live previews of dev servers. Requests under an attempt's `dev-server/proxy` path are
+//! forwarded to the port its running dev server was given, so the agent's changes can be tried
+//! in the browser while the dev server serves them from the attempt's worktree.
+
+use std::sync::OnceLock;
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::Request,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+/// The largest request body forwarded to a dev server
+const MAX_BODY_BYTES: usize = 32 * 1024 * 1024;
+
+/// Headers that describe a single hop rather than the request or response they come with
+const HOP_BY_HOP_HEADERS: &[&str] = &[
+    "connection",
+    "content-length",
+    "host",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+fn is_hop_by_hop(name: &str) -> bool {
+    HOP_BY_HOP_HEADERS.contains(&name)
+}
+
+/// Redirects are passed back to the browser rather than followed
+fn client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .unwrap_or_default()
+    })
+}
+
+/// The dev server's URL for `path`, keeping the query the request came with
+fn upstream_url(port: u16, path: &str, query: Option<&str>) -> String {
+    let path = path.trim_start_matches('/');
+    match query {
+        Some(query) => format!("http://127.0.0.1:{}/{}?{}", port, path, query),
+        None => format!("http://127.0.0.1:{}/{}", port, path),
+    }
+}
+
+/// Forward `request` to `path` on the dev server listening on `port` and return its response
+pub async fn forward(port: u16, path: &str, request: Request) -> Response {
+    let (parts, body) = request.into_parts();
+    let url = upstream_url(port, path, parts.uri.query());
+
+    let body = match to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(e) => {
+            return (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!("Failed to read the request body: {}", e),
+            )
+                .into_response()
+        }
+    };
+    let Ok(method) = reqwest::Method::from_bytes(parts.method.as_str().as_bytes()) else {
+        return StatusCode::METHOD_NOT_ALLOWED.into_response();
+    };
+
+    let mut upstream_request = client().request(method, &url).body(body);
+    for (name, value) in &parts.headers {
+        if !is_hop_by_hop(name.as_str()) {
+            upstream_request = upstream_request.header(name.as_str(), value.as_bytes());
+        }
+    }
+
+    let upstream = match upstream_request.send().await {
+        Ok(upstream) => upstream,
+        Err(e) => {
+            tracing::debug!("Dev server on port {} didn't answer: {}", port, e);
+            return (
+                StatusCode::BAD_GATEWAY,
+                format!("The dev server on port {} didn't answer: {}", port, e),
+            )
+                .into_response();
+        }
+    };
+
+    let status =
+        StatusCode::from_u16(upstream.status().as_u16()).unwrap_or(StatusCode::BAD_GATEWAY);
+    let mut response = Response::builder().status(status);
+    for (name, value) in upstream.headers() {
+        if !is_hop_by_hop(name.as_str()) {
+            response = response.header(name.as_str(), value.as_bytes());
+        }
+    }
+    let body = match upstream.bytes().await {
+        Ok(body) => body,
+        Err(e) => {
+            return (
+                StatusCode::BAD_GATEWAY,
+                format!("Failed to read the dev server's response: {}", e),
+            )
+                .into_response()
+        }
+    };
+    response
+        .body(Body::from(body))
+        .unwrap_or_else(|_| StatusCode::BAD_GATEWAY.into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upstream_url_keeps_path_and_query() {
+        assert_eq!(upstream_url(5173, "", None), "http://127.0.0.1:5173/");
+        assert_eq!(
+            upstream_url(5173, "/src/main.tsx", Some("t=1")),
+            "http://127.0.0.1:5173/src/main.tsx?t=1"
+        );
+    }
+}
@@ -0,0 +1,287 @@
+//! Self-diagnostics for whatever supervises the server: can it reach its database, has it
+//! applied every migration, is there room for worktrees, is git installed, and can the
+//! configured executors run. Each check reports a machine-readable status, and the worst of
+//! them is the status of the whole report.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::{
+    db::DbPool,
+    executor::ExecutorConfig,
+    models::{executor_profile::ExecutorProfile, task_attempt::TaskAttempt},
+    services::{executor_preflight, migrations},
+};
+
+/// Free space below which new worktrees will soon fail to check out
+const DISK_WARNING_BYTES: u64 = 5 * 1024 * 1024 * 1024;
+/// Free space below which a worktree or build may not fit at all
+const DISK_ERROR_BYTES: u64 = 1024 * 1024 * 1024;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, TS, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum CheckStatus {
+    Ok,
+    /// Working, but something will fail soon or some executor can't run
+    Warning,
+    /// The server can't do its job until this is fixed
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, JsonSchema)]
+#[ts(export)]
+pub struct DiagnosticCheck {
+    /// `database`, `migrations`, `disk_space`, `git`, or `executor:<name>`
+    pub name: String,
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, JsonSchema)]
+#[ts(export)]
+pub struct Diagnostics {
+    /// The worst status of the checks
+    pub status: CheckStatus,
+    pub checks: Vec<DiagnosticCheck>,
+}
+
+impl Diagnostics {
+    fn new(checks: Vec<DiagnosticCheck>) -> Self {
+        let status = checks
+            .iter()
+            .map(|check| check.status)
+            .max()
+            .unwrap_or(CheckStatus::Ok);
+        Self { status, checks }
+    }
+}
+
+impl DiagnosticCheck {
+    fn new(name: impl Into<String>, status: CheckStatus, message: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+/// The checks that answer in milliseconds, cheap enough to poll
+pub async fn quick_checks(pool: &DbPool) -> Diagnostics {
+    Diagnostics::new(base_checks(pool).await)
+}
+
+/// Every check, including a `--version` probe of each configured executor, which can take
+/// seconds: the default one and those of the global profiles
+pub async fn full_checks(pool: &DbPool, default_executor: &ExecutorConfig) -> Diagnostics {
+    let mut checks = base_checks(pool).await;
+
+    let mut executors = vec![default_executor.clone()];
+    match ExecutorProfile::find_global(pool).await {
+        Ok(profiles) => executors.extend(
+            profiles
+                .iter()
+                .filter_map(|profile| profile.executor.parse::<ExecutorConfig>().ok()),
+        ),
+        Err(e) => tracing::warn!("Failed to load executor profiles for diagnostics: {}", e),
+    }
+    let mut seen = HashSet::new();
+    for executor in executors {
+        if seen.insert(executor.to_string()) {
+            checks.push(check_executor(&executor).await);
+        }
+    }
+    Diagnostics::new(checks)
+}
+
+async fn base_checks(pool: &DbPool) -> Vec<DiagnosticCheck> {
+    let worktree_dir = TaskAttempt::get_worktree_base_dir();
+    let disk = tokio::task::spawn_blocking(move || check_disk_space(&worktree_dir))
+        .await
+        .unwrap_or_else(|e| DiagnosticCheck::new("disk_space", CheckStatus::Error, e.to_string()));
+    vec![
+        check_database(pool).await,
+        check_migrations(pool).await,
+        disk,
+        check_git().await,
+    ]
+}
+
+async fn check_database(pool: &DbPool) -> DiagnosticCheck {
+    let result = match pool {
+        DbPool::Sqlite(pool) => sqlx::query("SELECT 1").execute(pool).await.map(drop),
+        DbPool::Postgres(pool) => sqlx::query("SELECT 1").execute(pool).await.map(drop),
+    };
+    match result {
+        Ok(_) => DiagnosticCheck::new("database", CheckStatus::Ok, "Connected"),
+        Err(e) => DiagnosticCheck::new("database", CheckStatus::Error, e.to_string()),
+    }
+}
+
+/// Migrations shipped with this build that the database hasn't applied, or failed halfway
+async fn check_migrations(pool: &DbPool) -> DiagnosticCheck {
+    const APPLIED: &str = "SELECT version, success FROM _sqlx_migrations";
+    let applied: Result<Vec<(i64, bool)>, sqlx::Error> = match pool {
+        DbPool::Sqlite(pool) => sqlx::query_as(APPLIED).fetch_all(pool).await,
+        DbPool::Postgres(pool) => sqlx::query_as(APPLIED).fetch_all(pool).await,
+    };
+    let applied = match applied {
+        Ok(applied) => applied,
+        Err(e) => return DiagnosticCheck::new("migrations", CheckStatus::Error, e.to_string()),
+    };
+    let shipped: Vec<i64> = migrations::migrator(pool)
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .map(|migration| migration.version)
+        .collect();
+
+    let pending = pending_migrations(&shipped, &applied);
+    if pending.is_empty() {
+        DiagnosticCheck::new(
+            "migrations",
+            CheckStatus::Ok,
+            format!("All {} migrations applied", shipped.len()),
+        )
+    } else {
+        let versions: Vec<String> = pending.iter().map(|v| v.to_string()).collect();
+        DiagnosticCheck::new(
+            "migrations",
+            CheckStatus::Error,
+            format!("Pending migrations: {}", versions.join(", ")),
+        )
+    }
+}
+
+fn pending_migrations(shipped: &[i64], applied: &[(i64, bool)]) -> Vec<i64> {
+    let done: HashSet<i64> = applied
+        .iter()
+        .filter(|(_, success)| *success)
+        .map(|(version, _)| *version)
+        .collect();
+    shipped
+        .iter()
+        .copied()
+        .filter(|version| !done.contains(version))
+        .collect()
+}
+
+fn check_disk_space(worktree_dir: &Path) -> DiagnosticCheck {
+    // The worktree root is only created with the first attempt
+    let existing = worktree_dir
+        .ancestors()
+        .find(|dir| dir.exists())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| worktree_dir.to_path_buf());
+    match available_bytes(&existing) {
+        Ok(available) => DiagnosticCheck::new(
+            "disk_space",
+            disk_status(available),
+            format!(
+                "{:.1} GB free in {}",
+                available as f64 / (1024.0 * 1024.0 * 1024.0),
+                worktree_dir.display()
+            ),
+        ),
+        Err(e) => DiagnosticCheck::new(
+            "disk_space",
+            CheckStatus::Warning,
+            format!("Couldn't read free space of {}: {}", existing.display(), e),
+        ),
+    }
+}
+
+fn disk_status(available: u64) -> CheckStatus {
+    if available < DISK_ERROR_BYTES {
+        CheckStatus::Error
+    } else if available < DISK_WARNING_BYTES {
+        CheckStatus::Warning
+    } else {
+        CheckStatus::Ok
+    }
+}
+
+#[cfg(unix)]
+fn available_bytes(path: &Path) -> std::io::Result<u64> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let path = CString::new(path.as_os_str().as_bytes())?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    // The field types differ between platforms
+    #[allow(clippy::unnecessary_cast)]
+    let available = stat.f_bavail as u64 * stat.f_frsize as u64;
+    Ok(available)
+}
+
+#[cfg(not(unix))]
+fn available_bytes(_path: &Path) -> std::io::Result<u64> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "not supported on this platform",
+    ))
+}
+
+async fn check_git() -> DiagnosticCheck {
+    let Some(path) = executor_preflight::find_on_path("git") else {
+        return DiagnosticCheck::new("git", CheckStatus::Error, "`git` was not found on PATH");
+    };
+    match executor_preflight::probe_version(&path).await {
+        Ok(version) => DiagnosticCheck::new("git", CheckStatus::Ok, version),
+        Err(e) => DiagnosticCheck::new(
+            "git",
+            CheckStatus::Error,
+            format!("`git --version` failed: {}", e),
+        ),
+    }
+}
+
+/// A warning rather than an error: the server works, only attempts with this executor fail
+async fn check_executor(config: &ExecutorConfig) -> DiagnosticCheck {
+    let report = executor_preflight::check(config).await;
+    let name = format!("executor:{}", report.executor);
+    if report.problems.is_empty() {
+        let version = report.version.unwrap_or_else(|| "Ready".to_string());
+        return DiagnosticCheck::new(name, CheckStatus::Ok, version);
+    }
+    let problems: Vec<String> = report
+        .problems
+        .iter()
+        .map(|problem| problem.message.clone())
+        .collect();
+    DiagnosticCheck::new(name, CheckStatus::Warning, problems.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failed_and_missing_migrations_are_pending() {
+        let shipped = [1, 2, 3, 4];
+        let applied = [(1, true), (2, true), (3, false)];
+        assert_eq!(pending_migrations(&shipped, &applied), vec![3, 4]);
+        assert!(pending_migrations(&shipped[..2], &applied).is_empty());
+    }
+
+    #[test]
+    fn test_report_takes_the_worst_status() {
+        let report = Diagnostics::new(vec![
+            DiagnosticCheck::new("database", CheckStatus::Ok, "Connected"),
+            DiagnosticCheck::new("disk_space", disk_status(2 * 1024 * 1024 * 1024), ""),
+        ]);
+        assert_eq!(report.status, CheckStatus::Warning);
+        assert_eq!(disk_status(DISK_ERROR_BYTES - 1), CheckStatus::Error);
+        assert_eq!(Diagnostics::new(Vec::new()).status, CheckStatus::Ok);
+    }
+}
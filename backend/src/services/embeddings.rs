@@ -0,0 +1,166 @@
+//! Text embeddings from an OpenAI-compatible `/embeddings` endpoint. That covers hosted APIs
+//! as well as local model servers such as Ollama or llama.cpp, so no model runs in-process.
+
+use serde_json::{json, Value};
+
+use crate::models::config::EmbeddingsConfig;
+
+/// Inputs per request; providers reject very large batches
+const BATCH_SIZE: usize = 64;
+
+#[derive(Debug)]
+pub enum EmbeddingsError {
+    Request(reqwest::Error),
+    Api(String),
+    InvalidResponse,
+}
+
+impl std::fmt::Display for EmbeddingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EmbeddingsError::Request(e) => write!(f, "Failed to reach embeddings provider: {}", e),
+            EmbeddingsError::Api(e) => write!(f, "Embeddings provider error: {}", e),
+            EmbeddingsError::InvalidResponse => {
+                write!(f, "Embeddings provider returned an unexpected response")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EmbeddingsError {}
+
+pub struct EmbeddingsClient {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+}
+
+impl EmbeddingsClient {
+    /// `None` when no provider is configured, which turns embedding features off
+    pub fn from_config(config: &EmbeddingsConfig) -> Option<Self> {
+        let base_url = config.base_url.as_deref()?.trim().trim_end_matches('/');
+        if base_url.is_empty() {
+            return None;
+        }
+        Some(Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.to_string(),
+            api_key: config.api_key.clone().filter(|key| !key.is_empty()),
+            model: config.model.clone(),
+        })
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// One vector per input, in input order
+    pub async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingsError> {
+        let mut vectors = Vec::with_capacity(inputs.len());
+        for batch in inputs.chunks(BATCH_SIZE) {
+            vectors.extend(self.embed_batch(batch).await?);
+        }
+        Ok(vectors)
+    }
+
+    async fn embed_batch(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingsError> {
+        let mut request = self
+            .client
+            .post(format!("{}/embeddings", self.base_url))
+            .json(&json!({ "model": self.model, "input": inputs }));
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let response = request.send().await.map_err(EmbeddingsError::Request)?;
+        let status = response.status();
+        let body: Value = response.json().await.map_err(EmbeddingsError::Request)?;
+        if !status.is_success() {
+            let message = body["error"]["message"]
+                .as_str()
+                .or_else(|| body["error"].as_str())
+                .map(str::to_string)
+                .unwrap_or_else(|| status.to_string());
+            return Err(EmbeddingsError::Api(message));
+        }
+        parse_embeddings(&body, inputs.len())
+    }
+}
+
+fn parse_embeddings(body: &Value, expected: usize) -> Result<Vec<Vec<f32>>, EmbeddingsError> {
+    let data = body["data"]
+        .as_array()
+        .ok_or(EmbeddingsError::InvalidResponse)?;
+    let mut vectors: Vec<Option<Vec<f32>>> = vec![None; expected];
+    for (position, item) in data.iter().enumerate() {
+        // Entries carry their input index, which is not guaranteed to match their position
+        let index = item["index"].as_u64().map_or(position, |i| i as usize);
+        let vector = item["embedding"]
+            .as_array()
+            .ok_or(EmbeddingsError::InvalidResponse)?
+            .iter()
+            .map(|v| v.as_f64().map(|v| v as f32))
+            .collect::<Option<Vec<f32>>>()
+            .ok_or(EmbeddingsError::InvalidResponse)?;
+        *vectors
+            .get_mut(index)
+            .ok_or(EmbeddingsError::InvalidResponse)? = Some(vector);
+    }
+    vectors
+        .into_iter()
+        .collect::<Option<Vec<_>>>()
+        .ok_or(EmbeddingsError::InvalidResponse)
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+/// Vectors are stored as little-endian f32 blobs
+pub fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+pub fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_embeddings_orders_by_index() {
+        let body = json!({
+            "data": [
+                {"index": 1, "embedding": [0.0, 1.0]},
+                {"index": 0, "embedding": [1.0, 0.0]}
+            ]
+        });
+        let vectors = parse_embeddings(&body, 2).unwrap();
+        assert_eq!(vectors, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+        assert!(parse_embeddings(&body, 3).is_err());
+    }
+
+    #[test]
+    fn test_vector_round_trip_and_similarity() {
+        let vector = vec![0.25, -1.5, 3.0];
+        assert_eq!(decode_vector(&encode_vector(&vector)), vector);
+        assert!((cosine_similarity(&vector, &vector) - 1.0).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), 0.0);
+    }
+}
@@ -0,0 +1,285 @@
+//! Entries of a task's coding agent conversations bookmarked for later, exported together
+//! as Markdown into the task's description or a comment on its pull request. A comment is
+//! redacted even if log redaction is turned off, as it's read outside vibe-kanban.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    executor::NormalizedEntry,
+    models::{
+        config::GitHubConfig, entry_bookmark::EntryBookmark, execution_process::ExecutionProcess,
+        project::Project, task::Task, task_attempt::TaskAttempt,
+    },
+    routes::task_attempts::normalize_full_process_logs,
+    services::{
+        conversation_export,
+        git_host::{self, GitHostError},
+        log_redaction,
+    },
+};
+
+/// Marks where exported bookmarks start and end in a description, so exporting again
+/// replaces them
+const SECTION_START: &str = "<!-- vibe-kanban:bookmarks -->";
+const SECTION_END: &str = "<!-- /vibe-kanban:bookmarks -->";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum BookmarkExportTarget {
+    Description,
+    PullRequest,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct ExportEntryBookmarks {
+    pub target: BookmarkExportTarget,
+    /// The bookmarks to export; `None` exports all of the task's
+    #[serde(default)]
+    pub bookmark_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct ExportedEntryBookmarks {
+    pub exported: usize,
+    pub markdown: String,
+    /// The pull request commented on
+    pub pull_request_url: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum BookmarkExportError {
+    NoBookmarks,
+    NoPullRequest,
+    GitHost(GitHostError),
+    Database(sqlx::Error),
+}
+
+impl std::fmt::Display for BookmarkExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BookmarkExportError::NoBookmarks => write!(f, "There are no bookmarks to export"),
+            BookmarkExportError::NoPullRequest => {
+                write!(f, "None of the task's attempts has a pull request")
+            }
+            BookmarkExportError::GitHost(e) => write!(f, "{}", e),
+            BookmarkExportError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for BookmarkExportError {}
+
+impl From<GitHostError> for BookmarkExportError {
+    fn from(err: GitHostError) -> Self {
+        BookmarkExportError::GitHost(err)
+    }
+}
+
+impl From<sqlx::Error> for BookmarkExportError {
+    fn from(err: sqlx::Error) -> Self {
+        BookmarkExportError::Database(err)
+    }
+}
+
+/// The entry with id `entry_id` in the conversation of a run of the task, in full. `None`
+/// if the run isn't the task's or has no such entry.
+pub async fn find_entry(
+    pool: &DbPool,
+    task_id: Uuid,
+    execution_process_id: Uuid,
+    entry_id: &str,
+) -> Result<Option<NormalizedEntry>, sqlx::Error> {
+    let Some(process) = ExecutionProcess::find_by_id(pool, execution_process_id).await? else {
+        return Ok(None);
+    };
+    match TaskAttempt::find_by_id(pool, process.task_attempt_id).await? {
+        Some(attempt) if attempt.task_id == task_id => {}
+        _ => return Ok(None),
+    }
+    let conversation = normalize_full_process_logs(pool, &process).await;
+    Ok(conversation
+        .entries
+        .into_iter()
+        .find(|entry| entry.id.as_deref() == Some(entry_id)))
+}
+
+/// The bookmarks as a Markdown section, each entry under its note
+pub fn render(bookmarks: &[EntryBookmark]) -> String {
+    let mut markdown = String::from("## Bookmarks\n");
+    for bookmark in bookmarks {
+        markdown.push('\n');
+        if let Some(note) = &bookmark.note {
+            markdown.push_str(&format!("**{}**\n\n", note));
+        }
+        markdown.push_str(&conversation_export::render_entry(&bookmark.entry));
+    }
+    markdown
+}
+
+/// `description` with `markdown` in place of the bookmarks exported into it before, or
+/// else after the rest of it
+pub fn with_section(description: Option<&str>, markdown: &str) -> String {
+    let section = format!("{}\n{}{}", SECTION_START, markdown, SECTION_END);
+    let Some(description) = description.filter(|d| !d.trim().is_empty()) else {
+        return section;
+    };
+    match (
+        description.find(SECTION_START),
+        description.find(SECTION_END),
+    ) {
+        (Some(start), Some(end)) if end > start => format!(
+            "{}{}{}",
+            &description[..start],
+            section,
+            &description[end + SECTION_END.len()..]
+        ),
+        _ => format!("{}\n\n{}", description.trim_end(), section),
+    }
+}
+
+/// Export the task's bookmarks, or those of `request.bookmark_ids`, in the order they
+/// were made. A pull request comment goes on the pull request of the task's latest attempt
+/// that opened one.
+pub async fn export(
+    pool: &DbPool,
+    github: &GitHubConfig,
+    project: &Project,
+    task: &Task,
+    request: &ExportEntryBookmarks,
+) -> Result<ExportedEntryBookmarks, BookmarkExportError> {
+    let bookmarks: Vec<EntryBookmark> = EntryBookmark::find_by_task_id(pool, task.id)
+        .await?
+        .into_iter()
+        .filter(|bookmark| {
+            request
+                .bookmark_ids
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&bookmark.id))
+        })
+        .collect();
+    if bookmarks.is_empty() {
+        return Err(BookmarkExportError::NoBookmarks);
+    }
+
+    let markdown = render(&bookmarks);
+    let pull_request_url = match request.target {
+        BookmarkExportTarget::Description => {
+            Task::update(
+                pool,
+                task.id,
+                project.id,
+                task.title.clone(),
+                Some(with_section(task.description.as_deref(), &markdown)),
+                task.status.clone(),
+                task.parent_task_attempt,
+            )
+            .await?;
+            None
+        }
+        BookmarkExportTarget::PullRequest => {
+            let attempt = TaskAttempt::find_by_task_id(pool, task.id)
+                .await?
+                .into_iter()
+                .find(|attempt| attempt.pr_number.is_some())
+                .ok_or(BookmarkExportError::NoPullRequest)?;
+            let host =
+                git_host::for_project(pool, project.id, &project.git_repo_path, github).await?;
+            let comment = log_redaction::redact_enforced(&markdown);
+            host.comment_on_pull_request(attempt.pr_number.unwrap_or_default(), &comment)
+                .await?;
+            tracing::info!(
+                "Exported {} bookmarks of task {} to its pull request",
+                bookmarks.len(),
+                task.id
+            );
+            attempt.pr_url
+        }
+    };
+    Ok(ExportedEntryBookmarks {
+        exported: bookmarks.len(),
+        markdown,
+        pull_request_url,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::executor::NormalizedEntryType;
+
+    fn bookmark(
+        entry_type: NormalizedEntryType,
+        content: &str,
+        note: Option<&str>,
+    ) -> EntryBookmark {
+        EntryBookmark {
+            id: Uuid::new_v4(),
+            task_id: Uuid::new_v4(),
+            task_title: "Add search".to_string(),
+            execution_process_id: Uuid::new_v4(),
+            entry_id: "stdout-0".to_string(),
+            entry: NormalizedEntry {
+                id: Some("stdout-0".to_string()),
+                timestamp: None,
+                duration_ms: None,
+                entry_type,
+                content: content.to_string(),
+                metadata: None,
+            },
+            note: note.map(str::to_string),
+            created_by: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_bookmarks_render_under_their_notes() {
+        let markdown = render(&[
+            bookmark(
+                NormalizedEntryType::AssistantMessage,
+                "Use SQLite FTS rather than a search service",
+                Some("Key decision"),
+            ),
+            bookmark(
+                NormalizedEntryType::SystemMessage,
+                "Ran the migrations",
+                None,
+            ),
+        ]);
+        assert_eq!(
+            markdown,
+            "## Bookmarks\n\n**Key decision**\n\n**Assistant**\n\nUse SQLite FTS rather than a search service\n\n_Ran the migrations_\n"
+        );
+    }
+
+    #[test]
+    fn test_exporting_again_replaces_the_section() {
+        let first = with_section(Some("Add search to the board.\n"), "## Bookmarks\n\nOne\n");
+        assert_eq!(
+            first,
+            format!(
+                "Add search to the board.\n\n{}\n## Bookmarks\n\nOne\n{}",
+                SECTION_START, SECTION_END
+            )
+        );
+
+        let edited = format!("{}\n\nMore notes.", first);
+        let second = with_section(Some(&edited), "## Bookmarks\n\nTwo\n");
+        assert!(second.starts_with("Add search to the board.\n\n"));
+        assert!(second.contains("Two") && !second.contains("One"));
+        assert!(second.ends_with(&format!("{}\n\nMore notes.", SECTION_END)));
+
+        assert_eq!(
+            with_section(None, "x\n"),
+            format!("{}\nx\n{}", SECTION_START, SECTION_END)
+        );
+    }
+}
@@ -0,0 +1,153 @@
+//! Keeps single conversation entries small enough to send and render. An agent that cats a
+//! binary file or prints a huge JSON blob would otherwise produce an entry of megabytes, so
+//! such entries keep only their start (or nothing, for binary data) and record in their
+//! metadata what was cut. The full content is fetched on demand by the entry's index.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::executor::NormalizedEntry;
+
+/// Longest content an entry keeps, in bytes
+pub const MAX_ENTRY_BYTES: usize = 64 * 1024;
+/// How much of the content is looked at to tell whether it's binary
+const BINARY_SAMPLE_CHARS: usize = 4096;
+/// Fewest unprintable characters that make a sample binary, so a stray one doesn't
+const BINARY_MIN_UNPRINTABLE: usize = 4;
+
+/// What was left out of an entry's content
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TruncatedContent {
+    /// Length of the full content in bytes
+    pub original_bytes: usize,
+    /// The content looked like binary data, so none of it was kept
+    pub binary: bool,
+}
+
+/// Cuts down every entry whose content is binary or longer than [`MAX_ENTRY_BYTES`]
+pub fn limit_entries(entries: &mut [NormalizedEntry]) {
+    for entry in entries {
+        limit_entry(entry);
+    }
+}
+
+fn limit_entry(entry: &mut NormalizedEntry) {
+    let binary = looks_binary(&entry.content);
+    let original_bytes = entry.content.len();
+    if !binary && original_bytes <= MAX_ENTRY_BYTES {
+        return;
+    }
+
+    entry.content = if binary {
+        format!("[binary content, {}]", format_bytes(original_bytes))
+    } else {
+        let mut end = MAX_ENTRY_BYTES;
+        while !entry.content.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!(
+            "{}\n… [{} more not shown]",
+            &entry.content[..end],
+            format_bytes(original_bytes - end)
+        )
+    };
+    let truncated = serde_json::json!(TruncatedContent {
+        original_bytes,
+        binary,
+    });
+    match entry.metadata.as_mut().and_then(|m| m.as_object_mut()) {
+        Some(metadata) => {
+            metadata.insert("truncated".to_string(), truncated);
+        }
+        None => entry.metadata = Some(serde_json::json!({ "truncated": truncated })),
+    }
+}
+
+/// NUL bytes, or a tenth of the start being control characters or bytes that weren't valid
+/// UTF-8, which text never has
+fn looks_binary(content: &str) -> bool {
+    let mut sampled = 0;
+    let mut unprintable = 0;
+    for c in content.chars().take(BINARY_SAMPLE_CHARS) {
+        if c == '\0' {
+            return true;
+        }
+        sampled += 1;
+        if c == char::REPLACEMENT_CHARACTER || (c.is_control() && !matches!(c, '\n' | '\r' | '\t'))
+        {
+            unprintable += 1;
+        }
+    }
+    unprintable >= BINARY_MIN_UNPRINTABLE && unprintable * 10 >= sampled
+}
+
+fn format_bytes(bytes: usize) -> String {
+    match bytes {
+        b if b >= 1024 * 1024 => format!("{:.1} MB", b as f64 / (1024.0 * 1024.0)),
+        b if b >= 1024 => format!("{:.1} KB", b as f64 / 1024.0),
+        b => format!("{} bytes", b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::NormalizedEntryType;
+
+    fn entry(content: String) -> NormalizedEntry {
+        NormalizedEntry {
+            id: None,
+            timestamp: None,
+            duration_ms: None,
+            entry_type: NormalizedEntryType::SystemMessage,
+            content,
+            metadata: Some(serde_json::json!({ "usage": { "input_tokens": 3 } })),
+        }
+    }
+
+    fn truncation(entry: &NormalizedEntry) -> Option<TruncatedContent> {
+        let truncated = entry.metadata.as_ref()?.get("truncated")?;
+        serde_json::from_value(truncated.clone()).ok()
+    }
+
+    #[test]
+    fn test_large_and_binary_entries_are_cut_down() {
+        let blob = format!("{{\"rows\": [{}]}}", "é,".repeat(MAX_ENTRY_BYTES));
+        let png = String::from_utf8_lossy(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0\0\x01\0").to_string();
+        let mut entries = vec![
+            entry("All tests passed".to_string()),
+            entry(blob.clone()),
+            entry(png.clone()),
+        ];
+        limit_entries(&mut entries);
+
+        assert_eq!(entries[0].content, "All tests passed");
+        assert_eq!(truncation(&entries[0]), None);
+
+        assert!(entries[1].content.len() < MAX_ENTRY_BYTES + 64);
+        assert!(blob.starts_with(entries[1].content.split('\n').next().unwrap()));
+        assert!(entries[1].content.ends_with("more not shown]"));
+        assert_eq!(
+            truncation(&entries[1]),
+            Some(TruncatedContent {
+                original_bytes: blob.len(),
+                binary: false,
+            })
+        );
+        // Metadata it already had is kept
+        assert!(entries[1].usage_metrics().is_some());
+
+        assert_eq!(
+            entries[2].content,
+            format!("[binary content, {} bytes]", png.len())
+        );
+        assert!(truncation(&entries[2]).is_some_and(|t| t.binary));
+    }
+
+    #[test]
+    fn test_text_with_a_stray_control_character_is_not_binary() {
+        assert!(!looks_binary("warning: \u{1b} in output\nline two"));
+        assert!(looks_binary(&"\u{fffd}\u{fffd}ab".repeat(4)));
+    }
+}
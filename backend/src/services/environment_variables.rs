@@ -0,0 +1,348 @@
+//! The variables set in the environment of a project's executions. Secret values are
+//! encrypted with AES-256-GCM before they are stored, and replaced in an execution's output
+//! before it is stored, so they show up neither in the database nor in its logs.
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    path::Path,
+    sync::{Mutex, OnceLock},
+};
+
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    rand::{SecureRandom, SystemRandom},
+};
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    models::{
+        environment_variable::{EnvironmentVariable, UpsertEnvironmentVariable},
+        task::Task,
+    },
+    utils::asset_dir,
+};
+
+/// Key secrets are encrypted with, as 64 hex digits; otherwise one is kept in `secrets.key`
+pub const SECRETS_KEY_ENV: &str = "VIBE_KANBAN_SECRETS_KEY";
+
+/// What a secret's value is replaced with in an execution's output
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Marks the format of a stored secret, so it can change without losing older ones
+const CIPHERTEXT_PREFIX: &str = "v1:";
+
+lazy_static::lazy_static! {
+    static ref REDACTIONS: Mutex<HashMap<Uuid, Vec<String>>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug)]
+pub enum EnvironmentError {
+    Database(sqlx::Error),
+    /// The key secrets are encrypted with couldn't be read or created
+    Key(String),
+    /// A stored secret didn't decrypt, such as after the key changed
+    Decrypt {
+        key: String,
+    },
+    InvalidName(String),
+}
+
+impl std::fmt::Display for EnvironmentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EnvironmentError::Database(e) => write!(f, "Database error: {}", e),
+            EnvironmentError::Key(e) => write!(f, "Secrets key unavailable: {}", e),
+            EnvironmentError::Decrypt { key } => write!(
+                f,
+                "The secret {} can't be decrypted; set it again, or restore the key it was saved with",
+                key
+            ),
+            EnvironmentError::InvalidName(key) => write!(
+                f,
+                "{:?} isn't a valid variable name; use letters, digits and underscores, not starting with a digit",
+                key
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EnvironmentError {}
+
+impl From<sqlx::Error> for EnvironmentError {
+    fn from(err: sqlx::Error) -> Self {
+        EnvironmentError::Database(err)
+    }
+}
+
+/// Encrypts and decrypts secret values with one key
+pub struct SecretCipher {
+    key: LessSafeKey,
+    rng: SystemRandom,
+}
+
+impl SecretCipher {
+    pub fn new(key: &[u8; 32]) -> Self {
+        Self {
+            key: LessSafeKey::new(
+                UnboundKey::new(&AES_256_GCM, key).expect("an AES-256 key is 32 bytes"),
+            ),
+            rng: SystemRandom::new(),
+        }
+    }
+
+    /// Encrypt the value of the variable `name`. The ciphertext only decrypts as that
+    /// variable's, so it can't be moved to another.
+    pub fn encrypt(&self, name: &str, value: &str) -> String {
+        let mut nonce = [0u8; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .expect("the system random number generator failed");
+        let mut sealed = value.as_bytes().to_vec();
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(name.as_bytes()),
+                &mut sealed,
+            )
+            .expect("AES-GCM sealing doesn't fail for a value this size");
+        format!(
+            "{}{}{}",
+            CIPHERTEXT_PREFIX,
+            hex::encode(nonce),
+            hex::encode(sealed)
+        )
+    }
+
+    /// Decrypt the stored value of the variable `name`, or `None` if it wasn't encrypted
+    /// with this key for that variable
+    pub fn decrypt(&self, name: &str, stored: &str) -> Option<String> {
+        let bytes = hex::decode(stored.strip_prefix(CIPHERTEXT_PREFIX)?).ok()?;
+        if bytes.len() < NONCE_LEN {
+            return None;
+        }
+        let (nonce, sealed) = bytes.split_at(NONCE_LEN);
+        let mut sealed = sealed.to_vec();
+        let value = self
+            .key
+            .open_in_place(
+                Nonce::try_assume_unique_for_key(nonce).ok()?,
+                Aad::from(name.as_bytes()),
+                &mut sealed,
+            )
+            .ok()?;
+        String::from_utf8(value.to_vec()).ok()
+    }
+}
+
+/// The cipher for stored secrets, loading or creating its key on first use
+pub(crate) fn cipher() -> Result<&'static SecretCipher, EnvironmentError> {
+    static CIPHER: OnceLock<SecretCipher> = OnceLock::new();
+    if let Some(cipher) = CIPHER.get() {
+        return Ok(cipher);
+    }
+    let key = match std::env::var(SECRETS_KEY_ENV) {
+        Ok(hex_key) if !hex_key.is_empty() => parse_key(&hex_key).ok_or_else(|| {
+            EnvironmentError::Key(format!("{} isn't 64 hex digits", SECRETS_KEY_ENV))
+        })?,
+        _ => load_or_create_key_file(&asset_dir().join("secrets.key"))?,
+    };
+    Ok(CIPHER.get_or_init(|| SecretCipher::new(&key)))
+}
+
+fn parse_key(hex_key: &str) -> Option<[u8; 32]> {
+    hex::decode(hex_key.trim()).ok()?.try_into().ok()
+}
+
+fn load_or_create_key_file(path: &Path) -> Result<[u8; 32], EnvironmentError> {
+    if path.exists() {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| EnvironmentError::Key(format!("{}: {}", path.display(), e)))?;
+        return parse_key(&content).ok_or_else(|| {
+            EnvironmentError::Key(format!("{} isn't 64 hex digits", path.display()))
+        });
+    }
+
+    let mut key = [0u8; 32];
+    SystemRandom::new()
+        .fill(&mut key)
+        .map_err(|_| EnvironmentError::Key("the system random number generator failed".into()))?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| EnvironmentError::Key(format!("{}: {}", parent.display(), e)))?;
+    }
+    std::fs::write(path, hex::encode(key))
+        .map_err(|e| EnvironmentError::Key(format!("{}: {}", path.display(), e)))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600));
+    }
+    tracing::info!("Created the key for stored secrets at {}", path.display());
+    Ok(key)
+}
+
+/// Whether `key` can be an environment variable's name
+pub fn is_valid_name(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Set a variable for the project's executions, or for `task_id`'s alone
+pub async fn save(
+    pool: &DbPool,
+    project_id: Uuid,
+    task_id: Option<Uuid>,
+    data: &UpsertEnvironmentVariable,
+) -> Result<EnvironmentVariable, EnvironmentError> {
+    if !is_valid_name(&data.key) {
+        return Err(EnvironmentError::InvalidName(data.key.clone()));
+    }
+    let value = if data.is_secret {
+        Cow::Owned(cipher()?.encrypt(&data.key, &data.value))
+    } else {
+        Cow::Borrowed(data.value.as_str())
+    };
+    Ok(
+        EnvironmentVariable::upsert(pool, project_id, task_id, &data.key, &value, data.is_secret)
+            .await?,
+    )
+}
+
+/// A task's variables, ready to be set in its executions' environment
+#[derive(Debug, Default)]
+pub struct ResolvedEnvironment {
+    pub vars: Vec<(String, String)>,
+    /// The values of the variables that are secrets
+    pub secrets: Vec<String>,
+}
+
+/// The variables `task`'s executions get, with the task's own overriding the project's
+pub async fn resolve(pool: &DbPool, task: &Task) -> Result<ResolvedEnvironment, EnvironmentError> {
+    let mut values: Vec<(String, String, bool)> = Vec::new();
+    for variable in EnvironmentVariable::find_for_task(pool, task.project_id, task.id).await? {
+        let value = if variable.is_secret {
+            cipher()?
+                .decrypt(&variable.key, &variable.value)
+                .ok_or_else(|| EnvironmentError::Decrypt {
+                    key: variable.key.clone(),
+                })?
+        } else {
+            variable.value
+        };
+        match values.iter_mut().find(|(key, _, _)| *key == variable.key) {
+            Some(existing) => *existing = (variable.key, value, variable.is_secret),
+            None => values.push((variable.key, value, variable.is_secret)),
+        }
+    }
+
+    let mut resolved = ResolvedEnvironment::default();
+    for (key, value, is_secret) in values {
+        if is_secret && !value.is_empty() {
+            resolved.secrets.push(value.clone());
+        }
+        resolved.vars.push((key, value));
+    }
+    Ok(resolved)
+}
+
+/// Redact `secrets` from whatever output of the execution is stored from now on
+pub fn register_redactions(execution_id: Uuid, mut secrets: Vec<String>) {
+    if secrets.is_empty() {
+        return;
+    }
+    // A secret containing another is replaced first, so none of it is left behind
+    secrets.sort_by_key(|secret| std::cmp::Reverse(secret.len()));
+    REDACTIONS.lock().unwrap().insert(execution_id, secrets);
+}
+
+/// Stop redacting an execution's output once all of it has been stored
+pub fn forget_redactions(execution_id: Uuid) {
+    REDACTIONS.lock().unwrap().remove(&execution_id);
+}
+
+/// `output` of the execution with its secrets replaced by [`REDACTED`]
+pub fn redact(execution_id: Uuid, output: &str) -> Cow<'_, str> {
+    let redactions = REDACTIONS.lock().unwrap();
+    match redactions.get(&execution_id) {
+        Some(secrets) => redact_values(output, secrets),
+        None => Cow::Borrowed(output),
+    }
+}
+
+fn redact_values<'a>(output: &'a str, secrets: &[String]) -> Cow<'a, str> {
+    let mut redacted = Cow::Borrowed(output);
+    for secret in secrets {
+        if redacted.contains(secret.as_str()) {
+            redacted = Cow::Owned(redacted.replace(secret.as_str(), REDACTED));
+        }
+    }
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_round_trips_only_for_its_variable() {
+        let cipher = SecretCipher::new(&[7; 32]);
+        let stored = cipher.encrypt("API_TOKEN", "s3cr3t");
+        assert!(stored.starts_with(CIPHERTEXT_PREFIX));
+        assert!(!stored.contains("s3cr3t"));
+        assert_ne!(stored, cipher.encrypt("API_TOKEN", "s3cr3t"));
+
+        assert_eq!(
+            cipher.decrypt("API_TOKEN", &stored),
+            Some("s3cr3t".to_string())
+        );
+        assert_eq!(cipher.decrypt("OTHER_TOKEN", &stored), None);
+        assert_eq!(
+            SecretCipher::new(&[8; 32]).decrypt("API_TOKEN", &stored),
+            None
+        );
+        assert_eq!(cipher.decrypt("API_TOKEN", "s3cr3t"), None);
+    }
+
+    #[test]
+    fn test_redaction_replaces_longest_secret_first() {
+        let execution_id = Uuid::new_v4();
+        assert_eq!(redact(execution_id, "token abc"), "token abc");
+
+        register_redactions(execution_id, vec!["abc".to_string(), "abcdef".to_string()]);
+        assert_eq!(
+            redact(execution_id, "{\"token\":\"abcdef\",\"short\":\"abc\"}"),
+            "{\"token\":\"[REDACTED]\",\"short\":\"[REDACTED]\"}"
+        );
+
+        forget_redactions(execution_id);
+        assert_eq!(redact(execution_id, "abc"), "abc");
+    }
+
+    #[test]
+    fn test_variable_names() {
+        assert!(is_valid_name("API_TOKEN"));
+        assert!(is_valid_name("_private2"));
+        assert!(!is_valid_name("2FA"));
+        assert!(!is_valid_name("MY-VAR"));
+        assert!(!is_valid_name(""));
+    }
+
+    #[test]
+    fn test_key_file_is_created_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys").join("secrets.key");
+        let key = load_or_create_key_file(&path).unwrap();
+        assert_eq!(load_or_create_key_file(&path).unwrap(), key);
+
+        std::fs::write(&path, "not a key").unwrap();
+        assert!(matches!(
+            load_or_create_key_file(&path),
+            Err(EnvironmentError::Key(_))
+        ));
+    }
+}
@@ -0,0 +1,483 @@
+//! Concurrency limits on running attempts. An attempt started while a limit is reached
+//! waits in the execution queue, its task showing as queued, and is started once enough
+//! running attempts have finished. One whose executor's provider is rate limiting waits
+//! the same way until the limit resets, and so does one whose project's guardrails hold it
+//! back, until the project is back under its budget or inside its working hours.
+//!
+//! The queue is ordered by task priority, then by when attempts were queued, unless it's
+//! been reordered by hand. Under [`PreemptionPolicy::PauseLowerPriority`] the first attempt
+//! held back pauses a coding agent of a lower priority task in its way, whose attempt is
+//! queued again and resumed once it's dispatched.
+
+use std::collections::{HashMap, HashSet};
+
+use tokio::sync::{Mutex, MutexGuard};
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    models::{
+        config::{ExecutionLimits, PreemptionPolicy},
+        execution_process::ExecutionProcess,
+        execution_queue::{QueuedAttempt, RunningAgent, RunningAttempts},
+        task::{Task, TaskPriority, TaskStatus},
+        task_attempt::TaskAttempt,
+    },
+    services::{guardrails, ProcessService},
+};
+
+lazy_static::lazy_static! {
+    /// Held from counting the running attempts until the dispatched ones have started, so
+    /// two dispatches can't both take the last slot
+    static ref DISPATCH_LOCK: Mutex<()> = Mutex::new(());
+}
+
+pub async fn lock() -> MutexGuard<'static, ()> {
+    DISPATCH_LOCK.lock().await
+}
+
+/// The queued attempts that fit under the limits now, in queue order, leaving those of the
+/// executors in `cooling_down` and of the projects in `held`. An attempt held back by its
+/// project's limit doesn't hold back other projects' attempts behind it.
+pub fn dispatchable<'a>(
+    limits: &ExecutionLimits,
+    queue: &'a [QueuedAttempt],
+    running: &[RunningAttempts],
+    cooling_down: &HashSet<String>,
+    held: &HashSet<Uuid>,
+) -> Vec<&'a QueuedAttempt> {
+    let mut per_project: HashMap<Uuid, i64> = running
+        .iter()
+        .map(|running| (running.project_id, running.count))
+        .collect();
+    let mut total: i64 = per_project.values().sum();
+
+    let mut dispatched = Vec::new();
+    for queued in queue {
+        if queued
+            .executor
+            .as_ref()
+            .is_some_and(|executor| cooling_down.contains(executor))
+            || held.contains(&queued.project_id)
+        {
+            continue;
+        }
+        if limits
+            .max_running
+            .is_some_and(|max| total >= i64::from(max))
+        {
+            break;
+        }
+        let in_project = per_project.entry(queued.project_id).or_default();
+        if limits
+            .max_running_per_project
+            .is_some_and(|max| *in_project >= i64::from(max))
+        {
+            continue;
+        }
+        *in_project += 1;
+        total += 1;
+        dispatched.push(queued);
+    }
+    dispatched
+}
+
+/// The coding agent to pause so `blocked` fits: one of a lower priority task, of its own
+/// project when that project's limit is what holds it back. The lowest priority goes first,
+/// and of those the one in `agents` that comes first.
+pub fn preemption_victim<'a>(
+    limits: &ExecutionLimits,
+    blocked: &QueuedAttempt,
+    running: &[RunningAttempts],
+    agents: &'a [RunningAgent],
+) -> Option<&'a RunningAgent> {
+    let in_project = running
+        .iter()
+        .find(|running| running.project_id == blocked.project_id)
+        .map_or(0, |running| running.count);
+    let project_full = limits
+        .max_running_per_project
+        .is_some_and(|max| in_project >= i64::from(max));
+    let rank = TaskPriority::rank(blocked.priority);
+    agents
+        .iter()
+        .filter(|agent| TaskPriority::rank(agent.priority) < rank)
+        .filter(|agent| !project_full || agent.project_id == blocked.project_id)
+        .min_by_key(|agent| TaskPriority::rank(agent.priority))
+}
+
+/// What the queue and the running attempts look like to a dispatch
+struct QueueState {
+    limits: ExecutionLimits,
+    queue: Vec<QueuedAttempt>,
+    running: Vec<RunningAttempts>,
+    cooling_down: HashSet<String>,
+    /// Projects whose guardrails hold their attempts back
+    held: HashSet<Uuid>,
+}
+
+impl QueueState {
+    async fn load(app_state: &AppState) -> Result<Self, sqlx::Error> {
+        let limits = app_state.get_config().read().await.execution_limits.clone();
+        let queue = QueuedAttempt::find_all(&app_state.db_pool).await?;
+        if queue.is_empty() {
+            return Ok(Self {
+                limits,
+                queue,
+                running: Vec::new(),
+                cooling_down: HashSet::new(),
+                held: HashSet::new(),
+            });
+        }
+        let running = QueuedAttempt::count_running(&app_state.db_pool).await?;
+        let cooling_down = ExecutionProcess::find_cooling_down_executors(&app_state.db_pool)
+            .await?
+            .into_iter()
+            .collect();
+        let project_ids: Vec<Uuid> = queue.iter().map(|queued| queued.project_id).collect();
+        let held = guardrails::holds(&app_state.db_pool, &project_ids)
+            .await?
+            .into_keys()
+            .collect();
+        Ok(Self {
+            limits,
+            queue,
+            running,
+            cooling_down,
+            held,
+        })
+    }
+
+    fn dispatchable(&self) -> Vec<&QueuedAttempt> {
+        dispatchable(
+            &self.limits,
+            &self.queue,
+            &self.running,
+            &self.cooling_down,
+            &self.held,
+        )
+    }
+}
+
+/// Queued attempts to start now under the configured limits. The caller holds [`lock`].
+pub async fn find_dispatchable(app_state: &AppState) -> Result<Vec<QueuedAttempt>, sqlx::Error> {
+    let state = QueueState::load(app_state).await?;
+    Ok(state.dispatchable().into_iter().cloned().collect())
+}
+
+/// Pause a coding agent for the first queued attempt held back by the limits, if the policy
+/// allows it and there's a lower priority one in its way. Attempts that were themselves
+/// paused wait their turn rather than pausing others, and those their project's guardrails
+/// hold back couldn't take the slot. The caller holds [`lock`].
+async fn preempt_for_queue(app_state: &AppState) -> Result<(), sqlx::Error> {
+    let state = QueueState::load(app_state).await?;
+    if state.limits.preemption != PreemptionPolicy::PauseLowerPriority {
+        return Ok(());
+    }
+    let dispatchable = state.dispatchable();
+    let Some(blocked) = state.queue.iter().find(|queued| {
+        !dispatchable.contains(queued)
+            && queued.paused_process_id.is_none()
+            && !state.held.contains(&queued.project_id)
+            && !queued
+                .executor
+                .as_ref()
+                .is_some_and(|executor| state.cooling_down.contains(executor))
+    }) else {
+        return Ok(());
+    };
+    let agents = QueuedAttempt::find_preemptible(&app_state.db_pool).await?;
+    let Some(victim) = preemption_victim(&state.limits, blocked, &state.running, &agents) else {
+        return Ok(());
+    };
+
+    match app_state
+        .pause_running_execution_by_id(victim.execution_process_id)
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => return Ok(()),
+        Err(e) => {
+            tracing::error!(
+                "Failed to pause execution {} for attempt {}: {}",
+                victim.execution_process_id,
+                blocked.task_attempt_id,
+                e
+            );
+            return Ok(());
+        }
+    }
+    ExecutionProcess::set_paused(&app_state.db_pool, victim.execution_process_id, true).await?;
+    QueuedAttempt::enqueue_paused(
+        &app_state.db_pool,
+        victim.task_attempt_id,
+        victim.project_id,
+        victim.execution_process_id,
+    )
+    .await?;
+    Task::update_status(
+        &app_state.db_pool,
+        victim.task_id,
+        victim.project_id,
+        TaskStatus::Queued,
+    )
+    .await?;
+    tracing::info!(
+        "Paused attempt {} to make room for attempt {}",
+        victim.task_attempt_id,
+        blocked.task_attempt_id
+    );
+    Ok(())
+}
+
+/// Resume a coding agent that was paused to make room, now that it's been dispatched.
+/// Returns false if it's no longer there to resume, as after a restart.
+async fn resume_paused(app_state: &AppState, task: &Task, execution_process_id: Uuid) -> bool {
+    match app_state
+        .resume_running_execution_by_id(execution_process_id)
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => return false,
+        Err(e) => {
+            tracing::error!("Failed to resume execution {}: {}", execution_process_id, e);
+            return true;
+        }
+    }
+    if let Err(e) =
+        ExecutionProcess::set_paused(&app_state.db_pool, execution_process_id, false).await
+    {
+        tracing::error!(
+            "Failed to mark execution {} resumed: {}",
+            execution_process_id,
+            e
+        );
+    }
+    if let Err(e) = Task::update_status(
+        &app_state.db_pool,
+        task.id,
+        task.project_id,
+        TaskStatus::InProgress,
+    )
+    .await
+    {
+        tracing::error!("Failed to update the status of task {}: {}", task.id, e);
+    }
+    true
+}
+
+/// Start, or resume, every queued attempt there is now a slot for
+pub async fn dispatch_queued(app_state: &AppState) {
+    let _guard = lock().await;
+    if let Err(e) = preempt_for_queue(app_state).await {
+        tracing::error!("Failed to make room for a queued attempt: {}", e);
+    }
+    let queued = match find_dispatchable(app_state).await {
+        Ok(queued) => queued,
+        Err(e) => {
+            tracing::error!("Failed to check the execution queue: {}", e);
+            return;
+        }
+    };
+
+    for queued in queued {
+        let attempt_id = queued.task_attempt_id;
+        if let Err(e) = QueuedAttempt::remove(&app_state.db_pool, attempt_id).await {
+            tracing::error!("Failed to take attempt {} off the queue: {}", attempt_id, e);
+            continue;
+        }
+        let task = match TaskAttempt::find_by_id(&app_state.db_pool, attempt_id).await {
+            Ok(Some(attempt)) => Task::find_by_id(&app_state.db_pool, attempt.task_id)
+                .await
+                .ok()
+                .flatten(),
+            _ => None,
+        };
+        let Some(task) = task else {
+            continue;
+        };
+        if let Some(execution_process_id) = queued.paused_process_id {
+            tracing::info!("Resuming queued attempt {}", attempt_id);
+            if resume_paused(app_state, &task, execution_process_id).await {
+                continue;
+            }
+            tracing::info!(
+                "Execution {} of attempt {} is gone, starting the attempt again",
+                execution_process_id,
+                attempt_id
+            );
+        }
+
+        tracing::info!("Starting queued attempt {}", attempt_id);
+        if let Err(e) = ProcessService::start_execution_now(
+            &app_state.db_pool,
+            app_state,
+            attempt_id,
+            task.id,
+            task.project_id,
+        )
+        .await
+        {
+            tracing::error!("Failed to start queued attempt {}: {}", attempt_id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queued(project_id: Uuid) -> QueuedAttempt {
+        QueuedAttempt {
+            task_attempt_id: Uuid::new_v4(),
+            project_id,
+            executor: Some("claude".to_string()),
+            priority: None,
+            paused_process_id: None,
+        }
+    }
+
+    fn agent(project_id: Uuid, priority: Option<TaskPriority>) -> RunningAgent {
+        RunningAgent {
+            execution_process_id: Uuid::new_v4(),
+            task_attempt_id: Uuid::new_v4(),
+            task_id: Uuid::new_v4(),
+            project_id,
+            priority,
+        }
+    }
+
+    fn ids(queued: Vec<&QueuedAttempt>) -> Vec<Uuid> {
+        queued.into_iter().map(|q| q.task_attempt_id).collect()
+    }
+
+    #[test]
+    fn test_dispatch_respects_global_and_project_limits() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let queue = vec![queued(a), queued(a), queued(b), queued(b)];
+        let running = vec![RunningAttempts {
+            project_id: a,
+            count: 1,
+        }];
+
+        let limits = ExecutionLimits {
+            max_running: Some(3),
+            max_running_per_project: Some(2),
+            ..Default::default()
+        };
+        // Project a has one slot left, then b takes the last global slot
+        assert_eq!(
+            ids(dispatchable(
+                &limits,
+                &queue,
+                &running,
+                &HashSet::new(),
+                &HashSet::new()
+            )),
+            vec![queue[0].task_attempt_id, queue[2].task_attempt_id]
+        );
+
+        let unlimited = ExecutionLimits::default();
+        assert_eq!(
+            dispatchable(
+                &unlimited,
+                &queue,
+                &running,
+                &HashSet::new(),
+                &HashSet::new()
+            )
+            .len(),
+            4
+        );
+
+        let full = ExecutionLimits {
+            max_running: Some(1),
+            max_running_per_project: None,
+            ..Default::default()
+        };
+        assert!(dispatchable(&full, &queue, &running, &HashSet::new(), &HashSet::new()).is_empty());
+    }
+
+    #[test]
+    fn test_dispatch_holds_back_executors_cooling_down() {
+        let project = Uuid::new_v4();
+        let mut gemini = queued(project);
+        gemini.executor = Some("gemini".to_string());
+        let queue = vec![queued(project), gemini];
+
+        let cooling_down = HashSet::from(["claude".to_string()]);
+        assert_eq!(
+            ids(dispatchable(
+                &ExecutionLimits::default(),
+                &queue,
+                &[],
+                &cooling_down,
+                &HashSet::new()
+            )),
+            vec![queue[1].task_attempt_id]
+        );
+    }
+
+    #[test]
+    fn test_dispatch_holds_back_projects_held_by_their_guardrails() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let queue = vec![queued(a), queued(b), queued(a)];
+        let limits = ExecutionLimits {
+            max_running: Some(1),
+            ..Default::default()
+        };
+
+        // A held project doesn't take the slot from the one behind it
+        assert_eq!(
+            ids(dispatchable(
+                &limits,
+                &queue,
+                &[],
+                &HashSet::new(),
+                &HashSet::from([a])
+            )),
+            vec![queue[1].task_attempt_id]
+        );
+    }
+
+    #[test]
+    fn test_preemption_pauses_the_lowest_priority_agent_in_the_way() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut blocked = queued(a);
+        blocked.priority = Some(TaskPriority::High);
+        let agents = vec![
+            agent(b, None),
+            agent(a, Some(TaskPriority::Urgent)),
+            agent(b, Some(TaskPriority::Low)),
+            agent(a, Some(TaskPriority::Medium)),
+        ];
+        let running = vec![
+            RunningAttempts {
+                project_id: a,
+                count: 2,
+            },
+            RunningAttempts {
+                project_id: b,
+                count: 2,
+            },
+        ];
+
+        let global = ExecutionLimits {
+            max_running: Some(4),
+            ..Default::default()
+        };
+        let victim = preemption_victim(&global, &blocked, &running, &agents).unwrap();
+        assert_eq!(victim.execution_process_id, agents[2].execution_process_id);
+
+        // Only an agent of its own project frees a slot under the project limit
+        let per_project = ExecutionLimits {
+            max_running_per_project: Some(2),
+            ..Default::default()
+        };
+        let victim = preemption_victim(&per_project, &blocked, &running, &agents).unwrap();
+        assert_eq!(victim.execution_process_id, agents[3].execution_process_id);
+
+        blocked.priority = None;
+        assert!(preemption_victim(&per_project, &blocked, &running, &agents).is_none());
+    }
+}
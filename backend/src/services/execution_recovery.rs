@@ -0,0 +1,247 @@
+//! Runs cut off by the server stopping. Their processes can't be attached to again, as their
+//! output went to pipes that closed with the server, so when it starts again whatever is
+//! left of their process groups is killed and the runs are marked interrupted. A group is
+//! only killed while the process that led it is still the one the run started; if its id
+//! has gone to another process since, the run is marked failed and the group left alone. Coding agents
+//! are resumed as follow-ups when `resume_interrupted_runs` is on; otherwise, as for a setup
+//! script, their task is left for review.
+
+use crate::{
+    app_state::AppState,
+    models::{
+        execution_process::{
+            ExecutionProcess, ExecutionProcessStatus, ExecutionProcessType, ProcessGroup,
+        },
+        execution_queue::QueuedAttempt,
+        task::{Task, TaskStatus},
+        task_attempt::TaskAttempt,
+    },
+    services::ProcessService,
+};
+
+/// Sent as the follow-up when an interrupted coding agent is resumed
+pub const RESUME_PROMPT: &str = "The server running you restarted and cut your last run short. Check what you had done so far and continue the task from where you left off.";
+
+/// What becomes of the task of an interrupted run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recovery {
+    /// Resume the coding agent as a follow-up
+    Resume,
+    /// Leave the task for review
+    Review,
+    /// Leave the task as it is
+    Nothing,
+}
+
+/// The recovery of an interrupted run. Attempts in the execution queue are started again
+/// from there, so they're left as they are.
+pub fn recovery_for(process_type: &ExecutionProcessType, resume: bool, queued: bool) -> Recovery {
+    match process_type {
+        _ if queued => Recovery::Nothing,
+        ExecutionProcessType::CodingAgent if resume => Recovery::Resume,
+        ExecutionProcessType::CodingAgent | ExecutionProcessType::SetupScript => Recovery::Review,
+        ExecutionProcessType::CleanupScript | ExecutionProcessType::DevServer => Recovery::Nothing,
+    }
+}
+
+/// What was left of an interrupted run's process group
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Reaped {
+    /// Nothing; it ended with the server
+    Gone,
+    /// It was still running and has been killed
+    Killed,
+    /// A group has its id, but its leader isn't the run's process, so it was left alone
+    NotOurs,
+}
+
+/// The boot the process started in and its start time since boot, which together tell it
+/// from any later process given the same id. Only known on Linux.
+#[cfg(target_os = "linux")]
+pub fn process_identity(pid: u32) -> Option<String> {
+    let boot_id = std::fs::read_to_string("/proc/sys/kernel/random/boot_id").ok()?;
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // The command name before the other fields is in parentheses and may hold spaces
+    let fields = &stat[stat.rfind(')')? + 1..];
+    // The start time is the 22nd field, the 20th after the command name
+    let start_time = fields.split_whitespace().nth(19)?;
+    Some(format!("{}:{}", boot_id.trim(), start_time))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn process_identity(_pid: u32) -> Option<String> {
+    None
+}
+
+/// Kill what's left of a process group, continuing it first in case it was paused, as long
+/// as it is still led by the process the run started
+#[cfg(unix)]
+fn reap(group: &ProcessGroup) -> Reaped {
+    use nix::{
+        sys::signal::{killpg, Signal},
+        unistd::{getpgrp, Pid},
+    };
+
+    let Ok(leader) = u32::try_from(group.process_group) else {
+        return Reaped::Gone;
+    };
+    let process_group = Pid::from_raw(leader as i32);
+    if process_group.as_raw() <= 1 || killpg(process_group, None).is_err() {
+        return Reaped::Gone;
+    }
+    // The id may have been taken by another group since, so never let that be our own, nor
+    // any group whose leader isn't provably the run's
+    let ours =
+        group.process_identity.is_some() && group.process_identity == process_identity(leader);
+    if process_group == getpgrp() || !ours {
+        return Reaped::NotOurs;
+    }
+    let _ = killpg(process_group, Signal::SIGCONT);
+    if killpg(process_group, Signal::SIGKILL).is_ok() {
+        Reaped::Killed
+    } else {
+        Reaped::Gone
+    }
+}
+
+#[cfg(not(unix))]
+fn reap(_group: &ProcessGroup) -> Reaped {
+    Reaped::Gone
+}
+
+/// Recover every execution still marked running or paused. Call at startup, before the
+/// execution monitor takes those for orphans.
+pub async fn recover(app_state: &AppState) {
+    let pool = &app_state.db_pool;
+    let processes = match ExecutionProcess::find_running(pool).await {
+        Ok(processes) => processes,
+        Err(e) => {
+            tracing::error!("Failed to query interrupted executions: {}", e);
+            return;
+        }
+    };
+    if processes.is_empty() {
+        return;
+    }
+    let resume = app_state.get_config().read().await.resume_interrupted_runs;
+    let queued = match QueuedAttempt::find_all(pool).await {
+        Ok(queued) => queued,
+        Err(e) => {
+            tracing::error!("Failed to query the execution queue: {}", e);
+            Vec::new()
+        }
+    };
+
+    tracing::warn!("Recovering {} interrupted execution(s)", processes.len());
+    for process in processes {
+        let reaped = match ExecutionProcess::find_process_group(pool, process.id).await {
+            Ok(Some(group)) => {
+                let reaped = reap(&group);
+                match reaped {
+                    Reaped::Killed => tracing::info!(
+                        "Killed what was left of execution {} (process group {})",
+                        process.id,
+                        group.process_group
+                    ),
+                    Reaped::NotOurs => tracing::warn!(
+                        "Process group {} of execution {} is no longer its own, leaving it running",
+                        group.process_group,
+                        process.id
+                    ),
+                    Reaped::Gone => {}
+                }
+                reaped
+            }
+            Ok(None) => Reaped::Gone,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to look up the process group of execution {}: {}",
+                    process.id,
+                    e
+                );
+                Reaped::Gone
+            }
+        };
+        let status = match reaped {
+            Reaped::NotOurs => ExecutionProcessStatus::Failed,
+            Reaped::Gone | Reaped::Killed => ExecutionProcessStatus::Interrupted,
+        };
+        if let Err(e) = ExecutionProcess::update_completion(pool, process.id, status, None).await {
+            tracing::error!("Failed to mark execution {} finished: {}", process.id, e);
+            continue;
+        }
+
+        let is_queued = queued
+            .iter()
+            .any(|queued| queued.task_attempt_id == process.task_attempt_id);
+        let recovery = recovery_for(&process.process_type, resume, is_queued);
+        if recovery == Recovery::Nothing {
+            continue;
+        }
+        let Ok(Some(task_attempt)) = TaskAttempt::find_by_id(pool, process.task_attempt_id).await
+        else {
+            continue;
+        };
+        let Ok(Some(task)) = Task::find_by_id(pool, task_attempt.task_id).await else {
+            continue;
+        };
+
+        if recovery == Recovery::Resume {
+            match ProcessService::start_followup_execution(
+                pool,
+                app_state,
+                task_attempt.id,
+                task.id,
+                task.project_id,
+                RESUME_PROMPT,
+            )
+            .await
+            {
+                Ok(_) => {
+                    tracing::info!("Resumed interrupted attempt {}", task_attempt.id);
+                    continue;
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to resume interrupted attempt {}: {}",
+                        task_attempt.id,
+                        e
+                    );
+                }
+            }
+        }
+        if let Err(e) =
+            Task::update_status(pool, task.id, task.project_id, TaskStatus::InReview).await
+        {
+            tracing::error!("Failed to update the status of task {}: {}", task.id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recovery_resumes_only_coding_agents_when_turned_on() {
+        let agent = ExecutionProcessType::CodingAgent;
+        assert_eq!(recovery_for(&agent, true, false), Recovery::Resume);
+        assert_eq!(recovery_for(&agent, false, false), Recovery::Review);
+        assert_eq!(recovery_for(&agent, true, true), Recovery::Nothing);
+
+        let setup = ExecutionProcessType::SetupScript;
+        assert_eq!(recovery_for(&setup, true, false), Recovery::Review);
+        let dev_server = ExecutionProcessType::DevServer;
+        assert_eq!(recovery_for(&dev_server, true, false), Recovery::Nothing);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_process_identity_tells_processes_apart() {
+        let own = process_identity(std::process::id());
+        assert!(own.is_some());
+        assert_eq!(own, process_identity(std::process::id()));
+        assert_ne!(own, process_identity(1));
+        assert_eq!(process_identity(u32::MAX), None);
+    }
+}
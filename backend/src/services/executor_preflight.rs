@@ -0,0 +1,235 @@
+//! Checking that the machine can run a coding agent before it is started: its binary is on
+//! PATH and answers `--version`, and it has an API key or login for its model provider. A
+//! missing piece is reported with how to fix it, instead of as a failed spawn.
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::{
+    executor::{
+        CredentialRequirement, EnvironmentProblem, EnvironmentRequirements, ExecutorConfig,
+        ExecutorError,
+    },
+    models::execution_process::FailureReason,
+};
+
+/// How the problem with an executor's credentials starts, telling it from binary problems
+const MISSING_CREDENTIALS: &str = "None of";
+
+/// Longest a `--version` probe may take; npx can be slow on a cold cache
+const VERSION_PROBE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// What the pre-flight check found for an executor
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct EnvironmentReport {
+    pub executor: String,
+    /// Where the executor's binary was found
+    pub binary_path: Option<String>,
+    /// First line the binary printed for `--version`
+    pub version: Option<String>,
+    /// Empty when the executor is ready to run
+    pub problems: Vec<EnvironmentProblem>,
+}
+
+/// Check the executor's requirements against this machine
+pub async fn check(config: &ExecutorConfig) -> EnvironmentReport {
+    let mut report = EnvironmentReport {
+        executor: config.to_string(),
+        binary_path: None,
+        version: None,
+        problems: Vec::new(),
+    };
+    let Some(requirements) = config.create_executor().environment_requirements() else {
+        return report;
+    };
+
+    match find_on_path(requirements.binary) {
+        Some(path) => {
+            match probe_version(&path).await {
+                Ok(version) => report.version = Some(version),
+                Err(e) => report.problems.push(EnvironmentProblem {
+                    message: format!("`{} --version` failed: {}", requirements.binary, e),
+                    remediation: format!("Reinstall it. {}", requirements.install_hint),
+                }),
+            }
+            report.binary_path = Some(path.to_string_lossy().to_string());
+        }
+        None => report.problems.push(missing_binary(&requirements)),
+    }
+
+    if let Some(credentials) = &requirements.credentials {
+        report
+            .problems
+            .extend(credential_problem(credentials, |var| {
+                std::env::var_os(var).is_some_and(|value| !value.is_empty())
+            }));
+    }
+    report
+}
+
+/// `Ok` if the executor can run here, otherwise the problems as an
+/// [`ExecutorError::EnvironmentInvalid`]
+pub async fn validate(config: &ExecutorConfig) -> Result<(), ExecutorError> {
+    let report = check(config).await;
+    if report.problems.is_empty() {
+        return Ok(());
+    }
+    Err(ExecutorError::EnvironmentInvalid {
+        executor_type: config.display_name().to_string(),
+        problems: report.problems,
+    })
+}
+
+/// Why an execution the problems kept from starting failed: its login or API key if that's
+/// all that's missing, otherwise its binary
+pub fn failure_reason(problems: &[EnvironmentProblem]) -> FailureReason {
+    if problems
+        .iter()
+        .all(|problem| problem.message.starts_with(MISSING_CREDENTIALS))
+    {
+        FailureReason::AuthFailure
+    } else {
+        FailureReason::MissingBinary
+    }
+}
+
+fn missing_binary(requirements: &EnvironmentRequirements) -> EnvironmentProblem {
+    EnvironmentProblem {
+        message: format!("`{}` was not found on PATH", requirements.binary),
+        remediation: requirements.install_hint.to_string(),
+    }
+}
+
+/// A problem if none of the credential's variables is set and its login file is missing
+fn credential_problem(
+    credentials: &CredentialRequirement,
+    is_set: impl Fn(&str) -> bool,
+) -> Option<EnvironmentProblem> {
+    let logged_in = credentials
+        .login_file
+        .as_ref()
+        .is_some_and(|file| file.exists());
+    if logged_in || credentials.env_vars.iter().any(|var| is_set(var)) {
+        return None;
+    }
+    Some(EnvironmentProblem {
+        message: format!(
+            "{} {} is set",
+            MISSING_CREDENTIALS,
+            credentials.env_vars.join(", ")
+        ),
+        remediation: credentials.hint.to_string(),
+    })
+}
+
+/// The executable `binary` resolves to on PATH, trying PATHEXT's extensions on Windows
+pub(crate) fn find_on_path(binary: &str) -> Option<PathBuf> {
+    let extensions: Vec<String> = if cfg!(windows) {
+        std::env::var("PATHEXT")
+            .unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string())
+            .split(';')
+            .map(|ext| ext.to_string())
+            .collect()
+    } else {
+        vec![String::new()]
+    };
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).find_map(|dir| {
+        extensions
+            .iter()
+            .map(|ext| dir.join(format!("{}{}", binary, ext)))
+            .find(|candidate| is_executable(candidate))
+    })
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+pub(crate) async fn probe_version(path: &Path) -> Result<String, String> {
+    let output = tokio::time::timeout(
+        VERSION_PROBE_TIMEOUT,
+        tokio::process::Command::new(path)
+            .arg("--version")
+            .stdin(std::process::Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| format!("no answer within {}s", VERSION_PROBE_TIMEOUT.as_secs()))?
+    .map_err(|e| e.to_string())?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr.lines().next().unwrap_or_default().trim();
+        return Err(format!("exited with {}: {}", output.status, reason));
+    }
+    Ok(stdout.lines().next().unwrap_or_default().trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn credentials(login_file: Option<PathBuf>) -> CredentialRequirement {
+        CredentialRequirement {
+            env_vars: &["OPENAI_API_KEY"],
+            login_file,
+            hint: "Export OPENAI_API_KEY",
+        }
+    }
+
+    #[test]
+    fn test_credentials_met_by_env_var_or_login_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let login_file = dir.path().join("auth.json");
+
+        let problem = credential_problem(&credentials(Some(login_file.clone())), |_| false);
+        assert_eq!(
+            problem,
+            Some(EnvironmentProblem {
+                message: "None of OPENAI_API_KEY is set".to_string(),
+                remediation: "Export OPENAI_API_KEY".to_string(),
+            })
+        );
+        assert!(credential_problem(&credentials(None), |var| var == "OPENAI_API_KEY").is_none());
+
+        assert_eq!(
+            failure_reason(&[problem.unwrap()]),
+            FailureReason::AuthFailure
+        );
+
+        std::fs::write(&login_file, "{}").unwrap();
+        assert!(credential_problem(&credentials(Some(login_file)), |_| false).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_executors_without_requirements_pass() {
+        let report = check(&ExecutorConfig::Echo).await;
+        assert!(report.problems.is_empty());
+        assert!(validate(&ExecutorConfig::Mock).await.is_ok());
+    }
+
+    #[test]
+    fn test_missing_binary_is_not_found() {
+        assert!(find_on_path("vibe-kanban-no-such-binary").is_none());
+        if cfg!(unix) {
+            assert!(find_on_path("sh").is_some());
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Telling why a failed execution failed from the errors it printed, so the reason can be
+//! stored with it and shown with what to do about it
+
+use crate::{
+    executor::{NormalizedEntry, NormalizedEntryType},
+    models::execution_process::FailureReason,
+    services::network_recovery,
+};
+
+/// Lowercase fragments of the errors agent CLIs, shells and git print for each reason,
+/// checked in this order so the most specific reason wins
+const FAILURE_PATTERNS: &[(FailureReason, &[&str])] = &[
+    (
+        FailureReason::ContextLengthExceeded,
+        &[
+            "prompt is too long",
+            "context_length_exceeded",
+            "maximum context length",
+            "context window",
+            "input is too long",
+            "too many tokens",
+        ],
+    ),
+    (
+        FailureReason::RateLimited,
+        &[
+            "rate_limit_error",
+            "rate limit",
+            "ratelimit",
+            "too many requests",
+            "usage limit",
+            "quota exceeded",
+            "exceeded your current quota",
+            "exhausted your",
+            "resource_exhausted",
+            "insufficient_quota",
+            "overloaded",
+            "api error: 429",
+            "status code 429",
+            "last status: 429",
+        ],
+    ),
+    (
+        FailureReason::AuthFailure,
+        &[
+            "authentication_error",
+            "invalid api key",
+            "invalid x-api-key",
+            "incorrect api key",
+            "invalid_api_key",
+            "401 unauthorized",
+            "not logged in",
+            "please run /login",
+            "oauth token has expired",
+            "permission_error",
+        ],
+    ),
+    (
+        FailureReason::GitConflict,
+        &[
+            "conflict (content)",
+            "conflict (modify/delete)",
+            "automatic merge failed",
+            "merge conflict",
+            "could not apply",
+            "you have unmerged paths",
+        ],
+    ),
+    (
+        FailureReason::MissingBinary,
+        &[
+            "command not found",
+            "is not recognized as an internal or external command",
+            "no such file or directory (os error 2)",
+            "spawn enoent",
+        ],
+    ),
+];
+
+fn reason_of(line: &str) -> Option<FailureReason> {
+    let line = line.to_lowercase();
+    FAILURE_PATTERNS
+        .iter()
+        .find(|(_, patterns)| patterns.iter().any(|pattern| line.contains(pattern)))
+        .map(|(reason, _)| *reason)
+}
+
+/// Why a failed run failed, from the errors its executor's normalizer found and the end of
+/// its output, read from the last line back so what ended the run counts over what the
+/// agent came across on the way
+pub fn detect(
+    stdout: Option<&str>,
+    stderr: Option<&str>,
+    entries: &[NormalizedEntry],
+) -> Option<FailureReason> {
+    let errors = entries
+        .iter()
+        .rev()
+        .filter(|entry| matches!(entry.entry_type, NormalizedEntryType::ErrorMessage))
+        .map(|entry| entry.content.as_str());
+    let output = [stderr, stdout]
+        .into_iter()
+        .flatten()
+        .flat_map(|output| network_recovery::tail(output).lines().rev());
+    errors.chain(output).find_map(reason_of).or_else(|| {
+        network_recovery::detect_network_failure(stdout, stderr)
+            .map(|_| FailureReason::NetworkError)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            id: None,
+            timestamp: None,
+            duration_ms: None,
+            entry_type: NormalizedEntryType::ErrorMessage,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_reasons_come_from_normalized_errors_first() {
+        let entries = [error(
+            r#"API Error: 429 {"type":"error","error":{"type":"rate_limit_error"}}"#,
+        )];
+        assert_eq!(
+            detect(None, Some("fatal: not logged in"), &entries),
+            Some(FailureReason::RateLimited)
+        );
+        assert_eq!(
+            detect(
+                None,
+                None,
+                &[error("You have exhausted your daily quota on this model.")]
+            ),
+            Some(FailureReason::RateLimited)
+        );
+        assert_eq!(
+            detect(None, None, &[error("Prompt is too long")]),
+            Some(FailureReason::ContextLengthExceeded)
+        );
+    }
+
+    #[test]
+    fn test_reasons_come_from_the_end_of_the_output() {
+        assert_eq!(
+            detect(None, Some("bash: line 1: claude: command not found"), &[]),
+            Some(FailureReason::MissingBinary)
+        );
+        assert_eq!(
+            detect(
+                Some("Auto-merging src/lib.rs\nCONFLICT (content): Merge conflict in src/lib.rs"),
+                None,
+                &[]
+            ),
+            Some(FailureReason::GitConflict)
+        );
+        assert_eq!(
+            detect(
+                None,
+                Some("Error: getaddrinfo ENOTFOUND api.anthropic.com"),
+                &[]
+            ),
+            Some(FailureReason::NetworkError)
+        );
+        assert_eq!(detect(Some("error: 3 tests failed"), None, &[]), None);
+    }
+}
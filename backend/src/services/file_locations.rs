@@ -0,0 +1,126 @@
+//! Where the files a conversation's tool uses read or wrote are: on disk, and on the
+//! project's git host at the execution's commit, so they can be opened from the logs.
+
+use std::path::{Component, Path};
+
+use crate::{
+    executor::{ActionType, FileLocation, NormalizedEntry, NormalizedEntryType},
+    services::git_host::RepositoryWeb,
+};
+
+/// Fill in the location of each file read or written in `entries`, whose paths are
+/// relative to `worktree_path` unless absolute. Files inside the worktree are linked to on
+/// `web` at its commit, when there is one.
+pub fn resolve(
+    entries: &mut [NormalizedEntry],
+    worktree_path: &str,
+    web: Option<(&RepositoryWeb, &str)>,
+) {
+    let worktree = Path::new(worktree_path);
+    for entry in entries {
+        let NormalizedEntryType::ToolUse {
+            action_type:
+                ActionType::FileRead { path, location } | ActionType::FileWrite { path, location },
+            ..
+        } = &mut entry.entry_type
+        else {
+            continue;
+        };
+        if path.trim().is_empty() {
+            continue;
+        }
+        let absolute = worktree.join(path.as_str());
+        let url = web.and_then(|(web, commit)| {
+            let relative = absolute.strip_prefix(worktree).ok()?;
+            // `..` could step out of the repository, so such paths aren't linked
+            if !relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+            {
+                return None;
+            }
+            let relative: Vec<_> = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect();
+            Some(web.file_url(commit, &relative.join("/")))
+        });
+        *location = Some(FileLocation {
+            absolute_path: absolute.to_string_lossy().to_string(),
+            exists: absolute.exists(),
+            url,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_entry(action_type: ActionType) -> NormalizedEntry {
+        NormalizedEntry {
+            id: None,
+            timestamp: None,
+            duration_ms: None,
+            entry_type: NormalizedEntryType::ToolUse {
+                tool_name: "edit".to_string(),
+                action_type,
+            },
+            content: String::new(),
+            metadata: None,
+        }
+    }
+
+    fn location(entry: &NormalizedEntry) -> Option<&FileLocation> {
+        match &entry.entry_type {
+            NormalizedEntryType::ToolUse {
+                action_type:
+                    ActionType::FileRead { location, .. } | ActionType::FileWrite { location, .. },
+                ..
+            } => location.as_ref(),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_resolves_paths_and_links() {
+        let worktree = tempfile::tempdir().unwrap();
+        std::fs::write(worktree.path().join("main.rs"), "fn main() {}").unwrap();
+        let worktree_path = worktree.path().to_string_lossy().to_string();
+        let outside = format!("{}/../elsewhere.txt", worktree_path);
+        let mut entries: Vec<_> = ["main.rs", "src/deleted.rs", outside.as_str()]
+            .into_iter()
+            .map(|path| {
+                file_entry(ActionType::FileWrite {
+                    path: path.to_string(),
+                    location: None,
+                })
+            })
+            .collect();
+        entries.push(file_entry(ActionType::CommandRun {
+            command: "ls".to_string(),
+        }));
+
+        let web = RepositoryWeb::from_remote(None, "git@github.com:owner/repo.git").unwrap();
+        resolve(&mut entries, &worktree_path, Some((&web, "abc123")));
+
+        let main = location(&entries[0]).unwrap();
+        assert_eq!(
+            main.absolute_path,
+            worktree.path().join("main.rs").to_string_lossy()
+        );
+        assert!(main.exists);
+        assert_eq!(
+            main.url.as_deref(),
+            Some("https://github.com/owner/repo/blob/abc123/main.rs")
+        );
+        let deleted = location(&entries[1]).unwrap();
+        assert!(!deleted.exists);
+        assert_eq!(
+            deleted.url.as_deref(),
+            Some("https://github.com/owner/repo/blob/abc123/src/deleted.rs")
+        );
+        assert_eq!(location(&entries[2]).unwrap().url, None);
+        assert_eq!(location(&entries[3]), None);
+    }
+}
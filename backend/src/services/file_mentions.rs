@@ -0,0 +1,273 @@
+//! `@path/to/file.rs` mentions in task descriptions. Mentioned files are checked against the
+//! project's repository while the task is written, and their contents go into the agent's
+//! prompt when it starts so it begins with the code the task is about. A mention can narrow
+//! that to lines, as in `@src/main.rs:10-40`.
+
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::services::conversation_export::fenced;
+
+/// Longer files are cut down to their first lines; the agent can open the rest itself
+const MAX_FILE_CHARS: usize = 6_000;
+const MAX_TOTAL_CHARS: usize = 24_000;
+const MAX_INJECTED_FILES: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileMention {
+    /// As written after the `@`
+    pub mention: String,
+    pub path: String,
+    /// 1-based and inclusive
+    pub lines: Option<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export)]
+pub struct CheckFileMentions {
+    pub text: String,
+}
+
+/// A mention in a task's description that doesn't name a file of the repository
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct FileMentionProblem {
+    pub mention: String,
+    pub problem: String,
+}
+
+fn parse_lines(lines: &str) -> Option<(usize, usize)> {
+    let (start, end) = lines.split_once('-').unwrap_or((lines, lines));
+    let (start, end) = (start.parse().ok()?, end.parse().ok()?);
+    (start >= 1 && end >= start).then_some((start, end))
+}
+
+/// The `@` mentions of files in `text`, each once. An `@` inside a word (an email address)
+/// or before a name without a `/` or `.` (a person) isn't a mention.
+pub fn parse_mentions(text: &str) -> Vec<FileMention> {
+    let mut mentions: Vec<FileMention> = Vec::new();
+    for (index, _) in text.match_indices('@') {
+        let after_word = text[..index]
+            .chars()
+            .next_back()
+            .is_some_and(|c| !(c.is_whitespace() || matches!(c, '(' | '[' | '`' | '"' | '\'')));
+        if after_word {
+            continue;
+        }
+        let rest = &text[index + 1..];
+        let end = rest
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '/' | '.' | '-' | '_' | ':')))
+            .unwrap_or(rest.len());
+        // Punctuation ending the sentence isn't part of the path
+        let mention = rest[..end].trim_end_matches(['.', ':', '-']);
+        let (path, lines) = match mention.split_once(':') {
+            Some((path, lines)) => (path, parse_lines(lines)),
+            None => (mention, None),
+        };
+        let path = path.strip_prefix("./").unwrap_or(path);
+        if !(path.contains('/') || path.contains('.'))
+            || mentions.iter().any(|m| m.mention == mention)
+        {
+            continue;
+        }
+        mentions.push(FileMention {
+            mention: mention.to_string(),
+            path: path.to_string(),
+            lines,
+        });
+    }
+    mentions
+}
+
+/// The file a mentioned path names under `root`, if it is one and stays inside it
+fn resolve(root: &Path, path: &str) -> Result<PathBuf, &'static str> {
+    let relative = Path::new(path);
+    if !relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        return Err("is outside the repository");
+    }
+    let full = root.join(relative);
+    // A symlink could still lead out of it
+    let canonical = fs::canonicalize(&full).map_err(|_| "isn't a file in the repository")?;
+    let root = fs::canonicalize(root).map_err(|_| "isn't a file in the repository")?;
+    if !canonical.starts_with(&root) {
+        return Err("is outside the repository");
+    }
+    if !canonical.is_file() {
+        return Err("isn't a file in the repository");
+    }
+    Ok(canonical)
+}
+
+/// Why each mention in `text` doesn't name a file of the repository at `root`
+pub fn check_mentions(root: &Path, text: &str) -> Vec<FileMentionProblem> {
+    parse_mentions(text)
+        .into_iter()
+        .filter_map(|mention| {
+            let problem = match resolve(root, &mention.path) {
+                Err(problem) => problem.to_string(),
+                Ok(file) => {
+                    let (_, end) = mention.lines?;
+                    let line_count = fs::read_to_string(file).ok()?.lines().count();
+                    if end <= line_count {
+                        return None;
+                    }
+                    format!("goes past the end of the file ({} lines)", line_count)
+                }
+            };
+            Some(FileMentionProblem {
+                mention: mention.mention,
+                problem,
+            })
+        })
+        .collect()
+}
+
+/// The mentioned lines of `content`, or all of it, cut down to the lines that fit in
+/// `max_chars`
+fn excerpt(content: &str, lines: Option<(usize, usize)>, max_chars: usize) -> String {
+    let all: Vec<&str> = content.lines().collect();
+    let (start, end) = lines
+        .map(|(start, end)| (start.min(all.len()), end.min(all.len())))
+        .unwrap_or((1, all.len()));
+    let selected = all.get(start.saturating_sub(1)..end).unwrap_or_default();
+
+    let mut text = String::new();
+    let mut chars = 0;
+    for (index, line) in selected.iter().enumerate() {
+        chars += line.chars().count() + 1;
+        if chars > max_chars {
+            text.push_str(&format!(
+                "[… {} more lines not shown]\n",
+                selected.len() - index
+            ));
+            break;
+        }
+        text.push_str(line);
+        text.push('\n');
+    }
+    text
+}
+
+/// The prompt section with the contents of the files `text` mentions, read from the
+/// worktree. Mentions that don't name a file are left out; the agent still sees them in
+/// the task as written.
+pub fn mentioned_files_context(worktree: &Path, text: &str) -> String {
+    let mut budget = MAX_TOTAL_CHARS;
+    let mut sections = Vec::new();
+    for mention in parse_mentions(text) {
+        if sections.len() == MAX_INJECTED_FILES || budget == 0 {
+            break;
+        }
+        // Binary files aren't UTF-8 and are skipped here
+        let Some(content) = resolve(worktree, &mention.path)
+            .ok()
+            .and_then(|file| fs::read_to_string(file).ok())
+        else {
+            continue;
+        };
+        let shown = excerpt(&content, mention.lines, MAX_FILE_CHARS.min(budget));
+        if shown.trim().is_empty() {
+            continue;
+        }
+        budget = budget.saturating_sub(shown.chars().count());
+        let heading = match mention.lines {
+            Some((start, end)) => format!("{} (lines {}-{})", mention.path, start, end),
+            None => mention.path.clone(),
+        };
+        let language = Path::new(&mention.path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default();
+        sections.push(format!("{}:\n{}", heading, fenced(language, &shown)));
+    }
+    if sections.is_empty() {
+        return String::new();
+    }
+    format!(
+        "\n\nFiles mentioned in the task:\n\n{}",
+        sections.join("\n").trim_end()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mentions() {
+        let text = "Fix @src/main.rs:10-20 and @README.md. Ask @alice, mail bob@example.com, \
+                    see (@./docs/guide.md) and @src/main.rs:10-20 again.";
+        assert_eq!(
+            parse_mentions(text),
+            vec![
+                FileMention {
+                    mention: "src/main.rs:10-20".to_string(),
+                    path: "src/main.rs".to_string(),
+                    lines: Some((10, 20)),
+                },
+                FileMention {
+                    mention: "README.md".to_string(),
+                    path: "README.md".to_string(),
+                    lines: None,
+                },
+                FileMention {
+                    mention: "./docs/guide.md".to_string(),
+                    path: "docs/guide.md".to_string(),
+                    lines: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mentions_are_checked_against_the_repository() {
+        let repo = tempfile::tempdir().unwrap();
+        fs::create_dir(repo.path().join("src")).unwrap();
+        fs::write(repo.path().join("src/lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        let problems = check_mentions(
+            repo.path(),
+            "@src/lib.rs @src/lib.rs:2 @src/lib.rs:2-5 @src/gone.rs @../secret.txt",
+        );
+        let problems: Vec<(&str, &str)> = problems
+            .iter()
+            .map(|p| (p.mention.as_str(), p.problem.as_str()))
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                ("src/lib.rs:2-5", "goes past the end of the file (2 lines)"),
+                ("src/gone.rs", "isn't a file in the repository"),
+                ("../secret.txt", "is outside the repository"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mentioned_files_are_put_into_the_prompt() {
+        let repo = tempfile::tempdir().unwrap();
+        fs::write(repo.path().join("notes.md"), "one\ntwo\nthree\n").unwrap();
+        assert_eq!(
+            mentioned_files_context(repo.path(), "See @notes.md:2-3 and @missing.rs"),
+            "\n\nFiles mentioned in the task:\n\nnotes.md (lines 2-3):\n```md\ntwo\nthree\n```"
+        );
+        assert_eq!(mentioned_files_context(repo.path(), "No mentions"), "");
+    }
+
+    #[test]
+    fn test_long_files_are_cut_down() {
+        let content = "0123456789\n".repeat(10);
+        assert_eq!(
+            excerpt(&content, None, 25),
+            "0123456789\n0123456789\n[… 8 more lines not shown]\n"
+        );
+        assert_eq!(excerpt(&content, Some((9, 20)), 1000).lines().count(), 2);
+    }
+}
@@ -0,0 +1,867 @@
+//! Where pull requests are opened: GitHub, GitLab (merge requests, self-hosted or not) or
+//! Bitbucket Cloud, chosen per project. Each host's pull requests are described the way
+//! GitHub's are, with a status of "open", "merged" or "closed", so attempts track them
+//! the same whichever opened them.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::{RequestBuilder, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    models::{
+        config::GitHubConfig,
+        project_git_host::{GitHostKind, ProjectGitHost, UpsertProjectGitHost},
+    },
+    services::{
+        environment_variables::{self, EnvironmentError},
+        git_service::{remote_host_and_path, GitService},
+        github_service::PullRequestInfo,
+        CreatePrRequest, GitHubRepoInfo, GitHubService, GitHubServiceError,
+    },
+};
+
+const BITBUCKET_API_URL: &str = "https://api.bitbucket.org/2.0";
+
+#[derive(Debug)]
+pub enum GitHostError {
+    Request(reqwest::Error),
+    Api {
+        kind: GitHostKind,
+        status: StatusCode,
+        message: String,
+    },
+    TokenInvalid(GitHostKind),
+    /// Missing a token, or one that can't be used
+    NotConfigured(String),
+    InvalidUrl(String),
+    Repository(String),
+    GitHub(GitHubServiceError),
+    Secrets(EnvironmentError),
+    Database(sqlx::Error),
+}
+
+impl std::fmt::Display for GitHostError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GitHostError::Request(e) => write!(f, "Failed to reach the git host: {}", e),
+            GitHostError::Api {
+                kind,
+                status,
+                message,
+            } => write!(f, "{} API error ({}): {}", kind.name(), status, message),
+            GitHostError::TokenInvalid(kind) => {
+                write!(f, "{} token is invalid or expired.", kind.name())
+            }
+            GitHostError::NotConfigured(e) => write!(f, "{}", e),
+            GitHostError::InvalidUrl(url) => {
+                write!(f, "{:?} isn't an http:// or https:// URL", url)
+            }
+            GitHostError::Repository(e) => write!(f, "Repository error: {}", e),
+            GitHostError::GitHub(e) => write!(f, "{}", e),
+            GitHostError::Secrets(e) => write!(f, "{}", e),
+            GitHostError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for GitHostError {}
+
+impl From<reqwest::Error> for GitHostError {
+    fn from(err: reqwest::Error) -> Self {
+        GitHostError::Request(err)
+    }
+}
+
+impl From<GitHubServiceError> for GitHostError {
+    fn from(err: GitHubServiceError) -> Self {
+        match err {
+            GitHubServiceError::TokenInvalid => GitHostError::TokenInvalid(GitHostKind::GitHub),
+            err => GitHostError::GitHub(err),
+        }
+    }
+}
+
+impl From<EnvironmentError> for GitHostError {
+    fn from(err: EnvironmentError) -> Self {
+        GitHostError::Secrets(err)
+    }
+}
+
+impl From<sqlx::Error> for GitHostError {
+    fn from(err: sqlx::Error) -> Self {
+        GitHostError::Database(err)
+    }
+}
+
+/// A service pull requests of one repository are opened on
+#[async_trait]
+pub trait GitHost: Send + Sync {
+    fn kind(&self) -> GitHostKind;
+
+    /// User name and token to push the branch with over HTTPS
+    fn push_credentials(&self) -> (&str, &str);
+
+    async fn create_pull_request(
+        &self,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, GitHostError>;
+
+    async fn pull_request(&self, number: i64) -> Result<PullRequestInfo, GitHostError>;
+
+    async fn comment_on_pull_request(&self, number: i64, body: &str) -> Result<(), GitHostError>;
+}
+
+pub struct GitHubHost {
+    service: GitHubService,
+    repo: GitHubRepoInfo,
+    token: String,
+}
+
+impl GitHubHost {
+    /// `api_url` is GitHub Enterprise's; `None` for github.com
+    pub fn new(
+        api_url: Option<&str>,
+        repo: GitHubRepoInfo,
+        token: &str,
+    ) -> Result<Self, GitHostError> {
+        let service = match api_url {
+            Some(api_url) => GitHubService::new_with_base_url(token, api_url)?,
+            None => GitHubService::new(token)?,
+        };
+        Ok(Self {
+            service,
+            repo,
+            token: token.to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl GitHost for GitHubHost {
+    fn kind(&self) -> GitHostKind {
+        GitHostKind::GitHub
+    }
+
+    fn push_credentials(&self) -> (&str, &str) {
+        ("git", &self.token)
+    }
+
+    async fn create_pull_request(
+        &self,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, GitHostError> {
+        Ok(self.service.create_pr(&self.repo, request).await?)
+    }
+
+    async fn pull_request(&self, number: i64) -> Result<PullRequestInfo, GitHostError> {
+        Ok(self.service.update_pr_status(&self.repo, number).await?)
+    }
+
+    async fn comment_on_pull_request(&self, number: i64, body: &str) -> Result<(), GitHostError> {
+        Ok(self.service.comment_on_pr(&self.repo, number, body).await?)
+    }
+}
+
+pub struct GitLabHost {
+    client: reqwest::Client,
+    /// e.g. `https://gitlab.com/api/v4`
+    api_url: String,
+    /// Namespaced path of the project, e.g. `group/subgroup/repo`
+    project: String,
+    token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabMergeRequest {
+    iid: i64,
+    web_url: String,
+    /// "opened", "closed", "locked" or "merged"
+    state: String,
+    merged_at: Option<DateTime<Utc>>,
+    merge_commit_sha: Option<String>,
+    squash_commit_sha: Option<String>,
+}
+
+impl From<GitLabMergeRequest> for PullRequestInfo {
+    fn from(mr: GitLabMergeRequest) -> Self {
+        let status = match mr.state.as_str() {
+            "opened" | "locked" => "open",
+            "merged" => "merged",
+            _ => "closed",
+        };
+        PullRequestInfo {
+            number: mr.iid,
+            url: mr.web_url,
+            status: status.to_string(),
+            merged: status == "merged",
+            merged_at: mr.merged_at,
+            merge_commit_sha: mr.merge_commit_sha.or(mr.squash_commit_sha),
+        }
+    }
+}
+
+impl GitLabHost {
+    pub fn new(api_url: &str, project: &str, token: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_url: api_url.trim_end_matches('/').to_string(),
+            project: project.to_string(),
+            token: token.to_string(),
+        }
+    }
+
+    fn merge_requests_url(&self) -> String {
+        format!(
+            "{}/projects/{}/merge_requests",
+            self.api_url,
+            urlencoding::encode(&self.project)
+        )
+    }
+}
+
+#[async_trait]
+impl GitHost for GitLabHost {
+    fn kind(&self) -> GitHostKind {
+        GitHostKind::GitLab
+    }
+
+    fn push_credentials(&self) -> (&str, &str) {
+        ("oauth2", &self.token)
+    }
+
+    async fn create_pull_request(
+        &self,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, GitHostError> {
+        let body = json!({
+            "source_branch": request.head_branch,
+            "target_branch": request.base_branch,
+            "title": request.title,
+            "description": request.body.clone().unwrap_or_default(),
+        });
+        let mr: GitLabMergeRequest = send(
+            self.kind(),
+            self.client
+                .post(self.merge_requests_url())
+                .header("PRIVATE-TOKEN", &self.token)
+                .json(&body),
+        )
+        .await?;
+        tracing::info!(
+            "Created GitLab merge request !{} for branch {} in {}",
+            mr.iid,
+            request.head_branch,
+            self.project
+        );
+        Ok(mr.into())
+    }
+
+    async fn pull_request(&self, number: i64) -> Result<PullRequestInfo, GitHostError> {
+        let mr: GitLabMergeRequest = send(
+            self.kind(),
+            self.client
+                .get(format!("{}/{}", self.merge_requests_url(), number))
+                .header("PRIVATE-TOKEN", &self.token),
+        )
+        .await?;
+        Ok(mr.into())
+    }
+
+    async fn comment_on_pull_request(&self, number: i64, body: &str) -> Result<(), GitHostError> {
+        let _: Value = send(
+            self.kind(),
+            self.client
+                .post(format!("{}/{}/notes", self.merge_requests_url(), number))
+                .header("PRIVATE-TOKEN", &self.token)
+                .json(&json!({ "body": body })),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+pub struct BitbucketHost {
+    client: reqwest::Client,
+    api_url: String,
+    /// `workspace/repo`
+    repository: String,
+    token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketPullRequest {
+    id: i64,
+    /// "OPEN", "MERGED", "DECLINED" or "SUPERSEDED"
+    state: String,
+    links: BitbucketLinks,
+    merge_commit: Option<BitbucketCommit>,
+    updated_on: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketLinks {
+    html: BitbucketLink,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketLink {
+    href: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketCommit {
+    hash: String,
+}
+
+impl From<BitbucketPullRequest> for PullRequestInfo {
+    fn from(pr: BitbucketPullRequest) -> Self {
+        let status = match pr.state.as_str() {
+            "OPEN" => "open",
+            "MERGED" => "merged",
+            _ => "closed",
+        };
+        let merged = status == "merged";
+        PullRequestInfo {
+            number: pr.id,
+            url: pr.links.html.href,
+            status: status.to_string(),
+            merged,
+            // Bitbucket doesn't say when it was merged; merging is its last update
+            merged_at: pr.updated_on.filter(|_| merged),
+            merge_commit_sha: pr.merge_commit.map(|commit| commit.hash),
+        }
+    }
+}
+
+impl BitbucketHost {
+    pub fn new(api_url: &str, repository: &str, token: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_url: api_url.trim_end_matches('/').to_string(),
+            repository: repository.to_string(),
+            token: token.to_string(),
+        }
+    }
+
+    fn pull_requests_url(&self) -> String {
+        format!(
+            "{}/repositories/{}/pullrequests",
+            self.api_url, self.repository
+        )
+    }
+}
+
+#[async_trait]
+impl GitHost for BitbucketHost {
+    fn kind(&self) -> GitHostKind {
+        GitHostKind::Bitbucket
+    }
+
+    fn push_credentials(&self) -> (&str, &str) {
+        ("x-token-auth", &self.token)
+    }
+
+    async fn create_pull_request(
+        &self,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, GitHostError> {
+        let body = json!({
+            "title": request.title,
+            "description": request.body.clone().unwrap_or_default(),
+            "source": { "branch": { "name": request.head_branch } },
+            "destination": { "branch": { "name": request.base_branch } },
+        });
+        let pr: BitbucketPullRequest = send(
+            self.kind(),
+            self.client
+                .post(self.pull_requests_url())
+                .bearer_auth(&self.token)
+                .json(&body),
+        )
+        .await?;
+        tracing::info!(
+            "Created Bitbucket pull request #{} for branch {} in {}",
+            pr.id,
+            request.head_branch,
+            self.repository
+        );
+        Ok(pr.into())
+    }
+
+    async fn pull_request(&self, number: i64) -> Result<PullRequestInfo, GitHostError> {
+        let pr: BitbucketPullRequest = send(
+            self.kind(),
+            self.client
+                .get(format!("{}/{}", self.pull_requests_url(), number))
+                .bearer_auth(&self.token),
+        )
+        .await?;
+        Ok(pr.into())
+    }
+
+    async fn comment_on_pull_request(&self, number: i64, body: &str) -> Result<(), GitHostError> {
+        let _: Value = send(
+            self.kind(),
+            self.client
+                .post(format!("{}/{}/comments", self.pull_requests_url(), number))
+                .bearer_auth(&self.token)
+                .json(&json!({ "content": { "raw": body } })),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+async fn send<T: DeserializeOwned>(
+    kind: GitHostKind,
+    request: RequestBuilder,
+) -> Result<T, GitHostError> {
+    let response = request.send().await?;
+    let status = response.status();
+    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        return Err(GitHostError::TokenInvalid(kind));
+    }
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(GitHostError::Api {
+            kind,
+            status,
+            message: error_message(&body),
+        });
+    }
+    Ok(response.json().await?)
+}
+
+/// The message of an error response: GitLab's `message`, which can be a list, or
+/// Bitbucket's `error.message`
+fn error_message(body: &str) -> String {
+    let value: Option<Value> = serde_json::from_str(body).ok();
+    let message = value
+        .as_ref()
+        .and_then(|v| v.get("message").or_else(|| v.pointer("/error/message")));
+    match message {
+        Some(Value::String(message)) => message.clone(),
+        Some(Value::Array(messages)) => messages
+            .iter()
+            .map(|m| m.as_str().map_or_else(|| m.to_string(), str::to_string))
+            .collect::<Vec<_>>()
+            .join("; "),
+        Some(message) => message.to_string(),
+        None => body.trim().to_string(),
+    }
+}
+
+/// What the stored token of `project_id`'s host is encrypted for, so it can't be moved to
+/// another project
+fn token_name(project_id: Uuid) -> String {
+    format!("git-host:{}", project_id)
+}
+
+/// Save the project's host, encrypting a new token
+pub async fn save(
+    pool: &DbPool,
+    project_id: Uuid,
+    payload: &UpsertProjectGitHost,
+) -> Result<ProjectGitHost, GitHostError> {
+    let base_url = payload
+        .base_url
+        .as_deref()
+        .map(|url| url.trim().trim_end_matches('/'))
+        .filter(|url| !url.is_empty());
+    if let Some(url) = base_url {
+        if !(url.starts_with("https://") || url.starts_with("http://")) {
+            return Err(GitHostError::InvalidUrl(url.to_string()));
+        }
+    }
+    let token = match payload
+        .token
+        .as_deref()
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+    {
+        Some(token) => {
+            Some(environment_variables::cipher()?.encrypt(&token_name(project_id), token))
+        }
+        None => None,
+    };
+    Ok(ProjectGitHost::upsert(pool, project_id, payload.kind, base_url, token.as_deref()).await?)
+}
+
+/// The host the project's pull requests are opened on
+pub async fn for_project(
+    pool: &DbPool,
+    project_id: Uuid,
+    git_repo_path: &str,
+    github: &GitHubConfig,
+) -> Result<Box<dyn GitHost>, GitHostError> {
+    let settings = ProjectGitHost::find_by_project_id(pool, project_id).await?;
+    let token = match settings.as_ref().and_then(|s| s.token.as_deref()) {
+        Some(stored) => Some(
+            environment_variables::cipher()?
+                .decrypt(&token_name(project_id), stored)
+                .ok_or_else(|| {
+                    GitHostError::NotConfigured(
+                        "The project's git host token can't be decrypted; save it again, or restore the key it was saved with".to_string(),
+                    )
+                })?,
+        ),
+        None => None,
+    };
+    let remote_url = GitService::new(git_repo_path)
+        .and_then(|git| git.origin_url())
+        .map_err(|e| GitHostError::Repository(e.to_string()))?;
+    resolve(settings.as_ref(), token.as_deref(), &remote_url, github)
+}
+
+/// The kind of host saved in `settings`, or else guessed from the remote's host name
+fn host_kind(settings: Option<&ProjectGitHost>, host: &str) -> GitHostKind {
+    match settings {
+        Some(settings) => settings.kind,
+        None if host.contains("gitlab") => GitHostKind::GitLab,
+        None if host == "bitbucket.org" => GitHostKind::Bitbucket,
+        None => GitHostKind::GitHub,
+    }
+}
+
+/// A repository's pages on its host's website
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepositoryWeb {
+    kind: GitHostKind,
+    url: String,
+}
+
+impl RepositoryWeb {
+    /// The pages of the repository with the remote `remote_url`, on the host in `settings`
+    /// or else the one its host name suggests
+    pub fn from_remote(settings: Option<&ProjectGitHost>, remote_url: &str) -> Option<Self> {
+        let (host, path) = remote_host_and_path(remote_url)?;
+        let kind = host_kind(settings, &host);
+        // A self-hosted GitLab is browsed at its instance URL, which may be plain http
+        let origin = settings
+            .and_then(|s| s.base_url.as_deref())
+            .filter(|_| kind == GitHostKind::GitLab)
+            .and_then(|url| url.split_once("://"))
+            .map(|(scheme, rest)| {
+                format!("{}://{}", scheme, rest.split('/').next().unwrap_or(rest))
+            })
+            .unwrap_or_else(|| format!("https://{}", host));
+        Some(Self {
+            kind,
+            url: format!("{}/{}", origin, path),
+        })
+    }
+
+    /// The page of `path`, relative to the repository's root, at `commit`
+    pub fn file_url(&self, commit: &str, path: &str) -> String {
+        let path = path.trim_start_matches('/');
+        match self.kind {
+            GitHostKind::GitHub => format!("{}/blob/{}/{}", self.url, commit, path),
+            GitHostKind::GitLab => format!("{}/-/blob/{}/{}", self.url, commit, path),
+            GitHostKind::Bitbucket => format!("{}/src/{}/{}", self.url, commit, path),
+        }
+    }
+}
+
+/// Where the project's repository can be browsed; `None` when its origin isn't on a host,
+/// such as a local path
+pub async fn repository_web(
+    pool: &DbPool,
+    project_id: Uuid,
+    git_repo_path: &str,
+) -> Result<Option<RepositoryWeb>, GitHostError> {
+    let settings = ProjectGitHost::find_by_project_id(pool, project_id).await?;
+    let Ok(remote_url) = GitService::new(git_repo_path).and_then(|git| git.origin_url()) else {
+        return Ok(None);
+    };
+    Ok(RepositoryWeb::from_remote(settings.as_ref(), &remote_url))
+}
+
+/// The host for a repository with the remote `remote_url`. Without settings, the kind is
+/// guessed from the remote's host name and needs the account's GitHub token for GitHub.
+fn resolve(
+    settings: Option<&ProjectGitHost>,
+    token: Option<&str>,
+    remote_url: &str,
+    github: &GitHubConfig,
+) -> Result<Box<dyn GitHost>, GitHostError> {
+    let (host, path) = remote_host_and_path(remote_url).ok_or_else(|| {
+        GitHostError::Repository(format!("Can't tell the host of remote {}", remote_url))
+    })?;
+    let kind = host_kind(settings, &host);
+    let base_url = settings.and_then(|s| s.base_url.as_deref());
+    let project_token = |kind: GitHostKind| {
+        token.ok_or_else(|| {
+            GitHostError::NotConfigured(format!(
+                "Add a {} access token in the project's Git Host settings first.",
+                kind.name()
+            ))
+        })
+    };
+
+    match kind {
+        GitHostKind::GitHub => {
+            let token = token
+                .map(str::to_string)
+                .or_else(|| github.pat.clone())
+                .or_else(|| github.token.clone())
+                .ok_or_else(|| {
+                    GitHostError::NotConfigured(
+                        "GitHub authentication not configured. Please sign in with GitHub."
+                            .to_string(),
+                    )
+                })?;
+            let (owner, repo_name) = path.rsplit_once('/').ok_or_else(|| {
+                GitHostError::Repository(format!("Not a GitHub repository: {}", remote_url))
+            })?;
+            let repo = GitHubRepoInfo {
+                owner: owner.to_string(),
+                repo_name: repo_name.to_string(),
+            };
+            let api_url = base_url.or(github.enterprise_url.as_deref());
+            Ok(Box::new(GitHubHost::new(api_url, repo, &token)?))
+        }
+        GitHostKind::GitLab => {
+            let token = project_token(kind)?;
+            let instance_url = base_url
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("https://{}", host));
+            // An instance under a relative URL has it at the start of its projects' paths
+            let prefix = instance_url
+                .split_once("://")
+                .and_then(|(_, rest)| rest.split_once('/'))
+                .map(|(_, prefix)| prefix.trim_matches('/'))
+                .filter(|prefix| !prefix.is_empty());
+            let project = match prefix {
+                Some(prefix) => path
+                    .strip_prefix(prefix)
+                    .and_then(|rest| rest.strip_prefix('/'))
+                    .unwrap_or(&path),
+                None => &path,
+            };
+            Ok(Box::new(GitLabHost::new(
+                &format!("{}/api/v4", instance_url),
+                project,
+                token,
+            )))
+        }
+        GitHostKind::Bitbucket => Ok(Box::new(BitbucketHost::new(
+            base_url.unwrap_or(BITBUCKET_API_URL),
+            &path,
+            project_token(kind)?,
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use axum::{
+        body::Bytes,
+        http::{HeaderMap, Method, Uri},
+        Router,
+    };
+
+    use super::*;
+    use crate::models::config::Config;
+
+    #[derive(Debug, Clone)]
+    struct Received {
+        method: Method,
+        path: String,
+        headers: HeaderMap,
+        body: Value,
+    }
+
+    /// Serve `response` to every request, keeping what was asked for
+    async fn mock_host(response: Value) -> (String, Arc<Mutex<Vec<Received>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let log = received.clone();
+        let app = Router::new().fallback(
+            move |method: Method, uri: Uri, headers: HeaderMap, body: Bytes| {
+                let log = log.clone();
+                let response = response.clone();
+                async move {
+                    log.lock().unwrap().push(Received {
+                        method,
+                        path: uri.path().to_string(),
+                        headers,
+                        body: serde_json::from_slice(&body).unwrap_or(Value::Null),
+                    });
+                    axum::Json(response)
+                }
+            },
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (url, received)
+    }
+
+    fn settings(kind: GitHostKind, base_url: &str) -> ProjectGitHost {
+        ProjectGitHost {
+            project_id: Uuid::new_v4(),
+            kind,
+            base_url: Some(base_url.to_string()),
+            token: None,
+            has_token: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn request() -> CreatePrRequest {
+        CreatePrRequest {
+            title: "Add search".to_string(),
+            body: Some("Adds search".to_string()),
+            head_branch: "vk-1234-add-search".to_string(),
+            base_branch: "main".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gitlab_merge_requests() {
+        let (url, received) = mock_host(json!({
+            "iid": 7,
+            "web_url": "https://gitlab.example.com/group/sub/repo/-/merge_requests/7",
+            "state": "opened",
+            "merged_at": null,
+            "merge_commit_sha": null,
+        }))
+        .await;
+        let host = resolve(
+            Some(&settings(GitHostKind::GitLab, &format!("{}/gitlab", url))),
+            Some("glpat-secret"),
+            "git@gitlab.example.com:gitlab/group/sub/repo.git",
+            &Config::default().github,
+        )
+        .unwrap();
+        assert_eq!(host.push_credentials(), ("oauth2", "glpat-secret"));
+
+        let info = host.create_pull_request(&request()).await.unwrap();
+        assert_eq!(info.number, 7);
+        assert_eq!(info.status, "open");
+        assert!(!info.merged);
+
+        let received = received.lock().unwrap().clone();
+        assert_eq!(received[0].method, Method::POST);
+        assert_eq!(
+            received[0].path,
+            "/gitlab/api/v4/projects/group%2Fsub%2Frepo/merge_requests"
+        );
+        assert_eq!(received[0].headers["private-token"], "glpat-secret");
+        assert_eq!(received[0].body["source_branch"], "vk-1234-add-search");
+        assert_eq!(received[0].body["target_branch"], "main");
+    }
+
+    #[tokio::test]
+    async fn test_bitbucket_pull_requests() {
+        let (url, received) = mock_host(json!({
+            "id": 12,
+            "state": "MERGED",
+            "links": { "html": { "href": "https://bitbucket.org/team/repo/pull-requests/12" } },
+            "merge_commit": { "hash": "86ba9ca3f1e2" },
+            "updated_on": "2025-08-16T10:00:00Z",
+        }))
+        .await;
+        let host = resolve(
+            Some(&settings(GitHostKind::Bitbucket, &url)),
+            Some("bb-token"),
+            "git@bitbucket.org:team/repo.git",
+            &Config::default().github,
+        )
+        .unwrap();
+
+        let info = host.pull_request(12).await.unwrap();
+        assert_eq!(info.status, "merged");
+        assert!(info.merged);
+        assert!(info.merged_at.is_some());
+        assert_eq!(info.merge_commit_sha.as_deref(), Some("86ba9ca3f1e2"));
+        host.comment_on_pull_request(12, "Key decisions")
+            .await
+            .unwrap();
+
+        let received = received.lock().unwrap().clone();
+        assert_eq!(received[0].method, Method::GET);
+        assert_eq!(received[0].path, "/repositories/team/repo/pullrequests/12");
+        assert_eq!(received[0].headers["authorization"], "Bearer bb-token");
+        assert_eq!(received[1].method, Method::POST);
+        assert_eq!(
+            received[1].path,
+            "/repositories/team/repo/pullrequests/12/comments"
+        );
+        assert_eq!(received[1].body["content"]["raw"], "Key decisions");
+    }
+
+    #[tokio::test]
+    async fn test_resolves_host_from_remote() {
+        let mut github = Config::default().github;
+        let error = resolve(None, None, "https://gitlab.com/group/repo.git", &github)
+            .err()
+            .unwrap();
+        assert!(matches!(error, GitHostError::NotConfigured(_)));
+        assert!(error.to_string().contains("GitLab access token"));
+
+        let bitbucket = resolve(None, Some("t"), "git@bitbucket.org:team/repo.git", &github);
+        assert_eq!(bitbucket.unwrap().kind(), GitHostKind::Bitbucket);
+
+        assert!(resolve(None, None, "git@github.com:owner/repo.git", &github).is_err());
+        github.token = Some("gho_account".to_string());
+        let host = resolve(None, None, "git@github.com:owner/repo.git", &github).unwrap();
+        assert_eq!(host.kind(), GitHostKind::GitHub);
+        assert_eq!(host.push_credentials(), ("git", "gho_account"));
+    }
+
+    #[test]
+    fn test_file_urls() {
+        let github = RepositoryWeb::from_remote(None, "git@github.com:owner/repo.git").unwrap();
+        assert_eq!(
+            github.file_url("abc123", "src/main.rs"),
+            "https://github.com/owner/repo/blob/abc123/src/main.rs"
+        );
+        let bitbucket =
+            RepositoryWeb::from_remote(None, "https://bitbucket.org/team/repo.git").unwrap();
+        assert_eq!(
+            bitbucket.file_url("abc123", "README.md"),
+            "https://bitbucket.org/team/repo/src/abc123/README.md"
+        );
+
+        let settings = ProjectGitHost {
+            project_id: Uuid::new_v4(),
+            kind: GitHostKind::GitLab,
+            base_url: Some("http://git.internal:8080/gitlab".to_string()),
+            token: None,
+            has_token: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let gitlab = RepositoryWeb::from_remote(
+            Some(&settings),
+            "ssh://git@git.internal:2222/gitlab/group/sub/repo.git",
+        )
+        .unwrap();
+        assert_eq!(
+            gitlab.file_url("abc123", "lib/a.rb"),
+            "http://git.internal:8080/gitlab/group/sub/repo/-/blob/abc123/lib/a.rb"
+        );
+
+        assert_eq!(RepositoryWeb::from_remote(None, "/srv/repos/project"), None);
+    }
+
+    #[test]
+    fn test_error_message() {
+        assert_eq!(
+            error_message(r#"{"message":["Another open merge request already exists"]}"#),
+            "Another open merge request already exists"
+        );
+        assert_eq!(
+            error_message(r#"{"type":"error","error":{"message":"Repository not found"}}"#),
+            "Repository not found"
+        );
+        assert_eq!(error_message("Bad Gateway\n"), "Bad Gateway");
+    }
+}
@@ -1,5 +1,9 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+};
 
+use chrono::{DateTime, Utc};
 use git2::{
     build::CheckoutBuilder, BranchType, CherrypickOptions, Cred, DiffOptions, Error as GitError,
     FetchOptions, RemoteCallbacks, Repository, WorktreeAddOptions,
@@ -8,16 +12,29 @@ use regex;
 use tracing::{debug, info};
 
 use crate::{
-    models::task_attempt::{DiffChunk, DiffChunkType, FileDiff, WorktreeDiff},
-    utils::worktree_manager::WorktreeManager,
+    models::task_attempt::{
+        DiffChunk, DiffChunkType, DiffHunk, FileChangeKind, FileContent, FileDiff, FileHunks,
+        FileTreeNode, LineChange, LineChangeKind, SharedFileComparison, WorktreeComparison,
+        WorktreeDiff, WorktreeStats,
+    },
+    utils::{repo_lock, worktree_manager::WorktreeManager},
 };
 
+/// Where rebasing an attempt left its worktree
+#[derive(Debug)]
+pub struct RebaseOutcome {
+    /// Files left with conflict markers for someone to resolve, when conflicts were to be
+    /// left in the worktree instead of failing the rebase
+    pub conflicted_files: Vec<String>,
+}
+
 #[derive(Debug)]
 pub enum GitServiceError {
     Git(GitError),
     IoError(std::io::Error),
     InvalidRepository(String),
     BranchNotFound(String),
+    TagNotFound(String),
 
     MergeConflicts(String),
     InvalidPath(String),
@@ -31,6 +48,7 @@ impl std::fmt::Display for GitServiceError {
             GitServiceError::IoError(e) => write!(f, "IO error: {}", e),
             GitServiceError::InvalidRepository(e) => write!(f, "Invalid repository: {}", e),
             GitServiceError::BranchNotFound(e) => write!(f, "Branch not found: {}", e),
+            GitServiceError::TagNotFound(e) => write!(f, "Tag not found: {}", e),
 
             GitServiceError::MergeConflicts(e) => write!(f, "Merge conflicts: {}", e),
             GitServiceError::InvalidPath(e) => write!(f, "Invalid path: {}", e),
@@ -55,6 +73,9 @@ impl From<std::io::Error> for GitServiceError {
     }
 }
 
+/// Files larger than this are returned without content by [`GitService::get_file_content`]
+pub const MAX_FILE_CONTENT_BYTES: usize = 1024 * 1024;
+
 /// Service for managing Git operations in task execution workflows
 pub struct GitService {
     repo_path: PathBuf,
@@ -96,6 +117,17 @@ impl GitService {
         branch_name: &str,
         worktree_path: &Path,
         base_branch: Option<&str>,
+    ) -> Result<(), GitServiceError> {
+        repo_lock::with_repo_lock(&self.repo_path, || {
+            self.create_worktree_locked(branch_name, worktree_path, base_branch)
+        })
+    }
+
+    fn create_worktree_locked(
+        &self,
+        branch_name: &str,
+        worktree_path: &Path,
+        base_branch: Option<&str>,
     ) -> Result<(), GitServiceError> {
         let repo = self.open_repo()?;
 
@@ -187,13 +219,26 @@ impl GitService {
         Ok(())
     }
 
-    /// Merge changes from a worktree branch back to the main repository
+    /// Merge changes from a worktree branch back to the main repository. Merges into the
+    /// same repository run one at a time since they move its base branch.
     pub fn merge_changes(
         &self,
         worktree_path: &Path,
         branch_name: &str,
         base_branch_name: &str,
         commit_message: &str,
+    ) -> Result<String, GitServiceError> {
+        repo_lock::with_repo_lock(&self.repo_path, || {
+            self.merge_changes_locked(worktree_path, branch_name, base_branch_name, commit_message)
+        })
+    }
+
+    fn merge_changes_locked(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+        base_branch_name: &str,
+        commit_message: &str,
     ) -> Result<String, GitServiceError> {
         // Open the worktree repository
         let worktree_repo = Repository::open(worktree_path)?;
@@ -332,13 +377,17 @@ impl GitService {
         Ok(squash_commit_id)
     }
 
-    /// Rebase a worktree branch onto a new base
+    /// Rebase a worktree branch onto a new base. Conflicts are found before anything is
+    /// moved: unless `leave_conflicts`, the rebase then fails and the worktree stays as it
+    /// was; otherwise the branch's changes are put on the new base uncommitted, with conflict
+    /// markers in the files that conflict.
     pub fn rebase_branch(
         &self,
         worktree_path: &Path,
         new_base_branch: Option<&str>,
         old_base_branch: &str,
-    ) -> Result<String, GitServiceError> {
+        leave_conflicts: bool,
+    ) -> Result<RebaseOutcome, GitServiceError> {
         let worktree_repo = Repository::open(worktree_path)?;
         let main_repo = self.open_repo()?;
 
@@ -371,8 +420,9 @@ impl GitService {
             // This is a remote branch, fetch it and create/update local tracking branch
             let remote_branch_name = base_branch_name.strip_prefix("origin/").unwrap();
 
-            // First, fetch the latest changes from remote
-            self.fetch_from_remote(&main_repo)?;
+            // First, fetch the latest changes from remote, sharing a fetch that other attempts
+            // on this repository already have running
+            repo_lock::shared_fetch(&self.repo_path, || self.fetch_from_remote(&main_repo))?;
 
             // Try to find the remote branch after fetch
             let remote_branch = main_repo
@@ -380,20 +430,23 @@ impl GitService {
                 .map_err(|_| GitServiceError::BranchNotFound(base_branch_name.to_string()))?;
 
             // Check if local tracking branch exists
-            match main_repo.find_branch(remote_branch_name, BranchType::Local) {
-                Ok(mut local_branch) => {
-                    // Local tracking branch exists, update it to match remote
-                    let remote_commit = remote_branch.get().peel_to_commit()?;
-                    local_branch
-                        .get_mut()
-                        .set_target(remote_commit.id(), "Update local branch to match remote")?;
-                }
-                Err(_) => {
-                    // Local tracking branch doesn't exist, create it
-                    let remote_commit = remote_branch.get().peel_to_commit()?;
-                    main_repo.branch(remote_branch_name, &remote_commit, false)?;
+            let remote_commit = remote_branch.get().peel_to_commit()?;
+            repo_lock::with_repo_lock(&self.repo_path, || -> Result<(), GitServiceError> {
+                match main_repo.find_branch(remote_branch_name, BranchType::Local) {
+                    Ok(mut local_branch) => {
+                        // Local tracking branch exists, update it to match remote
+                        local_branch.get_mut().set_target(
+                            remote_commit.id(),
+                            "Update local branch to match remote",
+                        )?;
+                    }
+                    Err(_) => {
+                        // Local tracking branch doesn't exist, create it
+                        main_repo.branch(remote_branch_name, &remote_commit, false)?;
+                    }
                 }
-            }
+                Ok(())
+            })?;
 
             // Use the local branch name for rebase
             remote_branch_name
@@ -440,6 +493,29 @@ impl GitService {
         )?;
 
         if !unique_commits.is_empty() {
+            let conflicted_files =
+                Self::find_conflicts(&worktree_repo, task_branch_commit_id, new_base_commit_id)?;
+            if !conflicted_files.is_empty() {
+                if !leave_conflicts {
+                    return Err(GitServiceError::MergeConflicts(format!(
+                        "Rebasing onto {} conflicts in {}",
+                        local_branch_name,
+                        conflicted_files.join(", ")
+                    )));
+                }
+                Self::apply_with_conflict_markers(
+                    &worktree_repo,
+                    task_branch_commit_id,
+                    new_base_commit_id,
+                )?;
+                info!(
+                    "Rebased onto {} leaving conflicts in {}",
+                    local_branch_name,
+                    conflicted_files.join(", ")
+                );
+                return Ok(RebaseOutcome { conflicted_files });
+            }
+
             // Reset HEAD to the new base branch
             let new_base_commit = worktree_repo.find_commit(new_base_commit_id)?;
             worktree_repo.reset(new_base_commit.as_object(), git2::ResetType::Hard, None)?;
@@ -457,7 +533,62 @@ impl GitService {
         let final_commit = final_head.peel_to_commit()?;
 
         info!("Rebase completed. New HEAD: {}", final_commit.id());
-        Ok(final_commit.id().to_string())
+        Ok(RebaseOutcome {
+            conflicted_files: Vec::new(),
+        })
+    }
+
+    /// Files that would conflict if the changes of `branch` were replayed onto `base`
+    pub fn conflicting_files(
+        &self,
+        branch: git2::Oid,
+        base: git2::Oid,
+    ) -> Result<Vec<String>, GitServiceError> {
+        let repo = self.open_repo()?;
+        Self::find_conflicts(&repo, branch, base)
+    }
+
+    fn find_conflicts(
+        repo: &Repository,
+        branch: git2::Oid,
+        base: git2::Oid,
+    ) -> Result<Vec<String>, GitServiceError> {
+        let index =
+            repo.merge_commits(&repo.find_commit(base)?, &repo.find_commit(branch)?, None)?;
+        let mut paths = BTreeSet::new();
+        for conflict in index.conflicts()? {
+            let conflict = conflict?;
+            if let Some(entry) = conflict.our.or(conflict.their).or(conflict.ancestor) {
+                paths.insert(String::from_utf8_lossy(&entry.path).to_string());
+            }
+        }
+        Ok(paths.into_iter().collect())
+    }
+
+    /// Move the worktree to `base` with the changes of `branch` on top, uncommitted, and
+    /// conflict markers in the files where the two disagree. The commits of `branch` become
+    /// one change, committed once the conflicts are resolved.
+    fn apply_with_conflict_markers(
+        repo: &Repository,
+        branch: git2::Oid,
+        base: git2::Oid,
+    ) -> Result<(), GitServiceError> {
+        let base_commit = repo.find_commit(base)?;
+        let mut merged = repo.merge_commits(&base_commit, &repo.find_commit(branch)?, None)?;
+        repo.reset(base_commit.as_object(), git2::ResetType::Hard, None)?;
+
+        let mut checkout = CheckoutBuilder::new();
+        checkout
+            .force()
+            .allow_conflicts(true)
+            .conflict_style_merge(true);
+        repo.checkout_index(Some(&mut merged), Some(&mut checkout))?;
+
+        // Only the working tree carries the changes; the index stays at the new base
+        let mut index = repo.index()?;
+        index.read_tree(&base_commit.tree()?)?;
+        index.write()?;
+        Ok(())
     }
 
     /// Get enhanced diff for task attempts (from merge commit or worktree)
@@ -480,6 +611,418 @@ impl GitService {
         Ok(WorktreeDiff { files })
     }
 
+    /// Diff from the merge base with `base_branch` to the worktree's working directory,
+    /// covering committed, staged, unstaged and untracked changes
+    fn diff_from_merge_base<'a>(
+        &self,
+        worktree_repo: &'a Repository,
+        base_branch: &str,
+    ) -> Result<git2::Diff<'a>, GitServiceError> {
+        let main_repo = self.open_repo()?;
+
+        let base_branch_oid = main_repo
+            .find_branch(base_branch, BranchType::Local)
+            .map_err(|_| GitServiceError::BranchNotFound(base_branch.to_string()))?
+            .get()
+            .peel_to_commit()?
+            .id();
+        let worktree_head_oid = worktree_repo.head()?.peel_to_commit()?.id();
+        let base_oid = worktree_repo.merge_base(base_branch_oid, worktree_head_oid)?;
+        let base_tree = worktree_repo.find_commit(base_oid)?.tree()?;
+
+        let mut diff_opts = DiffOptions::new();
+        diff_opts
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+        Ok(
+            worktree_repo
+                .diff_tree_to_workdir_with_index(Some(&base_tree), Some(&mut diff_opts))?,
+        )
+    }
+
+    /// Changes in a worktree (committed and uncommitted) relative to its merge base with `base_branch`
+    pub fn get_worktree_changes(
+        &self,
+        worktree_path: &Path,
+        base_branch: &str,
+    ) -> Result<HashMap<String, FileChangeKind>, GitServiceError> {
+        let worktree_repo = Repository::open(worktree_path)?;
+        let diff = self.diff_from_merge_base(&worktree_repo, base_branch)?;
+
+        let mut changes = HashMap::new();
+        for delta in diff.deltas() {
+            let kind = match delta.status() {
+                git2::Delta::Added | git2::Delta::Untracked | git2::Delta::Copied => {
+                    FileChangeKind::Added
+                }
+                git2::Delta::Deleted => FileChangeKind::Deleted,
+                git2::Delta::Renamed => {
+                    if let Some(old_path) = delta.old_file().path() {
+                        changes.insert(
+                            old_path.to_string_lossy().to_string(),
+                            FileChangeKind::Deleted,
+                        );
+                    }
+                    FileChangeKind::Added
+                }
+                git2::Delta::Modified | git2::Delta::Typechange => FileChangeKind::Modified,
+                _ => continue,
+            };
+            if let Some(path) = delta.new_file().path().or(delta.old_file().path()) {
+                let path = path.to_string_lossy().to_string();
+                if !path.starts_with(".agent/") {
+                    changes.insert(path, kind);
+                }
+            }
+        }
+
+        Ok(changes)
+    }
+
+    /// Compare what two worktrees changed relative to `base_branch`: per-side line stats,
+    /// which files only one side touched, and how the shared files differ from each other
+    pub fn compare_worktrees(
+        &self,
+        left_path: &Path,
+        right_path: &Path,
+        base_branch: &str,
+    ) -> Result<WorktreeComparison, GitServiceError> {
+        let left = self.get_worktree_changes(left_path, base_branch)?;
+        let right = self.get_worktree_changes(right_path, base_branch)?;
+
+        let mut only_left: Vec<String> = left
+            .keys()
+            .filter(|p| !right.contains_key(*p))
+            .cloned()
+            .collect();
+        let mut only_right: Vec<String> = right
+            .keys()
+            .filter(|p| !left.contains_key(*p))
+            .cloned()
+            .collect();
+        only_left.sort();
+        only_right.sort();
+
+        let mut shared_paths: Vec<&String> =
+            left.keys().filter(|p| right.contains_key(*p)).collect();
+        shared_paths.sort();
+
+        let mut shared = Vec::new();
+        for path in shared_paths {
+            let read = |root: &Path| std::fs::read(root.join(path)).unwrap_or_default();
+            let (left_bytes, right_bytes) = (read(left_path), read(right_path));
+            let comparable = left_bytes.len().max(right_bytes.len()) <= MAX_FILE_CONTENT_BYTES
+                && !left_bytes
+                    .iter()
+                    .chain(&right_bytes)
+                    .take(8000)
+                    .any(|b| *b == 0);
+
+            let chunks = if comparable && left_bytes != right_bytes {
+                let mut opts = DiffOptions::new();
+                opts.context_lines(3);
+                let patch = git2::Patch::from_buffers(
+                    &left_bytes,
+                    Some(Path::new(path)),
+                    &right_bytes,
+                    Some(Path::new(path)),
+                    Some(&mut opts),
+                )?;
+                Self::patch_chunks(&patch)?
+            } else {
+                Vec::new()
+            };
+
+            shared.push(SharedFileComparison {
+                path: path.clone(),
+                left_change: left[path],
+                right_change: right[path],
+                identical: left_bytes == right_bytes,
+                left_to_right: chunks,
+            });
+        }
+
+        Ok(WorktreeComparison {
+            left: self.get_worktree_stats(left_path, base_branch)?,
+            right: self.get_worktree_stats(right_path, base_branch)?,
+            only_left,
+            only_right,
+            shared,
+        })
+    }
+
+    /// Files changed and lines added/removed relative to the merge base with `base_branch`
+    pub fn get_worktree_stats(
+        &self,
+        worktree_path: &Path,
+        base_branch: &str,
+    ) -> Result<WorktreeStats, GitServiceError> {
+        let worktree_repo = Repository::open(worktree_path)?;
+        let stats = self
+            .diff_from_merge_base(&worktree_repo, base_branch)?
+            .stats()?;
+        Ok(WorktreeStats {
+            files_changed: stats.files_changed(),
+            lines_added: stats.insertions(),
+            lines_removed: stats.deletions(),
+        })
+    }
+
+    /// The worktree's changes against its merge base with `base_branch`, split into hunks.
+    /// Binary files have no hunks and are left out.
+    pub fn get_worktree_hunks(
+        &self,
+        worktree_path: &Path,
+        base_branch: &str,
+    ) -> Result<Vec<FileHunks>, GitServiceError> {
+        let worktree_repo = Repository::open(worktree_path)?;
+        let diff = self.diff_from_merge_base(&worktree_repo, base_branch)?;
+        Self::file_hunks(&diff)
+    }
+
+    /// What changed in a worktree since `from_commit`, up to its HEAD. Returns HEAD too.
+    pub fn get_commit_hunks(
+        worktree_path: &Path,
+        from_commit: &str,
+    ) -> Result<(String, Vec<FileHunks>), GitServiceError> {
+        let worktree_repo = Repository::open(worktree_path)?;
+        let from_tree = worktree_repo
+            .find_commit(git2::Oid::from_str(from_commit)?)?
+            .tree()?;
+        let head = worktree_repo.head()?.peel_to_commit()?;
+        let diff = worktree_repo.diff_tree_to_tree(Some(&from_tree), Some(&head.tree()?), None)?;
+        Ok((head.id().to_string(), Self::file_hunks(&diff)?))
+    }
+
+    /// The hunks of each text file in `diff`, sorted by path
+    fn file_hunks(diff: &git2::Diff) -> Result<Vec<FileHunks>, GitServiceError> {
+        let mut files = Vec::new();
+        for delta_idx in 0..diff.deltas().len() {
+            let Some(patch) = git2::Patch::from_diff(diff, delta_idx)? else {
+                continue;
+            };
+            let delta = patch.delta();
+            if delta.flags().is_binary() {
+                continue;
+            }
+            let Some(path) = delta.new_file().path().or(delta.old_file().path()) else {
+                continue;
+            };
+            let path = path.to_string_lossy().replace('\\', "/");
+            if path.starts_with(".agent/") {
+                continue;
+            }
+
+            let mut hunks = Vec::new();
+            for hunk_idx in 0..patch.num_hunks() {
+                let (hunk, line_count) = patch.hunk(hunk_idx)?;
+                let mut lines = Vec::new();
+                for line_idx in 0..line_count {
+                    let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+                    let chunk_type = match line.origin() {
+                        ' ' => DiffChunkType::Equal,
+                        '+' => DiffChunkType::Insert,
+                        '-' => DiffChunkType::Delete,
+                        _ => continue,
+                    };
+                    lines.push(DiffChunk {
+                        chunk_type,
+                        content: String::from_utf8_lossy(line.content()).to_string(),
+                    });
+                }
+                hunks.push(DiffHunk {
+                    index: hunk_idx as u32,
+                    header: String::from_utf8_lossy(hunk.header())
+                        .trim_end()
+                        .to_string(),
+                    old_start: hunk.old_start(),
+                    new_start: hunk.new_start(),
+                    lines,
+                });
+            }
+            if !hunks.is_empty() {
+                files.push(FileHunks { path, hunks });
+            }
+        }
+
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(files)
+    }
+
+    /// Directory tree of a worktree, respecting .gitignore, annotated with changes against
+    /// `base_branch`. Deleted files are kept in the tree so reviewers can see them.
+    pub fn get_file_tree(
+        &self,
+        worktree_path: &Path,
+        base_branch: &str,
+    ) -> Result<Vec<FileTreeNode>, GitServiceError> {
+        use ignore::WalkBuilder;
+
+        let changes = self.get_worktree_changes(worktree_path, base_branch)?;
+
+        let mut entries: Vec<(String, bool)> = Vec::new();
+        let walker = WalkBuilder::new(worktree_path)
+            .git_ignore(true)
+            .git_global(true)
+            .git_exclude(true)
+            .hidden(false)
+            .filter_entry(|entry| entry.file_name() != ".git")
+            .build();
+        for result in walker {
+            let entry = result.map_err(|e| GitServiceError::IoError(std::io::Error::other(e)))?;
+            let Ok(relative) = entry.path().strip_prefix(worktree_path) else {
+                continue;
+            };
+            if relative.as_os_str().is_empty() {
+                continue;
+            }
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            entries.push((relative.to_string_lossy().replace('\\', "/"), is_dir));
+        }
+        for (path, kind) in &changes {
+            if *kind == FileChangeKind::Deleted {
+                entries.push((path.clone(), false));
+            }
+        }
+
+        let mut root = Vec::new();
+        for (path, is_dir) in entries {
+            insert_tree_node(&mut root, &path, is_dir, changes.get(&path).copied());
+        }
+        sort_tree(&mut root);
+        Ok(root)
+    }
+
+    /// Read a file from the worktree (or from `commit`), marking lines added or modified
+    /// relative to the merge base with `base_branch`
+    pub fn get_file_content(
+        &self,
+        worktree_path: &Path,
+        base_branch: &str,
+        path: &str,
+        commit: Option<&str>,
+    ) -> Result<FileContent, GitServiceError> {
+        let relative = Path::new(path);
+        if path.is_empty()
+            || relative
+                .components()
+                .any(|c| !matches!(c, std::path::Component::Normal(_)))
+        {
+            return Err(GitServiceError::InvalidPath(format!(
+                "Path must be relative to the worktree: {}",
+                path
+            )));
+        }
+
+        let worktree_repo = Repository::open(worktree_path)?;
+        let main_repo = self.open_repo()?;
+
+        let (head_oid, bytes) = match commit {
+            Some(commit) => {
+                let commit = worktree_repo.revparse_single(commit)?.peel_to_commit()?;
+                let entry = commit.tree()?.get_path(relative).map_err(|_| {
+                    GitServiceError::InvalidPath(format!("{} not found in {}", path, commit.id()))
+                })?;
+                let blob = worktree_repo.find_blob(entry.id())?;
+                (commit.id(), blob.content().to_vec())
+            }
+            None => {
+                let full_path = worktree_path.join(relative);
+                if !full_path.is_file() {
+                    return Err(GitServiceError::InvalidPath(format!(
+                        "{} not found in worktree",
+                        path
+                    )));
+                }
+                let head_oid = worktree_repo.head()?.peel_to_commit()?.id();
+                (head_oid, std::fs::read(full_path)?)
+            }
+        };
+
+        let size = bytes.len();
+        // Same heuristic git uses: a NUL byte near the start means binary
+        let is_binary = bytes.iter().take(8000).any(|b| *b == 0);
+        let too_large = size > MAX_FILE_CONTENT_BYTES;
+        if is_binary || too_large {
+            return Ok(FileContent {
+                path: path.to_string(),
+                size,
+                is_binary,
+                too_large,
+                content: None,
+                line_changes: Vec::new(),
+            });
+        }
+
+        let base_branch_oid = main_repo
+            .find_branch(base_branch, BranchType::Local)
+            .map_err(|_| GitServiceError::BranchNotFound(base_branch.to_string()))?
+            .get()
+            .peel_to_commit()?
+            .id();
+        let base_oid = worktree_repo.merge_base(base_branch_oid, head_oid)?;
+        let base_blob = worktree_repo
+            .find_commit(base_oid)?
+            .tree()?
+            .get_path(relative)
+            .ok()
+            .and_then(|entry| worktree_repo.find_blob(entry.id()).ok());
+
+        Ok(FileContent {
+            path: path.to_string(),
+            size,
+            is_binary: false,
+            too_large: false,
+            line_changes: Self::line_changes(base_blob.as_ref(), &bytes, relative)?,
+            content: Some(String::from_utf8_lossy(&bytes).to_string()),
+        })
+    }
+
+    /// Added lines in a hunk that also removes lines count as modified, up to the number removed
+    fn line_changes(
+        base: Option<&git2::Blob>,
+        current: &[u8],
+        path: &Path,
+    ) -> Result<Vec<LineChange>, GitServiceError> {
+        let mut opts = DiffOptions::new();
+        opts.context_lines(0);
+        let patch = match base {
+            Some(blob) => git2::Patch::from_blob_and_buffer(
+                blob,
+                Some(path),
+                current,
+                Some(path),
+                Some(&mut opts),
+            )?,
+            // New file: every line is an addition
+            None => git2::Patch::from_buffers(&[], None, current, Some(path), Some(&mut opts))?,
+        };
+
+        let mut changes = Vec::new();
+        for hunk in 0..patch.num_hunks() {
+            let mut removed = 0;
+            let mut added = Vec::new();
+            for line in 0..patch.num_lines_in_hunk(hunk)? {
+                let line = patch.line_in_hunk(hunk, line)?;
+                match line.origin() {
+                    '-' => removed += 1,
+                    '+' => added.extend(line.new_lineno()),
+                    _ => {}
+                }
+            }
+            changes.extend(added.into_iter().enumerate().map(|(i, line)| LineChange {
+                line: line as usize,
+                kind: if i < removed {
+                    LineChangeKind::Modified
+                } else {
+                    LineChangeKind::Added
+                },
+            }));
+        }
+        Ok(changes)
+    }
+
     /// Get diff from a merge commit
     fn get_merged_diff(
         &self,
@@ -713,8 +1256,6 @@ impl GitService {
         new_file: &git2::DiffFile,
         file_path: &str,
     ) -> Result<Vec<DiffChunk>, GitServiceError> {
-        let mut chunks = Vec::new();
-
         // Create a patch for the single file using Git's native diff
         let old_blob = if !old_file.id().is_zero() {
             Some(repo.find_blob(old_file.id())?)
@@ -756,10 +1297,17 @@ impl GitService {
                 Some(&mut diff_opts),
             )?,
             (None, None) => {
-                return Ok(chunks);
+                return Ok(Vec::new());
             }
         };
 
+        Self::patch_chunks(&patch)
+    }
+
+    /// Flatten a patch's hunks into equal/insert/delete chunks
+    fn patch_chunks(patch: &git2::Patch) -> Result<Vec<DiffChunk>, GitServiceError> {
+        let mut chunks = Vec::new();
+
         // Process the patch hunks
         for hunk_idx in 0..patch.num_hunks() {
             let (_hunk, hunk_lines) = patch.hunk(hunk_idx)?;
@@ -984,6 +1532,178 @@ impl GitService {
     }
 
     /// Get the default branch name for the repository
+    /// The commit a tag points at, and when it was made
+    pub fn resolve_tag(&self, tag: &str) -> Result<(String, DateTime<Utc>), GitServiceError> {
+        let repo = self.open_repo()?;
+        let commit = repo
+            .revparse_single(&format!("refs/tags/{}", tag))
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|_| GitServiceError::TagNotFound(tag.to_string()))?;
+        Ok((commit.id().to_string(), commit_time(&commit)))
+    }
+
+    /// When a commit was made, or `None` if the repository doesn't have it
+    pub fn commit_time(&self, sha: &str) -> Option<DateTime<Utc>> {
+        let repo = self.open_repo().ok()?;
+        let commit = repo.find_commit(git2::Oid::from_str(sha).ok()?).ok()?;
+        Some(commit_time(&commit))
+    }
+
+    /// Whether `ancestor` is `descendant` or reachable from it. `None` if either commit is
+    /// missing from the repository.
+    pub fn contains_commit(&self, descendant: &str, ancestor: &str) -> Option<bool> {
+        let repo = self.open_repo().ok()?;
+        let descendant = repo
+            .find_commit(git2::Oid::from_str(descendant).ok()?)
+            .ok()?;
+        let ancestor = repo.find_commit(git2::Oid::from_str(ancestor).ok()?).ok()?;
+        if descendant.id() == ancestor.id() {
+            return Some(true);
+        }
+        repo.graph_descendant_of(descendant.id(), ancestor.id())
+            .ok()
+    }
+
+    /// Move the worktree's branch back (or forward) to `commit`, discarding the worktree's
+    /// state. The commit it was on is kept under `backup_ref` and returned. Refuses when the
+    /// worktree has uncommitted changes to tracked files.
+    pub fn reset_worktree(
+        &self,
+        worktree_path: &Path,
+        commit: &str,
+        backup_ref: &str,
+    ) -> Result<String, GitServiceError> {
+        let worktree_repo = Repository::open(worktree_path)?;
+        self.check_worktree_clean(&worktree_repo)?;
+
+        let head = worktree_repo.head()?.peel_to_commit()?;
+        let target = worktree_repo.find_commit(git2::Oid::from_str(commit)?)?;
+        repo_lock::with_repo_lock(&self.repo_path, || -> Result<(), GitServiceError> {
+            worktree_repo.reference(
+                backup_ref,
+                head.id(),
+                false,
+                &format!("Before resetting to {}", target.id()),
+            )?;
+            let mut co = CheckoutBuilder::new();
+            co.force();
+            worktree_repo.reset(target.as_object(), git2::ResetType::Hard, Some(&mut co))?;
+            Ok(())
+        })?;
+
+        info!(
+            "Reset worktree {:?} from {} to {}",
+            worktree_path,
+            head.id(),
+            target.id()
+        );
+        Ok(head.id().to_string())
+    }
+
+    /// Commit the worktree as it is, uncommitted and untracked (but not ignored) files
+    /// included, on top of HEAD and under `snapshot_ref`, without touching the branch, the
+    /// index or the files. Returns the snapshot's commit.
+    pub fn snapshot_worktree(
+        worktree_path: &Path,
+        snapshot_ref: &str,
+    ) -> Result<String, GitServiceError> {
+        let worktree_repo = Repository::open(worktree_path)?;
+        let head = worktree_repo.head()?.peel_to_commit()?;
+
+        // Stage everything in memory only, then drop it again so the index is left alone
+        let mut index = worktree_repo.index()?;
+        index.add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)?;
+        index.update_all(["*"].iter(), None)?;
+        let tree_id = index.write_tree();
+        index.read(true)?;
+        let tree = worktree_repo.find_tree(tree_id?)?;
+
+        let signature = worktree_repo
+            .signature()
+            .or_else(|_| git2::Signature::now("Vibe Kanban", "noreply@vibekanban.com"))?;
+        let snapshot = worktree_repo.commit(
+            None,
+            &signature,
+            &signature,
+            &format!("Snapshot before run ({})", snapshot_ref),
+            &tree,
+            &[&head],
+        )?;
+        worktree_repo.reference(snapshot_ref, snapshot, true, "Snapshot before run")?;
+
+        debug!(
+            "Snapshot {} of worktree {:?} at {}",
+            snapshot, worktree_path, snapshot_ref
+        );
+        Ok(snapshot.to_string())
+    }
+
+    /// Undo what changed between the commits `before` and `after` on top of the worktree's
+    /// HEAD, keeping changes made since, and commit it with `message`. Returns the new
+    /// commit. Refuses when the worktree has uncommitted changes to tracked files, and fails
+    /// with the files when later changes conflict with undoing them.
+    pub fn revert_changes(
+        &self,
+        worktree_path: &Path,
+        before: &str,
+        after: &str,
+        message: &str,
+    ) -> Result<String, GitServiceError> {
+        let worktree_repo = Repository::open(worktree_path)?;
+        self.check_worktree_clean(&worktree_repo)?;
+
+        let before_tree = worktree_repo
+            .find_commit(git2::Oid::from_str(before)?)?
+            .tree()?;
+        let after_tree = worktree_repo
+            .find_commit(git2::Oid::from_str(after)?)?
+            .tree()?;
+        let head = worktree_repo.head()?.peel_to_commit()?;
+
+        // A three-way merge with the run's result as the base takes the run's changes back
+        // out of HEAD, the way `git revert` does
+        let mut index =
+            worktree_repo.merge_trees(&after_tree, &head.tree()?, &before_tree, None)?;
+        if index.has_conflicts() {
+            let files: BTreeSet<String> = index
+                .conflicts()?
+                .filter_map(|conflict| {
+                    let conflict = conflict.ok()?;
+                    let entry = conflict.our.or(conflict.their).or(conflict.ancestor)?;
+                    Some(String::from_utf8_lossy(&entry.path).to_string())
+                })
+                .collect();
+            return Err(GitServiceError::MergeConflicts(
+                files.into_iter().collect::<Vec<_>>().join(", "),
+            ));
+        }
+        let tree = worktree_repo.find_tree(index.write_tree_to(&worktree_repo)?)?;
+
+        let signature = worktree_repo
+            .signature()
+            .or_else(|_| git2::Signature::now("Vibe Kanban", "noreply@vibekanban.com"))?;
+        let commit_id = repo_lock::with_repo_lock(&self.repo_path, || {
+            let commit_id = worktree_repo.commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &[&head],
+            )?;
+            let mut co = CheckoutBuilder::new();
+            co.force();
+            worktree_repo.checkout_head(Some(&mut co))?;
+            Ok::<_, GitServiceError>(commit_id)
+        })?;
+
+        info!(
+            "Reverted {}..{} in worktree {:?} as {}",
+            before, after, worktree_path, commit_id
+        );
+        Ok(commit_id.to_string())
+    }
+
     pub fn get_default_branch_name(&self) -> Result<String, GitServiceError> {
         let repo = self.open_repo()?;
 
@@ -1079,6 +1799,17 @@ impl GitService {
         Ok(stored_worktree_path.to_path_buf())
     }
 
+    /// URL of the repository's origin remote
+    pub fn origin_url(&self) -> Result<String, GitServiceError> {
+        let repo = self.open_repo()?;
+        let remote = repo.find_remote("origin").map_err(|_| {
+            GitServiceError::InvalidRepository("No 'origin' remote found".to_string())
+        })?;
+        remote.url().map(str::to_string).ok_or_else(|| {
+            GitServiceError::InvalidRepository("Remote origin has no URL".to_string())
+        })
+    }
+
     /// Extract GitHub owner and repo name from git repo path
     pub fn get_github_repo_info(&self) -> Result<(String, String), GitServiceError> {
         let repo = self.open_repo()?;
@@ -1092,8 +1823,9 @@ impl GitService {
 
         // Parse GitHub URL (supports both HTTPS and SSH formats)
         // This regex supports both github.com and GitHub Enterprise domains
-        let github_regex = regex::Regex::new(r"(?:https?://|git@)([^/]+)[:/]([^/]+)/(.+?)(?:\.git)?/?$")
-            .map_err(|e| GitServiceError::InvalidRepository(format!("Regex error: {}", e)))?;
+        let github_regex =
+            regex::Regex::new(r"(?:https?://|git@)([^/]+)[:/]([^/]+)/(.+?)(?:\.git)?/?$")
+                .map_err(|e| GitServiceError::InvalidRepository(format!("Regex error: {}", e)))?;
 
         if let Some(captures) = github_regex.captures(url) {
             let domain = captures.get(1).unwrap().as_str();
@@ -1117,12 +1849,14 @@ impl GitService {
         }
     }
 
-    /// Push the branch to GitHub remote
-    pub fn push_to_github(
+    /// Push the branch to origin over HTTPS, authenticating as `username` with `token`.
+    /// SSH remotes are pushed to at the same path over HTTPS instead.
+    pub fn push_with_token(
         &self,
         worktree_path: &Path,
         branch_name: &str,
-        github_token: &str,
+        username: &str,
+        token: &str,
     ) -> Result<(), GitServiceError> {
         let repo = Repository::open(worktree_path)?;
 
@@ -1133,15 +1867,7 @@ impl GitService {
         })?;
 
         // Convert SSH URL to HTTPS URL if necessary
-        let https_url = if remote_url.starts_with("git@github.com:") {
-            // Convert git@github.com:owner/repo.git to https://github.com/owner/repo.git
-            remote_url.replace("git@github.com:", "https://github.com/")
-        } else if remote_url.starts_with("ssh://git@github.com/") {
-            // Convert ssh://git@github.com/owner/repo.git to https://github.com/owner/repo.git
-            remote_url.replace("ssh://git@github.com/", "https://github.com/")
-        } else {
-            remote_url.to_string()
-        };
+        let https_url = https_remote_url(remote_url);
 
         // Create a temporary remote with HTTPS URL for pushing
         let temp_remote_name = "temp_https_origin";
@@ -1155,10 +1881,10 @@ impl GitService {
         // Create refspec for pushing the branch
         let refspec = format!("refs/heads/{}:refs/heads/{}", branch_name, branch_name);
 
-        // Set up authentication callback using the GitHub token
+        // Set up authentication callback using the token
         let mut callbacks = git2::RemoteCallbacks::new();
         callbacks.credentials(|_url, username_from_url, _allowed_types| {
-            git2::Cred::userpass_plaintext(username_from_url.unwrap_or("git"), github_token)
+            git2::Cred::userpass_plaintext(username_from_url.unwrap_or(username), token)
         });
 
         // Configure push options
@@ -1174,7 +1900,7 @@ impl GitService {
         // Check push result
         push_result?;
 
-        info!("Pushed branch {} to GitHub using HTTPS", branch_name);
+        info!("Pushed branch {} over HTTPS", branch_name);
         Ok(())
     }
 
@@ -1304,17 +2030,18 @@ impl GitService {
         Ok(())
     }
 
-    /// Clone a repository to the specified directory
-    pub fn clone_repository(
-        clone_url: &str,
-        target_path: &Path,
-        token: Option<&str>,
-    ) -> Result<Repository, GitServiceError> {
-        if let Some(parent) = target_path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
+    /// Whether the repository's origin points at `clone_url`, however either URL is spelled
+    pub fn has_origin(&self, clone_url: &str) -> bool {
+        self.open_repo()
+            .ok()
+            .and_then(|repo| {
+                let remote = repo.find_remote("origin").ok()?;
+                Some(normalize_remote_url(remote.url()?) == normalize_remote_url(clone_url))
+            })
+            .unwrap_or(false)
+    }
 
-        // Set up callbacks for authentication if token is provided
+    fn clone_callbacks(token: Option<&str>) -> RemoteCallbacks<'_> {
         let mut callbacks = RemoteCallbacks::new();
         if let Some(token) = token {
             callbacks.credentials(|_url, username_from_url, _allowed_types| {
@@ -1336,16 +2063,38 @@ impl GitService {
                 Cred::ssh_key(username_from_url.unwrap_or("git"), None, &key_path, None)
             });
         }
+        callbacks
+    }
+
+    /// Clone a repository to the specified directory. With a `reference` (another local
+    /// clone of the same remote) the clone borrows its objects like `git clone --reference`,
+    /// downloading only what the reference lacks. The reference has to stay where it is for
+    /// as long as the clone exists.
+    pub fn clone_repository(
+        clone_url: &str,
+        target_path: &Path,
+        token: Option<&str>,
+        reference: Option<&Path>,
+    ) -> Result<Repository, GitServiceError> {
+        if let Some(parent) = target_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
 
         // Set up fetch options with our callbacks
         let mut fetch_opts = FetchOptions::new();
-        fetch_opts.remote_callbacks(callbacks);
-
-        // Create a repository builder with fetch options
-        let mut builder = git2::build::RepoBuilder::new();
-        builder.fetch_options(fetch_opts);
+        fetch_opts.remote_callbacks(Self::clone_callbacks(token));
 
-        let repo = builder.clone(clone_url, target_path)?;
+        let repo = match reference {
+            Some(reference) => {
+                Self::clone_with_reference(clone_url, target_path, reference, fetch_opts)?
+            }
+            None => {
+                // Create a repository builder with fetch options
+                let mut builder = git2::build::RepoBuilder::new();
+                builder.fetch_options(fetch_opts);
+                builder.clone(clone_url, target_path)?
+            }
+        };
 
         tracing::info!(
             "Successfully cloned repository from {} to {}",
@@ -1355,6 +2104,206 @@ impl GitService {
 
         Ok(repo)
     }
+
+    fn clone_with_reference(
+        clone_url: &str,
+        target_path: &Path,
+        reference: &Path,
+        mut fetch_opts: FetchOptions<'_>,
+    ) -> Result<Repository, GitServiceError> {
+        const BORROWED_REFS: &str = "refs/vibe-kanban-reference/";
+
+        let reference_repo = Repository::open(reference)?;
+        let reference_objects = std::fs::canonicalize(reference_repo.path().join("objects"))?;
+
+        let repo = Repository::init(target_path)?;
+        let info_dir = repo.path().join("objects").join("info");
+        std::fs::create_dir_all(&info_dir)?;
+        std::fs::write(
+            info_dir.join("alternates"),
+            format!("{}\n", reference_objects.display()),
+        )?;
+
+        // The fetch negotiates from local refs, so point some at the reference's commits to
+        // have the server skip everything reachable from them
+        for (index, reference) in reference_repo.references()?.flatten().enumerate() {
+            if let Some(oid) = reference.target() {
+                repo.reference(
+                    &format!("{}{}", BORROWED_REFS, index),
+                    oid,
+                    true,
+                    "borrow reference commit",
+                )?;
+            }
+        }
+
+        let fetched = (|| -> Result<(), GitServiceError> {
+            let mut remote = repo.remote("origin", clone_url)?;
+            remote.fetch(&[] as &[&str], Some(&mut fetch_opts), None)?;
+
+            let default_branch = remote
+                .default_branch()
+                .ok()
+                .and_then(|name| {
+                    name.as_str()
+                        .and_then(|name| name.strip_prefix("refs/heads/"))
+                        .map(str::to_string)
+                })
+                .or_else(|| {
+                    ["main", "master"]
+                        .into_iter()
+                        .map(str::to_string)
+                        .find(|name| {
+                            repo.find_branch(&format!("origin/{}", name), BranchType::Remote)
+                                .is_ok()
+                        })
+                });
+            if let Some(default_branch) = default_branch {
+                let commit = repo
+                    .find_branch(&format!("origin/{}", default_branch), BranchType::Remote)?
+                    .get()
+                    .peel_to_commit()?;
+                let mut branch = repo.branch(&default_branch, &commit, true)?;
+                branch.set_upstream(Some(&format!("origin/{}", default_branch)))?;
+                repo.set_head(&format!("refs/heads/{}", default_branch))?;
+                let mut checkout = CheckoutBuilder::new();
+                checkout.force();
+                repo.checkout_head(Some(&mut checkout))?;
+            }
+            Ok(())
+        })();
+
+        let borrowed: Vec<String> = repo
+            .references_glob(&format!("{}*", BORROWED_REFS))?
+            .flatten()
+            .filter_map(|reference| reference.name().map(str::to_string))
+            .collect();
+        for name in borrowed {
+            if let Ok(mut reference) = repo.find_reference(&name) {
+                let _ = reference.delete();
+            }
+        }
+
+        fetched?;
+        Ok(repo)
+    }
+}
+
+/// Compare-friendly form of a remote URL: `git@host:owner/repo.git`,
+/// `ssh://git@host/owner/repo` and `https://host/owner/repo/` all become `host/owner/repo`
+pub fn normalize_remote_url(url: &str) -> String {
+    let url = url.trim();
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let without_user = without_scheme
+        .split_once('@')
+        .filter(|(user, _)| !user.contains('/'))
+        .map_or(without_scheme, |(_, rest)| rest);
+    // scp-like syntax separates host and path with a colon
+    let (host, path) = match without_user.split_once(['/', ':']) {
+        Some((host, path)) => (host, path),
+        None => (without_user, ""),
+    };
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    format!("{}/{}", host.to_lowercase(), path)
+}
+
+/// Host and repository path of a remote URL: `git@gitlab.example.com:group/sub/repo.git`
+/// gives `("gitlab.example.com", "group/sub/repo")`. The port of an SSH URL is left out,
+/// since it isn't the HTTPS one; an HTTPS URL's port is kept.
+pub fn remote_host_and_path(url: &str) -> Option<(String, String)> {
+    let url = url.trim();
+    let (scheme, rest) = match url.split_once("://") {
+        Some((scheme, rest)) => (Some(scheme), rest),
+        None => (None, url),
+    };
+    let rest = rest
+        .split_once('@')
+        .filter(|(user, _)| !user.contains('/'))
+        .map_or(rest, |(_, rest)| rest);
+    let (host, path) = match scheme {
+        // scp-like syntax separates host and path with a colon
+        None => rest.split_once(':')?,
+        Some(_) => rest.split_once('/')?,
+    };
+    let host = match scheme {
+        Some("https") | Some("http") => host,
+        _ => host.split(':').next().unwrap_or(host),
+    };
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    if host.is_empty() || path.is_empty() || host.contains('/') {
+        return None;
+    }
+    Some((host.to_lowercase(), path.to_string()))
+}
+
+/// The HTTPS URL to push to a remote with a token. HTTPS remotes, and anything that isn't
+/// a URL of a host, such as a local path, are left as they are.
+pub fn https_remote_url(url: &str) -> String {
+    if url.starts_with("https://") || url.starts_with("http://") {
+        return url.to_string();
+    }
+    match remote_host_and_path(url) {
+        Some((host, path)) => format!("https://{}/{}.git", host, path),
+        None => url.to_string(),
+    }
+}
+
+/// Insert `path` into the tree, creating any missing parent directories
+fn insert_tree_node(
+    nodes: &mut Vec<FileTreeNode>,
+    path: &str,
+    is_dir: bool,
+    change: Option<FileChangeKind>,
+) {
+    let mut level = nodes;
+    let mut prefix = String::new();
+    let mut segments = path.split('/').peekable();
+
+    while let Some(segment) = segments.next() {
+        if !prefix.is_empty() {
+            prefix.push('/');
+        }
+        prefix.push_str(segment);
+        let is_last = segments.peek().is_none();
+
+        let index = match level.iter().position(|n| n.name == segment) {
+            Some(index) => index,
+            None => {
+                level.push(FileTreeNode {
+                    name: segment.to_string(),
+                    path: prefix.clone(),
+                    is_dir: !is_last || is_dir,
+                    change: None,
+                    has_changes: false,
+                    children: Vec::new(),
+                });
+                level.len() - 1
+            }
+        };
+
+        let node = &mut level[index];
+        if change.is_some() {
+            node.has_changes = true;
+            if is_last {
+                node.change = change;
+            }
+        }
+        level = &mut node.children;
+    }
+}
+
+fn commit_time(commit: &git2::Commit) -> DateTime<Utc> {
+    DateTime::from_timestamp(commit.time().seconds(), 0).unwrap_or_else(Utc::now)
+}
+
+/// Directories first, then files, each alphabetically
+fn sort_tree(nodes: &mut [FileTreeNode]) {
+    nodes.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+    for node in nodes {
+        sort_tree(&mut node.children);
+    }
 }
 
 #[cfg(test)]
@@ -1430,20 +2379,477 @@ mod tests {
             "Add test files",
             &tree,
             &[&head],
-        ).unwrap();
+        )
+        .unwrap();
 
         // Now modify the agent file to create unstaged changes
         std::fs::write(&agent_file, "modified agent content").unwrap();
 
         // check_worktree_clean should pass because .agent/ files are ignored
         let result = git_service.check_worktree_clean(&repo);
-        assert!(result.is_ok(), "check_worktree_clean should ignore .agent/ files");
+        assert!(
+            result.is_ok(),
+            "check_worktree_clean should ignore .agent/ files"
+        );
 
         // Modify the regular file to create unstaged changes
         std::fs::write(&test_file, "modified test content").unwrap();
 
         // Now check_worktree_clean should fail because regular files are dirty
         let result = git_service.check_worktree_clean(&repo);
-        assert!(result.is_err(), "check_worktree_clean should detect dirty regular files");
+        assert!(
+            result.is_err(),
+            "check_worktree_clean should detect dirty regular files"
+        );
+    }
+
+    #[test]
+    fn test_tag_resolution_and_ancestry() {
+        let (temp_dir, repo) = create_test_repo();
+        let git_service = GitService::new(temp_dir.path()).unwrap();
+        git_service.create_initial_commit(&repo).unwrap();
+
+        let first = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.tag_lightweight("v1.0.0", first.as_object(), false)
+            .unwrap();
+        let signature = repo.signature().unwrap();
+        let second = repo
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                "After the tag",
+                &first.tree().unwrap(),
+                &[&first],
+            )
+            .unwrap()
+            .to_string();
+
+        let (tag_commit, _) = git_service.resolve_tag("v1.0.0").unwrap();
+        assert_eq!(tag_commit, first.id().to_string());
+        assert!(matches!(
+            git_service.resolve_tag("v2.0.0"),
+            Err(GitServiceError::TagNotFound(_))
+        ));
+        assert_eq!(
+            git_service.contains_commit(&tag_commit, &tag_commit),
+            Some(true)
+        );
+        assert_eq!(
+            git_service.contains_commit(&second, &tag_commit),
+            Some(true)
+        );
+        assert_eq!(
+            git_service.contains_commit(&tag_commit, &second),
+            Some(false)
+        );
+        assert_eq!(
+            git_service.contains_commit(&tag_commit, &"0".repeat(40)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_file_tree_marks_changes() {
+        let (temp_dir, repo) = create_test_repo();
+        let git_service = GitService::new(temp_dir.path()).unwrap();
+        git_service.create_initial_commit(&repo).unwrap();
+
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("a.txt"), "a").unwrap();
+        std::fs::write(root.join("src/lib.rs"), "lib").unwrap();
+        std::fs::write(root.join("src/main.rs"), "main").unwrap();
+        let mut index = repo.index().unwrap();
+        for path in ["a.txt", "src/lib.rs", "src/main.rs"] {
+            index.add_path(Path::new(path)).unwrap();
+        }
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = repo.signature().unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "Add files",
+            &tree,
+            &[&head],
+        )
+        .unwrap();
+
+        std::fs::write(root.join("a.txt"), "changed").unwrap();
+        std::fs::remove_file(root.join("src/lib.rs")).unwrap();
+        std::fs::write(root.join("new.txt"), "new").unwrap();
+        std::fs::write(root.join(".gitignore"), "target/\n").unwrap();
+        std::fs::create_dir_all(root.join("target")).unwrap();
+        std::fs::write(root.join("target/out"), "build output").unwrap();
+
+        let tree = git_service.get_file_tree(root, "main").unwrap();
+        let summary: Vec<_> = tree
+            .iter()
+            .map(|n| (n.path.as_str(), n.change, n.has_changes))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("src", None, true),
+                (".gitignore", Some(FileChangeKind::Added), true),
+                ("a.txt", Some(FileChangeKind::Modified), true),
+                ("new.txt", Some(FileChangeKind::Added), true),
+            ]
+        );
+
+        let src: Vec<_> = tree[0]
+            .children
+            .iter()
+            .map(|n| (n.path.as_str(), n.change))
+            .collect();
+        assert_eq!(
+            src,
+            vec![
+                ("src/lib.rs", Some(FileChangeKind::Deleted)),
+                ("src/main.rs", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_file_content_line_changes() {
+        let (temp_dir, repo) = create_test_repo();
+        let git_service = GitService::new(temp_dir.path()).unwrap();
+        git_service.create_initial_commit(&repo).unwrap();
+
+        let root = temp_dir.path();
+        std::fs::write(root.join("notes.txt"), "one\ntwo\nthree\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("notes.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = repo.signature().unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let base_commit = repo
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                "Add notes",
+                &tree,
+                &[&head],
+            )
+            .unwrap();
+
+        std::fs::write(root.join("notes.txt"), "one\nTWO\nthree\nfour\n").unwrap();
+        std::fs::write(root.join("image.bin"), [0u8, 159, 146, 150]).unwrap();
+
+        let content = git_service
+            .get_file_content(root, "main", "notes.txt", None)
+            .unwrap();
+        assert_eq!(content.content.as_deref(), Some("one\nTWO\nthree\nfour\n"));
+        assert_eq!(
+            content.line_changes,
+            vec![
+                LineChange {
+                    line: 2,
+                    kind: LineChangeKind::Modified
+                },
+                LineChange {
+                    line: 4,
+                    kind: LineChangeKind::Added
+                },
+            ]
+        );
+
+        // Reading the base commit itself shows no changes
+        let committed = git_service
+            .get_file_content(root, "main", "notes.txt", Some(&base_commit.to_string()))
+            .unwrap();
+        assert_eq!(committed.content.as_deref(), Some("one\ntwo\nthree\n"));
+        assert!(committed.line_changes.is_empty());
+
+        let binary = git_service
+            .get_file_content(root, "main", "image.bin", None)
+            .unwrap();
+        assert!(binary.is_binary);
+        assert_eq!(binary.content, None);
+
+        assert!(matches!(
+            git_service.get_file_content(root, "main", "../etc/passwd", None),
+            Err(GitServiceError::InvalidPath(_))
+        ));
+    }
+
+    #[test]
+    fn test_compare_worktrees() {
+        let (temp_dir, repo) = create_test_repo();
+        let git_service = GitService::new(temp_dir.path()).unwrap();
+        git_service.create_initial_commit(&repo).unwrap();
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let mut paths = Vec::new();
+        for name in ["left", "right"] {
+            let branch = repo.branch(name, &head, false).unwrap();
+            let path = temp_dir.path().join(format!("wt-{}", name));
+            let mut opts = WorktreeAddOptions::new();
+            opts.reference(Some(branch.get()));
+            repo.worktree(name, &path, Some(&opts)).unwrap();
+            paths.push(path);
+        }
+        let (left, right) = (&paths[0], &paths[1]);
+
+        std::fs::write(left.join("shared.txt"), "a\nb\n").unwrap();
+        std::fs::write(right.join("shared.txt"), "a\nc\n").unwrap();
+        std::fs::write(left.join("same.txt"), "same\n").unwrap();
+        std::fs::write(right.join("same.txt"), "same\n").unwrap();
+        std::fs::write(left.join("left_only.txt"), "l\n").unwrap();
+
+        let comparison = git_service.compare_worktrees(left, right, "main").unwrap();
+        assert_eq!(comparison.only_left, vec!["left_only.txt".to_string()]);
+        assert!(comparison.only_right.is_empty());
+        assert_eq!(
+            comparison.left,
+            WorktreeStats {
+                files_changed: 3,
+                lines_added: 4,
+                lines_removed: 0,
+            }
+        );
+
+        let paths: Vec<_> = comparison
+            .shared
+            .iter()
+            .map(|f| (f.path.as_str(), f.identical))
+            .collect();
+        assert_eq!(paths, vec![("same.txt", true), ("shared.txt", false)]);
+        let shared = &comparison.shared[1];
+        assert_eq!(shared.left_change, FileChangeKind::Added);
+        assert!(shared
+            .left_to_right
+            .iter()
+            .any(|c| matches!(c.chunk_type, DiffChunkType::Delete) && c.content == "b\n"));
+        assert!(shared
+            .left_to_right
+            .iter()
+            .any(|c| matches!(c.chunk_type, DiffChunkType::Insert) && c.content == "c\n"));
+    }
+
+    /// Commit `content` as `path` on the branch `repo` has checked out
+    fn commit_file(repo: &Repository, path: &str, content: &str) -> git2::Oid {
+        std::fs::write(repo.workdir().unwrap().join(path), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(path)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        let signature = repo.signature().unwrap();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            path,
+            &tree,
+            &[&parent],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_rebase_conflicts() {
+        let (temp_dir, repo) = create_test_repo();
+        let git_service = GitService::new(temp_dir.path()).unwrap();
+        git_service.create_initial_commit(&repo).unwrap();
+        commit_file(&repo, "a.txt", "one\n");
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let branch = repo.branch("task", &head, false).unwrap();
+        let worktree_path = temp_dir.path().join("wt-task");
+        let mut opts = WorktreeAddOptions::new();
+        opts.reference(Some(branch.get()));
+        repo.worktree("task", &worktree_path, Some(&opts)).unwrap();
+        let worktree = Repository::open(&worktree_path).unwrap();
+        let task_commit = commit_file(&worktree, "a.txt", "task\n");
+        let base_commit = commit_file(&repo, "a.txt", "main\n");
+
+        assert_eq!(
+            git_service
+                .conflicting_files(task_commit, base_commit)
+                .unwrap(),
+            vec!["a.txt".to_string()]
+        );
+
+        // Without leaving conflicts, nothing is moved
+        assert!(matches!(
+            git_service.rebase_branch(&worktree_path, Some("main"), "main", false),
+            Err(GitServiceError::MergeConflicts(_))
+        ));
+        assert_eq!(
+            worktree.head().unwrap().peel_to_commit().unwrap().id(),
+            task_commit
+        );
+
+        let outcome = git_service
+            .rebase_branch(&worktree_path, Some("main"), "main", true)
+            .unwrap();
+        assert_eq!(
+            worktree.head().unwrap().peel_to_commit().unwrap().id(),
+            base_commit
+        );
+        assert_eq!(outcome.conflicted_files, vec!["a.txt".to_string()]);
+        let content = std::fs::read_to_string(worktree_path.join("a.txt")).unwrap();
+        assert!(
+            content.contains("<<<<<<<") && content.contains("task") && content.contains("main")
+        );
+    }
+
+    #[test]
+    fn test_normalize_remote_url() {
+        for url in [
+            "git@github.com:BloopAI/vibe-kanban.git",
+            "ssh://git@github.com/BloopAI/vibe-kanban",
+            "https://GitHub.com/BloopAI/vibe-kanban.git",
+            "https://github.com/BloopAI/vibe-kanban/",
+        ] {
+            assert_eq!(normalize_remote_url(url), "github.com/BloopAI/vibe-kanban");
+        }
+        assert_ne!(
+            normalize_remote_url("https://github.com/BloopAI/other"),
+            normalize_remote_url("https://github.com/BloopAI/vibe-kanban")
+        );
+    }
+
+    #[test]
+    fn test_remote_host_and_path() {
+        let expected = Some((
+            "gitlab.example.com".to_string(),
+            "group/sub/repo".to_string(),
+        ));
+        for url in [
+            "git@gitlab.example.com:group/sub/repo.git",
+            "ssh://git@gitlab.example.com:2222/group/sub/repo.git",
+            "https://gitlab.example.com/group/sub/repo",
+            "https://oauth2@GitLab.example.com/group/sub/repo.git/",
+        ] {
+            assert_eq!(remote_host_and_path(url), expected, "parsing {}", url);
+        }
+        assert_eq!(
+            remote_host_and_path("https://git.example.com:8443/team/repo.git"),
+            Some(("git.example.com:8443".to_string(), "team/repo".to_string()))
+        );
+        assert_eq!(remote_host_and_path("/srv/git/repo.git"), None);
+
+        assert_eq!(
+            https_remote_url("git@bitbucket.org:team/repo.git"),
+            "https://bitbucket.org/team/repo.git"
+        );
+        assert_eq!(https_remote_url("/srv/git/repo.git"), "/srv/git/repo.git");
+    }
+
+    #[test]
+    fn test_clone_with_reference_borrows_objects() {
+        let (origin_dir, origin) = create_test_repo();
+        let origin_service = GitService::new(origin_dir.path()).unwrap();
+        origin_service.create_initial_commit(&origin).unwrap();
+        let origin_url = origin_dir.path().to_string_lossy().to_string();
+
+        let workspace = TempDir::new().unwrap();
+        let reference_path = workspace.path().join("reference");
+        GitService::clone_repository(&origin_url, &reference_path, None, None).unwrap();
+
+        // Something the reference doesn't have yet
+        let parent = origin.head().unwrap().peel_to_commit().unwrap();
+        let signature = origin.signature().unwrap();
+        let latest = origin
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                "After the reference was cloned",
+                &parent.tree().unwrap(),
+                &[&parent],
+            )
+            .unwrap();
+
+        let clone_path = workspace.path().join("clone");
+        let clone =
+            GitService::clone_repository(&origin_url, &clone_path, None, Some(&reference_path))
+                .unwrap();
+
+        assert_eq!(clone.head().unwrap().peel_to_commit().unwrap().id(), latest);
+        assert_eq!(clone.head().unwrap().shorthand(), Some("main"));
+        assert!(clone.path().join("objects/info/alternates").exists());
+        assert!(clone
+            .references_glob("refs/vibe-kanban-reference/*")
+            .unwrap()
+            .next()
+            .is_none());
+        assert!(GitService::new(&clone_path)
+            .unwrap()
+            .has_origin(&origin_url));
+    }
+
+    #[test]
+    fn test_revert_one_run_keeping_the_others() {
+        let (temp_dir, repo) = create_test_repo();
+        let git_service = GitService::new(temp_dir.path()).unwrap();
+        git_service.create_initial_commit(&repo).unwrap();
+        let root = temp_dir.path();
+        let commit_all = |message: &str| {
+            let mut index = repo.index().unwrap();
+            index
+                .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+                .unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let signature = repo.signature().unwrap();
+            let head = repo.head().unwrap().peel_to_commit().unwrap();
+            repo.commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &[&head],
+            )
+            .unwrap()
+            .to_string()
+        };
+        std::fs::write(root.join("a.txt"), "a\n").unwrap();
+        std::fs::write(root.join("b.txt"), "b\n").unwrap();
+        commit_all("Add files");
+
+        // Left uncommitted when the first run starts
+        std::fs::write(root.join("notes.txt"), "mine\n").unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap().id();
+        let first_snapshot =
+            GitService::snapshot_worktree(root, "refs/vibe-kanban/snapshots/a/1").unwrap();
+        assert_eq!(repo.head().unwrap().peel_to_commit().unwrap().id(), head);
+        assert!(repo
+            .index()
+            .unwrap()
+            .get_path(Path::new("notes.txt"), 0)
+            .is_none());
+
+        std::fs::write(root.join("a.txt"), "a from the first run\n").unwrap();
+        let first_run = commit_all("First run");
+        let second_snapshot =
+            GitService::snapshot_worktree(root, "refs/vibe-kanban/snapshots/a/2").unwrap();
+        std::fs::write(root.join("b.txt"), "b from the second run\n").unwrap();
+        let second_run = commit_all("Second run");
+
+        git_service
+            .revert_changes(root, &first_snapshot, &first_run, "Revert the first run")
+            .unwrap();
+        let read = |path: &str| std::fs::read_to_string(root.join(path)).unwrap();
+        assert_eq!(read("a.txt"), "a\n");
+        assert_eq!(read("b.txt"), "b from the second run\n");
+        assert_eq!(read("notes.txt"), "mine\n");
+
+        // Reverting the second run would undo what was changed on top of it since
+        std::fs::write(root.join("b.txt"), "b fixed by hand\n").unwrap();
+        commit_all("Fix b");
+        assert!(matches!(
+            git_service.revert_changes(root, &second_snapshot, &second_run, "Revert"),
+            Err(GitServiceError::MergeConflicts(files)) if files == "b.txt"
+        ));
     }
 }
@@ -0,0 +1,348 @@
+//! Importing a repository's GitHub issues into the board: each open issue carrying the
+//! link's label becomes a task whose title and description follow the issue, and the issue
+//! is closed once its task is done. A poll keeps links up to date, and the GitHub webhook
+//! syncs a repository as soon as one of its issues changes.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use octocrab::{models::IssueState, params, Octocrab, OctocrabBuilder};
+use serde::Serialize;
+use tokio::{
+    sync::{Mutex, RwLock},
+    time::interval,
+};
+use tracing::{debug, error, info, warn};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    models::{
+        config::{Config, GitHubConfig},
+        github_issue_sync::{GitHubIssueLink, GitHubIssueTask},
+        task::{CreateTask, Task, TaskStatus},
+    },
+    services::GitHubServiceError,
+};
+
+/// What the sync needs of an issue
+#[derive(Debug, Clone)]
+pub struct LabeledIssue {
+    pub number: i64,
+    pub title: String,
+    pub body: Option<String>,
+    pub closed: bool,
+    pub url: String,
+}
+
+/// The description of the task an issue becomes, pointing back at the issue
+pub fn task_description(issue: &LabeledIssue) -> String {
+    match issue
+        .body
+        .as_deref()
+        .map(str::trim)
+        .filter(|b| !b.is_empty())
+    {
+        Some(body) => format!("{}\n\nImported from {}", body, issue.url),
+        None => format!("Imported from {}", issue.url),
+    }
+}
+
+pub struct GitHubIssuesClient {
+    client: Octocrab,
+}
+
+impl GitHubIssuesClient {
+    pub fn new(github_token: &str) -> Result<Self, GitHubServiceError> {
+        let client = OctocrabBuilder::new()
+            .personal_token(github_token.to_string())
+            .build()
+            .map_err(|e| {
+                GitHubServiceError::Auth(format!("Failed to create GitHub client: {}", e))
+            })?;
+        Ok(Self { client })
+    }
+
+    /// The client for the configured token, if there is one
+    pub fn from_config(github: &GitHubConfig) -> Option<Result<Self, GitHubServiceError>> {
+        let token = github.pat.as_ref().or(github.token.as_ref())?;
+        Some(Self::new(token))
+    }
+
+    /// Every issue of the repository with `label`, open or closed. Pull requests, which
+    /// GitHub lists as issues too, are left out.
+    pub async fn labeled_issues(
+        &self,
+        owner: &str,
+        repo: &str,
+        label: &str,
+    ) -> Result<Vec<LabeledIssue>, GitHubServiceError> {
+        let labels = [label.to_string()];
+        let page = self
+            .client
+            .issues(owner, repo)
+            .list()
+            .labels(&labels)
+            .state(params::State::All)
+            .per_page(100)
+            .send()
+            .await?;
+        let issues = self.client.all_pages(page).await?;
+        Ok(issues
+            .into_iter()
+            .filter(|issue| issue.pull_request.is_none())
+            .map(|issue| LabeledIssue {
+                number: issue.number as i64,
+                title: issue.title,
+                body: issue.body,
+                closed: issue.state == IssueState::Closed,
+                url: issue.html_url.to_string(),
+            })
+            .collect())
+    }
+
+    async fn close_issue(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: i64,
+    ) -> Result<(), GitHubServiceError> {
+        self.client
+            .issues(owner, repo)
+            .update(number as u64)
+            .state(IssueState::Closed)
+            .send()
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Serialize, TS)]
+#[ts(export)]
+pub struct GitHubIssueSyncResult {
+    pub created: usize,
+    pub updated: usize,
+    /// Issues closed because their task was done
+    pub closed: usize,
+}
+
+/// Held for the whole of a sync so the poll, a webhook and a manual sync can't all create a
+/// task for the same new issue
+static SYNC_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Bring the project in line with the repository's labeled issues: create tasks for new
+/// open issues, copy edited titles and bodies onto their tasks, and close the issues whose
+/// task is done. Tasks deleted from the board are never imported again.
+pub async fn sync_project(
+    pool: &DbPool,
+    client: &GitHubIssuesClient,
+    link: &GitHubIssueLink,
+) -> Result<GitHubIssueSyncResult, GitHubServiceError> {
+    let _guard = SYNC_LOCK.lock().await;
+    let db_error =
+        |e: sqlx::Error| GitHubServiceError::Repository(format!("Database error: {}", e));
+
+    let issues = client
+        .labeled_issues(&link.repo_owner, &link.repo_name, &link.label)
+        .await?;
+    let tasks: HashMap<Uuid, Task> = Task::find_by_project_id(pool, link.project_id)
+        .await
+        .map_err(db_error)?
+        .into_iter()
+        .map(|task| (task.id, task))
+        .collect();
+    let linked: HashMap<i64, GitHubIssueTask> =
+        GitHubIssueTask::find_by_project_id(pool, link.project_id)
+            .await
+            .map_err(db_error)?
+            .into_iter()
+            .map(|row| (row.issue_number, row))
+            .collect();
+
+    let mut result = GitHubIssueSyncResult::default();
+    for issue in &issues {
+        let description = task_description(issue);
+        let (task_id, mut issue_closed) = match linked.get(&issue.number) {
+            Some(row) => {
+                let Some(task) = tasks.get(&row.task_id) else {
+                    continue;
+                };
+                let edited = row.synced_title != issue.title || row.synced_body != issue.body;
+                let needs_closing = task.status == TaskStatus::Done && !issue.closed;
+                if !edited && !needs_closing && row.issue_closed == issue.closed {
+                    continue;
+                }
+                if edited {
+                    Task::update(
+                        pool,
+                        task.id,
+                        task.project_id,
+                        issue.title.clone(),
+                        Some(description),
+                        task.status.clone(),
+                        task.parent_task_attempt,
+                    )
+                    .await
+                    .map_err(db_error)?;
+                    result.updated += 1;
+                }
+                (task.id, issue.closed)
+            }
+            // Issues closed before they were ever imported aren't work to do
+            None if issue.closed => continue,
+            None => {
+                let task = Task::create(
+                    pool,
+                    &CreateTask {
+                        project_id: link.project_id,
+                        title: issue.title.clone(),
+                        description: Some(description),
+                        parent_task_attempt: None,
+                        parent_task_id: None,
+                    },
+                    Uuid::new_v4(),
+                )
+                .await
+                .map_err(db_error)?;
+                debug!("Imported issue #{} as task {}", issue.number, task.id);
+                result.created += 1;
+                (task.id, false)
+            }
+        };
+
+        let done = tasks
+            .get(&task_id)
+            .is_some_and(|task| task.status == TaskStatus::Done);
+        if done && !issue_closed {
+            client
+                .close_issue(&link.repo_owner, &link.repo_name, issue.number)
+                .await?;
+            result.closed += 1;
+            issue_closed = true;
+        }
+
+        GitHubIssueTask::upsert(
+            pool,
+            link.project_id,
+            issue.number,
+            task_id,
+            &issue.title,
+            issue.body.as_deref(),
+            issue_closed,
+        )
+        .await
+        .map_err(db_error)?;
+    }
+
+    Ok(result)
+}
+
+/// Sync every link importing from the repository GitHub calls `full_name`, recording how
+/// each went
+pub async fn sync_repository(
+    pool: &DbPool,
+    client: &GitHubIssuesClient,
+    full_name: &str,
+) -> Result<(), sqlx::Error> {
+    for link in GitHubIssueLink::find_all(pool).await? {
+        if link.is_for_repo(full_name) {
+            sync_and_record(pool, client, &link).await;
+        }
+    }
+    Ok(())
+}
+
+async fn sync_and_record(pool: &DbPool, client: &GitHubIssuesClient, link: &GitHubIssueLink) {
+    let outcome = sync_project(pool, client, link).await;
+    if let Err(e) = &outcome {
+        warn!(
+            "GitHub issue sync for project {} failed: {}",
+            link.project_id, e
+        );
+    }
+    let error = outcome.err().map(|e| e.to_string());
+    if let Err(e) = GitHubIssueLink::record_sync(pool, link.project_id, error.as_deref()).await {
+        error!("Failed to record GitHub issue sync: {}", e);
+    }
+}
+
+/// Background service that keeps every linked project's issues imported
+pub struct GitHubIssueSyncService {
+    pool: DbPool,
+    poll_interval: Duration,
+}
+
+impl GitHubIssueSyncService {
+    pub fn new(pool: DbPool) -> Self {
+        Self {
+            pool,
+            // Webhooks bring changes in right away; the poll catches what they miss
+            poll_interval: Duration::from_secs(60),
+        }
+    }
+
+    pub async fn start_with_config(&self, config: Arc<RwLock<Config>>) {
+        info!(
+            "Starting GitHub issue sync with interval {:?}",
+            self.poll_interval
+        );
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+
+            let links = match GitHubIssueLink::find_all(&self.pool).await {
+                Ok(links) => links,
+                Err(e) => {
+                    error!("Failed to load GitHub issue links: {}", e);
+                    continue;
+                }
+            };
+            if links.is_empty() {
+                continue;
+            }
+
+            let client = match GitHubIssuesClient::from_config(&config.read().await.github) {
+                Some(Ok(client)) => client,
+                Some(Err(e)) => {
+                    error!("{}", e);
+                    continue;
+                }
+                None => {
+                    debug!("No GitHub token configured, skipping GitHub issue sync");
+                    continue;
+                }
+            };
+
+            for link in links {
+                sync_and_record(&self.pool, &client, &link).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_description_links_the_issue() {
+        let mut issue = LabeledIssue {
+            number: 7,
+            title: "Crash on empty config".to_string(),
+            body: Some("Steps:\n1. Delete config.json\n".to_string()),
+            closed: false,
+            url: "https://github.com/acme/app/issues/7".to_string(),
+        };
+        assert_eq!(
+            task_description(&issue),
+            "Steps:\n1. Delete config.json\n\nImported from https://github.com/acme/app/issues/7"
+        );
+
+        issue.body = Some("  ".to_string());
+        assert_eq!(
+            task_description(&issue),
+            "Imported from https://github.com/acme/app/issues/7"
+        );
+    }
+}
@@ -0,0 +1,522 @@
+//! Mirroring tasks into a GitHub Projects (v2) board through the GraphQL API. Each task
+//! becomes a draft issue on the board whose Status field follows the task's column.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use octocrab::{Octocrab, OctocrabBuilder};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::{
+    sync::{Mutex, RwLock},
+    time::interval,
+};
+use tracing::{debug, error, info, warn};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    models::{
+        config::Config,
+        github_project_sync::{GitHubProjectItem, GitHubProjectLink},
+        task::{Task, TaskStatus, TaskStatusNames},
+    },
+    services::GitHubServiceError,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectUrl {
+    pub owner_kind: OwnerKind,
+    pub login: String,
+    pub number: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OwnerKind {
+    Organization,
+    User,
+}
+
+impl ProjectUrl {
+    /// Parse https://github.com/orgs/<org>/projects/<n> or https://github.com/users/<user>/projects/<n>
+    pub fn parse(url: &str) -> Option<Self> {
+        let path = url
+            .trim()
+            .trim_start_matches("https://")
+            .trim_start_matches("http://")
+            .strip_prefix("github.com/")?;
+        let mut segments = path.split('/').filter(|s| !s.is_empty());
+        let owner_kind = match segments.next()? {
+            "orgs" => OwnerKind::Organization,
+            "users" => OwnerKind::User,
+            _ => return None,
+        };
+        let login = segments.next()?.to_string();
+        if segments.next()? != "projects" {
+            return None;
+        }
+        let number = segments.next()?.parse().ok()?;
+        Some(Self {
+            owner_kind,
+            login,
+            number,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct StatusOption {
+    pub id: String,
+    pub name: String,
+}
+
+/// The board and its single-select Status field, if it has one
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct GitHubProjectBoard {
+    pub id: String,
+    pub title: String,
+    pub status_field_id: Option<String>,
+    pub status_options: Vec<StatusOption>,
+}
+
+impl GitHubProjectBoard {
+    fn from_json(project: &Value) -> Option<Self> {
+        let field = project.get("field").filter(|f| !f.is_null());
+        Some(Self {
+            id: project.get("id")?.as_str()?.to_string(),
+            title: project.get("title")?.as_str()?.to_string(),
+            status_field_id: field
+                .and_then(|f| f.get("id"))
+                .and_then(|id| id.as_str())
+                .map(str::to_string),
+            status_options: field
+                .and_then(|f| f.get("options"))
+                .and_then(|o| serde_json::from_value::<Vec<Value>>(o.clone()).ok())
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|o| {
+                    Some(StatusOption {
+                        id: o.get("id")?.as_str()?.to_string(),
+                        name: o.get("name")?.as_str()?.to_string(),
+                    })
+                })
+                .collect(),
+        })
+    }
+
+    /// The id of the Status option a task in `status` should show
+    pub fn option_for(&self, mapping: &TaskStatusNames, status: &TaskStatus) -> Option<&str> {
+        let names: Vec<&str> = self
+            .status_options
+            .iter()
+            .map(|o| o.name.as_str())
+            .collect();
+        let name = mapping.resolve(status, &names)?;
+        self.status_options
+            .iter()
+            .find(|o| o.name == name)
+            .map(|o| o.id.as_str())
+    }
+}
+
+const PROJECT_FIELDS: &str = r#"id title field(name: "Status") { ... on ProjectV2SingleSelectField { id options { id name } } }"#;
+
+pub struct GitHubProjectsClient {
+    client: Octocrab,
+}
+
+impl GitHubProjectsClient {
+    pub fn new(github_token: &str) -> Result<Self, GitHubServiceError> {
+        let client = OctocrabBuilder::new()
+            .personal_token(github_token.to_string())
+            .build()
+            .map_err(|e| {
+                GitHubServiceError::Auth(format!("Failed to create GitHub client: {}", e))
+            })?;
+        Ok(Self { client })
+    }
+
+    async fn graphql(&self, query: &str, variables: Value) -> Result<Value, GitHubServiceError> {
+        let response: Value = self
+            .client
+            .graphql(&json!({ "query": query, "variables": variables }))
+            .await?;
+        if let Some(errors) = response.get("errors").and_then(|e| e.as_array()) {
+            let messages: Vec<&str> = errors
+                .iter()
+                .filter_map(|e| e.get("message").and_then(|m| m.as_str()))
+                .collect();
+            // Tokens from before the project scope was requested are refused with this
+            if messages.iter().any(|m| m.contains("required scopes")) {
+                return Err(GitHubServiceError::TokenInvalid);
+            }
+            return Err(GitHubServiceError::Project(messages.join("; ")));
+        }
+        Ok(response.get("data").cloned().unwrap_or(Value::Null))
+    }
+
+    /// Look up a board from its URL
+    pub async fn find_board(
+        &self,
+        url: &ProjectUrl,
+    ) -> Result<GitHubProjectBoard, GitHubServiceError> {
+        let owner = match url.owner_kind {
+            OwnerKind::Organization => "organization",
+            OwnerKind::User => "user",
+        };
+        let query = format!(
+            "query($login: String!, $number: Int!) {{ {}(login: $login) {{ projectV2(number: $number) {{ {} }} }} }}",
+            owner, PROJECT_FIELDS
+        );
+        let data = self
+            .graphql(&query, json!({ "login": url.login, "number": url.number }))
+            .await?;
+        data.get(owner)
+            .and_then(|o| o.get("projectV2"))
+            .and_then(GitHubProjectBoard::from_json)
+            .ok_or_else(|| {
+                GitHubServiceError::Project(format!(
+                    "No project #{} found for {}",
+                    url.number, url.login
+                ))
+            })
+    }
+
+    pub async fn get_board(
+        &self,
+        project_id: &str,
+    ) -> Result<GitHubProjectBoard, GitHubServiceError> {
+        let query = format!(
+            "query($id: ID!) {{ node(id: $id) {{ ... on ProjectV2 {{ {} }} }} }}",
+            PROJECT_FIELDS
+        );
+        let data = self.graphql(&query, json!({ "id": project_id })).await?;
+        data.get("node")
+            .and_then(GitHubProjectBoard::from_json)
+            .ok_or_else(|| {
+                GitHubServiceError::Project("The linked project no longer exists".into())
+            })
+    }
+
+    /// Add a draft issue to the board; returns (item id, draft issue id)
+    async fn add_draft_issue(
+        &self,
+        project_id: &str,
+        title: &str,
+        body: &str,
+    ) -> Result<(String, String), GitHubServiceError> {
+        let data = self
+            .graphql(
+                "mutation($projectId: ID!, $title: String!, $body: String) { addProjectV2DraftIssue(input: {projectId: $projectId, title: $title, body: $body}) { projectItem { id content { ... on DraftIssue { id } } } } }",
+                json!({ "projectId": project_id, "title": title, "body": body }),
+            )
+            .await?;
+        let item = &data["addProjectV2DraftIssue"]["projectItem"];
+        match (item["id"].as_str(), item["content"]["id"].as_str()) {
+            (Some(item_id), Some(draft_id)) => Ok((item_id.to_string(), draft_id.to_string())),
+            _ => Err(GitHubServiceError::Project(
+                "GitHub did not return the new project item".into(),
+            )),
+        }
+    }
+
+    async fn update_draft_issue(
+        &self,
+        draft_issue_id: &str,
+        title: &str,
+        body: &str,
+    ) -> Result<(), GitHubServiceError> {
+        self.graphql(
+            "mutation($id: ID!, $title: String!, $body: String) { updateProjectV2DraftIssue(input: {draftIssueId: $id, title: $title, body: $body}) { draftIssue { id } } }",
+            json!({ "id": draft_issue_id, "title": title, "body": body }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn set_status(
+        &self,
+        board: &GitHubProjectBoard,
+        item_id: &str,
+        option_id: Option<&str>,
+    ) -> Result<(), GitHubServiceError> {
+        let Some(field_id) = &board.status_field_id else {
+            return Ok(());
+        };
+        match option_id {
+            Some(option_id) => self.graphql(
+                "mutation($projectId: ID!, $itemId: ID!, $fieldId: ID!, $optionId: String!) { updateProjectV2ItemFieldValue(input: {projectId: $projectId, itemId: $itemId, fieldId: $fieldId, value: {singleSelectOptionId: $optionId}}) { projectV2Item { id } } }",
+                json!({ "projectId": board.id, "itemId": item_id, "fieldId": field_id, "optionId": option_id }),
+            ),
+            None => self.graphql(
+                "mutation($projectId: ID!, $itemId: ID!, $fieldId: ID!) { clearProjectV2ItemFieldValue(input: {projectId: $projectId, itemId: $itemId, fieldId: $fieldId}) { projectV2Item { id } } }",
+                json!({ "projectId": board.id, "itemId": item_id, "fieldId": field_id }),
+            ),
+        }
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_item(&self, project_id: &str, item_id: &str) -> Result<(), GitHubServiceError> {
+        self.graphql(
+            "mutation($projectId: ID!, $itemId: ID!) { deleteProjectV2Item(input: {projectId: $projectId, itemId: $itemId}) { deletedItemId } }",
+            json!({ "projectId": project_id, "itemId": item_id }),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+/// Whether an error means the item was removed on GitHub, so it should be added again
+fn is_missing_node(error: &GitHubServiceError) -> bool {
+    matches!(error, GitHubServiceError::Project(msg) if msg.contains("Could not resolve to a node"))
+}
+
+#[derive(Debug, Default, Serialize, TS)]
+#[ts(export)]
+pub struct GitHubProjectSyncResult {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+}
+
+/// Held for the whole of a sync so a manual sync and the background one can't both add the
+/// same new task
+static SYNC_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Bring the board in line with the project's tasks: add new tasks, update changed ones and
+/// remove items whose task was deleted. Edits made on GitHub are overwritten.
+pub async fn sync_project(
+    pool: &DbPool,
+    client: &GitHubProjectsClient,
+    link: &GitHubProjectLink,
+) -> Result<GitHubProjectSyncResult, GitHubServiceError> {
+    let _guard = SYNC_LOCK.lock().await;
+    let db_error = |e: sqlx::Error| GitHubServiceError::Project(format!("Database error: {}", e));
+
+    let board = client.get_board(&link.github_project_id).await?;
+    let tasks = Task::find_by_project_id_with_attempt_status(pool, link.project_id)
+        .await
+        .map_err(db_error)?;
+    let mut items: HashMap<Uuid, GitHubProjectItem> =
+        GitHubProjectItem::find_by_project_id(pool, link.project_id)
+            .await
+            .map_err(db_error)?
+            .into_iter()
+            .map(|item| (item.task_id, item))
+            .collect();
+
+    let mut result = GitHubProjectSyncResult::default();
+    for task in &tasks {
+        let body = task.description.as_deref().unwrap_or("");
+        let option = board.option_for(&link.status_mapping, &task.status);
+
+        let (item_id, draft_issue_id) = match items.remove(&task.id) {
+            Some(item) => {
+                let content_changed = item.synced_title != task.title
+                    || item.synced_description.as_deref().unwrap_or("") != body;
+                let status_changed = item.synced_status != task.status;
+                if !content_changed && !status_changed {
+                    continue;
+                }
+
+                let update = async {
+                    if content_changed {
+                        client
+                            .update_draft_issue(&item.draft_issue_id, &task.title, body)
+                            .await?;
+                    }
+                    if status_changed {
+                        client.set_status(&board, &item.item_id, option).await?;
+                    }
+                    Ok::<_, GitHubServiceError>(())
+                };
+                match update.await {
+                    Ok(()) => {
+                        result.updated += 1;
+                        (item.item_id, item.draft_issue_id)
+                    }
+                    Err(e) if is_missing_node(&e) => {
+                        debug!(
+                            "Item for task {} was removed from the board, re-adding",
+                            task.id
+                        );
+                        let (item_id, draft_id) =
+                            client.add_draft_issue(&board.id, &task.title, body).await?;
+                        client.set_status(&board, &item_id, option).await?;
+                        result.added += 1;
+                        (item_id, draft_id)
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            None => {
+                let (item_id, draft_id) =
+                    client.add_draft_issue(&board.id, &task.title, body).await?;
+                client.set_status(&board, &item_id, option).await?;
+                result.added += 1;
+                (item_id, draft_id)
+            }
+        };
+
+        GitHubProjectItem::upsert(
+            pool,
+            task.id,
+            link.project_id,
+            &item_id,
+            &draft_issue_id,
+            &task.title,
+            task.description.as_deref(),
+            &task.status,
+        )
+        .await
+        .map_err(db_error)?;
+    }
+
+    // Whatever is left belongs to deleted tasks
+    for item in items.into_values() {
+        match client.delete_item(&board.id, &item.item_id).await {
+            Ok(()) => result.removed += 1,
+            Err(e) if is_missing_node(&e) => {}
+            Err(e) => return Err(e),
+        }
+        GitHubProjectItem::delete(pool, item.task_id)
+            .await
+            .map_err(db_error)?;
+    }
+
+    Ok(result)
+}
+
+/// Background service that keeps every linked board up to date
+pub struct GitHubProjectSyncService {
+    pool: DbPool,
+    poll_interval: Duration,
+}
+
+impl GitHubProjectSyncService {
+    pub fn new(pool: DbPool) -> Self {
+        Self {
+            pool,
+            poll_interval: Duration::from_secs(30),
+        }
+    }
+
+    pub async fn start_with_config(&self, config: Arc<RwLock<Config>>) {
+        info!(
+            "Starting GitHub Projects sync with interval {:?}",
+            self.poll_interval
+        );
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+
+            let links = match GitHubProjectLink::find_all(&self.pool).await {
+                Ok(links) => links,
+                Err(e) => {
+                    error!("Failed to load GitHub project links: {}", e);
+                    continue;
+                }
+            };
+            if links.is_empty() {
+                continue;
+            }
+
+            let github_token = {
+                let config = config.read().await;
+                config
+                    .github
+                    .pat
+                    .clone()
+                    .or_else(|| config.github.token.clone())
+            };
+            let Some(token) = github_token else {
+                debug!("No GitHub token configured, skipping GitHub Projects sync");
+                continue;
+            };
+            let client = match GitHubProjectsClient::new(&token) {
+                Ok(client) => client,
+                Err(e) => {
+                    error!("{}", e);
+                    continue;
+                }
+            };
+
+            for link in links {
+                let outcome = sync_project(&self.pool, &client, &link).await;
+                if let Err(e) = &outcome {
+                    warn!(
+                        "GitHub Projects sync for project {} failed: {}",
+                        link.project_id, e
+                    );
+                }
+                let error = outcome.err().map(|e| e.to_string());
+                if let Err(e) =
+                    GitHubProjectLink::record_sync(&self.pool, link.project_id, error.as_deref())
+                        .await
+                {
+                    error!("Failed to record GitHub Projects sync: {}", e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_project_url() {
+        assert_eq!(
+            ProjectUrl::parse("https://github.com/orgs/acme/projects/5/views/1"),
+            Some(ProjectUrl {
+                owner_kind: OwnerKind::Organization,
+                login: "acme".to_string(),
+                number: 5,
+            })
+        );
+        assert_eq!(
+            ProjectUrl::parse("github.com/users/octocat/projects/12").map(|u| u.owner_kind),
+            Some(OwnerKind::User)
+        );
+        assert_eq!(
+            ProjectUrl::parse("https://github.com/acme/repo/projects/1"),
+            None
+        );
+        assert_eq!(
+            ProjectUrl::parse("https://github.com/orgs/acme/projects/x"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_option_for_uses_mapping_then_names() {
+        let board = GitHubProjectBoard::from_json(&json!({
+            "id": "PVT_1",
+            "title": "Roadmap",
+            "field": {"id": "F_1", "options": [
+                {"id": "o1", "name": "Todo"},
+                {"id": "o2", "name": "In Progress"},
+                {"id": "o3", "name": "Shipped"}
+            ]}
+        }))
+        .unwrap();
+        let mapping = TaskStatusNames {
+            done: Some("shipped".to_string()),
+            inreview: Some("In Progress".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(board.option_for(&mapping, &TaskStatus::Todo), Some("o1"));
+        assert_eq!(
+            board.option_for(&mapping, &TaskStatus::InReview),
+            Some("o2")
+        );
+        assert_eq!(board.option_for(&mapping, &TaskStatus::Done), Some("o3"));
+        assert_eq!(board.option_for(&mapping, &TaskStatus::Cancelled), None);
+    }
+}
@@ -13,6 +13,7 @@ pub enum GitHubServiceError {
     Repository(String),
     PullRequest(String),
     Branch(String),
+    Project(String),
     TokenInvalid,
 }
 
@@ -24,6 +25,7 @@ impl std::fmt::Display for GitHubServiceError {
             GitHubServiceError::Repository(e) => write!(f, "Repository error: {}", e),
             GitHubServiceError::PullRequest(e) => write!(f, "Pull request error: {}", e),
             GitHubServiceError::Branch(e) => write!(f, "Branch error: {}", e),
+            GitHubServiceError::Project(e) => write!(f, "GitHub Projects error: {}", e),
             GitHubServiceError::TokenInvalid => write!(f, "GitHub token is invalid or expired."),
         }
     }
@@ -90,6 +92,33 @@ pub struct RepositoryInfo {
     pub private: bool,
 }
 
+#[derive(Debug, Clone)]
+pub struct FailedCheck {
+    pub name: String,
+    pub conclusion: String,
+    pub summary: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckRunsResponse {
+    check_runs: Vec<CheckRun>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckRun {
+    name: String,
+    conclusion: Option<String>,
+    html_url: Option<String>,
+    #[serde(default)]
+    output: CheckRunOutput,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CheckRunOutput {
+    title: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct GitHubService {
     client: Octocrab,
@@ -109,13 +138,14 @@ impl GitHubService {
     }
 
     /// Create a new GitHub service with authentication and custom base URL for GitHub Enterprise
-    pub fn new_with_base_url(github_token: &str, base_url: &str) -> Result<Self, GitHubServiceError> {
+    pub fn new_with_base_url(
+        github_token: &str,
+        base_url: &str,
+    ) -> Result<Self, GitHubServiceError> {
         let client = OctocrabBuilder::new()
             .personal_token(github_token.to_string())
             .base_uri(base_url)
-            .map_err(|e| {
-                GitHubServiceError::Auth(format!("Failed to set base URI: {}", e))
-            })?
+            .map_err(|e| GitHubServiceError::Auth(format!("Failed to set base URI: {}", e)))?
             .build()
             .map_err(|e| {
                 GitHubServiceError::Auth(format!("Failed to create GitHub client: {}", e))
@@ -308,6 +338,66 @@ impl GitHubService {
         Ok(pr_info)
     }
 
+    /// Comment on a pull request. Not retried, as a retry could comment twice.
+    pub async fn comment_on_pr(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        pr_number: i64,
+        body: &str,
+    ) -> Result<(), GitHubServiceError> {
+        self.client
+            .issues(&repo_info.owner, &repo_info.repo_name)
+            .create_comment(pr_number as u64, body)
+            .await
+            .map_err(|e| match e {
+                octocrab::Error::GitHub { source, .. }
+                    if matches!(source.status_code.as_u16(), 401 | 403) =>
+                {
+                    GitHubServiceError::TokenInvalid
+                }
+                _ => GitHubServiceError::PullRequest(format!(
+                    "Failed to comment on PR #{}: {}",
+                    pr_number, e
+                )),
+            })?;
+        Ok(())
+    }
+
+    /// Check runs on a commit that failed or timed out
+    pub async fn failed_checks(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        sha: &str,
+    ) -> Result<Vec<FailedCheck>, GitHubServiceError> {
+        let response: CheckRunsResponse = self
+            .client
+            .get(
+                format!(
+                    "/repos/{}/{}/commits/{}/check-runs",
+                    repo_info.owner, repo_info.repo_name, sha
+                ),
+                Some(&[("per_page", "100")]),
+            )
+            .await?;
+
+        Ok(response
+            .check_runs
+            .into_iter()
+            .filter(|run| {
+                matches!(
+                    run.conclusion.as_deref(),
+                    Some("failure") | Some("timed_out")
+                )
+            })
+            .map(|run| FailedCheck {
+                name: run.name,
+                conclusion: run.conclusion.unwrap_or_default(),
+                summary: run.output.title.filter(|t| !t.trim().is_empty()),
+                url: run.html_url,
+            })
+            .collect())
+    }
+
     /// List repositories for the authenticated user with pagination
     pub async fn list_repositories(
         &self,
@@ -0,0 +1,216 @@
+//! Per-project guardrails on what coding agents spend and when they start. While a
+//! project's runs this month have cost its monthly budget, or it's outside every window its
+//! agents may start in, its attempts wait in the execution queue however many slots are
+//! free. Once the budget is spent, new attempts and follow-ups are refused outright rather
+//! than left waiting for next month.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, Local, TimeZone, Utc};
+use serde::Serialize;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    models::{
+        execution_metrics::ExecutionMetrics,
+        execution_queue::{QueueEntry, QueuedAttempt},
+        project_guardrails::{GuardrailHold, ProjectGuardrails},
+    },
+};
+
+/// A project's guardrails and where it stands against them now
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct GuardrailStatus {
+    pub guardrails: Option<ProjectGuardrails>,
+    /// What the project's runs have cost since the first of the month
+    pub spent_this_month_usd: f64,
+    pub hold: Option<GuardrailHold>,
+}
+
+/// A spent budget, as it's reported to whoever tries to start an agent
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BudgetExceeded {
+    pub spent_usd: f64,
+    pub budget_usd: f64,
+}
+
+impl std::fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Budget exceeded: the project's runs have cost ${:.2} this month, of its ${:.2} monthly budget",
+            self.spent_usd, self.budget_usd
+        )
+    }
+}
+
+/// Midnight on the first of the month `now` is in, where `now` is
+pub fn month_start<Tz: TimeZone>(now: &DateTime<Tz>) -> DateTime<Utc> {
+    let first = now
+        .date_naive()
+        .with_day(1)
+        .and_then(|day| day.and_hms_opt(0, 0, 0))
+        .unwrap_or_else(|| now.naive_local());
+    now.timezone()
+        .from_local_datetime(&first)
+        .earliest()
+        .map(|start| start.with_timezone(&Utc))
+        .unwrap_or_else(|| first.and_utc())
+}
+
+/// What holds the project's attempts back at `now`, having spent `spent_usd` this month.
+/// A spent budget comes first, as it holds them longest.
+pub fn hold<Tz: TimeZone>(
+    guardrails: &ProjectGuardrails,
+    spent_usd: f64,
+    now: &DateTime<Tz>,
+) -> Option<GuardrailHold> {
+    if guardrails
+        .monthly_budget_usd
+        .is_some_and(|budget| spent_usd >= budget)
+    {
+        Some(GuardrailHold::BudgetExceeded)
+    } else if !guardrails.allows_starting_at(now.naive_local()) {
+        Some(GuardrailHold::OutsideWorkingHours)
+    } else {
+        None
+    }
+}
+
+/// What the project's runs have cost this month, if it has a budget to count against
+async fn spent_this_month(
+    pool: &DbPool,
+    guardrails: &ProjectGuardrails,
+    now: &DateTime<Local>,
+) -> Result<f64, sqlx::Error> {
+    if guardrails.monthly_budget_usd.is_none() {
+        return Ok(0.0);
+    }
+    ExecutionMetrics::project_cost_since(pool, guardrails.project_id, month_start(now)).await
+}
+
+pub async fn status(pool: &DbPool, project_id: Uuid) -> Result<GuardrailStatus, sqlx::Error> {
+    let now = Local::now();
+    let guardrails = ProjectGuardrails::find_by_project_id(pool, project_id).await?;
+    let spent_this_month_usd =
+        ExecutionMetrics::project_cost_since(pool, project_id, month_start(&now)).await?;
+    let hold = guardrails
+        .as_ref()
+        .and_then(|guardrails| hold(guardrails, spent_this_month_usd, &now));
+    Ok(GuardrailStatus {
+        guardrails,
+        spent_this_month_usd,
+        hold,
+    })
+}
+
+/// What holds back the attempts of each of `project_ids` that is held back now
+pub async fn holds(
+    pool: &DbPool,
+    project_ids: &[Uuid],
+) -> Result<HashMap<Uuid, GuardrailHold>, sqlx::Error> {
+    let now = Local::now();
+    let mut holds = HashMap::new();
+    for &project_id in project_ids {
+        if holds.contains_key(&project_id) {
+            continue;
+        }
+        let Some(guardrails) = ProjectGuardrails::find_by_project_id(pool, project_id).await?
+        else {
+            continue;
+        };
+        let spent = spent_this_month(pool, &guardrails, &now).await?;
+        if let Some(hold) = hold(&guardrails, spent, &now) {
+            holds.insert(project_id, hold);
+        }
+    }
+    Ok(holds)
+}
+
+/// The execution queue as it's shown, with what holds back the attempts that are held
+pub async fn queue_entries(pool: &DbPool) -> Result<Vec<QueueEntry>, sqlx::Error> {
+    let mut entries = QueuedAttempt::find_entries(pool).await?;
+    let project_ids: Vec<Uuid> = entries.iter().map(|entry| entry.project_id).collect();
+    let holds = holds(pool, &project_ids).await?;
+    for entry in &mut entries {
+        entry.hold = holds.get(&entry.project_id).copied();
+    }
+    Ok(entries)
+}
+
+/// The project's spent budget, if it has one and it's spent, to refuse starting an agent
+pub async fn budget_exceeded(
+    pool: &DbPool,
+    project_id: Uuid,
+) -> Result<Option<BudgetExceeded>, sqlx::Error> {
+    let Some(guardrails) = ProjectGuardrails::find_by_project_id(pool, project_id).await? else {
+        return Ok(None);
+    };
+    let Some(budget_usd) = guardrails.monthly_budget_usd else {
+        return Ok(None);
+    };
+    let now = Local::now();
+    let spent_usd = spent_this_month(pool, &guardrails, &now).await?;
+    Ok((spent_usd >= budget_usd).then_some(BudgetExceeded {
+        spent_usd,
+        budget_usd,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::FixedOffset;
+
+    use super::*;
+    use crate::models::project_guardrails::ExecutionWindow;
+
+    fn guardrails(budget: Option<f64>, windows: Vec<ExecutionWindow>) -> ProjectGuardrails {
+        ProjectGuardrails {
+            project_id: Uuid::new_v4(),
+            monthly_budget_usd: budget,
+            allowed_windows: windows,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_month_starts_at_local_midnight() {
+        let tz = FixedOffset::east_opt(2 * 3600).unwrap();
+        let now = tz.with_ymd_and_hms(2025, 10, 1, 1, 30, 0).unwrap();
+        assert_eq!(
+            month_start(&now),
+            Utc.with_ymd_and_hms(2025, 9, 30, 22, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_budget_holds_before_working_hours() {
+        let office = ExecutionWindow {
+            start: "09:00".to_string(),
+            end: "18:00".to_string(),
+            weekdays_only: false,
+        };
+        let evening = Utc.with_ymd_and_hms(2025, 9, 1, 20, 0, 0).unwrap();
+        let noon = Utc.with_ymd_and_hms(2025, 9, 1, 12, 0, 0).unwrap();
+
+        let project = guardrails(Some(100.0), vec![office]);
+        assert_eq!(hold(&project, 40.0, &noon), None);
+        assert_eq!(
+            hold(&project, 40.0, &evening),
+            Some(GuardrailHold::OutsideWorkingHours)
+        );
+        assert_eq!(
+            hold(&project, 100.0, &noon),
+            Some(GuardrailHold::BudgetExceeded)
+        );
+        assert_eq!(
+            hold(&project, 120.0, &evening),
+            Some(GuardrailHold::BudgetExceeded)
+        );
+        assert_eq!(hold(&guardrails(None, vec![]), 1000.0, &evening), None);
+    }
+}
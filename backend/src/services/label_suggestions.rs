@@ -0,0 +1,340 @@
+//! Label suggestions for newly created tasks. Each [`LabelClassifier`] looks at the task's
+//! text and proposes labels; the suggestions are stored as pending until the user accepts
+//! or dismisses them. Classifiers are registered on [`LabelSuggester`], which lives on the
+//! app state, so other classifiers (for example one backed by a language model) can be
+//! plugged in alongside the built-in ones.
+
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+
+use crate::models::task_label::{normalize_label, LabelSuggestion};
+
+/// What classifiers get to look at
+#[derive(Debug, Clone)]
+pub struct ClassificationInput<'a> {
+    pub title: &'a str,
+    pub description: Option<&'a str>,
+}
+
+#[async_trait]
+pub trait LabelClassifier: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn suggest(&self, input: &ClassificationInput<'_>) -> Vec<LabelSuggestion>;
+}
+
+pub struct LabelSuggester {
+    classifiers: Vec<Box<dyn LabelClassifier>>,
+}
+
+impl std::fmt::Debug for LabelSuggester {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.classifiers.iter().map(|c| c.name()))
+            .finish()
+    }
+}
+
+impl Default for LabelSuggester {
+    fn default() -> Self {
+        Self::empty()
+            .with_classifier(KindClassifier)
+            .with_classifier(AreaClassifier)
+    }
+}
+
+impl LabelSuggester {
+    pub fn empty() -> Self {
+        Self {
+            classifiers: Vec::new(),
+        }
+    }
+
+    pub fn with_classifier(mut self, classifier: impl LabelClassifier + 'static) -> Self {
+        self.classifiers.push(Box::new(classifier));
+        self
+    }
+
+    /// Suggestions from every classifier, normalized, first one wins on duplicates
+    pub async fn suggest(&self, input: &ClassificationInput<'_>) -> Vec<LabelSuggestion> {
+        let mut seen = HashSet::new();
+        let mut suggestions = Vec::new();
+        for classifier in &self.classifiers {
+            for suggestion in classifier.suggest(input).await {
+                let Some(name) = normalize_label(&suggestion.name) else {
+                    continue;
+                };
+                if seen.insert(name.clone()) {
+                    suggestions.push(LabelSuggestion {
+                        name,
+                        reason: suggestion.reason,
+                    });
+                }
+            }
+        }
+        suggestions
+    }
+}
+
+/// Keywords behind each kind of change. Single words match whole words, phrases match
+/// anywhere.
+const KINDS: &[(&str, &[&str])] = &[
+    (
+        "bug",
+        &[
+            "bug",
+            "fix",
+            "fixes",
+            "error",
+            "errors",
+            "crash",
+            "crashes",
+            "panic",
+            "panics",
+            "broken",
+            "fails",
+            "failing",
+            "failure",
+            "exception",
+            "regression",
+            "traceback",
+            "stack trace",
+            "doesn't work",
+            "not working",
+        ],
+    ),
+    (
+        "feature",
+        &[
+            "add",
+            "adds",
+            "support",
+            "implement",
+            "introduce",
+            "allow",
+            "new",
+            "feature",
+            "ability",
+        ],
+    ),
+    (
+        "refactor",
+        &[
+            "refactor",
+            "refactoring",
+            "cleanup",
+            "clean up",
+            "simplify",
+            "rename",
+            "extract",
+            "restructure",
+            "deduplicate",
+            "reorganize",
+            "tidy",
+        ],
+    ),
+];
+
+/// Guesses bug / feature / refactor from wording; the title counts double since it
+/// usually leads with the verb that gives it away
+pub struct KindClassifier;
+
+impl KindClassifier {
+    fn classify(input: &ClassificationInput<'_>) -> Option<LabelSuggestion> {
+        let title = input.title.to_lowercase();
+        let description = input.description.unwrap_or("").to_lowercase();
+        let title_words = words(&title);
+        let description_words = words(&description);
+
+        let mut best: Option<(&str, usize, &str)> = None;
+        for (kind, keywords) in KINDS {
+            let mut score = 0;
+            let mut first: Option<&str> = None;
+            for keyword in *keywords {
+                let (in_title, in_description) = if keyword.contains(' ') {
+                    (title.contains(keyword), description.contains(keyword))
+                } else {
+                    (
+                        title_words.contains(keyword),
+                        description_words.contains(keyword),
+                    )
+                };
+                if in_title || in_description {
+                    score += 2 * in_title as usize + in_description as usize;
+                    first.get_or_insert(keyword);
+                }
+            }
+            let Some(keyword) = first else {
+                continue;
+            };
+            match best {
+                Some((_, best_score, _)) if best_score >= score => {}
+                _ => best = Some((kind, score, keyword)),
+            }
+        }
+
+        // A lone mention deep in a description is too weak to act on
+        let (kind, _, keyword) = best.filter(|(_, score, _)| *score >= 2)?;
+        Some(LabelSuggestion {
+            name: kind.to_string(),
+            reason: format!("Mentions \"{}\"", keyword),
+        })
+    }
+}
+
+fn words(text: &str) -> HashSet<&str> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+#[async_trait]
+impl LabelClassifier for KindClassifier {
+    fn name(&self) -> &'static str {
+        "kind"
+    }
+
+    async fn suggest(&self, input: &ClassificationInput<'_>) -> Vec<LabelSuggestion> {
+        Self::classify(input).into_iter().collect()
+    }
+}
+
+/// Directories that say nothing about which part of the codebase a path is in
+const GENERIC_DIRS: &[&str] = &[
+    "", ".", "..", "src", "lib", "app", "source", "packages", "crates",
+];
+const MAX_AREAS: usize = 3;
+
+/// Suggests `area:<dir>` labels from file paths mentioned in the task
+pub struct AreaClassifier;
+
+impl AreaClassifier {
+    fn classify(input: &ClassificationInput<'_>) -> Vec<LabelSuggestion> {
+        let text = format!("{}\n{}", input.title, input.description.unwrap_or(""));
+        let mut seen = HashSet::new();
+        let mut suggestions = Vec::new();
+        for token in text.split_whitespace() {
+            let Some(area) = area_of(token) else {
+                continue;
+            };
+            if seen.insert(area.clone()) {
+                suggestions.push(LabelSuggestion {
+                    name: format!("area:{}", area),
+                    reason: format!("Mentions {}", path_token(token)),
+                });
+            }
+            if suggestions.len() == MAX_AREAS {
+                break;
+            }
+        }
+        suggestions
+    }
+}
+
+/// Strip surrounding quotes and brackets, and sentence punctuation at the end
+fn path_token(token: &str) -> &str {
+    token
+        .trim_start_matches(|c: char| "([{<\"'`".contains(c))
+        .trim_end_matches(|c: char| ")]}>\"'`,;:!?.".contains(c))
+}
+
+/// The first meaningful directory of a path-looking token
+fn area_of(token: &str) -> Option<String> {
+    let path = path_token(token);
+    if !path.contains('/') || path.contains("://") {
+        return None;
+    }
+    if !path
+        .chars()
+        .all(|c| c.is_alphanumeric() || "/._-@".contains(c))
+    {
+        return None;
+    }
+    let mut components: Vec<&str> = path.split('/').collect();
+    // A trailing file name isn't an area
+    if components.last().is_some_and(|last| last.contains('.')) {
+        components.pop();
+    }
+    components
+        .into_iter()
+        .find(|c| !GENERIC_DIRS.contains(c) && !c.starts_with('.'))
+        .map(str::to_lowercase)
+}
+
+#[async_trait]
+impl LabelClassifier for AreaClassifier {
+    fn name(&self) -> &'static str {
+        "area"
+    }
+
+    async fn suggest(&self, input: &ClassificationInput<'_>) -> Vec<LabelSuggestion> {
+        Self::classify(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input<'a>(title: &'a str, description: Option<&'a str>) -> ClassificationInput<'a> {
+        ClassificationInput { title, description }
+    }
+
+    #[test]
+    fn test_kind_classifier() {
+        let kind = |title, description| KindClassifier::classify(&input(title, description));
+        assert_eq!(kind("Fix crash when saving", None).unwrap().name, "bug");
+        assert_eq!(kind("Add dark mode", None).unwrap().name, "feature");
+        assert_eq!(
+            kind("Clean up the config loader", Some("Simplify merging"))
+                .unwrap()
+                .name,
+            "refactor"
+        );
+        // One passing mention in the description isn't enough
+        assert!(kind("Dark mode", Some("Colours would be nicer, no error")).is_none());
+    }
+
+    #[test]
+    fn test_area_classifier() {
+        let areas = AreaClassifier::classify(&input(
+            "Sessions lost on restart",
+            Some(
+                "See backend/src/routes/auth.rs and src/services/session.rs, docs at \
+                 https://example.com/a/b. Also (frontend/src/App.tsx).",
+            ),
+        ));
+        let names: Vec<&str> = areas.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["area:backend", "area:services", "area:frontend"]
+        );
+        assert_eq!(areas[2].reason, "Mentions frontend/src/App.tsx");
+    }
+
+    #[tokio::test]
+    async fn test_suggester_dedupes_across_classifiers() {
+        struct Fixed;
+
+        #[async_trait]
+        impl LabelClassifier for Fixed {
+            fn name(&self) -> &'static str {
+                "fixed"
+            }
+
+            async fn suggest(&self, _: &ClassificationInput<'_>) -> Vec<LabelSuggestion> {
+                vec![LabelSuggestion {
+                    name: "Bug ".to_string(),
+                    reason: "always".to_string(),
+                }]
+            }
+        }
+
+        let suggester = LabelSuggester::default().with_classifier(Fixed);
+        let suggestions = suggester.suggest(&input("Fix the panic", None)).await;
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].name, "bug");
+        assert_eq!(suggestions[0].reason, "Mentions \"fix\"");
+    }
+}
@@ -0,0 +1,83 @@
+//! Minimal client for an OpenAI-compatible `/chat/completions` endpoint, used for small
+//! one-shot text jobs rather than agent runs.
+
+use serde_json::{json, Value};
+
+use crate::models::config::LlmConfig;
+
+#[derive(Debug)]
+pub enum LlmError {
+    Request(reqwest::Error),
+    Api(String),
+    InvalidResponse,
+}
+
+impl std::fmt::Display for LlmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LlmError::Request(e) => write!(f, "Failed to reach language model: {}", e),
+            LlmError::Api(e) => write!(f, "Language model error: {}", e),
+            LlmError::InvalidResponse => {
+                write!(f, "Language model returned an unexpected response")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LlmError {}
+
+pub struct LlmClient {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+}
+
+impl LlmClient {
+    /// `None` when no model is configured
+    pub fn from_config(config: &LlmConfig) -> Option<Self> {
+        let base_url = config.base_url.as_deref()?.trim().trim_end_matches('/');
+        if base_url.is_empty() {
+            return None;
+        }
+        Some(Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.to_string(),
+            api_key: config.api_key.clone().filter(|key| !key.is_empty()),
+            model: config.model.clone(),
+        })
+    }
+
+    /// Run a single system + user exchange and return the reply text
+    pub async fn complete(&self, system: &str, user: &str) -> Result<String, LlmError> {
+        let mut request = self
+            .client
+            .post(format!("{}/chat/completions", self.base_url))
+            .json(&json!({
+                "model": self.model,
+                "temperature": 0.2,
+                "messages": [
+                    { "role": "system", "content": system },
+                    { "role": "user", "content": user },
+                ],
+            }));
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let response = request.send().await.map_err(LlmError::Request)?;
+        let status = response.status();
+        let body: Value = response.json().await.map_err(LlmError::Request)?;
+        if !status.is_success() {
+            let message = body["error"]["message"]
+                .as_str()
+                .or_else(|| body["error"].as_str())
+                .map(str::to_string)
+                .unwrap_or_else(|| status.to_string());
+            return Err(LlmError::Api(message));
+        }
+        body["choices"][0]["message"]["content"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or(LlmError::InvalidResponse)
+    }
+}
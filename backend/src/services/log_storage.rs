@@ -0,0 +1,431 @@
+//! Storage of execution output. Output is kept as the chunks it was appended in, so an append
+//! never rewrites what was stored before it. A stream past its configured limit is trimmed to
+//! its beginning and its latest output, with a marker line in place of what was dropped, and
+//! the full output can also be written to files on disk. Readers get a stream back as one
+//! string, so the normalizers work on a trimmed log as they do on a whole one.
+
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Mutex, RwLock},
+};
+
+use chrono::{DateTime, Utc};
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    models::{
+        config::LogStorageConfig,
+        execution_log_chunk::{ChunkSize, ChunkTiming, ExecutionLogChunk, LogStream},
+    },
+    utils::asset_dir,
+};
+
+/// Separates stderr chunks; see `stream_stderr_to_db`
+const STDERR_CHUNK_BOUNDARY: &str = "---STDERR_CHUNK_BOUNDARY---";
+
+lazy_static::lazy_static! {
+    static ref CONFIG: RwLock<LogStorageConfig> = RwLock::new(LogStorageConfig::default());
+    /// Bytes stored of the streams being written to, so appends don't have to sum them up
+    static ref STREAMS: Mutex<HashMap<(Uuid, LogStream), StreamState>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct StreamState {
+    stored_bytes: i64,
+    trimmed: bool,
+}
+
+pub fn configure(config: &LogStorageConfig) {
+    *CONFIG.write().unwrap_or_else(|e| e.into_inner()) = config.clone();
+}
+
+fn config() -> LogStorageConfig {
+    CONFIG.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// How much of a stream is kept, in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    pub max_bytes: i64,
+    pub head_bytes: i64,
+}
+
+impl Limits {
+    /// `None` when streams are kept whole
+    pub fn from_config(config: &LogStorageConfig) -> Option<Self> {
+        let max_bytes = i64::from(config.max_stream_kb?) * 1024;
+        Some(Self {
+            max_bytes,
+            head_bytes: (i64::from(config.head_kb) * 1024).min(max_bytes),
+        })
+    }
+}
+
+/// Chunks to replace with a marker: the ids from `first_id` to `last_id`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Trim {
+    pub first_id: i64,
+    pub last_id: i64,
+    /// Bytes the marker stands in for, including those of a marker it replaces
+    pub omitted_bytes: i64,
+    /// Bytes the stream keeps afterwards
+    pub retained_bytes: i64,
+}
+
+/// What to drop from a stream over its limit. The head is the leading chunks that fit its
+/// share of the limit; the tail is the latest chunks that fit the rest, less an eighth so
+/// that a growing log is trimmed now and then rather than on every append. The latest chunk
+/// is always kept, and chunks are never split, so every line kept is whole.
+pub fn plan_trim(chunks: &[ChunkSize], limits: Limits) -> Option<Trim> {
+    let total: i64 = chunks.iter().map(|chunk| chunk.size).sum();
+    if total <= limits.max_bytes {
+        return None;
+    }
+
+    let mut head_len = 0;
+    let mut head_bytes = 0;
+    for chunk in chunks {
+        if chunk.omitted_bytes > 0 || head_bytes + chunk.size > limits.head_bytes {
+            break;
+        }
+        head_bytes += chunk.size;
+        head_len += 1;
+    }
+
+    let tail_budget = limits.max_bytes - head_bytes;
+    let tail_target = tail_budget - tail_budget / 8;
+    let mut tail_start = chunks.len();
+    let mut tail_bytes = 0;
+    while tail_start > head_len {
+        let chunk = chunks[tail_start - 1];
+        let is_latest = tail_start == chunks.len();
+        if chunk.omitted_bytes > 0 || (!is_latest && tail_bytes + chunk.size > tail_target) {
+            break;
+        }
+        tail_bytes += chunk.size;
+        tail_start -= 1;
+    }
+
+    let dropped = &chunks[head_len..tail_start];
+    // Nothing but an earlier marker between the two
+    if !dropped.iter().any(|chunk| chunk.omitted_bytes == 0) {
+        return None;
+    }
+    Some(Trim {
+        first_id: dropped[0].id,
+        last_id: dropped[dropped.len() - 1].id,
+        omitted_bytes: dropped
+            .iter()
+            .map(|chunk| chunk.size + chunk.omitted_bytes)
+            .sum(),
+        retained_bytes: head_bytes + tail_bytes,
+    })
+}
+
+/// The file an execution's full output goes to when logs are offloaded
+pub fn full_log_path(execution_process_id: Uuid, stream: LogStream) -> PathBuf {
+    let stream = match stream {
+        LogStream::Stdout => "stdout",
+        LogStream::Stderr => "stderr",
+    };
+    asset_dir()
+        .join("logs")
+        .join(format!("{}.{}.log", execution_process_id, stream))
+}
+
+async fn offload(execution_process_id: Uuid, stream: LogStream, content: &str) {
+    let path = full_log_path(execution_process_id, stream);
+    let write = async {
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await?;
+        file.write_all(content.as_bytes()).await
+    };
+    if let Err(e) = write.await {
+        tracing::warn!(
+            "Failed to write the full log of execution {} to {}: {}",
+            execution_process_id,
+            path.display(),
+            e
+        );
+    }
+}
+
+/// Store output appended to the stream, trimming the stream if it has outgrown its limit.
+/// `received_at` is when the output was read from the process, if it was.
+pub async fn append(
+    pool: &DbPool,
+    execution_process_id: Uuid,
+    stream: LogStream,
+    content: &str,
+    received_at: Option<DateTime<Utc>>,
+) -> Result<(), sqlx::Error> {
+    let config = config();
+    if config.offload_full_logs {
+        offload(execution_process_id, stream, content).await;
+    }
+    ExecutionLogChunk::append(pool, execution_process_id, stream, content, received_at).await?;
+
+    let Some(limits) = Limits::from_config(&config) else {
+        return Ok(());
+    };
+    let key = (execution_process_id, stream);
+    let cached = STREAMS.lock().unwrap().get(&key).copied();
+    let mut state = match cached {
+        Some(state) => StreamState {
+            stored_bytes: state.stored_bytes + content.len() as i64,
+            ..state
+        },
+        None => StreamState {
+            stored_bytes: ExecutionLogChunk::total_size(pool, execution_process_id, stream).await?,
+            trimmed: false,
+        },
+    };
+
+    if state.stored_bytes > limits.max_bytes {
+        let chunks = ExecutionLogChunk::find_sizes(pool, execution_process_id, stream).await?;
+        state.trimmed |= chunks.iter().any(|chunk| chunk.omitted_bytes > 0);
+        match plan_trim(&chunks, limits) {
+            Some(trim) => {
+                ExecutionLogChunk::replace_with_marker(
+                    pool,
+                    execution_process_id,
+                    stream,
+                    trim.first_id,
+                    trim.last_id,
+                    trim.omitted_bytes,
+                )
+                .await?;
+                state.stored_bytes = trim.retained_bytes;
+                state.trimmed = true;
+            }
+            None => state.stored_bytes = chunks.iter().map(|chunk| chunk.size).sum(),
+        }
+    }
+    STREAMS.lock().unwrap().insert(key, state);
+    Ok(())
+}
+
+/// Whether output was dropped from the stream while it was being written
+pub fn was_trimmed(execution_process_id: Uuid, stream: LogStream) -> bool {
+    STREAMS
+        .lock()
+        .unwrap()
+        .get(&(execution_process_id, stream))
+        .is_some_and(|state| state.trimmed)
+}
+
+/// Stop tracking the streams of an execution that has finished
+pub fn forget(execution_process_id: Uuid) {
+    STREAMS
+        .lock()
+        .unwrap()
+        .retain(|(id, _), _| *id != execution_process_id);
+}
+
+/// The line standing in for output trimmed from the stream
+pub fn omission_marker(stream: LogStream, omitted_bytes: i64) -> String {
+    let notice = format!(
+        "[... {:.1} MB of output omitted ...]",
+        omitted_bytes as f64 / (1024.0 * 1024.0)
+    );
+    match stream {
+        LogStream::Stdout => format!("{}\n", notice),
+        LogStream::Stderr => format!("{}\n{}\n", notice, STDERR_CHUNK_BOUNDARY),
+    }
+}
+
+/// The stream's stored output, with a line in place of anything trimmed from it, or `None`
+/// if nothing was stored
+pub async fn read(
+    pool: &DbPool,
+    execution_process_id: Uuid,
+    stream: LogStream,
+) -> Result<Option<String>, sqlx::Error> {
+    let chunks =
+        ExecutionLogChunk::find_by_execution_process_id(pool, execution_process_id, stream).await?;
+    if chunks.is_empty() {
+        return Ok(None);
+    }
+    let mut log = String::with_capacity(chunks.iter().map(|chunk| chunk.size as usize).sum());
+    for chunk in &chunks {
+        if chunk.is_marker() {
+            if !log.is_empty() && !log.ends_with('\n') {
+                log.push('\n');
+            }
+            log.push_str(&omission_marker(stream, chunk.omitted_bytes));
+        } else {
+            log.push_str(&chunk.content);
+        }
+    }
+    Ok(Some(log))
+}
+
+/// Where in the stream, as [`read`] returns it, output received at each time starts: the
+/// byte offset of each chunk that has a receive time, in order
+pub async fn timeline(
+    pool: &DbPool,
+    execution_process_id: Uuid,
+    stream: LogStream,
+) -> Result<Vec<(usize, DateTime<Utc>)>, sqlx::Error> {
+    let chunks = ExecutionLogChunk::find_timings(pool, execution_process_id, stream).await?;
+    Ok(chunk_offsets(stream, &chunks))
+}
+
+fn chunk_offsets(stream: LogStream, chunks: &[ChunkTiming]) -> Vec<(usize, DateTime<Utc>)> {
+    let mut offsets = Vec::new();
+    let mut offset = 0;
+    let mut ends_with_newline = true;
+    for chunk in chunks {
+        if chunk.omitted_bytes > 0 {
+            // The marker goes on a line of its own, as `read` puts it
+            if !ends_with_newline {
+                offset += 1;
+            }
+            offset += omission_marker(stream, chunk.omitted_bytes).len();
+            ends_with_newline = true;
+            continue;
+        }
+        if chunk.size == 0 {
+            continue;
+        }
+        if let Some(received_at) = chunk.received_at {
+            offsets.push((offset, received_at));
+        }
+        offset += chunk.size as usize;
+        ends_with_newline = chunk.ends_with_newline;
+    }
+    offsets
+}
+
+/// Drop everything stored for an execution, in the database and on disk
+pub async fn clear(pool: &DbPool, execution_process_id: Uuid) -> Result<(), sqlx::Error> {
+    ExecutionLogChunk::delete_by_execution_process_id(pool, execution_process_id).await?;
+    forget(execution_process_id);
+    for stream in [LogStream::Stdout, LogStream::Stderr] {
+        let path = full_log_path(execution_process_id, stream);
+        match tokio::fs::remove_file(&path).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                tracing::warn!("Failed to remove {}: {}", path.display(), e)
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunks(sizes: &[i64]) -> Vec<ChunkSize> {
+        sizes
+            .iter()
+            .enumerate()
+            .map(|(i, &size)| ChunkSize {
+                id: i as i64 + 1,
+                size,
+                omitted_bytes: 0,
+            })
+            .collect()
+    }
+
+    const LIMITS: Limits = Limits {
+        max_bytes: 100,
+        head_bytes: 20,
+    };
+
+    #[test]
+    fn test_streams_within_the_limit_are_kept_whole() {
+        assert_eq!(plan_trim(&chunks(&[50, 50]), LIMITS), None);
+    }
+
+    #[test]
+    fn test_trimming_keeps_the_head_and_the_latest_output() {
+        // Head: 10 + 10. Tail target: 80 less an eighth, 70: the last seven chunks.
+        let trim = plan_trim(&chunks(&[10; 12]), LIMITS).unwrap();
+        assert_eq!(
+            trim,
+            Trim {
+                first_id: 3,
+                last_id: 5,
+                omitted_bytes: 30,
+                retained_bytes: 90,
+            }
+        );
+    }
+
+    #[test]
+    fn test_a_later_trim_folds_the_previous_marker_into_its_own() {
+        let mut stream = chunks(&[10, 10, 0, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10]);
+        stream[2].omitted_bytes = 500;
+        let trim = plan_trim(&stream, LIMITS).unwrap();
+        assert_eq!(trim.first_id, 3);
+        assert_eq!(trim.last_id, 6);
+        assert_eq!(trim.omitted_bytes, 500 + 30);
+        assert_eq!(trim.retained_bytes, 90);
+    }
+
+    #[test]
+    fn test_the_latest_chunk_is_kept_even_past_the_limit() {
+        let trim = plan_trim(&chunks(&[10, 30, 150]), LIMITS).unwrap();
+        assert_eq!((trim.first_id, trim.last_id), (2, 2));
+        assert_eq!(trim.retained_bytes, 160);
+
+        let mut trimmed = chunks(&[10, 0, 150]);
+        trimmed[1].omitted_bytes = 30;
+        assert_eq!(plan_trim(&trimmed, LIMITS), None);
+    }
+
+    #[test]
+    fn test_markers_read_as_their_own_line() {
+        assert_eq!(
+            omission_marker(LogStream::Stdout, 3 * 1024 * 1024),
+            "[... 3.0 MB of output omitted ...]\n"
+        );
+        assert!(omission_marker(LogStream::Stderr, 1).ends_with("---STDERR_CHUNK_BOUNDARY---\n"));
+    }
+
+    #[test]
+    fn test_chunk_offsets_count_the_marker_as_read_puts_it() {
+        let start = Utc::now();
+        let at = |secs| Some(start + chrono::Duration::seconds(secs));
+        let timing = |size, ends_with_newline, received_at| ChunkTiming {
+            size,
+            omitted_bytes: 0,
+            ends_with_newline,
+            received_at,
+        };
+        let marker = ChunkTiming {
+            omitted_bytes: 2048,
+            ..timing(0, false, None)
+        };
+        // "ab\n" "cd" [marker on a line of its own] "ef\n", and an old chunk without a time
+        let chunks = [
+            timing(3, true, at(0)),
+            timing(2, false, at(1)),
+            marker,
+            timing(3, true, at(5)),
+            timing(4, true, None),
+            timing(1, true, at(9)),
+        ];
+        let marker_len = omission_marker(LogStream::Stdout, 2048).len();
+        assert_eq!(
+            chunk_offsets(LogStream::Stdout, &chunks),
+            vec![
+                (0, at(0).unwrap()),
+                (3, at(1).unwrap()),
+                (6 + marker_len, at(5).unwrap()),
+                (6 + marker_len + 7, at(9).unwrap()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! Slows down guessing passwords and two-factor codes. Failed attempts are counted per
+//! account, per user's two-factor codes and per client address; too many of them within
+//! [`WINDOW`] lock that one out for [`LOCKOUT`], right guesses included.
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use uuid::Uuid;
+
+/// How long failed attempts count for
+const WINDOW: Duration = Duration::from_secs(15 * 60);
+/// How long a subject stays locked out once it has failed too often
+const LOCKOUT: Duration = Duration::from_secs(15 * 60);
+const MAX_ACCOUNT_FAILURES: u32 = 5;
+/// An address may be trying several accounts, as a team behind one NAT does
+const MAX_ADDRESS_FAILURES: u32 = 20;
+
+lazy_static::lazy_static! {
+    static ref THROTTLE: Mutex<Throttle> = Mutex::new(Throttle::default());
+}
+
+/// What failed attempts are counted against
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Subject {
+    /// Passwords tried for a username, whether or not an account has it
+    Account(String),
+    /// Two-factor codes tried for a user, at sign-in or to change their two-factor settings
+    TwoFactor(Uuid),
+    /// Everything tried from a client address
+    Address(IpAddr),
+}
+
+impl Subject {
+    pub fn account(username: &str) -> Self {
+        Subject::Account(username.trim().to_lowercase())
+    }
+
+    fn max_failures(&self) -> u32 {
+        match self {
+            Subject::Account(_) | Subject::TwoFactor(_) => MAX_ACCOUNT_FAILURES,
+            Subject::Address(_) => MAX_ADDRESS_FAILURES,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Failures {
+    count: u32,
+    first_at: Instant,
+    locked_until: Option<Instant>,
+}
+
+#[derive(Debug, Default)]
+struct Throttle {
+    failures: HashMap<Subject, Failures>,
+}
+
+impl Throttle {
+    fn retry_after(&self, subjects: &[Subject], now: Instant) -> Option<Duration> {
+        subjects
+            .iter()
+            .filter_map(|subject| self.failures.get(subject)?.locked_until)
+            .filter(|&until| until > now)
+            .map(|until| until - now)
+            .max()
+    }
+
+    fn record_failure(&mut self, subjects: &[Subject], now: Instant) {
+        self.prune(now);
+        for subject in subjects {
+            let failures = self.failures.entry(subject.clone()).or_insert(Failures {
+                count: 0,
+                first_at: now,
+                locked_until: None,
+            });
+            failures.count += 1;
+            if failures.count >= subject.max_failures() {
+                failures.locked_until = Some(now + LOCKOUT);
+            }
+        }
+    }
+
+    fn record_success(&mut self, subjects: &[Subject]) {
+        for subject in subjects {
+            self.failures.remove(subject);
+        }
+    }
+
+    /// Forget failures that no longer count and lockouts that are over
+    fn prune(&mut self, now: Instant) {
+        self.failures
+            .retain(|_, failures| match failures.locked_until {
+                Some(until) => until > now,
+                None => now.duration_since(failures.first_at) < WINDOW,
+            });
+    }
+}
+
+/// How long until every one of the subjects may try again, if any is locked out
+pub fn retry_after(subjects: &[Subject]) -> Option<Duration> {
+    THROTTLE
+        .lock()
+        .unwrap()
+        .retry_after(subjects, Instant::now())
+}
+
+pub fn record_failure(subjects: &[Subject]) {
+    THROTTLE
+        .lock()
+        .unwrap()
+        .record_failure(subjects, Instant::now());
+}
+
+/// Clear the subjects' failures, once the right password or code was given. Addresses are
+/// left alone, as whoever is guessing may well have an account of their own.
+pub fn record_success(subjects: &[Subject]) {
+    THROTTLE.lock().unwrap().record_success(subjects);
+}
+
+/// What to tell a client that is locked out
+pub fn locked_out_message(retry_after: Duration) -> String {
+    let minutes = retry_after.as_secs().div_ceil(60).max(1);
+    format!(
+        "Too many failed attempts; try again in {} minute{}",
+        minutes,
+        if minutes == 1 { "" } else { "s" }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_too_many_failures_lock_a_subject_out() {
+        let mut throttle = Throttle::default();
+        let now = Instant::now();
+        let account = [Subject::account(" Alice ")];
+        for _ in 0..MAX_ACCOUNT_FAILURES - 1 {
+            throttle.record_failure(&account, now);
+        }
+        assert_eq!(throttle.retry_after(&account, now), None);
+        throttle.record_failure(&[Subject::account("alice")], now);
+        assert_eq!(throttle.retry_after(&account, now), Some(LOCKOUT));
+
+        // Other accounts carry on, and the lockout ends
+        assert_eq!(throttle.retry_after(&[Subject::account("bob")], now), None);
+        let later = now + LOCKOUT;
+        assert_eq!(throttle.retry_after(&account, later), None);
+        throttle.prune(later);
+        assert!(throttle.failures.is_empty());
+    }
+
+    #[test]
+    fn test_failures_expire_and_successes_clear_them() {
+        let mut throttle = Throttle::default();
+        let now = Instant::now();
+        let user = [Subject::TwoFactor(Uuid::new_v4())];
+        let address = Subject::Address(IpAddr::from([192, 0, 2, 1]));
+        let both = [user[0].clone(), address.clone()];
+
+        for _ in 0..MAX_ACCOUNT_FAILURES - 1 {
+            throttle.record_failure(&both, now);
+        }
+        throttle.record_success(&user);
+        throttle.record_failure(&both, now);
+        assert_eq!(throttle.retry_after(&both, now), None);
+        assert_eq!(throttle.failures[&address].count, MAX_ACCOUNT_FAILURES);
+
+        // Failures from before the window don't add up with later ones
+        let later = now + WINDOW;
+        for _ in 0..MAX_ACCOUNT_FAILURES - 1 {
+            throttle.record_failure(&user, later);
+        }
+        assert_eq!(throttle.retry_after(&user, later), None);
+    }
+
+    #[test]
+    fn test_locked_out_message_rounds_up_to_minutes() {
+        assert_eq!(
+            locked_out_message(Duration::from_secs(61)),
+            "Too many failed attempts; try again in 2 minutes"
+        );
+        assert_eq!(
+            locked_out_message(Duration::from_secs(5)),
+            "Too many failed attempts; try again in 1 minute"
+        );
+    }
+}
@@ -0,0 +1,179 @@
+//! @username mentions in review comments and follow-up prompts. Each name is looked up
+//! among the people who can see the task's project, its members and the server's admins,
+//! and whoever it names gets the mention in their inbox. Names that match no one are left
+//! as plain text.
+
+use std::collections::{HashMap, HashSet};
+
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    models::{
+        mention::{Mention, MentionSource},
+        project_member::ProjectMember,
+        task::Task,
+        user::User,
+    },
+};
+
+/// How much of the comment or prompt the inbox shows
+const EXCERPT_CHARS: usize = 200;
+
+fn is_username_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '@')
+}
+
+/// The names mentioned in `text`, once each and in the order they first appear. An `@`
+/// only starts a mention at the start of a word, so email addresses aren't mistaken for
+/// one.
+pub fn parse_mentions(text: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut previous = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        let starts_word = previous.is_none_or(|p: char| !is_username_char(p));
+        previous = Some(c);
+        if c != '@' || !starts_word {
+            continue;
+        }
+        let start = index + 1;
+        let mut end = start;
+        while let Some(&(next_index, next)) = chars.peek() {
+            if !is_username_char(next) {
+                break;
+            }
+            end = next_index + next.len_utf8();
+            previous = Some(next);
+            chars.next();
+        }
+        let name = &text[start..end];
+        if !name.is_empty() && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// The text's start, on one line, for the inbox
+fn excerpt(text: &str) -> String {
+    let line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match line.char_indices().nth(EXCERPT_CHARS) {
+        Some((cut, _)) => format!("{}…", &line[..cut]),
+        None => line,
+    }
+}
+
+/// The user a mentioned name is for. Names match case-insensitively, and a mention
+/// that ends a sentence, as in "ask @ada.", still finds `ada`.
+fn resolve<'a>(people: &'a HashMap<String, Uuid>, name: &str) -> Option<&'a Uuid> {
+    let name = name.to_lowercase();
+    people
+        .get(&name)
+        .or_else(|| people.get(name.trim_end_matches(['.', '-'])))
+}
+
+/// Put the task's mentions in `text` into the inboxes of those mentioned, except the
+/// author's own and, for edited text, those `previous` already mentioned. Returns who was
+/// notified.
+pub async fn notify(
+    pool: &DbPool,
+    task: &Task,
+    task_attempt_id: Option<Uuid>,
+    source: MentionSource,
+    author: Option<&User>,
+    text: &str,
+    previous: Option<&str>,
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    let names = parse_mentions(text);
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut people: HashMap<String, Uuid> =
+        ProjectMember::find_by_project_id(pool, task.project_id)
+            .await?
+            .into_iter()
+            .map(|member| (member.username.to_lowercase(), member.user_id))
+            .collect();
+    for admin in User::find_all(pool)
+        .await?
+        .into_iter()
+        .filter(|user| user.is_admin)
+    {
+        people.insert(admin.username.to_lowercase(), admin.id);
+    }
+
+    let mut skipped: HashSet<Uuid> = previous
+        .map(parse_mentions)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|name| resolve(&people, name).copied())
+        .collect();
+    skipped.extend(author.map(|author| author.id));
+
+    let excerpt = excerpt(text);
+    let mut notified = Vec::new();
+    for name in &names {
+        let Some(&user_id) = resolve(&people, name) else {
+            continue;
+        };
+        if skipped.contains(&user_id) || notified.contains(&user_id) {
+            continue;
+        }
+        Mention::create(
+            pool,
+            user_id,
+            task.id,
+            task_attempt_id,
+            source,
+            author.map(|author| author.id),
+            &excerpt,
+        )
+        .await?;
+        notified.push(user_id);
+    }
+    if !notified.is_empty() {
+        tracing::info!(
+            "Notified {} user(s) mentioned on task {}",
+            notified.len(),
+            task.id
+        );
+    }
+    Ok(notified)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mentions_start_words_and_are_listed_once() {
+        assert_eq!(
+            parse_mentions("@ada can you look? cc @bob.smith, @ada"),
+            vec!["ada", "bob.smith"]
+        );
+        assert_eq!(parse_mentions("mail ada@example.com"), Vec::<String>::new());
+        assert_eq!(parse_mentions("(@ada) and @"), vec!["ada"]);
+        assert_eq!(
+            parse_mentions("ask @ada.lovelace@example.com"),
+            vec!["ada.lovelace@example.com"]
+        );
+    }
+
+    #[test]
+    fn test_names_match_without_case_or_trailing_punctuation() {
+        let ada = Uuid::new_v4();
+        let people = HashMap::from([("ada".to_string(), ada)]);
+        assert_eq!(resolve(&people, "Ada"), Some(&ada));
+        assert_eq!(resolve(&people, "ada."), Some(&ada));
+        assert_eq!(resolve(&people, "adam"), None);
+    }
+
+    #[test]
+    fn test_excerpts_are_one_short_line() {
+        assert_eq!(excerpt("  fix\n the   tests "), "fix the tests");
+        let long = "a ".repeat(EXCERPT_CHARS);
+        assert_eq!(excerpt(&long).chars().count(), EXCERPT_CHARS + 1);
+    }
+}
@@ -0,0 +1,396 @@
+//! Attempts that target the same base branch land one at a time. Each queued attempt waits
+//! for the merges ahead of it, is rebased onto the base branch as they left it, runs the
+//! project's merge check script in its worktree, and only then merges. Two attempts that
+//! each merge cleanly on their own can still break each other; this catches that before
+//! the second one lands rather than after.
+//!
+//! The queue is kept in the database. Merges still pending when the server stops are
+//! started over when it starts again, ahead of any queued since.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex as StdMutex},
+};
+
+use tokio::{
+    io::{AsyncRead, AsyncReadExt},
+    sync::{Mutex, OwnedMutexGuard},
+};
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    command_runner::{CommandError, CommandRunner},
+    db::DbPool,
+    models::{
+        merge_queue_entry::{MergeQueueEntry, MergeQueueStatus},
+        project::Project,
+        task::{Task, TaskStatus},
+        task_attempt::TaskAttempt,
+    },
+    utils::shell::get_shell_command,
+};
+
+/// Finished entries kept per project so the outcome stays visible for a while
+const KEPT_FINISHED_ENTRIES: i64 = 20;
+/// Only the end of a failing check's output is kept; that's where the error usually is
+const CHECK_OUTPUT_TAIL_CHARS: usize = 4_000;
+
+#[derive(Debug)]
+pub enum MergeQueueError {
+    AlreadyQueued,
+    Database(sqlx::Error),
+}
+
+impl std::fmt::Display for MergeQueueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MergeQueueError::AlreadyQueued => write!(f, "This attempt is already queued to merge"),
+            MergeQueueError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for MergeQueueError {}
+
+impl From<sqlx::Error> for MergeQueueError {
+    fn from(err: sqlx::Error) -> Self {
+        match err {
+            // An attempt has at most one pending entry
+            sqlx::Error::Database(e) if e.is_unique_violation() => MergeQueueError::AlreadyQueued,
+            err => MergeQueueError::Database(err),
+        }
+    }
+}
+
+/// Project and base branch
+type LaneKey = (Uuid, String);
+
+#[derive(Debug, Default)]
+pub struct MergeQueue {
+    /// One lane per project and base branch. Tokio's mutex hands the lock out in the order
+    /// it was asked for, which is what keeps the queue first in, first out.
+    lanes: StdMutex<HashMap<LaneKey, Arc<Mutex<()>>>>,
+}
+
+impl MergeQueue {
+    /// The lock every merge into `base_branch` holds while it rebases, checks and lands,
+    /// including merges started outside the queue
+    pub fn lane(&self, project_id: Uuid, base_branch: &str) -> Arc<Mutex<()>> {
+        self.lanes
+            .lock()
+            .unwrap()
+            .entry((project_id, base_branch.to_string()))
+            .or_default()
+            .clone()
+    }
+}
+
+/// Queue the attempt to merge into its base branch
+pub async fn enqueue(
+    pool: &DbPool,
+    project: &Project,
+    task: &Task,
+    attempt: &TaskAttempt,
+) -> Result<MergeQueueEntry, MergeQueueError> {
+    let entry = MergeQueueEntry::create(
+        pool,
+        project.id,
+        task.id,
+        attempt.id,
+        &task.title,
+        &attempt.base_branch,
+    )
+    .await?;
+    MergeQueueEntry::prune_finished(pool, project.id, KEPT_FINISHED_ENTRIES).await?;
+    Ok(entry)
+}
+
+/// Pending entries in queue order, then finished ones, most recent first
+pub async fn for_project(
+    pool: &DbPool,
+    project_id: Uuid,
+) -> Result<Vec<MergeQueueEntry>, sqlx::Error> {
+    let (finished, pending): (Vec<_>, Vec<_>) =
+        MergeQueueEntry::find_by_project_id(pool, project_id)
+            .await?
+            .into_iter()
+            .partition(|entry| entry.status.is_finished());
+    Ok(pending
+        .into_iter()
+        .chain(finished.into_iter().rev())
+        .collect())
+}
+
+async fn set_status(pool: &DbPool, entry_id: Uuid, status: MergeQueueStatus) {
+    if let Err(e) = MergeQueueEntry::set_status(pool, entry_id, status).await {
+        tracing::error!("Failed to update merge queue entry {}: {}", entry_id, e);
+    }
+}
+
+async fn fail(pool: &DbPool, entry_id: Uuid, error: String, check_output: Option<String>) {
+    if let Err(e) = MergeQueueEntry::fail(pool, entry_id, &error, check_output.as_deref()).await {
+        tracing::error!("Failed to update merge queue entry {}: {}", entry_id, e);
+    }
+}
+
+/// Mark the entry's task done and the entry merged
+async fn finish(pool: &DbPool, entry: &MergeQueueEntry, merge_commit: &str) {
+    if let Err(e) =
+        Task::update_status(pool, entry.task_id, entry.project_id, TaskStatus::Done).await
+    {
+        tracing::error!("Failed to update task status to Done after merge: {}", e);
+    }
+    if let Err(e) = MergeQueueEntry::mark_merged(pool, entry.id, merge_commit).await {
+        tracing::error!("Failed to update merge queue entry {}: {}", entry.id, e);
+    }
+}
+
+/// Wait for the entry's turn in its lane, then rebase, check and merge it
+pub async fn process(app_state: AppState, project: Project, entry: MergeQueueEntry) {
+    let lane = app_state
+        .merge_queue
+        .lane(entry.project_id, &entry.base_branch);
+    let _turn = lane.lock().await;
+    land(&app_state, &project, &entry).await;
+}
+
+/// Start over the merges still pending when the server stopped. Each lane's are taken up
+/// in the order they were queued, ahead of any queued from now on. Call at startup.
+pub async fn recover(app_state: &AppState) {
+    let pending = match MergeQueueEntry::find_pending(&app_state.db_pool).await {
+        Ok(pending) => pending,
+        Err(e) => {
+            tracing::error!("Failed to load the merge queue: {}", e);
+            return;
+        }
+    };
+
+    let mut lanes: Vec<(LaneKey, Vec<MergeQueueEntry>)> = Vec::new();
+    for entry in pending {
+        let key = (entry.project_id, entry.base_branch.clone());
+        match lanes.iter_mut().find(|(lane, _)| *lane == key) {
+            Some((_, entries)) => entries.push(entry),
+            None => lanes.push((key, vec![entry])),
+        }
+    }
+    for ((project_id, base_branch), entries) in lanes {
+        tracing::info!(
+            "Resuming {} queued merge(s) into {} of project {}",
+            entries.len(),
+            base_branch,
+            project_id
+        );
+        // Taken now, so merges queued later wait for these
+        let turn = app_state
+            .merge_queue
+            .lane(project_id, &base_branch)
+            .lock_owned()
+            .await;
+        tokio::spawn(resume_lane(app_state.clone(), turn, entries));
+    }
+}
+
+async fn resume_lane(
+    app_state: AppState,
+    _turn: OwnedMutexGuard<()>,
+    entries: Vec<MergeQueueEntry>,
+) {
+    let pool = &app_state.db_pool;
+    for entry in entries {
+        // A merge cut off after it landed only needs recording
+        match TaskAttempt::find_by_id(pool, entry.attempt_id).await {
+            Ok(Some(attempt)) => {
+                if let Some(merge_commit) = attempt.merge_commit {
+                    finish(pool, &entry, &merge_commit).await;
+                    continue;
+                }
+            }
+            Ok(None) => continue,
+            Err(e) => {
+                fail(pool, entry.id, format!("Attempt unavailable: {}", e), None).await;
+                continue;
+            }
+        }
+        match Project::find_by_id(pool, entry.project_id).await {
+            Ok(Some(project)) => land(&app_state, &project, &entry).await,
+            Ok(None) => {}
+            Err(e) => fail(pool, entry.id, format!("Project unavailable: {}", e), None).await,
+        }
+    }
+}
+
+/// Rebase, check and merge the entry. The caller holds its lane.
+async fn land(app_state: &AppState, project: &Project, entry: &MergeQueueEntry) {
+    let pool = &app_state.db_pool;
+    set_status(pool, entry.id, MergeQueueStatus::Rebasing).await;
+    if let Err(e) = TaskAttempt::rebase_attempt(
+        pool,
+        entry.attempt_id,
+        entry.task_id,
+        entry.project_id,
+        None,
+        false,
+    )
+    .await
+    {
+        fail(pool, entry.id, format!("Rebase failed: {}", e), None).await;
+        return;
+    }
+
+    if let Some(script) = project
+        .merge_check_script
+        .as_deref()
+        .filter(|script| !script.trim().is_empty())
+    {
+        set_status(pool, entry.id, MergeQueueStatus::Checking).await;
+        let worktree_path = match TaskAttempt::ensure_worktree_exists(
+            pool,
+            entry.attempt_id,
+            entry.project_id,
+            "merge check",
+        )
+        .await
+        {
+            Ok(path) => path,
+            Err(e) => {
+                fail(pool, entry.id, format!("Worktree unavailable: {}", e), None).await;
+                return;
+            }
+        };
+        match run_check(script, &worktree_path, &entry.base_branch).await {
+            Ok(None) => {}
+            Ok(Some(CheckFailure { code, output })) => {
+                let reason = match code {
+                    Some(code) => format!("Merge check failed with exit code {}", code),
+                    None => "Merge check was terminated".to_string(),
+                };
+                fail(pool, entry.id, reason, Some(output)).await;
+                return;
+            }
+            Err(e) => {
+                fail(
+                    pool,
+                    entry.id,
+                    format!("Merge check could not run: {}", e),
+                    None,
+                )
+                .await;
+                return;
+            }
+        }
+    }
+
+    set_status(pool, entry.id, MergeQueueStatus::Merging).await;
+    let merge_commit =
+        match TaskAttempt::merge_changes(pool, entry.attempt_id, entry.task_id, entry.project_id)
+            .await
+        {
+            Ok(commit) => commit,
+            Err(e) => {
+                fail(pool, entry.id, format!("Merge failed: {}", e), None).await;
+                return;
+            }
+        };
+
+    app_state
+        .track_analytics_event(
+            "task_attempt_merged",
+            Some(serde_json::json!({
+                "task_id": entry.task_id.to_string(),
+                "project_id": entry.project_id.to_string(),
+                "attempt_id": entry.attempt_id.to_string(),
+                "via_merge_queue": true,
+            })),
+        )
+        .await;
+    finish(pool, entry, &merge_commit).await;
+}
+
+struct CheckFailure {
+    /// `None` when the script was killed by a signal
+    code: Option<i32>,
+    output: String,
+}
+
+/// Run the merge check script in the worktree, `None` when it passed
+async fn run_check(
+    script: &str,
+    worktree_path: &str,
+    base_branch: &str,
+) -> Result<Option<CheckFailure>, CommandError> {
+    let (shell_cmd, shell_arg) = get_shell_command();
+    let mut child = CommandRunner::new()
+        .command(shell_cmd)
+        .arg(shell_arg)
+        .arg(script)
+        .working_dir(worktree_path)
+        .env("VK_BASE_BRANCH", base_branch)
+        .start()
+        .await?;
+
+    let stream = child.stream().await?;
+    let (stdout, stderr) = tokio::join!(read_all(stream.stdout), read_all(stream.stderr));
+    let status = child.wait().await?;
+    if status.success() {
+        return Ok(None);
+    }
+    Ok(Some(CheckFailure {
+        code: status.code(),
+        output: output_tail(&stdout, &stderr),
+    }))
+}
+
+pub(crate) async fn read_all(output: Option<Box<dyn AsyncRead + Unpin + Send>>) -> String {
+    let mut buffer = Vec::new();
+    if let Some(mut output) = output {
+        let _ = output.read_to_end(&mut buffer).await;
+    }
+    String::from_utf8_lossy(&buffer).into_owned()
+}
+
+pub(crate) fn output_tail(stdout: &str, stderr: &str) -> String {
+    let combined = match (stdout.trim_end(), stderr.trim_end()) {
+        (out, "") => out.to_string(),
+        ("", err) => err.to_string(),
+        (out, err) => format!("{}\n{}", out, err),
+    };
+    let chars = combined.chars().count();
+    if chars <= CHECK_OUTPUT_TAIL_CHARS {
+        return combined;
+    }
+    let tail: String = combined
+        .chars()
+        .skip(chars - CHECK_OUTPUT_TAIL_CHARS)
+        .collect();
+    format!("…{}", tail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_tail() {
+        assert_eq!(
+            output_tail("built\n", "error: boom\n"),
+            "built\nerror: boom"
+        );
+        let long = "x".repeat(CHECK_OUTPUT_TAIL_CHARS + 10);
+        let tail = output_tail(&long, "");
+        assert!(tail.starts_with('…'));
+        assert_eq!(tail.chars().count(), CHECK_OUTPUT_TAIL_CHARS + 1);
+    }
+
+    #[tokio::test]
+    async fn test_lanes_are_per_project_and_branch() {
+        let queue = MergeQueue::default();
+        let project = Uuid::new_v4();
+        let main = queue.lane(project, "main");
+        let _held = main.lock().await;
+
+        assert!(queue.lane(project, "main").try_lock().is_err());
+        assert!(queue.lane(project, "release").try_lock().is_ok());
+        assert!(queue.lane(Uuid::new_v4(), "main").try_lock().is_ok());
+    }
+}
@@ -0,0 +1,452 @@
+//! Schema migrations of the app database: the checksummed files in `migrations/`, or in
+//! `migrations/postgres/` for PostgreSQL, applied in version order. A SQLite database is
+//! copied to `backups/` before any are applied or rolled back, and a migration that comes with
+//! a `.down.sql` script can be rolled back with `--migrate-down`, one at a time.
+//!
+//! PostgreSQL's migrations start from a baseline of the SQLite schema as of its version. Every
+//! migration after it is written for both databases, under the same version, and a test run
+//! against a PostgreSQL server checks that the two build the same tables.
+//!
+//! Migrations keep the previous release working against the upgraded schema: they add
+//! tables and nullable or defaulted columns rather than renaming or dropping them. That lets
+//! a release run against a database a newer one has migrated, so going back to it doesn't
+//! require rolling anything back.
+
+use std::path::PathBuf;
+
+use anyhow::bail;
+use chrono::Utc;
+use sqlx::migrate::{Migration, Migrator};
+
+use crate::{db::DbPool, utils::asset_dir};
+
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+pub static POSTGRES_MIGRATOR: Migrator = sqlx::migrate!("./migrations/postgres");
+
+/// Backups of the database taken before migrating it; older ones are removed
+const KEPT_BACKUPS: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingMigration {
+    pub version: i64,
+    pub description: String,
+}
+
+/// What bringing the database up to this build's schema would do
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationPlan {
+    /// Migrations to apply, in order, including any that failed halfway before
+    pub pending: Vec<PendingMigration>,
+    /// Applied migrations whose file has changed since, which stop any others from applying
+    pub modified: Vec<i64>,
+    /// Applied migrations this build doesn't have, from a newer release
+    pub newer: Vec<i64>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct AppliedMigration {
+    version: i64,
+    success: bool,
+    checksum: Vec<u8>,
+}
+
+impl MigrationPlan {
+    /// The plan as `--migrate-dry-run` prints it
+    pub fn summary(&self) -> String {
+        let mut lines = Vec::new();
+        if self.pending.is_empty() {
+            lines.push("The database is up to date".to_string());
+        } else {
+            lines.push(format!("{} migration(s) to apply:", self.pending.len()));
+            for migration in &self.pending {
+                lines.push(format!("  {} {}", migration.version, migration.description));
+            }
+        }
+        if !self.modified.is_empty() {
+            lines.push(format!(
+                "Applied migrations changed since: {}",
+                join_versions(&self.modified)
+            ));
+        }
+        if !self.newer.is_empty() {
+            lines.push(format!(
+                "Applied by a newer release: {}",
+                join_versions(&self.newer)
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+fn join_versions(versions: &[i64]) -> String {
+    versions
+        .iter()
+        .map(|version| version.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn plan_migrations<'a>(
+    shipped: impl Iterator<Item = &'a Migration>,
+    applied: &[AppliedMigration],
+) -> MigrationPlan {
+    let mut plan = MigrationPlan::default();
+    let mut shipped_versions = Vec::new();
+    for migration in shipped.filter(|migration| !migration.migration_type.is_down_migration()) {
+        shipped_versions.push(migration.version);
+        match applied
+            .iter()
+            .find(|applied| applied.version == migration.version && applied.success)
+        {
+            Some(applied) if *applied.checksum != *migration.checksum => {
+                plan.modified.push(migration.version)
+            }
+            Some(_) => {}
+            None => plan.pending.push(PendingMigration {
+                version: migration.version,
+                description: migration.description.to_string(),
+            }),
+        }
+    }
+    plan.newer = applied
+        .iter()
+        .map(|applied| applied.version)
+        .filter(|version| !shipped_versions.contains(version))
+        .collect();
+    plan
+}
+
+/// The migrations of the pool's database
+pub fn migrator(pool: &DbPool) -> &'static Migrator {
+    match pool {
+        DbPool::Sqlite(_) => &MIGRATOR,
+        DbPool::Postgres(_) => &POSTGRES_MIGRATOR,
+    }
+}
+
+async fn applied_migrations(pool: &DbPool) -> Result<Vec<AppliedMigration>, sqlx::Error> {
+    const APPLIED: &str =
+        "SELECT version, success, checksum FROM _sqlx_migrations ORDER BY version";
+    match pool {
+        DbPool::Sqlite(pool) => {
+            let exists: Option<String> = sqlx::query_scalar(
+                "SELECT name FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations'",
+            )
+            .fetch_optional(pool)
+            .await?;
+            if exists.is_none() {
+                return Ok(Vec::new());
+            }
+            sqlx::query_as(APPLIED).fetch_all(pool).await
+        }
+        DbPool::Postgres(pool) => {
+            let exists: bool =
+                sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+                    .fetch_one(pool)
+                    .await?;
+            if !exists {
+                return Ok(Vec::new());
+            }
+            sqlx::query_as(APPLIED).fetch_all(pool).await
+        }
+    }
+}
+
+/// What [`upgrade`] would do, without doing it
+pub async fn plan(pool: &DbPool) -> Result<MigrationPlan, sqlx::Error> {
+    Ok(plan_migrations(
+        migrator(pool).iter(),
+        &applied_migrations(pool).await?,
+    ))
+}
+
+/// Apply the pending migrations, backing the database up first
+pub async fn upgrade(pool: &DbPool) -> anyhow::Result<()> {
+    let plan = plan(pool).await?;
+    if !plan.modified.is_empty() {
+        bail!(
+            "Migrations {} were changed after they were applied; restore a backup from {}",
+            join_versions(&plan.modified),
+            backup_dir().display()
+        );
+    }
+    if !plan.newer.is_empty() {
+        tracing::warn!(
+            "The database was migrated by a newer release (migrations {}); running against it anyway",
+            join_versions(&plan.newer)
+        );
+    }
+    if plan.pending.is_empty() {
+        return Ok(());
+    }
+
+    // A fresh database has nothing to lose
+    if !applied_migrations(pool).await?.is_empty() {
+        backup(pool, &format!("before-{}", plan.pending[0].version)).await?;
+    }
+    match pool {
+        DbPool::Sqlite(pool) => {
+            let mut migrator = sqlx::migrate!("./migrations");
+            migrator.set_ignore_missing(!plan.newer.is_empty());
+            migrator.run(pool).await?;
+        }
+        DbPool::Postgres(pool) => {
+            let mut migrator = sqlx::migrate!("./migrations/postgres");
+            migrator.set_ignore_missing(!plan.newer.is_empty());
+            migrator.run(pool).await?;
+        }
+    }
+    tracing::info!("Applied {} migration(s)", plan.pending.len());
+    Ok(())
+}
+
+/// Roll back the latest applied migration with its down script, backing the database up
+/// first. Returns the version rolled back, or `None` if no migration has been applied.
+pub async fn downgrade(pool: &DbPool) -> anyhow::Result<Option<i64>> {
+    let applied: Vec<i64> = applied_migrations(pool)
+        .await?
+        .into_iter()
+        .filter(|applied| applied.success)
+        .map(|applied| applied.version)
+        .collect();
+    let Some(&latest) = applied.last() else {
+        return Ok(None);
+    };
+    let reversible = migrator(pool).iter().any(|migration| {
+        migration.version == latest && migration.migration_type.is_down_migration()
+    });
+    if !reversible {
+        bail!(
+            "Migration {} has no down script; restore a backup from {} instead",
+            latest,
+            backup_dir().display()
+        );
+    }
+
+    backup(pool, &format!("before-down-{}", latest)).await?;
+    let previous = applied.iter().rev().nth(1).copied().unwrap_or(0);
+    match pool {
+        DbPool::Sqlite(pool) => MIGRATOR.undo(pool, previous).await?,
+        DbPool::Postgres(pool) => POSTGRES_MIGRATOR.undo(pool, previous).await?,
+    }
+    Ok(Some(latest))
+}
+
+fn backup_dir() -> PathBuf {
+    asset_dir().join("backups")
+}
+
+/// Copy a SQLite database to `backups/`, keeping the latest few copies. PostgreSQL databases
+/// are left to their server's own backups.
+async fn backup(pool: &DbPool, label: &str) -> anyhow::Result<()> {
+    let DbPool::Sqlite(pool) = pool else {
+        tracing::warn!("Not backing up the PostgreSQL database before migrating it");
+        return Ok(());
+    };
+    let dir = backup_dir();
+    tokio::fs::create_dir_all(&dir).await?;
+    let path = dir.join(format!(
+        "db-{}-{}.sqlite",
+        Utc::now().format("%Y%m%d%H%M%S"),
+        label
+    ));
+    // A consistent copy even with other connections writing, unlike copying the file
+    sqlx::query("VACUUM INTO $1")
+        .bind(path.to_string_lossy().to_string())
+        .execute(pool)
+        .await?;
+
+    let mut backups = Vec::new();
+    let mut entries = tokio::fs::read_dir(&dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with("db-") && name.ends_with(".sqlite") {
+            backups.push(entry.path());
+        }
+    }
+    // The names start with when they were taken
+    backups.sort();
+    for old in backups.iter().rev().skip(KEPT_BACKUPS) {
+        if let Err(e) = tokio::fs::remove_file(old).await {
+            tracing::warn!("Failed to remove old backup {}: {}", old.display(), e);
+        }
+    }
+    tracing::info!("Backed up the database to {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use sqlx::migrate::MigrationType;
+
+    use super::*;
+
+    fn migration(version: i64, sql: &'static str, migration_type: MigrationType) -> Migration {
+        Migration::new(
+            version,
+            Cow::Borrowed("add things"),
+            migration_type,
+            Cow::Borrowed(sql),
+            false,
+        )
+    }
+
+    fn applied(migration: &Migration, success: bool) -> AppliedMigration {
+        AppliedMigration {
+            version: migration.version,
+            success,
+            checksum: migration.checksum.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_plan_finds_pending_changed_and_newer_migrations() {
+        let shipped = [
+            migration(1, "CREATE TABLE a (id INTEGER)", MigrationType::Simple),
+            migration(
+                2,
+                "CREATE TABLE b (id INTEGER)",
+                MigrationType::ReversibleUp,
+            ),
+            migration(2, "DROP TABLE b", MigrationType::ReversibleDown),
+            migration(3, "CREATE TABLE c (id INTEGER)", MigrationType::Simple),
+            migration(4, "CREATE TABLE d (id INTEGER)", MigrationType::Simple),
+        ];
+        let edited = migration(1, "CREATE TABLE a (id TEXT)", MigrationType::Simple);
+        let applied = [
+            applied(&edited, true),
+            applied(&shipped[1], true),
+            applied(&shipped[3], false),
+            AppliedMigration {
+                version: 5,
+                success: true,
+                checksum: Vec::new(),
+            },
+        ];
+
+        let plan = plan_migrations(shipped.iter(), &applied);
+        let pending: Vec<i64> = plan.pending.iter().map(|m| m.version).collect();
+        assert_eq!(pending, vec![3, 4]);
+        assert_eq!(plan.modified, vec![1]);
+        assert_eq!(plan.newer, vec![5]);
+    }
+
+    #[test]
+    fn test_postgres_has_every_migration_after_its_baseline() {
+        let versions = |migrator: &Migrator, after: i64| -> Vec<i64> {
+            migrator
+                .iter()
+                .filter(|migration| !migration.migration_type.is_down_migration())
+                .map(|migration| migration.version)
+                .filter(|version| *version > after)
+                .collect()
+        };
+        let baseline = POSTGRES_MIGRATOR.iter().next().unwrap().version;
+        assert!(MIGRATOR
+            .iter()
+            .any(|migration| migration.version == baseline));
+        assert_eq!(
+            versions(&POSTGRES_MIGRATOR, baseline),
+            versions(&MIGRATOR, baseline)
+        );
+    }
+
+    /// Each table's columns and whether they may be null. The search index is left out, as
+    /// it's an FTS5 table on SQLite and a plain one with a `tsvector` on PostgreSQL.
+    type Schema = std::collections::BTreeSet<(String, String, bool)>;
+
+    async fn sqlite_schema(pool: &sqlx::SqlitePool) -> Schema {
+        sqlx::query_as(
+            r#"SELECT m.name, p.name, p."notnull" = 0 AND p.pk = 0
+               FROM sqlite_master m JOIN pragma_table_info(m.name) p
+               WHERE m.type = 'table'
+                 AND m.name NOT LIKE 'sqlite!_%' ESCAPE '!'
+                 AND m.name NOT LIKE '!_sqlx%' ESCAPE '!'
+                 AND m.name NOT LIKE 'search!_documents%' ESCAPE '!'"#,
+        )
+        .fetch_all(pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .collect()
+    }
+
+    async fn postgres_schema(pool: &sqlx::PgPool) -> Schema {
+        sqlx::query_as(
+            r#"SELECT table_name::text, column_name::text, is_nullable = 'YES'
+               FROM information_schema.columns
+               WHERE table_schema = 'public'
+                 AND table_name NOT LIKE '!_sqlx%' ESCAPE '!'
+                 AND table_name NOT LIKE 'search!_documents%' ESCAPE '!'"#,
+        )
+        .fetch_all(pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .collect()
+    }
+
+    /// PostgreSQL's migrations are kept by hand alongside SQLite's, so check they build the
+    /// same tables. Runs against the server `VIBE_KANBAN_TEST_POSTGRES_URL` points to, as
+    /// the scenario harness does, and passes without one.
+    #[tokio::test]
+    async fn test_postgres_schema_matches_sqlite() {
+        let Ok(url) = std::env::var("VIBE_KANBAN_TEST_POSTGRES_URL") else {
+            return;
+        };
+        let sqlite = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+        MIGRATOR.run(&sqlite).await.unwrap();
+
+        let server = sqlx::PgPool::connect(&url).await.unwrap();
+        let name = format!("vibe_kanban_schema_{}", uuid::Uuid::new_v4().simple());
+        sqlx::query(&format!("CREATE DATABASE {}", name))
+            .execute(&server)
+            .await
+            .unwrap();
+        let options = <sqlx::postgres::PgConnectOptions as std::str::FromStr>::from_str(&url)
+            .unwrap()
+            .database(&name);
+        let postgres = sqlx::PgPool::connect_with(options).await.unwrap();
+        POSTGRES_MIGRATOR.run(&postgres).await.unwrap();
+        let (sqlite_schema, postgres_schema) = (
+            sqlite_schema(&sqlite).await,
+            postgres_schema(&postgres).await,
+        );
+        postgres.close().await;
+        sqlx::query(&format!("DROP DATABASE {}", name))
+            .execute(&server)
+            .await
+            .unwrap();
+
+        assert!(!sqlite_schema.is_empty());
+        let only_sqlite: Vec<_> = sqlite_schema.difference(&postgres_schema).collect();
+        let only_postgres: Vec<_> = postgres_schema.difference(&sqlite_schema).collect();
+        assert!(
+            only_sqlite.is_empty() && only_postgres.is_empty(),
+            "columns (table, column, nullable) only on SQLite: {:?}; only on PostgreSQL: {:?}",
+            only_sqlite,
+            only_postgres
+        );
+    }
+
+    #[test]
+    fn test_summary_lists_what_would_be_applied() {
+        let plan = MigrationPlan {
+            pending: vec![PendingMigration {
+                version: 20251001000000,
+                description: "add execution log chunk received at".to_string(),
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            plan.summary(),
+            "1 migration(s) to apply:\n  20251001000000 add execution log chunk received at"
+        );
+        assert_eq!(
+            MigrationPlan::default().summary(),
+            "The database is up to date"
+        );
+    }
+}
@@ -1,13 +1,91 @@
+pub mod accounts;
+pub mod acp_session;
 pub mod analytics;
+pub mod attention;
+pub mod changelog;
+pub mod command_approval;
+pub mod commit_message;
+pub mod conversation_export;
+pub mod conversation_index;
+pub mod conversation_transcript;
+pub mod dev_server_proxy;
+pub mod diagnostics;
+pub mod embeddings;
+pub mod entry_bookmarks;
+pub mod entry_limits;
+pub mod environment_variables;
+pub mod execution_queue;
+pub mod execution_recovery;
+pub mod executor_preflight;
+pub mod failure_reasons;
+pub mod file_locations;
+pub mod file_mentions;
+pub mod git_host;
 pub mod git_service;
+pub mod github_issues;
+pub mod github_projects;
 pub mod github_service;
+pub mod guardrails;
+pub mod label_suggestions;
+pub mod llm;
+pub mod log_redaction;
+pub mod log_storage;
+pub mod login_throttle;
+pub mod mentions;
+pub mod merge_queue;
+pub mod migrations;
+pub mod network_recovery;
 pub mod notification_service;
+pub mod notification_webhooks;
+pub mod notion_sync;
+pub mod oidc;
+pub mod plan_review;
+pub mod plugins;
+pub mod pr_description;
 pub mod pr_monitor;
 pub mod process_service;
+pub mod project_archive;
+pub mod project_invites;
+pub mod prompt_context;
+pub mod prompt_template;
+pub mod rate_limits;
+pub mod related_tasks;
+pub mod releases;
+pub mod repo_analysis;
+pub mod repo_config;
+pub mod run_estimate;
+pub mod run_summary;
+pub mod sandbox;
+pub mod scoped_follow_up;
+pub mod script_hooks;
+pub mod search;
+pub mod spawn_retry;
+pub mod stale_tasks;
+pub mod task_decomposition;
+pub mod task_scheduler;
+pub mod task_sharing;
+pub mod task_summary;
+pub mod tracker_import;
+pub mod trello_import;
+pub mod two_factor;
+pub mod verification;
+pub mod webhook_signing;
+pub mod worktree_usage;
 
 pub use analytics::{generate_user_id, AnalyticsConfig, AnalyticsService};
+pub use attention::AttentionEscalationService;
 pub use git_service::{GitService, GitServiceError};
+pub use github_issues::GitHubIssueSyncService;
+pub use github_projects::GitHubProjectSyncService;
 pub use github_service::{CreatePrRequest, GitHubRepoInfo, GitHubService, GitHubServiceError};
-pub use notification_service::{NotificationConfig, NotificationService};
+pub use label_suggestions::LabelSuggester;
+pub use notification_service::{NotificationConfig, NotificationEvent, NotificationService};
+pub use notification_webhooks::NotificationDeliveryService;
+pub use notion_sync::NotionSyncService;
 pub use pr_monitor::PrMonitorService;
 pub use process_service::ProcessService;
+pub use script_hooks::ScriptHookService;
+pub use stale_tasks::StaleTaskService;
+pub use task_scheduler::TaskSchedulerService;
+pub use trello_import::{TrelloBoard, TrelloImportRequest, TrelloImportResult};
+pub use webhook_signing::{ReplayGuard, WebhookSignatureError, WebhookSigner};
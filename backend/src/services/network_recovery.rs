@@ -0,0 +1,102 @@
+//! Detecting coding agent runs that died because the network dropped, and checking when
+//! connectivity is back so they can be resumed
+
+use std::time::Duration;
+
+use futures_util::future::select_ok;
+use tokio::net::TcpStream;
+
+/// How many times one attempt is resumed after network failures before it is failed outright
+pub const MAX_NETWORK_RESUMES: usize = 3;
+
+/// Sent as the follow-up when a session is resumed
+pub const RESUME_PROMPT: &str = "The network connection dropped while you were working and has now been restored. Continue the task from where you left off.";
+
+/// Endpoints tried when checking connectivity; any one answering is enough
+const PROBE_ADDRS: &[&str] = &["api.anthropic.com:443", "api.openai.com:443", "1.1.1.1:443"];
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Only the end of the output is inspected, so errors the agent saw from commands it ran
+/// earlier in the session don't count
+const OUTPUT_TAIL_BYTES: usize = 4096;
+const MAX_ERROR_LEN: usize = 300;
+
+/// Lowercase fragments of the errors agent CLIs print when their API connection fails
+const NETWORK_ERROR_PATTERNS: &[&str] = &[
+    "api error: connection error",
+    "api error: request timed out",
+    "econnreset",
+    "econnrefused",
+    "enotfound",
+    "etimedout",
+    "eai_again",
+    "enetunreach",
+    "getaddrinfo",
+    "socket hang up",
+    "fetch failed",
+    "network is unreachable",
+    "could not resolve host",
+    "temporary failure in name resolution",
+    "failed to lookup address information",
+    "connection reset by peer",
+    "stream disconnected before completion",
+];
+
+/// The end of `output`, where what ended a run shows
+pub(crate) fn tail(output: &str) -> &str {
+    let mut start = output.len().saturating_sub(OUTPUT_TAIL_BYTES);
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+    &output[start..]
+}
+
+/// If a failed run's output ends in a network error, return the offending line
+pub fn detect_network_failure(stdout: Option<&str>, stderr: Option<&str>) -> Option<String> {
+    [stderr, stdout]
+        .into_iter()
+        .flatten()
+        .flat_map(|output| tail(output).lines().rev())
+        .find(|line| {
+            let line = line.to_lowercase();
+            NETWORK_ERROR_PATTERNS.iter().any(|p| line.contains(p))
+        })
+        .map(|line| line.trim().chars().take(MAX_ERROR_LEN).collect())
+}
+
+/// Whether any probe endpoint accepts a TCP connection. DNS failures count as offline.
+pub async fn connectivity_available() -> bool {
+    let probes = PROBE_ADDRS
+        .iter()
+        .map(|addr| Box::pin(TcpStream::connect(*addr)));
+    matches!(
+        tokio::time::timeout(PROBE_TIMEOUT, select_ok(probes)).await,
+        Ok(Ok(_))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_network_errors_at_end_of_output() {
+        let stdout = r#"{"type":"result","is_error":true,"result":"API Error: Connection error."}"#;
+        assert_eq!(
+            detect_network_failure(Some(stdout), None).as_deref(),
+            Some(stdout)
+        );
+        assert!(detect_network_failure(
+            None,
+            Some("Error: getaddrinfo ENOTFOUND api.anthropic.com")
+        )
+        .is_some());
+
+        // A network error early in a long session is not what ended it
+        let long = format!(
+            "curl: (6) Could not resolve host: example.com\n{}",
+            "ok\n".repeat(3000)
+        );
+        assert!(detect_network_failure(Some(&long), Some("error: tests failed")).is_none());
+    }
+}
@@ -1,6 +1,10 @@
 use std::sync::OnceLock;
 
-use crate::models::config::SoundFile;
+use serde::{Deserialize, Serialize};
+use sqlx::Type;
+use ts_rs::TS;
+
+use crate::models::config::{NotificationEvents, SoundFile};
 
 /// Service for handling cross-platform notifications including sound alerts and push notifications
 #[derive(Debug, Clone)]
@@ -25,6 +29,39 @@ impl Default for NotificationConfig {
     }
 }
 
+/// Attempt outcomes and board events that can raise a notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum NotificationEvent {
+    AttemptSucceeded,
+    AttemptFailed,
+    ApprovalNeeded,
+    TaskStale,
+}
+
+impl NotificationEvent {
+    /// Classify a finished coding agent run. A successful planning run that ended with a plan
+    /// is reported as waiting for approval rather than done.
+    pub fn for_completion(success: bool, awaiting_approval: bool) -> Self {
+        match (success, awaiting_approval) {
+            (false, _) => Self::AttemptFailed,
+            (true, true) => Self::ApprovalNeeded,
+            (true, false) => Self::AttemptSucceeded,
+        }
+    }
+
+    pub fn is_enabled(&self, events: &NotificationEvents) -> bool {
+        match self {
+            Self::AttemptSucceeded => events.attempt_succeeded,
+            Self::AttemptFailed => events.attempt_failed,
+            Self::ApprovalNeeded => events.approval_needed,
+            Self::TaskStale => events.task_stale,
+        }
+    }
+}
+
 /// Cache for WSL root path from PowerShell
 static WSL_ROOT_PATH_CACHE: OnceLock<Option<String>> = OnceLock::new();
 
@@ -261,3 +298,32 @@ impl NotificationService {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completion_events_respect_toggles() {
+        let events = NotificationEvents {
+            attempt_succeeded: false,
+            attempt_failed: true,
+            approval_needed: true,
+            task_stale: false,
+        };
+
+        let plan_done = NotificationEvent::for_completion(true, true);
+        assert_eq!(plan_done, NotificationEvent::ApprovalNeeded);
+        assert!(plan_done.is_enabled(&events));
+
+        let done = NotificationEvent::for_completion(true, false);
+        assert_eq!(done, NotificationEvent::AttemptSucceeded);
+        assert!(!done.is_enabled(&events));
+
+        assert_eq!(
+            NotificationEvent::for_completion(false, true),
+            NotificationEvent::AttemptFailed
+        );
+        assert!(!NotificationEvent::TaskStale.is_enabled(&events));
+    }
+}
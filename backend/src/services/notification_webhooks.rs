@@ -0,0 +1,246 @@
+//! Outbound notification webhooks. When an attempt finishes, fails or waits for approval, a
+//! delivery is queued for each of the project's webhooks that wants to hear about it, and a
+//! background service posts them as Slack or Discord messages, signed with the webhook secret
+//! when one is configured. Failed posts are retried with exponential backoff, and every
+//! delivery is kept as the project's notification log.
+
+use std::{
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use tokio::{sync::RwLock, time::interval};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    models::{
+        config::Config,
+        notification_delivery::NotificationDelivery,
+        notification_webhook::{NotificationWebhook, WebhookFormat},
+    },
+    services::{
+        webhook_signing::{DELIVERY_HEADER, SIGNATURE_HEADER},
+        NotificationEvent, WebhookSigner,
+    },
+};
+
+/// Attempts made at a delivery before it is marked as failed
+pub const MAX_DELIVERY_ATTEMPTS: i64 = 5;
+
+/// The wait before the first retry, doubled for each one after
+const BASE_RETRY_DELAY_SECS: i64 = 30;
+
+/// Discord rejects messages longer than this
+const DISCORD_MAX_CONTENT_CHARS: usize = 2000;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default()
+    })
+}
+
+/// The body posted to a webhook in `format`
+pub fn payload(format: WebhookFormat, title: &str, message: &str) -> serde_json::Value {
+    match format {
+        WebhookFormat::Slack => serde_json::json!({
+            "text": format!("*{}*\n{}", title, message),
+        }),
+        WebhookFormat::Discord => {
+            let content: String = format!("**{}**\n{}", title, message)
+                .chars()
+                .take(DISCORD_MAX_CONTENT_CHARS)
+                .collect();
+            serde_json::json!({ "content": content })
+        }
+    }
+}
+
+/// How long to wait before retrying a delivery that has failed `attempts` times, or `None`
+/// once it has had all its attempts
+pub fn retry_delay(attempts: i64) -> Option<chrono::Duration> {
+    if attempts >= MAX_DELIVERY_ATTEMPTS {
+        return None;
+    }
+    let exponent = (attempts - 1).clamp(0, 10) as u32;
+    Some(chrono::Duration::seconds(
+        BASE_RETRY_DELAY_SECS * 2i64.pow(exponent),
+    ))
+}
+
+/// Queue a delivery of the notification to each of the project's webhooks subscribed to
+/// `event`
+pub async fn queue(
+    pool: &DbPool,
+    project_id: Uuid,
+    task_attempt_id: Option<Uuid>,
+    event: NotificationEvent,
+    title: &str,
+    message: &str,
+) -> Result<Vec<NotificationDelivery>, sqlx::Error> {
+    let mut queued = Vec::new();
+    for webhook in NotificationWebhook::find_by_project_id(pool, project_id).await? {
+        if !webhook.is_subscribed(event) {
+            continue;
+        }
+        queued.push(
+            NotificationDelivery::queue_webhook(
+                pool,
+                project_id,
+                task_attempt_id,
+                webhook.id,
+                event,
+                title,
+                message,
+            )
+            .await?,
+        );
+    }
+    Ok(queued)
+}
+
+/// Post a delivery, signed with the newest webhook secret when one is set so receivers can
+/// check it came from us. Retries keep the delivery id, letting receivers drop repeats.
+async fn post(
+    webhook: &NotificationWebhook,
+    delivery: &NotificationDelivery,
+    signer: &WebhookSigner,
+    now: DateTime<Utc>,
+) -> Result<(), String> {
+    let body = serde_json::to_vec(&payload(webhook.format, &delivery.title, &delivery.message))
+        .map_err(|e| e.to_string())?;
+    let mut request = client()
+        .post(&webhook.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(DELIVERY_HEADER, delivery.id.to_string());
+    if signer.has_secrets() {
+        let signature = signer
+            .sign_timestamped(&body, now.timestamp())
+            .map_err(|e| e.to_string())?;
+        let github_signature = signer.sign_github(&body).map_err(|e| e.to_string())?;
+        request = request
+            .header(SIGNATURE_HEADER, signature)
+            .header("x-hub-signature-256", github_signature);
+    }
+    let response = request.body(body).send().await.map_err(|e| e.to_string())?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("The webhook responded with {}", response.status()))
+    }
+}
+
+pub struct NotificationDeliveryService {
+    pool: DbPool,
+    poll_interval: Duration,
+}
+
+impl NotificationDeliveryService {
+    pub fn new(pool: DbPool) -> Self {
+        Self {
+            pool,
+            poll_interval: Duration::from_secs(5),
+        }
+    }
+
+    pub async fn start_with_config(&self, config: Arc<RwLock<Config>>) {
+        info!(
+            "Starting notification delivery with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+        loop {
+            interval.tick().await;
+            let signer = config.read().await.webhooks.signer();
+            if let Err(e) = self.deliver_due(&signer, Utc::now()).await {
+                error!("Error delivering notifications: {}", e);
+            }
+        }
+    }
+
+    /// Post every pending delivery due at `now`, returning how many went through
+    pub async fn deliver_due(
+        &self,
+        signer: &WebhookSigner,
+        now: DateTime<Utc>,
+    ) -> Result<usize, sqlx::Error> {
+        let mut sent = 0;
+        for delivery in NotificationDelivery::find_due(&self.pool, now).await? {
+            let webhook = match delivery.webhook_id {
+                Some(webhook_id) => NotificationWebhook::find_by_id(&self.pool, webhook_id).await?,
+                None => None,
+            };
+            let Some(webhook) = webhook else {
+                NotificationDelivery::record_failure(
+                    &self.pool,
+                    delivery.id,
+                    "The webhook was removed",
+                    None,
+                )
+                .await?;
+                continue;
+            };
+
+            match post(&webhook, &delivery, signer, now).await {
+                Ok(()) => {
+                    NotificationDelivery::mark_sent(&self.pool, delivery.id).await?;
+                    sent += 1;
+                }
+                Err(e) => {
+                    let retry_at = retry_delay(delivery.attempts + 1).map(|delay| now + delay);
+                    warn!(
+                        "Failed to deliver notification {} to webhook {} (attempt {}): {}",
+                        delivery.id,
+                        webhook.id,
+                        delivery.attempts + 1,
+                        e
+                    );
+                    NotificationDelivery::record_failure(&self.pool, delivery.id, &e, retry_at)
+                        .await?;
+                }
+            }
+        }
+        Ok(sent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_matches_each_format() {
+        assert_eq!(
+            payload(WebhookFormat::Slack, "Task Failed: Fix", "Branch: vk-1"),
+            serde_json::json!({ "text": "*Task Failed: Fix*\nBranch: vk-1" })
+        );
+        assert_eq!(
+            payload(WebhookFormat::Discord, "Task Failed: Fix", "Branch: vk-1"),
+            serde_json::json!({ "content": "**Task Failed: Fix**\nBranch: vk-1" })
+        );
+
+        let long = "x".repeat(3000);
+        let discord = payload(WebhookFormat::Discord, "Title", &long);
+        assert_eq!(
+            discord["content"].as_str().unwrap().chars().count(),
+            DISCORD_MAX_CONTENT_CHARS
+        );
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_then_gives_up() {
+        assert_eq!(retry_delay(1), Some(chrono::Duration::seconds(30)));
+        assert_eq!(retry_delay(2), Some(chrono::Duration::seconds(60)));
+        assert_eq!(retry_delay(4), Some(chrono::Duration::seconds(240)));
+        assert_eq!(retry_delay(MAX_DELIVERY_ATTEMPTS), None);
+    }
+}
@@ -0,0 +1,659 @@
+//! One-way sync of a project's tasks into a Notion database: each task becomes a page whose
+//! title, status and (optionally) description properties follow the task. Edits made in
+//! Notion are overwritten on the next sync.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use reqwest::{Method, StatusCode};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use tokio::{
+    sync::{Mutex, RwLock},
+    time::interval,
+};
+use tracing::{debug, error, info, warn};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    models::{
+        config::Config,
+        notion_sync::{NotionLink, NotionPage, NotionPropertyMapping},
+        task::{Task, TaskStatus},
+    },
+};
+
+const NOTION_API_BASE: &str = "https://api.notion.com/v1";
+const NOTION_VERSION: &str = "2022-06-28";
+/// Notion caps each rich text object at 2000 characters and a property at 100 of them
+const RICH_TEXT_CHUNK: usize = 2000;
+const RICH_TEXT_MAX_CHUNKS: usize = 100;
+const MAX_RATE_LIMIT_RETRIES: usize = 3;
+
+#[derive(Debug)]
+pub enum NotionError {
+    Request(reqwest::Error),
+    Api {
+        status: StatusCode,
+        code: String,
+        message: String,
+    },
+    Mapping(String),
+    Database(sqlx::Error),
+}
+
+impl std::fmt::Display for NotionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotionError::Request(e) => write!(f, "Failed to reach Notion: {}", e),
+            NotionError::Api {
+                status: StatusCode::UNAUTHORIZED,
+                ..
+            } => write!(f, "Notion rejected the integration token"),
+            NotionError::Api {
+                status: StatusCode::NOT_FOUND,
+                ..
+            } => write!(
+                f,
+                "Notion database not found. Make sure it is shared with the integration."
+            ),
+            NotionError::Api { message, .. } => write!(f, "Notion API error: {}", message),
+            NotionError::Mapping(e) => write!(f, "{}", e),
+            NotionError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for NotionError {}
+
+impl NotionError {
+    /// The page was deleted or archived in Notion, so it should be created again
+    fn is_missing_page(&self) -> bool {
+        match self {
+            NotionError::Api { code, message, .. } => {
+                code == "object_not_found"
+                    || (code == "validation_error" && message.contains("archived"))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Accept a database URL, as copied from Notion, or a bare id with or without dashes
+pub fn parse_database_id(input: &str) -> Option<String> {
+    let path = input.trim().split(['?', '#']).next()?;
+    let last = path.trim_end_matches('/').rsplit('/').next()?;
+    let compact: String = last.chars().filter(|c| *c != '-').collect();
+    let id = compact.get(compact.len().checked_sub(32)?..)?;
+    if !id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some(format!(
+        "{}-{}-{}-{}-{}",
+        &id[0..8],
+        &id[8..12],
+        &id[12..16],
+        &id[16..20],
+        &id[20..32]
+    ))
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct NotionProperty {
+    pub name: String,
+    /// Notion property type, e.g. "title", "status", "select" or "rich_text"
+    pub kind: String,
+    /// Option names of status and select properties
+    pub options: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct NotionDatabase {
+    pub id: String,
+    pub title: String,
+    pub properties: Vec<NotionProperty>,
+}
+
+impl NotionDatabase {
+    fn from_json(value: &Value) -> Option<Self> {
+        let title = value["title"]
+            .as_array()
+            .map(|parts| {
+                parts
+                    .iter()
+                    .filter_map(|p| p["plain_text"].as_str())
+                    .collect::<String>()
+            })
+            .unwrap_or_default();
+        let mut properties: Vec<NotionProperty> = value["properties"]
+            .as_object()?
+            .iter()
+            .map(|(name, property)| {
+                let kind = property["type"].as_str().unwrap_or_default().to_string();
+                let options = property[kind.as_str()]["options"]
+                    .as_array()
+                    .map(|options| {
+                        options
+                            .iter()
+                            .filter_map(|o| o["name"].as_str().map(str::to_string))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                NotionProperty {
+                    name: name.clone(),
+                    kind,
+                    options,
+                }
+            })
+            .collect();
+        properties.sort_by(|a, b| a.name.cmp(&b.name));
+        Some(Self {
+            id: value["id"].as_str()?.to_string(),
+            title,
+            properties,
+        })
+    }
+
+    fn property(&self, name: &str) -> Option<&NotionProperty> {
+        self.properties.iter().find(|p| p.name == name)
+    }
+
+    fn title_property(&self, mapping: &NotionPropertyMapping) -> Result<&str, NotionError> {
+        let property = match &mapping.title {
+            Some(name) => self.property(name),
+            None => self.properties.iter().find(|p| p.kind == "title"),
+        };
+        match property {
+            Some(p) if p.kind == "title" => Ok(&p.name),
+            Some(p) => Err(NotionError::Mapping(format!(
+                "Property \"{}\" is not the database's title property",
+                p.name
+            ))),
+            None => Err(NotionError::Mapping(
+                "The database has no title property".to_string(),
+            )),
+        }
+    }
+
+    fn status_property(
+        &self,
+        mapping: &NotionPropertyMapping,
+    ) -> Result<Option<&NotionProperty>, NotionError> {
+        let property = match &mapping.status {
+            Some(name) => Some(self.property(name).ok_or_else(|| {
+                NotionError::Mapping(format!("The database has no property \"{}\"", name))
+            })?),
+            // An unmapped status is only synced if there's an obvious column for it
+            None => self
+                .property("Status")
+                .filter(|p| p.kind == "status" || p.kind == "select"),
+        };
+        match property {
+            Some(p) if p.kind != "status" && p.kind != "select" => {
+                Err(NotionError::Mapping(format!(
+                    "Property \"{}\" must be a status or select property",
+                    p.name
+                )))
+            }
+            p => Ok(p),
+        }
+    }
+
+    fn description_property(
+        &self,
+        mapping: &NotionPropertyMapping,
+    ) -> Result<Option<&str>, NotionError> {
+        let Some(name) = &mapping.description else {
+            return Ok(None);
+        };
+        match self.property(name) {
+            Some(p) if p.kind == "rich_text" => Ok(Some(&p.name)),
+            Some(_) => Err(NotionError::Mapping(format!(
+                "Property \"{}\" must be a text property",
+                name
+            ))),
+            None => Err(NotionError::Mapping(format!(
+                "The database has no property \"{}\"",
+                name
+            ))),
+        }
+    }
+
+    /// Check the mapping against the schema before saving it
+    pub fn validate(&self, mapping: &NotionPropertyMapping) -> Result<(), NotionError> {
+        self.title_property(mapping)?;
+        self.status_property(mapping)?;
+        self.description_property(mapping)?;
+        Ok(())
+    }
+
+    /// The page properties for a task under this mapping
+    pub fn page_properties(
+        &self,
+        mapping: &NotionPropertyMapping,
+        title: &str,
+        description: Option<&str>,
+        status: &TaskStatus,
+    ) -> Result<Map<String, Value>, NotionError> {
+        let mut properties = Map::new();
+        properties.insert(
+            self.title_property(mapping)?.to_string(),
+            json!({ "title": rich_text(title) }),
+        );
+
+        if let Some(property) = self.status_property(mapping)? {
+            let options: Vec<&str> = property.options.iter().map(String::as_str).collect();
+            let name = match mapping.status_names.resolve(status, &options) {
+                Some(name) => Some(name.to_string()),
+                // Select properties gain new options when written to; status properties don't
+                None if property.kind == "select" => Some(
+                    mapping
+                        .status_names
+                        .get(status)
+                        .unwrap_or(column_name(status))
+                        .to_string(),
+                ),
+                None => None,
+            };
+            let value = match name {
+                Some(name) => json!({ "name": name }),
+                None => Value::Null,
+            };
+            properties.insert(
+                property.name.clone(),
+                json!({ property.kind.as_str(): value }),
+            );
+        }
+
+        if let Some(name) = self.description_property(mapping)? {
+            properties.insert(
+                name.to_string(),
+                json!({ "rich_text": rich_text(description.unwrap_or("")) }),
+            );
+        }
+        Ok(properties)
+    }
+}
+
+fn column_name(status: &TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Todo => "To Do",
+        TaskStatus::Queued => "Queued",
+        TaskStatus::InProgress => "In Progress",
+        TaskStatus::PlanReview => "Plan Review",
+        TaskStatus::InReview => "In Review",
+        TaskStatus::Done => "Done",
+        TaskStatus::Cancelled => "Cancelled",
+    }
+}
+
+fn rich_text(text: &str) -> Value {
+    let chars: Vec<char> = text.chars().collect();
+    Value::Array(
+        chars
+            .chunks(RICH_TEXT_CHUNK)
+            .take(RICH_TEXT_MAX_CHUNKS)
+            .map(|chunk| json!({ "text": { "content": chunk.iter().collect::<String>() } }))
+            .collect(),
+    )
+}
+
+pub struct NotionClient {
+    client: reqwest::Client,
+    token: String,
+}
+
+impl NotionClient {
+    pub fn new(token: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            token: token.to_string(),
+        }
+    }
+
+    async fn request(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> Result<Value, NotionError> {
+        let mut retries = 0;
+        loop {
+            let mut request = self
+                .client
+                .request(method.clone(), format!("{}{}", NOTION_API_BASE, path))
+                .bearer_auth(&self.token)
+                .header("Notion-Version", NOTION_VERSION);
+            if let Some(body) = &body {
+                request = request.json(body);
+            }
+            let response = request.send().await.map_err(NotionError::Request)?;
+            let status = response.status();
+
+            if status == StatusCode::TOO_MANY_REQUESTS && retries < MAX_RATE_LIMIT_RETRIES {
+                let wait = response
+                    .headers()
+                    .get("retry-after")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(1);
+                debug!("Rate limited by Notion, retrying in {}s", wait);
+                tokio::time::sleep(Duration::from_secs(wait)).await;
+                retries += 1;
+                continue;
+            }
+
+            let value: Value = response.json().await.map_err(NotionError::Request)?;
+            if !status.is_success() {
+                return Err(NotionError::Api {
+                    status,
+                    code: value["code"].as_str().unwrap_or_default().to_string(),
+                    message: value["message"].as_str().unwrap_or_default().to_string(),
+                });
+            }
+            return Ok(value);
+        }
+    }
+
+    pub async fn get_database(&self, database_id: &str) -> Result<NotionDatabase, NotionError> {
+        let value = self
+            .request(Method::GET, &format!("/databases/{}", database_id), None)
+            .await?;
+        NotionDatabase::from_json(&value)
+            .ok_or_else(|| NotionError::Mapping("Unexpected database response".to_string()))
+    }
+
+    async fn create_page(
+        &self,
+        database_id: &str,
+        properties: Map<String, Value>,
+    ) -> Result<String, NotionError> {
+        let value = self
+            .request(
+                Method::POST,
+                "/pages",
+                Some(json!({ "parent": { "database_id": database_id }, "properties": properties })),
+            )
+            .await?;
+        value["id"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| NotionError::Mapping("Notion did not return the new page".to_string()))
+    }
+
+    async fn update_page(
+        &self,
+        page_id: &str,
+        properties: Map<String, Value>,
+    ) -> Result<(), NotionError> {
+        self.request(
+            Method::PATCH,
+            &format!("/pages/{}", page_id),
+            Some(json!({ "properties": properties })),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn archive_page(&self, page_id: &str) -> Result<(), NotionError> {
+        self.request(
+            Method::PATCH,
+            &format!("/pages/{}", page_id),
+            Some(json!({ "archived": true })),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Serialize, TS)]
+#[ts(export)]
+pub struct NotionSyncResult {
+    pub added: usize,
+    pub updated: usize,
+    pub archived: usize,
+}
+
+/// Held for the whole of a sync so a manual sync and the background one can't both create a
+/// page for the same new task
+static SYNC_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Bring the database in line with the project's tasks: add pages for new tasks, update
+/// changed ones and archive pages whose task was deleted
+pub async fn sync_project(
+    pool: &DbPool,
+    client: &NotionClient,
+    link: &NotionLink,
+) -> Result<NotionSyncResult, NotionError> {
+    let _guard = SYNC_LOCK.lock().await;
+
+    let database = client.get_database(&link.database_id).await?;
+    let mapping = &link.property_mapping;
+    let tasks = Task::find_by_project_id_with_attempt_status(pool, link.project_id)
+        .await
+        .map_err(NotionError::Database)?;
+    let mut pages: HashMap<Uuid, NotionPage> =
+        NotionPage::find_by_project_id(pool, link.project_id)
+            .await
+            .map_err(NotionError::Database)?
+            .into_iter()
+            .map(|page| (page.task_id, page))
+            .collect();
+
+    let mut result = NotionSyncResult::default();
+    for task in &tasks {
+        let properties = database.page_properties(
+            mapping,
+            &task.title,
+            task.description.as_deref(),
+            &task.status,
+        )?;
+
+        let page_id = match pages.remove(&task.id) {
+            Some(page) => {
+                // A mapping saved after the last write may put fields in other properties
+                let unchanged = page.synced_at >= link.updated_at
+                    && page.synced_title == task.title
+                    && page.synced_description == task.description
+                    && page.synced_status == task.status;
+                if unchanged {
+                    continue;
+                }
+                match client.update_page(&page.page_id, properties.clone()).await {
+                    Ok(()) => {
+                        result.updated += 1;
+                        page.page_id
+                    }
+                    Err(e) if e.is_missing_page() => {
+                        debug!("Page for task {} is gone from Notion, re-creating", task.id);
+                        result.added += 1;
+                        client.create_page(&database.id, properties).await?
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            None => {
+                result.added += 1;
+                client.create_page(&database.id, properties).await?
+            }
+        };
+
+        NotionPage::upsert(
+            pool,
+            task.id,
+            link.project_id,
+            &page_id,
+            &task.title,
+            task.description.as_deref(),
+            &task.status,
+        )
+        .await
+        .map_err(NotionError::Database)?;
+    }
+
+    // Whatever is left belongs to deleted tasks
+    for page in pages.into_values() {
+        match client.archive_page(&page.page_id).await {
+            Ok(()) => result.archived += 1,
+            Err(e) if e.is_missing_page() => {}
+            Err(e) => return Err(e),
+        }
+        NotionPage::delete(pool, page.task_id)
+            .await
+            .map_err(NotionError::Database)?;
+    }
+
+    Ok(result)
+}
+
+/// Background service that keeps every linked database up to date
+pub struct NotionSyncService {
+    pool: DbPool,
+    poll_interval: Duration,
+}
+
+impl NotionSyncService {
+    pub fn new(pool: DbPool) -> Self {
+        Self {
+            pool,
+            poll_interval: Duration::from_secs(30),
+        }
+    }
+
+    pub async fn start_with_config(&self, config: Arc<RwLock<Config>>) {
+        info!(
+            "Starting Notion sync with interval {:?}",
+            self.poll_interval
+        );
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+
+            let links = match NotionLink::find_all(&self.pool).await {
+                Ok(links) => links,
+                Err(e) => {
+                    error!("Failed to load Notion links: {}", e);
+                    continue;
+                }
+            };
+            if links.is_empty() {
+                continue;
+            }
+            let Some(token) = config.read().await.notion.token.clone() else {
+                debug!("No Notion token configured, skipping Notion sync");
+                continue;
+            };
+            let client = NotionClient::new(&token);
+
+            for link in links {
+                let outcome = sync_project(&self.pool, &client, &link).await;
+                if let Err(e) = &outcome {
+                    warn!("Notion sync for project {} failed: {}", link.project_id, e);
+                }
+                let error = outcome.err().map(|e| e.to_string());
+                if let Err(e) =
+                    NotionLink::record_sync(&self.pool, link.project_id, error.as_deref()).await
+                {
+                    error!("Failed to record Notion sync: {}", e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::task::TaskStatusNames;
+
+    #[test]
+    fn test_parse_database_id() {
+        let expected = Some("0123abcd-0123-4567-89ab-cdef01234567".to_string());
+        assert_eq!(
+            parse_database_id(
+                "https://www.notion.so/acme/Roadmap-0123abcd0123456789abcdef01234567?v=9f"
+            ),
+            expected
+        );
+        assert_eq!(
+            parse_database_id("0123abcd-0123-4567-89ab-cdef01234567"),
+            expected
+        );
+        assert_eq!(
+            parse_database_id("https://www.notion.so/acme/Roadmap"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_page_properties_follow_mapping() {
+        let database = NotionDatabase::from_json(&json!({
+            "id": "db",
+            "title": [{"plain_text": "Roadmap"}],
+            "properties": {
+                "Name": {"id": "title", "type": "title", "title": {}},
+                "Status": {"id": "s", "type": "status", "status": {"options": [
+                    {"name": "Not started"}, {"name": "In progress"}, {"name": "Done"}
+                ]}},
+                "Stage": {"id": "g", "type": "select", "select": {"options": []}},
+                "Notes": {"id": "n", "type": "rich_text", "rich_text": {}}
+            }
+        }))
+        .unwrap();
+
+        // Defaults: title column and the Status property, matched by name
+        let properties = database
+            .page_properties(
+                &NotionPropertyMapping::default(),
+                "Fix login",
+                Some("details"),
+                &TaskStatus::Todo,
+            )
+            .unwrap();
+        assert_eq!(
+            properties["Name"]["title"][0]["text"]["content"],
+            "Fix login"
+        );
+        assert_eq!(properties["Status"]["status"]["name"], "Not started");
+        assert!(!properties.contains_key("Notes"));
+        // No status option fits In Review, so the property is cleared
+        let properties = database
+            .page_properties(
+                &NotionPropertyMapping::default(),
+                "t",
+                None,
+                &TaskStatus::InReview,
+            )
+            .unwrap();
+        assert!(properties["Status"]["status"].is_null());
+
+        // A select property gets new options written by name
+        let mapping = NotionPropertyMapping {
+            status: Some("Stage".to_string()),
+            description: Some("Notes".to_string()),
+            status_names: TaskStatusNames {
+                done: Some("Shipped".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let properties = database
+            .page_properties(&mapping, "t", Some("details"), &TaskStatus::Done)
+            .unwrap();
+        assert_eq!(properties["Stage"]["select"]["name"], "Shipped");
+        assert_eq!(
+            properties["Notes"]["rich_text"][0]["text"]["content"],
+            "details"
+        );
+
+        let bad = NotionPropertyMapping {
+            description: Some("Status".to_string()),
+            ..Default::default()
+        };
+        assert!(database.validate(&bad).is_err());
+    }
+}
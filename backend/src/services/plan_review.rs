@@ -0,0 +1,170 @@
+//! Plan Review: a planning attempt stops at its plan, the task waits in Plan Review, and on
+//! approval the same attempt resumes its session in the executor's implementation mode. The
+//! approved plan is kept so the files it named can be checked against the files the
+//! implementation actually changed. Executors without a plan mode of their own plan when a
+//! plan-only attempt asks for one in their prompt, and their final reply is taken as the plan.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::{
+    db::DbPool,
+    executor::{ActionType, ExecutorConfig, NormalizedConversation, NormalizedEntryType},
+    models::{
+        attempt_plan::AttemptPlan,
+        execution_process::ExecutionProcess,
+        task_attempt::{FileChangeKind, TaskAttempt},
+    },
+    utils::text::file_references,
+};
+
+/// Sent as the follow-up that starts implementation once the plan is approved
+pub const IMPLEMENTATION_PROMPT: &str =
+    "The plan has been approved. Implement it now, following the plan as written. If something \
+     in it turns out not to work, say what you changed and why.";
+
+/// Appended to the prompt of a plan-only run whose executor has no plan mode
+pub const PLANNING_PROMPT: &str =
+    "Do not change any files yet. Investigate what the task needs and reply with a \
+     step-by-step plan for implementing it, naming the files you would change. End with the \
+     plan itself as your final message: it will be reviewed, and you will be asked to \
+     implement it once it is approved.";
+
+/// How the files a plan named line up with the files its implementation changed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, TS)]
+#[ts(export)]
+pub struct PlanComparison {
+    pub planned_and_changed: Vec<String>,
+    /// Named in the plan but left untouched
+    pub planned_not_changed: Vec<String>,
+    /// Changed although the plan never mentioned them
+    pub changed_not_planned: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct PlanReview {
+    pub plan: AttemptPlan,
+    /// Only once the plan is approved and implementation has started
+    pub comparison: Option<PlanComparison>,
+}
+
+/// Whether a run of `attempt` is asked for a plan in its prompt: the attempt is plan-only,
+/// its executor has no plan mode of its own, and the plan is still to be approved
+pub async fn asks_for_plan(pool: &DbPool, attempt: &TaskAttempt) -> Result<bool, sqlx::Error> {
+    let plans_natively = attempt
+        .executor
+        .as_deref()
+        .and_then(|executor| executor.parse::<ExecutorConfig>().ok())
+        .is_some_and(|config| config.implementation_executor().is_some());
+    if !attempt.plan_only || plans_natively {
+        return Ok(false);
+    }
+    Ok(!AttemptPlan::is_approved(pool, attempt.id).await?)
+}
+
+fn normalize(
+    process: &ExecutionProcess,
+    config: &ExecutorConfig,
+) -> Option<NormalizedConversation> {
+    let stdout = process.stdout.as_deref().filter(|s| !s.trim().is_empty())?;
+    let working_dir = std::fs::canonicalize(&process.working_directory)
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|_| process.working_directory.clone());
+    config
+        .create_executor()
+        .normalize_output(stdout, &working_dir)
+        .ok()
+}
+
+/// The last plan a planning run presented, if it got as far as presenting one
+pub fn plan_from_process(process: &ExecutionProcess) -> Option<String> {
+    let config: ExecutorConfig = process.executor_type.as_deref()?.parse().ok()?;
+    config.implementation_executor()?;
+    normalize(process, &config)?
+        .entries
+        .iter()
+        .rev()
+        .find_map(|entry| match &entry.entry_type {
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::PlanPresentation { plan },
+                ..
+            } => Some(plan.clone()),
+            _ => None,
+        })
+}
+
+/// The plan a run that was asked for one in its prompt replied with: its last message
+pub fn plan_from_reply(process: &ExecutionProcess) -> Option<String> {
+    let config: ExecutorConfig = process.executor_type.as_deref()?.parse().ok()?;
+    normalize(process, &config)?
+        .entries
+        .iter()
+        .rev()
+        .find(|entry| matches!(entry.entry_type, NormalizedEntryType::AssistantMessage))
+        .map(|entry| entry.content.trim().to_string())
+        .filter(|plan| !plan.is_empty())
+}
+
+/// Compare a plan against an attempt's changes. A bare file name in the plan matches any
+/// changed file of that name.
+pub fn compare_plan(plan: &str, changes: &HashMap<String, FileChangeKind>) -> PlanComparison {
+    let references = file_references(plan);
+    let refers_to = |reference: &str, path: &str| {
+        path == reference || path.ends_with(&format!("/{}", reference))
+    };
+
+    let mut changed: Vec<&String> = changes.keys().collect();
+    changed.sort();
+
+    let mut comparison = PlanComparison::default();
+    for path in changed {
+        if references
+            .iter()
+            .any(|reference| refers_to(reference, path))
+        {
+            comparison.planned_and_changed.push(path.clone());
+        } else {
+            comparison.changed_not_planned.push(path.clone());
+        }
+    }
+    comparison.planned_not_changed = references
+        .into_iter()
+        .filter(|reference| !changes.keys().any(|path| refers_to(reference, path)))
+        .collect();
+    comparison
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_plan() {
+        let plan = "Edit `src/models/task.rs` and `api.ts`, then add docs/plan.md";
+        let changes = HashMap::from([
+            (
+                "backend/src/models/task.rs".to_string(),
+                FileChangeKind::Modified,
+            ),
+            (
+                "frontend/src/lib/api.ts".to_string(),
+                FileChangeKind::Modified,
+            ),
+            ("Cargo.lock".to_string(), FileChangeKind::Modified),
+        ]);
+        assert_eq!(
+            compare_plan(plan, &changes),
+            PlanComparison {
+                planned_and_changed: vec![
+                    "backend/src/models/task.rs".to_string(),
+                    "frontend/src/lib/api.ts".to_string(),
+                ],
+                planned_not_changed: vec!["docs/plan.md".to_string()],
+                changed_not_planned: vec!["Cargo.lock".to_string()],
+            }
+        );
+    }
+}
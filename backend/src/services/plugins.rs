@@ -0,0 +1,605 @@
+//! User-provided WASM plugins, run with wasmtime. A plugin is a `.wasm` (or `.wat`) module in
+//! the `plugins` directory of the server's data directory, loaded at startup. Through the
+//! guest API it can transform the conversations executors' output is normalized into, hear
+//! about the events of every project's activity feed, and refuse new tasks and merges. Each
+//! call gets a fresh instance with its own fuel and memory, so a plugin can't hang the server
+//! or keep state from one call to the next.
+//!
+//! Guest API, version 1, as docs/plugins.md describes it for plugin authors:
+//! - every plugin exports `memory`, `vk_api_version() -> i32`, answering 1, and
+//!   `vk_alloc(len: i32) -> i32`, which the host calls for room to write a call's input in
+//! - `vk_normalize(ptr: i32, len: i32) -> i64` is given `{"executor_type", "entries"}` and
+//!   answers with the entries to show instead
+//! - `vk_on_event(ptr: i32, len: i32)` is given an event of the activity feed
+//! - `vk_validate(ptr: i32, len: i32) -> i64` is given a [`Validation`] and answers
+//!   `{"allow": bool, "message": string?}`
+//!
+//! Inputs and answers are UTF-8 JSON. An answer is returned as `ptr << 32 | len` of where it
+//! is in the guest's memory; 0 is no answer, which leaves things as they were. Plugins may
+//! import `vk.log(level: i32, ptr: i32, len: i32)` to write to the server's log.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::RecvError;
+use ts_rs::TS;
+use uuid::Uuid;
+use wasmtime::{
+    Caller, Config, Engine, Instance, Linker, Module, Store, StoreLimits, StoreLimitsBuilder,
+};
+
+use crate::{
+    executor::NormalizedEntry,
+    models::event::{self, Event},
+};
+
+pub const GUEST_API_VERSION: i32 = 1;
+
+/// Roughly the instructions a call may run before it's stopped
+const FUEL_PER_CALL: u64 = 500_000_000;
+const MAX_MEMORY_BYTES: usize = 64 << 20;
+const MAX_ANSWER_BYTES: usize = 16 << 20;
+
+const NORMALIZE_EXPORT: &str = "vk_normalize";
+const EVENT_EXPORT: &str = "vk_on_event";
+const VALIDATE_EXPORT: &str = "vk_validate";
+
+/// A loaded plugin and the hooks it implements
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct PluginInfo {
+    /// The module's file name, without its extension
+    pub name: String,
+    pub normalizer: bool,
+    pub event_hook: bool,
+    pub validator: bool,
+}
+
+/// What validating plugins are asked to allow
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "check", rename_all = "snake_case")]
+pub enum Validation {
+    /// A task about to be created
+    TaskCreate {
+        project_id: Uuid,
+        title: String,
+        description: Option<String>,
+    },
+    /// An attempt about to be merged into its base branch
+    Merge {
+        project_id: Uuid,
+        task_id: Uuid,
+        task_attempt_id: Uuid,
+        task_title: String,
+        branch: String,
+        base_branch: String,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct Verdict {
+    allow: bool,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct NormalizeInput<'a> {
+    executor_type: &'a str,
+    entries: &'a [NormalizedEntry],
+}
+
+struct Plugin {
+    info: PluginInfo,
+    module: Module,
+}
+
+struct HostState {
+    plugin: String,
+    limits: StoreLimits,
+}
+
+pub struct PluginHost {
+    engine: Engine,
+    linker: Linker<HostState>,
+    plugins: Vec<Plugin>,
+}
+
+/// The string at `ptr` in the calling guest's memory
+fn guest_string(caller: &mut Caller<'_, HostState>, ptr: i32, len: i32) -> Option<String> {
+    let memory = caller.get_export("memory")?.into_memory()?;
+    let bytes = memory
+        .data(&caller)
+        .get(ptr as u32 as usize..)?
+        .get(..len as u32 as usize)?;
+    Some(String::from_utf8_lossy(bytes).into_owned())
+}
+
+fn host_linker(engine: &Engine) -> Linker<HostState> {
+    let mut linker = Linker::new(engine);
+    linker
+        .func_wrap(
+            "vk",
+            "log",
+            |mut caller: Caller<'_, HostState>, level: i32, ptr: i32, len: i32| {
+                let message = guest_string(&mut caller, ptr, len).unwrap_or_default();
+                let plugin = &caller.data().plugin;
+                match level {
+                    0 => tracing::debug!("[plugin {}] {}", plugin, message),
+                    1 => tracing::info!("[plugin {}] {}", plugin, message),
+                    2 => tracing::warn!("[plugin {}] {}", plugin, message),
+                    _ => tracing::error!("[plugin {}] {}", plugin, message),
+                }
+            },
+        )
+        .expect("the host's imports are each defined once");
+    linker
+}
+
+impl PluginHost {
+    /// A host for the modules, each named and given as WASM or its text format. Modules that
+    /// don't compile or implement another guest API version are left out.
+    pub fn new(modules: impl IntoIterator<Item = (String, Vec<u8>)>) -> Self {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config).expect("wasmtime supports this platform");
+        let linker = host_linker(&engine);
+        let mut host = Self {
+            engine,
+            linker,
+            plugins: Vec::new(),
+        };
+        for (name, bytes) in modules {
+            match host.compile(&name, &bytes) {
+                Ok(plugin) => {
+                    tracing::info!("Loaded plugin {}", name);
+                    host.plugins.push(plugin);
+                }
+                Err(e) => tracing::warn!("Skipping plugin {}: {:#}", name, e),
+            }
+        }
+        host
+    }
+
+    /// The modules in `dir`, in name order. There are none when it doesn't exist.
+    pub fn load_dir(dir: &Path) -> Self {
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| {
+                        matches!(
+                            path.extension().and_then(|extension| extension.to_str()),
+                            Some("wasm" | "wat")
+                        )
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        paths.sort();
+        let modules = paths.into_iter().filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().into_owned();
+            match std::fs::read(&path) {
+                Ok(bytes) => Some((name, bytes)),
+                Err(e) => {
+                    tracing::warn!("Failed to read plugin {}: {}", path.display(), e);
+                    None
+                }
+            }
+        });
+        Self::new(modules)
+    }
+
+    fn compile(&self, name: &str, bytes: &[u8]) -> anyhow::Result<Plugin> {
+        let module = Module::new(&self.engine, bytes)?;
+        for export in ["memory", "vk_api_version", "vk_alloc"] {
+            if module.get_export(export).is_none() {
+                bail!("it doesn't export `{}`", export);
+            }
+        }
+        let plugin = Plugin {
+            info: PluginInfo {
+                name: name.to_string(),
+                normalizer: module.get_export(NORMALIZE_EXPORT).is_some(),
+                event_hook: module.get_export(EVENT_EXPORT).is_some(),
+                validator: module.get_export(VALIDATE_EXPORT).is_some(),
+            },
+            module,
+        };
+        let (mut store, instance) = self.instantiate(&plugin)?;
+        let version = instance
+            .get_typed_func::<(), i32>(&mut store, "vk_api_version")?
+            .call(&mut store, ())?;
+        if version != GUEST_API_VERSION {
+            bail!(
+                "it implements guest API version {}, not {}",
+                version,
+                GUEST_API_VERSION
+            );
+        }
+        Ok(plugin)
+    }
+
+    fn instantiate(&self, plugin: &Plugin) -> anyhow::Result<(Store<HostState>, Instance)> {
+        let mut store = Store::new(
+            &self.engine,
+            HostState {
+                plugin: plugin.info.name.clone(),
+                limits: StoreLimitsBuilder::new()
+                    .memory_size(MAX_MEMORY_BYTES)
+                    .build(),
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(FUEL_PER_CALL)?;
+        let instance = self.linker.instantiate(&mut store, &plugin.module)?;
+        Ok((store, instance))
+    }
+
+    /// Call the plugin's `export` with `input`. Hooks that answer give their answer, if any.
+    fn call(
+        &self,
+        plugin: &Plugin,
+        export: &str,
+        input: &[u8],
+        answers: bool,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let (mut store, instance) = self.instantiate(plugin)?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .context("its `memory` export isn't a memory")?;
+        let len = i32::try_from(input.len()).context("the input is too large")?;
+        let ptr = instance
+            .get_typed_func::<i32, i32>(&mut store, "vk_alloc")?
+            .call(&mut store, len)?;
+        memory.write(&mut store, ptr as u32 as usize, input)?;
+
+        if !answers {
+            instance
+                .get_typed_func::<(i32, i32), ()>(&mut store, export)?
+                .call(&mut store, (ptr, len))?;
+            return Ok(None);
+        }
+        let packed = instance
+            .get_typed_func::<(i32, i32), i64>(&mut store, export)?
+            .call(&mut store, (ptr, len))? as u64;
+        if packed == 0 {
+            return Ok(None);
+        }
+        let (answer_ptr, answer_len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+        if answer_len > MAX_ANSWER_BYTES {
+            bail!("its answer is over {} bytes", MAX_ANSWER_BYTES);
+        }
+        let mut answer = vec![0; answer_len];
+        memory.read(&store, answer_ptr, &mut answer)?;
+        Ok(Some(answer))
+    }
+
+    pub fn plugins(&self) -> Vec<PluginInfo> {
+        self.plugins
+            .iter()
+            .map(|plugin| plugin.info.clone())
+            .collect()
+    }
+
+    fn has_normalizers(&self) -> bool {
+        self.plugins.iter().any(|plugin| plugin.info.normalizer)
+    }
+
+    fn has_event_hooks(&self) -> bool {
+        self.plugins.iter().any(|plugin| plugin.info.event_hook)
+    }
+
+    fn has_validators(&self) -> bool {
+        self.plugins.iter().any(|plugin| plugin.info.validator)
+    }
+
+    /// The entries as each normalizing plugin transforms them in turn. A plugin that fails or
+    /// doesn't answer with entries is skipped.
+    pub fn normalize(
+        &self,
+        executor_type: &str,
+        mut entries: Vec<NormalizedEntry>,
+    ) -> Vec<NormalizedEntry> {
+        for plugin in self.plugins.iter().filter(|plugin| plugin.info.normalizer) {
+            let transformed = serde_json::to_vec(&NormalizeInput {
+                executor_type,
+                entries: &entries,
+            })
+            .map_err(anyhow::Error::from)
+            .and_then(|input| self.call(plugin, NORMALIZE_EXPORT, &input, true))
+            .and_then(|answer| {
+                answer
+                    .map(|answer| serde_json::from_slice::<Vec<NormalizedEntry>>(&answer))
+                    .transpose()
+                    .map_err(anyhow::Error::from)
+            });
+            match transformed {
+                Ok(Some(transformed)) => entries = transformed,
+                Ok(None) => {}
+                Err(e) => tracing::warn!(
+                    "Plugin {} failed to normalize {} output: {:#}",
+                    plugin.info.name,
+                    executor_type,
+                    e
+                ),
+            }
+        }
+        entries
+    }
+
+    pub fn on_event(&self, event: &Event) {
+        let Ok(input) = serde_json::to_vec(event) else {
+            return;
+        };
+        for plugin in self.plugins.iter().filter(|plugin| plugin.info.event_hook) {
+            if let Err(e) = self.call(plugin, EVENT_EXPORT, &input, false) {
+                tracing::warn!(
+                    "Plugin {} failed on event {}: {:#}",
+                    plugin.info.name,
+                    event.id,
+                    e
+                );
+            }
+        }
+    }
+
+    /// `Err` with why, when a plugin refuses. A plugin that fails allows, so a broken plugin
+    /// can't stop all work.
+    pub fn validate(&self, validation: &Validation) -> Result<(), String> {
+        let Ok(input) = serde_json::to_vec(validation) else {
+            return Ok(());
+        };
+        for plugin in self.plugins.iter().filter(|plugin| plugin.info.validator) {
+            let verdict = self
+                .call(plugin, VALIDATE_EXPORT, &input, true)
+                .and_then(|answer| {
+                    answer
+                        .map(|answer| serde_json::from_slice::<Verdict>(&answer))
+                        .transpose()
+                        .map_err(anyhow::Error::from)
+                });
+            match verdict {
+                Ok(Some(verdict)) if !verdict.allow => {
+                    return Err(verdict
+                        .message
+                        .unwrap_or_else(|| format!("Refused by plugin {}", plugin.info.name)));
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!(
+                    "Plugin {} failed to validate, so it allows: {:#}",
+                    plugin.info.name,
+                    e
+                ),
+            }
+        }
+        Ok(())
+    }
+}
+
+static HOST: OnceLock<PluginHost> = OnceLock::new();
+
+/// Make `host` the server's plugins. Only the first call counts.
+pub fn install(host: PluginHost) {
+    if HOST.set(host).is_err() {
+        tracing::warn!("Plugins were already loaded");
+    }
+}
+
+/// The server's plugins: none until they're installed
+pub fn host() -> &'static PluginHost {
+    HOST.get_or_init(|| PluginHost::new([]))
+}
+
+/// The entries after the plugins' normalizers, run off the async runtime
+pub async fn normalize(
+    executor_type: String,
+    entries: Vec<NormalizedEntry>,
+) -> Vec<NormalizedEntry> {
+    if !host().has_normalizers() {
+        return entries;
+    }
+    let unchanged = entries.clone();
+    tokio::task::spawn_blocking(move || host().normalize(&executor_type, entries))
+        .await
+        .unwrap_or(unchanged)
+}
+
+/// Whether the plugins allow it; `Err` with why not
+pub async fn validate(validation: Validation) -> Result<(), String> {
+    if !host().has_validators() {
+        return Ok(());
+    }
+    tokio::task::spawn_blocking(move || host().validate(&validation))
+        .await
+        .unwrap_or(Ok(()))
+}
+
+/// Hand every event recorded from now on to the plugins' event hooks, in order
+pub async fn deliver_events() {
+    if !host().has_event_hooks() {
+        return;
+    }
+    let mut receiver = event::subscribe();
+    loop {
+        match receiver.recv().await {
+            Ok(event) => {
+                let _ = tokio::task::spawn_blocking(move || host().on_event(&event)).await;
+            }
+            Err(RecvError::Lagged(missed)) => {
+                tracing::warn!("Plugin event hooks missed {} events", missed);
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::executor::NormalizedEntryType;
+
+    /// A plugin exporting `hooks`, with a bump allocator and `answer` in its memory at 64
+    fn plugin(version: i32, answer: &str, hooks: &str) -> Vec<u8> {
+        format!(
+            r#"(module
+                (import "vk" "log" (func $log (param i32 i32 i32)))
+                (memory (export "memory") 1)
+                (global $next (mut i32) (i32.const 4096))
+                (data (i32.const 64) "{answer}")
+                (func (export "vk_api_version") (result i32) (i32.const {version}))
+                (func (export "vk_alloc") (param $len i32) (result i32)
+                    (local $ptr i32)
+                    (local.set $ptr (global.get $next))
+                    (global.set $next (i32.add (global.get $next) (local.get $len)))
+                    (local.get $ptr))
+                {hooks})"#,
+            answer = answer.replace('"', "\\\""),
+            version = version,
+            hooks = hooks,
+        )
+        .into_bytes()
+    }
+
+    /// Answers with the `len` bytes at 64
+    fn answer_hook(export: &str, len: usize) -> String {
+        format!(
+            r#"(func (export "{}") (param i32 i32) (result i64)
+                (i64.or (i64.shl (i64.const 64) (i64.const 32)) (i64.const {})))"#,
+            export, len
+        )
+    }
+
+    fn entry(content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            id: None,
+            timestamp: None,
+            duration_ms: None,
+            entry_type: NormalizedEntryType::AssistantMessage,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_only_plugins_of_this_guest_api_load() {
+        let host = PluginHost::new([
+            ("current".to_string(), plugin(GUEST_API_VERSION, "", "")),
+            ("future".to_string(), plugin(GUEST_API_VERSION + 1, "", "")),
+            ("broken".to_string(), b"(module".to_vec()),
+            (
+                "bare".to_string(),
+                b"(module (memory (export \"memory\") 1))".to_vec(),
+            ),
+        ]);
+        let names: Vec<String> = host.plugins().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["current"]);
+    }
+
+    #[test]
+    fn test_normalizers_replace_entries_and_failures_keep_them() {
+        let replacement = json!([{
+            "timestamp": null,
+            "entry_type": { "type": "system_message" },
+            "content": "from a plugin",
+            "metadata": null,
+        }])
+        .to_string();
+        let host = PluginHost::new([(
+            "replace".to_string(),
+            plugin(
+                GUEST_API_VERSION,
+                &replacement,
+                &answer_hook(NORMALIZE_EXPORT, replacement.len()),
+            ),
+        )]);
+        assert!(host.plugins()[0].normalizer);
+        let entries = host.normalize("claude", vec![entry("original")]);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].content, "from a plugin");
+
+        // Runs out of fuel
+        let spinning = PluginHost::new([(
+            "spin".to_string(),
+            plugin(
+                GUEST_API_VERSION,
+                "",
+                r#"(func (export "vk_normalize") (param i32 i32) (result i64)
+                    (loop $forever (br $forever))
+                    (i64.const 0))"#,
+            ),
+        )]);
+        let entries = spinning.normalize("claude", vec![entry("original")]);
+        assert_eq!(entries[0].content, "original");
+    }
+
+    #[test]
+    fn test_validators_refuse_with_their_message() {
+        let verdict = json!({ "allow": false, "message": "No merges on Fridays" }).to_string();
+        let host = PluginHost::new([
+            (
+                "allows".to_string(),
+                plugin(GUEST_API_VERSION, "", &answer_hook(VALIDATE_EXPORT, 0)),
+            ),
+            (
+                "refuses".to_string(),
+                plugin(
+                    GUEST_API_VERSION,
+                    &verdict,
+                    &answer_hook(VALIDATE_EXPORT, verdict.len()),
+                ),
+            ),
+        ]);
+        let merge = Validation::Merge {
+            project_id: Uuid::nil(),
+            task_id: Uuid::nil(),
+            task_attempt_id: Uuid::nil(),
+            task_title: "Ship it".to_string(),
+            branch: "vk/ship-it".to_string(),
+            base_branch: "main".to_string(),
+        };
+        assert_eq!(
+            host.validate(&merge),
+            Err("No merges on Fridays".to_string())
+        );
+        assert_eq!(PluginHost::new([]).validate(&merge), Ok(()));
+    }
+
+    #[test]
+    fn test_event_hooks_can_log_and_trap() {
+        let host = PluginHost::new([
+            (
+                "logs".to_string(),
+                plugin(
+                    GUEST_API_VERSION,
+                    "heard",
+                    r#"(func (export "vk_on_event") (param i32 i32)
+                        (call $log (i32.const 1) (i32.const 64) (i32.const 5)))"#,
+                ),
+            ),
+            (
+                "traps".to_string(),
+                plugin(
+                    GUEST_API_VERSION,
+                    "",
+                    r#"(func (export "vk_on_event") (param i32 i32) unreachable)"#,
+                ),
+            ),
+        ]);
+        let plugins = host.plugins();
+        assert!(plugins.iter().all(|plugin| plugin.event_hook));
+        let input = br#"{"kind":"task_created"}"#;
+        assert!(host
+            .call(&host.plugins[0], EVENT_EXPORT, input, false)
+            .is_ok());
+        assert!(host
+            .call(&host.plugins[1], EVENT_EXPORT, input, false)
+            .is_err());
+    }
+}
@@ -0,0 +1,551 @@
+//! Pull request bodies built from what an attempt actually did: the task, a summary of the
+//! agent's conversation, the diff and how the setup and cleanup scripts went. The layout comes
+//! from a user-editable template in the config. On request, a language model writes the
+//! title and description instead, from the attempt's diff and conversations.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    executor::NormalizedEntryType,
+    models::{
+        config::Config,
+        execution_diff::ExecutionDiff,
+        execution_process::{ExecutionProcess, ExecutionProcessStatus, ExecutionProcessType},
+        executor_session::ExecutorSession,
+        task::Task,
+        task_attempt::{FileChangeKind, TaskAttempt, TaskAttemptError, WorktreeStats},
+    },
+    routes::task_attempts::normalize_process_logs,
+    services::{
+        llm::{LlmClient, LlmError},
+        scoped_follow_up::unified_hunk,
+        GitService,
+    },
+    utils::text::fill_placeholders,
+};
+
+pub const DEFAULT_TEMPLATE: &str = "{{task_description}}
+
+## Summary
+
+{{summary}}
+
+## Changes
+
+{{changes}}
+
+## Validation
+
+{{validation}}
+";
+
+const MAX_LISTED_FILES: usize = 30;
+const MAX_SUMMARY_CHARS: usize = 2000;
+/// The end of a conversation is where agents wrap up what they did
+const MAX_CONVERSATION_CHARS: usize = 8000;
+
+const SUMMARY_PROMPT: &str = "You write the summary section of a pull request description. \
+Given a task, the messages a coding agent wrote while working on it and the files it changed, \
+explain in 2 to 5 sentences what was changed and why. Write plain Markdown without headings. \
+Do not invent details that are not in the input.";
+
+const GENERATE_PROMPT: &str = "You write pull requests for changes a coding agent made. \
+Given the task, what the agent was asked and reported in each run, and the diff of its changes, \
+reply with only a JSON object of the form {\"title\": string, \"description\": string}. \
+The title is an imperative summary of at most 10 words. The description is Markdown that says \
+what changed and why, then lists notable changes as bullet points. \
+Do not invent details that are not in the input.";
+/// Diffs are clipped at the end, since the first files usually show the change best
+const MAX_DIFF_CHARS: usize = 12000;
+const MAX_TITLE_CHARS: usize = 80;
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+pub struct PrDescription {
+    pub title: String,
+    pub body: String,
+}
+
+#[derive(Debug)]
+pub enum PrGenerationError {
+    Database(sqlx::Error),
+    Llm(LlmError),
+    /// The attempt has no coding agent run with changes to describe
+    NothingToDescribe,
+}
+
+impl std::fmt::Display for PrGenerationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PrGenerationError::Database(e) => write!(f, "Database error: {}", e),
+            PrGenerationError::Llm(e) => write!(f, "{}", e),
+            PrGenerationError::NothingToDescribe => {
+                write!(
+                    f,
+                    "The attempt has no changes from a coding agent to describe"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for PrGenerationError {}
+
+impl From<sqlx::Error> for PrGenerationError {
+    fn from(e: sqlx::Error) -> Self {
+        PrGenerationError::Database(e)
+    }
+}
+
+impl From<LlmError> for PrGenerationError {
+    fn from(e: LlmError) -> Self {
+        PrGenerationError::Llm(e)
+    }
+}
+
+/// Everything the template can refer to
+#[derive(Debug, Clone)]
+pub struct PrContext {
+    pub task_title: String,
+    pub task_description: Option<String>,
+    pub branch: String,
+    pub base_branch: String,
+    pub summary: Option<String>,
+    pub stats: WorktreeStats,
+    pub files: Vec<(String, FileChangeKind)>,
+    pub checks: Vec<ScriptCheck>,
+}
+
+/// Outcome of the latest run of a setup or cleanup script
+#[derive(Debug, Clone)]
+pub struct ScriptCheck {
+    pub name: &'static str,
+    pub status: ExecutionProcessStatus,
+    pub exit_code: Option<i64>,
+}
+
+pub async fn generate(
+    pool: &DbPool,
+    config: &Config,
+    attempt_id: Uuid,
+    task_id: Uuid,
+    project_id: Uuid,
+) -> Result<PrDescription, TaskAttemptError> {
+    let ctx = TaskAttempt::load_context(pool, attempt_id, task_id, project_id).await?;
+    let attempt = &ctx.task_attempt;
+
+    let worktree_path =
+        TaskAttempt::ensure_worktree_exists(pool, attempt_id, project_id, "PR description").await?;
+    let git_service = GitService::new(&ctx.project.git_repo_path)?;
+    let worktree = Path::new(&worktree_path);
+    let stats = git_service.get_worktree_stats(worktree, &attempt.base_branch)?;
+    let mut files: Vec<(String, FileChangeKind)> = git_service
+        .get_worktree_changes(worktree, &attempt.base_branch)?
+        .into_iter()
+        .collect();
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let processes = ExecutionProcess::find_by_task_attempt_id(pool, attempt_id).await?;
+    let checks = [
+        (ExecutionProcessType::SetupScript, "Setup script"),
+        (ExecutionProcessType::CleanupScript, "Cleanup script"),
+    ]
+    .into_iter()
+    .filter_map(|(process_type, name)| {
+        let latest = processes
+            .iter()
+            .rev()
+            .find(|p| p.process_type == process_type)?;
+        Some(ScriptCheck {
+            name,
+            status: latest.status.clone(),
+            exit_code: latest.exit_code,
+        })
+    })
+    .collect();
+
+    let agent_messages = match processes
+        .iter()
+        .rev()
+        .find(|p| p.process_type == ExecutionProcessType::CodingAgent)
+    {
+        Some(process) => normalize_process_logs(pool, process)
+            .await
+            .entries
+            .into_iter()
+            .filter(|e| matches!(e.entry_type, NormalizedEntryType::AssistantMessage))
+            .map(|e| e.content.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let mut context = PrContext {
+        task_title: ctx.task.title.clone(),
+        task_description: ctx.task.description.clone(),
+        branch: attempt.branch.clone(),
+        base_branch: attempt.base_branch.clone(),
+        summary: agent_messages.last().map(|m| clip(m, MAX_SUMMARY_CHARS)),
+        stats,
+        files,
+        checks,
+    };
+
+    if config.pr_description.summarize_with_llm && !agent_messages.is_empty() {
+        if let Some(client) = LlmClient::from_config(&config.llm) {
+            match summarize(&client, &context, &agent_messages).await {
+                Ok(summary) => context.summary = Some(summary),
+                Err(e) => tracing::warn!(
+                    "Falling back to the agent's last message for the PR summary of attempt {}: {}",
+                    attempt_id,
+                    e
+                ),
+            }
+        }
+    }
+
+    Ok(PrDescription {
+        title: format!("{} (vibe-kanban)", ctx.task.title),
+        body: render(&config.pr_description.template, &context),
+    })
+}
+
+async fn summarize(
+    client: &LlmClient,
+    context: &PrContext,
+    agent_messages: &[String],
+) -> Result<String, LlmError> {
+    let conversation = agent_messages.join("\n\n");
+    let conversation_chars = conversation.chars().count();
+    let conversation: String = if conversation_chars > MAX_CONVERSATION_CHARS {
+        conversation
+            .chars()
+            .skip(conversation_chars - MAX_CONVERSATION_CHARS)
+            .collect()
+    } else {
+        conversation
+    };
+
+    let mut input = format!("Task: {}\n", context.task_title);
+    if let Some(description) = &context.task_description {
+        input.push_str(&format!("\n{}\n", clip(description, MAX_SUMMARY_CHARS)));
+    }
+    input.push_str(&format!(
+        "\nChanged files:\n{}\n\nAgent messages:\n{}",
+        changes_section(context),
+        conversation
+    ));
+
+    let summary = client.complete(SUMMARY_PROMPT, &input).await?;
+    let summary = summary.trim();
+    if summary.is_empty() {
+        return Err(LlmError::InvalidResponse);
+    }
+    Ok(summary.to_string())
+}
+
+/// A title and description written by the language model from the diffs of the attempt's
+/// coding agent runs, with what each run was asked and what it reported back
+pub async fn generate_with_llm(
+    pool: &DbPool,
+    client: &LlmClient,
+    task: &Task,
+    attempt: &TaskAttempt,
+) -> Result<PrDescription, PrGenerationError> {
+    let mut conversation = Vec::new();
+    let mut diff = String::new();
+    for process in ExecutionProcess::find_by_task_attempt_id(pool, attempt.id).await? {
+        if process.process_type != ExecutionProcessType::CodingAgent {
+            continue;
+        }
+        let session = ExecutorSession::find_by_execution_process_id(pool, process.id).await?;
+        if let Some(prompt) = session.as_ref().and_then(|s| s.prompt.as_deref()) {
+            conversation.push(format!("Asked: {}", clip(prompt.trim(), MAX_SUMMARY_CHARS)));
+        }
+        // The agent's own closing words say more than the run's one-line outcome
+        let reported = normalize_process_logs(pool, &process)
+            .await
+            .entries
+            .into_iter()
+            .rev()
+            .find(|e| matches!(e.entry_type, NormalizedEntryType::AssistantMessage))
+            .map(|e| e.content)
+            .or_else(|| session.and_then(|s| s.summary));
+        if let Some(reported) = reported.filter(|r| !r.trim().is_empty()) {
+            conversation.push(format!(
+                "Reported: {}",
+                clip(reported.trim(), MAX_SUMMARY_CHARS)
+            ));
+        }
+
+        let Some(execution_diff) = ExecutionDiff::find_by_execution_process_id(pool, process.id)
+            .await?
+            .filter(|d| d.to_commit.is_some())
+        else {
+            continue;
+        };
+        for file in &execution_diff.files {
+            diff.push_str(&format!("--- a/{0}\n+++ b/{0}\n", file.path));
+            for hunk in &file.hunks {
+                diff.push_str(&unified_hunk(hunk));
+            }
+        }
+    }
+    if diff.is_empty() {
+        return Err(PrGenerationError::NothingToDescribe);
+    }
+
+    let mut input = format!("Task: {}\n", task.title);
+    if let Some(description) = task.description.as_deref().filter(|d| !d.trim().is_empty()) {
+        input.push_str(&format!(
+            "\n{}\n",
+            clip(description.trim(), MAX_SUMMARY_CHARS)
+        ));
+    }
+    let conversation = conversation.join("\n\n");
+    let conversation_chars = conversation.chars().count();
+    let conversation: String = conversation
+        .chars()
+        .skip(conversation_chars.saturating_sub(MAX_CONVERSATION_CHARS))
+        .collect();
+    input.push_str(&format!(
+        "\nAgent runs:\n{}\n\nDiff:\n{}",
+        conversation,
+        clip(&diff, MAX_DIFF_CHARS)
+    ));
+
+    let reply = client.complete(GENERATE_PROMPT, &input).await?;
+    parse_generated(&reply).ok_or(PrGenerationError::Llm(LlmError::InvalidResponse))
+}
+
+#[derive(Deserialize)]
+struct GeneratedDescription {
+    title: String,
+    description: String,
+}
+
+/// Models often wrap the JSON in a code fence or a sentence, so take the outermost object
+fn parse_generated(reply: &str) -> Option<PrDescription> {
+    let start = reply.find('{')?;
+    let end = reply.rfind('}')?;
+    let generated: GeneratedDescription = serde_json::from_str(reply.get(start..=end)?).ok()?;
+    let title = generated.title.trim().trim_end_matches('.');
+    if title.is_empty() {
+        return None;
+    }
+    Some(PrDescription {
+        title: clip(title, MAX_TITLE_CHARS),
+        body: generated.description.trim().to_string(),
+    })
+}
+
+/// Fill in `{{placeholders}}` and drop headings whose section came out empty
+pub fn render(template: &str, context: &PrContext) -> String {
+    let values = [
+        ("task_title", context.task_title.clone()),
+        (
+            "task_description",
+            context.task_description.clone().unwrap_or_default(),
+        ),
+        ("branch", context.branch.clone()),
+        ("base_branch", context.base_branch.clone()),
+        ("summary", context.summary.clone().unwrap_or_default()),
+        ("changes", changes_section(context)),
+        ("validation", validation_section(context)),
+    ];
+    drop_empty_sections(&fill_placeholders(template, &values))
+}
+
+fn changes_section(context: &PrContext) -> String {
+    let stats = &context.stats;
+    if stats.files_changed == 0 {
+        return String::new();
+    }
+    let mut lines = vec![
+        format!(
+            "{} file{} changed, +{} −{}",
+            stats.files_changed,
+            if stats.files_changed == 1 { "" } else { "s" },
+            stats.lines_added,
+            stats.lines_removed
+        ),
+        String::new(),
+    ];
+    for (path, change) in context.files.iter().take(MAX_LISTED_FILES) {
+        let change = match change {
+            FileChangeKind::Added => "added",
+            FileChangeKind::Modified => "modified",
+            FileChangeKind::Deleted => "deleted",
+        };
+        lines.push(format!("- `{}` ({})", path, change));
+    }
+    if context.files.len() > MAX_LISTED_FILES {
+        lines.push(format!(
+            "- …and {} more",
+            context.files.len() - MAX_LISTED_FILES
+        ));
+    }
+    lines.join("\n")
+}
+
+fn validation_section(context: &PrContext) -> String {
+    context
+        .checks
+        .iter()
+        .map(|check| match (&check.status, check.exit_code) {
+            (ExecutionProcessStatus::Completed, _) => format!("- ✅ {} passed", check.name),
+            (ExecutionProcessStatus::Failed, Some(code)) => {
+                format!("- ❌ {} failed (exit code {})", check.name, code)
+            }
+            (ExecutionProcessStatus::Failed, None) => format!("- ❌ {} failed", check.name),
+            (ExecutionProcessStatus::Killed, _) => format!("- ⚠️ {} was stopped", check.name),
+            (ExecutionProcessStatus::Interrupted, _) => {
+                format!("- ⚠️ {} was cut off by a restart", check.name)
+            }
+            (ExecutionProcessStatus::TimedOut, _) => format!("- ⏱️ {} timed out", check.name),
+            (ExecutionProcessStatus::ResourceLimitExceeded, _) => {
+                format!("- ❌ {} went over its resource limits", check.name)
+            }
+            (ExecutionProcessStatus::RateLimited, _) => {
+                format!("- ⏳ {} was rate limited", check.name)
+            }
+            (ExecutionProcessStatus::Running | ExecutionProcessStatus::Paused, _) => {
+                format!("- ⏳ {} was still running", check.name)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn drop_empty_sections(text: &str) -> String {
+    let mut sections: Vec<Vec<&str>> = vec![Vec::new()];
+    for line in text.lines() {
+        if line.starts_with('#') {
+            sections.push(Vec::new());
+        }
+        if let Some(section) = sections.last_mut() {
+            section.push(line);
+        }
+    }
+
+    let mut lines = Vec::new();
+    for section in sections {
+        let heading = section.first().is_some_and(|line| line.starts_with('#'));
+        let empty = section
+            .iter()
+            .skip(heading as usize)
+            .all(|line| line.trim().is_empty());
+        if !(heading && empty) {
+            lines.extend(section);
+        }
+    }
+
+    // Removed sections and empty values leave runs of blank lines behind
+    let mut out = String::new();
+    let mut previous_blank = true;
+    for line in lines {
+        let blank = line.trim().is_empty();
+        if !(blank && previous_blank) {
+            out.push_str(line);
+            out.push('\n');
+        }
+        previous_blank = blank;
+    }
+    out.trim_end().to_string()
+}
+
+fn clip(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let clipped: String = text.chars().take(max_chars - 1).collect();
+    format!("{}…", clipped.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> PrContext {
+        PrContext {
+            task_title: "Fix session panic".to_string(),
+            task_description: Some("Saving a session panics.".to_string()),
+            branch: "vk-1234-fix-session".to_string(),
+            base_branch: "main".to_string(),
+            summary: None,
+            stats: WorktreeStats {
+                files_changed: 2,
+                lines_added: 10,
+                lines_removed: 3,
+            },
+            files: vec![
+                ("src/session.rs".to_string(), FileChangeKind::Modified),
+                ("tests/session.rs".to_string(), FileChangeKind::Added),
+            ],
+            checks: vec![ScriptCheck {
+                name: "Cleanup script",
+                status: ExecutionProcessStatus::Failed,
+                exit_code: Some(1),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_render_default_template_drops_empty_sections() {
+        let body = render(DEFAULT_TEMPLATE, &context());
+        assert_eq!(
+            body,
+            "Saving a session panics.
+
+## Changes
+
+2 files changed, +10 −3
+
+- `src/session.rs` (modified)
+- `tests/session.rs` (added)
+
+## Validation
+
+- ❌ Cleanup script failed (exit code 1)"
+        );
+    }
+
+    #[test]
+    fn test_render_custom_template() {
+        let mut context = context();
+        context.summary = Some("Handles a missing store.".to_string());
+        context.checks.clear();
+        let body = render(
+            "Closes the task \"{{task_title}}\" ({{branch}} → {{base_branch}}).\n\n\
+             ### What\n{{summary}}\n### Checks\n{{validation}}\n",
+            &context,
+        );
+        assert_eq!(
+            body,
+            "Closes the task \"Fix session panic\" (vk-1234-fix-session → main).\n\n\
+             ### What\nHandles a missing store."
+        );
+    }
+
+    #[test]
+    fn test_parse_generated_tolerates_wrapping() {
+        let reply = "```json\n{\"title\": \"Handle a missing session store.\", \"description\": \" Saving no longer panics.\\n\\n- Checks the store \"}\n```";
+        assert_eq!(
+            parse_generated(reply),
+            Some(PrDescription {
+                title: "Handle a missing session store".to_string(),
+                body: "Saving no longer panics.\n\n- Checks the store".to_string(),
+            })
+        );
+        assert_eq!(parse_generated("Sure, here it is."), None);
+        assert_eq!(
+            parse_generated("{\"title\": \"\", \"description\": \"x\"}"),
+            None
+        );
+    }
+}
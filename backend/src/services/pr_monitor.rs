@@ -1,38 +1,42 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::Arc,
+    time::Duration,
+};
 
-use sqlx::SqlitePool;
+use sqlx::FromRow;
 use tokio::{sync::RwLock, time::interval};
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 use crate::{
+    db::{self, DbPool},
     models::{
-        config::Config,
+        config::{Config, GitHubConfig},
         task::{Task, TaskStatus},
         task_attempt::TaskAttempt,
     },
-    services::{GitHubRepoInfo, GitHubService, GitService},
+    services::git_host::{self, GitHost},
 };
 
-/// Service to monitor GitHub PRs and update task status when they are merged
+/// Service to monitor pull requests on each project's git host and update task status when
+/// they are merged
 pub struct PrMonitorService {
-    pool: SqlitePool,
+    pool: DbPool,
     poll_interval: Duration,
 }
 
-#[derive(Debug)]
+#[derive(Debug, FromRow)]
 pub struct PrInfo {
     pub attempt_id: Uuid,
     pub task_id: Uuid,
     pub project_id: Uuid,
     pub pr_number: i64,
-    pub repo_owner: String,
-    pub repo_name: String,
-    pub github_token: String,
+    pub git_repo_path: String,
 }
 
 impl PrMonitorService {
-    pub fn new(pool: SqlitePool) -> Self {
+    pub fn new(pool: DbPool) -> Self {
         Self {
             pool,
             poll_interval: Duration::from_secs(60), // Check every minute
@@ -51,35 +55,20 @@ impl PrMonitorService {
         loop {
             interval.tick().await;
 
-            // Get GitHub token from config
-            let github_token = {
-                let config_read = config.read().await;
-                if config_read.github.pat.is_some() {
-                    config_read.github.pat.clone()
-                } else {
-                    config_read.github.token.clone()
-                }
-            };
-
-            match github_token {
-                Some(token) => {
-                    if let Err(e) = self.check_all_open_prs_with_token(&token).await {
-                        error!("Error checking PRs: {}", e);
-                    }
-                }
-                None => {
-                    debug!("No GitHub token configured, skipping PR monitoring");
-                }
+            let github = config.read().await.github.clone();
+            if let Err(e) = self.check_all_open_prs(&github).await {
+                error!("Error checking PRs: {}", e);
             }
         }
     }
 
-    /// Check all open PRs for updates with the provided GitHub token
-    async fn check_all_open_prs_with_token(
+    /// Check all open PRs for updates, with `github` for projects on GitHub with the
+    /// account's token
+    pub async fn check_all_open_prs(
         &self,
-        github_token: &str,
+        github: &GitHubConfig,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let open_prs = self.get_open_prs_with_token(github_token).await?;
+        let open_prs = self.get_open_prs().await?;
 
         if open_prs.is_empty() {
             debug!("No open PRs to check");
@@ -88,8 +77,33 @@ impl PrMonitorService {
 
         info!("Checking {} open PRs", open_prs.len());
 
+        // Each project's host, or None if its pull requests can't be checked
+        let mut hosts: HashMap<Uuid, Option<Box<dyn GitHost>>> = HashMap::new();
         for pr_info in open_prs {
-            if let Err(e) = self.check_pr_status(&pr_info).await {
+            let host = match hosts.entry(pr_info.project_id) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let host = git_host::for_project(
+                        &self.pool,
+                        pr_info.project_id,
+                        &pr_info.git_repo_path,
+                        github,
+                    )
+                    .await;
+                    entry.insert(match host {
+                        Ok(host) => Some(host),
+                        Err(e) => {
+                            warn!("Not checking PRs of project {}: {}", pr_info.project_id, e);
+                            None
+                        }
+                    })
+                }
+            };
+            let Some(host) = host else {
+                continue;
+            };
+
+            if let Err(e) = self.check_pr_status(host.as_ref(), &pr_info).await {
                 error!(
                     "Error checking PR #{} for attempt {}: {}",
                     pr_info.pr_number, pr_info.attempt_id, e
@@ -100,17 +114,14 @@ impl PrMonitorService {
         Ok(())
     }
 
-    /// Get all task attempts with open PRs using the provided GitHub token
-    async fn get_open_prs_with_token(
-        &self,
-        github_token: &str,
-    ) -> Result<Vec<PrInfo>, sqlx::Error> {
-        let rows = sqlx::query!(
+    /// Get all task attempts with open PRs
+    async fn get_open_prs(&self) -> Result<Vec<PrInfo>, sqlx::Error> {
+        db::query_as!(
+            PrInfo,
             r#"SELECT 
                 ta.id as "attempt_id!: Uuid",
                 ta.task_id as "task_id!: Uuid",
                 ta.pr_number as "pr_number!: i64",
-                ta.pr_url,
                 t.project_id as "project_id!: Uuid",
                 p.git_repo_path
                FROM task_attempts ta
@@ -119,61 +130,16 @@ impl PrMonitorService {
                WHERE ta.pr_status = 'open' AND ta.pr_number IS NOT NULL"#
         )
         .fetch_all(&self.pool)
-        .await?;
-
-        let mut pr_infos = Vec::new();
-
-        for row in rows {
-            // Get GitHub repo info from local git repository
-            match GitService::new(&row.git_repo_path) {
-                Ok(git_service) => match git_service.get_github_repo_info() {
-                    Ok((owner, repo_name)) => {
-                        pr_infos.push(PrInfo {
-                            attempt_id: row.attempt_id,
-                            task_id: row.task_id,
-                            project_id: row.project_id,
-                            pr_number: row.pr_number,
-                            repo_owner: owner,
-                            repo_name,
-                            github_token: github_token.to_string(),
-                        });
-                    }
-                    Err(e) => {
-                        warn!(
-                            "Could not extract repo info from git path {}: {}",
-                            row.git_repo_path, e
-                        );
-                    }
-                },
-                Err(e) => {
-                    warn!(
-                        "Could not create git service for path {}: {}",
-                        row.git_repo_path, e
-                    );
-                }
-            }
-        }
-
-        Ok(pr_infos)
+        .await
     }
 
     /// Check the status of a specific PR
     async fn check_pr_status(
         &self,
+        host: &dyn GitHost,
         pr_info: &PrInfo,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        // For now, we'll use the standard GitHub service since we don't have enterprise_url in PrInfo
-        // TODO: Add enterprise_url to PrInfo if needed for enterprise support in PR monitoring
-        let github_service = GitHubService::new(&pr_info.github_token)?;
-
-        let repo_info = GitHubRepoInfo {
-            owner: pr_info.repo_owner.clone(),
-            repo_name: pr_info.repo_name.clone(),
-        };
-
-        let pr_status = github_service
-            .update_pr_status(&repo_info, pr_info.pr_number)
-            .await?;
+        let pr_status = host.pull_request(pr_info.pr_number).await?;
 
         debug!(
             "PR #{} status: {} (was open)",
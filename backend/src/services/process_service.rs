@@ -1,19 +1,39 @@
 use std::str::FromStr;
 
-use sqlx::SqlitePool;
 use tracing::{debug, info};
 use uuid::Uuid;
 
 use crate::{
     command_runner,
+    db::DbPool,
     executor::Executor,
     models::{
-        execution_process::{CreateExecutionProcess, ExecutionProcess, ExecutionProcessType},
+        attempt_plan::AttemptPlan,
+        config::{Config, ShellEnvironmentConfig},
+        dev_server_port::DevServerPort,
+        execution_diff::ExecutionDiff,
+        execution_process::{
+            CreateExecutionProcess, ExecutionProcess, ExecutionProcessStatus, ExecutionProcessType,
+            FailureReason,
+        },
+        execution_queue::QueuedAttempt,
+        execution_snapshot::{self, ExecutionSnapshot},
+        executor_profile::ExecutorProfile,
         executor_session::{CreateExecutorSession, ExecutorSession},
         project::Project,
+        project_container::ProjectContainer,
+        project_shell_environment::ProjectShellEnvironment,
+        project_worker::ProjectWorker,
         task::Task,
+        task_attachment::TaskAttachment,
         task_attempt::{TaskAttempt, TaskAttemptError},
     },
+    services::{
+        acp_session, command_approval, conversation_transcript,
+        environment_variables::{self, EnvironmentError},
+        execution_queue, execution_recovery, executor_preflight, guardrails, repo_config, sandbox,
+        GitService,
+    },
     utils::shell::get_shell_command,
 };
 
@@ -23,7 +43,7 @@ pub struct ProcessService;
 impl ProcessService {
     /// Run cleanup script if project has one configured
     pub async fn run_cleanup_script_if_configured(
-        pool: &SqlitePool,
+        pool: &DbPool,
         app_state: &crate::app_state::AppState,
         attempt_id: Uuid,
         task_id: Uuid,
@@ -64,7 +84,7 @@ impl ProcessService {
 
     /// Automatically run setup if needed, then continue with the specified operation
     pub async fn auto_setup_and_execute(
-        pool: &SqlitePool,
+        pool: &DbPool,
         app_state: &crate::app_state::AppState,
         attempt_id: Uuid,
         task_id: Uuid,
@@ -76,9 +96,10 @@ impl ProcessService {
         let setup_completed = TaskAttempt::is_setup_completed(pool, attempt_id).await?;
 
         // Get project to check if setup script exists
-        let project = Project::find_by_id(pool, project_id)
+        let mut project = Project::find_by_id(pool, project_id)
             .await?
             .ok_or(TaskAttemptError::ProjectNotFound)?;
+        repo_config::apply(&mut project).map_err(TaskAttemptError::ValidationError)?;
 
         let needs_setup = Self::should_run_setup_script(&project) && !setup_completed;
 
@@ -110,8 +131,13 @@ impl ProcessService {
                         .and_then(|p| p.get("prompt"))
                         .and_then(|p| p.as_str())
                         .unwrap_or("");
+                    let executor = operation_params
+                        .as_ref()
+                        .and_then(|p| p.get("executor"))
+                        .and_then(|e| e.as_str())
+                        .and_then(|e| e.parse().ok());
                     Self::start_followup_execution_direct(
-                        pool, app_state, attempt_id, task_id, project_id, prompt,
+                        pool, app_state, attempt_id, task_id, project_id, prompt, executor,
                     )
                     .await
                     .map(|_| ())
@@ -126,7 +152,7 @@ impl ProcessService {
 
     /// Execute setup script with delegation context for continuing after completion
     async fn execute_setup_with_delegation(
-        pool: &SqlitePool,
+        pool: &DbPool,
         app_state: &crate::app_state::AppState,
         attempt_id: Uuid,
         task_id: Uuid,
@@ -187,6 +213,7 @@ impl ProcessService {
             app_state,
             process_id,
             attempt_id,
+            task_id,
             &ExecutionProcessType::SetupScript,
             child,
         )
@@ -202,7 +229,45 @@ impl ProcessService {
 
     /// Start the execution flow for a task attempt (setup script + executor)
     pub async fn start_execution(
-        pool: &SqlitePool,
+        pool: &DbPool,
+        app_state: &crate::app_state::AppState,
+        attempt_id: Uuid,
+        task_id: Uuid,
+        project_id: Uuid,
+    ) -> Result<(), TaskAttemptError> {
+        use crate::models::{
+            task::{Task, TaskStatus},
+            task_dependency::{blocked_message, TaskDependency},
+        };
+
+        let blockers = TaskDependency::unfinished_blockers(pool, task_id).await?;
+        if !blockers.is_empty() {
+            return Err(TaskAttemptError::ValidationError(blocked_message(
+                &blockers,
+            )));
+        }
+
+        // Past the concurrency limits, while its executor is rate limited, or while its
+        // project's guardrails hold it back, the attempt waits in the queue
+        let _guard = execution_queue::lock().await;
+        QueuedAttempt::enqueue(pool, attempt_id, project_id).await?;
+        if !execution_queue::find_dispatchable(app_state)
+            .await?
+            .iter()
+            .any(|queued| queued.task_attempt_id == attempt_id)
+        {
+            tracing::info!("Queued attempt {} until it can be started", attempt_id);
+            Task::update_status(pool, task_id, project_id, TaskStatus::Queued).await?;
+            return Ok(());
+        }
+        QueuedAttempt::remove(pool, attempt_id).await?;
+
+        Self::start_execution_now(pool, app_state, attempt_id, task_id, project_id).await
+    }
+
+    /// Start a task attempt's execution without checking the concurrency limits
+    pub async fn start_execution_now(
+        pool: &DbPool,
         app_state: &crate::app_state::AppState,
         attempt_id: Uuid,
         task_id: Uuid,
@@ -210,15 +275,16 @@ impl ProcessService {
     ) -> Result<(), TaskAttemptError> {
         use crate::models::task::{Task, TaskStatus};
 
-        // Load required entities
         let (task_attempt, project) =
             Self::load_execution_context(pool, attempt_id, project_id).await?;
 
         // Update task status to indicate execution has started
         Task::update_status(pool, task_id, project_id, TaskStatus::InProgress).await?;
 
-        // Determine execution sequence based on project configuration
-        if Self::should_run_setup_script(&project) {
+        // Determine execution sequence based on project configuration; the setup script runs
+        // once per worktree, so an attempt whose worktree is already set up goes straight on
+        let setup_completed = TaskAttempt::is_setup_completed(pool, attempt_id).await?;
+        if Self::should_run_setup_script(&project) && !setup_completed {
             Self::start_setup_script(
                 pool,
                 app_state,
@@ -235,7 +301,7 @@ impl ProcessService {
 
     /// Start the coding agent after setup is complete or if no setup is needed
     pub async fn start_coding_agent(
-        pool: &SqlitePool,
+        pool: &DbPool,
         app_state: &crate::app_state::AppState,
         attempt_id: Uuid,
         task_id: Uuid,
@@ -245,7 +311,7 @@ impl ProcessService {
             .await?
             .ok_or(TaskAttemptError::TaskNotFound)?;
 
-        let executor_config = Self::resolve_executor_config(&task_attempt.executor);
+        let executor_config = Self::coding_agent_config(app_state, &task_attempt.executor).await;
 
         Self::start_process_execution(
             pool,
@@ -263,9 +329,134 @@ impl ProcessService {
         .await
     }
 
+    /// Start a coding agent execution that failed to start again, in the same execution
+    /// record. Another failure schedules the next retry while the policy allows one.
+    pub async fn retry_coding_agent(
+        app_state: &crate::app_state::AppState,
+        process: &ExecutionProcess,
+    ) -> Result<(), TaskAttemptError> {
+        let pool = &app_state.db_pool;
+        let task_attempt = TaskAttempt::find_by_id(pool, process.task_attempt_id)
+            .await?
+            .ok_or(TaskAttemptError::TaskNotFound)?;
+        let config = Self::coding_agent_config(app_state, &process.executor_type).await;
+
+        // Retrying won't help an agent the machine can't run
+        if let Err(e) = executor_preflight::validate(&config).await {
+            ExecutionProcess::update_completion(
+                pool,
+                process.id,
+                crate::models::execution_process::ExecutionProcessStatus::Failed,
+                None,
+            )
+            .await?;
+            ExecutionProcess::set_failure_reason(pool, process.id, e.failure_reason()).await?;
+            return Err(TaskAttemptError::Executor(Box::new(e)));
+        }
+        let executor_type = crate::executor::ExecutorType::CodingAgent {
+            config,
+            follow_up: None,
+        };
+
+        ExecutionProcess::restart_for_retry(pool, process.id).await?;
+        app_state.register_cancellation(process.id).await;
+        tracing::info!(
+            "Retrying execution {} for task attempt {} (retry {})",
+            process.id,
+            task_attempt.id,
+            process.retry_count + 1
+        );
+
+        let child = match Self::spawn_cancellable(
+            app_state,
+            &executor_type,
+            task_attempt.task_id,
+            task_attempt.id,
+            process.id,
+            &task_attempt.worktree_path,
+        )
+        .await
+        {
+            Ok(Some(child)) => child,
+            Ok(None) => return Ok(()),
+            Err(e) => {
+                if Self::defer_spawn_failure(
+                    app_state,
+                    process.id,
+                    process.retry_count + 1,
+                    &e.to_string(),
+                )
+                .await
+                {
+                    return Ok(());
+                }
+                // Out of retries: the execution stands as a failure
+                ExecutionProcess::update_completion(
+                    pool,
+                    process.id,
+                    crate::models::execution_process::ExecutionProcessStatus::Failed,
+                    None,
+                )
+                .await?;
+                let reason = match &e {
+                    TaskAttemptError::Executor(e) => e.failure_reason(),
+                    _ => FailureReason::SpawnFailed,
+                };
+                ExecutionProcess::set_failure_reason(pool, process.id, reason).await?;
+                return Err(e);
+            }
+        };
+
+        Self::register_for_monitoring(
+            app_state,
+            process.id,
+            task_attempt.id,
+            task_attempt.task_id,
+            &ExecutionProcessType::CodingAgent,
+            child,
+        )
+        .await;
+        Ok(())
+    }
+
+    /// Schedule a retry of an execution that couldn't be spawned, if the configured policy
+    /// has one left. Returns whether it did.
+    pub async fn defer_spawn_failure(
+        app_state: &crate::app_state::AppState,
+        process_id: Uuid,
+        retry_count: i64,
+        reason: &str,
+    ) -> bool {
+        let policy = app_state.get_config().read().await.executor_retry.clone();
+        let Some(delay) = crate::services::spawn_retry::retry_delay(&policy, retry_count) else {
+            return false;
+        };
+        let retry_at = chrono::Utc::now()
+            + chrono::Duration::from_std(delay).unwrap_or(chrono::Duration::zero());
+        match ExecutionProcess::schedule_retry(&app_state.db_pool, process_id, retry_at).await {
+            Ok(()) => {
+                tracing::warn!(
+                    "Execution {} failed to start ({}), retrying in {}s",
+                    process_id,
+                    reason,
+                    delay.as_secs()
+                );
+                true
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Failed to schedule retry of execution {}: {}",
+                    process_id,
+                    e
+                );
+                false
+            }
+        }
+    }
+
     /// Start a dev server for this task attempt (with automatic setup)
     pub async fn start_dev_server(
-        pool: &SqlitePool,
+        pool: &DbPool,
         app_state: &crate::app_state::AppState,
         attempt_id: Uuid,
         task_id: Uuid,
@@ -290,7 +481,7 @@ impl ProcessService {
 
     /// Start a dev server directly without setup check (internal method)
     pub async fn start_dev_server_direct(
-        pool: &SqlitePool,
+        pool: &DbPool,
         app_state: &crate::app_state::AppState,
         attempt_id: Uuid,
         task_id: Uuid,
@@ -345,18 +536,93 @@ impl ProcessService {
         result
     }
 
+    /// The port a dev server is to listen on: the `PORT` configured for the task if there
+    /// is one, else a free one
+    fn dev_server_port(vars: &[(String, String)]) -> Result<u16, TaskAttemptError> {
+        if let Some((_, value)) = vars.iter().find(|(name, _)| name == "PORT") {
+            return value.trim().parse().map_err(|_| {
+                TaskAttemptError::ValidationError(format!(
+                    "PORT is set to `{}`, which is not a port number",
+                    value
+                ))
+            });
+        }
+        std::net::TcpListener::bind(("127.0.0.1", 0))
+            .and_then(|listener| listener.local_addr())
+            .map(|addr| addr.port())
+            .map_err(|e| {
+                TaskAttemptError::ValidationError(format!(
+                    "Failed to find a free port for the dev server: {}",
+                    e
+                ))
+            })
+    }
+
+    /// Stop dev servers and mark them killed, logging the ones that fail to stop
+    pub async fn stop_dev_servers(
+        app_state: &crate::app_state::AppState,
+        dev_servers: Vec<ExecutionProcess>,
+    ) {
+        for dev_server in dev_servers {
+            info!(
+                "Stopping dev server {} of task attempt {}",
+                dev_server.id, dev_server.task_attempt_id
+            );
+            if let Err(e) = app_state.stop_running_execution_by_id(dev_server.id).await {
+                tracing::error!("Failed to stop dev server {}: {}", dev_server.id, e);
+                continue;
+            }
+            if let Err(e) = ExecutionProcess::update_completion(
+                &app_state.db_pool,
+                dev_server.id,
+                ExecutionProcessStatus::Killed,
+                None,
+            )
+            .await
+            {
+                tracing::error!(
+                    "Failed to update dev server {} status: {}",
+                    dev_server.id,
+                    e
+                );
+            }
+        }
+    }
+
     /// Start a follow-up execution using the same executor type as the first process (with automatic setup)
     /// Returns the attempt_id that was actually used (always the original attempt_id for session continuity)
     pub async fn start_followup_execution(
-        pool: &SqlitePool,
+        pool: &DbPool,
         app_state: &crate::app_state::AppState,
         attempt_id: Uuid,
         task_id: Uuid,
         project_id: Uuid,
         prompt: &str,
+    ) -> Result<Uuid, TaskAttemptError> {
+        Self::start_followup_execution_with_executor(
+            pool, app_state, attempt_id, task_id, project_id, prompt, None,
+        )
+        .await
+    }
+
+    /// Start a follow-up execution (with automatic setup) with `executor`, handing the
+    /// attempt over to it if the attempt's last run was another's; `None` keeps the executor
+    #[allow(clippy::too_many_arguments)]
+    pub async fn start_followup_execution_with_executor(
+        pool: &DbPool,
+        app_state: &crate::app_state::AppState,
+        attempt_id: Uuid,
+        task_id: Uuid,
+        project_id: Uuid,
+        prompt: &str,
+        executor: Option<crate::executor::ExecutorConfig>,
     ) -> Result<Uuid, TaskAttemptError> {
         use crate::models::task::{Task, TaskStatus};
 
+        if let Some(exceeded) = guardrails::budget_exceeded(pool, project_id).await? {
+            return Err(TaskAttemptError::BudgetExceeded(exceeded));
+        }
+
         // Get the current task attempt to check if worktree is deleted
         let current_attempt = TaskAttempt::find_by_id(pool, attempt_id)
             .await?
@@ -387,7 +653,8 @@ impl ProcessService {
 
         // Use automatic setup logic with followup parameters
         let operation_params = serde_json::json!({
-            "prompt": prompt
+            "prompt": prompt,
+            "executor": executor.map(|executor| executor.to_string()),
         });
 
         Self::auto_setup_and_execute(
@@ -405,13 +672,15 @@ impl ProcessService {
     }
 
     /// Start a follow-up execution directly without setup check (internal method)
+    #[allow(clippy::too_many_arguments)]
     pub async fn start_followup_execution_direct(
-        pool: &SqlitePool,
+        pool: &DbPool,
         app_state: &crate::app_state::AppState,
         attempt_id: Uuid,
         task_id: Uuid,
         project_id: Uuid,
         prompt: &str,
+        handover_to: Option<crate::executor::ExecutorConfig>,
     ) -> Result<Uuid, TaskAttemptError> {
         // Ensure worktree exists (recreate if needed for cold task support)
         // This will resurrect the worktree at the exact same path for session continuity
@@ -474,8 +743,58 @@ impl ProcessService {
             }
         };
 
+        // Once its plan is approved, a planning attempt carries on in the same session with
+        // the executor's implementation mode
+        let executor_config = match executor_config.implementation_executor() {
+            Some(implementation) if AttemptPlan::is_approved(pool, attempt_id).await? => {
+                implementation
+            }
+            _ => executor_config,
+        };
+
+        // Handed over to another executor, the attempt can't resume a session: the new
+        // executor starts its own, given a transcript of the attempt so far, and the attempt
+        // carries on with it
+        if let Some(target) = handover_to.filter(|target| {
+            target.to_string() != executor_config.to_string()
+                && most_recent_coding_agent.executor_type.as_deref() != Some(&target.to_string())
+        }) {
+            info!(
+                "Handing attempt {} over from {} to {}",
+                attempt_id, executor_config, target
+            );
+            TaskAttempt::hand_over(pool, attempt_id, &target.to_string()).await?;
+            Self::start_process_execution(
+                pool,
+                app_state,
+                attempt_id,
+                task_id,
+                crate::executor::ExecutorType::CodingAgent {
+                    config: target,
+                    follow_up: Some(crate::executor::FollowUpInfo {
+                        session_id: String::new(),
+                        prompt: prompt.to_string(),
+                        handover: true,
+                    }),
+                },
+                "Starting follow-up executor (handed over)".to_string(),
+                ExecutionProcessType::CodingAgent,
+                &worktree_path,
+            )
+            .await?;
+            return Ok(attempt_id);
+        }
+
+        // Executors without sessions always follow up: they ignore the session ID, and the
+        // prompt carries the attempt's earlier conversation instead
+        let session_id = if executor_config.supports_sessions() {
+            executor_session.session_id.clone()
+        } else {
+            Some(executor_session.session_id.clone().unwrap_or_default())
+        };
+
         // Try to use follow-up with session ID, but fall back to new session if it fails
-        let followup_executor = if let Some(session_id) = &executor_session.session_id {
+        let followup_executor = if let Some(session_id) = &session_id {
             // First try with session ID for continuation
             debug!(
                 "SESSION_FOLLOWUP: Attempting follow-up execution with session ID: {} (attempt: {}, worktree: {})",
@@ -486,6 +805,7 @@ impl ProcessService {
                 follow_up: Some(crate::executor::FollowUpInfo {
                     session_id: session_id.clone(),
                     prompt: prompt.to_string(),
+                    handover: false,
                 }),
             }
         } else {
@@ -514,8 +834,11 @@ impl ProcessService {
         .await;
 
         // If follow-up execution failed and we tried to use a session ID,
-        // fall back to a new session
-        if execution_result.is_err() && executor_session.session_id.is_some() {
+        // fall back to a new session. A new session can't fix a broken environment.
+        if execution_result.is_err()
+            && executor_session.session_id.is_some()
+            && !matches!(execution_result, Err(TaskAttemptError::Executor(_)))
+        {
             tracing::warn!(
                 "SESSION_FOLLOWUP: Follow-up execution with session ID '{}' failed for attempt {}, falling back to new session. Error: {:?}",
                 executor_session.session_id.as_ref().unwrap(),
@@ -548,10 +871,47 @@ impl ProcessService {
         Ok(attempt_id)
     }
 
+    /// Snapshot the worktree under the run's ref and record it. A run without one just can't
+    /// be reverted, so failing to take it doesn't stop the run.
+    async fn snapshot_worktree(
+        pool: &DbPool,
+        attempt_id: Uuid,
+        process_id: Uuid,
+        worktree_path: &str,
+    ) {
+        let snapshot_ref = execution_snapshot::snapshot_ref(attempt_id, process_id);
+        let path = std::path::PathBuf::from(worktree_path);
+        let ref_name = snapshot_ref.clone();
+        let snapshot =
+            tokio::task::spawn_blocking(move || GitService::snapshot_worktree(&path, &ref_name))
+                .await;
+        match snapshot {
+            Ok(Ok(commit)) => {
+                if let Err(e) =
+                    ExecutionSnapshot::create(pool, process_id, attempt_id, &commit, &snapshot_ref)
+                        .await
+                {
+                    tracing::warn!(
+                        "Failed to record snapshot of execution {}: {}",
+                        process_id,
+                        e
+                    );
+                }
+            }
+            Ok(Err(e)) => tracing::warn!(
+                "Failed to snapshot {} before execution {}: {}",
+                worktree_path,
+                process_id,
+                e
+            ),
+            Err(e) => tracing::warn!("Snapshot before execution {} panicked: {}", process_id, e),
+        }
+    }
+
     /// Unified function to start any type of process execution
     #[allow(clippy::too_many_arguments)]
     pub async fn start_process_execution(
-        pool: &SqlitePool,
+        pool: &DbPool,
         app_state: &crate::app_state::AppState,
         attempt_id: Uuid,
         task_id: Uuid,
@@ -562,6 +922,26 @@ impl ProcessService {
     ) -> Result<(), TaskAttemptError> {
         let process_id = Uuid::new_v4();
 
+        // In dry-run mode coding agents are swapped for the scripted mock so no LLM is called
+        #[cfg(any(test, feature = "mock-executor"))]
+        let executor_type = match executor_type {
+            crate::executor::ExecutorType::CodingAgent { follow_up, .. } if app_state.dry_run => {
+                tracing::info!("Dry run: using mock executor for attempt {}", attempt_id);
+                crate::executor::ExecutorType::CodingAgent {
+                    config: crate::executor::ExecutorConfig::Mock,
+                    follow_up,
+                }
+            }
+            other => other,
+        };
+
+        // An agent this machine can't run is reported as such, before anything is recorded
+        if let crate::executor::ExecutorType::CodingAgent { config, .. } = &executor_type {
+            executor_preflight::validate(config)
+                .await
+                .map_err(|e| TaskAttemptError::Executor(Box::new(e)))?;
+        }
+
         // Create execution process record
         let _execution_process = Self::create_execution_process_record(
             pool,
@@ -572,6 +952,8 @@ impl ProcessService {
             worktree_path,
         )
         .await?;
+        // Stopping it from here on cancels the spawn too
+        app_state.register_cancellation(process_id).await;
 
         // Create executor session for coding agents
         if matches!(process_type, ExecutionProcessType::CodingAgent) {
@@ -591,6 +973,37 @@ impl ProcessService {
                 followup_prompt,
             )
             .await?;
+
+            // Note where the run starts so its own changes can be captured when it ends
+            let head = git2::Repository::open(worktree_path)
+                .and_then(|repo| Ok(repo.head()?.peel_to_commit()?.id().to_string()));
+            match head {
+                Ok(head) => {
+                    if let Err(e) = ExecutionDiff::record_start(pool, process_id, &head).await {
+                        tracing::warn!("Failed to record start of execution {}: {}", process_id, e);
+                    }
+                }
+                Err(e) => tracing::warn!(
+                    "Failed to read HEAD of {} for execution {}: {}",
+                    worktree_path,
+                    process_id,
+                    e
+                ),
+            }
+
+            // Keep the worktree as the run finds it, so its changes alone can be reverted
+            Self::snapshot_worktree(pool, attempt_id, process_id, worktree_path).await;
+        }
+
+        // The executor picks up the task's images itself, as files where its CLI takes them
+        // and as paths in the prompt otherwise; remember which it was given so the run's
+        // conversation can show them
+        if let crate::executor::ExecutorType::CodingAgent {
+            follow_up: None, ..
+        } = &executor_type
+        {
+            let images = TaskAttachment::find_images_by_task_id(pool, task_id).await?;
+            TaskAttachment::record_for_execution_process(pool, process_id, &images).await?;
         }
 
         // Process started successfully
@@ -598,19 +1011,65 @@ impl ProcessService {
         tracing::info!("Starting {} for task attempt {}", activity_note, attempt_id);
 
         // Execute the process
-        let child = Self::execute_process(
+        let child = match Self::spawn_cancellable(
+            app_state,
             &executor_type,
-            pool,
             task_id,
             attempt_id,
             process_id,
             worktree_path,
         )
-        .await?;
+        .await
+        {
+            Ok(Some(child)) => child,
+            Ok(None) => return Ok(()),
+            // A first run that can't be spawned is tried again later; follow-ups fall back
+            // to a fresh session instead
+            Err(e)
+                if matches!(
+                    executor_type,
+                    crate::executor::ExecutorType::CodingAgent {
+                        follow_up: None,
+                        ..
+                    }
+                ) && Self::defer_spawn_failure(app_state, process_id, 0, &e.to_string())
+                    .await =>
+            {
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
 
         // Register for monitoring
-        Self::register_for_monitoring(app_state, process_id, attempt_id, &process_type, child)
-            .await;
+        Self::register_for_monitoring(
+            app_state,
+            process_id,
+            attempt_id,
+            task_id,
+            &process_type,
+            child,
+        )
+        .await;
+
+        // Agents whose CLI asks before running each command have the dangerous ones held
+        // for the user. ACP agents are carried through their turn, asking the same way.
+        if let crate::executor::ExecutorType::CodingAgent { config, .. } = &executor_type {
+            if matches!(config, crate::executor::ExecutorConfig::Acp { .. }) {
+                tokio::spawn(acp_session::drive(app_state.clone(), process_id, task_id));
+            } else if config
+                .create_executor()
+                .command_approval_reply(true)
+                .is_some()
+            {
+                tokio::spawn(command_approval::watch(
+                    app_state.clone(),
+                    process_id,
+                    task_id,
+                    config.clone(),
+                    worktree_path.to_string(),
+                ));
+            }
+        }
 
         tracing::info!(
             "Started execution {} for task attempt {}",
@@ -620,9 +1079,10 @@ impl ProcessService {
         Ok(())
     }
 
-    /// Load the execution context (task attempt and project) with validation
+    /// Load the execution context (task attempt and project) with validation. The project
+    /// has the settings its repository's `.vibe-kanban.toml` fills in.
     async fn load_execution_context(
-        pool: &SqlitePool,
+        pool: &DbPool,
         attempt_id: Uuid,
         project_id: Uuid,
     ) -> Result<(TaskAttempt, Project), TaskAttemptError> {
@@ -630,9 +1090,10 @@ impl ProcessService {
             .await?
             .ok_or(TaskAttemptError::TaskNotFound)?;
 
-        let project = Project::find_by_id(pool, project_id)
+        let mut project = Project::find_by_id(pool, project_id)
             .await?
             .ok_or(TaskAttemptError::ProjectNotFound)?;
+        repo_config::apply(&mut project).map_err(TaskAttemptError::ValidationError)?;
 
         Ok((task_attempt, project))
     }
@@ -656,7 +1117,7 @@ impl ProcessService {
 
     /// Start the setup script execution
     async fn start_setup_script(
-        pool: &SqlitePool,
+        pool: &DbPool,
         app_state: &crate::app_state::AppState,
         attempt_id: Uuid,
         task_id: Uuid,
@@ -680,7 +1141,7 @@ impl ProcessService {
 
     /// Start the cleanup script execution
     async fn start_cleanup_script(
-        pool: &SqlitePool,
+        pool: &DbPool,
         app_state: &crate::app_state::AppState,
         attempt_id: Uuid,
         task_id: Uuid,
@@ -702,6 +1163,26 @@ impl ProcessService {
         .await
     }
 
+    /// The executor a coding agent runs with, from the name stored on its attempt
+    async fn coding_agent_config(
+        app_state: &crate::app_state::AppState,
+        executor_name: &Option<String>,
+    ) -> crate::executor::ExecutorConfig {
+        let executor_config = Self::resolve_executor_config(executor_name);
+
+        // The attempt only stores the executor name, so take the recording from the
+        // configured replay executor
+        if let crate::executor::ExecutorConfig::Replay { recording_path, .. } = &executor_config {
+            if recording_path.is_empty() {
+                let configured = app_state.get_config().read().await.executor.clone();
+                if matches!(configured, crate::executor::ExecutorConfig::Replay { .. }) {
+                    return configured;
+                }
+            }
+        }
+        executor_config
+    }
+
     /// Resolve executor configuration from string name
     fn resolve_executor_config(executor_name: &Option<String>) -> crate::executor::ExecutorConfig {
         if let Some(name) = executor_name {
@@ -720,7 +1201,7 @@ impl ProcessService {
 
     /// Create execution process database record
     async fn create_execution_process_record(
-        pool: &SqlitePool,
+        pool: &DbPool,
         attempt_id: Uuid,
         process_id: Uuid,
         executor_type: &crate::executor::ExecutorType,
@@ -770,7 +1251,7 @@ impl ProcessService {
 
     /// Create executor session record for coding agents
     async fn create_executor_session_record(
-        pool: &SqlitePool,
+        pool: &DbPool,
         attempt_id: Uuid,
         task_id: Uuid,
         process_id: Uuid,
@@ -799,65 +1280,287 @@ impl ProcessService {
             .map_err(TaskAttemptError::from)
     }
 
+    /// Spawn the execution tied to its cancellation token. `Ok(None)` if it was stopped while
+    /// it spawned: what did start is killed and the execution stands as killed.
+    async fn spawn_cancellable(
+        app_state: &crate::app_state::AppState,
+        executor_type: &crate::executor::ExecutorType,
+        task_id: Uuid,
+        attempt_id: Uuid,
+        process_id: Uuid,
+        worktree_path: &str,
+    ) -> Result<Option<command_runner::CommandProcess>, TaskAttemptError> {
+        let pool = &app_state.db_pool;
+        let cancellation = app_state.cancellation(process_id).await;
+        let spawned = Self::execute_process(
+            executor_type,
+            pool,
+            task_id,
+            attempt_id,
+            process_id,
+            worktree_path,
+            cancellation.clone(),
+        )
+        .await;
+        if !cancellation.is_cancelled() {
+            if spawned.is_err() {
+                app_state.forget_cancellation(process_id).await;
+            }
+            return spawned.map(Some);
+        }
+
+        tracing::info!("Execution {} was stopped while it spawned", process_id);
+        if let Ok(mut child) = spawned {
+            if let Err(e) = child.kill_if_cancelled().await {
+                tracing::error!("Failed to kill cancelled execution {}: {}", process_id, e);
+            }
+            environment_variables::forget_redactions(process_id);
+        }
+        ExecutionProcess::update_completion(pool, process_id, ExecutionProcessStatus::Killed, None)
+            .await?;
+        Ok(None)
+    }
+
     /// Execute the process based on type
     async fn execute_process(
         executor_type: &crate::executor::ExecutorType,
-        pool: &SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
         attempt_id: Uuid,
         process_id: Uuid,
         worktree_path: &str,
+        cancellation: tokio_util::sync::CancellationToken,
     ) -> Result<command_runner::CommandProcess, TaskAttemptError> {
         use crate::executors::{CleanupScriptExecutor, DevServerExecutor, SetupScriptExecutor};
 
-        let result = match executor_type {
-            crate::executor::ExecutorType::SetupScript(script) => {
-                let executor = SetupScriptExecutor {
-                    script: script.clone(),
-                };
-                executor
-                    .execute_streaming(pool, task_id, attempt_id, process_id, worktree_path)
-                    .await
+        let task = Task::find_by_id(pool, task_id)
+            .await?
+            .ok_or(TaskAttemptError::TaskNotFound)?;
+        let mut environment =
+            environment_variables::resolve(pool, &task)
+                .await
+                .map_err(|e| match e {
+                    EnvironmentError::Database(e) => TaskAttemptError::Database(e),
+                    e => TaskAttemptError::ValidationError(e.to_string()),
+                })?;
+        environment_variables::register_redactions(process_id, environment.secrets);
+
+        if let crate::executor::ExecutorType::DevServer(_) = executor_type {
+            let port = Self::dev_server_port(&environment.vars)?;
+            environment
+                .vars
+                .retain(|(name, _)| name != "PORT" && name != "VK_DEV_SERVER_PORT");
+            environment
+                .vars
+                .push(("PORT".to_string(), port.to_string()));
+            environment
+                .vars
+                .push(("VK_DEV_SERVER_PORT".to_string(), port.to_string()));
+            DevServerPort::create(pool, process_id, port).await?;
+        }
+
+        let settings = Config::load(&crate::utils::config_path()).unwrap_or_else(|e| {
+            tracing::warn!(
+                "Failed to load config for the sandbox, resource limit and context budget settings: {}",
+                e
+            );
+            Config::default()
+        });
+
+        // Coding agents of a multi-repository task work in the workspace holding all of its
+        // checkouts; scripts run in the task's own repository
+        let agent_workspace = match executor_type {
+            crate::executor::ExecutorType::CodingAgent { .. } => {
+                TaskAttempt::agent_workspace(pool, attempt_id, worktree_path).await?
             }
-            crate::executor::ExecutorType::CleanupScript(script) => {
-                let executor = CleanupScriptExecutor {
-                    script: script.clone(),
-                };
-                executor
-                    .execute_streaming(pool, task_id, attempt_id, process_id, worktree_path)
-                    .await
+            _ => worktree_path.to_string(),
+        };
+
+        // Only coding agents are sandboxed; scripts run the project's own commands
+        let sandbox = match executor_type {
+            crate::executor::ExecutorType::CodingAgent { config, .. } => {
+                sandbox::for_coding_agent(
+                    pool,
+                    &settings.sandbox,
+                    task.project_id,
+                    &config.to_string(),
+                    &agent_workspace,
+                )
+                .await?
             }
-            crate::executor::ExecutorType::DevServer(script) => {
-                let executor = DevServerExecutor {
-                    script: script.clone(),
-                };
-                executor
-                    .execute_streaming(pool, task_id, attempt_id, process_id, worktree_path)
-                    .await
+            _ => None,
+        };
+
+        // The attempt's profile is read as each of its agents starts, so edits to it apply
+        // from the next run on. The attempt's own model wins over the profile's, and that
+        // over the executor's default from the settings.
+        let mut agent_options = crate::executor::AgentOptions::default();
+        if let crate::executor::ExecutorType::CodingAgent { config, follow_up } = executor_type {
+            let attempt = TaskAttempt::find_by_id(pool, attempt_id).await?;
+            let profile = match attempt.as_ref().and_then(|a| a.executor_profile_id) {
+                Some(profile_id) => ExecutorProfile::find_by_id(pool, profile_id).await?,
+                None => None,
+            };
+            if let Some(profile) = &profile {
+                for (name, value) in &profile.env {
+                    if !environment.vars.iter().any(|(var, _)| var == name) {
+                        environment.vars.push((name.clone(), value.clone()));
+                    }
+                }
             }
-            crate::executor::ExecutorType::CodingAgent { config, follow_up } => {
-                let executor = config.create_executor();
+            if config.supports_model() {
+                agent_options.model = attempt
+                    .and_then(|attempt| attempt.model)
+                    .or_else(|| profile.as_ref().and_then(|profile| profile.model()))
+                    .or_else(|| {
+                        settings
+                            .executor_models
+                            .get(&config.to_string())
+                            .map(|model| model.trim().to_string())
+                            .filter(|model| crate::executor::is_valid_model_name(model))
+                    });
+                agent_options.extra_args = profile
+                    .map(|profile| profile.extra_args)
+                    .unwrap_or_default();
+            }
+            agent_options.context_tokens =
+                Some(settings.context_budget.max_tokens_for(&config.to_string()));
+            // An executor taking over the attempt starts out with what was done before it
+            if let Some(follow_up) = follow_up.as_ref().filter(|f| f.handover) {
+                agent_options.prompt = Some(
+                    conversation_transcript::prompt_with_transcript(
+                        pool,
+                        attempt_id,
+                        &follow_up.prompt,
+                        settings.context_budget.max_tokens_for(&config.to_string()),
+                    )
+                    .await,
+                );
+            }
+        }
+        if let Some(model) = &agent_options.model {
+            ExecutionProcess::set_model(pool, process_id, model).await?;
+        }
+
+        // Dev servers stay on the host, where their port can be reached
+        let (worker, container) = match executor_type {
+            crate::executor::ExecutorType::DevServer(_) => (None, None),
+            _ => (
+                ProjectWorker::find_by_project_id(pool, task.project_id)
+                    .await?
+                    .map(command_runner::Worker::from),
+                ProjectContainer::find_by_project_id(pool, task.project_id)
+                    .await?
+                    .map(|project_container| command_runner::Container {
+                        image: project_container.image,
+                        mounts: sandbox::writable_paths(std::path::Path::new(&agent_workspace)),
+                    }),
+            ),
+        };
+
+        // Dev servers run as long as they're wanted, however much they use
+        let limits = match executor_type {
+            crate::executor::ExecutorType::DevServer(_) => {
+                command_runner::ResourceLimits::default()
+            }
+            _ => settings.resource_limits.to_limits(),
+        };
+
+        // Commands run here start from the environment the user's shell would give them, with
+        // the project's variables on top
+        if worker.is_none() && container.is_none() {
+            let shell_environment: ShellEnvironmentConfig =
+                match ProjectShellEnvironment::find_by_project_id(pool, task.project_id).await? {
+                    Some(project) => project.into(),
+                    None => settings.shell_environment.clone(),
+                };
+            let mut vars = shell_environment
+                .bootstrap(std::path::Path::new(worktree_path))
+                .await;
+            vars.retain(|(name, _)| !environment.vars.iter().any(|(set, _)| set == name));
+            vars.append(&mut environment.vars);
+            environment.vars = vars;
+        }
 
-                if let Some(ref follow_up_info) = follow_up {
+        let run = command_runner::with_env(environment.vars, async {
+            match executor_type {
+                crate::executor::ExecutorType::SetupScript(script) => {
+                    let executor = SetupScriptExecutor {
+                        script: script.clone(),
+                    };
                     executor
-                        .execute_followup_streaming(
-                            pool,
-                            task_id,
-                            attempt_id,
-                            process_id,
-                            &follow_up_info.session_id,
-                            &follow_up_info.prompt,
-                            worktree_path,
-                        )
+                        .execute_streaming(pool, task_id, attempt_id, process_id, worktree_path)
                         .await
-                } else {
+                }
+                crate::executor::ExecutorType::CleanupScript(script) => {
+                    let executor = CleanupScriptExecutor {
+                        script: script.clone(),
+                    };
                     executor
                         .execute_streaming(pool, task_id, attempt_id, process_id, worktree_path)
                         .await
                 }
+                crate::executor::ExecutorType::DevServer(script) => {
+                    let executor = DevServerExecutor {
+                        script: script.clone(),
+                    };
+                    executor
+                        .execute_streaming(pool, task_id, attempt_id, process_id, worktree_path)
+                        .await
+                }
+                crate::executor::ExecutorType::CodingAgent { config, follow_up } => {
+                    let executor = config.create_executor();
+
+                    if let Some(follow_up_info) = follow_up.as_ref().filter(|f| !f.handover) {
+                        let prompt = if config.supports_sessions() {
+                            follow_up_info.prompt.clone()
+                        } else {
+                            conversation_transcript::prompt_with_transcript(
+                                pool,
+                                attempt_id,
+                                &follow_up_info.prompt,
+                                settings.context_budget.max_tokens_for(&config.to_string()),
+                            )
+                            .await
+                        };
+                        executor
+                            .execute_followup_streaming(
+                                pool,
+                                task_id,
+                                attempt_id,
+                                process_id,
+                                &follow_up_info.session_id,
+                                &prompt,
+                                &agent_workspace,
+                            )
+                            .await
+                    } else {
+                        executor
+                            .execute_streaming(
+                                pool,
+                                task_id,
+                                attempt_id,
+                                process_id,
+                                &agent_workspace,
+                            )
+                            .await
+                    }
+                }
             }
+        });
+        let run = crate::executor::with_agent_options(agent_options, run);
+        let run = command_runner::with_limits(limits, run);
+        let run = command_runner::with_cancellation(cancellation, run);
+        let result = match (worker, container, sandbox) {
+            (Some(worker), _, _) => command_runner::with_worker(worker, run).await,
+            (None, Some(container), _) => command_runner::with_container(container, run).await,
+            (None, None, Some(sandbox)) => command_runner::with_sandbox(sandbox, run).await,
+            (None, None, None) => run.await,
         };
 
+        if result.is_err() {
+            environment_variables::forget_redactions(process_id);
+        }
         result.map_err(|e| TaskAttemptError::Git(git2::Error::from_str(&e.to_string())))
     }
 
@@ -866,9 +1569,14 @@ impl ProcessService {
         app_state: &crate::app_state::AppState,
         process_id: Uuid,
         attempt_id: Uuid,
+        task_id: Uuid,
         process_type: &ExecutionProcessType,
-        child: command_runner::CommandProcess,
+        mut child: command_runner::CommandProcess,
     ) {
+        if let Some(timeout) = Self::execution_timeout(app_state, task_id, process_type).await {
+            child.set_timeout(timeout);
+        }
+
         let execution_type = match process_type {
             ExecutionProcessType::SetupScript => crate::app_state::ExecutionType::SetupScript,
             ExecutionProcessType::CleanupScript => crate::app_state::ExecutionType::CleanupScript,
@@ -876,6 +1584,23 @@ impl ProcessService {
             ExecutionProcessType::DevServer => crate::app_state::ExecutionType::DevServer,
         };
 
+        if let Some(process_group) = child.process_group() {
+            if let Err(e) = ExecutionProcess::set_process_group(
+                &app_state.db_pool,
+                process_id,
+                i64::from(process_group),
+                execution_recovery::process_identity(process_group),
+            )
+            .await
+            {
+                tracing::warn!(
+                    "Failed to keep the process group of execution {}: {}",
+                    process_id,
+                    e
+                );
+            }
+        }
+
         app_state
             .add_running_execution(
                 process_id,
@@ -883,14 +1608,47 @@ impl ProcessService {
                     task_attempt_id: attempt_id,
                     _execution_type: execution_type,
                     child,
+                    normalizer: None,
+                    approval: None,
                 },
             )
             .await;
     }
 
+    /// How long an execution may run before the monitor kills it: the task's own limit if
+    /// it has one, otherwise the configured default. Dev servers are meant to keep running.
+    async fn execution_timeout(
+        app_state: &crate::app_state::AppState,
+        task_id: Uuid,
+        process_type: &ExecutionProcessType,
+    ) -> Option<std::time::Duration> {
+        if matches!(process_type, ExecutionProcessType::DevServer) {
+            return None;
+        }
+        let task_minutes = match Task::find_by_id(&app_state.db_pool, task_id).await {
+            Ok(task) => task.and_then(|task| task.timeout_minutes),
+            Err(e) => {
+                tracing::error!("Failed to look up timeout for task {}: {}", task_id, e);
+                None
+            }
+        };
+        let minutes = match task_minutes {
+            Some(minutes) => u64::try_from(minutes).ok(),
+            None => app_state
+                .get_config()
+                .read()
+                .await
+                .execution_timeout_minutes
+                .map(u64::from),
+        };
+        minutes
+            .filter(|&minutes| minutes > 0)
+            .map(|minutes| std::time::Duration::from_secs(minutes * 60))
+    }
+
     /// Create execution process database record with delegation context
     async fn create_execution_process_record_with_delegation(
-        pool: &SqlitePool,
+        pool: &DbPool,
         attempt_id: Uuid,
         process_id: Uuid,
         _setup_script: &str,
@@ -924,7 +1682,7 @@ impl ProcessService {
     /// Execute setup script process specifically
     async fn execute_setup_script_process(
         setup_script: &str,
-        pool: &SqlitePool,
+        pool: &DbPool,
         task_id: Uuid,
         attempt_id: Uuid,
         process_id: Uuid,
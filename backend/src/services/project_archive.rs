@@ -0,0 +1,328 @@
+//! Portable project archives: a project's tasks with their attempts, executions and agent
+//! conversations, plus its task templates, as one JSON document that can be imported into a
+//! project on another machine. Imported rows get fresh ids; worktrees aren't part of the
+//! archive, so imported attempts have none until they are picked up again.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    executor::NormalizedConversation,
+    models::{
+        execution_process::ExecutionProcess,
+        project::Project,
+        task::{CreateTask, Task, TaskStatus},
+        task_attempt::TaskAttempt,
+        task_template::{CreateTaskTemplate, TaskTemplate},
+    },
+    routes::task_attempts::normalize_process_logs,
+};
+
+/// Bumped when the archive layout changes in a way older versions can't import
+pub const ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum ProjectArchiveError {
+    UnsupportedVersion(u32),
+    Database(sqlx::Error),
+}
+
+impl std::fmt::Display for ProjectArchiveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProjectArchiveError::UnsupportedVersion(version) => write!(
+                f,
+                "Archive format version {} is not supported (expected {} or older)",
+                version, ARCHIVE_FORMAT_VERSION
+            ),
+            ProjectArchiveError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ProjectArchiveError {}
+
+impl From<sqlx::Error> for ProjectArchiveError {
+    fn from(e: sqlx::Error) -> Self {
+        ProjectArchiveError::Database(e)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProjectArchive {
+    pub format_version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub project_name: String,
+    pub tasks: Vec<ArchivedTask>,
+    pub templates: Vec<TaskTemplate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ArchivedTask {
+    #[serde(flatten)]
+    pub task: Task,
+    pub attempts: Vec<ArchivedAttempt>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ArchivedAttempt {
+    #[serde(flatten)]
+    pub attempt: TaskAttempt,
+    pub executions: Vec<ArchivedExecution>,
+}
+
+/// An execution with its conversation. Its raw stdout and stderr are only kept when the
+/// export asked for logs.
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ArchivedExecution {
+    #[serde(flatten)]
+    pub process: ExecutionProcess,
+    pub conversation: Option<NormalizedConversation>,
+}
+
+#[derive(Debug, Default, Serialize, TS)]
+#[ts(export)]
+pub struct ProjectImportResult {
+    pub tasks_created: usize,
+    pub attempts_created: usize,
+    pub executions_created: usize,
+    /// Templates whose name the project already had are skipped
+    pub templates_created: usize,
+}
+
+/// The project as an archive. Conversations are always included; `include_logs` also keeps
+/// each execution's raw output, which makes the archive much larger.
+pub async fn export(
+    pool: &DbPool,
+    project: &Project,
+    include_logs: bool,
+) -> Result<ProjectArchive, sqlx::Error> {
+    let mut tasks = Vec::new();
+    for task in Task::find_by_project_id(pool, project.id).await? {
+        let mut attempts = Vec::new();
+        for attempt in TaskAttempt::find_by_task_id(pool, task.id).await? {
+            let mut executions = Vec::new();
+            for mut process in ExecutionProcess::find_by_task_attempt_id(pool, attempt.id).await? {
+                let conversation = normalize_process_logs(pool, &process).await;
+                let conversation = (!conversation.entries.is_empty()).then_some(conversation);
+                if !include_logs {
+                    process.stdout = None;
+                    process.stderr = None;
+                }
+                executions.push(ArchivedExecution {
+                    process,
+                    conversation,
+                });
+            }
+            attempts.push(ArchivedAttempt {
+                attempt,
+                executions,
+            });
+        }
+        tasks.push(ArchivedTask { task, attempts });
+    }
+
+    Ok(ProjectArchive {
+        format_version: ARCHIVE_FORMAT_VERSION,
+        exported_at: Utc::now(),
+        project_name: project.name.clone(),
+        tasks,
+        templates: TaskTemplate::find_by_project_id(pool, Some(project.id)).await?,
+    })
+}
+
+/// Tasks ordered so each comes after the task it is a subtask of. A task whose parent isn't
+/// in the archive goes first and is imported as a top-level task.
+fn parents_first(tasks: &[ArchivedTask]) -> Vec<&ArchivedTask> {
+    let ids: HashSet<Uuid> = tasks.iter().map(|archived| archived.task.id).collect();
+    let mut placed = HashSet::new();
+    let mut ordered = Vec::with_capacity(tasks.len());
+    let mut remaining: Vec<&ArchivedTask> = tasks.iter().collect();
+    while !remaining.is_empty() {
+        let before = remaining.len();
+        remaining.retain(|archived| {
+            let ready = archived
+                .task
+                .parent_task_id
+                .is_none_or(|parent| !ids.contains(&parent) || placed.contains(&parent));
+            if ready {
+                placed.insert(archived.task.id);
+                ordered.push(*archived);
+            }
+            !ready
+        });
+        // A cycle can only come from a hand-edited archive; break it rather than loop
+        if remaining.len() == before {
+            ordered.append(&mut remaining);
+        }
+    }
+    ordered
+}
+
+/// Re-create the archive's tasks, attempts, executions and templates in `project_id`
+pub async fn import(
+    pool: &DbPool,
+    project_id: Uuid,
+    archive: &ProjectArchive,
+) -> Result<ProjectImportResult, ProjectArchiveError> {
+    if archive.format_version > ARCHIVE_FORMAT_VERSION {
+        return Err(ProjectArchiveError::UnsupportedVersion(
+            archive.format_version,
+        ));
+    }
+
+    let mut result = ProjectImportResult::default();
+    let mut task_ids = HashMap::new();
+    let mut attempt_ids = HashMap::new();
+
+    for archived in parents_first(&archive.tasks) {
+        let task = Task::create(
+            pool,
+            &CreateTask {
+                project_id,
+                title: archived.task.title.clone(),
+                description: archived.task.description.clone(),
+                parent_task_attempt: None,
+                parent_task_id: archived
+                    .task
+                    .parent_task_id
+                    .and_then(|parent| task_ids.get(&parent).copied()),
+            },
+            Uuid::new_v4(),
+        )
+        .await?;
+        if archived.task.timeout_minutes.is_some() {
+            Task::set_timeout_minutes(pool, task.id, project_id, archived.task.timeout_minutes)
+                .await?;
+        }
+        if archived.task.priority.is_some() {
+            Task::set_priority(pool, task.id, project_id, archived.task.priority).await?;
+        }
+        task_ids.insert(archived.task.id, task.id);
+        result.tasks_created += 1;
+
+        for archived_attempt in &archived.attempts {
+            let attempt =
+                TaskAttempt::create_imported(pool, task.id, &archived_attempt.attempt).await?;
+            attempt_ids.insert(archived_attempt.attempt.id, attempt.id);
+            result.attempts_created += 1;
+
+            for execution in &archived_attempt.executions {
+                ExecutionProcess::create_imported(pool, attempt.id, &execution.process).await?;
+                result.executions_created += 1;
+            }
+        }
+    }
+
+    // Statuses and parent attempts are restored once every attempt they may point at exists
+    for archived in &archive.tasks {
+        let parent_task_attempt = archived
+            .task
+            .parent_task_attempt
+            .and_then(|parent| attempt_ids.get(&parent).copied());
+        if archived.task.status == TaskStatus::Todo && parent_task_attempt.is_none() {
+            continue;
+        }
+        Task::update(
+            pool,
+            task_ids[&archived.task.id],
+            project_id,
+            archived.task.title.clone(),
+            archived.task.description.clone(),
+            archived.task.status.clone(),
+            parent_task_attempt,
+        )
+        .await?;
+    }
+
+    let existing: HashSet<String> = TaskTemplate::find_by_project_id(pool, Some(project_id))
+        .await?
+        .into_iter()
+        .map(|template| template.template_name)
+        .collect();
+    for template in &archive.templates {
+        if existing.contains(&template.template_name) {
+            continue;
+        }
+        TaskTemplate::create(
+            pool,
+            &CreateTaskTemplate {
+                project_id: Some(project_id),
+                title: template.title.clone(),
+                description: template.description.clone(),
+                template_name: template.template_name.clone(),
+                default_executor: template.default_executor.clone(),
+                verification_script: template.verification_script.clone(),
+                checklist: template.checklist.clone(),
+            },
+        )
+        .await?;
+        result.templates_created += 1;
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn archived_task(id: Uuid, parent_task_id: Option<Uuid>) -> ArchivedTask {
+        ArchivedTask {
+            task: Task {
+                id,
+                project_id: Uuid::nil(),
+                title: id.to_string(),
+                description: None,
+                status: TaskStatus::Todo,
+                parent_task_attempt: None,
+                parent_task_id,
+                timeout_minutes: None,
+                priority: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            },
+            attempts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_parents_are_ordered_before_their_subtasks() {
+        let (parent, child, grandchild, orphan) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        let tasks = vec![
+            archived_task(grandchild, Some(child)),
+            archived_task(child, Some(parent)),
+            archived_task(orphan, Some(Uuid::new_v4())),
+            archived_task(parent, None),
+        ];
+        let order: Vec<Uuid> = parents_first(&tasks)
+            .iter()
+            .map(|archived| archived.task.id)
+            .collect();
+        let position = |id| order.iter().position(|&o| o == id).unwrap();
+        assert_eq!(order.len(), 4);
+        assert!(position(parent) < position(child));
+        assert!(position(child) < position(grandchild));
+    }
+
+    #[test]
+    fn test_cycles_are_broken_rather_than_dropped() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let tasks = vec![archived_task(a, Some(b)), archived_task(b, Some(a))];
+        assert_eq!(parents_first(&tasks).len(), 2);
+    }
+}
@@ -0,0 +1,68 @@
+//! Invites to join a project. A project admin creates one with a role and sends its link;
+//! whoever opens it joins with that role, signing in first or creating an account. Each
+//! invite works once, and its token is kept only as a hash.
+
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    models::{
+        project_invite::{CreateProjectInvite, CreatedProjectInvite, ProjectInvite},
+        project_member::{ProjectMember, ProjectMembership},
+    },
+    services::accounts,
+};
+
+pub const INVITE_TOKEN_PREFIX: &str = "vki_";
+/// How much of an invite's token is kept in the clear to tell invites apart
+const INVITE_TOKEN_SHOWN_LENGTH: usize = 12;
+const DEFAULT_EXPIRY_DAYS: u32 = 7;
+
+pub async fn create(
+    pool: &DbPool,
+    project_id: Uuid,
+    invited_by: Option<Uuid>,
+    data: &CreateProjectInvite,
+) -> Result<CreatedProjectInvite, sqlx::Error> {
+    let token = format!("{}{}", INVITE_TOKEN_PREFIX, accounts::random_token());
+    let days = data.expires_in_days.unwrap_or(DEFAULT_EXPIRY_DAYS).max(1);
+    let invite = ProjectInvite::create(
+        pool,
+        project_id,
+        data.role,
+        invited_by,
+        &accounts::token_hash(&token),
+        &token[..INVITE_TOKEN_SHOWN_LENGTH],
+        Utc::now() + Duration::days(i64::from(days)),
+    )
+    .await?;
+    Ok(CreatedProjectInvite { invite, token })
+}
+
+/// The invite the token is for, if it can still be accepted
+pub async fn find_pending(
+    pool: &DbPool,
+    token: &str,
+) -> Result<Option<ProjectInvite>, sqlx::Error> {
+    ProjectInvite::find_pending_by_hash(pool, &accounts::token_hash(token), Utc::now()).await
+}
+
+/// Use up the invite and give the user its role in the project. A user who already has a
+/// higher role keeps it. `None` if someone else used the invite first or it has expired.
+pub async fn accept(
+    pool: &DbPool,
+    invite: &ProjectInvite,
+    user_id: Uuid,
+) -> Result<Option<ProjectMembership>, sqlx::Error> {
+    if !ProjectInvite::accept(pool, invite.id, user_id, Utc::now()).await? {
+        return Ok(None);
+    }
+    let current = ProjectMember::find_role(pool, invite.project_id, user_id).await?;
+    let role = current.map_or(invite.role, |current| current.max(invite.role));
+    ProjectMember::upsert(pool, invite.project_id, user_id, role).await?;
+    Ok(Some(ProjectMembership {
+        project_id: invite.project_id,
+        role,
+    }))
+}
@@ -0,0 +1,329 @@
+//! Project context a task's description can pull into the agent's prompt. Writing
+//! `{{recent_commits}}`, `{{readme}}`, `{{toolchain}}`, `{{repo_map}}` or `{{ci_failures}}`
+//! in a task (or a template) fills in that context from the attempt's worktree when the
+//! agent starts. Only the variables a description actually uses are computed.
+
+use std::{fs, path::Path, time::Duration};
+
+use git2::{Repository, Sort};
+
+use crate::{
+    services::{
+        git_service::GitService,
+        github_service::{GitHubRepoInfo, GitHubService},
+        repo_analysis,
+    },
+    utils::text::fill_placeholders,
+};
+
+pub const CONTEXT_VARIABLES: &[&str] = &[
+    "recent_commits",
+    "readme",
+    "toolchain",
+    "repo_map",
+    "ci_failures",
+];
+
+const RECENT_COMMITS: usize = 10;
+const README_MAX_LINES: usize = 60;
+const README_MAX_CHARS: usize = 4_000;
+const CHECKS_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Directories never worth looking into for toolchain markers
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "dist", "build", "vendor"];
+
+/// Marker file and the toolchain it stands for
+const TOOLCHAIN_MARKERS: &[(&str, &str)] = &[
+    ("Cargo.toml", "Rust (cargo)"),
+    ("package.json", "JavaScript/TypeScript"),
+    ("pyproject.toml", "Python"),
+    ("requirements.txt", "Python (pip)"),
+    ("setup.py", "Python"),
+    ("go.mod", "Go"),
+    ("pom.xml", "Java (Maven)"),
+    ("build.gradle", "JVM (Gradle)"),
+    ("build.gradle.kts", "JVM (Gradle)"),
+    ("Gemfile", "Ruby (Bundler)"),
+    ("composer.json", "PHP (Composer)"),
+    ("mix.exs", "Elixir (Mix)"),
+    ("Package.swift", "Swift (SwiftPM)"),
+    ("CMakeLists.txt", "C/C++ (CMake)"),
+    ("deno.json", "Deno"),
+];
+
+/// Lockfiles that tell which package manager a JavaScript project uses
+const JS_PACKAGE_MANAGERS: &[(&str, &str)] = &[
+    ("pnpm-lock.yaml", "pnpm"),
+    ("yarn.lock", "yarn"),
+    ("bun.lockb", "bun"),
+    ("package-lock.json", "npm"),
+];
+
+fn uses(text: &str, variable: &str) -> bool {
+    text.contains(&format!("{{{{{}}}}}", variable))
+}
+
+/// Fill in the context variables `text` uses from the worktree. A variable whose context
+/// can't be gathered becomes empty rather than staying a placeholder.
+pub async fn fill_context_variables(
+    text: &str,
+    worktree: &Path,
+    github: Option<(&str, Option<&str>)>,
+) -> String {
+    if !CONTEXT_VARIABLES
+        .iter()
+        .any(|variable| uses(text, variable))
+    {
+        return text.to_string();
+    }
+
+    let mut values = Vec::new();
+    if uses(text, "recent_commits") {
+        values.push((
+            "recent_commits",
+            recent_commits(worktree).unwrap_or_default(),
+        ));
+    }
+    if uses(text, "readme") {
+        values.push(("readme", readme_excerpt(worktree).unwrap_or_default()));
+    }
+    if uses(text, "toolchain") {
+        values.push(("toolchain", detect_toolchain(worktree)));
+    }
+    if uses(text, "repo_map") {
+        values.push(("repo_map", repo_map(worktree).await.unwrap_or_default()));
+    }
+    if uses(text, "ci_failures") {
+        let summary = match github {
+            Some((token, enterprise_url)) => ci_failures(worktree, token, enterprise_url).await,
+            None => None,
+        };
+        values.push(("ci_failures", summary.unwrap_or_default()));
+    }
+    fill_placeholders(text, &values)
+}
+
+fn recent_commits(worktree: &Path) -> Option<String> {
+    let repo = Repository::open(worktree).ok()?;
+    let mut walk = repo.revwalk().ok()?;
+    walk.push_head().ok()?;
+    walk.set_sorting(Sort::TIME).ok()?;
+
+    let lines: Vec<String> = walk
+        .flatten()
+        .take(RECENT_COMMITS)
+        .filter_map(|oid| {
+            let commit = repo.find_commit(oid).ok()?;
+            let date = chrono::DateTime::from_timestamp(commit.time().seconds(), 0)?;
+            Some(format!(
+                "- {} {} ({}, {})",
+                &oid.to_string()[..7],
+                commit.summary().unwrap_or_default(),
+                commit.author().name().unwrap_or("unknown"),
+                date.format("%Y-%m-%d")
+            ))
+        })
+        .collect();
+    Some(lines.join("\n"))
+}
+
+fn readme_excerpt(worktree: &Path) -> Option<String> {
+    let entries = fs::read_dir(worktree).ok()?;
+    let mut readmes: Vec<_> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .to_ascii_lowercase()
+                .starts_with("readme")
+        })
+        .map(|entry| entry.path())
+        .collect();
+    // Prefer README.md over README.txt and the like
+    readmes.sort_by_key(|path| path.extension().and_then(|e| e.to_str()) != Some("md"));
+    let content = fs::read_to_string(readmes.first()?).ok()?;
+
+    let mut excerpt = String::new();
+    let mut truncated = false;
+    for (index, line) in content.lines().enumerate() {
+        if index >= README_MAX_LINES || excerpt.len() + line.len() > README_MAX_CHARS {
+            truncated = true;
+            break;
+        }
+        excerpt.push_str(line);
+        excerpt.push('\n');
+    }
+    if truncated {
+        excerpt.push_str("…\n");
+    }
+    Some(excerpt)
+}
+
+/// Toolchains at the root of the worktree and one directory down, which covers the usual
+/// monorepo layouts
+pub fn detect_toolchain(worktree: &Path) -> String {
+    let mut dirs = vec![(String::new(), worktree.to_path_buf())];
+    if let Ok(entries) = fs::read_dir(worktree) {
+        let mut children: Vec<_> = entries
+            .flatten()
+            .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                (!name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_str()))
+                    .then(|| (format!("{}/", name), entry.path()))
+            })
+            .collect();
+        children.sort();
+        dirs.extend(children);
+    }
+
+    let mut lines = Vec::new();
+    for (prefix, dir) in dirs {
+        for (marker, toolchain) in TOOLCHAIN_MARKERS {
+            if !dir.join(marker).is_file() {
+                continue;
+            }
+            let toolchain = if *marker == "package.json" {
+                match JS_PACKAGE_MANAGERS
+                    .iter()
+                    .find(|(lockfile, _)| dir.join(lockfile).is_file())
+                {
+                    Some((_, manager)) => format!("{} ({})", toolchain, manager),
+                    None => toolchain.to_string(),
+                }
+            } else {
+                toolchain.to_string()
+            };
+            lines.push(format!("- {} ({}{})", toolchain, prefix, marker));
+        }
+    }
+    lines.join("\n")
+}
+
+/// The shared analysis of the worktree's repository, refreshed for what the worktree has
+async fn repo_map(worktree: &Path) -> Option<String> {
+    let worktree = worktree.to_path_buf();
+    match tokio::task::spawn_blocking(move || repo_analysis::load_or_refresh(&worktree)).await {
+        Ok(Ok((analysis, _))) => Some(analysis.render_map(None, repo_analysis::DEFAULT_MAP_CHARS)),
+        Ok(Err(e)) => {
+            tracing::warn!("Failed to analyze the repository for the repo map: {}", e);
+            None
+        }
+        Err(e) => {
+            tracing::warn!("Repository analysis panicked: {}", e);
+            None
+        }
+    }
+}
+
+async fn ci_failures(worktree: &Path, token: &str, enterprise_url: Option<&str>) -> Option<String> {
+    let (owner, repo_name) = GitService::new(worktree)
+        .ok()?
+        .get_github_repo_info()
+        .ok()?;
+    let sha = Repository::open(worktree)
+        .ok()?
+        .head()
+        .ok()?
+        .peel_to_commit()
+        .ok()?
+        .id()
+        .to_string();
+
+    let github = match enterprise_url {
+        Some(url) => GitHubService::new_with_base_url(token, url),
+        None => GitHubService::new(token),
+    }
+    .ok()?;
+    let repo_info = GitHubRepoInfo { owner, repo_name };
+    let checks =
+        match tokio::time::timeout(CHECKS_TIMEOUT, github.failed_checks(&repo_info, &sha)).await {
+            Ok(Ok(checks)) => checks,
+            Ok(Err(e)) => {
+                tracing::warn!("Failed to fetch CI checks for {}: {}", &sha[..7], e);
+                return None;
+            }
+            Err(_) => {
+                tracing::warn!("Timed out fetching CI checks for {}", &sha[..7]);
+                return None;
+            }
+        };
+
+    if checks.is_empty() {
+        return Some(format!("No failing CI checks on {}.", &sha[..7]));
+    }
+    let lines: Vec<String> = checks
+        .iter()
+        .map(|check| {
+            let mut line = format!("- {}: {}", check.name, check.conclusion.replace('_', " "));
+            if let Some(summary) = &check.summary {
+                line.push_str(&format!(" — {}", summary));
+            }
+            if let Some(url) = &check.url {
+                line.push_str(&format!(" ({})", url));
+            }
+            line
+        })
+        .collect();
+    Some(format!(
+        "Failing CI checks on {}:\n{}",
+        &sha[..7],
+        lines.join("\n")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_detect_toolchain() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        for file in [
+            "Cargo.toml",
+            "frontend/package.json",
+            "frontend/pnpm-lock.yaml",
+            "node_modules/dep/package.json",
+            "docs/README.md",
+        ] {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+
+        assert_eq!(
+            detect_toolchain(root),
+            "- Rust (cargo) (Cargo.toml)\n- JavaScript/TypeScript (pnpm) (frontend/package.json)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fill_context_variables() {
+        let dir = TempDir::new().unwrap();
+        let lines: Vec<String> = (1..=100).map(|i| format!("line {}", i)).collect();
+        fs::write(dir.path().join("README.md"), lines.join("\n")).unwrap();
+
+        let filled = fill_context_variables(
+            "Fix the build.\n\n{{readme}}\n{{ci_failures}}{{unknown}}",
+            dir.path(),
+            None,
+        )
+        .await;
+        assert!(filled.starts_with("Fix the build.\n\nline 1\n"));
+        assert!(filled.contains("line 60\n…"));
+        assert!(!filled.contains("line 61"));
+        // No GitHub access: the variable is dropped, unknown placeholders stay
+        assert!(filled.ends_with("…\n{{unknown}}"));
+
+        let untouched = "Nothing to fill in";
+        assert_eq!(
+            fill_context_variables(untouched, dir.path(), None).await,
+            untouched
+        );
+    }
+}
@@ -0,0 +1,390 @@
+//! Rendering a project's prompt template into the prompt its coding agent starts with.
+//! Templates use `{{variable}}` for a task's fields and `{{#variable}}...{{/variable}}` for
+//! text that is only kept when the variable is non-empty (`{{^variable}}` for when it is
+//! empty). A task's custom fields are `{{fields.<name>}}`. Context variables such as
+//! `{{readme}}` are left for [`fill_context_variables`].
+//!
+//! [`fill_context_variables`]: crate::services::prompt_context::fill_context_variables
+
+use std::path::Path;
+
+use crate::{
+    db::DbPool,
+    models::{
+        project::Project,
+        prompt_template::PromptTemplate,
+        task::Task,
+        task_attempt::TaskAttempt,
+        task_custom_field::{normalize_field_name, CustomField, TaskCustomFields},
+        task_label::TaskLabels,
+    },
+    services::{prompt_context::CONTEXT_VARIABLES, repo_config::RepoConfig},
+};
+
+pub const TEMPLATE_VARIABLES: &[&str] = &[
+    "title",
+    "description",
+    "priority",
+    "labels",
+    "branch",
+    "base_branch",
+    "project_name",
+    "project_id",
+    "task_id",
+    "executor",
+];
+
+const FIELD_PREFIX: &str = "fields.";
+
+/// Whether `name` is a custom field variable. Fields can be defined after the template, so
+/// any well-formed name is accepted.
+fn is_field_variable(name: &str) -> bool {
+    name.strip_prefix(FIELD_PREFIX)
+        .is_some_and(|field| normalize_field_name(field).as_deref() == Some(field))
+}
+
+/// What a run is prompted with when its project has no template, for the runs that need a
+/// prompt of their own rather than the executor's usual one
+pub const DEFAULT_TEMPLATE: &str =
+    "Task title: {{title}}\n{{#description}}Task description: {{description}}\n{{/description}}";
+
+#[derive(Debug, PartialEq)]
+enum Node {
+    Text(String),
+    Variable(String),
+    Section {
+        name: String,
+        inverted: bool,
+        children: Vec<Node>,
+    },
+}
+
+/// An open section while parsing: its name, whether it is inverted, and what it holds so far
+type OpenSection = (String, bool, Vec<Node>);
+
+fn parse(template: &str) -> Result<Vec<Node>, String> {
+    fn current<'a>(stack: &'a mut [OpenSection], nodes: &'a mut Vec<Node>) -> &'a mut Vec<Node> {
+        match stack.last_mut() {
+            Some((_, _, children)) => children,
+            None => nodes,
+        }
+    }
+
+    let mut nodes = Vec::new();
+    let mut stack: Vec<OpenSection> = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(length) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let tag = rest[start + 2..start + 2 + length].trim();
+        if start > 0 {
+            current(&mut stack, &mut nodes).push(Node::Text(rest[..start].to_string()));
+        }
+        rest = &rest[start + 2 + length + 2..];
+
+        if let Some(name) = tag.strip_prefix('#') {
+            stack.push((name.trim().to_string(), false, Vec::new()));
+        } else if let Some(name) = tag.strip_prefix('^') {
+            stack.push((name.trim().to_string(), true, Vec::new()));
+        } else if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim();
+            let (open, inverted, children) = stack
+                .pop()
+                .ok_or_else(|| format!("{{{{/{}}}}} closes a section that isn't open", name))?;
+            if open != name {
+                return Err(format!(
+                    "{{{{/{}}}}} closes a section, but {{{{#{}}}}} is the one open",
+                    name, open
+                ));
+            }
+            current(&mut stack, &mut nodes).push(Node::Section {
+                name: open,
+                inverted,
+                children,
+            });
+        } else {
+            current(&mut stack, &mut nodes).push(Node::Variable(tag.to_string()));
+        }
+    }
+    if !rest.is_empty() {
+        current(&mut stack, &mut nodes).push(Node::Text(rest.to_string()));
+    }
+    if let Some((name, _, _)) = stack.last() {
+        return Err(format!("{{{{#{}}}}} is never closed", name));
+    }
+    Ok(nodes)
+}
+
+/// Check a template parses and only uses variables there are values for
+pub fn validate(template: &str) -> Result<(), String> {
+    fn check(nodes: &[Node]) -> Result<(), String> {
+        for node in nodes {
+            let name = match node {
+                Node::Text(_) => continue,
+                Node::Variable(name) => name,
+                Node::Section { name, children, .. } => {
+                    check(children)?;
+                    name
+                }
+            };
+            if !TEMPLATE_VARIABLES.contains(&name.as_str())
+                && !CONTEXT_VARIABLES.contains(&name.as_str())
+                && !is_field_variable(name)
+            {
+                return Err(format!("Unknown variable {{{{{}}}}}", name));
+            }
+        }
+        Ok(())
+    }
+    check(&parse(template)?)
+}
+
+/// Render `template` with `values`. Variables without a value are kept as placeholders, so
+/// context variables can be filled in afterwards; a template that doesn't parse is used as
+/// it is.
+pub fn render(template: &str, values: &[(&str, String)]) -> String {
+    fn value<'a>(values: &'a [(&str, String)], name: &str) -> Option<&'a str> {
+        values
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.trim())
+    }
+
+    fn render_nodes(nodes: &[Node], values: &[(&str, String)], out: &mut String) {
+        for node in nodes {
+            match node {
+                Node::Text(text) => out.push_str(text),
+                Node::Variable(name) => match value(values, name) {
+                    Some(value) => out.push_str(value),
+                    None => out.push_str(&format!("{{{{{}}}}}", name)),
+                },
+                Node::Section {
+                    name,
+                    inverted,
+                    children,
+                } => {
+                    let present = value(values, name).is_some_and(|value| !value.is_empty());
+                    if present != *inverted {
+                        render_nodes(children, values, out);
+                    }
+                }
+            }
+        }
+    }
+
+    match parse(template) {
+        Ok(nodes) => {
+            let mut out = String::new();
+            render_nodes(&nodes, values, &mut out);
+            out
+        }
+        Err(_) => template.to_string(),
+    }
+}
+
+/// The variables `render` would leave as placeholders for lack of a value, in the order
+/// they first appear. Variables in sections that `values` leave out aren't needed.
+pub fn missing_variables(template: &str, values: &[(&str, String)]) -> Vec<String> {
+    fn collect(nodes: &[Node], values: &[(&str, String)], missing: &mut Vec<String>) {
+        for node in nodes {
+            match node {
+                Node::Text(_) => {}
+                Node::Variable(name) => {
+                    if !values.iter().any(|(key, _)| key == name) && !missing.contains(name) {
+                        missing.push(name.clone());
+                    }
+                }
+                Node::Section {
+                    name,
+                    inverted,
+                    children,
+                } => {
+                    let present = values
+                        .iter()
+                        .any(|(key, value)| key == name && !value.trim().is_empty());
+                    if present != *inverted {
+                        collect(children, values, missing);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut missing = Vec::new();
+    if let Ok(nodes) = parse(template) {
+        collect(&nodes, values, &mut missing);
+    }
+    missing
+}
+
+/// The prompt the project's template gives `task` for a run of `executor` in
+/// `worktree_path`, or `None` if the project has no template for the executor. Without one,
+/// the template in the repository's `.vibe-kanban.toml` is used.
+pub async fn render_for_task(
+    pool: &DbPool,
+    task: &Task,
+    worktree_path: &str,
+    executor: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    let template = match PromptTemplate::find_for_executor(pool, task.project_id, executor).await? {
+        Some(template) => Some(template.template),
+        None => repo_prompt_template(pool, task).await?,
+    };
+    let Some(template) = template else {
+        return Ok(None);
+    };
+    Ok(Some(
+        render_template_for_task(pool, &template, task, worktree_path, executor).await?,
+    ))
+}
+
+async fn repo_prompt_template(pool: &DbPool, task: &Task) -> Result<Option<String>, sqlx::Error> {
+    let Some(project) = Project::find_by_id(pool, task.project_id).await? else {
+        return Ok(None);
+    };
+    match RepoConfig::load(Path::new(&project.git_repo_path)) {
+        Ok(config) => Ok(config.and_then(|config| config.prompt_template)),
+        Err(e) => {
+            tracing::warn!(
+                "Ignoring the repository config of project {}: {}",
+                project.id,
+                e
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// Render `template` with the values of `task`'s run of `executor` in `worktree_path`
+pub async fn render_template_for_task(
+    pool: &DbPool,
+    template: &str,
+    task: &Task,
+    worktree_path: &str,
+    executor: &str,
+) -> Result<String, sqlx::Error> {
+    let project = Project::find_by_id(pool, task.project_id).await?;
+    let attempt = TaskAttempt::find_by_worktree_path(pool, worktree_path).await?;
+    let labels = TaskLabels::find_by_task_id(pool, task.id).await?.labels;
+    // Every field of the project gets a value, so unset ones render empty
+    let mut field_values = TaskCustomFields::find_by_task_id(pool, task.id).await?;
+    let fields: Vec<(String, String)> = CustomField::find_by_project_id(pool, task.project_id)
+        .await?
+        .into_iter()
+        .map(|field| {
+            let value = field_values.remove(&field.name).unwrap_or_default();
+            (format!("{}{}", FIELD_PREFIX, field.name), value)
+        })
+        .collect();
+
+    let mut values = vec![
+        ("title", task.title.clone()),
+        ("description", task.description.clone().unwrap_or_default()),
+        (
+            "priority",
+            task.priority
+                .map(|priority| priority.as_str().to_string())
+                .unwrap_or_default(),
+        ),
+        ("labels", labels.join(", ")),
+        (
+            "branch",
+            attempt
+                .as_ref()
+                .map(|attempt| attempt.branch.clone())
+                .unwrap_or_default(),
+        ),
+        (
+            "base_branch",
+            attempt
+                .as_ref()
+                .map(|attempt| attempt.base_branch.clone())
+                .unwrap_or_default(),
+        ),
+        (
+            "project_name",
+            project.map(|project| project.name).unwrap_or_default(),
+        ),
+        ("project_id", task.project_id.to_string()),
+        ("task_id", task.id.to_string()),
+        ("executor", executor.to_string()),
+    ];
+    values.extend(
+        fields
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.clone())),
+    );
+    Ok(render(template, &values))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(description: &str) -> Vec<(&'static str, String)> {
+        vec![
+            ("title", "Add login".to_string()),
+            ("description", description.to_string()),
+            ("branch", "vk-1a2b-add-login".to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_render_fills_variables_and_sections() {
+        let template = "Task: {{ title }} on {{branch}}\n\
+                        {{#description}}Details: {{description}}\n{{/description}}\
+                        {{^description}}No details given.\n{{/description}}\
+                        {{readme}}";
+
+        assert_eq!(
+            render(template, &values("Use OAuth")),
+            "Task: Add login on vk-1a2b-add-login\nDetails: Use OAuth\n{{readme}}"
+        );
+        assert_eq!(
+            render(template, &values("  ")),
+            "Task: Add login on vk-1a2b-add-login\nNo details given.\n{{readme}}"
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_unknown_variables_and_unbalanced_sections() {
+        assert!(
+            validate("{{title}} {{#description}}{{description}}{{/description}} {{readme}}")
+                .is_ok()
+        );
+        assert_eq!(
+            validate("{{titel}}"),
+            Err("Unknown variable {{titel}}".to_string())
+        );
+        assert_eq!(
+            validate("{{#description}}x"),
+            Err("{{#description}} is never closed".to_string())
+        );
+        assert!(validate("{{#title}}x{{/description}}").is_err());
+        assert!(validate(
+            "{{priority}} {{labels}} {{#fields.severity}}{{fields.severity}}{{/fields.severity}}"
+        )
+        .is_ok());
+        assert_eq!(
+            validate("{{fields.Story Points}}"),
+            Err("Unknown variable {{fields.Story Points}}".to_string())
+        );
+        assert!(validate("x{{/title}}").is_err());
+    }
+
+    #[test]
+    fn test_missing_variables_skips_sections_that_are_left_out() {
+        let template =
+            "Fix {{component}} {{#ticket}}({{ticket}}, {{owner}}){{/ticket}} {{component}}";
+        let component = vec![("component", "login".to_string())];
+
+        assert_eq!(missing_variables(template, &[]), vec!["component"]);
+        assert!(missing_variables(template, &component).is_empty());
+        let ticket = vec![
+            ("component", "login".to_string()),
+            ("ticket", "VK-12".to_string()),
+        ];
+        assert_eq!(missing_variables(template, &ticket), vec!["owner"]);
+    }
+}
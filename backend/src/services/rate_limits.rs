@@ -0,0 +1,164 @@
+//! Deciding how long a coding agent stopped by its provider's rate limit waits before it
+//! goes on, from the wait the provider advertised where it did
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::services::network_recovery;
+
+/// How many times one attempt goes on after a rate limit before it is failed outright
+pub const MAX_RATE_LIMIT_RETRIES: i64 = 5;
+
+/// Sent as the follow-up when a rate-limited session goes on
+pub const RESUME_PROMPT: &str = "Your provider's rate limit stopped you while you were working and has now reset. Continue the task from where you left off.";
+
+/// The wait when the provider didn't say, doubled for each earlier rate limit of the attempt
+const DEFAULT_COOLDOWN: Duration = Duration::from_secs(60);
+/// Shorter waits than this only hit the limit again
+const MIN_COOLDOWN: Duration = Duration::from_secs(10);
+const MAX_COOLDOWN: Duration = Duration::from_secs(60 * 60);
+
+/// Lowercase fragments providers put before the wait they ask for, as in "retry-after: 30",
+/// "Please try again in 1m20s", "retry in 6.5s" or Gemini's `"retryDelay": "41s"`
+const RETRY_AFTER_MARKERS: &[&str] = &[
+    "retry-after",
+    "retry_after",
+    "retry after",
+    "try again in",
+    "retry in",
+    "resets in",
+    "retrydelay",
+];
+
+/// Claude's usage limit message ends in the Unix time the limit resets at
+const RESET_AT_MARKER: &str = "limit reached|";
+
+/// A wait written like "30", "30 seconds", "1.5s", "500ms" or "6m0s"
+fn parse_duration(text: &str) -> Option<Duration> {
+    let mut rest = text.trim_start_matches(|c: char| !c.is_ascii_alphanumeric());
+    let mut seconds = 0.0;
+    loop {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let Ok(number) = rest[..number_len].parse::<f64>() else {
+            break;
+        };
+        rest = rest[number_len..].trim_start();
+        let unit_len = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let scale = match &rest[..unit_len] {
+            "ms" | "msec" | "millisecond" | "milliseconds" => 0.001,
+            "m" | "min" | "mins" | "minute" | "minutes" => 60.0,
+            "h" | "hr" | "hrs" | "hour" | "hours" => 3600.0,
+            _ => 1.0,
+        };
+        seconds += number * scale;
+        rest = &rest[unit_len..];
+        if !rest.starts_with(|c: char| c.is_ascii_digit()) {
+            break;
+        }
+    }
+    (seconds > 0.0).then(|| Duration::from_secs_f64(seconds.min(MAX_COOLDOWN.as_secs_f64())))
+}
+
+fn advertised_wait(line: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let line = line.to_lowercase();
+    if let Some(pos) = line.find(RESET_AT_MARKER) {
+        let reset_at = &line[pos + RESET_AT_MARKER.len()..];
+        let digits = reset_at
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(reset_at.len());
+        let reset_at = reset_at[..digits]
+            .parse::<i64>()
+            .ok()
+            .and_then(|seconds| DateTime::from_timestamp(seconds, 0));
+        if let Some(wait) = reset_at.and_then(|reset_at| (reset_at - now).to_std().ok()) {
+            return Some(wait);
+        }
+    }
+    RETRY_AFTER_MARKERS.iter().find_map(|marker| {
+        line.find(marker)
+            .and_then(|pos| parse_duration(&line[pos + marker.len()..]))
+    })
+}
+
+/// The wait a rate-limited run's provider asked for at the end of its output, if it did
+pub fn advertised_retry_after(
+    stdout: Option<&str>,
+    stderr: Option<&str>,
+    now: DateTime<Utc>,
+) -> Option<Duration> {
+    [stderr, stdout]
+        .into_iter()
+        .flatten()
+        .flat_map(|output| network_recovery::tail(output).lines().rev())
+        .find_map(|line| advertised_wait(line, now))
+}
+
+/// How long a run waits before going on after its attempt's `previous` rate limits
+pub fn cooldown(advertised: Option<Duration>, previous: i64) -> Duration {
+    let wait = advertised.unwrap_or_else(|| {
+        let factor = 2u32.saturating_pow(u32::try_from(previous).unwrap_or(u32::MAX));
+        DEFAULT_COOLDOWN.saturating_mul(factor)
+    });
+    wait.clamp(MIN_COOLDOWN, MAX_COOLDOWN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_advertised_waits_are_read_from_the_output() {
+        let now = Utc::now();
+        let wait = |stderr: &str| advertised_retry_after(None, Some(stderr), now);
+        assert_eq!(
+            wait("Rate limit reached for gpt-4o. Please try again in 1m20s."),
+            Some(Duration::from_secs(80))
+        );
+        assert_eq!(
+            wait("429 Too Many Requests\nretry-after: 30"),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            wait("Quota exceeded. Please retry in 6.5s"),
+            Some(Duration::from_secs_f64(6.5))
+        );
+        assert_eq!(
+            wait(r#"{"@type": "type.googleapis.com/google.rpc.RetryInfo", "retryDelay": "41s"}"#),
+            Some(Duration::from_secs(41))
+        );
+        assert_eq!(
+            wait("Retry after 2 minutes"),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            advertised_retry_after(
+                Some(&format!(
+                    "Claude AI usage limit reached|{}",
+                    (now + chrono::Duration::minutes(10)).timestamp()
+                )),
+                None,
+                now
+            )
+            .map(|wait| wait.as_secs() / 60),
+            Some(9)
+        );
+        assert_eq!(wait("API Error: 529 Overloaded"), None);
+    }
+
+    #[test]
+    fn test_cooldown_backs_off_when_no_wait_was_advertised() {
+        assert_eq!(cooldown(None, 0), DEFAULT_COOLDOWN);
+        assert_eq!(cooldown(None, 2), Duration::from_secs(240));
+        assert_eq!(cooldown(None, 40), MAX_COOLDOWN);
+        assert_eq!(cooldown(Some(Duration::from_secs(1)), 0), MIN_COOLDOWN);
+        assert_eq!(
+            cooldown(Some(Duration::from_secs(90)), 3),
+            Duration::from_secs(90)
+        );
+    }
+}
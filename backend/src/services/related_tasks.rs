@@ -0,0 +1,314 @@
+//! "Similar existing tasks" for a task that is about to be created, so the same problem
+//! doesn't get handed to an agent twice. Task embeddings are computed lazily: each lookup
+//! first embeds any task that is new or was edited since it was last embedded.
+//!
+//! Task creation runs a stricter version of the same check and reports open tasks that look
+//! like duplicates alongside the created task.
+
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    models::{
+        task::{Task, TaskStatus, TaskWithAttemptStatus},
+        task_embedding::TaskEmbedding,
+    },
+    services::embeddings::{cosine_similarity, EmbeddingsClient, EmbeddingsError},
+};
+
+/// Below this the match is usually only topical, e.g. two unrelated UI bugs
+const MIN_SIMILARITY: f32 = 0.5;
+const MAX_SUGGESTIONS: usize = 5;
+/// Too little text to say anything useful about similarity
+const MIN_QUERY_CHARS: usize = 8;
+const DUPLICATE_TITLE_SIMILARITY: f32 = 0.8;
+/// Well above MIN_SIMILARITY: a duplicate is the same problem, not the same area
+const DUPLICATE_EMBEDDING_SIMILARITY: f32 = 0.9;
+const MAX_DUPLICATES: usize = 3;
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct SimilarTasksQuery {
+    pub title: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct SimilarTask {
+    pub id: Uuid,
+    pub title: String,
+    pub status: TaskStatus,
+    /// Cosine similarity, 0 to 1
+    pub score: f32,
+}
+
+/// An open task that may already cover a task being created
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct DuplicateCandidate {
+    pub id: Uuid,
+    pub title: String,
+    pub status: TaskStatus,
+    /// An agent is working on it right now
+    pub has_in_progress_attempt: bool,
+    /// 0 to 1
+    pub title_similarity: f32,
+    /// Cosine similarity; absent without an embeddings provider
+    pub embedding_similarity: Option<f32>,
+}
+
+impl DuplicateCandidate {
+    fn strength(&self) -> f32 {
+        self.title_similarity
+            .max(self.embedding_similarity.unwrap_or_default())
+    }
+}
+
+#[derive(Debug)]
+pub enum RelatedTasksError {
+    Database(sqlx::Error),
+    Embeddings(EmbeddingsError),
+}
+
+impl std::fmt::Display for RelatedTasksError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RelatedTasksError::Database(e) => write!(f, "Database error: {}", e),
+            RelatedTasksError::Embeddings(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for RelatedTasksError {}
+
+impl From<sqlx::Error> for RelatedTasksError {
+    fn from(e: sqlx::Error) -> Self {
+        RelatedTasksError::Database(e)
+    }
+}
+
+impl From<EmbeddingsError> for RelatedTasksError {
+    fn from(e: EmbeddingsError) -> Self {
+        RelatedTasksError::Embeddings(e)
+    }
+}
+
+pub async fn find_similar_tasks(
+    pool: &DbPool,
+    client: &EmbeddingsClient,
+    project_id: Uuid,
+    query: &SimilarTasksQuery,
+) -> Result<Vec<SimilarTask>, RelatedTasksError> {
+    let content = TaskEmbedding::content(&query.title, query.description.as_deref());
+    if content.chars().count() < MIN_QUERY_CHARS {
+        return Ok(Vec::new());
+    }
+
+    let tasks = Task::find_by_project_id_with_attempt_status(pool, project_id).await?;
+    let scores = embedding_scores(pool, client, project_id, &tasks, content).await?;
+    Ok(rank(tasks.into_iter().filter_map(|task| {
+        Some(SimilarTask {
+            score: *scores.get(&task.id)?,
+            id: task.id,
+            title: task.title,
+            status: task.status,
+        })
+    })))
+}
+
+/// Open tasks that look like the same piece of work as a task about to be created: a near
+/// identical title, or (with an embeddings client) a description that says the same thing.
+/// An embeddings failure only costs the second check.
+pub async fn find_duplicate_candidates(
+    pool: &DbPool,
+    client: Option<&EmbeddingsClient>,
+    project_id: Uuid,
+    title: &str,
+    description: Option<&str>,
+) -> Result<Vec<DuplicateCandidate>, sqlx::Error> {
+    let tasks: Vec<TaskWithAttemptStatus> =
+        Task::find_by_project_id_with_attempt_status(pool, project_id)
+            .await?
+            .into_iter()
+            .filter(|task| is_open(&task.status))
+            .collect();
+    if tasks.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let content = TaskEmbedding::content(title, description);
+    let scores = match client {
+        Some(client) if content.chars().count() >= MIN_QUERY_CHARS => {
+            match embedding_scores(pool, client, project_id, &tasks, content).await {
+                Ok(scores) => scores,
+                Err(RelatedTasksError::Database(e)) => return Err(e),
+                Err(e) => {
+                    tracing::warn!(
+                        "Embedding check for duplicates in project {} failed: {}",
+                        project_id,
+                        e
+                    );
+                    HashMap::new()
+                }
+            }
+        }
+        _ => HashMap::new(),
+    };
+
+    let mut candidates: Vec<DuplicateCandidate> = tasks
+        .into_iter()
+        .filter_map(|task| {
+            let title_similarity = title_similarity(title, &task.title);
+            let embedding_similarity = scores.get(&task.id).copied();
+            let duplicate = title_similarity >= DUPLICATE_TITLE_SIMILARITY
+                || embedding_similarity.is_some_and(|s| s >= DUPLICATE_EMBEDDING_SIMILARITY);
+            duplicate.then_some(DuplicateCandidate {
+                id: task.id,
+                title: task.title,
+                status: task.status,
+                has_in_progress_attempt: task.has_in_progress_attempt,
+                title_similarity,
+                embedding_similarity,
+            })
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.strength().total_cmp(&a.strength()));
+    candidates.truncate(MAX_DUPLICATES);
+    Ok(candidates)
+}
+
+fn is_open(status: &TaskStatus) -> bool {
+    match status {
+        TaskStatus::Todo
+        | TaskStatus::Queued
+        | TaskStatus::InProgress
+        | TaskStatus::PlanReview
+        | TaskStatus::InReview => true,
+        TaskStatus::Done | TaskStatus::Cancelled => false,
+    }
+}
+
+/// Cosine similarity of every task to `content`, embedding first whatever is new or edited
+async fn embedding_scores(
+    pool: &DbPool,
+    client: &EmbeddingsClient,
+    project_id: Uuid,
+    tasks: &[TaskWithAttemptStatus],
+    content: String,
+) -> Result<HashMap<Uuid, f32>, RelatedTasksError> {
+    let mut embeddings: HashMap<Uuid, Vec<f32>> = HashMap::new();
+    let mut cached: HashMap<Uuid, TaskEmbedding> =
+        TaskEmbedding::find_by_project_id(pool, project_id)
+            .await?
+            .into_iter()
+            .map(|embedding| (embedding.task_id, embedding))
+            .collect();
+
+    let mut stale = Vec::new();
+    for task in tasks {
+        let content = TaskEmbedding::content(&task.title, task.description.as_deref());
+        let hash = TaskEmbedding::content_hash(&content);
+        match cached.remove(&task.id) {
+            Some(e) if e.content_hash == hash && e.model == client.model() => {
+                embeddings.insert(task.id, e.embedding);
+            }
+            _ => stale.push((task.id, content, hash)),
+        }
+    }
+    if !stale.is_empty() {
+        let contents: Vec<String> = stale
+            .iter()
+            .map(|(_, content, _)| content.clone())
+            .collect();
+        let vectors = client.embed(&contents).await?;
+        for ((task_id, _, hash), vector) in stale.into_iter().zip(vectors) {
+            TaskEmbedding::upsert(pool, task_id, client.model(), &hash, &vector).await?;
+            embeddings.insert(task_id, vector);
+        }
+    }
+
+    let query_vector = client
+        .embed(&[content])
+        .await?
+        .pop()
+        .ok_or(EmbeddingsError::InvalidResponse)?;
+    Ok(embeddings
+        .into_iter()
+        .map(|(task_id, vector)| (task_id, cosine_similarity(&query_vector, &vector)))
+        .collect())
+}
+
+/// Dice coefficient over character bigrams of the normalized titles, so reworded
+/// punctuation, casing and small typos still match
+pub fn title_similarity(a: &str, b: &str) -> f32 {
+    let a = bigrams(a);
+    let b = bigrams(b);
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let mut remaining = b.clone();
+    let mut shared = 0;
+    for bigram in &a {
+        if let Some(pos) = remaining.iter().position(|other| other == bigram) {
+            remaining.swap_remove(pos);
+            shared += 1;
+        }
+    }
+    (2 * shared) as f32 / (a.len() + b.len()) as f32
+}
+
+fn bigrams(title: &str) -> Vec<(char, char)> {
+    let normalized: Vec<char> = title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .collect();
+    normalized.windows(2).map(|w| (w[0], w[1])).collect()
+}
+
+fn rank(candidates: impl Iterator<Item = SimilarTask>) -> Vec<SimilarTask> {
+    let mut similar: Vec<SimilarTask> = candidates
+        .filter(|task| task.score >= MIN_SIMILARITY)
+        .collect();
+    similar.sort_by(|a, b| b.score.total_cmp(&a.score));
+    similar.truncate(MAX_SUGGESTIONS);
+    similar
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rank_filters_weak_matches_and_caps_results() {
+        let candidates = (0..10).map(|i| SimilarTask {
+            id: Uuid::new_v4(),
+            title: format!("task {}", i),
+            status: TaskStatus::Todo,
+            score: i as f32 / 10.0,
+        });
+        let ranked = rank(candidates);
+        let scores: Vec<f32> = ranked.iter().map(|t| t.score).collect();
+        assert_eq!(scores, vec![0.9, 0.8, 0.7, 0.6, 0.5]);
+    }
+
+    #[test]
+    fn test_title_similarity() {
+        assert_eq!(
+            title_similarity("Fix login redirect", "fix login-redirect!"),
+            1.0
+        );
+        assert!(title_similarity("Fix login redirect", "Fix the login redirct") >= 0.8);
+        assert!(title_similarity("Fix login redirect", "Add dark mode") < 0.3);
+        assert_eq!(title_similarity("", "Add dark mode"), 0.0);
+    }
+}
@@ -0,0 +1,349 @@
+//! Running a release: the project's release script is started from the repository root with
+//! the release's details in the environment, its output is stored on the release as it
+//! arrives, and the exit status decides whether the release's tasks count as released.
+//!
+//! The script sees `VK_RELEASE_ID`, `VK_RELEASE_VERSION` and, when set, `VK_RELEASE_NOTES`.
+
+use std::collections::HashSet;
+
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use uuid::Uuid;
+
+use crate::{
+    command_runner::{CommandProcess, CommandRunner},
+    db::DbPool,
+    executor::{NormalizedConversation, NormalizedEntry, NormalizedEntryType},
+    models::{
+        project::Project,
+        release::{Release, ReleaseOutput, ReleaseStatus},
+        task_attempt::TaskAttempt,
+    },
+    utils::shell::get_shell_command,
+};
+
+const LINES_PER_ENTRY: usize = 10;
+
+#[derive(Debug)]
+pub enum ReleaseError {
+    NoScript,
+    AlreadyRunning,
+    AlreadyReleased,
+    Spawn(String),
+    Database(sqlx::Error),
+}
+
+impl std::fmt::Display for ReleaseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReleaseError::NoScript => write!(f, "The project has no release script"),
+            ReleaseError::AlreadyRunning => write!(f, "The release script is already running"),
+            ReleaseError::AlreadyReleased => write!(f, "This release has already gone out"),
+            ReleaseError::Spawn(e) => write!(f, "Failed to start the release script: {}", e),
+            ReleaseError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ReleaseError {}
+
+impl From<sqlx::Error> for ReleaseError {
+    fn from(err: sqlx::Error) -> Self {
+        ReleaseError::Database(err)
+    }
+}
+
+/// Merged tasks of the project that no release has picked up yet, oldest merge first
+pub async fn unreleased_task_ids(
+    pool: &DbPool,
+    project_id: Uuid,
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    let mut seen: HashSet<Uuid> = Release::find_released_task_ids(pool, project_id)
+        .await?
+        .into_iter()
+        .collect();
+    Ok(TaskAttempt::find_merged_by_project_id(pool, project_id)
+        .await?
+        .into_iter()
+        .map(|attempt| attempt.task_id)
+        .filter(|task_id| seen.insert(*task_id))
+        .collect())
+}
+
+/// Start the release script and follow it in the background
+pub async fn start(pool: DbPool, project: &Project, release: &Release) -> Result<(), ReleaseError> {
+    match release.status {
+        ReleaseStatus::Running => return Err(ReleaseError::AlreadyRunning),
+        ReleaseStatus::Released => return Err(ReleaseError::AlreadyReleased),
+        ReleaseStatus::Draft | ReleaseStatus::Failed => {}
+    }
+    let script = project
+        .release_script
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .ok_or(ReleaseError::NoScript)?;
+
+    let (shell_cmd, shell_arg) = get_shell_command();
+    let mut command = CommandRunner::new();
+    command
+        .command(shell_cmd)
+        .arg(shell_arg)
+        .arg(script)
+        .working_dir(&project.git_repo_path)
+        .env("VK_RELEASE_ID", &release.id.to_string())
+        .env("VK_RELEASE_VERSION", &release.version);
+    if let Some(notes) = &release.notes {
+        command.env("VK_RELEASE_NOTES", notes);
+    }
+
+    let child = command
+        .start()
+        .await
+        .map_err(|e| ReleaseError::Spawn(e.to_string()))?;
+    Release::mark_running(&pool, release.id).await?;
+
+    let release_id = release.id;
+    tokio::spawn(async move {
+        follow(pool, release_id, child).await;
+    });
+    Ok(())
+}
+
+async fn follow(pool: DbPool, release_id: Uuid, mut child: CommandProcess) {
+    let mut readers = Vec::new();
+    match child.stream().await {
+        Ok(stream) => {
+            if let Some(stdout) = stream.stdout {
+                readers.push(tokio::spawn(store_output(
+                    stdout,
+                    pool.clone(),
+                    release_id,
+                    true,
+                )));
+            }
+            if let Some(stderr) = stream.stderr {
+                readers.push(tokio::spawn(store_output(
+                    stderr,
+                    pool.clone(),
+                    release_id,
+                    false,
+                )));
+            }
+        }
+        Err(e) => tracing::error!(
+            "Failed to read the output of the release script for {}: {}",
+            release_id,
+            e
+        ),
+    }
+
+    let status = child.wait().await;
+    for reader in readers {
+        let _ = reader.await;
+    }
+
+    let (succeeded, exit_code) = match status {
+        Ok(status) => (status.success(), status.code().map(i64::from)),
+        Err(e) => {
+            tracing::error!(
+                "Failed to wait for the release script of {}: {}",
+                release_id,
+                e
+            );
+            (false, None)
+        }
+    };
+    if let Err(e) = Release::finish(&pool, release_id, succeeded, exit_code).await {
+        tracing::error!(
+            "Failed to record the result of release {}: {}",
+            release_id,
+            e
+        );
+    } else if succeeded {
+        tracing::info!("Release {} went out", release_id);
+    }
+}
+
+/// Store output line by line so a running script can be followed
+async fn store_output(
+    output: impl AsyncRead + Unpin,
+    pool: DbPool,
+    release_id: Uuid,
+    is_stdout: bool,
+) {
+    let mut reader = BufReader::new(output);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        match reader.read_line(&mut line).await {
+            Ok(0) => break,
+            Ok(_) => {
+                let (stdout, stderr) = if is_stdout {
+                    (Some(line.as_str()), None)
+                } else {
+                    (None, Some(line.as_str()))
+                };
+                if let Err(e) = Release::append_output(&pool, release_id, stdout, stderr).await {
+                    tracing::error!(
+                        "Failed to store release script output for {}: {}",
+                        release_id,
+                        e
+                    );
+                }
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Error reading release script output for {}: {}",
+                    release_id,
+                    e
+                );
+                break;
+            }
+        }
+    }
+}
+
+/// The script's run as a conversation, the way setup and cleanup scripts are shown
+pub fn conversation(
+    script: Option<&str>,
+    release: &Release,
+    output: &ReleaseOutput,
+) -> NormalizedConversation {
+    let mut entries = vec![system_entry(format!(
+        "Releasing {} with the release script",
+        release.version
+    ))];
+    let failed = release.status == ReleaseStatus::Failed;
+    if let Some(stdout) = &output.stdout {
+        entries.extend(chunk_entries(stdout, NormalizedEntryType::SystemMessage));
+    }
+    if let Some(stderr) = &output.stderr {
+        // Plenty of tools report progress on stderr, so it only reads as an error on failure
+        let entry_type = if failed {
+            NormalizedEntryType::ErrorMessage
+        } else {
+            NormalizedEntryType::SystemMessage
+        };
+        entries.extend(chunk_entries(stderr, entry_type));
+    }
+    match (release.status, release.exit_code) {
+        (ReleaseStatus::Released, _) => entries.push(system_entry(format!(
+            "Released {}; its tasks are marked as released",
+            release.version
+        ))),
+        (ReleaseStatus::Failed, Some(code)) => entries.push(NormalizedEntry {
+            id: None,
+            timestamp: None,
+            duration_ms: None,
+            entry_type: NormalizedEntryType::ErrorMessage,
+            content: format!("Release script failed with exit code {}", code),
+            metadata: None,
+        }),
+        (ReleaseStatus::Failed, None) => entries.push(NormalizedEntry {
+            id: None,
+            timestamp: None,
+            duration_ms: None,
+            entry_type: NormalizedEntryType::ErrorMessage,
+            content: "Release script was interrupted".to_string(),
+            metadata: None,
+        }),
+        (ReleaseStatus::Draft | ReleaseStatus::Running, _) => {}
+    }
+
+    NormalizedConversation {
+        entries,
+        session_id: None,
+        executor_type: "release-script".to_string(),
+        prompt: script.map(str::to_string),
+        summary: None,
+        diff_summary: None,
+    }
+}
+
+fn system_entry(content: String) -> NormalizedEntry {
+    NormalizedEntry {
+        id: None,
+        timestamp: None,
+        duration_ms: None,
+        entry_type: NormalizedEntryType::SystemMessage,
+        content,
+        metadata: None,
+    }
+}
+
+fn chunk_entries(text: &str, entry_type: NormalizedEntryType) -> Vec<NormalizedEntry> {
+    let lines: Vec<&str> = text.lines().collect();
+    lines
+        .chunks(LINES_PER_ENTRY)
+        .map(|chunk| chunk.join("\n").trim().to_string())
+        .filter(|content| !content.is_empty())
+        .map(|content| NormalizedEntry {
+            id: None,
+            timestamp: None,
+            duration_ms: None,
+            entry_type: entry_type.clone(),
+            content,
+            metadata: None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn release(status: ReleaseStatus, exit_code: Option<i64>) -> Release {
+        Release {
+            id: Uuid::nil(),
+            project_id: Uuid::nil(),
+            version: "v1.4.0".to_string(),
+            notes: None,
+            status,
+            exit_code,
+            started_at: None,
+            released_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_conversation_of_failed_run() {
+        let output = ReleaseOutput {
+            stdout: Some((1..=12).map(|i| format!("step {}\n", i)).collect()),
+            stderr: Some("npm ERR! 403\n".to_string()),
+        };
+        let conversation = conversation(
+            Some("./release.sh"),
+            &release(ReleaseStatus::Failed, Some(1)),
+            &output,
+        );
+        let kinds: Vec<bool> = conversation
+            .entries
+            .iter()
+            .map(|e| matches!(e.entry_type, NormalizedEntryType::ErrorMessage))
+            .collect();
+        // Intro, two stdout chunks, stderr, exit status
+        assert_eq!(kinds, vec![false, false, false, true, true]);
+        assert_eq!(conversation.entries[2].content, "step 11\nstep 12");
+        assert_eq!(
+            conversation.entries[4].content,
+            "Release script failed with exit code 1"
+        );
+        assert_eq!(conversation.prompt.as_deref(), Some("./release.sh"));
+    }
+
+    #[test]
+    fn test_stderr_is_not_an_error_when_released() {
+        let output = ReleaseOutput {
+            stdout: None,
+            stderr: Some("Enumerating objects: 5, done.\n".to_string()),
+        };
+        let conversation = conversation(None, &release(ReleaseStatus::Released, Some(0)), &output);
+        assert!(conversation
+            .entries
+            .iter()
+            .all(|e| matches!(e.entry_type, NormalizedEntryType::SystemMessage)));
+    }
+}
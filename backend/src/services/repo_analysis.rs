@@ -0,0 +1,502 @@
+//! A per-repository index of the tracked files, their languages and the top-level symbols
+//! defined in them. Every attempt's agent otherwise starts by listing and grepping its way
+//! around the same codebase; the index gives it that map up front, through the
+//! `{{repo_map}}` prompt variable or the MCP `get_repo_map` tool.
+//!
+//! Entries are keyed by git blob id, so refreshing only parses files whose content changed,
+//! and the attempts' worktrees share the cache of the repository they were created from.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use git2::Repository;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use ts_rs::TS;
+
+/// Files above this size are indexed without symbols; they're usually generated
+const MAX_PARSED_BYTES: usize = 512 * 1024;
+const MAX_SYMBOLS_PER_FILE: usize = 40;
+/// Symbols listed per file in the rendered map
+const MAP_SYMBOLS_PER_FILE: usize = 12;
+pub const DEFAULT_MAP_CHARS: usize = 12_000;
+/// Bump when the entry format or symbol extraction changes, so old caches are rebuilt
+const CACHE_VERSION: u32 = 1;
+
+/// File extension and the language it stands for
+const LANGUAGES: &[(&str, &str)] = &[
+    ("rs", "Rust"),
+    ("ts", "TypeScript"),
+    ("tsx", "TypeScript"),
+    ("js", "JavaScript"),
+    ("jsx", "JavaScript"),
+    ("mjs", "JavaScript"),
+    ("py", "Python"),
+    ("go", "Go"),
+    ("java", "Java"),
+    ("kt", "Kotlin"),
+    ("rb", "Ruby"),
+    ("php", "PHP"),
+    ("c", "C"),
+    ("h", "C"),
+    ("cpp", "C++"),
+    ("cc", "C++"),
+    ("hpp", "C++"),
+    ("cs", "C#"),
+    ("swift", "Swift"),
+    ("ex", "Elixir"),
+    ("exs", "Elixir"),
+    ("sh", "Shell"),
+    ("sql", "SQL"),
+    ("css", "CSS"),
+    ("scss", "CSS"),
+    ("html", "HTML"),
+    ("md", "Markdown"),
+    ("json", "JSON"),
+    ("toml", "TOML"),
+    ("yaml", "YAML"),
+    ("yml", "YAML"),
+];
+
+lazy_static::lazy_static! {
+    static ref RUST_SYMBOL: Regex = Regex::new(
+        r"^(?:pub(?:\([^)]*\))?\s+)?(?:async\s+|const\s+|unsafe\s+)*(fn|struct|enum|trait|mod|type|macro_rules!)\s*([A-Za-z_][A-Za-z0-9_]*)"
+    ).unwrap();
+    static ref JS_SYMBOL: Regex = Regex::new(
+        r"^(?:export\s+)?(?:default\s+)?(?:declare\s+)?(?:async\s+)?(function\*?|class|interface|type|enum|const)\s+([A-Za-z_$][A-Za-z0-9_$]*)"
+    ).unwrap();
+    static ref PYTHON_SYMBOL: Regex =
+        Regex::new(r"^(?:async\s+)?(def|class)\s+([A-Za-z_][A-Za-z0-9_]*)").unwrap();
+    static ref GO_SYMBOL: Regex = Regex::new(
+        r"^(func|type)\s+(?:\([^)]*\)\s*)?([A-Za-z_][A-Za-z0-9_]*)"
+    ).unwrap();
+    static ref RUBY_SYMBOL: Regex =
+        Regex::new(r"^\s*(def|class|module)\s+([A-Za-z_][A-Za-z0-9_:.?!]*)").unwrap();
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Symbol {
+    pub kind: String,
+    pub name: String,
+    /// 1-based
+    pub line: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileEntry {
+    /// Git blob id of the content the entry was built from
+    pub blob: String,
+    pub bytes: u64,
+    pub language: Option<String>,
+    pub symbols: Vec<Symbol>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoAnalysis {
+    version: u32,
+    pub head: Option<String>,
+    pub updated_at: DateTime<Utc>,
+    pub files: BTreeMap<String, FileEntry>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, TS)]
+#[ts(export)]
+pub struct RefreshStats {
+    pub reused: u32,
+    pub parsed: u32,
+    pub removed: u32,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct LanguageStat {
+    pub language: String,
+    pub files: u32,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct RepoAnalysisSummary {
+    pub head: Option<String>,
+    pub updated_at: DateTime<Utc>,
+    pub files: u32,
+    pub bytes: u64,
+    pub symbols: u32,
+    /// Most bytes first
+    pub languages: Vec<LanguageStat>,
+    pub last_refresh: RefreshStats,
+}
+
+fn language_of(path: &str) -> Option<&'static str> {
+    let extension = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+    LANGUAGES
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map(|(_, language)| *language)
+}
+
+/// Top-level definitions, found by matching declarations at the start of unindented lines
+/// (Ruby and Python methods excepted). Crude next to a real parser, but language-agnostic
+/// enough for a map of where things live.
+pub fn extract_symbols(language: &str, content: &str) -> Vec<Symbol> {
+    let (pattern, indented): (&Regex, bool) = match language {
+        "Rust" => (&RUST_SYMBOL, false),
+        "TypeScript" | "JavaScript" => (&JS_SYMBOL, false),
+        "Python" => (&PYTHON_SYMBOL, true),
+        "Go" => (&GO_SYMBOL, false),
+        "Ruby" => (&RUBY_SYMBOL, true),
+        _ => return Vec::new(),
+    };
+
+    let mut symbols = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        if !indented && line.starts_with(char::is_whitespace) {
+            continue;
+        }
+        // Python methods are one level in; anything deeper is local
+        if language == "Python" && line.starts_with("        ") {
+            continue;
+        }
+        if let Some(captures) = pattern.captures(line.trim_start()) {
+            symbols.push(Symbol {
+                kind: captures[1].trim_end_matches('!').to_string(),
+                name: captures[2].to_string(),
+                line: index as u32 + 1,
+            });
+            if symbols.len() >= MAX_SYMBOLS_PER_FILE {
+                break;
+            }
+        }
+    }
+    symbols
+}
+
+impl RepoAnalysis {
+    fn empty() -> Self {
+        Self {
+            version: CACHE_VERSION,
+            head: None,
+            updated_at: Utc::now(),
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Bring the analysis up to date with the repository's index, reusing every entry whose
+    /// blob hasn't changed
+    pub fn refresh(&mut self, repo: &Repository) -> Result<RefreshStats, git2::Error> {
+        let index = repo.index()?;
+        let mut previous = std::mem::take(&mut self.files);
+        let mut stats = RefreshStats::default();
+
+        for entry in index.iter() {
+            let path = String::from_utf8_lossy(&entry.path).into_owned();
+            if path.starts_with(".agent/") {
+                continue;
+            }
+            let blob = entry.id.to_string();
+            if let Some(existing) = previous.remove(&path).filter(|e| e.blob == blob) {
+                self.files.insert(path, existing);
+                stats.reused += 1;
+                continue;
+            }
+
+            let language = language_of(&path);
+            // Submodules and other non-blob entries have nothing to read
+            let Ok(content) = repo.find_blob(entry.id) else {
+                continue;
+            };
+            let symbols = match language {
+                Some(language) if content.size() <= MAX_PARSED_BYTES && !content.is_binary() => {
+                    extract_symbols(language, &String::from_utf8_lossy(content.content()))
+                }
+                _ => Vec::new(),
+            };
+            self.files.insert(
+                path,
+                FileEntry {
+                    blob,
+                    bytes: content.size() as u64,
+                    language: language.map(str::to_string),
+                    symbols,
+                },
+            );
+            stats.parsed += 1;
+        }
+        stats.removed = previous.len() as u32;
+
+        self.head = repo
+            .head()
+            .ok()
+            .and_then(|head| head.target())
+            .map(|oid| oid.to_string());
+        self.updated_at = Utc::now();
+        Ok(stats)
+    }
+
+    pub fn summary(&self, last_refresh: RefreshStats) -> RepoAnalysisSummary {
+        let mut languages: HashMap<&str, (u32, u64)> = HashMap::new();
+        for entry in self.files.values() {
+            if let Some(language) = &entry.language {
+                let stat = languages.entry(language).or_default();
+                stat.0 += 1;
+                stat.1 += entry.bytes;
+            }
+        }
+        let mut languages: Vec<LanguageStat> = languages
+            .into_iter()
+            .map(|(language, (files, bytes))| LanguageStat {
+                language: language.to_string(),
+                files,
+                bytes,
+            })
+            .collect();
+        languages.sort_by(|a, b| b.bytes.cmp(&a.bytes).then(a.language.cmp(&b.language)));
+
+        RepoAnalysisSummary {
+            head: self.head.clone(),
+            updated_at: self.updated_at,
+            files: self.files.len() as u32,
+            bytes: self.files.values().map(|entry| entry.bytes).sum(),
+            symbols: self.files.values().map(|e| e.symbols.len() as u32).sum(),
+            languages,
+            last_refresh,
+        }
+    }
+
+    /// The map handed to agents: language stats, then every file (under `prefix`, when
+    /// given) with the symbols it defines, cut off after `max_chars`
+    pub fn render_map(&self, prefix: Option<&str>, max_chars: usize) -> String {
+        let summary = self.summary(RefreshStats::default());
+        let mut map = format!("{} tracked files", summary.files);
+        if !summary.languages.is_empty() {
+            let languages: Vec<String> = summary
+                .languages
+                .iter()
+                .take(8)
+                .map(|stat| format!("{} ({})", stat.language, stat.files))
+                .collect();
+            map.push_str(&format!("; {}", languages.join(", ")));
+        }
+        map.push_str("\n\n");
+
+        let files: Vec<(&String, &FileEntry)> = self
+            .files
+            .iter()
+            .filter(|(path, _)| match prefix {
+                Some(prefix) => path.starts_with(prefix),
+                None => true,
+            })
+            .collect();
+        for (index, (path, entry)) in files.iter().enumerate() {
+            let mut line = path.to_string();
+            if !entry.symbols.is_empty() {
+                let names: Vec<String> = entry
+                    .symbols
+                    .iter()
+                    .take(MAP_SYMBOLS_PER_FILE)
+                    .map(|symbol| format!("{} {}", symbol.kind, symbol.name))
+                    .collect();
+                line.push_str(&format!(": {}", names.join(", ")));
+                if entry.symbols.len() > MAP_SYMBOLS_PER_FILE {
+                    line.push_str(&format!(
+                        ", +{} more",
+                        entry.symbols.len() - MAP_SYMBOLS_PER_FILE
+                    ));
+                }
+            }
+            if map.len() + line.len() + 1 > max_chars {
+                map.push_str(&format!("… and {} more files\n", files.len() - index));
+                break;
+            }
+            map.push_str(&line);
+            map.push('\n');
+        }
+        map
+    }
+}
+
+/// Cache file for the repository `repo` belongs to. Worktrees resolve to the repository
+/// they were created from.
+fn cache_path(repo: &Repository) -> PathBuf {
+    // A worktree's git dir is `<repo>/.git/worktrees/<name>`
+    let git_dir = match repo.is_worktree() {
+        true => repo
+            .path()
+            .parent()
+            .and_then(Path::parent)
+            .unwrap_or(repo.path()),
+        false => repo.path(),
+    };
+    let common_dir = fs::canonicalize(git_dir).unwrap_or_else(|_| git_dir.to_path_buf());
+    let digest = Sha256::digest(common_dir.to_string_lossy().as_bytes());
+    crate::utils::cache_dir()
+        .join("repo-analysis")
+        .join(format!("{}.json", &hex::encode(digest)[..16]))
+}
+
+fn load_cached(path: &Path) -> Option<RepoAnalysis> {
+    let analysis: RepoAnalysis = serde_json::from_slice(&fs::read(path).ok()?).ok()?;
+    (analysis.version == CACHE_VERSION).then_some(analysis)
+}
+
+/// The repository's analysis, refreshed from its current index and written back to the
+/// cache. Blocking; reads every changed blob.
+pub fn load_or_refresh(
+    repo_path: &Path,
+) -> Result<(RepoAnalysis, RefreshStats), RepoAnalysisError> {
+    let repo = Repository::open(repo_path)?;
+    let path = cache_path(&repo);
+    let mut analysis = load_cached(&path).unwrap_or_else(RepoAnalysis::empty);
+    let stats = analysis.refresh(&repo)?;
+
+    if stats.parsed > 0 || stats.removed > 0 || !path.exists() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Attempts refresh concurrently; a rename keeps readers from seeing half a file
+        let temp = path.with_extension(format!("{}.tmp", uuid::Uuid::new_v4()));
+        fs::write(&temp, serde_json::to_vec(&analysis)?)?;
+        fs::rename(&temp, &path)?;
+    }
+    Ok((analysis, stats))
+}
+
+#[derive(Debug)]
+pub enum RepoAnalysisError {
+    Git(git2::Error),
+    Io(std::io::Error),
+    Json(serde_json::Error),
+}
+
+impl std::fmt::Display for RepoAnalysisError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RepoAnalysisError::Git(e) => write!(f, "Git error: {}", e),
+            RepoAnalysisError::Io(e) => write!(f, "Failed to write the analysis cache: {}", e),
+            RepoAnalysisError::Json(e) => write!(f, "Failed to encode the analysis: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RepoAnalysisError {}
+
+impl From<git2::Error> for RepoAnalysisError {
+    fn from(e: git2::Error) -> Self {
+        RepoAnalysisError::Git(e)
+    }
+}
+
+impl From<std::io::Error> for RepoAnalysisError {
+    fn from(e: std::io::Error) -> Self {
+        RepoAnalysisError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for RepoAnalysisError {
+    fn from(e: serde_json::Error) -> Self {
+        RepoAnalysisError::Json(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn git(root: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(root)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn test_extract_symbols() {
+        let rust = "use std::fs;\n\npub struct Config {\n    fn inner() {}\n}\n\npub(crate) async fn load() {}\nimpl Config {}\nmacro_rules! log {}\n";
+        let names: Vec<(String, String, u32)> = extract_symbols("Rust", rust)
+            .into_iter()
+            .map(|s| (s.kind, s.name, s.line))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("struct".to_string(), "Config".to_string(), 3),
+                ("fn".to_string(), "load".to_string(), 7),
+                ("macro_rules".to_string(), "log".to_string(), 9),
+            ]
+        );
+
+        let ts = "export default function App() {}\nexport interface Props {}\n  const local = 1;\nexport const api = {};\n";
+        let names: Vec<String> = extract_symbols("TypeScript", ts)
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(names, vec!["App", "Props", "api"]);
+
+        let py = "class Service:\n    def run(self):\n        def helper():\n            pass\n";
+        let names: Vec<String> = extract_symbols("Python", py)
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(names, vec!["Service", "run"]);
+    }
+
+    #[test]
+    fn test_refresh_reuses_unchanged_blobs() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        git(root, &["init", "-q"]);
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), "pub fn one() {}\n").unwrap();
+        fs::write(root.join("src/main.rs"), "fn main() {}\n").unwrap();
+        fs::write(root.join("notes.txt"), "hello\n").unwrap();
+        git(root, &["add", "."]);
+
+        let repo = Repository::open(root).unwrap();
+        let mut analysis = RepoAnalysis::empty();
+        let first = analysis.refresh(&repo).unwrap();
+        assert_eq!(first.parsed, 3);
+
+        fs::write(
+            root.join("src/lib.rs"),
+            "pub fn one() {}\npub fn two() {}\n",
+        )
+        .unwrap();
+        fs::remove_file(root.join("notes.txt")).unwrap();
+        git(root, &["add", "-A"]);
+        let repo = Repository::open(root).unwrap();
+        let second = analysis.refresh(&repo).unwrap();
+        assert_eq!(
+            second,
+            RefreshStats {
+                reused: 1,
+                parsed: 1,
+                removed: 1
+            }
+        );
+        assert_eq!(analysis.files["src/lib.rs"].symbols.len(), 2);
+
+        let summary = analysis.summary(second);
+        assert_eq!(summary.files, 2);
+        assert_eq!(summary.languages[0].language, "Rust");
+
+        let map = analysis.render_map(None, DEFAULT_MAP_CHARS);
+        assert!(map.starts_with("2 tracked files; Rust (2)\n\n"));
+        assert!(map.contains("src/lib.rs: fn one, fn two\n"));
+        assert!(analysis.render_map(None, 40).contains("… and 2 more files"));
+        assert!(!analysis
+            .render_map(Some("src/m"), DEFAULT_MAP_CHARS)
+            .contains("src/lib.rs"));
+    }
+}
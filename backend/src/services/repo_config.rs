@@ -0,0 +1,275 @@
+//! A project's settings checked into its repository as `.vibe-kanban.toml`, so everyone
+//! working on it starts from the same ones:
+//!
+//! ```toml
+//! default_executor = "claude"
+//! setup_script = "pnpm install"
+//! verification_commands = ["cargo test", "pnpm run lint"]
+//! prompt_template = "{{title}}\n\n{{description}}"
+//! worktree_base_dir = "../worktrees"
+//! ```
+//!
+//! The file is read when the project is registered and again every time an attempt starts,
+//! so a change to it applies to the next run. Settings made on the project itself win over
+//! the file's; the file only fills in the ones the project leaves unset.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::{
+    executor::ExecutorConfig,
+    models::{project::Project, task_attempt::TaskAttempt},
+    services::{prompt_template, verification},
+};
+
+pub const REPO_CONFIG_FILE: &str = ".vibe-kanban.toml";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[serde(deny_unknown_fields)]
+#[ts(export)]
+pub struct RepoConfig {
+    /// Executor for attempts that don't pick one
+    pub default_executor: Option<String>,
+    /// Run once in each new worktree before the coding agent
+    pub setup_script: Option<String>,
+    /// Run one after another in the worktree once the coding agent finishes
+    #[serde(default)]
+    pub verification_commands: Vec<String>,
+    /// What the coding agent is prompted with; see `services::prompt_template`
+    pub prompt_template: Option<String>,
+    /// Where attempt worktrees are made, relative to the repository unless absolute
+    pub worktree_base_dir: Option<String>,
+}
+
+/// A project's settings as its runs use them: its own, with the gaps filled from its
+/// repository's `.vibe-kanban.toml`
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct EffectiveProjectConfig {
+    /// Whether the repository has a `.vibe-kanban.toml`
+    pub has_repo_config: bool,
+    pub default_executor: Option<String>,
+    pub setup_script: Option<String>,
+    pub verification_script: Option<String>,
+    /// The template for every executor; executors can still have their own on the project
+    pub prompt_template: Option<String>,
+    pub worktree_base_dir: String,
+    /// Settings above that come from the file rather than the project
+    pub from_repo_config: Vec<String>,
+}
+
+impl RepoConfig {
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let config: Self =
+            toml::from_str(contents).map_err(|e| format!("{}: {}", REPO_CONFIG_FILE, e))?;
+        config
+            .validate()
+            .map_err(|e| format!("{}: {}", REPO_CONFIG_FILE, e))?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if let Some(executor) = &self.default_executor {
+            executor
+                .parse::<ExecutorConfig>()
+                .map_err(|e| format!("invalid default_executor: {}", e))?;
+        }
+        if self
+            .verification_commands
+            .iter()
+            .any(|command| command.trim().is_empty())
+        {
+            return Err("verification_commands can't have empty commands".to_string());
+        }
+        if let Some(template) = &self.prompt_template {
+            prompt_template::validate(template)
+                .map_err(|e| format!("invalid prompt_template: {}", e))?;
+        }
+        if self
+            .worktree_base_dir
+            .as_deref()
+            .is_some_and(|dir| dir.trim().is_empty())
+        {
+            return Err("worktree_base_dir can't be empty".to_string());
+        }
+        Ok(())
+    }
+
+    /// The repository's config, if it has one. `Err` with what's wrong when it can't be read
+    /// or isn't valid.
+    pub fn load(repo_path: &Path) -> Result<Option<Self>, String> {
+        match std::fs::read_to_string(repo_path.join(REPO_CONFIG_FILE)) {
+            Ok(contents) => Self::parse(&contents).map(Some),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("Failed to read {}: {}", REPO_CONFIG_FILE, e)),
+        }
+    }
+
+    /// The verification commands as a project's verification script, one per line
+    pub fn verification_script(&self) -> Option<String> {
+        if self.verification_commands.is_empty() {
+            None
+        } else {
+            Some(self.verification_commands.join("\n"))
+        }
+    }
+
+    /// Where the repository's attempt worktrees are made
+    pub fn worktree_base_dir(&self, repo_path: &Path) -> Option<PathBuf> {
+        let dir = self.worktree_base_dir.as_deref()?.trim();
+        if let Some(rest) = dir.strip_prefix("~/") {
+            return dirs::home_dir().map(|home| home.join(rest));
+        }
+        Some(repo_path.join(dir))
+    }
+
+    /// Fill the settings `project` leaves unset from the file. Returns the names of those
+    /// filled.
+    pub fn fill(&self, project: &mut Project) -> Vec<&'static str> {
+        let mut filled = Vec::new();
+        let is_unset =
+            |value: &Option<String>| value.as_deref().is_none_or(|v| v.trim().is_empty());
+        if is_unset(&project.default_executor) && self.default_executor.is_some() {
+            project.default_executor = self.default_executor.clone();
+            filled.push("default_executor");
+        }
+        if is_unset(&project.setup_script) && self.setup_script.is_some() {
+            project.setup_script = self.setup_script.clone();
+            filled.push("setup_script");
+        }
+        if is_unset(&project.verification_script) {
+            if let Some(script) = self.verification_script() {
+                project.verification_script = Some(script);
+                filled.push("verification_script");
+            }
+        }
+        filled
+    }
+}
+
+/// Fill the settings `project` leaves unset from its repository's config, returning the
+/// config. `Err` with what's wrong when the file isn't valid; the project is left as it is.
+pub fn apply(project: &mut Project) -> Result<Option<RepoConfig>, String> {
+    let config = RepoConfig::load(Path::new(&project.git_repo_path))?;
+    if let Some(config) = &config {
+        config.fill(project);
+    }
+    Ok(config)
+}
+
+/// Where the project's attempt worktrees are made
+pub fn worktree_base_dir(project: &Project, config: Option<&RepoConfig>) -> PathBuf {
+    config
+        .and_then(|config| config.worktree_base_dir(Path::new(&project.git_repo_path)))
+        .unwrap_or_else(TaskAttempt::get_worktree_base_dir)
+}
+
+/// The effective config of `project`, given its own template for every executor
+pub fn effective(
+    project: &Project,
+    prompt_template: Option<String>,
+) -> Result<EffectiveProjectConfig, String> {
+    let mut effective_project = project.clone();
+    let config = RepoConfig::load(Path::new(&project.git_repo_path))?;
+    let mut from_repo_config: Vec<String> = config
+        .as_ref()
+        .map(|config| config.fill(&mut effective_project))
+        .unwrap_or_default()
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+
+    let prompt_template = match prompt_template {
+        Some(template) => Some(template),
+        None => {
+            let template = config
+                .as_ref()
+                .and_then(|config| config.prompt_template.clone());
+            if template.is_some() {
+                from_repo_config.push("prompt_template".to_string());
+            }
+            template
+        }
+    };
+    if config
+        .as_ref()
+        .is_some_and(|config| config.worktree_base_dir.is_some())
+    {
+        from_repo_config.push("worktree_base_dir".to_string());
+    }
+
+    Ok(EffectiveProjectConfig {
+        has_repo_config: config.is_some(),
+        default_executor: effective_project.default_executor,
+        setup_script: effective_project.setup_script,
+        verification_script: effective_project
+            .verification_script
+            .filter(|script| !verification::parse_commands(script).is_empty()),
+        prompt_template,
+        worktree_base_dir: worktree_base_dir(project, config.as_ref())
+            .to_string_lossy()
+            .to_string(),
+        from_repo_config,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reads_settings_and_rejects_unknown_keys() {
+        let config = RepoConfig::parse(
+            "default_executor = \"claude\"\nverification_commands = [\"cargo test\", \"cargo clippy\"]\nworktree_base_dir = \"../worktrees\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.default_executor.as_deref(), Some("claude"));
+        assert_eq!(
+            config.verification_script().as_deref(),
+            Some("cargo test\ncargo clippy")
+        );
+        assert_eq!(
+            config.worktree_base_dir(Path::new("/repo")),
+            Some(PathBuf::from("/repo/../worktrees"))
+        );
+
+        assert!(RepoConfig::parse("setup = \"make\"\n").is_err());
+        assert!(RepoConfig::parse("default_executor = \"nope\"\n").is_err());
+        assert!(RepoConfig::parse("prompt_template = \"{{#title}}\"\n").is_err());
+    }
+
+    #[test]
+    fn test_fill_keeps_the_projects_own_settings() {
+        let config = RepoConfig {
+            default_executor: Some("claude".to_string()),
+            setup_script: Some("pnpm install".to_string()),
+            verification_commands: vec!["pnpm test".to_string()],
+            ..Default::default()
+        };
+        let now = chrono::Utc::now();
+        let mut project = Project {
+            id: uuid::Uuid::new_v4(),
+            name: "project".to_string(),
+            git_repo_path: "/repo".to_string(),
+            setup_script: Some("make setup".to_string()),
+            dev_script: None,
+            cleanup_script: None,
+            commit_message_template: None,
+            release_script: None,
+            merge_check_script: None,
+            verification_script: Some(String::new()),
+            default_executor: None,
+            default_branch: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let filled = config.fill(&mut project);
+        assert_eq!(filled, vec!["default_executor", "verification_script"]);
+        assert_eq!(project.setup_script.as_deref(), Some("make setup"));
+        assert_eq!(project.default_executor.as_deref(), Some("claude"));
+        assert_eq!(project.verification_script.as_deref(), Some("pnpm test"));
+    }
+}
@@ -0,0 +1,321 @@
+//! A rough prediction of what an attempt will cost before it starts: how many tokens the
+//! agent is likely to burn and which files it will probably touch. Repository size and the
+//! paths the task mentions give a baseline; when a language model is configured, a small
+//! call over the file list refines the files and the scope. The numbers are only meant to
+//! catch the occasional task that is much bigger than it looks.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::{
+    models::config::RunEstimateConfig,
+    services::llm::{LlmClient, LlmError},
+    utils::text::file_references,
+};
+
+/// System prompt, tool definitions and the agent's first look around
+const BASE_TOKENS: u64 = 30_000;
+/// Listing and searching grows with the repository, up to a point
+const EXPLORATION_TOKENS_PER_FILE: u64 = 10;
+const MAX_EXPLORED_FILES: u64 = 5_000;
+/// Each likely file is usually read, edited and read again
+const READS_PER_FILE: u64 = 3;
+const BYTES_PER_TOKEN: u64 = 4;
+/// The repository listing sent to the model is cut after this many paths
+const MAX_LISTED_PATHS: usize = 800;
+const MAX_LIKELY_FILES: usize = 30;
+
+const ESTIMATE_PROMPT: &str = "You estimate the scope of a coding task before an agent \
+starts on it. Given the task and the list of files in the repository, reply with JSON only, \
+in the form {\"files\": [\"path\", ...], \"scope\": \"small\" | \"medium\" | \"large\"}. \
+`files` are the files the change will most likely edit, at most 15, spelled exactly as listed. \
+`scope` is small for a focused fix, medium for a feature touching a few areas and large for \
+cross-cutting work.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum EstimateScope {
+    Small,
+    Medium,
+    Large,
+}
+
+impl EstimateScope {
+    /// Tokens for the agent's own reasoning and output at this scope
+    fn work_tokens(self) -> u64 {
+        match self {
+            EstimateScope::Small => 20_000,
+            EstimateScope::Medium => 80_000,
+            EstimateScope::Large => 250_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct RunEstimate {
+    pub estimated_tokens: u32,
+    pub scope: EstimateScope,
+    pub likely_files: Vec<String>,
+    pub repo_files: usize,
+    pub repo_bytes: u64,
+    /// Whether a language model refined the estimate, or only the heuristics went into it
+    pub used_llm: bool,
+    /// Why the estimate needs a confirmation; empty when it stays under the thresholds
+    pub warnings: Vec<String>,
+}
+
+/// Relative path and size of every file in the repository, respecting .gitignore
+#[derive(Debug, Clone, Default)]
+pub struct RepoProfile {
+    pub files: HashMap<String, u64>,
+}
+
+impl RepoProfile {
+    pub fn scan(root: &Path) -> Self {
+        let walker = ignore::WalkBuilder::new(root)
+            .hidden(false)
+            .filter_entry(|entry| entry.file_name() != ".git")
+            .build();
+        let files = walker
+            .flatten()
+            .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+            .filter_map(|entry| {
+                let relative = entry.path().strip_prefix(root).ok()?;
+                let size = entry.metadata().ok()?.len();
+                Some((relative.to_string_lossy().replace('\\', "/"), size))
+            })
+            .collect();
+        Self { files }
+    }
+
+    fn total_bytes(&self) -> u64 {
+        self.files.values().sum()
+    }
+
+    /// A repository path for a path mentioned in the task: exact, or the only file that ends
+    /// with it
+    fn resolve(&self, mentioned: &str) -> Option<String> {
+        if self.files.contains_key(mentioned) {
+            return Some(mentioned.to_string());
+        }
+        let suffix = format!("/{}", mentioned);
+        let mut matches = self.files.keys().filter(|path| path.ends_with(&suffix));
+        let first = matches.next()?;
+        matches.next().is_none().then(|| first.clone())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelEstimate {
+    #[serde(default)]
+    files: Vec<String>,
+    scope: EstimateScope,
+}
+
+/// Estimate from the repository and the task text alone
+pub fn heuristic_estimate(
+    profile: &RepoProfile,
+    title: &str,
+    description: Option<&str>,
+) -> (EstimateScope, Vec<String>) {
+    let text = format!("{}\n{}", title, description.unwrap_or_default());
+    let files: Vec<String> = file_references(&text)
+        .iter()
+        .filter_map(|mentioned| profile.resolve(mentioned))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    let description_chars = description.map(|d| d.chars().count()).unwrap_or(0);
+    let scope = match (files.len(), description_chars) {
+        (0..=2, 0..=600) => EstimateScope::Small,
+        (0..=6, 0..=3000) => EstimateScope::Medium,
+        _ => EstimateScope::Large,
+    };
+    (scope, files)
+}
+
+/// Ask the model for the likely files and scope. Files it names that aren't in the
+/// repository are dropped.
+async fn model_estimate(
+    client: &LlmClient,
+    profile: &RepoProfile,
+    title: &str,
+    description: Option<&str>,
+) -> Result<(EstimateScope, Vec<String>), LlmError> {
+    let mut paths: Vec<&String> = profile.files.keys().collect();
+    paths.sort();
+    let mut listing = paths
+        .iter()
+        .take(MAX_LISTED_PATHS)
+        .map(|path| path.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    if paths.len() > MAX_LISTED_PATHS {
+        listing.push_str(&format!(
+            "\n…and {} more files",
+            paths.len() - MAX_LISTED_PATHS
+        ));
+    }
+
+    let mut input = format!("Task: {}\n", title);
+    if let Some(description) = description {
+        input.push_str(&format!("\n{}\n", description));
+    }
+    input.push_str(&format!("\nFiles in the repository:\n{}", listing));
+
+    let reply = client.complete(ESTIMATE_PROMPT, &input).await?;
+    let estimate = parse_model_estimate(&reply).ok_or(LlmError::InvalidResponse)?;
+    let files = estimate
+        .files
+        .iter()
+        .filter_map(|path| profile.resolve(path.trim().trim_start_matches("./")))
+        .collect();
+    Ok((estimate.scope, files))
+}
+
+/// Models like to wrap JSON in prose or code fences, so take the outermost object
+fn parse_model_estimate(reply: &str) -> Option<ModelEstimate> {
+    let start = reply.find('{')?;
+    let end = reply.rfind('}')?;
+    serde_json::from_str(reply.get(start..=end)?).ok()
+}
+
+pub fn estimate_tokens(profile: &RepoProfile, scope: EstimateScope, files: &[String]) -> u64 {
+    let exploration =
+        (profile.files.len() as u64).min(MAX_EXPLORED_FILES) * EXPLORATION_TOKENS_PER_FILE;
+    let file_bytes: u64 = files
+        .iter()
+        .filter_map(|path| profile.files.get(path))
+        .sum();
+    BASE_TOKENS + exploration + scope.work_tokens() + file_bytes / BYTES_PER_TOKEN * READS_PER_FILE
+}
+
+fn warnings(config: &RunEstimateConfig, tokens: u32, files: usize) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if tokens > config.confirm_above_tokens {
+        warnings.push(format!(
+            "About {}k tokens, above the {}k limit set in Settings",
+            tokens / 1000,
+            config.confirm_above_tokens / 1000
+        ));
+    }
+    if files > config.confirm_above_files as usize {
+        warnings.push(format!(
+            "Likely touches {} files, above the limit of {}",
+            files, config.confirm_above_files
+        ));
+    }
+    warnings
+}
+
+pub async fn estimate(
+    config: &RunEstimateConfig,
+    client: Option<&LlmClient>,
+    profile: &RepoProfile,
+    title: &str,
+    description: Option<&str>,
+) -> RunEstimate {
+    let (mut scope, mut files) = heuristic_estimate(profile, title, description);
+
+    let mut used_llm = false;
+    if let Some(client) = client {
+        match model_estimate(client, profile, title, description).await {
+            Ok((model_scope, model_files)) => {
+                used_llm = true;
+                scope = model_scope;
+                files = files
+                    .into_iter()
+                    .chain(model_files)
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .collect();
+            }
+            Err(e) => {
+                tracing::warn!("Falling back to heuristics for the run estimate: {}", e);
+            }
+        }
+    }
+    files.truncate(MAX_LIKELY_FILES);
+
+    let estimated_tokens =
+        u32::try_from(estimate_tokens(profile, scope, &files)).unwrap_or(u32::MAX);
+    RunEstimate {
+        estimated_tokens,
+        scope,
+        warnings: warnings(config, estimated_tokens, files.len()),
+        likely_files: files,
+        repo_files: profile.files.len(),
+        repo_bytes: profile.total_bytes(),
+        used_llm,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> RepoProfile {
+        RepoProfile {
+            files: HashMap::from([
+                ("src/main.rs".to_string(), 4_000),
+                ("src/routes/tasks.rs".to_string(), 40_000),
+                ("frontend/src/lib/api.ts".to_string(), 20_000),
+                ("README.md".to_string(), 2_000),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_heuristic_estimate_resolves_mentioned_files() {
+        let (scope, files) = heuristic_estimate(
+            &profile(),
+            "Fix the tasks route",
+            Some("The handler in routes/tasks.rs and `api.ts` disagree; see docs/missing.md"),
+        );
+        assert_eq!(scope, EstimateScope::Small);
+        assert_eq!(
+            files,
+            vec!["frontend/src/lib/api.ts", "src/routes/tasks.rs"]
+        );
+    }
+
+    #[test]
+    fn test_tokens_and_warnings() {
+        let profile = profile();
+        let files = vec!["src/routes/tasks.rs".to_string()];
+        // 30k base + 4 files explored + 80k work + 40k bytes read three times
+        assert_eq!(
+            estimate_tokens(&profile, EstimateScope::Medium, &files),
+            30_000 + 40 + 80_000 + 30_000
+        );
+
+        let config = RunEstimateConfig {
+            enabled: true,
+            confirm_above_tokens: 100_000,
+            confirm_above_files: 5,
+        };
+        assert_eq!(
+            warnings(&config, 140_040, 1),
+            vec!["About 140k tokens, above the 100k limit set in Settings"]
+        );
+        assert!(warnings(&config, 90_000, 5).is_empty());
+    }
+
+    #[test]
+    fn test_parse_model_estimate() {
+        let reply =
+            "Here you go:\n```json\n{\"files\": [\"src/main.rs\"], \"scope\": \"large\"}\n```";
+        let estimate = parse_model_estimate(reply).unwrap();
+        assert_eq!(estimate.scope, EstimateScope::Large);
+        assert_eq!(estimate.files, vec!["src/main.rs"]);
+        assert!(parse_model_estimate("no idea").is_none());
+    }
+}
@@ -0,0 +1,156 @@
+//! A one-line outcome of a finished coding agent run, for board cards and the run's
+//! conversation: the gist of the agent's closing message and what it changed. Worked out
+//! from the normalized conversation, so it's the same for every executor.
+
+use crate::{
+    executor::{NormalizedEntry, NormalizedEntryType},
+    models::execution_diff::DiffSummary,
+};
+
+const MAX_OUTCOME_CHARS: usize = 140;
+
+/// The agent's closing message, if it left one
+pub fn final_message(entries: &[NormalizedEntry]) -> Option<String> {
+    last_content(entries, |entry_type| {
+        matches!(entry_type, NormalizedEntryType::AssistantMessage)
+    })
+}
+
+/// What the run came to in a line, as in "Added a login page · changed 3 files (+40 −12)".
+/// Falls back to its last error when the agent said nothing; `None` if there is neither
+/// and nothing changed.
+pub fn outcome(entries: &[NormalizedEntry], diff: Option<&DiffSummary>) -> Option<String> {
+    let said = final_message(entries)
+        .and_then(|message| gist(&message))
+        .or_else(|| {
+            last_content(entries, |entry_type| {
+                matches!(entry_type, NormalizedEntryType::ErrorMessage)
+            })
+            .and_then(|error| gist(&error))
+            .map(|error| format!("Error: {}", error))
+        });
+    let changed = diff.filter(|diff| !diff.files.is_empty()).map(|diff| {
+        format!(
+            "{} file{} (+{} −{})",
+            diff.files.len(),
+            if diff.files.len() == 1 { "" } else { "s" },
+            diff.additions,
+            diff.deletions
+        )
+    });
+    match (said, changed) {
+        (Some(said), Some(changed)) => Some(format!("{} · changed {}", said, changed)),
+        (Some(said), None) => Some(said),
+        (None, Some(changed)) => Some(format!("Changed {}", changed)),
+        (None, None) => None,
+    }
+}
+
+fn last_content(
+    entries: &[NormalizedEntry],
+    matches: impl Fn(&NormalizedEntryType) -> bool,
+) -> Option<String> {
+    entries
+        .iter()
+        .rev()
+        .filter(|entry| matches(&entry.entry_type))
+        .map(|entry| entry.content.trim())
+        .find(|content| !content.is_empty())
+        .map(str::to_string)
+}
+
+/// The first sentence of `text` that isn't a heading, without markdown emphasis
+fn gist(text: &str) -> Option<String> {
+    let line = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#') && !line.starts_with("```"))
+        .map(|line| {
+            line.trim_start_matches(['-', '*', '>', ' '])
+                .replace("**", "")
+                .replace("__", "")
+        })
+        .find(|line| !line.trim().is_empty())?;
+    let sentence = line
+        .match_indices(['.', '!', '?'])
+        .map(|(i, _)| i + 1)
+        .find(|&end| line[end..].starts_with(' '))
+        .map_or(line.as_str(), |end| &line[..end])
+        .trim();
+
+    if sentence.chars().count() <= MAX_OUTCOME_CHARS {
+        return Some(sentence.to_string());
+    }
+    let clipped: String = sentence.chars().take(MAX_OUTCOME_CHARS - 1).collect();
+    Some(format!("{}…", clipped.trim_end()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::execution_diff::FileDiffSummary;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            id: None,
+            timestamp: None,
+            duration_ms: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_outcome_from_closing_message_and_diff() {
+        let entries = vec![
+            entry(NormalizedEntryType::AssistantMessage, "Looking around."),
+            entry(
+                NormalizedEntryType::AssistantMessage,
+                "## Summary\n\n**Added the login page.** It checks the session first.\n\n- src/login.tsx",
+            ),
+        ];
+        let diff = DiffSummary {
+            additions: 40,
+            deletions: 12,
+            files: vec![
+                FileDiffSummary {
+                    path: "src/login.tsx".to_string(),
+                    additions: 38,
+                    deletions: 0,
+                },
+                FileDiffSummary {
+                    path: "src/routes.ts".to_string(),
+                    additions: 2,
+                    deletions: 12,
+                },
+            ],
+        };
+
+        assert_eq!(
+            outcome(&entries, Some(&diff)).as_deref(),
+            Some("Added the login page. · changed 2 files (+40 −12)")
+        );
+        assert_eq!(
+            outcome(&entries, None).as_deref(),
+            Some("Added the login page.")
+        );
+        assert_eq!(
+            outcome(&[], Some(&diff)).as_deref(),
+            Some("Changed 2 files (+40 −12)")
+        );
+    }
+
+    #[test]
+    fn test_outcome_falls_back_to_the_last_error() {
+        let entries = vec![entry(
+            NormalizedEntryType::ErrorMessage,
+            "Rate limit reached for requests. Try again in 20s.",
+        )];
+        assert_eq!(
+            outcome(&entries, None).as_deref(),
+            Some("Error: Rate limit reached for requests.")
+        );
+        assert_eq!(outcome(&[], None), None);
+    }
+}
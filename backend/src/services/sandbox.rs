@@ -0,0 +1,154 @@
+//! Whether a coding agent runs sandboxed, and what it may write to when it does
+
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+use crate::{
+    command_runner::Sandbox,
+    db::DbPool,
+    models::{config::SandboxConfig, project_sandbox::ProjectSandbox},
+};
+
+/// Whether `executor` is sandboxed and, if so, whether it keeps the network. The project's
+/// own setting, if any, takes the place of the global one; the list of agents applies to
+/// both.
+pub fn policy(
+    config: &SandboxConfig,
+    project: Option<&ProjectSandbox>,
+    executor: &str,
+) -> Option<bool> {
+    let (enabled, deny_network) = match project {
+        Some(project) => (project.enabled, project.deny_network),
+        None => (config.enabled, config.deny_network),
+    };
+    let mut listed = config
+        .executors
+        .iter()
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+        .peekable();
+    let covers_executor = listed.peek().is_none() || listed.any(|name| name == executor);
+    (enabled && covers_executor).then_some(deny_network)
+}
+
+/// The paths an agent working in `worktree_path` must write to: the worktree, and the main
+/// repository's git directory, where a worktree's commits, refs and index live. For the
+/// workspace of a multi-repository task, that of each checkout in it.
+pub fn writable_paths(worktree_path: &Path) -> Vec<PathBuf> {
+    let mut paths = vec![worktree_path.to_path_buf()];
+    let checkouts: Vec<PathBuf> = if worktree_path.join(".git").exists() {
+        vec![worktree_path.to_path_buf()]
+    } else {
+        std::fs::read_dir(worktree_path)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| path.join(".git").exists())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    for checkout in checkouts {
+        if let Ok(repo) = git2::Repository::open(&checkout) {
+            let common_dir = common_dir(repo.path());
+            if !common_dir.starts_with(worktree_path) && !paths.contains(&common_dir) {
+                paths.push(common_dir);
+            }
+        }
+    }
+    paths
+}
+
+/// The directory holding the objects and refs a git directory shares: for a linked
+/// worktree the main repository's, named in its `commondir` file, otherwise its own
+fn common_dir(git_dir: &Path) -> PathBuf {
+    match std::fs::read_to_string(git_dir.join("commondir")) {
+        Ok(common) => {
+            let common = git_dir.join(common.trim());
+            common.canonicalize().unwrap_or(common)
+        }
+        Err(_) => git_dir.to_path_buf(),
+    }
+}
+
+/// The sandbox a task's coding agent runs in, or `None` when it isn't sandboxed
+pub async fn for_coding_agent(
+    pool: &DbPool,
+    config: &SandboxConfig,
+    project_id: Uuid,
+    executor: &str,
+    worktree_path: &str,
+) -> Result<Option<Sandbox>, sqlx::Error> {
+    let project = ProjectSandbox::find_by_project_id(pool, project_id).await?;
+    Ok(
+        policy(config, project.as_ref(), executor).map(|deny_network| Sandbox {
+            writable: writable_paths(Path::new(worktree_path)),
+            deny_network,
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn project(enabled: bool, deny_network: bool) -> ProjectSandbox {
+        ProjectSandbox {
+            project_id: Uuid::new_v4(),
+            enabled,
+            deny_network,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_project_setting_replaces_global_one() {
+        let config = SandboxConfig {
+            enabled: true,
+            deny_network: false,
+            executors: Vec::new(),
+        };
+        assert_eq!(policy(&config, None, "claude"), Some(false));
+        assert_eq!(
+            policy(&config, Some(&project(false, false)), "claude"),
+            None
+        );
+        assert_eq!(
+            policy(
+                &SandboxConfig::default(),
+                Some(&project(true, true)),
+                "codex"
+            ),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn test_only_listed_executors_are_sandboxed() {
+        let config = SandboxConfig {
+            enabled: true,
+            deny_network: true,
+            executors: vec!["codex".to_string(), " ".to_string()],
+        };
+        assert_eq!(policy(&config, None, "codex"), Some(true));
+        assert_eq!(policy(&config, None, "claude"), None);
+        assert_eq!(policy(&config, Some(&project(true, false)), "claude"), None);
+    }
+
+    #[test]
+    fn test_linked_worktrees_share_the_main_git_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let main = dir.path().join("repo/.git");
+        let linked = main.join("worktrees/feature");
+        std::fs::create_dir_all(&linked).unwrap();
+        std::fs::write(linked.join("commondir"), "../..\n").unwrap();
+
+        let main = main.canonicalize().unwrap();
+        assert_eq!(common_dir(&linked), main);
+        assert_eq!(common_dir(&main), main);
+    }
+}
@@ -0,0 +1,167 @@
+//! Follow-ups that carry only part of an attempt's diff. The user picks files, or single
+//! hunks within them, and writes an instruction; the agent gets that instruction with just
+//! the selected changes, rather than having to work out which of its edits are meant.
+
+use serde::Deserialize;
+use ts_rs::TS;
+
+use crate::models::task_attempt::{DiffChunkType, DiffHunk, FileHunks};
+
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export)]
+pub struct HunkSelection {
+    pub path: String,
+    /// Indexes from the attempt's hunk listing; `None` selects every hunk in the file
+    pub hunks: Option<Vec<u32>>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateScopedFollowUp {
+    pub prompt: String,
+    pub selections: Vec<HunkSelection>,
+}
+
+/// The follow-up prompt for `request`, or why the selection doesn't match the diff
+pub fn compile_scoped_prompt(
+    request: &CreateScopedFollowUp,
+    files: &[FileHunks],
+) -> Result<String, String> {
+    let instruction = request.prompt.trim();
+    if instruction.is_empty() {
+        return Err("Write what the agent should do with the selected changes".to_string());
+    }
+    if request.selections.is_empty() {
+        return Err("Select at least one file or hunk".to_string());
+    }
+
+    let mut sections = Vec::new();
+    for selection in &request.selections {
+        let file = files
+            .iter()
+            .find(|file| file.path == selection.path)
+            .ok_or_else(|| format!("{} has no changes in this attempt", selection.path))?;
+        let hunks: Vec<&DiffHunk> = match &selection.hunks {
+            None => file.hunks.iter().collect(),
+            Some(indexes) if indexes.is_empty() => {
+                return Err(format!("No hunks selected in {}", selection.path));
+            }
+            Some(indexes) => indexes
+                .iter()
+                .map(|index| {
+                    file.hunks
+                        .iter()
+                        .find(|hunk| hunk.index == *index)
+                        .ok_or_else(|| {
+                            format!(
+                                "{} has no hunk {}; the diff may have changed since it was loaded",
+                                selection.path, index
+                            )
+                        })
+                })
+                .collect::<Result<_, _>>()?,
+        };
+
+        let mut section = format!("### {}\n```diff\n", file.path);
+        for hunk in hunks {
+            section.push_str(&unified_hunk(hunk));
+        }
+        section.push_str("```\n");
+        sections.push(section);
+    }
+
+    Ok(format!(
+        "{}\n\nThis follow-up is only about the changes below, which you made earlier in this \
+         attempt. Leave the rest of your changes as they are.\n\n{}",
+        instruction,
+        sections.join("\n")
+    ))
+}
+
+/// The hunk as unified diff text, header first
+pub fn unified_hunk(hunk: &DiffHunk) -> String {
+    let mut text = format!("{}\n", hunk.header);
+    for line in &hunk.lines {
+        let prefix = match line.chunk_type {
+            DiffChunkType::Equal => ' ',
+            DiffChunkType::Insert => '+',
+            DiffChunkType::Delete => '-',
+        };
+        text.push(prefix);
+        text.push_str(&line.content);
+        if !line.content.ends_with('\n') {
+            text.push('\n');
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::task_attempt::DiffChunk;
+
+    fn hunk(index: u32, added: &str) -> DiffHunk {
+        DiffHunk {
+            index,
+            header: format!("@@ -{0},1 +{0},1 @@ fn f{0}()", index * 10 + 1),
+            old_start: index * 10 + 1,
+            new_start: index * 10 + 1,
+            lines: vec![
+                DiffChunk {
+                    chunk_type: DiffChunkType::Delete,
+                    content: "old\n".to_string(),
+                },
+                DiffChunk {
+                    chunk_type: DiffChunkType::Insert,
+                    content: format!("{}\n", added),
+                },
+            ],
+        }
+    }
+
+    fn files() -> Vec<FileHunks> {
+        vec![
+            FileHunks {
+                path: "src/lib.rs".to_string(),
+                hunks: vec![hunk(0, "first"), hunk(1, "second")],
+            },
+            FileHunks {
+                path: "README.md".to_string(),
+                hunks: vec![hunk(0, "docs")],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_prompt_includes_only_selected_hunks() {
+        let request = CreateScopedFollowUp {
+            prompt: "  Rename the variable  ".to_string(),
+            selections: vec![HunkSelection {
+                path: "src/lib.rs".to_string(),
+                hunks: Some(vec![1]),
+            }],
+        };
+        let prompt = compile_scoped_prompt(&request, &files()).unwrap();
+        assert!(prompt.starts_with("Rename the variable\n\n"));
+        assert!(prompt
+            .contains("### src/lib.rs\n```diff\n@@ -11,1 +11,1 @@ fn f11()\n-old\n+second\n```"));
+        assert!(!prompt.contains("+first"));
+        assert!(!prompt.contains("README.md"));
+    }
+
+    #[test]
+    fn test_invalid_selections_are_rejected() {
+        let select = |path: &str, hunks: Option<Vec<u32>>| CreateScopedFollowUp {
+            prompt: "Fix it".to_string(),
+            selections: vec![HunkSelection {
+                path: path.to_string(),
+                hunks,
+            }],
+        };
+        assert!(compile_scoped_prompt(&select("README.md", None), &files()).is_ok());
+        assert!(compile_scoped_prompt(&select("src/main.rs", None), &files()).is_err());
+        assert!(compile_scoped_prompt(&select("src/lib.rs", Some(vec![2])), &files()).is_err());
+        assert!(compile_scoped_prompt(&select("src/lib.rs", Some(vec![])), &files()).is_err());
+    }
+}
@@ -0,0 +1,592 @@
+//! Project script hooks: small Rhai scripts a project runs when one of its tasks is created,
+//! when an attempt's coding agent finishes, and before an attempt is merged. A script sees
+//! the task, and the attempt for attempt events, and acts only through the functions below.
+//! It has no modules, files or network, and is stopped after a bounded number of operations.
+//!
+//! - `task`: `id`, `title`, `description`, `status` and `labels`
+//! - `attempt`, on attempt events: `id`, `branch`, `base_branch`, `runs`, how often its coding
+//!   agent has run, and after it finishes, `succeeded`
+//! - `set_labels(["bug", "ui"])` replaces the task's labels
+//! - `notify(title, message)` sends a desktop notification, as the settings allow
+//! - `enqueue_follow_up(prompt)` runs the attempt's coding agent again once it has finished
+//! - `block_merge(reason)` stops the merge, before one
+//!
+//! `print` and `debug` write to the server's log. A hook that fails is logged and noted on
+//! the hook; it doesn't stop the hooks after it, nor a merge.
+
+use std::{cell::RefCell, rc::Rc};
+
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Map, Scope};
+use tokio::sync::broadcast::error::RecvError;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    models::{
+        event::{self, Event, EventKind},
+        execution_process::{ExecutionProcess, ExecutionProcessType},
+        script_hook::{ScriptEvent, ScriptHook},
+        task::Task,
+        task_attempt::TaskAttempt,
+        task_label::{normalize_label, TaskLabels},
+    },
+    services::{NotificationConfig, NotificationService, ProcessService},
+};
+
+const MAX_OPERATIONS: u64 = 1_000_000;
+const MAX_STRING_BYTES: usize = 64 * 1024;
+const MAX_COLLECTION_SIZE: usize = 1_000;
+const MAX_NOTIFICATIONS: usize = 5;
+/// Coding agent runs after which hooks can't ask an attempt for another follow-up, so a
+/// hook that always asks for one doesn't run forever
+const MAX_SCRIPTED_RUNS: i64 = 5;
+
+/// The task, as scripts see it
+#[derive(Debug, Clone)]
+pub struct ScriptTask {
+    pub id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub status: String,
+    pub labels: Vec<String>,
+}
+
+/// The attempt, as scripts of attempt events see it
+#[derive(Debug, Clone)]
+pub struct ScriptAttempt {
+    pub id: Uuid,
+    pub branch: String,
+    pub base_branch: String,
+    pub runs: i64,
+    /// Whether its coding agent succeeded, once it has finished
+    pub succeeded: Option<bool>,
+}
+
+/// What a script asked for
+#[derive(Debug, Default, PartialEq)]
+pub struct ScriptActions {
+    pub labels: Option<Vec<String>>,
+    pub notifications: Vec<(String, String)>,
+    pub follow_up: Option<String>,
+    pub block_merge: Option<String>,
+}
+
+fn event_name(event: ScriptEvent) -> &'static str {
+    match event {
+        ScriptEvent::TaskCreated => "task_created",
+        ScriptEvent::AttemptFinished => "attempt_finished",
+        ScriptEvent::BeforeMerge => "before_merge",
+    }
+}
+
+fn unavailable(function: &str, event: ScriptEvent) -> Box<EvalAltResult> {
+    format!("{} can't be used by {} hooks", function, event_name(event)).into()
+}
+
+fn engine(event: ScriptEvent, actions: Rc<RefCell<ScriptActions>>) -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(32)
+        .set_max_expr_depths(64, 32)
+        .set_max_string_size(MAX_STRING_BYTES)
+        .set_max_array_size(MAX_COLLECTION_SIZE)
+        .set_max_map_size(MAX_COLLECTION_SIZE);
+    engine.disable_symbol("eval");
+    engine.on_print(|text| tracing::info!("[script hook] {}", text));
+    engine.on_debug(|text, _, position| tracing::debug!("[script hook] {} at {}", text, position));
+
+    let labels = actions.clone();
+    engine.register_fn(
+        "set_labels",
+        move |names: Array| -> Result<(), Box<EvalAltResult>> {
+            let names = names
+                .into_iter()
+                .map(|name| {
+                    name.into_string()
+                        .map_err(|kind| format!("labels are strings, not {}", kind).into())
+                })
+                .collect::<Result<Vec<String>, Box<EvalAltResult>>>()?;
+            labels.borrow_mut().labels = Some(names);
+            Ok(())
+        },
+    );
+
+    let notifications = actions.clone();
+    engine.register_fn(
+        "notify",
+        move |title: &str, message: &str| -> Result<(), Box<EvalAltResult>> {
+            let mut actions = notifications.borrow_mut();
+            if actions.notifications.len() >= MAX_NOTIFICATIONS {
+                return Err(
+                    format!("a hook sends at most {} notifications", MAX_NOTIFICATIONS).into(),
+                );
+            }
+            actions
+                .notifications
+                .push((title.to_string(), message.to_string()));
+            Ok(())
+        },
+    );
+
+    let follow_up = actions.clone();
+    engine.register_fn(
+        "enqueue_follow_up",
+        move |prompt: &str| -> Result<(), Box<EvalAltResult>> {
+            if event != ScriptEvent::AttemptFinished {
+                return Err(unavailable("enqueue_follow_up", event));
+            }
+            if prompt.trim().is_empty() {
+                return Err("a follow-up needs a prompt".into());
+            }
+            follow_up.borrow_mut().follow_up = Some(prompt.to_string());
+            Ok(())
+        },
+    );
+
+    engine.register_fn(
+        "block_merge",
+        move |reason: &str| -> Result<(), Box<EvalAltResult>> {
+            if event != ScriptEvent::BeforeMerge {
+                return Err(unavailable("block_merge", event));
+            }
+            actions.borrow_mut().block_merge = Some(reason.to_string());
+            Ok(())
+        },
+    );
+
+    engine
+}
+
+fn strings(values: &[String]) -> Dynamic {
+    Dynamic::from_array(values.iter().cloned().map(Dynamic::from).collect())
+}
+
+fn task_map(task: &ScriptTask) -> Map {
+    let mut map = Map::new();
+    map.insert("id".into(), task.id.to_string().into());
+    map.insert("title".into(), task.title.clone().into());
+    map.insert(
+        "description".into(),
+        task.description
+            .clone()
+            .map_or(Dynamic::UNIT, Dynamic::from),
+    );
+    map.insert("status".into(), task.status.clone().into());
+    map.insert("labels".into(), strings(&task.labels));
+    map
+}
+
+fn attempt_map(attempt: &ScriptAttempt) -> Map {
+    let mut map = Map::new();
+    map.insert("id".into(), attempt.id.to_string().into());
+    map.insert("branch".into(), attempt.branch.clone().into());
+    map.insert("base_branch".into(), attempt.base_branch.clone().into());
+    map.insert("runs".into(), attempt.runs.into());
+    if let Some(succeeded) = attempt.succeeded {
+        map.insert("succeeded".into(), succeeded.into());
+    }
+    map
+}
+
+/// Why the script doesn't compile, if it doesn't
+pub fn compile_error(script: &str) -> Option<String> {
+    engine(ScriptEvent::TaskCreated, Rc::default())
+        .compile(script)
+        .err()
+        .map(|e| e.to_string())
+}
+
+/// Run a hook's script, returning what it asked for
+pub fn run(
+    event: ScriptEvent,
+    script: &str,
+    task: &ScriptTask,
+    attempt: Option<&ScriptAttempt>,
+) -> Result<ScriptActions, String> {
+    let actions = Rc::new(RefCell::new(ScriptActions::default()));
+    let engine = engine(event, actions.clone());
+    let mut scope = Scope::new();
+    scope.push_constant("task", task_map(task));
+    if let Some(attempt) = attempt {
+        scope.push_constant("attempt", attempt_map(attempt));
+    }
+    engine
+        .run_with_scope(&mut scope, script)
+        .map_err(|e| e.to_string())?;
+    Ok(actions.take())
+}
+
+async fn script_task(app_state: &AppState, task: &Task) -> Result<ScriptTask, sqlx::Error> {
+    let labels = TaskLabels::find_by_task_id(&app_state.db_pool, task.id).await?;
+    Ok(ScriptTask {
+        id: task.id,
+        title: task.title.clone(),
+        description: task.description.clone(),
+        status: serde_json::to_value(&task.status)
+            .ok()
+            .and_then(|status| status.as_str().map(str::to_string))
+            .unwrap_or_default(),
+        labels: labels.labels,
+    })
+}
+
+async fn script_attempt(
+    app_state: &AppState,
+    task_attempt: &TaskAttempt,
+    succeeded: Option<bool>,
+) -> Result<ScriptAttempt, sqlx::Error> {
+    let runs = ExecutionProcess::find_by_task_attempt_id(&app_state.db_pool, task_attempt.id)
+        .await?
+        .iter()
+        .filter(|process| process.process_type == ExecutionProcessType::CodingAgent)
+        .count() as i64;
+    Ok(ScriptAttempt {
+        id: task_attempt.id,
+        branch: task_attempt.branch.clone(),
+        base_branch: task_attempt.base_branch.clone(),
+        runs,
+        succeeded,
+    })
+}
+
+async fn notify(app_state: &AppState, title: &str, message: &str) {
+    let sound_enabled = app_state.get_sound_alerts_enabled().await;
+    let push_enabled = app_state.get_push_notifications_enabled().await;
+    if !sound_enabled && !push_enabled {
+        return;
+    }
+    let sound_file = app_state.get_sound_file().await;
+    NotificationService::new(NotificationConfig {
+        sound_enabled,
+        push_enabled,
+    })
+    .notify(title, message, &sound_file)
+    .await;
+}
+
+/// Carry out what a hook asked for. Returns why the merge is blocked, if the hook blocked it.
+async fn apply(
+    app_state: &AppState,
+    task: &Task,
+    seen: &mut ScriptTask,
+    attempt: Option<&ScriptAttempt>,
+    followed_up: &mut bool,
+    actions: ScriptActions,
+) -> Result<Option<String>, String> {
+    let pool = &app_state.db_pool;
+    if let Some(names) = actions.labels {
+        let mut labels: Vec<String> = names
+            .iter()
+            .filter_map(|name| normalize_label(name))
+            .collect();
+        labels.sort();
+        labels.dedup();
+        for old in seen.labels.iter().filter(|label| !labels.contains(label)) {
+            TaskLabels::remove(pool, task.id, old)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        for new in labels.iter().filter(|label| !seen.labels.contains(label)) {
+            TaskLabels::add(pool, task.id, new)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        // Hooks after this one see the labels it set
+        seen.labels = labels;
+    }
+
+    for (title, message) in &actions.notifications {
+        notify(app_state, title, message).await;
+    }
+
+    if let (Some(prompt), Some(attempt)) = (actions.follow_up, attempt) {
+        if *followed_up {
+            return Err("another hook already enqueued a follow-up".to_string());
+        }
+        if attempt.runs >= MAX_SCRIPTED_RUNS {
+            return Err(format!(
+                "the attempt has run its coding agent {} times, after which hooks can't enqueue follow-ups",
+                attempt.runs
+            ));
+        }
+        ProcessService::start_followup_execution(
+            pool,
+            app_state,
+            attempt.id,
+            task.id,
+            task.project_id,
+            &prompt,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+        *followed_up = true;
+    }
+
+    Ok(actions.block_merge)
+}
+
+/// Run the project's enabled hooks for `event`, in name order. Returns why the merge is
+/// blocked, when a before-merge hook blocks it; the hooks after it don't run.
+async fn run_hooks(
+    app_state: &AppState,
+    event: ScriptEvent,
+    task: &Task,
+    attempt: Option<ScriptAttempt>,
+) -> Result<Option<String>, sqlx::Error> {
+    let pool = &app_state.db_pool;
+    let hooks = ScriptHook::find_enabled(pool, task.project_id, event).await?;
+    if hooks.is_empty() {
+        return Ok(None);
+    }
+    let mut seen = script_task(app_state, task).await?;
+    let mut followed_up = false;
+    for hook in hooks {
+        let (script, script_task, script_attempt) =
+            (hook.script.clone(), seen.clone(), attempt.clone());
+        let actions = tokio::task::spawn_blocking(move || {
+            run(event, &script, &script_task, script_attempt.as_ref())
+        })
+        .await
+        .unwrap_or_else(|e| Err(e.to_string()));
+        let outcome = match actions {
+            Ok(actions) => {
+                apply(
+                    app_state,
+                    task,
+                    &mut seen,
+                    attempt.as_ref(),
+                    &mut followed_up,
+                    actions,
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+        ScriptHook::record_run(pool, hook.id, outcome.as_ref().err().map(String::as_str)).await?;
+        match outcome {
+            Ok(Some(reason)) => {
+                tracing::info!("Script hook {} blocked merging task {}", hook.name, task.id);
+                return Ok(Some(reason));
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!(
+                "Script hook {} of project {} failed: {}",
+                hook.name,
+                task.project_id,
+                e
+            ),
+        }
+    }
+    Ok(None)
+}
+
+pub async fn task_created(app_state: &AppState, task: &Task) {
+    if let Err(e) = run_hooks(app_state, ScriptEvent::TaskCreated, task, None).await {
+        tracing::error!(
+            "Failed to run task created hooks of task {}: {}",
+            task.id,
+            e
+        );
+    }
+}
+
+pub async fn attempt_finished(app_state: &AppState, task_attempt_id: Uuid, succeeded: bool) {
+    let result = async {
+        let Some(task_attempt) =
+            TaskAttempt::find_by_id(&app_state.db_pool, task_attempt_id).await?
+        else {
+            return Ok(());
+        };
+        let Some(task) = Task::find_by_id(&app_state.db_pool, task_attempt.task_id).await? else {
+            return Ok(());
+        };
+        let attempt = script_attempt(app_state, &task_attempt, Some(succeeded)).await?;
+        run_hooks(
+            app_state,
+            ScriptEvent::AttemptFinished,
+            &task,
+            Some(attempt),
+        )
+        .await
+        .map(|_| ())
+    }
+    .await;
+    if let Err(e) = result {
+        tracing::error!(
+            "Failed to run attempt finished hooks of attempt {}: {}",
+            task_attempt_id,
+            e
+        );
+    }
+}
+
+/// Why the project's hooks block merging the attempt, if one does
+pub async fn before_merge(
+    app_state: &AppState,
+    task: &Task,
+    task_attempt: &TaskAttempt,
+) -> Option<String> {
+    let result = async {
+        let attempt = script_attempt(app_state, task_attempt, None).await?;
+        run_hooks(app_state, ScriptEvent::BeforeMerge, task, Some(attempt)).await
+    }
+    .await;
+    result.unwrap_or_else(|e| {
+        tracing::error!(
+            "Failed to run before merge hooks of attempt {}: {}",
+            task_attempt.id,
+            e
+        );
+        None
+    })
+}
+
+/// Runs the task created and attempt finished hooks as the events are recorded
+pub struct ScriptHookService {
+    app_state: AppState,
+}
+
+impl ScriptHookService {
+    pub fn new(app_state: AppState) -> Self {
+        Self { app_state }
+    }
+
+    pub async fn start(&self) {
+        let mut receiver = event::subscribe();
+        loop {
+            match receiver.recv().await {
+                Ok(event) => self.handle(&event).await,
+                Err(RecvError::Lagged(missed)) => {
+                    tracing::warn!("Script hooks missed {} events", missed);
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    }
+
+    async fn handle(&self, event: &Event) {
+        let coding_agent = event.details["process_type"] == "codingagent";
+        match (event.kind, event.task_id, event.task_attempt_id) {
+            (EventKind::TaskCreated, Some(task_id), _) => {
+                match Task::find_by_id(&self.app_state.db_pool, task_id).await {
+                    Ok(Some(task)) => task_created(&self.app_state, &task).await,
+                    Ok(None) => {}
+                    Err(e) => tracing::error!("Failed to load task {}: {}", task_id, e),
+                }
+            }
+            (EventKind::ExecutionCompleted | EventKind::ExecutionFailed, _, Some(attempt_id))
+                if coding_agent =>
+            {
+                let succeeded = event.kind == EventKind::ExecutionCompleted;
+                attempt_finished(&self.app_state, attempt_id, succeeded).await;
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task() -> ScriptTask {
+        ScriptTask {
+            id: Uuid::new_v4(),
+            title: "Fix the login page".to_string(),
+            description: None,
+            status: "todo".to_string(),
+            labels: vec!["frontend".to_string()],
+        }
+    }
+
+    fn attempt(runs: i64, succeeded: Option<bool>) -> ScriptAttempt {
+        ScriptAttempt {
+            id: Uuid::new_v4(),
+            branch: "vk/fix-login".to_string(),
+            base_branch: "main".to_string(),
+            runs,
+            succeeded,
+        }
+    }
+
+    #[test]
+    fn test_scripts_read_the_task_and_set_labels() {
+        let script = r#"
+            let labels = task.labels;
+            if task.title.contains("login") { labels.push("auth"); }
+            set_labels(labels);
+            notify("New task", task.title);
+        "#;
+        let actions = run(ScriptEvent::TaskCreated, script, &task(), None).unwrap();
+        assert_eq!(
+            actions.labels,
+            Some(vec!["frontend".to_string(), "auth".to_string()])
+        );
+        assert_eq!(
+            actions.notifications,
+            vec![("New task".to_string(), "Fix the login page".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_attempt_hooks_see_the_attempt() {
+        let script = r#"
+            if !attempt.succeeded && attempt.runs < 3 {
+                enqueue_follow_up("The tests fail; fix them");
+            }
+        "#;
+        let failed = run(
+            ScriptEvent::AttemptFinished,
+            script,
+            &task(),
+            Some(&attempt(1, Some(false))),
+        )
+        .unwrap();
+        assert_eq!(
+            failed.follow_up.as_deref(),
+            Some("The tests fail; fix them")
+        );
+        let succeeded = run(
+            ScriptEvent::AttemptFinished,
+            script,
+            &task(),
+            Some(&attempt(1, Some(true))),
+        )
+        .unwrap();
+        assert_eq!(succeeded, ScriptActions::default());
+
+        let blocked = run(
+            ScriptEvent::BeforeMerge,
+            r#"
+                if attempt.base_branch == "main" && !task.labels.contains("hotfix") {
+                    block_merge("main is frozen");
+                }
+            "#,
+            &task(),
+            Some(&attempt(1, None)),
+        )
+        .unwrap();
+        assert_eq!(blocked.block_merge.as_deref(), Some("main is frozen"));
+    }
+
+    #[test]
+    fn test_scripts_are_confined() {
+        let error = run(
+            ScriptEvent::TaskCreated,
+            r#"block_merge("no")"#,
+            &task(),
+            None,
+        )
+        .unwrap_err();
+        assert!(error.contains("block_merge can't be used by task_created hooks"));
+
+        let error = run(ScriptEvent::TaskCreated, "loop {}", &task(), None).unwrap_err();
+        assert!(error.contains("Too many operations"), "{}", error);
+
+        assert!(compile_error(r#"import "secrets" as s;"#).is_some());
+        assert!(compile_error(r#"eval("1")"#).is_some());
+        assert!(compile_error("let x = ;").is_some());
+        assert_eq!(compile_error(r#"set_labels(["bug"])"#), None);
+    }
+}
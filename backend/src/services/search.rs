@@ -0,0 +1,135 @@
+//! Full-text search across tasks and coding agent conversations, backed by an SQLite FTS5
+//! index or PostgreSQL's text search. Tasks are indexed as they change; conversations are indexed once their process has
+//! finished, on the first search after that.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use tokio::sync::Mutex;
+
+use crate::{
+    db::DbPool,
+    models::{
+        conversation_search::searchable_text,
+        execution_process::ExecutionProcess,
+        search_document::{NewConversationDocument, SearchDocument, SearchFilters, SearchHit},
+    },
+    routes::task_attempts::normalize_process_logs,
+};
+
+/// Keeps concurrent searches from indexing the same conversation twice
+static INDEX_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Turn what the user typed into an FTS5 query matching every word. Each word is quoted, so
+/// `payments.rs` matches the file name rather than being read as query syntax. PostgreSQL's
+/// `websearch_to_tsquery` reads the same query the same way.
+pub fn fts_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split_whitespace()
+        .map(|word| word.replace('"', ""))
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .map(|word| format!("\"{}\"", word))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// A date filter, as `YYYY-MM-DD` or an RFC 3339 time. A bare date covers the whole day, so
+/// with `end_of_day` it stands for its last second.
+pub fn parse_date(text: &str, end_of_day: bool) -> Result<DateTime<Utc>, String> {
+    let text = text.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .map_err(|_| format!("`{}` is not a date (YYYY-MM-DD) or RFC 3339 time", text))?;
+    let time = if end_of_day {
+        date.and_hms_opt(23, 59, 59)
+    } else {
+        date.and_hms_opt(0, 0, 0)
+    };
+    time.map(|t| t.and_utc())
+        .ok_or_else(|| format!("`{}` is out of range", text))
+}
+
+/// Add the conversations of coding agent processes that finished since the last search
+pub async fn index_conversations(pool: &DbPool) -> Result<(), sqlx::Error> {
+    let _guard = INDEX_LOCK.lock().await;
+
+    for process in ExecutionProcess::find_coding_agents_missing_from_search_index(pool).await? {
+        let conversation = normalize_process_logs(pool, &process).await;
+        let documents: Vec<NewConversationDocument> = conversation
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(entry_index, entry)| {
+                let content = searchable_text(entry);
+                if content.trim().is_empty() {
+                    return None;
+                }
+                Some(NewConversationDocument {
+                    entry_index,
+                    entry_type: entry.entry_type.clone(),
+                    timestamp: entry.timestamp.clone(),
+                    content,
+                })
+            })
+            .collect();
+        SearchDocument::add_conversation_entries(
+            pool,
+            process.id,
+            &documents,
+            conversation.entries.len(),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Search tasks and conversations for every word of `text`, best matches first
+pub async fn search(
+    pool: &DbPool,
+    text: &str,
+    filters: &SearchFilters,
+    limit: usize,
+) -> Result<Vec<SearchHit>, sqlx::Error> {
+    let Some(query) = fts_query(text) else {
+        return Ok(Vec::new());
+    };
+    index_conversations(pool).await?;
+    SearchDocument::search(pool, &query, filters, limit as i64).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fts_query_quotes_each_word() {
+        assert_eq!(
+            fts_query("payments.rs  \"retry\" OR").as_deref(),
+            Some("\"payments.rs\" \"retry\" \"OR\"")
+        );
+        assert_eq!(fts_query(" \" - "), None);
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(
+            parse_date("2025-08-20", false).unwrap().to_rfc3339(),
+            "2025-08-20T00:00:00+00:00"
+        );
+        assert_eq!(
+            parse_date("2025-08-20", true).unwrap().to_rfc3339(),
+            "2025-08-20T23:59:59+00:00"
+        );
+        assert_eq!(
+            parse_date("2025-08-20T10:30:00+02:00", true)
+                .unwrap()
+                .to_rfc3339(),
+            "2025-08-20T08:30:00+00:00"
+        );
+        assert!(parse_date("yesterday", false).is_err());
+    }
+}
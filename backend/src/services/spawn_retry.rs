@@ -0,0 +1,108 @@
+//! Deciding when a coding agent that failed to start is started again, and how long to
+//! wait before it is
+
+use std::time::Duration;
+
+use crate::models::{
+    config::RetryPolicy,
+    execution_process::{ExecutionProcess, ExecutionProcessStatus, ExecutionProcessType},
+};
+
+/// A run that ends in an error this soon after starting never got going, which usually
+/// means a missing binary or bad arguments rather than a problem with the task
+pub const IMMEDIATE_EXIT: Duration = Duration::from_secs(10);
+
+/// Longest wait between two starts, however many retries came before
+const MAX_BACKOFF: Duration = Duration::from_secs(15 * 60);
+
+/// The wait before starting an execution again after `retry_count` earlier retries, or
+/// `None` once the policy has used up its attempts
+pub fn retry_delay(policy: &RetryPolicy, retry_count: i64) -> Option<Duration> {
+    let retry_count = u32::try_from(retry_count).ok()?;
+    if retry_count.saturating_add(1) >= policy.max_attempts {
+        return None;
+    }
+    let factor = 2u64.saturating_pow(retry_count);
+    let delay = u64::from(policy.backoff_seconds).saturating_mul(factor);
+    Some(Duration::from_secs(delay).min(MAX_BACKOFF))
+}
+
+/// Whether a finished execution is the first run of a coding agent that failed right
+/// after starting. Follow-ups are left alone, they already fall back to a fresh session.
+pub fn failed_on_start(process: &ExecutionProcess) -> bool {
+    let ran_for = process
+        .completed_at
+        .map(|completed_at| completed_at - process.started_at)
+        .and_then(|ran_for| ran_for.to_std().ok());
+    process.process_type == ExecutionProcessType::CodingAgent
+        && process.status == ExecutionProcessStatus::Failed
+        && process.command == "executor"
+        && ran_for.is_some_and(|ran_for| ran_for < IMMEDIATE_EXIT)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn policy(max_attempts: u32, backoff_seconds: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            backoff_seconds,
+        }
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_until_attempts_run_out() {
+        let four_starts = policy(4, 5);
+        assert_eq!(retry_delay(&four_starts, 0), Some(Duration::from_secs(5)));
+        assert_eq!(retry_delay(&four_starts, 1), Some(Duration::from_secs(10)));
+        assert_eq!(retry_delay(&four_starts, 2), Some(Duration::from_secs(20)));
+        assert_eq!(retry_delay(&four_starts, 3), None);
+
+        assert_eq!(retry_delay(&policy(1, 5), 0), None, "retries turned off");
+        assert_eq!(retry_delay(&policy(100, 60), 40), Some(MAX_BACKOFF));
+    }
+
+    #[test]
+    fn test_failed_on_start() {
+        let started_at = Utc::now();
+        let mut process = ExecutionProcess {
+            id: Uuid::new_v4(),
+            task_attempt_id: Uuid::new_v4(),
+            process_type: ExecutionProcessType::CodingAgent,
+            executor_type: Some("claude".to_string()),
+            status: ExecutionProcessStatus::Failed,
+            command: "executor".to_string(),
+            args: None,
+            working_directory: "/tmp".to_string(),
+            stdout: None,
+            stderr: Some("claude: command not found".to_string()),
+            exit_code: Some(127),
+            retry_count: 0,
+            retry_at: None,
+            commit_sha: None,
+            failure_reason: None,
+            model: None,
+            started_at,
+            completed_at: Some(started_at + chrono::Duration::seconds(1)),
+            duration_ms: Some(1000),
+            created_at: started_at,
+            updated_at: started_at,
+        };
+        assert!(failed_on_start(&process));
+
+        process.completed_at = Some(started_at + chrono::Duration::minutes(5));
+        assert!(!failed_on_start(&process), "a long run failed on its own");
+
+        process.completed_at = Some(started_at + chrono::Duration::seconds(1));
+        process.command = "followup_executor".to_string();
+        assert!(!failed_on_start(&process));
+
+        process.command = "executor".to_string();
+        process.status = ExecutionProcessStatus::TimedOut;
+        assert!(!failed_on_start(&process));
+    }
+}
@@ -0,0 +1,373 @@
+//! Flags tasks that have sat in In Progress or In Review without any activity for longer than
+//! the configured thresholds, so half-finished work in forgotten worktrees shows up on the
+//! board, and nudges the user once per idle stretch through the usual notifications and the
+//! project's webhooks. Tasks left stale long enough can be archived along with their
+//! worktrees, unless those hold uncommitted changes.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use chrono::{DateTime, Utc};
+use tokio::{sync::RwLock, time::interval};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    execution_monitor,
+    models::{
+        config::{Config, StaleTasksConfig},
+        stale_task::{IdleTask, StaleTask},
+        task::{Task, TaskStatus},
+    },
+    services::{notification_webhooks, NotificationConfig, NotificationEvent, NotificationService},
+};
+
+pub struct StaleTaskService {
+    pool: DbPool,
+    poll_interval: Duration,
+}
+
+impl StaleTaskService {
+    pub fn new(pool: DbPool) -> Self {
+        Self {
+            pool,
+            poll_interval: Duration::from_secs(10 * 60),
+        }
+    }
+
+    pub async fn start_with_config(&self, config: Arc<RwLock<Config>>) {
+        info!(
+            "Starting stale task check with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+        loop {
+            interval.tick().await;
+
+            let config = config.read().await.clone();
+            if !config.stale_tasks.enabled {
+                if let Err(e) = StaleTask::unflag_all(&self.pool).await {
+                    error!("Failed to clear stale task flags: {}", e);
+                }
+                continue;
+            }
+
+            match self.check(&config.stale_tasks, Utc::now()).await {
+                Ok(newly_stale) if !newly_stale.is_empty() => {
+                    self.nudge(&config, &newly_stale).await;
+                }
+                Ok(_) => {}
+                Err(e) => error!("Error checking for stale tasks: {}", e),
+            }
+            if let Err(e) = self
+                .archive_abandoned(&config.stale_tasks, Utc::now())
+                .await
+            {
+                error!("Error archiving abandoned tasks: {}", e);
+            }
+        }
+    }
+
+    /// Bring the flags up to date and return the tasks that still need a nudge
+    pub async fn check(
+        &self,
+        config: &StaleTasksConfig,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<IdleTask>, sqlx::Error> {
+        let flagged: HashMap<Uuid, StaleTask> = StaleTask::find_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|stale| (stale.task_id, stale))
+            .collect();
+
+        let mut still_stale = Vec::new();
+        let mut newly_stale = Vec::new();
+        for task in IdleTask::find_all(&self.pool).await? {
+            if !is_stale(config, &task.status, task.last_activity, now) {
+                continue;
+            }
+            StaleTask::flag(&self.pool, task.id, &task.status, task.last_activity).await?;
+            still_stale.push(task.id);
+
+            let already_nudged = flagged.get(&task.id).is_some_and(|stale| {
+                stale.nudged_at.is_some()
+                    && stale.status == task.status
+                    && stale.idle_since == task.last_activity
+            });
+            if !already_nudged {
+                newly_stale.push(task);
+            }
+        }
+
+        for task_id in flagged.keys() {
+            if !still_stale.contains(task_id) {
+                StaleTask::unflag(&self.pool, *task_id).await?;
+            }
+        }
+        Ok(newly_stale)
+    }
+
+    async fn nudge(&self, config: &Config, tasks: &[IdleTask]) {
+        let task_ids: Vec<Uuid> = tasks.iter().map(|task| task.id).collect();
+        let enabled = (config.sound_alerts || config.push_notifications)
+            && NotificationEvent::TaskStale.is_enabled(&config.notification_events);
+        if enabled {
+            let (title, message) = nudge_message(tasks, Utc::now());
+            NotificationService::new(NotificationConfig {
+                sound_enabled: config.sound_alerts,
+                push_enabled: config.push_notifications,
+            })
+            .notify(&title, &message, &config.sound_file)
+            .await;
+        }
+
+        let mut by_project: HashMap<Uuid, Vec<IdleTask>> = HashMap::new();
+        for task in tasks {
+            by_project
+                .entry(task.project_id)
+                .or_default()
+                .push(task.clone());
+        }
+        for (project_id, tasks) in by_project {
+            let (title, message) = nudge_message(&tasks, Utc::now());
+            if let Err(e) = notification_webhooks::queue(
+                &self.pool,
+                project_id,
+                None,
+                NotificationEvent::TaskStale,
+                &title,
+                &message,
+            )
+            .await
+            {
+                error!(
+                    "Failed to queue stale task webhooks for project {}: {}",
+                    project_id, e
+                );
+            }
+        }
+
+        // Also recorded when notifications are off, so turning them back on doesn't replay
+        // every task that went stale in the meantime
+        if let Err(e) = StaleTask::mark_nudged(&self.pool, &task_ids).await {
+            error!("Failed to record stale task nudges: {}", e);
+        }
+    }
+
+    /// Archive the stale tasks idle for longer than `archive_after_days` and remove their
+    /// worktrees; the branches stay, so picking a task back up recreates them. Worktrees with
+    /// uncommitted changes are kept, as those changes are on no branch. Returns the archived
+    /// tasks.
+    pub async fn archive_abandoned(
+        &self,
+        config: &StaleTasksConfig,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        if config.archive_after_days.is_none() {
+            return Ok(Vec::new());
+        }
+
+        let mut archived = Vec::new();
+        for task in IdleTask::find_all(&self.pool).await? {
+            if !is_abandoned(config, &task.status, task.last_activity, now)
+                || !Task::archive(&self.pool, task.id, task.project_id).await?
+            {
+                continue;
+            }
+            StaleTask::unflag(&self.pool, task.id).await?;
+            match execution_monitor::cleanup_clean_task_worktrees(&self.pool, task.id).await {
+                Ok(kept) if !kept.is_empty() => info!(
+                    "Kept the worktrees of {} attempt(s) of archived task {}, which have uncommitted changes",
+                    kept.len(),
+                    task.id
+                ),
+                Ok(_) => {}
+                Err(e) => error!(
+                    "Failed to remove the worktrees of archived task {}: {}",
+                    task.id, e
+                ),
+            }
+            info!(
+                "Archived task {} after {} without activity",
+                task.id,
+                idle_for(task.last_activity, now)
+            );
+            archived.push(task.id);
+        }
+        Ok(archived)
+    }
+}
+
+pub fn threshold_hours(config: &StaleTasksConfig, status: &TaskStatus) -> Option<u32> {
+    match status {
+        TaskStatus::InProgress => Some(config.in_progress_hours),
+        TaskStatus::PlanReview | TaskStatus::InReview => Some(config.in_review_hours),
+        // Queued tasks are waiting on other executions, not on anyone's attention
+        TaskStatus::Todo | TaskStatus::Queued | TaskStatus::Done | TaskStatus::Cancelled => None,
+    }
+}
+
+fn is_stale(
+    config: &StaleTasksConfig,
+    status: &TaskStatus,
+    last_activity: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> bool {
+    match threshold_hours(config, status) {
+        // A zero threshold turns the check off for that column
+        Some(0) | None => false,
+        Some(hours) => now - last_activity >= chrono::Duration::hours(hours.into()),
+    }
+}
+
+/// Whether a stale task has been idle long enough to archive
+fn is_abandoned(
+    config: &StaleTasksConfig,
+    status: &TaskStatus,
+    last_activity: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> bool {
+    match config.archive_after_days {
+        Some(days) if days > 0 => {
+            is_stale(config, status, last_activity, now)
+                && now - last_activity >= chrono::Duration::days(days.into())
+        }
+        _ => false,
+    }
+}
+
+fn idle_for(last_activity: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let hours = (now - last_activity).num_hours();
+    match hours {
+        h if h >= 48 => format!("{} days", h / 24),
+        1 => "1 hour".to_string(),
+        h => format!("{} hours", h),
+    }
+}
+
+fn nudge_message(tasks: &[IdleTask], now: DateTime<Utc>) -> (String, String) {
+    match tasks {
+        [task] => {
+            let column = match task.status {
+                TaskStatus::PlanReview => "waiting for plan approval",
+                TaskStatus::InReview => "in review",
+                _ => "in progress",
+            };
+            (
+                format!("Task Idle: {}", task.title),
+                format!(
+                    "⏰ '{}' has been {} for {} without activity",
+                    task.title,
+                    column,
+                    idle_for(task.last_activity, now)
+                ),
+            )
+        }
+        _ => {
+            let titles: Vec<String> = tasks
+                .iter()
+                .take(3)
+                .map(|task| format!("'{}'", task.title))
+                .collect();
+            let more = if tasks.len() > 3 {
+                format!(" and {} more", tasks.len() - 3)
+            } else {
+                String::new()
+            };
+            (
+                format!("{} Tasks Idle", tasks.len()),
+                format!("⏰ {}{} have gone stale", titles.join(", "), more),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> StaleTasksConfig {
+        StaleTasksConfig {
+            enabled: true,
+            in_progress_hours: 24,
+            in_review_hours: 0,
+            archive_after_days: Some(14),
+        }
+    }
+
+    #[test]
+    fn test_thresholds_per_column() {
+        let now = Utc::now();
+        let day_ago = now - chrono::Duration::hours(25);
+        assert!(is_stale(&config(), &TaskStatus::InProgress, day_ago, now));
+        assert!(!is_stale(
+            &config(),
+            &TaskStatus::InProgress,
+            now - chrono::Duration::hours(23),
+            now
+        ));
+        // Zero disables the review column, and other columns are never stale
+        assert!(!is_stale(&config(), &TaskStatus::InReview, day_ago, now));
+        assert!(!is_stale(&config(), &TaskStatus::Todo, day_ago, now));
+    }
+
+    #[test]
+    fn test_only_stale_tasks_idle_long_enough_are_abandoned() {
+        let now = Utc::now();
+        let weeks_ago = now - chrono::Duration::days(15);
+        assert!(is_abandoned(
+            &config(),
+            &TaskStatus::InProgress,
+            weeks_ago,
+            now
+        ));
+        assert!(!is_abandoned(
+            &config(),
+            &TaskStatus::InProgress,
+            now - chrono::Duration::days(13),
+            now
+        ));
+        // A column left out of the stale check is never archived either
+        assert!(!is_abandoned(
+            &config(),
+            &TaskStatus::InReview,
+            weeks_ago,
+            now
+        ));
+        let keep = StaleTasksConfig {
+            archive_after_days: None,
+            ..config()
+        };
+        assert!(!is_abandoned(
+            &keep,
+            &TaskStatus::InProgress,
+            weeks_ago,
+            now
+        ));
+    }
+
+    #[test]
+    fn test_nudge_message() {
+        let now = Utc::now();
+        let task = |title: &str, hours| IdleTask {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            title: title.to_string(),
+            status: TaskStatus::InReview,
+            last_activity: now - chrono::Duration::hours(hours),
+        };
+
+        let (title, message) = nudge_message(&[task("Fix login", 73)], now);
+        assert_eq!(title, "Task Idle: Fix login");
+        assert_eq!(
+            message,
+            "⏰ 'Fix login' has been in review for 3 days without activity"
+        );
+
+        let tasks: Vec<IdleTask> = ["a", "b", "c", "d"].iter().map(|t| task(t, 30)).collect();
+        let (title, message) = nudge_message(&tasks, now);
+        assert_eq!(title, "4 Tasks Idle");
+        assert_eq!(message, "⏰ 'a', 'b', 'c' and 1 more have gone stale");
+    }
+}
@@ -0,0 +1,162 @@
+//! Task decomposition: a planning attempt is asked to split its task into smaller steps
+//! instead of implementing it, and the steps it proposes become subtasks of the task. Steps
+//! the agent handed off as tasks of their own are taken first; otherwise they are read from
+//! the numbered or bulleted list its plan ended with.
+
+use crate::executor::{ActionType, NormalizedEntry, NormalizedEntryType};
+
+/// Appended to the prompt of every run of a decomposition attempt
+pub const DECOMPOSITION_PROMPT: &str =
+    "Do not change any files and do not implement anything. Investigate what the task needs \
+     and split it into smaller subtasks that can each be implemented and reviewed on their \
+     own, in the order they should be done. End with the subtasks as your final message, as \
+     a numbered list with one subtask per item: a short title, a colon, then what the \
+     subtask involves.";
+
+/// Longest title a proposed step keeps; the rest of it moves to the description
+const MAX_TITLE_CHARS: usize = 80;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProposedSubtask {
+    pub title: String,
+    pub description: Option<String>,
+}
+
+impl ProposedSubtask {
+    /// Split a step into a title and a description: at its first colon if what comes
+    /// before is short enough for a title, otherwise at its first line
+    fn from_text(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let (head, rest) = text.split_once('\n').unwrap_or((text, ""));
+        let (title, rest) = match head.split_once(": ") {
+            Some((title, detail)) if title.chars().count() <= MAX_TITLE_CHARS => {
+                (title, format!("{}\n{}", detail, rest))
+            }
+            _ => (head, rest.to_string()),
+        };
+        let title = title.trim().trim_matches('*').trim();
+        if title.is_empty() {
+            return None;
+        }
+        let (title, rest) = if title.chars().count() > MAX_TITLE_CHARS {
+            let cut: String = title.chars().take(MAX_TITLE_CHARS).collect();
+            let cut = cut.rsplit_once(' ').map_or(cut.as_str(), |(cut, _)| cut);
+            (format!("{}…", cut.trim_end()), text.to_string())
+        } else {
+            (title.to_string(), rest)
+        };
+        let description = rest.trim();
+        Some(Self {
+            title,
+            description: (!description.is_empty()).then(|| description.to_string()),
+        })
+    }
+}
+
+/// The steps a run handed off as tasks of their own, in the order it created them
+pub fn subtasks_from_entries(entries: &[NormalizedEntry]) -> Vec<ProposedSubtask> {
+    entries
+        .iter()
+        .filter_map(|entry| match &entry.entry_type {
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::TaskCreate { description },
+                ..
+            } => ProposedSubtask::from_text(description),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The steps of a plan: the items of its last numbered list, or of its last bulleted list
+/// if it has none. Indented lines under an item belong to the item.
+pub fn subtasks_from_plan(plan: &str) -> Vec<ProposedSubtask> {
+    fn list_item(line: &str, numbered: bool) -> Option<&str> {
+        if numbered {
+            let digits = line.find(|c: char| !c.is_ascii_digit())?;
+            let rest = line[digits..].strip_prefix(['.', ')'])?;
+            (digits > 0 && rest.starts_with(' ')).then(|| rest.trim())
+        } else {
+            line.strip_prefix("- ")
+                .or_else(|| line.strip_prefix("* "))
+                .map(str::trim)
+        }
+    }
+
+    for numbered in [true, false] {
+        let mut lists: Vec<Vec<String>> = Vec::new();
+        let mut in_list = false;
+        for line in plan.lines() {
+            if let Some(item) = list_item(line, numbered) {
+                if !in_list {
+                    lists.push(Vec::new());
+                    in_list = true;
+                }
+                if let Some(list) = lists.last_mut() {
+                    list.push(item.to_string());
+                }
+            } else if in_list && (line.starts_with([' ', '\t']) || line.trim().is_empty()) {
+                if let Some(item) = lists.last_mut().and_then(|list| list.last_mut()) {
+                    item.push('\n');
+                    item.push_str(line.trim());
+                }
+            } else {
+                in_list = false;
+            }
+        }
+        if let Some(list) = lists.pop() {
+            return list
+                .iter()
+                .filter_map(|item| ProposedSubtask::from_text(item))
+                .collect();
+        }
+    }
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subtasks_from_plan_takes_the_last_numbered_list() {
+        let plan = "Looked at:\n1. the routes\n2. the models\n\nSubtasks:\n\n\
+                    1. **Add the table**: a migration for `subtasks`\n   with an index\n\
+                    2) Wire up the API\n\
+                    - not a step\n\
+                    Done.";
+        assert_eq!(
+            subtasks_from_plan(plan),
+            vec![
+                ProposedSubtask {
+                    title: "Add the table".to_string(),
+                    description: Some("a migration for `subtasks`\nwith an index".to_string()),
+                },
+                ProposedSubtask {
+                    title: "Wire up the API".to_string(),
+                    description: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_subtasks_from_plan_falls_back_to_bullets() {
+        let plan = "- Update the docs\n* Bump the version: in Cargo.toml";
+        let titles: Vec<_> = subtasks_from_plan(plan)
+            .into_iter()
+            .map(|subtask| subtask.title)
+            .collect();
+        assert_eq!(titles, vec!["Update the docs", "Bump the version"]);
+        assert!(subtasks_from_plan("Nothing to split here.").is_empty());
+    }
+
+    #[test]
+    fn test_long_steps_keep_a_short_title() {
+        let step = "Rework the execution monitor so that it no longer polls every process on \
+                    each tick but reacts to exit events";
+        let subtask = ProposedSubtask::from_text(step).unwrap();
+        assert!(subtask.title.ends_with('…'));
+        assert!(subtask.title.chars().count() <= MAX_TITLE_CHARS + 1);
+        assert_eq!(subtask.description.as_deref(), Some(step));
+    }
+}
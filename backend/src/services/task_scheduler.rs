@@ -0,0 +1,195 @@
+//! Recurring task runs. A task with a cron schedule is copied into a new subtask each time the
+//! schedule comes due, and the copy gets an attempt of its own with a fresh worktree, so every
+//! run can be reviewed and merged on its own. A schedule doesn't fire again while its last
+//! run is still going; the runs it missed meanwhile are skipped rather than caught up on.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tokio::time::interval;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    models::{
+        execution_process::ExecutionProcess,
+        task::{CreateTask, Task},
+        task_attempt::{CreateTaskAttempt, TaskAttempt},
+        task_defaults::TaskDefaults,
+        task_schedule::{TaskSchedule, TaskScheduleRun},
+    },
+    utils::cron::CronSchedule,
+};
+
+pub struct TaskSchedulerService {
+    app_state: AppState,
+    poll_interval: Duration,
+}
+
+impl TaskSchedulerService {
+    pub fn new(app_state: AppState) -> Self {
+        Self {
+            app_state,
+            poll_interval: Duration::from_secs(30),
+        }
+    }
+
+    pub async fn start(&self) {
+        info!(
+            "Starting task scheduler with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.run_due(Utc::now()).await {
+                error!("Error running scheduled tasks: {}", e);
+            }
+        }
+    }
+
+    /// Start a run of every enabled schedule that is due at `now`, returning the runs started
+    pub async fn run_due(&self, now: DateTime<Utc>) -> Result<Vec<TaskScheduleRun>, sqlx::Error> {
+        let pool = &self.app_state.db_pool;
+        let mut started = Vec::new();
+        for schedule in TaskSchedule::find_enabled(pool).await? {
+            if schedule
+                .next_run_at
+                .is_none_or(|next_run_at| next_run_at > now)
+            {
+                continue;
+            }
+
+            // Moved on first, so a run that fails to start isn't retried on every tick
+            let next_run_at = match schedule.cron_expression.parse::<CronSchedule>() {
+                Ok(cron) => cron.next_after(now),
+                Err(e) => {
+                    warn!(
+                        "Schedule {} has an invalid expression `{}`: {}",
+                        schedule.id, schedule.cron_expression, e
+                    );
+                    None
+                }
+            };
+            TaskSchedule::record_fired(pool, schedule.id, now, next_run_at).await?;
+
+            if self.is_still_running(&schedule).await? {
+                info!(
+                    "Skipping scheduled run of task {}: its last run hasn't finished",
+                    schedule.task_id
+                );
+                continue;
+            }
+            if let Some(run) = self.start_run(&schedule).await? {
+                started.push(run);
+            }
+        }
+        Ok(started)
+    }
+
+    /// Whether one of the schedule's runs is still going. A run whose attempt has no live
+    /// process left was stopped without finishing, and is closed as failed.
+    async fn is_still_running(&self, schedule: &TaskSchedule) -> Result<bool, sqlx::Error> {
+        let pool = &self.app_state.db_pool;
+        let mut still_running = false;
+        for run in TaskScheduleRun::find_running_by_schedule_id(pool, schedule.id).await? {
+            let Some(attempt_id) = run.task_attempt_id else {
+                TaskScheduleRun::fail(pool, run.id, "The run never started").await?;
+                continue;
+            };
+            let processes = ExecutionProcess::find_by_task_attempt_id(pool, attempt_id).await?;
+            if processes.is_empty() || processes.iter().any(|p| p.status.is_live()) {
+                still_running = true;
+            } else {
+                TaskScheduleRun::fail(pool, run.id, "The run was stopped before it finished")
+                    .await?;
+            }
+        }
+        Ok(still_running)
+    }
+
+    /// Copy the scheduled task into a subtask of it and start an attempt on the copy
+    async fn start_run(
+        &self,
+        schedule: &TaskSchedule,
+    ) -> Result<Option<TaskScheduleRun>, sqlx::Error> {
+        let pool = &self.app_state.db_pool;
+        let Some(task) = Task::find_by_id(pool, schedule.task_id).await? else {
+            return Ok(None);
+        };
+
+        let copy = Task::create(
+            pool,
+            &CreateTask {
+                project_id: task.project_id,
+                title: task.title.clone(),
+                description: task.description.clone(),
+                parent_task_attempt: None,
+                parent_task_id: Some(task.id),
+            },
+            Uuid::new_v4(),
+        )
+        .await?;
+        let run = TaskScheduleRun::create(pool, schedule.id, copy.id).await?;
+
+        let executor = match &schedule.executor {
+            Some(executor) => Some(executor.clone()),
+            None => TaskDefaults::find_by_task_id(pool, task.id)
+                .await?
+                .and_then(|defaults| defaults.executor),
+        };
+        let attempt_payload = CreateTaskAttempt {
+            executor: executor.clone(),
+            base_branch: None,
+            plan_only: None,
+            model: None,
+            executor_profile_id: None,
+        };
+        let attempt = match TaskAttempt::create(pool, &attempt_payload, copy.id).await {
+            Ok(attempt) => attempt,
+            Err(e) => {
+                error!(
+                    "Failed to create scheduled attempt for task {}: {}",
+                    task.id, e
+                );
+                TaskScheduleRun::fail(pool, run.id, &e.to_string()).await?;
+                return Ok(Some(run));
+            }
+        };
+        TaskScheduleRun::set_attempt(pool, run.id, attempt.id).await?;
+
+        self.app_state
+            .track_analytics_event(
+                "scheduled_task_run_started",
+                Some(serde_json::json!({
+                    "task_id": task.id.to_string(),
+                    "executor_type": executor.as_deref().unwrap_or("default"),
+                    "attempt_id": attempt.id.to_string(),
+                })),
+            )
+            .await;
+
+        if let Err(e) = TaskAttempt::start_execution(
+            pool,
+            &self.app_state,
+            attempt.id,
+            copy.id,
+            copy.project_id,
+        )
+        .await
+        {
+            error!(
+                "Failed to start execution for scheduled attempt {}: {}",
+                attempt.id, e
+            );
+            TaskScheduleRun::fail(pool, run.id, &e.to_string()).await?;
+        }
+
+        Ok(Some(TaskScheduleRun {
+            task_attempt_id: Some(attempt.id),
+            ..run
+        }))
+    }
+}
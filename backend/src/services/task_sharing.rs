@@ -0,0 +1,184 @@
+//! Read-only links to a task's coding agent conversations and what each run changed, to show
+//! someone without access to the board what the agent did. A link carries a random token of
+//! which only the hash is kept, and what it shows is redacted even if log redaction is
+//! turned off.
+
+use std::borrow::Cow;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    models::{
+        task::Task,
+        task_share_link::{CreateTaskShareLink, CreatedTaskShareLink, TaskShareLink},
+    },
+    services::{
+        accounts,
+        conversation_export::{self, TaskConversations},
+        log_redaction,
+    },
+};
+
+pub const SHARE_TOKEN_PREFIX: &str = "vks_";
+/// How much of a link's token is kept in the clear to tell links apart
+const SHARE_TOKEN_SHOWN_LENGTH: usize = 12;
+
+/// What a share link shows
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct SharedTask {
+    pub conversations: TaskConversations,
+    /// When the link stops working, if it expires
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+pub async fn create(
+    pool: &DbPool,
+    task_id: Uuid,
+    created_by: Option<Uuid>,
+    data: &CreateTaskShareLink,
+) -> Result<CreatedTaskShareLink, sqlx::Error> {
+    let token = format!("{}{}", SHARE_TOKEN_PREFIX, accounts::random_token());
+    let expires_at = data
+        .expires_in_days
+        .map(|days| Utc::now() + Duration::days(i64::from(days.max(1))));
+    let link = TaskShareLink::create(
+        pool,
+        task_id,
+        created_by,
+        &accounts::token_hash(&token),
+        &token[..SHARE_TOKEN_SHOWN_LENGTH],
+        expires_at,
+    )
+    .await?;
+    Ok(CreatedTaskShareLink { link, token })
+}
+
+/// What the link with this token shows, redacted, unless there's no such link or it was
+/// revoked or has expired
+pub async fn shared_task(pool: &DbPool, token: &str) -> anyhow::Result<Option<SharedTask>> {
+    if !token.starts_with(SHARE_TOKEN_PREFIX) {
+        return Ok(None);
+    }
+    let Some(link) =
+        TaskShareLink::find_active_by_hash(pool, &accounts::token_hash(token), Utc::now()).await?
+    else {
+        return Ok(None);
+    };
+    let Some(task) = Task::find_by_id(pool, link.task_id).await? else {
+        return Ok(None);
+    };
+
+    let conversations = redact(&conversation_export::export(pool, &task).await?)?;
+    TaskShareLink::record_view(pool, link.id).await?;
+    Ok(Some(SharedTask {
+        conversations,
+        expires_at: link.expires_at,
+    }))
+}
+
+/// The export with every string in it redacted: not only the entries' content, but the
+/// commands and paths of tool calls, what the agent sent along with them, and the lines of
+/// the diffs
+fn redact(export: &TaskConversations) -> Result<TaskConversations, serde_json::Error> {
+    let mut value = serde_json::to_value(export)?;
+    redact_value(&mut value);
+    serde_json::from_value(value)
+}
+
+fn redact_value(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::String(text) => {
+            if let Cow::Owned(redacted) = log_redaction::redact_enforced(text) {
+                *text = redacted;
+            }
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(redact_value),
+        serde_json::Value::Object(fields) => fields.values_mut().for_each(redact_value),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        executor::{ActionType, NormalizedConversation, NormalizedEntry, NormalizedEntryType},
+        models::{
+            execution_process::ExecutionProcessStatus,
+            task_attempt::{DiffChunk, DiffChunkType, DiffHunk, FileHunks},
+        },
+        services::conversation_export::{AttemptConversations, RunConversation},
+    };
+
+    const KEY: &str = "sk-proj-abcdefghijklmnopqrstuvwx";
+
+    #[test]
+    fn test_redacts_everything_the_link_shows() {
+        let export = TaskConversations {
+            task_id: Uuid::new_v4(),
+            title: "Call the billing API".to_string(),
+            description: Some(format!("Use the key {}", KEY)),
+            exported_at: Utc::now(),
+            attempts: vec![AttemptConversations {
+                attempt_id: Uuid::new_v4(),
+                branch: "vk-1a2b-call-billing".to_string(),
+                executor: Some("claude".to_string()),
+                created_at: Utc::now(),
+                runs: vec![RunConversation {
+                    execution_process_id: Uuid::new_v4(),
+                    status: ExecutionProcessStatus::Completed,
+                    started_at: Utc::now(),
+                    completed_at: None,
+                    conversation: NormalizedConversation {
+                        entries: vec![NormalizedEntry {
+                            id: None,
+                            timestamp: None,
+                            duration_ms: None,
+                            entry_type: NormalizedEntryType::ToolUse {
+                                tool_name: "bash".to_string(),
+                                action_type: ActionType::CommandRun {
+                                    command: format!("export OPENAI_API_KEY={}", KEY),
+                                },
+                            },
+                            content: "Setting the key".to_string(),
+                            metadata: None,
+                        }],
+                        session_id: None,
+                        executor_type: "claude".to_string(),
+                        prompt: None,
+                        summary: None,
+                        diff_summary: None,
+                    },
+                    diff: Some(vec![FileHunks {
+                        path: ".env".to_string(),
+                        hunks: vec![DiffHunk {
+                            index: 0,
+                            header: "@@ -0,0 +1 @@".to_string(),
+                            old_start: 0,
+                            new_start: 1,
+                            lines: vec![DiffChunk {
+                                chunk_type: DiffChunkType::Insert,
+                                content: format!("OPENAI_API_KEY={}\n", KEY),
+                            }],
+                        }],
+                    }]),
+                }],
+            }],
+        };
+
+        let redacted = redact(&export).unwrap();
+        let json = serde_json::to_string(&redacted).unwrap();
+        assert!(!json.contains(KEY));
+        assert_eq!(
+            redacted.description.as_deref(),
+            Some("Use the key [REDACTED]")
+        );
+        assert_eq!(redacted.title, export.title);
+        assert_eq!(redacted.attempts[0].branch, "vk-1a2b-call-billing");
+    }
+}
@@ -0,0 +1,105 @@
+//! Turn a pasted wall of text (an error dump, a long bug report) into a short task title and
+//! a tidied description.
+
+use serde::Deserialize;
+
+use crate::services::llm::{LlmClient, LlmError};
+
+/// Error dumps put the interesting parts at both ends, so long input keeps its head and tail
+const INPUT_HEAD_CHARS: usize = 8000;
+const INPUT_TAIL_CHARS: usize = 4000;
+const MAX_TITLE_CHARS: usize = 80;
+
+const SYSTEM_PROMPT: &str =
+    "You turn raw notes, bug reports and error output into a task for a coding agent. \
+Reply with only a JSON object of the form {\"title\": string, \"description\": string}. \
+The title is an imperative summary of at most 10 words. \
+The description restates the problem clearly in a few short paragraphs or bullet points, \
+keeps exact error messages, file paths, commands and identifiers, and drops noise such as \
+repeated stack frames and timestamps. Do not invent details.";
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TaskSummary {
+    pub title: String,
+    pub description: String,
+}
+
+pub async fn summarize(
+    client: &LlmClient,
+    title: &str,
+    description: Option<&str>,
+) -> Result<TaskSummary, LlmError> {
+    let mut input = format!("Title: {}", title.trim());
+    if let Some(description) = description.filter(|d| !d.trim().is_empty()) {
+        input.push_str("\n\n");
+        input.push_str(&clip_input(description.trim()));
+    }
+    let reply = client.complete(SYSTEM_PROMPT, &input).await?;
+    parse_summary(&reply).ok_or(LlmError::InvalidResponse)
+}
+
+fn clip_input(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() <= INPUT_HEAD_CHARS + INPUT_TAIL_CHARS {
+        return text.to_string();
+    }
+    let head: String = chars[..INPUT_HEAD_CHARS].iter().collect();
+    let tail: String = chars[chars.len() - INPUT_TAIL_CHARS..].iter().collect();
+    let omitted = chars.len() - INPUT_HEAD_CHARS - INPUT_TAIL_CHARS;
+    format!("{}\n[… {} characters omitted …]\n{}", head, omitted, tail)
+}
+
+/// Models often wrap the JSON in a code fence or a sentence, so take the outermost object
+fn parse_summary(reply: &str) -> Option<TaskSummary> {
+    let start = reply.find('{')?;
+    let end = reply.rfind('}')?;
+    let mut summary: TaskSummary = serde_json::from_str(reply.get(start..=end)?).ok()?;
+
+    summary.title = summary.title.trim().trim_end_matches('.').to_string();
+    if summary.title.chars().count() > MAX_TITLE_CHARS {
+        let clipped: String = summary.title.chars().take(MAX_TITLE_CHARS - 1).collect();
+        summary.title = format!("{}…", clipped.trim_end());
+    }
+    summary.description = summary.description.trim().to_string();
+    if summary.title.is_empty() {
+        return None;
+    }
+    Some(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_summary_tolerates_wrapping() {
+        let reply = "Here you go:\n```json\n{\"title\": \"Fix panic in session store.\", \"description\": \" Saving a session panics. \"}\n```";
+        assert_eq!(
+            parse_summary(reply),
+            Some(TaskSummary {
+                title: "Fix panic in session store".to_string(),
+                description: "Saving a session panics.".to_string(),
+            })
+        );
+        assert_eq!(parse_summary("no json here"), None);
+        assert_eq!(
+            parse_summary("{\"title\": \" \", \"description\": \"x\"}"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_clip_input_keeps_head_and_tail() {
+        let text = format!(
+            "{}{}{}",
+            "a".repeat(INPUT_HEAD_CHARS),
+            "b".repeat(100),
+            "c".repeat(INPUT_TAIL_CHARS)
+        );
+        let clipped = clip_input(&text);
+        assert!(clipped.starts_with('a') && clipped.ends_with('c'));
+        assert!(clipped.contains("[… 100 characters omitted …]"));
+        assert!(!clipped.contains('b'));
+        assert_eq!(clip_input("short"), "short");
+    }
+}
@@ -0,0 +1,525 @@
+//! Importing issues from Jira and Linear as tasks, so planning can stay in the tracker while
+//! the work happens here. Each issue is imported once, with its priority and labels;
+//! re-syncing only fetches issues updated since the last sync and copies edited
+//! titles and descriptions onto their tasks.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    models::{
+        config::{Config, JiraConfig, LinearConfig},
+        task::{CreateTask, Task, TaskPriority},
+        task_label::{normalize_label, TaskLabels},
+        tracker_import::{Tracker, TrackerImport, TrackerIssue},
+    },
+};
+
+const LINEAR_API_URL: &str = "https://api.linear.app/graphql";
+const PAGE_SIZE: i64 = 100;
+/// JQL compares dates in the account's own timezone, so incremental searches reach back far
+/// enough to cover any offset; issues that didn't change are skipped anyway
+const JIRA_TIMEZONE_MARGIN_DAYS: i64 = 1;
+
+#[derive(Debug)]
+pub enum TrackerImportError {
+    NotConfigured(Tracker),
+    Request(reqwest::Error),
+    Api(String),
+    Database(sqlx::Error),
+}
+
+impl std::fmt::Display for TrackerImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrackerImportError::NotConfigured(tracker) => write!(
+                f,
+                "Add your {} credentials in Settings first",
+                tracker.display_name()
+            ),
+            TrackerImportError::Request(e) => write!(f, "Failed to reach the tracker: {}", e),
+            TrackerImportError::Api(msg) => write!(f, "{}", msg),
+            TrackerImportError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for TrackerImportError {}
+
+impl From<reqwest::Error> for TrackerImportError {
+    fn from(e: reqwest::Error) -> Self {
+        TrackerImportError::Request(e)
+    }
+}
+
+impl From<sqlx::Error> for TrackerImportError {
+    fn from(e: sqlx::Error) -> Self {
+        TrackerImportError::Database(e)
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct TrackerImportRequest {
+    pub tracker: Tracker,
+    /// Jira project key or Linear team key, such as `ENG`
+    pub key: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct TrackerSyncResult {
+    pub created: usize,
+    pub updated: usize,
+}
+
+/// What the import needs of an issue from either tracker
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedIssue {
+    pub id: String,
+    pub key: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub priority: Option<TaskPriority>,
+    pub labels: Vec<String>,
+    /// Done or cancelled in the tracker
+    pub closed: bool,
+    pub url: String,
+}
+
+impl ImportedIssue {
+    /// From an issue of Jira's search API, asked for its summary, description, priority,
+    /// labels and status
+    fn from_jira(issue: &Value, base_url: &str) -> Option<Self> {
+        let fields = issue.get("fields")?;
+        let key = issue.get("key")?.as_str()?;
+        Some(Self {
+            id: issue.get("id")?.as_str()?.to_string(),
+            key: key.to_string(),
+            title: fields.get("summary")?.as_str()?.trim().to_string(),
+            description: fields["description"].as_str().map(str::to_string),
+            priority: fields["priority"]["name"].as_str().and_then(jira_priority),
+            labels: fields["labels"]
+                .as_array()
+                .map(|labels| {
+                    labels
+                        .iter()
+                        .filter_map(|l| l.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            closed: fields["status"]["statusCategory"]["key"].as_str() == Some("done"),
+            url: format!("{}/browse/{}", base_url.trim_end_matches('/'), key),
+        })
+    }
+
+    /// From an issue node of Linear's GraphQL API
+    fn from_linear(issue: &Value) -> Option<Self> {
+        Some(Self {
+            id: issue.get("id")?.as_str()?.to_string(),
+            key: issue.get("identifier")?.as_str()?.to_string(),
+            title: issue.get("title")?.as_str()?.trim().to_string(),
+            description: issue["description"].as_str().map(str::to_string),
+            // Priority 0 is Linear's "No priority"
+            priority: match issue["priority"].as_i64() {
+                Some(1) => Some(TaskPriority::Urgent),
+                Some(2) => Some(TaskPriority::High),
+                Some(3) => Some(TaskPriority::Medium),
+                Some(4) => Some(TaskPriority::Low),
+                _ => None,
+            },
+            labels: issue["labels"]["nodes"]
+                .as_array()
+                .map(|labels| {
+                    labels
+                        .iter()
+                        .filter_map(|l| l["name"].as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            closed: matches!(
+                issue["state"]["type"].as_str(),
+                Some("completed") | Some("canceled")
+            ),
+            url: issue.get("url")?.as_str()?.to_string(),
+        })
+    }
+
+    pub fn task_labels(&self) -> Vec<String> {
+        let mut labels: Vec<String> = self
+            .labels
+            .iter()
+            .filter_map(|label| normalize_label(label))
+            .collect();
+        labels.dedup();
+        labels
+    }
+}
+
+/// Jira's default priority scheme, plus the names of its older one. Custom priorities
+/// aren't imported.
+fn jira_priority(name: &str) -> Option<TaskPriority> {
+    match name.trim().to_lowercase().as_str() {
+        "highest" | "blocker" | "critical" => Some(TaskPriority::Urgent),
+        "high" | "major" => Some(TaskPriority::High),
+        "medium" | "normal" => Some(TaskPriority::Medium),
+        "low" | "lowest" | "minor" | "trivial" => Some(TaskPriority::Low),
+        _ => None,
+    }
+}
+
+/// The description of the task an issue becomes, pointing back at the issue
+pub fn task_description(tracker: Tracker, issue: &ImportedIssue) -> String {
+    let source = format!(
+        "Imported from {} {}: {}",
+        tracker.display_name(),
+        issue.key,
+        issue.url
+    );
+    match issue
+        .description
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+    {
+        Some(description) => format!("{}\n\n{}", description, source),
+        None => source,
+    }
+}
+
+/// JQL for the project's issues: open ones on the first import, and afterwards every issue
+/// updated since the last sync
+fn jira_query(project_key: &str, since: Option<DateTime<Utc>>) -> String {
+    let project = format!("project = \"{}\"", project_key.replace('"', "\\\""));
+    match since {
+        Some(since) => format!(
+            "{} AND updated >= \"{}\" ORDER BY updated ASC",
+            project,
+            (since - Duration::days(JIRA_TIMEZONE_MARGIN_DAYS)).format("%Y/%m/%d %H:%M")
+        ),
+        None => format!(
+            "{} AND statusCategory != Done ORDER BY created ASC",
+            project
+        ),
+    }
+}
+
+/// Linear's counterpart of [`jira_query`]
+fn linear_filter(team_key: &str, since: Option<DateTime<Utc>>) -> Value {
+    match since {
+        Some(since) => json!({
+            "team": { "key": { "eq": team_key } },
+            "updatedAt": { "gte": since.to_rfc3339_opts(SecondsFormat::Millis, true) },
+        }),
+        None => json!({
+            "team": { "key": { "eq": team_key } },
+            "state": { "type": { "nin": ["completed", "canceled"] } },
+        }),
+    }
+}
+
+async fn fetch_jira(
+    config: &JiraConfig,
+    project_key: &str,
+    since: Option<DateTime<Utc>>,
+) -> Result<Vec<ImportedIssue>, TrackerImportError> {
+    let (Some(base_url), Some(email), Some(api_token)) =
+        (&config.base_url, &config.email, &config.api_token)
+    else {
+        return Err(TrackerImportError::NotConfigured(Tracker::Jira));
+    };
+    let base_url = base_url.trim_end_matches('/');
+    let jql = jira_query(project_key, since);
+    let max_results = PAGE_SIZE.to_string();
+    let client = reqwest::Client::new();
+
+    let mut issues = Vec::new();
+    let mut page_token: Option<String> = None;
+    loop {
+        let mut request = client
+            .get(format!("{}/rest/api/2/search/jql", base_url))
+            .basic_auth(email, Some(api_token))
+            .query(&[
+                ("jql", jql.as_str()),
+                ("fields", "summary,description,priority,labels,status"),
+                ("maxResults", max_results.as_str()),
+            ]);
+        if let Some(token) = &page_token {
+            request = request.query(&[("nextPageToken", token)]);
+        }
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(TrackerImportError::Api(format!(
+                "Jira returned {}: {}",
+                status,
+                body.trim()
+            )));
+        }
+        let page: Value = response.json().await?;
+        issues.extend(
+            page["issues"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|issue| ImportedIssue::from_jira(issue, base_url)),
+        );
+        page_token = page["nextPageToken"].as_str().map(str::to_string);
+        if page["isLast"].as_bool().unwrap_or(true) || page_token.is_none() {
+            return Ok(issues);
+        }
+    }
+}
+
+const LINEAR_ISSUES_QUERY: &str = "query($filter: IssueFilter, $first: Int, $after: String) { issues(first: $first, after: $after, filter: $filter) { nodes { id identifier title description priority url state { type } labels { nodes { name } } } pageInfo { hasNextPage endCursor } } }";
+
+async fn fetch_linear(
+    config: &LinearConfig,
+    team_key: &str,
+    since: Option<DateTime<Utc>>,
+) -> Result<Vec<ImportedIssue>, TrackerImportError> {
+    let Some(api_key) = &config.api_key else {
+        return Err(TrackerImportError::NotConfigured(Tracker::Linear));
+    };
+    let filter = linear_filter(team_key, since);
+    let client = reqwest::Client::new();
+
+    let mut issues = Vec::new();
+    let mut after: Option<String> = None;
+    loop {
+        let response = client
+            .post(LINEAR_API_URL)
+            // Personal API keys are sent as they are, without a Bearer prefix
+            .header(reqwest::header::AUTHORIZATION, api_key)
+            .json(&json!({
+                "query": LINEAR_ISSUES_QUERY,
+                "variables": { "filter": filter, "first": PAGE_SIZE, "after": after },
+            }))
+            .send()
+            .await?;
+        let status = response.status();
+        let body: Value = response.json().await?;
+        if let Some(errors) = body["errors"].as_array().filter(|e| !e.is_empty()) {
+            let messages: Vec<&str> = errors
+                .iter()
+                .filter_map(|e| e["message"].as_str())
+                .collect();
+            return Err(TrackerImportError::Api(format!(
+                "Linear returned {}: {}",
+                status,
+                messages.join("; ")
+            )));
+        }
+        let page = &body["data"]["issues"];
+        issues.extend(
+            page["nodes"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(ImportedIssue::from_linear),
+        );
+        after = page["pageInfo"]["endCursor"].as_str().map(str::to_string);
+        if !page["pageInfo"]["hasNextPage"].as_bool().unwrap_or(false) || after.is_none() {
+            return Ok(issues);
+        }
+    }
+}
+
+/// Held for the whole of a sync so two syncs of a source can't both import a new issue
+static SYNC_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Import the source's new issues and copy edits of already imported ones onto their tasks.
+/// Issues closed before they were imported, and issues whose task was deleted, are skipped.
+async fn sync_issues(
+    pool: &DbPool,
+    config: &Config,
+    source: &TrackerImport,
+) -> Result<TrackerSyncResult, TrackerImportError> {
+    let issues = match source.tracker {
+        Tracker::Jira => {
+            fetch_jira(&config.jira, &source.source_key, source.last_synced_at).await?
+        }
+        Tracker::Linear => {
+            fetch_linear(&config.linear, &source.source_key, source.last_synced_at).await?
+        }
+    };
+    let tasks: HashMap<Uuid, Task> = Task::find_by_project_id(pool, source.project_id)
+        .await?
+        .into_iter()
+        .map(|task| (task.id, task))
+        .collect();
+    let linked: HashMap<String, TrackerIssue> =
+        TrackerIssue::find_by_tracker(pool, source.project_id, source.tracker)
+            .await?
+            .into_iter()
+            .map(|row| (row.issue_id.clone(), row))
+            .collect();
+
+    let mut result = TrackerSyncResult::default();
+    for issue in &issues {
+        let description = task_description(source.tracker, issue);
+        let task_id = match linked.get(&issue.id) {
+            Some(row) => {
+                let Some(task) = tasks.get(&row.task_id) else {
+                    continue;
+                };
+                if row.synced_title == issue.title
+                    && row.synced_description.as_deref() == Some(description.as_str())
+                {
+                    continue;
+                }
+                Task::update(
+                    pool,
+                    task.id,
+                    task.project_id,
+                    issue.title.clone(),
+                    Some(description.clone()),
+                    task.status.clone(),
+                    task.parent_task_attempt,
+                )
+                .await?;
+                result.updated += 1;
+                task.id
+            }
+            None if issue.closed => continue,
+            None => {
+                let task = Task::create(
+                    pool,
+                    &CreateTask {
+                        project_id: source.project_id,
+                        title: issue.title.clone(),
+                        description: Some(description.clone()),
+                        parent_task_attempt: None,
+                        parent_task_id: None,
+                    },
+                    Uuid::new_v4(),
+                )
+                .await?;
+                for label in issue.task_labels() {
+                    TaskLabels::add(pool, task.id, &label).await?;
+                }
+                if issue.priority.is_some() {
+                    Task::set_priority(pool, task.id, task.project_id, issue.priority).await?;
+                }
+                result.created += 1;
+                task.id
+            }
+        };
+
+        TrackerIssue::upsert(
+            pool,
+            source.project_id,
+            source.tracker,
+            &issue.id,
+            &issue.key,
+            task_id,
+            &issue.title,
+            Some(&description),
+        )
+        .await?;
+    }
+    Ok(result)
+}
+
+/// Sync one source and record the outcome on it
+pub async fn sync_source(
+    pool: &DbPool,
+    config: &Config,
+    source: &TrackerImport,
+) -> Result<TrackerSyncResult, TrackerImportError> {
+    let _guard = SYNC_LOCK.lock().await;
+    let started_at = Utc::now();
+    let outcome = sync_issues(pool, config, source).await;
+    let error = outcome.as_ref().err().map(|e| e.to_string());
+    TrackerImport::record_sync(
+        pool,
+        source.project_id,
+        source.tracker,
+        &source.source_key,
+        outcome.is_ok().then_some(started_at),
+        error.as_deref(),
+    )
+    .await?;
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_issues_from_both_trackers() {
+        let jira = ImportedIssue::from_jira(
+            &json!({
+                "id": "10042",
+                "key": "ENG-42",
+                "fields": {
+                    "summary": " Fix login redirect ",
+                    "description": "Users land on a blank page.",
+                    "priority": {"name": "High"},
+                    "labels": ["auth", "Frontend"],
+                    "status": {"statusCategory": {"key": "indeterminate"}}
+                }
+            }),
+            "https://acme.atlassian.net/",
+        )
+        .unwrap();
+        assert_eq!(jira.title, "Fix login redirect");
+        assert_eq!(jira.url, "https://acme.atlassian.net/browse/ENG-42");
+        assert!(!jira.closed);
+        assert_eq!(jira.priority, Some(TaskPriority::High));
+        assert_eq!(jira.task_labels(), vec!["auth", "frontend"]);
+        assert_eq!(
+            task_description(Tracker::Jira, &jira),
+            "Users land on a blank page.\n\nImported from Jira ENG-42: https://acme.atlassian.net/browse/ENG-42"
+        );
+
+        let linear = ImportedIssue::from_linear(&json!({
+            "id": "9cfb482a",
+            "identifier": "APP-7",
+            "title": "Dark mode",
+            "description": null,
+            "priority": 0,
+            "url": "https://linear.app/acme/issue/APP-7",
+            "state": {"type": "completed"},
+            "labels": {"nodes": [{"name": "UI"}]}
+        }))
+        .unwrap();
+        assert!(linear.closed);
+        assert_eq!(linear.priority, None);
+        assert_eq!(linear.task_labels(), vec!["ui"]);
+        assert_eq!(
+            task_description(Tracker::Linear, &linear),
+            "Imported from Linear APP-7: https://linear.app/acme/issue/APP-7"
+        );
+    }
+
+    #[test]
+    fn test_incremental_queries() {
+        assert_eq!(
+            jira_query("ENG", None),
+            "project = \"ENG\" AND statusCategory != Done ORDER BY created ASC"
+        );
+        let since = Utc.with_ymd_and_hms(2025, 9, 14, 10, 30, 0).unwrap();
+        assert_eq!(
+            jira_query("ENG", Some(since)),
+            "project = \"ENG\" AND updated >= \"2025/09/13 10:30\" ORDER BY updated ASC"
+        );
+        assert_eq!(
+            linear_filter("APP", Some(since))["updatedAt"]["gte"],
+            "2025-09-14T10:30:00.000Z"
+        );
+        assert!(linear_filter("APP", None)["updatedAt"].is_null());
+    }
+}
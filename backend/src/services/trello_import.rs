@@ -0,0 +1,357 @@
+//! Turning a Trello board, from a JSON export or the REST API, into tasks for a project
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::models::task::TaskStatus;
+
+const TRELLO_API_BASE: &str = "https://api.trello.com/1";
+
+#[derive(Debug)]
+pub enum TrelloImportError {
+    Request(reqwest::Error),
+    Api(String),
+    InvalidBoard(serde_json::Error),
+}
+
+impl std::fmt::Display for TrelloImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrelloImportError::Request(e) => write!(f, "Failed to reach Trello: {}", e),
+            TrelloImportError::Api(msg) => write!(f, "Trello API error: {}", msg),
+            TrelloImportError::InvalidBoard(e) => write!(f, "Not a Trello board export: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for TrelloImportError {}
+
+/// Where to read the board from: the JSON file from Trello's "Print, export and share" menu,
+/// or the REST API with a key and token from trello.com/app-key
+#[derive(Debug, Deserialize, TS)]
+#[serde(tag = "source", rename_all = "snake_case")]
+#[ts(export)]
+pub enum TrelloImportRequest {
+    Export {
+        #[ts(type = "unknown")]
+        board: serde_json::Value,
+    },
+    Api {
+        board_id: String,
+        key: String,
+        token: String,
+    },
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct TrelloImportResult {
+    pub board_name: String,
+    pub tasks_created: usize,
+    pub subtasks_created: usize,
+}
+
+/// The parts of a board export we use; the API returns the same shape when asked for
+/// lists, cards, checklists and labels
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrelloBoard {
+    pub name: String,
+    #[serde(default)]
+    pub lists: Vec<TrelloList>,
+    #[serde(default)]
+    pub cards: Vec<TrelloCard>,
+    #[serde(default)]
+    pub checklists: Vec<TrelloChecklist>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrelloList {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub closed: bool,
+    #[serde(default)]
+    pub pos: f64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrelloCard {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub desc: String,
+    pub id_list: String,
+    #[serde(default)]
+    pub closed: bool,
+    #[serde(default)]
+    pub pos: f64,
+    #[serde(default)]
+    pub labels: Vec<TrelloLabel>,
+    pub short_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TrelloLabel {
+    #[serde(default)]
+    pub name: String,
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrelloChecklist {
+    pub id_card: String,
+    pub name: String,
+    #[serde(default)]
+    pub pos: f64,
+    #[serde(default)]
+    pub check_items: Vec<TrelloCheckItem>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TrelloCheckItem {
+    pub name: String,
+    pub state: String,
+    #[serde(default)]
+    pub pos: f64,
+}
+
+/// A task to create, with the checklist items that become its subtasks
+#[derive(Debug, PartialEq)]
+pub struct PlannedTask {
+    pub title: String,
+    pub description: Option<String>,
+    pub status: TaskStatus,
+    pub subtasks: Vec<PlannedTask>,
+}
+
+impl TrelloBoard {
+    pub fn from_json(json: serde_json::Value) -> Result<Self, TrelloImportError> {
+        serde_json::from_value(json).map_err(TrelloImportError::InvalidBoard)
+    }
+
+    pub async fn load(request: TrelloImportRequest) -> Result<Self, TrelloImportError> {
+        match request {
+            TrelloImportRequest::Export { board } => Self::from_json(board),
+            TrelloImportRequest::Api {
+                board_id,
+                key,
+                token,
+            } => Self::fetch(&board_id, &key, &token).await,
+        }
+    }
+
+    async fn fetch(board_id: &str, key: &str, token: &str) -> Result<Self, TrelloImportError> {
+        let response = reqwest::Client::new()
+            .get(format!("{}/boards/{}", TRELLO_API_BASE, board_id))
+            .query(&[
+                ("lists", "all"),
+                ("cards", "all"),
+                ("checklists", "all"),
+                ("key", key),
+                ("token", token),
+            ])
+            .send()
+            .await
+            .map_err(TrelloImportError::Request)?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(TrelloImportError::Api(format!(
+                "{} {}",
+                status,
+                body.trim()
+            )));
+        }
+        let json = response
+            .json::<serde_json::Value>()
+            .await
+            .map_err(TrelloImportError::Request)?;
+        Self::from_json(json)
+    }
+
+    /// Map open lists to board columns and their open cards to tasks, in board order.
+    /// Archived lists and cards are left out.
+    pub fn plan(&self) -> Vec<PlannedTask> {
+        let mut checklists: HashMap<&str, Vec<&TrelloChecklist>> = HashMap::new();
+        for checklist in &self.checklists {
+            checklists
+                .entry(checklist.id_card.as_str())
+                .or_default()
+                .push(checklist);
+        }
+
+        let mut lists: Vec<&TrelloList> = self.lists.iter().filter(|l| !l.closed).collect();
+        lists.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+
+        let mut planned = Vec::new();
+        for list in lists {
+            let status = status_for_list(&list.name);
+            let mut cards: Vec<&TrelloCard> = self
+                .cards
+                .iter()
+                .filter(|c| !c.closed && c.id_list == list.id)
+                .collect();
+            cards.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+
+            for card in cards {
+                let mut card_checklists = checklists.remove(card.id.as_str()).unwrap_or_default();
+                card_checklists.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+                planned.push(PlannedTask {
+                    title: card.name.trim().to_string(),
+                    description: card_description(card),
+                    status: status.clone(),
+                    subtasks: card_checklists
+                        .into_iter()
+                        .flat_map(|checklist| checklist_subtasks(card, checklist))
+                        .collect(),
+                });
+            }
+        }
+        planned
+    }
+}
+
+/// Guess the column from the list name; lists that don't look like a stage land in To Do
+fn status_for_list(name: &str) -> TaskStatus {
+    let name = name.to_lowercase();
+    let matches = |words: &[&str]| words.iter().any(|w| name.contains(w));
+
+    if matches(&["done", "complete", "finished", "shipped", "released"]) {
+        TaskStatus::Done
+    } else if matches(&["review", "testing", "qa", "verify"]) {
+        TaskStatus::InReview
+    } else if matches(&["doing", "progress", "wip", "working", "active"]) {
+        TaskStatus::InProgress
+    } else if matches(&["cancel", "abandon", "won't", "wont", "dropped"]) {
+        TaskStatus::Cancelled
+    } else {
+        TaskStatus::Todo
+    }
+}
+
+/// The card text plus the labels and link, which have nowhere else to go
+fn card_description(card: &TrelloCard) -> Option<String> {
+    let mut parts = Vec::new();
+    if !card.desc.trim().is_empty() {
+        parts.push(card.desc.trim().to_string());
+    }
+    let labels: Vec<&str> = card
+        .labels
+        .iter()
+        .filter_map(|l| {
+            let name = l.name.trim();
+            if name.is_empty() {
+                l.color.as_deref()
+            } else {
+                Some(name)
+            }
+        })
+        .collect();
+    if !labels.is_empty() {
+        parts.push(format!("Labels: {}", labels.join(", ")));
+    }
+    if let Some(url) = &card.short_url {
+        parts.push(format!("Imported from Trello: {}", url));
+    }
+    (!parts.is_empty()).then(|| parts.join("\n\n"))
+}
+
+fn checklist_subtasks(card: &TrelloCard, checklist: &TrelloChecklist) -> Vec<PlannedTask> {
+    let mut items: Vec<&TrelloCheckItem> = checklist.check_items.iter().collect();
+    items.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+    items
+        .into_iter()
+        .map(|item| PlannedTask {
+            title: item.name.trim().to_string(),
+            description: Some(format!(
+                "From the \"{}\" checklist on \"{}\".",
+                checklist.name.trim(),
+                card.name.trim()
+            )),
+            status: if item.state == "complete" {
+                TaskStatus::Done
+            } else {
+                TaskStatus::Todo
+            },
+            subtasks: Vec::new(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_plan_maps_lists_cards_and_checklists() {
+        let board = TrelloBoard::from_json(json!({
+            "name": "Side project",
+            "lists": [
+                {"id": "l2", "name": "Done", "closed": false, "pos": 2.0},
+                {"id": "l1", "name": "Backlog", "closed": false, "pos": 1.0},
+                {"id": "l3", "name": "Old ideas", "closed": true, "pos": 3.0}
+            ],
+            "cards": [
+                {"id": "c2", "name": "Ship it", "desc": "", "idList": "l2", "closed": false, "pos": 1.0,
+                 "labels": [], "shortUrl": null},
+                {"id": "c1", "name": "Add login ", "desc": "OAuth only", "idList": "l1", "closed": false,
+                 "pos": 1.0, "labels": [{"name": "auth", "color": "red"}, {"name": "", "color": "blue"}],
+                 "shortUrl": "https://trello.com/c/abc"},
+                {"id": "c3", "name": "Archived", "desc": "", "idList": "l1", "closed": true, "pos": 2.0,
+                 "labels": []},
+                {"id": "c4", "name": "In a closed list", "desc": "", "idList": "l3", "closed": false,
+                 "pos": 1.0, "labels": []}
+            ],
+            "checklists": [
+                {"id": "k1", "idCard": "c1", "name": "Steps", "pos": 1.0, "checkItems": [
+                    {"name": "Callback route", "state": "incomplete", "pos": 2.0},
+                    {"name": "Pick provider", "state": "complete", "pos": 1.0}
+                ]}
+            ]
+        }))
+        .unwrap();
+
+        let plan = board.plan();
+        assert_eq!(plan.len(), 2);
+
+        let login = &plan[0];
+        assert_eq!(login.title, "Add login");
+        assert_eq!(login.status, TaskStatus::Todo);
+        assert_eq!(
+            login.description.as_deref(),
+            Some("OAuth only\n\nLabels: auth, blue\n\nImported from Trello: https://trello.com/c/abc")
+        );
+        let subtasks: Vec<(&str, &TaskStatus)> = login
+            .subtasks
+            .iter()
+            .map(|s| (s.title.as_str(), &s.status))
+            .collect();
+        assert_eq!(
+            subtasks,
+            vec![
+                ("Pick provider", &TaskStatus::Done),
+                ("Callback route", &TaskStatus::Todo)
+            ]
+        );
+
+        assert_eq!(plan[1].title, "Ship it");
+        assert_eq!(plan[1].status, TaskStatus::Done);
+        assert_eq!(plan[1].description, None);
+    }
+
+    #[test]
+    fn test_status_for_list() {
+        assert_eq!(status_for_list("In Progress"), TaskStatus::InProgress);
+        assert_eq!(status_for_list("Code review"), TaskStatus::InReview);
+        assert_eq!(status_for_list("Ideas"), TaskStatus::Todo);
+    }
+}
@@ -0,0 +1,279 @@
+//! TOTP two-factor authentication (RFC 6238), as authenticator apps do it: six digits from
+//! an HMAC-SHA1 of the 30-second time step. Recovery codes stand in for a lost device; each
+//! works once.
+
+use chrono::{DateTime, Utc};
+use ring::hmac;
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    models::{
+        project_security::ProjectSecurity,
+        two_factor::{RecoveryCodes, TwoFactor, TwoFactorEnrollment},
+        user::User,
+    },
+    services::{
+        accounts,
+        login_throttle::{self, Subject},
+    },
+};
+
+const ISSUER: &str = "Vibe Kanban";
+const STEP_SECONDS: i64 = 30;
+const DIGITS: u32 = 6;
+/// Codes of the steps either side of now still count, for clocks that drift
+const ALLOWED_DRIFT: i64 = 1;
+const RECOVERY_CODE_COUNT: usize = 10;
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::new();
+    let (mut buffer, mut bits) = (0u32, 0);
+    for &byte in bytes {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(BASE32_ALPHABET[((buffer >> bits) & 31) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        encoded.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 31) as usize] as char);
+    }
+    encoded
+}
+
+fn base32_decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let (mut buffer, mut bits) = (0u32, 0);
+    for c in encoded.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| a as char == c.to_ascii_uppercase())?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+fn time_step(now: DateTime<Utc>) -> i64 {
+    now.timestamp().div_euclid(STEP_SECONDS)
+}
+
+fn code_at(secret: &[u8], step: i64) -> u32 {
+    let key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, secret);
+    let tag = hmac::sign(&key, &(step as u64).to_be_bytes());
+    let digest = tag.as_ref();
+    let offset = usize::from(digest[digest.len() - 1] & 0x0f);
+    let truncated = u32::from_be_bytes([
+        digest[offset],
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]) & 0x7fff_ffff;
+    truncated % 10u32.pow(DIGITS)
+}
+
+/// The time step `code` is the secret's code for, if it is one around `now`
+fn matching_step(secret: &str, code: &str, now: DateTime<Utc>) -> Option<i64> {
+    let secret = base32_decode(secret)?;
+    let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+    if code.len() != DIGITS as usize {
+        return None;
+    }
+    let code: u32 = code.parse().ok()?;
+    let now = time_step(now);
+    (now - ALLOWED_DRIFT..=now + ALLOWED_DRIFT)
+        .find(|&step| bool::from(code_at(&secret, step).ct_eq(&code)))
+}
+
+/// The secret's code at `now`, as the user's authenticator app shows it
+#[cfg(test)]
+pub(crate) fn code_for(secret: &str, now: DateTime<Utc>) -> String {
+    let secret = base32_decode(secret).expect("a base32 secret");
+    format!("{:06}", code_at(&secret, time_step(now)))
+}
+
+/// Recovery codes are compared without their dashes or case
+fn recovery_code_hash(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    accounts::token_hash(&normalized)
+}
+
+fn new_recovery_code() -> String {
+    let code = hex::encode(accounts::random_bytes::<5>());
+    format!("{}-{}", &code[..5], &code[5..])
+}
+
+/// A new secret for the user, pending until [`confirm_enrollment`]. Replaces an enrollment
+/// that was never confirmed.
+pub async fn start_enrollment(
+    pool: &DbPool,
+    user: &User,
+) -> Result<TwoFactorEnrollment, sqlx::Error> {
+    let secret = base32_encode(&accounts::random_bytes::<20>());
+    TwoFactor::start_enrollment(pool, user.id, &secret).await?;
+    let label = format!("{}:{}", ISSUER, user.username);
+    let otpauth_url = format!(
+        "otpauth://totp/{}?secret={}&issuer={}",
+        urlencoding::encode(&label),
+        secret,
+        urlencoding::encode(ISSUER)
+    );
+    Ok(TwoFactorEnrollment {
+        secret,
+        otpauth_url,
+    })
+}
+
+/// Turn two-factor authentication on once the user shows a code from their app, answering
+/// with their recovery codes. `None` if the code is wrong or nothing is being enrolled.
+pub async fn confirm_enrollment(
+    pool: &DbPool,
+    user_id: Uuid,
+    code: &str,
+    now: DateTime<Utc>,
+) -> Result<Option<RecoveryCodes>, sqlx::Error> {
+    let Some(pending) = TwoFactor::find_by_user_id(pool, user_id).await? else {
+        return Ok(None);
+    };
+    if pending.enabled_at.is_some() {
+        return Ok(None);
+    }
+    let Some(step) = matching_step(&pending.secret, code, now) else {
+        return Ok(None);
+    };
+    TwoFactor::enable(pool, user_id, step).await?;
+    replace_recovery_codes(pool, user_id).await.map(Some)
+}
+
+/// New recovery codes for the user, in place of the ones they had
+pub async fn replace_recovery_codes(
+    pool: &DbPool,
+    user_id: Uuid,
+) -> Result<RecoveryCodes, sqlx::Error> {
+    let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+        .map(|_| new_recovery_code())
+        .collect();
+    let hashes: Vec<String> = codes.iter().map(|code| recovery_code_hash(code)).collect();
+    TwoFactor::replace_recovery_codes(pool, user_id, &hashes).await?;
+    Ok(RecoveryCodes { codes })
+}
+
+/// Whether `code` is the user's current code from their app, or one of their unused
+/// recovery codes, which it then uses up. Always false without two-factor authentication
+/// turned on, and while the user's codes are locked out for failing too often.
+pub async fn verify(
+    pool: &DbPool,
+    user_id: Uuid,
+    code: &str,
+    now: DateTime<Utc>,
+) -> Result<bool, sqlx::Error> {
+    let subject = [Subject::TwoFactor(user_id)];
+    if login_throttle::retry_after(&subject).is_some() {
+        return Ok(false);
+    }
+    let Some(two_factor) = TwoFactor::find_by_user_id(pool, user_id).await? else {
+        return Ok(false);
+    };
+    if two_factor.enabled_at.is_none() {
+        return Ok(false);
+    }
+    let verified = match matching_step(&two_factor.secret, code, now) {
+        Some(step) => TwoFactor::use_step(pool, user_id, step).await?,
+        None => TwoFactor::use_recovery_code(pool, user_id, &recovery_code_hash(code)).await?,
+    };
+    if verified {
+        login_throttle::record_success(&subject);
+    } else {
+        login_throttle::record_failure(&subject);
+    }
+    Ok(verified)
+}
+
+/// Whether a project the user reaches requires two-factor authentication of them. Accounts
+/// that sign in through OIDC are left to the provider.
+pub async fn required_by_projects(pool: &DbPool, user: &User) -> Result<bool, sqlx::Error> {
+    if user.is_oidc {
+        return Ok(false);
+    }
+    ProjectSecurity::requires_two_factor_of(pool, user.id, user.is_admin).await
+}
+
+/// Whether a project the user reaches holds them back until they turn on two-factor
+/// authentication
+pub async fn enrollment_required(pool: &DbPool, user: &User) -> Result<bool, sqlx::Error> {
+    Ok(required_by_projects(pool, user).await? && !TwoFactor::is_enabled(pool, user.id).await?)
+}
+
+/// Whether the project holds the user back until they turn on two-factor authentication
+pub async fn enrollment_required_for(
+    pool: &DbPool,
+    user: &User,
+    project_id: Uuid,
+) -> Result<bool, sqlx::Error> {
+    if user.is_oidc {
+        return Ok(false);
+    }
+    let required = ProjectSecurity::find_by_project_id(pool, project_id)
+        .await?
+        .is_some_and(|security| security.require_two_factor);
+    Ok(required && !TwoFactor::is_enabled(pool, user.id).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    // The SHA-1 secret of RFC 6238's test vectors
+    const RFC_SECRET: &[u8] = b"12345678901234567890";
+
+    #[test]
+    fn test_codes_match_the_rfc_test_vectors() {
+        let at = |seconds| time_step(Utc.timestamp_opt(seconds, 0).unwrap());
+        // The RFC's eight-digit codes, cut to six
+        assert_eq!(code_at(RFC_SECRET, at(59)), 287_082);
+        assert_eq!(code_at(RFC_SECRET, at(1_111_111_109)), 81_804);
+        assert_eq!(code_at(RFC_SECRET, at(2_000_000_000)), 279_037);
+    }
+
+    #[test]
+    fn test_codes_are_accepted_one_step_either_side() {
+        let secret = base32_encode(RFC_SECRET);
+        assert_eq!(secret, "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+        assert_eq!(base32_decode(&secret.to_lowercase()).unwrap(), RFC_SECRET);
+
+        let now = Utc.timestamp_opt(1_111_111_109, 0).unwrap();
+        let step = time_step(now);
+        assert_eq!(code_for(&secret, now), "081804");
+        assert_eq!(matching_step(&secret, "081804", now), Some(step));
+        assert_eq!(matching_step(&secret, "081 804", now), Some(step));
+        let late = now + chrono::Duration::seconds(STEP_SECONDS);
+        assert_eq!(matching_step(&secret, "081804", late), Some(step));
+        let too_late = now + chrono::Duration::seconds(3 * STEP_SECONDS);
+        assert_eq!(matching_step(&secret, "081804", too_late), None);
+        assert_eq!(matching_step(&secret, "81804", now), None);
+    }
+
+    #[test]
+    fn test_recovery_codes_ignore_dashes_and_case() {
+        let code = new_recovery_code();
+        assert_eq!(code.len(), 11);
+        assert_eq!(
+            recovery_code_hash(&code),
+            recovery_code_hash(&code.replace('-', "").to_uppercase())
+        );
+    }
+}
@@ -0,0 +1,389 @@
+//! A project's verification commands: its tests, linters and build, run one after another in
+//! an attempt's worktree once a coding agent finishes successfully. Each command's outcome
+//! is stored with the run and shown at the end of its conversation, and a failure can
+//! label the task so it's picked up for a follow-up, or send the agent that follow-up
+//! itself: the failing output with a request to fix it, up to a configured number of times.
+//!
+//! The attempt counts as running while the commands run, so nothing else starts in the
+//! worktree, and the task only moves to review once they're done.
+
+use std::time::{Duration, Instant};
+
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    command_runner::{self, CommandError, CommandRunner},
+    models::{
+        execution_verification::ExecutionVerification, task::Task, task_attempt::TaskAttempt,
+        task_label::TaskLabels, verification_fix_loop::VerificationFixLoop,
+    },
+    services::{
+        environment_variables, log_redaction,
+        merge_queue::{output_tail, read_all},
+        ProcessService,
+    },
+    utils::shell::get_shell_command,
+};
+
+/// Added to a task whose verification failed, and dropped once it passes
+pub const FOLLOW_UP_LABEL: &str = "needs-follow-up";
+
+/// The commands of a verification script: one per line, skipping blank lines and `#`
+/// comments
+pub fn parse_commands(script: &str) -> Vec<&str> {
+    script
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+}
+
+struct CommandOutcome {
+    passed: bool,
+    exit_code: Option<i64>,
+    output: String,
+}
+
+/// Run the commands for a finished coding agent run and store their results. Returns
+/// whether all of them passed. Cancelling the run stops the command running and skips the
+/// rest, storing nothing for them and leaving the task's labels as they were.
+pub async fn run(
+    app_state: &AppState,
+    task: &Task,
+    task_attempt: &TaskAttempt,
+    execution_process_id: Uuid,
+    commands: &[&str],
+    cancellation: &CancellationToken,
+) -> bool {
+    let config = app_state.get_config().read().await.verification.clone();
+    let timeout = Duration::from_secs(config.timeout_secs.into());
+
+    let environment = match environment_variables::resolve(&app_state.db_pool, task).await {
+        Ok(environment) => environment,
+        Err(e) => {
+            tracing::warn!(
+                "Running verification for attempt {} without its environment variables: {}",
+                task_attempt.id,
+                e
+            );
+            Default::default()
+        }
+    };
+    environment_variables::register_redactions(execution_process_id, environment.secrets);
+
+    let mut all_passed = true;
+    for command in commands {
+        if cancellation.is_cancelled() {
+            break;
+        }
+        let started = Instant::now();
+        let outcome = match command_runner::with_cancellation(
+            cancellation.clone(),
+            command_runner::with_env(
+                environment.vars.clone(),
+                run_command(command, &task_attempt.worktree_path, timeout),
+            ),
+        )
+        .await
+        {
+            Ok(outcome) => outcome,
+            Err(CommandError::Cancelled) => break,
+            Err(e) => CommandOutcome {
+                passed: false,
+                exit_code: None,
+                output: format!("Could not run: {}", e),
+            },
+        };
+        let duration_ms = started.elapsed().as_millis().try_into().unwrap_or(i64::MAX);
+        all_passed &= outcome.passed;
+
+        let output = environment_variables::redact(execution_process_id, &outcome.output);
+        let output = log_redaction::redact(&output);
+        if let Err(e) = ExecutionVerification::create(
+            &app_state.db_pool,
+            execution_process_id,
+            command,
+            outcome.passed,
+            outcome.exit_code,
+            &output,
+            duration_ms,
+        )
+        .await
+        {
+            tracing::error!(
+                "Failed to store verification of execution {}: {}",
+                execution_process_id,
+                e
+            );
+        }
+    }
+    environment_variables::forget_redactions(execution_process_id);
+
+    if cancellation.is_cancelled() {
+        tracing::info!("Verification of attempt {} was cancelled", task_attempt.id);
+        return false;
+    }
+
+    if all_passed {
+        if let Err(e) = VerificationFixLoop::delete(&app_state.db_pool, task_attempt.id).await {
+            tracing::error!(
+                "Failed to clear automatic fixes of attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+        }
+    }
+
+    if config.follow_up_on_failure {
+        let labeled = if all_passed {
+            TaskLabels::remove(&app_state.db_pool, task.id, FOLLOW_UP_LABEL)
+                .await
+                .map(|_| ())
+        } else {
+            TaskLabels::add(&app_state.db_pool, task.id, FOLLOW_UP_LABEL).await
+        };
+        if let Err(e) = labeled {
+            tracing::error!(
+                "Failed to update follow-up label of task {}: {}",
+                task.id,
+                e
+            );
+        }
+    }
+
+    app_state
+        .track_analytics_event(
+            "verification_finished",
+            Some(serde_json::json!({
+                "task_id": task.id.to_string(),
+                "project_id": task.project_id.to_string(),
+                "attempt_id": task_attempt.id.to_string(),
+                "commands": commands.len(),
+                "passed": all_passed,
+            })),
+        )
+        .await;
+
+    all_passed
+}
+
+/// The follow-up asking the agent to fix the commands that failed
+pub fn fix_prompt(failures: &[ExecutionVerification]) -> String {
+    let mut prompt = String::from(
+        "These verification commands failed after your changes. Fix what they report; they \
+         run again once you finish.",
+    );
+    for failure in failures {
+        let outcome = match failure.exit_code {
+            Some(code) => format!("failed with exit code {}", code),
+            None => "was stopped".to_string(),
+        };
+        prompt.push_str(&format!("\n\n`{}` {}", failure.command, outcome));
+        if !failure.output.trim().is_empty() {
+            prompt.push_str(&format!(":\n```\n{}\n```", failure.output.trim_end()));
+        }
+    }
+    prompt
+}
+
+/// After a failed verification, ask the agent to fix it, unless automatic fixes are off or
+/// the attempt has used them up. Returns whether a follow-up started.
+pub async fn start_fix(
+    app_state: &AppState,
+    task: &Task,
+    task_attempt: &TaskAttempt,
+    execution_process_id: Uuid,
+) -> bool {
+    let limit = app_state
+        .get_config()
+        .read()
+        .await
+        .verification
+        .auto_fix_attempts;
+    if limit == 0 {
+        return false;
+    }
+    let pool = &app_state.db_pool;
+    let fixes = match VerificationFixLoop::find_by_task_attempt_id(pool, task_attempt.id).await {
+        Ok(fix_loop) => fix_loop.map_or(0, |fix_loop| fix_loop.fix_count),
+        Err(e) => {
+            tracing::error!(
+                "Failed to load automatic fixes of attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+            return false;
+        }
+    };
+    if fixes >= i64::from(limit) {
+        tracing::info!(
+            "Verification of attempt {} still fails after {} automatic fixes",
+            task_attempt.id,
+            fixes
+        );
+        return false;
+    }
+
+    let failures: Vec<_> =
+        match ExecutionVerification::find_by_execution_process_id(pool, execution_process_id).await
+        {
+            Ok(results) => results
+                .into_iter()
+                .filter(|result| !result.passed)
+                .collect(),
+            Err(e) => {
+                tracing::error!(
+                    "Failed to load verification of execution {}: {}",
+                    execution_process_id,
+                    e
+                );
+                return false;
+            }
+        };
+    if failures.is_empty() {
+        return false;
+    }
+    // Recorded before the follow-up starts, so it's still sent if the server stops first
+    let prompt = fix_prompt(&failures);
+    if let Err(e) = VerificationFixLoop::record_failure(pool, task_attempt.id, &prompt).await {
+        tracing::error!(
+            "Failed to record verification failure of attempt {}: {}",
+            task_attempt.id,
+            e
+        );
+        return false;
+    }
+    send_fix(app_state, task, task_attempt.id, &prompt).await
+}
+
+/// Start the follow-up due for a recorded failure. Returns whether it started; if it
+/// couldn't, no more fixes are attempted for that failure.
+pub async fn send_fix(
+    app_state: &AppState,
+    task: &Task,
+    task_attempt_id: Uuid,
+    prompt: &str,
+) -> bool {
+    let pool = &app_state.db_pool;
+    match ProcessService::start_followup_execution(
+        pool,
+        app_state,
+        task_attempt_id,
+        task.id,
+        task.project_id,
+        prompt,
+    )
+    .await
+    {
+        Ok(_) => {
+            if let Err(e) = VerificationFixLoop::mark_started(pool, task_attempt_id).await {
+                tracing::error!(
+                    "Failed to record automatic fix of attempt {}: {}",
+                    task_attempt_id,
+                    e
+                );
+            }
+            app_state
+                .track_analytics_event(
+                    "verification_fix_started",
+                    Some(serde_json::json!({
+                        "task_id": task.id.to_string(),
+                        "project_id": task.project_id.to_string(),
+                        "attempt_id": task_attempt_id.to_string(),
+                    })),
+                )
+                .await;
+            true
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to start automatic fix of attempt {}: {}",
+                task_attempt_id,
+                e
+            );
+            let _ = VerificationFixLoop::cancel(pool, task_attempt_id).await;
+            false
+        }
+    }
+}
+
+async fn run_command(
+    command: &str,
+    worktree_path: &str,
+    timeout: Duration,
+) -> Result<CommandOutcome, CommandError> {
+    let (shell_cmd, shell_arg) = get_shell_command();
+    let mut child = CommandRunner::new()
+        .command(shell_cmd)
+        .arg(shell_arg)
+        .arg(command)
+        .working_dir(worktree_path)
+        .start()
+        .await?;
+
+    let stream = child.stream().await?;
+    let cancellation = command_runner::cancellation().unwrap_or_default();
+    let output = tokio::select! {
+        output = tokio::time::timeout(timeout, async {
+            tokio::join!(read_all(stream.stdout), read_all(stream.stderr))
+        }) => output,
+        _ = cancellation.cancelled() => {
+            child.kill().await?;
+            return Err(CommandError::Cancelled);
+        }
+    };
+    let Ok((stdout, stderr)) = output else {
+        child.kill().await?;
+        return Ok(CommandOutcome {
+            passed: false,
+            exit_code: None,
+            output: format!("Stopped after running for {} seconds", timeout.as_secs()),
+        });
+    };
+
+    let status = child.wait().await?;
+    Ok(CommandOutcome {
+        passed: status.success(),
+        exit_code: status.code().map(i64::from),
+        output: output_tail(&stdout, &stderr),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands_skips_blank_lines_and_comments() {
+        let script = "cargo build\n\n  # lint too\n  cargo clippy -- -D warnings  \nnpm test\n";
+        assert_eq!(
+            parse_commands(script),
+            vec!["cargo build", "cargo clippy -- -D warnings", "npm test"]
+        );
+        assert!(parse_commands("# nothing yet\n\n").is_empty());
+    }
+
+    #[test]
+    fn test_fix_prompt_quotes_each_failure() {
+        let failure = |command: &str, exit_code, output: &str| ExecutionVerification {
+            id: Uuid::new_v4(),
+            execution_process_id: Uuid::new_v4(),
+            command: command.to_string(),
+            passed: false,
+            exit_code,
+            output: output.to_string(),
+            duration_ms: 10,
+            created_at: chrono::Utc::now(),
+        };
+        let prompt = fix_prompt(&[
+            failure("cargo test", Some(101), "test parse ... FAILED\n"),
+            failure("npm run lint", None, ""),
+        ]);
+
+        let quoted = "`cargo test` failed with exit code 101:\n```\ntest parse ... FAILED\n```";
+        assert!(prompt.contains(quoted));
+        assert!(prompt.ends_with("`npm run lint` was stopped"));
+    }
+}
@@ -0,0 +1,348 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Default window in which a timestamped signature is accepted
+pub const DEFAULT_TIMESTAMP_TOLERANCE: Duration = Duration::from_secs(300);
+
+/// Header carrying the `t=<unix>,v1=<hex>` signature on outgoing webhooks and signed requests
+pub const SIGNATURE_HEADER: &str = "x-vibe-kanban-signature";
+
+/// Header carrying the id the replay guard remembers a delivery by
+pub const DELIVERY_HEADER: &str = "x-vibe-kanban-delivery";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum WebhookSignatureError {
+    NoSecretConfigured,
+    MissingSignature,
+    MalformedSignature(String),
+    InvalidSignature,
+    TimestampOutOfTolerance { age_secs: i64 },
+    Replayed(String),
+}
+
+impl std::fmt::Display for WebhookSignatureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WebhookSignatureError::NoSecretConfigured => {
+                write!(f, "No webhook secret is configured")
+            }
+            WebhookSignatureError::MissingSignature => write!(f, "Missing webhook signature"),
+            WebhookSignatureError::MalformedSignature(e) => {
+                write!(f, "Malformed webhook signature: {}", e)
+            }
+            WebhookSignatureError::InvalidSignature => {
+                write!(f, "Webhook signature does not match payload")
+            }
+            WebhookSignatureError::TimestampOutOfTolerance { age_secs } => write!(
+                f,
+                "Webhook timestamp is outside the accepted window ({}s old)",
+                age_secs
+            ),
+            WebhookSignatureError::Replayed(id) => {
+                write!(f, "Webhook delivery {} was already processed", id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WebhookSignatureError {}
+
+/// Signs and verifies webhook payloads with HMAC-SHA256.
+///
+/// The first secret is used for signing; every secret is accepted when
+/// verifying so a new secret can be rolled out before the old one is removed.
+#[derive(Debug, Clone)]
+pub struct WebhookSigner {
+    secrets: Vec<String>,
+    tolerance: Duration,
+}
+
+impl WebhookSigner {
+    pub fn new(secrets: Vec<String>) -> Self {
+        Self {
+            secrets: secrets.into_iter().filter(|s| !s.is_empty()).collect(),
+            tolerance: DEFAULT_TIMESTAMP_TOLERANCE,
+        }
+    }
+
+    pub fn with_tolerance(mut self, tolerance: Duration) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn has_secrets(&self) -> bool {
+        !self.secrets.is_empty()
+    }
+
+    fn active_secret(&self) -> Result<&str, WebhookSignatureError> {
+        self.secrets
+            .first()
+            .map(String::as_str)
+            .ok_or(WebhookSignatureError::NoSecretConfigured)
+    }
+
+    fn mac(secret: &str, parts: &[&[u8]]) -> Vec<u8> {
+        // HMAC accepts keys of any length, so this cannot fail
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key");
+        for part in parts {
+            mac.update(part);
+        }
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    fn matches_any_secret(&self, parts: &[&[u8]], expected: &[u8]) -> bool {
+        self.secrets
+            .iter()
+            .any(|secret| bool::from(Self::mac(secret, parts).ct_eq(expected)))
+    }
+
+    /// Produce a GitHub-style `sha256=<hex>` signature for a payload
+    pub fn sign_github(&self, payload: &[u8]) -> Result<String, WebhookSignatureError> {
+        let secret = self.active_secret()?;
+        Ok(format!(
+            "sha256={}",
+            hex::encode(Self::mac(secret, &[payload]))
+        ))
+    }
+
+    /// Verify an `X-Hub-Signature-256` header sent by GitHub
+    pub fn verify_github(
+        &self,
+        signature_header: Option<&str>,
+        payload: &[u8],
+    ) -> Result<(), WebhookSignatureError> {
+        if !self.has_secrets() {
+            return Err(WebhookSignatureError::NoSecretConfigured);
+        }
+        let header = signature_header.ok_or(WebhookSignatureError::MissingSignature)?;
+        let hex_sig = header.trim().strip_prefix("sha256=").ok_or_else(|| {
+            WebhookSignatureError::MalformedSignature("expected sha256= prefix".to_string())
+        })?;
+        let expected = hex::decode(hex_sig)
+            .map_err(|e| WebhookSignatureError::MalformedSignature(e.to_string()))?;
+
+        if self.matches_any_secret(&[payload], &expected) {
+            Ok(())
+        } else {
+            Err(WebhookSignatureError::InvalidSignature)
+        }
+    }
+
+    /// Verify an `X-Gitlab-Token` header, which carries the shared secret verbatim
+    pub fn verify_gitlab(&self, token_header: Option<&str>) -> Result<(), WebhookSignatureError> {
+        if !self.has_secrets() {
+            return Err(WebhookSignatureError::NoSecretConfigured);
+        }
+        let token = token_header.ok_or(WebhookSignatureError::MissingSignature)?;
+        let matched = self
+            .secrets
+            .iter()
+            .any(|secret| bool::from(secret.as_bytes().ct_eq(token.as_bytes())));
+
+        if matched {
+            Ok(())
+        } else {
+            Err(WebhookSignatureError::InvalidSignature)
+        }
+    }
+
+    /// Produce a `t=<unix>,v1=<hex>` header for outgoing webhooks.
+    /// The timestamp is part of the signed message so it cannot be swapped.
+    pub fn sign_timestamped(
+        &self,
+        payload: &[u8],
+        timestamp: i64,
+    ) -> Result<String, WebhookSignatureError> {
+        let secret = self.active_secret()?;
+        let ts = timestamp.to_string();
+        let sig = Self::mac(secret, &[ts.as_bytes(), b".", payload]);
+        Ok(format!("t={},v1={}", ts, hex::encode(sig)))
+    }
+
+    /// Verify a `t=<unix>,v1=<hex>` header, rejecting stale timestamps
+    pub fn verify_timestamped(
+        &self,
+        signature_header: Option<&str>,
+        payload: &[u8],
+        now: i64,
+    ) -> Result<(), WebhookSignatureError> {
+        if !self.has_secrets() {
+            return Err(WebhookSignatureError::NoSecretConfigured);
+        }
+        let header = signature_header.ok_or(WebhookSignatureError::MissingSignature)?;
+
+        let mut timestamp = None;
+        let mut signatures = Vec::new();
+        for part in header.split(',') {
+            match part.trim().split_once('=') {
+                Some(("t", value)) => timestamp = Some(value),
+                // Several v1 entries may be present while secrets are being rotated
+                Some(("v1", value)) => signatures.push(value),
+                _ => {}
+            }
+        }
+
+        let ts_str = timestamp.ok_or_else(|| {
+            WebhookSignatureError::MalformedSignature("missing timestamp".to_string())
+        })?;
+        let ts: i64 = ts_str.parse().map_err(|_| {
+            WebhookSignatureError::MalformedSignature("timestamp is not a number".to_string())
+        })?;
+        if signatures.is_empty() {
+            return Err(WebhookSignatureError::MalformedSignature(
+                "missing v1 signature".to_string(),
+            ));
+        }
+
+        let age_secs = now - ts;
+        if age_secs.unsigned_abs() > self.tolerance.as_secs() {
+            return Err(WebhookSignatureError::TimestampOutOfTolerance { age_secs });
+        }
+
+        for sig in signatures {
+            let Ok(expected) = hex::decode(sig) else {
+                continue;
+            };
+            if self.matches_any_secret(&[ts_str.as_bytes(), b".", payload], &expected) {
+                return Ok(());
+            }
+        }
+
+        Err(WebhookSignatureError::InvalidSignature)
+    }
+}
+
+/// Remembers recently seen delivery ids so a captured request cannot be replayed
+#[derive(Debug)]
+pub struct ReplayGuard {
+    seen: Mutex<HashMap<String, Instant>>,
+    ttl: Duration,
+}
+
+impl ReplayGuard {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            seen: Mutex::new(HashMap::new()),
+            ttl,
+        }
+    }
+
+    /// Record a delivery id, failing if it was already seen within the TTL
+    pub fn check_and_record(&self, delivery_id: &str) -> Result<(), WebhookSignatureError> {
+        let now = Instant::now();
+        let mut seen = self.seen.lock().unwrap();
+        seen.retain(|_, seen_at| now.duration_since(*seen_at) < self.ttl);
+
+        if seen.contains_key(delivery_id) {
+            return Err(WebhookSignatureError::Replayed(delivery_id.to_string()));
+        }
+        seen.insert(delivery_id.to_string(), now);
+        Ok(())
+    }
+}
+
+impl Default for ReplayGuard {
+    fn default() -> Self {
+        // Keep ids for twice the timestamp window so nothing slips through the edge
+        Self::new(DEFAULT_TIMESTAMP_TOLERANCE * 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_github_signature_roundtrip() {
+        let signer = WebhookSigner::new(vec!["It's a Secret to Everybody".to_string()]);
+        let payload = b"Hello, World!";
+
+        // Example from GitHub's webhook validation docs
+        let signature = signer.sign_github(payload).unwrap();
+        assert_eq!(
+            signature,
+            "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17"
+        );
+        assert!(signer.verify_github(Some(&signature), payload).is_ok());
+        assert_eq!(
+            signer.verify_github(Some(&signature), b"tampered"),
+            Err(WebhookSignatureError::InvalidSignature)
+        );
+        assert_eq!(
+            signer.verify_github(None, payload),
+            Err(WebhookSignatureError::MissingSignature)
+        );
+    }
+
+    #[test]
+    fn test_rotation_accepts_previous_secret() {
+        let old = WebhookSigner::new(vec!["old-secret".to_string()]);
+        let rotated = WebhookSigner::new(vec!["new-secret".to_string(), "old-secret".to_string()]);
+        let payload = br#"{"action":"closed"}"#;
+
+        let old_sig = old.sign_github(payload).unwrap();
+        assert!(rotated.verify_github(Some(&old_sig), payload).is_ok());
+
+        // Signing always uses the newest secret
+        let new_sig = rotated.sign_github(payload).unwrap();
+        assert_ne!(new_sig, old_sig);
+        assert!(old.verify_github(Some(&new_sig), payload).is_err());
+    }
+
+    #[test]
+    fn test_timestamped_signature_tolerance() {
+        let signer =
+            WebhookSigner::new(vec!["secret".to_string()]).with_tolerance(Duration::from_secs(60));
+        let payload = b"{}";
+        let header = signer.sign_timestamped(payload, 1_000).unwrap();
+
+        assert!(signer
+            .verify_timestamped(Some(&header), payload, 1_030)
+            .is_ok());
+        assert_eq!(
+            signer.verify_timestamped(Some(&header), payload, 1_100),
+            Err(WebhookSignatureError::TimestampOutOfTolerance { age_secs: 100 })
+        );
+
+        // Changing the timestamp invalidates the signature
+        let forged = header.replace("t=1000", "t=1090");
+        assert_eq!(
+            signer.verify_timestamped(Some(&forged), payload, 1_100),
+            Err(WebhookSignatureError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_gitlab_token() {
+        let signer = WebhookSigner::new(vec!["token-a".to_string(), "token-b".to_string()]);
+        assert!(signer.verify_gitlab(Some("token-b")).is_ok());
+        assert_eq!(
+            signer.verify_gitlab(Some("token-c")),
+            Err(WebhookSignatureError::InvalidSignature)
+        );
+        assert_eq!(
+            WebhookSigner::new(vec![]).verify_gitlab(Some("token-a")),
+            Err(WebhookSignatureError::NoSecretConfigured)
+        );
+    }
+
+    #[test]
+    fn test_replay_guard() {
+        let guard = ReplayGuard::default();
+        assert!(guard.check_and_record("delivery-1").is_ok());
+        assert!(guard.check_and_record("delivery-2").is_ok());
+        assert_eq!(
+            guard.check_and_record("delivery-1"),
+            Err(WebhookSignatureError::Replayed("delivery-1".to_string()))
+        );
+    }
+}
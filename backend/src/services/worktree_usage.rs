@@ -0,0 +1,260 @@
+//! Disk usage of attempt worktrees. Most of it is build output and installed dependencies
+//! (node_modules, target and friends), which can be deleted and rebuilt at any time. Only
+//! directories git ignores and tracks nothing under are counted as artifacts, so cleaning
+//! them never touches the attempt's changes.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use git2::Repository;
+use serde::Serialize;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Directory names that hold dependencies or build output in common toolchains
+const ARTIFACT_DIR_NAMES: &[&str] = &[
+    "node_modules",
+    "target",
+    "dist",
+    "build",
+    "out",
+    ".next",
+    ".nuxt",
+    ".turbo",
+    ".parcel-cache",
+    ".svelte-kit",
+    ".gradle",
+    "__pycache__",
+    ".pytest_cache",
+    ".mypy_cache",
+    ".venv",
+    "venv",
+    "coverage",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+pub struct ArtifactDir {
+    /// Relative to the worktree root
+    pub path: String,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct WorktreeUsage {
+    pub attempt_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub branch: String,
+    pub worktree_path: String,
+    /// False when the worktree directory is gone even though the attempt still points at it
+    pub exists: bool,
+    pub total_bytes: u64,
+    pub artifact_bytes: u64,
+    /// Largest first
+    pub artifacts: Vec<ArtifactDir>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ArtifactCleanup {
+    pub removed: Vec<ArtifactDir>,
+    pub freed_bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiskUsage {
+    pub total_bytes: u64,
+    pub artifacts: Vec<ArtifactDir>,
+}
+
+impl DiskUsage {
+    pub fn artifact_bytes(&self) -> u64 {
+        self.artifacts.iter().map(|artifact| artifact.bytes).sum()
+    }
+}
+
+/// Decides which directories are safe to delete: ignored by git, nothing tracked inside
+struct ArtifactFilter {
+    repo: Option<Repository>,
+    tracked: Vec<String>,
+}
+
+impl ArtifactFilter {
+    fn open(worktree: &Path) -> Self {
+        let repo = Repository::open(worktree).ok();
+        let tracked = repo
+            .as_ref()
+            .and_then(|repo| repo.index().ok())
+            .map(|index| {
+                index
+                    .iter()
+                    .map(|entry| String::from_utf8_lossy(&entry.path).into_owned())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { repo, tracked }
+    }
+
+    fn is_artifact(&self, relative: &str) -> bool {
+        let Some(name) = relative.rsplit('/').next() else {
+            return false;
+        };
+        if !ARTIFACT_DIR_NAMES.contains(&name) {
+            return false;
+        }
+        // Without a repository there is no telling what belongs to the attempt
+        let Some(repo) = &self.repo else {
+            return false;
+        };
+        if !repo.is_path_ignored(relative).unwrap_or(false) {
+            return false;
+        }
+        let prefix = format!("{}/", relative);
+        !self.tracked.iter().any(|path| path.starts_with(&prefix))
+    }
+}
+
+/// Total size of the worktree and of the artifact directories in it. Symlinks are not
+/// followed; artifact directories nested in another artifact are counted as part of it.
+pub fn measure(worktree: &Path) -> DiskUsage {
+    let filter = ArtifactFilter::open(worktree);
+    let mut usage = DiskUsage::default();
+    let mut pending = vec![PathBuf::new()];
+
+    while let Some(relative) = pending.pop() {
+        let Ok(entries) = fs::read_dir(worktree.join(&relative)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let child = relative.join(entry.file_name());
+            if file_type.is_dir() {
+                let child_str = child.to_string_lossy().replace('\\', "/");
+                if filter.is_artifact(&child_str) {
+                    let bytes = dir_size(&entry.path());
+                    usage.total_bytes += bytes;
+                    usage.artifacts.push(ArtifactDir {
+                        path: child_str,
+                        bytes,
+                    });
+                } else {
+                    pending.push(child);
+                }
+            } else if let Ok(metadata) = entry.metadata() {
+                usage.total_bytes += metadata.len();
+            }
+        }
+    }
+
+    usage
+        .artifacts
+        .sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+    usage
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let mut total = 0;
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            match entry.file_type() {
+                Ok(file_type) if file_type.is_dir() => pending.push(entry.path()),
+                Ok(_) => total += entry.metadata().map(|m| m.len()).unwrap_or(0),
+                Err(_) => {}
+            }
+        }
+    }
+    total
+}
+
+/// Delete the worktree's artifact directories. Stops at the first one that can't be
+/// removed; whatever was removed before it is already gone.
+pub fn clean_artifacts(worktree: &Path) -> io::Result<ArtifactCleanup> {
+    let usage = measure(worktree);
+    let mut removed = Vec::new();
+    for artifact in usage.artifacts {
+        fs::remove_dir_all(worktree.join(&artifact.path))?;
+        removed.push(artifact);
+    }
+    Ok(ArtifactCleanup {
+        freed_bytes: removed.iter().map(|artifact| artifact.bytes).sum(),
+        removed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn write(root: &Path, path: &str, bytes: usize) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, vec![b'x'; bytes]).unwrap();
+    }
+
+    fn git(root: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(root)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn test_measure_and_clean_only_ignored_untracked_artifacts() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        git(root, &["init", "-q"]);
+        fs::write(root.join(".gitignore"), "node_modules/\ntarget/\n").unwrap();
+        write(root, "src/main.rs", 100);
+        write(root, "node_modules/left-pad/index.js", 1_000);
+        write(root, "web/node_modules/react/index.js", 500);
+        write(root, "target/debug/app", 4_000);
+        // Tracked, so not an artifact even though it's named like one
+        write(root, "build/release.sh", 50);
+        git(root, &["add", "."]);
+
+        let usage = measure(root);
+        assert_eq!(
+            usage.artifacts,
+            vec![
+                ArtifactDir {
+                    path: "target".to_string(),
+                    bytes: 4_000
+                },
+                ArtifactDir {
+                    path: "node_modules".to_string(),
+                    bytes: 1_000
+                },
+                ArtifactDir {
+                    path: "web/node_modules".to_string(),
+                    bytes: 500
+                },
+            ]
+        );
+        assert!(usage.total_bytes >= 5_650);
+
+        let cleanup = clean_artifacts(root).unwrap();
+        assert_eq!(cleanup.freed_bytes, 5_500);
+        assert!(!root.join("target").exists());
+        assert!(!root.join("web/node_modules").exists());
+        assert!(root.join("build/release.sh").exists());
+        assert!(root.join("src/main.rs").exists());
+    }
+}
@@ -0,0 +1,3052 @@
+//! Scenario harness for exercising the orchestration pipeline end to end.
+//!
+//! A [`Scenario`] owns a throwaway git repository and database, runs coding agents with the
+//! mock executor and drives the execution monitor by hand, so tests can script a sequence of
+//! tasks, dependencies, attempts, failures, retries, approvals and merges and then assert on
+//! the resulting board.
+
+use std::{path::Path, str::FromStr, sync::Arc, time::Duration};
+
+use git2::Repository;
+use sqlx::{
+    postgres::{PgConnectOptions, PgPool},
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool},
+};
+use tempfile::TempDir;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    db::{self, DbPool},
+    execution_monitor::handle_completed_executions,
+    executor::ExecutorConfig,
+    executors::mock::MockScript,
+    models::{
+        config::{Config, RetryPolicy},
+        execution_process::{ExecutionProcess, ExecutionProcessStatus, ExecutionProcessType},
+        project::{CreateProject, Project},
+        task::{CreateTask, Task, TaskStatus},
+        task_attempt::{CreateTaskAttempt, TaskAttempt, TaskAttemptError},
+        task_decomposition::TaskDecomposition,
+        Environment,
+    },
+    services::migrations,
+};
+
+/// How long [`Scenario::run_until_idle`] waits for executions before failing the test
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A PostgreSQL server to run scenarios against instead of SQLite, as in
+/// `postgres://postgres@localhost`. Each scenario creates a database of its own on it.
+const TEST_POSTGRES_URL_ENV: &str = "VIBE_KANBAN_TEST_POSTGRES_URL";
+
+/// Optional project scripts for a scenario
+#[derive(Debug, Default)]
+pub struct ProjectScripts {
+    pub setup: Option<String>,
+    pub dev: Option<String>,
+    pub cleanup: Option<String>,
+    pub release: Option<String>,
+    pub verification: Option<String>,
+}
+
+/// A fresh, unmigrated database for a scenario kept in `dir`
+async fn connect(dir: &Path) -> DbPool {
+    if let Ok(url) = std::env::var(TEST_POSTGRES_URL_ENV) {
+        let server = PgPool::connect(&url).await.unwrap();
+        let name = format!("vibe_kanban_test_{}", Uuid::new_v4().simple());
+        sqlx::query(&format!("CREATE DATABASE {}", name))
+            .execute(&server)
+            .await
+            .unwrap();
+        let options = PgConnectOptions::from_str(&url).unwrap().database(&name);
+        return DbPool::Postgres(PgPool::connect_with(options).await.unwrap());
+    }
+
+    // Same journal and lock wait as the app's database, so background writers that
+    // overlap a test's own queries wait their turn instead of failing with SQLITE_BUSY
+    let options = SqliteConnectOptions::new()
+        .filename(dir.join("db.sqlite"))
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .busy_timeout(Duration::from_secs(30));
+    DbPool::Sqlite(SqlitePool::connect_with(options).await.unwrap())
+}
+
+pub struct Scenario {
+    _dir: TempDir,
+    pub app_state: AppState,
+    pub project: Project,
+    worktrees: std::sync::Mutex<Vec<String>>,
+}
+
+impl Scenario {
+    pub async fn new() -> Self {
+        Self::with_scripts(ProjectScripts::default()).await
+    }
+
+    pub async fn with_scripts(scripts: ProjectScripts) -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let repo_path = dir.path().join("repo");
+        init_repo(&repo_path);
+
+        let pool = connect(dir.path()).await;
+        migrations::upgrade(&pool).await.unwrap();
+
+        // Scripted failures are the agent's own, so they aren't retried unless a test asks
+        let config = Config {
+            executor: ExecutorConfig::Mock,
+            sound_alerts: false,
+            push_notifications: false,
+            analytics_enabled: Some(false),
+            executor_retry: RetryPolicy {
+                max_attempts: 1,
+                backoff_seconds: 0,
+            },
+            ..Config::default()
+        };
+        let app_state = AppState::new(
+            pool.clone(),
+            Arc::new(tokio::sync::RwLock::new(config)),
+            Environment::Local,
+        )
+        .await;
+
+        let project = Project::create(
+            &pool,
+            &CreateProject {
+                name: "scenario".to_string(),
+                git_repo_path: repo_path.to_string_lossy().to_string(),
+                use_existing_repo: true,
+                setup_script: scripts.setup,
+                dev_script: scripts.dev,
+                cleanup_script: scripts.cleanup,
+                commit_message_template: None,
+                release_script: scripts.release,
+                merge_check_script: None,
+                verification_script: scripts.verification,
+                default_executor: None,
+                default_branch: None,
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+
+        Self {
+            _dir: dir,
+            app_state,
+            project,
+            worktrees: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+
+    fn pool(&self) -> &DbPool {
+        &self.app_state.db_pool
+    }
+
+    /// Create a task whose description embeds the script the mock agent will play back
+    pub async fn add_task(&self, title: &str, script: &MockScript) -> Task {
+        Task::create(
+            self.pool(),
+            &CreateTask {
+                project_id: self.project.id,
+                title: title.to_string(),
+                description: Some(script_description(script)),
+                parent_task_attempt: None,
+                parent_task_id: None,
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap()
+    }
+
+    /// Replace the script a task's next attempt will run, e.g. before a retry
+    pub async fn set_script(&self, task: &Task, script: &MockScript) {
+        let current = Task::find_by_id(self.pool(), task.id)
+            .await
+            .unwrap()
+            .unwrap();
+        Task::update(
+            self.pool(),
+            task.id,
+            task.project_id,
+            current.title,
+            Some(script_description(script)),
+            current.status,
+            current.parent_task_attempt,
+        )
+        .await
+        .unwrap();
+    }
+
+    /// Create an attempt and start its execution, exactly as the attempts route does
+    pub async fn start_attempt(&self, task: &Task) -> TaskAttempt {
+        self.try_start_attempt(task).await.unwrap()
+    }
+
+    /// Like [`Self::start_attempt`], but hands back why the attempt couldn't start, e.g.
+    /// because the task is blocked
+    pub async fn try_start_attempt(&self, task: &Task) -> Result<TaskAttempt, TaskAttemptError> {
+        self.start_attempt_with(task, false, false).await
+    }
+
+    /// Like [`Self::start_attempt`], but the attempt only plans until its plan is approved
+    pub async fn start_plan_only_attempt(&self, task: &Task) -> TaskAttempt {
+        self.start_attempt_with(task, true, false).await.unwrap()
+    }
+
+    /// Start an attempt that proposes subtasks for the task, as the decompose route does
+    pub async fn start_decomposition(&self, task: &Task) -> TaskAttempt {
+        self.start_attempt_with(task, true, true).await.unwrap()
+    }
+
+    async fn start_attempt_with(
+        &self,
+        task: &Task,
+        plan_only: bool,
+        decomposing: bool,
+    ) -> Result<TaskAttempt, TaskAttemptError> {
+        let attempt = TaskAttempt::create(
+            self.pool(),
+            &CreateTaskAttempt {
+                executor: Some(ExecutorConfig::Mock.to_string()),
+                base_branch: None,
+                plan_only: Some(plan_only),
+                model: None,
+                executor_profile_id: None,
+            },
+            task.id,
+        )
+        .await
+        .unwrap();
+        self.worktrees
+            .lock()
+            .unwrap()
+            .push(attempt.worktree_path.clone());
+        if decomposing {
+            TaskDecomposition::create(self.pool(), attempt.id, task.id)
+                .await
+                .unwrap();
+        }
+
+        TaskAttempt::start_execution(
+            self.pool(),
+            &self.app_state,
+            attempt.id,
+            task.id,
+            self.project.id,
+        )
+        .await?;
+        Ok(attempt)
+    }
+
+    /// Drive the execution monitor until no process of the given attempts is still running
+    pub async fn run_until_idle(&self, attempts: &[&TaskAttempt]) {
+        let deadline = tokio::time::Instant::now() + IDLE_TIMEOUT;
+        loop {
+            handle_completed_executions(&self.app_state).await;
+
+            let mut busy = false;
+            for attempt in attempts {
+                busy |= self.app_state.has_running_execution(attempt.id).await;
+            }
+            if !busy {
+                return;
+            }
+
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "executions did not finish within {:?}",
+                IDLE_TIMEOUT
+            );
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Merge an attempt into its base branch and mark the task done, as the merge route does
+    pub async fn merge(&self, attempt: &TaskAttempt) -> String {
+        let commit =
+            TaskAttempt::merge_changes(self.pool(), attempt.id, attempt.task_id, self.project.id)
+                .await
+                .unwrap();
+        Task::update_status(
+            self.pool(),
+            attempt.task_id,
+            self.project.id,
+            TaskStatus::Done,
+        )
+        .await
+        .unwrap();
+        commit
+    }
+
+    /// Status of every task on the board, keyed by title
+    pub async fn board(&self) -> Vec<(String, TaskStatus)> {
+        let mut tasks = Task::find_by_project_id_with_attempt_status(self.pool(), self.project.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| (t.title, t.status))
+            .collect::<Vec<_>>();
+        tasks.sort_by(|a, b| a.0.cmp(&b.0));
+        tasks
+    }
+
+    pub async fn assert_board(&self, expected: &[(&str, TaskStatus)]) {
+        let mut expected = expected
+            .iter()
+            .map(|(title, status)| (title.to_string(), status.clone()))
+            .collect::<Vec<_>>();
+        expected.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(self.board().await, expected);
+    }
+
+    /// Type and final status of each process the attempt ran, in start order
+    pub async fn processes(
+        &self,
+        attempt: &TaskAttempt,
+    ) -> Vec<(ExecutionProcessType, ExecutionProcessStatus)> {
+        ExecutionProcess::find_by_task_attempt_id(self.pool(), attempt.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|p| (p.process_type, p.status))
+            .collect()
+    }
+
+    /// Read a file as committed on the project's `main` branch
+    pub fn read_base_file(&self, path: &str) -> Option<String> {
+        let repo = Repository::open(&self.project.git_repo_path).unwrap();
+        let tree = repo
+            .find_branch("main", git2::BranchType::Local)
+            .unwrap()
+            .get()
+            .peel_to_tree()
+            .unwrap();
+        let entry = tree.get_path(std::path::Path::new(path)).ok()?;
+        let blob = repo.find_blob(entry.id()).unwrap();
+        Some(String::from_utf8_lossy(blob.content()).to_string())
+    }
+}
+
+impl Drop for Scenario {
+    fn drop(&mut self) {
+        // Worktrees live outside the temp dir, under the shared worktree base directory
+        for path in self.worktrees.lock().unwrap().iter() {
+            let _ = std::fs::remove_dir_all(path);
+        }
+    }
+}
+
+fn script_description(script: &MockScript) -> String {
+    format!(
+        "```mock-script\n{}\n```",
+        serde_json::to_string(script).unwrap()
+    )
+}
+
+fn init_repo(path: &std::path::Path) {
+    let repo = Repository::init(path).unwrap();
+    let mut config = repo.config().unwrap();
+    config.set_str("user.name", "Scenario").unwrap();
+    config
+        .set_str("user.email", "scenario@example.com")
+        .unwrap();
+
+    std::fs::write(path.join("README.md"), "# scenario\n").unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(std::path::Path::new("README.md")).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = repo.signature().unwrap();
+    repo.commit(
+        Some("refs/heads/main"),
+        &signature,
+        &signature,
+        "Initial commit",
+        &tree,
+        &[],
+    )
+    .unwrap();
+    repo.set_head("refs/heads/main").unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        executors::mock::MockStep,
+        models::{attempt_rollback::AttemptRollback, execution_diff::ExecutionDiff},
+    };
+
+    fn edit_script(path: &str, content: &str, exit_code: i32) -> MockScript {
+        MockScript {
+            steps: vec![
+                MockStep::FileEdit {
+                    path: path.to_string(),
+                    content: content.to_string(),
+                },
+                MockStep::Message {
+                    content: format!("Edited {}", path),
+                },
+            ],
+            exit_code,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parallel_tasks_and_merge() {
+        let scenario = Scenario::new().await;
+        let first = scenario
+            .add_task("First", &edit_script("first.txt", "one\n", 0))
+            .await;
+        let second = scenario
+            .add_task("Second", &edit_script("second.txt", "two\n", 0))
+            .await;
+        scenario
+            .add_task("Untouched", &MockScript::default_for_task("Untouched"))
+            .await;
+
+        let first_attempt = scenario.start_attempt(&first).await;
+        let second_attempt = scenario.start_attempt(&second).await;
+        scenario
+            .assert_board(&[
+                ("First", TaskStatus::InProgress),
+                ("Second", TaskStatus::InProgress),
+                ("Untouched", TaskStatus::Todo),
+            ])
+            .await;
+
+        scenario
+            .run_until_idle(&[&first_attempt, &second_attempt])
+            .await;
+        scenario.merge(&first_attempt).await;
+
+        scenario
+            .assert_board(&[
+                ("First", TaskStatus::Done),
+                ("Second", TaskStatus::InReview),
+                ("Untouched", TaskStatus::Todo),
+            ])
+            .await;
+        assert_eq!(
+            scenario.read_base_file("first.txt").as_deref(),
+            Some("one\n")
+        );
+        assert_eq!(scenario.read_base_file("second.txt"), None);
+    }
+
+    #[tokio::test]
+    async fn test_merge_queue_picks_up_where_the_server_stopped() {
+        use crate::{
+            models::merge_queue_entry::{MergeQueueEntry, MergeQueueStatus},
+            services::merge_queue::{self, MergeQueueError},
+        };
+
+        let scenario = Scenario::new().await;
+        let pool = scenario.pool();
+        let first = scenario
+            .add_task("First", &edit_script("first.txt", "one\n", 0))
+            .await;
+        let second = scenario
+            .add_task("Second", &edit_script("second.txt", "two\n", 0))
+            .await;
+        let first_attempt = scenario.start_attempt(&first).await;
+        let second_attempt = scenario.start_attempt(&second).await;
+        scenario
+            .run_until_idle(&[&first_attempt, &second_attempt])
+            .await;
+
+        let queued = merge_queue::enqueue(pool, &scenario.project, &first, &first_attempt)
+            .await
+            .unwrap();
+        assert!(matches!(
+            merge_queue::enqueue(pool, &scenario.project, &first, &first_attempt).await,
+            Err(MergeQueueError::AlreadyQueued)
+        ));
+        merge_queue::enqueue(pool, &scenario.project, &second, &second_attempt)
+            .await
+            .unwrap();
+        // The server stopped while rebasing the first, and after the second landed but before
+        // that was recorded
+        MergeQueueEntry::set_status(pool, queued.id, MergeQueueStatus::Rebasing)
+            .await
+            .unwrap();
+        let landed =
+            TaskAttempt::merge_changes(pool, second_attempt.id, second.id, scenario.project.id)
+                .await
+                .unwrap();
+
+        merge_queue::recover(&scenario.app_state).await;
+        let entries = tokio::time::timeout(IDLE_TIMEOUT, async {
+            loop {
+                let entries = merge_queue::for_project(pool, scenario.project.id)
+                    .await
+                    .unwrap();
+                if entries.iter().all(|entry| entry.status.is_finished()) {
+                    return entries;
+                }
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        })
+        .await
+        .expect("queued merges did not finish");
+        let outcomes: Vec<_> = entries
+            .iter()
+            .map(|entry| (entry.attempt_id, entry.status))
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                (second_attempt.id, MergeQueueStatus::Merged),
+                (first_attempt.id, MergeQueueStatus::Merged),
+            ]
+        );
+        assert_eq!(entries[0].merge_commit.as_deref(), Some(landed.as_str()));
+        scenario
+            .assert_board(&[("First", TaskStatus::Done), ("Second", TaskStatus::Done)])
+            .await;
+        assert_eq!(
+            scenario.read_base_file("first.txt").as_deref(),
+            Some("one\n")
+        );
+        assert_eq!(
+            scenario.read_base_file("second.txt").as_deref(),
+            Some("two\n")
+        );
+
+        // Only the entries that finished last are kept
+        for _ in 0..20 {
+            let entry = merge_queue::enqueue(pool, &scenario.project, &first, &first_attempt)
+                .await
+                .unwrap();
+            MergeQueueEntry::fail(pool, entry.id, "Stopped", None)
+                .await
+                .unwrap();
+        }
+        merge_queue::enqueue(pool, &scenario.project, &first, &first_attempt)
+            .await
+            .unwrap();
+        let entries = merge_queue::for_project(pool, scenario.project.id)
+            .await
+            .unwrap();
+        assert_eq!(entries.len(), 21);
+        assert_eq!(entries[0].status, MergeQueueStatus::Queued);
+        assert!(entries[1..]
+            .iter()
+            .all(|entry| entry.status == MergeQueueStatus::Failed));
+    }
+
+    #[tokio::test]
+    async fn test_coding_agent_run_diff_is_captured() {
+        let scenario = Scenario::new().await;
+        let task = scenario
+            .add_task("Edit", &edit_script("notes.txt", "one\ntwo\n", 0))
+            .await;
+        let attempt = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&attempt]).await;
+
+        let agent = ExecutionProcess::find_by_task_attempt_id(scenario.pool(), attempt.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|p| p.process_type == ExecutionProcessType::CodingAgent)
+            .unwrap();
+        let diff = ExecutionDiff::find_by_execution_process_id(scenario.pool(), agent.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(diff.to_commit.is_some());
+        assert_ne!(diff.to_commit.as_deref(), Some(diff.from_commit.as_str()));
+        assert_eq!(diff.summary.additions, 2);
+        assert_eq!(diff.summary.files.len(), 1);
+        assert_eq!(diff.summary.files[0].path, "notes.txt");
+
+        let conversation =
+            crate::routes::task_attempts::normalize_process_logs(scenario.pool(), &agent).await;
+        assert_eq!(conversation.diff_summary, Some(diff.summary));
+    }
+
+    #[tokio::test]
+    async fn test_board_shows_latest_pull_request() {
+        let scenario = Scenario::new().await;
+        let task = scenario
+            .add_task("Review", &edit_script("notes.txt", "one\n", 0))
+            .await;
+        let first = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&first]).await;
+        let second = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&second]).await;
+
+        for (attempt, number, status) in [(&first, 1, "closed"), (&second, 2, "open")] {
+            let url = format!("https://github.com/o/r/pull/{}", number);
+            db::query!(
+                "UPDATE task_attempts SET pr_url = $1, pr_number = $2, pr_status = $3 WHERE id = $4",
+                url,
+                number,
+                status,
+                attempt.id
+            )
+            .execute(scenario.pool())
+            .await
+            .unwrap();
+        }
+
+        let board =
+            Task::find_by_project_id_with_attempt_status(scenario.pool(), scenario.project.id)
+                .await
+                .unwrap();
+        assert_eq!(board[0].pr_number, Some(2));
+        assert_eq!(board[0].pr_status.as_deref(), Some("open"));
+        assert_eq!(
+            board[0].pr_url.as_deref(),
+            Some("https://github.com/o/r/pull/2")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_commit_is_recorded_unless_auto_commit_is_off() {
+        let scenario = Scenario::new().await;
+        let pool = scenario.pool();
+        let agent_of = |attempt_id| async move {
+            ExecutionProcess::find_by_task_attempt_id(pool, attempt_id)
+                .await
+                .unwrap()
+                .into_iter()
+                .find(|p| p.process_type == ExecutionProcessType::CodingAgent)
+                .unwrap()
+        };
+
+        let task = scenario
+            .add_task("Commit", &edit_script("notes.txt", "one\n", 0))
+            .await;
+        let attempt = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&attempt]).await;
+        let agent = agent_of(attempt.id).await;
+        let diff = ExecutionDiff::find_by_execution_process_id(scenario.pool(), agent.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(agent.commit_sha.is_some());
+        assert_eq!(agent.commit_sha, diff.to_commit);
+
+        scenario
+            .app_state
+            .get_config()
+            .write()
+            .await
+            .post_execution_git
+            .auto_commit = false;
+        let task = scenario
+            .add_task("Leave", &edit_script("other.txt", "two\n", 0))
+            .await;
+        let attempt = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&attempt]).await;
+        let agent = agent_of(attempt.id).await;
+        let diff = ExecutionDiff::find_by_execution_process_id(scenario.pool(), agent.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(agent.commit_sha.as_deref(), Some(diff.from_commit.as_str()));
+        let worktree = Repository::open(&attempt.worktree_path).unwrap();
+        assert!(!worktree.statuses(None).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_plan_only_attempt_waits_for_approval() {
+        use crate::{
+            executor::NormalizedEntryType,
+            models::attempt_plan::{AttemptPlan, AttemptPlanStatus},
+        };
+
+        let scenario = Scenario::new().await;
+        let script = MockScript {
+            steps: vec![MockStep::Message {
+                content: "1. Add `plan.txt`".to_string(),
+            }],
+            exit_code: 0,
+        };
+        let task = scenario.add_task("Planned", &script).await;
+        let attempt = scenario.start_plan_only_attempt(&task).await;
+        scenario.run_until_idle(&[&attempt]).await;
+
+        scenario
+            .assert_board(&[("Planned", TaskStatus::PlanReview)])
+            .await;
+        let plan = AttemptPlan::find_by_task_attempt_id(scenario.pool(), attempt.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(plan.content, "1. Add `plan.txt`");
+        assert_eq!(plan.status, AttemptPlanStatus::Pending);
+
+        let agent = ExecutionProcess::find_by_id(scenario.pool(), plan.execution_process_id)
+            .await
+            .unwrap()
+            .unwrap();
+        let conversation =
+            crate::routes::task_attempts::normalize_process_logs(scenario.pool(), &agent).await;
+        assert!(conversation.entries.iter().any(|entry| {
+            matches!(entry.entry_type, NormalizedEntryType::PlanEntry)
+                && entry.content == plan.content
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_approval_gates_hold_changes_until_approved() {
+        use axum::{extract::State, Extension, Json};
+
+        use crate::{
+            models::{
+                attempt_plan::{AttemptPlan, AttemptPlanStatus},
+                attempt_review::{ApproveAttempt, AttemptReview, ReviewDecision},
+            },
+            routes::{attempt_reviews::approve_attempt, task_attempts::approve_plan},
+        };
+
+        let succeeded = |response: serde_json::Value| response["success"] == true;
+
+        let scenario = Scenario::new().await;
+        let pool = scenario.pool();
+        let plan = MockScript {
+            steps: vec![MockStep::Message {
+                content: "1. Add `gated.txt`".to_string(),
+            }],
+            exit_code: 0,
+        };
+        let task = scenario.add_task("Gated", &plan).await;
+        let attempt = scenario.start_plan_only_attempt(&task).await;
+        scenario.run_until_idle(&[&attempt]).await;
+        scenario
+            .assert_board(&[("Gated", TaskStatus::PlanReview)])
+            .await;
+
+        // Approving the plan has the same session implement it
+        scenario
+            .set_script(&task, &edit_script("gated.txt", "done\n", 0))
+            .await;
+        let task = Task::find_by_id(pool, task.id).await.unwrap().unwrap();
+        let approved = approve_plan(
+            Extension(scenario.project.clone()),
+            Extension(task.clone()),
+            Extension(attempt.clone()),
+            State(scenario.app_state.clone()),
+        )
+        .await
+        .unwrap();
+        assert!(succeeded(serde_json::to_value(&approved.0).unwrap()));
+        scenario.run_until_idle(&[&attempt]).await;
+        let plan = AttemptPlan::find_by_task_attempt_id(pool, attempt.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(plan.status, AttemptPlanStatus::Approved);
+
+        // The implementation waits for review; nothing reaches the base branch before that
+        scenario
+            .assert_board(&[("Gated", TaskStatus::InReview)])
+            .await;
+        assert_eq!(scenario.read_base_file("gated.txt"), None);
+        let again = approve_plan(
+            Extension(scenario.project.clone()),
+            Extension(task.clone()),
+            Extension(attempt.clone()),
+            State(scenario.app_state.clone()),
+        )
+        .await
+        .unwrap();
+        assert!(!succeeded(serde_json::to_value(&again.0).unwrap()));
+
+        let review = approve_attempt(
+            Extension(scenario.project.clone()),
+            Extension(task.clone()),
+            Extension(attempt.clone()),
+            State(scenario.app_state.clone()),
+            None,
+            Json(ApproveAttempt {
+                comment: Some("Ship it".to_string()),
+            }),
+        )
+        .await
+        .unwrap();
+        assert!(succeeded(serde_json::to_value(&review.0).unwrap()));
+        scenario.assert_board(&[("Gated", TaskStatus::Done)]).await;
+        assert_eq!(
+            scenario.read_base_file("gated.txt").as_deref(),
+            Some("done\n")
+        );
+        let reviews = AttemptReview::find_by_task_attempt_id(pool, attempt.id)
+            .await
+            .unwrap();
+        assert_eq!(reviews.len(), 1);
+        assert_eq!(reviews[0].decision, ReviewDecision::Approved);
+    }
+
+    #[tokio::test]
+    async fn test_blocked_task_starts_once_its_dependency_is_merged() {
+        use crate::models::task_dependency::{topological_order, TaskDependency};
+
+        let scenario = Scenario::new().await;
+        let pool = scenario.pool();
+        let schema = scenario
+            .add_task("Schema", &edit_script("schema.sql", "create\n", 0))
+            .await;
+        let api = scenario
+            .add_task("API", &edit_script("api.txt", "uses schema\n", 0))
+            .await;
+        TaskDependency::add(pool, &api, schema.id).await.unwrap();
+        let edges = TaskDependency::find_by_project_id(pool, scenario.project.id)
+            .await
+            .unwrap();
+        assert_eq!(
+            topological_order(&[api.id, schema.id], &edges),
+            vec![schema.id, api.id]
+        );
+
+        match scenario.try_start_attempt(&api).await {
+            Err(TaskAttemptError::ValidationError(message)) => {
+                assert_eq!(message, "Blocked by unfinished tasks: Schema")
+            }
+            other => panic!(
+                "expected the task to be blocked, got {:?}",
+                other.map(|a| a.id)
+            ),
+        }
+        scenario
+            .assert_board(&[("API", TaskStatus::Todo), ("Schema", TaskStatus::Todo)])
+            .await;
+
+        // Finishing the run isn't enough; the blocker has to be done
+        let first = scenario.start_attempt(&schema).await;
+        scenario.run_until_idle(&[&first]).await;
+        assert!(scenario.try_start_attempt(&api).await.is_err());
+
+        scenario.merge(&first).await;
+        let second = scenario.start_attempt(&api).await;
+        scenario.run_until_idle(&[&second]).await;
+        scenario.merge(&second).await;
+
+        scenario
+            .assert_board(&[("API", TaskStatus::Done), ("Schema", TaskStatus::Done)])
+            .await;
+        assert_eq!(
+            scenario.read_base_file("schema.sql").as_deref(),
+            Some("create\n")
+        );
+        assert_eq!(
+            scenario.read_base_file("api.txt").as_deref(),
+            Some("uses schema\n")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_decomposition_creates_subtasks_instead_of_a_plan() {
+        use crate::models::{
+            attempt_plan::AttemptPlan,
+            task_decomposition::{TaskDecomposition, TaskDecompositionStatus},
+        };
+
+        let scenario = Scenario::new().await;
+        let script = MockScript {
+            steps: vec![MockStep::Message {
+                content: "Subtasks:\n1. Add the table: a migration\n2. Wire up the API".to_string(),
+            }],
+            exit_code: 0,
+        };
+        let task = scenario.add_task("Big", &script).await;
+        let attempt = scenario.start_decomposition(&task).await;
+        scenario.run_until_idle(&[&attempt]).await;
+
+        assert!(
+            AttemptPlan::find_by_task_attempt_id(scenario.pool(), attempt.id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        let decomposition = TaskDecomposition::find_by_task_attempt_id(scenario.pool(), attempt.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(decomposition.status, TaskDecompositionStatus::Completed);
+        assert_eq!(decomposition.subtask_count, 2);
+
+        let subtasks = Task::find_subtasks(scenario.pool(), task.id).await.unwrap();
+        let titles: Vec<_> = subtasks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, vec!["Add the table", "Wire up the API"]);
+        assert_eq!(subtasks[0].description.as_deref(), Some("a migration"));
+        assert!(subtasks
+            .iter()
+            .all(|t| t.status == TaskStatus::Todo && t.project_id == task.project_id));
+        scenario
+            .assert_board(&[
+                ("Wire up the API", TaskStatus::Todo),
+                ("Add the table", TaskStatus::Todo),
+                ("Big", TaskStatus::InReview),
+            ])
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_prune_removes_only_finished_worktrees() {
+        let scenario = Scenario::new().await;
+        let merged = scenario
+            .add_task("Merged", &edit_script("merged.txt", "done\n", 0))
+            .await;
+        let open = scenario
+            .add_task("Open", &edit_script("open.txt", "wip\n", 0))
+            .await;
+        let merged_attempt = scenario.start_attempt(&merged).await;
+        let open_attempt = scenario.start_attempt(&open).await;
+        scenario
+            .run_until_idle(&[&merged_attempt, &open_attempt])
+            .await;
+        scenario.merge(&merged_attempt).await;
+
+        let pruned = crate::execution_monitor::prune_finished_worktrees(
+            scenario.pool(),
+            scenario.project.id,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(pruned, vec![merged_attempt.id]);
+        assert!(!std::path::Path::new(&merged_attempt.worktree_path).exists());
+        assert!(std::path::Path::new(&open_attempt.worktree_path).exists());
+        let merged_attempt = TaskAttempt::find_by_id(scenario.pool(), merged_attempt.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(merged_attempt.worktree_deleted);
+    }
+
+    #[tokio::test]
+    async fn test_failed_attempt_then_retry() {
+        let scenario = Scenario::new().await;
+        let task = scenario
+            .add_task("Flaky", &edit_script("flaky.txt", "broken\n", 1))
+            .await;
+
+        let failed = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&failed]).await;
+        assert_eq!(
+            scenario.processes(&failed).await,
+            vec![(
+                ExecutionProcessType::CodingAgent,
+                ExecutionProcessStatus::Failed
+            )]
+        );
+        scenario
+            .assert_board(&[("Flaky", TaskStatus::InReview)])
+            .await;
+
+        scenario
+            .set_script(&task, &edit_script("flaky.txt", "fixed\n", 0))
+            .await;
+        let retry = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&retry]).await;
+        scenario.merge(&retry).await;
+
+        scenario.assert_board(&[("Flaky", TaskStatus::Done)]).await;
+        assert_eq!(
+            scenario.read_base_file("flaky.txt").as_deref(),
+            Some("fixed\n")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_start_is_retried_until_policy_runs_out() {
+        use crate::execution_monitor::retry_failed_spawns;
+
+        let scenario = Scenario::new().await;
+        scenario.app_state.get_config().write().await.executor_retry = RetryPolicy {
+            max_attempts: 2,
+            backoff_seconds: 0,
+        };
+        let task = scenario
+            .add_task("Broken", &edit_script("broken.txt", "x\n", 127))
+            .await;
+
+        let attempt = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&attempt]).await;
+        let waiting = ExecutionProcess::find_by_task_attempt_id(scenario.pool(), attempt.id)
+            .await
+            .unwrap();
+        assert_eq!(waiting.len(), 1);
+        assert!(waiting[0].retry_at.is_some(), "the retry is scheduled");
+        scenario
+            .assert_board(&[("Broken", TaskStatus::InProgress)])
+            .await;
+
+        retry_failed_spawns(&scenario.app_state).await;
+        scenario.run_until_idle(&[&attempt]).await;
+        let retried = ExecutionProcess::find_by_task_attempt_id(scenario.pool(), attempt.id)
+            .await
+            .unwrap();
+        assert_eq!(retried.len(), 1, "the retry reuses the execution record");
+        assert_eq!(retried[0].retry_count, 1);
+        assert_eq!(retried[0].status, ExecutionProcessStatus::Failed);
+        assert!(retried[0].retry_at.is_none(), "no attempts left");
+        scenario
+            .assert_board(&[("Broken", TaskStatus::InReview)])
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_attempt_over_limit_waits_in_queue() {
+        use crate::{models::config::ExecutionLimits, services::execution_queue};
+
+        let scenario = Scenario::new().await;
+        scenario
+            .app_state
+            .get_config()
+            .write()
+            .await
+            .execution_limits = ExecutionLimits {
+            max_running: Some(1),
+            max_running_per_project: None,
+            ..Default::default()
+        };
+        let first = scenario
+            .add_task("First", &edit_script("first.txt", "one\n", 0))
+            .await;
+        let second = scenario
+            .add_task("Second", &edit_script("second.txt", "two\n", 0))
+            .await;
+
+        let first_attempt = scenario.start_attempt(&first).await;
+        let second_attempt = scenario.start_attempt(&second).await;
+        scenario
+            .assert_board(&[
+                ("First", TaskStatus::InProgress),
+                ("Second", TaskStatus::Queued),
+            ])
+            .await;
+        let tasks =
+            Task::find_by_project_id_with_attempt_status(scenario.pool(), scenario.project.id)
+                .await
+                .unwrap();
+        let positions: Vec<_> = tasks
+            .iter()
+            .map(|t| (t.title.as_str(), t.queue_position))
+            .collect();
+        assert!(positions.contains(&("First", None)));
+        assert!(positions.contains(&("Second", Some(1))));
+
+        scenario.run_until_idle(&[&first_attempt]).await;
+        execution_queue::dispatch_queued(&scenario.app_state).await;
+        scenario.run_until_idle(&[&second_attempt]).await;
+        scenario
+            .assert_board(&[
+                ("First", TaskStatus::InReview),
+                ("Second", TaskStatus::InReview),
+            ])
+            .await;
+        assert_eq!(
+            scenario.processes(&second_attempt).await,
+            vec![(
+                ExecutionProcessType::CodingAgent,
+                ExecutionProcessStatus::Completed
+            )],
+            "the queued attempt ran once it had a slot"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_setup_and_cleanup_scripts_run_around_agent() {
+        let scenario = Scenario::with_scripts(ProjectScripts {
+            setup: Some("echo setup > .setup-ran".to_string()),
+            cleanup: Some("echo cleanup".to_string()),
+            ..ProjectScripts::default()
+        })
+        .await;
+        let task = scenario
+            .add_task("Scripted", &edit_script("out.txt", "done\n", 0))
+            .await;
+
+        let attempt = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&attempt]).await;
+
+        assert_eq!(
+            scenario.processes(&attempt).await,
+            vec![
+                (
+                    ExecutionProcessType::SetupScript,
+                    ExecutionProcessStatus::Completed
+                ),
+                (
+                    ExecutionProcessType::CodingAgent,
+                    ExecutionProcessStatus::Completed
+                ),
+                (
+                    ExecutionProcessType::CleanupScript,
+                    ExecutionProcessStatus::Completed
+                ),
+            ]
+        );
+        scenario
+            .assert_board(&[("Scripted", TaskStatus::InReview)])
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_failed_setup_script_keeps_the_agent_from_starting() {
+        use crate::{executor::NormalizedEntryType, routes::task_attempts::normalize_process_logs};
+
+        let scenario = Scenario::with_scripts(ProjectScripts {
+            setup: Some("echo installing >&2; exit 3".to_string()),
+            ..ProjectScripts::default()
+        })
+        .await;
+        let task = scenario
+            .add_task("Broken setup", &edit_script("out.txt", "done\n", 0))
+            .await;
+
+        let attempt = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&attempt]).await;
+
+        assert_eq!(
+            scenario.processes(&attempt).await,
+            vec![(
+                ExecutionProcessType::SetupScript,
+                ExecutionProcessStatus::Failed
+            )]
+        );
+        let setup = ExecutionProcess::find_by_task_attempt_id(scenario.pool(), attempt.id)
+            .await
+            .unwrap()
+            .remove(0);
+        let entries = normalize_process_logs(scenario.pool(), &setup)
+            .await
+            .entries;
+        assert!(entries.iter().any(|e| {
+            matches!(e.entry_type, NormalizedEntryType::SystemMessage) && e.content == "installing"
+        }));
+        assert!(entries.iter().any(|e| {
+            matches!(e.entry_type, NormalizedEntryType::ErrorMessage)
+                && e.content.contains("exited with code 3")
+        }));
+        scenario
+            .assert_board(&[("Broken setup", TaskStatus::InReview)])
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_failed_verification_labels_task_until_it_passes() {
+        use crate::{
+            executor::{NormalizedEntryType, UsageMetrics},
+            models::{execution_metrics::ExecutionMetrics, task_label::TaskLabels},
+            routes::task_attempts::normalize_process_logs,
+            services::verification::FOLLOW_UP_LABEL,
+        };
+
+        let scenario = Scenario::with_scripts(ProjectScripts {
+            verification: Some("# checks\ntest -f out.txt\n\ntest -f fixed.txt".to_string()),
+            ..ProjectScripts::default()
+        })
+        .await;
+        let task = scenario
+            .add_task("Verified", &edit_script("out.txt", "done\n", 0))
+            .await;
+        let (scenario, task_id) = (&scenario, task.id);
+        let run_verified = |attempt: TaskAttempt| async move {
+            scenario.run_until_idle(&[&attempt]).await;
+            let agent = ExecutionProcess::find_by_task_attempt_id(scenario.pool(), attempt.id)
+                .await
+                .unwrap()
+                .into_iter()
+                .find(|p| p.process_type == ExecutionProcessType::CodingAgent)
+                .unwrap();
+            let conversation = normalize_process_logs(scenario.pool(), &agent).await;
+            let outcomes: Vec<(String, bool)> = conversation
+                .entries
+                .into_iter()
+                .filter_map(|entry| match entry.entry_type {
+                    NormalizedEntryType::Verification {
+                        command, passed, ..
+                    } => Some((command, passed)),
+                    _ => None,
+                })
+                .collect();
+            let labels = TaskLabels::find_by_task_id(scenario.pool(), task_id)
+                .await
+                .unwrap()
+                .labels;
+            (outcomes, labels)
+        };
+
+        let first = scenario.start_attempt(&task).await;
+        let (outcomes, labels) = run_verified(first.clone()).await;
+        assert_eq!(
+            outcomes,
+            vec![
+                ("test -f out.txt".to_string(), true),
+                ("test -f fixed.txt".to_string(), false),
+            ]
+        );
+        assert_eq!(labels, vec![FOLLOW_UP_LABEL.to_string()]);
+        scenario
+            .assert_board(&[("Verified", TaskStatus::InReview)])
+            .await;
+
+        let fixed = MockScript {
+            steps: vec![
+                MockStep::FileEdit {
+                    path: "out.txt".to_string(),
+                    content: "done\n".to_string(),
+                },
+                MockStep::FileEdit {
+                    path: "fixed.txt".to_string(),
+                    content: "yes\n".to_string(),
+                },
+            ],
+            exit_code: 0,
+        };
+        scenario.set_script(&task, &fixed).await;
+        let second = scenario.start_attempt(&task).await;
+        let (outcomes, labels) = run_verified(second.clone()).await;
+        assert!(outcomes.iter().all(|(_, passed)| *passed));
+        assert_eq!(outcomes.len(), 2);
+        assert!(labels.is_empty(), "a passing run drops the follow-up label");
+
+        // Comparing the two tries puts their verification results and cost side by side
+        let agent = ExecutionProcess::find_by_task_attempt_id(scenario.pool(), second.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|p| p.process_type == ExecutionProcessType::CodingAgent)
+            .unwrap();
+        let usage = UsageMetrics {
+            input_tokens: 1200,
+            output_tokens: 300,
+            cost_usd: Some(0.04),
+        };
+        ExecutionMetrics::record(scenario.pool(), agent.id, second.id, &usage)
+            .await
+            .unwrap();
+        let comparison = TaskAttempt::compare(
+            scenario.pool(),
+            first.id,
+            second.id,
+            task.id,
+            scenario.project.id,
+        )
+        .await
+        .unwrap();
+        assert_eq!(comparison.left.verification_passed, Some(false));
+        assert_eq!(comparison.right.verification_passed, Some(true));
+        assert_eq!(comparison.right.verifications.len(), 2);
+        assert_eq!(
+            (comparison.left.input_tokens, comparison.left.cost_usd),
+            (0, None)
+        );
+        assert_eq!(
+            (
+                comparison.right.input_tokens,
+                comparison.right.output_tokens,
+                comparison.right.cost_usd
+            ),
+            (1200, 300, Some(0.04))
+        );
+        assert_eq!(comparison.changes.only_right, vec!["fixed.txt".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_verification_is_fixed_automatically() {
+        use crate::models::{
+            executor_session::ExecutorSession, verification_fix_loop::VerificationFixLoop,
+        };
+
+        // Fails the first time it runs in a worktree and passes after that
+        let scenario = Scenario::with_scripts(ProjectScripts {
+            verification: Some("test -f .verified || { touch .verified; exit 1; }".to_string()),
+            ..ProjectScripts::default()
+        })
+        .await;
+        scenario
+            .app_state
+            .get_config()
+            .write()
+            .await
+            .verification
+            .auto_fix_attempts = 2;
+        let task = scenario
+            .add_task("Flaky", &edit_script("out.txt", "done\n", 0))
+            .await;
+
+        let attempt = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&attempt]).await;
+
+        let agents: Vec<_> = ExecutionProcess::find_by_task_attempt_id(scenario.pool(), attempt.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|p| p.process_type == ExecutionProcessType::CodingAgent)
+            .collect();
+        assert_eq!(agents.len(), 2, "one automatic fix, after which it passed");
+        let fix = ExecutorSession::find_by_execution_process_id(scenario.pool(), agents[1].id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(fix.prompt.unwrap().contains("exit code 1"));
+        assert!(
+            VerificationFixLoop::find_by_task_attempt_id(scenario.pool(), attempt.id)
+                .await
+                .unwrap()
+                .is_none(),
+            "passing clears the loop"
+        );
+        scenario
+            .assert_board(&[("Flaky", TaskStatus::InReview)])
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_automatic_fixes_stop_at_the_limit_and_resume_after_restart() {
+        use crate::{
+            execution_monitor::resume_pending_fixes,
+            models::verification_fix_loop::VerificationFixLoop,
+        };
+
+        let scenario = Scenario::with_scripts(ProjectScripts {
+            verification: Some("exit 3".to_string()),
+            ..ProjectScripts::default()
+        })
+        .await;
+        scenario
+            .app_state
+            .get_config()
+            .write()
+            .await
+            .verification
+            .auto_fix_attempts = 1;
+        let task = scenario
+            .add_task("Broken", &edit_script("out.txt", "done\n", 0))
+            .await;
+        let attempt = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&attempt]).await;
+
+        let coding_runs = |processes: Vec<(ExecutionProcessType, ExecutionProcessStatus)>| {
+            processes
+                .into_iter()
+                .filter(|(kind, _)| *kind == ExecutionProcessType::CodingAgent)
+                .count()
+        };
+        assert_eq!(coding_runs(scenario.processes(&attempt).await), 2);
+        let fix_loop = VerificationFixLoop::find_by_task_attempt_id(scenario.pool(), attempt.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(fix_loop.fix_count, 1);
+        assert!(!fix_loop.pending);
+        scenario
+            .assert_board(&[("Broken", TaskStatus::InReview)])
+            .await;
+
+        // A fix that was due when the server stopped is sent once it's back
+        VerificationFixLoop::record_failure(scenario.pool(), attempt.id, "Fix the build")
+            .await
+            .unwrap();
+        resume_pending_fixes(&scenario.app_state).await;
+        scenario.run_until_idle(&[&attempt]).await;
+        assert_eq!(coding_runs(scenario.processes(&attempt).await), 3);
+        let fix_loop = VerificationFixLoop::find_by_task_attempt_id(scenario.pool(), attempt.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(fix_loop.fix_count, 2);
+        assert!(!fix_loop.pending);
+    }
+
+    #[tokio::test]
+    async fn test_release_marks_merged_tasks_released() {
+        use crate::{
+            models::release::{Release, ReleaseStatus},
+            services::releases,
+        };
+
+        let scenario = Scenario::with_scripts(ProjectScripts {
+            release: Some("echo \"publishing $VK_RELEASE_VERSION\"".to_string()),
+            ..ProjectScripts::default()
+        })
+        .await;
+        let shipped = scenario
+            .add_task("Shipped", &edit_script("shipped.txt", "yes\n", 0))
+            .await;
+        let pending = scenario
+            .add_task("Pending", &edit_script("pending.txt", "no\n", 0))
+            .await;
+        let shipped_attempt = scenario.start_attempt(&shipped).await;
+        let pending_attempt = scenario.start_attempt(&pending).await;
+        scenario
+            .run_until_idle(&[&shipped_attempt, &pending_attempt])
+            .await;
+        scenario.merge(&shipped_attempt).await;
+
+        let pool = scenario.pool();
+        let task_ids = releases::unreleased_task_ids(pool, scenario.project.id)
+            .await
+            .unwrap();
+        assert_eq!(task_ids, vec![shipped.id]);
+        let release = Release::create(pool, scenario.project.id, "v1.0.0", None, &task_ids)
+            .await
+            .unwrap();
+        releases::start(pool.clone(), &scenario.project, &release)
+            .await
+            .unwrap();
+
+        let deadline = tokio::time::Instant::now() + IDLE_TIMEOUT;
+        let release = loop {
+            let release = Release::find_by_id(pool, release.id)
+                .await
+                .unwrap()
+                .unwrap();
+            if release.status != ReleaseStatus::Running {
+                break release;
+            }
+            assert!(tokio::time::Instant::now() < deadline);
+            tokio::time::sleep(POLL_INTERVAL).await;
+        };
+
+        assert_eq!(release.status, ReleaseStatus::Released);
+        assert!(release.released_at.is_some());
+        let output = Release::find_output(pool, release.id).await.unwrap();
+        assert_eq!(output.stdout.as_deref(), Some("publishing v1.0.0\n"));
+        assert!(releases::unreleased_task_ids(pool, scenario.project.id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_task_from_template_gets_its_checklist_and_verification() {
+        use std::collections::HashMap;
+
+        use crate::models::{
+            execution_verification::ExecutionVerification,
+            task_checklist::TaskChecklist,
+            task_template::{CreateTaskTemplate, InstantiateTaskTemplate, TaskTemplate},
+        };
+
+        let scenario = Scenario::new().await;
+        let template = TaskTemplate::create(
+            scenario.pool(),
+            &CreateTaskTemplate {
+                project_id: Some(scenario.project.id),
+                title: "Fix {{component}}".to_string(),
+                description: Some(script_description(&edit_script("out.txt", "done\n", 0))),
+                template_name: "Bug fix".to_string(),
+                default_executor: Some(ExecutorConfig::Mock.to_string()),
+                verification_script: Some("test -f out.txt".to_string()),
+                checklist: Some("Reproduce it\n\n  Add a test  \n".to_string()),
+            },
+        )
+        .await
+        .unwrap();
+        let mut request = InstantiateTaskTemplate {
+            project_id: scenario.project.id,
+            variables: HashMap::new(),
+            parent_task_attempt: None,
+        };
+        assert_eq!(
+            template.missing_variables(&request.variables),
+            vec!["component"]
+        );
+
+        request
+            .variables
+            .insert("component".to_string(), "login".to_string());
+        let task = template
+            .instantiate(scenario.pool(), &request, Uuid::new_v4())
+            .await
+            .unwrap();
+        assert_eq!(task.title, "Fix login");
+
+        let checklist = TaskChecklist::find_by_task_id(scenario.pool(), task.id)
+            .await
+            .unwrap();
+        let items: Vec<_> = checklist.items.iter().map(|i| i.content.as_str()).collect();
+        assert_eq!(items, vec!["Reproduce it", "Add a test"]);
+        let ticked = checklist.items[1].id;
+        assert!(
+            TaskChecklist::set_completed(scenario.pool(), task.id, ticked, true)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !TaskChecklist::set_completed(scenario.pool(), Uuid::new_v4(), ticked, false)
+                .await
+                .unwrap()
+        );
+        let checklist = TaskChecklist::find_by_task_id(scenario.pool(), task.id)
+            .await
+            .unwrap();
+        assert_eq!((checklist.completed, checklist.total), (1, 2));
+
+        // The project has no verification commands; the template's run instead
+        let attempt = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&attempt]).await;
+        let agent = ExecutionProcess::find_by_task_attempt_id(scenario.pool(), attempt.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|p| p.process_type == ExecutionProcessType::CodingAgent)
+            .unwrap();
+        let verifications =
+            ExecutionVerification::find_by_execution_process_id(scenario.pool(), agent.id)
+                .await
+                .unwrap();
+        assert_eq!(verifications.len(), 1);
+        assert_eq!(verifications[0].command, "test -f out.txt");
+        assert!(verifications[0].passed);
+    }
+
+    #[tokio::test]
+    async fn test_due_schedule_runs_a_copy_of_the_task() {
+        use chrono::{Duration, SubsecRound, Utc};
+
+        use crate::{
+            models::task_schedule::{
+                TaskSchedule, TaskScheduleRun, TaskScheduleRunStatus, UpsertTaskSchedule,
+            },
+            services::TaskSchedulerService,
+        };
+
+        let scenario = Scenario::new().await;
+        let task = scenario
+            .add_task("Update deps", &edit_script("deps.txt", "bumped\n", 0))
+            .await;
+        // PostgreSQL keeps microseconds
+        let now = Utc::now().trunc_subsecs(6);
+        let schedule = TaskSchedule::upsert(
+            scenario.pool(),
+            task.id,
+            &UpsertTaskSchedule {
+                cron_expression: "@nightly".to_string(),
+                executor: Some(ExecutorConfig::Mock.to_string()),
+                enabled: None,
+            },
+            Some(now + Duration::hours(1)),
+        )
+        .await
+        .unwrap();
+        let scheduler = TaskSchedulerService::new(scenario.app_state.clone());
+        assert!(scheduler.run_due(now).await.unwrap().is_empty());
+
+        let due = now + Duration::hours(2);
+        let runs = scheduler.run_due(due).await.unwrap();
+        assert_eq!(runs.len(), 1);
+        // Not due again until the next night
+        assert!(scheduler.run_due(due).await.unwrap().is_empty());
+        let schedule = TaskSchedule::find_by_task_id(scenario.pool(), schedule.task_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(schedule.last_run_at, Some(due));
+        assert!(schedule.next_run_at.is_some_and(|next| next > due));
+
+        let attempt = TaskAttempt::find_by_id(scenario.pool(), runs[0].task_attempt_id.unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        scenario
+            .worktrees
+            .lock()
+            .unwrap()
+            .push(attempt.worktree_path.clone());
+        scenario.run_until_idle(&[&attempt]).await;
+
+        let history = TaskScheduleRun::find_by_schedule_id(scenario.pool(), schedule.id)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].status, TaskScheduleRunStatus::Succeeded);
+        assert!(history[0].finished_at.is_some());
+
+        let copies = Task::find_subtasks(scenario.pool(), task.id).await.unwrap();
+        assert_eq!(copies.len(), 1);
+        assert_eq!(Some(copies[0].id), history[0].task_id);
+        assert_eq!(attempt.task_id, copies[0].id);
+        assert_eq!(copies[0].status, TaskStatus::InReview);
+        let task = Task::find_by_id(scenario.pool(), task.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(task.status, TaskStatus::Todo);
+    }
+
+    #[tokio::test]
+    async fn test_attempt_uses_project_defaults_it_does_not_override() {
+        let scenario = Scenario::new().await;
+        let project = &scenario.project;
+        project.create_branch("release", None).unwrap();
+        Project::update(
+            scenario.pool(),
+            project.id,
+            project.name.clone(),
+            project.git_repo_path.clone(),
+            project.setup_script.clone(),
+            project.dev_script.clone(),
+            project.cleanup_script.clone(),
+            project.commit_message_template.clone(),
+            project.release_script.clone(),
+            project.merge_check_script.clone(),
+            project.verification_script.clone(),
+            Some(ExecutorConfig::Mock.to_string()),
+            Some("release".to_string()),
+        )
+        .await
+        .unwrap();
+
+        let task = scenario
+            .add_task("Defaults", &edit_script("a.txt", "a\n", 0))
+            .await;
+        let mut attempts = Vec::new();
+        for executor in [None, Some(ExecutorConfig::Echo.to_string())] {
+            let attempt = TaskAttempt::create(
+                scenario.pool(),
+                &CreateTaskAttempt {
+                    executor,
+                    base_branch: None,
+                    plan_only: None,
+                    model: None,
+                    executor_profile_id: None,
+                },
+                task.id,
+            )
+            .await
+            .unwrap();
+            scenario
+                .worktrees
+                .lock()
+                .unwrap()
+                .push(attempt.worktree_path.clone());
+            attempts.push(attempt);
+        }
+
+        assert_eq!(attempts[0].base_branch, "release");
+        assert_eq!(
+            attempts[0].executor.as_deref(),
+            Some(ExecutorConfig::Mock.to_string().as_str())
+        );
+        assert_eq!(
+            attempts[1].executor.as_deref(),
+            Some(ExecutorConfig::Echo.to_string().as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dev_server_gets_a_port_and_stops_when_its_task_is_done() {
+        use crate::{
+            execution_monitor::stop_finished_tasks_dev_servers,
+            models::dev_server_port::DevServerPort, services::ProcessService,
+        };
+
+        let scenario = Scenario::with_scripts(ProjectScripts {
+            dev: Some("echo \"$PORT\" > .port; sleep 60".to_string()),
+            ..ProjectScripts::default()
+        })
+        .await;
+        let task = scenario
+            .add_task("Preview", &edit_script("out.txt", "done\n", 0))
+            .await;
+        let attempt = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&attempt]).await;
+
+        ProcessService::start_dev_server_direct(
+            scenario.pool(),
+            &scenario.app_state,
+            attempt.id,
+            task.id,
+            scenario.project.id,
+        )
+        .await
+        .unwrap();
+        let dev_server =
+            DevServerPort::find_running_by_task_attempt_id(scenario.pool(), attempt.id)
+                .await
+                .unwrap()
+                .expect("the dev server was given a port");
+
+        let worktree = TaskAttempt::find_by_id(scenario.pool(), attempt.id)
+            .await
+            .unwrap()
+            .unwrap()
+            .worktree_path;
+        let port_file = std::path::Path::new(&worktree).join(".port");
+        let deadline = tokio::time::Instant::now() + IDLE_TIMEOUT;
+        while !port_file.exists() {
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "the dev server never started"
+            );
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+        assert_eq!(
+            std::fs::read_to_string(&port_file).unwrap().trim(),
+            dev_server.port.to_string()
+        );
+
+        Task::update_status(
+            scenario.pool(),
+            task.id,
+            scenario.project.id,
+            TaskStatus::Done,
+        )
+        .await
+        .unwrap();
+        stop_finished_tasks_dev_servers(&scenario.app_state).await;
+
+        assert!(
+            DevServerPort::find_running_by_task_attempt_id(scenario.pool(), attempt.id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(
+            scenario.processes(&attempt).await.last(),
+            Some(&(
+                ExecutionProcessType::DevServer,
+                ExecutionProcessStatus::Killed
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_finds_tasks_and_conversations() {
+        use crate::{
+            models::search_document::{SearchFilters, SearchHitKind},
+            services::search::search,
+        };
+
+        let scenario = Scenario::new().await;
+        let task = scenario
+            .add_task(
+                "Fix billing rounding",
+                &edit_script("payments.rs", "fixed\n", 0),
+            )
+            .await;
+        scenario
+            .add_task("Unrelated", &edit_script("readme.md", "docs\n", 0))
+            .await;
+        let attempt = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&attempt]).await;
+
+        let hits = search(
+            scenario.pool(),
+            "payments.rs",
+            &SearchFilters::default(),
+            10,
+        )
+        .await
+        .unwrap();
+        assert!(!hits.is_empty());
+        assert!(hits.iter().all(|hit| hit.task_id == task.id));
+        assert!(hits
+            .iter()
+            .any(|hit| hit.kind == SearchHitKind::Conversation
+                && hit.attempt_id == Some(attempt.id)
+                && hit.snippet.contains("payments")));
+
+        let hits = search(scenario.pool(), "billing", &SearchFilters::default(), 10)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].kind, SearchHitKind::Task);
+
+        let done_only = SearchFilters {
+            status: Some(TaskStatus::Done),
+            ..SearchFilters::default()
+        };
+        assert!(search(scenario.pool(), "payments.rs", &done_only, 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_transitions_are_recorded_in_a_verifiable_event_log() {
+        use crate::models::event::{Event, EventActor, EventKind};
+
+        let scenario = Scenario::new().await;
+        let task = scenario
+            .add_task("Audited", &edit_script("audit.txt", "done\n", 0))
+            .await;
+        let attempt = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&attempt]).await;
+
+        let events = Event::find_page(scenario.pool(), Some(task.project_id), None, 100)
+            .await
+            .unwrap();
+        let kinds: Vec<EventKind> = events.iter().rev().map(|event| event.kind).collect();
+        assert_eq!(kinds.first(), Some(&EventKind::TaskCreated));
+        assert!(kinds.contains(&EventKind::ExecutionStarted));
+        assert!(kinds.contains(&EventKind::ExecutionCompleted));
+        assert!(events.iter().all(|event| event.actor == EventActor::System));
+
+        let verification = Event::verify_chain(scenario.pool()).await.unwrap();
+        assert!(verification.valid);
+        assert_eq!(verification.checked, events.len() as i64);
+
+        // The log is append-only
+        assert!(db::query!("UPDATE events SET details = '{}'")
+            .execute(scenario.pool())
+            .await
+            .is_err());
+        assert!(db::query!("DELETE FROM events")
+            .execute(scenario.pool())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_finished_attempts_are_posted_to_project_webhooks() {
+        use axum::{body::Bytes, http::HeaderMap, routing::post, Router};
+        use chrono::Utc;
+
+        use crate::{
+            models::{
+                notification_delivery::{
+                    NotificationChannel, NotificationDelivery, NotificationDeliveryStatus,
+                },
+                notification_webhook::{
+                    NotificationWebhook, UpsertNotificationWebhook, WebhookFormat,
+                },
+            },
+            services::{
+                webhook_signing::{DELIVERY_HEADER, SIGNATURE_HEADER},
+                NotificationDeliveryService, WebhookSigner,
+            },
+        };
+
+        let (sender, mut received) = tokio::sync::mpsc::unbounded_channel();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let hook_url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let receiver = Router::new().route(
+            "/hook",
+            post(move |headers: HeaderMap, body: Bytes| {
+                let sender = sender.clone();
+                async move {
+                    let _ = sender.send((headers, body));
+                }
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, receiver).await });
+        // Nothing listens here, so posts to it fail
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let dead_url = format!("http://{}/hook", closed.local_addr().unwrap());
+        drop(closed);
+
+        let scenario = Scenario::new().await;
+        let task = scenario
+            .add_task("Notify me", &edit_script("notify.txt", "done\n", 0))
+            .await;
+        let webhook = |url: &str, attempt_succeeded: bool| UpsertNotificationWebhook {
+            url: url.to_string(),
+            format: WebhookFormat::Slack,
+            enabled: None,
+            attempt_succeeded: Some(attempt_succeeded),
+            attempt_failed: None,
+            approval_needed: None,
+        };
+        for (url, attempt_succeeded) in [(&hook_url, true), (&dead_url, true), (&hook_url, false)] {
+            NotificationWebhook::create(
+                scenario.pool(),
+                task.project_id,
+                &webhook(url, attempt_succeeded),
+            )
+            .await
+            .unwrap();
+        }
+
+        let attempt = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&attempt]).await;
+
+        let now = Utc::now();
+        let service = NotificationDeliveryService::new(scenario.pool().clone());
+        let signer = WebhookSigner::new(vec!["hook-secret".to_string()]);
+        assert_eq!(service.deliver_due(&signer, now).await.unwrap(), 1);
+        let (headers, raw_body) = received.try_recv().unwrap();
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        assert!(signer
+            .verify_timestamped(header(SIGNATURE_HEADER), &raw_body, now.timestamp())
+            .is_ok());
+        assert!(signer
+            .verify_github(header("x-hub-signature-256"), &raw_body)
+            .is_ok());
+        assert!(header(DELIVERY_HEADER).is_some());
+        let body: serde_json::Value = serde_json::from_slice(&raw_body).unwrap();
+        assert!(body["text"]
+            .as_str()
+            .unwrap()
+            .starts_with("*Task Complete: Notify me*"));
+        assert!(received.try_recv().is_err());
+
+        // Desktop notifications are off in the scenario, so only the webhooks are logged
+        let log = NotificationDelivery::find_by_project_id(scenario.pool(), task.project_id, 10)
+            .await
+            .unwrap();
+        assert_eq!(log.len(), 2);
+        assert!(log
+            .iter()
+            .all(|delivery| delivery.channel == NotificationChannel::Webhook));
+        let failed = log
+            .iter()
+            .find(|delivery| delivery.status != NotificationDeliveryStatus::Sent)
+            .unwrap();
+        assert_eq!(failed.status, NotificationDeliveryStatus::Pending);
+        assert_eq!(failed.attempts, 1);
+        assert!(failed.last_error.is_some());
+        assert!(failed.next_attempt_at.is_some_and(|at| at > now));
+
+        // Not due again until its backoff has passed
+        assert_eq!(service.deliver_due(&signer, now).await.unwrap(), 0);
+        assert_eq!(
+            NotificationDelivery::find_due(scenario.pool(), now)
+                .await
+                .unwrap()
+                .len(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_board_updates_are_published_to_the_event_bus() {
+        use tokio::sync::broadcast::error::TryRecvError;
+
+        use crate::event_bus::{self, BoardEvent};
+
+        let mut receiver = event_bus::subscribe();
+        let scenario = Scenario::new().await;
+        let task = scenario
+            .add_task("Broadcast", &edit_script("bus.txt", "done\n", 0))
+            .await;
+        let attempt = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&attempt]).await;
+
+        // Other tests publish into the same bus, so keep only this scenario's updates
+        let process_ids: Vec<Uuid> =
+            ExecutionProcess::find_by_task_attempt_id(scenario.pool(), attempt.id)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|process| process.id)
+                .collect();
+        let mut names = Vec::new();
+        loop {
+            let event = match receiver.try_recv() {
+                Ok(event) => event,
+                Err(TryRecvError::Lagged(_)) => continue,
+                Err(_) => break,
+            };
+            let ours = match &event {
+                BoardEvent::ProcessRunning {
+                    execution_process_id,
+                }
+                | BoardEvent::ProcessStopped {
+                    execution_process_id,
+                } => process_ids.contains(execution_process_id),
+                event => event.project_id() == Some(task.project_id),
+            };
+            if ours {
+                names.push(event.name());
+            }
+        }
+
+        assert_eq!(names.first(), Some(&"task_created"));
+        for name in [
+            "execution_started",
+            "process_running",
+            "process_stopped",
+            "execution_finished",
+            "task_status_changed",
+        ] {
+            assert!(names.contains(&name), "{} missing from {:?}", name, names);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_exported_projects_import_with_fresh_ids() {
+        use crate::{
+            models::task_template::{CreateTaskTemplate, TaskTemplate},
+            services::project_archive::{self, ProjectArchive, ProjectArchiveError},
+        };
+
+        let scenario = Scenario::new().await;
+        let task = scenario
+            .add_task("Archive me", &edit_script("archive.txt", "done\n", 0))
+            .await;
+        let attempt = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&attempt]).await;
+        let subtask = Task::create(
+            scenario.pool(),
+            &CreateTask {
+                project_id: scenario.project.id,
+                title: "Follow-up".to_string(),
+                description: None,
+                parent_task_attempt: None,
+                parent_task_id: Some(task.id),
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        TaskTemplate::create(
+            scenario.pool(),
+            &CreateTaskTemplate {
+                project_id: Some(scenario.project.id),
+                title: "Bug".to_string(),
+                description: None,
+                template_name: "Bug report".to_string(),
+                default_executor: None,
+                verification_script: None,
+                checklist: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let archive = project_archive::export(scenario.pool(), &scenario.project, false)
+            .await
+            .unwrap();
+        assert_eq!(archive.tasks.len(), 2);
+        assert_eq!(archive.templates.len(), 1);
+        let executions = &archive.tasks[0].attempts[0].executions;
+        let agent = executions
+            .iter()
+            .find(|execution| execution.process.process_type == ExecutionProcessType::CodingAgent)
+            .unwrap();
+        assert!(agent.conversation.is_some());
+        assert!(agent.process.stdout.is_none());
+
+        // Through JSON, as it would travel between machines
+        let archive: ProjectArchive =
+            serde_json::from_str(&serde_json::to_string(&archive).unwrap()).unwrap();
+        let result = project_archive::import(scenario.pool(), scenario.project.id, &archive)
+            .await
+            .unwrap();
+        assert_eq!(result.tasks_created, 2);
+        assert_eq!(result.attempts_created, 1);
+        assert_eq!(result.executions_created, executions.len());
+        // The project already has a template by that name
+        assert_eq!(result.templates_created, 0);
+
+        let original_status = Task::find_by_id(scenario.pool(), task.id)
+            .await
+            .unwrap()
+            .unwrap()
+            .status;
+        let tasks = Task::find_by_project_id(scenario.pool(), scenario.project.id)
+            .await
+            .unwrap();
+        assert_eq!(tasks.len(), 4);
+        let imported = tasks
+            .iter()
+            .find(|t| t.title == "Archive me" && t.id != task.id)
+            .unwrap();
+        assert_eq!(imported.status, original_status);
+        let imported_subtask = tasks
+            .iter()
+            .find(|t| t.title == "Follow-up" && t.id != subtask.id)
+            .unwrap();
+        assert_eq!(imported_subtask.parent_task_id, Some(imported.id));
+
+        let attempts = TaskAttempt::find_by_task_id(scenario.pool(), imported.id)
+            .await
+            .unwrap();
+        assert_eq!(attempts.len(), 1);
+        assert_ne!(attempts[0].id, attempt.id);
+        assert!(attempts[0].worktree_deleted);
+        assert_eq!(attempts[0].branch, attempt.branch);
+        let processes = ExecutionProcess::find_by_task_attempt_id(scenario.pool(), attempts[0].id)
+            .await
+            .unwrap();
+        assert_eq!(processes.len(), executions.len());
+        assert!(processes.iter().all(|process| !process.status.is_live()));
+
+        let mut future = archive;
+        future.format_version = project_archive::ARCHIVE_FORMAT_VERSION + 1;
+        assert!(matches!(
+            project_archive::import(scenario.pool(), scenario.project.id, &future).await,
+            Err(ProjectArchiveError::UnsupportedVersion(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_output_appended_in_pieces_reads_back_whole() {
+        use crate::{models::execution_log_chunk::LogStream, services::log_storage};
+
+        let scenario = Scenario::new().await;
+        let task = scenario
+            .add_task("Chatty", &edit_script("chatty.txt", "done\n", 0))
+            .await;
+        let attempt = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&attempt]).await;
+        let agent = ExecutionProcess::find_by_task_attempt_id(scenario.pool(), attempt.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|process| process.process_type == ExecutionProcessType::CodingAgent)
+            .unwrap();
+        let before = agent.stdout.clone().unwrap_or_default();
+
+        for line in ["first\n", "second\n", "third\n"] {
+            ExecutionProcess::append_stdout(scenario.pool(), agent.id, line)
+                .await
+                .unwrap();
+        }
+        let agent = ExecutionProcess::find_by_id(scenario.pool(), agent.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            agent.stdout.as_deref(),
+            Some(format!("{}first\nsecond\nthird\n", before).as_str())
+        );
+        assert!(!log_storage::was_trimmed(agent.id, LogStream::Stdout));
+
+        // A retry starts from empty output
+        ExecutionProcess::restart_for_retry(scenario.pool(), agent.id)
+            .await
+            .unwrap();
+        let agent = ExecutionProcess::find_by_id(scenario.pool(), agent.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(agent.stdout.is_none());
+        assert!(agent.stderr.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_metrics_report_finished_runs_and_the_queue() {
+        let scenario = Scenario::new().await;
+        let task = scenario
+            .add_task("Measured", &edit_script("measured.txt", "done\n", 0))
+            .await;
+        let attempt = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&attempt]).await;
+
+        let metrics = crate::metrics::render(scenario.pool()).await.unwrap();
+        assert!(
+            metrics.contains("vibe_kanban_running_executions{process_type=\"codingagent\"} 0\n")
+        );
+        assert!(metrics.contains("vibe_kanban_queued_attempts 0\n"));
+        let finished_agents = metrics
+            .lines()
+            .find(|line| {
+                line.starts_with(
+                    "vibe_kanban_execution_duration_seconds_count{process_type=\"codingagent\"",
+                )
+            })
+            .unwrap();
+        assert!(finished_agents.ends_with(" 1"), "{}", finished_agents);
+    }
+
+    #[tokio::test]
+    async fn test_project_roles_come_from_membership_or_admin() {
+        use crate::{
+            models::{
+                project_member::{ProjectMember, ProjectRole},
+                user::User,
+            },
+            services::accounts,
+        };
+
+        let scenario = Scenario::new().await;
+        let pool = scenario.pool();
+        let admin = User::create(pool, "admin", None, Some("hash"), None, true)
+            .await
+            .unwrap();
+        let viewer = User::create(pool, "viewer", None, Some("hash"), None, false)
+            .await
+            .unwrap();
+        let outsider = User::create(pool, "outsider", None, Some("hash"), None, false)
+            .await
+            .unwrap();
+        ProjectMember::upsert(pool, scenario.project.id, viewer.id, ProjectRole::Viewer)
+            .await
+            .unwrap();
+
+        let project_id = scenario.project.id;
+        let role = |user: User| async move {
+            accounts::project_role(pool, &user, project_id)
+                .await
+                .unwrap()
+        };
+        let admin_id = admin.id;
+        assert_eq!(role(admin).await, Some(ProjectRole::Admin));
+        assert_eq!(role(viewer.clone()).await, Some(ProjectRole::Viewer));
+        assert_eq!(role(outsider).await, None);
+        assert!(role(viewer).await < Some(ProjectRole::Member));
+
+        // Sessions find their account until they are ended
+        let token = accounts::start_session(pool, admin_id, 1).await.unwrap();
+        let mut headers = axum::http::HeaderMap::new();
+        headers.insert(
+            axum::http::header::AUTHORIZATION,
+            format!("Bearer {}", token).parse().unwrap(),
+        );
+        let signed_in = accounts::current_user(pool, &headers).await.unwrap();
+        assert_eq!(
+            signed_in.map(|user| user.username).as_deref(),
+            Some("admin")
+        );
+        accounts::end_session(pool, &token).await.unwrap();
+        assert!(accounts::current_user(pool, &headers)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_api_tokens_work_until_revoked_and_record_their_uses() {
+        use crate::{
+            models::{
+                api_token::{ApiToken, CreateApiToken, TokenScope},
+                user::User,
+            },
+            services::accounts,
+        };
+
+        let scenario = Scenario::new().await;
+        let pool = scenario.pool();
+        let user = User::create(pool, "ci", None, Some("hash"), None, false)
+            .await
+            .unwrap();
+        let created = accounts::create_api_token(
+            pool,
+            &user,
+            &CreateApiToken {
+                name: "CI".to_string(),
+                scopes: vec![TokenScope::CreateTask],
+                expires_in_days: None,
+            },
+        )
+        .await
+        .unwrap();
+        assert!(accounts::is_api_token(&created.secret));
+        assert!(created.secret.starts_with(&created.token.token_prefix));
+
+        let (token, token_user) = accounts::api_token_user(pool, &created.secret)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(token_user.id, user.id);
+        assert!(token.allows(&[TokenScope::CreateTask]));
+        assert!(!token.allows(&[TokenScope::CreateTask, TokenScope::TriggerExecution]));
+
+        ApiToken::record_use(pool, token.id, "POST", "/api/projects/x/tasks", 200)
+            .await
+            .unwrap();
+        let uses = ApiToken::find_uses(pool, token.id, 10).await.unwrap();
+        assert_eq!(uses.len(), 1);
+        assert_eq!(uses[0].status, 200);
+
+        assert_eq!(ApiToken::revoke(pool, token.id).await.unwrap(), 1);
+        assert!(accounts::api_token_user(pool, &created.secret)
+            .await
+            .unwrap()
+            .is_none());
+        let token = ApiToken::find_by_id(pool, token.id).await.unwrap().unwrap();
+        assert!(token.revoked_at.is_some());
+        assert!(token.last_used_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_sessions_refresh_until_their_limit_and_tokens_rotate() {
+        use crate::{
+            models::{
+                api_token::{CreateApiToken, TokenScope},
+                user::{User, UserSession},
+            },
+            services::accounts,
+        };
+
+        let scenario = Scenario::new().await;
+        let pool = scenario.pool();
+        let user = User::create(pool, "ops", None, Some("hash"), None, false)
+            .await
+            .unwrap();
+        let bearer = |token: &str| {
+            let mut headers = axum::http::HeaderMap::new();
+            headers.insert(
+                axum::http::header::AUTHORIZATION,
+                format!("Bearer {}", token).parse().unwrap(),
+            );
+            headers
+        };
+        let signed_in = |token: String| async move {
+            accounts::current_user(pool, &bearer(&token))
+                .await
+                .unwrap()
+                .is_some()
+        };
+
+        // Refreshing swaps the token; the old one stops working
+        let token = accounts::start_session(pool, user.id, 1).await.unwrap();
+        let refreshed = accounts::refresh_session(pool, &token, 1, 30)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!signed_in(token.clone()).await);
+        assert!(signed_in(refreshed.clone()).await);
+        assert!(accounts::refresh_session(pool, &token, 1, 30)
+            .await
+            .unwrap()
+            .is_none());
+
+        // A session signed in longer ago than the limit can't be refreshed
+        db::query!("UPDATE user_sessions SET created_at = datetime('now', '-31 days')")
+            .execute(pool)
+            .await
+            .unwrap();
+        assert!(accounts::refresh_session(pool, &refreshed, 1, 30)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(signed_in(refreshed.clone()).await, "until it expires");
+
+        // Signing out everywhere ends every session
+        let other = accounts::start_session(pool, user.id, 1).await.unwrap();
+        assert_eq!(
+            UserSession::delete_by_user_id(pool, user.id).await.unwrap(),
+            2
+        );
+        assert!(!signed_in(refreshed).await);
+        assert!(!signed_in(other).await);
+
+        // Rotating a token revokes it and keeps its name, scopes and lifetime
+        let created = accounts::create_api_token(
+            pool,
+            &user,
+            &CreateApiToken {
+                name: "Deploys".to_string(),
+                scopes: vec![TokenScope::ReadLogs],
+                expires_in_days: Some(7),
+            },
+        )
+        .await
+        .unwrap();
+        let rotated = accounts::rotate_api_token(pool, &created.token)
+            .await
+            .unwrap();
+        assert_ne!(rotated.secret, created.secret);
+        assert!(accounts::api_token_user(pool, &created.secret)
+            .await
+            .unwrap()
+            .is_none());
+        let (token, token_user) = accounts::api_token_user(pool, &rotated.secret)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(token_user.id, user.id);
+        assert_eq!(token.name, "Deploys");
+        assert_eq!(token.scopes, vec![TokenScope::ReadLogs]);
+        let lifetime = token.expires_at.unwrap() - token.created_at;
+        assert!((lifetime - chrono::Duration::days(7)).num_minutes().abs() < 1);
+    }
+
+    #[tokio::test]
+    async fn test_two_factor_codes_guard_sign_in_and_work_once() {
+        use std::net::SocketAddr;
+
+        use axum::{
+            extract::{ConnectInfo, State},
+            Json,
+        };
+        use chrono::Utc;
+
+        use crate::{
+            models::{
+                project_member::{ProjectMember, ProjectRole},
+                project_security::{ProjectSecurity, UpsertProjectSecurity},
+                two_factor::TwoFactor,
+                user::User,
+            },
+            routes::users::{login, LoginRequest},
+            services::{accounts, two_factor},
+        };
+
+        let scenario = Scenario::new().await;
+        let pool = scenario.pool();
+        let user = User::create(
+            pool,
+            "sec",
+            None,
+            Some(&accounts::hash_password("correct horse")),
+            None,
+            false,
+        )
+        .await
+        .unwrap();
+        let sso = User::create(pool, "sso", None, None, Some("sub-1"), false)
+            .await
+            .unwrap();
+        let project_id = scenario.project.id;
+        for member in [&user, &sso] {
+            ProjectMember::upsert(pool, project_id, member.id, ProjectRole::Member)
+                .await
+                .unwrap();
+        }
+        assert!(!two_factor::enrollment_required(pool, &user).await.unwrap());
+
+        // Only the project that requires it holds the user back
+        let require = UpsertProjectSecurity {
+            require_two_factor: true,
+        };
+        ProjectSecurity::upsert(pool, project_id, &require)
+            .await
+            .unwrap();
+        assert!(two_factor::enrollment_required(pool, &user).await.unwrap());
+        assert!(two_factor::enrollment_required_for(pool, &user, project_id)
+            .await
+            .unwrap());
+        assert!(
+            !two_factor::enrollment_required_for(pool, &user, Uuid::new_v4())
+                .await
+                .unwrap()
+        );
+        assert!(!two_factor::enrollment_required(pool, &sso).await.unwrap());
+
+        // Enrollment only takes effect once a code from the app confirms it
+        let now = Utc::now();
+        let enrollment = two_factor::start_enrollment(pool, &user).await.unwrap();
+        assert!(enrollment.otpauth_url.contains(&enrollment.secret));
+        assert!(!TwoFactor::is_enabled(pool, user.id).await.unwrap());
+        let wrong = if two_factor::code_for(&enrollment.secret, now) == "000000" {
+            "111111"
+        } else {
+            "000000"
+        };
+        assert!(two_factor::confirm_enrollment(pool, user.id, wrong, now)
+            .await
+            .unwrap()
+            .is_none());
+        let code = two_factor::code_for(&enrollment.secret, now);
+        let recovery = two_factor::confirm_enrollment(pool, user.id, &code, now)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(recovery.codes.len(), 10);
+        assert!(!two_factor::enrollment_required(pool, &user).await.unwrap());
+
+        // Signing in takes the password and a code, which can't be replayed
+        let app_state = &scenario.app_state;
+        let sign_in_with = |password: &'static str, code: Option<String>| async move {
+            let response = login(
+                State(app_state.clone()),
+                ConnectInfo(SocketAddr::from(([192, 0, 2, 1], 50000))),
+                Json(LoginRequest {
+                    username: "sec".to_string(),
+                    password: password.to_string(),
+                    code,
+                }),
+            )
+            .await
+            .unwrap();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()["success"] == true
+        };
+        let sign_in = |code| sign_in_with("correct horse", code);
+        assert!(!sign_in(None).await);
+        assert!(!sign_in(Some(code.clone())).await, "used to enroll");
+        let later = now + chrono::Duration::seconds(30);
+        assert!(two_factor::verify(
+            pool,
+            user.id,
+            &two_factor::code_for(&enrollment.secret, later),
+            later
+        )
+        .await
+        .unwrap());
+
+        // Recovery codes stand in for the app, once each
+        assert!(sign_in(Some(recovery.codes[0].to_uppercase())).await);
+        assert!(!sign_in(Some(recovery.codes[0].clone())).await);
+
+        TwoFactor::delete(pool, user.id).await.unwrap();
+        assert!(sign_in(None).await);
+
+        // Guessing passwords locks the account out for a while, the right one included
+        for _ in 0..5 {
+            assert!(!sign_in_with("wrong horse", None).await);
+        }
+        assert!(!sign_in(None).await);
+    }
+
+    #[tokio::test]
+    async fn test_project_invites_add_members_once_each() {
+        use axum::{
+            extract::{Path, State},
+            http::{header, HeaderMap},
+            Json,
+        };
+
+        use crate::{
+            models::{
+                project_invite::{AcceptProjectInvite, CreateProjectInvite, ProjectInvite},
+                project_member::{ProjectMember, ProjectRole},
+                user::User,
+            },
+            routes::users::accept_invite,
+            services::project_invites,
+        };
+
+        let scenario = Scenario::new().await;
+        let pool = scenario.pool();
+        let project_id = scenario.project.id;
+        let admin = User::create(pool, "lead", None, Some("hash"), None, false)
+            .await
+            .unwrap();
+        ProjectMember::upsert(pool, project_id, admin.id, ProjectRole::Admin)
+            .await
+            .unwrap();
+        let invite = |role| async move {
+            project_invites::create(
+                pool,
+                project_id,
+                Some(admin.id),
+                &CreateProjectInvite {
+                    role,
+                    expires_in_days: None,
+                },
+            )
+            .await
+            .unwrap()
+        };
+
+        // Someone without an account joins by creating one, and is signed in
+        let created = invite(ProjectRole::Member).await;
+        assert!(created.token.starts_with(&created.invite.token_prefix));
+        let accept = |token: String, username: &str| {
+            accept_invite(
+                Path(token),
+                State(scenario.app_state.clone()),
+                HeaderMap::new(),
+                Json(AcceptProjectInvite {
+                    username: Some(username.to_string()),
+                    display_name: None,
+                    password: Some("long enough".to_string()),
+                }),
+            )
+        };
+        let response = accept(created.token.clone(), "newcomer").await.unwrap();
+        assert!(response.headers().contains_key(header::SET_COOKIE));
+        let newcomer = User::find_by_username(pool, "newcomer")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!newcomer.is_admin);
+        assert_eq!(
+            ProjectMember::find_role(pool, project_id, newcomer.id)
+                .await
+                .unwrap(),
+            Some(ProjectRole::Member)
+        );
+
+        // The invite is used up
+        let response = accept(created.token, "second").await.unwrap();
+        assert!(!response.headers().contains_key(header::SET_COOKIE));
+        assert!(User::find_by_username(pool, "second")
+            .await
+            .unwrap()
+            .is_none());
+
+        // Accepting never demotes, and revoked invites can't be accepted
+        let viewer_invite = invite(ProjectRole::Viewer).await;
+        let pending = project_invites::find_pending(pool, &viewer_invite.token)
+            .await
+            .unwrap()
+            .unwrap();
+        let joined = project_invites::accept(pool, &pending, admin.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(joined.role, ProjectRole::Admin);
+
+        let revoked = invite(ProjectRole::Admin).await;
+        assert_eq!(
+            ProjectInvite::revoke(pool, project_id, revoked.invite.id)
+                .await
+                .unwrap(),
+            1
+        );
+        assert!(project_invites::find_pending(pool, &revoked.token)
+            .await
+            .unwrap()
+            .is_none());
+
+        // Nor can invites that expired after they were looked up
+        let expiring = invite(ProjectRole::Admin).await;
+        assert!(!ProjectInvite::accept(
+            pool,
+            expiring.invite.id,
+            admin.id,
+            expiring.invite.expires_at
+        )
+        .await
+        .unwrap());
+        let listed = ProjectInvite::find_by_project_id(pool, project_id)
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 4);
+        assert_eq!(
+            listed
+                .iter()
+                .filter(|invite| invite.accepted_by.is_some())
+                .count(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mentions_reach_project_members_once() {
+        use axum::{
+            extract::{Path, State},
+            Extension, Json,
+        };
+
+        use crate::{
+            models::{
+                mention::{Mention, MentionSource},
+                project_member::{ProjectMember, ProjectRole},
+                review_comment::{CreateReviewComment, ReviewComment, UpdateReviewComment},
+                user::User,
+            },
+            routes::review_comments::{create_review_comment, update_review_comment},
+            services::mentions,
+        };
+
+        let scenario = Scenario::new().await;
+        let pool = scenario.pool();
+        let project_id = scenario.project.id;
+        let task = scenario
+            .add_task("Mentioned", &edit_script("mentioned.txt", "done\n", 0))
+            .await;
+        let attempt = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&attempt]).await;
+
+        let user = |username: &'static str, is_admin| async move {
+            User::create(pool, username, None, Some("hash"), None, is_admin)
+                .await
+                .unwrap()
+        };
+        let author = user("author", false).await;
+        let ada = user("ada", false).await;
+        let carol = user("carol", false).await;
+        let root = user("root", true).await;
+        let outsider = user("outsider", false).await;
+        for member in [&author, &ada, &carol] {
+            ProjectMember::upsert(pool, project_id, member.id, ProjectRole::Member)
+                .await
+                .unwrap();
+        }
+        let unread =
+            |user_id| async move { Mention::find_by_user_id(pool, user_id, true).await.unwrap() };
+
+        // Members and admins are notified; outsiders, unknown names and the author aren't
+        let created = create_review_comment(
+            Extension(task.clone()),
+            Extension(attempt.clone()),
+            State(scenario.app_state.clone()),
+            Some(Extension(author.clone())),
+            Json(CreateReviewComment {
+                execution_process_id: None,
+                file_path: "mentioned.txt".to_string(),
+                line_number: 1,
+                body: "@Ada and @root, please check. cc @outsider @nobody @author".to_string(),
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(serde_json::to_value(&created.0).unwrap()["success"], true);
+        let comment = ReviewComment::find_by_task_attempt_id(pool, attempt.id)
+            .await
+            .unwrap()
+            .remove(0);
+        let mentioned = unread(ada.id).await;
+        assert_eq!(mentioned.len(), 1);
+        assert_eq!(mentioned[0].source, MentionSource::ReviewComment);
+        assert_eq!(mentioned[0].task_title, "Mentioned");
+        assert_eq!(mentioned[0].project_id, project_id);
+        assert_eq!(
+            mentioned[0].mentioned_by_username.as_deref(),
+            Some("author")
+        );
+        assert_eq!(unread(root.id).await.len(), 1);
+        assert!(unread(outsider.id).await.is_empty());
+        assert!(unread(author.id).await.is_empty());
+
+        // An edit only notifies the people it adds
+        let updated = update_review_comment(
+            Extension(task.clone()),
+            Extension(attempt.clone()),
+            State(scenario.app_state.clone()),
+            Some(Extension(author.clone())),
+            Path((project_id, task.id, attempt.id, comment.id)),
+            Json(UpdateReviewComment {
+                body: Some("@ada and @carol, please check".to_string()),
+                resolved: None,
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(serde_json::to_value(&updated.0).unwrap()["success"], true);
+        assert_eq!(unread(ada.id).await.len(), 1);
+        assert_eq!(unread(carol.id).await.len(), 1);
+
+        let notified = mentions::notify(
+            pool,
+            &task,
+            Some(attempt.id),
+            MentionSource::FollowUp,
+            None,
+            "@ada try again",
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(notified, vec![ada.id]);
+        assert_eq!(unread(ada.id).await.len(), 2);
+        Mention::mark_read(pool, ada.id, mentioned[0].id)
+            .await
+            .unwrap();
+        assert_eq!(unread(ada.id).await.len(), 1);
+        Mention::mark_all_read(pool, ada.id).await.unwrap();
+        assert!(unread(ada.id).await.is_empty());
+        assert_eq!(
+            Mention::find_by_user_id(pool, ada.id, false)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_review_decisions_are_kept_and_audited() {
+        use crate::models::{
+            attempt_review::{AttemptReview, ReviewDecision},
+            event::{Event, EventKind},
+        };
+
+        let scenario = Scenario::new().await;
+        let pool = scenario.pool();
+        let task = scenario
+            .add_task("Reviewed", &edit_script("reviewed.txt", "done\n", 0))
+            .await;
+        let attempt = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&attempt]).await;
+        scenario
+            .assert_board(&[("Reviewed", TaskStatus::InReview)])
+            .await;
+
+        AttemptReview::create(
+            pool,
+            attempt.id,
+            ReviewDecision::Rejected,
+            None,
+            Some("Needs tests"),
+        )
+        .await
+        .unwrap();
+        Event::record_for_attempt(
+            pool,
+            attempt.id,
+            None,
+            EventKind::ReviewRejected,
+            serde_json::json!({ "comment": "Needs tests" }),
+        )
+        .await
+        .unwrap();
+        AttemptReview::create(
+            pool,
+            attempt.id,
+            ReviewDecision::Approved,
+            Some("lead"),
+            None,
+        )
+        .await
+        .unwrap();
+        Event::record_for_attempt(
+            pool,
+            attempt.id,
+            None,
+            EventKind::ReviewApproved,
+            serde_json::json!({ "reviewer": "lead" }),
+        )
+        .await
+        .unwrap();
+
+        let reviews = AttemptReview::find_by_task_attempt_id(pool, attempt.id)
+            .await
+            .unwrap();
+        let decisions: Vec<ReviewDecision> = reviews.iter().map(|r| r.decision).collect();
+        assert_eq!(
+            decisions,
+            vec![ReviewDecision::Rejected, ReviewDecision::Approved]
+        );
+        assert_eq!(reviews[1].reviewer.as_deref(), Some("lead"));
+
+        let events = Event::find_page(pool, Some(task.project_id), None, 100)
+            .await
+            .unwrap();
+        let kinds: Vec<EventKind> = events.iter().map(|event| event.kind).collect();
+        assert!(kinds.contains(&EventKind::ReviewRejected));
+        assert!(kinds.contains(&EventKind::ReviewApproved));
+        assert!(Event::verify_chain(pool).await.unwrap().valid);
+    }
+
+    #[tokio::test]
+    async fn test_side_by_side_attempts_keep_the_picked_one() {
+        use crate::models::attempt_group::AttemptGroup;
+
+        let scenario = Scenario::new().await;
+        let pool = scenario.pool();
+        let task = scenario
+            .add_task("Contested", &edit_script("contested.txt", "done\n", 0))
+            .await;
+        let first = scenario.start_attempt(&task).await;
+        let second = scenario.start_attempt(&task).await;
+        let group = AttemptGroup::create(pool, task.id, &[first.id, second.id])
+            .await
+            .unwrap();
+        assert_eq!(group.attempt_ids, vec![first.id, second.id]);
+        assert_eq!(group.winner_attempt_id, None);
+        scenario.run_until_idle(&[&first, &second]).await;
+
+        let group = AttemptGroup::set_winner(pool, group.id, second.id)
+            .await
+            .unwrap();
+        assert_eq!(group.winner_attempt_id, Some(second.id));
+        assert!(group.decided_at.is_some());
+        assert_eq!(group.losers().collect::<Vec<_>>(), vec![first.id]);
+
+        let found = AttemptGroup::find_by_attempt_id(pool, first.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, group.id);
+        let groups = AttemptGroup::find_by_task_id(pool, task.id).await.unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].winner_attempt_id, Some(second.id));
+    }
+
+    #[tokio::test]
+    async fn test_rollback_restores_an_earlier_run_and_keeps_the_later_one() {
+        let scenario = Scenario::new().await;
+        let pool = scenario.pool();
+        let task = scenario
+            .add_task("Regressed", &edit_script("story.txt", "good\n", 0))
+            .await;
+        let attempt = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&attempt]).await;
+
+        scenario
+            .set_script(&task, &edit_script("story.txt", "worse\n", 0))
+            .await;
+        TaskAttempt::start_followup_execution(
+            pool,
+            &scenario.app_state,
+            attempt.id,
+            task.id,
+            scenario.project.id,
+            "Polish it",
+        )
+        .await
+        .unwrap();
+        scenario.run_until_idle(&[&attempt]).await;
+
+        let runs = ExecutionProcess::find_summaries_by_task_attempt_id(pool, attempt.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|p| p.process_type == ExecutionProcessType::CodingAgent)
+            .collect::<Vec<_>>();
+        assert_eq!(runs.len(), 2);
+        let story = || std::fs::read_to_string(format!("{}/story.txt", attempt.worktree_path));
+        assert_eq!(story().unwrap(), "worse\n");
+
+        let rollback =
+            TaskAttempt::rollback_to(pool, attempt.id, task.id, scenario.project.id, runs[0].id)
+                .await
+                .unwrap();
+        assert_eq!(story().unwrap(), "good\n");
+        assert_eq!(
+            Some(rollback.to_commit.as_str()),
+            runs[0].commit_sha.as_deref()
+        );
+        assert_eq!(
+            Some(rollback.from_commit.as_str()),
+            runs[1].commit_sha.as_deref()
+        );
+
+        // The later run is kept, so the attempt can go forward to it again
+        let repo = Repository::open(&scenario.project.git_repo_path).unwrap();
+        let kept = repo.revparse_single(&rollback.backup_ref).unwrap();
+        assert_eq!(kept.id().to_string(), rollback.from_commit);
+        TaskAttempt::rollback_to(pool, attempt.id, task.id, scenario.project.id, runs[1].id)
+            .await
+            .unwrap();
+        assert_eq!(story().unwrap(), "worse\n");
+
+        let rollbacks = AttemptRollback::find_by_task_attempt_id(pool, attempt.id)
+            .await
+            .unwrap();
+        assert_eq!(rollbacks.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_script_hooks_label_follow_up_and_block_merges() {
+        use crate::{
+            models::{
+                script_hook::{ScriptEvent, ScriptHook, UpsertScriptHook},
+                task_label::TaskLabels,
+            },
+            routes::task_attempts::merge_attempt,
+            services::script_hooks,
+        };
+
+        let scenario = Scenario::new().await;
+        let pool = scenario.pool();
+        let project_id = scenario.project.id;
+        let hook = |name: &str, event, script: &str| {
+            let hook = UpsertScriptHook {
+                name: name.to_string(),
+                event,
+                script: script.to_string(),
+                enabled: None,
+            };
+            async move { ScriptHook::upsert(pool, project_id, &hook).await.unwrap() }
+        };
+        // Hooks run in name order, and a failing one doesn't stop those after it
+        hook("a-broken", ScriptEvent::TaskCreated, r#"throw "oops";"#).await;
+        hook(
+            "b-label",
+            ScriptEvent::TaskCreated,
+            r#"if task.title.contains("login") { set_labels(task.labels + ["Auth"]); }"#,
+        )
+        .await;
+        hook(
+            "retry",
+            ScriptEvent::AttemptFinished,
+            r#"if attempt.succeeded && attempt.runs < 2 { enqueue_follow_up("Once more"); }"#,
+        )
+        .await;
+        hook(
+            "freeze",
+            ScriptEvent::BeforeMerge,
+            r#"block_merge("Merges into " + attempt.base_branch + " are frozen");"#,
+        )
+        .await;
+
+        let task = scenario
+            .add_task(
+                "Fix the login page",
+                &edit_script("login.txt", "fixed\n", 0),
+            )
+            .await;
+        script_hooks::task_created(&scenario.app_state, &task).await;
+        assert_eq!(
+            TaskLabels::find_by_task_id(pool, task.id)
+                .await
+                .unwrap()
+                .labels,
+            vec!["auth"]
+        );
+        let hooks = ScriptHook::find_by_project_id(pool, project_id)
+            .await
+            .unwrap();
+        assert!(hooks[0].last_error.as_deref().unwrap().contains("oops"));
+        assert!(hooks[1].last_run_at.is_some() && hooks[1].last_error.is_none());
+
+        // The follow-up's own finish doesn't ask for another
+        let attempt = scenario.start_attempt(&task).await;
+        scenario.run_until_idle(&[&attempt]).await;
+        for _ in 0..2 {
+            script_hooks::attempt_finished(&scenario.app_state, attempt.id, true).await;
+            scenario.run_until_idle(&[&attempt]).await;
+        }
+        let runs = scenario
+            .processes(&attempt)
+            .await
+            .into_iter()
+            .filter(|(process_type, _)| *process_type == ExecutionProcessType::CodingAgent)
+            .count();
+        assert_eq!(runs, 2);
+
+        let task = Task::find_by_id(pool, task.id).await.unwrap().unwrap();
+        let merged = merge_attempt(&scenario.app_state, &scenario.project, &task, &attempt)
+            .await
+            .unwrap();
+        assert_eq!(
+            merged,
+            Err(format!("Merges into {} are frozen", attempt.base_branch))
+        );
+        assert_eq!(scenario.read_base_file("login.txt"), None);
+    }
+
+    #[tokio::test]
+    async fn test_every_documented_operation_is_routed() {
+        use axum::{
+            body::Body,
+            http::{Request, StatusCode},
+            Router,
+        };
+        use tower::ServiceExt;
+
+        use crate::routes::{health, openapi, projects, task_attempts, tasks};
+
+        let scenario = Scenario::new().await;
+        let app_state = scenario.app_state.clone();
+        // The routers main.rs serves these paths from, without the middleware that loads
+        // what they're about, so no handler gets far
+        let router = Router::new()
+            .merge(health::health_router())
+            .merge(projects::projects_base_router())
+            .merge(projects::projects_with_id_router())
+            .merge(tasks::tasks_project_router())
+            .merge(tasks::tasks_with_id_router())
+            .merge(task_attempts::task_attempts_list_router(app_state.clone()))
+            .merge(task_attempts::task_attempts_with_id_router(
+                app_state.clone(),
+            ))
+            .with_state(app_state);
+
+        let doc = openapi::document();
+        let paths = doc["paths"].as_object().unwrap();
+        assert!(!paths.is_empty());
+        for (path, operations) in paths {
+            let uri: String = path
+                .split('/')
+                .map(|segment| {
+                    if segment.starts_with('{') {
+                        Uuid::nil().to_string()
+                    } else {
+                        segment.to_string()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("/");
+            for method in operations.as_object().unwrap().keys() {
+                let request = Request::builder()
+                    .method(method.to_uppercase().as_str())
+                    .uri(&uri)
+                    .body(Body::empty())
+                    .unwrap();
+                let status = router.clone().oneshot(request).await.unwrap().status();
+                assert!(
+                    status != StatusCode::NOT_FOUND && status != StatusCode::METHOD_NOT_ALLOWED,
+                    "{} {} is documented but not routed ({})",
+                    method,
+                    path,
+                    status
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use crate::{
+    db::DbPool,
+    executor::NormalizedEntry,
+    models::{
+        execution_process::ExecutionProcess,
+        project::Project,
+        task::{Task, TaskStatus, TaskWithAttemptStatus},
+        task_attempt::TaskAttempt,
+    },
+    routes::task_attempts::normalize_process_logs,
+};
+
+/// Board columns, in display order
+pub const COLUMNS: [TaskStatus; 7] = [
+    TaskStatus::Todo,
+    TaskStatus::Queued,
+    TaskStatus::InProgress,
+    TaskStatus::PlanReview,
+    TaskStatus::InReview,
+    TaskStatus::Done,
+    TaskStatus::Cancelled,
+];
+
+pub fn column_title(status: &TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Todo => "To Do",
+        TaskStatus::Queued => "Queued",
+        TaskStatus::InProgress => "In Progress",
+        TaskStatus::PlanReview => "Plan Review",
+        TaskStatus::InReview => "In Review",
+        TaskStatus::Done => "Done",
+        TaskStatus::Cancelled => "Cancelled",
+    }
+}
+
+/// Latest attempt of the open task, with its conversation so far
+pub struct AttemptView {
+    pub attempt: TaskAttempt,
+    pub attempt_count: usize,
+    pub running: bool,
+    pub entries: Vec<NormalizedEntry>,
+}
+
+pub struct App {
+    pub projects: Vec<Project>,
+    pub project_index: usize,
+    pub tasks: Vec<TaskWithAttemptStatus>,
+    pub column: usize,
+    /// Selected row in each column
+    pub rows: [usize; COLUMNS.len()],
+    /// Task shown in the detail pane, if open
+    pub detail: Option<Task>,
+    pub attempt: Option<AttemptView>,
+    /// Lines scrolled up from the bottom of the log; 0 follows new output
+    pub log_scroll: u16,
+    pub error: Option<String>,
+    pub should_quit: bool,
+}
+
+impl App {
+    pub fn new() -> Self {
+        Self {
+            projects: Vec::new(),
+            project_index: 0,
+            tasks: Vec::new(),
+            column: 0,
+            rows: [0; COLUMNS.len()],
+            detail: None,
+            attempt: None,
+            log_scroll: 0,
+            error: None,
+            should_quit: false,
+        }
+    }
+
+    pub fn project(&self) -> Option<&Project> {
+        self.projects.get(self.project_index)
+    }
+
+    pub fn column_tasks(&self, column: usize) -> Vec<&TaskWithAttemptStatus> {
+        self.tasks
+            .iter()
+            .filter(|t| t.status == COLUMNS[column])
+            .collect()
+    }
+
+    pub fn selected_task(&self) -> Option<&TaskWithAttemptStatus> {
+        self.column_tasks(self.column)
+            .get(self.rows[self.column])
+            .copied()
+    }
+
+    /// Reload projects, tasks and the open task's latest attempt
+    pub async fn refresh(&mut self, pool: &DbPool) {
+        if let Err(e) = self.load(pool).await {
+            self.error = Some(format!("Failed to load board: {}", e));
+        } else {
+            self.error = None;
+        }
+    }
+
+    async fn load(&mut self, pool: &DbPool) -> Result<(), sqlx::Error> {
+        let selected_project = self.project().map(|p| p.id);
+        self.projects = Project::find_all(pool).await?;
+        self.project_index = selected_project
+            .and_then(|id| self.projects.iter().position(|p| p.id == id))
+            .unwrap_or(0);
+
+        self.tasks = match self.project() {
+            Some(project) => Task::find_by_project_id_with_attempt_status(pool, project.id).await?,
+            None => Vec::new(),
+        };
+        self.clamp_rows();
+
+        if let Some(task) = &self.detail {
+            self.detail = Task::find_by_id(pool, task.id).await?;
+        }
+        self.attempt = match &self.detail {
+            Some(task) => load_attempt_view(pool, task.id).await?,
+            None => None,
+        };
+        Ok(())
+    }
+
+    fn clamp_rows(&mut self) {
+        for column in 0..COLUMNS.len() {
+            let len = self.column_tasks(column).len();
+            self.rows[column] = self.rows[column].min(len.saturating_sub(1));
+        }
+    }
+
+    pub fn move_column(&mut self, delta: isize) {
+        self.column = (self.column as isize + delta).clamp(0, COLUMNS.len() as isize - 1) as usize;
+    }
+
+    pub fn move_row(&mut self, delta: isize) {
+        let len = self.column_tasks(self.column).len();
+        if len == 0 {
+            return;
+        }
+        let row = &mut self.rows[self.column];
+        *row = (*row as isize + delta).clamp(0, len as isize - 1) as usize;
+    }
+
+    pub fn next_project(&mut self) {
+        if !self.projects.is_empty() {
+            self.project_index = (self.project_index + 1) % self.projects.len();
+            self.rows = [0; COLUMNS.len()];
+            self.close_detail();
+        }
+    }
+
+    /// Open the detail pane for the selected task; the next refresh fills it in
+    pub fn open_detail(&mut self) {
+        if let Some(task) = self.selected_task() {
+            self.detail = Some(Task {
+                id: task.id,
+                project_id: task.project_id,
+                title: task.title.clone(),
+                description: task.description.clone(),
+                status: task.status.clone(),
+                parent_task_attempt: task.parent_task_attempt,
+                parent_task_id: task.parent_task_id,
+                timeout_minutes: task.timeout_minutes,
+                priority: task.priority,
+                created_at: task.created_at,
+                updated_at: task.updated_at,
+            });
+            self.attempt = None;
+            self.log_scroll = 0;
+        }
+    }
+
+    pub fn close_detail(&mut self) {
+        self.detail = None;
+        self.attempt = None;
+        self.log_scroll = 0;
+    }
+
+    pub fn scroll_log(&mut self, delta: i32) {
+        self.log_scroll = (self.log_scroll as i32 + delta).max(0) as u16;
+    }
+}
+
+async fn load_attempt_view(
+    pool: &DbPool,
+    task_id: uuid::Uuid,
+) -> Result<Option<AttemptView>, sqlx::Error> {
+    let attempts = TaskAttempt::find_by_task_id(pool, task_id).await?;
+    let attempt_count = attempts.len();
+    let Some(attempt) = attempts.into_iter().max_by_key(|a| a.created_at) else {
+        return Ok(None);
+    };
+
+    let processes = ExecutionProcess::find_by_task_attempt_id(pool, attempt.id).await?;
+    let running = processes.iter().any(|p| p.status.is_live());
+    let mut entries = Vec::new();
+    for process in &processes {
+        entries.extend(normalize_process_logs(pool, process).await.entries);
+    }
+
+    Ok(Some(AttemptView {
+        attempt,
+        attempt_count,
+        running,
+        entries,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn task(title: &str, status: TaskStatus) -> TaskWithAttemptStatus {
+        TaskWithAttemptStatus {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            title: title.to_string(),
+            description: None,
+            status,
+            parent_task_attempt: None,
+            parent_task_id: None,
+            timeout_minutes: None,
+            priority: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            labels: Vec::new(),
+            custom_fields: Default::default(),
+            board_column_id: None,
+            has_in_progress_attempt: false,
+            has_merged_attempt: false,
+            last_attempt_failed: false,
+            verification_failed: false,
+            latest_attempt_executor: None,
+            stale_since: None,
+            queue_position: None,
+            pr_url: None,
+            pr_number: None,
+            pr_status: None,
+            latest_summary: None,
+        }
+    }
+
+    #[test]
+    fn test_navigation_stays_within_columns() {
+        let mut app = App::new();
+        app.tasks = vec![
+            task("a", TaskStatus::Todo),
+            task("b", TaskStatus::Todo),
+            task("c", TaskStatus::Done),
+        ];
+
+        app.move_row(5);
+        assert_eq!(app.selected_task().unwrap().title, "b");
+        app.move_column(-1);
+        assert_eq!(app.column, 0);
+        app.move_column(5);
+        assert_eq!(app.selected_task().unwrap().title, "c");
+        app.move_column(1);
+        assert!(app.selected_task().is_none());
+        app.move_row(1);
+        assert_eq!(app.rows[6], 0);
+
+        app.move_column(-6);
+        app.open_detail();
+        assert_eq!(app.detail.as_ref().unwrap().title, "b");
+    }
+}
@@ -0,0 +1,81 @@
+//! Terminal board for headless installs, started with `vibe-kanban tui`.
+//!
+//! Reads the same database as the server and polls it, so it shows attempts started from the
+//! web UI, the CLI or MCP while they run.
+
+mod app;
+mod ui;
+
+use std::time::Duration;
+
+use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use futures_util::StreamExt;
+
+use self::app::App;
+use crate::db::DbPool;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+pub async fn run(pool: DbPool) -> anyhow::Result<()> {
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &pool).await;
+    ratatui::restore();
+    result
+}
+
+async fn event_loop(terminal: &mut ratatui::DefaultTerminal, pool: &DbPool) -> anyhow::Result<()> {
+    let mut app = App::new();
+    let mut events = EventStream::new();
+    let mut refresh = tokio::time::interval(REFRESH_INTERVAL);
+
+    while !app.should_quit {
+        terminal.draw(|frame| ui::draw(frame, &app))?;
+
+        tokio::select! {
+            _ = refresh.tick() => app.refresh(pool).await,
+            event = events.next() => match event {
+                Some(Ok(Event::Key(key))) if key.kind == KeyEventKind::Press => {
+                    if handle_key(&mut app, key) {
+                        app.refresh(pool).await;
+                        refresh.reset();
+                    }
+                }
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e.into()),
+                None => break,
+            },
+        }
+    }
+    Ok(())
+}
+
+/// Apply a key press; returns true if the board should be reloaded right away
+fn handle_key(app: &mut App, key: KeyEvent) -> bool {
+    if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+        app.should_quit = true;
+        return false;
+    }
+
+    match key.code {
+        KeyCode::Char('q') => app.should_quit = true,
+        KeyCode::Char('r') => return true,
+        KeyCode::Char('p') => {
+            app.next_project();
+            return true;
+        }
+        KeyCode::Esc => app.close_detail(),
+        KeyCode::Enter => {
+            app.open_detail();
+            return true;
+        }
+        KeyCode::Left | KeyCode::Char('h') => app.move_column(-1),
+        KeyCode::Right | KeyCode::Char('l') => app.move_column(1),
+        // With the detail pane open, up/down scroll the log instead of moving the selection
+        KeyCode::Up | KeyCode::Char('k') if app.detail.is_some() => app.scroll_log(1),
+        KeyCode::Down | KeyCode::Char('j') if app.detail.is_some() => app.scroll_log(-1),
+        KeyCode::Up | KeyCode::Char('k') => app.move_row(-1),
+        KeyCode::Down | KeyCode::Char('j') => app.move_row(1),
+        _ => {}
+    }
+    false
+}
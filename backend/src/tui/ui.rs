@@ -0,0 +1,211 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+use super::app::{column_title, App, COLUMNS};
+use crate::executor::{NormalizedEntry, NormalizedEntryType};
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [header, body, footer] = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Min(3),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+    draw_header(frame, app, header);
+
+    if app.detail.is_some() {
+        let [board, detail] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .areas(body);
+        draw_board(frame, app, board);
+        draw_detail(frame, app, detail);
+    } else {
+        draw_board(frame, app, body);
+    }
+
+    let help = if app.detail.is_some() {
+        "esc close · j/k scroll log · q quit"
+    } else {
+        "←/→ column · ↑/↓ task · enter details · p next project · r refresh · q quit"
+    };
+    let footer_line = match &app.error {
+        Some(error) => Line::from(Span::styled(error.as_str(), Style::new().fg(Color::Red))),
+        None => Line::from(Span::styled(help, Style::new().fg(Color::DarkGray))),
+    };
+    frame.render_widget(Paragraph::new(footer_line), footer);
+}
+
+fn draw_header(frame: &mut Frame, app: &App, area: Rect) {
+    let title = match app.project() {
+        Some(project) => format!(
+            " vibe-kanban · {} ({}/{})",
+            project.name,
+            app.project_index + 1,
+            app.projects.len()
+        ),
+        None => " vibe-kanban · no projects yet".to_string(),
+    };
+    frame.render_widget(
+        Paragraph::new(title).style(Style::new().add_modifier(Modifier::BOLD)),
+        area,
+    );
+}
+
+fn draw_board(frame: &mut Frame, app: &App, area: Rect) {
+    let areas = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, COLUMNS.len() as u32); COLUMNS.len()])
+        .split(area);
+
+    for (column, status) in COLUMNS.iter().enumerate() {
+        let tasks = app.column_tasks(column);
+        let focused = column == app.column;
+        let items: Vec<ListItem> = tasks
+            .iter()
+            .map(|task| {
+                let marker = if task.has_in_progress_attempt {
+                    Span::styled("● ", Style::new().fg(Color::Yellow))
+                } else if task.last_attempt_failed {
+                    Span::styled("✗ ", Style::new().fg(Color::Red))
+                } else if task.has_merged_attempt {
+                    Span::styled("✓ ", Style::new().fg(Color::Green))
+                } else {
+                    Span::raw("  ")
+                };
+                ListItem::new(Line::from(vec![marker, Span::raw(task.title.as_str())]))
+            })
+            .collect();
+
+        let border_style = if focused {
+            Style::new().fg(Color::Cyan)
+        } else {
+            Style::new()
+        };
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(border_style)
+                    .title(format!(" {} ({}) ", column_title(status), tasks.len())),
+            )
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+
+        let mut state = ListState::default();
+        if focused && !tasks.is_empty() {
+            state.select(Some(app.rows[column]));
+        }
+        frame.render_stateful_widget(list, areas[column], &mut state);
+    }
+}
+
+fn draw_detail(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(task) = &app.detail else {
+        return;
+    };
+    let [info, log] = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+        .areas(area);
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            task.title.as_str(),
+            Style::new().add_modifier(Modifier::BOLD),
+        )),
+        Line::from(format!("Status: {}", column_title(&task.status))),
+        Line::default(),
+    ];
+    if let Some(description) = &task.description {
+        lines.extend(description.lines().map(|l| Line::from(l.to_string())));
+        lines.push(Line::default());
+    }
+    match &app.attempt {
+        Some(view) => {
+            lines.push(Line::from(format!(
+                "Attempt #{}{}",
+                view.attempt_count,
+                if view.running { " · running" } else { "" }
+            )));
+            lines.push(Line::from(format!(
+                "Executor: {}",
+                view.attempt.executor.as_deref().unwrap_or("default")
+            )));
+            lines.push(Line::from(format!(
+                "Branch: {} → {}",
+                view.attempt.branch, view.attempt.base_branch
+            )));
+            if view.attempt.merge_commit.is_some() {
+                lines.push(Line::from("Merged"));
+            }
+            if let Some(pr_url) = &view.attempt.pr_url {
+                lines.push(Line::from(format!("PR: {}", pr_url)));
+            }
+        }
+        None => lines.push(Line::from("No attempts yet")),
+    }
+    frame.render_widget(
+        Paragraph::new(Text::from(lines))
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::ALL).title(" Task ")),
+        info,
+    );
+
+    let entries = app
+        .attempt
+        .as_ref()
+        .map(|view| view.entries.as_slice())
+        .unwrap_or_default();
+    let log_lines: Vec<Line> = entries.iter().flat_map(entry_lines).collect();
+    // Keep the newest output in view unless the user scrolled up
+    let visible = area.height.saturating_sub(2);
+    let bottom = (log_lines.len() as u16).saturating_sub(visible);
+    let scroll = bottom.saturating_sub(app.log_scroll);
+    frame.render_widget(
+        Paragraph::new(Text::from(log_lines))
+            .scroll((scroll, 0))
+            .block(Block::default().borders(Borders::ALL).title(" Logs ")),
+        log,
+    );
+}
+
+fn entry_lines(entry: &NormalizedEntry) -> Vec<Line<'_>> {
+    let (label, color) = match &entry.entry_type {
+        NormalizedEntryType::UserMessage => ("user".to_string(), Color::Cyan),
+        NormalizedEntryType::AssistantMessage => ("assistant".to_string(), Color::Green),
+        NormalizedEntryType::ToolUse { tool_name, .. } => (tool_name.clone(), Color::Yellow),
+        NormalizedEntryType::SystemMessage => ("system".to_string(), Color::DarkGray),
+        NormalizedEntryType::ErrorMessage => ("error".to_string(), Color::Red),
+        NormalizedEntryType::Thinking => ("thinking".to_string(), Color::Magenta),
+        NormalizedEntryType::Attachment { .. } => ("attachment".to_string(), Color::Blue),
+        NormalizedEntryType::PlanEntry => ("plan".to_string(), Color::LightBlue),
+        NormalizedEntryType::FileEdit { .. } => ("edit".to_string(), Color::Yellow),
+        NormalizedEntryType::Verification { passed: true, .. } => {
+            ("verify".to_string(), Color::Green)
+        }
+        NormalizedEntryType::Verification { passed: false, .. } => {
+            ("verify".to_string(), Color::Red)
+        }
+    };
+    entry
+        .content
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let prefix = if i == 0 {
+                Span::styled(format!("[{}] ", label), Style::new().fg(color))
+            } else {
+                Span::raw("  ")
+            };
+            Line::from(vec![prefix, Span::raw(line)])
+        })
+        .collect()
+}
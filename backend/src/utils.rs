@@ -2,9 +2,16 @@ use std::{env, sync::OnceLock};
 
 use directories::ProjectDirs;
 
+pub mod cron;
+pub mod database;
 pub mod path;
+#[cfg(unix)]
+pub mod pty;
+pub mod repo_lock;
 pub mod shell;
+pub mod terminal;
 pub mod text;
+pub mod tokens;
 pub mod worktree_manager;
 
 const PROJECT_ROOT: &str = env!("CARGO_MANIFEST_DIR");
@@ -68,6 +75,11 @@ pub fn cache_dir() -> std::path::PathBuf {
     proj.cache_dir().to_path_buf()
 }
 
+/// File the running server writes its port to, so local clients like `vk` can find it
+pub fn port_file_path() -> std::path::PathBuf {
+    cache_dir().join("server.port")
+}
+
 /// Get or create cached PowerShell script file
 pub async fn get_powershell_script(
 ) -> Result<std::path::PathBuf, Box<dyn std::error::Error + Send + Sync>> {
@@ -0,0 +1,233 @@
+//! Five-field cron expressions: minute, hour, day of month, month and day of week, evaluated
+//! in UTC. A field takes `*`, a number, a range `a-b`, a step `*/n` or `a-b/n`, or a comma
+//! separated list of those. Days of week run from 0 (Sunday) to 7 (Sunday again). `@hourly`,
+//! `@daily` (also `@nightly` and `@midnight`), `@weekly`, `@monthly` and `@yearly` stand for
+//! the usual expressions.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Timelike, Utc};
+
+/// How far ahead to look for a next run before deciding there is none, e.g. for `0 0 30 2 *`
+const MAX_YEARS_AHEAD: i32 = 5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    /// Cron runs on days matching either day field when both are restricted
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+/// The set of values a field allows, as a bit per value
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("Invalid step in `{}`", field))?;
+                if step == 0 {
+                    return Err(format!("Step of zero in `{}`", field));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else {
+            let value = |text: &str| -> Result<u32, String> {
+                let value: u32 = text
+                    .parse()
+                    .map_err(|_| format!("Invalid value `{}` in `{}`", text, field))?;
+                if value < min || value > max {
+                    return Err(format!(
+                        "`{}` is out of range {}-{} in `{}`",
+                        value, min, max, field
+                    ));
+                }
+                Ok(value)
+            };
+            match range.split_once('-') {
+                Some((start, end)) => (value(start)?, value(end)?),
+                // `a/n` runs from a to the end of the range
+                None if part.contains('/') => (value(range)?, max),
+                None => {
+                    let single = value(range)?;
+                    (single, single)
+                }
+            }
+        };
+        if start > end {
+            return Err(format!(
+                "Range {}-{} runs backwards in `{}`",
+                start, end, field
+            ));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+impl FromStr for CronSchedule {
+    type Err = String;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let expression = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@nightly" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(format!(
+                "Expected 5 fields (minute hour day-of-month month day-of-week), found {}",
+                fields.len()
+            ));
+        };
+
+        let mut days_of_week = parse_field(day_of_week, 0, 7)?;
+        // 7 is Sunday as well as 0
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59)?,
+            hours: parse_field(hour, 0, 23)?,
+            days_of_month: parse_field(day_of_month, 1, 31)?,
+            months: parse_field(month, 1, 12)?,
+            days_of_week,
+            day_of_month_restricted: day_of_month != "*",
+            day_of_week_restricted: day_of_week != "*",
+        })
+    }
+}
+
+impl CronSchedule {
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let day_of_month = self.days_of_month & (1 << date.day()) != 0;
+        let day_of_week = self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+        match (self.day_of_month_restricted, self.day_of_week_restricted) {
+            (true, true) => day_of_month || day_of_week,
+            (true, false) => day_of_month,
+            (false, true) => day_of_week,
+            (false, false) => true,
+        }
+    }
+
+    /// The first time the schedule fires strictly after `after`, or `None` if it never does
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let mut time = start.naive_utc();
+        let last_year = start.year() + MAX_YEARS_AHEAD;
+
+        while time.year() <= last_year {
+            if self.months & (1 << time.month()) == 0 {
+                // First minute of the next month
+                let (year, month) = if time.month() == 12 {
+                    (time.year() + 1, 1)
+                } else {
+                    (time.year(), time.month() + 1)
+                };
+                time = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.matches_day(time.date()) {
+                time = time.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if self.hours & (1 << time.hour()) == 0 {
+                time = time.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if self.minutes & (1 << time.minute()) == 0 {
+                time += Duration::minutes(1);
+                continue;
+            }
+            return Some(Utc.from_utc_datetime(&time));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn next(expression: &str, after: &str) -> Option<DateTime<Utc>> {
+        expression
+            .parse::<CronSchedule>()
+            .unwrap()
+            .next_after(at(after))
+    }
+
+    #[test]
+    fn test_next_after() {
+        assert_eq!(
+            next("@nightly", "2025-08-20T13:45:10Z"),
+            Some(at("2025-08-21T00:00:00Z"))
+        );
+        assert_eq!(
+            next("*/15 9-17 * * 1-5", "2025-08-22T17:50:00Z"),
+            Some(at("2025-08-25T09:00:00Z"))
+        );
+        // Strictly after: a run due now isn't due again until its next slot
+        assert_eq!(
+            next("30 2 * * *", "2025-08-20T02:30:00Z"),
+            Some(at("2025-08-21T02:30:00Z"))
+        );
+        assert_eq!(
+            next("0 0 31 * *", "2025-09-01T00:00:00Z"),
+            Some(at("2025-10-31T00:00:00Z"))
+        );
+        assert_eq!(next("0 0 30 2 *", "2025-01-01T00:00:00Z"), None);
+    }
+
+    #[test]
+    fn test_either_day_field_matches_when_both_are_restricted() {
+        // The 1st of the month, or any Sunday (written as 7)
+        assert_eq!(
+            next("0 12 1 * 7", "2025-08-20T00:00:00Z"),
+            Some(at("2025-08-24T12:00:00Z"))
+        );
+        assert_eq!(
+            next("0 12 1 * 7", "2025-08-31T12:00:00Z"),
+            Some(at("2025-09-01T12:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        for expression in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+        ] {
+            assert!(
+                expression.parse::<CronSchedule>().is_err(),
+                "{:?} should be rejected",
+                expression
+            );
+        }
+    }
+}
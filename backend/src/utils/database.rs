@@ -0,0 +1,165 @@
+use std::{str::FromStr, time::Duration};
+
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqliteSynchronous},
+    PgPool, SqlitePool,
+};
+
+use super::asset_dir;
+use crate::{db::DbPool, models::config::DatabaseConfig};
+
+/// URL of a PostgreSQL database to use instead of the configured one
+pub const DATABASE_URL_ENV: &str = "VIBE_KANBAN_DATABASE_URL";
+
+/// Passphrase for an encrypted database, given directly
+pub const DB_KEY_ENV: &str = "VIBE_KANBAN_DB_KEY";
+/// Path to a file whose first line is the database passphrase
+pub const DB_KEY_FILE_ENV: &str = "VIBE_KANBAN_DB_KEY_FILE";
+/// When set, the passphrase is read from the OS keychain
+pub const DB_KEY_KEYCHAIN_ENV: &str = "VIBE_KANBAN_DB_KEY_FROM_KEYCHAIN";
+
+const KEYCHAIN_SERVICE: &str = "vibe-kanban";
+const KEYCHAIN_ACCOUNT: &str = "db-key";
+
+/// How long a connection waits on another's write lock before giving up with SQLITE_BUSY.
+/// Monitors, queue workers and request handlers all write, so the 5s default is too tight
+/// under load.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub fn database_path() -> std::path::PathBuf {
+    asset_dir().join("db.sqlite")
+}
+
+/// Connect to the app database: PostgreSQL when a URL is set in the environment or the
+/// config, and otherwise the SQLite file in the app's data directory
+pub async fn connect(config: &DatabaseConfig, create_if_missing: bool) -> anyhow::Result<DbPool> {
+    let url = std::env::var(DATABASE_URL_ENV)
+        .ok()
+        .filter(|url| !url.is_empty())
+        .or_else(|| config.url.clone());
+    match url {
+        Some(url) => {
+            if !url.starts_with("postgres://") && !url.starts_with("postgresql://") {
+                anyhow::bail!("The database URL must be a postgres:// one");
+            }
+            tracing::info!("Opening PostgreSQL database");
+            Ok(DbPool::Postgres(PgPool::connect(&url).await?))
+        }
+        None => Ok(DbPool::Sqlite(
+            SqlitePool::connect_with(connect_options(create_if_missing)?).await?,
+        )),
+    }
+}
+
+/// Build connection options for the app database, applying the SQLCipher key if one is provisioned.
+/// The database is kept in WAL mode, so readers don't block the writer or each other.
+pub fn connect_options(create_if_missing: bool) -> anyhow::Result<SqliteConnectOptions> {
+    let database_url = format!("sqlite://{}", database_path().to_string_lossy());
+    let options = SqliteConnectOptions::from_str(&database_url)?
+        .create_if_missing(create_if_missing)
+        .journal_mode(SqliteJournalMode::Wal)
+        // Safe with WAL: a power loss can only drop the last commits, never corrupt the file
+        .synchronous(SqliteSynchronous::Normal)
+        .busy_timeout(BUSY_TIMEOUT);
+
+    match database_key()? {
+        Some(key) => {
+            if !cfg!(feature = "sqlcipher") {
+                anyhow::bail!(
+                    "A database key was provided but this build does not include SQLCipher. \
+                     Rebuild with `--features sqlcipher` or unset {}.",
+                    DB_KEY_ENV
+                );
+            }
+            tracing::info!("Opening encrypted database");
+            // sqlx always issues the key pragma before any other statement
+            Ok(options.pragma("key", quote_pragma_value(&key)))
+        }
+        None => Ok(options),
+    }
+}
+
+/// Resolve the database key from the environment, a key file, or the OS keychain
+pub fn database_key() -> anyhow::Result<Option<String>> {
+    if let Ok(key) = std::env::var(DB_KEY_ENV) {
+        if !key.is_empty() {
+            return Ok(Some(key));
+        }
+    }
+
+    if let Ok(path) = std::env::var(DB_KEY_FILE_ENV) {
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("Failed to read database key file {}: {}", path, e))?;
+        let key = content.lines().next().unwrap_or("").trim().to_string();
+        if key.is_empty() {
+            anyhow::bail!("Database key file {} is empty", path);
+        }
+        return Ok(Some(key));
+    }
+
+    if std::env::var(DB_KEY_KEYCHAIN_ENV).is_ok() {
+        return read_keychain_key().map(Some);
+    }
+
+    Ok(None)
+}
+
+fn read_keychain_key() -> anyhow::Result<String> {
+    let output = if cfg!(target_os = "macos") {
+        std::process::Command::new("security")
+            .args([
+                "find-generic-password",
+                "-s",
+                KEYCHAIN_SERVICE,
+                "-a",
+                KEYCHAIN_ACCOUNT,
+                "-w",
+            ])
+            .output()
+    } else if cfg!(target_os = "linux") {
+        // libsecret (GNOME Keyring / KWallet) via its CLI
+        std::process::Command::new("secret-tool")
+            .args([
+                "lookup",
+                "service",
+                KEYCHAIN_SERVICE,
+                "account",
+                KEYCHAIN_ACCOUNT,
+            ])
+            .output()
+    } else {
+        anyhow::bail!(
+            "Keychain lookup is not supported on this platform; use {} or {}",
+            DB_KEY_ENV,
+            DB_KEY_FILE_ENV
+        );
+    }
+    .map_err(|e| anyhow::anyhow!("Failed to query keychain: {}", e))?;
+
+    let key = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || key.is_empty() {
+        anyhow::bail!(
+            "No database key found in keychain (service '{}', account '{}')",
+            KEYCHAIN_SERVICE,
+            KEYCHAIN_ACCOUNT
+        );
+    }
+    Ok(key)
+}
+
+/// Quote a passphrase as an SQL string literal for `PRAGMA key = ...`
+fn quote_pragma_value(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_pragma_value() {
+        assert_eq!(quote_pragma_value("secret"), "'secret'");
+        assert_eq!(quote_pragma_value("it's"), "'it''s'");
+        assert_eq!(quote_pragma_value("'; DROP"), "'''; DROP'");
+    }
+}
@@ -1,5 +1,40 @@
 use std::path::Path;
 
+/// Whether `path` is an absolute Windows path: one with a drive letter, a UNC share or a
+/// `\\?\` prefix. Agents on Windows report these whatever platform reads their logs.
+fn is_windows_absolute(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    (bytes.len() >= 3
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && matches!(bytes[2], b'\\' | b'/'))
+        || path.starts_with("\\\\")
+}
+
+/// A Windows path with forward slashes, without a `\\?\` prefix or trailing separator
+fn normalize_windows_path(path: &str) -> String {
+    let path = path
+        .strip_prefix(r"\\?\UNC\")
+        .map(|share| format!(r"\\{}", share))
+        .unwrap_or_else(|| path.strip_prefix(r"\\?\").unwrap_or(path).to_string());
+    path.replace('\\', "/").trim_end_matches('/').to_string()
+}
+
+/// `path` relative to `worktree_path` when both are Windows paths, compared the way
+/// Windows does: without regard to case or to which slashes separate them
+fn make_windows_path_relative(path: &str, worktree_path: &str) -> Option<String> {
+    let path = normalize_windows_path(path);
+    let worktree = normalize_windows_path(worktree_path);
+    let prefix = path.get(..worktree.len())?;
+    if !prefix.eq_ignore_ascii_case(&worktree) {
+        return None;
+    }
+    match &path[worktree.len()..] {
+        "" => Some(String::new()),
+        rest => rest.strip_prefix('/').map(str::to_string),
+    }
+}
+
 /// Convert absolute paths to relative paths based on worktree path
 /// This is a robust implementation that handles symlinks and edge cases
 pub fn make_path_relative(path: &str, worktree_path: &str) -> String {
@@ -8,6 +43,13 @@ pub fn make_path_relative(path: &str, worktree_path: &str) -> String {
 
     tracing::debug!("Making path relative: {} -> {}", path, worktree_path);
 
+    // Before the checks below, as off Windows these don't even count as absolute
+    if is_windows_absolute(path) {
+        if let Some(relative) = make_windows_path_relative(path, worktree_path) {
+            return relative;
+        }
+    }
+
     // If path is already relative, return as is
     if path_obj.is_relative() {
         return path.to_string();
@@ -93,4 +135,38 @@ mod tests {
             "/other/path/file.js"
         );
     }
+
+    #[test]
+    fn test_make_windows_path_relative() {
+        let worktree = r"C:\Users\dev\AppData\Local\Temp\vibe-kanban\worktrees\vk-1234";
+        assert_eq!(
+            make_path_relative(&format!(r"{}\src\main.rs", worktree), worktree),
+            "src/main.rs"
+        );
+        // Drive letters and directory names in another case, forward slashes, a verbatim
+        // prefix and a trailing separator on the worktree
+        assert_eq!(
+            make_path_relative(
+                "c:/users/dev/appdata/local/temp/vibe-kanban/worktrees/vk-1234/src/lib.rs",
+                &format!(r"\\?\{}\", worktree)
+            ),
+            "src/lib.rs"
+        );
+        assert_eq!(
+            make_path_relative(
+                r"\\server\share\repo\README.md",
+                r"\\?\UNC\server\share\repo"
+            ),
+            "README.md"
+        );
+        // A sibling directory sharing the worktree's name as a prefix is outside it
+        assert_eq!(
+            make_path_relative(&format!(r"{}-other\main.rs", worktree), worktree),
+            format!(r"{}-other\main.rs", worktree)
+        );
+        assert_eq!(
+            make_path_relative(r"D:\elsewhere\main.rs", worktree),
+            r"D:\elsewhere\main.rs"
+        );
+    }
 }
@@ -0,0 +1,133 @@
+//! Pseudo-terminal support for interactive shells in attempt worktrees
+
+use std::{
+    os::fd::{AsRawFd, OwnedFd},
+    path::Path,
+    process::Stdio,
+};
+
+use nix::pty::{openpty, Winsize};
+use tokio::process::{Child, Command};
+
+/// An interactive shell attached to the slave side of a pty
+pub struct PtyShell {
+    master: OwnedFd,
+    pub child: Child,
+}
+
+fn winsize(cols: u16, rows: u16) -> Winsize {
+    Winsize {
+        ws_row: rows,
+        ws_col: cols,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    }
+}
+
+/// The user's login shell, falling back to bash or sh
+fn default_shell() -> String {
+    std::env::var("SHELL")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| {
+            if Path::new("/bin/bash").exists() {
+                "/bin/bash".to_string()
+            } else {
+                "/bin/sh".to_string()
+            }
+        })
+}
+
+impl PtyShell {
+    /// Start the user's shell in `working_dir` on a fresh pty of the given size
+    pub fn spawn(working_dir: &Path, cols: u16, rows: u16) -> std::io::Result<Self> {
+        let pty = openpty(&winsize(cols, rows), None).map_err(std::io::Error::from)?;
+
+        let mut command = Command::new(default_shell());
+        command
+            .current_dir(working_dir)
+            .env("TERM", "xterm-256color")
+            .stdin(Stdio::from(pty.slave.try_clone()?))
+            .stdout(Stdio::from(pty.slave.try_clone()?))
+            .stderr(Stdio::from(pty.slave))
+            .kill_on_drop(true);
+
+        // SAFETY: only async-signal-safe calls between fork and exec
+        unsafe {
+            command.pre_exec(|| {
+                // New session with the pty as controlling terminal, so job control and ^C work
+                if libc::setsid() == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                if libc::ioctl(0, libc::TIOCSCTTY as _, 0) == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+
+        let child = command.spawn()?;
+        Ok(Self {
+            master: pty.master,
+            child,
+        })
+    }
+
+    /// Handles for reading shell output and writing keystrokes
+    pub fn io(&self) -> std::io::Result<(tokio::fs::File, tokio::fs::File)> {
+        let reader = std::fs::File::from(self.master.try_clone()?);
+        let writer = std::fs::File::from(self.master.try_clone()?);
+        Ok((
+            tokio::fs::File::from_std(reader),
+            tokio::fs::File::from_std(writer),
+        ))
+    }
+
+    pub fn resize(&self, cols: u16, rows: u16) -> std::io::Result<()> {
+        let size = winsize(cols, rows);
+        // SAFETY: TIOCSWINSZ reads a winsize struct that outlives the call
+        if unsafe { libc::ioctl(self.master.as_raw_fd(), libc::TIOCSWINSZ, &size) } == -1 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_shell_runs_in_working_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut shell = PtyShell::spawn(dir.path(), 80, 24).unwrap();
+        shell.resize(120, 40).unwrap();
+        let (mut reader, mut writer) = shell.io().unwrap();
+
+        writer.write_all(b"pwd; exit\n").await.unwrap();
+        writer.flush().await.unwrap();
+
+        let expected = dir.path().canonicalize().unwrap();
+        let mut output = Vec::new();
+        let mut buf = [0u8; 1024];
+        let found = tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            loop {
+                match reader.read(&mut buf).await {
+                    // The master side reports EIO once the shell has exited
+                    Ok(0) | Err(_) => return false,
+                    Ok(n) => output.extend_from_slice(&buf[..n]),
+                }
+                if String::from_utf8_lossy(&output).contains(&*expected.to_string_lossy()) {
+                    return true;
+                }
+            }
+        })
+        .await
+        .unwrap_or(false);
+
+        assert!(found, "output: {}", String::from_utf8_lossy(&output));
+        let _ = shell.child.kill().await;
+    }
+}
@@ -0,0 +1,149 @@
+//! Coordination between attempts that run against the same repository. Worktrees share the
+//! main repository's refs, config, worktree metadata and object database, so writes to any
+//! of those from parallel attempts are serialized here, and fetches are shared: an attempt
+//! that asks for a fetch while another one is running waits for it and reuses the result
+//! instead of fetching again.
+//!
+//! The locks are plain blocking mutexes because git operations run synchronously; async
+//! callers take them inside `spawn_blocking`.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+};
+
+#[derive(Default)]
+struct RepoState {
+    /// Held while the main repository's refs or worktree metadata change
+    operations: Mutex<()>,
+    fetch: Mutex<()>,
+    /// Bumped after every successful fetch
+    fetches: AtomicU64,
+}
+
+lazy_static::lazy_static! {
+    static ref REPOS: Mutex<HashMap<PathBuf, Arc<RepoState>>> = Mutex::new(HashMap::new());
+}
+
+/// One entry per repository, whichever path it is reached by
+fn repo_state(repo_path: &Path) -> Arc<RepoState> {
+    let key = std::fs::canonicalize(repo_path).unwrap_or_else(|_| repo_path.to_path_buf());
+    lock(&REPOS).entry(key).or_default().clone()
+}
+
+/// A panic in one attempt's git operation shouldn't wedge every later one
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Run `f` while no other attempt changes the repository's refs or worktrees
+pub fn with_repo_lock<T>(repo_path: &Path, f: impl FnOnce() -> T) -> T {
+    let state = repo_state(repo_path);
+    let _guard = lock(&state.operations);
+    f()
+}
+
+/// Fetch unless a fetch of the same repository finished while this one waited its turn.
+/// Returns whether `fetch` actually ran.
+pub fn shared_fetch<E>(repo_path: &Path, fetch: impl FnOnce() -> Result<(), E>) -> Result<bool, E> {
+    let state = repo_state(repo_path);
+    let seen = state.fetches.load(Ordering::Acquire);
+    let _guard = lock(&state.fetch);
+    if state.fetches.load(Ordering::Acquire) != seen {
+        return Ok(false);
+    }
+    fetch()?;
+    state.fetches.fetch_add(1, Ordering::AcqRel);
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{atomic::AtomicUsize, Barrier},
+        thread,
+        time::Duration,
+    };
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_concurrent_fetches_are_shared() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().to_path_buf();
+        let runs = Arc::new(AtomicUsize::new(0));
+        let barrier = Arc::new(Barrier::new(4));
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let path = path.clone();
+                let runs = runs.clone();
+                let barrier = barrier.clone();
+                thread::spawn(move || {
+                    barrier.wait();
+                    shared_fetch(&path, || {
+                        runs.fetch_add(1, Ordering::SeqCst);
+                        thread::sleep(Duration::from_millis(300));
+                        Ok::<_, ()>(())
+                    })
+                    .unwrap()
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // Everyone queued behind the first fetch and reused it
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        // A later request fetches again
+        assert!(shared_fetch(&path, || Ok::<_, ()>(())).unwrap());
+    }
+
+    #[test]
+    fn test_failed_fetch_is_not_reused() {
+        let dir = TempDir::new().unwrap();
+        assert!(shared_fetch(dir.path(), || Err::<(), _>("offline")).is_err());
+        assert!(shared_fetch(dir.path(), || Ok::<_, ()>(())).unwrap());
+    }
+
+    #[test]
+    fn test_repo_lock_serializes_by_repository() {
+        let dir = TempDir::new().unwrap();
+        // The same repository reached through a different spelling of its path
+        let other_spelling = dir.path().join(".");
+        let inside = Arc::new(AtomicUsize::new(0));
+        let overlapped = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = [dir.path().to_path_buf(), other_spelling]
+            .into_iter()
+            .cycle()
+            .take(6)
+            .map(|path| {
+                let inside = inside.clone();
+                let overlapped = overlapped.clone();
+                thread::spawn(move || {
+                    with_repo_lock(&path, || {
+                        if inside.fetch_add(1, Ordering::SeqCst) > 0 {
+                            overlapped.fetch_add(1, Ordering::SeqCst);
+                        }
+                        thread::sleep(Duration::from_millis(20));
+                        inside.fetch_sub(1, Ordering::SeqCst);
+                    })
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(overlapped.load(Ordering::SeqCst), 0);
+    }
+}
@@ -1,19 +1,402 @@
-//! Cross-platform shell command utilities
+//! Cross-platform shell command utilities, and the environment the user's shell would give
+//! executor commands
+
+use std::{io::IsTerminal, path::Path, process::Stdio, sync::OnceLock, time::Duration};
+
+use tokio::{process::Command, sync::OnceCell};
+
+/// The shell commands are run through
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Sh,
+    Cmd,
+    /// Windows PowerShell
+    Powershell,
+    /// PowerShell 7 and later
+    Pwsh,
+}
+
+/// Set to `bash`, `sh`, `cmd`, `powershell` or `pwsh` to run commands through that shell
+/// instead of the platform's default
+const SHELL_OVERRIDE_VAR: &str = "VIBE_KANBAN_SHELL";
+
+static SHELL: OnceLock<Shell> = OnceLock::new();
+
+impl Shell {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "bash" => Some(Shell::Bash),
+            "sh" => Some(Shell::Sh),
+            "cmd" | "cmd.exe" => Some(Shell::Cmd),
+            "powershell" | "powershell.exe" => Some(Shell::Powershell),
+            "pwsh" | "pwsh.exe" => Some(Shell::Pwsh),
+            _ => None,
+        }
+    }
+
+    /// The shell a platform runs commands through unless told otherwise: cmd on Windows,
+    /// so `npx` and other `.cmd` shims resolve, and bash, or sh without it, elsewhere
+    fn platform_default() -> Self {
+        if cfg!(windows) {
+            Shell::Cmd
+        } else if std::path::Path::new("/bin/bash").exists() {
+            Shell::Bash
+        } else {
+            Shell::Sh
+        }
+    }
+
+    pub fn program(self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Sh => "sh",
+            Shell::Cmd => "cmd",
+            Shell::Powershell => "powershell",
+            Shell::Pwsh => "pwsh",
+        }
+    }
+
+    /// The argument the command line follows
+    pub fn command_arg(self) -> &'static str {
+        match self {
+            Shell::Bash | Shell::Sh => "-c",
+            Shell::Cmd => "/C",
+            Shell::Powershell | Shell::Pwsh => "-Command",
+        }
+    }
+
+    /// Quote a value as a single word of this shell's command lines
+    pub fn quote(self, value: &str) -> String {
+        match self {
+            Shell::Bash | Shell::Sh => format!("'{}'", value.replace('\'', "'\\''")),
+            Shell::Powershell | Shell::Pwsh => format!("'{}'", value.replace('\'', "''")),
+            Shell::Cmd => quote_windows_arg(value),
+        }
+    }
+}
+
+/// Quote a value the way Windows programs split their command line back into arguments:
+/// in double quotes, with a quote escaped by a backslash and the backslashes before a quote
+/// doubled. cmd still expands `%VAR%` inside the quotes.
+fn quote_windows_arg(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    let mut backslashes = 0;
+    for c in value.chars() {
+        match c {
+            '\\' => {
+                backslashes += 1;
+                continue;
+            }
+            '"' => quoted.extend(std::iter::repeat_n('\\', backslashes * 2 + 1)),
+            _ => quoted.extend(std::iter::repeat_n('\\', backslashes)),
+        }
+        backslashes = 0;
+        quoted.push(c);
+    }
+    quoted.extend(std::iter::repeat_n('\\', backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+/// The shell commands are run through: `VIBE_KANBAN_SHELL` if it names one, otherwise the
+/// platform's default. Resolved once.
+pub fn current_shell() -> Shell {
+    *SHELL.get_or_init(|| match std::env::var(SHELL_OVERRIDE_VAR) {
+        Ok(name) if !name.trim().is_empty() => Shell::from_name(&name).unwrap_or_else(|| {
+            tracing::warn!(
+                "{} is set to unknown shell {:?}; using the platform's default",
+                SHELL_OVERRIDE_VAR,
+                name
+            );
+            Shell::platform_default()
+        }),
+        _ => Shell::platform_default(),
+    })
+}
 
 /// Returns the appropriate shell command and argument for the current platform.
 ///
 /// Returns (shell_program, shell_arg) where:
-/// - Windows: ("cmd", "/C")
+/// - Windows: ("cmd", "/C"), or PowerShell's ("powershell", "-Command") when configured
 /// - Unix-like: ("sh", "-c") or ("bash", "-c") if available
 pub fn get_shell_command() -> (&'static str, &'static str) {
-    if cfg!(windows) {
-        ("cmd", "/C")
-    } else {
-        // Prefer bash if available, fallback to sh
-        if std::path::Path::new("/bin/bash").exists() {
-            ("bash", "-c")
+    let shell = current_shell();
+    (shell.program(), shell.command_arg())
+}
+
+/// Quote a value as a single word of the current shell's command lines
+pub fn shell_quote(value: &str) -> String {
+    current_shell().quote(value)
+}
+
+/// Printed by the login shell ahead of its environment, so whatever its profile prints
+/// before is skipped
+const ENV_MARKER: &str = "__VIBE_KANBAN_ENV__";
+
+/// How long the login shell gets to print its environment, as a slow profile can hang
+const LOGIN_SHELL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Variables of the login shell's own session rather than of the user's environment
+const SESSION_VARS: &[&str] = &["_", "PWD", "OLDPWD", "SHLVL"];
+
+static LOGIN_ENVIRONMENT: OnceCell<Vec<(String, String)>> = OnceCell::const_new();
+
+/// Whether the server was started from a terminal, whose shell already set up its
+/// environment, rather than from a desktop launcher or a service manager
+pub fn started_from_terminal() -> bool {
+    std::io::stdin().is_terminal()
+}
+
+fn is_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// The variables in `env` output following [`ENV_MARKER`]. A line that doesn't start a
+/// variable continues the value of the one before.
+pub fn parse_env_output(output: &str) -> Vec<(String, String)> {
+    let Some((_, listing)) = output.split_once(ENV_MARKER) else {
+        return Vec::new();
+    };
+    let mut vars: Vec<(String, String)> = Vec::new();
+    for line in listing.lines().skip(1) {
+        match line.split_once('=') {
+            Some((name, value)) if is_env_name(name) => {
+                vars.push((name.to_string(), value.to_string()));
+            }
+            _ => {
+                if let Some((_, value)) = vars.last_mut() {
+                    value.push('\n');
+                    value.push_str(line);
+                }
+            }
+        }
+    }
+    vars.retain(|(name, _)| !SESSION_VARS.contains(&name.as_str()));
+    vars
+}
+
+/// The environment of the user's login shell, as an interactive one sets it up, so version
+/// managers such as nvm or pyenv are on its PATH. Loaded once; empty on Windows, whose
+/// programs get the user's environment however they're started, or if the shell fails.
+pub async fn login_environment() -> &'static [(String, String)] {
+    LOGIN_ENVIRONMENT
+        .get_or_init(|| async {
+            if cfg!(windows) {
+                return Vec::new();
+            }
+            let shell = std::env::var("SHELL")
+                .ok()
+                .filter(|shell| !shell.trim().is_empty())
+                .unwrap_or_else(|| "/bin/sh".to_string());
+            let script = format!("printf '%s\\n' {}; env", ENV_MARKER);
+            let output = Command::new(&shell)
+                .args(["-l", "-i", "-c", &script])
+                .stdin(Stdio::null())
+                .stderr(Stdio::null())
+                .kill_on_drop(true)
+                .output();
+            match tokio::time::timeout(LOGIN_SHELL_TIMEOUT, output).await {
+                Ok(Ok(output)) => {
+                    let vars = parse_env_output(&String::from_utf8_lossy(&output.stdout));
+                    tracing::info!(
+                        "Loaded {} variables from the login environment of {}",
+                        vars.len(),
+                        shell
+                    );
+                    vars
+                }
+                Ok(Err(e)) => {
+                    tracing::warn!("Failed to start login shell {}: {}", shell, e);
+                    Vec::new()
+                }
+                Err(_) => {
+                    tracing::warn!(
+                        "Login shell {} took over {}s to print its environment",
+                        shell,
+                        LOGIN_SHELL_TIMEOUT.as_secs()
+                    );
+                    Vec::new()
+                }
+            }
+        })
+        .await
+}
+
+/// The variables of an env file: `KEY=value` lines, optionally starting with `export`, with
+/// `#` comments. `$NAME` and `${NAME}` expand to a variable set earlier in the file, in
+/// `base`, or in the server's environment, except in single quotes.
+pub fn parse_env_file(contents: &str, base: &[(String, String)]) -> Vec<(String, String)> {
+    let mut vars: Vec<(String, String)> = Vec::new();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((name, value)) = line.split_once('=') else {
+            continue;
+        };
+        let name = name.trim();
+        if !is_env_name(name) {
+            continue;
+        }
+        let value = value.trim();
+        let lookup = |var: &str| {
+            vars.iter()
+                .rev()
+                .chain(base.iter().rev())
+                .find(|(name, _)| name == var)
+                .map(|(_, value)| value.clone())
+                .or_else(|| std::env::var(var).ok())
+        };
+        let value = if let Some(quoted) = value
+            .strip_prefix('\'')
+            .and_then(|value| value.strip_suffix('\''))
+        {
+            quoted.to_string()
+        } else if let Some(quoted) = value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+        {
+            let unescaped = quoted
+                .replace("\\n", "\n")
+                .replace("\\\"", "\"")
+                .replace("\\\\", "\\");
+            expand_vars(&unescaped, lookup)
+        } else {
+            let unquoted = value.split(" #").next().unwrap_or_default().trim_end();
+            expand_vars(unquoted, lookup)
+        };
+        vars.push((name.to_string(), value));
+    }
+    vars
+}
+
+/// Replace `$NAME` and `${NAME}` in `value`; unset variables expand to nothing
+fn expand_vars(value: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let (name, consumed) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            },
+            None => {
+                let end = after
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                (&after[..end], end)
+            }
+        };
+        if is_env_name(name) {
+            expanded.push_str(&lookup(name).unwrap_or_default());
+            rest = &after[consumed..];
         } else {
-            ("sh", "-c")
+            expanded.push('$');
+            rest = after;
         }
     }
+    expanded.push_str(rest);
+    expanded
+}
+
+/// The environment executor commands run here start from: the login shell's, when
+/// `login_shell` is set, with the variables of `env_file` on top. Variables the project
+/// sets go on top of these.
+pub async fn bootstrap_environment(
+    login_shell: bool,
+    env_file: Option<&Path>,
+) -> Vec<(String, String)> {
+    let mut vars = if login_shell {
+        login_environment().await.to_vec()
+    } else {
+        Vec::new()
+    };
+    if let Some(path) = env_file {
+        match tokio::fs::read_to_string(path).await {
+            Ok(contents) => {
+                for (name, value) in parse_env_file(&contents, &vars) {
+                    vars.retain(|(existing, _)| *existing != name);
+                    vars.push((name, value));
+                }
+            }
+            Err(e) => tracing::warn!("Failed to read env file {}: {}", path.display(), e),
+        }
+    }
+    vars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_quote_per_shell() {
+        assert_eq!(Shell::Bash.quote("it's"), r#"'it'\''s'"#);
+        assert_eq!(Shell::Pwsh.quote("it's"), "'it''s'");
+        assert_eq!(Shell::Cmd.quote("plain"), r#""plain""#);
+        assert_eq!(Shell::Cmd.quote(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(
+            Shell::Cmd.quote(r"C:\Program Files\dir\"),
+            r#""C:\Program Files\dir\\""#
+        );
+        assert_eq!(Shell::Cmd.quote(r#"a\"b"#), r#""a\\\"b""#);
+    }
+
+    #[test]
+    fn test_env_output_after_the_marker() {
+        let output = format!(
+            "Welcome!\n{}\nPATH=/home/me/.nvm/bin:/usr/bin\nNOTE=two\nlines\nSHLVL=2\n",
+            ENV_MARKER
+        );
+        assert_eq!(
+            parse_env_output(&output),
+            vec![
+                ("PATH".to_string(), "/home/me/.nvm/bin:/usr/bin".to_string()),
+                ("NOTE".to_string(), "two\nlines".to_string()),
+            ]
+        );
+        assert!(parse_env_output("PATH=/usr/bin").is_empty());
+    }
+
+    #[test]
+    fn test_env_file_expands_and_unquotes() {
+        let base = vec![("PATH".to_string(), "/usr/bin".to_string())];
+        let contents = r#"
+# toolchains
+export CARGO_HOME=/opt/cargo
+PATH="${CARGO_HOME}/bin:$PATH"
+LITERAL='$PATH'
+NAME=value # a comment
+not a variable
+"#;
+        assert_eq!(
+            parse_env_file(contents, &base),
+            vec![
+                ("CARGO_HOME".to_string(), "/opt/cargo".to_string()),
+                ("PATH".to_string(), "/opt/cargo/bin:/usr/bin".to_string()),
+                ("LITERAL".to_string(), "$PATH".to_string()),
+                ("NAME".to_string(), "value".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shell_names() {
+        assert_eq!(Shell::from_name("PowerShell.exe"), Some(Shell::Powershell));
+        assert_eq!(Shell::from_name(" pwsh "), Some(Shell::Pwsh));
+        assert_eq!(Shell::from_name("cmd"), Some(Shell::Cmd));
+        assert_eq!(Shell::from_name("fish"), None);
+        assert_eq!(Shell::Cmd.command_arg(), "/C");
+        assert_eq!(Shell::Powershell.command_arg(), "-Command");
+    }
 }
@@ -0,0 +1,224 @@
+//! Turning raw terminal output into the text a user would have seen. Agent CLIs write for a
+//! terminal even when piped: colors, progress bars redrawn with `\r`, spinners and lines
+//! cleared or rewritten with cursor movements. Normalizers expect plain lines, so output is
+//! rendered here first, onto a grid of lines the way a terminal would.
+
+use std::borrow::Cow;
+
+const ESC: char = '\x1b';
+const BEL: char = '\x07';
+
+/// Spinner frames CLIs draw at the start of a status line
+const SPINNER_FRAMES: &[char] = &[
+    '◐', '◓', '◑', '◒', '◴', '◷', '◶', '◵', '◰', '◳', '◲', '◱', '▖', '▘', '▝', '▗', '✢', '✶', '✸',
+    '✹', '✺', '✻', '✽',
+];
+
+/// `raw` as it would have ended up on screen: escape sequences and control characters are
+/// removed, carriage returns, backspaces and line clears overwrite what they would have, and
+/// a spinner redrawn on successive lines is kept once. Output without any of them is
+/// returned as it is.
+pub fn clean_output(raw: &str) -> Cow<'_, str> {
+    if !raw
+        .chars()
+        .any(|c| (c.is_control() && c != '\n' && c != '\t') || is_spinner_frame(c))
+    {
+        return Cow::Borrowed(raw);
+    }
+    let mut screen = Screen::default();
+    screen.feed(raw);
+    Cow::Owned(collapse_spinners(&screen.render()))
+}
+
+fn is_spinner_frame(c: char) -> bool {
+    ('\u{2800}'..='\u{28ff}').contains(&c) || SPINNER_FRAMES.contains(&c)
+}
+
+/// Lines written so far, and the cursor position in them
+#[derive(Default)]
+struct Screen {
+    lines: Vec<Vec<char>>,
+    row: usize,
+    col: usize,
+}
+
+impl Screen {
+    fn feed(&mut self, raw: &str) {
+        let mut chars = raw.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\n' => {
+                    self.row += 1;
+                    self.col = 0;
+                }
+                '\r' => self.col = 0,
+                '\x08' => self.col = self.col.saturating_sub(1),
+                '\t' => self.write('\t'),
+                ESC => match chars.next() {
+                    Some('[') => {
+                        let mut params = String::new();
+                        for next in chars.by_ref() {
+                            // Parameter and intermediate bytes, up to the final byte
+                            if ('\x20'..='\x3f').contains(&next) {
+                                params.push(next);
+                            } else {
+                                self.control_sequence(&params, next);
+                                break;
+                            }
+                        }
+                    }
+                    // Operating system commands (window titles, hyperlinks) run to BEL or ST
+                    Some(']') => {
+                        while let Some(next) = chars.next() {
+                            if next == BEL || (next == ESC && chars.next_if_eq(&'\\').is_some()) {
+                                break;
+                            }
+                        }
+                    }
+                    // Other escapes are an optional intermediate byte and a final one
+                    Some(next) if ('\x20'..='\x2f').contains(&next) => {
+                        chars.next();
+                    }
+                    _ => {}
+                },
+                c if c.is_control() => {}
+                c => self.write(c),
+            }
+        }
+    }
+
+    /// Applies the cursor and erase sequences that change what ends up on screen; the rest
+    /// (colors, modes) only change how it looks
+    fn control_sequence(&mut self, params: &str, command: char) {
+        let n = params.parse::<usize>().unwrap_or(1).max(1);
+        match command {
+            'A' => self.row = self.row.saturating_sub(n),
+            'B' => self.row += n,
+            'C' => self.col += n,
+            'D' => self.col = self.col.saturating_sub(n),
+            'G' => self.col = n - 1,
+            'K' => {
+                let col = self.col;
+                let line = self.line();
+                match params {
+                    "" | "0" => line.truncate(col),
+                    "2" => line.clear(),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn line(&mut self) -> &mut Vec<char> {
+        if self.lines.len() <= self.row {
+            self.lines.resize_with(self.row + 1, Vec::new);
+        }
+        &mut self.lines[self.row]
+    }
+
+    fn write(&mut self, c: char) {
+        let col = self.col;
+        let line = self.line();
+        if col < line.len() {
+            line[col] = c;
+        } else {
+            line.resize(col, ' ');
+            line.push(c);
+        }
+        self.col += 1;
+    }
+
+    fn render(&self) -> String {
+        // Newlines at the end leave the cursor on rows nothing was written to
+        let rows = self.lines.len().max(self.row + 1);
+        (0..rows)
+            .map(|row| {
+                self.lines
+                    .get(row)
+                    .map(|line| line.iter().collect())
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+/// Drops a spinner line when the next one only differs by the spinner frame
+fn collapse_spinners(text: &str) -> String {
+    let spinner_text = |line: &str| {
+        let mut chars = line.chars();
+        match chars.next() {
+            Some(c) if is_spinner_frame(c) => Some(chars.as_str().trim().to_string()),
+            _ => None,
+        }
+    };
+    let lines: Vec<&str> = text.split('\n').collect();
+    let mut kept = Vec::with_capacity(lines.len());
+    for (i, line) in lines.iter().enumerate() {
+        let redrawn = spinner_text(line).is_some_and(|current| {
+            lines
+                .get(i + 1)
+                .and_then(|next| spinner_text(next))
+                .is_some_and(|next| next == current)
+        });
+        if !redrawn {
+            kept.push(*line);
+        }
+    }
+    kept.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_output_is_untouched() {
+        let json = "{\"type\":\"assistant\",\"text\":\"\\u001b[31m stays escaped\"}\n";
+        assert!(matches!(clean_output(json), Cow::Borrowed(_)));
+        assert_eq!(clean_output("a\tb\n"), "a\tb\n");
+    }
+
+    #[test]
+    fn test_colors_and_titles_are_stripped() {
+        // goose
+        let raw = "\x1b[2m\x1b[1mstarting session\x1b[0m | \x1b[36mprovider:\x1b[0m openai\n\
+                   \x1b]0;goose\x07─── shell | developer ──────\n";
+        assert_eq!(
+            clean_output(raw),
+            "starting session | provider: openai\n─── shell | developer ──────\n"
+        );
+        assert_eq!(
+            clean_output("\x1b]8;;https://x.dev\x1b\\link\x1b]8;;\x1b\\ \x1b(Bdone"),
+            "link done"
+        );
+    }
+
+    #[test]
+    fn test_carriage_returns_overwrite_the_line() {
+        // npm install progress, then a line ending in CRLF
+        let raw = "\r⸨░░░░░░⸩ ⠋ idealTree: timing\r\x1b[K\rup to date in 2s\r\n";
+        assert_eq!(clean_output(raw), "up to date in 2s\n");
+        // A download percentage redrawn in place, last with backspaces
+        assert_eq!(
+            clean_output("Downloading  10%\rDownloading  55%\x08\x08\x0899%"),
+            "Downloading  99%"
+        );
+        assert_eq!(clean_output("\x1b[0m"), "");
+    }
+
+    #[test]
+    fn test_rewritten_lines_and_spinners_are_kept_once() {
+        // ora-style spinner rewriting the line above, as in `npx` installs
+        let raw = "Installing\n⠋ Resolving packages\n\x1b[1A\x1b[2K⠙ Resolving packages\n\
+                   \x1b[1A\x1b[2K✔ Resolved 42 packages\n";
+        assert_eq!(clean_output(raw), "Installing\n✔ Resolved 42 packages\n");
+        // A spinner that printed each frame on a new line
+        let raw = "⠋ Thinking...\n⠙ Thinking...\n⠹ Thinking...\n⠸ Reading src/lib.rs\nDone\n";
+        assert_eq!(
+            clean_output(raw),
+            "⠹ Thinking...\n⠸ Reading src/lib.rs\nDone\n"
+        );
+    }
+}
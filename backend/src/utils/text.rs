@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 use regex::Regex;
 use uuid::Uuid;
 
@@ -22,3 +24,68 @@ pub fn short_uuid(u: &Uuid) -> String {
     let full = u.simple().to_string();
     full.chars().take(4).collect() // grab the first 4 chars
 }
+
+/// Replace each `{{name}}` in `template` with its trimmed value; unknown placeholders are left
+/// as they are
+pub fn fill_placeholders(template: &str, values: &[(&str, String)]) -> String {
+    let mut filled = template.to_string();
+    for (name, value) in values {
+        filled = filled.replace(&format!("{{{{{}}}}}", name), value.trim());
+    }
+    filled
+}
+
+/// File paths mentioned in free text such as a plan or a task description, e.g.
+/// `src/routes/tasks.rs` or README.md. Anything without a directory separator or a file
+/// extension is taken to be prose.
+pub fn file_references(text: &str) -> Vec<String> {
+    let mut paths = BTreeSet::new();
+    for token in text.split(|c: char| c.is_whitespace() || matches!(c, '`' | '(' | ')' | '[' | ']'))
+    {
+        let token = token.trim_matches(|c: char| {
+            matches!(c, '"' | '\'' | '*' | ',' | ';' | ':' | '.' | '!' | '?')
+        });
+        let token = token.strip_prefix("./").unwrap_or(token);
+        // Drop a trailing line reference such as `main.rs:42`
+        let token = token.split(':').next().unwrap_or_default();
+        if token.is_empty() || token.contains("://") || token.starts_with('/') {
+            continue;
+        }
+        let file_name = token.rsplit('/').next().unwrap_or_default();
+        // A one-letter "extension" without a directory is more likely "e.g" than a file
+        let has_extension = file_name.rsplit_once('.').is_some_and(|(stem, ext)| {
+            !stem.is_empty()
+                && ext.starts_with(|c: char| c.is_ascii_alphabetic())
+                && ext.chars().all(|c| c.is_ascii_alphanumeric())
+                && (ext.len() > 1 || token.contains('/'))
+        });
+        let plausible = token
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '/' | '.' | '-' | '_'));
+        if plausible && has_extension {
+            paths.insert(token.to_string());
+        }
+    }
+    paths.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_references() {
+        let plan = "1. Add a `status` column in `backend/migrations/new.sql`\n\
+                    2. Update src/models/task.rs: (see task.rs:42), then README.md.\n\
+                    3. Check https://example.com/docs.html and e.g. the v1.2 notes";
+        assert_eq!(
+            file_references(plan),
+            vec![
+                "README.md",
+                "backend/migrations/new.sql",
+                "src/models/task.rs",
+                "task.rs"
+            ]
+        );
+    }
+}
@@ -7,6 +7,8 @@ use std::{
 use git2::{Error as GitError, Repository, WorktreeAddOptions};
 use tracing::{debug, info, warn};
 
+use crate::utils::repo_lock;
+
 // Global synchronization for worktree creation to prevent race conditions
 lazy_static::lazy_static! {
     static ref WORKTREE_CREATION_LOCKS: Arc<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>> =
@@ -16,6 +18,38 @@ lazy_static::lazy_static! {
 pub struct WorktreeManager;
 
 impl WorktreeManager {
+    /// Directory names for the checkouts of a multi-repository workspace, one per repository
+    /// in order: the repository's own directory name, numbered when two share one
+    pub fn workspace_checkout_names(repo_paths: &[&str]) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for repo_path in repo_paths {
+            let base = Path::new(repo_path)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| "repo".to_string());
+            let mut name = base.clone();
+            let mut suffix = 2;
+            while names.contains(&name) {
+                name = format!("{}-{}", base, suffix);
+                suffix += 1;
+            }
+            names.push(name);
+        }
+        names
+    }
+
+    /// Prepare the workspace of a multi-repository attempt: a worktree of each repository
+    /// (repository path, branch, worktree path), side by side in one directory. Worktrees
+    /// that were cleaned up are recreated from their branch.
+    pub async fn ensure_workspace_exists(
+        checkouts: Vec<(String, String, PathBuf)>,
+    ) -> Result<(), GitError> {
+        for (repo_path, branch_name, worktree_path) in checkouts {
+            Self::ensure_worktree_exists(repo_path, branch_name, worktree_path).await?;
+        }
+        Ok(())
+    }
+
     /// Ensure worktree exists, recreating if necessary with proper synchronization
     /// This is the main entry point for ensuring a worktree exists and prevents race conditions
     pub async fn ensure_worktree_exists(
@@ -217,11 +251,13 @@ impl WorktreeManager {
             Ok(Ok(repo)) => {
                 // Repository exists, perform comprehensive cleanup
                 tokio::task::spawn_blocking(move || {
-                    Self::comprehensive_worktree_cleanup(
-                        &repo,
-                        &worktree_path_owned,
-                        &worktree_name_owned,
-                    )
+                    repo_lock::with_repo_lock(Path::new(&git_repo_path_owned), || {
+                        Self::comprehensive_worktree_cleanup(
+                            &repo,
+                            &worktree_path_owned,
+                            &worktree_name_owned,
+                        )
+                    })
                 })
                 .await
                 .map_err(|e| GitError::from_str(&format!("Task join error: {}", e)))?
@@ -254,100 +290,108 @@ impl WorktreeManager {
         let path_str = path_str.to_string();
 
         tokio::task::spawn_blocking(move || {
-            // Open repository in blocking context
-            let repo = Repository::open(&git_repo_path)
-                .map_err(|e| GitError::from_str(&format!("Failed to open repository: {}", e)))?;
+            // Hold the repository while its worktree metadata changes, so parallel attempts
+            // don't trip over each other's half-written entries
+            let repo_path = PathBuf::from(&git_repo_path);
+            repo_lock::with_repo_lock(&repo_path, || {
+                // Open repository in blocking context
+                let repo = Repository::open(&git_repo_path).map_err(|e| {
+                    GitError::from_str(&format!("Failed to open repository: {}", e))
+                })?;
 
-            // Find the branch reference using the branch name
-            let branch_ref = repo
-                .find_branch(&branch_name, git2::BranchType::Local)
-                .map_err(|e| {
-                    GitError::from_str(&format!("Branch '{}' not found: {}", branch_name, e))
-                })?
-                .into_reference();
-
-            // Create worktree options
-            let mut worktree_opts = WorktreeAddOptions::new();
-            worktree_opts.reference(Some(&branch_ref));
-
-            match repo.worktree(&branch_name, &worktree_path, Some(&worktree_opts)) {
-                Ok(_) => {
-                    // Verify the worktree was actually created
-                    if !worktree_path.exists() {
-                        return Err(GitError::from_str(&format!(
-                            "Worktree creation reported success but path {} does not exist",
-                            path_str
-                        )));
-                    }
+                // Find the branch reference using the branch name
+                let branch_ref = repo
+                    .find_branch(&branch_name, git2::BranchType::Local)
+                    .map_err(|e| {
+                        GitError::from_str(&format!("Branch '{}' not found: {}", branch_name, e))
+                    })?
+                    .into_reference();
+
+                // Create worktree options
+                let mut worktree_opts = WorktreeAddOptions::new();
+                worktree_opts.reference(Some(&branch_ref));
+
+                match repo.worktree(&branch_name, &worktree_path, Some(&worktree_opts)) {
+                    Ok(_) => {
+                        // Verify the worktree was actually created
+                        if !worktree_path.exists() {
+                            return Err(GitError::from_str(&format!(
+                                "Worktree creation reported success but path {} does not exist",
+                                path_str
+                            )));
+                        }
 
-                    info!(
-                        "Successfully created worktree {} at {}",
-                        branch_name, path_str
-                    );
+                        info!(
+                            "Successfully created worktree {} at {}",
+                            branch_name, path_str
+                        );
+
+                        // Fix commondir for Windows/WSL compatibility
+                        if let Err(e) = Self::fix_worktree_commondir_for_windows_wsl(
+                            Path::new(&git_repo_path),
+                            &worktree_name,
+                        ) {
+                            warn!("Failed to fix worktree commondir for Windows/WSL: {}", e);
+                        }
 
-                    // Fix commondir for Windows/WSL compatibility
-                    if let Err(e) = Self::fix_worktree_commondir_for_windows_wsl(
-                        Path::new(&git_repo_path),
-                        &worktree_name,
-                    ) {
-                        warn!("Failed to fix worktree commondir for Windows/WSL: {}", e);
+                        Ok(())
                     }
-
-                    Ok(())
-                }
-                Err(e) if e.code() == git2::ErrorCode::Exists => {
-                    // Handle the specific "directory exists" error for metadata
-                    debug!(
-                        "Worktree metadata directory exists, attempting force cleanup: {}",
-                        e
-                    );
-
-                    // Force cleanup metadata and try one more time
-                    Self::force_cleanup_worktree_metadata(&git_repo_path, &worktree_name).map_err(
-                        |e| {
-                            GitError::from_str(&format!(
-                                "Failed to cleanup worktree metadata: {}",
-                                e
-                            ))
-                        },
-                    )?;
-
-                    // Try again after cleanup
-                    match repo.worktree(&branch_name, &worktree_path, Some(&worktree_opts)) {
-                        Ok(_) => {
-                            if !worktree_path.exists() {
-                                return Err(GitError::from_str(&format!(
+                    Err(e) if e.code() == git2::ErrorCode::Exists => {
+                        // Handle the specific "directory exists" error for metadata
+                        debug!(
+                            "Worktree metadata directory exists, attempting force cleanup: {}",
+                            e
+                        );
+
+                        // Force cleanup metadata and try one more time
+                        Self::force_cleanup_worktree_metadata(&git_repo_path, &worktree_name)
+                            .map_err(|e| {
+                                GitError::from_str(&format!(
+                                    "Failed to cleanup worktree metadata: {}",
+                                    e
+                                ))
+                            })?;
+
+                        // Try again after cleanup
+                        match repo.worktree(&branch_name, &worktree_path, Some(&worktree_opts)) {
+                            Ok(_) => {
+                                if !worktree_path.exists() {
+                                    return Err(GitError::from_str(&format!(
                                     "Worktree creation reported success but path {} does not exist",
                                     path_str
                                 )));
+                                }
+
+                                info!(
+                                    "Successfully created worktree {} at {} after metadata cleanup",
+                                    branch_name, path_str
+                                );
+
+                                // Fix commondir for Windows/WSL compatibility
+                                if let Err(e) = Self::fix_worktree_commondir_for_windows_wsl(
+                                    Path::new(&git_repo_path),
+                                    &worktree_name,
+                                ) {
+                                    warn!(
+                                        "Failed to fix worktree commondir for Windows/WSL: {}",
+                                        e
+                                    );
+                                }
+
+                                Ok(())
                             }
-
-                            info!(
-                                "Successfully created worktree {} at {} after metadata cleanup",
-                                branch_name, path_str
-                            );
-
-                            // Fix commondir for Windows/WSL compatibility
-                            if let Err(e) = Self::fix_worktree_commondir_for_windows_wsl(
-                                Path::new(&git_repo_path),
-                                &worktree_name,
-                            ) {
-                                warn!("Failed to fix worktree commondir for Windows/WSL: {}", e);
+                            Err(retry_error) => {
+                                debug!(
+                                    "Worktree creation failed even after metadata cleanup: {}",
+                                    retry_error
+                                );
+                                Err(retry_error)
                             }
-
-                            Ok(())
-                        }
-                        Err(retry_error) => {
-                            debug!(
-                                "Worktree creation failed even after metadata cleanup: {}",
-                                retry_error
-                            );
-                            Err(retry_error)
                         }
                     }
+                    Err(e) => Err(e),
                 }
-                Err(e) => Err(e),
-            }
+            })
         })
         .await
         .map_err(|e| GitError::from_str(&format!("Task join error: {}", e)))?
@@ -576,3 +620,20 @@ impl WorktreeManager {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspace_checkouts_are_named_after_their_repositories() {
+        assert_eq!(
+            WorktreeManager::workspace_checkout_names(&[
+                "/code/acme/backend",
+                "/code/acme/frontend/",
+                "/code/other/backend",
+            ]),
+            vec!["backend", "frontend", "backend-2"]
+        );
+    }
+}
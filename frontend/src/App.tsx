@@ -1,18 +1,29 @@
-import { useEffect, useState } from 'react';
+import { ReactNode, useCallback, useEffect, useState } from 'react';
 import { BrowserRouter, Route, Routes } from 'react-router-dom';
 import { Navbar } from '@/components/layout/navbar';
 import { Projects } from '@/pages/projects';
 import { ProjectTasks } from '@/pages/project-tasks';
 
 import { Settings } from '@/pages/Settings';
+import { Login } from '@/pages/Login';
+import { SharedTaskPage } from '@/pages/SharedTask';
+import { AcceptInvitePage } from '@/pages/AcceptInvite';
 import { DisclaimerDialog } from '@/components/DisclaimerDialog';
 import { OnboardingDialog } from '@/components/OnboardingDialog';
+import { TwoFactorManager } from '@/components/TwoFactorManager';
 import { ConfigProvider, useConfig } from '@/components/config-provider';
 import { ThemeProvider } from '@/components/theme-provider';
-import type { EditorType, ExecutorConfig } from 'shared/types';
-import { configApi } from '@/lib/api';
+import type { AuthStatus, EditorType, ExecutorConfig } from 'shared/types';
+import { authApi, configApi } from '@/lib/api';
 import * as Sentry from '@sentry/react';
 import { Loader } from '@/components/ui/loader';
+import {
+  Card,
+  CardContent,
+  CardDescription,
+  CardHeader,
+  CardTitle,
+} from '@/components/ui/card';
 
 const SentryRoutes = Sentry.withSentryReactRouterV6Routing(Routes);
 
@@ -132,12 +143,91 @@ function AppContent() {
   );
 }
 
+// With sign-in on, nothing but the sign-in page loads until there is a session
+function AuthGate({ children }: { children: ReactNode }) {
+  const [status, setStatus] = useState<AuthStatus | null>(null);
+  const [loading, setLoading] = useState(true);
+
+  const loadStatus = useCallback(async () => {
+    try {
+      setStatus(await authApi.getStatus());
+    } catch (err) {
+      console.error('Error loading sign-in status:', err);
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    loadStatus();
+  }, [loadStatus]);
+
+  if (loading) {
+    return (
+      <div className="min-h-screen bg-background flex items-center justify-center">
+        <Loader message="Loading..." size={32} />
+      </div>
+    );
+  }
+
+  if (status?.auth_enabled && !status.user) {
+    return <Login status={status} onSignedIn={loadStatus} />;
+  }
+
+  if (status?.two_factor_required) {
+    return (
+      <div className="min-h-screen bg-background flex items-center justify-center">
+        <Card className="w-full max-w-md">
+          <CardHeader>
+            <CardTitle>Set up two-factor authentication</CardTitle>
+            <CardDescription>
+              A project you belong to requires it before you can continue.
+            </CardDescription>
+          </CardHeader>
+          <CardContent>
+            <TwoFactorManager onEnabled={loadStatus} />
+          </CardContent>
+        </Card>
+      </div>
+    );
+  }
+
+  return <>{children}</>;
+}
+
 function App() {
   return (
     <BrowserRouter>
-      <ConfigProvider>
-        <AppContent />
-      </ConfigProvider>
+      <Routes>
+        {/* Share links are for people without an account */}
+        <Route
+          path="/shared/:token"
+          element={
+            <ThemeProvider>
+              <SharedTaskPage />
+            </ThemeProvider>
+          }
+        />
+        {/* Invite links sign in or create an account themselves */}
+        <Route
+          path="/invites/:token"
+          element={
+            <ThemeProvider>
+              <AcceptInvitePage />
+            </ThemeProvider>
+          }
+        />
+        <Route
+          path="*"
+          element={
+            <AuthGate>
+              <ConfigProvider>
+                <AppContent />
+              </ConfigProvider>
+            </AuthGate>
+          }
+        />
+      </Routes>
     </BrowserRouter>
   );
 }
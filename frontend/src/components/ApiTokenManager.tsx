@@ -0,0 +1,223 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Badge } from '@/components/ui/badge';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { Loader2, RefreshCw, Trash2 } from 'lucide-react';
+import { apiTokensApi } from '@/lib/api';
+import type { ApiToken, TokenScope } from 'shared/types';
+
+const SCOPES: { scope: TokenScope; label: string }[] = [
+  { scope: 'create-task', label: 'Create tasks' },
+  { scope: 'read-logs', label: 'Read boards and logs' },
+  { scope: 'trigger-execution', label: 'Start attempts' },
+];
+
+export function ApiTokenManager() {
+  const [tokens, setTokens] = useState<ApiToken[]>([]);
+  const [loading, setLoading] = useState(true);
+  const [error, setError] = useState<string | null>(null);
+  const [name, setName] = useState('');
+  const [scopes, setScopes] = useState<TokenScope[]>(['create-task']);
+  const [expiresInDays, setExpiresInDays] = useState('');
+  const [creating, setCreating] = useState(false);
+  const [secret, setSecret] = useState<string | null>(null);
+
+  const fetchTokens = useCallback(async () => {
+    setLoading(true);
+    try {
+      setTokens(await apiTokensApi.getAll());
+      setError(null);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to load tokens');
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    fetchTokens();
+  }, [fetchTokens]);
+
+  const toggleScope = (scope: TokenScope, checked: boolean) => {
+    setScopes((current) =>
+      checked ? [...current, scope] : current.filter((s) => s !== scope)
+    );
+  };
+
+  const handleCreate = async () => {
+    setCreating(true);
+    setError(null);
+    try {
+      const days = parseInt(expiresInDays);
+      const created = await apiTokensApi.create({
+        name,
+        scopes,
+        expires_in_days: days > 0 ? days : null,
+      });
+      setSecret(created.secret);
+      setName('');
+      await fetchTokens();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to create token');
+    } finally {
+      setCreating(false);
+    }
+  };
+
+  const handleRevoke = async (token: ApiToken) => {
+    if (!confirm(`Revoke "${token.name}"? Scripts using it stop working.`)) {
+      return;
+    }
+    try {
+      await apiTokensApi.revoke(token.id);
+      await fetchTokens();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to revoke token');
+    }
+  };
+
+  const handleRotate = async (token: ApiToken) => {
+    if (
+      !confirm(
+        `Rotate "${token.name}"? The current secret stops working right away.`
+      )
+    ) {
+      return;
+    }
+    try {
+      const rotated = await apiTokensApi.rotate(token.id);
+      setSecret(rotated.secret);
+      await fetchTokens();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to rotate token');
+    }
+  };
+
+  return (
+    <div className="space-y-4">
+      {error && (
+        <Alert variant="destructive">
+          <AlertDescription>{error}</AlertDescription>
+        </Alert>
+      )}
+      {secret && (
+        <Alert>
+          <AlertDescription>
+            Copy the token now; it won't be shown again:{' '}
+            <code className="break-all">{secret}</code>
+          </AlertDescription>
+        </Alert>
+      )}
+
+      {loading ? (
+        <div className="flex justify-center py-4">
+          <Loader2 className="h-5 w-5 animate-spin" />
+        </div>
+      ) : tokens.length === 0 ? (
+        <p className="text-sm text-muted-foreground">No tokens yet.</p>
+      ) : (
+        <div className="space-y-2">
+          {tokens.map((token) => (
+            <div
+              key={token.id}
+              className="flex items-center justify-between rounded border p-2"
+            >
+              <div className="space-y-1">
+                <div className="flex items-center gap-2">
+                  <span className="font-medium">{token.name}</span>
+                  <code className="text-xs text-muted-foreground">
+                    {token.token_prefix}…
+                  </code>
+                  {token.revoked_at && (
+                    <Badge variant="secondary">Revoked</Badge>
+                  )}
+                </div>
+                <p className="text-xs text-muted-foreground">
+                  {token.scopes.join(', ')}
+                  {' · '}
+                  {token.last_used_at
+                    ? `Last used ${new Date(token.last_used_at).toLocaleString()}`
+                    : 'Never used'}
+                </p>
+              </div>
+              {!token.revoked_at && (
+                <div className="flex gap-1">
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    title="Rotate"
+                    onClick={() => handleRotate(token)}
+                  >
+                    <RefreshCw className="h-4 w-4" />
+                  </Button>
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    title="Revoke"
+                    onClick={() => handleRevoke(token)}
+                  >
+                    <Trash2 className="h-4 w-4" />
+                  </Button>
+                </div>
+              )}
+            </div>
+          ))}
+        </div>
+      )}
+
+      <div className="space-y-3 rounded border p-3">
+        <div className="grid grid-cols-2 gap-4">
+          <div className="space-y-2">
+            <Label htmlFor="api-token-name">Name</Label>
+            <Input
+              id="api-token-name"
+              placeholder="CI pipeline"
+              value={name}
+              onChange={(e) => setName(e.target.value)}
+            />
+          </div>
+          <div className="space-y-2">
+            <Label htmlFor="api-token-expiry">Expires after (days)</Label>
+            <Input
+              id="api-token-expiry"
+              type="number"
+              min={1}
+              placeholder="Never"
+              value={expiresInDays}
+              onChange={(e) => setExpiresInDays(e.target.value)}
+            />
+          </div>
+        </div>
+        <div className="flex flex-wrap gap-4">
+          {SCOPES.map(({ scope, label }) => (
+            <div key={scope} className="flex items-center space-x-2">
+              <Checkbox
+                id={`api-token-scope-${scope}`}
+                checked={scopes.includes(scope)}
+                onCheckedChange={(checked: boolean) =>
+                  toggleScope(scope, checked)
+                }
+              />
+              <Label
+                htmlFor={`api-token-scope-${scope}`}
+                className="cursor-pointer"
+              >
+                {label}
+              </Label>
+            </div>
+          ))}
+        </div>
+        <Button
+          onClick={handleCreate}
+          disabled={creating || !name.trim() || scopes.length === 0}
+        >
+          {creating && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+          Create token
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,113 @@
+import { useCallback, useEffect, useState } from 'react';
+import { GripVertical, Pause } from 'lucide-react';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogHeader,
+  DialogTitle,
+} from './ui/dialog';
+import { Badge } from './ui/badge';
+import { guardrailHoldLabels } from './projects/guardrails-settings';
+import { executionQueueApi } from '@/lib/api';
+import type { QueueEntry } from 'shared/types';
+
+export function ExecutionQueueDialog({
+  open,
+  onOpenChange,
+}: {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+}) {
+  const [entries, setEntries] = useState<QueueEntry[]>([]);
+  const [dragging, setDragging] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = useCallback(async () => {
+    try {
+      setEntries(await executionQueueApi.list());
+      setError(null);
+    } catch (err) {
+      setError('Failed to load the execution queue');
+    }
+  }, []);
+
+  useEffect(() => {
+    if (open) load();
+  }, [open, load]);
+
+  const handleDrop = async (targetId: string) => {
+    if (!dragging || dragging === targetId) return;
+    const ids = entries.map((entry) => entry.task_attempt_id);
+    const to = ids.indexOf(targetId);
+    ids.splice(ids.indexOf(dragging), 1);
+    ids.splice(to, 0, dragging);
+    setDragging(null);
+    try {
+      setEntries(await executionQueueApi.reorder({ task_attempt_ids: ids }));
+      setError(null);
+    } catch (err) {
+      setError('Failed to reorder the execution queue');
+      load();
+    }
+  };
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent className="max-w-lg">
+        <DialogHeader>
+          <DialogTitle>Execution Queue</DialogTitle>
+          <DialogDescription>
+            Attempts waiting for a slot, in the order they start. Drag one to
+            change its place.
+          </DialogDescription>
+        </DialogHeader>
+        {error && <p className="text-sm text-destructive">{error}</p>}
+        {entries.length === 0 ? (
+          <p className="text-sm text-muted-foreground">Nothing is queued.</p>
+        ) : (
+          <ol className="space-y-1">
+            {entries.map((entry) => (
+              <li
+                key={entry.task_attempt_id}
+                draggable
+                onDragStart={() => setDragging(entry.task_attempt_id)}
+                onDragEnd={() => setDragging(null)}
+                onDragOver={(e) => e.preventDefault()}
+                onDrop={() => handleDrop(entry.task_attempt_id)}
+                className={`flex cursor-grab items-center gap-2 rounded border p-2 text-sm ${
+                  dragging === entry.task_attempt_id ? 'opacity-50' : ''
+                }`}
+              >
+                <GripVertical className="h-4 w-4 text-muted-foreground" />
+                <div className="min-w-0 flex-1">
+                  <div className="truncate font-medium">{entry.task_title}</div>
+                  <div className="truncate text-xs text-muted-foreground">
+                    {entry.project_name}
+                    {entry.executor && ` · ${entry.executor}`}
+                  </div>
+                </div>
+                {entry.paused && (
+                  <Pause
+                    className="h-3 w-3 text-muted-foreground"
+                    aria-label="Paused for a more urgent attempt"
+                  />
+                )}
+                {entry.hold && (
+                  <Badge variant="destructive">
+                    {guardrailHoldLabels[entry.hold]}
+                  </Badge>
+                )}
+                {entry.priority && (
+                  <Badge variant="outline" className="capitalize">
+                    {entry.priority}
+                  </Badge>
+                )}
+              </li>
+            ))}
+          </ol>
+        )}
+      </DialogContent>
+    </Dialog>
+  );
+}
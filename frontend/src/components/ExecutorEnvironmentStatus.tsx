@@ -0,0 +1,69 @@
+import { useEffect, useState } from 'react';
+import { AlertTriangle, CheckCircle2, Loader2 } from 'lucide-react';
+import { configApi } from '@/lib/api';
+import type { EnvironmentReport } from 'shared/types';
+
+interface ExecutorEnvironmentStatusProps {
+  executor: string;
+}
+
+// Whether this machine can run the executor, and how to fix it if not
+export function ExecutorEnvironmentStatus({
+  executor,
+}: ExecutorEnvironmentStatusProps) {
+  const [report, setReport] = useState<EnvironmentReport | null>(null);
+  const [loading, setLoading] = useState(true);
+
+  useEffect(() => {
+    let cancelled = false;
+    setLoading(true);
+    configApi
+      .checkExecutorEnvironment(executor)
+      .then((result) => {
+        if (!cancelled) setReport(result);
+      })
+      .catch(() => {
+        if (!cancelled) setReport(null);
+      })
+      .finally(() => {
+        if (!cancelled) setLoading(false);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [executor]);
+
+  if (loading) {
+    return (
+      <p className="flex items-center gap-2 text-sm text-muted-foreground">
+        <Loader2 className="h-4 w-4 animate-spin" />
+        Checking this machine can run it...
+      </p>
+    );
+  }
+  if (!report) return null;
+
+  if (report.problems.length === 0) {
+    return (
+      <p className="flex items-center gap-2 text-sm text-muted-foreground">
+        <CheckCircle2 className="h-4 w-4 text-green-600" />
+        Ready to run
+        {report.version && ` (${report.version})`}
+      </p>
+    );
+  }
+
+  return (
+    <div className="space-y-1 rounded-md border border-amber-300 bg-amber-50 p-3 text-sm dark:border-amber-800 dark:bg-amber-950">
+      {report.problems.map((problem) => (
+        <div key={problem.message} className="flex gap-2">
+          <AlertTriangle className="mt-0.5 h-4 w-4 shrink-0 text-amber-600" />
+          <div>
+            <div className="font-medium">{problem.message}</div>
+            <div className="text-muted-foreground">{problem.remediation}</div>
+          </div>
+        </div>
+      ))}
+    </div>
+  );
+}
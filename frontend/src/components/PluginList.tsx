@@ -0,0 +1,73 @@
+import { useEffect, useState } from 'react';
+import { Loader2, Puzzle } from 'lucide-react';
+import { Badge } from '@/components/ui/badge';
+import { pluginsApi } from '@/lib/api';
+import type { PluginInfo } from 'shared/types';
+
+const HOOKS: {
+  key: 'normalizer' | 'event_hook' | 'validator';
+  label: string;
+}[] = [
+  { key: 'normalizer', label: 'Output transform' },
+  { key: 'event_hook', label: 'Task events' },
+  { key: 'validator', label: 'Validation' },
+];
+
+// The WASM plugins the server loaded at startup, and what each hooks into
+export function PluginList() {
+  const [plugins, setPlugins] = useState<PluginInfo[] | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    pluginsApi
+      .getAll()
+      .then(setPlugins)
+      .catch((err) =>
+        setError(err instanceof Error ? err.message : 'Failed to load plugins')
+      );
+  }, []);
+
+  if (error) {
+    return <p className="text-sm text-destructive">{error}</p>;
+  }
+  if (!plugins) {
+    return (
+      <p className="flex items-center gap-2 text-sm text-muted-foreground">
+        <Loader2 className="h-4 w-4 animate-spin" />
+        Loading plugins...
+      </p>
+    );
+  }
+  if (plugins.length === 0) {
+    return (
+      <p className="text-sm text-muted-foreground">
+        No plugins are loaded. Put <code>.wasm</code> modules in the{' '}
+        <code>plugins</code> folder of the data directory and restart the
+        server.
+      </p>
+    );
+  }
+
+  return (
+    <div className="space-y-2">
+      {plugins.map((plugin) => (
+        <div
+          key={plugin.name}
+          className="flex items-center justify-between rounded-md border p-3"
+        >
+          <span className="flex items-center gap-2 text-sm font-medium">
+            <Puzzle className="h-4 w-4" />
+            {plugin.name}
+          </span>
+          <span className="flex gap-1">
+            {HOOKS.filter((hook) => plugin[hook.key]).map((hook) => (
+              <Badge key={hook.key} variant="secondary">
+                {hook.label}
+              </Badge>
+            ))}
+          </span>
+        </div>
+      ))}
+    </div>
+  );
+}
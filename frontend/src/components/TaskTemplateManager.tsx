@@ -37,6 +37,9 @@ export function TaskTemplateManager({
     template_name: '',
     title: '',
     description: '',
+    default_executor: '',
+    verification_script: '',
+    checklist: '',
   });
   const [saving, setSaving] = useState(false);
   const [error, setError] = useState<string | null>(null);
@@ -76,6 +79,9 @@ export function TaskTemplateManager({
         template_name: template.template_name,
         title: template.title,
         description: template.description || '',
+        default_executor: template.default_executor || '',
+        verification_script: template.verification_script || '',
+        checklist: template.checklist || '',
       });
     } else {
       setEditingTemplate(null);
@@ -83,6 +89,9 @@ export function TaskTemplateManager({
         template_name: '',
         title: '',
         description: '',
+        default_executor: '',
+        verification_script: '',
+        checklist: '',
       });
     }
     setError(null);
@@ -115,6 +124,10 @@ export function TaskTemplateManager({
           template_name: formData.template_name,
           title: formData.title,
           description: formData.description || null,
+          // Sent as-is so that a cleared field clears the template's
+          default_executor: formData.default_executor,
+          verification_script: formData.verification_script,
+          checklist: formData.checklist,
         };
         await templatesApi.update(editingTemplate.id, updateData);
       } else {
@@ -123,6 +136,9 @@ export function TaskTemplateManager({
           template_name: formData.template_name,
           title: formData.title,
           description: formData.description || null,
+          default_executor: formData.default_executor || null,
+          verification_script: formData.verification_script || null,
+          checklist: formData.checklist || null,
         };
         await templatesApi.create(createData);
       }
@@ -314,6 +330,47 @@ export function TaskTemplateManager({
                 rows={4}
               />
             </div>
+            <div>
+              <Label htmlFor="template-executor">Default Executor</Label>
+              <Input
+                id="template-executor"
+                value={formData.default_executor}
+                onChange={(e) =>
+                  setFormData({ ...formData, default_executor: e.target.value })
+                }
+                placeholder="e.g., claude (leave empty to use the configured one)"
+              />
+            </div>
+            <div>
+              <Label htmlFor="template-verification">
+                Verification Commands
+              </Label>
+              <Textarea
+                id="template-verification"
+                value={formData.verification_script}
+                onChange={(e) =>
+                  setFormData({
+                    ...formData,
+                    verification_script: e.target.value,
+                  })
+                }
+                placeholder="One command per line, run instead of the project's"
+                rows={3}
+                className="font-mono"
+              />
+            </div>
+            <div>
+              <Label htmlFor="template-checklist">Checklist</Label>
+              <Textarea
+                id="template-checklist"
+                value={formData.checklist}
+                onChange={(e) =>
+                  setFormData({ ...formData, checklist: e.target.value })
+                }
+                placeholder="One item per line"
+                rows={4}
+              />
+            </div>
             {error && <div className="text-sm text-red-600">{error}</div>}
           </div>
           <DialogFooter>
@@ -0,0 +1,183 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Badge } from '@/components/ui/badge';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { Loader2 } from 'lucide-react';
+import { authApi } from '@/lib/api';
+import type { TwoFactorEnrollment } from 'shared/types';
+
+interface TwoFactorManagerProps {
+  // Called once two-factor authentication is on, such as to let the user in
+  // where the server requires it
+  onEnabled?: () => void;
+}
+
+export function TwoFactorManager({ onEnabled }: TwoFactorManagerProps) {
+  const [enabled, setEnabled] = useState<boolean | null>(null);
+  const [enrollment, setEnrollment] = useState<TwoFactorEnrollment | null>(
+    null
+  );
+  const [recoveryCodes, setRecoveryCodes] = useState<string[] | null>(null);
+  const [code, setCode] = useState('');
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const fetchStatus = useCallback(async () => {
+    try {
+      const status = await authApi.getStatus();
+      setEnabled(status.two_factor_enabled);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to load status');
+    }
+  }, []);
+
+  useEffect(() => {
+    fetchStatus();
+  }, [fetchStatus]);
+
+  const run = async (action: () => Promise<void>) => {
+    setBusy(true);
+    setError(null);
+    try {
+      await action();
+      setCode('');
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Something went wrong');
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const handleStart = () =>
+    run(async () => {
+      setRecoveryCodes(null);
+      setEnrollment(await authApi.startTwoFactor());
+    });
+
+  const handleConfirm = () =>
+    run(async () => {
+      const codes = await authApi.confirmTwoFactor(code);
+      setRecoveryCodes(codes.codes);
+      setEnrollment(null);
+      setEnabled(true);
+    });
+
+  const handleReplaceCodes = () =>
+    run(async () => {
+      const codes = await authApi.replaceRecoveryCodes(code);
+      setRecoveryCodes(codes.codes);
+    });
+
+  const handleDisable = () =>
+    run(async () => {
+      await authApi.disableTwoFactor(code);
+      setRecoveryCodes(null);
+      setEnabled(false);
+    });
+
+  if (enabled === null) {
+    return (
+      <div className="flex justify-center py-4">
+        <Loader2 className="h-5 w-5 animate-spin" />
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      {error && (
+        <Alert variant="destructive">
+          <AlertDescription>{error}</AlertDescription>
+        </Alert>
+      )}
+      {recoveryCodes && (
+        <Alert>
+          <AlertDescription className="space-y-2">
+            <p>
+              Keep these recovery codes somewhere safe; each signs in once in
+              place of your authenticator app. They won't be shown again.
+            </p>
+            <pre className="text-sm">{recoveryCodes.join('\n')}</pre>
+            {onEnabled && (
+              <Button size="sm" onClick={onEnabled}>
+                Continue
+              </Button>
+            )}
+          </AlertDescription>
+        </Alert>
+      )}
+
+      <div className="flex items-center gap-2">
+        <span className="text-sm">Two-factor authentication</span>
+        <Badge variant={enabled ? 'default' : 'secondary'}>
+          {enabled ? 'On' : 'Off'}
+        </Badge>
+      </div>
+
+      {!enabled && !enrollment && (
+        <Button onClick={handleStart} disabled={busy}>
+          {busy && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+          Set up an authenticator app
+        </Button>
+      )}
+
+      {enrollment && (
+        <div className="space-y-2">
+          <p className="text-sm text-muted-foreground">
+            Add this key to your authenticator app, or open the link on your
+            phone, then enter the code it shows.
+          </p>
+          <code className="block break-all text-sm">{enrollment.secret}</code>
+          <a
+            href={enrollment.otpauth_url}
+            className="text-sm underline break-all"
+          >
+            {enrollment.otpauth_url}
+          </a>
+        </div>
+      )}
+
+      {(enabled || enrollment) && (
+        <div className="space-y-2">
+          <Label htmlFor="two-factor-code">
+            {enrollment
+              ? 'Code from the app'
+              : 'Code from the app, or a recovery code'}
+          </Label>
+          <Input
+            id="two-factor-code"
+            autoComplete="one-time-code"
+            value={code}
+            onChange={(e) => setCode(e.target.value)}
+          />
+          <div className="flex gap-2">
+            {enrollment ? (
+              <Button onClick={handleConfirm} disabled={busy || !code.trim()}>
+                Turn on
+              </Button>
+            ) : (
+              <>
+                <Button
+                  variant="outline"
+                  onClick={handleReplaceCodes}
+                  disabled={busy || !code.trim()}
+                >
+                  New recovery codes
+                </Button>
+                <Button
+                  variant="destructive"
+                  onClick={handleDisable}
+                  disabled={busy || !code.trim()}
+                >
+                  Turn off
+                </Button>
+              </>
+            )}
+          </div>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,147 @@
+import {
+  createContext,
+  FC,
+  ReactNode,
+  useCallback,
+  useContext,
+  useEffect,
+  useMemo,
+  useRef,
+  useState,
+} from 'react';
+import type { DraftQuote } from 'shared/types';
+import { followUpDraftsApi } from '@/lib/api.ts';
+import {
+  TaskDetailsContext,
+  TaskSelectedAttemptContext,
+} from './taskDetailsContext.ts';
+
+// How long typing has to pause before the draft is saved
+const SAVE_DELAY_MS = 800;
+
+interface FollowUpDraftContextValue {
+  prompt: string;
+  quotes: DraftQuote[];
+  setPrompt: (prompt: string) => void;
+  // Quotes are of the selected attempt; adding one drops another attempt's
+  addQuote: (quote: DraftQuote) => void;
+  removeQuote: (index: number) => void;
+  // Save what's there now, e.g. right before it's sent
+  save: () => Promise<void>;
+  // Forget the draft locally once the server has sent and discarded it
+  clear: () => void;
+}
+
+export const FollowUpDraftContext = createContext<FollowUpDraftContextValue>(
+  {} as FollowUpDraftContextValue
+);
+
+export const useFollowUpDraft = () => useContext(FollowUpDraftContext);
+
+const sameQuote = (a: DraftQuote, b: DraftQuote) =>
+  JSON.stringify(a) === JSON.stringify(b);
+
+export const FollowUpDraftProvider: FC<{ children: ReactNode }> = ({
+  children,
+}) => {
+  const { task, projectId } = useContext(TaskDetailsContext);
+  const { selectedAttempt } = useContext(TaskSelectedAttemptContext);
+  const [prompt, setPromptState] = useState('');
+  const [quotes, setQuotes] = useState<DraftQuote[]>([]);
+  const [attemptId, setAttemptId] = useState<string | null>(null);
+  const [dirty, setDirty] = useState(false);
+  const saveTimer = useRef<ReturnType<typeof setTimeout> | null>(null);
+
+  useEffect(() => {
+    setPromptState('');
+    setQuotes([]);
+    setAttemptId(null);
+    setDirty(false);
+    followUpDraftsApi
+      .get(projectId, task.id)
+      .then((draft) => {
+        if (!draft) return;
+        setPromptState(draft.prompt);
+        setQuotes(draft.quotes);
+        setAttemptId(draft.task_attempt_id);
+      })
+      .catch((err) => console.error('Failed to load follow-up draft:', err));
+  }, [projectId, task.id]);
+
+  const save = useCallback(async () => {
+    if (saveTimer.current) clearTimeout(saveTimer.current);
+    setDirty(false);
+    if (!prompt.trim() && quotes.length === 0) {
+      await followUpDraftsApi.discard(projectId, task.id);
+      return;
+    }
+    await followUpDraftsApi.save(projectId, task.id, {
+      task_attempt_id: attemptId ?? selectedAttempt?.id ?? null,
+      prompt,
+      quotes,
+    });
+  }, [projectId, task.id, prompt, quotes, attemptId, selectedAttempt?.id]);
+
+  useEffect(() => {
+    if (!dirty) return;
+    saveTimer.current = setTimeout(() => {
+      save().catch((err) =>
+        console.error('Failed to save follow-up draft:', err)
+      );
+    }, SAVE_DELAY_MS);
+    return () => {
+      if (saveTimer.current) clearTimeout(saveTimer.current);
+    };
+  }, [dirty, save]);
+
+  const setPrompt = useCallback((value: string) => {
+    setPromptState(value);
+    setDirty(true);
+  }, []);
+
+  const addQuote = useCallback(
+    (quote: DraftQuote) => {
+      if (!selectedAttempt) return;
+      const sameAttempt = attemptId === selectedAttempt.id;
+      setQuotes((prev) => {
+        const kept = sameAttempt ? prev : [];
+        return kept.some((q) => sameQuote(q, quote)) ? kept : [...kept, quote];
+      });
+      setAttemptId(selectedAttempt.id);
+      setDirty(true);
+    },
+    [selectedAttempt, attemptId]
+  );
+
+  const removeQuote = useCallback((index: number) => {
+    setQuotes((prev) => prev.filter((_, i) => i !== index));
+    setDirty(true);
+  }, []);
+
+  const clear = useCallback(() => {
+    if (saveTimer.current) clearTimeout(saveTimer.current);
+    setPromptState('');
+    setQuotes([]);
+    setAttemptId(null);
+    setDirty(false);
+  }, []);
+
+  const value = useMemo(
+    () => ({
+      prompt,
+      quotes,
+      setPrompt,
+      addQuote,
+      removeQuote,
+      save,
+      clear,
+    }),
+    [prompt, quotes, setPrompt, addQuote, removeQuote, save, clear]
+  );
+
+  return (
+    <FollowUpDraftContext.Provider value={value}>
+      {children}
+    </FollowUpDraftContext.Provider>
+  );
+};
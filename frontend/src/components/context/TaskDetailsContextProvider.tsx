@@ -13,6 +13,8 @@ import type {
   EditorType,
   ExecutionProcess,
   ExecutionProcessSummary,
+  LogStreamMessage,
+  PlanReview,
   Task,
   TaskAttempt,
   TaskAttemptState,
@@ -33,7 +35,7 @@ import {
   TaskSelectedAttemptContext,
 } from './taskDetailsContext.ts';
 import { TaskPlanContext } from './TaskPlanContext.ts';
-import { is_planning_executor_type } from '@/lib/utils.ts';
+import { is_plan_entry, is_planning_executor_type } from '@/lib/utils.ts';
 import type { AttemptData } from '@/lib/types.ts';
 
 const TaskDetailsProvider: FC<{
@@ -78,8 +80,11 @@ const TaskDetailsProvider: FC<{
     processes: [],
     runningProcessDetails: {},
     allLogs: [], // new field for all logs
+    approvals: {},
   });
 
+  const [plan, setPlan] = useState<PlanReview | null>(null);
+
   const relatedTasksLoadingRef = useRef(false);
 
   const fetchRelatedTasks = useCallback(async () => {
@@ -213,14 +218,20 @@ const TaskDetailsProvider: FC<{
     [task, projectId, selectedAttempt, setShowEditorDialog]
   );
 
+  const logStreamOpen = useRef(false);
+
   const fetchAttemptData = useCallback(
     async (attemptId: string, taskId: string) => {
       if (!task) return;
 
       try {
+        // While the log stream is open it keeps the logs current
+        const streamingLogs = logStreamOpen.current;
         const [processesResult, allLogsResult] = await Promise.all([
           attemptsApi.getExecutionProcesses(projectId, taskId, attemptId),
-          attemptsApi.getAllLogs(projectId, taskId, attemptId),
+          streamingLogs
+            ? null
+            : attemptsApi.getAllLogs(projectId, taskId, attemptId),
         ]);
 
         if (processesResult !== undefined && allLogsResult !== undefined) {
@@ -257,7 +268,8 @@ const TaskDetailsProvider: FC<{
             const newData = {
               processes: processesResult,
               runningProcessDetails,
-              allLogs: allLogsResult,
+              allLogs: allLogsResult ?? prev.allLogs,
+              approvals: prev.approvals,
             };
             if (JSON.stringify(prev) === JSON.stringify(newData)) return prev;
             return newData;
@@ -282,14 +294,25 @@ const TaskDetailsProvider: FC<{
       return false;
     }
 
+    // An attempt waiting to go on after a network drop or a rate limit has
+    // nothing running but should still poll and offer Stop
+    if (executionState?.execution_state === 'CodingAgentRetrying') {
+      return true;
+    }
+
     return attemptData.processes.some(
       (process: ExecutionProcessSummary) =>
         (process.process_type === 'codingagent' ||
           process.process_type === 'setupscript' ||
           process.process_type === 'cleanupscript') &&
-        process.status === 'running'
+        (process.status === 'running' || process.status === 'paused')
     );
-  }, [selectedAttempt, attemptData.processes, isStopping]);
+  }, [
+    selectedAttempt,
+    attemptData.processes,
+    isStopping,
+    executionState?.execution_state,
+  ]);
 
   useEffect(() => {
     if (!isAttemptRunning || !task) return;
@@ -310,6 +333,73 @@ const TaskDetailsProvider: FC<{
     fetchExecutionState,
   ]);
 
+  // Push new log entries while the attempt runs instead of refetching whole logs
+  useEffect(() => {
+    if (!isAttemptRunning || !selectedAttempt) return;
+
+    const socket = new WebSocket(
+      attemptsApi.logsStreamUrl(
+        projectId,
+        selectedAttempt.task_id,
+        selectedAttempt.id
+      )
+    );
+    socket.onopen = () => {
+      logStreamOpen.current = true;
+    };
+    socket.onclose = () => {
+      logStreamOpen.current = false;
+    };
+    socket.onmessage = (event) => {
+      const message: LogStreamMessage = JSON.parse(event.data);
+      setAttemptData((prev: AttemptData) => {
+        if (message.type === 'snapshot') {
+          const { process } = message;
+          const known = prev.allLogs.some((log) => log.id === process.id);
+          return {
+            ...prev,
+            allLogs: known
+              ? prev.allLogs.map((log) =>
+                  log.id === process.id ? process : log
+                )
+              : [...prev.allLogs, process],
+          };
+        }
+        if (message.type === 'approval') {
+          const approvals = { ...prev.approvals };
+          if (message.approval) {
+            approvals[message.process_id] = message.approval;
+          } else {
+            delete approvals[message.process_id];
+          }
+          return { ...prev, approvals };
+        }
+        return {
+          ...prev,
+          allLogs: prev.allLogs.map((log) =>
+            log.id === message.process_id
+              ? {
+                  ...log,
+                  normalized_conversation: {
+                    ...log.normalized_conversation,
+                    entries: [
+                      ...log.normalized_conversation.entries,
+                      ...message.entries,
+                    ],
+                  },
+                }
+              : log
+          ),
+        };
+      });
+    };
+
+    return () => {
+      logStreamOpen.current = false;
+      socket.close();
+    };
+  }, [isAttemptRunning, selectedAttempt, projectId]);
+
   // Refresh diff when coding agent is running and making changes
   useEffect(() => {
     if (!executionState || !selectedAttempt) return;
@@ -438,29 +528,52 @@ const TaskDetailsProvider: FC<{
     ]
   );
 
+  // A run starting or finishing is what changes the stored plan
+  const processStatusKey = useMemo(
+    () =>
+      attemptData.processes
+        .map((process) => `${process.id}:${process.status}`)
+        .join(','),
+    [attemptData.processes]
+  );
+
+  useEffect(() => {
+    if (!projectId || !task?.id || !selectedAttempt?.id) {
+      setPlan(null);
+      return;
+    }
+    attemptsApi
+      .getPlan(projectId, task.id, selectedAttempt.id)
+      .then(setPlan)
+      .catch(() => setPlan(null));
+  }, [projectId, task?.id, selectedAttempt?.id, processStatusKey]);
+
   // Plan context value
   const planValue = useMemo(() => {
-    const isPlanningMode =
+    const hasPlanningProcess =
       attemptData.processes?.some(
         (process) =>
           process.executor_type &&
           is_planning_executor_type(process.executor_type)
       ) ?? false;
+    const isPlanningMode = !!selectedAttempt?.plan_only || hasPlanningProcess;
 
     const planCount =
       attemptData.allLogs?.reduce((count, processLog) => {
         const planEntries =
-          processLog.normalized_conversation?.entries.filter(
-            (entry) =>
-              entry.entry_type.type === 'tool_use' &&
-              entry.entry_type.action_type.action === 'plan_presentation'
-          ) ?? [];
+          processLog.normalized_conversation?.entries.filter(is_plan_entry) ??
+          [];
         return count + planEntries.length;
       }, 0) ?? 0;
 
     const hasPlans = planCount > 0;
 
+    // Once the plan is approved the runs that follow implement it, so they
+    // aren't expected to present one
+    const planApproved = plan?.plan.status === 'approved';
+
     const latestProcessHasNoPlan = (() => {
+      if (planApproved) return false;
       if (!attemptData.allLogs || attemptData.allLogs.length === 0)
         return false;
       const latestProcessLog =
@@ -468,9 +581,7 @@ const TaskDetailsProvider: FC<{
       if (!latestProcessLog.normalized_conversation?.entries) return true;
 
       return !latestProcessLog.normalized_conversation.entries.some(
-        (entry) =>
-          entry.entry_type.type === 'tool_use' &&
-          entry.entry_type.action_type.action === 'plan_presentation'
+        is_plan_entry
       );
     })();
 
@@ -485,8 +596,14 @@ const TaskDetailsProvider: FC<{
       planCount,
       latestProcessHasNoPlan,
       canCreateTask,
+      plan,
     };
-  }, [attemptData.processes, attemptData.allLogs]);
+  }, [
+    selectedAttempt?.plan_only,
+    attemptData.processes,
+    attemptData.allLogs,
+    plan,
+  ]);
 
   return (
     <TaskDetailsContext.Provider value={value}>
@@ -1,4 +1,5 @@
 import { createContext, useContext } from 'react';
+import type { PlanReview } from 'shared/types';
 
 interface TaskPlanContextValue {
   isPlanningMode: boolean;
@@ -6,6 +7,8 @@ interface TaskPlanContextValue {
   planCount: number;
   latestProcessHasNoPlan: boolean;
   canCreateTask: boolean;
+  // The attempt's stored plan, pending or approved
+  plan: PlanReview | null;
 }
 
 export const TaskPlanContext = createContext<TaskPlanContextValue>({
@@ -14,6 +17,7 @@ export const TaskPlanContext = createContext<TaskPlanContextValue>({
   planCount: 0,
   latestProcessHasNoPlan: false,
   canCreateTask: true,
+  plan: null,
 });
 
 export const useTaskPlan = () => {
@@ -27,6 +31,7 @@ export const useTaskPlan = () => {
       planCount: 0,
       latestProcessHasNoPlan: false,
       canCreateTask: true,
+      plan: null,
     };
   }
   return context;
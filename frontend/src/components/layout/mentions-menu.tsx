@@ -0,0 +1,108 @@
+import { useCallback, useEffect, useState } from 'react';
+import { useNavigate } from 'react-router-dom';
+import { AtSign } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Badge } from '@/components/ui/badge';
+import {
+  DropdownMenu,
+  DropdownMenuContent,
+  DropdownMenuItem,
+  DropdownMenuLabel,
+  DropdownMenuSeparator,
+  DropdownMenuTrigger,
+} from '@/components/ui/dropdown-menu';
+import { mentionsApi } from '@/lib/api';
+import type { Mention } from 'shared/types';
+
+const POLL_INTERVAL_MS = 30000;
+
+const sourceLabel = {
+  review_comment: 'a review comment',
+  follow_up: 'a follow-up',
+};
+
+// The signed-in user's unread @mentions, from review comments and follow-ups
+export function MentionsMenu() {
+  const navigate = useNavigate();
+  const [mentions, setMentions] = useState<Mention[]>([]);
+
+  const fetchMentions = useCallback(async () => {
+    try {
+      setMentions(await mentionsApi.getAll(true));
+    } catch (err) {
+      console.error('Failed to load mentions:', err);
+    }
+  }, []);
+
+  useEffect(() => {
+    fetchMentions();
+    const interval = setInterval(fetchMentions, POLL_INTERVAL_MS);
+    return () => clearInterval(interval);
+  }, [fetchMentions]);
+
+  const handleOpen = async (mention: Mention) => {
+    navigate(`/projects/${mention.project_id}/tasks/${mention.task_id}`);
+    try {
+      await mentionsApi.markRead(mention.id);
+    } finally {
+      fetchMentions();
+    }
+  };
+
+  const handleMarkAllRead = async () => {
+    try {
+      await mentionsApi.markAllRead();
+    } finally {
+      fetchMentions();
+    }
+  };
+
+  return (
+    <DropdownMenu>
+      <DropdownMenuTrigger asChild>
+        <Button variant="ghost" size="sm">
+          <AtSign className="mr-2 h-4 w-4" />
+          Mentions
+          {mentions.length > 0 && (
+            <Badge className="ml-2" variant="destructive">
+              {mentions.length}
+            </Badge>
+          )}
+        </Button>
+      </DropdownMenuTrigger>
+      <DropdownMenuContent align="end" className="w-80">
+        <DropdownMenuLabel>Mentions</DropdownMenuLabel>
+        <DropdownMenuSeparator />
+        {mentions.length === 0 ? (
+          <p className="px-2 py-1.5 text-sm text-muted-foreground">
+            Nothing new.
+          </p>
+        ) : (
+          <>
+            {mentions.map((mention) => (
+              <DropdownMenuItem
+                key={mention.id}
+                className="flex-col items-start gap-1"
+                onClick={() => handleOpen(mention)}
+              >
+                <span className="text-sm font-medium">
+                  {mention.task_title}
+                </span>
+                <span className="text-xs text-muted-foreground">
+                  {mention.mentioned_by_username ?? 'Someone'} in{' '}
+                  {sourceLabel[mention.source]},{' '}
+                  {new Date(mention.created_at).toLocaleString()}
+                </span>
+                <span className="line-clamp-2 text-xs">{mention.excerpt}</span>
+              </DropdownMenuItem>
+            ))}
+            <DropdownMenuSeparator />
+            <DropdownMenuItem onClick={handleMarkAllRead}>
+              Mark all read
+            </DropdownMenuItem>
+          </>
+        )}
+      </DropdownMenuContent>
+    </DropdownMenu>
+  );
+}
@@ -1,13 +1,14 @@
+import { useState } from 'react';
 import { Link, useLocation } from 'react-router-dom';
 import { Button } from '@/components/ui/button';
-import {
-  FolderOpen,
-  Settings,
-} from 'lucide-react';
+import { FolderOpen, ListOrdered, Settings } from 'lucide-react';
 import { Logo } from '@/components/logo';
+import { ExecutionQueueDialog } from '@/components/ExecutionQueueDialog';
+import { MentionsMenu } from '@/components/layout/mentions-menu';
 
 export function Navbar() {
   const location = useLocation();
+  const [showQueue, setShowQueue] = useState(false);
 
   return (
     <div className="border-b">
@@ -44,9 +45,19 @@ export function Navbar() {
             </div>
           </div>
           <div className="flex items-center space-x-1">
+            <MentionsMenu />
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={() => setShowQueue(true)}
+            >
+              <ListOrdered className="mr-2 h-4 w-4" />
+              Queue
+            </Button>
           </div>
         </div>
       </div>
+      <ExecutionQueueDialog open={showQueue} onOpenChange={setShowQueue} />
     </div>
   );
 }
@@ -0,0 +1,125 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Coins, Loader2, RefreshCw } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import {
+  Card,
+  CardContent,
+  CardDescription,
+  CardHeader,
+  CardTitle,
+} from '@/components/ui/card';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { projectsApi } from '@/lib/api';
+import type { ProjectUsage } from 'shared/types';
+
+function formatTokens(value: bigint | number): string {
+  return Number(value).toLocaleString();
+}
+
+function formatCost(cost: number | null): string {
+  return cost === null ? 'cost not reported' : `$${cost.toFixed(2)}`;
+}
+
+interface AgentUsageCardProps {
+  projectId: string;
+}
+
+export function AgentUsageCard({ projectId }: AgentUsageCardProps) {
+  const [usage, setUsage] = useState<ProjectUsage | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState('');
+
+  const fetchUsage = useCallback(async () => {
+    setLoading(true);
+    setError('');
+    try {
+      setUsage(await projectsApi.getUsage(projectId));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to load usage');
+    } finally {
+      setLoading(false);
+    }
+  }, [projectId]);
+
+  useEffect(() => {
+    fetchUsage();
+  }, [fetchUsage]);
+
+  return (
+    <Card>
+      <CardHeader>
+        <div className="flex items-start justify-between">
+          <div>
+            <CardTitle className="flex items-center">
+              <Coins className="mr-2 h-5 w-5" />
+              Agent Usage
+            </CardTitle>
+            <CardDescription>
+              {usage
+                ? `${formatTokens(usage.input_tokens)} input and ${formatTokens(usage.output_tokens)} output tokens, ${formatCost(usage.cost_usd)}`
+                : 'Tokens and cost of this project’s coding agent runs'}
+            </CardDescription>
+          </div>
+          <Button
+            variant="outline"
+            size="sm"
+            onClick={fetchUsage}
+            disabled={loading}
+          >
+            {loading ? (
+              <Loader2 className="h-4 w-4 animate-spin" />
+            ) : (
+              <RefreshCw className="h-4 w-4" />
+            )}
+          </Button>
+        </div>
+      </CardHeader>
+      <CardContent className="space-y-3">
+        {error && (
+          <Alert variant="destructive">
+            <AlertDescription>{error}</AlertDescription>
+          </Alert>
+        )}
+        {usage && usage.tasks.length === 0 && (
+          <p className="text-sm text-muted-foreground">
+            No runs have reported usage yet.
+          </p>
+        )}
+        {usage && usage.models.some((model) => model.model !== null) && (
+          <div className="flex flex-wrap gap-2">
+            {usage.models.map((model) => (
+              <span
+                key={model.model ?? 'default'}
+                className="rounded-md bg-muted px-2 py-1 text-xs"
+                title={`${formatTokens(model.input_tokens)} in · ${formatTokens(model.output_tokens)} out · ${String(model.runs)} runs`}
+              >
+                <span className="font-mono">
+                  {model.model ?? 'agent default'}
+                </span>{' '}
+                {model.cost_usd === null ? '' : `$${model.cost_usd.toFixed(2)}`}
+              </span>
+            ))}
+          </div>
+        )}
+        {usage?.tasks.map((task) => (
+          <div
+            key={task.task_id}
+            className="flex items-center justify-between gap-4 rounded-md border p-3"
+          >
+            <div className="min-w-0 space-y-1">
+              <p className="truncate text-sm font-medium">{task.title}</p>
+              <p className="text-xs text-muted-foreground">
+                {formatTokens(task.input_tokens)} in ·{' '}
+                {formatTokens(task.output_tokens)} out · {String(task.runs)}{' '}
+                runs
+              </p>
+            </div>
+            <span className="text-sm font-medium">
+              {task.cost_usd === null ? '—' : `$${task.cost_usd.toFixed(2)}`}
+            </span>
+          </div>
+        ))}
+      </CardContent>
+    </Card>
+  );
+}
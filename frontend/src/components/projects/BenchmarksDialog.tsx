@@ -0,0 +1,462 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Play, Plus, Trash2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Badge } from '@/components/ui/badge';
+import { Checkbox } from '@/components/ui/checkbox';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Textarea } from '@/components/ui/textarea';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { benchmarksApi, executorProfilesApi } from '@/lib/api';
+import {
+  EXECUTOR_LABELS,
+  EXECUTOR_TYPES,
+  type BenchmarkCaseInput,
+  type BenchmarkContestant,
+  type BenchmarkReport,
+  type BenchmarkResultStatus,
+  type BenchmarkSuite,
+  type ExecutorProfile,
+} from 'shared/types';
+
+interface BenchmarksDialogProps {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+  projectId: string;
+}
+
+const statusVariant: Record<
+  BenchmarkResultStatus,
+  'default' | 'secondary' | 'destructive'
+> = {
+  running: 'secondary',
+  passed: 'default',
+  failed: 'destructive',
+};
+
+const emptyCase = (): BenchmarkCaseInput => ({
+  title: '',
+  prompt: '',
+  verification_script: '',
+});
+
+function formatDuration(ms: bigint | null): string {
+  if (ms === null) return '—';
+  const seconds = Math.round(Number(ms) / 1000);
+  return seconds < 60
+    ? `${seconds}s`
+    : `${Math.floor(seconds / 60)}m ${seconds % 60}s`;
+}
+
+export function BenchmarksDialog({
+  open,
+  onOpenChange,
+  projectId,
+}: BenchmarksDialogProps) {
+  const [suites, setSuites] = useState<BenchmarkSuite[]>([]);
+  const [profiles, setProfiles] = useState<ExecutorProfile[]>([]);
+  // The suite being edited; null id for a new one
+  const [editing, setEditing] = useState<{
+    id: string | null;
+    name: string;
+    cases: BenchmarkCaseInput[];
+  } | null>(null);
+  const [selectedId, setSelectedId] = useState<string | null>(null);
+  const [reports, setReports] = useState<BenchmarkReport[]>([]);
+  // Contestants to run, keyed `executor:<name>` or `profile:<id>`
+  const [contestants, setContestants] = useState<string[]>([]);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const fetchSuites = useCallback(async () => {
+    try {
+      setSuites(await benchmarksApi.list(projectId));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to load suites');
+    }
+  }, [projectId]);
+
+  const fetchReports = useCallback(
+    async (suiteId: string) => {
+      try {
+        setReports(await benchmarksApi.getRuns(projectId, suiteId));
+      } catch (err) {
+        setError(err instanceof Error ? err.message : 'Failed to load runs');
+      }
+    },
+    [projectId]
+  );
+
+  useEffect(() => {
+    if (open) {
+      fetchSuites();
+      executorProfilesApi
+        .getAvailable(projectId)
+        .then(setProfiles)
+        .catch(() => setProfiles([]));
+    } else {
+      setEditing(null);
+      setSelectedId(null);
+      setReports([]);
+      setError(null);
+    }
+  }, [open, projectId, fetchSuites]);
+
+  // Follow a run until every result is in
+  const running = reports.some((report) => report.run.finished_at === null);
+  useEffect(() => {
+    if (!selectedId || !running) return;
+    const interval = setInterval(() => fetchReports(selectedId), 5000);
+    return () => clearInterval(interval);
+  }, [selectedId, running, fetchReports]);
+
+  const select = (suite: BenchmarkSuite) => {
+    setEditing(null);
+    setSelectedId(suite.id);
+    fetchReports(suite.id);
+  };
+
+  const updateCase = (index: number, change: Partial<BenchmarkCaseInput>) => {
+    setEditing((current) =>
+      current
+        ? {
+            ...current,
+            cases: current.cases.map((c, i) =>
+              i === index ? { ...c, ...change } : c
+            ),
+          }
+        : current
+    );
+  };
+
+  const handleSave = async () => {
+    if (!editing) return;
+    setBusy(true);
+    setError(null);
+    try {
+      const data = { name: editing.name, cases: editing.cases };
+      const saved = editing.id
+        ? await benchmarksApi.update(projectId, editing.id, data)
+        : await benchmarksApi.create(projectId, data);
+      await fetchSuites();
+      select(saved);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to save suite');
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const handleDelete = async (suite: BenchmarkSuite) => {
+    if (!confirm(`Delete the ${suite.name} suite and its runs?`)) return;
+    setError(null);
+    try {
+      await benchmarksApi.delete(projectId, suite.id);
+      if (selectedId === suite.id) {
+        setSelectedId(null);
+        setReports([]);
+      }
+      await fetchSuites();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to delete suite');
+    }
+  };
+
+  const handleRun = async () => {
+    if (!selectedId) return;
+    setBusy(true);
+    setError(null);
+    try {
+      const picked: BenchmarkContestant[] = contestants.map((key) => {
+        const [kind, value] = key.split(':');
+        return kind === 'profile'
+          ? { executor: null, executor_profile_id: value, model: null }
+          : { executor: value, executor_profile_id: null, model: null };
+      });
+      await benchmarksApi.startRun(projectId, selectedId, {
+        contestants: picked,
+      });
+      await fetchReports(selectedId);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to start run');
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const toggleContestant = (key: string) => {
+    setContestants((current) =>
+      current.includes(key)
+        ? current.filter((k) => k !== key)
+        : [...current, key]
+    );
+  };
+
+  const contestantOptions = [
+    ...EXECUTOR_TYPES.filter((type) => type !== 'echo').map((type) => ({
+      key: `executor:${type}`,
+      label: EXECUTOR_LABELS[type] ?? type,
+    })),
+    ...profiles.map((profile) => ({
+      key: `profile:${profile.id}`,
+      label: `${profile.name} (profile)`,
+    })),
+  ];
+  const selected = suites.find((suite) => suite.id === selectedId);
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent className="sm:max-w-[900px] max-h-[85vh] overflow-y-auto">
+        <DialogHeader>
+          <DialogTitle>Benchmarks</DialogTitle>
+          <DialogDescription>
+            A suite is a set of tasks with commands that tell whether each was
+            done. Running it starts every task with each executor or profile
+            you pick, side by side in their own worktrees, and compares how
+            many passed, how long they took and what they cost.
+          </DialogDescription>
+        </DialogHeader>
+
+        {error && (
+          <Alert variant="destructive">
+            <AlertDescription>{error}</AlertDescription>
+          </Alert>
+        )}
+
+        <div className="space-y-2">
+          {suites.map((suite) => (
+            <div
+              key={suite.id}
+              className={`flex items-center gap-3 rounded-md border px-3 py-2 cursor-pointer ${
+                selectedId === suite.id ? 'bg-muted' : ''
+              }`}
+              onClick={() => select(suite)}
+            >
+              <span className="font-medium flex-1">{suite.name}</span>
+              <span className="text-xs text-muted-foreground">
+                {suite.cases.length} cases
+              </span>
+              <Button
+                variant="ghost"
+                size="sm"
+                onClick={(e) => {
+                  e.stopPropagation();
+                  setSelectedId(null);
+                  setEditing({
+                    id: suite.id,
+                    name: suite.name,
+                    cases: suite.cases.map((c) => ({
+                      title: c.title,
+                      prompt: c.prompt,
+                      verification_script: c.verification_script,
+                    })),
+                  });
+                }}
+              >
+                Edit
+              </Button>
+              <Button
+                variant="ghost"
+                size="sm"
+                className="h-8 w-8 p-0"
+                title="Delete suite"
+                onClick={(e) => {
+                  e.stopPropagation();
+                  handleDelete(suite);
+                }}
+              >
+                <Trash2 className="h-4 w-4" />
+              </Button>
+            </div>
+          ))}
+          {!editing && (
+            <Button
+              variant="outline"
+              onClick={() => {
+                setSelectedId(null);
+                setEditing({ id: null, name: '', cases: [emptyCase()] });
+              }}
+            >
+              <Plus className="h-4 w-4 mr-2" />
+              New suite
+            </Button>
+          )}
+        </div>
+
+        {editing && (
+          <div className="space-y-3 border-t pt-3">
+            <div className="space-y-2">
+              <Label htmlFor="benchmark-suite-name">Suite name</Label>
+              <Input
+                id="benchmark-suite-name"
+                value={editing.name}
+                onChange={(e) =>
+                  setEditing({ ...editing, name: e.target.value })
+                }
+              />
+            </div>
+            {editing.cases.map((c, index) => (
+              <div key={index} className="space-y-2 rounded-md border p-2">
+                <div className="flex items-center gap-2">
+                  <Input
+                    placeholder="Case title"
+                    value={c.title}
+                    onChange={(e) =>
+                      updateCase(index, { title: e.target.value })
+                    }
+                  />
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    disabled={editing.cases.length === 1}
+                    onClick={() =>
+                      setEditing({
+                        ...editing,
+                        cases: editing.cases.filter((_, i) => i !== index),
+                      })
+                    }
+                  >
+                    <Trash2 className="h-4 w-4" />
+                  </Button>
+                </div>
+                <Textarea
+                  placeholder="What the agent is asked to do"
+                  rows={3}
+                  value={c.prompt}
+                  onChange={(e) =>
+                    updateCase(index, { prompt: e.target.value })
+                  }
+                />
+                <Textarea
+                  placeholder="Verification commands, one per line"
+                  rows={2}
+                  className="font-mono text-xs"
+                  value={c.verification_script}
+                  onChange={(e) =>
+                    updateCase(index, { verification_script: e.target.value })
+                  }
+                />
+              </div>
+            ))}
+            <div className="flex justify-between">
+              <Button
+                variant="outline"
+                onClick={() =>
+                  setEditing({
+                    ...editing,
+                    cases: [...editing.cases, emptyCase()],
+                  })
+                }
+              >
+                <Plus className="h-4 w-4 mr-2" />
+                Add case
+              </Button>
+              <div className="flex gap-2">
+                <Button variant="outline" onClick={() => setEditing(null)}>
+                  Cancel
+                </Button>
+                <Button onClick={handleSave} disabled={busy}>
+                  Save suite
+                </Button>
+              </div>
+            </div>
+          </div>
+        )}
+
+        {selected && (
+          <div className="space-y-3 border-t pt-3">
+            <h3 className="text-sm font-semibold">Run {selected.name} with</h3>
+            <div className="flex flex-wrap gap-x-4 gap-y-1">
+              {contestantOptions.map((option) => (
+                <div key={option.key} className="flex items-center gap-1">
+                  <Checkbox
+                    id={`benchmark-${option.key}`}
+                    checked={contestants.includes(option.key)}
+                    onCheckedChange={() => toggleContestant(option.key)}
+                  />
+                  <Label
+                    htmlFor={`benchmark-${option.key}`}
+                    className="text-xs cursor-pointer"
+                  >
+                    {option.label}
+                  </Label>
+                </div>
+              ))}
+            </div>
+            <div className="flex justify-end">
+              <Button
+                onClick={handleRun}
+                disabled={busy || contestants.length === 0}
+              >
+                <Play className="h-4 w-4 mr-2" />
+                Start run
+              </Button>
+            </div>
+
+            {reports.length === 0 && (
+              <p className="text-sm text-muted-foreground">No runs yet.</p>
+            )}
+            {reports.map((report) => (
+              <div
+                key={report.run.id}
+                className="space-y-2 rounded-md border p-3"
+              >
+                <p className="text-xs text-muted-foreground">
+                  {new Date(report.run.created_at).toLocaleString()}
+                  {report.run.finished_at === null && ' · running'}
+                </p>
+                <table className="w-full text-sm">
+                  <thead className="text-xs text-muted-foreground">
+                    <tr className="text-left">
+                      <th className="font-normal">Contestant</th>
+                      <th className="font-normal">Passed</th>
+                      <th className="font-normal">Median time</th>
+                      <th className="font-normal">Cost</th>
+                    </tr>
+                  </thead>
+                  <tbody>
+                    {report.contestants.map((contestant) => (
+                      <tr key={contestant.contestant}>
+                        <td className="font-mono">{contestant.contestant}</td>
+                        <td>
+                          {String(contestant.passed)}/
+                          {String(contestant.cases)}
+                          {contestant.pass_rate !== null &&
+                            ` (${Math.round(contestant.pass_rate * 100)}%)`}
+                        </td>
+                        <td>{formatDuration(contestant.median_duration_ms)}</td>
+                        <td>
+                          {contestant.cost_usd === null
+                            ? '—'
+                            : `$${contestant.cost_usd.toFixed(2)}`}
+                        </td>
+                      </tr>
+                    ))}
+                  </tbody>
+                </table>
+                <div className="flex flex-wrap gap-1">
+                  {report.results.map((result) => (
+                    <Badge
+                      key={result.id}
+                      variant={statusVariant[result.status]}
+                      title={`${result.contestant}: ${formatDuration(result.duration_ms)}`}
+                    >
+                      {result.case_title} · {result.contestant}
+                    </Badge>
+                  ))}
+                </div>
+              </div>
+            ))}
+          </div>
+        )}
+      </DialogContent>
+    </Dialog>
+  );
+}
@@ -0,0 +1,195 @@
+import { useState } from 'react';
+import { Check, Copy } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Textarea } from '@/components/ui/textarea';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { projectsApi } from '@/lib/api';
+import type { Changelog, ChangelogFormat } from 'shared/types';
+
+interface ChangelogDialogProps {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+  projectId: string;
+}
+
+export function ChangelogDialog({
+  open,
+  onOpenChange,
+  projectId,
+}: ChangelogDialogProps) {
+  const [from, setFrom] = useState('');
+  const [to, setTo] = useState('');
+  const [sinceTag, setSinceTag] = useState('');
+  const [version, setVersion] = useState('');
+  const [format, setFormat] = useState<ChangelogFormat>('markdown');
+  const [changelog, setChangelog] = useState<Changelog | null>(null);
+  const [generating, setGenerating] = useState(false);
+  const [copied, setCopied] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const handleGenerate = async () => {
+    setGenerating(true);
+    setError(null);
+    setCopied(false);
+    try {
+      const result = await projectsApi.getChangelog(projectId, {
+        from: from || null,
+        to: to || null,
+        since_tag: sinceTag.trim() || null,
+        version: version.trim() || null,
+        format,
+      });
+      setChangelog(result);
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to generate changelog'
+      );
+    } finally {
+      setGenerating(false);
+    }
+  };
+
+  const handleCopy = async () => {
+    if (!changelog) return;
+    try {
+      await navigator.clipboard.writeText(changelog.content);
+      setCopied(true);
+      setTimeout(() => setCopied(false), 2000);
+    } catch (err) {
+      console.error('Failed to copy changelog:', err);
+    }
+  };
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent className="sm:max-w-[640px]">
+        <DialogHeader>
+          <DialogTitle>Changelog</DialogTitle>
+          <DialogDescription>
+            Lists the tasks merged in a date range or since a tag, grouped by
+            their labels.
+          </DialogDescription>
+        </DialogHeader>
+
+        <div className="grid grid-cols-2 gap-3">
+          <div className="space-y-2">
+            <Label htmlFor="changelog-from">From</Label>
+            <Input
+              id="changelog-from"
+              type="date"
+              value={from}
+              onChange={(e) => setFrom(e.target.value)}
+            />
+          </div>
+          <div className="space-y-2">
+            <Label htmlFor="changelog-to">To</Label>
+            <Input
+              id="changelog-to"
+              type="date"
+              value={to}
+              onChange={(e) => setTo(e.target.value)}
+            />
+          </div>
+          <div className="space-y-2">
+            <Label htmlFor="changelog-tag">Since tag</Label>
+            <Input
+              id="changelog-tag"
+              placeholder="v1.2.0"
+              value={sinceTag}
+              onChange={(e) => setSinceTag(e.target.value)}
+            />
+          </div>
+          <div className="space-y-2">
+            <Label htmlFor="changelog-version">Version</Label>
+            <Input
+              id="changelog-version"
+              placeholder="Unreleased"
+              value={version}
+              onChange={(e) => setVersion(e.target.value)}
+            />
+          </div>
+          <div className="space-y-2 col-span-2">
+            <Label htmlFor="changelog-format">Format</Label>
+            <Select
+              value={format}
+              onValueChange={(value) => setFormat(value as ChangelogFormat)}
+            >
+              <SelectTrigger id="changelog-format">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value="markdown">Markdown</SelectItem>
+                <SelectItem value="keep_a_changelog">
+                  Keep a Changelog
+                </SelectItem>
+              </SelectContent>
+            </Select>
+          </div>
+        </div>
+
+        {error && (
+          <Alert variant="destructive">
+            <AlertDescription>{error}</AlertDescription>
+          </Alert>
+        )}
+
+        {changelog && (
+          <div className="space-y-2">
+            <div className="flex items-center justify-between">
+              <Label htmlFor="changelog-content">
+                {changelog.entries.length} task
+                {changelog.entries.length === 1 ? '' : 's'}
+              </Label>
+              <Button
+                variant="ghost"
+                size="sm"
+                onClick={handleCopy}
+                className="h-8 w-8 p-0"
+                title="Copy to clipboard"
+              >
+                {copied ? (
+                  <Check className="h-4 w-4" />
+                ) : (
+                  <Copy className="h-4 w-4" />
+                )}
+              </Button>
+            </div>
+            <Textarea
+              id="changelog-content"
+              readOnly
+              rows={12}
+              value={changelog.content}
+              className="font-mono text-xs"
+            />
+          </div>
+        )}
+
+        <DialogFooter>
+          <Button variant="outline" onClick={() => onOpenChange(false)}>
+            Close
+          </Button>
+          <Button onClick={handleGenerate} disabled={generating}>
+            {generating ? 'Generating...' : 'Generate'}
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
@@ -0,0 +1,153 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Loader2, RefreshCw, Timer } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import {
+  Card,
+  CardContent,
+  CardDescription,
+  CardHeader,
+  CardTitle,
+} from '@/components/ui/card';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { projectsApi } from '@/lib/api';
+import type { ProjectAnalytics } from 'shared/types';
+
+function formatDuration(ms: bigint | number | null): string {
+  if (ms === null) {
+    return '—';
+  }
+  const seconds = Math.round(Number(ms) / 1000);
+  if (seconds < 60) {
+    return `${seconds}s`;
+  }
+  const minutes = Math.floor(seconds / 60);
+  if (minutes < 60) {
+    return `${minutes}m ${seconds % 60}s`;
+  }
+  const hours = Math.floor(minutes / 60);
+  if (hours < 48) {
+    return `${hours}h ${minutes % 60}m`;
+  }
+  return `${Math.floor(hours / 24)}d ${hours % 24}h`;
+}
+
+function formatRate(rate: number | null): string {
+  return rate === null ? '—' : `${Math.round(rate * 100)}%`;
+}
+
+interface ExecutionAnalyticsCardProps {
+  projectId: string;
+}
+
+export function ExecutionAnalyticsCard({
+  projectId,
+}: ExecutionAnalyticsCardProps) {
+  const [analytics, setAnalytics] = useState<ProjectAnalytics | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState('');
+
+  const fetchAnalytics = useCallback(async () => {
+    setLoading(true);
+    setError('');
+    try {
+      setAnalytics(await projectsApi.getAnalytics(projectId));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to load analytics');
+    } finally {
+      setLoading(false);
+    }
+  }, [projectId]);
+
+  useEffect(() => {
+    fetchAnalytics();
+  }, [fetchAnalytics]);
+
+  const timeToDone =
+    analytics?.median_time_to_done_secs == null
+      ? null
+      : Number(analytics.median_time_to_done_secs) * 1000;
+
+  return (
+    <Card>
+      <CardHeader>
+        <div className="flex items-start justify-between">
+          <div>
+            <CardTitle className="flex items-center">
+              <Timer className="mr-2 h-5 w-5" />
+              Execution Analytics
+            </CardTitle>
+            <CardDescription>
+              {analytics
+                ? `${formatDuration(analytics.agent_time_ms)} of agent time, ${String(analytics.tasks_done)} tasks done in a median of ${formatDuration(timeToDone)}, ${analytics.follow_ups_per_task === null ? 'no' : analytics.follow_ups_per_task.toFixed(1)} follow-ups per task`
+                : 'Time and outcomes of this project’s coding agent runs'}
+            </CardDescription>
+          </div>
+          <Button
+            variant="outline"
+            size="sm"
+            onClick={fetchAnalytics}
+            disabled={loading}
+          >
+            {loading ? (
+              <Loader2 className="h-4 w-4 animate-spin" />
+            ) : (
+              <RefreshCw className="h-4 w-4" />
+            )}
+          </Button>
+        </div>
+      </CardHeader>
+      <CardContent className="space-y-3">
+        {error && (
+          <Alert variant="destructive">
+            <AlertDescription>{error}</AlertDescription>
+          </Alert>
+        )}
+        {analytics && analytics.executors.length === 0 && (
+          <p className="text-sm text-muted-foreground">
+            No coding agent has run yet.
+          </p>
+        )}
+        {analytics?.executors.map((executor) => (
+          <div
+            key={executor.executor}
+            className="flex items-center justify-between gap-4 rounded-md border p-3"
+          >
+            <div className="min-w-0 space-y-1">
+              <p className="font-mono text-sm font-medium">
+                {executor.executor}
+              </p>
+              <p className="text-xs text-muted-foreground">
+                {String(executor.runs)} runs · median{' '}
+                {formatDuration(executor.median_duration_ms)} ·{' '}
+                {String(executor.merged_attempts)}/{String(executor.attempts)}{' '}
+                attempts merged ·{' '}
+                {executor.follow_ups_per_attempt.toFixed(1)} follow-ups per
+                attempt
+              </p>
+            </div>
+            <span
+              className="text-sm font-medium"
+              title={`${String(executor.completed)} completed, ${String(executor.failed)} failed, ${String(executor.killed)} stopped`}
+            >
+              {formatRate(executor.success_rate)}
+            </span>
+          </div>
+        ))}
+        {analytics?.tasks.slice(0, 10).map((task) => (
+          <div
+            key={task.task_id}
+            className="flex items-center justify-between gap-4 text-sm"
+          >
+            <span className="truncate">{task.title}</span>
+            <span
+              className="shrink-0 text-muted-foreground"
+              title={`${String(task.runs)} runs, ${String(task.follow_ups)} follow-ups`}
+            >
+              {formatDuration(task.agent_time_ms)}
+            </span>
+          </div>
+        ))}
+      </CardContent>
+    </Card>
+  );
+}
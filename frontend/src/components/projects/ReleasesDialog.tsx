@@ -0,0 +1,291 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Play, Trash2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Badge } from '@/components/ui/badge';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Textarea } from '@/components/ui/textarea';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import DisplayConversationEntry from '@/components/tasks/TaskDetails/DisplayConversationEntry';
+import { releasesApi } from '@/lib/api';
+import type {
+  NormalizedConversation,
+  Release,
+  ReleaseStatus,
+  ReleaseWithTasks,
+} from 'shared/types';
+
+interface ReleasesDialogProps {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+  projectId: string;
+  hasReleaseScript: boolean;
+}
+
+const statusVariant: Record<
+  ReleaseStatus,
+  'default' | 'secondary' | 'destructive' | 'outline'
+> = {
+  draft: 'outline',
+  running: 'secondary',
+  released: 'default',
+  failed: 'destructive',
+};
+
+export function ReleasesDialog({
+  open,
+  onOpenChange,
+  projectId,
+  hasReleaseScript,
+}: ReleasesDialogProps) {
+  const [releases, setReleases] = useState<Release[]>([]);
+  const [selected, setSelected] = useState<ReleaseWithTasks | null>(null);
+  const [conversation, setConversation] =
+    useState<NormalizedConversation | null>(null);
+  const [version, setVersion] = useState('');
+  const [notes, setNotes] = useState('');
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const fetchReleases = useCallback(async () => {
+    try {
+      setReleases(await releasesApi.getAll(projectId));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to load releases');
+    }
+  }, [projectId]);
+
+  const fetchSelected = useCallback(
+    async (releaseId: string) => {
+      try {
+        const [details, log] = await Promise.all([
+          releasesApi.get(projectId, releaseId),
+          releasesApi.getConversation(projectId, releaseId),
+        ]);
+        setSelected(details);
+        setConversation(log);
+      } catch (err) {
+        setError(err instanceof Error ? err.message : 'Failed to load release');
+      }
+    },
+    [projectId]
+  );
+
+  useEffect(() => {
+    if (open) {
+      fetchReleases();
+    } else {
+      setSelected(null);
+      setConversation(null);
+      setError(null);
+    }
+  }, [open, fetchReleases]);
+
+  // Follow the release script while it runs
+  const selectedId = selected?.release.id;
+  const isRunning = selected?.release.status === 'running';
+  useEffect(() => {
+    if (!selectedId || !isRunning) return;
+    const interval = setInterval(() => {
+      fetchSelected(selectedId);
+      fetchReleases();
+    }, 2000);
+    return () => clearInterval(interval);
+  }, [selectedId, isRunning, fetchSelected, fetchReleases]);
+
+  const handleCreate = async () => {
+    setBusy(true);
+    setError(null);
+    try {
+      const created = await releasesApi.create(projectId, {
+        version: version.trim(),
+        notes: notes.trim() || null,
+        task_ids: null,
+      });
+      setVersion('');
+      setNotes('');
+      await fetchReleases();
+      await fetchSelected(created.release.id);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to create release');
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const handleRun = async (release: Release) => {
+    setBusy(true);
+    setError(null);
+    try {
+      await releasesApi.run(projectId, release.id);
+      await fetchReleases();
+      await fetchSelected(release.id);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to start release');
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const handleDelete = async (release: Release) => {
+    if (!confirm(`Delete release ${release.version}?`)) return;
+    setError(null);
+    try {
+      await releasesApi.delete(projectId, release.id);
+      if (selected?.release.id === release.id) {
+        setSelected(null);
+        setConversation(null);
+      }
+      await fetchReleases();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to delete release');
+    }
+  };
+
+  const canRun = (release: Release) =>
+    hasReleaseScript &&
+    (release.status === 'draft' || release.status === 'failed');
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent className="sm:max-w-[800px] max-h-[85vh] overflow-y-auto">
+        <DialogHeader>
+          <DialogTitle>Releases</DialogTitle>
+          <DialogDescription>
+            A new release takes every merged task that hasn't been released
+            yet. Running it starts the project's release script; its tasks are
+            marked as released once the script succeeds.
+          </DialogDescription>
+        </DialogHeader>
+
+        {!hasReleaseScript && (
+          <Alert>
+            <AlertDescription>
+              Add a release script in the project settings to run releases.
+            </AlertDescription>
+          </Alert>
+        )}
+
+        <div className="grid grid-cols-[1fr_2fr] gap-3 items-end">
+          <div className="space-y-2">
+            <Label htmlFor="release-version">Version</Label>
+            <Input
+              id="release-version"
+              placeholder="v1.4.0"
+              value={version}
+              onChange={(e) => setVersion(e.target.value)}
+            />
+          </div>
+          <div className="space-y-2">
+            <Label htmlFor="release-notes">Notes (Optional)</Label>
+            <Textarea
+              id="release-notes"
+              rows={1}
+              value={notes}
+              onChange={(e) => setNotes(e.target.value)}
+            />
+          </div>
+        </div>
+        <div className="flex justify-end">
+          <Button onClick={handleCreate} disabled={busy || !version.trim()}>
+            Create release
+          </Button>
+        </div>
+
+        {error && (
+          <Alert variant="destructive">
+            <AlertDescription>{error}</AlertDescription>
+          </Alert>
+        )}
+
+        <div className="space-y-2">
+          {releases.length === 0 && (
+            <p className="text-sm text-muted-foreground">No releases yet.</p>
+          )}
+          {releases.map((release) => (
+            <div
+              key={release.id}
+              className={`flex items-center gap-3 rounded-md border px-3 py-2 cursor-pointer ${
+                selected?.release.id === release.id ? 'bg-muted' : ''
+              }`}
+              onClick={() => fetchSelected(release.id)}
+            >
+              <span className="font-medium">{release.version}</span>
+              <Badge variant={statusVariant[release.status]}>
+                {release.status}
+              </Badge>
+              <span className="text-xs text-muted-foreground flex-1">
+                {release.released_at
+                  ? new Date(release.released_at).toLocaleString()
+                  : new Date(release.created_at).toLocaleString()}
+              </span>
+              {canRun(release) && (
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  className="h-8 w-8 p-0"
+                  title="Run release script"
+                  disabled={busy}
+                  onClick={(e) => {
+                    e.stopPropagation();
+                    handleRun(release);
+                  }}
+                >
+                  <Play className="h-4 w-4" />
+                </Button>
+              )}
+              {(release.status === 'draft' || release.status === 'failed') && (
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  className="h-8 w-8 p-0"
+                  title="Delete release"
+                  onClick={(e) => {
+                    e.stopPropagation();
+                    handleDelete(release);
+                  }}
+                >
+                  <Trash2 className="h-4 w-4" />
+                </Button>
+              )}
+            </div>
+          ))}
+        </div>
+
+        {selected && (
+          <div className="space-y-3 border-t pt-3">
+            <div>
+              <h3 className="text-sm font-semibold mb-1">
+                Tasks in {selected.release.version}
+              </h3>
+              <ul className="text-sm list-disc pl-5">
+                {selected.tasks.map((task) => (
+                  <li key={task.task_id}>{task.title}</li>
+                ))}
+              </ul>
+            </div>
+            {selected.release.status !== 'draft' && conversation && (
+              <div className="space-y-2">
+                <h3 className="text-sm font-semibold">Release script</h3>
+                {conversation.entries.map((entry, index) => (
+                  <DisplayConversationEntry
+                    key={index}
+                    entry={entry}
+                    index={index}
+                  />
+                ))}
+              </div>
+            )}
+          </div>
+        )}
+      </DialogContent>
+    </Dialog>
+  );
+}
@@ -0,0 +1,104 @@
+import { useCallback, useEffect, useState } from 'react';
+import { FileCode, Loader2, RefreshCw } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import {
+  Card,
+  CardContent,
+  CardDescription,
+  CardHeader,
+  CardTitle,
+} from '@/components/ui/card';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { projectsApi } from '@/lib/api';
+import type { RepoAnalysisSummary } from 'shared/types';
+
+interface RepoAnalysisCardProps {
+  projectId: string;
+}
+
+export function RepoAnalysisCard({ projectId }: RepoAnalysisCardProps) {
+  const [summary, setSummary] = useState<RepoAnalysisSummary | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState('');
+
+  const fetchAnalysis = useCallback(async () => {
+    setLoading(true);
+    setError('');
+    try {
+      setSummary(await projectsApi.getAnalysis(projectId));
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to analyze repository'
+      );
+    } finally {
+      setLoading(false);
+    }
+  }, [projectId]);
+
+  useEffect(() => {
+    fetchAnalysis();
+  }, [fetchAnalysis]);
+
+  const totalFiles = summary?.files ?? 0;
+
+  return (
+    <Card>
+      <CardHeader>
+        <div className="flex items-start justify-between">
+          <div>
+            <CardTitle className="flex items-center">
+              <FileCode className="mr-2 h-5 w-5" />
+              Repository Map
+            </CardTitle>
+            <CardDescription>
+              {summary
+                ? `${summary.files} tracked files, ${summary.symbols} symbols. Agents get it through {{repo_map}} or the get_repo_map tool.`
+                : 'Files and symbols shared with every attempt’s agent'}
+            </CardDescription>
+          </div>
+          <Button
+            variant="outline"
+            size="sm"
+            onClick={fetchAnalysis}
+            disabled={loading}
+          >
+            {loading ? (
+              <Loader2 className="h-4 w-4 animate-spin" />
+            ) : (
+              <RefreshCw className="h-4 w-4" />
+            )}
+          </Button>
+        </div>
+      </CardHeader>
+      <CardContent className="space-y-2">
+        {error && (
+          <Alert variant="destructive">
+            <AlertDescription>{error}</AlertDescription>
+          </Alert>
+        )}
+        {summary?.languages.slice(0, 6).map((stat) => (
+          <div key={stat.language} className="flex items-center gap-3 text-sm">
+            <span className="w-28 truncate">{stat.language}</span>
+            <div className="h-2 flex-1 rounded bg-muted">
+              <div
+                className="h-2 rounded bg-primary"
+                style={{
+                  width: `${totalFiles ? (stat.files / totalFiles) * 100 : 0}%`,
+                }}
+              />
+            </div>
+            <span className="w-16 text-right text-xs text-muted-foreground">
+              {stat.files} files
+            </span>
+          </div>
+        ))}
+        {summary && (
+          <p className="text-xs text-muted-foreground">
+            Last refresh reused {summary.last_refresh.reused} files and parsed{' '}
+            {summary.last_refresh.parsed}.
+          </p>
+        )}
+      </CardContent>
+    </Card>
+  );
+}
@@ -0,0 +1,203 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Eraser, HardDrive, Loader2, RefreshCw, Trash2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import {
+  Card,
+  CardContent,
+  CardDescription,
+  CardHeader,
+  CardTitle,
+} from '@/components/ui/card';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { attemptsApi, projectsApi } from '@/lib/api';
+import type { WorktreeUsage } from 'shared/types';
+
+function formatBytes(value: bigint | number): string {
+  const bytes = Number(value);
+  const units = ['B', 'KB', 'MB', 'GB', 'TB'];
+  let size = bytes;
+  let unit = 0;
+  while (size >= 1024 && unit < units.length - 1) {
+    size /= 1024;
+    unit++;
+  }
+  return `${size.toFixed(unit === 0 ? 0 : 1)} ${units[unit]}`;
+}
+
+interface WorktreeUsageCardProps {
+  projectId: string;
+}
+
+export function WorktreeUsageCard({ projectId }: WorktreeUsageCardProps) {
+  const [usages, setUsages] = useState<WorktreeUsage[] | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [cleaning, setCleaning] = useState<string | null>(null);
+  const [pruning, setPruning] = useState(false);
+  const [error, setError] = useState('');
+
+  const fetchUsage = useCallback(async () => {
+    setLoading(true);
+    setError('');
+    try {
+      setUsages(await projectsApi.getWorktreeUsage(projectId));
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to measure worktrees'
+      );
+    } finally {
+      setLoading(false);
+    }
+  }, [projectId]);
+
+  useEffect(() => {
+    fetchUsage();
+  }, [fetchUsage]);
+
+  const handleClean = async (usage: WorktreeUsage) => {
+    setCleaning(usage.attempt_id);
+    setError('');
+    try {
+      await attemptsApi.cleanArtifacts(
+        projectId,
+        usage.task_id,
+        usage.attempt_id
+      );
+      await fetchUsage();
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to clean build artifacts'
+      );
+    } finally {
+      setCleaning(null);
+    }
+  };
+
+  const handlePrune = async () => {
+    setPruning(true);
+    setError('');
+    try {
+      await projectsApi.pruneWorktrees(projectId);
+      await fetchUsage();
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to prune worktrees'
+      );
+    } finally {
+      setPruning(false);
+    }
+  };
+
+  const total = (usages ?? []).reduce(
+    (sum, usage) => sum + Number(usage.total_bytes),
+    0
+  );
+  const artifacts = (usages ?? []).reduce(
+    (sum, usage) => sum + Number(usage.artifact_bytes),
+    0
+  );
+
+  return (
+    <Card>
+      <CardHeader>
+        <div className="flex items-start justify-between">
+          <div>
+            <CardTitle className="flex items-center">
+              <HardDrive className="mr-2 h-5 w-5" />
+              Worktree Disk Usage
+            </CardTitle>
+            <CardDescription>
+              {usages
+                ? `${formatBytes(total)} across ${usages.length} worktrees, ${formatBytes(artifacts)} of it build artifacts and dependencies`
+                : 'Space taken by the worktrees of this project’s attempts'}
+            </CardDescription>
+          </div>
+          <div className="flex gap-2">
+            <Button
+              variant="outline"
+              size="sm"
+              onClick={handlePrune}
+              disabled={pruning || loading}
+              title="Delete the worktrees of merged attempts and done tasks"
+            >
+              {pruning ? (
+                <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+              ) : (
+                <Eraser className="mr-2 h-4 w-4" />
+              )}
+              Prune finished
+            </Button>
+            <Button
+              variant="outline"
+              size="sm"
+              onClick={fetchUsage}
+              disabled={loading}
+            >
+              {loading ? (
+                <Loader2 className="h-4 w-4 animate-spin" />
+              ) : (
+                <RefreshCw className="h-4 w-4" />
+              )}
+            </Button>
+          </div>
+        </div>
+      </CardHeader>
+      <CardContent className="space-y-3">
+        {error && (
+          <Alert variant="destructive">
+            <AlertDescription>{error}</AlertDescription>
+          </Alert>
+        )}
+        {usages && usages.length === 0 && (
+          <p className="text-sm text-muted-foreground">
+            No attempt worktrees on disk.
+          </p>
+        )}
+        {usages?.map((usage) => (
+          <div
+            key={usage.attempt_id}
+            className="flex items-center justify-between gap-4 rounded-md border p-3"
+          >
+            <div className="min-w-0 space-y-1">
+              <p className="truncate text-sm font-medium">{usage.task_title}</p>
+              <p className="truncate font-mono text-xs text-muted-foreground">
+                {usage.branch}
+              </p>
+              {usage.exists ? (
+                <p className="text-xs text-muted-foreground">
+                  {formatBytes(usage.total_bytes)} total
+                  {usage.artifacts.length > 0 &&
+                    ` · ${formatBytes(usage.artifact_bytes)} in ${usage.artifacts
+                      .slice(0, 3)
+                      .map((artifact) => artifact.path)
+                      .join(', ')}${usage.artifacts.length > 3 ? ', …' : ''}`}
+                </p>
+              ) : (
+                <p className="text-xs text-muted-foreground">
+                  Worktree missing on disk
+                </p>
+              )}
+            </div>
+            <Button
+              variant="outline"
+              size="sm"
+              onClick={() => handleClean(usage)}
+              disabled={
+                !usage.exists ||
+                usage.artifacts.length === 0 ||
+                cleaning !== null
+              }
+              title="Delete build output and dependencies, keeping the attempt's files and git state"
+            >
+              {cleaning === usage.attempt_id ? (
+                <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+              ) : (
+                <Trash2 className="mr-2 h-4 w-4" />
+              )}
+              Clean
+            </Button>
+          </div>
+        ))}
+      </CardContent>
+    </Card>
+  );
+}
@@ -0,0 +1,308 @@
+import { useEffect, useState } from 'react';
+import { ArrowDown, ArrowUp, Loader2, Plus, Trash2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { boardColumnsApi } from '@/lib/api';
+import type {
+  BoardColumn,
+  BoardColumnInput,
+  BoardCondition,
+  TaskStatus,
+} from 'shared/types';
+
+interface BoardColumnsProps {
+  projectId: string;
+}
+
+// A column being edited; key tells rows apart before new ones have an id
+interface ColumnDraft {
+  key: string;
+  id: string | null;
+  name: string;
+  status: TaskStatus;
+  wipLimit: string;
+  conditions: BoardCondition[];
+}
+
+const STATUS_LABELS: Record<TaskStatus, string> = {
+  todo: 'To Do',
+  queued: 'Queued',
+  inprogress: 'In Progress',
+  planreview: 'Plan Review',
+  inreview: 'In Review',
+  done: 'Done',
+  cancelled: 'Cancelled',
+};
+
+const CONDITION_LABELS: Record<BoardCondition, string> = {
+  execution_running: 'Execution running',
+  execution_failed: 'Execution failed',
+  verification_failed: 'Verification failed',
+  pr_open: 'Pull request open',
+  merged: 'Merged',
+};
+
+function toDraft(column: BoardColumn): ColumnDraft {
+  return {
+    key: column.id,
+    id: column.id,
+    name: column.name,
+    status: column.status,
+    wipLimit: column.wip_limit === null ? '' : String(column.wip_limit),
+    conditions: column.conditions,
+  };
+}
+
+function newDraft(name: string, status: TaskStatus): ColumnDraft {
+  return {
+    key: crypto.randomUUID(),
+    id: null,
+    name,
+    status,
+    wipLimit: '',
+    conditions: [],
+  };
+}
+
+export function BoardColumns({ projectId }: BoardColumnsProps) {
+  const [drafts, setDrafts] = useState<ColumnDraft[]>([]);
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [message, setMessage] = useState<string | null>(null);
+
+  useEffect(() => {
+    setLoading(true);
+    boardColumnsApi
+      .list(projectId)
+      .then((columns) => setDrafts(columns.map(toDraft)))
+      .catch((err) => setError(err.message))
+      .finally(() => setLoading(false));
+  }, [projectId]);
+
+  const update = (key: string, change: Partial<ColumnDraft>) => {
+    setDrafts((current) =>
+      current.map((draft) =>
+        draft.key === key ? { ...draft, ...change } : draft
+      )
+    );
+  };
+
+  const move = (index: number, offset: number) => {
+    setDrafts((current) => {
+      const next = [...current];
+      const [draft] = next.splice(index, 1);
+      next.splice(index + offset, 0, draft);
+      return next;
+    });
+  };
+
+  const toggleCondition = (draft: ColumnDraft, condition: BoardCondition) => {
+    update(draft.key, {
+      conditions: draft.conditions.includes(condition)
+        ? draft.conditions.filter((c) => c !== condition)
+        : [...draft.conditions, condition],
+    });
+  };
+
+  const save = async (columns: ColumnDraft[]) => {
+    const input: BoardColumnInput[] = [];
+    for (const draft of columns) {
+      const limit =
+        draft.wipLimit.trim() === '' ? null : parseInt(draft.wipLimit);
+      if (limit !== null && (isNaN(limit) || limit < 1)) {
+        setError(
+          `The WIP limit of ${draft.name || 'a column'} must be 1 or more`
+        );
+        return;
+      }
+      input.push({
+        id: draft.id,
+        name: draft.name,
+        status: draft.status,
+        wip_limit: limit === null ? null : BigInt(limit),
+        conditions: draft.conditions,
+      });
+    }
+    setSaving(true);
+    setError(null);
+    setMessage(null);
+    try {
+      const saved = await boardColumnsApi.update(projectId, input);
+      setDrafts(saved.map(toDraft));
+      setMessage(
+        saved.length > 0
+          ? 'Columns saved.'
+          : 'The board shows a column per status again.'
+      );
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to save columns');
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  if (loading) {
+    return (
+      <div className="flex items-center justify-center py-4">
+        <Loader2 className="h-6 w-6 animate-spin" />
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      <p className="text-sm text-muted-foreground">
+        Lay out the board with your own columns. A task dragged into a column
+        takes its status; otherwise it shows in the first column with a
+        condition it meets, or else the first with its status. Moving a task
+        into a column at its WIP limit is refused. Tasks you moved into a
+        column follow it when its status changes, and go back to their status
+        when it's removed.
+      </p>
+
+      {drafts.length === 0 && (
+        <Button
+          variant="outline"
+          onClick={() =>
+            setDrafts(
+              (Object.keys(STATUS_LABELS) as TaskStatus[]).map((status) =>
+                newDraft(STATUS_LABELS[status], status)
+              )
+            )
+          }
+        >
+          Start from the status columns
+        </Button>
+      )}
+
+      {drafts.map((draft, index) => (
+        <div key={draft.key} className="space-y-2 rounded-md border p-2">
+          <div className="flex items-center gap-2">
+            <Input
+              placeholder="Column name"
+              value={draft.name}
+              onChange={(e) => update(draft.key, { name: e.target.value })}
+            />
+            <Select
+              value={draft.status}
+              onValueChange={(status) =>
+                update(draft.key, { status: status as TaskStatus })
+              }
+            >
+              <SelectTrigger className="w-36" title="Status of tasks moved in">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                {Object.entries(STATUS_LABELS).map(([value, label]) => (
+                  <SelectItem key={value} value={value}>
+                    {label}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+            <Input
+              type="number"
+              min={1}
+              placeholder="WIP"
+              title="WIP limit"
+              value={draft.wipLimit}
+              onChange={(e) => update(draft.key, { wipLimit: e.target.value })}
+              className="w-20"
+            />
+            <Button
+              variant="ghost"
+              size="sm"
+              disabled={index === 0}
+              onClick={() => move(index, -1)}
+            >
+              <ArrowUp className="h-4 w-4" />
+            </Button>
+            <Button
+              variant="ghost"
+              size="sm"
+              disabled={index === drafts.length - 1}
+              onClick={() => move(index, 1)}
+            >
+              <ArrowDown className="h-4 w-4" />
+            </Button>
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={() =>
+                setDrafts((current) =>
+                  current.filter((d) => d.key !== draft.key)
+                )
+              }
+            >
+              <Trash2 className="h-4 w-4" />
+            </Button>
+          </div>
+          <div className="flex flex-wrap gap-x-4 gap-y-1">
+            {(Object.keys(CONDITION_LABELS) as BoardCondition[]).map(
+              (condition) => (
+                <div key={condition} className="flex items-center gap-1">
+                  <Checkbox
+                    id={`${draft.key}-${condition}`}
+                    checked={draft.conditions.includes(condition)}
+                    onCheckedChange={() => toggleCondition(draft, condition)}
+                  />
+                  <Label
+                    htmlFor={`${draft.key}-${condition}`}
+                    className="text-xs cursor-pointer"
+                  >
+                    {CONDITION_LABELS[condition]}
+                  </Label>
+                </div>
+              )
+            )}
+          </div>
+        </div>
+      ))}
+
+      {error && (
+        <Alert variant="destructive">
+          <AlertDescription>{error}</AlertDescription>
+        </Alert>
+      )}
+      {message && (
+        <Alert>
+          <AlertDescription>{message}</AlertDescription>
+        </Alert>
+      )}
+
+      <div className="flex justify-between">
+        <Button
+          variant="outline"
+          onClick={() =>
+            setDrafts((current) => [...current, newDraft('', 'todo')])
+          }
+        >
+          <Plus className="h-4 w-4 mr-2" />
+          Add column
+        </Button>
+        <div className="flex gap-2">
+          <Button variant="outline" disabled={saving} onClick={() => save([])}>
+            Use status columns
+          </Button>
+          <Button
+            disabled={saving || drafts.length === 0}
+            onClick={() => save(drafts)}
+          >
+            {saving ? 'Saving...' : 'Save columns'}
+          </Button>
+        </div>
+      </div>
+    </div>
+  );
+}
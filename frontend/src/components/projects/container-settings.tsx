@@ -0,0 +1,118 @@
+import { useEffect, useState } from 'react';
+import { Loader2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { projectContainerApi } from '@/lib/api';
+import type { ProjectContainer } from 'shared/types';
+
+interface ContainerSettingsProps {
+  projectId: string;
+}
+
+export function ContainerSettings({ projectId }: ContainerSettingsProps) {
+  const [container, setContainer] = useState<ProjectContainer | null>(null);
+  const [image, setImage] = useState('');
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setLoading(true);
+    projectContainerApi
+      .get(projectId)
+      .then((existing) => {
+        setContainer(existing);
+        setImage(existing?.image ?? '');
+      })
+      .catch((err) => setError(err.message))
+      .finally(() => setLoading(false));
+  }, [projectId]);
+
+  const handleSave = async () => {
+    setSaving(true);
+    setError(null);
+    setMessage(null);
+    try {
+      const saved = await projectContainerApi.save(projectId, {
+        image: image.trim(),
+      });
+      setContainer(saved);
+      setMessage(`Attempts will run in ${saved.image}.`);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to save image');
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleReset = async () => {
+    setError(null);
+    setMessage(null);
+    try {
+      await projectContainerApi.reset(projectId);
+      setContainer(null);
+      setImage('');
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to reset image');
+    }
+  };
+
+  if (loading) {
+    return (
+      <div className="flex items-center justify-center py-4">
+        <Loader2 className="h-6 w-6 animate-spin" />
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      <p className="text-sm text-muted-foreground">
+        Run this project's coding agents and setup and cleanup scripts in a
+        Docker container, with the attempt's worktree mounted. Dev servers
+        still run on this machine. Without an image, everything runs on this
+        machine.
+      </p>
+
+      <div className="space-y-2">
+        <Label htmlFor="container-image">Image</Label>
+        <Input
+          id="container-image"
+          placeholder="e.g. node:20"
+          value={image}
+          onChange={(e) => setImage(e.target.value)}
+        />
+        <p className="text-sm text-muted-foreground">
+          Pulled when it isn't already present. It needs a shell and the coding
+          agent's CLI; pass the agent's API key in the project's environment
+          variables.
+        </p>
+      </div>
+
+      {error && (
+        <Alert variant="destructive">
+          <AlertDescription>{error}</AlertDescription>
+        </Alert>
+      )}
+      {message && (
+        <Alert>
+          <AlertDescription>{message}</AlertDescription>
+        </Alert>
+      )}
+
+      <div className="flex justify-end gap-2">
+        {container && (
+          <Button variant="outline" onClick={handleReset}>
+            Reset
+          </Button>
+        )}
+        <Button onClick={handleSave} disabled={saving || !image.trim()}>
+          {saving ? 'Saving...' : 'Save'}
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,204 @@
+import { useEffect, useState } from 'react';
+import { Loader2, Trash2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { customFieldsApi } from '@/lib/api';
+import type { CustomField, CustomFieldType } from 'shared/types';
+
+interface CustomFieldsProps {
+  projectId: string;
+}
+
+const FIELD_TYPE_LABELS: Record<CustomFieldType, string> = {
+  text: 'Text',
+  number: 'Number',
+  boolean: 'Yes / no',
+  date: 'Date',
+  select: 'Select',
+};
+
+export function CustomFields({ projectId }: CustomFieldsProps) {
+  const [fields, setFields] = useState<CustomField[]>([]);
+  const [name, setName] = useState('');
+  const [fieldType, setFieldType] = useState<CustomFieldType>('text');
+  const [options, setOptions] = useState('');
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setLoading(true);
+    customFieldsApi
+      .list(projectId)
+      .then(setFields)
+      .catch((err) => setError(err.message))
+      .finally(() => setLoading(false));
+  }, [projectId]);
+
+  const handleEdit = (field: CustomField) => {
+    setName(field.name);
+    setFieldType(field.field_type);
+    setOptions(field.options.join(', '));
+  };
+
+  const handleSave = async () => {
+    setSaving(true);
+    setError(null);
+    try {
+      const saved = await customFieldsApi.upsert(projectId, {
+        name: name.trim(),
+        field_type: fieldType,
+        options:
+          fieldType === 'select'
+            ? options.split(',').filter((option) => option.trim() !== '')
+            : [],
+      });
+      setFields((current) => {
+        const index = current.findIndex((f) => f.name === saved.name);
+        return index === -1
+          ? [...current, saved]
+          : current.map((f, i) => (i === index ? saved : f));
+      });
+      setName('');
+      setFieldType('text');
+      setOptions('');
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to save field');
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleDelete = async (fieldName: string) => {
+    setError(null);
+    try {
+      await customFieldsApi.delete(projectId, fieldName);
+      setFields((current) => current.filter((f) => f.name !== fieldName));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to delete field');
+    }
+  };
+
+  if (loading) {
+    return (
+      <div className="flex items-center justify-center py-4">
+        <Loader2 className="h-6 w-6 animate-spin" />
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      <p className="text-sm text-muted-foreground">
+        Fields every task of this project can have a value for. Prompt
+        templates insert a task's value with{' '}
+        <code>{'{{fields.<name>}}'}</code>. Changing a field's type clears
+        values that no longer fit it.
+      </p>
+
+      {fields.length > 0 && (
+        <div className="space-y-2">
+          {fields.map((field) => (
+            <div
+              key={field.name}
+              className="flex items-center justify-between gap-2 rounded-md border p-2"
+            >
+              <button
+                type="button"
+                className="min-w-0 text-left"
+                onClick={() => handleEdit(field)}
+              >
+                <div className="text-sm font-medium font-mono">
+                  {field.name}
+                </div>
+                <div className="text-xs text-muted-foreground truncate">
+                  {FIELD_TYPE_LABELS[field.field_type]}
+                  {field.field_type === 'select' &&
+                    `: ${field.options.join(', ')}`}
+                </div>
+              </button>
+              <Button
+                variant="ghost"
+                size="sm"
+                onClick={() => handleDelete(field.name)}
+              >
+                <Trash2 className="h-4 w-4" />
+              </Button>
+            </div>
+          ))}
+        </div>
+      )}
+
+      <div className="space-y-2">
+        <Label htmlFor="custom-field-name">Name</Label>
+        <Input
+          id="custom-field-name"
+          placeholder="e.g. story_points"
+          value={name}
+          onChange={(e) => setName(e.target.value)}
+        />
+      </div>
+
+      <div className="space-y-2">
+        <Label htmlFor="custom-field-type">Type</Label>
+        <Select
+          value={fieldType}
+          onValueChange={(value) => setFieldType(value as CustomFieldType)}
+        >
+          <SelectTrigger id="custom-field-type">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            {Object.entries(FIELD_TYPE_LABELS).map(([value, label]) => (
+              <SelectItem key={value} value={value}>
+                {label}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+      </div>
+
+      {fieldType === 'select' && (
+        <div className="space-y-2">
+          <Label htmlFor="custom-field-options">
+            Options (comma separated)
+          </Label>
+          <Input
+            id="custom-field-options"
+            placeholder="e.g. frontend, backend, infra"
+            value={options}
+            onChange={(e) => setOptions(e.target.value)}
+          />
+        </div>
+      )}
+
+      {error && (
+        <Alert variant="destructive">
+          <AlertDescription>{error}</AlertDescription>
+        </Alert>
+      )}
+
+      <div className="flex justify-end">
+        <Button
+          onClick={handleSave}
+          disabled={
+            saving ||
+            !name.trim() ||
+            (fieldType === 'select' && !options.trim())
+          }
+        >
+          {saving ? 'Saving...' : 'Save field'}
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,195 @@
+import { useEffect, useState } from 'react';
+import { Loader2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { gitHostApi } from '@/lib/api';
+import type { GitHostKind, ProjectGitHost } from 'shared/types';
+
+const HOSTS: {
+  kind: GitHostKind;
+  label: string;
+  urlLabel: string;
+  urlPlaceholder: string;
+  tokenHelp: string;
+}[] = [
+  {
+    kind: 'github',
+    label: 'GitHub',
+    urlLabel: 'GitHub Enterprise API URL (optional)',
+    urlPlaceholder: 'https://github.example.com/api/v3',
+    tokenHelp:
+      'Leave blank to use the account you signed in with in Settings.',
+  },
+  {
+    kind: 'gitlab',
+    label: 'GitLab',
+    urlLabel: 'Instance URL (optional)',
+    urlPlaceholder: 'https://gitlab.example.com',
+    tokenHelp:
+      'A personal or project access token with the api and write_repository scopes.',
+  },
+  {
+    kind: 'bitbucket',
+    label: 'Bitbucket Cloud',
+    urlLabel: 'API URL (optional)',
+    urlPlaceholder: 'https://api.bitbucket.org/2.0',
+    tokenHelp:
+      'A repository or workspace access token with pull request and repository write access.',
+  },
+];
+
+interface GitHostSettingsProps {
+  projectId: string;
+}
+
+export function GitHostSettings({ projectId }: GitHostSettingsProps) {
+  const [host, setHost] = useState<ProjectGitHost | null>(null);
+  const [kind, setKind] = useState<GitHostKind>('github');
+  const [baseUrl, setBaseUrl] = useState('');
+  const [token, setToken] = useState('');
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setLoading(true);
+    gitHostApi
+      .get(projectId)
+      .then((existing) => {
+        setHost(existing);
+        setKind(existing?.kind ?? 'github');
+        setBaseUrl(existing?.base_url ?? '');
+      })
+      .catch((err) => setError(err.message))
+      .finally(() => setLoading(false));
+  }, [projectId]);
+
+  const selected = HOSTS.find((h) => h.kind === kind) ?? HOSTS[0];
+
+  const handleSave = async () => {
+    setSaving(true);
+    setError(null);
+    setMessage(null);
+    try {
+      const saved = await gitHostApi.save(projectId, {
+        kind,
+        base_url: baseUrl.trim() || null,
+        token: token.trim() || null,
+      });
+      setHost(saved);
+      setToken('');
+      setMessage(`Pull requests will be opened on ${selected.label}.`);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to save git host');
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleReset = async () => {
+    setError(null);
+    setMessage(null);
+    try {
+      await gitHostApi.reset(projectId);
+      setHost(null);
+      setKind('github');
+      setBaseUrl('');
+      setToken('');
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to reset git host');
+    }
+  };
+
+  if (loading) {
+    return (
+      <div className="flex items-center justify-center py-4">
+        <Loader2 className="h-6 w-6 animate-spin" />
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      <p className="text-sm text-muted-foreground">
+        Where this project's pull requests are opened and its branches pushed.
+        Without a host set here, GitHub is used with your account's token, or
+        GitLab and Bitbucket when the origin remote points at them.
+      </p>
+
+      <div className="space-y-2">
+        <Label htmlFor="git-host-kind">Host</Label>
+        <Select
+          value={kind}
+          onValueChange={(value) => setKind(value as GitHostKind)}
+        >
+          <SelectTrigger id="git-host-kind">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            {HOSTS.map((option) => (
+              <SelectItem key={option.kind} value={option.kind}>
+                {option.label}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+      </div>
+
+      <div className="space-y-2">
+        <Label htmlFor="git-host-url">{selected.urlLabel}</Label>
+        <Input
+          id="git-host-url"
+          placeholder={selected.urlPlaceholder}
+          value={baseUrl}
+          onChange={(e) => setBaseUrl(e.target.value)}
+        />
+      </div>
+
+      <div className="space-y-2">
+        <Label htmlFor="git-host-token">Access token</Label>
+        <Input
+          id="git-host-token"
+          type="password"
+          placeholder={
+            host?.has_token ? 'Saved; enter a new one to replace it' : ''
+          }
+          value={token}
+          onChange={(e) => setToken(e.target.value)}
+        />
+        <p className="text-sm text-muted-foreground">{selected.tokenHelp}</p>
+      </div>
+
+      {error && (
+        <Alert variant="destructive">
+          <AlertDescription>{error}</AlertDescription>
+        </Alert>
+      )}
+      {message && (
+        <Alert>
+          <AlertDescription>{message}</AlertDescription>
+        </Alert>
+      )}
+
+      <div className="flex justify-end gap-2">
+        {host && (
+          <Button variant="outline" onClick={handleReset}>
+            Reset
+          </Button>
+        )}
+        <Button onClick={handleSave} disabled={saving}>
+          {saving ? 'Saving...' : 'Save'}
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,154 @@
+import { useEffect, useState } from 'react';
+import { Loader2, RefreshCw } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { githubIssuesApi } from '@/lib/api';
+import type { GitHubIssueLink } from 'shared/types';
+
+interface GitHubIssueSyncProps {
+  projectId: string;
+}
+
+export function GitHubIssueSync({ projectId }: GitHubIssueSyncProps) {
+  const [link, setLink] = useState<GitHubIssueLink | null>(null);
+  const [label, setLabel] = useState('');
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [syncing, setSyncing] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setLoading(true);
+    githubIssuesApi
+      .get(projectId)
+      .then((existing) => {
+        setLink(existing);
+        setLabel(existing?.label ?? '');
+      })
+      .catch((err) => setError(err.message))
+      .finally(() => setLoading(false));
+  }, [projectId]);
+
+  const handleSave = async () => {
+    setSaving(true);
+    setError(null);
+    setMessage(null);
+    try {
+      const saved = await githubIssuesApi.link(projectId, {
+        label: label.trim(),
+      });
+      setLink(saved);
+      setMessage(
+        `Importing issues labeled "${saved.label}" from ${saved.repo_owner}/${saved.repo_name}. They will appear shortly.`
+      );
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to link issues');
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleSync = async () => {
+    setSyncing(true);
+    setError(null);
+    setMessage(null);
+    try {
+      const result = await githubIssuesApi.sync(projectId);
+      setMessage(
+        `Synced: ${result.created} created, ${result.updated} updated, ${result.closed} issues closed.`
+      );
+      setLink(await githubIssuesApi.get(projectId));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Sync failed');
+    } finally {
+      setSyncing(false);
+    }
+  };
+
+  const handleUnlink = async () => {
+    setError(null);
+    setMessage(null);
+    try {
+      await githubIssuesApi.unlink(projectId);
+      setLink(null);
+      setLabel('');
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to unlink issues');
+    }
+  };
+
+  if (loading) {
+    return (
+      <div className="flex items-center justify-center py-4">
+        <Loader2 className="h-6 w-6 animate-spin" />
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      <p className="text-sm text-muted-foreground">
+        Import open issues with a label from this project's GitHub repository
+        as tasks. Edits to an issue's title or body carry over to its task, and
+        the issue is closed once its task is done. Add a webhook for the Issues
+        event to sync right away instead of every minute.
+      </p>
+
+      <div className="space-y-2">
+        <Label htmlFor="github-issue-label">Label</Label>
+        <Input
+          id="github-issue-label"
+          placeholder="vibe-kanban"
+          value={label}
+          onChange={(e) => setLabel(e.target.value)}
+        />
+      </div>
+
+      {link && (
+        <p className="text-sm text-muted-foreground">
+          {`${link.repo_owner}/${link.repo_name} · `}
+          {link.last_synced_at
+            ? `Last synced ${new Date(link.last_synced_at).toLocaleString()}`
+            : 'Not synced yet'}
+        </p>
+      )}
+      {link?.last_error && !error && (
+        <Alert variant="destructive">
+          <AlertDescription>{link.last_error}</AlertDescription>
+        </Alert>
+      )}
+      {error && (
+        <Alert variant="destructive">
+          <AlertDescription>{error}</AlertDescription>
+        </Alert>
+      )}
+      {message && (
+        <Alert>
+          <AlertDescription>{message}</AlertDescription>
+        </Alert>
+      )}
+
+      <div className="flex justify-end gap-2">
+        {link && (
+          <>
+            <Button variant="outline" onClick={handleUnlink}>
+              Unlink
+            </Button>
+            <Button variant="outline" onClick={handleSync} disabled={syncing}>
+              <RefreshCw
+                className={`h-4 w-4 mr-2 ${syncing ? 'animate-spin' : ''}`}
+              />
+              Sync now
+            </Button>
+          </>
+        )}
+        <Button onClick={handleSave} disabled={saving || !label.trim()}>
+          {saving ? 'Saving...' : link ? 'Save' : 'Link'}
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,179 @@
+import { useEffect, useState } from 'react';
+import { Loader2, RefreshCw } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { githubProjectsApi } from '@/lib/api';
+import type {
+  GitHubProjectLink,
+  StatusOption,
+  TaskStatusNames,
+} from 'shared/types';
+import { EMPTY_STATUS_NAMES, StatusNamesFields } from './status-names-fields';
+
+interface GitHubProjectSyncProps {
+  projectId: string;
+}
+
+export function GitHubProjectSync({ projectId }: GitHubProjectSyncProps) {
+  const [link, setLink] = useState<GitHubProjectLink | null>(null);
+  const [projectUrl, setProjectUrl] = useState('');
+  const [mapping, setMapping] = useState<TaskStatusNames>(EMPTY_STATUS_NAMES);
+  const [options, setOptions] = useState<StatusOption[]>([]);
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [syncing, setSyncing] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setLoading(true);
+    githubProjectsApi
+      .get(projectId)
+      .then((existing) => {
+        setLink(existing);
+        setProjectUrl(existing?.project_url ?? '');
+        setMapping(existing?.status_mapping ?? EMPTY_STATUS_NAMES);
+      })
+      .catch((err) => setError(err.message))
+      .finally(() => setLoading(false));
+  }, [projectId]);
+
+  const handleSave = async () => {
+    setSaving(true);
+    setError(null);
+    setMessage(null);
+    try {
+      const result = await githubProjectsApi.link(projectId, {
+        project_url: projectUrl.trim(),
+        status_mapping: mapping,
+      });
+      setLink(result.link);
+      setOptions(result.board.status_options);
+      setMessage(
+        result.board.status_field_id
+          ? `Linked to "${result.board.title}". Tasks will appear there shortly.`
+          : `Linked to "${result.board.title}", but it has no Status field, so only titles and descriptions are synced.`
+      );
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to link project');
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleSync = async () => {
+    setSyncing(true);
+    setError(null);
+    setMessage(null);
+    try {
+      const result = await githubProjectsApi.sync(projectId);
+      setMessage(
+        `Synced: ${result.added} added, ${result.updated} updated, ${result.removed} removed.`
+      );
+      setLink(await githubProjectsApi.get(projectId));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Sync failed');
+    } finally {
+      setSyncing(false);
+    }
+  };
+
+  const handleUnlink = async () => {
+    setError(null);
+    setMessage(null);
+    try {
+      await githubProjectsApi.unlink(projectId);
+      setLink(null);
+      setOptions([]);
+      setProjectUrl('');
+      setMapping(EMPTY_STATUS_NAMES);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to unlink project');
+    }
+  };
+
+  if (loading) {
+    return (
+      <div className="flex items-center justify-center py-4">
+        <Loader2 className="h-6 w-6 animate-spin" />
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      <p className="text-sm text-muted-foreground">
+        Mirror this project's tasks into a GitHub Projects board as draft
+        issues. The board's Status field follows each task as it moves. Changes
+        made on GitHub are overwritten on the next sync.
+      </p>
+
+      <div className="space-y-2">
+        <Label htmlFor="github-project-url">Project URL</Label>
+        <Input
+          id="github-project-url"
+          placeholder="https://github.com/orgs/acme/projects/1"
+          value={projectUrl}
+          onChange={(e) => setProjectUrl(e.target.value)}
+        />
+      </div>
+
+      <div className="space-y-2">
+        <Label>Status mapping</Label>
+        <p className="text-sm text-muted-foreground">
+          Leave blank to use the Status option with the same name.
+        </p>
+        <StatusNamesFields
+          id="github-status-options"
+          value={mapping}
+          options={options.map((option) => option.name)}
+          onChange={setMapping}
+        />
+      </div>
+
+      {link && (
+        <p className="text-sm text-muted-foreground">
+          {link.last_synced_at
+            ? `Last synced ${new Date(link.last_synced_at).toLocaleString()}`
+            : 'Not synced yet'}
+        </p>
+      )}
+      {link?.last_error && !error && (
+        <Alert variant="destructive">
+          <AlertDescription>{link.last_error}</AlertDescription>
+        </Alert>
+      )}
+      {error && (
+        <Alert variant="destructive">
+          <AlertDescription>{error}</AlertDescription>
+        </Alert>
+      )}
+      {message && (
+        <Alert>
+          <AlertDescription>{message}</AlertDescription>
+        </Alert>
+      )}
+
+      <div className="flex justify-end gap-2">
+        {link && (
+          <>
+            <Button variant="outline" onClick={handleUnlink}>
+              Unlink
+            </Button>
+            <Button variant="outline" onClick={handleSync} disabled={syncing}>
+              <RefreshCw
+                className={`h-4 w-4 mr-2 ${syncing ? 'animate-spin' : ''}`}
+              />
+              Sync now
+            </Button>
+          </>
+        )}
+        <Button onClick={handleSave} disabled={saving || !projectUrl.trim()}>
+          {saving ? 'Saving...' : link ? 'Save' : 'Link'}
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,205 @@
+import { useEffect, useState } from 'react';
+import { Loader2, Plus, Trash2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { projectGuardrailsApi } from '@/lib/api';
+import type {
+  ExecutionWindow,
+  GuardrailHold,
+  GuardrailStatus,
+} from 'shared/types';
+
+interface GuardrailsSettingsProps {
+  projectId: string;
+}
+
+export const guardrailHoldLabels: Record<GuardrailHold, string> = {
+  budget_exceeded: 'Budget exceeded',
+  outside_working_hours: 'Outside working hours',
+};
+
+export function GuardrailsSettings({ projectId }: GuardrailsSettingsProps) {
+  const [status, setStatus] = useState<GuardrailStatus | null>(null);
+  const [budget, setBudget] = useState('');
+  const [windows, setWindows] = useState<ExecutionWindow[]>([]);
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const apply = (loaded: GuardrailStatus) => {
+    setStatus(loaded);
+    setBudget(loaded.guardrails?.monthly_budget_usd?.toString() ?? '');
+    setWindows(loaded.guardrails?.allowed_windows ?? []);
+  };
+
+  useEffect(() => {
+    setLoading(true);
+    projectGuardrailsApi
+      .get(projectId)
+      .then(apply)
+      .catch((err) => setError(err.message))
+      .finally(() => setLoading(false));
+  }, [projectId]);
+
+  const updateWindow = (index: number, changes: Partial<ExecutionWindow>) => {
+    setWindows((current) =>
+      current.map((allowed, i) =>
+        i === index ? { ...allowed, ...changes } : allowed
+      )
+    );
+  };
+
+  const handleSave = async () => {
+    setSaving(true);
+    setError(null);
+    try {
+      const parsed = parseFloat(budget);
+      apply(
+        await projectGuardrailsApi.save(projectId, {
+          monthly_budget_usd: budget.trim() && !isNaN(parsed) ? parsed : null,
+          allowed_windows: windows,
+        })
+      );
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to save guardrails'
+      );
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleReset = async () => {
+    setError(null);
+    try {
+      await projectGuardrailsApi.reset(projectId);
+      apply(await projectGuardrailsApi.get(projectId));
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to reset guardrails'
+      );
+    }
+  };
+
+  if (loading) {
+    return (
+      <div className="flex items-center justify-center py-4">
+        <Loader2 className="h-6 w-6 animate-spin" />
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      <p className="text-sm text-muted-foreground">
+        Hold this project's attempts in the execution queue once its runs this
+        month have cost its budget, or outside the windows its agents may start
+        in. Times are in the server's local time; agents already running when a
+        window closes carry on.
+      </p>
+
+      {status && (
+        <p className="text-sm">
+          Spent this month: ${status.spent_this_month_usd.toFixed(2)}
+          {status.hold && (
+            <span className="ml-2 font-medium text-destructive">
+              {guardrailHoldLabels[status.hold]}
+            </span>
+          )}
+        </p>
+      )}
+
+      <div className="space-y-2">
+        <Label htmlFor="guardrails-budget">Monthly budget (USD)</Label>
+        <Input
+          id="guardrails-budget"
+          type="number"
+          min={0}
+          step="0.01"
+          placeholder="No budget"
+          value={budget}
+          onChange={(e) => setBudget(e.target.value)}
+        />
+      </div>
+
+      <div className="space-y-2">
+        <Label>Working hours</Label>
+        {windows.length === 0 && (
+          <p className="text-sm text-muted-foreground">
+            Agents may start any time.
+          </p>
+        )}
+        {windows.map((allowed, index) => (
+          <div key={index} className="flex items-center gap-2">
+            <Input
+              type="time"
+              value={allowed.start}
+              onChange={(e) => updateWindow(index, { start: e.target.value })}
+            />
+            <span className="text-sm text-muted-foreground">to</span>
+            <Input
+              type="time"
+              value={allowed.end}
+              onChange={(e) => updateWindow(index, { end: e.target.value })}
+            />
+            <Checkbox
+              id={`guardrails-weekdays-${index}`}
+              checked={allowed.weekdays_only}
+              onCheckedChange={(checked: boolean) =>
+                updateWindow(index, { weekdays_only: checked })
+              }
+            />
+            <Label
+              htmlFor={`guardrails-weekdays-${index}`}
+              className="cursor-pointer whitespace-nowrap"
+            >
+              Weekdays
+            </Label>
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={() =>
+                setWindows((current) => current.filter((_, i) => i !== index))
+              }
+            >
+              <Trash2 className="h-4 w-4" />
+            </Button>
+          </div>
+        ))}
+        <Button
+          variant="outline"
+          size="sm"
+          onClick={() =>
+            setWindows((current) => [
+              ...current,
+              { start: '09:00', end: '18:00', weekdays_only: true },
+            ])
+          }
+        >
+          <Plus className="mr-2 h-4 w-4" />
+          Add window
+        </Button>
+      </div>
+
+      {error && (
+        <Alert variant="destructive">
+          <AlertDescription>{error}</AlertDescription>
+        </Alert>
+      )}
+
+      <div className="flex justify-end gap-2">
+        {status?.guardrails && (
+          <Button variant="outline" onClick={handleReset}>
+            Reset
+          </Button>
+        )}
+        <Button onClick={handleSave} disabled={saving}>
+          {saving ? 'Saving...' : 'Save'}
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,199 @@
+import { useEffect, useState } from 'react';
+import { Loader2, Trash2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { projectMcpServersApi } from '@/lib/api';
+import type { ProjectMcpServer } from 'shared/types';
+
+interface McpServerSettingsProps {
+  projectId: string;
+}
+
+// One KEY=value per line
+function parseEnv(text: string): { [key: string]: string } {
+  const env: { [key: string]: string } = {};
+  for (const line of text.split('\n')) {
+    const separator = line.indexOf('=');
+    if (separator > 0) {
+      env[line.slice(0, separator).trim()] = line.slice(separator + 1);
+    }
+  }
+  return env;
+}
+
+export function McpServerSettings({ projectId }: McpServerSettingsProps) {
+  const [servers, setServers] = useState<ProjectMcpServer[]>([]);
+  const [name, setName] = useState('');
+  const [command, setCommand] = useState('');
+  const [args, setArgs] = useState('');
+  const [env, setEnv] = useState('');
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setLoading(true);
+    projectMcpServersApi
+      .getAll(projectId)
+      .then(setServers)
+      .catch((err) => setError(err.message))
+      .finally(() => setLoading(false));
+  }, [projectId]);
+
+  const handleEdit = (server: ProjectMcpServer) => {
+    setName(server.name);
+    setCommand(server.command);
+    setArgs(server.args.join('\n'));
+    setEnv(
+      Object.entries(server.env)
+        .map(([key, value]) => `${key}=${value}`)
+        .join('\n')
+    );
+  };
+
+  const handleSave = async () => {
+    setSaving(true);
+    setError(null);
+    try {
+      const saved = await projectMcpServersApi.save(projectId, {
+        name: name.trim(),
+        command: command.trim(),
+        args: args.split('\n').filter((arg) => arg.trim() !== ''),
+        env: parseEnv(env),
+      });
+      setServers((current) => {
+        const others = current.filter((s) => s.name !== saved.name);
+        return [...others, saved].sort((a, b) => a.name.localeCompare(b.name));
+      });
+      setName('');
+      setCommand('');
+      setArgs('');
+      setEnv('');
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to save MCP server'
+      );
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleDelete = async (serverName: string) => {
+    setError(null);
+    try {
+      await projectMcpServersApi.delete(projectId, serverName);
+      setServers((current) => current.filter((s) => s.name !== serverName));
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to delete MCP server'
+      );
+    }
+  };
+
+  if (loading) {
+    return (
+      <div className="flex items-center justify-center py-4">
+        <Loader2 className="h-6 w-6 animate-spin" />
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      <p className="text-sm text-muted-foreground">
+        MCP servers the coding agents of this project's attempts are started
+        with, on top of those in the agent's own configuration. Claude Code,
+        Claude Code Router and Codex receive them.
+      </p>
+
+      {servers.length > 0 && (
+        <div className="space-y-2">
+          {servers.map((server) => (
+            <div
+              key={server.id}
+              className="flex items-center justify-between gap-2 rounded-md border p-2"
+            >
+              <button
+                type="button"
+                className="min-w-0 text-left"
+                onClick={() => handleEdit(server)}
+              >
+                <div className="text-sm font-medium">{server.name}</div>
+                <div className="text-xs text-muted-foreground font-mono truncate">
+                  {[server.command, ...server.args].join(' ')}
+                </div>
+              </button>
+              <Button
+                variant="ghost"
+                size="sm"
+                onClick={() => handleDelete(server.name)}
+              >
+                <Trash2 className="h-4 w-4" />
+              </Button>
+            </div>
+          ))}
+        </div>
+      )}
+
+      <div className="space-y-2">
+        <Label htmlFor="mcp-server-name">Name</Label>
+        <Input
+          id="mcp-server-name"
+          placeholder="e.g. docs"
+          value={name}
+          onChange={(e) => setName(e.target.value)}
+        />
+      </div>
+
+      <div className="space-y-2">
+        <Label htmlFor="mcp-server-command">Command</Label>
+        <Input
+          id="mcp-server-command"
+          placeholder="e.g. npx"
+          value={command}
+          onChange={(e) => setCommand(e.target.value)}
+        />
+      </div>
+
+      <div className="space-y-2">
+        <Label htmlFor="mcp-server-args">Arguments (one per line)</Label>
+        <textarea
+          id="mcp-server-args"
+          placeholder="-y&#10;@acme/mcp-server"
+          value={args}
+          onChange={(e) => setArgs(e.target.value)}
+          className="w-full p-2 border rounded-md resize-none font-mono text-sm"
+          rows={3}
+        />
+      </div>
+
+      <div className="space-y-2">
+        <Label htmlFor="mcp-server-env">Environment (KEY=value per line)</Label>
+        <textarea
+          id="mcp-server-env"
+          value={env}
+          onChange={(e) => setEnv(e.target.value)}
+          className="w-full p-2 border rounded-md resize-none font-mono text-sm"
+          rows={2}
+        />
+      </div>
+
+      {error && (
+        <Alert variant="destructive">
+          <AlertDescription>{error}</AlertDescription>
+        </Alert>
+      )}
+
+      <div className="flex justify-end">
+        <Button
+          onClick={handleSave}
+          disabled={saving || !name.trim() || !command.trim()}
+        >
+          {saving ? 'Saving...' : 'Save server'}
+        </Button>
+      </div>
+    </div>
+  );
+}
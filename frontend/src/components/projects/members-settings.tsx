@@ -0,0 +1,246 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Loader2, Trash2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Badge } from '@/components/ui/badge';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { projectInvitesApi, projectMembersApi } from '@/lib/api';
+import type { ProjectInvite, ProjectMember, ProjectRole } from 'shared/types';
+
+interface MembersSettingsProps {
+  projectId: string;
+}
+
+const ROLES: { role: ProjectRole; label: string }[] = [
+  { role: 'viewer', label: 'Viewer' },
+  { role: 'member', label: 'Member' },
+  { role: 'admin', label: 'Admin' },
+];
+
+function RoleSelect({
+  id,
+  value,
+  onChange,
+}: {
+  id?: string;
+  value: ProjectRole;
+  onChange: (role: ProjectRole) => void;
+}) {
+  return (
+    <Select value={value} onValueChange={(v) => onChange(v as ProjectRole)}>
+      <SelectTrigger id={id} className="w-32">
+        <SelectValue />
+      </SelectTrigger>
+      <SelectContent>
+        {ROLES.map(({ role, label }) => (
+          <SelectItem key={role} value={role}>
+            {label}
+          </SelectItem>
+        ))}
+      </SelectContent>
+    </Select>
+  );
+}
+
+export function MembersSettings({ projectId }: MembersSettingsProps) {
+  const [members, setMembers] = useState<ProjectMember[]>([]);
+  const [invites, setInvites] = useState<ProjectInvite[]>([]);
+  const [loading, setLoading] = useState(true);
+  const [error, setError] = useState<string | null>(null);
+  const [username, setUsername] = useState('');
+  const [memberRole, setMemberRole] = useState<ProjectRole>('member');
+  const [inviteRole, setInviteRole] = useState<ProjectRole>('member');
+  const [inviteLink, setInviteLink] = useState<string | null>(null);
+
+  const fetchAll = useCallback(async () => {
+    try {
+      const [loadedMembers, loadedInvites] = await Promise.all([
+        projectMembersApi.getAll(projectId),
+        projectInvitesApi.getAll(projectId),
+      ]);
+      setMembers(loadedMembers);
+      setInvites(loadedInvites);
+      setError(null);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to load members');
+    } finally {
+      setLoading(false);
+    }
+  }, [projectId]);
+
+  useEffect(() => {
+    fetchAll();
+  }, [fetchAll]);
+
+  const run = async (action: () => Promise<void>) => {
+    setError(null);
+    try {
+      await action();
+      await fetchAll();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Something went wrong');
+    }
+  };
+
+  const handleAdd = () =>
+    run(async () => {
+      await projectMembersApi.set(projectId, { username, role: memberRole });
+      setUsername('');
+    });
+
+  const handleInvite = () =>
+    run(async () => {
+      const created = await projectInvitesApi.create(projectId, {
+        role: inviteRole,
+        expires_in_days: null,
+      });
+      setInviteLink(`${window.location.origin}/invites/${created.token}`);
+    });
+
+  if (loading) {
+    return (
+      <div className="flex justify-center py-4">
+        <Loader2 className="h-5 w-5 animate-spin" />
+      </div>
+    );
+  }
+
+  const pending = invites.filter(
+    (invite) =>
+      !invite.accepted_at &&
+      !invite.revoked_at &&
+      new Date(invite.expires_at) > new Date()
+  );
+
+  return (
+    <div className="space-y-6">
+      {error && (
+        <Alert variant="destructive">
+          <AlertDescription>{error}</AlertDescription>
+        </Alert>
+      )}
+
+      <div className="space-y-2">
+        <h3 className="text-sm font-medium">Members</h3>
+        {members.length === 0 ? (
+          <p className="text-sm text-muted-foreground">
+            Only server admins have access so far.
+          </p>
+        ) : (
+          members.map((member) => (
+            <div
+              key={member.user_id}
+              className="flex items-center justify-between rounded border p-2"
+            >
+              <span className="text-sm">
+                {member.display_name ?? member.username}
+                {member.display_name && (
+                  <span className="text-muted-foreground">
+                    {' '}
+                    ({member.username})
+                  </span>
+                )}
+              </span>
+              <div className="flex items-center gap-2">
+                <RoleSelect
+                  value={member.role}
+                  onChange={(role) =>
+                    run(async () => {
+                      await projectMembersApi.set(projectId, {
+                        username: member.username,
+                        role,
+                      });
+                    })
+                  }
+                />
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  onClick={() =>
+                    run(() =>
+                      projectMembersApi.remove(projectId, member.user_id)
+                    )
+                  }
+                >
+                  <Trash2 className="h-4 w-4" />
+                </Button>
+              </div>
+            </div>
+          ))
+        )}
+        <div className="flex items-end gap-2">
+          <div className="flex-1 space-y-2">
+            <Label htmlFor="member-username">Add an existing account</Label>
+            <Input
+              id="member-username"
+              placeholder="Username"
+              value={username}
+              onChange={(e) => setUsername(e.target.value)}
+            />
+          </div>
+          <RoleSelect value={memberRole} onChange={setMemberRole} />
+          <Button onClick={handleAdd} disabled={!username.trim()}>
+            Add
+          </Button>
+        </div>
+      </div>
+
+      <div className="space-y-2">
+        <h3 className="text-sm font-medium">Invites</h3>
+        <p className="text-sm text-muted-foreground">
+          Anyone with the link can join once, signing in or creating an
+          account. Links expire after a week.
+        </p>
+        {inviteLink && (
+          <Alert>
+            <AlertDescription>
+              Copy the link now; it won't be shown again:{' '}
+              <code className="break-all">{inviteLink}</code>
+            </AlertDescription>
+          </Alert>
+        )}
+        {pending.map((invite) => (
+          <div
+            key={invite.id}
+            className="flex items-center justify-between rounded border p-2"
+          >
+            <div className="flex items-center gap-2 text-sm">
+              <code className="text-xs text-muted-foreground">
+                {invite.token_prefix}…
+              </code>
+              <Badge variant="secondary">{invite.role}</Badge>
+              <span className="text-muted-foreground">
+                Expires {new Date(invite.expires_at).toLocaleDateString()}
+              </span>
+            </div>
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={() =>
+                run(() => projectInvitesApi.revoke(projectId, invite.id))
+              }
+            >
+              <Trash2 className="h-4 w-4" />
+            </Button>
+          </div>
+        ))}
+        <div className="flex items-center gap-2">
+          <RoleSelect
+            id="invite-role"
+            value={inviteRole}
+            onChange={setInviteRole}
+          />
+          <Button onClick={handleInvite}>Create invite link</Button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,230 @@
+import { useEffect, useState } from 'react';
+import { Loader2, RefreshCw } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { notionApi } from '@/lib/api';
+import type {
+  NotionDatabase,
+  NotionLink,
+  NotionPropertyMapping,
+} from 'shared/types';
+import { EMPTY_STATUS_NAMES, StatusNamesFields } from './status-names-fields';
+
+const EMPTY_MAPPING: NotionPropertyMapping = {
+  title: null,
+  status: null,
+  description: null,
+  status_names: EMPTY_STATUS_NAMES,
+};
+
+const PROPERTY_FIELDS: {
+  key: 'title' | 'status' | 'description';
+  label: string;
+  placeholder: string;
+}[] = [
+  { key: 'title', label: 'Title', placeholder: 'Title column' },
+  { key: 'status', label: 'Status', placeholder: 'Status' },
+  { key: 'description', label: 'Description', placeholder: 'Not synced' },
+];
+
+interface NotionSyncProps {
+  projectId: string;
+}
+
+export function NotionSync({ projectId }: NotionSyncProps) {
+  const [link, setLink] = useState<NotionLink | null>(null);
+  const [databaseUrl, setDatabaseUrl] = useState('');
+  const [mapping, setMapping] = useState<NotionPropertyMapping>(EMPTY_MAPPING);
+  const [database, setDatabase] = useState<NotionDatabase | null>(null);
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [syncing, setSyncing] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setLoading(true);
+    notionApi
+      .get(projectId)
+      .then((existing) => {
+        setLink(existing);
+        setDatabaseUrl(existing?.database_id ?? '');
+        setMapping(existing?.property_mapping ?? EMPTY_MAPPING);
+      })
+      .catch((err) => setError(err.message))
+      .finally(() => setLoading(false));
+  }, [projectId]);
+
+  const statusProperty = database?.properties.find(
+    (property) => property.name === (mapping.status ?? 'Status')
+  );
+
+  const handleSave = async () => {
+    setSaving(true);
+    setError(null);
+    setMessage(null);
+    try {
+      const result = await notionApi.link(projectId, {
+        database: databaseUrl.trim(),
+        property_mapping: mapping,
+      });
+      setLink(result.link);
+      setDatabase(result.database);
+      setDatabaseUrl(result.link.database_id);
+      setMessage(
+        `Linked to "${result.database.title || 'Untitled'}". Tasks will appear there shortly.`
+      );
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to link database');
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleSync = async () => {
+    setSyncing(true);
+    setError(null);
+    setMessage(null);
+    try {
+      const result = await notionApi.sync(projectId);
+      setMessage(
+        `Synced: ${result.added} added, ${result.updated} updated, ${result.archived} archived.`
+      );
+      setLink(await notionApi.get(projectId));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Sync failed');
+    } finally {
+      setSyncing(false);
+    }
+  };
+
+  const handleUnlink = async () => {
+    setError(null);
+    setMessage(null);
+    try {
+      await notionApi.unlink(projectId);
+      setLink(null);
+      setDatabase(null);
+      setDatabaseUrl('');
+      setMapping(EMPTY_MAPPING);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to unlink database');
+    }
+  };
+
+  if (loading) {
+    return (
+      <div className="flex items-center justify-center py-4">
+        <Loader2 className="h-6 w-6 animate-spin" />
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      <p className="text-sm text-muted-foreground">
+        Mirror this project's tasks into a Notion database, one page per task.
+        Changes made in Notion are overwritten on the next sync. The database
+        must be shared with the integration set up in Settings.
+      </p>
+
+      <div className="space-y-2">
+        <Label htmlFor="notion-database">Database URL</Label>
+        <Input
+          id="notion-database"
+          placeholder="https://www.notion.so/acme/0123456789abcdef0123456789abcdef"
+          value={databaseUrl}
+          onChange={(e) => setDatabaseUrl(e.target.value)}
+        />
+      </div>
+
+      <div className="space-y-2">
+        <Label>Properties</Label>
+        <p className="text-sm text-muted-foreground">
+          Which database properties task fields are written to.
+        </p>
+        <div className="space-y-2">
+          {PROPERTY_FIELDS.map(({ key, label, placeholder }) => (
+            <div key={key} className="flex items-center gap-2">
+              <span className="text-sm w-24 shrink-0">{label}</span>
+              <Input
+                list="notion-properties"
+                placeholder={placeholder}
+                value={mapping[key] ?? ''}
+                onChange={(e) =>
+                  setMapping({ ...mapping, [key]: e.target.value || null })
+                }
+              />
+            </div>
+          ))}
+        </div>
+        <datalist id="notion-properties">
+          {database?.properties.map((property) => (
+            <option key={property.name} value={property.name}>
+              {property.kind}
+            </option>
+          ))}
+        </datalist>
+      </div>
+
+      <div className="space-y-2">
+        <Label>Status mapping</Label>
+        <p className="text-sm text-muted-foreground">
+          Leave blank to use the option with the same name.
+        </p>
+        <StatusNamesFields
+          id="notion-status-options"
+          value={mapping.status_names}
+          options={statusProperty?.options ?? []}
+          onChange={(statusNames) =>
+            setMapping({ ...mapping, status_names: statusNames })
+          }
+        />
+      </div>
+
+      {link && (
+        <p className="text-sm text-muted-foreground">
+          {link.last_synced_at
+            ? `Last synced ${new Date(link.last_synced_at).toLocaleString()}`
+            : 'Not synced yet'}
+        </p>
+      )}
+      {link?.last_error && !error && (
+        <Alert variant="destructive">
+          <AlertDescription>{link.last_error}</AlertDescription>
+        </Alert>
+      )}
+      {error && (
+        <Alert variant="destructive">
+          <AlertDescription>{error}</AlertDescription>
+        </Alert>
+      )}
+      {message && (
+        <Alert>
+          <AlertDescription>{message}</AlertDescription>
+        </Alert>
+      )}
+
+      <div className="flex justify-end gap-2">
+        {link && (
+          <>
+            <Button variant="outline" onClick={handleUnlink}>
+              Unlink
+            </Button>
+            <Button variant="outline" onClick={handleSync} disabled={syncing}>
+              <RefreshCw
+                className={`h-4 w-4 mr-2 ${syncing ? 'animate-spin' : ''}`}
+              />
+              Sync now
+            </Button>
+          </>
+        )}
+        <Button onClick={handleSave} disabled={saving || !databaseUrl.trim()}>
+          {saving ? 'Saving...' : link ? 'Save' : 'Link'}
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -12,6 +12,10 @@ import { Badge } from '@/components/ui/badge';
 import { Alert, AlertDescription } from '@/components/ui/alert';
 import { ProjectWithBranch } from 'shared/types';
 import { ProjectForm } from './project-form';
+import { RepoAnalysisCard } from './RepoAnalysisCard';
+import { AgentUsageCard } from './AgentUsageCard';
+import { ExecutionAnalyticsCard } from './ExecutionAnalyticsCard';
+import { WorktreeUsageCard } from './WorktreeUsageCard';
 import { projectsApi } from '@/lib/api';
 import {
   AlertCircle,
@@ -240,6 +244,13 @@ export function ProjectDetail({ projectId, onBack }: ProjectDetailProps) {
         </Card>
       </div>
 
+      <AgentUsageCard projectId={projectId} />
+      <ExecutionAnalyticsCard projectId={projectId} />
+
+      <WorktreeUsageCard projectId={projectId} />
+
+      <RepoAnalysisCard projectId={projectId} />
+
       <ProjectForm
         open={showEditForm}
         onClose={() => setShowEditForm(false)}
@@ -2,13 +2,24 @@ import { Label } from '@/components/ui/label';
 import { Input } from '@/components/ui/input';
 import { Button } from '@/components/ui/button';
 import { Alert, AlertDescription } from '@/components/ui/alert';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
 import { AlertCircle, Folder } from 'lucide-react';
+import { EXECUTOR_LABELS, EXECUTOR_TYPES } from 'shared/types';
 import { useSystemInfo } from '@/hooks/use-system-info';
 import {
   createScriptPlaceholderStrategy,
   ScriptPlaceholderContext,
 } from '@/utils/script-placeholders';
 
+// Select items can't have an empty value
+const NO_DEFAULT_EXECUTOR = 'none';
+
 interface ProjectFormFieldsProps {
   isEditing: boolean;
   repoMode: 'existing' | 'new';
@@ -28,6 +39,18 @@ interface ProjectFormFieldsProps {
   setDevScript: (script: string) => void;
   cleanupScript: string;
   setCleanupScript: (script: string) => void;
+  commitMessageTemplate: string;
+  setCommitMessageTemplate: (template: string) => void;
+  releaseScript: string;
+  setReleaseScript: (script: string) => void;
+  mergeCheckScript: string;
+  setMergeCheckScript: (script: string) => void;
+  verificationScript: string;
+  setVerificationScript: (script: string) => void;
+  defaultExecutor: string;
+  setDefaultExecutor: (executor: string) => void;
+  defaultBranch: string;
+  setDefaultBranch: (branch: string) => void;
   error: string;
 }
 
@@ -50,6 +73,18 @@ export function ProjectFormFields({
   setDevScript,
   cleanupScript,
   setCleanupScript,
+  commitMessageTemplate,
+  setCommitMessageTemplate,
+  releaseScript,
+  setReleaseScript,
+  mergeCheckScript,
+  setMergeCheckScript,
+  verificationScript,
+  setVerificationScript,
+  defaultExecutor,
+  setDefaultExecutor,
+  defaultBranch,
+  setDefaultBranch,
   error,
 }: ProjectFormFieldsProps) {
   const { systemInfo } = useSystemInfo();
@@ -64,6 +99,10 @@ export function ProjectFormFields({
         dev: '#!/bin/bash\nnpm run dev\n# Add dev server start command here...',
         cleanup:
           '#!/bin/bash\n# Add cleanup commands here...\n# This runs after coding agent execution',
+        release:
+          '#!/bin/bash\ngit tag "$VK_RELEASE_VERSION"\ngit push origin "$VK_RELEASE_VERSION"\n# Add build and publish commands here...',
+        mergeCheck: '#!/bin/bash\nnpm run build && npm test',
+        verification: 'npm run lint\nnpm test',
       };
 
   return (
@@ -246,6 +285,127 @@ export function ProjectFormFields({
         </p>
       </div>
 
+      <div className="space-y-2">
+        <Label htmlFor="release-script">Release Script (Optional)</Label>
+        <textarea
+          id="release-script"
+          value={releaseScript}
+          onChange={(e) => setReleaseScript(e.target.value)}
+          placeholder={placeholders.release}
+          rows={4}
+          className="w-full px-3 py-2 border border-input bg-background text-foreground rounded-md resize-vertical focus:outline-none focus:ring-2 focus:ring-ring"
+        />
+        <p className="text-sm text-muted-foreground">
+          Runs from the repository root when you start a release, to tag, build
+          and publish it. The release is available as $VK_RELEASE_VERSION,
+          $VK_RELEASE_ID and $VK_RELEASE_NOTES.
+        </p>
+      </div>
+
+      <div className="space-y-2">
+        <Label htmlFor="merge-check-script">
+          Merge Check Script (Optional)
+        </Label>
+        <textarea
+          id="merge-check-script"
+          value={mergeCheckScript}
+          onChange={(e) => setMergeCheckScript(e.target.value)}
+          placeholder={placeholders.mergeCheck}
+          rows={4}
+          className="w-full px-3 py-2 border border-input bg-background text-foreground rounded-md resize-vertical focus:outline-none focus:ring-2 focus:ring-ring"
+        />
+        <p className="text-sm text-muted-foreground">
+          Runs in the attempt&apos;s worktree when it reaches the front of the
+          merge queue, after rebasing onto the latest base branch. A non-zero
+          exit keeps the attempt from merging. The base branch is available as
+          $VK_BASE_BRANCH.
+        </p>
+      </div>
+
+      <div className="space-y-2">
+        <Label htmlFor="verification-script">
+          Verification Commands (Optional)
+        </Label>
+        <textarea
+          id="verification-script"
+          value={verificationScript}
+          onChange={(e) => setVerificationScript(e.target.value)}
+          placeholder={placeholders.verification}
+          rows={4}
+          className="w-full px-3 py-2 border border-input bg-background text-foreground rounded-md resize-vertical focus:outline-none focus:ring-2 focus:ring-ring"
+        />
+        <p className="text-sm text-muted-foreground">
+          One command per line, run in the attempt&apos;s worktree after the
+          coding agent finishes successfully and before the cleanup script.
+          Each command&apos;s result shows up in the conversation; a failure
+          labels the task needs-follow-up.
+        </p>
+      </div>
+
+      <div className="space-y-2">
+        <Label htmlFor="default-executor">Default Executor (Optional)</Label>
+        <Select
+          value={defaultExecutor || NO_DEFAULT_EXECUTOR}
+          onValueChange={(value) =>
+            setDefaultExecutor(value === NO_DEFAULT_EXECUTOR ? '' : value)
+          }
+        >
+          <SelectTrigger id="default-executor">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            <SelectItem value={NO_DEFAULT_EXECUTOR}>
+              Use the configured executor
+            </SelectItem>
+            {EXECUTOR_TYPES.map((type) => (
+              <SelectItem key={type} value={type}>
+                {EXECUTOR_LABELS[type]}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+        <p className="text-sm text-muted-foreground">
+          The coding agent this project&apos;s attempts start with, unless the
+          attempt or the task&apos;s template picks another.
+        </p>
+      </div>
+
+      <div className="space-y-2">
+        <Label htmlFor="default-branch">Default Base Branch (Optional)</Label>
+        <Input
+          id="default-branch"
+          value={defaultBranch}
+          onChange={(e) => setDefaultBranch(e.target.value)}
+          placeholder="e.g., develop (leave empty to use the current branch)"
+        />
+        <p className="text-sm text-muted-foreground">
+          The branch new attempts are based on when they don&apos;t name one.
+        </p>
+      </div>
+
+      <div className="space-y-2">
+        <Label htmlFor="commit-message-template">
+          Commit Message Template (Optional)
+        </Label>
+        <textarea
+          id="commit-message-template"
+          value={commitMessageTemplate}
+          onChange={(e) => setCommitMessageTemplate(e.target.value)}
+          placeholder={
+            '{{task_title}}\n\n{{summary}}\n\n{{stats}}\n\n{{changes}}\n\nTask: {{task_id}}'
+          }
+          rows={4}
+          className="w-full px-3 py-2 border border-input bg-background text-foreground rounded-md resize-vertical font-mono text-sm focus:outline-none focus:ring-2 focus:ring-ring"
+        />
+        <p className="text-sm text-muted-foreground">
+          Used when vibe-kanban commits changes left by the agent or the
+          cleanup script. Available placeholders: {'{{task_title}}'},{' '}
+          {'{{task_description}}'}, {'{{summary}}'}, {'{{stats}}'},{' '}
+          {'{{changes}}'}, {'{{source}}'}, {'{{task_id}}'} and{' '}
+          {'{{attempt_id}}'}.
+        </p>
+      </div>
+
       {error && (
         <Alert variant="destructive">
           <AlertCircle className="h-4 w-4" />
@@ -15,6 +15,22 @@ import { FolderPicker } from '@/components/ui/folder-picker';
 import { TaskTemplateManager } from '@/components/TaskTemplateManager';
 import { ProjectFormFields } from './project-form-fields';
 import { GitHubRepositoryPicker } from './github-repository-picker';
+import { GitHubIssueSync } from './github-issue-sync';
+import { GitHubProjectSync } from './github-project-sync';
+import { NotionSync } from './notion-sync';
+import { GitHostSettings } from './git-host-settings';
+import { McpServerSettings } from './mcp-server-settings';
+import { ContainerSettings } from './container-settings';
+import { CustomFields } from './custom-fields';
+import { BoardColumns } from './board-columns';
+import { SandboxSettings } from './sandbox-settings';
+import { SecuritySettings } from './security-settings';
+import { ShellEnvironmentSettings } from './shell-environment-settings';
+import { WorkerSettings } from './worker-settings';
+import { GuardrailsSettings } from './guardrails-settings';
+import { MembersSettings } from './members-settings';
+import { ScriptHookSettings } from './script-hook-settings';
+import { RepoConfigSummary } from './repo-config-summary';
 import {
   CreateProject,
   CreateProjectFromGitHub,
@@ -44,6 +60,24 @@ export function ProjectForm({
   const [cleanupScript, setCleanupScript] = useState(
     project?.cleanup_script ?? ''
   );
+  const [commitMessageTemplate, setCommitMessageTemplate] = useState(
+    project?.commit_message_template ?? ''
+  );
+  const [releaseScript, setReleaseScript] = useState(
+    project?.release_script ?? ''
+  );
+  const [mergeCheckScript, setMergeCheckScript] = useState(
+    project?.merge_check_script ?? ''
+  );
+  const [verificationScript, setVerificationScript] = useState(
+    project?.verification_script ?? ''
+  );
+  const [defaultExecutor, setDefaultExecutor] = useState(
+    project?.default_executor ?? ''
+  );
+  const [defaultBranch, setDefaultBranch] = useState(
+    project?.default_branch ?? ''
+  );
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState('');
   const [showFolderPicker, setShowFolderPicker] = useState(false);
@@ -87,12 +121,24 @@ export function ProjectForm({
       setSetupScript(project.setup_script ?? '');
       setDevScript(project.dev_script ?? '');
       setCleanupScript(project.cleanup_script ?? '');
+      setCommitMessageTemplate(project.commit_message_template ?? '');
+      setReleaseScript(project.release_script ?? '');
+      setMergeCheckScript(project.merge_check_script ?? '');
+      setVerificationScript(project.verification_script ?? '');
+      setDefaultExecutor(project.default_executor ?? '');
+      setDefaultBranch(project.default_branch ?? '');
     } else {
       setName('');
       setGitRepoPath('');
       setSetupScript('');
       setDevScript('');
       setCleanupScript('');
+      setCommitMessageTemplate('');
+      setReleaseScript('');
+      setMergeCheckScript('');
+      setVerificationScript('');
+      setDefaultExecutor('');
+      setDefaultBranch('');
       setSelectedRepository(null);
     }
   }, [project]);
@@ -134,6 +180,12 @@ export function ProjectForm({
           setup_script: setupScript.trim() || null,
           dev_script: devScript.trim() || null,
           cleanup_script: cleanupScript.trim() || null,
+          commit_message_template: commitMessageTemplate.trim() || null,
+          release_script: releaseScript.trim() || null,
+          merge_check_script: mergeCheckScript.trim() || null,
+          verification_script: verificationScript.trim() || null,
+          default_executor: defaultExecutor || null,
+          default_branch: defaultBranch.trim() || null,
         };
 
         await projectsApi.update(project.id, updateData);
@@ -153,6 +205,12 @@ export function ProjectForm({
             setup_script: setupScript.trim() || null,
             dev_script: devScript.trim() || null,
             cleanup_script: cleanupScript.trim() || null,
+            commit_message_template: commitMessageTemplate.trim() || null,
+            release_script: releaseScript.trim() || null,
+            merge_check_script: mergeCheckScript.trim() || null,
+            verification_script: verificationScript.trim() || null,
+            default_executor: defaultExecutor || null,
+            default_branch: defaultBranch.trim() || null,
           };
 
           await githubApi.createProjectFromRepository(githubData);
@@ -173,6 +231,12 @@ export function ProjectForm({
             setup_script: setupScript.trim() || null,
             dev_script: devScript.trim() || null,
             cleanup_script: cleanupScript.trim() || null,
+            commit_message_template: commitMessageTemplate.trim() || null,
+            release_script: releaseScript.trim() || null,
+            merge_check_script: mergeCheckScript.trim() || null,
+            verification_script: verificationScript.trim() || null,
+            default_executor: defaultExecutor || null,
+            default_branch: defaultBranch.trim() || null,
           };
 
           await projectsApi.create(createData);
@@ -186,6 +250,12 @@ export function ProjectForm({
       setSetupScript('');
       setDevScript('');
       setCleanupScript('');
+      setCommitMessageTemplate('');
+      setReleaseScript('');
+      setMergeCheckScript('');
+      setVerificationScript('');
+      setDefaultExecutor('');
+      setDefaultBranch('');
       setParentPath('');
       setFolderName('');
       setSelectedRepository(null);
@@ -232,9 +302,24 @@ export function ProjectForm({
 
         {isEditing ? (
           <Tabs defaultValue="general" className="w-full -mt-2">
-            <TabsList className="grid w-full grid-cols-2 mb-4">
+            <TabsList className="grid w-full grid-cols-12 mb-4">
               <TabsTrigger value="general">General</TabsTrigger>
               <TabsTrigger value="templates">Task Templates</TabsTrigger>
+              <TabsTrigger value="custom-fields">Custom Fields</TabsTrigger>
+              <TabsTrigger value="board">Board</TabsTrigger>
+              <TabsTrigger value="github-project">GitHub Projects</TabsTrigger>
+              <TabsTrigger value="github-issues">GitHub Issues</TabsTrigger>
+              <TabsTrigger value="notion">Notion</TabsTrigger>
+              <TabsTrigger value="git-host">Git Host</TabsTrigger>
+              <TabsTrigger value="mcp-servers">MCP Servers</TabsTrigger>
+              <TabsTrigger value="sandbox">Sandbox</TabsTrigger>
+              <TabsTrigger value="shell">Shell</TabsTrigger>
+              <TabsTrigger value="container">Container</TabsTrigger>
+              <TabsTrigger value="worker">Worker</TabsTrigger>
+              <TabsTrigger value="guardrails">Guardrails</TabsTrigger>
+              <TabsTrigger value="script-hooks">Script Hooks</TabsTrigger>
+              <TabsTrigger value="members">Members</TabsTrigger>
+              <TabsTrigger value="security">Security</TabsTrigger>
             </TabsList>
             <TabsContent value="general" className="space-y-4">
               <form onSubmit={handleSubmit} className="space-y-4">
@@ -257,8 +342,21 @@ export function ProjectForm({
                   setDevScript={setDevScript}
                   cleanupScript={cleanupScript}
                   setCleanupScript={setCleanupScript}
+                  commitMessageTemplate={commitMessageTemplate}
+                  setCommitMessageTemplate={setCommitMessageTemplate}
+                  releaseScript={releaseScript}
+                  setReleaseScript={setReleaseScript}
+                  mergeCheckScript={mergeCheckScript}
+                  setMergeCheckScript={setMergeCheckScript}
+                  verificationScript={verificationScript}
+                  setVerificationScript={setVerificationScript}
+                  defaultExecutor={defaultExecutor}
+                  setDefaultExecutor={setDefaultExecutor}
+                  defaultBranch={defaultBranch}
+                  setDefaultBranch={setDefaultBranch}
                   error={error}
                 />
+                {project && <RepoConfigSummary projectId={project.id} />}
                 <DialogFooter>
                   <Button
                     type="button"
@@ -280,6 +378,51 @@ export function ProjectForm({
             <TabsContent value="templates" className="mt-0 pt-0">
               <TaskTemplateManager projectId={project?.id} />
             </TabsContent>
+            <TabsContent value="custom-fields" className="mt-0 pt-0">
+              {project && <CustomFields projectId={project.id} />}
+            </TabsContent>
+            <TabsContent value="board" className="mt-0 pt-0">
+              {project && <BoardColumns projectId={project.id} />}
+            </TabsContent>
+            <TabsContent value="github-project" className="mt-0 pt-0">
+              {project && <GitHubProjectSync projectId={project.id} />}
+            </TabsContent>
+            <TabsContent value="github-issues" className="mt-0 pt-0">
+              {project && <GitHubIssueSync projectId={project.id} />}
+            </TabsContent>
+            <TabsContent value="notion" className="mt-0 pt-0">
+              {project && <NotionSync projectId={project.id} />}
+            </TabsContent>
+            <TabsContent value="git-host" className="mt-0 pt-0">
+              {project && <GitHostSettings projectId={project.id} />}
+            </TabsContent>
+            <TabsContent value="mcp-servers" className="mt-0 pt-0">
+              {project && <McpServerSettings projectId={project.id} />}
+            </TabsContent>
+            <TabsContent value="sandbox" className="mt-0 pt-0">
+              {project && <SandboxSettings projectId={project.id} />}
+            </TabsContent>
+            <TabsContent value="shell" className="mt-0 pt-0">
+              {project && <ShellEnvironmentSettings projectId={project.id} />}
+            </TabsContent>
+            <TabsContent value="container" className="mt-0 pt-0">
+              {project && <ContainerSettings projectId={project.id} />}
+            </TabsContent>
+            <TabsContent value="worker" className="mt-0 pt-0">
+              {project && <WorkerSettings projectId={project.id} />}
+            </TabsContent>
+            <TabsContent value="guardrails" className="mt-0 pt-0">
+              {project && <GuardrailsSettings projectId={project.id} />}
+            </TabsContent>
+            <TabsContent value="script-hooks" className="mt-0 pt-0">
+              {project && <ScriptHookSettings projectId={project.id} />}
+            </TabsContent>
+            <TabsContent value="members" className="mt-0 pt-0">
+              {project && <MembersSettings projectId={project.id} />}
+            </TabsContent>
+            <TabsContent value="security" className="mt-0 pt-0">
+              {project && <SecuritySettings projectId={project.id} />}
+            </TabsContent>
           </Tabs>
         ) : (
           <form onSubmit={handleSubmit} className="space-y-4">
@@ -363,6 +506,18 @@ export function ProjectForm({
                 setDevScript={setDevScript}
                 cleanupScript={cleanupScript}
                 setCleanupScript={setCleanupScript}
+                commitMessageTemplate={commitMessageTemplate}
+                setCommitMessageTemplate={setCommitMessageTemplate}
+                releaseScript={releaseScript}
+                setReleaseScript={setReleaseScript}
+                mergeCheckScript={mergeCheckScript}
+                setMergeCheckScript={setMergeCheckScript}
+                verificationScript={verificationScript}
+                setVerificationScript={setVerificationScript}
+                defaultExecutor={defaultExecutor}
+                setDefaultExecutor={setDefaultExecutor}
+                defaultBranch={defaultBranch}
+                setDefaultBranch={setDefaultBranch}
                 error={error}
               />
             )}
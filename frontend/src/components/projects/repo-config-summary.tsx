@@ -0,0 +1,67 @@
+import { useEffect, useState } from 'react';
+import { FileCog } from 'lucide-react';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { projectsApi } from '@/lib/api';
+import type { EffectiveProjectConfig } from 'shared/types';
+
+interface RepoConfigSummaryProps {
+  projectId: string;
+}
+
+const SETTING_LABELS: Record<string, string> = {
+  default_executor: 'Default executor',
+  setup_script: 'Setup script',
+  verification_script: 'Verification commands',
+  prompt_template: 'Prompt template',
+  worktree_base_dir: 'Worktree directory',
+};
+
+// What the repository's .vibe-kanban.toml fills in for the settings left empty here
+export function RepoConfigSummary({ projectId }: RepoConfigSummaryProps) {
+  const [config, setConfig] = useState<EffectiveProjectConfig | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    projectsApi
+      .getEffectiveConfig(projectId)
+      .then(setConfig)
+      .catch((err) => setError(err.message));
+  }, [projectId]);
+
+  if (error) {
+    return (
+      <Alert variant="destructive">
+        <AlertDescription>{error}</AlertDescription>
+      </Alert>
+    );
+  }
+  if (!config?.has_repo_config) return null;
+
+  return (
+    <div className="rounded-md border p-3 space-y-1 text-sm">
+      <div className="flex items-center gap-2 font-medium">
+        <FileCog className="h-4 w-4" />
+        .vibe-kanban.toml
+      </div>
+      {config.from_repo_config.length === 0 ? (
+        <p className="text-muted-foreground">
+          Every setting it has is overridden by this project's own.
+        </p>
+      ) : (
+        <p className="text-muted-foreground">
+          Used for the settings left empty here:{' '}
+          {config.from_repo_config
+            .map((setting) => SETTING_LABELS[setting] ?? setting)
+            .join(', ')}
+          .
+        </p>
+      )}
+      {config.from_repo_config.includes('worktree_base_dir') && (
+        <p className="text-muted-foreground">
+          Worktrees are made in{' '}
+          <code className="text-xs">{config.worktree_base_dir}</code>
+        </p>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,140 @@
+import { useEffect, useState } from 'react';
+import { Loader2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Label } from '@/components/ui/label';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { projectSandboxApi } from '@/lib/api';
+import type { ProjectSandbox } from 'shared/types';
+
+interface SandboxSettingsProps {
+  projectId: string;
+}
+
+export function SandboxSettings({ projectId }: SandboxSettingsProps) {
+  const [sandbox, setSandbox] = useState<ProjectSandbox | null>(null);
+  const [enabled, setEnabled] = useState(false);
+  const [denyNetwork, setDenyNetwork] = useState(false);
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setLoading(true);
+    projectSandboxApi
+      .get(projectId)
+      .then((existing) => {
+        setSandbox(existing);
+        setEnabled(existing?.enabled ?? false);
+        setDenyNetwork(existing?.deny_network ?? false);
+      })
+      .catch((err) => setError(err.message))
+      .finally(() => setLoading(false));
+  }, [projectId]);
+
+  const handleSave = async () => {
+    setSaving(true);
+    setError(null);
+    setMessage(null);
+    try {
+      const saved = await projectSandboxApi.save(projectId, {
+        enabled,
+        deny_network: denyNetwork,
+      });
+      setSandbox(saved);
+      setMessage(
+        saved.enabled
+          ? "This project's coding agents will run sandboxed."
+          : "This project's coding agents won't be sandboxed."
+      );
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to save sandbox setting'
+      );
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleReset = async () => {
+    setError(null);
+    setMessage(null);
+    try {
+      await projectSandboxApi.reset(projectId);
+      setSandbox(null);
+      setEnabled(false);
+      setDenyNetwork(false);
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to reset sandbox setting'
+      );
+    }
+  };
+
+  if (loading) {
+    return (
+      <div className="flex items-center justify-center py-4">
+        <Loader2 className="h-6 w-6 animate-spin" />
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      <p className="text-sm text-muted-foreground">
+        Run this project's coding agents under bubblewrap on Linux, so they can
+        only write to their worktree and their own settings. Without a setting
+        here, the one in Settings is used.
+      </p>
+
+      <div className="flex items-center space-x-2">
+        <Checkbox
+          id="project-sandbox-enabled"
+          checked={enabled}
+          onCheckedChange={(checked: boolean) => setEnabled(checked)}
+        />
+        <Label htmlFor="project-sandbox-enabled" className="cursor-pointer">
+          Sandbox coding agents
+        </Label>
+      </div>
+
+      <div className="flex items-center space-x-2">
+        <Checkbox
+          id="project-sandbox-deny-network"
+          checked={denyNetwork}
+          disabled={!enabled}
+          onCheckedChange={(checked: boolean) => setDenyNetwork(checked)}
+        />
+        <Label
+          htmlFor="project-sandbox-deny-network"
+          className="cursor-pointer"
+        >
+          Deny network access
+        </Label>
+      </div>
+
+      {error && (
+        <Alert variant="destructive">
+          <AlertDescription>{error}</AlertDescription>
+        </Alert>
+      )}
+      {message && (
+        <Alert>
+          <AlertDescription>{message}</AlertDescription>
+        </Alert>
+      )}
+
+      <div className="flex justify-end gap-2">
+        {sandbox && (
+          <Button variant="outline" onClick={handleReset}>
+            Reset
+          </Button>
+        )}
+        <Button onClick={handleSave} disabled={saving}>
+          {saving ? 'Saving...' : 'Save'}
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,231 @@
+import { useEffect, useState } from 'react';
+import { Loader2, Trash2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { scriptHooksApi } from '@/lib/api';
+import type { ScriptEvent, ScriptHook } from 'shared/types';
+
+interface ScriptHookSettingsProps {
+  projectId: string;
+}
+
+const EVENT_LABELS: Record<ScriptEvent, string> = {
+  task_created: 'Task created',
+  attempt_finished: 'Attempt finished',
+  before_merge: 'Before merge',
+};
+
+// What each event's scripts can do, beyond reading `task`
+const EVENT_HELP: Record<ScriptEvent, string> = {
+  task_created: 'set_labels([...]) and notify(title, message).',
+  attempt_finished:
+    'attempt.succeeded and attempt.runs, set_labels, notify and enqueue_follow_up(prompt).',
+  before_merge:
+    'attempt.base_branch, set_labels, notify and block_merge(reason).',
+};
+
+export function ScriptHookSettings({ projectId }: ScriptHookSettingsProps) {
+  const [hooks, setHooks] = useState<ScriptHook[]>([]);
+  const [name, setName] = useState('');
+  const [event, setEvent] = useState<ScriptEvent>('task_created');
+  const [script, setScript] = useState('');
+  const [enabled, setEnabled] = useState(true);
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setLoading(true);
+    scriptHooksApi
+      .getAll(projectId)
+      .then(setHooks)
+      .catch((err) => setError(err.message))
+      .finally(() => setLoading(false));
+  }, [projectId]);
+
+  const handleEdit = (hook: ScriptHook) => {
+    setName(hook.name);
+    setEvent(hook.event);
+    setScript(hook.script);
+    setEnabled(hook.enabled);
+  };
+
+  const handleSave = async () => {
+    setSaving(true);
+    setError(null);
+    try {
+      const saved = await scriptHooksApi.save(projectId, {
+        name: name.trim(),
+        event,
+        script,
+        enabled,
+      });
+      setHooks((current) => {
+        const others = current.filter((h) => h.name !== saved.name);
+        return [...others, saved].sort((a, b) => a.name.localeCompare(b.name));
+      });
+      setName('');
+      setScript('');
+      setEnabled(true);
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to save script hook'
+      );
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleDelete = async (hookName: string) => {
+    setError(null);
+    try {
+      await scriptHooksApi.delete(projectId, hookName);
+      setHooks((current) => current.filter((h) => h.name !== hookName));
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to delete script hook'
+      );
+    }
+  };
+
+  if (loading) {
+    return (
+      <div className="flex items-center justify-center py-4">
+        <Loader2 className="h-6 w-6 animate-spin" />
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      <p className="text-sm text-muted-foreground">
+        Rhai scripts this project runs when a task is created, when an
+        attempt's coding agent finishes and before an attempt is merged. Hooks
+        of the same event run in name order.
+      </p>
+
+      {hooks.length > 0 && (
+        <div className="space-y-2">
+          {hooks.map((hook) => (
+            <div
+              key={hook.id}
+              className="flex items-center justify-between gap-2 rounded-md border p-2"
+            >
+              <button
+                type="button"
+                className="min-w-0 text-left"
+                onClick={() => handleEdit(hook)}
+              >
+                <div className="text-sm font-medium">
+                  {hook.name}
+                  {!hook.enabled && (
+                    <span className="ml-2 text-xs text-muted-foreground">
+                      (disabled)
+                    </span>
+                  )}
+                </div>
+                <div className="text-xs text-muted-foreground">
+                  {EVENT_LABELS[hook.event]}
+                  {hook.last_run_at &&
+                    `, last run ${new Date(hook.last_run_at).toLocaleString()}`}
+                </div>
+                {hook.last_error && (
+                  <div className="text-xs text-destructive truncate">
+                    {hook.last_error}
+                  </div>
+                )}
+              </button>
+              <Button
+                variant="ghost"
+                size="sm"
+                onClick={() => handleDelete(hook.name)}
+              >
+                <Trash2 className="h-4 w-4" />
+              </Button>
+            </div>
+          ))}
+        </div>
+      )}
+
+      <div className="space-y-2">
+        <Label htmlFor="script-hook-name">Name</Label>
+        <Input
+          id="script-hook-name"
+          placeholder="e.g. label-bugs"
+          value={name}
+          onChange={(e) => setName(e.target.value)}
+        />
+      </div>
+
+      <div className="space-y-2">
+        <Label>Runs on</Label>
+        <Select
+          value={event}
+          onValueChange={(value) => setEvent(value as ScriptEvent)}
+        >
+          <SelectTrigger>
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            {Object.entries(EVENT_LABELS).map(([value, label]) => (
+              <SelectItem key={value} value={value}>
+                {label}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+        <p className="text-xs text-muted-foreground">{EVENT_HELP[event]}</p>
+      </div>
+
+      <div className="space-y-2">
+        <Label htmlFor="script-hook-script">Script</Label>
+        <textarea
+          id="script-hook-script"
+          placeholder={
+            'if task.title.contains("bug") {\n  set_labels(task.labels + ["bug"]);\n}'
+          }
+          value={script}
+          onChange={(e) => setScript(e.target.value)}
+          className="w-full p-2 border rounded-md resize-y font-mono text-sm"
+          rows={6}
+        />
+      </div>
+
+      <div className="flex items-center space-x-2">
+        <Checkbox
+          id="script-hook-enabled"
+          checked={enabled}
+          onCheckedChange={(checked: boolean) => setEnabled(checked)}
+        />
+        <Label htmlFor="script-hook-enabled" className="cursor-pointer">
+          Enabled
+        </Label>
+      </div>
+
+      {error && (
+        <Alert variant="destructive">
+          <AlertDescription>{error}</AlertDescription>
+        </Alert>
+      )}
+
+      <div className="flex justify-end">
+        <Button
+          onClick={handleSave}
+          disabled={saving || !name.trim() || !script.trim()}
+        >
+          {saving ? 'Saving...' : 'Save hook'}
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,106 @@
+import { useEffect, useState } from 'react';
+import { Loader2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Label } from '@/components/ui/label';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { projectSecurityApi } from '@/lib/api';
+
+interface SecuritySettingsProps {
+  projectId: string;
+}
+
+export function SecuritySettings({ projectId }: SecuritySettingsProps) {
+  const [requireTwoFactor, setRequireTwoFactor] = useState(false);
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setLoading(true);
+    projectSecurityApi
+      .get(projectId)
+      .then((existing) =>
+        setRequireTwoFactor(existing?.require_two_factor ?? false)
+      )
+      .catch((err) => setError(err.message))
+      .finally(() => setLoading(false));
+  }, [projectId]);
+
+  const handleSave = async () => {
+    setSaving(true);
+    setError(null);
+    setMessage(null);
+    try {
+      const saved = await projectSecurityApi.save(projectId, {
+        require_two_factor: requireTwoFactor,
+      });
+      setMessage(
+        saved.require_two_factor
+          ? 'Password accounts need two-factor authentication to reach this project.'
+          : "This project doesn't require two-factor authentication."
+      );
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to save sign-in rules'
+      );
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  if (loading) {
+    return (
+      <div className="flex items-center justify-center py-4">
+        <Loader2 className="h-6 w-6 animate-spin" />
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      <p className="text-sm text-muted-foreground">
+        Sign-in rules for the people who reach this project, admins included.
+        They only apply while sign-in is on.
+      </p>
+
+      <div className="flex items-center space-x-2">
+        <Checkbox
+          id="project-require-two-factor"
+          checked={requireTwoFactor}
+          onCheckedChange={(checked: boolean) => setRequireTwoFactor(checked)}
+        />
+        <div className="space-y-0.5">
+          <Label
+            htmlFor="project-require-two-factor"
+            className="cursor-pointer"
+          >
+            Require two-factor authentication
+          </Label>
+          <p className="text-sm text-muted-foreground">
+            Password accounts set up an authenticator app before they can reach
+            the project. OIDC accounts are left to the provider.
+          </p>
+        </div>
+      </div>
+
+      {error && (
+        <Alert variant="destructive">
+          <AlertDescription>{error}</AlertDescription>
+        </Alert>
+      )}
+      {message && (
+        <Alert>
+          <AlertDescription>{message}</AlertDescription>
+        </Alert>
+      )}
+
+      <div className="flex justify-end">
+        <Button onClick={handleSave} disabled={saving}>
+          {saving ? 'Saving...' : 'Save'}
+        </Button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,156 @@
+import { useEffect, useState } from 'react';
+import { Loader2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { projectShellEnvironmentApi } from '@/lib/api';
+import type { LoginShellMode, ProjectShellEnvironment } from 'shared/types';
+
+interface ShellEnvironmentSettingsProps {
+  projectId: string;
+}
+
+export function ShellEnvironmentSettings({
+  projectId,
+}: ShellEnvironmentSettingsProps) {
+  const [shellEnvironment, setShellEnvironment] =
+    useState<ProjectShellEnvironment | null>(null);
+  const [loginShell, setLoginShell] = useState<LoginShellMode>('auto');
+  const [envFile, setEnvFile] = useState('');
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setLoading(true);
+    projectShellEnvironmentApi
+      .get(projectId)
+      .then((existing) => {
+        setShellEnvironment(existing);
+        setLoginShell(existing?.login_shell ?? 'auto');
+        setEnvFile(existing?.env_file ?? '');
+      })
+      .catch((err) => setError(err.message))
+      .finally(() => setLoading(false));
+  }, [projectId]);
+
+  const handleSave = async () => {
+    setSaving(true);
+    setError(null);
+    setMessage(null);
+    try {
+      const saved = await projectShellEnvironmentApi.save(projectId, {
+        login_shell: loginShell,
+        env_file: envFile.trim() || null,
+      });
+      setShellEnvironment(saved);
+      setMessage("This project's commands will use this environment.");
+    } catch (err) {
+      setError(
+        err instanceof Error
+          ? err.message
+          : 'Failed to save shell environment setting'
+      );
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleReset = async () => {
+    setError(null);
+    setMessage(null);
+    try {
+      await projectShellEnvironmentApi.reset(projectId);
+      setShellEnvironment(null);
+      setLoginShell('auto');
+      setEnvFile('');
+    } catch (err) {
+      setError(
+        err instanceof Error
+          ? err.message
+          : 'Failed to reset shell environment setting'
+      );
+    }
+  };
+
+  if (loading) {
+    return (
+      <div className="flex items-center justify-center py-4">
+        <Loader2 className="h-6 w-6 animate-spin" />
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      <p className="text-sm text-muted-foreground">
+        Start this project's agents and scripts from the environment of your
+        login shell, so tools installed with nvm, pyenv or rustup are found.
+        Without a setting here, the one in Settings is used.
+      </p>
+
+      <div className="space-y-2">
+        <Label htmlFor="project-login-shell">Login shell environment</Label>
+        <Select
+          value={loginShell}
+          onValueChange={(value: LoginShellMode) => setLoginShell(value)}
+        >
+          <SelectTrigger id="project-login-shell">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            <SelectItem value="auto">
+              When not started from a terminal
+            </SelectItem>
+            <SelectItem value="always">Always</SelectItem>
+            <SelectItem value="never">Never</SelectItem>
+          </SelectContent>
+        </Select>
+      </div>
+
+      <div className="space-y-2">
+        <Label htmlFor="project-env-file">Env file</Label>
+        <Input
+          id="project-env-file"
+          placeholder=".env.development"
+          value={envFile}
+          onChange={(e) => setEnvFile(e.target.value)}
+        />
+        <p className="text-sm text-muted-foreground">
+          KEY=value lines loaded on top, relative to the attempt's worktree.
+        </p>
+      </div>
+
+      {error && (
+        <Alert variant="destructive">
+          <AlertDescription>{error}</AlertDescription>
+        </Alert>
+      )}
+      {message && (
+        <Alert>
+          <AlertDescription>{message}</AlertDescription>
+        </Alert>
+      )}
+
+      <div className="flex justify-end gap-2">
+        {shellEnvironment && (
+          <Button variant="outline" onClick={handleReset}>
+            Reset
+          </Button>
+        )}
+        <Button onClick={handleSave} disabled={saving}>
+          {saving ? 'Saving...' : 'Save'}
+        </Button>
+      </div>
+    </div>
+  );
+}
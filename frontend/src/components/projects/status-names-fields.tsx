@@ -0,0 +1,62 @@
+import { Input } from '@/components/ui/input';
+import type { TaskStatusNames } from 'shared/types';
+
+const STATUSES: { key: keyof TaskStatusNames; label: string }[] = [
+  { key: 'todo', label: 'To Do' },
+  { key: 'queued', label: 'Queued' },
+  { key: 'inprogress', label: 'In Progress' },
+  { key: 'planreview', label: 'Plan Review' },
+  { key: 'inreview', label: 'In Review' },
+  { key: 'done', label: 'Done' },
+  { key: 'cancelled', label: 'Cancelled' },
+];
+
+export const EMPTY_STATUS_NAMES: TaskStatusNames = {
+  todo: null,
+  queued: null,
+  inprogress: null,
+  planreview: null,
+  inreview: null,
+  done: null,
+  cancelled: null,
+};
+
+interface StatusNamesFieldsProps {
+  id: string;
+  value: TaskStatusNames;
+  options: string[];
+  onChange: (value: TaskStatusNames) => void;
+}
+
+/** Per-status overrides for the option name a task status is written as */
+export function StatusNamesFields({
+  id,
+  value,
+  options,
+  onChange,
+}: StatusNamesFieldsProps) {
+  return (
+    <>
+      <div className="grid grid-cols-2 gap-2">
+        {STATUSES.map(({ key, label }) => (
+          <div key={key} className="flex items-center gap-2">
+            <span className="text-sm w-24 shrink-0">{label}</span>
+            <Input
+              list={id}
+              placeholder={label}
+              value={value[key] ?? ''}
+              onChange={(e) =>
+                onChange({ ...value, [key]: e.target.value || null })
+              }
+            />
+          </div>
+        ))}
+      </div>
+      <datalist id={id}>
+        {options.map((option) => (
+          <option key={option} value={option} />
+        ))}
+      </datalist>
+    </>
+  );
+}
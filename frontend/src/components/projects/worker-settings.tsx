@@ -0,0 +1,141 @@
+import { useEffect, useState } from 'react';
+import { Loader2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { projectWorkerApi } from '@/lib/api';
+import type { ProjectWorker } from 'shared/types';
+
+interface WorkerSettingsProps {
+  projectId: string;
+}
+
+export function WorkerSettings({ projectId }: WorkerSettingsProps) {
+  const [worker, setWorker] = useState<ProjectWorker | null>(null);
+  const [url, setUrl] = useState('');
+  const [localRoot, setLocalRoot] = useState('');
+  const [remoteRoot, setRemoteRoot] = useState('');
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setLoading(true);
+    projectWorkerApi
+      .get(projectId)
+      .then((existing) => {
+        setWorker(existing);
+        setUrl(existing?.url ?? '');
+        setLocalRoot(existing?.local_root ?? '');
+        setRemoteRoot(existing?.remote_root ?? '');
+      })
+      .catch((err) => setError(err.message))
+      .finally(() => setLoading(false));
+  }, [projectId]);
+
+  const handleSave = async () => {
+    setSaving(true);
+    setError(null);
+    setMessage(null);
+    try {
+      const saved = await projectWorkerApi.save(projectId, {
+        url: url.trim(),
+        local_root: localRoot.trim() || null,
+        remote_root: remoteRoot.trim() || null,
+      });
+      setWorker(saved);
+      setMessage(`Attempts will run on ${saved.url}.`);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to save worker');
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleReset = async () => {
+    setError(null);
+    setMessage(null);
+    try {
+      await projectWorkerApi.reset(projectId);
+      setWorker(null);
+      setUrl('');
+      setLocalRoot('');
+      setRemoteRoot('');
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to reset worker');
+    }
+  };
+
+  if (loading) {
+    return (
+      <div className="flex items-center justify-center py-4">
+        <Loader2 className="h-6 w-6 animate-spin" />
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      <p className="text-sm text-muted-foreground">
+        Run this project's coding agents and setup and cleanup scripts on
+        another machine running cloud-runner. The worker must see the attempts'
+        worktrees, for example over a shared mount. Dev servers still run on
+        this machine.
+      </p>
+
+      <div className="space-y-2">
+        <Label htmlFor="worker-url">Worker URL</Label>
+        <Input
+          id="worker-url"
+          placeholder="e.g. http://build-box:8000"
+          value={url}
+          onChange={(e) => setUrl(e.target.value)}
+        />
+      </div>
+
+      <div className="space-y-2">
+        <Label htmlFor="worker-local-root">Worktrees directory here</Label>
+        <Input
+          id="worker-local-root"
+          placeholder="Leave empty if the worker uses the same paths"
+          value={localRoot}
+          onChange={(e) => setLocalRoot(e.target.value)}
+        />
+      </div>
+
+      <div className="space-y-2">
+        <Label htmlFor="worker-remote-root">Same directory on the worker</Label>
+        <Input
+          id="worker-remote-root"
+          placeholder="e.g. /mnt/vibe-kanban/worktrees"
+          value={remoteRoot}
+          onChange={(e) => setRemoteRoot(e.target.value)}
+        />
+      </div>
+
+      {error && (
+        <Alert variant="destructive">
+          <AlertDescription>{error}</AlertDescription>
+        </Alert>
+      )}
+      {message && (
+        <Alert>
+          <AlertDescription>{message}</AlertDescription>
+        </Alert>
+      )}
+
+      <div className="flex justify-end gap-2">
+        {worker && (
+          <Button variant="outline" onClick={handleReset}>
+            Reset
+          </Button>
+        )}
+        <Button onClick={handleSave} disabled={saving || !url.trim()}>
+          {saving ? 'Saving...' : 'Save'}
+        </Button>
+      </div>
+    </div>
+  );
+}
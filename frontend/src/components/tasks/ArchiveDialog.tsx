@@ -0,0 +1,226 @@
+import { ReactNode, useCallback, useEffect, useState } from 'react';
+import { ArchiveRestore, Trash2, Undo2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { Tabs, TabsContent, TabsList, TabsTrigger } from '@/components/ui/tabs';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { tasksApi } from '@/lib/api';
+import type { ShelvedTask } from 'shared/types';
+
+interface ArchiveDialogProps {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+  projectId: string;
+  // Called when a task goes back on the board
+  onChanged: () => void;
+}
+
+const ARCHIVE_DONE_AFTER_DAYS = 30;
+
+function ShelvedTaskRow({
+  task,
+  when,
+  children,
+}: {
+  task: ShelvedTask;
+  when: string | null;
+  children: ReactNode;
+}) {
+  return (
+    <div className="flex items-center gap-2 rounded-md border p-2">
+      <div className="min-w-0 flex-1">
+        <div className="truncate text-sm font-medium">{task.title}</div>
+        {when && (
+          <div className="text-xs text-muted-foreground">
+            {new Date(when).toLocaleString()}
+          </div>
+        )}
+      </div>
+      {children}
+    </div>
+  );
+}
+
+export function ArchiveDialog({
+  open,
+  onOpenChange,
+  projectId,
+  onChanged,
+}: ArchiveDialogProps) {
+  const [archived, setArchived] = useState<ShelvedTask[]>([]);
+  const [trashed, setTrashed] = useState<ShelvedTask[]>([]);
+  const [busy, setBusy] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const fetchShelved = useCallback(async () => {
+    try {
+      const [archivedTasks, trashedTasks] = await Promise.all([
+        tasksApi.getArchived(projectId),
+        tasksApi.getTrash(projectId),
+      ]);
+      setArchived(archivedTasks);
+      setTrashed(trashedTasks);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to load tasks');
+    }
+  }, [projectId]);
+
+  useEffect(() => {
+    if (open) {
+      fetchShelved();
+    } else {
+      setMessage(null);
+      setError(null);
+    }
+  }, [open, fetchShelved]);
+
+  const run = async (action: () => Promise<unknown>, onBoard: boolean) => {
+    setBusy(true);
+    setError(null);
+    setMessage(null);
+    try {
+      await action();
+      await fetchShelved();
+      if (onBoard) onChanged();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Something went wrong');
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const handleArchiveDone = () =>
+    run(async () => {
+      const result = await tasksApi.bulk(projectId, {
+        action: 'archive_done',
+        older_than_days: ARCHIVE_DONE_AFTER_DAYS,
+      });
+      setMessage(
+        result.succeeded.length === 0
+          ? 'No done tasks to archive.'
+          : `Archived ${result.succeeded.length} done task${result.succeeded.length === 1 ? '' : 's'}.`
+      );
+    }, true);
+
+  const handleDeleteForever = (task: ShelvedTask) => {
+    if (!confirm(`Delete "${task.title}" and its attempts for good?`)) return;
+    run(() => tasksApi.delete(projectId, task.id, true), false);
+  };
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent className="max-w-2xl">
+        <DialogHeader>
+          <DialogTitle>Archive and Trash</DialogTitle>
+          <DialogDescription>
+            Archived and deleted tasks are kept off the board. Put them back
+            from here.
+          </DialogDescription>
+        </DialogHeader>
+
+        {error && (
+          <Alert variant="destructive">
+            <AlertDescription>{error}</AlertDescription>
+          </Alert>
+        )}
+        {message && (
+          <Alert>
+            <AlertDescription>{message}</AlertDescription>
+          </Alert>
+        )}
+
+        <Tabs defaultValue="archived" className="w-full">
+          <TabsList className="grid w-full grid-cols-2 mb-4">
+            <TabsTrigger value="archived">
+              Archived ({archived.length})
+            </TabsTrigger>
+            <TabsTrigger value="trash">Trash ({trashed.length})</TabsTrigger>
+          </TabsList>
+          <TabsContent value="archived" className="space-y-2">
+            <div className="flex justify-end">
+              <Button
+                variant="outline"
+                size="sm"
+                onClick={handleArchiveDone}
+                disabled={busy}
+              >
+                Archive done tasks older than {ARCHIVE_DONE_AFTER_DAYS} days
+              </Button>
+            </div>
+            <div className="max-h-96 space-y-2 overflow-y-auto">
+              {archived.length === 0 && (
+                <p className="py-4 text-center text-sm text-muted-foreground">
+                  No archived tasks.
+                </p>
+              )}
+              {archived.map((task) => (
+                <ShelvedTaskRow
+                  key={task.id}
+                  task={task}
+                  when={task.archived_at}
+                >
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    disabled={busy}
+                    onClick={() =>
+                      run(() => tasksApi.unarchive(projectId, task.id), true)
+                    }
+                  >
+                    <ArchiveRestore className="h-4 w-4 mr-1" />
+                    Unarchive
+                  </Button>
+                </ShelvedTaskRow>
+              ))}
+            </div>
+          </TabsContent>
+          <TabsContent value="trash" className="space-y-2">
+            <div className="max-h-96 space-y-2 overflow-y-auto">
+              {trashed.length === 0 && (
+                <p className="py-4 text-center text-sm text-muted-foreground">
+                  The trash is empty.
+                </p>
+              )}
+              {trashed.map((task) => (
+                <ShelvedTaskRow
+                  key={task.id}
+                  task={task}
+                  when={task.deleted_at}
+                >
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    disabled={busy}
+                    onClick={() =>
+                      run(() => tasksApi.restore(projectId, task.id), true)
+                    }
+                  >
+                    <Undo2 className="h-4 w-4 mr-1" />
+                    Restore
+                  </Button>
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    className="text-destructive"
+                    disabled={busy}
+                    onClick={() => handleDeleteForever(task)}
+                  >
+                    <Trash2 className="h-4 w-4 mr-1" />
+                    Delete forever
+                  </Button>
+                </ShelvedTaskRow>
+              ))}
+            </div>
+          </TabsContent>
+        </Tabs>
+      </DialogContent>
+    </Dialog>
+  );
+}
@@ -0,0 +1,216 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Check, Copy, Loader2, Trash2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Badge } from '@/components/ui/badge';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { shareLinksApi } from '@/lib/api';
+import type { TaskShareLink } from 'shared/types';
+
+interface ShareLinksDialogProps {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+  projectId: string;
+  taskId: string;
+}
+
+const DEFAULT_EXPIRY_DAYS = '7';
+
+function linkState(link: TaskShareLink): string | null {
+  if (link.revoked_at) {
+    return 'Revoked';
+  }
+  if (link.expires_at && new Date(link.expires_at) <= new Date()) {
+    return 'Expired';
+  }
+  return null;
+}
+
+function ShareLinksDialog({
+  open,
+  onOpenChange,
+  projectId,
+  taskId,
+}: ShareLinksDialogProps) {
+  const [links, setLinks] = useState<TaskShareLink[]>([]);
+  const [loading, setLoading] = useState(true);
+  const [error, setError] = useState<string | null>(null);
+  const [expiresInDays, setExpiresInDays] = useState(DEFAULT_EXPIRY_DAYS);
+  const [creating, setCreating] = useState(false);
+  // The link just created; its token can't be shown again
+  const [createdUrl, setCreatedUrl] = useState<string | null>(null);
+  const [copied, setCopied] = useState(false);
+
+  const fetchLinks = useCallback(async () => {
+    setLoading(true);
+    try {
+      setLinks(await shareLinksApi.getAll(projectId, taskId));
+      setError(null);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to load links');
+    } finally {
+      setLoading(false);
+    }
+  }, [projectId, taskId]);
+
+  useEffect(() => {
+    if (open) {
+      setCreatedUrl(null);
+      fetchLinks();
+    }
+  }, [open, fetchLinks]);
+
+  const handleCreate = async () => {
+    setCreating(true);
+    setError(null);
+    try {
+      const days = parseInt(expiresInDays);
+      const created = await shareLinksApi.create(projectId, taskId, {
+        expires_in_days: days > 0 ? days : null,
+      });
+      setCreatedUrl(shareLinksApi.url(created.token));
+      setCopied(false);
+      await fetchLinks();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to create link');
+    } finally {
+      setCreating(false);
+    }
+  };
+
+  const handleCopy = async () => {
+    if (!createdUrl) {
+      return;
+    }
+    await navigator.clipboard.writeText(createdUrl);
+    setCopied(true);
+  };
+
+  const handleRevoke = async (link: TaskShareLink) => {
+    if (!confirm('Revoke this link? Anyone holding it loses access.')) {
+      return;
+    }
+    try {
+      await shareLinksApi.revoke(projectId, taskId, link.id);
+      await fetchLinks();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to revoke link');
+    }
+  };
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent className="max-w-lg">
+        <DialogHeader>
+          <DialogTitle>Share conversations</DialogTitle>
+          <DialogDescription>
+            Anyone with a link can read this task's coding agent conversations
+            and diffs, with secrets redacted, but nothing else on the board.
+          </DialogDescription>
+        </DialogHeader>
+
+        <div className="space-y-4">
+          {error && (
+            <Alert variant="destructive">
+              <AlertDescription>{error}</AlertDescription>
+            </Alert>
+          )}
+          {createdUrl && (
+            <Alert>
+              <AlertDescription className="space-y-2">
+                <p>Copy the link now; it won't be shown again.</p>
+                <div className="flex items-center gap-2">
+                  <Input readOnly value={createdUrl} className="text-xs" />
+                  <Button variant="outline" size="icon" onClick={handleCopy}>
+                    {copied ? (
+                      <Check className="h-4 w-4" />
+                    ) : (
+                      <Copy className="h-4 w-4" />
+                    )}
+                  </Button>
+                </div>
+              </AlertDescription>
+            </Alert>
+          )}
+
+          {loading ? (
+            <div className="flex justify-center py-4">
+              <Loader2 className="h-5 w-5 animate-spin" />
+            </div>
+          ) : links.length === 0 ? (
+            <p className="text-sm text-muted-foreground">No links yet.</p>
+          ) : (
+            <div className="space-y-2">
+              {links.map((link) => {
+                const state = linkState(link);
+                return (
+                  <div
+                    key={link.id}
+                    className="flex items-center justify-between rounded border p-2"
+                  >
+                    <div className="space-y-1">
+                      <div className="flex items-center gap-2">
+                        <code className="text-xs">{link.token_prefix}…</code>
+                        {state && <Badge variant="secondary">{state}</Badge>}
+                      </div>
+                      <p className="text-xs text-muted-foreground">
+                        {link.expires_at
+                          ? `Expires ${new Date(link.expires_at).toLocaleString()}`
+                          : 'Never expires'}
+                        {' · '}
+                        {link.last_viewed_at
+                          ? `Viewed ${link.view_count} time${
+                              Number(link.view_count) === 1 ? '' : 's'
+                            }, last ${new Date(
+                              link.last_viewed_at
+                            ).toLocaleString()}`
+                          : 'Never viewed'}
+                      </p>
+                    </div>
+                    {!link.revoked_at && (
+                      <Button
+                        variant="ghost"
+                        size="sm"
+                        onClick={() => handleRevoke(link)}
+                      >
+                        <Trash2 className="h-4 w-4" />
+                      </Button>
+                    )}
+                  </div>
+                );
+              })}
+            </div>
+          )}
+
+          <div className="flex items-end gap-2 rounded border p-3">
+            <div className="flex-1 space-y-2">
+              <Label htmlFor="share-link-expiry">Expires after (days)</Label>
+              <Input
+                id="share-link-expiry"
+                type="number"
+                min={1}
+                placeholder="Never"
+                value={expiresInDays}
+                onChange={(e) => setExpiresInDays(e.target.value)}
+              />
+            </div>
+            <Button onClick={handleCreate} disabled={creating}>
+              {creating && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+              Create link
+            </Button>
+          </div>
+        </div>
+      </DialogContent>
+    </Dialog>
+  );
+}
+
+export default ShareLinksDialog;
@@ -0,0 +1,116 @@
+import { ChangeEvent, useEffect, useRef, useState } from 'react';
+import { Paperclip, X } from 'lucide-react';
+import type { TaskAttachment } from 'shared/types';
+import { tasksApi } from '@/lib/api';
+
+interface TaskAttachmentsRowProps {
+  projectId: string;
+  taskId: string;
+}
+
+function TaskAttachmentsRow({ projectId, taskId }: TaskAttachmentsRowProps) {
+  const [attachments, setAttachments] = useState<TaskAttachment[]>([]);
+  const [uploading, setUploading] = useState(false);
+  const inputRef = useRef<HTMLInputElement>(null);
+
+  useEffect(() => {
+    setAttachments([]);
+    tasksApi
+      .getAttachments(projectId, taskId)
+      .then(setAttachments)
+      .catch(() => setAttachments([]));
+  }, [projectId, taskId]);
+
+  const handleFiles = async (e: ChangeEvent<HTMLInputElement>) => {
+    const files = Array.from(e.target.files ?? []);
+    e.target.value = '';
+    if (files.length === 0) return;
+    setUploading(true);
+    try {
+      for (const file of files) {
+        const attachment = await tasksApi.uploadAttachment(
+          projectId,
+          taskId,
+          file
+        );
+        setAttachments((current) => [...current, attachment]);
+      }
+    } catch (err) {
+      console.error('Failed to upload attachment:', err);
+    } finally {
+      setUploading(false);
+    }
+  };
+
+  const handleDelete = async (attachment: TaskAttachment) => {
+    try {
+      await tasksApi.deleteAttachment(projectId, taskId, attachment.id);
+      setAttachments((current) =>
+        current.filter((a) => a.id !== attachment.id)
+      );
+    } catch (err) {
+      console.error('Failed to delete attachment:', err);
+    }
+  };
+
+  return (
+    <div className="mt-2 flex flex-wrap items-center gap-2">
+      {attachments.map((attachment) => {
+        const url = tasksApi.attachmentUrl(projectId, taskId, attachment.id);
+        return (
+          <span
+            key={attachment.id}
+            className="inline-flex items-center gap-1 rounded-md border px-1 py-0.5 text-xs"
+          >
+            <a
+              href={url}
+              target="_blank"
+              rel="noreferrer"
+              className="inline-flex items-center gap-1 hover:underline"
+            >
+              {attachment.content_type.startsWith('image/') ? (
+                <img
+                  src={url}
+                  alt={attachment.file_name}
+                  className="h-6 w-6 rounded object-cover"
+                />
+              ) : (
+                <Paperclip className="h-3 w-3" />
+              )}
+              <span className="max-w-[10rem] truncate">
+                {attachment.file_name}
+              </span>
+            </a>
+            <button
+              type="button"
+              className="text-muted-foreground hover:text-foreground"
+              onClick={() => handleDelete(attachment)}
+              aria-label={`Remove attachment ${attachment.file_name}`}
+            >
+              <X className="h-3 w-3" />
+            </button>
+          </span>
+        );
+      })}
+      <button
+        type="button"
+        className="inline-flex items-center gap-1 text-xs text-muted-foreground hover:text-foreground disabled:opacity-50"
+        onClick={() => inputRef.current?.click()}
+        disabled={uploading}
+        title="Images are given to Claude, Gemini and Codex; other executors get their paths"
+      >
+        <Paperclip className="h-3 w-3" />
+        {uploading ? 'Uploading…' : 'Attach'}
+      </button>
+      <input
+        ref={inputRef}
+        type="file"
+        multiple
+        className="hidden"
+        onChange={handleFiles}
+      />
+    </div>
+  );
+}
+
+export default TaskAttachmentsRow;
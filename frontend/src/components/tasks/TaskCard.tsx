@@ -9,24 +9,37 @@ import {
 } from '@/components/ui/dropdown-menu';
 import { KanbanCard } from '@/components/ui/shadcn-io/kanban';
 import {
+  Archive,
   CheckCircle,
+  Clock,
   Edit,
+  GitPullRequest,
   Loader2,
   MoreHorizontal,
   Trash2,
   XCircle,
 } from 'lucide-react';
 import type { TaskWithAttemptStatus } from 'shared/types';
+import { tasksApi } from '@/lib/api';
 import { is_planning_executor_type } from '@/lib/utils';
+import { PRIORITY_LABELS } from '@/components/tasks/TaskFieldsRow';
 
 type Task = TaskWithAttemptStatus;
 
+const PRIORITY_COLORS: Record<NonNullable<Task['priority']>, string> = {
+  urgent: 'border-red-500 text-red-500',
+  high: 'border-orange-500 text-orange-500',
+  medium: 'border-yellow-500 text-yellow-600',
+  low: 'border-muted-foreground text-muted-foreground',
+};
+
 interface TaskCardProps {
   task: Task;
   index: number;
   status: string;
   onEdit: (task: Task) => void;
   onDelete: (taskId: string) => void;
+  onArchive: (taskId: string) => void;
   onViewDetails: (task: Task) => void;
   isFocused: boolean;
   tabIndex?: number;
@@ -38,6 +51,7 @@ export function TaskCard({
   status,
   onEdit,
   onDelete,
+  onArchive,
   onViewDetails,
   isFocused,
   tabIndex = -1,
@@ -65,6 +79,26 @@ export function TaskCard({
     onViewDetails(task);
   }, [task, onViewDetails]);
 
+  const handleCreatePullRequest = useCallback(async () => {
+    try {
+      const url = await tasksApi.createPullRequest(task.project_id, task.id);
+      window.open(url, '_blank');
+    } catch (err) {
+      console.error('Failed to create pull request:', err);
+    }
+  }, [task]);
+
+  const prColor =
+    task.pr_status === 'merged'
+      ? 'text-purple-500'
+      : task.pr_status === 'closed'
+        ? 'text-red-500'
+        : 'text-green-500';
+  const canCreatePullRequest =
+    task.latest_attempt_executor !== null &&
+    task.pr_status !== 'open' &&
+    task.pr_status !== 'merged';
+
   return (
     <KanbanCard
       key={task.id}
@@ -105,6 +139,36 @@ export function TaskCard({
             {task.last_attempt_failed && !task.has_merged_attempt && (
               <XCircle className="h-3 w-3 text-red-500" />
             )}
+            {/* Stale Indicator */}
+            {task.stale_since && !task.has_in_progress_attempt && (
+              <span
+                title={`Idle since ${new Date(task.stale_since).toLocaleString()}`}
+              >
+                <Clock className="h-3 w-3 text-amber-500" />
+              </span>
+            )}
+            {/* Pull Request */}
+            {task.pr_url && (
+              <a
+                href={task.pr_url}
+                target="_blank"
+                rel="noopener noreferrer"
+                title={`Pull request #${task.pr_number} is ${task.pr_status}`}
+                onClick={(e) => e.stopPropagation()}
+                onPointerDown={(e) => e.stopPropagation()}
+              >
+                <GitPullRequest className={`h-3 w-3 ${prColor}`} />
+              </a>
+            )}
+            {/* Queue Position */}
+            {task.queue_position !== null && (
+              <span
+                className="text-[10px] text-muted-foreground"
+                title="Waiting for a free execution slot"
+              >
+                {`#${task.queue_position}`}
+              </span>
+            )}
             {/* Actions Menu */}
             <div
               onPointerDown={(e) => e.stopPropagation()}
@@ -127,6 +191,16 @@ export function TaskCard({
                     <Edit className="h-4 w-4 mr-2" />
                     Edit
                   </DropdownMenuItem>
+                  {canCreatePullRequest && (
+                    <DropdownMenuItem onClick={handleCreatePullRequest}>
+                      <GitPullRequest className="h-4 w-4 mr-2" />
+                      Create Pull Request
+                    </DropdownMenuItem>
+                  )}
+                  <DropdownMenuItem onClick={() => onArchive(task.id)}>
+                    <Archive className="h-4 w-4 mr-2" />
+                    Archive
+                  </DropdownMenuItem>
                   <DropdownMenuItem
                     onClick={() => onDelete(task.id)}
                     className="text-destructive"
@@ -148,6 +222,33 @@ export function TaskCard({
             </p>
           </div>
         )}
+        {task.latest_summary && (
+          <p
+            className="truncate text-xs italic text-muted-foreground"
+            title={task.latest_summary}
+          >
+            {task.latest_summary}
+          </p>
+        )}
+        {(task.priority || task.labels.length > 0) && (
+          <div className="flex flex-wrap gap-1">
+            {task.priority && (
+              <span
+                className={`rounded-full border px-1.5 text-[10px] ${PRIORITY_COLORS[task.priority]}`}
+              >
+                {PRIORITY_LABELS[task.priority]}
+              </span>
+            )}
+            {task.labels.map((label) => (
+              <span
+                key={label}
+                className="rounded-full border px-1.5 text-[10px] text-muted-foreground"
+              >
+                {label}
+              </span>
+            ))}
+          </div>
+        )}
       </div>
     </KanbanCard>
   );
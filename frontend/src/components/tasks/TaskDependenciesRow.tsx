@@ -0,0 +1,144 @@
+import { useEffect, useState } from 'react';
+import { Link2, X } from 'lucide-react';
+import type {
+  DependencyTask,
+  TaskDependencies,
+  TaskWithAttemptStatus,
+} from 'shared/types';
+import { tasksApi } from '@/lib/api';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+
+interface TaskDependenciesRowProps {
+  projectId: string;
+  taskId: string;
+}
+
+function DependencyChip({
+  task,
+  onRemove,
+}: {
+  task: DependencyTask;
+  onRemove?: () => void;
+}) {
+  const done = task.status === 'done';
+  return (
+    <span
+      title={`Status: ${task.status}`}
+      className={`inline-flex items-center gap-1 rounded-full border px-2 py-0.5 text-xs ${
+        done ? 'text-muted-foreground line-through' : ''
+      }`}
+    >
+      {task.title}
+      {onRemove && (
+        <button
+          type="button"
+          className="text-muted-foreground hover:text-foreground"
+          onClick={onRemove}
+          aria-label={`Remove blocker ${task.title}`}
+        >
+          <X className="h-3 w-3" />
+        </button>
+      )}
+    </span>
+  );
+}
+
+function TaskDependenciesRow({ projectId, taskId }: TaskDependenciesRowProps) {
+  const [dependencies, setDependencies] = useState<TaskDependencies | null>(
+    null
+  );
+  const [candidates, setCandidates] = useState<TaskWithAttemptStatus[] | null>(
+    null
+  );
+  const [error, setError] = useState('');
+
+  useEffect(() => {
+    setDependencies(null);
+    setCandidates(null);
+    setError('');
+    tasksApi
+      .getDependencies(projectId, taskId)
+      .then(setDependencies)
+      .catch(() => setDependencies(null));
+  }, [projectId, taskId]);
+
+  const update = async (request: Promise<TaskDependencies>) => {
+    setError('');
+    try {
+      setDependencies(await request);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to update');
+    }
+  };
+
+  const loadCandidates = (open: boolean) => {
+    if (open && candidates === null) {
+      tasksApi
+        .getAll(projectId)
+        .then(setCandidates)
+        .catch(() => setCandidates([]));
+    }
+  };
+
+  if (!dependencies) {
+    return null;
+  }
+
+  const blockerIds = new Set(dependencies.blocked_by.map((task) => task.id));
+  const options = (candidates ?? []).filter(
+    (task) => task.id !== taskId && !blockerIds.has(task.id)
+  );
+
+  return (
+    <div className="mt-2 space-y-1 text-xs">
+      <div className="flex flex-wrap items-center gap-1">
+        <Link2 className="h-3 w-3 text-muted-foreground" />
+        <span className="text-muted-foreground">Blocked by</span>
+        {dependencies.blocked_by.map((task) => (
+          <DependencyChip
+            key={task.id}
+            task={task}
+            onRemove={() =>
+              update(tasksApi.removeDependency(projectId, taskId, task.id))
+            }
+          />
+        ))}
+        <Select
+          value=""
+          onOpenChange={loadCandidates}
+          onValueChange={(blockerId) =>
+            update(tasksApi.addDependency(projectId, taskId, blockerId))
+          }
+        >
+          <SelectTrigger className="h-6 w-auto gap-1 px-2 text-xs">
+            <SelectValue placeholder="Add blocker" />
+          </SelectTrigger>
+          <SelectContent>
+            {options.map((task) => (
+              <SelectItem key={task.id} value={task.id}>
+                {task.title}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+      </div>
+      {dependencies.blocks.length > 0 && (
+        <div className="flex flex-wrap items-center gap-1">
+          <span className="text-muted-foreground">Blocks</span>
+          {dependencies.blocks.map((task) => (
+            <DependencyChip key={task.id} task={task} />
+          ))}
+        </div>
+      )}
+      {error && <p className="text-destructive">{error}</p>}
+    </div>
+  );
+}
+
+export default TaskDependenciesRow;
@@ -5,12 +5,14 @@ import type { WorktreeDiff } from 'shared/types.ts';
 import { TaskBackgroundRefreshContext } from '@/components/context/taskDetailsContext.ts';
 import DiffFile from '@/components/tasks/TaskDetails/DiffFile.tsx';
 import { Loader } from '@/components/ui/loader';
+import { DiffReview } from '@/lib/types.ts';
 
 interface DiffCardProps {
   diff: WorktreeDiff | null;
   deletable?: boolean;
   compact?: boolean;
   className?: string;
+  review?: DiffReview;
 }
 
 export function DiffCard({
@@ -18,6 +20,7 @@ export function DiffCard({
   deletable = false,
   compact = false,
   className = '',
+  review,
 }: DiffCardProps) {
   const { isBackgroundRefreshing } = useContext(TaskBackgroundRefreshContext);
   const [collapsedFiles, setCollapsedFiles] = useState<Set<string>>(new Set());
@@ -99,6 +102,7 @@ export function DiffCard({
               deletable={deletable}
               file={file}
               fileIndex={fileIndex}
+              review={review}
               setCollapsedFiles={setCollapsedFiles}
             />
           ))}
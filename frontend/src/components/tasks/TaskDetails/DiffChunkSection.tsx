@@ -1,20 +1,27 @@
 import { Button } from '@/components/ui/button.tsx';
 import { ChevronDown, ChevronUp } from 'lucide-react';
 import type { DiffChunkType } from 'shared/types.ts';
-import { Dispatch, SetStateAction } from 'react';
-import { ProcessedSection } from '@/lib/types.ts';
+import { Dispatch, Fragment, SetStateAction, useState } from 'react';
+import { DiffReview, ProcessedSection } from '@/lib/types.ts';
+import LineComments from '@/components/tasks/TaskDetails/LineComments.tsx';
 
 type Props = {
+  filePath: string;
+  review?: DiffReview;
   section: ProcessedSection;
   sectionIndex: number;
   setExpandedSections: Dispatch<SetStateAction<Set<string>>>;
 };
 
 function DiffChunkSection({
+  filePath,
+  review,
   section,
   sectionIndex,
   setExpandedSections,
 }: Props) {
+  const [composingLine, setComposingLine] = useState<number | null>(null);
+
   const toggleExpandSection = (expandKey: string) => {
     setExpandedSections((prev) => {
       const newSet = new Set(prev);
@@ -106,28 +113,65 @@ function DiffChunkSection({
           </Button>
         </div>
       )}
-      {section.lines.map((line, lineIndex) => (
-        <div
-          key={`${sectionIndex}-${lineIndex}`}
-          className={getChunkClassName(line.chunkType)}
-          style={{ minWidth: 'max-content' }}
-        >
-          <div className={getLineNumberClassName(line.chunkType)}>
-            <span className="inline-block w-4 text-right text-xs">
-              {line.oldLineNumber || ''}
-            </span>
-            <span className="inline-block w-4 text-right ml-1 text-xs">
-              {line.newLineNumber || ''}
-            </span>
-          </div>
-          <div className="flex-1 px-2 min-h-[1rem] flex items-center">
-            <span className="inline-block w-3 text-xs">
-              {getChunkPrefix(line.chunkType)}
-            </span>
-            <span className="text-xs">{line.content}</span>
-          </div>
-        </div>
-      ))}
+      {section.lines.map((line, lineIndex) => {
+        const lineNumber = line.newLineNumber;
+        const lineComments =
+          review && lineNumber
+            ? review.comments.filter(
+                (comment) =>
+                  comment.file_path === filePath &&
+                  Number(comment.line_number) === lineNumber
+              )
+            : [];
+        const composing =
+          lineNumber !== undefined && composingLine === lineNumber;
+
+        return (
+          <Fragment key={`${sectionIndex}-${lineIndex}`}>
+            <div
+              className={getChunkClassName(line.chunkType)}
+              style={{ minWidth: 'max-content' }}
+            >
+              <div
+                className={`${getLineNumberClassName(line.chunkType)} ${
+                  review && lineNumber ? 'cursor-pointer' : ''
+                }`}
+                onClick={() =>
+                  review && lineNumber && setComposingLine(lineNumber)
+                }
+                title={
+                  review && lineNumber ? 'Comment on this line' : undefined
+                }
+              >
+                <span className="inline-block w-4 text-right text-xs">
+                  {line.oldLineNumber || ''}
+                </span>
+                <span className="inline-block w-4 text-right ml-1 text-xs">
+                  {line.newLineNumber || ''}
+                </span>
+              </div>
+              <div className="flex-1 px-2 min-h-[1rem] flex items-center">
+                <span className="inline-block w-3 text-xs">
+                  {getChunkPrefix(line.chunkType)}
+                </span>
+                <span className="text-xs">{line.content}</span>
+              </div>
+            </div>
+            {review &&
+              lineNumber !== undefined &&
+              (composing || lineComments.length > 0) && (
+                <LineComments
+                  comments={lineComments}
+                  composing={composing}
+                  filePath={filePath}
+                  lineNumber={lineNumber}
+                  onClose={() => setComposingLine(null)}
+                  review={review}
+                />
+              )}
+          </Fragment>
+        );
+      })}
     </div>
   );
 }
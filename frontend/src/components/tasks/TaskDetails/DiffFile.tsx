@@ -11,7 +11,7 @@ import {
   useState,
 } from 'react';
 import { TaskDeletingFilesContext } from '@/components/context/taskDetailsContext.ts';
-import { ProcessedLine, ProcessedSection } from '@/lib/types.ts';
+import { DiffReview, ProcessedLine, ProcessedSection } from '@/lib/types.ts';
 
 type Props = {
   collapsedFiles: Set<string>;
@@ -19,6 +19,7 @@ type Props = {
   deletable: boolean;
   file: FileDiff;
   fileIndex: number;
+  review?: DiffReview;
   setCollapsedFiles: Dispatch<SetStateAction<Set<string>>>;
 };
 
@@ -28,6 +29,7 @@ function DiffFile({
   deletable,
   compact,
   fileIndex,
+  review,
   setCollapsedFiles,
 }: Props) {
   const { deletingFiles, setFileToDelete } = useContext(
@@ -281,6 +283,8 @@ function DiffFile({
             {processedFileChunks.map((section, sectionIndex) => (
               <DiffChunkSection
                 key={`expand-${sectionIndex}`}
+                filePath={file.path}
+                review={review}
                 section={section}
                 sectionIndex={sectionIndex}
                 setExpandedSections={setExpandedSections}
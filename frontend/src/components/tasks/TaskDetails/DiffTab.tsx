@@ -1,10 +1,114 @@
 import { DiffCard } from '@/components/tasks/TaskDetails/DiffCard.tsx';
-import { useContext } from 'react';
-import { TaskDiffContext } from '@/components/context/taskDetailsContext.ts';
+import { useCallback, useContext, useEffect, useMemo, useState } from 'react';
+import { MessageSquare } from 'lucide-react';
+import {
+  TaskAttemptDataContext,
+  TaskDetailsContext,
+  TaskDiffContext,
+  TaskSelectedAttemptContext,
+} from '@/components/context/taskDetailsContext.ts';
 import { Loader } from '@/components/ui/loader';
+import { Button } from '@/components/ui/button.tsx';
+import { attemptsApi, reviewCommentsApi } from '@/lib/api.ts';
+import { DiffReview } from '@/lib/types.ts';
+import type { LinkedRepoDiff, ReviewComment } from 'shared/types.ts';
 
 function DiffTab() {
   const { diff, diffLoading, diffError } = useContext(TaskDiffContext);
+  const { projectId } = useContext(TaskDetailsContext);
+  const { selectedAttempt } = useContext(TaskSelectedAttemptContext);
+  const { fetchAttemptData, isAttemptRunning } = useContext(
+    TaskAttemptDataContext
+  );
+  const [comments, setComments] = useState<ReviewComment[]>([]);
+  const [sending, setSending] = useState(false);
+  const [commentError, setCommentError] = useState<string | null>(null);
+  const [linkedDiffs, setLinkedDiffs] = useState<LinkedRepoDiff[]>([]);
+
+  const attemptId = selectedAttempt?.id;
+  const taskId = selectedAttempt?.task_id;
+
+  const fetchComments = useCallback(async () => {
+    if (!projectId || !attemptId || !taskId) return;
+    try {
+      setComments(await reviewCommentsApi.getAll(projectId, taskId, attemptId));
+    } catch (err) {
+      console.error('Failed to load review comments:', err);
+    }
+  }, [projectId, attemptId, taskId]);
+
+  useEffect(() => {
+    setComments([]);
+    fetchComments();
+  }, [fetchComments]);
+
+  // A multi-repository attempt's other checkouts, shown apart from its own repository
+  useEffect(() => {
+    setLinkedDiffs([]);
+    if (!projectId || !attemptId || !taskId) return;
+    attemptsApi
+      .getLinkedDiffs(projectId, taskId, attemptId)
+      .then(setLinkedDiffs)
+      .catch((err) => console.error('Failed to load linked diffs:', err));
+  }, [projectId, attemptId, taskId, diff]);
+
+  const review = useMemo<DiffReview | undefined>(() => {
+    if (!projectId || !attemptId || !taskId) return undefined;
+    return {
+      comments,
+      onAddComment: async (filePath, lineNumber, body) => {
+        try {
+          await reviewCommentsApi.create(projectId, taskId, attemptId, {
+            execution_process_id: null,
+            file_path: filePath,
+            line_number: BigInt(lineNumber),
+            body,
+          });
+          setCommentError(null);
+          await fetchComments();
+        } catch (err) {
+          setCommentError(
+            err instanceof Error ? err.message : 'Failed to save comment'
+          );
+        }
+      },
+      onResolveComment: async (comment) => {
+        try {
+          await reviewCommentsApi.update(
+            projectId,
+            taskId,
+            attemptId,
+            comment.id,
+            { body: null, resolved: true }
+          );
+          await fetchComments();
+        } catch (err) {
+          setCommentError(
+            err instanceof Error ? err.message : 'Failed to resolve comment'
+          );
+        }
+      },
+    };
+  }, [projectId, attemptId, taskId, comments, fetchComments]);
+
+  const openComments = comments.filter((comment) => !comment.resolved).length;
+
+  const handleSendToAgent = async () => {
+    if (!projectId || !attemptId || !taskId) return;
+    setSending(true);
+    try {
+      await reviewCommentsApi.sendToAgent(projectId, taskId, attemptId);
+      setCommentError(null);
+      await fetchComments();
+      fetchAttemptData(attemptId, taskId);
+    } catch (err) {
+      setCommentError(
+        err instanceof Error ? err.message : 'Failed to send comments'
+      );
+    } finally {
+      setSending(false);
+    }
+  };
 
   if (diffLoading) {
     return (
@@ -23,8 +127,40 @@ function DiffTab() {
   }
 
   return (
-    <div className="h-full px-4 pb-4">
-      <DiffCard diff={diff} deletable compact={false} className="h-full" />
+    <div className="h-full px-4 pb-4 flex flex-col gap-2">
+      {(openComments > 0 || commentError) && (
+        <div className="flex items-center justify-between gap-2 flex-shrink-0">
+          <p className="text-xs text-destructive">{commentError}</p>
+          {openComments > 0 && (
+            <Button
+              size="sm"
+              onClick={handleSendToAgent}
+              disabled={sending || isAttemptRunning}
+              className="gap-1"
+            >
+              <MessageSquare className="h-3 w-3" />
+              {sending
+                ? 'Sending...'
+                : `Send ${openComments} comment${openComments !== 1 ? 's' : ''} to agent`}
+            </Button>
+          )}
+        </div>
+      )}
+      <DiffCard
+        diff={diff}
+        deletable
+        compact={false}
+        className="flex-1 min-h-0"
+        review={review}
+      />
+      {linkedDiffs.map((linked) => (
+        <div key={linked.project_id} className="flex flex-col gap-1">
+          <h3 className="text-sm font-medium text-muted-foreground">
+            {linked.project_name}
+          </h3>
+          <DiffCard diff={linked.diff} compact={false} />
+        </div>
+      ))}
     </div>
   );
 }
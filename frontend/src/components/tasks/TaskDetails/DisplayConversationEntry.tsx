@@ -6,14 +6,20 @@ import {
   Bot,
   Brain,
   CheckSquare,
+  ClipboardList,
   ChevronRight,
   ChevronUp,
   Edit,
+  ExternalLink,
   Eye,
+  FileDiff,
   Globe,
+  Paperclip,
   Plus,
   Search,
   Settings,
+  ShieldCheck,
+  ShieldX,
   Terminal,
   User,
 } from 'lucide-react';
@@ -22,7 +28,11 @@ import {
   type NormalizedEntryType,
   type WorktreeDiff,
 } from 'shared/types.ts';
-import { TaskDiffContext } from '@/components/context/taskDetailsContext.ts';
+import {
+  TaskDetailsContext,
+  TaskDiffContext,
+} from '@/components/context/taskDetailsContext.ts';
+import { tasksApi } from '@/lib/api';
 
 type Props = {
   entry: NormalizedEntry;
@@ -46,6 +56,22 @@ const getEntryIcon = (entryType: NormalizedEntryType) => {
   if (entryType.type === 'error_message') {
     return <AlertCircle className="h-4 w-4 text-red-600" />;
   }
+  if (entryType.type === 'attachment') {
+    return <Paperclip className="h-4 w-4 text-sky-600" />;
+  }
+  if (entryType.type === 'plan_entry') {
+    return <ClipboardList className="h-4 w-4 text-blue-600" />;
+  }
+  if (entryType.type === 'file_edit') {
+    return <FileDiff className="h-4 w-4 text-red-600" />;
+  }
+  if (entryType.type === 'verification') {
+    return entryType.passed ? (
+      <ShieldCheck className="h-4 w-4 text-green-600" />
+    ) : (
+      <ShieldX className="h-4 w-4 text-red-600" />
+    );
+  }
   if (entryType.type === 'tool_use') {
     const { action_type, tool_name } = entryType;
 
@@ -112,14 +138,21 @@ const getContentClassName = (entryType: NormalizedEntryType) => {
     return `${baseClasses} font-mono text-purple-700 dark:text-purple-300 bg-purple-50 dark:bg-purple-950/20 px-2 py-1 rounded`;
   }
 
-  // Special styling for plan presentations
+  // Special styling for plan presentations and plans up for review
   if (
-    entryType.type === 'tool_use' &&
-    entryType.action_type.action === 'plan_presentation'
+    entryType.type === 'plan_entry' ||
+    (entryType.type === 'tool_use' &&
+      entryType.action_type.action === 'plan_presentation')
   ) {
     return `${baseClasses} text-blue-700 dark:text-blue-300 bg-blue-50 dark:bg-blue-950/20 px-3 py-2 rounded-md border-l-4 border-blue-400`;
   }
 
+  if (entryType.type === 'verification') {
+    return entryType.passed
+      ? `${baseClasses} bg-green-50 dark:bg-green-950/20 px-3 py-2 rounded-md border-l-4 border-green-400`
+      : `${baseClasses} bg-red-50 dark:bg-red-950/20 px-3 py-2 rounded-md border-l-4 border-red-400`;
+  }
+
   return baseClasses;
 };
 
@@ -238,6 +271,7 @@ const shouldRenderMarkdown = (entryType: NormalizedEntryType) => {
   // Render markdown for assistant messages, plan presentations, and tool outputs that contain backticks
   return (
     entryType.type === 'assistant_message' ||
+    entryType.type === 'plan_entry' ||
     (entryType.type === 'tool_use' &&
       entryType.action_type.action === 'plan_presentation') ||
     (entryType.type === 'tool_use' &&
@@ -266,6 +300,146 @@ const shouldRenderMarkdown = (entryType: NormalizedEntryType) => {
   );
 };
 
+// An attachment the executor was given, shown the way it saw it
+function AttachmentContent({ entry }: { entry: NormalizedEntry }) {
+  const { task, projectId } = useContext(TaskDetailsContext);
+  if (entry.entry_type.type !== 'attachment' || !task) {
+    return <>{entry.content}</>;
+  }
+  const url = tasksApi.attachmentUrl(
+    projectId,
+    task.id,
+    entry.entry_type.attachment_id
+  );
+  return (
+    <a href={url} target="_blank" rel="noreferrer" className="space-y-1">
+      <span className="block text-muted-foreground">
+        Attached {entry.content}
+      </span>
+      {entry.entry_type.content_type.startsWith('image/') && (
+        <img
+          src={url}
+          alt={entry.content}
+          className="max-h-48 rounded border object-contain"
+        />
+      )}
+    </a>
+  );
+}
+
+// Where the file a tool read or wrote is: on the git host, or at least on disk
+function FileLocationLink({ entry }: { entry: NormalizedEntry }) {
+  if (entry.entry_type.type !== 'tool_use') return null;
+  const { action_type } = entry.entry_type;
+  if (
+    action_type.action !== 'file_read' &&
+    action_type.action !== 'file_write'
+  ) {
+    return null;
+  }
+  const { location } = action_type;
+  if (!location) return null;
+  return (
+    <div
+      className="mt-1 flex items-center gap-2 text-xs text-muted-foreground"
+      title={location.absolute_path}
+    >
+      {location.url && (
+        <a
+          href={location.url}
+          target="_blank"
+          rel="noreferrer"
+          className="inline-flex items-center gap-1 hover:underline"
+        >
+          <ExternalLink className="h-3 w-3" />
+          Open {action_type.path}
+        </a>
+      )}
+      {!location.exists && <span>(no longer exists)</span>}
+    </div>
+  );
+}
+
+// The unified diff of one tool call's edit, coloured line by line
+function FileEditContent({ entry }: { entry: NormalizedEntry }) {
+  if (entry.entry_type.type !== 'file_edit') {
+    return <>{entry.content}</>;
+  }
+  const lineClassName = (line: string) => {
+    if (line.startsWith('+++') || line.startsWith('---')) {
+      return 'text-muted-foreground';
+    }
+    if (line.startsWith('@@')) return 'text-blue-600 dark:text-blue-400';
+    if (line.startsWith('+')) {
+      return 'bg-green-50 text-green-800 dark:bg-green-950/30 dark:text-green-300';
+    }
+    if (line.startsWith('-')) {
+      return 'bg-red-50 text-red-800 dark:bg-red-950/30 dark:text-red-300';
+    }
+    return '';
+  };
+  return (
+    <details open>
+      <summary className="cursor-pointer font-mono">{entry.content}</summary>
+      <pre className="mt-2 max-h-96 overflow-auto rounded border font-mono text-xs">
+        {entry.entry_type.diff
+          .replace(/\n$/, '')
+          .split('\n')
+          .map((line, i) => (
+            <div key={i} className={`px-2 ${lineClassName(line)}`}>
+              {line || ' '}
+            </div>
+          ))}
+      </pre>
+    </details>
+  );
+}
+
+// How long a tool call kept the agent busy, with when it started on hover
+function ToolCallDuration({ entry }: { entry: NormalizedEntry }) {
+  if (entry.entry_type.type !== 'tool_use' || entry.duration_ms === null) {
+    return null;
+  }
+  return (
+    <div
+      className="mt-1 text-xs text-muted-foreground"
+      title={
+        entry.timestamp
+          ? `Started ${new Date(entry.timestamp).toLocaleString()}`
+          : undefined
+      }
+    >
+      Took {(entry.duration_ms / 1000).toFixed(1)}s
+    </div>
+  );
+}
+
+// A verification command's outcome, with its output folded away unless it failed
+function VerificationContent({ entry }: { entry: NormalizedEntry }) {
+  if (entry.entry_type.type !== 'verification') {
+    return <>{entry.content}</>;
+  }
+  const { command, passed, exit_code, duration_ms } = entry.entry_type;
+  const outcome = passed
+    ? 'passed'
+    : exit_code === null
+      ? 'was stopped'
+      : `failed with exit code ${exit_code}`;
+  return (
+    <details open={!passed}>
+      <summary className="cursor-pointer">
+        Verification <code className="font-mono">{command}</code> {outcome}{' '}
+        in {(duration_ms / 1000).toFixed(1)}s
+      </summary>
+      {entry.content && (
+        <pre className="mt-2 max-h-64 overflow-auto font-mono text-xs">
+          {entry.content}
+        </pre>
+      )}
+    </details>
+  );
+}
+
 function DisplayConversationEntry({ entry, index, diffDeletable }: Props) {
   const { diff } = useContext(TaskDiffContext);
   const [expandedErrors, setExpandedErrors] = useState<Set<number>>(new Set());
@@ -362,7 +536,13 @@ function DisplayConversationEntry({ entry, index, diffDeletable }: Props) {
             </div>
           ) : (
             <div className={getContentClassName(entry.entry_type)}>
-              {shouldRenderMarkdown(entry.entry_type) ? (
+              {entry.entry_type.type === 'attachment' ? (
+                <AttachmentContent entry={entry} />
+              ) : entry.entry_type.type === 'verification' ? (
+                <VerificationContent entry={entry} />
+              ) : entry.entry_type.type === 'file_edit' ? (
+                <FileEditContent entry={entry} />
+              ) : shouldRenderMarkdown(entry.entry_type) ? (
                 <MarkdownRenderer
                   content={entry.content}
                   className="whitespace-pre-wrap break-words"
@@ -372,6 +552,8 @@ function DisplayConversationEntry({ entry, index, diffDeletable }: Props) {
               )}
             </div>
           )}
+          <FileLocationLink entry={entry} />
+          <ToolCallDuration entry={entry} />
         </div>
       </div>
 
@@ -0,0 +1,95 @@
+import { useState } from 'react';
+import { Check } from 'lucide-react';
+import { Button } from '@/components/ui/button.tsx';
+import { Textarea } from '@/components/ui/textarea.tsx';
+import type { ReviewComment } from 'shared/types.ts';
+import { DiffReview } from '@/lib/types.ts';
+
+type Props = {
+  comments: ReviewComment[];
+  composing: boolean;
+  filePath: string;
+  lineNumber: number;
+  onClose: () => void;
+  review: DiffReview;
+};
+
+function LineComments({
+  comments,
+  composing,
+  filePath,
+  lineNumber,
+  onClose,
+  review,
+}: Props) {
+  const [body, setBody] = useState('');
+  const [saving, setSaving] = useState(false);
+
+  const handleSave = async () => {
+    setSaving(true);
+    try {
+      await review.onAddComment(filePath, lineNumber, body.trim());
+      setBody('');
+      onClose();
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  return (
+    <div className="border-y bg-blue-50/50 dark:bg-blue-950/20 px-3 py-2 space-y-2 font-sans">
+      {comments.map((comment) => (
+        <div
+          key={comment.id}
+          className={`flex items-start justify-between gap-2 text-xs ${
+            comment.resolved ? 'text-muted-foreground line-through' : ''
+          }`}
+        >
+          <p className="whitespace-pre-wrap">{comment.body}</p>
+          {!comment.resolved && (
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={() => review.onResolveComment(comment)}
+              className="h-5 px-1"
+              title="Resolve"
+            >
+              <Check className="h-3 w-3" />
+            </Button>
+          )}
+        </div>
+      ))}
+      {composing && (
+        <div className="space-y-2">
+          <Textarea
+            autoFocus
+            placeholder={`Comment on line ${lineNumber}`}
+            value={body}
+            onChange={(e) => setBody(e.target.value)}
+            className="min-h-[60px] text-xs"
+          />
+          <div className="flex justify-end gap-2">
+            <Button
+              variant="outline"
+              size="sm"
+              onClick={onClose}
+              className="h-6 text-xs"
+            >
+              Cancel
+            </Button>
+            <Button
+              size="sm"
+              onClick={handleSave}
+              disabled={saving || !body.trim()}
+              className="h-6 text-xs"
+            >
+              {saving ? 'Saving...' : 'Comment'}
+            </Button>
+          </div>
+        </div>
+      )}
+    </div>
+  );
+}
+
+export default LineComments;
@@ -63,6 +63,8 @@ function LogsTab() {
     executionState.execution_state === 'CodingAgentFailed';
   const isCodingAgentStopped =
     executionState.execution_state === 'CodingAgentStopped';
+  const isCodingAgentRetrying =
+    executionState.execution_state === 'CodingAgentRetrying';
   const isComplete = executionState.execution_state === 'Complete';
   const hasChanges = executionState.has_changes;
 
@@ -133,6 +135,7 @@ function LogsTab() {
     isCodingAgentComplete ||
     isCodingAgentFailed ||
     isCodingAgentStopped ||
+    isCodingAgentRetrying ||
     hasChanges
   ) {
     return <Conversation />;
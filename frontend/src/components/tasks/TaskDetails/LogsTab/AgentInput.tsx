@@ -0,0 +1,71 @@
+import { useContext, useState } from 'react';
+import { Send } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import {
+  TaskDetailsContext,
+  TaskSelectedAttemptContext,
+} from '@/components/context/taskDetailsContext.ts';
+import { attemptsApi } from '@/lib/api.ts';
+
+type Props = {
+  processId: string;
+};
+
+// A message for the agent while it runs, read by its CLI between steps
+function AgentInput({ processId }: Props) {
+  const { projectId } = useContext(TaskDetailsContext);
+  const { selectedAttempt } = useContext(TaskSelectedAttemptContext);
+  const [message, setMessage] = useState('');
+  const [sending, setSending] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const send = async () => {
+    if (!selectedAttempt || !message.trim()) return;
+    setSending(true);
+    setError(null);
+    try {
+      await attemptsApi.sendInput(
+        projectId,
+        selectedAttempt.task_id,
+        selectedAttempt.id,
+        processId,
+        { content: message.trim() }
+      );
+      setMessage('');
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to send');
+    } finally {
+      setSending(false);
+    }
+  };
+
+  return (
+    <div className="mt-4">
+      <div className="flex gap-2">
+        <Input
+          placeholder="Tell the agent something while it works..."
+          value={message}
+          disabled={sending}
+          onChange={(e) => setMessage(e.target.value)}
+          onKeyDown={(e) => {
+            if (e.key === 'Enter' && !e.shiftKey) {
+              e.preventDefault();
+              send();
+            }
+          }}
+        />
+        <Button
+          size="sm"
+          disabled={sending || !message.trim()}
+          onClick={send}
+        >
+          <Send className="h-4 w-4" />
+        </Button>
+      </div>
+      {error && <p className="text-sm text-destructive mt-2">{error}</p>}
+    </div>
+  );
+}
+
+export default AgentInput;
@@ -0,0 +1,85 @@
+import { useContext, useState } from 'react';
+import { ShieldAlert } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import {
+  TaskAttemptDataContext,
+  TaskDetailsContext,
+  TaskSelectedAttemptContext,
+} from '@/components/context/taskDetailsContext.ts';
+import { attemptsApi } from '@/lib/api.ts';
+import type { PendingApproval } from 'shared/types.ts';
+
+type Props = {
+  approval: PendingApproval;
+};
+
+// A dangerous command the agent is waiting on; it runs only once allowed
+function CommandApproval({ approval }: Props) {
+  const { projectId } = useContext(TaskDetailsContext);
+  const { selectedAttempt } = useContext(TaskSelectedAttemptContext);
+  const { setAttemptData } = useContext(TaskAttemptDataContext);
+  const [answering, setAnswering] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const answer = async (allow: boolean) => {
+    if (!selectedAttempt) return;
+    setAnswering(true);
+    setError(null);
+    try {
+      await attemptsApi.answerApproval(
+        projectId,
+        selectedAttempt.task_id,
+        selectedAttempt.id,
+        approval.execution_process_id,
+        { approval_id: approval.id, allow }
+      );
+      setAttemptData((prev) => {
+        const approvals = { ...prev.approvals };
+        delete approvals[approval.execution_process_id];
+        return { ...prev, approvals };
+      });
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to answer');
+    } finally {
+      setAnswering(false);
+    }
+  };
+
+  return (
+    <div className="mt-4 p-4 rounded-lg border border-orange-200 dark:border-orange-800 bg-orange-50 dark:bg-orange-950/20">
+      <div className="flex items-center gap-2 mb-2">
+        <ShieldAlert className="h-5 w-5 text-orange-600 dark:text-orange-400" />
+        <p className="text-lg font-semibold text-orange-800 dark:text-orange-300">
+          Approval Needed
+        </p>
+      </div>
+      <p className="text-orange-700 dark:text-orange-400 mb-2">
+        The agent is waiting to run this command, which {approval.reason}.
+      </p>
+      <pre className="mb-3 p-2 rounded bg-background text-xs whitespace-pre-wrap break-all">
+        {approval.command}
+      </pre>
+      {error && <p className="text-sm text-destructive mb-2">{error}</p>}
+      <div className="flex gap-2">
+        <Button
+          size="sm"
+          variant="destructive"
+          disabled={answering}
+          onClick={() => answer(true)}
+        >
+          Allow
+        </Button>
+        <Button
+          size="sm"
+          variant="outline"
+          disabled={answering}
+          onClick={() => answer(false)}
+        >
+          Deny
+        </Button>
+      </div>
+    </div>
+  );
+}
+
+export default CommandApproval;
@@ -7,17 +7,23 @@ import {
   useRef,
   useState,
 } from 'react';
-import { TaskAttemptDataContext } from '@/components/context/taskDetailsContext.ts';
+import {
+  TaskAttemptDataContext,
+  TaskExecutionStateContext,
+} from '@/components/context/taskDetailsContext.ts';
 import { useTaskPlan } from '@/components/context/TaskPlanContext.ts';
 import { Loader } from '@/components/ui/loader.tsx';
 import { Button } from '@/components/ui/button';
 import { AlertTriangle } from 'lucide-react';
 import Prompt from './Prompt';
 import ConversationEntry from './ConversationEntry';
+import CommandApproval from './CommandApproval';
+import AgentInput from './AgentInput';
 import { ConversationEntryDisplayType } from '@/lib/types';
 
 function Conversation() {
   const { attemptData, isAttemptRunning } = useContext(TaskAttemptDataContext);
+  const { executionState } = useContext(TaskExecutionStateContext);
   const { isPlanningMode, latestProcessHasNoPlan } = useTaskPlan();
   const [shouldAutoScrollLogs, setShouldAutoScrollLogs] = useState(true);
   const [conversationUpdateTrigger, setConversationUpdateTrigger] = useState(0);
@@ -192,7 +198,10 @@ function Conversation() {
   const showStatusBanner =
     mostRecentProcess &&
     (mostRecentProcess.status === 'failed' ||
-      mostRecentProcess.status === 'killed');
+      mostRecentProcess.status === 'killed' ||
+      mostRecentProcess.status === 'interrupted' ||
+      mostRecentProcess.status === 'timedout' ||
+      mostRecentProcess.status === 'resourcelimitexceeded');
 
   return (
     <div
@@ -216,6 +225,18 @@ function Conversation() {
       )}
       {/* Render live viewers for running processes (after paginated list) */}
       {renderedRunningProcessLogs}
+      {runningProcessLogs.map((log) => {
+        const approval = attemptData.approvals[String(log.id)];
+        return approval ? (
+          <CommandApproval key={approval.id} approval={approval} />
+        ) : null;
+      })}
+      {executionState?.capabilities?.supports_interactive &&
+        runningProcessLogs
+          .filter((log) => log.process_type === 'codingagent')
+          .map((log) => (
+            <AgentInput key={String(log.id)} processId={String(log.id)} />
+          ))}
       {/* If nothing to show at all, show loader */}
       {visibleEntries.length === 0 && runningProcessLogs.length === 0 && (
         <Loader
@@ -243,12 +264,24 @@ function Conversation() {
           >
             {mostRecentProcess.status === 'failed'
               ? 'Coding Agent Failed'
-              : 'Coding Agent Stopped'}
+              : mostRecentProcess.status === 'timedout'
+                ? 'Coding Agent Timed Out'
+                : mostRecentProcess.status === 'resourcelimitexceeded'
+                  ? 'Coding Agent Out of Memory'
+                  : mostRecentProcess.status === 'interrupted'
+                    ? 'Coding Agent Interrupted'
+                    : 'Coding Agent Stopped'}
           </p>
           <p className="text-muted-foreground">
             {mostRecentProcess.status === 'failed'
               ? 'The coding agent encountered an error.'
-              : 'The coding agent was stopped.'}
+              : mostRecentProcess.status === 'timedout'
+                ? 'The coding agent ran past its timeout and was stopped.'
+                : mostRecentProcess.status === 'resourcelimitexceeded'
+                  ? 'The coding agent went over its memory limit and was stopped.'
+                  : mostRecentProcess.status === 'interrupted'
+                    ? 'The server restarted while the coding agent was running.'
+                    : 'The coding agent was stopped.'}
           </p>
         </div>
       )}
@@ -263,7 +296,7 @@ function Conversation() {
             </p>
           </div>
           <p className="text-orange-700 dark:text-orange-400">
-            The last execution attempt did not produce a plan. Task creation is
+            The last execution attempt did not produce a plan. Approval is
             disabled until a plan is available. Try providing more specific
             instructions or check the conversation for any errors.
           </p>
@@ -1,9 +1,29 @@
+import { useContext } from 'react';
 import { ConversationEntryDisplayType } from '@/lib/types';
 import DisplayConversationEntry from '../DisplayConversationEntry';
 import { NormalizedConversationViewer } from './NormalizedConversationViewer';
 import Prompt from './Prompt';
 import { Loader } from '@/components/ui/loader.tsx';
-import { ExecutionProcess } from 'shared/types';
+import {
+  ExecutionProcess,
+  NormalizedEntry,
+  TruncatedContent,
+} from 'shared/types';
+import { Quote } from 'lucide-react';
+import {
+  useFollowUpDraft,
+} from '@/components/context/FollowUpDraftContext.tsx';
+import {
+  TaskDetailsContext,
+  TaskSelectedAttemptContext,
+} from '@/components/context/taskDetailsContext.ts';
+import { attemptsApi } from '@/lib/api.ts';
+
+// Entries too large to render keep their start; what was cut is in their metadata, which
+// the generated types leave out
+const truncation = (entry: NormalizedEntry): TruncatedContent | null =>
+  (entry as { metadata?: { truncated?: TruncatedContent } | null }).metadata
+    ?.truncated ?? null;
 
 type Props = {
   item: ConversationEntryDisplayType;
@@ -20,6 +40,10 @@ const ConversationEntry = ({
   visibleEntriesLength,
   runningProcessDetails,
 }: Props) => {
+  const { addQuote } = useFollowUpDraft();
+  const { projectId, task } = useContext(TaskDetailsContext);
+  const { selectedAttempt } = useContext(TaskSelectedAttemptContext);
+  const truncated = truncation(item.entry);
   const showPrompt = item.isFirstInProcess && item.processPrompt;
   // For running processes, render the live viewer below the static entries
   if (item.processIsRunning && idx === visibleEntriesLength - 1) {
@@ -41,13 +65,46 @@ const ConversationEntry = ({
     return <Loader message="Loading live logs..." size={24} className="py-4" />;
   } else {
     return (
-      <div key={item.entry.timestamp || idx}>
+      <div key={item.entry.timestamp || idx} className="group relative">
         {showPrompt && <Prompt prompt={item.processPrompt || ''} />}
         <DisplayConversationEntry
           entry={item.entry}
           index={idx}
           diffDeletable
         />
+        {truncated && selectedAttempt && (
+          <a
+            className="ml-6 text-xs text-muted-foreground underline hover:text-foreground"
+            href={attemptsApi.entryContentUrl(
+              projectId,
+              task.id,
+              selectedAttempt.id,
+              item.processId,
+              item.entryIndex
+            )}
+            target="_blank"
+            rel="noreferrer"
+          >
+            {truncated.binary ? 'Open the binary content' : 'Show all'} (
+            {truncated.original_bytes.toLocaleString()} bytes)
+          </a>
+        )}
+        {addQuote && (
+          <button
+            type="button"
+            className="absolute right-0 bottom-0 hidden group-hover:block p-1 text-muted-foreground hover:text-foreground"
+            title="Quote in the follow-up"
+            onClick={() =>
+              addQuote({
+                type: 'entry',
+                execution_process_id: item.processId,
+                entry_index: item.entryIndex,
+              })
+            }
+          >
+            <Quote className="h-3 w-3" />
+          </button>
+        )}
       </div>
     );
   }
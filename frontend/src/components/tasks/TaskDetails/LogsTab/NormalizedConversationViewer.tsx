@@ -1,4 +1,4 @@
-import { Hammer } from 'lucide-react';
+import { FileDiff, Hammer } from 'lucide-react';
 import { Loader } from '@/components/ui/loader.tsx';
 import MarkdownRenderer from '@/components/ui/markdown-renderer.tsx';
 import type { ExecutionProcess, WorktreeDiff } from 'shared/types.ts';
@@ -87,6 +87,37 @@ export function NormalizedConversationViewer({
           />
         ))}
       </div>
+
+      {/* What this run changed, once it has finished */}
+      {conversation.diff_summary &&
+        conversation.diff_summary.files.length > 0 && (
+          <div className="mt-3 flex items-start gap-3">
+            <div className="flex-shrink-0 mt-1">
+              <FileDiff className="h-4 w-4 text-muted-foreground" />
+            </div>
+            <div className="flex-1 min-w-0 text-xs text-muted-foreground">
+              <p>
+                Changed {conversation.diff_summary.files.length} file
+                {conversation.diff_summary.files.length === 1 ? '' : 's'}{' '}
+                <span className="text-green-600">
+                  +{conversation.diff_summary.additions}
+                </span>{' '}
+                <span className="text-red-600">
+                  -{conversation.diff_summary.deletions}
+                </span>
+              </p>
+              <ul className="mt-1 space-y-0.5 font-mono">
+                {conversation.diff_summary.files.map((file) => (
+                  <li key={file.path} className="truncate">
+                    {file.path}{' '}
+                    <span className="text-green-600">+{file.additions}</span>{' '}
+                    <span className="text-red-600">-{file.deletions}</span>
+                  </li>
+                ))}
+              </ul>
+            </div>
+          </div>
+        )}
     </div>
   );
 }
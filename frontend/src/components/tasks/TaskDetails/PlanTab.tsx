@@ -14,7 +14,8 @@ import {
 import { useTaskPlan } from '@/components/context/TaskPlanContext.ts';
 import { Loader } from '@/components/ui/loader';
 import MarkdownRenderer from '@/components/ui/markdown-renderer.tsx';
-import { NormalizedEntry } from 'shared/types.ts';
+import { NormalizedEntry, PlanComparison } from 'shared/types.ts';
+import { is_plan_entry } from '@/lib/utils.ts';
 
 interface PlanEntry {
   entry: NormalizedEntry;
@@ -24,10 +25,62 @@ interface PlanEntry {
   isCurrent: boolean;
 }
 
+const COMPARISON_GROUPS: {
+  key: keyof PlanComparison;
+  label: string;
+  className: string;
+}[] = [
+  {
+    key: 'planned_and_changed',
+    label: 'Planned and changed',
+    className: 'text-green-700 dark:text-green-400',
+  },
+  {
+    key: 'planned_not_changed',
+    label: 'Planned but not changed',
+    className: 'text-orange-700 dark:text-orange-400',
+  },
+  {
+    key: 'changed_not_planned',
+    label: 'Changed but not in the plan',
+    className: 'text-blue-700 dark:text-blue-400',
+  },
+];
+
+// Files the approved plan named against the files the implementation changed
+function PlanComparisonSection({ comparison }: { comparison: PlanComparison }) {
+  return (
+    <div className="border rounded-lg p-4 space-y-3 flex-shrink-0">
+      <h4 className="text-sm font-semibold">Plan vs. implementation</h4>
+      <div className="grid gap-3 sm:grid-cols-3">
+        {COMPARISON_GROUPS.map(({ key, label, className }) => (
+          <div key={key} className="min-w-0">
+            <p className={`text-xs font-medium mb-1 ${className}`}>
+              {label} ({comparison[key].length})
+            </p>
+            <ul className="space-y-0.5">
+              {comparison[key].map((path) => (
+                <li
+                  key={path}
+                  className="text-xs font-mono truncate text-muted-foreground"
+                  title={path}
+                >
+                  {path}
+                </li>
+              ))}
+            </ul>
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
+
 function PlanTab() {
   const { loading } = useContext(TaskAttemptLoadingContext);
   const { attemptData } = useContext(TaskAttemptDataContext);
-  const { isPlanningMode, hasPlans, latestProcessHasNoPlan } = useTaskPlan();
+  const { isPlanningMode, hasPlans, latestProcessHasNoPlan, plan } =
+    useTaskPlan();
   const [copiedPlan, setCopiedPlan] = useState<string | null>(null);
   const [expandedPlans, setExpandedPlans] = useState<Set<string>>(new Set());
 
@@ -43,10 +96,7 @@ function PlanTab() {
 
       let localPlanIndex = 1;
       processLog.normalized_conversation.entries.forEach((entry) => {
-        if (
-          entry.entry_type.type === 'tool_use' &&
-          entry.entry_type.action_type.action === 'plan_presentation'
-        ) {
+        if (is_plan_entry(entry)) {
           planEntries.push({
             entry,
             processId: processLog.id,
@@ -122,7 +172,7 @@ function PlanTab() {
             No plan generated
           </p>
           <p className="text-sm text-muted-foreground mb-4">
-            The last execution attempt did not produce a plan. Task creation is
+            The last execution attempt did not produce a plan. Approval is
             disabled until a plan is available.
           </p>
         </div>
@@ -146,6 +196,14 @@ function PlanTab() {
     <div className="p-4 space-y-6 h-full flex flex-col">
       <div className="flex items-center justify-between flex-shrink-0">
         <h3 className="text-lg font-semibold">Plans ({plans.length})</h3>
+        {plan?.plan.status === 'approved' && (
+          <div className="flex items-center gap-2 text-green-600 dark:text-green-400 text-sm">
+            <CheckCircle className="h-4 w-4" />
+            Approved
+            {plan.plan.approved_at &&
+              ` ${new Date(plan.plan.approved_at).toLocaleString()}`}
+          </div>
+        )}
         {latestProcessHasNoPlan && (
           <div className="flex items-center gap-2 text-orange-600 dark:text-orange-400 text-sm">
             <AlertTriangle className="h-4 w-4" />
@@ -154,6 +212,10 @@ function PlanTab() {
         )}
       </div>
 
+      {plan?.comparison && (
+        <PlanComparisonSection comparison={plan.comparison} />
+      )}
+
       <div className="flex-1 overflow-y-auto space-y-4 min-h-0">
         {plans.map((planEntry, index) => {
           const planId = `${planEntry.processId}-${planEntry.planIndex}`;
@@ -1,4 +1,4 @@
-import { useContext, useState } from 'react';
+import { useCallback, useContext, useEffect, useState } from 'react';
 import {
   Play,
   Square,
@@ -7,31 +7,260 @@ import {
   Clock,
   Cog,
   ArrowLeft,
+  Pause,
+  RotateCcw,
+  Undo2,
 } from 'lucide-react';
-import { TaskAttemptDataContext } from '@/components/context/taskDetailsContext.ts';
-import { executionProcessesApi } from '@/lib/api.ts';
+import {
+  TaskAttemptDataContext,
+  TaskDetailsContext,
+  TaskSelectedAttemptContext,
+} from '@/components/context/taskDetailsContext.ts';
+import { Button } from '@/components/ui/button';
+import { attemptsApi, executionProcessesApi } from '@/lib/api.ts';
 import type {
+  AttemptRollback,
   ExecutionProcessStatus,
   ExecutionProcessSummary,
+  ExecutionResources,
+  ExecutionSnapshot,
+  ExecutionToolUsage,
+  FailureReason,
 } from 'shared/types.ts';
 
+const FAILURE_REASON_LABELS: Record<FailureReason, string> = {
+  missing_binary: 'Missing binary',
+  auth_failure: 'Authentication failed',
+  rate_limited: 'Rate limited',
+  context_length_exceeded: 'Context length exceeded',
+  git_conflict: 'Git conflict',
+  network_error: 'Network error',
+  spawn_failed: "Couldn't start",
+};
+
+function formatMegabytes(bytes: number | bigint) {
+  return `${(Number(bytes) / (1024 * 1024)).toFixed(0)} MB`;
+}
+
+function formatSeconds(seconds: number) {
+  return seconds >= 60
+    ? `${Math.floor(seconds / 60)}m ${Math.round(seconds % 60)}s`
+    : `${seconds.toFixed(1)}s`;
+}
+
+// At a glance, whether the run changed code or only talked
+function summarizeToolUsage(usage: ExecutionToolUsage) {
+  const parts = [
+    [usage.files_written, 'file written', 'files written'],
+    [usage.files_read, 'file read', 'files read'],
+    [usage.commands_run, 'command', 'commands'],
+    [usage.searches, 'search', 'searches'],
+    [usage.web_fetches, 'web fetch', 'web fetches'],
+  ] as const;
+  const summary = parts
+    .filter(([count]) => Number(count) > 0)
+    .map(
+      ([count, one, many]) => `${count} ${Number(count) === 1 ? one : many}`
+    )
+    .join(' · ');
+  if (Number(usage.files_written) > 0) return summary;
+  return summary ? `No edits · ${summary}` : 'No edits';
+}
+
 function ProcessesTab() {
-  const { attemptData, setAttemptData } = useContext(TaskAttemptDataContext);
+  const { projectId } = useContext(TaskDetailsContext);
+  const { selectedAttempt } = useContext(TaskSelectedAttemptContext);
+  const { attemptData, setAttemptData, fetchAttemptData } = useContext(
+    TaskAttemptDataContext
+  );
   const [selectedProcessId, setSelectedProcessId] = useState<string | null>(
     null
   );
   const [loadingProcessId, setLoadingProcessId] = useState<string | null>(null);
+  const [controllingProcessId, setControllingProcessId] = useState<
+    string | null
+  >(null);
+  const [rollbacks, setRollbacks] = useState<AttemptRollback[]>([]);
+  const [rollingBackId, setRollingBackId] = useState<string | null>(null);
+  const [rollbackError, setRollbackError] = useState<string | null>(null);
+  const [snapshots, setSnapshots] = useState<ExecutionSnapshot[]>([]);
+  const [revertingId, setRevertingId] = useState<string | null>(null);
+  const [resources, setResources] = useState<ExecutionResources | null>(
+    null
+  );
+  const [toolUsage, setToolUsage] = useState<ExecutionToolUsage[]>([]);
+
+  const fetchRollbacks = useCallback(async () => {
+    if (!selectedAttempt) return;
+    try {
+      setRollbacks(
+        await attemptsApi.getRollbacks(
+          projectId,
+          selectedAttempt.task_id,
+          selectedAttempt.id
+        )
+      );
+    } catch (err) {
+      console.error('Failed to fetch rollbacks:', err);
+    }
+  }, [projectId, selectedAttempt]);
+
+  const fetchSnapshots = useCallback(async () => {
+    if (!selectedAttempt) return;
+    try {
+      setSnapshots(
+        await attemptsApi.getExecutionSnapshots(
+          projectId,
+          selectedAttempt.task_id,
+          selectedAttempt.id
+        )
+      );
+    } catch (err) {
+      console.error('Failed to fetch execution snapshots:', err);
+    }
+  }, [projectId, selectedAttempt]);
+
+  useEffect(() => {
+    setRollbacks([]);
+    fetchRollbacks();
+  }, [fetchRollbacks]);
+
+  useEffect(() => {
+    setSnapshots([]);
+    fetchSnapshots();
+  }, [fetchSnapshots]);
+
+  useEffect(() => {
+    setToolUsage([]);
+    if (!selectedAttempt) return;
+    attemptsApi
+      .getToolUsage(projectId, selectedAttempt.task_id, selectedAttempt.id)
+      .then(setToolUsage)
+      .catch((err) => console.error('Failed to fetch tool usage:', err));
+  }, [projectId, selectedAttempt, attemptData.processes]);
+
+  const toolUsageOf = (process: ExecutionProcessSummary) =>
+    toolUsage.find((usage) => usage.execution_process_id === process.id);
+
+  const snapshotOf = (process: ExecutionProcessSummary) =>
+    snapshots.find((snapshot) => snapshot.execution_process_id === process.id);
+
+  // The commit the attempt is on: the latest rollback's or revert's, unless a
+  // run finished after it
+  const currentCommit = (() => {
+    const lastRun = attemptData.processes
+      .filter((process) => process.commit_sha && process.completed_at)
+      .reduce<ExecutionProcessSummary | null>(
+        (latest, process) =>
+          !latest ||
+          new Date(process.completed_at!) > new Date(latest.completed_at!)
+            ? process
+            : latest,
+        null
+      );
+    const changes = [
+      ...rollbacks.map((rollback) => ({
+        at: rollback.created_at,
+        commit: rollback.to_commit,
+      })),
+      ...snapshots
+        .filter((snapshot) => snapshot.reverted_at && snapshot.revert_commit)
+        .map((snapshot) => ({
+          at: snapshot.reverted_at!,
+          commit: snapshot.revert_commit!,
+        })),
+    ];
+    const lastChange = changes.reduce<(typeof changes)[number] | null>(
+      (latest, change) =>
+        !latest || new Date(change.at) > new Date(latest.at) ? change : latest,
+      null
+    );
+    if (
+      lastChange &&
+      (!lastRun || new Date(lastChange.at) > new Date(lastRun.completed_at!))
+    ) {
+      return lastChange.commit;
+    }
+    return lastRun?.commit_sha ?? null;
+  })();
+
+  const rollbackTo = async (process: ExecutionProcessSummary) => {
+    if (!selectedAttempt) return;
+    if (
+      !confirm(
+        "Reset the attempt's branch to what this run left? Later changes are kept and can be restored from their run."
+      )
+    ) {
+      return;
+    }
+    try {
+      setRollingBackId(process.id);
+      await attemptsApi.rollback(
+        projectId,
+        selectedAttempt.task_id,
+        selectedAttempt.id,
+        { execution_process_id: process.id }
+      );
+      setRollbackError(null);
+      fetchRollbacks();
+      fetchAttemptData(selectedAttempt.id, selectedAttempt.task_id);
+    } catch (err) {
+      setRollbackError(
+        err instanceof Error ? err.message : 'Failed to roll back'
+      );
+    } finally {
+      setRollingBackId(null);
+    }
+  };
+
+  const revert = async (process: ExecutionProcessSummary) => {
+    if (!selectedAttempt) return;
+    if (
+      !confirm(
+        "Undo this run's changes? What the other runs changed is kept, and the revert is a commit of its own."
+      )
+    ) {
+      return;
+    }
+    try {
+      setRevertingId(process.id);
+      await attemptsApi.revertExecution(
+        projectId,
+        selectedAttempt.task_id,
+        selectedAttempt.id,
+        { execution_process_id: process.id }
+      );
+      setRollbackError(null);
+      fetchSnapshots();
+      fetchAttemptData(selectedAttempt.id, selectedAttempt.task_id);
+    } catch (err) {
+      setRollbackError(
+        err instanceof Error ? err.message : 'Failed to revert the run'
+      );
+    } finally {
+      setRevertingId(null);
+    }
+  };
 
   const getStatusIcon = (status: ExecutionProcessStatus) => {
     switch (status) {
       case 'running':
         return <Play className="h-4 w-4 text-blue-500" />;
+      case 'paused':
+        return <Pause className="h-4 w-4 text-yellow-500" />;
       case 'completed':
         return <CheckCircle className="h-4 w-4 text-green-500" />;
       case 'failed':
         return <AlertCircle className="h-4 w-4 text-red-500" />;
       case 'killed':
+      case 'interrupted':
         return <Square className="h-4 w-4 text-gray-500" />;
+      case 'timedout':
+        return <Clock className="h-4 w-4 text-orange-500" />;
+      case 'resourcelimitexceeded':
+        return <AlertCircle className="h-4 w-4 text-orange-500" />;
+      case 'ratelimited':
+        return <Clock className="h-4 w-4 text-yellow-500" />;
       default:
         return <Clock className="h-4 w-4 text-gray-400" />;
     }
@@ -41,12 +270,19 @@ function ProcessesTab() {
     switch (status) {
       case 'running':
         return 'bg-blue-50 border-blue-200 text-blue-800';
+      case 'paused':
+      case 'ratelimited':
+        return 'bg-yellow-50 border-yellow-200 text-yellow-800';
       case 'completed':
         return 'bg-green-50 border-green-200 text-green-800';
       case 'failed':
         return 'bg-red-50 border-red-200 text-red-800';
       case 'killed':
+      case 'interrupted':
         return 'bg-gray-50 border-gray-200 text-gray-800';
+      case 'timedout':
+      case 'resourcelimitexceeded':
+        return 'bg-orange-50 border-orange-200 text-orange-800';
       default:
         return 'bg-gray-50 border-gray-200 text-gray-800';
     }
@@ -78,6 +314,33 @@ function ProcessesTab() {
     }
   };
 
+  const controlProcess = async (
+    process: ExecutionProcessSummary,
+    action: 'pause' | 'resume' | 'stop'
+  ) => {
+    if (!selectedAttempt) return;
+
+    const request = {
+      pause: attemptsApi.pauseExecutionProcess,
+      resume: attemptsApi.resumeExecutionProcess,
+      stop: attemptsApi.stopExecutionProcess,
+    }[action];
+    try {
+      setControllingProcessId(process.id);
+      await request(
+        projectId,
+        selectedAttempt.task_id,
+        selectedAttempt.id,
+        process.id
+      );
+      fetchAttemptData(selectedAttempt.id, selectedAttempt.task_id);
+    } catch (err) {
+      console.error(`Failed to ${action} process:`, err);
+    } finally {
+      setControllingProcessId(null);
+    }
+  };
+
   const handleProcessClick = async (process: ExecutionProcessSummary) => {
     setSelectedProcessId(process.id);
 
@@ -91,6 +354,16 @@ function ProcessesTab() {
     ? attemptData.runningProcessDetails[selectedProcessId]
     : null;
 
+  useEffect(() => {
+    setResources(null);
+    if (!selectedProcessId) return;
+    // Runs that couldn't be measured have no samples
+    executionProcessesApi
+      .getResources(selectedProcessId)
+      .then(setResources)
+      .catch(() => setResources(null));
+  }, [selectedProcessId]);
+
   if (!attemptData.processes || attemptData.processes.length === 0) {
     return (
       <div className="flex-1 flex items-center justify-center text-muted-foreground">
@@ -106,6 +379,9 @@ function ProcessesTab() {
     <div className="flex-1 flex flex-col min-h-0">
       {!selectedProcessId ? (
         <div className="flex-1 overflow-auto px-4 pb-20">
+          {rollbackError && (
+            <p className="text-sm text-destructive mb-3">{rollbackError}</p>
+          )}
           <div className="space-y-3">
             {attemptData.processes.map((process) => (
               <div
@@ -138,6 +414,11 @@ function ProcessesTab() {
                           Args: {process.args}
                         </p>
                       )}
+                      {toolUsageOf(process) && (
+                        <p className="text-xs text-muted-foreground mt-1">
+                          {summarizeToolUsage(toolUsageOf(process)!)}
+                        </p>
+                      )}
                     </div>
                   </div>
                   <div className="text-right">
@@ -148,11 +429,110 @@ function ProcessesTab() {
                     >
                       {process.status}
                     </span>
+                    {(process.status === 'running' ||
+                      process.status === 'paused') && (
+                      <div
+                        className="flex justify-end gap-1 mt-2"
+                        onClick={(e) => e.stopPropagation()}
+                      >
+                        <Button
+                          variant="outline"
+                          size="sm"
+                          disabled={controllingProcessId === process.id}
+                          onClick={() =>
+                            controlProcess(
+                              process,
+                              process.status === 'paused' ? 'resume' : 'pause'
+                            )
+                          }
+                        >
+                          {process.status === 'paused' ? (
+                            <Play className="h-3 w-3 mr-1" />
+                          ) : (
+                            <Pause className="h-3 w-3 mr-1" />
+                          )}
+                          {process.status === 'paused' ? 'Resume' : 'Pause'}
+                        </Button>
+                        <Button
+                          variant="outline"
+                          size="sm"
+                          disabled={controllingProcessId === process.id}
+                          onClick={() => controlProcess(process, 'stop')}
+                        >
+                          <Square className="h-3 w-3 mr-1" />
+                          Stop
+                        </Button>
+                      </div>
+                    )}
+                    {process.commit_sha &&
+                      process.status !== 'running' &&
+                      process.status !== 'paused' &&
+                      (process.commit_sha === currentCommit ? (
+                        <p className="text-xs text-muted-foreground mt-2">
+                          Current
+                        </p>
+                      ) : (
+                        <div
+                          className="flex justify-end mt-2"
+                          onClick={(e) => e.stopPropagation()}
+                        >
+                          <Button
+                            variant="outline"
+                            size="sm"
+                            disabled={rollingBackId !== null}
+                            onClick={() => rollbackTo(process)}
+                            title="Reset the attempt's branch to what this run left"
+                          >
+                            <RotateCcw className="h-3 w-3 mr-1" />
+                            {rollingBackId === process.id
+                              ? 'Rolling back...'
+                              : 'Roll back here'}
+                          </Button>
+                        </div>
+                      ))}
+                    {process.commit_sha &&
+                      process.status !== 'running' &&
+                      process.status !== 'paused' &&
+                      snapshotOf(process) &&
+                      (snapshotOf(process)!.reverted_at ? (
+                        <p className="text-xs text-muted-foreground mt-2">
+                          Reverted{' '}
+                          {formatDate(snapshotOf(process)!.reverted_at!)}
+                        </p>
+                      ) : (
+                        <div
+                          className="flex justify-end mt-2"
+                          onClick={(e) => e.stopPropagation()}
+                        >
+                          <Button
+                            variant="outline"
+                            size="sm"
+                            disabled={revertingId !== null}
+                            onClick={() => revert(process)}
+                            title="Undo only this run's changes, keeping the other runs'"
+                          >
+                            <Undo2 className="h-3 w-3 mr-1" />
+                            {revertingId === process.id
+                              ? 'Reverting...'
+                              : 'Revert this run'}
+                          </Button>
+                        </div>
+                      ))}
                     {process.exit_code !== null && (
                       <p className="text-xs text-muted-foreground mt-1">
                         Exit: {process.exit_code.toString()}
                       </p>
                     )}
+                    {Number(process.retry_count) > 0 && (
+                      <p className="text-xs text-muted-foreground mt-1">
+                        Retry: {process.retry_count.toString()}
+                      </p>
+                    )}
+                    {process.retry_at && (
+                      <p className="text-xs text-muted-foreground mt-1">
+                        Retrying at {formatDate(process.retry_at)}
+                      </p>
+                    )}
                   </div>
                 </div>
                 <div className="mt-3 text-xs text-muted-foreground">
@@ -203,10 +583,34 @@ function ProcessesTab() {
                           {selectedProcess.executor_type}
                         </p>
                       )}
+                      {selectedProcess.model && (
+                        <p>
+                          <span className="font-medium">Model:</span>{' '}
+                          <span className="font-mono">
+                            {selectedProcess.model}
+                          </span>
+                        </p>
+                      )}
                       <p>
                         <span className="font-medium">Exit Code:</span>{' '}
                         {selectedProcess.exit_code?.toString() ?? 'N/A'}
                       </p>
+                      {selectedProcess.failure_reason && (
+                        <p>
+                          <span className="font-medium">Failure:</span>{' '}
+                          <span className="text-red-600">
+                            {FAILURE_REASON_LABELS[selectedProcess.failure_reason]}
+                          </span>
+                        </p>
+                      )}
+                      {selectedProcess.commit_sha && (
+                        <p>
+                          <span className="font-medium">Commit:</span>{' '}
+                          <span className="font-mono">
+                            {selectedProcess.commit_sha.slice(0, 8)}
+                          </span>
+                        </p>
+                      )}
                     </div>
                   </div>
                   <div>
@@ -226,6 +630,30 @@ function ProcessesTab() {
                   </div>
                 </div>
 
+                {resources && (
+                  <div>
+                    <h3 className="font-medium text-sm mb-2">Resources</h3>
+                    <div className="grid grid-cols-2 gap-1 text-sm">
+                      <p>
+                        <span className="font-medium">Peak memory:</span>{' '}
+                        {formatMegabytes(resources.peak_rss_bytes)}
+                      </p>
+                      <p>
+                        <span className="font-medium">Peak CPU:</span>{' '}
+                        {resources.peak_cpu_percent.toFixed(0)}%
+                      </p>
+                      <p>
+                        <span className="font-medium">CPU time:</span>{' '}
+                        {formatSeconds(resources.cpu_seconds)}
+                      </p>
+                      <p>
+                        <span className="font-medium">Wall time:</span>{' '}
+                        {formatSeconds(resources.wall_seconds)}
+                      </p>
+                    </div>
+                  </div>
+                )}
+
                 <div>
                   <h3 className="font-medium text-sm mb-2">Command</h3>
                   <div className="bg-muted/50 p-3 rounded-md font-mono text-sm">
@@ -91,6 +91,7 @@ function RelatedTasksTab() {
         return <Clock className="h-4 w-4 text-blue-500" />;
       case 'cancelled':
         return <XCircle className="h-4 w-4 text-red-500" />;
+      case 'planreview':
       case 'inreview':
         return <AlertCircle className="h-4 w-4 text-yellow-500" />;
       default:
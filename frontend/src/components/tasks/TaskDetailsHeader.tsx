@@ -1,5 +1,15 @@
-import { memo, useContext, useState } from 'react';
-import { ChevronDown, ChevronUp, Edit, Trash2, X } from 'lucide-react';
+import { memo, useContext, useEffect, useState } from 'react';
+import {
+  ChevronDown,
+  ChevronUp,
+  Download,
+  Edit,
+  Loader2,
+  Share2,
+  Sparkles,
+  Trash2,
+  X,
+} from 'lucide-react';
 import { Button } from '@/components/ui/button';
 import { Chip } from '@/components/ui/chip';
 import {
@@ -8,8 +18,23 @@ import {
   TooltipProvider,
   TooltipTrigger,
 } from '@/components/ui/tooltip';
-import type { TaskStatus, TaskWithAttemptStatus } from 'shared/types';
+import type {
+  TaskOriginalText,
+  TaskStatus,
+  TaskWithAttemptStatus,
+} from 'shared/types';
 import { TaskDetailsContext } from '@/components/context/taskDetailsContext.ts';
+import { useConfig } from '@/components/config-provider';
+import { tasksApi } from '@/lib/api';
+import TaskLabelsRow from '@/components/tasks/TaskLabelsRow';
+import TaskDependenciesRow from '@/components/tasks/TaskDependenciesRow';
+import TaskTimeoutRow from '@/components/tasks/TaskTimeoutRow';
+import TaskFieldsRow from '@/components/tasks/TaskFieldsRow';
+import TaskAttachmentsRow from '@/components/tasks/TaskAttachmentsRow';
+import ShareLinksDialog from '@/components/tasks/ShareLinksDialog';
+
+// Descriptions shorter than this are rarely worth summarizing
+const SUMMARIZE_MIN_CHARS = 300;
 
 interface TaskDetailsHeaderProps {
   onClose: () => void;
@@ -19,7 +44,9 @@ interface TaskDetailsHeaderProps {
 
 const statusLabels: Record<TaskStatus, string> = {
   todo: 'To Do',
+  queued: 'Queued',
   inprogress: 'In Progress',
+  planreview: 'Plan Review',
   inreview: 'In Review',
   done: 'Done',
   cancelled: 'Cancelled',
@@ -29,8 +56,12 @@ const getTaskStatusDotColor = (status: TaskStatus): string => {
   switch (status) {
     case 'todo':
       return 'bg-gray-400';
+    case 'queued':
+      return 'bg-slate-500';
     case 'inprogress':
       return 'bg-blue-500';
+    case 'planreview':
+      return 'bg-purple-500';
     case 'inreview':
       return 'bg-yellow-500';
     case 'done':
@@ -48,7 +79,42 @@ function TaskDetailsHeader({
   onDeleteTask,
 }: TaskDetailsHeaderProps) {
   const { task } = useContext(TaskDetailsContext);
+  const { config } = useConfig();
   const [isDescriptionExpanded, setIsDescriptionExpanded] = useState(false);
+  const [isSummarizing, setIsSummarizing] = useState(false);
+  const [summarizeError, setSummarizeError] = useState<string | null>(null);
+  const [original, setOriginal] = useState<TaskOriginalText | null>(null);
+  const [showOriginal, setShowOriginal] = useState(false);
+  const [showShareLinks, setShowShareLinks] = useState(false);
+
+  const canSummarize =
+    Boolean(config?.llm.base_url) &&
+    (task.description?.length ?? 0) >= SUMMARIZE_MIN_CHARS;
+
+  useEffect(() => {
+    setShowOriginal(false);
+    setSummarizeError(null);
+    tasksApi
+      .getOriginalText(task.project_id, task.id)
+      .then(setOriginal)
+      .catch(() => setOriginal(null));
+  }, [task.project_id, task.id]);
+
+  const handleSummarize = async () => {
+    setIsSummarizing(true);
+    setSummarizeError(null);
+    try {
+      // The board's polling picks up the rewritten task
+      await tasksApi.summarize(task.project_id, task.id);
+      setOriginal(await tasksApi.getOriginalText(task.project_id, task.id));
+    } catch (err) {
+      setSummarizeError(
+        err instanceof Error ? err.message : 'Failed to summarize task'
+      );
+    } finally {
+      setIsSummarizing(false);
+    }
+  };
 
   return (
     <div>
@@ -66,6 +132,65 @@ function TaskDetailsHeader({
             </div>
           </div>
           <div className="flex items-center gap-1">
+            {canSummarize && (
+              <TooltipProvider>
+                <Tooltip>
+                  <TooltipTrigger asChild>
+                    <Button
+                      variant="ghost"
+                      size="icon"
+                      onClick={handleSummarize}
+                      disabled={isSummarizing}
+                    >
+                      {isSummarizing ? (
+                        <Loader2 className="h-4 w-4 animate-spin" />
+                      ) : (
+                        <Sparkles className="h-4 w-4" />
+                      )}
+                    </Button>
+                  </TooltipTrigger>
+                  <TooltipContent>
+                    <p>Summarize into a title and description</p>
+                  </TooltipContent>
+                </Tooltip>
+              </TooltipProvider>
+            )}
+            <TooltipProvider>
+              <Tooltip>
+                <TooltipTrigger asChild>
+                  <Button variant="ghost" size="icon" asChild>
+                    <a
+                      href={tasksApi.conversationsExportUrl(
+                        task.project_id,
+                        task.id
+                      )}
+                      download
+                    >
+                      <Download className="h-4 w-4" />
+                    </a>
+                  </Button>
+                </TooltipTrigger>
+                <TooltipContent>
+                  <p>Export conversations as Markdown</p>
+                </TooltipContent>
+              </Tooltip>
+            </TooltipProvider>
+            <TooltipProvider>
+              <Tooltip>
+                <TooltipTrigger asChild>
+                  <Button
+                    variant="ghost"
+                    size="icon"
+                    onClick={() => setShowShareLinks(true)}
+                  >
+                    <Share2 className="h-4 w-4" />
+                  </Button>
+                </TooltipTrigger>
+                <TooltipContent>
+                  <p>Share a read-only link to the conversations</p>
+                </TooltipContent>
+              </Tooltip>
+            </TooltipProvider>
             {onEditTask && (
               <TooltipProvider>
                 <Tooltip>
@@ -160,8 +285,53 @@ function TaskDetailsHeader({
               </p>
             )}
           </div>
+          {summarizeError && (
+            <p className="mt-1 text-xs text-destructive">{summarizeError}</p>
+          )}
+          {original && (
+            <div className="mt-1">
+              <Button
+                variant="ghost"
+                size="sm"
+                onClick={() => setShowOriginal(!showOriginal)}
+                className="p-0 h-auto text-xs text-muted-foreground hover:text-foreground"
+              >
+                {showOriginal ? 'Hide original text' : 'Show original text'}
+              </Button>
+              {showOriginal && (
+                <div className="mt-1 p-2 bg-muted/20 rounded border-l-2 border-muted max-h-48 overflow-y-auto">
+                  <p className="text-xs font-medium">{original.title}</p>
+                  {original.description && (
+                    <p className="text-xs whitespace-pre-wrap text-muted-foreground mt-1">
+                      {original.description}
+                    </p>
+                  )}
+                </div>
+              )}
+            </div>
+          )}
         </div>
+
+        <TaskLabelsRow projectId={task.project_id} taskId={task.id} />
+        <TaskFieldsRow
+          projectId={task.project_id}
+          taskId={task.id}
+          priority={task.priority}
+        />
+        <TaskDependenciesRow projectId={task.project_id} taskId={task.id} />
+        <TaskTimeoutRow
+          projectId={task.project_id}
+          taskId={task.id}
+          timeoutMinutes={task.timeout_minutes}
+        />
+        <TaskAttachmentsRow projectId={task.project_id} taskId={task.id} />
       </div>
+      <ShareLinksDialog
+        open={showShareLinks}
+        onOpenChange={setShowShareLinks}
+        projectId={task.project_id}
+        taskId={task.id}
+      />
     </div>
   );
 }
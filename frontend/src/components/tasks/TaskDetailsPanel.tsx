@@ -16,6 +16,7 @@ import DeleteFileConfirmationDialog from '@/components/tasks/DeleteFileConfirmat
 import TabNavigation from '@/components/tasks/TaskDetails/TabNavigation.tsx';
 import CollapsibleToolbar from '@/components/tasks/TaskDetails/CollapsibleToolbar.tsx';
 import TaskDetailsProvider from '../context/TaskDetailsContextProvider.tsx';
+import { FollowUpDraftProvider } from '../context/FollowUpDraftContext.tsx';
 
 interface TaskDetailsPanelProps {
   task: TaskWithAttemptStatus | null;
@@ -76,52 +77,54 @@ export function TaskDetailsPanel({
           setShowEditorDialog={setShowEditorDialog}
           projectHasDevScript={projectHasDevScript}
         >
-          {/* Backdrop - only on smaller screens (overlay mode) */}
-          <div className={getBackdropClasses()} onClick={onClose} />
+          <FollowUpDraftProvider>
+            {/* Backdrop - only on smaller screens (overlay mode) */}
+            <div className={getBackdropClasses()} onClick={onClose} />
 
-          {/* Panel */}
-          <div className={getTaskPanelClasses()}>
-            <div className="flex flex-col h-full">
-              <TaskDetailsHeader
-                onClose={onClose}
-                onEditTask={onEditTask}
-                onDeleteTask={onDeleteTask}
-              />
+            {/* Panel */}
+            <div className={getTaskPanelClasses()}>
+              <div className="flex flex-col h-full">
+                <TaskDetailsHeader
+                  onClose={onClose}
+                  onEditTask={onEditTask}
+                  onDeleteTask={onDeleteTask}
+                />
 
-              <CollapsibleToolbar />
+                <CollapsibleToolbar />
 
-              <TabNavigation
-                activeTab={activeTab}
-                setActiveTab={setActiveTab}
-              />
+                <TabNavigation
+                  activeTab={activeTab}
+                  setActiveTab={setActiveTab}
+                />
 
-              {/* Tab Content */}
-              <div
-                className={`flex-1 flex flex-col min-h-0 ${activeTab === 'logs' ? 'p-4' : 'pt-4'}`}
-              >
-                {activeTab === 'diffs' ? (
-                  <DiffTab />
-                ) : activeTab === 'related' ? (
-                  <RelatedTasksTab />
-                ) : activeTab === 'processes' ? (
-                  <ProcessesTab />
-                ) : activeTab === 'plan' ? (
-                  <PlanTab />
-                ) : (
-                  <LogsTab />
-                )}
-              </div>
+                {/* Tab Content */}
+                <div
+                  className={`flex-1 flex flex-col min-h-0 ${activeTab === 'logs' ? 'p-4' : 'pt-4'}`}
+                >
+                  {activeTab === 'diffs' ? (
+                    <DiffTab />
+                  ) : activeTab === 'related' ? (
+                    <RelatedTasksTab />
+                  ) : activeTab === 'processes' ? (
+                    <ProcessesTab />
+                  ) : activeTab === 'plan' ? (
+                    <PlanTab />
+                  ) : (
+                    <LogsTab />
+                  )}
+                </div>
 
-              <TaskFollowUpSection />
+                <TaskFollowUpSection />
+              </div>
             </div>
-          </div>
 
-          <EditorSelectionDialog
-            isOpen={showEditorDialog}
-            onClose={() => setShowEditorDialog(false)}
-          />
+            <EditorSelectionDialog
+              isOpen={showEditorDialog}
+              onClose={() => setShowEditorDialog(false)}
+            />
 
-          <DeleteFileConfirmationDialog />
+            <DeleteFileConfirmationDialog />
+          </FollowUpDraftProvider>
         </TaskDetailsProvider>
       )}
     </>
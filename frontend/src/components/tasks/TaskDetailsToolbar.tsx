@@ -4,7 +4,7 @@ import { Play } from 'lucide-react';
 import { Button } from '@/components/ui/button';
 import { useConfig } from '@/components/config-provider';
 import { attemptsApi, projectsApi } from '@/lib/api';
-import type { GitBranch, TaskAttempt } from 'shared/types';
+import type { AttemptGroup, GitBranch, TaskAttempt } from 'shared/types';
 import { EXECUTOR_LABELS, EXECUTOR_TYPES } from 'shared/types';
 import {
   TaskAttemptDataContext,
@@ -35,6 +35,7 @@ function TaskDetailsToolbar() {
   );
 
   const [taskAttempts, setTaskAttempts] = useState<TaskAttempt[]>([]);
+  const [attemptGroups, setAttemptGroups] = useState<AttemptGroup[]>([]);
   const location = useLocation();
 
   const { config } = useConfig();
@@ -45,6 +46,9 @@ function TaskDetailsToolbar() {
   const [selectedExecutor, setSelectedExecutor] = useState<string>(
     config?.executor.type || 'claude'
   );
+  const [projectDefaultExecutor, setProjectDefaultExecutor] = useState<
+    string | null
+  >(null);
 
   // State for create attempt mode
   const [isInCreateAttemptMode, setIsInCreateAttemptMode] = useState(false);
@@ -60,13 +64,19 @@ function TaskDetailsToolbar() {
   const [error, setError] = useState<string | null>(null);
 
   const fetchProjectBranches = useCallback(async () => {
-    const result = await projectsApi.getBranches(projectId);
+    const [result, project] = await Promise.all([
+      projectsApi.getBranches(projectId),
+      projectsApi.getById(projectId),
+    ]);
 
     setBranches(result);
-    // Set current branch as default
-    const currentBranch = result.find((b) => b.is_current);
-    if (currentBranch) {
-      setSelectedBranch((prev) => (!prev ? currentBranch.name : prev));
+    setProjectDefaultExecutor(project.default_executor);
+    // Set the project's default branch, or the current branch, as default
+    const defaultBranch =
+      result.find((b) => b.name === project.default_branch) ??
+      result.find((b) => b.is_current);
+    if (defaultBranch) {
+      setSelectedBranch((prev) => (!prev ? defaultBranch.name : prev));
     }
   }, [projectId]);
 
@@ -74,12 +84,13 @@ function TaskDetailsToolbar() {
     fetchProjectBranches();
   }, [fetchProjectBranches]);
 
-  // Set default executor from config
+  // Set default executor from the project, or from config if it has none
   useEffect(() => {
-    if (config && config.executor.type !== selectedExecutor) {
-      setSelectedExecutor(config.executor.type);
+    const defaultExecutor = projectDefaultExecutor ?? config?.executor.type;
+    if (defaultExecutor && defaultExecutor !== selectedExecutor) {
+      setSelectedExecutor(defaultExecutor);
     }
-  }, [config, selectedExecutor]);
+  }, [config, projectDefaultExecutor, selectedExecutor]);
 
   // Set create attempt mode when there are no attempts
   useEffect(() => {
@@ -118,7 +129,11 @@ function TaskDetailsToolbar() {
 
     try {
       setLoading(true);
-      const result = await attemptsApi.getAll(projectId, task.id);
+      const [result, groups] = await Promise.all([
+        attemptsApi.getAll(projectId, task.id),
+        attemptsApi.getGroups(projectId, task.id),
+      ]);
+      setAttemptGroups(groups);
 
       setTaskAttempts((prev) => {
         if (JSON.stringify(prev) === JSON.stringify(result)) return prev;
@@ -154,6 +169,15 @@ function TaskDetailsToolbar() {
               ? current
               : latest
           );
+          // When it ran side by side with others, the picked one carries on
+          const latestId = selectedAttemptToUse.id;
+          const winnerId = groups.find((group) =>
+            group.attempt_ids.includes(latestId)
+          )?.winner_attempt_id;
+          const winner = result.find((attempt) => attempt.id === winnerId);
+          if (winner) {
+            selectedAttemptToUse = winner;
+          }
         }
 
         setSelectedAttempt((prev) => {
@@ -167,6 +191,7 @@ function TaskDetailsToolbar() {
           processes: [],
           runningProcessDetails: {},
           allLogs: [],
+          approvals: {},
         });
       }
     } catch (error) {
@@ -257,6 +282,8 @@ function TaskDetailsToolbar() {
                   handleEnterCreateAttemptMode={handleEnterCreateAttemptMode}
                   availableExecutors={availableExecutors}
                   branches={branches}
+                  attemptGroups={attemptGroups}
+                  fetchTaskAttempts={fetchTaskAttempts}
                 />
               ) : (
                 <div className="text-center py-8">
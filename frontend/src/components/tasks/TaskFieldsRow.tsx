@@ -0,0 +1,165 @@
+import { useEffect, useState } from 'react';
+import { Flag } from 'lucide-react';
+import type { CustomField, TaskPriority } from 'shared/types';
+import { customFieldsApi, tasksApi } from '@/lib/api';
+import { Input } from '@/components/ui/input';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+
+const NONE = 'none';
+
+export const PRIORITY_LABELS: Record<TaskPriority, string> = {
+  urgent: 'Urgent',
+  high: 'High',
+  medium: 'Medium',
+  low: 'Low',
+};
+
+interface TaskFieldsRowProps {
+  projectId: string;
+  taskId: string;
+  priority: TaskPriority | null;
+}
+
+function TaskFieldsRow({ projectId, taskId, priority }: TaskFieldsRowProps) {
+  const [currentPriority, setCurrentPriority] = useState(priority);
+  const [fields, setFields] = useState<CustomField[]>([]);
+  const [saved, setSaved] = useState<Record<string, string>>({});
+  const [values, setValues] = useState<Record<string, string>>({});
+  const [error, setError] = useState('');
+
+  useEffect(() => {
+    setCurrentPriority(priority);
+  }, [taskId, priority]);
+
+  useEffect(() => {
+    setError('');
+    Promise.all([
+      customFieldsApi.list(projectId),
+      customFieldsApi.getTaskValues(projectId, taskId),
+    ])
+      .then(([fields, values]) => {
+        setFields(fields);
+        setSaved(values);
+        setValues(values);
+      })
+      .catch(() => setFields([]));
+  }, [projectId, taskId]);
+
+  const savePriority = async (value: string) => {
+    const next = value === NONE ? null : (value as TaskPriority);
+    setError('');
+    try {
+      const task = await tasksApi.setPriority(projectId, taskId, next);
+      setCurrentPriority(task.priority);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to save priority');
+    }
+  };
+
+  const saveField = async (name: string, value: string) => {
+    if ((saved[name] ?? '') === value) {
+      return;
+    }
+    setError('');
+    try {
+      const stored = await customFieldsApi.setTaskValue(projectId, taskId, {
+        name,
+        value,
+      });
+      setSaved(stored);
+      setValues(stored);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : `Failed to save ${name}`);
+      setValues(saved);
+    }
+  };
+
+  const renderField = (field: CustomField) => {
+    const value = values[field.name] ?? '';
+    if (field.field_type === 'select' || field.field_type === 'boolean') {
+      const options =
+        field.field_type === 'select' ? field.options : ['true', 'false'];
+      return (
+        <Select
+          value={value || NONE}
+          onValueChange={(next) =>
+            saveField(field.name, next === NONE ? '' : next)
+          }
+        >
+          <SelectTrigger className="h-6 w-32 px-2 text-xs">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            <SelectItem value={NONE}>Not set</SelectItem>
+            {options.map((option) => (
+              <SelectItem key={option} value={option}>
+                {option}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+      );
+    }
+    return (
+      <Input
+        type={
+          field.field_type === 'number'
+            ? 'number'
+            : field.field_type === 'date'
+              ? 'date'
+              : 'text'
+        }
+        value={value}
+        onChange={(e) =>
+          setValues((prev) => ({ ...prev, [field.name]: e.target.value }))
+        }
+        onBlur={() => saveField(field.name, value)}
+        onKeyDown={(e) => {
+          if (e.key === 'Enter') {
+            saveField(field.name, value);
+          }
+        }}
+        className="h-6 w-32 px-2 text-xs"
+      />
+    );
+  };
+
+  return (
+    <div className="mt-2 space-y-1 text-xs">
+      <div className="flex flex-wrap items-center gap-x-3 gap-y-1">
+        <div className="flex items-center gap-1">
+          <Flag className="h-3 w-3 text-muted-foreground" />
+          <span className="text-muted-foreground">Priority</span>
+          <Select value={currentPriority ?? NONE} onValueChange={savePriority}>
+            <SelectTrigger className="h-6 w-24 px-2 text-xs">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              <SelectItem value={NONE}>None</SelectItem>
+              {Object.entries(PRIORITY_LABELS).map(([value, label]) => (
+                <SelectItem key={value} value={value}>
+                  {label}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        </div>
+        {fields.map((field) => (
+          <div key={field.name} className="flex items-center gap-1">
+            <span className="text-muted-foreground">{field.name}</span>
+            {renderField(field)}
+          </div>
+        ))}
+      </div>
+      {error && <p className="text-destructive">{error}</p>}
+    </div>
+  );
+}
+
+export default TaskFieldsRow;
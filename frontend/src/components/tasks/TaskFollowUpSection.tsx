@@ -1,15 +1,52 @@
-import { AlertCircle, Send } from 'lucide-react';
+import { AlertCircle, Quote, Send, X } from 'lucide-react';
 import { Button } from '@/components/ui/button';
 import { Alert, AlertDescription } from '@/components/ui/alert';
 import { FileSearchTextarea } from '@/components/ui/file-search-textarea';
+import {
+  DropdownMenu,
+  DropdownMenuContent,
+  DropdownMenuItem,
+  DropdownMenuLabel,
+  DropdownMenuTrigger,
+} from '@/components/ui/dropdown-menu';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
 import { useContext, useMemo, useState } from 'react';
-import { attemptsApi } from '@/lib/api.ts';
+import { attemptsApi, followUpDraftsApi } from '@/lib/api.ts';
+import type { DraftQuote, FileHunks } from 'shared/types';
+import { EXECUTOR_LABELS, EXECUTOR_TYPES } from 'shared/types';
 import {
   TaskAttemptDataContext,
   TaskDetailsContext,
+  TaskExecutionStateContext,
   TaskSelectedAttemptContext,
 } from '@/components/context/taskDetailsContext.ts';
 import { Loader } from '@/components/ui/loader';
+import {
+  useFollowUpDraft,
+} from '@/components/context/FollowUpDraftContext.tsx';
+import type { AttemptData } from '@/lib/types.ts';
+
+// Select items can't have an empty value
+const SAME_EXECUTOR = 'same';
+
+// A short label for a quote, from the attempt's logs for entries
+function quoteLabel(quote: DraftQuote, attemptData: AttemptData): string {
+  if (quote.type === 'hunk') {
+    return `${quote.path} #${quote.hunk_index + 1}`;
+  }
+  const entry = attemptData.allLogs.find(
+    (log) => log.id === quote.execution_process_id
+  )?.normalized_conversation.entries[quote.entry_index];
+  if (!entry) return 'Message';
+  const text = entry.content.replace(/\s+/g, ' ').trim();
+  return text.length > 40 ? `${text.slice(0, 40)}…` : text;
+}
 
 export function TaskFollowUpSection() {
   const { task, projectId } = useContext(TaskDetailsContext);
@@ -17,14 +54,33 @@ export function TaskFollowUpSection() {
   const { attemptData, fetchAttemptData, isAttemptRunning } = useContext(
     TaskAttemptDataContext
   );
+  const { executionState } = useContext(TaskExecutionStateContext);
+
+  // Assume follow-ups work until the attempt's executor is known
+  const followUpSupported =
+    executionState?.capabilities?.supports_followup ?? true;
 
-  const [followUpMessage, setFollowUpMessage] = useState('');
+  const {
+    prompt: followUpMessage,
+    setPrompt: setFollowUpMessage,
+    quotes,
+    addQuote,
+    removeQuote,
+    save: saveDraft,
+    clear: clearDraft,
+  } = useFollowUpDraft();
   const [isSendingFollowUp, setIsSendingFollowUp] = useState(false);
   const [followUpError, setFollowUpError] = useState<string | null>(null);
+  const [hunks, setHunks] = useState<FileHunks[] | null>(null);
+  // Another executor to hand the attempt over to with the follow-up
+  const [handoverExecutor, setHandoverExecutor] = useState<string | null>(
+    null
+  );
 
   const canSendFollowUp = useMemo(() => {
     if (
       !selectedAttempt ||
+      (!followUpSupported && !handoverExecutor) ||
       attemptData.processes.length === 0 ||
       isAttemptRunning ||
       isSendingFollowUp
@@ -35,12 +91,18 @@ export function TaskFollowUpSection() {
     const completedOrKilledCodingAgentProcesses = attemptData.processes.filter(
       (process) =>
         process.process_type === 'codingagent' &&
-        (process.status === 'completed' || process.status === 'killed')
+        (process.status === 'completed' ||
+          process.status === 'killed' ||
+          process.status === 'interrupted' ||
+          process.status === 'timedout' ||
+          process.status === 'resourcelimitexceeded')
     );
 
     return completedOrKilledCodingAgentProcesses.length > 0;
   }, [
     selectedAttempt,
+    followUpSupported,
+    handoverExecutor,
     attemptData.processes,
     isAttemptRunning,
     isSendingFollowUp,
@@ -52,15 +114,16 @@ export function TaskFollowUpSection() {
     try {
       setIsSendingFollowUp(true);
       setFollowUpError(null);
-      await attemptsApi.followUp(
+      // Sent from the saved draft, so the server expands its quotes
+      await saveDraft();
+      await followUpDraftsApi.send(
         projectId!,
         selectedAttempt.task_id,
         selectedAttempt.id,
-        {
-          prompt: followUpMessage.trim(),
-        }
+        handoverExecutor
       );
-      setFollowUpMessage('');
+      clearDraft();
+      setHandoverExecutor(null);
       fetchAttemptData(selectedAttempt.id, selectedAttempt.task_id);
     } catch (error: unknown) {
       // @ts-expect-error it is type ApiError
@@ -80,9 +143,87 @@ export function TaskFollowUpSection() {
               <AlertDescription>{followUpError}</AlertDescription>
             </Alert>
           )}
+          {quotes.length > 0 && (
+            <div className="flex flex-wrap gap-1">
+              {quotes.map((quote, index) => (
+                <span
+                  key={index}
+                  className="inline-flex items-center gap-1 rounded-full bg-muted px-2 py-0.5 text-xs text-muted-foreground"
+                >
+                  <Quote className="h-3 w-3" />
+                  {quoteLabel(quote, attemptData)}
+                  <button
+                    type="button"
+                    onClick={() => removeQuote(index)}
+                    aria-label="Remove quote"
+                  >
+                    <X className="h-3 w-3" />
+                  </button>
+                </span>
+              ))}
+            </div>
+          )}
           <div className="flex gap-2 items-start">
+            <DropdownMenu
+              onOpenChange={(open) => {
+                if (!open || !selectedAttempt) return;
+                attemptsApi
+                  .getDiffHunks(
+                    projectId!,
+                    selectedAttempt.task_id,
+                    selectedAttempt.id
+                  )
+                  .then(setHunks)
+                  .catch(() => setHunks([]));
+              }}
+            >
+              <DropdownMenuTrigger asChild>
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  disabled={!canSendFollowUp}
+                  title="Quote a change in the follow-up"
+                >
+                  <Quote className="h-4 w-4" />
+                </Button>
+              </DropdownMenuTrigger>
+              <DropdownMenuContent
+                align="start"
+                className="max-h-80 overflow-y-auto"
+              >
+                <DropdownMenuLabel>Quote a change</DropdownMenuLabel>
+                {hunks === null ? (
+                  <DropdownMenuItem disabled>Loading…</DropdownMenuItem>
+                ) : hunks.every((file) => file.hunks.length === 0) ? (
+                  <DropdownMenuItem disabled>No changes</DropdownMenuItem>
+                ) : (
+                  hunks.flatMap((file) =>
+                    file.hunks.map((hunk) => (
+                      <DropdownMenuItem
+                        key={`${file.path}:${hunk.index}`}
+                        onClick={() =>
+                          addQuote({
+                            type: 'hunk',
+                            path: file.path,
+                            hunk_index: hunk.index,
+                          })
+                        }
+                      >
+                        <span className="font-mono text-xs truncate">
+                          {file.path} {hunk.header}
+                        </span>
+                      </DropdownMenuItem>
+                    ))
+                  )
+                )}
+              </DropdownMenuContent>
+            </DropdownMenu>
             <FileSearchTextarea
-              placeholder="Continue working on this task... Type @ to search files."
+              placeholder={
+                followUpSupported
+                  ? 'Continue working on this task... Type @ to search files.'
+                  : "This attempt's executor doesn't support follow-ups"
+              }
               value={followUpMessage}
               onChange={(value) => {
                 setFollowUpMessage(value);
@@ -106,6 +247,30 @@ export function TaskFollowUpSection() {
               rows={1}
               maxRows={6}
             />
+            <Select
+              value={handoverExecutor ?? SAME_EXECUTOR}
+              onValueChange={(value) =>
+                setHandoverExecutor(value === SAME_EXECUTOR ? null : value)
+              }
+              disabled={isAttemptRunning || isSendingFollowUp}
+            >
+              <SelectTrigger
+                className="w-40 h-9 text-xs"
+                title="Hand the attempt over to another executor, which starts from a transcript of it"
+              >
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value={SAME_EXECUTOR}>Same executor</SelectItem>
+                {EXECUTOR_TYPES.filter((type) => type !== 'echo').map(
+                  (type) => (
+                    <SelectItem key={type} value={type}>
+                      {EXECUTOR_LABELS[type]}
+                    </SelectItem>
+                  )
+                )}
+              </SelectContent>
+            </Select>
             <Button
               onClick={onSendFollowUp}
               disabled={
@@ -1,5 +1,5 @@
 import { useState, useEffect, useCallback } from 'react';
-import { Globe2, AlertTriangle } from 'lucide-react';
+import { Globe2, AlertTriangle, Copy } from 'lucide-react';
 import { Button } from '@/components/ui/button';
 import {
   Dialog,
@@ -18,8 +18,24 @@ import {
   SelectValue,
 } from '@/components/ui/select';
 import { useConfig } from '@/components/config-provider';
-import { templatesApi } from '@/lib/api';
-import type { TaskStatus, ExecutorConfig, TaskTemplate } from 'shared/types';
+import { projectsApi, tasksApi, templatesApi } from '@/lib/api';
+import type {
+  TaskStatus,
+  ExecutorConfig,
+  FileMentionProblem,
+  SimilarTask,
+  TaskTemplate,
+} from 'shared/types';
+
+const STATUS_LABELS: Record<TaskStatus, string> = {
+  todo: 'To Do',
+  queued: 'Queued',
+  inprogress: 'In Progress',
+  planreview: 'Plan Review',
+  inreview: 'In Review',
+  done: 'Done',
+  cancelled: 'Cancelled',
+};
 
 interface Task {
   id: string;
@@ -74,9 +90,14 @@ export function TaskFormDialog({
   const [isSubmittingAndStart, setIsSubmittingAndStart] = useState(false);
   const [templates, setTemplates] = useState<TaskTemplate[]>([]);
   const [selectedTemplate, setSelectedTemplate] = useState<string>('');
+  const [similarTasks, setSimilarTasks] = useState<SimilarTask[]>([]);
+  const [mentionProblems, setMentionProblems] = useState<FileMentionProblem[]>(
+    []
+  );
 
   const { config } = useConfig();
   const isEditMode = Boolean(task);
+  const embeddingsEnabled = Boolean(config?.embeddings.base_url);
 
   // Check if task creation should be disabled based on plan context
   const isPlanningModeWithoutPlan =
@@ -121,6 +142,52 @@ export function TaskFormDialog({
     }
   }, [isOpen, isEditMode, projectId]);
 
+  // Look for similar existing tasks once the user pauses typing
+  useEffect(() => {
+    if (!isOpen || isEditMode || !projectId || !embeddingsEnabled) {
+      setSimilarTasks([]);
+      return;
+    }
+    let cancelled = false;
+    const timeout = setTimeout(() => {
+      tasksApi
+        .findSimilar(projectId, { title, description: description || null })
+        .then((similar) => {
+          if (!cancelled) setSimilarTasks(similar);
+        })
+        .catch(() => {
+          if (!cancelled) setSimilarTasks([]);
+        });
+    }, 600);
+    return () => {
+      cancelled = true;
+      clearTimeout(timeout);
+    };
+  }, [isOpen, isEditMode, projectId, embeddingsEnabled, title, description]);
+
+  // Check @ file mentions against the repository once the user pauses typing
+  useEffect(() => {
+    if (!isOpen || !projectId || !description.includes('@')) {
+      setMentionProblems([]);
+      return;
+    }
+    let cancelled = false;
+    const timeout = setTimeout(() => {
+      projectsApi
+        .checkFileMentions(projectId, description)
+        .then((problems) => {
+          if (!cancelled) setMentionProblems(problems);
+        })
+        .catch(() => {
+          if (!cancelled) setMentionProblems([]);
+        });
+    }, 600);
+    return () => {
+      cancelled = true;
+      clearTimeout(timeout);
+    };
+  }, [isOpen, projectId, description]);
+
   // Handle template selection
   const handleTemplateChange = (templateId: string) => {
     setSelectedTemplate(templateId);
@@ -307,8 +374,49 @@ export function TaskFormDialog({
               disabled={isSubmitting || isSubmittingAndStart}
               projectId={projectId}
             />
+            <p className="mt-1 text-xs text-muted-foreground">
+              {'{{recent_commits}}, {{readme}}, {{toolchain}}, {{repo_map}} '}
+              {'and {{ci_failures}} are filled in from the worktree when the '}
+              agent starts, and so are the contents of @-mentioned files (add
+              :10-40 for just those lines).
+            </p>
+            {mentionProblems.length > 0 && (
+              <ul className="mt-1 space-y-0.5 text-xs text-orange-600 dark:text-orange-400">
+                {mentionProblems.map((problem) => (
+                  <li key={problem.mention} className="flex items-center gap-1">
+                    <AlertTriangle className="h-3 w-3 shrink-0" />
+                    <span>
+                      @{problem.mention} {problem.problem}
+                    </span>
+                  </li>
+                ))}
+              </ul>
+            )}
           </div>
 
+          {!isEditMode && similarTasks.length > 0 && (
+            <div className="p-3 rounded-lg border bg-muted/40">
+              <div className="flex items-center gap-2 mb-2">
+                <Copy className="h-4 w-4 text-muted-foreground" />
+                <p className="text-sm font-medium">Similar existing tasks</p>
+              </div>
+              <ul className="space-y-1">
+                {similarTasks.map((similar) => (
+                  <li
+                    key={similar.id}
+                    className="flex items-center justify-between gap-2 text-sm"
+                  >
+                    <span className="truncate">{similar.title}</span>
+                    <span className="shrink-0 text-xs text-muted-foreground">
+                      {STATUS_LABELS[similar.status]} ·{' '}
+                      {Math.round(similar.score * 100)}%
+                    </span>
+                  </li>
+                ))}
+              </ul>
+            </div>
+          )}
+
           {!isEditMode && templates.length > 0 && (
             <div className="pt-2">
               <details className="group">
@@ -373,6 +481,7 @@ export function TaskFormDialog({
                 <SelectContent>
                   <SelectItem value="todo">To Do</SelectItem>
                   <SelectItem value="inprogress">In Progress</SelectItem>
+                  <SelectItem value="planreview">Plan Review</SelectItem>
                   <SelectItem value="inreview">In Review</SelectItem>
                   <SelectItem value="done">Done</SelectItem>
                   <SelectItem value="cancelled">Cancelled</SelectItem>
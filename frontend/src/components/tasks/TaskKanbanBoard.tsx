@@ -1,4 +1,4 @@
-import { memo, useEffect, useMemo, useState } from 'react';
+import { memo, useCallback, useEffect, useMemo, useState } from 'react';
 import {
   type DragEndEvent,
   KanbanBoard,
@@ -7,7 +7,11 @@ import {
   KanbanProvider,
 } from '@/components/ui/shadcn-io/kanban';
 import { TaskCard } from './TaskCard';
-import type { TaskStatus, TaskWithAttemptStatus } from 'shared/types';
+import type {
+  BoardColumn,
+  TaskStatus,
+  TaskWithAttemptStatus,
+} from 'shared/types';
 import { useNavigate, useParams } from 'react-router-dom';
 import {
   useKeyboardShortcuts,
@@ -18,17 +22,22 @@ type Task = TaskWithAttemptStatus;
 
 interface TaskKanbanBoardProps {
   tasks: Task[];
+  // The project's own columns; without any there is a column per status
+  columns?: BoardColumn[];
   searchQuery?: string;
   onDragEnd: (event: DragEndEvent) => void;
   onEditTask: (task: Task) => void;
   onDeleteTask: (taskId: string) => void;
+  onArchiveTask: (taskId: string) => void;
   onViewTaskDetails: (task: Task) => void;
   isPanelOpen: boolean;
 }
 
 const allTaskStatuses: TaskStatus[] = [
   'todo',
+  'queued',
   'inprogress',
+  'planreview',
   'inreview',
   'done',
   'cancelled',
@@ -36,7 +45,9 @@ const allTaskStatuses: TaskStatus[] = [
 
 const statusLabels: Record<TaskStatus, string> = {
   todo: 'To Do',
+  queued: 'Queued',
   inprogress: 'In Progress',
+  planreview: 'Plan Review',
   inreview: 'In Review',
   done: 'Done',
   cancelled: 'Cancelled',
@@ -44,18 +55,24 @@ const statusLabels: Record<TaskStatus, string> = {
 
 const statusBoardColors: Record<TaskStatus, string> = {
   todo: 'hsl(var(--neutral))',
+  queued: 'hsl(var(--neutral))',
   inprogress: 'hsl(var(--info))',
+  planreview: 'hsl(var(--warning))',
   inreview: 'hsl(var(--warning))',
   done: 'hsl(var(--success))',
   cancelled: 'hsl(var(--destructive))',
 };
 
+const noColumns: BoardColumn[] = [];
+
 function TaskKanbanBoard({
   tasks,
+  columns = noColumns,
   searchQuery = '',
   onDragEnd,
   onEditTask,
   onDeleteTask,
+  onArchiveTask,
   onViewTaskDetails,
   isPanelOpen,
 }: TaskKanbanBoardProps) {
@@ -73,7 +90,21 @@ function TaskKanbanBoard({
   const [focusedTaskId, setFocusedTaskId] = useState<string | null>(
     taskId || null
   );
-  const [focusedStatus, setFocusedStatus] = useState<TaskStatus | null>(null);
+  // The status, or the column id on a board with its own columns
+  const [focusedStatus, setFocusedStatus] = useState<string | null>(null);
+
+  const columnKeys = useMemo(
+    () =>
+      columns.length > 0 ? columns.map((column) => column.id) : allTaskStatuses,
+    [columns]
+  );
+  const columnOf = useCallback(
+    (task: Task): string =>
+      columns.length > 0
+        ? (task.board_column_id ?? columns[0].id)
+        : task.status.toLowerCase(),
+    [columns]
+  );
 
   // Memoize filtered tasks
   const filteredTasks = useMemo(() => {
@@ -90,20 +121,51 @@ function TaskKanbanBoard({
 
   // Memoize grouped tasks
   const groupedTasks = useMemo(() => {
-    const groups: Record<TaskStatus, Task[]> = {} as Record<TaskStatus, Task[]>;
-    allTaskStatuses.forEach((status) => {
-      groups[status] = [];
+    const groups: Record<string, Task[]> = {};
+    columnKeys.forEach((key) => {
+      groups[key] = [];
     });
     filteredTasks.forEach((task) => {
-      const normalizedStatus = task.status.toLowerCase() as TaskStatus;
-      if (groups[normalizedStatus]) {
-        groups[normalizedStatus].push(task);
+      const key = columnOf(task);
+      if (groups[key]) {
+        groups[key].push(task);
       } else {
-        groups['todo'].push(task);
+        groups[columnKeys[0]].push(task);
       }
     });
     return groups;
-  }, [filteredTasks]);
+  }, [filteredTasks, columnKeys, columnOf]);
+
+  // Count every task against WIP limits, not just those matching the search
+  const columnCounts = useMemo(() => {
+    const counts: Record<string, number> = {};
+    tasks.forEach((task) => {
+      const key = columnOf(task);
+      counts[key] = (counts[key] ?? 0) + 1;
+    });
+    return counts;
+  }, [tasks, columnOf]);
+
+  const header = (key: string) => {
+    const column = columns.find((c) => c.id === key);
+    if (!column) {
+      return {
+        name: statusLabels[key as TaskStatus],
+        color: statusBoardColors[key as TaskStatus],
+      };
+    }
+    if (column.wip_limit === null) {
+      return { name: column.name, color: statusBoardColors[column.status] };
+    }
+    const count = columnCounts[key] ?? 0;
+    return {
+      name: `${column.name} (${count}/${column.wip_limit})`,
+      color:
+        count > Number(column.wip_limit)
+          ? 'hsl(var(--destructive))'
+          : statusBoardColors[column.status],
+    };
+  };
 
   // Sync focus state with taskId param
   useEffect(() => {
@@ -111,23 +173,23 @@ function TaskKanbanBoard({
       const found = filteredTasks.find((t) => t.id === taskId);
       if (found) {
         setFocusedTaskId(taskId);
-        setFocusedStatus((found.status.toLowerCase() as TaskStatus) || null);
+        setFocusedStatus(columnOf(found));
       }
     }
-  }, [taskId, filteredTasks]);
+  }, [taskId, filteredTasks, columnOf]);
 
   // If no taskId in params, keep last focused, or focus first available
   useEffect(() => {
     if (!taskId && !focusedTaskId) {
-      for (const status of allTaskStatuses) {
-        if (groupedTasks[status] && groupedTasks[status].length > 0) {
-          setFocusedTaskId(groupedTasks[status][0].id);
-          setFocusedStatus(status);
+      for (const key of columnKeys) {
+        if (groupedTasks[key] && groupedTasks[key].length > 0) {
+          setFocusedTaskId(groupedTasks[key][0].id);
+          setFocusedStatus(key);
           break;
         }
       }
     }
-  }, [taskId, focusedTaskId, groupedTasks]);
+  }, [taskId, focusedTaskId, groupedTasks, columnKeys]);
 
   // Keyboard navigation handler
   useKanbanKeyboardNavigation({
@@ -142,22 +204,19 @@ function TaskKanbanBoard({
       }
     },
     focusedStatus,
-    setFocusedStatus: (status) => setFocusedStatus(status as TaskStatus | null),
+    setFocusedStatus,
     groupedTasks,
     filteredTasks,
-    allTaskStatuses,
+    allTaskStatuses: columnKeys,
   });
 
   return (
     <KanbanProvider onDragEnd={onDragEnd}>
-      {Object.entries(groupedTasks).map(([status, statusTasks]) => (
-        <KanbanBoard key={status} id={status as TaskStatus}>
-          <KanbanHeader
-            name={statusLabels[status as TaskStatus]}
-            color={statusBoardColors[status as TaskStatus]}
-          />
+      {columnKeys.map((status) => (
+        <KanbanBoard key={status} id={status}>
+          <KanbanHeader {...header(status)} />
           <KanbanCards>
-            {statusTasks.map((task, index) => (
+            {groupedTasks[status].map((task, index) => (
               <TaskCard
                 key={task.id}
                 task={task}
@@ -165,6 +224,7 @@ function TaskKanbanBoard({
                 status={status}
                 onEdit={onEditTask}
                 onDelete={onDeleteTask}
+                onArchive={onArchiveTask}
                 onViewDetails={onViewTaskDetails}
                 isFocused={focusedTaskId === task.id}
                 tabIndex={focusedTaskId === task.id ? 0 : -1}
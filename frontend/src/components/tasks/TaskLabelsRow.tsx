@@ -0,0 +1,116 @@
+import { useEffect, useState } from 'react';
+import { Check, X } from 'lucide-react';
+import type { TaskLabels } from 'shared/types';
+import { tasksApi } from '@/lib/api';
+import { Input } from '@/components/ui/input';
+
+interface TaskLabelsRowProps {
+  projectId: string;
+  taskId: string;
+}
+
+function TaskLabelsRow({ projectId, taskId }: TaskLabelsRowProps) {
+  const [labels, setLabels] = useState<TaskLabels | null>(null);
+  const [newLabel, setNewLabel] = useState('');
+
+  useEffect(() => {
+    setLabels(null);
+    tasksApi
+      .getLabels(projectId, taskId)
+      .then(setLabels)
+      .catch(() => setLabels(null));
+  }, [projectId, taskId]);
+
+  const update = async (request: Promise<TaskLabels>) => {
+    try {
+      setLabels(await request);
+    } catch (err) {
+      console.error('Failed to update labels:', err);
+    }
+  };
+
+  const addLabel = async () => {
+    const name = newLabel.trim();
+    if (!name) {
+      return;
+    }
+    setNewLabel('');
+    await update(tasksApi.addLabel(projectId, taskId, name));
+  };
+
+  if (!labels) {
+    return null;
+  }
+
+  return (
+    <div className="mt-2 flex flex-wrap items-center gap-1">
+      {labels.labels.map((name) => (
+        <span
+          key={name}
+          className="inline-flex items-center gap-1 rounded-full border px-2 py-0.5 text-xs"
+        >
+          {name}
+          <button
+            type="button"
+            className="text-muted-foreground hover:text-foreground"
+            onClick={() =>
+              update(tasksApi.removeLabel(projectId, taskId, name))
+            }
+            aria-label={`Remove label ${name}`}
+          >
+            <X className="h-3 w-3" />
+          </button>
+        </span>
+      ))}
+      {labels.suggestions.map((suggestion) => (
+        <span
+          key={suggestion.name}
+          title={`Suggested: ${suggestion.reason}`}
+          className="inline-flex items-center gap-1 rounded-full border border-dashed px-2 py-0.5 text-xs text-muted-foreground"
+        >
+          {suggestion.name}
+          <button
+            type="button"
+            className="hover:text-foreground"
+            onClick={() =>
+              update(tasksApi.addLabel(projectId, taskId, suggestion.name))
+            }
+            aria-label={`Accept label ${suggestion.name}`}
+          >
+            <Check className="h-3 w-3" />
+          </button>
+          <button
+            type="button"
+            className="hover:text-foreground"
+            onClick={() =>
+              update(
+                tasksApi.dismissLabelSuggestion(
+                  projectId,
+                  taskId,
+                  suggestion.name
+                )
+              )
+            }
+            aria-label={`Dismiss label ${suggestion.name}`}
+          >
+            <X className="h-3 w-3" />
+          </button>
+        </span>
+      ))}
+      <Input
+        placeholder="Add label"
+        value={newLabel}
+        onChange={(e) => setNewLabel(e.target.value)}
+        onBlur={addLabel}
+        onKeyDown={(e) => {
+          if (e.key === 'Enter') {
+            addLabel();
+          }
+        }}
+        className="h-6 w-24 px-2 text-xs"
+      />
+    </div>
+  );
+}
+
+export default TaskLabelsRow;
@@ -0,0 +1,77 @@
+import { useEffect, useState } from 'react';
+import { Clock } from 'lucide-react';
+import { tasksApi } from '@/lib/api';
+import { Input } from '@/components/ui/input';
+
+interface TaskTimeoutRowProps {
+  projectId: string;
+  taskId: string;
+  timeoutMinutes: bigint | null;
+}
+
+function TaskTimeoutRow({
+  projectId,
+  taskId,
+  timeoutMinutes,
+}: TaskTimeoutRowProps) {
+  const [saved, setSaved] = useState(
+    timeoutMinutes === null ? '' : String(timeoutMinutes)
+  );
+  const [value, setValue] = useState(saved);
+  const [error, setError] = useState('');
+
+  useEffect(() => {
+    const initial = timeoutMinutes === null ? '' : String(timeoutMinutes);
+    setSaved(initial);
+    setValue(initial);
+    setError('');
+  }, [taskId, timeoutMinutes]);
+
+  const save = async () => {
+    if (value === saved) {
+      return;
+    }
+    const minutes = value.trim() === '' ? null : parseInt(value);
+    if (minutes !== null && (isNaN(minutes) || minutes < 0)) {
+      setError('Enter a number of minutes, or leave empty for the default');
+      return;
+    }
+    setError('');
+    try {
+      const task = await tasksApi.setTimeout(projectId, taskId, minutes);
+      const stored =
+        task.timeout_minutes === null ? '' : String(task.timeout_minutes);
+      setSaved(stored);
+      setValue(stored);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to save timeout');
+    }
+  };
+
+  return (
+    <div className="mt-2 space-y-1 text-xs">
+      <div className="flex items-center gap-1">
+        <Clock className="h-3 w-3 text-muted-foreground" />
+        <span className="text-muted-foreground">Timeout</span>
+        <Input
+          type="number"
+          min={0}
+          placeholder="Default"
+          value={value}
+          onChange={(e) => setValue(e.target.value)}
+          onBlur={save}
+          onKeyDown={(e) => {
+            if (e.key === 'Enter') {
+              save();
+            }
+          }}
+          className="h-6 w-20 px-2 text-xs"
+        />
+        <span className="text-muted-foreground">minutes (0 for no limit)</span>
+      </div>
+      {error && <p className="text-destructive">{error}</p>}
+    </div>
+  );
+}
+
+export default TaskTimeoutRow;
@@ -0,0 +1,147 @@
+import { useContext, useEffect, useState } from 'react';
+import { Trophy } from 'lucide-react';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { Button } from '@/components/ui/button';
+import { Loader } from '@/components/ui/loader';
+import { DiffCard } from '@/components/tasks/TaskDetails/DiffCard.tsx';
+import { TaskDetailsContext } from '@/components/context/taskDetailsContext.ts';
+import { attemptsApi } from '@/lib/api.ts';
+import type { AttemptGroup, TaskAttempt, WorktreeDiff } from 'shared/types.ts';
+
+type Props = {
+  group: AttemptGroup;
+  taskAttempts: TaskAttempt[];
+  availableExecutors: {
+    id: string;
+    name: string;
+  }[];
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+  onPicked: (group: AttemptGroup) => void;
+};
+
+function CompareAttemptsDialog({
+  group,
+  taskAttempts,
+  availableExecutors,
+  open,
+  onOpenChange,
+  onPicked,
+}: Props) {
+  const { projectId, task } = useContext(TaskDetailsContext);
+  const [diffs, setDiffs] = useState<Record<string, WorktreeDiff | null>>({});
+  const [loading, setLoading] = useState(false);
+  const [picking, setPicking] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!open || !projectId) return;
+    setLoading(true);
+    Promise.all(
+      group.attempt_ids.map(async (attemptId) => {
+        try {
+          const diff = await attemptsApi.getDiff(projectId, task.id, attemptId);
+          return [attemptId, diff] as const;
+        } catch (err) {
+          console.error('Failed to load diff:', err);
+          return [attemptId, null] as const;
+        }
+      })
+    )
+      .then((entries) => setDiffs(Object.fromEntries(entries)))
+      .finally(() => setLoading(false));
+  }, [open, projectId, task.id, group.attempt_ids]);
+
+  const executorName = (attemptId: string) => {
+    const executor = taskAttempts.find((a) => a.id === attemptId)?.executor;
+    return (
+      availableExecutors.find((e) => e.id === executor)?.name ||
+      executor ||
+      'Unknown'
+    );
+  };
+
+  const handlePick = async (attemptId: string) => {
+    if (!projectId) return;
+    setPicking(attemptId);
+    try {
+      const picked = await attemptsApi.pickWinner(projectId, task.id, {
+        attempt_id: attemptId,
+      });
+      setError(null);
+      onPicked(picked);
+      onOpenChange(false);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to pick attempt');
+    } finally {
+      setPicking(null);
+    }
+  };
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent className="max-w-[90vw] h-[85vh] flex flex-col">
+        <DialogHeader>
+          <DialogTitle>Compare attempts</DialogTitle>
+          <DialogDescription>
+            Each coding agent worked in its own worktree. Pick the attempt to
+            keep; the others are stopped.
+          </DialogDescription>
+        </DialogHeader>
+        {error && <p className="text-sm text-destructive">{error}</p>}
+        {loading ? (
+          <div className="flex items-center justify-center flex-1">
+            <Loader message="Loading changes..." size={32} />
+          </div>
+        ) : (
+          <div
+            className={`grid gap-3 flex-1 min-h-0 ${
+              group.attempt_ids.length === 3 ? 'grid-cols-3' : 'grid-cols-2'
+            }`}
+          >
+            {group.attempt_ids.map((attemptId) => (
+              <div key={attemptId} className="flex flex-col gap-2 min-h-0">
+                <div className="flex items-center justify-between gap-2">
+                  <span className="text-sm font-medium truncate">
+                    {executorName(attemptId)}
+                  </span>
+                  {group.winner_attempt_id === attemptId ? (
+                    <span className="flex gap-1 items-center text-xs text-green-700">
+                      <Trophy className="h-3 w-3" />
+                      Picked
+                    </span>
+                  ) : (
+                    <Button
+                      size="sm"
+                      onClick={() => handlePick(attemptId)}
+                      disabled={
+                        picking !== null || group.winner_attempt_id !== null
+                      }
+                      className="gap-1"
+                    >
+                      <Trophy className="h-3 w-3" />
+                      {picking === attemptId ? 'Picking...' : 'Pick'}
+                    </Button>
+                  )}
+                </div>
+                <DiffCard
+                  diff={diffs[attemptId] ?? null}
+                  compact={false}
+                  className="flex-1 min-h-0"
+                />
+              </div>
+            ))}
+          </div>
+        )}
+      </DialogContent>
+    </Dialog>
+  );
+}
+
+export default CompareAttemptsDialog;
@@ -1,5 +1,14 @@
-import { Dispatch, SetStateAction, useCallback, useContext } from 'react';
+import {
+  Dispatch,
+  SetStateAction,
+  useCallback,
+  useContext,
+  useEffect,
+} from 'react';
 import { Button } from '@/components/ui/button.tsx';
+import { Checkbox } from '@/components/ui/checkbox.tsx';
+import { Input } from '@/components/ui/input.tsx';
+import { Label } from '@/components/ui/label.tsx';
 import { ArrowDown, Play, Settings2, X, AlertTriangle } from 'lucide-react';
 import {
   DropdownMenu,
@@ -7,8 +16,14 @@ import {
   DropdownMenuItem,
   DropdownMenuTrigger,
 } from '@/components/ui/dropdown-menu.tsx';
-import type { GitBranch, TaskAttempt } from 'shared/types.ts';
-import { attemptsApi } from '@/lib/api.ts';
+import type {
+  ExecutorProfile,
+  GitBranch,
+  RunEstimate,
+  TaskAttempt,
+} from 'shared/types.ts';
+import { MODEL_SUPPORTED_EXECUTORS } from 'shared/types.ts';
+import { attemptsApi, executorProfilesApi, tasksApi } from '@/lib/api.ts';
 import {
   TaskAttemptDataContext,
   TaskDetailsContext,
@@ -70,21 +85,94 @@ function CreateAttempt({
   const [pendingBaseBranch, setPendingBaseBranch] = useState<
     string | undefined
   >(undefined);
+  const [estimate, setEstimate] = useState<RunEstimate | null>(null);
+  const [isEstimating, setIsEstimating] = useState(false);
+  const [planOnly, setPlanOnly] = useState(false);
+  const [model, setModel] = useState('');
+  const [profiles, setProfiles] = useState<ExecutorProfile[]>([]);
+  const [profileId, setProfileId] = useState<string | null>(null);
+  // Other coding agents to run alongside the chosen one, for comparison
+  const [compareWith, setCompareWith] = useState<string[]>([]);
+
+  useEffect(() => {
+    if (!projectId) return;
+    executorProfilesApi
+      .getAvailable(projectId)
+      .then(setProfiles)
+      .catch(() => setProfiles([]));
+  }, [projectId]);
 
   // Create attempt logic
   const actuallyCreateAttempt = useCallback(
     async (executor?: string, baseBranch?: string) => {
       try {
-        await attemptsApi.create(projectId!, task.id, {
-          executor: executor || selectedExecutor,
-          base_branch: baseBranch || selectedBranch,
-        });
+        const chosen = executor || selectedExecutor;
+        const others = compareWith.filter((id) => id !== chosen);
+        if (others.length > 0) {
+          await attemptsApi.createParallel(projectId!, task.id, {
+            executors: [chosen, ...others],
+            base_branch: baseBranch || selectedBranch,
+          });
+        } else {
+          await attemptsApi.create(projectId!, task.id, {
+            executor: chosen,
+            base_branch: baseBranch || selectedBranch,
+            plan_only: planOnly,
+            model: MODEL_SUPPORTED_EXECUTORS.includes(chosen)
+              ? model.trim() || null
+              : null,
+            executor_profile_id: profileId,
+          });
+        }
         fetchTaskAttempts();
       } catch (error) {
         // Optionally handle error
       }
     },
-    [projectId, task.id, selectedExecutor, selectedBranch, fetchTaskAttempts]
+    [
+      projectId,
+      task.id,
+      selectedExecutor,
+      selectedBranch,
+      planOnly,
+      model,
+      profileId,
+      compareWith,
+      fetchTaskAttempts,
+    ]
+  );
+
+  // An estimate over the configured limits has to be confirmed before the
+  // attempt starts. If estimating fails the attempt starts anyway.
+  const startAttempt = useCallback(
+    async (executor?: string, baseBranch?: string) => {
+      setShowCreateAttemptConfirmation(false);
+      if (config?.run_estimate.enabled) {
+        setIsEstimating(true);
+        try {
+          const result = await tasksApi.estimate(projectId!, task.id);
+          if (result.warnings.length > 0) {
+            setPendingExecutor(executor);
+            setPendingBaseBranch(baseBranch);
+            setEstimate(result);
+            return;
+          }
+        } catch (error) {
+          // Start without an estimate
+        } finally {
+          setIsEstimating(false);
+        }
+      }
+      actuallyCreateAttempt(executor, baseBranch);
+      setIsInCreateAttemptMode(false);
+    },
+    [
+      config?.run_estimate.enabled,
+      projectId,
+      task.id,
+      actuallyCreateAttempt,
+      setIsInCreateAttemptMode,
+    ]
   );
 
   // Handler for Enter key or Start button
@@ -95,12 +183,10 @@ function CreateAttempt({
         setPendingBaseBranch(baseBranch);
         setShowCreateAttemptConfirmation(true);
       } else {
-        actuallyCreateAttempt(executor, baseBranch);
-        setShowCreateAttemptConfirmation(false);
-        setIsInCreateAttemptMode(false);
+        startAttempt(executor, baseBranch);
       }
     },
-    [task.status, actuallyCreateAttempt, setIsInCreateAttemptMode]
+    [task.status, startAttempt]
   );
 
   // Keyboard shortcuts
@@ -108,6 +194,8 @@ function CreateAttempt({
     onEnter: () => {
       if (showCreateAttemptConfirmation) {
         handleConfirmCreateAttempt();
+      } else if (estimate) {
+        handleConfirmEstimate();
       } else {
         onCreateNewAttempt(
           createAttemptExecutor,
@@ -116,10 +204,43 @@ function CreateAttempt({
         );
       }
     },
-    hasOpenDialog: showCreateAttemptConfirmation,
-    closeDialog: () => setShowCreateAttemptConfirmation(false),
+    hasOpenDialog: showCreateAttemptConfirmation || estimate !== null,
+    closeDialog: () => {
+      setShowCreateAttemptConfirmation(false);
+      setEstimate(null);
+    },
   });
 
+  const toggleCompareWith = (executorId: string, checked: boolean) => {
+    setCompareWith((prev) =>
+      checked
+        ? [...prev, executorId]
+        : prev.filter((id) => id !== executorId)
+    );
+  };
+
+  const comparedOthers = compareWith.filter(
+    (id) => id !== createAttemptExecutor
+  );
+  const comparing = comparedOthers.length > 0;
+  const supportsModel = MODEL_SUPPORTED_EXECUTORS.includes(
+    createAttemptExecutor
+  );
+  const profile = profiles.find((p) => p.id === profileId);
+
+  // A profile runs its own executor; picking an agent goes back to none
+  const selectProfile = (selected: ExecutorProfile | null) => {
+    setProfileId(selected?.id ?? null);
+    if (selected) {
+      setCreateAttemptExecutor(selected.executor);
+    }
+  };
+
+  const selectExecutor = (executorId: string) => {
+    setProfileId(null);
+    setCreateAttemptExecutor(executorId);
+  };
+
   const handleExitCreateAttemptMode = () => {
     setIsInCreateAttemptMode(false);
   };
@@ -129,8 +250,12 @@ function CreateAttempt({
   };
 
   const handleConfirmCreateAttempt = () => {
+    startAttempt(pendingExecutor, pendingBaseBranch);
+  };
+
+  const handleConfirmEstimate = () => {
+    setEstimate(null);
     actuallyCreateAttempt(pendingExecutor, pendingBaseBranch);
-    setShowCreateAttemptConfirmation(false);
     setIsInCreateAttemptMode(false);
   };
 
@@ -218,7 +343,7 @@ function CreateAttempt({
                 {availableExecutors.map((executor) => (
                   <DropdownMenuItem
                     key={executor.id}
-                    onClick={() => setCreateAttemptExecutor(executor.id)}
+                    onClick={() => selectExecutor(executor.id)}
                     className={
                       createAttemptExecutor === executor.id ? 'bg-accent' : ''
                     }
@@ -238,6 +363,7 @@ function CreateAttempt({
               disabled={
                 !createAttemptExecutor ||
                 isAttemptRunning ||
+                isEstimating ||
                 (isPlanningMode && !canCreateTask)
               }
               size="sm"
@@ -258,10 +384,131 @@ function CreateAttempt({
               {!(isPlanningMode && !canCreateTask) && (
                 <Play className="h-3 w-3 mr-1.5" />
               )}
-              Start
+              {isEstimating ? 'Estimating...' : 'Start'}
             </Button>
           </div>
         </div>
+
+        <div className="flex items-start gap-2">
+          <Checkbox
+            id="plan-only"
+            checked={planOnly && !comparing}
+            disabled={comparing}
+            onCheckedChange={(checked: boolean) => setPlanOnly(checked)}
+          />
+          <div className="space-y-0.5">
+            <Label htmlFor="plan-only" className="cursor-pointer text-xs">
+              Plan first
+            </Label>
+            <p className="text-xs text-muted-foreground">
+              The agent only writes a plan. Once you approve it, the same
+              session implements it.
+            </p>
+          </div>
+        </div>
+
+        {profiles.length > 0 && (
+          <div className="space-y-1">
+            <p className="text-xs font-medium text-muted-foreground">
+              Profile
+            </p>
+            <DropdownMenu>
+              <DropdownMenuTrigger asChild>
+                <Button
+                  variant="outline"
+                  size="sm"
+                  disabled={comparing}
+                  className="w-full justify-between text-xs"
+                >
+                  <span className="truncate">
+                    {profile && !comparing ? profile.name : 'None'}
+                  </span>
+                  <ArrowDown className="h-3 w-3" />
+                </Button>
+              </DropdownMenuTrigger>
+              <DropdownMenuContent className="w-full">
+                <DropdownMenuItem
+                  onClick={() => selectProfile(null)}
+                  className={!profile ? 'bg-accent' : ''}
+                >
+                  None
+                </DropdownMenuItem>
+                {profiles.map((p) => (
+                  <DropdownMenuItem
+                    key={p.id}
+                    onClick={() => selectProfile(p)}
+                    className={profileId === p.id ? 'bg-accent' : ''}
+                  >
+                    {p.name}
+                    <span className="ml-1 text-muted-foreground">
+                      ({p.executor}
+                      {p.project_id ? '' : ', global'})
+                    </span>
+                  </DropdownMenuItem>
+                ))}
+              </DropdownMenuContent>
+            </DropdownMenu>
+          </div>
+        )}
+
+        {supportsModel && (
+          <div className="space-y-1">
+            <Label htmlFor="attempt-model" className="text-xs">
+              Model
+            </Label>
+            <Input
+              id="attempt-model"
+              value={comparing ? '' : model}
+              disabled={comparing}
+              onChange={(e) => setModel(e.target.value)}
+              placeholder={
+                profile?.model ||
+                config?.executor_models[createAttemptExecutor] ||
+                'The agent’s default'
+              }
+              className="h-8 text-xs"
+            />
+            <p className="text-xs text-muted-foreground">
+              As the agent’s CLI names it, e.g. opus or o4-mini. Left empty, the
+              default from the settings is used.
+            </p>
+          </div>
+        )}
+
+        <div className="space-y-1">
+          <p className="text-xs font-medium text-muted-foreground">
+            Run side by side with
+          </p>
+          <div className="flex flex-wrap gap-x-4 gap-y-1">
+            {availableExecutors
+              .filter((executor) => executor.id !== createAttemptExecutor)
+              .map((executor) => {
+                const checked = compareWith.includes(executor.id);
+                return (
+                  <div key={executor.id} className="flex items-center gap-1.5">
+                    <Checkbox
+                      id={`compare-${executor.id}`}
+                      checked={checked}
+                      disabled={!checked && comparedOthers.length >= 2}
+                      onCheckedChange={(value: boolean) =>
+                        toggleCompareWith(executor.id, value)
+                      }
+                    />
+                    <Label
+                      htmlFor={`compare-${executor.id}`}
+                      className="cursor-pointer text-xs"
+                    >
+                      {executor.name}
+                    </Label>
+                  </div>
+                );
+              })}
+          </div>
+          <p className="text-xs text-muted-foreground">
+            Each agent gets its own worktree. Compare their changes and pick
+            the attempt to keep.
+          </p>
+        </div>
       </div>
 
       {/* Confirmation Dialog */}
@@ -288,6 +535,51 @@ function CreateAttempt({
           </DialogFooter>
         </DialogContent>
       </Dialog>
+
+      {/* Run Estimate Dialog */}
+      <Dialog
+        open={estimate !== null}
+        onOpenChange={(open) => !open && setEstimate(null)}
+      >
+        <DialogContent className="sm:max-w-lg">
+          <DialogHeader>
+            <DialogTitle>This attempt looks large</DialogTitle>
+            <DialogDescription>
+              Estimated at about{' '}
+              {Math.round((estimate?.estimated_tokens ?? 0) / 1000)}k
+              tokens, {estimate?.scope} scope
+              {estimate?.used_llm ? '' : ' (from repository size only)'}.
+            </DialogDescription>
+          </DialogHeader>
+          {estimate && (
+            <div className="space-y-3 text-sm">
+              <ul className="list-disc pl-5 text-orange-700 dark:text-orange-400">
+                {estimate.warnings.map((warning) => (
+                  <li key={warning}>{warning}</li>
+                ))}
+              </ul>
+              {estimate.likely_files.length > 0 && (
+                <div>
+                  <p className="text-xs font-medium text-muted-foreground mb-1">
+                    Likely to touch
+                  </p>
+                  <ul className="max-h-40 overflow-y-auto font-mono text-xs">
+                    {estimate.likely_files.map((file) => (
+                      <li key={file}>{file}</li>
+                    ))}
+                  </ul>
+                </div>
+              )}
+            </div>
+          )}
+          <DialogFooter>
+            <Button variant="outline" onClick={() => setEstimate(null)}>
+              Cancel
+            </Button>
+            <Button onClick={handleConfirmEstimate}>Start anyway</Button>
+          </DialogFooter>
+        </DialogContent>
+      </Dialog>
     </div>
   );
 }
@@ -22,10 +22,11 @@ import {
   TaskDetailsContext,
   TaskSelectedAttemptContext,
 } from '@/components/context/taskDetailsContext.ts';
-import { ApiError, attemptsApi } from '@/lib/api.ts';
+import { ApiError, attemptsApi, tasksApi } from '@/lib/api.ts';
 import { ProvidePatDialog } from '@/components/ProvidePatDialog';
 import { GitHubLoginDialog } from '@/components/GitHubLoginDialog';
 import { GitBranch } from 'shared/types.ts';
+import { Sparkles } from 'lucide-react';
 
 type Props = {
   showCreatePRDialog: boolean;
@@ -48,6 +49,8 @@ function CreatePrDialog({
   const { selectedAttempt } = useContext(TaskSelectedAttemptContext);
   const [prTitle, setPrTitle] = useState('');
   const [prBody, setPrBody] = useState('');
+  const [generatingBody, setGeneratingBody] = useState(false);
+  const [writingWithLlm, setWritingWithLlm] = useState(false);
   const [prBaseBranch, setPrBaseBranch] = useState(
     selectedAttempt?.base_branch || 'main'
   );
@@ -56,10 +59,35 @@ function CreatePrDialog({
   const [showGitHubLoginDialog, setShowGitHubLoginDialog] = useState(false);
 
   useEffect(() => {
-    if (showCreatePRDialog) {
-      setPrTitle(`${task.title} (vibe-kanban)`);
-      setPrBody(task.description || '');
-    }
+    if (!showCreatePRDialog) return;
+    setPrTitle(`${task.title} (vibe-kanban)`);
+    setPrBody(task.description || '');
+    if (!projectId || !selectedAttempt) return;
+
+    let cancelled = false;
+    setGeneratingBody(true);
+    attemptsApi
+      .getPrDescription(projectId, selectedAttempt.task_id, selectedAttempt.id)
+      .then((description) => {
+        // Keep whatever the user started typing while this was loading
+        if (!cancelled) {
+          setPrTitle((title) =>
+            title === `${task.title} (vibe-kanban)`
+              ? description.title
+              : title
+          );
+          setPrBody((body) =>
+            body === (task.description || '') ? description.body : body
+          );
+        }
+      })
+      .catch((err) => {
+        console.error('Failed to generate PR description:', err);
+      })
+      .finally(() => setGeneratingBody(false));
+    return () => {
+      cancelled = true;
+    };
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, [showCreatePRDialog]);
 
@@ -70,6 +98,26 @@ function CreatePrDialog({
     }
   }, [selectedAttempt?.base_branch]);
 
+  const handleWriteWithLlm = useCallback(async () => {
+    if (!projectId || !selectedAttempt) return;
+    setWritingWithLlm(true);
+    try {
+      const description = await tasksApi.generatePrDescription(
+        projectId,
+        task.id,
+        selectedAttempt.id
+      );
+      setPrTitle(description.title);
+      setPrBody(description.body);
+    } catch (err) {
+      setError(
+        err instanceof Error ? err.message : 'Failed to write PR description'
+      );
+    } finally {
+      setWritingWithLlm(false);
+    }
+  }, [projectId, task.id, selectedAttempt, setError]);
+
   const handleConfirmCreatePR = useCallback(async () => {
     if (!projectId || !selectedAttempt?.id || !selectedAttempt?.task_id) return;
 
@@ -173,13 +221,32 @@ function CreatePrDialog({
               />
             </div>
             <div className="space-y-2">
-              <Label htmlFor="pr-body">Description (optional)</Label>
+              <div className="flex items-center justify-between">
+                <Label htmlFor="pr-body">
+                  Description (optional)
+                  {generatingBody && (
+                    <span className="ml-2 text-xs text-muted-foreground">
+                      Generating...
+                    </span>
+                  )}
+                </Label>
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  onClick={handleWriteWithLlm}
+                  disabled={writingWithLlm || generatingBody}
+                  title="Write the title and description from the attempt's changes and conversation"
+                >
+                  <Sparkles className="h-4 w-4 mr-1" />
+                  {writingWithLlm ? 'Writing...' : 'Write from changes'}
+                </Button>
+              </div>
               <Textarea
                 id="pr-body"
                 value={prBody}
                 onChange={(e) => setPrBody(e.target.value)}
                 placeholder="Enter PR description"
-                rows={4}
+                rows={8}
               />
             </div>
             <div className="space-y-2">
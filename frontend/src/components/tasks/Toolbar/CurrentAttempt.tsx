@@ -1,14 +1,17 @@
 import {
   Check,
+  Columns,
   ExternalLink,
   GitBranch as GitBranchIcon,
   GitPullRequest,
   History,
+  ListOrdered,
   Play,
   Plus,
   RefreshCw,
   Settings,
   StopCircle,
+  X,
 } from 'lucide-react';
 import { is_planning_executor_type } from '@/lib/utils';
 import {
@@ -32,13 +35,14 @@ import {
   DialogHeader,
   DialogTitle,
 } from '@/components/ui/dialog.tsx';
+import { Textarea } from '@/components/ui/textarea.tsx';
+import { Checkbox } from '@/components/ui/checkbox.tsx';
 import BranchSelector from '@/components/tasks/BranchSelector.tsx';
+import CompareAttemptsDialog from '@/components/tasks/Toolbar/CompareAttemptsDialog.tsx';
 import {
   attemptsApi,
   executionProcessesApi,
-  makeRequest,
-  FollowUpResponse,
-  ApiResponse,
+  projectsApi,
 } from '@/lib/api.ts';
 import {
   Dispatch,
@@ -50,9 +54,11 @@ import {
   useState,
 } from 'react';
 import type {
+  AttemptGroup,
   BranchStatus,
   ExecutionProcess,
   GitBranch,
+  MergeQueueEntry,
   TaskAttempt,
 } from 'shared/types.ts';
 import {
@@ -66,7 +72,6 @@ import {
 import { useTaskPlan } from '@/components/context/TaskPlanContext.ts';
 import { useConfig } from '@/components/config-provider.tsx';
 import { useKeyboardShortcuts } from '@/lib/keyboard-shortcuts.ts';
-import { useNavigate } from 'react-router-dom';
 
 // Helper function to get the display name for different editor types
 function getEditorDisplayName(editorType: string): string {
@@ -101,6 +106,8 @@ type Props = {
     name: string;
   }[];
   branches: GitBranch[];
+  attemptGroups: AttemptGroup[];
+  fetchTaskAttempts: () => void;
 };
 
 function CurrentAttempt({
@@ -113,12 +120,13 @@ function CurrentAttempt({
   handleEnterCreateAttemptMode,
   availableExecutors,
   branches,
+  attemptGroups,
+  fetchTaskAttempts,
 }: Props) {
   const { task, projectId, handleOpenInEditor, projectHasDevScript } =
     useContext(TaskDetailsContext);
   const { config } = useConfig();
   const { setSelectedAttempt } = useContext(TaskSelectedAttemptContext);
-  const navigate = useNavigate();
   const { isStopping, setIsStopping } = useContext(TaskAttemptStoppingContext);
   const { attemptData, fetchAttemptData, isAttemptRunning } = useContext(
     TaskAttemptDataContext
@@ -127,10 +135,13 @@ function CurrentAttempt({
   const { executionState, fetchExecutionState } = useContext(
     TaskExecutionStateContext
   );
-  const { isPlanningMode, canCreateTask } = useTaskPlan();
+  const { isPlanningMode, canCreateTask, plan } = useTaskPlan();
 
   const [isStartingDevServer, setIsStartingDevServer] = useState(false);
   const [merging, setMerging] = useState(false);
+  const [queuedMerge, setQueuedMerge] = useState<MergeQueueEntry | null>(
+    null
+  );
   const [rebasing, setRebasing] = useState(false);
   const [devServerDetails, setDevServerDetails] =
     useState<ExecutionProcess | null>(null);
@@ -141,7 +152,20 @@ function CurrentAttempt({
   const [selectedRebaseBranch, setSelectedRebaseBranch] = useState<string>('');
   const [showStopConfirmation, setShowStopConfirmation] = useState(false);
   const [isApprovingPlan, setIsApprovingPlan] = useState(false);
+  const [reviewing, setReviewing] = useState(false);
+  const [showRejectDialog, setShowRejectDialog] = useState(false);
+  const [rejectComment, setRejectComment] = useState('');
+  const [rejectFollowUp, setRejectFollowUp] = useState(true);
   const [copied, setCopied] = useState(false);
+  const [showCompareDialog, setShowCompareDialog] = useState(false);
+
+  const attemptGroup = useMemo(
+    () =>
+      attemptGroups.find((group) =>
+        group.attempt_ids.includes(selectedAttempt.id)
+      ),
+    [attemptGroups, selectedAttempt.id]
+  );
 
   const processedDevServerLogs = useMemo(() => {
     if (!devServerDetails) return 'No output yet...';
@@ -162,13 +186,20 @@ function CurrentAttempt({
     );
   }, [attemptData.processes]);
 
-  // Check if plan approval is needed
+  // Check if plan approval is needed; an approved plan turns the attempt into
+  // a regular one that is merged or opened as a PR
   const isPlanTask = useMemo(() => {
     return !!(
-      selectedAttempt.executor &&
-      is_planning_executor_type(selectedAttempt.executor)
+      (selectedAttempt.plan_only ||
+        (selectedAttempt.executor &&
+          is_planning_executor_type(selectedAttempt.executor))) &&
+      plan?.plan.status !== 'approved'
     );
-  }, [selectedAttempt.executor]);
+  }, [
+    selectedAttempt.plan_only,
+    selectedAttempt.executor,
+    plan?.plan.status,
+  ]);
 
   const fetchDevServerDetails = useCallback(async () => {
     if (!runningDevServer || !task || !selectedAttempt) return;
@@ -219,11 +250,10 @@ function CurrentAttempt({
     setIsStartingDevServer(true);
 
     try {
-      await attemptsApi.stopExecutionProcess(
+      await attemptsApi.stopDevServer(
         projectId,
         selectedAttempt.task_id,
-        selectedAttempt.id,
-        runningDevServer.id
+        selectedAttempt.id
       );
       fetchAttemptData(selectedAttempt.id, selectedAttempt.task_id);
     } catch (err) {
@@ -281,6 +311,16 @@ function CurrentAttempt({
     [fetchAttemptData, fetchExecutionState, setSelectedAttempt]
   );
 
+  const handleWinnerPicked = (group: AttemptGroup) => {
+    const winner = taskAttempts.find(
+      (attempt) => attempt.id === group.winner_attempt_id
+    );
+    if (winner) {
+      handleAttemptChange(winner);
+    }
+    fetchTaskAttempts();
+  };
+
   const handleMergeClick = async () => {
     if (!projectId || !selectedAttempt?.id || !selectedAttempt?.task_id) return;
 
@@ -337,15 +377,121 @@ function CurrentAttempt({
     }
   };
 
+  const reviewRequired = Boolean(config?.review_gate.require_approval);
+
+  const handleApproveClick = async () => {
+    if (!projectId || !selectedAttempt?.id || !selectedAttempt?.task_id) return;
+
+    try {
+      setReviewing(true);
+      await attemptsApi.approve(
+        projectId,
+        selectedAttempt.task_id,
+        selectedAttempt.id,
+        { comment: null }
+      );
+      fetchBranchStatus();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to approve');
+    } finally {
+      setReviewing(false);
+    }
+  };
+
+  const handleRejectConfirm = async () => {
+    if (!projectId || !selectedAttempt?.id || !selectedAttempt?.task_id) return;
+
+    try {
+      setReviewing(true);
+      const rejection = await attemptsApi.reject(
+        projectId,
+        selectedAttempt.task_id,
+        selectedAttempt.id,
+        { comment: rejectComment.trim() || null, follow_up: rejectFollowUp }
+      );
+      setShowRejectDialog(false);
+      setRejectComment('');
+      if (rejection.follow_up_attempt_id) {
+        fetchAttemptData(selectedAttempt.id, selectedAttempt.task_id);
+        fetchExecutionState(selectedAttempt.id, selectedAttempt.task_id);
+      }
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to reject');
+    } finally {
+      setReviewing(false);
+    }
+  };
+
+  const handleQueueMergeClick = async () => {
+    if (!projectId || !selectedAttempt?.id || !selectedAttempt?.task_id) return;
+
+    try {
+      setQueuedMerge(
+        await attemptsApi.queueMerge(
+          projectId,
+          selectedAttempt.task_id,
+          selectedAttempt.id
+        )
+      );
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to queue merge');
+    }
+  };
+
+  const queuedMergeId = queuedMerge?.id;
+  const queuedMergePending =
+    queuedMerge !== null &&
+    queuedMerge.status !== 'merged' &&
+    queuedMerge.status !== 'failed';
+
+  // Follow the queued merge until it lands or fails
+  useEffect(() => {
+    if (!projectId || !queuedMergeId || !queuedMergePending) return;
+
+    const interval = setInterval(async () => {
+      try {
+        const entry = (await projectsApi.getMergeQueue(projectId)).find(
+          (queued) => queued.id === queuedMergeId
+        );
+        if (!entry) return;
+        setQueuedMerge(entry);
+        if (entry.status === 'failed') {
+          setError(entry.error || 'Queued merge failed');
+        }
+        if (entry.status === 'merged' || entry.status === 'failed') {
+          fetchBranchStatus();
+        }
+      } catch (err) {
+        console.error('Failed to fetch merge queue:', err);
+      }
+    }, 3000);
+    return () => clearInterval(interval);
+  }, [
+    projectId,
+    queuedMergeId,
+    queuedMergePending,
+    fetchBranchStatus,
+    setError,
+  ]);
+
+  useEffect(() => {
+    setQueuedMerge(null);
+  }, [selectedAttempt?.id]);
+
+  const hasConflicts = (branchStatus?.conflicted_files.length ?? 0) > 0;
+
   const handleRebaseClick = async () => {
     if (!projectId || !selectedAttempt?.id || !selectedAttempt?.task_id) return;
 
     try {
       setRebasing(true);
+      // Conflicting changes are rebased anyway and the agent resolves them
       await attemptsApi.rebase(
         projectId,
         selectedAttempt.task_id,
-        selectedAttempt.id
+        selectedAttempt.id,
+        undefined,
+        hasConflicts
       );
       // Refresh branch status after rebase
       fetchBranchStatus();
@@ -405,37 +551,12 @@ function CurrentAttempt({
 
     setIsApprovingPlan(true);
     try {
-      const response = await makeRequest(
-        `/api/projects/${projectId}/tasks/${task.id}/attempts/${selectedAttempt.id}/approve-plan`,
-        {
-          method: 'POST',
-          // No body needed - endpoint only handles approval now
-        }
-      );
-
-      if (response.ok) {
-        const result: ApiResponse<FollowUpResponse> = await response.json();
-        if (result.success && result.data) {
-          console.log('Plan approved successfully:', result.message);
-
-          // If a new task was created, navigate to it
-          if (result.data.created_new_attempt) {
-            const newTaskId = result.data.actual_attempt_id;
-            console.log('Navigating to new task:', newTaskId);
-            navigate(`/projects/${projectId}/tasks/${newTaskId}`);
-          } else {
-            // Otherwise, just refresh the current task data
-            fetchAttemptData(selectedAttempt.id, selectedAttempt.task_id);
-          }
-        } else {
-          setError(`Failed to approve plan: ${result.message}`);
-        }
-      } else {
-        setError('Failed to approve plan');
-      }
+      // The same attempt goes on to implement the plan in its existing session
+      await attemptsApi.approvePlan(projectId, task.id, selectedAttempt.id);
+      fetchAttemptData(selectedAttempt.id, selectedAttempt.task_id);
     } catch (error) {
       setError(
-        `Error approving plan: ${error instanceof Error ? error.message : 'Unknown error'}`
+        `Failed to approve plan: ${error instanceof Error ? error.message : 'Unknown error'}`
       );
     } finally {
       setIsApprovingPlan(false);
@@ -531,6 +652,14 @@ function CurrentAttempt({
               {branchStatus?.base_branch_name || selectedBranchDisplayName}
             </span>
           </div>
+          {branchStatus?.is_behind && !branchStatus.merged && (
+            <div className="text-xs text-orange-700 mt-1">
+              Behind by {branchStatus.commits_behind} commit
+              {branchStatus.commits_behind === 1 ? '' : 's'}
+              {hasConflicts &&
+                ` · conflicts in ${branchStatus.conflicted_files.join(', ')}`}
+            </div>
+          )}
         </div>
 
         <div>
@@ -671,9 +800,37 @@ function CurrentAttempt({
               </TooltipContent>
             </Tooltip>
           </TooltipProvider>
+          {runningDevServer && selectedAttempt && (
+            <Button variant="outline" size="sm" className="gap-1" asChild>
+              <a
+                href={attemptsApi.devServerPreviewUrl(
+                  projectId,
+                  selectedAttempt.task_id,
+                  selectedAttempt.id
+                )}
+                target="_blank"
+                rel="noopener noreferrer"
+              >
+                <ExternalLink className="h-3 w-3" />
+                Preview
+              </a>
+            </Button>
+          )}
         </div>
 
         <div className="flex items-center gap-2 flex-wrap">
+          {attemptGroup && (
+            <Button
+              variant="outline"
+              size="sm"
+              onClick={() => setShowCompareDialog(true)}
+              className="gap-1"
+              title="Compare the attempts that ran side by side"
+            >
+              <Columns className="h-3 w-3" />
+              {attemptGroup.winner_attempt_id ? 'Compare' : 'Compare & pick'}
+            </Button>
+          )}
           {taskAttempts.length > 1 && (
             <DropdownMenu>
               <TooltipProvider>
@@ -733,7 +890,11 @@ function CurrentAttempt({
                     <RefreshCw
                       className={`h-3 w-3 ${rebasing ? 'animate-spin' : ''}`}
                     />
-                    {rebasing ? 'Rebasing...' : `Rebase`}
+                    {rebasing
+                      ? 'Rebasing...'
+                      : hasConflicts
+                        ? 'Rebase & Resolve'
+                        : 'Rebase'}
                   </Button>
                 )}
               {isPlanTask ? (
@@ -749,8 +910,8 @@ function CurrentAttempt({
                   size="sm"
                   className="bg-green-600 hover:bg-green-700 disabled:bg-gray-400 gap-1"
                 >
-                  <GitBranchIcon className="h-3 w-3" />
-                  {isApprovingPlan ? 'Approving...' : 'Create Task'}
+                  <Check className="h-3 w-3" />
+                  {isApprovingPlan ? 'Approving...' : 'Approve Plan'}
                 </Button>
               ) : (
                 // Normal merge and PR buttons for regular tasks
@@ -774,19 +935,65 @@ function CurrentAttempt({
                           ? 'Creating...'
                           : 'Create PR'}
                     </Button>
-                    <Button
-                      onClick={handleMergeClick}
-                      disabled={
-                        merging ||
-                        Boolean(branchStatus.is_behind) ||
-                        isAttemptRunning
-                      }
-                      size="sm"
-                      className="bg-green-600 hover:bg-green-700 disabled:bg-gray-400 gap-1"
-                    >
-                      <GitBranchIcon className="h-3 w-3" />
-                      {merging ? 'Merging...' : 'Merge'}
-                    </Button>
+                    {reviewRequired ? (
+                      <>
+                        <Button
+                          onClick={handleApproveClick}
+                          disabled={
+                            reviewing ||
+                            Boolean(branchStatus.is_behind) ||
+                            isAttemptRunning
+                          }
+                          size="sm"
+                          className="bg-green-600 hover:bg-green-700 disabled:bg-gray-400 gap-1"
+                          title="Approve the changes and merge them"
+                        >
+                          <Check className="h-3 w-3" />
+                          {reviewing ? 'Reviewing...' : 'Approve'}
+                        </Button>
+                        <Button
+                          onClick={() => setShowRejectDialog(true)}
+                          disabled={reviewing || isAttemptRunning}
+                          variant="outline"
+                          size="sm"
+                          className="border-red-300 text-red-700 hover:bg-red-50 gap-1"
+                        >
+                          <X className="h-3 w-3" />
+                          Reject
+                        </Button>
+                      </>
+                    ) : (
+                      <>
+                        <Button
+                          onClick={handleMergeClick}
+                          disabled={
+                            merging ||
+                            Boolean(branchStatus.is_behind) ||
+                            isAttemptRunning
+                          }
+                          size="sm"
+                          className="bg-green-600 hover:bg-green-700 disabled:bg-gray-400 gap-1"
+                        >
+                          <GitBranchIcon className="h-3 w-3" />
+                          {merging ? 'Merging...' : 'Merge'}
+                        </Button>
+                        <Button
+                          onClick={handleQueueMergeClick}
+                          disabled={
+                            merging || queuedMergePending || isAttemptRunning
+                          }
+                          variant="outline"
+                          size="sm"
+                          className="gap-1"
+                          title="Rebase onto the latest base branch, run the merge check script and merge once the merges ahead of this one have landed"
+                        >
+                          <ListOrdered className="h-3 w-3" />
+                          {queuedMergePending && queuedMerge
+                            ? `${queuedMerge.status.charAt(0).toUpperCase()}${queuedMerge.status.slice(1)}...`
+                            : 'Queue Merge'}
+                        </Button>
+                      </>
+                    )}
                   </>
                 )
               )}
@@ -895,6 +1102,70 @@ function CurrentAttempt({
           </DialogFooter>
         </DialogContent>
       </Dialog>
+
+      {/* Reject Review Dialog */}
+      <Dialog open={showRejectDialog} onOpenChange={setShowRejectDialog}>
+        <DialogContent className="sm:max-w-md">
+          <DialogHeader>
+            <DialogTitle>Reject Changes</DialogTitle>
+            <DialogDescription>
+              Say what needs to change. A follow-up also sends the agent the
+              attempt's open review comments.
+            </DialogDescription>
+          </DialogHeader>
+
+          <div className="space-y-4">
+            <Textarea
+              placeholder="What's wrong with the changes"
+              value={rejectComment}
+              onChange={(e) => setRejectComment(e.target.value)}
+            />
+            <div className="flex items-center space-x-2">
+              <Checkbox
+                id="reject-follow-up"
+                checked={rejectFollowUp}
+                onCheckedChange={(checked: boolean) =>
+                  setRejectFollowUp(checked)
+                }
+              />
+              <label
+                htmlFor="reject-follow-up"
+                className="text-sm cursor-pointer"
+              >
+                Send to the agent as a follow-up
+              </label>
+            </div>
+          </div>
+
+          <DialogFooter>
+            <Button
+              variant="outline"
+              onClick={() => setShowRejectDialog(false)}
+              disabled={reviewing}
+            >
+              Cancel
+            </Button>
+            <Button
+              variant="destructive"
+              onClick={handleRejectConfirm}
+              disabled={reviewing}
+            >
+              {reviewing ? 'Rejecting...' : 'Reject'}
+            </Button>
+          </DialogFooter>
+        </DialogContent>
+      </Dialog>
+
+      {attemptGroup && (
+        <CompareAttemptsDialog
+          group={attemptGroup}
+          taskAttempts={taskAttempts}
+          availableExecutors={availableExecutors}
+          open={showCompareDialog}
+          onOpenChange={setShowCompareDialog}
+          onPicked={handleWinnerPicked}
+        />
+      )}
     </div>
   );
 }
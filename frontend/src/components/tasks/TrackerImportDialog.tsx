@@ -0,0 +1,182 @@
+import { useEffect, useState } from 'react';
+import { RefreshCw } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { Tabs, TabsList, TabsTrigger } from '@/components/ui/tabs';
+import { tasksApi } from '@/lib/api';
+import type { Tracker, TrackerImport, TrackerSyncResult } from 'shared/types';
+
+interface TrackerImportDialogProps {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+  projectId: string;
+  onImported: (result: TrackerSyncResult) => void;
+}
+
+const TRACKER_NAMES: Record<Tracker, string> = {
+  jira: 'Jira',
+  linear: 'Linear',
+};
+
+export function TrackerImportDialog({
+  open,
+  onOpenChange,
+  projectId,
+  onImported,
+}: TrackerImportDialogProps) {
+  const [tracker, setTracker] = useState<Tracker>('jira');
+  const [key, setKey] = useState('');
+  const [sources, setSources] = useState<TrackerImport[]>([]);
+  const [importing, setImporting] = useState(false);
+  const [syncing, setSyncing] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!open) return;
+    setMessage(null);
+    setError(null);
+    tasksApi
+      .getTrackerImports(projectId)
+      .then(setSources)
+      .catch((err) => setError(err.message));
+  }, [open, projectId]);
+
+  const finish = async (result: TrackerSyncResult) => {
+    setMessage(
+      `${result.created} tasks created, ${result.updated} tasks updated.`
+    );
+    onImported(result);
+    setSources(await tasksApi.getTrackerImports(projectId));
+  };
+
+  const handleImport = async () => {
+    setImporting(true);
+    setError(null);
+    setMessage(null);
+    try {
+      await finish(
+        await tasksApi.importFromTracker(projectId, {
+          tracker,
+          key: key.trim(),
+        })
+      );
+      setKey('');
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Import failed');
+    } finally {
+      setImporting(false);
+    }
+  };
+
+  const handleSync = async () => {
+    setSyncing(true);
+    setError(null);
+    setMessage(null);
+    try {
+      await finish(await tasksApi.syncTrackerImports(projectId));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Sync failed');
+    } finally {
+      setSyncing(false);
+    }
+  };
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent className="sm:max-w-[500px]">
+        <DialogHeader>
+          <DialogTitle>Import from Jira or Linear</DialogTitle>
+          <DialogDescription>
+            Open issues become tasks with their priority and labels. Importing
+            again, or re-syncing, only fetches issues updated since the last
+            import and carries title and description edits over to their
+            tasks.
+          </DialogDescription>
+        </DialogHeader>
+
+        <Tabs
+          value={tracker}
+          onValueChange={(value) => setTracker(value as Tracker)}
+        >
+          <TabsList className="grid w-full grid-cols-2">
+            <TabsTrigger value="jira">Jira</TabsTrigger>
+            <TabsTrigger value="linear">Linear</TabsTrigger>
+          </TabsList>
+        </Tabs>
+
+        <div className="space-y-2">
+          <Label htmlFor="tracker-key">
+            {tracker === 'jira' ? 'Project key' : 'Team key'}
+          </Label>
+          <Input
+            id="tracker-key"
+            placeholder="ENG"
+            value={key}
+            onChange={(e) => setKey(e.target.value)}
+          />
+          <p className="text-sm text-muted-foreground">
+            Add your {TRACKER_NAMES[tracker]} credentials in Settings first.
+          </p>
+        </div>
+
+        {sources.length > 0 && (
+          <div className="space-y-1">
+            <Label>Imported sources</Label>
+            {sources.map((source) => (
+              <p
+                key={`${source.tracker}-${source.source_key}`}
+                className="text-sm text-muted-foreground"
+              >
+                {`${TRACKER_NAMES[source.tracker]} ${source.source_key} · `}
+                {source.last_error
+                  ? `Failed: ${source.last_error}`
+                  : source.last_synced_at
+                    ? `Last synced ${new Date(source.last_synced_at).toLocaleString()}`
+                    : 'Not synced yet'}
+              </p>
+            ))}
+          </div>
+        )}
+
+        {error && (
+          <Alert variant="destructive">
+            <AlertDescription>{error}</AlertDescription>
+          </Alert>
+        )}
+        {message && (
+          <Alert>
+            <AlertDescription>{message}</AlertDescription>
+          </Alert>
+        )}
+
+        <DialogFooter>
+          {sources.length > 0 && (
+            <Button variant="outline" onClick={handleSync} disabled={syncing}>
+              <RefreshCw
+                className={`h-4 w-4 mr-2 ${syncing ? 'animate-spin' : ''}`}
+              />
+              Re-sync all
+            </Button>
+          )}
+          <Button
+            onClick={handleImport}
+            disabled={!key.trim() || importing || syncing}
+          >
+            {importing ? 'Importing...' : 'Import'}
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
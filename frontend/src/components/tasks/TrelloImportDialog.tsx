@@ -0,0 +1,160 @@
+import { useState } from 'react';
+import { Button } from '@/components/ui/button';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { Tabs, TabsContent, TabsList, TabsTrigger } from '@/components/ui/tabs';
+import { tasksApi } from '@/lib/api';
+import type { TrelloImportRequest, TrelloImportResult } from 'shared/types';
+
+interface TrelloImportDialogProps {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+  projectId: string;
+  onImported: (result: TrelloImportResult) => void;
+}
+
+export function TrelloImportDialog({
+  open,
+  onOpenChange,
+  projectId,
+  onImported,
+}: TrelloImportDialogProps) {
+  const [source, setSource] = useState<'export' | 'api'>('export');
+  const [file, setFile] = useState<File | null>(null);
+  const [boardId, setBoardId] = useState('');
+  const [apiKey, setApiKey] = useState('');
+  const [token, setToken] = useState('');
+  const [importing, setImporting] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const canImport =
+    source === 'export'
+      ? file !== null
+      : boardId.trim() !== '' && apiKey.trim() !== '' && token.trim() !== '';
+
+  const handleImport = async () => {
+    setImporting(true);
+    setError(null);
+    try {
+      let request: TrelloImportRequest;
+      if (source === 'export') {
+        let board: unknown;
+        try {
+          board = JSON.parse(await file!.text());
+        } catch {
+          throw new Error('The selected file is not valid JSON');
+        }
+        request = { source: 'export', board };
+      } else {
+        request = {
+          source: 'api',
+          board_id: boardId.trim(),
+          key: apiKey.trim(),
+          token: token.trim(),
+        };
+      }
+      const result = await tasksApi.importTrello(projectId, request);
+      onImported(result);
+      onOpenChange(false);
+      setFile(null);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Import failed');
+    } finally {
+      setImporting(false);
+    }
+  };
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent className="sm:max-w-[500px]">
+        <DialogHeader>
+          <DialogTitle>Import from Trello</DialogTitle>
+          <DialogDescription>
+            Lists become columns by name, cards become tasks with their labels
+            in the description, and checklist items become tasks of their own.
+            Archived lists and cards are skipped.
+          </DialogDescription>
+        </DialogHeader>
+
+        <Tabs
+          value={source}
+          onValueChange={(value) => setSource(value as 'export' | 'api')}
+        >
+          <TabsList className="grid w-full grid-cols-2">
+            <TabsTrigger value="export">JSON export</TabsTrigger>
+            <TabsTrigger value="api">Trello API</TabsTrigger>
+          </TabsList>
+          <TabsContent value="export" className="space-y-2 pt-2">
+            <Label htmlFor="trello-file">Board export</Label>
+            <Input
+              id="trello-file"
+              type="file"
+              accept=".json,application/json"
+              onChange={(e) => setFile(e.target.files?.[0] ?? null)}
+            />
+            <p className="text-sm text-muted-foreground">
+              In Trello, open the board menu and choose Print, export and share
+              → Export as JSON.
+            </p>
+          </TabsContent>
+          <TabsContent value="api" className="space-y-3 pt-2">
+            <div className="space-y-2">
+              <Label htmlFor="trello-board">Board ID</Label>
+              <Input
+                id="trello-board"
+                placeholder="The part after /b/ in the board URL"
+                value={boardId}
+                onChange={(e) => setBoardId(e.target.value)}
+              />
+            </div>
+            <div className="space-y-2">
+              <Label htmlFor="trello-key">API key</Label>
+              <Input
+                id="trello-key"
+                value={apiKey}
+                onChange={(e) => setApiKey(e.target.value)}
+              />
+            </div>
+            <div className="space-y-2">
+              <Label htmlFor="trello-token">Token</Label>
+              <Input
+                id="trello-token"
+                type="password"
+                value={token}
+                onChange={(e) => setToken(e.target.value)}
+              />
+            </div>
+            <p className="text-sm text-muted-foreground">
+              Create a key and token at trello.com/app-key. They are only used
+              for this import and are not saved.
+            </p>
+          </TabsContent>
+        </Tabs>
+
+        {error && (
+          <Alert variant="destructive">
+            <AlertDescription>{error}</AlertDescription>
+          </Alert>
+        )}
+
+        <DialogFooter>
+          <Button variant="outline" onClick={() => onOpenChange(false)}>
+            Cancel
+          </Button>
+          <Button onClick={handleImport} disabled={!canImport || importing}>
+            {importing ? 'Importing...' : 'Import'}
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
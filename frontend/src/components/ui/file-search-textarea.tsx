@@ -141,7 +141,8 @@ export function FileSearchTextarea({
 
     const beforeAt = value.slice(0, atSymbolPosition);
     const afterQuery = value.slice(atSymbolPosition + 1 + searchQuery.length);
-    const newValue = beforeAt + file.path + afterQuery;
+    // The @ stays so the file's contents are given to the agent with the task
+    const newValue = beforeAt + '@' + file.path + afterQuery;
 
     onChange(newValue);
     setShowDropdown(false);
@@ -151,7 +152,7 @@ export function FileSearchTextarea({
     // Focus back to textarea
     setTimeout(() => {
       if (textareaRef.current) {
-        const newCursorPos = atSymbolPosition + file.path.length;
+        const newCursorPos = atSymbolPosition + 1 + file.path.length;
         textareaRef.current.focus();
         textareaRef.current.setSelectionRange(newCursorPos, newCursorPos);
       }
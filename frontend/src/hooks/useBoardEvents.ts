@@ -0,0 +1,54 @@
+import { useEffect, useRef } from 'react';
+import { BoardEvent } from 'shared/types';
+
+const BOARD_EVENT_TYPES: BoardEvent['type'][] = [
+  'task_created',
+  'task_updated',
+  'task_status_changed',
+  'task_deleted',
+  'execution_started',
+  'execution_finished',
+  'process_running',
+  'process_stopped',
+  'queue_changed',
+  'resync',
+];
+
+/**
+ * Subscribe to live board updates for a project. The browser reconnects the stream on its
+ * own if it drops; `onEvent` gets a `resync` whenever updates may have been missed.
+ */
+export function useBoardEvents(
+  projectId: string | undefined,
+  onEvent: (event: BoardEvent) => void
+) {
+  const onEventRef = useRef(onEvent);
+  onEventRef.current = onEvent;
+
+  useEffect(() => {
+    if (!projectId) return;
+
+    const source = new EventSource(
+      `/api/events?project_id=${encodeURIComponent(projectId)}`
+    );
+    const handle = (message: MessageEvent) => {
+      try {
+        onEventRef.current(JSON.parse(message.data) as BoardEvent);
+      } catch (err) {
+        console.error('Failed to parse board event:', err);
+      }
+    };
+    // The stream may have missed updates while it was reconnecting
+    const handleOpen = () => onEventRef.current({ type: 'resync' });
+
+    BOARD_EVENT_TYPES.forEach((type) => source.addEventListener(type, handle));
+    source.addEventListener('open', handleOpen);
+    return () => {
+      BOARD_EVENT_TYPES.forEach((type) =>
+        source.removeEventListener(type, handle)
+      );
+      source.removeEventListener('open', handleOpen);
+      source.close();
+    };
+  }, [projectId]);
+}
@@ -146,6 +146,7 @@ const useNormalizedConversation = ({
                   executor_type: 'unknown',
                   prompt: null,
                   summary: null,
+                  diff_summary: null,
                 };
 
                 try {
@@ -1,33 +1,190 @@
 // Import all necessary types from shared types
 import {
+  AcceptProjectInvite,
+  ApiToken,
+  ApiTokenUse,
+  ApprovalDecision,
+  ApproveAttempt,
+  ArtifactCleanup,
+  AttemptRejection,
+  AttemptReview,
+  AttemptRollback,
+  AuditVerification,
+  AuthStatus,
+  BenchmarkReport,
+  BenchmarkSuite,
+  BoardColumn,
+  BoardColumnInput,
   BranchStatus,
+  BulkTaskAction,
+  BulkTaskResult,
+  Changelog,
+  ChangePassword,
+  ChangelogQuery,
   Config,
   ConfigConstants,
+  ConversationExportFormat,
+  CreateApiToken,
+  CreatedApiToken,
+  CreatedProjectInvite,
+  CreateProjectInvite,
   CreateFollowUpAttempt,
+  CreateParallelAttempts,
   CreateProject,
   CreateProjectFromGitHub,
+  CreateScopedFollowUp,
+  CreateSubtask,
+  CreateRelease,
   CreateTask,
   CreateTaskAndStart,
   CreateTaskAttempt,
+  CreateTaskPullRequest,
   CreateTaskTemplate,
+  CreateUser,
+  CreateReviewComment,
+  CreatedTask,
+  CreatedTaskShareLink,
+  CreateTaskShareLink,
+  CreateEntryBookmark,
+  CustomField,
+  DecomposeTask,
+  DevServerPort,
   DeviceStartResponse,
   DirectoryEntry,
+  EffectiveProjectConfig,
   type EditorType,
+  EntryBookmark,
+  EnvironmentReport,
+  Event as ActivityEvent,
+  ExportEntryBookmarks,
+  ExportedEntryBookmarks,
   ExecutionProcess,
+  ExecutionResources,
+  ExecutionAttention,
   ExecutionProcessSummary,
+  ExecutionSnapshot,
+  ExecutionToolUsage,
+  ExecutorProfile,
+  FileHunks,
+  FileMentionProblem,
+  FollowUpDraft,
   GitBranch,
+  GitHubIssueLink,
+  GitHubIssueSyncResult,
+  GitHubProjectLink,
+  GitHubProjectLinkResponse,
+  GitHubProjectSyncResult,
+  InstantiateTaskTemplate,
+  InviteDetails,
+  LinkedRepoDiff,
+  LoginRequest,
+  LoginResponse,
+  LogStream,
+  Mention,
+  MergeQueueEntry,
+  NormalizedConversation,
+  NotificationDelivery,
+  NotificationWebhook,
+  NotionLink,
+  NotionLinkResponse,
+  NotionSyncResult,
+  PlanReview,
+  PluginInfo,
+  QueueEntry,
+  PrDescription,
   ProcessLogsResponse,
+  PendingApproval,
+  PickAttemptGroupWinner,
   Project,
+  ProjectArchive,
+  ProjectContainer,
+  ProjectGitHost,
+  ProjectMcpServer,
+  ProjectSandbox,
+  ProjectSecurity,
+  ProjectShellEnvironment,
+  ProjectWorker,
+  GuardrailStatus,
+  ProjectImportResult,
+  ProjectInvite,
+  ProjectMember,
+  ProjectMembership,
+  ProjectAnalytics,
+  ProjectUsage,
   ProjectWithBranch,
+  RecoveryCodes,
+  RejectAttempt,
+  ReorderQueue,
+  RevertExecution,
+  RollbackAttempt,
+  Release,
+  ReleaseWithTasks,
+  RepoAnalysisSummary,
+  ReviewComment,
+  RunEstimate,
+  SaveFollowUpDraft,
+  ScriptHook,
+  SearchHit,
+  SearchQuery,
+  SendExecutionInput,
+  SetProjectMember,
+  SimilarTask,
+  SimilarTasksQuery,
+  SetTaskCustomField,
+  SharedTask,
+  ShelvedTask,
+  StartBenchmarkRun,
   Task,
+  TaskAttachment,
   TaskAttempt,
   TaskAttemptState,
+  TaskBoardQuery,
+  TaskChecklist,
+  TaskDependencies,
+  TaskDependencyGraph,
+  TaskLabels,
+  TaskLinkedProject,
+  TaskOriginalText,
+  TaskPrDescription,
+  TaskPriority,
+  TaskSchedule,
+  TaskScheduleRun,
+  TaskShareLink,
+  TaskSubtasks,
   TaskTemplate,
   TaskWithAttemptStatus,
+  TrackerImport,
+  TrackerImportRequest,
+  TrackerSyncResult,
+  TrelloImportRequest,
+  TrelloImportResult,
+  TwoFactorEnrollment,
   UpdateProject,
+  UpdateReviewComment,
   UpdateTask,
+  UpdateTaskChecklistItem,
   UpdateTaskTemplate,
+  UpsertCustomField,
+  UpsertExecutorProfile,
+  UpdateUser,
+  UpsertBenchmarkSuite,
+  UpsertGitHubIssueLink,
+  UpsertGitHubProjectLink,
+  UpsertNotificationWebhook,
+  UpsertNotionLink,
+  UpsertProjectContainer,
+  UpsertProjectGitHost,
+  UpsertProjectMcpServer,
+  UpsertScriptHook,
+  UpsertProjectSandbox,
+  UpsertProjectSecurity,
+  UpsertProjectShellEnvironment,
+  UpsertProjectWorker,
+  UpsertProjectGuardrails,
+  UpsertTaskSchedule,
+  User,
   WorktreeDiff,
+  WorktreeUsage,
 } from 'shared/types';
 
 export const makeRequest = async (url: string, options: RequestInit = {}) => {
@@ -170,6 +327,20 @@ export const projectsApi = {
     return handleApiResponse<void>(response);
   },
 
+  cleanArtifacts: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<ArtifactCleanup> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/clean-artifacts`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<ArtifactCleanup>(response);
+  },
+
   openEditor: async (id: string): Promise<void> => {
     const response = await makeRequest(`/api/projects/${id}/open-editor`, {
       method: 'POST',
@@ -192,12 +363,99 @@ export const projectsApi = {
     );
     return handleApiResponse<FileSearchResult[]>(response);
   },
+
+  checkFileMentions: async (
+    id: string,
+    text: string
+  ): Promise<FileMentionProblem[]> => {
+    const response = await makeRequest(`/api/projects/${id}/file-mentions`, {
+      method: 'POST',
+      body: JSON.stringify({ text }),
+    });
+    return handleApiResponse<FileMentionProblem[]>(response);
+  },
+
+  getChangelog: async (
+    id: string,
+    query: ChangelogQuery
+  ): Promise<Changelog> => {
+    const params = new URLSearchParams({ format: query.format });
+    if (query.from) params.set('from', query.from);
+    if (query.to) params.set('to', query.to);
+    if (query.since_tag) params.set('since_tag', query.since_tag);
+    if (query.version) params.set('version', query.version);
+    const response = await makeRequest(
+      `/api/projects/${id}/changelog?${params.toString()}`
+    );
+    return handleApiResponse<Changelog>(response);
+  },
+
+  getWorktreeUsage: async (id: string): Promise<WorktreeUsage[]> => {
+    const response = await makeRequest(`/api/projects/${id}/worktrees`);
+    return handleApiResponse<WorktreeUsage[]>(response);
+  },
+
+  pruneWorktrees: async (id: string): Promise<string[]> => {
+    const response = await makeRequest(`/api/projects/${id}/worktrees/prune`, {
+      method: 'POST',
+    });
+    return handleApiResponse<string[]>(response);
+  },
+
+  getAnalysis: async (id: string): Promise<RepoAnalysisSummary> => {
+    const response = await makeRequest(`/api/projects/${id}/analysis`);
+    return handleApiResponse<RepoAnalysisSummary>(response);
+  },
+
+  getEffectiveConfig: async (id: string): Promise<EffectiveProjectConfig> => {
+    const response = await makeRequest(`/api/projects/${id}/effective-config`);
+    return handleApiResponse<EffectiveProjectConfig>(response);
+  },
+
+  getMergeQueue: async (id: string): Promise<MergeQueueEntry[]> => {
+    const response = await makeRequest(`/api/projects/${id}/merge-queue`);
+    return handleApiResponse<MergeQueueEntry[]>(response);
+  },
+
+  getUsage: async (id: string): Promise<ProjectUsage> => {
+    const response = await makeRequest(`/api/projects/${id}/usage`);
+    return handleApiResponse<ProjectUsage>(response);
+  },
+
+  getAnalytics: async (id: string): Promise<ProjectAnalytics> => {
+    const response = await makeRequest(`/api/projects/${id}/analytics`);
+    return handleApiResponse<ProjectAnalytics>(response);
+  },
+  exportUrl: (id: string, includeLogs = false) =>
+    `/api/projects/${id}/export${includeLogs ? '?include_logs=true' : ''}`,
+
+  importArchive: async (
+    id: string,
+    archive: ProjectArchive
+  ): Promise<ProjectImportResult> => {
+    const response = await makeRequest(`/api/projects/${id}/import`, {
+      method: 'POST',
+      body: JSON.stringify(archive),
+    });
+    return handleApiResponse<ProjectImportResult>(response);
+  },
 };
 
 // Task Management APIs
 export const tasksApi = {
-  getAll: async (projectId: string): Promise<TaskWithAttemptStatus[]> => {
-    const response = await makeRequest(`/api/projects/${projectId}/tasks`);
+  getAll: async (
+    projectId: string,
+    query: Partial<TaskBoardQuery> = {}
+  ): Promise<TaskWithAttemptStatus[]> => {
+    const params = new URLSearchParams();
+    Object.entries(query).forEach(([key, value]) => {
+      if (value !== null && value !== undefined && value !== '') {
+        params.set(key, String(value));
+      }
+    });
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks?${params.toString()}`
+    );
     return handleApiResponse<TaskWithAttemptStatus[]>(response);
   },
 
@@ -208,12 +466,22 @@ export const tasksApi = {
     return handleApiResponse<Task>(response);
   },
 
-  create: async (projectId: string, data: CreateTask): Promise<Task> => {
+  conversationsExportUrl: (
+    projectId: string,
+    taskId: string,
+    format: ConversationExportFormat = 'markdown'
+  ) =>
+    `/api/projects/${projectId}/tasks/${taskId}/conversations/export?format=${format}`,
+
+  create: async (
+    projectId: string,
+    data: CreateTask
+  ): Promise<CreatedTask> => {
     const response = await makeRequest(`/api/projects/${projectId}/tasks`, {
       method: 'POST',
       body: JSON.stringify(data),
     });
-    return handleApiResponse<Task>(response);
+    return handleApiResponse<CreatedTask>(response);
   },
 
   createAndStart: async (
@@ -230,6 +498,63 @@ export const tasksApi = {
     return handleApiResponse<TaskWithAttemptStatus>(response);
   },
 
+  findSimilar: async (
+    projectId: string,
+    data: SimilarTasksQuery
+  ): Promise<SimilarTask[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/similar`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<SimilarTask[]>(response);
+  },
+
+  importTrello: async (
+    projectId: string,
+    data: TrelloImportRequest
+  ): Promise<TrelloImportResult> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/import/trello`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<TrelloImportResult>(response);
+  },
+
+  getTrackerImports: async (projectId: string): Promise<TrackerImport[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/import/tracker`
+    );
+    return handleApiResponse<TrackerImport[]>(response);
+  },
+
+  importFromTracker: async (
+    projectId: string,
+    data: TrackerImportRequest
+  ): Promise<TrackerSyncResult> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/import/tracker`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<TrackerSyncResult>(response);
+  },
+
+  syncTrackerImports: async (projectId: string): Promise<TrackerSyncResult> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/import/tracker/sync`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<TrackerSyncResult>(response);
+  },
+
   update: async (
     projectId: string,
     taskId: string,
@@ -245,9 +570,13 @@ export const tasksApi = {
     return handleApiResponse<Task>(response);
   },
 
-  delete: async (projectId: string, taskId: string): Promise<void> => {
+  delete: async (
+    projectId: string,
+    taskId: string,
+    permanent = false
+  ): Promise<void> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/tasks/${taskId}`,
+      `/api/projects/${projectId}/tasks/${taskId}${permanent ? '?permanent=true' : ''}`,
       {
         method: 'DELETE',
       }
@@ -255,249 +584,1199 @@ export const tasksApi = {
     return handleApiResponse<void>(response);
   },
 
-  getChildren: async (
-    projectId: string,
-    taskId: string,
-    attemptId: string
-  ): Promise<Task[]> => {
+  archive: async (projectId: string, taskId: string): Promise<void> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/children`
+      `/api/projects/${projectId}/tasks/${taskId}/archive`,
+      {
+        method: 'POST',
+      }
     );
-    return handleApiResponse<Task[]>(response);
+    return handleApiResponse<void>(response);
   },
-};
 
-// Task Attempts APIs
-export const attemptsApi = {
-  getAll: async (projectId: string, taskId: string): Promise<TaskAttempt[]> => {
+  unarchive: async (projectId: string, taskId: string): Promise<void> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/tasks/${taskId}/attempts`
+      `/api/projects/${projectId}/tasks/${taskId}/archive`,
+      {
+        method: 'DELETE',
+      }
     );
-    return handleApiResponse<TaskAttempt[]>(response);
+    return handleApiResponse<void>(response);
   },
 
-  create: async (
-    projectId: string,
-    taskId: string,
-    data: CreateTaskAttempt
-  ): Promise<TaskAttempt> => {
+  restore: async (projectId: string, taskId: string): Promise<void> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/tasks/${taskId}/attempts`,
+      `/api/projects/${projectId}/tasks/${taskId}/restore`,
       {
         method: 'POST',
-        body: JSON.stringify(data),
       }
     );
-    return handleApiResponse<TaskAttempt>(response);
+    return handleApiResponse<void>(response);
   },
 
-  getState: async (
-    projectId: string,
-    taskId: string,
-    attemptId: string
-  ): Promise<TaskAttemptState> => {
+  getArchived: async (projectId: string): Promise<ShelvedTask[]> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}`
+      `/api/projects/${projectId}/tasks/archived`
     );
-    return handleApiResponse<TaskAttemptState>(response);
+    return handleApiResponse<ShelvedTask[]>(response);
   },
 
-  stop: async (
+  getTrash: async (projectId: string): Promise<ShelvedTask[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/trash`
+    );
+    return handleApiResponse<ShelvedTask[]>(response);
+  },
+
+  bulk: async (
     projectId: string,
-    taskId: string,
-    attemptId: string
-  ): Promise<void> => {
+    action: BulkTaskAction
+  ): Promise<BulkTaskResult> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/stop`,
+      `/api/projects/${projectId}/tasks/bulk`,
       {
         method: 'POST',
+        body: JSON.stringify(action),
       }
     );
-    return handleApiResponse<void>(response);
+    return handleApiResponse<BulkTaskResult>(response);
   },
 
-  followUp: async (
+  summarize: async (projectId: string, taskId: string): Promise<Task> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/summarize`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<Task>(response);
+  },
+
+  createPullRequest: async (
     projectId: string,
     taskId: string,
-    attemptId: string,
-    data: CreateFollowUpAttempt
-  ): Promise<void> => {
+    data: CreateTaskPullRequest = { base_branch: null }
+  ): Promise<string> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/follow-up`,
+      `/api/projects/${projectId}/tasks/${taskId}/create-pr`,
       {
         method: 'POST',
         body: JSON.stringify(data),
       }
     );
-    return handleApiResponse<void>(response);
+    return handleApiResponse<string>(response);
   },
 
-  getDiff: async (
+  getLabels: async (
     projectId: string,
-    taskId: string,
-    attemptId: string
-  ): Promise<WorktreeDiff> => {
+    taskId: string
+  ): Promise<TaskLabels> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/diff`
+      `/api/projects/${projectId}/tasks/${taskId}/labels`
     );
-    return handleApiResponse<WorktreeDiff>(response);
+    return handleApiResponse<TaskLabels>(response);
   },
 
-  deleteFile: async (
+  addLabel: async (
     projectId: string,
     taskId: string,
-    attemptId: string,
-    fileToDelete: string
-  ): Promise<void> => {
+    name: string
+  ): Promise<TaskLabels> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/delete-filefile_path=${encodeURIComponent(
-        fileToDelete
-      )}`,
+      `/api/projects/${projectId}/tasks/${taskId}/labels`,
       {
         method: 'POST',
+        body: JSON.stringify({ name }),
       }
     );
-    return handleApiResponse<void>(response);
+    return handleApiResponse<TaskLabels>(response);
   },
 
-  openEditor: async (
+  removeLabel: async (
     projectId: string,
     taskId: string,
-    attemptId: string,
-    editorType?: EditorType
-  ): Promise<void> => {
+    name: string
+  ): Promise<TaskLabels> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/open-editor`,
+      `/api/projects/${projectId}/tasks/${taskId}/labels`,
       {
-        method: 'POST',
-        body: JSON.stringify(editorType ? { editor_type: editorType } : null),
+        method: 'DELETE',
+        body: JSON.stringify({ name }),
       }
     );
-    return handleApiResponse<void>(response);
+    return handleApiResponse<TaskLabels>(response);
   },
 
-  getBranchStatus: async (
+  getChecklist: async (
     projectId: string,
-    taskId: string,
-    attemptId: string
-  ): Promise<BranchStatus> => {
+    taskId: string
+  ): Promise<TaskChecklist> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/branch-status`
+      `/api/projects/${projectId}/tasks/${taskId}/checklist`
     );
-    return handleApiResponse<BranchStatus>(response);
+    return handleApiResponse<TaskChecklist>(response);
   },
 
-  merge: async (
+  updateChecklistItem: async (
     projectId: string,
     taskId: string,
-    attemptId: string
-  ): Promise<void> => {
+    data: UpdateTaskChecklistItem
+  ): Promise<TaskChecklist> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/merge`,
+      `/api/projects/${projectId}/tasks/${taskId}/checklist`,
       {
-        method: 'POST',
+        method: 'PUT',
+        body: JSON.stringify(data),
       }
     );
-    return handleApiResponse<void>(response);
+    return handleApiResponse<TaskChecklist>(response);
   },
 
-  rebase: async (
+  getSubtasks: async (
     projectId: string,
-    taskId: string,
-    attemptId: string,
-    newBaseBranch?: string
-  ): Promise<void> => {
+    taskId: string
+  ): Promise<TaskSubtasks> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/rebase`,
-      {
-        method: 'POST',
-        headers: {
-          'Content-Type': 'application/json',
-        },
-        body: JSON.stringify({
-          new_base_branch: newBaseBranch || null,
-        }),
-      }
+      `/api/projects/${projectId}/tasks/${taskId}/subtasks`
     );
-    return handleApiResponse<void>(response);
+    return handleApiResponse<TaskSubtasks>(response);
   },
 
-  createPR: async (
+  createSubtask: async (
     projectId: string,
     taskId: string,
-    attemptId: string,
-    data: {
-      title: string;
-      body: string | null;
-      base_branch: string | null;
-    }
-  ): Promise<string> => {
+    data: CreateSubtask
+  ): Promise<Task> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/create-pr`,
+      `/api/projects/${projectId}/tasks/${taskId}/subtasks`,
       {
         method: 'POST',
         body: JSON.stringify(data),
       }
     );
-    return handleApiResponse<string>(response);
+    return handleApiResponse<Task>(response);
   },
 
-  startDevServer: async (
+  decompose: async (
     projectId: string,
     taskId: string,
-    attemptId: string
-  ): Promise<void> => {
+    data: DecomposeTask
+  ): Promise<TaskAttempt> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/start-dev-server`,
+      `/api/projects/${projectId}/tasks/${taskId}/decompose`,
       {
         method: 'POST',
+        body: JSON.stringify(data),
       }
     );
-    return handleApiResponse<void>(response);
+    return handleApiResponse<TaskAttempt>(response);
   },
 
-  getExecutionProcesses: async (
+  getSchedule: async (
     projectId: string,
-    taskId: string,
-    attemptId: string
-  ): Promise<ExecutionProcessSummary[]> => {
+    taskId: string
+  ): Promise<TaskSchedule | null> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/execution-processes`
+      `/api/projects/${projectId}/tasks/${taskId}/schedule`
     );
-    return handleApiResponse<ExecutionProcessSummary[]>(response);
+    return handleApiResponse<TaskSchedule | null>(response);
   },
 
-  stopExecutionProcess: async (
+  updateSchedule: async (
     projectId: string,
     taskId: string,
-    attemptId: string,
-    processId: string
-  ): Promise<void> => {
+    data: UpsertTaskSchedule
+  ): Promise<TaskSchedule> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/execution-processes/${processId}/stop`,
+      `/api/projects/${projectId}/tasks/${taskId}/schedule`,
       {
-        method: 'POST',
+        method: 'PUT',
+        body: JSON.stringify(data),
       }
     );
-    return handleApiResponse<void>(response);
+    return handleApiResponse<TaskSchedule>(response);
   },
 
-  getDetails: async (attemptId: string): Promise<TaskAttempt> => {
-    const response = await makeRequest(`/api/attempts/${attemptId}/details`);
-    return handleApiResponse<TaskAttempt>(response);
+  deleteSchedule: async (projectId: string, taskId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/schedule`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
   },
 
-  getAllLogs: async (
+  getScheduleRuns: async (
     projectId: string,
-    taskId: string,
-    attemptId: string
-  ): Promise<ProcessLogsResponse[]> => {
+    taskId: string
+  ): Promise<TaskScheduleRun[]> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/logs`
+      `/api/projects/${projectId}/tasks/${taskId}/schedule/runs`
     );
-    return handleApiResponse(response);
+    return handleApiResponse<TaskScheduleRun[]>(response);
   },
-};
+
+  getDependencies: async (
+    projectId: string,
+    taskId: string
+  ): Promise<TaskDependencies> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/dependencies`
+    );
+    return handleApiResponse<TaskDependencies>(response);
+  },
+
+  addDependency: async (
+    projectId: string,
+    taskId: string,
+    blockedByTaskId: string
+  ): Promise<TaskDependencies> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/dependencies`,
+      {
+        method: 'POST',
+        body: JSON.stringify({ blocked_by_task_id: blockedByTaskId }),
+      }
+    );
+    return handleApiResponse<TaskDependencies>(response);
+  },
+
+  removeDependency: async (
+    projectId: string,
+    taskId: string,
+    blockedByTaskId: string
+  ): Promise<TaskDependencies> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/dependencies`,
+      {
+        method: 'DELETE',
+        body: JSON.stringify({ blocked_by_task_id: blockedByTaskId }),
+      }
+    );
+    return handleApiResponse<TaskDependencies>(response);
+  },
+
+  setPriority: async (
+    projectId: string,
+    taskId: string,
+    priority: TaskPriority | null
+  ): Promise<Task> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/priority`,
+      {
+        method: 'PUT',
+        body: JSON.stringify({ priority }),
+      }
+    );
+    return handleApiResponse<Task>(response);
+  },
+
+  moveToColumn: async (
+    projectId: string,
+    taskId: string,
+    columnId: string
+  ): Promise<Task> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/column`,
+      {
+        method: 'PUT',
+        body: JSON.stringify({ column_id: columnId }),
+      }
+    );
+    return handleApiResponse<Task>(response);
+  },
+
+  setTimeout: async (
+    projectId: string,
+    taskId: string,
+    timeoutMinutes: number | null
+  ): Promise<Task> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/timeout`,
+      {
+        method: 'PUT',
+        body: JSON.stringify({ timeout_minutes: timeoutMinutes }),
+      }
+    );
+    return handleApiResponse<Task>(response);
+  },
+
+  // The executor the task's attempts start with unless they pick one
+  getExecutor: async (
+    projectId: string,
+    taskId: string
+  ): Promise<string | null> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/executor`
+    );
+    return handleApiResponse<string | null>(response);
+  },
+
+  setExecutor: async (
+    projectId: string,
+    taskId: string,
+    executor: string | null
+  ): Promise<string | null> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/executor`,
+      {
+        method: 'PUT',
+        body: JSON.stringify({ executor }),
+      }
+    );
+    return handleApiResponse<string | null>(response);
+  },
+
+  // The other projects the task spans, checked out next to its own
+  getLinkedProjects: async (
+    projectId: string,
+    taskId: string
+  ): Promise<TaskLinkedProject[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/linked-projects`
+    );
+    return handleApiResponse<TaskLinkedProject[]>(response);
+  },
+
+  setLinkedProjects: async (
+    projectId: string,
+    taskId: string,
+    projectIds: string[]
+  ): Promise<TaskLinkedProject[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/linked-projects`,
+      {
+        method: 'PUT',
+        body: JSON.stringify({ project_ids: projectIds }),
+      }
+    );
+    return handleApiResponse<TaskLinkedProject[]>(response);
+  },
+
+  getDependencyGraph: async (
+    projectId: string
+  ): Promise<TaskDependencyGraph> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/dependency-graph`
+    );
+    return handleApiResponse<TaskDependencyGraph>(response);
+  },
+
+  getAttachments: async (
+    projectId: string,
+    taskId: string
+  ): Promise<TaskAttachment[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attachments`
+    );
+    return handleApiResponse<TaskAttachment[]>(response);
+  },
+
+  uploadAttachment: async (
+    projectId: string,
+    taskId: string,
+    file: File
+  ): Promise<TaskAttachment> => {
+    const params = new URLSearchParams({ file_name: file.name });
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attachments?${params}`,
+      {
+        method: 'POST',
+        headers: {
+          'Content-Type': file.type || 'application/octet-stream',
+        },
+        body: file,
+      }
+    );
+    return handleApiResponse<TaskAttachment>(response);
+  },
+
+  deleteAttachment: async (
+    projectId: string,
+    taskId: string,
+    attachmentId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attachments/${attachmentId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  attachmentUrl: (projectId: string, taskId: string, attachmentId: string) =>
+    `/api/projects/${projectId}/tasks/${taskId}/attachments/${attachmentId}/content`,
+
+  dismissLabelSuggestion: async (
+    projectId: string,
+    taskId: string,
+    name: string
+  ): Promise<TaskLabels> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/label-suggestions`,
+      {
+        method: 'DELETE',
+        body: JSON.stringify({ name }),
+      }
+    );
+    return handleApiResponse<TaskLabels>(response);
+  },
+
+  getOriginalText: async (
+    projectId: string,
+    taskId: string
+  ): Promise<TaskOriginalText | null> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/original`
+    );
+    return handleApiResponse<TaskOriginalText | null>(response);
+  },
+
+  generatePrDescription: async (
+    projectId: string,
+    taskId: string,
+    attemptId?: string
+  ): Promise<TaskPrDescription> => {
+    const query = attemptId ? `?attempt_id=${attemptId}` : '';
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/pr-description${query}`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<TaskPrDescription>(response);
+  },
+
+  deletePrDescription: async (
+    projectId: string,
+    taskId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/pr-description`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  estimate: async (projectId: string, taskId: string): Promise<RunEstimate> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/estimate`
+    );
+    return handleApiResponse<RunEstimate>(response);
+  },
+
+  getChildren: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<Task[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/children`
+    );
+    return handleApiResponse<Task[]>(response);
+  },
+};
+
+// Custom field APIs
+export const customFieldsApi = {
+  list: async (projectId: string): Promise<CustomField[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/custom-fields`
+    );
+    return handleApiResponse<CustomField[]>(response);
+  },
+
+  upsert: async (
+    projectId: string,
+    data: UpsertCustomField
+  ): Promise<CustomField> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/custom-fields`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<CustomField>(response);
+  },
+
+  delete: async (projectId: string, name: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/custom-fields?name=${encodeURIComponent(name)}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getTaskValues: async (
+    projectId: string,
+    taskId: string
+  ): Promise<Record<string, string>> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/custom-fields`
+    );
+    return handleApiResponse<Record<string, string>>(response);
+  },
+
+  setTaskValue: async (
+    projectId: string,
+    taskId: string,
+    data: SetTaskCustomField
+  ): Promise<Record<string, string>> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/custom-fields`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<Record<string, string>>(response);
+  },
+};
+
+// Board column APIs
+export const boardColumnsApi = {
+  list: async (projectId: string): Promise<BoardColumn[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/board/columns`
+    );
+    return handleApiResponse<BoardColumn[]>(response);
+  },
+
+  update: async (
+    projectId: string,
+    columns: BoardColumnInput[]
+  ): Promise<BoardColumn[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/board/columns`,
+      {
+        method: 'PUT',
+        // BigInt doesn't serialize to JSON
+        body: JSON.stringify({ columns }, (_key, value) =>
+          typeof value === 'bigint' ? Number(value) : value
+        ),
+      }
+    );
+    return handleApiResponse<BoardColumn[]>(response);
+  },
+};
+
+// Task Attempts APIs
+export const attemptsApi = {
+  getAll: async (projectId: string, taskId: string): Promise<TaskAttempt[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts`
+    );
+    return handleApiResponse<TaskAttempt[]>(response);
+  },
+
+  create: async (
+    projectId: string,
+    taskId: string,
+    data: CreateTaskAttempt
+  ): Promise<TaskAttempt> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<TaskAttempt>(response);
+  },
+
+  createParallel: async (
+    projectId: string,
+    taskId: string,
+    data: CreateParallelAttempts
+  ): Promise<AttemptGroup> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/parallel`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<AttemptGroup>(response);
+  },
+
+  getGroups: async (
+    projectId: string,
+    taskId: string
+  ): Promise<AttemptGroup[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempt-groups`
+    );
+    return handleApiResponse<AttemptGroup[]>(response);
+  },
+
+  pickWinner: async (
+    projectId: string,
+    taskId: string,
+    data: PickAttemptGroupWinner
+  ): Promise<AttemptGroup> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempt-groups/winner`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<AttemptGroup>(response);
+  },
+
+  getState: (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<TaskAttemptState> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}`
+    );
+    return handleApiResponse<TaskAttemptState>(response);
+  },
+
+  stop: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/stop`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  followUp: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    data: CreateFollowUpAttempt
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/follow-up`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  scopedFollowUp: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    data: CreateScopedFollowUp
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/follow-up/scoped`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getPlan: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<PlanReview | null> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/plan`
+    );
+    return handleApiResponse<PlanReview | null>(response);
+  },
+
+  approvePlan: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<FollowUpResponse> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/approve-plan`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<FollowUpResponse>(response);
+  },
+
+  getDiff: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<WorktreeDiff> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/diff`
+    );
+    return handleApiResponse<WorktreeDiff>(response);
+  },
+
+  // The changes of each linked project of a multi-repository attempt
+  getLinkedDiffs: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<LinkedRepoDiff[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/linked-diffs`
+    );
+    return handleApiResponse<LinkedRepoDiff[]>(response);
+  },
+
+  getDiffHunks: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<FileHunks[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/diff/hunks`
+    );
+    return handleApiResponse<FileHunks[]>(response);
+  },
+
+  deleteFile: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    fileToDelete: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/delete-filefile_path=${encodeURIComponent(
+        fileToDelete
+      )}`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  openEditor: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    editorType?: EditorType
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/open-editor`,
+      {
+        method: 'POST',
+        body: JSON.stringify(editorType ? { editor_type: editorType } : null),
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getBranchStatus: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<BranchStatus> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/branch-status`
+    );
+    return handleApiResponse<BranchStatus>(response);
+  },
+
+  merge: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/merge`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  queueMerge: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<MergeQueueEntry> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/merge-queue`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<MergeQueueEntry>(response);
+  },
+
+  getReviews: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<AttemptReview[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/reviews`
+    );
+    return handleApiResponse<AttemptReview[]>(response);
+  },
+
+  approve: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    data: ApproveAttempt
+  ): Promise<AttemptReview> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/review/approve`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<AttemptReview>(response);
+  },
+
+  reject: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    data: RejectAttempt
+  ): Promise<AttemptRejection> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/review/reject`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<AttemptRejection>(response);
+  },
+
+  getRollbacks: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<AttemptRollback[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/rollbacks`
+    );
+    return handleApiResponse<AttemptRollback[]>(response);
+  },
+
+  rollback: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    data: RollbackAttempt
+  ): Promise<AttemptRollback> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/rollback`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<AttemptRollback>(response);
+  },
+
+  // The snapshots taken before each run, with the runs that were reverted
+  getExecutionSnapshots: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<ExecutionSnapshot[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/execution-snapshots`
+    );
+    return handleApiResponse<ExecutionSnapshot[]>(response);
+  },
+
+  getToolUsage: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<ExecutionToolUsage[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/tool-usage`
+    );
+    return handleApiResponse<ExecutionToolUsage[]>(response);
+  },
+
+  revertExecution: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    data: RevertExecution
+  ): Promise<ExecutionSnapshot> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/revert-execution`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ExecutionSnapshot>(response);
+  },
+
+  getAttention: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<ExecutionAttention[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/attention`
+    );
+    return handleApiResponse<ExecutionAttention[]>(response);
+  },
+
+  rebase: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    newBaseBranch?: string,
+    resolveConflicts?: boolean
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/rebase`,
+      {
+        method: 'POST',
+        headers: {
+          'Content-Type': 'application/json',
+        },
+        body: JSON.stringify({
+          new_base_branch: newBaseBranch || null,
+          resolve_conflicts: resolveConflicts ?? false,
+        }),
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  createPR: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    data: {
+      title: string;
+      body: string | null;
+      base_branch: string | null;
+    }
+  ): Promise<string> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/create-pr`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<string>(response);
+  },
+
+  getPrDescription: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<PrDescription> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/pr-description`
+    );
+    return handleApiResponse<PrDescription>(response);
+  },
+
+  startDevServer: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/start-dev-server`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getDevServer: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<DevServerPort | null> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/dev-server`
+    );
+    return handleApiResponse<DevServerPort | null>(response);
+  },
+
+  stopDevServer: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/dev-server/stop`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  devServerPreviewUrl: (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): string =>
+    `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/dev-server/proxy/`,
+
+  getExecutionProcesses: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<ExecutionProcessSummary[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/execution-processes`
+    );
+    return handleApiResponse<ExecutionProcessSummary[]>(response);
+  },
+
+  stopExecutionProcess: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    processId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/execution-processes/${processId}/stop`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  pauseExecutionProcess: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    processId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/execution-processes/${processId}/pause`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  resumeExecutionProcess: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    processId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/execution-processes/${processId}/resume`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getPendingApproval: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    processId: string
+  ): Promise<PendingApproval | null> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/execution-processes/${processId}/approval`
+    );
+    return handleApiResponse<PendingApproval | null>(response);
+  },
+
+  // The answer is written to the agent's CLI, which then runs the command or not
+  answerApproval: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    processId: string,
+    decision: ApprovalDecision
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/execution-processes/${processId}/approval`,
+      {
+        method: 'POST',
+        body: JSON.stringify(decision),
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  // Written to the running agent's stdin; it shows in the conversation once sent
+  sendInput: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    processId: string,
+    input: SendExecutionInput
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/execution-processes/${processId}/input`,
+      {
+        method: 'POST',
+        body: JSON.stringify(input),
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  // Plain-text output, whole when full logs are kept on disk
+  rawLogUrl: (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    processId: string,
+    stream: LogStream
+  ): string =>
+    `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/execution-processes/${processId}/raw-log?stream=${stream}`,
+
+  // Plain-text content of a conversation entry the logs only show the start of
+  entryContentUrl: (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    processId: string,
+    entryIndex: number
+  ): string =>
+    `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/execution-processes/${processId}/entries/${entryIndex}`,
+
+  getDetails: async (attemptId: string): Promise<TaskAttempt> => {
+    const response = await makeRequest(`/api/attempts/${attemptId}/details`);
+    return handleApiResponse<TaskAttempt>(response);
+  },
+
+  getAllLogs: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<ProcessLogsResponse[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/logs`
+    );
+    return handleApiResponse(response);
+  },
+
+  // WebSocket pushing LogStreamMessage frames as the attempt's logs grow
+  logsStreamUrl: (projectId: string, taskId: string, attemptId: string) => {
+    const protocol = window.location.protocol === 'https:' ? 'wss:' : 'ws:';
+    return `${protocol}//${window.location.host}/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/logs/stream`;
+  },
+};
 
 // Execution Process APIs
 export const executionProcessesApi = {
@@ -505,83 +1784,852 @@ export const executionProcessesApi = {
     const response = await makeRequest(`/api/execution-processes/${processId}`);
     return handleApiResponse<ExecutionProcess>(response);
   },
+
+  getResources: async (processId: string): Promise<ExecutionResources> => {
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/resources`
+    );
+    return handleApiResponse<ExecutionResources>(response);
+  },
+
+  getToolUsage: async (processId: string): Promise<ExecutionToolUsage> => {
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/tool-usage`
+    );
+    return handleApiResponse<ExecutionToolUsage>(response);
+  },
+};
+
+// Execution queue APIs
+export const executionQueueApi = {
+  list: async (): Promise<QueueEntry[]> => {
+    const response = await makeRequest('/api/execution-queue');
+    return handleApiResponse<QueueEntry[]>(response);
+  },
+
+  reorder: async (data: ReorderQueue): Promise<QueueEntry[]> => {
+    const response = await makeRequest('/api/execution-queue', {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<QueueEntry[]>(response);
+  },
+};
+
+// Executions waiting on the user
+export const attentionApi = {
+  list: async (): Promise<ExecutionAttention[]> => {
+    const response = await makeRequest('/api/attention');
+    return handleApiResponse<ExecutionAttention[]>(response);
+  },
+};
+
+// Review comment APIs
+export const reviewCommentsApi = {
+  getAll: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<ReviewComment[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/comments`
+    );
+    return handleApiResponse<ReviewComment[]>(response);
+  },
+  create: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    data: CreateReviewComment
+  ): Promise<ReviewComment> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/comments`,
+      {
+        method: 'POST',
+        // BigInt doesn't serialize to JSON
+        body: JSON.stringify({
+          ...data,
+          line_number: Number(data.line_number),
+        }),
+      }
+    );
+    return handleApiResponse<ReviewComment>(response);
+  },
+  update: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    commentId: string,
+    data: UpdateReviewComment
+  ): Promise<ReviewComment> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/comments/${commentId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ReviewComment>(response);
+  },
+  remove: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    commentId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/comments/${commentId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+  sendToAgent: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<FollowUpResponse> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/comments/follow-up`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<FollowUpResponse>(response);
+  },
+};
+
+// Follow-ups being written for a task, quoting entries and diff hunks
+export const followUpDraftsApi = {
+  get: async (
+    projectId: string,
+    taskId: string
+  ): Promise<FollowUpDraft | null> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/follow-up-draft`
+    );
+    return handleApiResponse<FollowUpDraft | null>(response);
+  },
+  save: async (
+    projectId: string,
+    taskId: string,
+    data: SaveFollowUpDraft
+  ): Promise<FollowUpDraft> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/follow-up-draft`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<FollowUpDraft>(response);
+  },
+  discard: async (projectId: string, taskId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/follow-up-draft`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+  // The prompt the saved draft is sent as, with its quotes expanded
+  preview: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string
+  ): Promise<string> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/follow-up-draft/preview`
+    );
+    return handleApiResponse<string>(response);
+  },
+  // Sending it with an executor hands the attempt over to that one
+  send: async (
+    projectId: string,
+    taskId: string,
+    attemptId: string,
+    executor: string | null = null
+  ): Promise<FollowUpResponse> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/attempts/${attemptId}/follow-up-draft/send`,
+      {
+        method: 'POST',
+        body: JSON.stringify({ executor }),
+      }
+    );
+    return handleApiResponse<FollowUpResponse>(response);
+  },
+};
+
+// File System APIs
+export const fileSystemApi = {
+  list: async (path?: string): Promise<DirectoryListResponse> => {
+    const queryParam = path ? `?path=${encodeURIComponent(path)}` : '';
+    const response = await makeRequest(`/api/filesystem/list${queryParam}`);
+    return handleApiResponse<DirectoryListResponse>(response);
+  },
+};
+
+// Config APIs
+export const configApi = {
+  getConfig: async (): Promise<Config> => {
+    const response = await makeRequest('/api/config');
+    return handleApiResponse<Config>(response);
+  },
+  saveConfig: async (config: Config): Promise<Config> => {
+    const response = await makeRequest('/api/config', {
+      method: 'POST',
+      body: JSON.stringify(config),
+    });
+    return handleApiResponse<Config>(response);
+  },
+  getConstants: async (): Promise<ConfigConstants> => {
+    const response = await makeRequest('/api/config/constants');
+    return handleApiResponse<ConfigConstants>(response);
+  },
+  checkExecutorEnvironment: async (
+    executor: string
+  ): Promise<EnvironmentReport> => {
+    const response = await makeRequest(
+      `/api/config/executor-environment?executor=${encodeURIComponent(executor)}`
+    );
+    return handleApiResponse<EnvironmentReport>(response);
+  },
+};
+
+// GitHub Device Auth APIs
+export const githubAuthApi = {
+  checkGithubToken: async (): Promise<boolean | undefined> => {
+    try {
+      const response = await makeRequest('/api/auth/github/check');
+      const result: ApiResponse<null> = await response.json();
+      if (!result.success && result.message === 'github_token_invalid') {
+        return false;
+      }
+      return result.success;
+    } catch (err) {
+      // On network/server error, return undefined (unknown)
+      return undefined;
+    }
+  },
+  start: async (): Promise<DeviceStartResponse> => {
+    const response = await makeRequest('/api/auth/github/device/start', {
+      method: 'POST',
+    });
+    return handleApiResponse<DeviceStartResponse>(response);
+  },
+  poll: async (device_code: string): Promise<string> => {
+    const response = await makeRequest('/api/auth/github/device/poll', {
+      method: 'POST',
+      body: JSON.stringify({ device_code }),
+      headers: { 'Content-Type': 'application/json' },
+    });
+    return handleApiResponse<string>(response);
+  },
+};
+
+// GitHub APIs (only available in cloud mode)
+export const githubApi = {
+  listRepositories: async (page: number = 1): Promise<RepositoryInfo[]> => {
+    const response = await makeRequest(`/api/github/repositories?page=${page}`);
+    return handleApiResponse<RepositoryInfo[]>(response);
+  },
+  createProjectFromRepository: async (
+    data: CreateProjectFromGitHub
+  ): Promise<Project> => {
+    const response = await makeRequest('/api/projects/from-github', {
+      method: 'POST',
+      body: JSON.stringify(data, (_key, value) =>
+        typeof value === 'bigint' ? Number(value) : value
+      ),
+    });
+    return handleApiResponse<Project>(response);
+  },
+};
+
+// GitHub Projects sync APIs
+export const githubProjectsApi = {
+  get: async (projectId: string): Promise<GitHubProjectLink | null> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/github-project`
+    );
+    return handleApiResponse<GitHubProjectLink | null>(response);
+  },
+
+  link: async (
+    projectId: string,
+    data: UpsertGitHubProjectLink
+  ): Promise<GitHubProjectLinkResponse> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/github-project`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<GitHubProjectLinkResponse>(response);
+  },
+
+  unlink: async (projectId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/github-project`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  sync: async (projectId: string): Promise<GitHubProjectSyncResult> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/github-project/sync`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<GitHubProjectSyncResult>(response);
+  },
+};
+
+// GitHub issue import APIs
+export const githubIssuesApi = {
+  get: async (projectId: string): Promise<GitHubIssueLink | null> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/github-issues`
+    );
+    return handleApiResponse<GitHubIssueLink | null>(response);
+  },
+
+  link: async (
+    projectId: string,
+    data: UpsertGitHubIssueLink
+  ): Promise<GitHubIssueLink> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/github-issues`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<GitHubIssueLink>(response);
+  },
+
+  unlink: async (projectId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/github-issues`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  sync: async (projectId: string): Promise<GitHubIssueSyncResult> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/github-issues/sync`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<GitHubIssueSyncResult>(response);
+  },
+};
+
+// Notion APIs
+export const notionApi = {
+  get: async (projectId: string): Promise<NotionLink | null> => {
+    const response = await makeRequest(`/api/projects/${projectId}/notion`);
+    return handleApiResponse<NotionLink | null>(response);
+  },
+
+  link: async (
+    projectId: string,
+    data: UpsertNotionLink
+  ): Promise<NotionLinkResponse> => {
+    const response = await makeRequest(`/api/projects/${projectId}/notion`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<NotionLinkResponse>(response);
+  },
+
+  unlink: async (projectId: string): Promise<void> => {
+    const response = await makeRequest(`/api/projects/${projectId}/notion`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  sync: async (projectId: string): Promise<NotionSyncResult> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/notion/sync`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<NotionSyncResult>(response);
+  },
+};
+
+// Git host APIs
+export const gitHostApi = {
+  get: async (projectId: string): Promise<ProjectGitHost | null> => {
+    const response = await makeRequest(`/api/projects/${projectId}/git-host`);
+    return handleApiResponse<ProjectGitHost | null>(response);
+  },
+
+  save: async (
+    projectId: string,
+    data: UpsertProjectGitHost
+  ): Promise<ProjectGitHost> => {
+    const response = await makeRequest(`/api/projects/${projectId}/git-host`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectGitHost>(response);
+  },
+
+  reset: async (projectId: string): Promise<void> => {
+    const response = await makeRequest(`/api/projects/${projectId}/git-host`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Project MCP server APIs
+export const projectMcpServersApi = {
+  getAll: async (projectId: string): Promise<ProjectMcpServer[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/mcp-servers`
+    );
+    return handleApiResponse<ProjectMcpServer[]>(response);
+  },
+
+  save: async (
+    projectId: string,
+    data: UpsertProjectMcpServer
+  ): Promise<ProjectMcpServer> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/mcp-servers`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ProjectMcpServer>(response);
+  },
+
+  delete: async (projectId: string, name: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/mcp-servers?name=${encodeURIComponent(name)}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
 };
 
-// File System APIs
-export const fileSystemApi = {
-  list: async (path?: string): Promise<DirectoryListResponse> => {
-    const queryParam = path ? `?path=${encodeURIComponent(path)}` : '';
-    const response = await makeRequest(`/api/filesystem/list${queryParam}`);
-    return handleApiResponse<DirectoryListResponse>(response);
+// Project script hook APIs
+export const scriptHooksApi = {
+  getAll: async (projectId: string): Promise<ScriptHook[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/script-hooks`
+    );
+    return handleApiResponse<ScriptHook[]>(response);
+  },
+
+  save: async (
+    projectId: string,
+    data: UpsertScriptHook
+  ): Promise<ScriptHook> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/script-hooks`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ScriptHook>(response);
+  },
+
+  delete: async (projectId: string, name: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/script-hooks?name=${encodeURIComponent(name)}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
   },
 };
 
-// Config APIs
-export const configApi = {
-  getConfig: async (): Promise<Config> => {
-    const response = await makeRequest('/api/config');
-    return handleApiResponse<Config>(response);
+// Executor profile APIs
+export const executorProfilesApi = {
+  // The project's own profiles, then the global ones
+  getAvailable: async (projectId: string): Promise<ExecutorProfile[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/executor-profiles`
+    );
+    return handleApiResponse<ExecutorProfile[]>(response);
   },
-  saveConfig: async (config: Config): Promise<Config> => {
-    const response = await makeRequest('/api/config', {
-      method: 'POST',
-      body: JSON.stringify(config),
+
+  getGlobal: async (): Promise<ExecutorProfile[]> => {
+    const response = await makeRequest('/api/executor-profiles');
+    return handleApiResponse<ExecutorProfile[]>(response);
+  },
+
+  // A global profile without a project
+  create: async (
+    projectId: string | null,
+    data: UpsertExecutorProfile
+  ): Promise<ExecutorProfile> => {
+    const response = await makeRequest(
+      projectId
+        ? `/api/projects/${projectId}/executor-profiles`
+        : '/api/executor-profiles',
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ExecutorProfile>(response);
+  },
+
+  update: async (
+    profileId: string,
+    data: UpsertExecutorProfile
+  ): Promise<ExecutorProfile> => {
+    const response = await makeRequest(`/api/executor-profiles/${profileId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
     });
-    return handleApiResponse<Config>(response);
+    return handleApiResponse<ExecutorProfile>(response);
   },
-  getConstants: async (): Promise<ConfigConstants> => {
-    const response = await makeRequest('/api/config/constants');
-    return handleApiResponse<ConfigConstants>(response);
+
+  delete: async (profileId: string): Promise<void> => {
+    const response = await makeRequest(`/api/executor-profiles/${profileId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  getForTask: async (
+    projectId: string,
+    taskId: string
+  ): Promise<ExecutorProfile | null> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/executor-profile`
+    );
+    return handleApiResponse<ExecutorProfile | null>(response);
+  },
+
+  assignToTask: async (
+    projectId: string,
+    taskId: string,
+    profileId: string | null
+  ): Promise<ExecutorProfile | null> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/executor-profile`,
+      {
+        method: 'PUT',
+        body: JSON.stringify({ executor_profile_id: profileId }),
+      }
+    );
+    return handleApiResponse<ExecutorProfile | null>(response);
   },
 };
 
-// GitHub Device Auth APIs
-export const githubAuthApi = {
-  checkGithubToken: async (): Promise<boolean | undefined> => {
-    try {
-      const response = await makeRequest('/api/auth/github/check');
-      const result: ApiResponse<null> = await response.json();
-      if (!result.success && result.message === 'github_token_invalid') {
-        return false;
+// Project sandbox APIs
+export const projectSandboxApi = {
+  get: async (projectId: string): Promise<ProjectSandbox | null> => {
+    const response = await makeRequest(`/api/projects/${projectId}/sandbox`);
+    return handleApiResponse<ProjectSandbox | null>(response);
+  },
+
+  save: async (
+    projectId: string,
+    data: UpsertProjectSandbox
+  ): Promise<ProjectSandbox> => {
+    const response = await makeRequest(`/api/projects/${projectId}/sandbox`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectSandbox>(response);
+  },
+
+  reset: async (projectId: string): Promise<void> => {
+    const response = await makeRequest(`/api/projects/${projectId}/sandbox`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Project sign-in rule APIs
+export const projectSecurityApi = {
+  get: async (projectId: string): Promise<ProjectSecurity | null> => {
+    const response = await makeRequest(`/api/projects/${projectId}/security`);
+    return handleApiResponse<ProjectSecurity | null>(response);
+  },
+
+  save: async (
+    projectId: string,
+    data: UpsertProjectSecurity
+  ): Promise<ProjectSecurity> => {
+    const response = await makeRequest(`/api/projects/${projectId}/security`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectSecurity>(response);
+  },
+};
+
+// Project shell environment APIs
+export const projectShellEnvironmentApi = {
+  get: async (projectId: string): Promise<ProjectShellEnvironment | null> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/shell-environment`
+    );
+    return handleApiResponse<ProjectShellEnvironment | null>(response);
+  },
+
+  save: async (
+    projectId: string,
+    data: UpsertProjectShellEnvironment
+  ): Promise<ProjectShellEnvironment> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/shell-environment`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
       }
-      return result.success;
-    } catch (err) {
-      // On network/server error, return undefined (unknown)
-      return undefined;
-    }
+    );
+    return handleApiResponse<ProjectShellEnvironment>(response);
   },
-  start: async (): Promise<DeviceStartResponse> => {
-    const response = await makeRequest('/api/auth/github/device/start', {
-      method: 'POST',
+
+  reset: async (projectId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/shell-environment`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Project container APIs
+export const projectContainerApi = {
+  get: async (projectId: string): Promise<ProjectContainer | null> => {
+    const response = await makeRequest(`/api/projects/${projectId}/container`);
+    return handleApiResponse<ProjectContainer | null>(response);
+  },
+
+  save: async (
+    projectId: string,
+    data: UpsertProjectContainer
+  ): Promise<ProjectContainer> => {
+    const response = await makeRequest(`/api/projects/${projectId}/container`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
     });
-    return handleApiResponse<DeviceStartResponse>(response);
+    return handleApiResponse<ProjectContainer>(response);
   },
-  poll: async (device_code: string): Promise<string> => {
-    const response = await makeRequest('/api/auth/github/device/poll', {
-      method: 'POST',
-      body: JSON.stringify({ device_code }),
-      headers: { 'Content-Type': 'application/json' },
+
+  reset: async (projectId: string): Promise<void> => {
+    const response = await makeRequest(`/api/projects/${projectId}/container`, {
+      method: 'DELETE',
     });
-    return handleApiResponse<string>(response);
+    return handleApiResponse<void>(response);
   },
 };
 
-// GitHub APIs (only available in cloud mode)
-export const githubApi = {
-  listRepositories: async (page: number = 1): Promise<RepositoryInfo[]> => {
-    const response = await makeRequest(`/api/github/repositories?page=${page}`);
-    return handleApiResponse<RepositoryInfo[]>(response);
+// Project worker APIs
+export const projectWorkerApi = {
+  get: async (projectId: string): Promise<ProjectWorker | null> => {
+    const response = await makeRequest(`/api/projects/${projectId}/worker`);
+    return handleApiResponse<ProjectWorker | null>(response);
   },
-  createProjectFromRepository: async (
-    data: CreateProjectFromGitHub
-  ): Promise<Project> => {
-    const response = await makeRequest('/api/projects/from-github', {
+
+  save: async (
+    projectId: string,
+    data: UpsertProjectWorker
+  ): Promise<ProjectWorker> => {
+    const response = await makeRequest(`/api/projects/${projectId}/worker`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectWorker>(response);
+  },
+
+  reset: async (projectId: string): Promise<void> => {
+    const response = await makeRequest(`/api/projects/${projectId}/worker`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Project guardrails APIs
+export const projectGuardrailsApi = {
+  get: async (projectId: string): Promise<GuardrailStatus> => {
+    const response = await makeRequest(`/api/projects/${projectId}/guardrails`);
+    return handleApiResponse<GuardrailStatus>(response);
+  },
+
+  save: async (
+    projectId: string,
+    data: UpsertProjectGuardrails
+  ): Promise<GuardrailStatus> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/guardrails`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<GuardrailStatus>(response);
+  },
+
+  reset: async (projectId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/guardrails`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Release APIs
+export const releasesApi = {
+  getAll: async (projectId: string): Promise<Release[]> => {
+    const response = await makeRequest(`/api/projects/${projectId}/releases`);
+    return handleApiResponse<Release[]>(response);
+  },
+
+  get: async (
+    projectId: string,
+    releaseId: string
+  ): Promise<ReleaseWithTasks> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/releases/${releaseId}`
+    );
+    return handleApiResponse<ReleaseWithTasks>(response);
+  },
+
+  create: async (
+    projectId: string,
+    data: CreateRelease
+  ): Promise<ReleaseWithTasks> => {
+    const response = await makeRequest(`/api/projects/${projectId}/releases`, {
       method: 'POST',
-      body: JSON.stringify(data, (_key, value) =>
-        typeof value === 'bigint' ? Number(value) : value
-      ),
+      body: JSON.stringify(data),
     });
-    return handleApiResponse<Project>(response);
+    return handleApiResponse<ReleaseWithTasks>(response);
+  },
+
+  run: async (projectId: string, releaseId: string): Promise<Release> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/releases/${releaseId}/run`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<Release>(response);
+  },
+
+  getConversation: async (
+    projectId: string,
+    releaseId: string
+  ): Promise<NormalizedConversation> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/releases/${releaseId}/conversation`
+    );
+    return handleApiResponse<NormalizedConversation>(response);
+  },
+
+  delete: async (projectId: string, releaseId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/releases/${releaseId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Benchmark APIs
+export const benchmarksApi = {
+  list: async (projectId: string): Promise<BenchmarkSuite[]> => {
+    const response = await makeRequest(`/api/projects/${projectId}/benchmarks`);
+    return handleApiResponse<BenchmarkSuite[]>(response);
+  },
+
+  create: async (
+    projectId: string,
+    data: UpsertBenchmarkSuite
+  ): Promise<BenchmarkSuite> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/benchmarks`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<BenchmarkSuite>(response);
+  },
+
+  update: async (
+    projectId: string,
+    suiteId: string,
+    data: UpsertBenchmarkSuite
+  ): Promise<BenchmarkSuite> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/benchmarks/${suiteId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<BenchmarkSuite>(response);
+  },
+
+  delete: async (projectId: string, suiteId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/benchmarks/${suiteId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getRuns: async (
+    projectId: string,
+    suiteId: string
+  ): Promise<BenchmarkReport[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/benchmarks/${suiteId}/runs`
+    );
+    return handleApiResponse<BenchmarkReport[]>(response);
+  },
+
+  startRun: async (
+    projectId: string,
+    suiteId: string,
+    data: StartBenchmarkRun
+  ): Promise<BenchmarkReport> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/benchmarks/${suiteId}/runs`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<BenchmarkReport>(response);
   },
 };
 
@@ -626,6 +2674,17 @@ export const templatesApi = {
     return handleApiResponse<TaskTemplate>(response);
   },
 
+  instantiate: async (
+    templateId: string,
+    data: InstantiateTaskTemplate
+  ): Promise<Task> => {
+    const response = await makeRequest(`/api/templates/${templateId}/tasks`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Task>(response);
+  },
+
   delete: async (templateId: string): Promise<void> => {
     const response = await makeRequest(`/api/templates/${templateId}`, {
       method: 'DELETE',
@@ -634,6 +2693,461 @@ export const templatesApi = {
   },
 };
 
+// Search APIs
+export const searchApi = {
+  search: async (query: SearchQuery): Promise<SearchHit[]> => {
+    const params = new URLSearchParams({ q: query.q });
+    if (query.project_id) params.set('project_id', query.project_id);
+    if (query.status) params.set('status', query.status);
+    if (query.executor) params.set('executor', query.executor);
+    if (query.from) params.set('from', query.from);
+    if (query.to) params.set('to', query.to);
+    if (query.limit) params.set('limit', String(query.limit));
+    const response = await makeRequest(`/api/search?${params.toString()}`);
+    return handleApiResponse<SearchHit[]>(response);
+  },
+};
+
+// Activity feed and audit trail APIs
+export const eventsApi = {
+  getProjectEvents: async (
+    projectId: string,
+    beforeId?: bigint,
+    limit?: number
+  ): Promise<ActivityEvent[]> => {
+    const params = new URLSearchParams();
+    if (beforeId !== undefined) params.set('before_id', String(beforeId));
+    if (limit !== undefined) params.set('limit', String(limit));
+    const response = await makeRequest(
+      `/api/projects/${projectId}/events?${params.toString()}`
+    );
+    return handleApiResponse<ActivityEvent[]>(response);
+  },
+  getAll: async (
+    beforeId?: bigint,
+    limit?: number
+  ): Promise<ActivityEvent[]> => {
+    const params = new URLSearchParams();
+    if (beforeId !== undefined) params.set('before_id', String(beforeId));
+    if (limit !== undefined) params.set('limit', String(limit));
+    const response = await makeRequest(`/api/events?${params.toString()}`);
+    return handleApiResponse<ActivityEvent[]>(response);
+  },
+  verify: async (): Promise<AuditVerification> => {
+    const response = await makeRequest('/api/events/verify');
+    return handleApiResponse<AuditVerification>(response);
+  },
+  streamUrl: (projectId: string, sinceId?: bigint): string =>
+    sinceId !== undefined
+      ? `/api/projects/${projectId}/events/stream?since_id=${sinceId}`
+      : `/api/projects/${projectId}/events/stream`,
+};
+
+// Notification webhook APIs
+export const notificationsApi = {
+  getWebhooks: async (projectId: string): Promise<NotificationWebhook[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/notification-webhooks`
+    );
+    return handleApiResponse<NotificationWebhook[]>(response);
+  },
+  createWebhook: async (
+    projectId: string,
+    data: UpsertNotificationWebhook
+  ): Promise<NotificationWebhook> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/notification-webhooks`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<NotificationWebhook>(response);
+  },
+  updateWebhook: async (
+    webhookId: string,
+    data: UpsertNotificationWebhook
+  ): Promise<NotificationWebhook> => {
+    const response = await makeRequest(
+      `/api/notification-webhooks/${webhookId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<NotificationWebhook>(response);
+  },
+  deleteWebhook: async (webhookId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/notification-webhooks/${webhookId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+  getLog: async (
+    projectId: string,
+    limit?: number
+  ): Promise<NotificationDelivery[]> => {
+    const query = limit !== undefined ? `?limit=${limit}` : '';
+    const response = await makeRequest(
+      `/api/projects/${projectId}/notifications${query}`
+    );
+    return handleApiResponse<NotificationDelivery[]>(response);
+  },
+};
+
+// Sign-in APIs
+export const authApi = {
+  getStatus: async (): Promise<AuthStatus> => {
+    const response = await makeRequest('/api/auth/status');
+    return handleApiResponse<AuthStatus>(response);
+  },
+  register: async (data: CreateUser): Promise<LoginResponse> => {
+    const response = await makeRequest('/api/auth/register', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<LoginResponse>(response);
+  },
+  login: async (data: LoginRequest): Promise<LoginResponse> => {
+    const response = await makeRequest('/api/auth/login', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<LoginResponse>(response);
+  },
+  logout: async (): Promise<void> => {
+    const response = await makeRequest('/api/auth/logout', {
+      method: 'POST',
+    });
+    return handleApiResponse<void>(response);
+  },
+  logoutAll: async (): Promise<void> => {
+    const response = await makeRequest('/api/auth/logout-all', {
+      method: 'POST',
+    });
+    return handleApiResponse<void>(response);
+  },
+  refresh: async (): Promise<LoginResponse> => {
+    const response = await makeRequest('/api/auth/refresh', {
+      method: 'POST',
+    });
+    return handleApiResponse<LoginResponse>(response);
+  },
+  changePassword: async (data: ChangePassword): Promise<LoginResponse> => {
+    const response = await makeRequest('/api/auth/password', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<LoginResponse>(response);
+  },
+  oidcLoginUrl: (): string => '/api/auth/oidc/login',
+  startTwoFactor: async (): Promise<TwoFactorEnrollment> => {
+    const response = await makeRequest('/api/auth/two-factor/enroll', {
+      method: 'POST',
+    });
+    return handleApiResponse<TwoFactorEnrollment>(response);
+  },
+  confirmTwoFactor: async (code: string): Promise<RecoveryCodes> => {
+    const response = await makeRequest('/api/auth/two-factor/confirm', {
+      method: 'POST',
+      body: JSON.stringify({ code }),
+    });
+    return handleApiResponse<RecoveryCodes>(response);
+  },
+  replaceRecoveryCodes: async (code: string): Promise<RecoveryCodes> => {
+    const response = await makeRequest('/api/auth/two-factor/recovery-codes', {
+      method: 'POST',
+      body: JSON.stringify({ code }),
+    });
+    return handleApiResponse<RecoveryCodes>(response);
+  },
+  disableTwoFactor: async (code: string): Promise<void> => {
+    const response = await makeRequest('/api/auth/two-factor/disable', {
+      method: 'POST',
+      body: JSON.stringify({ code }),
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Account APIs, for admins
+export const usersApi = {
+  getAll: async (): Promise<User[]> => {
+    const response = await makeRequest('/api/users');
+    return handleApiResponse<User[]>(response);
+  },
+  create: async (data: CreateUser): Promise<User> => {
+    const response = await makeRequest('/api/users', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<User>(response);
+  },
+  update: async (userId: string, data: UpdateUser): Promise<User> => {
+    const response = await makeRequest(`/api/users/${userId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<User>(response);
+  },
+  delete: async (userId: string): Promise<void> => {
+    const response = await makeRequest(`/api/users/${userId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+  endSessions: async (userId: string): Promise<void> => {
+    const response = await makeRequest(`/api/users/${userId}/sessions`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+  resetTwoFactor: async (userId: string): Promise<void> => {
+    const response = await makeRequest(`/api/users/${userId}/two-factor`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Project Member APIs
+export const projectMembersApi = {
+  getAll: async (projectId: string): Promise<ProjectMember[]> => {
+    const response = await makeRequest(`/api/projects/${projectId}/members`);
+    return handleApiResponse<ProjectMember[]>(response);
+  },
+  set: async (
+    projectId: string,
+    data: SetProjectMember
+  ): Promise<ProjectMember[]> => {
+    const response = await makeRequest(`/api/projects/${projectId}/members`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectMember[]>(response);
+  },
+  remove: async (projectId: string, userId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/members/${userId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Invite links to join a project, for its admins
+export const projectInvitesApi = {
+  getAll: async (projectId: string): Promise<ProjectInvite[]> => {
+    const response = await makeRequest(`/api/projects/${projectId}/invites`);
+    return handleApiResponse<ProjectInvite[]>(response);
+  },
+  create: async (
+    projectId: string,
+    data: CreateProjectInvite
+  ): Promise<CreatedProjectInvite> => {
+    const response = await makeRequest(`/api/projects/${projectId}/invites`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<CreatedProjectInvite>(response);
+  },
+  revoke: async (projectId: string, inviteId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/invites/${inviteId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Opening an invite link
+export const invitesApi = {
+  get: async (token: string): Promise<InviteDetails> => {
+    const response = await makeRequest(`/api/auth/invites/${token}`);
+    return handleApiResponse<InviteDetails>(response);
+  },
+  accept: async (
+    token: string,
+    data: AcceptProjectInvite
+  ): Promise<ProjectMembership> => {
+    const response = await makeRequest(`/api/auth/invites/${token}/accept`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectMembership>(response);
+  },
+};
+
+// Mentions of the signed-in user, as their inbox
+export const mentionsApi = {
+  getAll: async (unread = false): Promise<Mention[]> => {
+    const response = await makeRequest(
+      `/api/mentions${unread ? '?unread=true' : ''}`
+    );
+    return handleApiResponse<Mention[]>(response);
+  },
+  markRead: async (mentionId: string): Promise<void> => {
+    const response = await makeRequest(`/api/mentions/${mentionId}/read`, {
+      method: 'POST',
+    });
+    return handleApiResponse<void>(response);
+  },
+  markAllRead: async (): Promise<void> => {
+    const response = await makeRequest('/api/mentions/read', {
+      method: 'POST',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+export const pluginsApi = {
+  getAll: async (): Promise<PluginInfo[]> => {
+    const response = await makeRequest('/api/plugins');
+    return handleApiResponse<PluginInfo[]>(response);
+  },
+};
+
+// Personal access token APIs
+export const apiTokensApi = {
+  getAll: async (all = false): Promise<ApiToken[]> => {
+    const response = await makeRequest(`/api/tokens${all ? '?all=true' : ''}`);
+    return handleApiResponse<ApiToken[]>(response);
+  },
+  create: async (data: CreateApiToken): Promise<CreatedApiToken> => {
+    const response = await makeRequest('/api/tokens', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<CreatedApiToken>(response);
+  },
+  revoke: async (tokenId: string): Promise<void> => {
+    const response = await makeRequest(`/api/tokens/${tokenId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+  rotate: async (tokenId: string): Promise<CreatedApiToken> => {
+    const response = await makeRequest(`/api/tokens/${tokenId}/rotate`, {
+      method: 'POST',
+    });
+    return handleApiResponse<CreatedApiToken>(response);
+  },
+  getUses: async (tokenId: string, limit?: number): Promise<ApiTokenUse[]> => {
+    const query = limit !== undefined ? `?limit=${limit}` : '';
+    const response = await makeRequest(`/api/tokens/${tokenId}/uses${query}`);
+    return handleApiResponse<ApiTokenUse[]>(response);
+  },
+};
+
+// Read-only share links for a task's conversations
+export const shareLinksApi = {
+  getAll: async (
+    projectId: string,
+    taskId: string
+  ): Promise<TaskShareLink[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/share-links`
+    );
+    return handleApiResponse<TaskShareLink[]>(response);
+  },
+  create: async (
+    projectId: string,
+    taskId: string,
+    data: CreateTaskShareLink
+  ): Promise<CreatedTaskShareLink> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/share-links`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<CreatedTaskShareLink>(response);
+  },
+  revoke: async (
+    projectId: string,
+    taskId: string,
+    linkId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/share-links/${linkId}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+  // What a link shows, to anyone holding its token
+  getShared: async (token: string): Promise<SharedTask> => {
+    const response = await makeRequest(
+      `/api/shared/${encodeURIComponent(token)}`
+    );
+    return handleApiResponse<SharedTask>(response);
+  },
+  url: (token: string) => `${window.location.origin}/shared/${token}`,
+};
+
+// Bookmarked conversation entries APIs
+export const bookmarksApi = {
+  getAll: async (
+    projectId: string,
+    taskId: string
+  ): Promise<EntryBookmark[]> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/bookmarks`
+    );
+    return handleApiResponse<EntryBookmark[]>(response);
+  },
+  getForProject: async (projectId: string): Promise<EntryBookmark[]> => {
+    const response = await makeRequest(`/api/projects/${projectId}/bookmarks`);
+    return handleApiResponse<EntryBookmark[]>(response);
+  },
+  create: async (
+    projectId: string,
+    taskId: string,
+    data: CreateEntryBookmark
+  ): Promise<EntryBookmark> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/bookmarks`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<EntryBookmark>(response);
+  },
+  delete: async (
+    projectId: string,
+    taskId: string,
+    bookmarkId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/bookmarks/${bookmarkId}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+  export: async (
+    projectId: string,
+    taskId: string,
+    data: ExportEntryBookmarks
+  ): Promise<ExportedEntryBookmarks> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/${taskId}/bookmarks/export`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ExportedEntryBookmarks>(response);
+  },
+};
+
 // MCP Servers APIs
 export const mcpServersApi = {
   load: async (executor: string): Promise<any> => {
@@ -2,13 +2,17 @@ import {
   DiffChunkType,
   ExecutionProcess,
   ExecutionProcessSummary,
+  PendingApproval,
   ProcessLogsResponse,
+  ReviewComment,
 } from 'shared/types.ts';
 
 export type AttemptData = {
   processes: ExecutionProcessSummary[];
   runningProcessDetails: Record<string, ExecutionProcess>;
   allLogs: ProcessLogsResponse[];
+  // Commands running processes are waiting to be allowed, by process id
+  approvals: Record<string, PendingApproval>;
 };
 
 export interface ProcessedLine {
@@ -18,6 +22,16 @@ export interface ProcessedLine {
   newLineNumber?: number;
 }
 
+export interface DiffReview {
+  comments: ReviewComment[];
+  onAddComment: (
+    filePath: string,
+    lineNumber: number,
+    body: string
+  ) => Promise<void>;
+  onResolveComment: (comment: ReviewComment) => Promise<void>;
+}
+
 export interface ProcessedSection {
   type: 'context' | 'change' | 'expanded';
   lines: ProcessedLine[];
@@ -1,5 +1,6 @@
 import { type ClassValue, clsx } from 'clsx';
 import { twMerge } from 'tailwind-merge';
+import type { NormalizedEntry } from 'shared/types';
 
 export function cn(...inputs: ClassValue[]) {
   return twMerge(clsx(inputs));
@@ -8,3 +9,13 @@ export function cn(...inputs: ClassValue[]) {
 export function is_planning_executor_type(executorType: string): boolean {
   return executorType === 'claude-plan';
 }
+
+// A plan presented in an executor's plan mode, or one a plan-only run was
+// prompted for
+export function is_plan_entry(entry: NormalizedEntry): boolean {
+  return (
+    entry.entry_type.type === 'plan_entry' ||
+    (entry.entry_type.type === 'tool_use' &&
+      entry.entry_type.action_type.action === 'plan_presentation')
+  );
+}
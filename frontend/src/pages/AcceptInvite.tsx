@@ -0,0 +1,170 @@
+import { FormEvent, useEffect, useState } from 'react';
+import { useNavigate, useParams } from 'react-router-dom';
+import { Button } from '@/components/ui/button';
+import {
+  Card,
+  CardContent,
+  CardDescription,
+  CardHeader,
+  CardTitle,
+} from '@/components/ui/card';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { Loader } from '@/components/ui/loader';
+import { authApi, invitesApi } from '@/lib/api';
+import type { AuthStatus, InviteDetails } from 'shared/types';
+
+// Where an invite link lands: whoever opens it joins the project, signing in
+// first or creating an account
+export function AcceptInvitePage() {
+  const { token } = useParams<{ token: string }>();
+  const navigate = useNavigate();
+  const [invite, setInvite] = useState<InviteDetails | null>(null);
+  const [status, setStatus] = useState<AuthStatus | null>(null);
+  const [newAccount, setNewAccount] = useState(true);
+  const [username, setUsername] = useState('');
+  const [password, setPassword] = useState('');
+  const [code, setCode] = useState('');
+  const [error, setError] = useState<string | null>(null);
+  const [submitting, setSubmitting] = useState(false);
+
+  useEffect(() => {
+    if (!token) {
+      return;
+    }
+    Promise.all([invitesApi.get(token), authApi.getStatus()])
+      .then(([details, authStatus]) => {
+        setInvite(details);
+        setStatus(authStatus);
+      })
+      .catch(() =>
+        setError('This invite does not exist, was used, revoked or expired.')
+      );
+  }, [token]);
+
+  const handleSubmit = async (e: FormEvent) => {
+    e.preventDefault();
+    if (!token || !invite) {
+      return;
+    }
+    setSubmitting(true);
+    setError(null);
+    try {
+      const signedIn = status?.user != null;
+      if (!signedIn && !newAccount) {
+        await authApi.login({ username, password, code: code || null });
+      }
+      const createAccount = !signedIn && newAccount;
+      const membership = await invitesApi.accept(token, {
+        username: createAccount ? username : null,
+        display_name: null,
+        password: createAccount ? password : null,
+      });
+      navigate(`/projects/${membership.project_id}/tasks`);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to join');
+    } finally {
+      setSubmitting(false);
+    }
+  };
+
+  if (!invite || !status) {
+    return (
+      <div className="min-h-screen bg-background flex items-center justify-center">
+        {error ? (
+          <Alert variant="destructive" className="max-w-sm">
+            <AlertDescription>{error}</AlertDescription>
+          </Alert>
+        ) : (
+          <Loader message="Loading invite..." size={32} />
+        )}
+      </div>
+    );
+  }
+
+  return (
+    <div className="min-h-screen bg-background flex items-center justify-center">
+      <Card className="w-full max-w-sm">
+        <CardHeader>
+          <CardTitle>Join {invite.project_name}</CardTitle>
+          <CardDescription>
+            You're invited as {invite.role}. The invite expires{' '}
+            {new Date(invite.expires_at).toLocaleDateString()}.
+          </CardDescription>
+        </CardHeader>
+        <CardContent>
+          <form onSubmit={handleSubmit} className="space-y-4">
+            {error && (
+              <Alert variant="destructive">
+                <AlertDescription>{error}</AlertDescription>
+              </Alert>
+            )}
+            {status.user ? (
+              <p className="text-sm text-muted-foreground">
+                Signed in as {status.user.username}.
+              </p>
+            ) : (
+              <>
+                <div className="flex gap-2">
+                  <Button
+                    type="button"
+                    size="sm"
+                    variant={newAccount ? 'default' : 'outline'}
+                    onClick={() => setNewAccount(true)}
+                  >
+                    New account
+                  </Button>
+                  <Button
+                    type="button"
+                    size="sm"
+                    variant={newAccount ? 'outline' : 'default'}
+                    onClick={() => setNewAccount(false)}
+                  >
+                    I have an account
+                  </Button>
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="invite-username">Username</Label>
+                  <Input
+                    id="invite-username"
+                    autoComplete="username"
+                    value={username}
+                    onChange={(e) => setUsername(e.target.value)}
+                  />
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="invite-password">Password</Label>
+                  <Input
+                    id="invite-password"
+                    type="password"
+                    autoComplete={
+                      newAccount ? 'new-password' : 'current-password'
+                    }
+                    value={password}
+                    onChange={(e) => setPassword(e.target.value)}
+                  />
+                </div>
+                {!newAccount && (
+                  <div className="space-y-2">
+                    <Label htmlFor="invite-code">Two-factor code</Label>
+                    <Input
+                      id="invite-code"
+                      autoComplete="one-time-code"
+                      placeholder="Only if your account uses one"
+                      value={code}
+                      onChange={(e) => setCode(e.target.value)}
+                    />
+                  </div>
+                )}
+              </>
+            )}
+            <Button type="submit" className="w-full" disabled={submitting}>
+              Join project
+            </Button>
+          </form>
+        </CardContent>
+      </Card>
+    </div>
+  );
+}
@@ -0,0 +1,114 @@
+import { FormEvent, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import {
+  Card,
+  CardContent,
+  CardDescription,
+  CardHeader,
+  CardTitle,
+} from '@/components/ui/card';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import type { AuthStatus } from 'shared/types';
+import { authApi } from '@/lib/api';
+
+interface LoginProps {
+  status: AuthStatus;
+  onSignedIn: () => void;
+}
+
+export function Login({ status, onSignedIn }: LoginProps) {
+  const [username, setUsername] = useState('');
+  const [password, setPassword] = useState('');
+  const [code, setCode] = useState('');
+  const [error, setError] = useState<string | null>(null);
+  const [submitting, setSubmitting] = useState(false);
+  const registering = status.needs_setup;
+
+  const handleSubmit = async (e: FormEvent) => {
+    e.preventDefault();
+    setSubmitting(true);
+    setError(null);
+    try {
+      if (registering) {
+        await authApi.register({
+          username,
+          password,
+          display_name: null,
+          is_admin: null,
+        });
+      } else {
+        await authApi.login({ username, password, code: code || null });
+      }
+      onSignedIn();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to sign in');
+    } finally {
+      setSubmitting(false);
+    }
+  };
+
+  return (
+    <div className="min-h-screen bg-background flex items-center justify-center">
+      <Card className="w-full max-w-sm">
+        <CardHeader>
+          <CardTitle>{registering ? 'Create an admin' : 'Sign in'}</CardTitle>
+          <CardDescription>
+            {registering
+              ? 'No account exists yet. The first one manages the server.'
+              : 'This server needs an account to use.'}
+          </CardDescription>
+        </CardHeader>
+        <CardContent>
+          <form onSubmit={handleSubmit} className="space-y-4">
+            {error && (
+              <Alert variant="destructive">
+                <AlertDescription>{error}</AlertDescription>
+              </Alert>
+            )}
+            <div className="space-y-2">
+              <Label htmlFor="login-username">Username</Label>
+              <Input
+                id="login-username"
+                autoComplete="username"
+                value={username}
+                onChange={(e) => setUsername(e.target.value)}
+              />
+            </div>
+            <div className="space-y-2">
+              <Label htmlFor="login-password">Password</Label>
+              <Input
+                id="login-password"
+                type="password"
+                autoComplete={registering ? 'new-password' : 'current-password'}
+                value={password}
+                onChange={(e) => setPassword(e.target.value)}
+              />
+            </div>
+            {!registering && (
+              <div className="space-y-2">
+                <Label htmlFor="login-code">Two-factor code</Label>
+                <Input
+                  id="login-code"
+                  autoComplete="one-time-code"
+                  placeholder="Only if your account uses one"
+                  value={code}
+                  onChange={(e) => setCode(e.target.value)}
+                />
+              </div>
+            )}
+            <Button type="submit" className="w-full" disabled={submitting}>
+              {registering ? 'Create account' : 'Sign in'}
+            </Button>
+            {status.oidc_enabled && !registering && (
+              <Button variant="outline" className="w-full" asChild>
+                <a href={authApi.oidcLoginUrl()}>Sign in with SSO</a>
+              </Button>
+            )}
+          </form>
+        </CardContent>
+      </Card>
+    </div>
+  );
+}
@@ -18,13 +18,25 @@ import { Label } from '@/components/ui/label';
 import { Alert, AlertDescription } from '@/components/ui/alert';
 import { Checkbox } from '@/components/ui/checkbox';
 import { Input } from '@/components/ui/input';
+import { Textarea } from '@/components/ui/textarea';
 import { Key, Loader2, Volume2 } from 'lucide-react';
-import type { EditorType, SoundFile, ThemeMode } from 'shared/types';
+import type {
+  AttentionChannels,
+  EditorType,
+  LoginShellMode,
+  NotificationEvents,
+  PreemptionPolicy,
+  SmtpSecurity,
+  SoundFile,
+  ThemeMode,
+} from 'shared/types';
 import {
+  CONTEXT_REPLAY_EXECUTORS,
   EDITOR_LABELS,
   EDITOR_TYPES,
   EXECUTOR_LABELS,
   EXECUTOR_TYPES,
+  MODEL_SUPPORTED_EXECUTORS,
   SOUND_FILES,
   SOUND_LABELS,
 } from 'shared/types';
@@ -32,6 +44,32 @@ import { useTheme } from '@/components/theme-provider';
 import { useConfig } from '@/components/config-provider';
 import { GitHubLoginDialog } from '@/components/GitHubLoginDialog';
 import { TaskTemplateManager } from '@/components/TaskTemplateManager';
+import { ApiTokenManager } from '@/components/ApiTokenManager';
+import { TwoFactorManager } from '@/components/TwoFactorManager';
+import { PluginList } from '@/components/PluginList';
+import { ExecutorEnvironmentStatus } from '@/components/ExecutorEnvironmentStatus';
+
+const NOTIFICATION_EVENTS: {
+  key: keyof NotificationEvents;
+  label: string;
+}[] = [
+  { key: 'attempt_succeeded', label: 'An attempt finishes successfully' },
+  { key: 'attempt_failed', label: 'An attempt fails' },
+  {
+    key: 'approval_needed',
+    label: 'A command or plan is waiting for approval',
+  },
+  { key: 'task_stale', label: 'A task sits idle in progress or in review' },
+];
+
+const ATTENTION_CHANNELS: {
+  key: keyof AttentionChannels;
+  label: string;
+}[] = [
+  { key: 'desktop', label: 'Desktop' },
+  { key: 'webhook', label: 'Project webhooks' },
+  { key: 'email', label: 'Email' },
+];
 
 export function Settings() {
   const { config, updateConfig, saveConfig, loading, updateAndSaveConfig } =
@@ -200,9 +238,20 @@ export function Settings() {
               <div className="space-y-2">
                 <Label htmlFor="executor">Default Executor</Label>
                 <Select
-                  value={config.executor.type}
-                  onValueChange={(value: 'echo' | 'claude' | 'amp') =>
-                    updateConfig({ executor: { type: value } })
+                  value={
+                    config.executor.type === 'jsonl' ||
+                    config.executor.type === 'acp'
+                      ? `${config.executor.type}:${config.executor.name}`
+                      : config.executor.type
+                  }
+                  onValueChange={(value: string) =>
+                    updateConfig({
+                      executor: value.startsWith('jsonl:')
+                        ? { type: 'jsonl', name: value.slice('jsonl:'.length) }
+                        : value.startsWith('acp:')
+                          ? { type: 'acp', name: value.slice('acp:'.length) }
+                          : { type: value as 'echo' | 'claude' | 'amp' },
+                    })
                   }
                 >
                   <SelectTrigger id="executor">
@@ -214,208 +263,1739 @@ export function Settings() {
                         {EXECUTOR_LABELS[type]}
                       </SelectItem>
                     ))}
+                    {config.jsonl_executors.map((definition) => (
+                      <SelectItem
+                        key={definition.name}
+                        value={`jsonl:${definition.name}`}
+                      >
+                        {definition.name} (JSON lines)
+                      </SelectItem>
+                    ))}
+                    {config.acp_agents.map((definition) => (
+                      <SelectItem
+                        key={definition.name}
+                        value={`acp:${definition.name}`}
+                      >
+                        {definition.name} (ACP)
+                      </SelectItem>
+                    ))}
                   </SelectContent>
                 </Select>
                 <p className="text-sm text-muted-foreground">
-                  Choose the default executor for running tasks.
+                  Choose the default executor for running tasks. Agents that
+                  print JSON lines can be added under{' '}
+                  <code>jsonl_executors</code> in the config file, and agents
+                  speaking the Agent Client Protocol under{' '}
+                  <code>acp_agents</code>.
                 </p>
+                <ExecutorEnvironmentStatus
+                  executor={
+                    config.executor.type === 'jsonl' ||
+                    config.executor.type === 'acp'
+                      ? `${config.executor.type}:${config.executor.name}`
+                      : config.executor.type
+                  }
+                />
               </div>
-            </CardContent>
-          </Card>
-
-          <Card>
-            <CardHeader>
-              <CardTitle>Editor</CardTitle>
-              <CardDescription>
-                Configure which editor to open when viewing task attempts.
-              </CardDescription>
-            </CardHeader>
-            <CardContent className="space-y-4">
               <div className="space-y-2">
-                <Label htmlFor="editor">Preferred Editor</Label>
-                <Select
-                  value={config.editor.editor_type}
-                  onValueChange={(value: EditorType) =>
+                <Label>Default models</Label>
+                <div className="grid grid-cols-2 gap-4">
+                  {MODEL_SUPPORTED_EXECUTORS.map((type) => (
+                    <div key={type} className="space-y-1">
+                      <Label
+                        htmlFor={`model-${type}`}
+                        className="text-xs text-muted-foreground"
+                      >
+                        {EXECUTOR_LABELS[type]}
+                      </Label>
+                      <Input
+                        id={`model-${type}`}
+                        placeholder="The agent’s default"
+                        value={config.executor_models[type] ?? ''}
+                        onChange={(e) => {
+                          const models = { ...config.executor_models };
+                          if (e.target.value.trim()) {
+                            models[type] = e.target.value;
+                          } else {
+                            delete models[type];
+                          }
+                          updateConfig({ executor_models: models });
+                        }}
+                      />
+                    </div>
+                  ))}
+                </div>
+                <p className="text-sm text-muted-foreground">
+                  The model each agent runs with unless an attempt picks one, as
+                  its CLI names it.
+                </p>
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="context-budget">
+                  Follow-up context budget (tokens)
+                </Label>
+                <Input
+                  id="context-budget"
+                  type="number"
+                  min={500}
+                  value={config.context_budget.max_tokens}
+                  onChange={(e) =>
                     updateConfig({
-                      editor: {
-                        ...config.editor,
-                        editor_type: value,
-                        custom_command:
-                          value === 'custom'
-                            ? config.editor.custom_command
-                            : null,
+                      context_budget: {
+                        ...config.context_budget,
+                        max_tokens: Math.max(
+                          500,
+                          parseInt(e.target.value) || 0
+                        ),
                       },
                     })
                   }
-                >
-                  <SelectTrigger id="editor">
-                    <SelectValue placeholder="Select editor" />
-                  </SelectTrigger>
-                  <SelectContent>
-                    {EDITOR_TYPES.map((type) => (
-                      <SelectItem key={type} value={type}>
-                        {EDITOR_LABELS[type]}
-                      </SelectItem>
-                    ))}
-                  </SelectContent>
-                </Select>
+                />
+                <div className="grid grid-cols-3 gap-4">
+                  {CONTEXT_REPLAY_EXECUTORS.map((type) => (
+                    <div key={type} className="space-y-1">
+                      <Label
+                        htmlFor={`context-budget-${type}`}
+                        className="text-xs text-muted-foreground"
+                      >
+                        {EXECUTOR_LABELS[type]}
+                      </Label>
+                      <Input
+                        id={`context-budget-${type}`}
+                        type="number"
+                        min={500}
+                        placeholder={String(config.context_budget.max_tokens)}
+                        value={config.context_budget.executors[type] ?? ''}
+                        onChange={(e) => {
+                          const executors = {
+                            ...config.context_budget.executors,
+                          };
+                          const tokens = parseInt(e.target.value);
+                          if (tokens > 0) {
+                            executors[type] = tokens;
+                          } else {
+                            delete executors[type];
+                          }
+                          updateConfig({
+                            context_budget: {
+                              ...config.context_budget,
+                              executors,
+                            },
+                          });
+                        }}
+                      />
+                    </div>
+                  ))}
+                </div>
                 <p className="text-sm text-muted-foreground">
-                  Choose your preferred code editor for opening task attempts.
+                  How much of an attempt’s earlier conversation these agents are
+                  given back with a follow-up. Older turns are summarized to fit
+                  and the most recent kept as they were.
                 </p>
               </div>
-
-              {config.editor.editor_type === 'custom' && (
+              <div className="space-y-2">
+                <Label htmlFor="execution-timeout">Timeout (minutes)</Label>
+                <Input
+                  id="execution-timeout"
+                  type="number"
+                  min={0}
+                  placeholder="No limit"
+                  value={config.execution_timeout_minutes ?? ''}
+                  onChange={(e) => {
+                    const minutes = parseInt(e.target.value);
+                    updateConfig({
+                      execution_timeout_minutes:
+                        minutes > 0 ? minutes : null,
+                    });
+                  }}
+                />
+                <p className="text-sm text-muted-foreground">
+                  Coding agents and scripts still running after this long are
+                  stopped. Tasks can set their own limit.
+                </p>
+              </div>
+              <div className="grid grid-cols-2 gap-4">
                 <div className="space-y-2">
-                  <Label htmlFor="custom-command">Custom Command</Label>
+                  <Label htmlFor="retry-attempts">Start attempts</Label>
                   <Input
-                    id="custom-command"
-                    placeholder="e.g., code, subl, vim"
-                    value={config.editor.custom_command || ''}
+                    id="retry-attempts"
+                    type="number"
+                    min={1}
+                    value={config.executor_retry.max_attempts}
                     onChange={(e) =>
                       updateConfig({
-                        editor: {
-                          ...config.editor,
-                          custom_command: e.target.value || null,
+                        executor_retry: {
+                          ...config.executor_retry,
+                          max_attempts: Math.max(
+                            1,
+                            parseInt(e.target.value) || 1
+                          ),
+                        },
+                      })
+                    }
+                  />
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="retry-backoff">
+                    First retry after (seconds)
+                  </Label>
+                  <Input
+                    id="retry-backoff"
+                    type="number"
+                    min={0}
+                    value={config.executor_retry.backoff_seconds}
+                    onChange={(e) =>
+                      updateConfig({
+                        executor_retry: {
+                          ...config.executor_retry,
+                          backoff_seconds: Math.max(
+                            0,
+                            parseInt(e.target.value) || 0
+                          ),
                         },
                       })
                     }
                   />
+                </div>
+                <p className="col-span-2 text-sm text-muted-foreground">
+                  A coding agent that fails to start, or exits with an error
+                  right away, is started again with a doubling wait before the
+                  attempt fails.
+                </p>
+              </div>
+              <div className="grid grid-cols-2 gap-4">
+                <div className="space-y-2">
+                  <Label htmlFor="max-running">Running attempts</Label>
+                  <Input
+                    id="max-running"
+                    type="number"
+                    min={0}
+                    placeholder="No limit"
+                    value={config.execution_limits.max_running ?? ''}
+                    onChange={(e) => {
+                      const max = parseInt(e.target.value);
+                      updateConfig({
+                        execution_limits: {
+                          ...config.execution_limits,
+                          max_running: max > 0 ? max : null,
+                        },
+                      });
+                    }}
+                  />
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="max-running-per-project">
+                    Running attempts per project
+                  </Label>
+                  <Input
+                    id="max-running-per-project"
+                    type="number"
+                    min={0}
+                    placeholder="No limit"
+                    value={
+                      config.execution_limits.max_running_per_project ?? ''
+                    }
+                    onChange={(e) => {
+                      const max = parseInt(e.target.value);
+                      updateConfig({
+                        execution_limits: {
+                          ...config.execution_limits,
+                          max_running_per_project: max > 0 ? max : null,
+                        },
+                      });
+                    }}
+                  />
+                </div>
+                <p className="col-span-2 text-sm text-muted-foreground">
+                  Attempts started beyond these limits are queued and start as
+                  running ones finish.
+                </p>
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="preemption">Higher priority attempts</Label>
+                <Select
+                  value={config.execution_limits.preemption}
+                  onValueChange={(value: PreemptionPolicy) =>
+                    updateConfig({
+                      execution_limits: {
+                        ...config.execution_limits,
+                        preemption: value,
+                      },
+                    })
+                  }
+                >
+                  <SelectTrigger id="preemption">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectItem value="never">Wait for a free slot</SelectItem>
+                    <SelectItem value="pause_lower_priority">
+                      Pause lower priority runs
+                    </SelectItem>
+                  </SelectContent>
+                </Select>
+                <p className="text-sm text-muted-foreground">
+                  The queue is ordered by task priority. A paused run is queued
+                  again and resumes when there's a slot for it.
+                </p>
+              </div>
+              <div className="flex items-center space-x-2">
+                <Checkbox
+                  id="resume-interrupted"
+                  checked={config.resume_interrupted_runs}
+                  onCheckedChange={(checked: boolean) =>
+                    updateConfig({ resume_interrupted_runs: checked })
+                  }
+                />
+                <div className="space-y-0.5">
+                  <Label
+                    htmlFor="resume-interrupted"
+                    className="cursor-pointer"
+                  >
+                    Resume interrupted runs
+                  </Label>
                   <p className="text-sm text-muted-foreground">
-                    Enter the command to run your custom editor. Use spaces for
-                    arguments (e.g., "code --wait").
+                    Coding agents cut off by a restart of the server go on as
+                    follow-ups when it starts again, rather than waiting for
+                    review.
                   </p>
                 </div>
-              )}
+              </div>
+              <div className="flex items-center space-x-2">
+                <Checkbox
+                  id="suggest-labels"
+                  checked={config.suggest_labels}
+                  onCheckedChange={(checked: boolean) =>
+                    updateConfig({ suggest_labels: checked })
+                  }
+                />
+                <div className="space-y-0.5">
+                  <Label htmlFor="suggest-labels" className="cursor-pointer">
+                    Suggest Labels
+                  </Label>
+                  <p className="text-sm text-muted-foreground">
+                    Propose labels such as bug, feature or the affected area
+                    when a task is created.
+                  </p>
+                </div>
+              </div>
             </CardContent>
           </Card>
 
           <Card>
             <CardHeader>
-              <CardTitle className="flex items-center gap-2">
-                <Key className="h-5 w-5" />
-                GitHub Integration
-              </CardTitle>
+              <CardTitle>Commits</CardTitle>
               <CardDescription>
-                Configure GitHub settings for creating pull requests from task
-                attempts.
+                What happens to an attempt's branch when the coding agent or
+                cleanup script finishes.
               </CardDescription>
             </CardHeader>
             <CardContent className="space-y-4">
-              <div className="space-y-2">
-                <Label htmlFor="github-enterprise-url">GitHub Enterprise URL (Optional)</Label>
-                <Input
-                  id="github-enterprise-url"
-                  type="url"
-                  placeholder="https://github.company.com/api/v3"
-                  value={config.github.enterprise_url || ''}
-                  onChange={(e) =>
+              <div className="flex items-center space-x-2">
+                <Checkbox
+                  id="auto-commit"
+                  checked={config.post_execution_git.auto_commit}
+                  onCheckedChange={(checked: boolean) =>
                     updateConfig({
-                      github: {
-                        ...config.github,
-                        enterprise_url: e.target.value || null,
+                      post_execution_git: {
+                        ...config.post_execution_git,
+                        auto_commit: checked,
                       },
                     })
                   }
                 />
-                <p className="text-sm text-muted-foreground">
-                  For GitHub Enterprise Server, enter your API base URL (e.g., https://github.company.com/api/v3).
-                  Leave empty for GitHub.com.
-                </p>
+                <div className="space-y-0.5">
+                  <Label htmlFor="auto-commit" className="cursor-pointer">
+                    Commit changes after each run
+                  </Label>
+                  <p className="text-sm text-muted-foreground">
+                    Uses the project's commit message template, which names the
+                    task by default.
+                  </p>
+                </div>
               </div>
-              <div className="space-y-2">
-                <Label htmlFor="github-token">Personal Access Token</Label>
-                <Input
-                  id="github-token"
-                  type="password"
-                  placeholder="ghp_xxxxxxxxxxxxxxxxxxxx"
-                  value={config.github.pat || ''}
-                  onChange={(e) =>
+              <div className="flex items-center space-x-2">
+                <Checkbox
+                  id="auto-push"
+                  checked={config.post_execution_git.push}
+                  onCheckedChange={(checked: boolean) =>
                     updateConfig({
-                      github: {
-                        ...config.github,
-                        pat: e.target.value || null,
+                      post_execution_git: {
+                        ...config.post_execution_git,
+                        push: checked,
                       },
                     })
                   }
                 />
-                <p className="text-sm text-muted-foreground">
-                  GitHub Personal Access Token with 'repo' permissions. Required
-                  for creating pull requests.{' '}
-                  <a
-                    href={config.github.enterprise_url ?
-                      `${config.github.enterprise_url.replace('/api/v3', '')}/settings/tokens` :
-                      "https://github.com/settings/tokens"
-                    }
-                    target="_blank"
-                    rel="noopener noreferrer"
-                    className="text-blue-600 hover:underline"
-                  >
-                    Create token here
-                  </a>
-                </p>
-              </div>
-              {config && isAuthenticated ? (
-                <div className="flex items-center justify-between gap-4">
-                  <div>
-                    <Label>Signed in as</Label>
-                    <div className="text-lg font-mono">
-                      {config.github.username}
-                    </div>
-                  </div>
-                  <Button variant="outline" onClick={handleLogout}>
-                    Log out
-                  </Button>
+                <div className="space-y-0.5">
+                  <Label htmlFor="auto-push" className="cursor-pointer">
+                    Push the branch after a successful run
+                  </Label>
+                  <p className="text-sm text-muted-foreground">
+                    Pushes to origin with your GitHub token.
+                  </p>
                 </div>
-              ) : (
-                <Button onClick={() => setShowGitHubLogin(true)}>
-                  Sign in with GitHub
-                </Button>
-              )}
-              <GitHubLoginDialog
-                open={showGitHubLogin}
-                onOpenChange={setShowGitHubLogin}
-              />
-              <div className="space-y-2 pt-4">
-                <Label htmlFor="default-pr-base">Default PR Base Branch</Label>
-                <Input
-                  id="default-pr-base"
-                  placeholder="main"
-                  value={config.github.default_pr_base || ''}
-                  onChange={(e) =>
-                    updateConfig({
-                      github: {
-                        ...config.github,
-                        default_pr_base: e.target.value || null,
-                      },
-                    })
-                  }
-                />
-                <p className="text-sm text-muted-foreground">
-                  Default base branch for pull requests. Defaults to 'main' if
-                  not specified.
-                </p>
               </div>
             </CardContent>
           </Card>
 
           <Card>
             <CardHeader>
-              <CardTitle>Notifications</CardTitle>
+              <CardTitle>Verification</CardTitle>
               <CardDescription>
-                Configure how you receive notifications about task completion.
+                How a project's verification commands run after the coding
+                agent finishes. Set the commands in each project's settings.
               </CardDescription>
             </CardHeader>
             <CardContent className="space-y-4">
               <div className="flex items-center space-x-2">
                 <Checkbox
-                  id="sound-alerts"
-                  checked={config.sound_alerts}
+                  id="verification-follow-up"
+                  checked={config.verification.follow_up_on_failure}
                   onCheckedChange={(checked: boolean) =>
-                    updateConfig({ sound_alerts: checked })
+                    updateConfig({
+                      verification: {
+                        ...config.verification,
+                        follow_up_on_failure: checked,
+                      },
+                    })
                   }
                 />
                 <div className="space-y-0.5">
-                  <Label htmlFor="sound-alerts" className="cursor-pointer">
+                  <Label
+                    htmlFor="verification-follow-up"
+                    className="cursor-pointer"
+                  >
+                    Label failed tasks for follow-up
+                  </Label>
+                  <p className="text-sm text-muted-foreground">
+                    Adds needs-follow-up to a task whose verification fails,
+                    and removes it once the commands pass again.
+                  </p>
+                </div>
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="verification-timeout">
+                  Timeout per command (seconds)
+                </Label>
+                <Input
+                  id="verification-timeout"
+                  type="number"
+                  min={1}
+                  value={config.verification.timeout_secs}
+                  onChange={(e) =>
+                    updateConfig({
+                      verification: {
+                        ...config.verification,
+                        timeout_secs: Math.max(
+                          1,
+                          parseInt(e.target.value) || 1
+                        ),
+                      },
+                    })
+                  }
+                />
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="verification-auto-fix">
+                  Automatic fix attempts
+                </Label>
+                <Input
+                  id="verification-auto-fix"
+                  type="number"
+                  min={0}
+                  value={config.verification.auto_fix_attempts}
+                  onChange={(e) =>
+                    updateConfig({
+                      verification: {
+                        ...config.verification,
+                        auto_fix_attempts: Math.max(
+                          0,
+                          parseInt(e.target.value) || 0
+                        ),
+                      },
+                    })
+                  }
+                />
+                <p className="text-sm text-muted-foreground">
+                  Follow-ups sent to the agent with the failing output, asking
+                  it to fix the problem, before the attempt goes to review. 0
+                  turns them off.
+                </p>
+              </div>
+            </CardContent>
+          </Card>
+
+          <Card>
+            <CardHeader>
+              <CardTitle>Review Gate</CardTitle>
+              <CardDescription>
+                Have a reviewer approve an attempt's changes before they are
+                merged.
+              </CardDescription>
+            </CardHeader>
+            <CardContent className="space-y-4">
+              <div className="flex items-center space-x-2">
+                <Checkbox
+                  id="review-gate-require"
+                  checked={config.review_gate.require_approval}
+                  onCheckedChange={(checked: boolean) =>
+                    updateConfig({
+                      review_gate: {
+                        ...config.review_gate,
+                        require_approval: checked,
+                      },
+                    })
+                  }
+                />
+                <div className="space-y-0.5">
+                  <Label
+                    htmlFor="review-gate-require"
+                    className="cursor-pointer"
+                  >
+                    Require approval to merge
+                  </Label>
+                  <p className="text-sm text-muted-foreground">
+                    Approving merges the attempt; rejecting can send the
+                    reviewer's comments back to the agent.
+                  </p>
+                </div>
+              </div>
+              <div className="flex items-center space-x-2">
+                <Checkbox
+                  id="review-gate-push"
+                  checked={config.review_gate.push_on_approve}
+                  onCheckedChange={(checked: boolean) =>
+                    updateConfig({
+                      review_gate: {
+                        ...config.review_gate,
+                        push_on_approve: checked,
+                      },
+                    })
+                  }
+                />
+                <Label htmlFor="review-gate-push" className="cursor-pointer">
+                  Push the base branch after an approved merge
+                </Label>
+              </div>
+            </CardContent>
+          </Card>
+
+          <Card>
+            <CardHeader>
+              <CardTitle>Sandbox</CardTitle>
+              <CardDescription>
+                Run coding agents under bubblewrap on Linux, so they can only
+                write to their worktree and their own settings. Projects can
+                override this.
+              </CardDescription>
+            </CardHeader>
+            <CardContent className="space-y-4">
+              <div className="flex items-center space-x-2">
+                <Checkbox
+                  id="sandbox-enabled"
+                  checked={config.sandbox.enabled}
+                  onCheckedChange={(checked: boolean) =>
+                    updateConfig({
+                      sandbox: { ...config.sandbox, enabled: checked },
+                    })
+                  }
+                />
+                <Label htmlFor="sandbox-enabled" className="cursor-pointer">
+                  Sandbox coding agents
+                </Label>
+              </div>
+              <div className="flex items-center space-x-2">
+                <Checkbox
+                  id="sandbox-deny-network"
+                  checked={config.sandbox.deny_network}
+                  disabled={!config.sandbox.enabled}
+                  onCheckedChange={(checked: boolean) =>
+                    updateConfig({
+                      sandbox: { ...config.sandbox, deny_network: checked },
+                    })
+                  }
+                />
+                <div className="space-y-0.5">
+                  <Label
+                    htmlFor="sandbox-deny-network"
+                    className="cursor-pointer"
+                  >
+                    Deny network access
+                  </Label>
+                  <p className="text-sm text-muted-foreground">
+                    Most agents need the network to reach their model.
+                  </p>
+                </div>
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="sandbox-executors">Agents to sandbox</Label>
+                <Textarea
+                  id="sandbox-executors"
+                  rows={2}
+                  placeholder="claude"
+                  className="font-mono text-sm"
+                  disabled={!config.sandbox.enabled}
+                  value={config.sandbox.executors.join('\n')}
+                  onChange={(e) =>
+                    updateConfig({
+                      sandbox: {
+                        ...config.sandbox,
+                        executors: e.target.value.split('\n'),
+                      },
+                    })
+                  }
+                />
+                <p className="text-sm text-muted-foreground">
+                  One agent per line, e.g. claude or codex. Leave empty to
+                  sandbox every coding agent.
+                </p>
+              </div>
+            </CardContent>
+          </Card>
+
+          <Card>
+            <CardHeader>
+              <CardTitle>Shell Environment</CardTitle>
+              <CardDescription>
+                Start agents and scripts from the environment of your login
+                shell, so tools installed with nvm, pyenv or rustup are found
+                when the server wasn't started from a terminal. Projects can
+                override this.
+              </CardDescription>
+            </CardHeader>
+            <CardContent className="space-y-4">
+              <div className="space-y-2">
+                <Label htmlFor="login-shell">Login shell environment</Label>
+                <Select
+                  value={config.shell_environment.login_shell}
+                  onValueChange={(value: LoginShellMode) =>
+                    updateConfig({
+                      shell_environment: {
+                        ...config.shell_environment,
+                        login_shell: value,
+                      },
+                    })
+                  }
+                >
+                  <SelectTrigger id="login-shell">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectItem value="auto">
+                      When not started from a terminal
+                    </SelectItem>
+                    <SelectItem value="always">Always</SelectItem>
+                    <SelectItem value="never">Never</SelectItem>
+                  </SelectContent>
+                </Select>
+                <p className="text-sm text-muted-foreground">
+                  Not used on Windows. Loaded once, so restart the server to
+                  pick up changes to your shell profile.
+                </p>
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="env-file">Env file</Label>
+                <Input
+                  id="env-file"
+                  placeholder="~/.config/vibe-kanban/env"
+                  value={config.shell_environment.env_file ?? ''}
+                  onChange={(e) =>
+                    updateConfig({
+                      shell_environment: {
+                        ...config.shell_environment,
+                        env_file: e.target.value || null,
+                      },
+                    })
+                  }
+                />
+                <p className="text-sm text-muted-foreground">
+                  KEY=value lines loaded on top of the login shell's
+                  environment. A relative path is from the attempt's worktree.
+                </p>
+              </div>
+            </CardContent>
+          </Card>
+
+          <Card>
+            <CardHeader>
+              <CardTitle>Resource Limits</CardTitle>
+              <CardDescription>
+                Cap the memory, CPU and open files of each coding agent and
+                script, together with whatever it starts. A run that goes over
+                its memory limit is stopped.
+              </CardDescription>
+            </CardHeader>
+            <CardContent className="space-y-4">
+              <div className="grid grid-cols-3 gap-4">
+                <div className="space-y-2">
+                  <Label htmlFor="limit-memory">Memory (MB)</Label>
+                  <Input
+                    id="limit-memory"
+                    type="number"
+                    min={1}
+                    placeholder="No limit"
+                    value={config.resource_limits.max_memory_mb ?? ''}
+                    onChange={(e) => {
+                      const value = parseInt(e.target.value);
+                      updateConfig({
+                        resource_limits: {
+                          ...config.resource_limits,
+                          max_memory_mb: value > 0 ? value : null,
+                        },
+                      });
+                    }}
+                  />
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="limit-cpu-shares">CPU shares</Label>
+                  <Input
+                    id="limit-cpu-shares"
+                    type="number"
+                    min={1}
+                    placeholder="1024"
+                    value={config.resource_limits.cpu_shares ?? ''}
+                    onChange={(e) => {
+                      const value = parseInt(e.target.value);
+                      updateConfig({
+                        resource_limits: {
+                          ...config.resource_limits,
+                          cpu_shares: value > 0 ? value : null,
+                        },
+                      });
+                    }}
+                  />
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="limit-open-files">Open files</Label>
+                  <Input
+                    id="limit-open-files"
+                    type="number"
+                    min={1}
+                    placeholder="No limit"
+                    value={config.resource_limits.max_open_files ?? ''}
+                    onChange={(e) => {
+                      const value = parseInt(e.target.value);
+                      updateConfig({
+                        resource_limits: {
+                          ...config.resource_limits,
+                          max_open_files: value > 0 ? value : null,
+                        },
+                      });
+                    }}
+                  />
+                </div>
+              </div>
+              <p className="text-sm text-muted-foreground">
+                CPU shares weigh runs against each other, 1024 being the
+                default. On Linux, set VIBE_KANBAN_CGROUP to a delegated cgroup
+                to have the kernel enforce memory and CPU.
+              </p>
+            </CardContent>
+          </Card>
+
+          <Card>
+            <CardHeader>
+              <CardTitle>Log Redaction</CardTitle>
+              <CardDescription>
+                Replace API keys, tokens and AWS credentials in agent output
+                with [REDACTED] before it's stored.
+              </CardDescription>
+            </CardHeader>
+            <CardContent className="space-y-4">
+              <div className="flex items-center space-x-2">
+                <Checkbox
+                  id="log-redaction"
+                  checked={config.log_redaction.enabled}
+                  onCheckedChange={(checked: boolean) =>
+                    updateConfig({
+                      log_redaction: {
+                        ...config.log_redaction,
+                        enabled: checked,
+                      },
+                    })
+                  }
+                />
+                <Label htmlFor="log-redaction" className="cursor-pointer">
+                  Redact credentials
+                </Label>
+              </div>
+              <div className="flex items-center space-x-2">
+                <Checkbox
+                  id="log-redaction-entropy"
+                  checked={config.log_redaction.detect_high_entropy}
+                  disabled={!config.log_redaction.enabled}
+                  onCheckedChange={(checked: boolean) =>
+                    updateConfig({
+                      log_redaction: {
+                        ...config.log_redaction,
+                        detect_high_entropy: checked,
+                      },
+                    })
+                  }
+                />
+                <div className="space-y-0.5">
+                  <Label
+                    htmlFor="log-redaction-entropy"
+                    className="cursor-pointer"
+                  >
+                    Redact random-looking tokens
+                  </Label>
+                  <p className="text-sm text-muted-foreground">
+                    Catches keys of unknown formats, at the risk of hiding the
+                    odd long identifier.
+                  </p>
+                </div>
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="log-redaction-patterns">Extra patterns</Label>
+                <Textarea
+                  id="log-redaction-patterns"
+                  rows={3}
+                  placeholder="internal-token-[0-9a-f]{32}"
+                  className="font-mono text-sm"
+                  disabled={!config.log_redaction.enabled}
+                  value={config.log_redaction.extra_patterns.join('\n')}
+                  onChange={(e) =>
+                    updateConfig({
+                      log_redaction: {
+                        ...config.log_redaction,
+                        extra_patterns: e.target.value.split('\n'),
+                      },
+                    })
+                  }
+                />
+                <p className="text-sm text-muted-foreground">
+                  One regex per line. Of a pattern with a group named secret,
+                  only that group is replaced.
+                </p>
+              </div>
+            </CardContent>
+          </Card>
+
+          <Card>
+            <CardHeader>
+              <CardTitle>Log Storage</CardTitle>
+              <CardDescription>
+                Keep the beginning and the latest output of long runs, and drop
+                what was in between.
+              </CardDescription>
+            </CardHeader>
+            <CardContent className="space-y-4">
+              <div className="grid grid-cols-2 gap-4">
+                <div className="space-y-2">
+                  <Label htmlFor="log-storage-max">Limit per stream (KB)</Label>
+                  <Input
+                    id="log-storage-max"
+                    type="number"
+                    min={0}
+                    placeholder="No limit"
+                    value={config.log_storage.max_stream_kb ?? ''}
+                    onChange={(e) => {
+                      const kb = parseInt(e.target.value);
+                      updateConfig({
+                        log_storage: {
+                          ...config.log_storage,
+                          max_stream_kb: kb > 0 ? kb : null,
+                        },
+                      });
+                    }}
+                  />
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="log-storage-head">
+                    Kept from the beginning (KB)
+                  </Label>
+                  <Input
+                    id="log-storage-head"
+                    type="number"
+                    min={0}
+                    disabled={config.log_storage.max_stream_kb === null}
+                    value={config.log_storage.head_kb}
+                    onChange={(e) =>
+                      updateConfig({
+                        log_storage: {
+                          ...config.log_storage,
+                          head_kb: Math.max(0, parseInt(e.target.value) || 0),
+                        },
+                      })
+                    }
+                  />
+                </div>
+              </div>
+              <div className="flex items-center space-x-2">
+                <Checkbox
+                  id="log-storage-offload"
+                  checked={config.log_storage.offload_full_logs}
+                  onCheckedChange={(checked: boolean) =>
+                    updateConfig({
+                      log_storage: {
+                        ...config.log_storage,
+                        offload_full_logs: checked,
+                      },
+                    })
+                  }
+                />
+                <div className="space-y-0.5">
+                  <Label
+                    htmlFor="log-storage-offload"
+                    className="cursor-pointer"
+                  >
+                    Keep full logs on disk
+                  </Label>
+                  <p className="text-sm text-muted-foreground">
+                    Every run's complete output is also written to the app's
+                    data directory, where the raw log download reads it from.
+                  </p>
+                </div>
+              </div>
+            </CardContent>
+          </Card>
+
+          <Card>
+            <CardHeader>
+              <CardTitle>Sign-in</CardTitle>
+              <CardDescription>
+                Require an account on a server shared by a team. Admins manage
+                accounts and give users a role in each project.
+              </CardDescription>
+            </CardHeader>
+            <CardContent className="space-y-4">
+              <div className="flex items-center space-x-2">
+                <Checkbox
+                  id="auth-enabled"
+                  checked={config.auth.enabled}
+                  onCheckedChange={(checked: boolean) =>
+                    updateConfig({
+                      auth: { ...config.auth, enabled: checked },
+                    })
+                  }
+                />
+                <div className="space-y-0.5">
+                  <Label htmlFor="auth-enabled" className="cursor-pointer">
+                    Require sign-in
+                  </Label>
+                  <p className="text-sm text-muted-foreground">
+                    Without an account yet, the first one registered becomes an
+                    admin.
+                  </p>
+                </div>
+              </div>
+              <div className="grid grid-cols-2 gap-4">
+                <div className="space-y-2">
+                  <Label htmlFor="auth-session-hours">
+                    Session length (hours)
+                  </Label>
+                  <Input
+                    id="auth-session-hours"
+                    type="number"
+                    min={1}
+                    value={config.auth.session_hours}
+                    onChange={(e) =>
+                      updateConfig({
+                        auth: {
+                          ...config.auth,
+                          session_hours: Math.max(
+                            1,
+                            parseInt(e.target.value) || 1
+                          ),
+                        },
+                      })
+                    }
+                  />
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="auth-max-session-days">
+                    Sign in again after (days)
+                  </Label>
+                  <Input
+                    id="auth-max-session-days"
+                    type="number"
+                    min={1}
+                    value={config.auth.max_session_days}
+                    onChange={(e) =>
+                      updateConfig({
+                        auth: {
+                          ...config.auth,
+                          max_session_days: Math.max(
+                            1,
+                            parseInt(e.target.value) || 1
+                          ),
+                        },
+                      })
+                    }
+                  />
+                  <p className="text-sm text-muted-foreground">
+                    Refreshing keeps a session going until then.
+                  </p>
+                </div>
+              </div>
+              <div className="grid grid-cols-2 gap-4">
+                <div className="space-y-2">
+                  <Label htmlFor="oidc-issuer">OIDC issuer URL</Label>
+                  <Input
+                    id="oidc-issuer"
+                    placeholder="https://accounts.google.com"
+                    value={config.auth.oidc.issuer_url ?? ''}
+                    onChange={(e) =>
+                      updateConfig({
+                        auth: {
+                          ...config.auth,
+                          oidc: {
+                            ...config.auth.oidc,
+                            issuer_url: e.target.value || null,
+                          },
+                        },
+                      })
+                    }
+                  />
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="oidc-client-id">OIDC client ID</Label>
+                  <Input
+                    id="oidc-client-id"
+                    value={config.auth.oidc.client_id}
+                    onChange={(e) =>
+                      updateConfig({
+                        auth: {
+                          ...config.auth,
+                          oidc: {
+                            ...config.auth.oidc,
+                            client_id: e.target.value,
+                          },
+                        },
+                      })
+                    }
+                  />
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="oidc-client-secret">OIDC client secret</Label>
+                  <Input
+                    id="oidc-client-secret"
+                    type="password"
+                    value={config.auth.oidc.client_secret ?? ''}
+                    onChange={(e) =>
+                      updateConfig({
+                        auth: {
+                          ...config.auth,
+                          oidc: {
+                            ...config.auth.oidc,
+                            client_secret: e.target.value || null,
+                          },
+                        },
+                      })
+                    }
+                  />
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="oidc-redirect">OIDC redirect URL</Label>
+                  <Input
+                    id="oidc-redirect"
+                    placeholder="http://localhost:3000/api/auth/oidc/callback"
+                    value={config.auth.oidc.redirect_url ?? ''}
+                    onChange={(e) =>
+                      updateConfig({
+                        auth: {
+                          ...config.auth,
+                          oidc: {
+                            ...config.auth.oidc,
+                            redirect_url: e.target.value || null,
+                          },
+                        },
+                      })
+                    }
+                  />
+                </div>
+              </div>
+            </CardContent>
+          </Card>
+
+          <Card>
+            <CardHeader>
+              <CardTitle>Editor</CardTitle>
+              <CardDescription>
+                Configure which editor to open when viewing task attempts.
+              </CardDescription>
+            </CardHeader>
+            <CardContent className="space-y-4">
+              <div className="space-y-2">
+                <Label htmlFor="editor">Preferred Editor</Label>
+                <Select
+                  value={config.editor.editor_type}
+                  onValueChange={(value: EditorType) =>
+                    updateConfig({
+                      editor: {
+                        ...config.editor,
+                        editor_type: value,
+                        custom_command:
+                          value === 'custom'
+                            ? config.editor.custom_command
+                            : null,
+                      },
+                    })
+                  }
+                >
+                  <SelectTrigger id="editor">
+                    <SelectValue placeholder="Select editor" />
+                  </SelectTrigger>
+                  <SelectContent>
+                    {EDITOR_TYPES.map((type) => (
+                      <SelectItem key={type} value={type}>
+                        {EDITOR_LABELS[type]}
+                      </SelectItem>
+                    ))}
+                  </SelectContent>
+                </Select>
+                <p className="text-sm text-muted-foreground">
+                  Choose your preferred code editor for opening task attempts.
+                </p>
+              </div>
+
+              {config.editor.editor_type === 'custom' && (
+                <div className="space-y-2">
+                  <Label htmlFor="custom-command">Custom Command</Label>
+                  <Input
+                    id="custom-command"
+                    placeholder="e.g., code, subl, vim"
+                    value={config.editor.custom_command || ''}
+                    onChange={(e) =>
+                      updateConfig({
+                        editor: {
+                          ...config.editor,
+                          custom_command: e.target.value || null,
+                        },
+                      })
+                    }
+                  />
+                  <p className="text-sm text-muted-foreground">
+                    Enter the command to run your custom editor. Use spaces for
+                    arguments (e.g., "code --wait").
+                  </p>
+                </div>
+              )}
+            </CardContent>
+          </Card>
+
+          <Card>
+            <CardHeader>
+              <CardTitle className="flex items-center gap-2">
+                <Key className="h-5 w-5" />
+                GitHub Integration
+              </CardTitle>
+              <CardDescription>
+                Configure GitHub settings for creating pull requests from task
+                attempts.
+              </CardDescription>
+            </CardHeader>
+            <CardContent className="space-y-4">
+              <div className="space-y-2">
+                <Label htmlFor="github-enterprise-url">GitHub Enterprise URL (Optional)</Label>
+                <Input
+                  id="github-enterprise-url"
+                  type="url"
+                  placeholder="https://github.company.com/api/v3"
+                  value={config.github.enterprise_url || ''}
+                  onChange={(e) =>
+                    updateConfig({
+                      github: {
+                        ...config.github,
+                        enterprise_url: e.target.value || null,
+                      },
+                    })
+                  }
+                />
+                <p className="text-sm text-muted-foreground">
+                  For GitHub Enterprise Server, enter your API base URL (e.g., https://github.company.com/api/v3).
+                  Leave empty for GitHub.com.
+                </p>
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="github-token">Personal Access Token</Label>
+                <Input
+                  id="github-token"
+                  type="password"
+                  placeholder="ghp_xxxxxxxxxxxxxxxxxxxx"
+                  value={config.github.pat || ''}
+                  onChange={(e) =>
+                    updateConfig({
+                      github: {
+                        ...config.github,
+                        pat: e.target.value || null,
+                      },
+                    })
+                  }
+                />
+                <p className="text-sm text-muted-foreground">
+                  GitHub Personal Access Token with 'repo' permissions. Required
+                  for creating pull requests.{' '}
+                  <a
+                    href={config.github.enterprise_url ?
+                      `${config.github.enterprise_url.replace('/api/v3', '')}/settings/tokens` :
+                      "https://github.com/settings/tokens"
+                    }
+                    target="_blank"
+                    rel="noopener noreferrer"
+                    className="text-blue-600 hover:underline"
+                  >
+                    Create token here
+                  </a>
+                </p>
+              </div>
+              {config && isAuthenticated ? (
+                <div className="flex items-center justify-between gap-4">
+                  <div>
+                    <Label>Signed in as</Label>
+                    <div className="text-lg font-mono">
+                      {config.github.username}
+                    </div>
+                  </div>
+                  <Button variant="outline" onClick={handleLogout}>
+                    Log out
+                  </Button>
+                </div>
+              ) : (
+                <Button onClick={() => setShowGitHubLogin(true)}>
+                  Sign in with GitHub
+                </Button>
+              )}
+              <GitHubLoginDialog
+                open={showGitHubLogin}
+                onOpenChange={setShowGitHubLogin}
+              />
+              <div className="space-y-2 pt-4">
+                <Label htmlFor="default-pr-base">Default PR Base Branch</Label>
+                <Input
+                  id="default-pr-base"
+                  placeholder="main"
+                  value={config.github.default_pr_base || ''}
+                  onChange={(e) =>
+                    updateConfig({
+                      github: {
+                        ...config.github,
+                        default_pr_base: e.target.value || null,
+                      },
+                    })
+                  }
+                />
+                <p className="text-sm text-muted-foreground">
+                  Default base branch for pull requests. Defaults to 'main' if
+                  not specified.
+                </p>
+              </div>
+            </CardContent>
+          </Card>
+
+          <Card>
+            <CardHeader>
+              <CardTitle>Notion Integration</CardTitle>
+              <CardDescription>
+                Mirror project tasks into Notion databases. Link a database
+                from a project's settings.
+              </CardDescription>
+            </CardHeader>
+            <CardContent className="space-y-4">
+              <div className="space-y-2">
+                <Label htmlFor="notion-token">Integration Token</Label>
+                <Input
+                  id="notion-token"
+                  type="password"
+                  placeholder="secret_xxxxxxxxxxxxxxxxxxxx"
+                  value={config.notion.token || ''}
+                  onChange={(e) =>
+                    updateConfig({
+                      notion: {
+                        ...config.notion,
+                        token: e.target.value || null,
+                      },
+                    })
+                  }
+                />
+                <p className="text-sm text-muted-foreground">
+                  The secret of an internal integration. Share each database
+                  you want to sync with the integration.{' '}
+                  <a
+                    href="https://www.notion.so/my-integrations"
+                    target="_blank"
+                    rel="noopener noreferrer"
+                    className="text-blue-600 hover:underline"
+                  >
+                    Create an integration here
+                  </a>
+                </p>
+              </div>
+            </CardContent>
+          </Card>
+
+          <Card>
+            <CardHeader>
+              <CardTitle>Jira and Linear</CardTitle>
+              <CardDescription>
+                Credentials for importing issues as tasks. Start an import from
+                a project's task board.
+              </CardDescription>
+            </CardHeader>
+            <CardContent className="space-y-4">
+              <div className="space-y-2">
+                <Label htmlFor="jira-base-url">Jira Site URL</Label>
+                <Input
+                  id="jira-base-url"
+                  placeholder="https://acme.atlassian.net"
+                  value={config.jira.base_url || ''}
+                  onChange={(e) =>
+                    updateConfig({
+                      jira: {
+                        ...config.jira,
+                        base_url: e.target.value || null,
+                      },
+                    })
+                  }
+                />
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="jira-email">Jira Email</Label>
+                <Input
+                  id="jira-email"
+                  placeholder="you@example.com"
+                  value={config.jira.email || ''}
+                  onChange={(e) =>
+                    updateConfig({
+                      jira: {
+                        ...config.jira,
+                        email: e.target.value || null,
+                      },
+                    })
+                  }
+                />
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="jira-api-token">Jira API Token</Label>
+                <Input
+                  id="jira-api-token"
+                  type="password"
+                  value={config.jira.api_token || ''}
+                  onChange={(e) =>
+                    updateConfig({
+                      jira: {
+                        ...config.jira,
+                        api_token: e.target.value || null,
+                      },
+                    })
+                  }
+                />
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="linear-api-key">Linear API Key</Label>
+                <Input
+                  id="linear-api-key"
+                  type="password"
+                  placeholder="lin_api_xxxxxxxxxxxxxxxx"
+                  value={config.linear.api_key || ''}
+                  onChange={(e) =>
+                    updateConfig({
+                      linear: {
+                        ...config.linear,
+                        api_key: e.target.value || null,
+                      },
+                    })
+                  }
+                />
+              </div>
+              <p className="text-sm text-muted-foreground">
+                Create a Jira API token at
+                id.atlassian.com/manage-profile/security/api-tokens and a Linear
+                API key under Settings → Security & access in Linear.
+              </p>
+            </CardContent>
+          </Card>
+
+          <Card>
+            <CardHeader>
+              <CardTitle>Similar Task Suggestions</CardTitle>
+              <CardDescription>
+                Use an embeddings model to point out existing tasks that look
+                like the one you are creating.
+              </CardDescription>
+            </CardHeader>
+            <CardContent className="space-y-4">
+              <div className="space-y-2">
+                <Label htmlFor="embeddings-url">Embeddings API URL</Label>
+                <Input
+                  id="embeddings-url"
+                  placeholder="https://api.openai.com/v1"
+                  value={config.embeddings.base_url || ''}
+                  onChange={(e) =>
+                    updateConfig({
+                      embeddings: {
+                        ...config.embeddings,
+                        base_url: e.target.value || null,
+                      },
+                    })
+                  }
+                />
+                <p className="text-sm text-muted-foreground">
+                  Any OpenAI-compatible endpoint, including a local model
+                  server such as Ollama (http://localhost:11434/v1). Leave
+                  empty to turn suggestions off.
+                </p>
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="embeddings-model">Model</Label>
+                <Input
+                  id="embeddings-model"
+                  placeholder="text-embedding-3-small"
+                  value={config.embeddings.model}
+                  onChange={(e) =>
+                    updateConfig({
+                      embeddings: {
+                        ...config.embeddings,
+                        model: e.target.value,
+                      },
+                    })
+                  }
+                />
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="embeddings-key">API Key</Label>
+                <Input
+                  id="embeddings-key"
+                  type="password"
+                  placeholder="Not needed for local servers"
+                  value={config.embeddings.api_key || ''}
+                  onChange={(e) =>
+                    updateConfig({
+                      embeddings: {
+                        ...config.embeddings,
+                        api_key: e.target.value || null,
+                      },
+                    })
+                  }
+                />
+              </div>
+            </CardContent>
+          </Card>
+
+          <Card>
+            <CardHeader>
+              <CardTitle>Language Model</CardTitle>
+              <CardDescription>
+                Used to turn long pasted descriptions and error dumps into a
+                concise title and a cleaned-up description.
+              </CardDescription>
+            </CardHeader>
+            <CardContent className="space-y-4">
+              <div className="space-y-2">
+                <Label htmlFor="llm-url">Chat Completions API URL</Label>
+                <Input
+                  id="llm-url"
+                  placeholder="https://api.openai.com/v1"
+                  value={config.llm.base_url || ''}
+                  onChange={(e) =>
+                    updateConfig({
+                      llm: {
+                        ...config.llm,
+                        base_url: e.target.value || null,
+                      },
+                    })
+                  }
+                />
+                <p className="text-sm text-muted-foreground">
+                  Any OpenAI-compatible endpoint, hosted or local. Leave empty
+                  to turn summarizing off.
+                </p>
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="llm-model">Model</Label>
+                <Input
+                  id="llm-model"
+                  placeholder="gpt-4o-mini"
+                  value={config.llm.model}
+                  onChange={(e) =>
+                    updateConfig({
+                      llm: {
+                        ...config.llm,
+                        model: e.target.value,
+                      },
+                    })
+                  }
+                />
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="llm-key">API Key</Label>
+                <Input
+                  id="llm-key"
+                  type="password"
+                  placeholder="Not needed for local servers"
+                  value={config.llm.api_key || ''}
+                  onChange={(e) =>
+                    updateConfig({
+                      llm: {
+                        ...config.llm,
+                        api_key: e.target.value || null,
+                      },
+                    })
+                  }
+                />
+              </div>
+            </CardContent>
+          </Card>
+
+          <Card>
+            <CardHeader>
+              <CardTitle>Pull Request Descriptions</CardTitle>
+              <CardDescription>
+                How the description of a pull request created from an attempt
+                is filled in.
+              </CardDescription>
+            </CardHeader>
+            <CardContent className="space-y-4">
+              <div className="space-y-2">
+                <Label htmlFor="pr-template">Template</Label>
+                <Textarea
+                  id="pr-template"
+                  className="font-mono text-sm"
+                  rows={10}
+                  value={config.pr_description.template}
+                  onChange={(e) =>
+                    updateConfig({
+                      pr_description: {
+                        ...config.pr_description,
+                        template: e.target.value,
+                      },
+                    })
+                  }
+                />
+                <p className="text-sm text-muted-foreground">
+                  Available placeholders: {'{{task_title}}'},{' '}
+                  {'{{task_description}}'}, {'{{summary}}'}, {'{{changes}}'},{' '}
+                  {'{{validation}}'}, {'{{branch}}'} and {'{{base_branch}}'}.
+                  Headings over empty sections are left out.
+                </p>
+              </div>
+              <div className="flex items-center space-x-2">
+                <Checkbox
+                  id="pr-summarize"
+                  checked={config.pr_description.summarize_with_llm}
+                  onCheckedChange={(checked: boolean) =>
+                    updateConfig({
+                      pr_description: {
+                        ...config.pr_description,
+                        summarize_with_llm: checked,
+                      },
+                    })
+                  }
+                />
+                <div className="space-y-0.5">
+                  <Label htmlFor="pr-summarize" className="cursor-pointer">
+                    Summarize with Language Model
+                  </Label>
+                  <p className="text-sm text-muted-foreground">
+                    Write the summary from the agent's messages using the
+                    language model above. Without one, the agent's last message
+                    is used.
+                  </p>
+                </div>
+              </div>
+            </CardContent>
+          </Card>
+
+          <Card>
+            <CardHeader>
+              <CardTitle>Stale Tasks</CardTitle>
+              <CardDescription>
+                Flag tasks on the board that sit in a column without any
+                activity, and send a nudge when they go stale.
+              </CardDescription>
+            </CardHeader>
+            <CardContent className="space-y-4">
+              <div className="flex items-center space-x-2">
+                <Checkbox
+                  id="stale-tasks"
+                  checked={config.stale_tasks.enabled}
+                  onCheckedChange={(checked: boolean) =>
+                    updateConfig({
+                      stale_tasks: { ...config.stale_tasks, enabled: checked },
+                    })
+                  }
+                />
+                <Label htmlFor="stale-tasks" className="cursor-pointer">
+                  Check for stale tasks
+                </Label>
+              </div>
+              {config.stale_tasks.enabled && (
+                <div className="grid grid-cols-2 gap-4">
+                  <div className="space-y-2">
+                    <Label htmlFor="stale-in-progress">
+                      In Progress (hours)
+                    </Label>
+                    <Input
+                      id="stale-in-progress"
+                      type="number"
+                      min={0}
+                      value={config.stale_tasks.in_progress_hours}
+                      onChange={(e) =>
+                        updateConfig({
+                          stale_tasks: {
+                            ...config.stale_tasks,
+                            in_progress_hours: Math.max(
+                              0,
+                              parseInt(e.target.value) || 0
+                            ),
+                          },
+                        })
+                      }
+                    />
+                  </div>
+                  <div className="space-y-2">
+                    <Label htmlFor="stale-in-review">In Review (hours)</Label>
+                    <Input
+                      id="stale-in-review"
+                      type="number"
+                      min={0}
+                      value={config.stale_tasks.in_review_hours}
+                      onChange={(e) =>
+                        updateConfig({
+                          stale_tasks: {
+                            ...config.stale_tasks,
+                            in_review_hours: Math.max(
+                              0,
+                              parseInt(e.target.value) || 0
+                            ),
+                          },
+                        })
+                      }
+                    />
+                  </div>
+                  <p className="col-span-2 text-sm text-muted-foreground">
+                    Set a column to 0 to leave it out of the check.
+                  </p>
+                  <div className="col-span-2 space-y-2">
+                    <Label htmlFor="stale-archive-after">
+                      Archive after (days)
+                    </Label>
+                    <Input
+                      id="stale-archive-after"
+                      type="number"
+                      min={1}
+                      placeholder="Never"
+                      value={config.stale_tasks.archive_after_days ?? ''}
+                      onChange={(e) => {
+                        const days = parseInt(e.target.value);
+                        updateConfig({
+                          stale_tasks: {
+                            ...config.stale_tasks,
+                            archive_after_days: days > 0 ? days : null,
+                          },
+                        });
+                      }}
+                    />
+                    <p className="text-sm text-muted-foreground">
+                      Stale tasks idle this long are taken off the board and
+                      their worktrees removed. Their branches are kept, and so
+                      are worktrees with uncommitted changes.
+                    </p>
+                  </div>
+                </div>
+              )}
+            </CardContent>
+          </Card>
+
+          <Card>
+            <CardHeader>
+              <CardTitle>Run Estimate</CardTitle>
+              <CardDescription>
+                Estimate the tokens and files an attempt will need before it
+                starts, and ask for confirmation when it looks large. The
+                language model above refines the estimate when configured.
+              </CardDescription>
+            </CardHeader>
+            <CardContent className="space-y-4">
+              <div className="flex items-center space-x-2">
+                <Checkbox
+                  id="run-estimate"
+                  checked={config.run_estimate.enabled}
+                  onCheckedChange={(checked: boolean) =>
+                    updateConfig({
+                      run_estimate: {
+                        ...config.run_estimate,
+                        enabled: checked,
+                      },
+                    })
+                  }
+                />
+                <Label htmlFor="run-estimate" className="cursor-pointer">
+                  Estimate attempts before starting them
+                </Label>
+              </div>
+              {config.run_estimate.enabled && (
+                <div className="grid grid-cols-2 gap-4">
+                  <div className="space-y-2">
+                    <Label htmlFor="estimate-tokens">
+                      Confirm above (tokens)
+                    </Label>
+                    <Input
+                      id="estimate-tokens"
+                      type="number"
+                      min={0}
+                      step={10000}
+                      value={config.run_estimate.confirm_above_tokens}
+                      onChange={(e) =>
+                        updateConfig({
+                          run_estimate: {
+                            ...config.run_estimate,
+                            confirm_above_tokens: Math.max(
+                              0,
+                              parseInt(e.target.value) || 0
+                            ),
+                          },
+                        })
+                      }
+                    />
+                  </div>
+                  <div className="space-y-2">
+                    <Label htmlFor="estimate-files">
+                      Confirm above (files)
+                    </Label>
+                    <Input
+                      id="estimate-files"
+                      type="number"
+                      min={0}
+                      value={config.run_estimate.confirm_above_files}
+                      onChange={(e) =>
+                        updateConfig({
+                          run_estimate: {
+                            ...config.run_estimate,
+                            confirm_above_files: Math.max(
+                              0,
+                              parseInt(e.target.value) || 0
+                            ),
+                          },
+                        })
+                      }
+                    />
+                  </div>
+                </div>
+              )}
+            </CardContent>
+          </Card>
+
+          <Card>
+            <CardHeader>
+              <CardTitle>Notifications</CardTitle>
+              <CardDescription>
+                Configure how you receive notifications about task completion.
+              </CardDescription>
+            </CardHeader>
+            <CardContent className="space-y-4">
+              <div className="flex items-center space-x-2">
+                <Checkbox
+                  id="sound-alerts"
+                  checked={config.sound_alerts}
+                  onCheckedChange={(checked: boolean) =>
+                    updateConfig({ sound_alerts: checked })
+                  }
+                />
+                <div className="space-y-0.5">
+                  <Label htmlFor="sound-alerts" className="cursor-pointer">
                     Sound Alerts
                   </Label>
                   <p className="text-sm text-muted-foreground">
@@ -480,6 +2060,246 @@ export function Settings() {
                   </p>
                 </div>
               </div>
+              {(config.sound_alerts || config.push_notifications) && (
+                <div className="space-y-2 ml-6">
+                  <Label>Notify me when</Label>
+                  {NOTIFICATION_EVENTS.map(({ key, label }) => (
+                    <div key={key} className="flex items-center space-x-2">
+                      <Checkbox
+                        id={`notify-${key}`}
+                        checked={config.notification_events[key]}
+                        onCheckedChange={(checked: boolean) =>
+                          updateConfig({
+                            notification_events: {
+                              ...config.notification_events,
+                              [key]: checked,
+                            },
+                          })
+                        }
+                      />
+                      <Label
+                        htmlFor={`notify-${key}`}
+                        className="cursor-pointer font-normal"
+                      >
+                        {label}
+                      </Label>
+                    </div>
+                  ))}
+                </div>
+              )}
+            </CardContent>
+          </Card>
+
+          <Card>
+            <CardHeader>
+              <CardTitle>Waiting on You</CardTitle>
+              <CardDescription>
+                Where to announce an execution that's waiting for you to
+                approve a command or review a plan, and who hears about it
+                again when nobody answers.
+              </CardDescription>
+            </CardHeader>
+            <CardContent className="space-y-4">
+              {(['notify', 'escalate_to'] as const).map((route) => (
+                <div key={route} className="space-y-2">
+                  <Label>
+                    {route === 'notify'
+                      ? 'Announce right away through'
+                      : 'Escalate through'}
+                  </Label>
+                  <div className="flex flex-wrap gap-4">
+                    {ATTENTION_CHANNELS.map(({ key, label }) => (
+                      <div key={key} className="flex items-center space-x-2">
+                        <Checkbox
+                          id={`attention-${route}-${key}`}
+                          checked={config.attention[route][key]}
+                          onCheckedChange={(checked: boolean) =>
+                            updateConfig({
+                              attention: {
+                                ...config.attention,
+                                [route]: {
+                                  ...config.attention[route],
+                                  [key]: checked,
+                                },
+                              },
+                            })
+                          }
+                        />
+                        <Label
+                          htmlFor={`attention-${route}-${key}`}
+                          className="cursor-pointer font-normal"
+                        >
+                          {label}
+                        </Label>
+                      </div>
+                    ))}
+                  </div>
+                </div>
+              ))}
+              <div className="space-y-2">
+                <Label htmlFor="attention-escalate-after">
+                  Escalate after (minutes)
+                </Label>
+                <Input
+                  id="attention-escalate-after"
+                  type="number"
+                  min={0}
+                  value={config.attention.escalate_after_minutes ?? ''}
+                  placeholder="Never"
+                  onChange={(e) =>
+                    updateConfig({
+                      attention: {
+                        ...config.attention,
+                        escalate_after_minutes: e.target.value
+                          ? Math.max(0, parseInt(e.target.value) || 0)
+                          : null,
+                      },
+                    })
+                  }
+                />
+              </div>
+              <div className="grid grid-cols-2 gap-4">
+                <div className="space-y-2">
+                  <Label htmlFor="smtp-host">SMTP Server</Label>
+                  <Input
+                    id="smtp-host"
+                    placeholder="smtp.example.com"
+                    value={config.attention.smtp.host}
+                    onChange={(e) =>
+                      updateConfig({
+                        attention: {
+                          ...config.attention,
+                          smtp: {
+                            ...config.attention.smtp,
+                            host: e.target.value,
+                          },
+                        },
+                      })
+                    }
+                  />
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="smtp-port">Port</Label>
+                  <Input
+                    id="smtp-port"
+                    type="number"
+                    min={1}
+                    value={config.attention.smtp.port}
+                    onChange={(e) =>
+                      updateConfig({
+                        attention: {
+                          ...config.attention,
+                          smtp: {
+                            ...config.attention.smtp,
+                            port: parseInt(e.target.value) || 0,
+                          },
+                        },
+                      })
+                    }
+                  />
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="smtp-security">Security</Label>
+                  <Select
+                    value={config.attention.smtp.security}
+                    onValueChange={(value: SmtpSecurity) =>
+                      updateConfig({
+                        attention: {
+                          ...config.attention,
+                          smtp: { ...config.attention.smtp, security: value },
+                        },
+                      })
+                    }
+                  >
+                    <SelectTrigger id="smtp-security">
+                      <SelectValue />
+                    </SelectTrigger>
+                    <SelectContent>
+                      <SelectItem value="starttls">STARTTLS</SelectItem>
+                      <SelectItem value="tls">TLS</SelectItem>
+                      <SelectItem value="none">None</SelectItem>
+                    </SelectContent>
+                  </Select>
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="smtp-from">From</Label>
+                  <Input
+                    id="smtp-from"
+                    placeholder="vibe-kanban@example.com"
+                    value={config.attention.smtp.from}
+                    onChange={(e) =>
+                      updateConfig({
+                        attention: {
+                          ...config.attention,
+                          smtp: {
+                            ...config.attention.smtp,
+                            from: e.target.value,
+                          },
+                        },
+                      })
+                    }
+                  />
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="smtp-username">Username</Label>
+                  <Input
+                    id="smtp-username"
+                    value={config.attention.smtp.username ?? ''}
+                    onChange={(e) =>
+                      updateConfig({
+                        attention: {
+                          ...config.attention,
+                          smtp: {
+                            ...config.attention.smtp,
+                            username: e.target.value || null,
+                          },
+                        },
+                      })
+                    }
+                  />
+                </div>
+                <div className="space-y-2">
+                  <Label htmlFor="smtp-password">Password</Label>
+                  <Input
+                    id="smtp-password"
+                    type="password"
+                    value={config.attention.smtp.password ?? ''}
+                    onChange={(e) =>
+                      updateConfig({
+                        attention: {
+                          ...config.attention,
+                          smtp: {
+                            ...config.attention.smtp,
+                            password: e.target.value || null,
+                          },
+                        },
+                      })
+                    }
+                  />
+                </div>
+                <div className="col-span-2 space-y-2">
+                  <Label htmlFor="smtp-to">Send To</Label>
+                  <Input
+                    id="smtp-to"
+                    placeholder="me@example.com, oncall@example.com"
+                    value={config.attention.smtp.to.join(', ')}
+                    onChange={(e) =>
+                      updateConfig({
+                        attention: {
+                          ...config.attention,
+                          smtp: {
+                            ...config.attention.smtp,
+                            to: e.target.value
+                              .split(',')
+                              .map((address) => address.trim())
+                              .filter(Boolean),
+                          },
+                        },
+                      })
+                    }
+                  />
+                </div>
+              </div>
             </CardContent>
           </Card>
 
@@ -498,6 +2318,49 @@ export function Settings() {
             </CardContent>
           </Card>
 
+          {config.auth.enabled && (
+            <Card>
+              <CardHeader>
+                <CardTitle>Two-Factor Authentication</CardTitle>
+                <CardDescription>
+                  Ask for a code from an authenticator app when you sign in
+                  with your password.
+                </CardDescription>
+              </CardHeader>
+              <CardContent>
+                <TwoFactorManager />
+              </CardContent>
+            </Card>
+          )}
+
+          {config.auth.enabled && (
+            <Card>
+              <CardHeader>
+                <CardTitle>API Tokens</CardTitle>
+                <CardDescription>
+                  Let scripts and CI pipelines use the API as you, limited to
+                  what each token is for.
+                </CardDescription>
+              </CardHeader>
+              <CardContent>
+                <ApiTokenManager />
+              </CardContent>
+            </Card>
+          )}
+
+          <Card>
+            <CardHeader>
+              <CardTitle>Plugins</CardTitle>
+              <CardDescription>
+                WASM modules that transform agent output, react to task events
+                and can refuse new tasks and merges.
+              </CardDescription>
+            </CardHeader>
+            <CardContent>
+              <PluginList />
+            </CardContent>
+          </Card>
+
           <Card>
             <CardHeader>
               <CardTitle>Safety & Disclaimers</CardTitle>
@@ -0,0 +1,141 @@
+import { useEffect, useState } from 'react';
+import { useParams } from 'react-router-dom';
+import { Badge } from '@/components/ui/badge';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { Loader } from '@/components/ui/loader';
+import DisplayConversationEntry from '@/components/tasks/TaskDetails/DisplayConversationEntry';
+import { shareLinksApi } from '@/lib/api';
+import type { FileHunks, RunConversation, SharedTask } from 'shared/types';
+
+const lineClassName = {
+  Insert: 'bg-green-50 text-green-800 dark:bg-green-900/20 dark:text-green-300',
+  Delete: 'bg-red-50 text-red-800 dark:bg-red-900/20 dark:text-red-300',
+  Equal: 'text-muted-foreground',
+};
+
+const linePrefix = { Insert: '+', Delete: '-', Equal: ' ' };
+
+function SharedDiff({ files }: { files: FileHunks[] }) {
+  return (
+    <div className="space-y-3">
+      {files.map((file) => (
+        <div key={file.path} className="rounded border">
+          <div className="border-b bg-muted/40 px-2 py-1 font-mono text-xs">
+            {file.path}
+          </div>
+          {file.hunks.map((hunk) => (
+            <pre
+              key={hunk.index}
+              className="overflow-x-auto px-2 py-1 font-mono text-xs"
+            >
+              <div className="text-muted-foreground">{hunk.header}</div>
+              {hunk.lines.map((line, index) => (
+                <div key={index} className={lineClassName[line.chunk_type]}>
+                  {linePrefix[line.chunk_type]}
+                  {line.content.replace(/\n$/, '')}
+                </div>
+              ))}
+            </pre>
+          ))}
+        </div>
+      ))}
+    </div>
+  );
+}
+
+function SharedRun({ run }: { run: RunConversation }) {
+  return (
+    <div className="space-y-3">
+      <div className="flex items-center gap-2 text-xs text-muted-foreground">
+        <span>{new Date(run.started_at).toLocaleString()}</span>
+        <Badge variant="secondary">{run.status}</Badge>
+      </div>
+      <div className="space-y-2">
+        {run.conversation.entries.map((entry, index) => (
+          <DisplayConversationEntry key={index} entry={entry} index={index} />
+        ))}
+      </div>
+      {run.diff && run.diff.length > 0 && <SharedDiff files={run.diff} />}
+    </div>
+  );
+}
+
+// What a share link shows: a task's conversations and diffs, read-only, to anyone
+// holding the link
+export function SharedTaskPage() {
+  const { token } = useParams<{ token: string }>();
+  const [shared, setShared] = useState<SharedTask | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!token) {
+      return;
+    }
+    shareLinksApi
+      .getShared(token)
+      .then(setShared)
+      .catch(() =>
+        setError('This link does not exist, was revoked or has expired.')
+      );
+  }, [token]);
+
+  if (error) {
+    return (
+      <div className="min-h-screen bg-background flex items-center justify-center">
+        <Alert variant="destructive" className="max-w-md">
+          <AlertDescription>{error}</AlertDescription>
+        </Alert>
+      </div>
+    );
+  }
+
+  if (!shared) {
+    return (
+      <div className="min-h-screen bg-background flex items-center justify-center">
+        <Loader message="Loading..." size={32} />
+      </div>
+    );
+  }
+
+  const { conversations } = shared;
+  return (
+    <div className="h-screen overflow-y-auto bg-background">
+      <div className="mx-auto max-w-4xl space-y-6 p-6">
+        <div className="space-y-2">
+          <h1 className="text-2xl font-bold">{conversations.title}</h1>
+          {conversations.description && (
+            <p className="whitespace-pre-wrap text-sm text-muted-foreground">
+              {conversations.description}
+            </p>
+          )}
+          <p className="text-xs text-muted-foreground">
+            Shared read-only, with secrets redacted
+            {shared.expires_at &&
+              ` · Expires ${new Date(shared.expires_at).toLocaleString()}`}
+          </p>
+        </div>
+        {conversations.attempts.length === 0 && (
+          <p className="text-sm text-muted-foreground">
+            No coding agent has worked on this task yet.
+          </p>
+        )}
+        {conversations.attempts.map((attempt) => (
+          <div
+            key={attempt.attempt_id}
+            className="space-y-4 rounded border p-4"
+          >
+            <div className="flex items-center gap-2">
+              <h2 className="font-semibold">{attempt.executor ?? 'Attempt'}</h2>
+              <code className="text-xs text-muted-foreground">
+                {attempt.branch}
+              </code>
+            </div>
+            {attempt.runs.map((run) => (
+              <SharedRun key={run.execution_process_id} run={run} />
+            ))}
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
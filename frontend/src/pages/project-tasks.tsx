@@ -1,12 +1,43 @@
-import { useCallback, useEffect, useState } from 'react';
+import { useCallback, useEffect, useRef, useState } from 'react';
 import { useNavigate, useParams } from 'react-router-dom';
 import { Button } from '@/components/ui/button';
 import { Card, CardContent } from '@/components/ui/card';
 import { Input } from '@/components/ui/input';
-import { FolderOpen, Plus, Settings, LibraryBig, Globe2 } from 'lucide-react';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import {
+  FolderOpen,
+  Plus,
+  Settings,
+  LibraryBig,
+  Globe2,
+  Upload,
+  Download,
+  ScrollText,
+  Rocket,
+  Gauge,
+  Archive,
+} from 'lucide-react';
 import { Loader } from '@/components/ui/loader';
-import { projectsApi, tasksApi, templatesApi } from '@/lib/api';
+import {
+  ApiError,
+  boardColumnsApi,
+  projectsApi,
+  tasksApi,
+  templatesApi,
+} from '@/lib/api';
 import { TaskFormDialog } from '@/components/tasks/TaskFormDialog';
+import { ArchiveDialog } from '@/components/tasks/ArchiveDialog';
+import { TrelloImportDialog } from '@/components/tasks/TrelloImportDialog';
+import { TrackerImportDialog } from '@/components/tasks/TrackerImportDialog';
+import { ChangelogDialog } from '@/components/projects/ChangelogDialog';
+import { ReleasesDialog } from '@/components/projects/ReleasesDialog';
+import { BenchmarksDialog } from '@/components/projects/BenchmarksDialog';
 import { ProjectForm } from '@/components/projects/project-form';
 import { TaskTemplateManager } from '@/components/TaskTemplateManager';
 import { useKeyboardShortcuts } from '@/lib/keyboard-shortcuts';
@@ -33,18 +64,39 @@ import {
 
 import TaskKanbanBoard from '@/components/tasks/TaskKanbanBoard';
 import { TaskDetailsPanel } from '@/components/tasks/TaskDetailsPanel';
+import { PRIORITY_LABELS } from '@/components/tasks/TaskFieldsRow';
 import type {
+  BoardColumn,
   CreateTaskAndStart,
   ExecutorConfig,
   ProjectWithBranch,
+  TaskBoardQuery,
+  TaskPriority,
+  TaskSort,
   TaskStatus,
   TaskWithAttemptStatus,
   TaskTemplate,
 } from 'shared/types';
 import type { DragEndEvent } from '@/components/ui/shadcn-io/kanban';
+import { useBoardEvents } from '@/hooks/useBoardEvents';
 
 type Task = TaskWithAttemptStatus;
 
+// The task as an edit started from, so the server refuses the edit if someone else has
+// changed the same fields since and keeps their changes to the others
+const editedFrom = (task: Task) => ({
+  expected_updated_at: task.updated_at,
+  base: {
+    title: task.title,
+    description: task.description,
+    status: task.status,
+    parent_task_attempt: task.parent_task_attempt,
+  },
+});
+
+const isConflict = (err: unknown): err is ApiError =>
+  err instanceof ApiError && err.status === 409;
+
 export function ProjectTasks() {
   const { projectId, taskId } = useParams<{
     projectId: string;
@@ -53,12 +105,17 @@ export function ProjectTasks() {
   const navigate = useNavigate();
   const [tasks, setTasks] = useState<Task[]>([]);
   const [project, setProject] = useState<ProjectWithBranch | null>(null);
+  const [columns, setColumns] = useState<BoardColumn[]>([]);
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
   const [isTaskDialogOpen, setIsTaskDialogOpen] = useState(false);
   const [editingTask, setEditingTask] = useState<Task | null>(null);
   const [isProjectSettingsOpen, setIsProjectSettingsOpen] = useState(false);
   const [searchQuery, setSearchQuery] = useState('');
+  const [boardQuery, setBoardQuery] = useState<Partial<TaskBoardQuery>>({});
+  // fetchTasks reads the ref so the polling interval sees the current filters
+  const boardQueryRef = useRef(boardQuery);
+  const isFiltered = Boolean(boardQuery.priority || boardQuery.label);
   const [templates, setTemplates] = useState<TaskTemplate[]>([]);
   const [selectedTemplate, setSelectedTemplate] = useState<TaskTemplate | null>(
     null
@@ -66,6 +123,12 @@ export function ProjectTasks() {
 
   // Template management state
   const [isTemplateManagerOpen, setIsTemplateManagerOpen] = useState(false);
+  const [isTrelloImportOpen, setIsTrelloImportOpen] = useState(false);
+  const [isTrackerImportOpen, setIsTrackerImportOpen] = useState(false);
+  const [isChangelogOpen, setIsChangelogOpen] = useState(false);
+  const [isReleasesOpen, setIsReleasesOpen] = useState(false);
+  const [isBenchmarksOpen, setIsBenchmarksOpen] = useState(false);
+  const [isArchiveOpen, setIsArchiveOpen] = useState(false);
 
   // Panel state
   const [selectedTask, setSelectedTask] = useState<Task | null>(null);
@@ -102,8 +165,12 @@ export function ProjectTasks() {
 
   const fetchProject = useCallback(async () => {
     try {
-      const result = await projectsApi.getWithBranch(projectId!);
+      const [result, boardColumns] = await Promise.all([
+        projectsApi.getWithBranch(projectId!),
+        boardColumnsApi.list(projectId!),
+      ]);
       setProject(result);
+      setColumns(boardColumns);
     } catch (err) {
       setError('Failed to load project');
     }
@@ -141,7 +208,10 @@ export function ProjectTasks() {
         if (!skipLoading) {
           setLoading(true);
         }
-        const result = await tasksApi.getAll(projectId!);
+        const result = await tasksApi.getAll(
+          projectId!,
+          boardQueryRef.current
+        );
         // Only update if data has actually changed
         setTasks((prevTasks) => {
           const newTasks = result;
@@ -182,6 +252,7 @@ export function ProjectTasks() {
           title,
           description: description || null,
           parent_task_attempt: null,
+          parent_task_id: null,
         });
         await fetchTasks();
         // Open the newly created task in the details panel
@@ -204,6 +275,7 @@ export function ProjectTasks() {
           description: description || null,
           parent_task_attempt: null,
           executor: executor || null,
+          model: null,
         };
         const result = await tasksApi.createAndStart(projectId!, payload);
         await fetchTasks();
@@ -226,11 +298,18 @@ export function ProjectTasks() {
           description: description || null,
           status,
           parent_task_attempt: null,
+          ...editedFrom(editingTask),
         });
         await fetchTasks();
         setEditingTask(null);
       } catch (err) {
-        setError('Failed to update task');
+        if (isConflict(err)) {
+          setError(err.message);
+          setEditingTask(null);
+          await fetchTasks();
+        } else {
+          setError('Failed to update task');
+        }
       }
     },
     [projectId, editingTask, fetchTasks]
@@ -238,7 +317,7 @@ export function ProjectTasks() {
 
   const handleDeleteTask = useCallback(
     async (taskId: string) => {
-      if (!confirm('Are you sure you want to delete this task?')) return;
+      if (!confirm('Move this task to the trash?')) return;
 
       try {
         await tasksApi.delete(projectId!, taskId);
@@ -250,6 +329,18 @@ export function ProjectTasks() {
     [projectId, fetchTasks]
   );
 
+  const handleArchiveTask = useCallback(
+    async (taskId: string) => {
+      try {
+        await tasksApi.archive(projectId!, taskId);
+        await fetchTasks();
+      } catch (error) {
+        setError('Failed to archive task');
+      }
+    },
+    [projectId, fetchTasks]
+  );
+
   const handleEditTask = useCallback((task: Task) => {
     setEditingTask(task);
     setIsTaskDialogOpen(true);
@@ -275,7 +366,33 @@ export function ProjectTasks() {
   const handleProjectSettingsSuccess = useCallback(() => {
     setIsProjectSettingsOpen(false);
     fetchProject(); // Refresh project data after settings change
-  }, [fetchProject]);
+    fetchTasks(true);
+  }, [fetchProject, fetchTasks]);
+
+  // Board columns are saved from the settings without submitting the form
+  const handleProjectSettingsClose = useCallback(() => {
+    setIsProjectSettingsOpen(false);
+    fetchProject();
+    fetchTasks(true);
+  }, [fetchProject, fetchTasks]);
+
+  const moveToColumn = useCallback(
+    async (task: Task, columnId: string) => {
+      if (task.board_column_id === columnId) return;
+      setTasks((prev) =>
+        prev.map((t) =>
+          t.id === task.id ? { ...t, board_column_id: columnId } : t
+        )
+      );
+      try {
+        await tasksApi.moveToColumn(projectId!, task.id, columnId);
+      } catch (err) {
+        setError(err instanceof Error ? err.message : 'Failed to move task');
+      }
+      fetchTasks(true);
+    },
+    [projectId, fetchTasks]
+  );
 
   const handleDragEnd = useCallback(
     async (event: DragEndEvent) => {
@@ -284,8 +401,12 @@ export function ProjectTasks() {
       if (!over || !active.data.current) return;
 
       const taskId = active.id as string;
-      const newStatus = over.id as Task['status'];
       const task = tasks.find((t) => t.id === taskId);
+      if (task && columns.length > 0) {
+        await moveToColumn(task, over.id as string);
+        return;
+      }
+      const newStatus = over.id as Task['status'];
 
       if (!task || task.status === newStatus) return;
 
@@ -301,6 +422,7 @@ export function ProjectTasks() {
           description: task.description,
           status: newStatus,
           parent_task_attempt: task.parent_task_attempt,
+          ...editedFrom(task),
         });
       } catch (err) {
         // Revert the optimistic update if the API call failed
@@ -309,10 +431,15 @@ export function ProjectTasks() {
             t.id === taskId ? { ...t, status: previousStatus } : t
           )
         );
-        setError('Failed to update task status');
+        if (isConflict(err)) {
+          setError(err.message);
+          fetchTasks();
+        } else {
+          setError('Failed to update task status');
+        }
       }
     },
-    [projectId, tasks]
+    [projectId, tasks, columns, moveToColumn, fetchTasks]
   );
 
   // Setup keyboard shortcuts
@@ -320,7 +447,14 @@ export function ProjectTasks() {
     navigate,
     currentPath: `/projects/${projectId}/tasks`,
     hasOpenDialog:
-      isTaskDialogOpen || isTemplateManagerOpen || isProjectSettingsOpen,
+      isTaskDialogOpen ||
+      isTemplateManagerOpen ||
+      isProjectSettingsOpen ||
+      isTrelloImportOpen ||
+      isTrackerImportOpen ||
+      isChangelogOpen ||
+      isReleasesOpen ||
+      isBenchmarksOpen,
     closeDialog: () => setIsTaskDialogOpen(false),
     onC: handleCreateNewTask,
   });
@@ -332,16 +466,32 @@ export function ProjectTasks() {
       fetchTasks();
       fetchTemplates();
 
-      // Set up polling to refresh tasks every 5 seconds
+      // Board updates arrive over the event stream; this only catches what it misses
       const interval = setInterval(() => {
         fetchTasks(true); // Skip loading spinner for polling
-      }, 2000);
+      }, 30000);
 
       // Cleanup interval on unmount
       return () => clearInterval(interval);
     }
   }, [projectId]);
 
+  useEffect(() => {
+    if (boardQueryRef.current === boardQuery) {
+      return;
+    }
+    boardQueryRef.current = boardQuery;
+    fetchTasks(true);
+  }, [boardQuery, fetchTasks]);
+
+  // Keep the board in sync with other tabs and with running attempts
+  useBoardEvents(projectId, (event) => {
+    if (event.type === 'process_running' || event.type === 'process_stopped') {
+      return;
+    }
+    fetchTasks(true);
+  });
+
   // Handle direct navigation to task URLs
   useEffect(() => {
     if (taskId && tasks.length > 0) {
@@ -406,8 +556,118 @@ export function ProjectTasks() {
             >
               <Settings className="h-4 w-4" />
             </Button>
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={() => setIsTrelloImportOpen(true)}
+              className="h-8 w-8 p-0"
+              title="Import from Trello"
+            >
+              <Upload className="h-4 w-4" />
+            </Button>
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={() => setIsTrackerImportOpen(true)}
+              className="h-8 w-8 p-0"
+              title="Import from Jira or Linear"
+            >
+              <Download className="h-4 w-4" />
+            </Button>
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={() => setIsChangelogOpen(true)}
+              className="h-8 w-8 p-0"
+              title="Changelog"
+            >
+              <ScrollText className="h-4 w-4" />
+            </Button>
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={() => setIsReleasesOpen(true)}
+              className="h-8 w-8 p-0"
+              title="Releases"
+            >
+              <Rocket className="h-4 w-4" />
+            </Button>
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={() => setIsBenchmarksOpen(true)}
+              className="h-8 w-8 p-0"
+              title="Benchmarks"
+            >
+              <Gauge className="h-4 w-4" />
+            </Button>
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={() => setIsArchiveOpen(true)}
+              className="h-8 w-8 p-0"
+              title="Archive and Trash"
+            >
+              <Archive className="h-4 w-4" />
+            </Button>
           </div>
           <div className="flex items-center gap-3">
+            <Select
+              value={boardQuery.sort ?? 'created'}
+              onValueChange={(sort) =>
+                setBoardQuery((prev) => ({ ...prev, sort: sort as TaskSort }))
+              }
+            >
+              <SelectTrigger className="w-36" title="Sort tasks">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value="created">Newest</SelectItem>
+                <SelectItem value="updated">Recently updated</SelectItem>
+                <SelectItem value="priority">Priority</SelectItem>
+                <SelectItem value="title">Title</SelectItem>
+              </SelectContent>
+            </Select>
+            <Select
+              value={boardQuery.priority ?? 'any'}
+              onValueChange={(priority) =>
+                setBoardQuery((prev) => ({
+                  ...prev,
+                  priority:
+                    priority === 'any' ? null : (priority as TaskPriority),
+                }))
+              }
+            >
+              <SelectTrigger className="w-32" title="Filter by priority">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value="any">Any priority</SelectItem>
+                {Object.entries(PRIORITY_LABELS).map(([value, label]) => (
+                  <SelectItem key={value} value={value}>
+                    {label}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+            <Input
+              type="text"
+              placeholder="Label"
+              title="Filter by label"
+              defaultValue={boardQuery.label ?? ''}
+              onBlur={(e) => {
+                const label = e.target.value.trim() || null;
+                setBoardQuery((prev) =>
+                  (prev.label ?? null) === label ? prev : { ...prev, label }
+                );
+              }}
+              onKeyDown={(e) => {
+                if (e.key === 'Enter') {
+                  e.currentTarget.blur();
+                }
+              }}
+              className="w-28"
+            />
             <Input
               type="text"
               placeholder="Search tasks..."
@@ -486,7 +746,11 @@ export function ProjectTasks() {
         </div>
 
         {/* Tasks View */}
-        {tasks.length === 0 ? (
+        {tasks.length === 0 && isFiltered ? (
+          <div className="text-center py-8 text-muted-foreground">
+            No tasks match the filters.
+          </div>
+        ) : tasks.length === 0 ? (
           <div className="max-w-7xl mx-auto">
             <Card>
               <CardContent className="text-center py-8">
@@ -506,9 +770,11 @@ export function ProjectTasks() {
               <TaskKanbanBoard
                 tasks={tasks}
                 searchQuery={searchQuery}
+                columns={columns}
                 onDragEnd={handleDragEnd}
                 onEditTask={handleEditTask}
                 onDeleteTask={handleDeleteTask}
+                onArchiveTask={handleArchiveTask}
                 onViewTaskDetails={handleViewTaskDetails}
                 isPanelOpen={isPanelOpen}
               />
@@ -554,11 +820,51 @@ export function ProjectTasks() {
 
       <ProjectForm
         open={isProjectSettingsOpen}
-        onClose={() => setIsProjectSettingsOpen(false)}
+        onClose={handleProjectSettingsClose}
         onSuccess={handleProjectSettingsSuccess}
         project={project}
       />
 
+      <TrelloImportDialog
+        open={isTrelloImportOpen}
+        onOpenChange={setIsTrelloImportOpen}
+        projectId={projectId!}
+        onImported={() => fetchTasks(true)}
+      />
+
+      <TrackerImportDialog
+        open={isTrackerImportOpen}
+        onOpenChange={setIsTrackerImportOpen}
+        projectId={projectId!}
+        onImported={() => fetchTasks(true)}
+      />
+
+      <ChangelogDialog
+        open={isChangelogOpen}
+        onOpenChange={setIsChangelogOpen}
+        projectId={projectId!}
+      />
+
+      <ReleasesDialog
+        open={isReleasesOpen}
+        onOpenChange={setIsReleasesOpen}
+        projectId={projectId!}
+        hasReleaseScript={!!project?.release_script}
+      />
+
+      <BenchmarksDialog
+        open={isBenchmarksOpen}
+        onOpenChange={setIsBenchmarksOpen}
+        projectId={projectId!}
+      />
+
+      <ArchiveDialog
+        open={isArchiveOpen}
+        onOpenChange={setIsArchiveOpen}
+        projectId={projectId!}
+        onChanged={() => fetchTasks(true)}
+      />
+
       {/* Template Manager Dialog */}
       <Dialog
         open={isTemplateManagerOpen}
@@ -2,6 +2,9 @@ interface ScriptPlaceholders {
   setup: string;
   dev: string;
   cleanup: string;
+  release: string;
+  mergeCheck: string;
+  verification: string;
 }
 
 interface ScriptPlaceholderStrategy {
@@ -20,6 +23,15 @@ REM Add dev server start command here...`,
       cleanup: `@echo off
 REM Add cleanup commands here...
 REM This runs after coding agent execution`,
+      release: `@echo off
+git tag %VK_RELEASE_VERSION%
+git push origin %VK_RELEASE_VERSION%
+REM Add build and publish commands here...`,
+      mergeCheck: `@echo off
+npm run build
+npm test`,
+      verification: `npm run lint
+npm test`,
     };
   }
 }
@@ -36,6 +48,14 @@ npm run dev
       cleanup: `#!/bin/bash
 # Add cleanup commands here...
 # This runs after coding agent execution`,
+      release: `#!/bin/bash
+git tag "$VK_RELEASE_VERSION"
+git push origin "$VK_RELEASE_VERSION"
+# Add build and publish commands here...`,
+      mergeCheck: `#!/bin/bash
+npm run build && npm test`,
+      verification: `npm run lint
+npm test`,
     };
   }
 }
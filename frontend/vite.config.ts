@@ -22,6 +22,7 @@ export default defineConfig({
       '/api': {
         target: `http://localhost:${process.env.BACKEND_PORT || '3001'}`,
         changeOrigin: true,
+        ws: true,
       },
     },
   },
@@ -4,7 +4,34 @@
 
 export type ApiResponse<T> = { success: boolean, data: T | null, message: string | null, };
 
-export type Config = { theme: ThemeMode, executor: ExecutorConfig, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, github_login_acknowledged: boolean, telemetry_acknowledged: boolean, sound_alerts: boolean, sound_file: SoundFile, push_notifications: boolean, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean | null, environment: EnvironmentInfo, workspace_dir: string | null, };
+export type Config = { theme: ThemeMode, executor: ExecutorConfig, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, github_login_acknowledged: boolean, telemetry_acknowledged: boolean, sound_alerts: boolean, sound_file: SoundFile, push_notifications: boolean, notification_events: NotificationEvents, attention: AttentionConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean | null, environment: EnvironmentInfo, workspace_dir: string | null, webhooks: WebhookConfig, notion: NotionConfig, jira: JiraConfig, linear: LinearConfig, embeddings: EmbeddingsConfig, llm: LlmConfig, 
+/**
+ * Propose labels for new tasks from their wording and the paths they mention
+ */
+suggest_labels: boolean, pr_description: PrDescriptionConfig, stale_tasks: StaleTasksConfig, run_estimate: RunEstimateConfig, 
+/**
+ * Agents run through the generic JSON-lines executor, selectable as `jsonl:<name>`
+ */
+jsonl_executors: Array<JsonlExecutorDefinition>, 
+/**
+ * Agents speaking the Agent Client Protocol, selectable as `acp:<name>`
+ */
+acp_agents: Array<AcpAgentDefinition>, 
+/**
+ * The model each executor runs with when an attempt doesn't pick one, keyed by the
+ * executor as in `claude` or `codex`. Executors left out use their CLI's default.
+ */
+executor_models: { [key: string]: string }, 
+/**
+ * Minutes a coding agent or script may run before it's killed, unless its task sets
+ * its own limit. `None` lets executions run indefinitely.
+ */
+execution_timeout_minutes: number | null, executor_retry: RetryPolicy, execution_limits: ExecutionLimits, 
+/**
+ * Resume coding agents cut off by the server stopping as follow-ups when it starts again,
+ * rather than leaving their tasks for review
+ */
+resume_interrupted_runs: boolean, log_redaction: LogRedactionConfig, log_storage: LogStorageConfig, auth: AuthConfig, post_execution_git: PostExecutionGitConfig, verification: VerificationConfig, review_gate: ReviewGateConfig, sandbox: SandboxConfig, resource_limits: ResourceLimitsConfig, context_budget: ContextBudgetConfig, shell_environment: ShellEnvironmentConfig, database: DatabaseConfig, };
 
 export type EnvironmentInfo = { os_type: string, os_version: string, architecture: string, bitness: string, };
 
@@ -16,6 +43,262 @@ export type EditorConfig = { editor_type: EditorType, custom_command: string | n
 
 export type GitHubConfig = { pat: string | null, token: string | null, username: string | null, primary_email: string | null, default_pr_base: string | null, enterprise_url: string | null, };
 
+export type WebhookConfig = { 
+/**
+ * Shared secrets, newest first. The first one signs outgoing payloads.
+ */
+secrets: Array<string>, timestamp_tolerance_secs: bigint, };
+
+export type NotificationEvents = { attempt_succeeded: boolean, attempt_failed: boolean, 
+/**
+ * A dangerous command or a finished plan is waiting to be approved
+ */
+approval_needed: boolean, 
+/**
+ * A task went stale in In Progress or In Review
+ */
+task_stale: boolean, };
+
+export type AttentionConfig = { 
+/**
+ * Used as soon as an execution starts waiting
+ */
+notify: AttentionChannels, 
+/**
+ * Minutes an execution may wait before it's announced through `escalate_to`; `None`
+ * never escalates
+ */
+escalate_after_minutes: number | null, escalate_to: AttentionChannels, smtp: SmtpConfig, };
+
+export type AttentionChannels = { 
+/**
+ * Sound and push notifications, as turned on above
+ */
+desktop: boolean, 
+/**
+ * The project's webhooks that want approval notifications
+ */
+webhook: boolean, 
+/**
+ * Mail to `smtp.to`
+ */
+email: boolean, };
+
+export type SmtpConfig = { 
+/**
+ * Empty until a server is set up, which keeps email from being sent
+ */
+host: string, port: number, security: SmtpSecurity, username: string | null, password: string | null, from: string, to: Array<string>, };
+
+export type SmtpSecurity = "tls" | "starttls" | "none";
+
+export type NotionConfig = { 
+/**
+ * Internal integration secret; databases must be shared with the integration
+ */
+token: string | null, };
+
+export type JiraConfig = { 
+/**
+ * Site URL such as https://acme.atlassian.net
+ */
+base_url: string | null, 
+/**
+ * Email of the account the API token belongs to
+ */
+email: string | null, 
+/**
+ * API token from id.atlassian.com/manage-profile/security/api-tokens
+ */
+api_token: string | null, };
+
+export type LinearConfig = { 
+/**
+ * Personal API key from Linear's security settings
+ */
+api_key: string | null, };
+
+export type EmbeddingsConfig = { 
+/**
+ * API base such as https://api.openai.com/v1 or http://localhost:11434/v1 for Ollama;
+ * embeddings are off when unset
+ */
+base_url: string | null, model: string, api_key: string | null, };
+
+export type LlmConfig = { 
+/**
+ * API base of a `/chat/completions` endpoint; these features are off when unset
+ */
+base_url: string | null, model: string, api_key: string | null, };
+
+export type PrDescriptionConfig = { 
+/**
+ * Markdown with `{{task_title}}`, `{{task_description}}`, `{{summary}}`, `{{changes}}`,
+ * `{{validation}}`, `{{branch}}` and `{{base_branch}}`; headings over empty sections are
+ * left out
+ */
+template: string, 
+/**
+ * Summarize the agent's messages with the language model instead of quoting its last one
+ */
+summarize_with_llm: boolean, };
+
+export type StaleTasksConfig = { enabled: boolean, in_progress_hours: number, in_review_hours: number, 
+/**
+ * Archive stale tasks that stay idle this many days, removing their worktrees unless
+ * they have uncommitted changes; `None` leaves them on the board
+ */
+archive_after_days: number | null, };
+
+export type RunEstimateConfig = { enabled: boolean, confirm_above_tokens: number, confirm_above_files: number, };
+
+export type RetryPolicy = { 
+/**
+ * Starts in total, counting the first; `1` turns retries off
+ */
+max_attempts: number, 
+/**
+ * Wait before the first retry, doubled for each one after it
+ */
+backoff_seconds: number, };
+
+export type ExecutionLimits = { max_running: number | null, 
+/**
+ * Applies to each project on its own
+ */
+max_running_per_project: number | null, preemption: PreemptionPolicy, };
+
+export type PreemptionPolicy = "never" | "pause_lower_priority";
+
+export type LogRedactionConfig = { enabled: boolean, 
+/**
+ * Also redact long tokens that look random, which no pattern knows the shape of
+ */
+detect_high_entropy: boolean, 
+/**
+ * Regexes redacted on top of the built-in ones. Of a pattern with a group named
+ * `secret`, only that group is replaced.
+ */
+extra_patterns: Array<string>, };
+
+export type LogStorageConfig = { 
+/**
+ * Limit on each of an execution's stdout and stderr; `None` keeps all of it
+ */
+max_stream_kb: number | null, 
+/**
+ * How much of the limit goes to the beginning of the output
+ */
+head_kb: number, 
+/**
+ * Also write every execution's full output to files in the app's data directory
+ */
+offload_full_logs: boolean, };
+
+export type AuthConfig = { enabled: boolean, 
+/**
+ * How long a sign-in lasts
+ */
+session_hours: number, 
+/**
+ * How long refreshing can keep a session going, counted from sign-in. Past it, the
+ * user signs in again.
+ */
+max_session_days: number, oidc: OidcConfig, };
+
+export type OidcConfig = { 
+/**
+ * Such as https://accounts.google.com; OIDC sign-in is off when unset
+ */
+issuer_url: string | null, client_id: string, client_secret: string | null, 
+/**
+ * Where the provider sends users back to: this server's `/api/auth/oidc/callback`
+ */
+redirect_url: string | null, };
+
+export type PostExecutionGitConfig = { 
+/**
+ * Commit what the run left uncommitted, with the project's commit message template
+ */
+auto_commit: boolean, 
+/**
+ * Push the branch to origin after a successful run, using the GitHub token
+ */
+push: boolean, };
+
+export type VerificationConfig = { 
+/**
+ * Label the task `needs-follow-up` when a command fails, and drop the label once they
+ * all pass again
+ */
+follow_up_on_failure: boolean, 
+/**
+ * Seconds each command may run before it's stopped and counted as failed
+ */
+timeout_secs: number, 
+/**
+ * Follow-ups sent to the agent with the failing output, asking it to fix what failed,
+ * before the attempt goes to review anyway; `0` turns them off
+ */
+auto_fix_attempts: number, };
+
+export type ReviewGateConfig = { 
+/**
+ * Merging, directly or through the merge queue, is refused until the attempt is
+ * approved
+ */
+require_approval: boolean, 
+/**
+ * Push the base branch to origin once an approved attempt is merged into it
+ */
+push_on_approve: boolean, };
+
+export type SandboxConfig = { enabled: boolean, 
+/**
+ * Cut the agents off from the network too; most need it to reach their model
+ */
+deny_network: boolean, 
+/**
+ * The agents to sandbox, as in `executor.type`; empty sandboxes every coding agent
+ */
+executors: Array<string>, };
+
+export type ResourceLimitsConfig = { 
+/**
+ * Megabytes of resident memory a run is killed for going over
+ */
+max_memory_mb: number | null, 
+/**
+ * CPU weight against other runs and processes; 1024 is the default, 512 half of it
+ */
+cpu_shares: number | null, 
+/**
+ * Files each process of a run may have open
+ */
+max_open_files: number | null, };
+
+export type ContextBudgetConfig = { max_tokens: number, 
+/**
+ * Budgets for particular executors, keyed as in `claude` or `gemini`
+ */
+executors: { [key: string]: number }, };
+
+export type ShellEnvironmentConfig = { login_shell: LoginShellMode, 
+/**
+ * A file of `KEY=value` lines loaded on top of the login shell's environment. `~` is the
+ * home directory, and a relative path is from the attempt's worktree.
+ */
+env_file: string | null, };
+
+export type DatabaseConfig = { 
+/**
+ * A `postgres://` URL to keep the data in PostgreSQL. `None` keeps it in SQLite, in the
+ * app's data directory.
+ */
+url: string | null, };
+
+export type LoginShellMode = "auto" | "always" | "never";
+
 export type EditorType = "vscode" | "cursor" | "windsurf" | "intellij" | "zed" | "custom";
 
 export type EditorConstants = { editor_types: Array<EditorType>, editor_labels: Array<string>, };
@@ -24,21 +307,144 @@ export type SoundFile = "abstract-sound1" | "abstract-sound2" | "abstract-sound3
 
 export type SoundConstants = { sound_files: Array<SoundFile>, sound_labels: Array<string>, };
 
-export type ConfigConstants = { editor: EditorConstants, sound: SoundConstants, mode: Environment, };
+export type ConfigConstants = { editor: EditorConstants, sound: SoundConstants, mode: Environment, dry_run: boolean, };
 
-export type ExecutorConfig = { "type": "echo" } | { "type": "claude" } | { "type": "claude-plan" } | { "type": "amp" } | { "type": "gemini" } | { "type": "setup-script", script: string, } | { "type": "claude-code-router" } | { "type": "charm-opencode" } | { "type": "sst-opencode" } | { "type": "aider" } | { "type": "codex" } | { "type": "aaa" };
+export type ExecutorConfig = { "type": "echo" } | { "type": "claude" } | { "type": "claude-plan" } | { "type": "amp" } | { "type": "gemini" } | { "type": "setup-script", script: string, } | { "type": "claude-code-router" } | { "type": "charm-opencode" } | { "type": "sst-opencode" } | { "type": "aider" } | { "type": "codex" } | { "type": "aaa" } | { "type": "goose" } | { "type": "replay", recording_path: string, speed: number | null, normalize_as: string | null, } | { "type": "jsonl", name: string, } | { "type": "acp", name: string, };
 
 export type ExecutorConstants = { executor_types: Array<ExecutorConfig>, executor_labels: Array<string>, };
 
-export type CreateProject = { name: string, git_repo_path: string, use_existing_repo: boolean, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, };
-
-export type CreateProjectFromGitHub = { repository_id: bigint, name: string, clone_url: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, };
-
-export type Project = { id: string, name: string, git_repo_path: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, created_at: Date, updated_at: Date, };
-
-export type ProjectWithBranch = { id: string, name: string, git_repo_path: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, current_branch: string | null, created_at: Date, updated_at: Date, };
-
-export type UpdateProject = { name: string | null, git_repo_path: string | null, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, };
+export type ExecutorCapabilities = { 
+/**
+ * Can be given a follow-up prompt once a run finishes
+ */
+supports_followup: boolean, 
+/**
+ * A follow-up continues the earlier conversation by itself. Follow-ups of executors
+ * that can't are given a condensed transcript of the attempt's earlier runs.
+ */
+supports_sessions: boolean, 
+/**
+ * Proposes a plan for approval before changing anything
+ */
+supports_plan_mode: boolean, 
+/**
+ * Takes further messages from the user on stdin while it runs
+ */
+supports_interactive: boolean, 
+/**
+ * Logs record messages and tool calls as structured events rather than plain text
+ */
+emits_structured_logs: boolean, };
+
+export type EnvironmentProblem = { message: string, remediation: string, };
+
+export type EnvironmentReport = { executor: string, 
+/**
+ * Where the executor's binary was found
+ */
+binary_path: string | null, 
+/**
+ * First line the binary printed for `--version`
+ */
+version: string | null, 
+/**
+ * Empty when the executor is ready to run
+ */
+problems: Array<EnvironmentProblem>, };
+
+export type CheckStatus = "ok" | "warning" | "error";
+
+export type DiagnosticCheck = { 
+/**
+ * `database`, `migrations`, `disk_space`, `git`, or `executor:<name>`
+ */
+name: string, status: CheckStatus, message: string, };
+
+export type Diagnostics = { 
+/**
+ * The worst status of the checks
+ */
+status: CheckStatus, checks: Array<DiagnosticCheck>, };
+
+export type JsonlFieldMapping = { 
+/**
+ * Field naming the kind of record; its value is looked up in the `*_types` lists
+ */
+type_field: string, assistant_types: Array<string>, thinking_types: Array<string>, tool_use_types: Array<string>, system_types: Array<string>, error_types: Array<string>, 
+/**
+ * Text of assistant, thinking, system and error records
+ */
+content_field: string, tool_name_field: string, tool_input_field: string | null, 
+/**
+ * Records of any kind are searched for this until a session id turns up
+ */
+session_id_field: string | null, };
+
+export type JsonlExecutorDefinition = { name: string, 
+/**
+ * Shell command run in the worktree; the prompt is written to its stdin
+ */
+command: string, 
+/**
+ * Command continuing a session, with `{session_id}` where the id goes. Follow-ups
+ * aren't supported without it.
+ */
+follow_up_command: string | null, mapping: JsonlFieldMapping, 
+/**
+ * Set for CLIs run in a mode where they wait on stdin before running each command.
+ * Stdin then stays open after the prompt, for the answers.
+ */
+approval: JsonlApprovalReplies | null, 
+/**
+ * Set for CLIs that read messages from the user on stdin while they run: the line
+ * written for each, with `{message}` where the message goes, escaped as inside a JSON
+ * string. Stdin then stays open after the prompt.
+ */
+input_template: string | null, };
+
+export type JsonlApprovalReplies = { allow: string, deny: string, };
+
+export type AcpAgentDefinition = { name: string, 
+/**
+ * Shell command run in the worktree that starts the agent in ACP mode
+ */
+command: string, };
+
+export type CreateProject = { name: string, git_repo_path: string, use_existing_repo: boolean, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, commit_message_template: string | null, release_script: string | null, merge_check_script: string | null, verification_script: string | null, default_executor: string | null, default_branch: string | null, };
+
+export type CreateProjectFromGitHub = { repository_id: bigint, name: string, clone_url: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, commit_message_template: string | null, release_script: string | null, merge_check_script: string | null, verification_script: string | null, default_executor: string | null, default_branch: string | null, };
+
+export type Project = { id: string, name: string, git_repo_path: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, 
+/**
+ * Used for commits vibe-kanban makes in attempt worktrees; see
+ * `services::commit_message` for the placeholders
+ */
+commit_message_template: string | null, 
+/**
+ * Run from the repository root to tag, build and publish a release
+ */
+release_script: string | null, 
+/**
+ * Run in an attempt's worktree by the merge queue before the attempt lands
+ */
+merge_check_script: string | null, 
+/**
+ * Commands run one per line in an attempt's worktree after its coding agent finishes;
+ * see `services::verification`
+ */
+verification_script: string | null, 
+/**
+ * Executor for attempts started without one, ahead of the configured executor
+ */
+default_executor: string | null, 
+/**
+ * Branch attempts are based on when they don't name one, instead of the current branch
+ */
+default_branch: string | null, created_at: Date, updated_at: Date, };
+
+export type ProjectWithBranch = { id: string, name: string, git_repo_path: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, commit_message_template: string | null, release_script: string | null, merge_check_script: string | null, verification_script: string | null, default_executor: string | null, default_branch: string | null, current_branch: string | null, created_at: Date, updated_at: Date, };
+
+export type UpdateProject = { name: string | null, git_repo_path: string | null, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, commit_message_template: string | null, release_script: string | null, merge_check_script: string | null, verification_script: string | null, default_executor: string | null, default_branch: string | null, };
 
 export type SearchResult = { path: string, is_file: boolean, match_type: SearchMatchType, };
 
@@ -48,33 +454,155 @@ export type GitBranch = { name: string, is_current: boolean, is_remote: boolean,
 
 export type CreateBranch = { name: string, base_branch: string | null, };
 
-export type CreateTask = { project_id: string, title: string, description: string | null, parent_task_attempt: string | null, };
+export type CreateTask = { project_id: string, title: string, description: string | null, parent_task_attempt: string | null, parent_task_id: string | null, };
 
-export type CreateTaskAndStart = { project_id: string, title: string, description: string | null, parent_task_attempt: string | null, executor: ExecutorConfig | null, };
+export type CreateSubtask = { title: string, description: string | null, };
 
-export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelled";
+export type CreateTaskAndStart = { project_id: string, title: string, description: string | null, parent_task_attempt: string | null, executor: ExecutorConfig | null, 
+/**
+ * The model to run the agent with, as its CLI names it
+ */
+model: string | null, };
 
-export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, created_at: string, updated_at: string, };
+export type TaskStatus = "todo" | "queued" | "inprogress" | "planreview" | "inreview" | "done" | "cancelled";
 
-export type TaskWithAttemptStatus = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, created_at: string, updated_at: string, has_in_progress_attempt: boolean, has_merged_attempt: boolean, last_attempt_failed: boolean, latest_attempt_executor: string | null, };
+export type TaskStatusNames = { todo: string | null, queued: string | null, inprogress: string | null, planreview: string | null, inreview: string | null, done: string | null, cancelled: string | null, };
 
-export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_task_attempt: string | null, };
+export type TaskPriority = "low" | "medium" | "high" | "urgent";
 
-export type TaskTemplate = { id: string, project_id: string | null, title: string, description: string | null, template_name: string, created_at: string, updated_at: string, };
+export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, parent_task_id: string | null, 
+/**
+ * Overrides the configured execution timeout for this task's attempts
+ */
+timeout_minutes: bigint | null, priority: TaskPriority | null, created_at: string, updated_at: string, };
 
-export type CreateTaskTemplate = { project_id: string | null, title: string, description: string | null, template_name: string, };
+export type TaskWithAttemptStatus = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, parent_task_id: string | null, timeout_minutes: bigint | null, priority: TaskPriority | null, created_at: string, updated_at: string, labels: Array<string>, 
+/**
+ * Values of the project's custom fields, keyed by field name
+ */
+custom_fields: { [key: string]: string }, 
+/**
+ * The column the task shows in when the project has its own board columns
+ */
+board_column_id: string | null, has_in_progress_attempt: boolean, has_merged_attempt: boolean, last_attempt_failed: boolean, 
+/**
+ * A verification command failed after the latest coding agent run
+ */
+verification_failed: boolean, latest_attempt_executor: string | null, 
+/**
+ * Set while the stale task check has this task flagged in its current column
+ */
+stale_since: string | null, 
+/**
+ * Place of the task's attempt in the execution queue; 1 is next
+ */
+queue_position: bigint | null, 
+/**
+ * Pull request of the latest attempt that opened one
+ */
+pr_url: string | null, pr_number: bigint | null, 
+/**
+ * open, closed or merged
+ */
+pr_status: string | null, 
+/**
+ * One-line outcome of the latest coding agent run that finished with one
+ */
+latest_summary: string | null, };
 
-export type UpdateTaskTemplate = { title: string | null, description: string | null, template_name: string | null, };
+export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_task_attempt: string | null, 
+/**
+ * The task's `updated_at` when the edit started. The update is refused when the task has
+ * changed since, unless `base` shows the changes were to other fields.
+ */
+expected_updated_at: string | null, 
+/**
+ * The task as the edit started from, so changes made since to other fields are kept
+ */
+base: TaskEdit | null, };
 
-export type TaskAttemptStatus = "setuprunning" | "setupcomplete" | "setupfailed" | "executorrunning" | "executorcomplete" | "executorfailed";
+export type TaskEdit = { title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, };
+
+export type TaskTimeoutRequest = { timeout_minutes: bigint | null, };
+
+export type TaskPriorityRequest = { priority: TaskPriority | null, };
+
+export type TaskSort = "created" | "updated" | "priority" | "title" | "field";
+
+export type TaskBoardQuery = { label: string | null, priority: TaskPriority | null, 
+/**
+ * A custom field value to match, as `name:value`
+ */
+field: string | null, sort: TaskSort, sort_field: string | null, };
+
+export type ShelvedTask = { id: string, project_id: string, title: string, status: TaskStatus, priority: TaskPriority | null, archived_at: string | null, deleted_at: string | null, updated_at: string, };
+
+export type BulkTaskAction = { "action": "archive", task_ids: Array<string>, } | { "action": "archive_done", older_than_days: number | null, } | { "action": "unarchive", task_ids: Array<string>, } | { "action": "delete", task_ids: Array<string>, } | { "action": "restore", task_ids: Array<string>, } | { "action": "move", task_ids: Array<string>, column_id: string | null, status: TaskStatus | null, } | { "action": "rerun", task_ids: Array<string>, executor: string, };
+
+export type BulkTaskFailure = { task_id: string, error: string, };
+
+export type BulkTaskResult = { succeeded: Array<string>, failed: Array<BulkTaskFailure>, };
+
+export type TaskTemplate = { id: string, project_id: string | null, title: string, description: string | null, template_name: string, default_executor: string | null, verification_script: string | null, checklist: string | null, created_at: string, updated_at: string, };
+
+export type CreateTaskTemplate = { project_id: string | null, title: string, description: string | null, template_name: string, default_executor: string | null, verification_script: string | null, checklist: string | null, };
+
+export type UpdateTaskTemplate = { title: string | null, description: string | null, template_name: string | null, default_executor: string | null, verification_script: string | null, checklist: string | null, };
+
+export type InstantiateTaskTemplate = { project_id: string, variables: { [key: string]: string }, parent_task_attempt: string | null, };
+
+export type TaskChecklistItem = { id: string, task_id: string, position: bigint, content: string, completed_at: string | null, created_at: string, };
+
+export type TaskChecklist = { items: Array<TaskChecklistItem>, completed: number, total: number, };
+
+export type UpdateTaskChecklistItem = { item_id: string, completed: boolean, };
+
+export type TaskDecompositionStatus = "pending" | "completed" | "failed";
+
+export type TaskDecomposition = { task_attempt_id: string, task_id: string, status: TaskDecompositionStatus, subtask_count: bigint, created_at: string, completed_at: string | null, };
+
+export type DecomposeTask = { executor: string | null, };
+
+export type TaskSubtasks = { subtasks: Array<Task>, decomposition: TaskDecomposition | null, };
+
+export type TaskSchedule = { id: string, task_id: string, cron_expression: string, executor: string | null, enabled: boolean, next_run_at: string | null, last_run_at: string | null, created_at: string, updated_at: string, };
+
+export type UpsertTaskSchedule = { cron_expression: string, executor: string | null, enabled: boolean | null, };
+
+export type TaskScheduleRunStatus = "running" | "succeeded" | "failed";
+
+export type TaskScheduleRun = { id: string, schedule_id: string, task_id: string | null, task_attempt_id: string | null, status: TaskScheduleRunStatus, error: string | null, started_at: string, finished_at: string | null, };
 
-export type TaskAttempt = { id: string, task_id: string, worktree_path: string, branch: string, base_branch: string, merge_commit: string | null, executor: string | null, pr_url: string | null, pr_number: bigint | null, pr_status: string | null, pr_merged_at: string | null, worktree_deleted: boolean, setup_completed_at: string | null, created_at: string, updated_at: string, };
+export type TaskAttemptStatus = "setuprunning" | "setupcomplete" | "setupfailed" | "executorrunning" | "executorcomplete" | "executorfailed";
 
-export type CreateTaskAttempt = { executor: string | null, base_branch: string | null, };
+export type TaskAttempt = { id: string, task_id: string, worktree_path: string, branch: string, base_branch: string, merge_commit: string | null, executor: string | null, pr_url: string | null, pr_number: bigint | null, pr_status: string | null, pr_merged_at: string | null, worktree_deleted: boolean, setup_completed_at: string | null, plan_only: boolean, 
+/**
+ * The model the agent is asked to run with; `None` leaves it to the config's default
+ * for the executor, or the CLI's own
+ */
+model: string | null, 
+/**
+ * The executor profile it was started with, whose flags and variables its runs get
+ */
+executor_profile_id: string | null, created_at: string, updated_at: string, };
+
+export type CreateTaskAttempt = { executor: string | null, base_branch: string | null, plan_only: boolean | null, 
+/**
+ * The model to run the agent with, as its CLI names it (e.g. "opus", "o4-mini")
+ */
+model: string | null, 
+/**
+ * A profile to run with, which picks the executor
+ */
+executor_profile_id: string | null, };
 
 export type UpdateTaskAttempt = Record<string, never>;
 
-export type CreateFollowUpAttempt = { prompt: string, };
+export type CreateFollowUpAttempt = { prompt: string, 
+/**
+ * Hand the attempt over to this executor instead of continuing the current one's session
+ */
+executor: string | null, };
 
 export type DirectoryEntry = { name: string, path: string, is_directory: boolean, is_git_repo: boolean, };
 
@@ -82,10 +610,810 @@ export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_pa
 
 export type DeviceStartResponse = { device_code: string, user_code: string, verification_uri: string, expires_in: number, interval: number, };
 
+export type AuthStatus = { auth_enabled: boolean, oidc_enabled: boolean, 
+/**
+ * No account exists yet, so the first one can be registered and becomes an admin
+ */
+needs_setup: boolean, user: User | null, memberships: Array<ProjectMembership>, 
+/**
+ * The signed-in user asks for a code from their authenticator app at sign-in
+ */
+two_factor_enabled: boolean, 
+/**
+ * A project the signed-in user reaches requires two-factor authentication and they
+ * haven't turned it on yet, so that project turns them away until they have
+ */
+two_factor_required: boolean, };
+
+export type LoginRequest = { username: string, password: string, 
+/**
+ * A code from the authenticator app, or a recovery code, for accounts with two-factor
+ * authentication turned on
+ */
+code: string | null, };
+
+export type LoginResponse = { user: User, 
+/**
+ * The session token, for API clients that send it as a bearer token rather than
+ * keeping the cookie
+ */
+token: string, };
+
+export type ChangePassword = { current_password: string, new_password: string, };
+
 export type RepositoryInfo = { id: bigint, name: string, full_name: string, owner: string, description: string | null, clone_url: string, ssh_url: string, default_branch: string, private: boolean, };
 
+export type GitHubProjectLink = { project_id: string, project_url: string, github_project_id: string, status_mapping: TaskStatusNames, last_synced_at: string | null, last_error: string | null, created_at: string, updated_at: string, };
+
+export type UpsertGitHubProjectLink = { 
+/**
+ * e.g. https://github.com/orgs/acme/projects/5 or https://github.com/users/me/projects/2
+ */
+project_url: string, status_mapping: TaskStatusNames, };
+
+export type StatusOption = { id: string, name: string, };
+
+export type GitHubProjectBoard = { id: string, title: string, status_field_id: string | null, status_options: Array<StatusOption>, };
+
+export type GitHubProjectSyncResult = { added: number, updated: number, removed: number, };
+
+export type GitHubProjectLinkResponse = { link: GitHubProjectLink, board: GitHubProjectBoard, };
+
+export type GitHubIssueLink = { project_id: string, repo_owner: string, repo_name: string, 
+/**
+ * Only issues with this label are imported
+ */
+label: string, last_synced_at: string | null, last_error: string | null, created_at: string, updated_at: string, };
+
+export type UpsertGitHubIssueLink = { label: string, };
+
+export type GitHubIssueSyncResult = { created: number, updated: number, 
+/**
+ * Issues closed because their task was done
+ */
+closed: number, };
+
+export type NotionPropertyMapping = { 
+/**
+ * Title property; defaults to the database's title column
+ */
+title: string | null, 
+/**
+ * A status or select property; defaults to one named "Status"
+ */
+status: string | null, 
+/**
+ * A text property for the task description; descriptions aren't synced when unset
+ */
+description: string | null, status_names: TaskStatusNames, };
+
+export type NotionLink = { project_id: string, database_id: string, property_mapping: NotionPropertyMapping, last_synced_at: string | null, last_error: string | null, created_at: string, updated_at: string, };
+
+export type UpsertNotionLink = { 
+/**
+ * Database URL or id
+ */
+database: string, property_mapping: NotionPropertyMapping, };
+
+export type NotionProperty = { name: string, 
+/**
+ * Notion property type, e.g. "title", "status", "select" or "rich_text"
+ */
+kind: string, 
+/**
+ * Option names of status and select properties
+ */
+options: Array<string>, };
+
+export type NotionDatabase = { id: string, title: string, properties: Array<NotionProperty>, };
+
+export type NotionSyncResult = { added: number, updated: number, archived: number, };
+
+export type NotionLinkResponse = { link: NotionLink, database: NotionDatabase, };
+
+export type TrelloImportRequest = { "source": "export", board: unknown, } | { "source": "api", board_id: string, key: string, token: string, };
+
+export type TrelloImportResult = { board_name: string, tasks_created: number, subtasks_created: number, };
+
+export type Tracker = "jira" | "linear";
+
+export type TrackerImport = { project_id: string, tracker: Tracker, 
+/**
+ * Jira project key or Linear team key, such as `ENG`
+ */
+source_key: string, 
+/**
+ * When the last successful sync started; the next one only fetches issues updated since
+ */
+last_synced_at: string | null, last_error: string | null, created_at: string, };
+
+export type TrackerImportRequest = { tracker: Tracker, 
+/**
+ * Jira project key or Linear team key, such as `ENG`
+ */
+key: string, };
+
+export type TrackerSyncResult = { created: number, updated: number, };
+
+export type ProjectArchive = { format_version: number, exported_at: string, project_name: string, tasks: Array<ArchivedTask>, templates: Array<TaskTemplate>, };
+
+export type ArchivedTask = { attempts: Array<ArchivedAttempt>, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, parent_task_id: string | null, 
+/**
+ * Overrides the configured execution timeout for this task's attempts
+ */
+timeout_minutes: bigint | null, priority: TaskPriority | null, created_at: string, updated_at: string, };
+
+export type ArchivedAttempt = { executions: Array<ArchivedExecution>, id: string, task_id: string, worktree_path: string, branch: string, base_branch: string, merge_commit: string | null, executor: string | null, pr_url: string | null, pr_number: bigint | null, pr_status: string | null, pr_merged_at: string | null, worktree_deleted: boolean, setup_completed_at: string | null, plan_only: boolean, 
+/**
+ * The model the agent is asked to run with; `None` leaves it to the config's default
+ * for the executor, or the CLI's own
+ */
+model: string | null, 
+/**
+ * The executor profile it was started with, whose flags and variables its runs get
+ */
+executor_profile_id: string | null, created_at: string, updated_at: string, };
+
+export type ArchivedExecution = { conversation: NormalizedConversation | null, id: string, task_attempt_id: string, process_type: ExecutionProcessType, executor_type: string | null, status: ExecutionProcessStatus, command: string, args: string | null, working_directory: string, stdout: string | null, stderr: string | null, exit_code: bigint | null, 
+/**
+ * How many times a failed start has been retried
+ */
+retry_count: bigint, 
+/**
+ * Set while a failed start waits to be retried
+ */
+retry_at: string | null, 
+/**
+ * The worktree's commit once the run finished and its changes were committed
+ */
+commit_sha: string | null, 
+/**
+ * Why the run failed, where that could be told
+ */
+failure_reason: FailureReason | null, 
+/**
+ * The model a coding agent was told to run with; `None` leaves it to the agent
+ */
+model: string | null, started_at: string, completed_at: string | null, 
+/**
+ * Wall-clock milliseconds from start to finish, pauses included
+ */
+duration_ms: bigint | null, created_at: string, updated_at: string, };
+
+export type ProjectImportResult = { tasks_created: number, attempts_created: number, executions_created: number, 
+/**
+ * Templates whose name the project already had are skipped
+ */
+templates_created: number, };
+
+export type ConversationExportFormat = "markdown" | "json";
+
+export type ConversationExportQuery = { format: ConversationExportFormat, };
+
+export type TaskConversations = { task_id: string, title: string, description: string | null, exported_at: string, 
+/**
+ * Oldest first
+ */
+attempts: Array<AttemptConversations>, };
+
+export type AttemptConversations = { attempt_id: string, branch: string, executor: string | null, created_at: string, 
+/**
+ * The attempt's coding agent runs, its first run and then each follow-up
+ */
+runs: Array<RunConversation>, };
+
+export type RunConversation = { execution_process_id: string, status: ExecutionProcessStatus, started_at: string, completed_at: string | null, conversation: NormalizedConversation, 
+/**
+ * What the run changed, if it finished with its changes captured
+ */
+diff: Array<FileHunks> | null, };
+
+export type TaskShareLink = { id: string, task_id: string, 
+/**
+ * The token's first characters, to tell links apart
+ */
+token_prefix: string, 
+/**
+ * Who created the link, when sign-in is turned on
+ */
+created_by: string | null, expires_at: string | null, revoked_at: string | null, last_viewed_at: string | null, view_count: bigint, created_at: string, };
+
+export type CreateTaskShareLink = { 
+/**
+ * Days until the link stops working; `None` keeps it working until it's revoked
+ */
+expires_in_days: number | null, };
+
+export type CreatedTaskShareLink = { link: TaskShareLink, token: string, };
+
+export type SharedTask = { conversations: TaskConversations, 
+/**
+ * When the link stops working, if it expires
+ */
+expires_at: string | null, };
+
+export type EntryBookmark = { id: string, task_id: string, task_title: string, 
+/**
+ * The run whose conversation the entry is in
+ */
+execution_process_id: string, 
+/**
+ * The entry's id among the run's
+ */
+entry_id: string, entry: NormalizedEntry, note: string | null, 
+/**
+ * Who bookmarked the entry, when sign-in is turned on
+ */
+created_by: string | null, created_at: string, };
+
+export type CreateEntryBookmark = { execution_process_id: string, 
+/**
+ * The entry's `id` in the run's normalized conversation
+ */
+entry_id: string, note: string | null, };
+
+export type BookmarkExportTarget = "description" | "pull_request";
+
+export type ExportEntryBookmarks = { target: BookmarkExportTarget, 
+/**
+ * The bookmarks to export; `None` exports all of the task's
+ */
+bookmark_ids: Array<string> | null, };
+
+export type ExportedEntryBookmarks = { exported: number, markdown: string, 
+/**
+ * The pull request commented on
+ */
+pull_request_url: string | null, };
+
+export type CheckFileMentions = { text: string, };
+
+export type FileMentionProblem = { mention: string, problem: string, };
+
+export type SimilarTasksQuery = { title: string, description: string | null, };
+
+export type SimilarTask = { id: string, title: string, status: TaskStatus, 
+/**
+ * Cosine similarity, 0 to 1
+ */
+score: number, };
+
+export type DuplicateCandidate = { id: string, title: string, status: TaskStatus, 
+/**
+ * An agent is working on it right now
+ */
+has_in_progress_attempt: boolean, 
+/**
+ * 0 to 1
+ */
+title_similarity: number, 
+/**
+ * Cosine similarity; absent without an embeddings provider
+ */
+embedding_similarity: number | null, };
+
+export type CreatedTask = { possible_duplicates: Array<DuplicateCandidate>, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, parent_task_id: string | null, 
+/**
+ * Overrides the configured execution timeout for this task's attempts
+ */
+timeout_minutes: bigint | null, priority: TaskPriority | null, created_at: string, updated_at: string, };
+
+export type CreateTaskPullRequest = { 
+/**
+ * Defaults to the base branch of the attempt the pull request is for
+ */
+base_branch: string | null, };
+
+export type TaskOriginalText = { task_id: string, title: string, description: string | null, created_at: string, };
+
+export type TaskPrDescription = { task_id: string, 
+/**
+ * The attempt whose diff and conversation it describes
+ */
+task_attempt_id: string, title: string, body: string, created_at: string, };
+
+export type TaskAttachment = { id: string, task_id: string, file_name: string, content_type: string, size_bytes: bigint, created_at: string, };
+
+export type LabelSuggestion = { name: string, reason: string, };
+
+export type TaskLabels = { labels: Array<string>, suggestions: Array<LabelSuggestion>, };
+
+export type TaskLabelName = { name: string, };
+
+export type CustomFieldType = "text" | "number" | "boolean" | "date" | "select";
+
+export type CustomField = { project_id: string, 
+/**
+ * Lowercase letters, digits and underscores; prompt templates use the value as
+ * `{{fields.<name>}}`
+ */
+name: string, field_type: CustomFieldType, 
+/**
+ * The values a select field allows
+ */
+options: Array<string>, created_at: string, };
+
+export type UpsertCustomField = { name: string, field_type: CustomFieldType, options: Array<string>, };
+
+export type SetTaskCustomField = { name: string, 
+/**
+ * An empty value clears the field
+ */
+value: string, };
+
+export type BoardCondition = "execution_running" | "execution_failed" | "verification_failed" | "pr_open" | "merged";
+
+export type BoardColumn = { id: string, project_id: string, name: string, 
+/**
+ * Columns are shown from the lowest position up
+ */
+position: bigint, 
+/**
+ * The status a task moved into the column gets
+ */
+status: TaskStatus, 
+/**
+ * How many tasks the column holds before moving more in is refused
+ */
+wip_limit: bigint | null, 
+/**
+ * Tasks in any of these states show in the column, checked in column order
+ */
+conditions: Array<BoardCondition>, created_at: string, };
+
+export type BoardColumnInput = { 
+/**
+ * Unset for a new column
+ */
+id: string | null, name: string, status: TaskStatus, wip_limit: bigint | null, conditions: Array<BoardCondition>, };
+
+export type UpdateBoardColumns = { columns: Array<BoardColumnInput>, };
+
+export type MoveTaskToColumn = { column_id: string, };
+
+export type TaskDependency = { task_id: string, blocked_by_task_id: string, };
+
+export type DependencyTask = { id: string, title: string, status: TaskStatus, };
+
+export type TaskDependencies = { blocked_by: Array<DependencyTask>, blocks: Array<DependencyTask>, };
+
+export type TaskDependencyRequest = { blocked_by_task_id: string, };
+
+export type TaskDependencyGraph = { order: Array<string>, edges: Array<TaskDependency>, };
+
+export type PrDescription = { title: string, body: string, };
+
+export type ChangelogFormat = "markdown" | "keep_a_changelog";
+
+export type ChangelogQuery = { 
+/**
+ * RFC 3339 timestamp or `YYYY-MM-DD`, inclusive
+ */
+from: string | null, 
+/**
+ * RFC 3339 timestamp or `YYYY-MM-DD`, inclusive
+ */
+to: string | null, 
+/**
+ * Only tasks merged after this tag
+ */
+since_tag: string | null, 
+/**
+ * Heading for the keep-a-changelog format; "Unreleased" when omitted
+ */
+version: string | null, format: ChangelogFormat, };
+
+export type ChangelogSection = "added" | "changed" | "deprecated" | "removed" | "fixed" | "security";
+
+export type ChangelogEntry = { task_id: string, title: string, labels: Array<string>, section: ChangelogSection, merged_at: string, pr_url: string | null, pr_number: bigint | null, };
+
+export type Changelog = { from: string | null, to: string | null, entries: Array<ChangelogEntry>, 
+/**
+ * The entries rendered in the requested format
+ */
+content: string, };
+
+export type ReleaseStatus = "draft" | "running" | "released" | "failed";
+
+export type Release = { id: string, project_id: string, version: string, notes: string | null, status: ReleaseStatus, exit_code: bigint | null, started_at: string | null, released_at: string | null, created_at: string, updated_at: string, };
+
+export type ReleaseTask = { task_id: string, title: string, };
+
+export type ReleaseWithTasks = { release: Release, tasks: Array<ReleaseTask>, };
+
+export type CreateRelease = { version: string, notes: string | null, 
+/**
+ * Tasks to include; every merged task not yet in a release when omitted
+ */
+task_ids: Array<string> | null, };
+
+export type AttemptPlanStatus = "pending" | "approved";
+
+export type AttemptPlan = { task_attempt_id: string, 
+/**
+ * The planning run the plan came from
+ */
+execution_process_id: string, content: string, status: AttemptPlanStatus, created_at: string, approved_at: string | null, };
+
+export type PlanComparison = { planned_and_changed: Array<string>, 
+/**
+ * Named in the plan but left untouched
+ */
+planned_not_changed: Array<string>, 
+/**
+ * Changed although the plan never mentioned them
+ */
+changed_not_planned: Array<string>, };
+
+export type PlanReview = { plan: AttemptPlan, 
+/**
+ * Only once the plan is approved and implementation has started
+ */
+comparison: PlanComparison | null, };
+
+export type EstimateScope = "small" | "medium" | "large";
+
+export type RunEstimate = { estimated_tokens: number, scope: EstimateScope, likely_files: Array<string>, repo_files: number, repo_bytes: bigint, 
+/**
+ * Whether a language model refined the estimate, or only the heuristics went into it
+ */
+used_llm: boolean, 
+/**
+ * Why the estimate needs a confirmation; empty when it stays under the thresholds
+ */
+warnings: Array<string>, };
+
+export type ArtifactDir = { 
+/**
+ * Relative to the worktree root
+ */
+path: string, bytes: bigint, };
+
+export type WorktreeUsage = { attempt_id: string, task_id: string, task_title: string, branch: string, worktree_path: string, 
+/**
+ * False when the worktree directory is gone even though the attempt still points at it
+ */
+exists: boolean, total_bytes: bigint, artifact_bytes: bigint, 
+/**
+ * Largest first
+ */
+artifacts: Array<ArtifactDir>, updated_at: string, };
+
+export type ProjectStaleTask = { task_id: string, title: string, status: TaskStatus, 
+/**
+ * When the task last saw activity
+ */
+idle_since: string, 
+/**
+ * When the user was nudged about it, if they have been yet
+ */
+nudged_at: string | null, };
+
+export type MergeQueueStatus = "queued" | "rebasing" | "checking" | "merging" | "merged" | "failed";
+
+export type MergeQueueEntry = { id: string, project_id: string, task_id: string, attempt_id: string, task_title: string, base_branch: string, status: MergeQueueStatus, error: string | null, 
+/**
+ * Tail of the merge check script's output when it failed
+ */
+check_output: string | null, merge_commit: string | null, queued_at: string, finished_at: string | null, };
+
+export type TaskUsage = { task_id: string, title: string, 
+/**
+ * Runs that reported usage
+ */
+runs: bigint, input_tokens: bigint, output_tokens: bigint, 
+/**
+ * `None` if none of the runs reported cost
+ */
+cost_usd: number | null, };
+
+export type ModelUsage = { 
+/**
+ * `None` for runs that left the model to the agent
+ */
+model: string | null, runs: bigint, input_tokens: bigint, output_tokens: bigint, cost_usd: number | null, };
+
+export type ProjectUsage = { input_tokens: bigint, output_tokens: bigint, cost_usd: number | null, tasks: Array<TaskUsage>, models: Array<ModelUsage>, };
+
+export type ExecutorStats = { executor: string, runs: bigint, completed: bigint, 
+/**
+ * Runs that failed, timed out or hit a limit
+ */
+failed: bigint, 
+/**
+ * Runs stopped by the user or cut off by a restart, which count neither way towards the
+ * success rate
+ */
+killed: bigint, 
+/**
+ * Completed runs out of the finished ones that weren't killed; `None` before any
+ */
+success_rate: number | null, median_duration_ms: bigint | null, total_duration_ms: bigint, attempts: bigint, merged_attempts: bigint, 
+/**
+ * Coding agent runs after the first in an attempt
+ */
+follow_ups_per_attempt: number, };
+
+export type TaskTime = { task_id: string, title: string, agent_time_ms: bigint, runs: bigint, follow_ups: bigint, };
+
+export type ProjectAnalytics = { tasks_done: bigint, 
+/**
+ * From a task's creation to it last being moved to done
+ */
+median_time_to_done_secs: bigint | null, 
+/**
+ * Over the tasks with at least one run
+ */
+follow_ups_per_task: number | null, agent_time_ms: bigint, executors: Array<ExecutorStats>, tasks: Array<TaskTime>, };
+
+export type BenchmarkResultStatus = "running" | "passed" | "failed";
+
+export type BenchmarkCase = { id: string, suite_id: string, position: bigint, title: string, 
+/**
+ * What the coding agent is asked to do
+ */
+prompt: string, 
+/**
+ * Commands that must all pass after the agent, one per line
+ */
+verification_script: string, };
+
+export type BenchmarkSuite = { id: string, project_id: string, name: string, cases: Array<BenchmarkCase>, created_at: string, updated_at: string, };
+
+export type BenchmarkCaseInput = { title: string, prompt: string, verification_script: string, };
+
+export type UpsertBenchmarkSuite = { name: string, cases: Array<BenchmarkCaseInput>, };
+
+export type BenchmarkContestant = { executor: string | null, executor_profile_id: string | null, 
+/**
+ * The model to run the executor with, when it's not a profile
+ */
+model: string | null, };
+
+export type StartBenchmarkRun = { contestants: Array<BenchmarkContestant>, };
+
+export type BenchmarkRun = { id: string, suite_id: string, created_at: string, 
+/**
+ * Set once every result is in
+ */
+finished_at: string | null, };
+
+export type BenchmarkResult = { id: string, run_id: string, case_title: string, 
+/**
+ * The profile's name, or the executor with its model
+ */
+contestant: string, task_id: string | null, task_attempt_id: string | null, status: BenchmarkResultStatus, 
+/**
+ * Wall-clock time of the attempt's coding agent runs, follow-up fixes included
+ */
+duration_ms: bigint | null, 
+/**
+ * `None` when the agent reported no cost
+ */
+cost_usd: number | null, created_at: string, finished_at: string | null, };
+
+export type ContestantReport = { contestant: string, cases: bigint, passed: bigint, failed: bigint, running: bigint, 
+/**
+ * Passed cases out of the finished ones; `None` before any finished
+ */
+pass_rate: number | null, median_duration_ms: bigint | null, total_duration_ms: bigint, cost_usd: number | null, };
+
+export type BenchmarkReport = { run: BenchmarkRun, contestants: Array<ContestantReport>, results: Array<BenchmarkResult>, };
+
+export type PromptTemplate = { id: string, project_id: string, 
+/**
+ * Executor this template is for, such as `claude`; `None` for every executor
+ */
+executor: string | null, template: string, created_at: string, updated_at: string, };
+
+export type UpsertPromptTemplate = { executor: string | null, template: string, };
+
+export type EnvironmentVariable = { id: string, project_id: string, 
+/**
+ * Task whose executions alone get this variable; `None` for every task of the project
+ */
+task_id: string | null, key: string, 
+/**
+ * Encrypted for a secret, and left out when a secret is sent back
+ */
+value: string, is_secret: boolean, created_at: string, updated_at: string, };
+
+export type UpsertEnvironmentVariable = { key: string, value: string, is_secret: boolean, };
+
+export type ProjectMcpServer = { id: string, project_id: string, name: string, command: string, args: Array<string>, env: { [key: string]: string }, created_at: string, updated_at: string, };
+
+export type UpsertProjectMcpServer = { 
+/**
+ * Letters, digits, `-` and `_`; a server of the same name is replaced
+ */
+name: string, command: string, args: Array<string>, env: { [key: string]: string }, };
+
+export type ExecutorProfile = { id: string, project_id: string | null, name: string, 
+/**
+ * The executor it runs, such as `claude` or `jsonl:<name>`
+ */
+executor: string, 
+/**
+ * Used unless an attempt picks its own model
+ */
+model: string | null, 
+/**
+ * Flags put after the executor's CLI's own
+ */
+extra_args: Array<string>, 
+/**
+ * Variables set for its runs. Secrets belong in the project's environment variables,
+ * which are stored encrypted and redacted from logs.
+ */
+env: { [key: string]: string }, created_at: string, updated_at: string, };
+
+export type UpsertExecutorProfile = { 
+/**
+ * Letters, digits, `-` and `_`, unique among the profiles of its scope
+ */
+name: string, executor: string, model: string | null, extra_args: Array<string>, env: { [key: string]: string }, };
+
+export type AssignExecutorProfile = { executor_profile_id: string | null, };
+
+export type SetTaskExecutor = { executor: string | null, };
+
+export type ProjectSandbox = { project_id: string, enabled: boolean, 
+/**
+ * Cut the agents off from the network too
+ */
+deny_network: boolean, created_at: string, updated_at: string, };
+
+export type UpsertProjectSandbox = { enabled: boolean, deny_network: boolean, };
+
+export type ProjectSecurity = { project_id: string, 
+/**
+ * Password accounts can't reach the project until they turn on two-factor
+ * authentication. Accounts that sign in through OIDC are left to the provider.
+ */
+require_two_factor: boolean, created_at: string, updated_at: string, };
+
+export type UpsertProjectSecurity = { require_two_factor: boolean, };
+
+export type ProjectShellEnvironment = { project_id: string, login_shell: LoginShellMode, 
+/**
+ * Relative to the attempt's worktree, so it can be a file in the repository
+ */
+env_file: string | null, created_at: string, updated_at: string, };
+
+export type UpsertProjectShellEnvironment = { login_shell: LoginShellMode, env_file: string | null, };
+
+export type ProjectContainer = { project_id: string, 
+/**
+ * As `docker run` takes it, e.g. `node:20` or `ghcr.io/acme/toolchain:1.4`
+ */
+image: string, created_at: string, updated_at: string, };
+
+export type UpsertProjectContainer = { image: string, };
+
+export type ProjectWorker = { project_id: string, 
+/**
+ * The runner's address, e.g. `http://build-box:8000`
+ */
+url: string, 
+/**
+ * The directory here holding the attempts' worktrees, when the worker sees it at
+ * `remote_root` rather than at the same path
+ */
+local_root: string | null, remote_root: string | null, created_at: string, updated_at: string, };
+
+export type UpsertProjectWorker = { url: string, local_root: string | null, remote_root: string | null, };
+
+export type GuardrailHold = "budget_exceeded" | "outside_working_hours";
+
+export type ExecutionWindow = { 
+/**
+ * `HH:MM`
+ */
+start: string, 
+/**
+ * `HH:MM`, before `start` for a window running past midnight
+ */
+end: string, 
+/**
+ * Only windows starting Monday to Friday
+ */
+weekdays_only: boolean, };
+
+export type ProjectGuardrails = { project_id: string, 
+/**
+ * In US dollars, counted from the first of the month in the server's local time
+ */
+monthly_budget_usd: number | null, 
+/**
+ * Empty lets agents start any time
+ */
+allowed_windows: Array<ExecutionWindow>, created_at: string, updated_at: string, };
+
+export type UpsertProjectGuardrails = { monthly_budget_usd: number | null, allowed_windows: Array<ExecutionWindow>, };
+
+export type GuardrailStatus = { guardrails: ProjectGuardrails | null, 
+/**
+ * What the project's runs have cost since the first of the month
+ */
+spent_this_month_usd: number, hold: GuardrailHold | null, };
+
+export type GitHostKind = "github" | "gitlab" | "bitbucket";
+
+export type ProjectGitHost = { project_id: string, kind: GitHostKind, 
+/**
+ * The instance's URL, for self-hosted GitLab and GitHub Enterprise. `None` means the
+ * host the remote points at, or the cloud API for Bitbucket.
+ */
+base_url: string | null, has_token: boolean, created_at: string, updated_at: string, };
+
+export type UpsertProjectGitHost = { kind: GitHostKind, base_url: string | null, 
+/**
+ * A new token; `None` or empty keeps the one saved
+ */
+token: string | null, };
+
+export type RefreshStats = { reused: number, parsed: number, removed: number, };
+
+export type LanguageStat = { language: string, files: number, bytes: bigint, };
+
+export type RepoAnalysisSummary = { head: string | null, updated_at: string, files: number, bytes: bigint, symbols: number, 
+/**
+ * Most bytes first
+ */
+languages: Array<LanguageStat>, last_refresh: RefreshStats, };
+
+export type RepoConfig = { 
+/**
+ * Executor for attempts that don't pick one
+ */
+default_executor: string | null, 
+/**
+ * Run once in each new worktree before the coding agent
+ */
+setup_script: string | null, 
+/**
+ * Run one after another in the worktree once the coding agent finishes
+ */
+verification_commands: Array<string>, 
+/**
+ * What the coding agent is prompted with; see `services::prompt_template`
+ */
+prompt_template: string | null, 
+/**
+ * Where attempt worktrees are made, relative to the repository unless absolute
+ */
+worktree_base_dir: string | null, };
+
+export type EffectiveProjectConfig = { 
+/**
+ * Whether the repository has a `.vibe-kanban.toml`
+ */
+has_repo_config: boolean, default_executor: string | null, setup_script: string | null, verification_script: string | null, 
+/**
+ * The template for every executor; executors can still have their own on the project
+ */
+prompt_template: string | null, worktree_base_dir: string, 
+/**
+ * Settings above that come from the file rather than the project
+ */
+from_repo_config: Array<string>, };
+
+export type ArtifactCleanup = { removed: Array<ArtifactDir>, freed_bytes: bigint, };
+
 export type ProcessLogsResponse = { id: string, process_type: ExecutionProcessType, command: string, executor_type: string | null, status: ExecutionProcessStatus, normalized_conversation: NormalizedConversation, };
 
+export type LogStreamMessage = { "type": "snapshot", process: ProcessLogsResponse, } | { "type": "entries", process_id: string, entries: Array<NormalizedEntry>, } | { "type": "approval", process_id: string, approval: PendingApproval | null, };
+
+export type PendingApproval = { id: string, execution_process_id: string, command: string, 
+/**
+ * What makes the command dangerous
+ */
+reason: string, requested_at: string, };
+
+export type ApprovalDecision = { approval_id: string, allow: boolean, };
+
+export type SendExecutionInput = { content: string, };
+
 export type DiffChunkType = "Equal" | "Insert" | "Delete";
 
 export type DiffChunk = { chunk_type: DiffChunkType, content: string, };
@@ -94,17 +1422,473 @@ export type FileDiff = { path: string, chunks: Array<DiffChunk>, };
 
 export type WorktreeDiff = { files: Array<FileDiff>, };
 
-export type BranchStatus = { is_behind: boolean, commits_behind: number, commits_ahead: number, up_to_date: boolean, merged: boolean, has_uncommitted_changes: boolean, base_branch_name: string, };
+export type DiffHunk = { index: number, 
+/**
+ * The `@@ -a,b +c,d @@` line, including any function context git finds
+ */
+header: string, old_start: number, new_start: number, lines: Array<DiffChunk>, };
+
+export type FileHunks = { path: string, hunks: Array<DiffHunk>, };
+
+export type LogStream = "stdout" | "stderr";
+
+export type ExecutionDiff = { execution_process_id: string, 
+/**
+ * HEAD of the worktree when the run started
+ */
+from_commit: string, 
+/**
+ * HEAD once the run finished and its changes were committed; `None` while it runs
+ */
+to_commit: string | null, files: Array<FileHunks>, summary: DiffSummary, created_at: string, updated_at: string, };
+
+export type DiffSummary = { additions: number, deletions: number, files: Array<FileDiffSummary>, };
+
+export type FileDiffSummary = { path: string, additions: number, deletions: number, };
+
+export type ExecutionResourceSample = { 
+/**
+ * Of one core, since the previous sample; a run busy on two cores reads 200
+ */
+cpu_percent: number, rss_bytes: bigint, sampled_at: string, };
+
+export type ExecutionResources = { execution_process_id: string, peak_rss_bytes: bigint, peak_cpu_percent: number, 
+/**
+ * CPU time at the last sample; what processes that exited between samples used is
+ * missing
+ */
+cpu_seconds: number, 
+/**
+ * From start to finish, or until now while it runs
+ */
+wall_seconds: number, samples: Array<ExecutionResourceSample>, };
+
+export type HunkSelection = { path: string, 
+/**
+ * Indexes from the attempt's hunk listing; `None` selects every hunk in the file
+ */
+hunks: Array<number> | null, };
+
+export type CreateScopedFollowUp = { prompt: string, selections: Array<HunkSelection>, };
+
+export type DraftQuote = { "type": "entry", execution_process_id: string, entry_index: number, } | { "type": "hunk", path: string, hunk_index: number, };
+
+export type FollowUpDraft = { task_id: string, 
+/**
+ * The attempt the quotes were picked from
+ */
+task_attempt_id: string | null, prompt: string, quotes: Array<DraftQuote>, updated_at: string, };
+
+export type SaveFollowUpDraft = { task_attempt_id: string | null, prompt: string, quotes: Array<DraftQuote>, };
+
+export type SendFollowUpDraft = { 
+/**
+ * Hand the attempt over to this executor instead of continuing the current one's session
+ */
+executor: string | null, };
+
+export type FileChangeKind = "added" | "modified" | "deleted";
+
+export type FileTreeNode = { name: string, path: string, is_dir: boolean, change: FileChangeKind | null, 
+/**
+ * True when this node or anything beneath it has changed
+ */
+has_changes: boolean, children: Array<FileTreeNode>, };
+
+export type LineChangeKind = "added" | "modified";
+
+export type LineChange = { 
+/**
+ * 1-based line number in the returned content
+ */
+line: number, kind: LineChangeKind, };
+
+export type FileContent = { path: string, size: number, is_binary: boolean, too_large: boolean, content: string | null, line_changes: Array<LineChange>, };
+
+export type WorktreeStats = { files_changed: number, lines_added: number, lines_removed: number, };
+
+export type SharedFileComparison = { path: string, left_change: FileChangeKind, right_change: FileChangeKind, identical: boolean, left_to_right: Array<DiffChunk>, };
+
+export type WorktreeComparison = { left: WorktreeStats, right: WorktreeStats, only_left: Array<string>, only_right: Array<string>, shared: Array<SharedFileComparison>, };
+
+export type ExecutionVerification = { id: string, execution_process_id: string, command: string, passed: boolean, exit_code: bigint | null, output: string, duration_ms: bigint, created_at: string, };
+
+export type AttemptRunSummary = { attempt_id: string, executor: string | null, branch: string, merged: boolean, 
+/**
+ * Status of the most recent coding agent run
+ */
+agent_status: ExecutionProcessStatus | null, 
+/**
+ * Total wall-clock time spent in coding agent runs
+ */
+agent_duration_ms: bigint | null, 
+/**
+ * Whether every verification command passed after the latest agent run; `None` when
+ * it wasn't verified
+ */
+verification_passed: boolean | null, 
+/**
+ * The verification commands run after the latest agent run, in the order they ran
+ */
+verifications: Array<ExecutionVerification>, input_tokens: bigint, output_tokens: bigint, 
+/**
+ * `None` when none of the agent runs reported cost
+ */
+cost_usd: number | null, };
+
+export type AttemptComparison = { left: AttemptRunSummary, right: AttemptRunSummary, changes: WorktreeComparison, };
+
+export type ReviewComment = { id: string, task_attempt_id: string, 
+/**
+ * The coding agent run whose captured diff the comment is on; `None` for the
+ * attempt's diff as a whole
+ */
+execution_process_id: string | null, file_path: string, line_number: bigint, body: string, resolved: boolean, created_at: string, updated_at: string, };
+
+export type CreateReviewComment = { 
+/**
+ * Comment on this run's captured diff rather than the attempt's current one
+ */
+execution_process_id: string | null, file_path: string, line_number: bigint, body: string, };
+
+export type UpdateReviewComment = { body: string | null, resolved: boolean | null, };
+
+export type ReviewDecision = "approved" | "rejected";
+
+export type AttemptReview = { id: string, task_attempt_id: string, decision: ReviewDecision, 
+/**
+ * The reviewer's username, when sign-in is on
+ */
+reviewer: string | null, comment: string | null, created_at: string, };
+
+export type ApproveAttempt = { comment: string | null, };
+
+export type RejectAttempt = { 
+/**
+ * What's wrong with the changes
+ */
+comment: string | null, 
+/**
+ * Send the comment, and the attempt's open review comments, to the agent as a
+ * follow-up
+ */
+follow_up: boolean, };
+
+export type AttemptRejection = { review: AttemptReview, 
+/**
+ * The attempt the follow-up runs in, when one was started
+ */
+follow_up_attempt_id: string | null, };
+
+export type AttemptRollback = { id: string, task_attempt_id: string, 
+/**
+ * The run whose commit the attempt went back to
+ */
+execution_process_id: string | null, from_commit: string, to_commit: string, 
+/**
+ * The ref keeping `from_commit`, so a later rollback can return to it
+ */
+backup_ref: string, created_at: string, };
+
+export type RollbackAttempt = { 
+/**
+ * The run to go back to; the attempt's branch is reset to the commit it left
+ */
+execution_process_id: string, };
+
+export type ExecutionSnapshot = { execution_process_id: string, task_attempt_id: string, snapshot_commit: string, 
+/**
+ * The ref keeping `snapshot_commit`, which is on no branch
+ */
+snapshot_ref: string, 
+/**
+ * The commit that took the run's changes back out, once it was reverted
+ */
+revert_commit: string | null, reverted_at: string | null, created_at: string, };
+
+export type RevertExecution = { 
+/**
+ * The run whose changes to undo; what the other runs changed is kept
+ */
+execution_process_id: string, };
+
+export type ExecutionToolUsage = { execution_process_id: string, task_attempt_id: string, 
+/**
+ * Distinct files, however many times each was read
+ */
+files_read: bigint, 
+/**
+ * Distinct files, however many times each was written; zero for a run that only talked
+ */
+files_written: bigint, commands_run: bigint, searches: bigint, web_fetches: bigint, 
+/**
+ * Delegated tasks, presented plans and tools the executor doesn't classify
+ */
+other_tools: bigint, assistant_messages: bigint, };
+
+export type AttentionKind = "command_approval" | "plan_review";
+
+export type ExecutionAttention = { id: string, execution_process_id: string, task_attempt_id: string, kind: AttentionKind, title: string, message: string, requested_at: string, 
+/**
+ * When the user was notified again for taking too long to answer
+ */
+escalated_at: string | null, resolved_at: string | null, };
+
+export type TaskLinkedProject = { project_id: string, name: string, git_repo_path: string, 
+/**
+ * What attempts branch from in this project
+ */
+default_branch: string | null, };
+
+export type SetTaskLinkedProjects = { project_ids: Array<string>, };
+
+export type AttemptLinkedWorktree = { task_attempt_id: string, project_id: string, worktree_path: string, base_branch: string, created_at: string, };
+
+export type LinkedRepoDiff = { project_id: string, project_name: string, diff: WorktreeDiff, };
+
+export type AttemptGroup = { id: string, task_id: string, attempt_ids: Array<string>, 
+/**
+ * The attempt picked to carry on with; `None` until one is
+ */
+winner_attempt_id: string | null, decided_at: string | null, created_at: string, };
+
+export type CreateParallelAttempts = { 
+/**
+ * The coding agents to run, one attempt each
+ */
+executors: Array<string>, base_branch: string | null, };
+
+export type PickAttemptGroupWinner = { attempt_id: string, };
+
+export type ConversationSearchHit = { task_id: string, task_title: string, attempt_id: string, execution_process_id: string, 
+/**
+ * Index into the process's normalized conversation entries
+ */
+entry_index: number, entry_type: NormalizedEntryType, timestamp: string | null, snippet: string, score: number, };
+
+export type SearchHitKind = "task" | "conversation";
+
+export type SearchHit = { kind: SearchHitKind, project_id: string, task_id: string, task_title: string, task_status: TaskStatus, attempt_id: string | null, execution_process_id: string | null, 
+/**
+ * Index into the process's normalized conversation entries
+ */
+entry_index: bigint | null, entry_type: NormalizedEntryType | null, timestamp: string | null, snippet: string, 
+/**
+ * Higher is better
+ */
+score: number, };
+
+export type SearchQuery = { q: string, project_id: string | null, status: TaskStatus | null, executor: string | null, 
+/**
+ * RFC 3339 timestamp or `YYYY-MM-DD`, inclusive
+ */
+from: string | null, 
+/**
+ * RFC 3339 timestamp or `YYYY-MM-DD`, inclusive
+ */
+to: string | null, limit: number | null, };
+
+export type EventKind = "task_created" | "task_status_changed" | "task_deleted" | "execution_started" | "execution_completed" | "execution_failed" | "execution_stopped" | "pr_opened" | "review_approved" | "review_rejected" | "attempt_rolled_back" | "execution_reverted";
+
+export type EventActor = "user" | "system";
+
+export type Event = { id: bigint, project_id: string, task_id: string | null, task_attempt_id: string | null, execution_process_id: string | null, kind: EventKind, actor: EventActor, 
+/**
+ * What changed, e.g. the statuses a task moved between
+ */
+details: unknown, created_at: string, prev_hash: string | null, hash: string, };
+
+export type AuditVerification = { valid: boolean, checked: bigint, 
+/**
+ * The first event whose hash doesn't match its contents or the event before it
+ */
+first_invalid_event_id: bigint | null, };
+
+export type BoardEvent = { "type": "task_created", project_id: string, task_id: string, } | { "type": "task_updated", project_id: string, task_id: string, } | { "type": "task_status_changed", project_id: string, task_id: string, status: TaskStatus, } | { "type": "task_deleted", project_id: string, task_id: string, } | { "type": "execution_started", project_id: string, task_id: string, task_attempt_id: string, execution_process_id: string, process_type: ExecutionProcessType, } | { "type": "execution_finished", project_id: string, task_id: string, task_attempt_id: string, execution_process_id: string, status: ExecutionProcessStatus, } | { "type": "process_running", execution_process_id: string, } | { "type": "process_stopped", execution_process_id: string, } | { "type": "queue_changed", project_id: string, task_attempt_id: string, queued: boolean, } | { "type": "resync" };
+
+export type NotificationEvent = "attempt_succeeded" | "attempt_failed" | "approval_needed" | "task_stale";
+
+export type WebhookFormat = "slack" | "discord";
+
+export type NotificationWebhook = { id: string, project_id: string, url: string, format: WebhookFormat, enabled: boolean, attempt_succeeded: boolean, attempt_failed: boolean, approval_needed: boolean, created_at: string, updated_at: string, };
+
+export type UpsertNotificationWebhook = { url: string, format: WebhookFormat, enabled: boolean | null, attempt_succeeded: boolean | null, attempt_failed: boolean | null, approval_needed: boolean | null, };
+
+export type NotificationChannel = "desktop" | "webhook";
 
-export type ExecutionState = "NotStarted" | "SetupRunning" | "SetupComplete" | "SetupFailed" | "SetupStopped" | "CodingAgentRunning" | "CodingAgentComplete" | "CodingAgentFailed" | "CodingAgentStopped" | "Complete";
+export type NotificationDeliveryStatus = "pending" | "sent" | "failed";
 
-export type TaskAttemptState = { execution_state: ExecutionState, has_changes: boolean, has_setup_script: boolean, setup_process_id: string | null, coding_agent_process_id: string | null, };
+export type NotificationDelivery = { id: string, project_id: string, task_attempt_id: string | null, webhook_id: string | null, channel: NotificationChannel, event: NotificationEvent, title: string, message: string, status: NotificationDeliveryStatus, attempts: bigint, last_error: string | null, next_attempt_at: string | null, created_at: string, sent_at: string | null, };
 
-export type ExecutionProcess = { id: string, task_attempt_id: string, process_type: ExecutionProcessType, executor_type: string | null, status: ExecutionProcessStatus, command: string, args: string | null, working_directory: string, stdout: string | null, stderr: string | null, exit_code: bigint | null, started_at: string, completed_at: string | null, created_at: string, updated_at: string, };
+export type User = { id: string, username: string, display_name: string | null, 
+/**
+ * Admin of every project, and manages accounts and server settings
+ */
+is_admin: boolean, 
+/**
+ * Signed in through the OIDC provider rather than with a password
+ */
+is_oidc: boolean, created_at: string, updated_at: string, };
 
-export type ExecutionProcessSummary = { id: string, task_attempt_id: string, process_type: ExecutionProcessType, executor_type: string | null, status: ExecutionProcessStatus, command: string, args: string | null, working_directory: string, exit_code: bigint | null, started_at: string, completed_at: string | null, created_at: string, updated_at: string, };
+export type CreateUser = { username: string, display_name: string | null, password: string, is_admin: boolean | null, };
 
-export type ExecutionProcessStatus = "running" | "completed" | "failed" | "killed";
+export type UpdateUser = { display_name: string | null, is_admin: boolean | null, 
+/**
+ * A new password; signs the account out everywhere
+ */
+password: string | null, };
+
+export type ProjectRole = "viewer" | "member" | "admin";
+
+export type ProjectMember = { project_id: string, user_id: string, username: string, display_name: string | null, role: ProjectRole, created_at: string, };
+
+export type SetProjectMember = { username: string, role: ProjectRole, };
+
+export type ProjectMembership = { project_id: string, role: ProjectRole, };
+
+export type ProjectInvite = { id: string, project_id: string, 
+/**
+ * The token's first characters, to tell invites apart
+ */
+token_prefix: string, role: ProjectRole, invited_by: string | null, expires_at: string, accepted_by: string | null, accepted_at: string | null, revoked_at: string | null, created_at: string, };
+
+export type CreateProjectInvite = { role: ProjectRole, 
+/**
+ * Days until the invite stops working; a week when left out
+ */
+expires_in_days: number | null, };
+
+export type CreatedProjectInvite = { invite: ProjectInvite, token: string, };
+
+export type InviteDetails = { project_id: string, project_name: string, role: ProjectRole, expires_at: string, };
+
+export type AcceptProjectInvite = { username: string | null, display_name: string | null, password: string | null, };
+
+export type MentionSource = "review_comment" | "follow_up";
+
+export type Mention = { id: string, user_id: string, project_id: string, task_id: string, task_title: string, task_attempt_id: string | null, source: MentionSource, 
+/**
+ * Who wrote the mention, when sign-in is turned on
+ */
+mentioned_by: string | null, mentioned_by_username: string | null, 
+/**
+ * The start of the comment or prompt the mention is in
+ */
+excerpt: string, read_at: string | null, created_at: string, };
+
+export type PluginInfo = { 
+/**
+ * The module's file name, without its extension
+ */
+name: string, normalizer: boolean, event_hook: boolean, validator: boolean, };
+
+export type ScriptEvent = "task_created" | "attempt_finished" | "before_merge";
+
+export type ScriptHook = { id: string, project_id: string, name: string, event: ScriptEvent, script: string, enabled: boolean, last_run_at: string | null, 
+/**
+ * Why the last run failed; `None` when it succeeded
+ */
+last_error: string | null, created_at: string, updated_at: string, };
+
+export type UpsertScriptHook = { 
+/**
+ * Letters, digits, `-` and `_`; a hook of the same name is replaced
+ */
+name: string, event: ScriptEvent, script: string, enabled: boolean | null, };
+
+export type TokenScope = "create-task" | "read-logs" | "trigger-execution";
+
+export type ApiToken = { id: string, user_id: string, name: string, 
+/**
+ * The token's first characters, to tell tokens apart
+ */
+token_prefix: string, scopes: Array<TokenScope>, expires_at: string | null, last_used_at: string | null, revoked_at: string | null, created_at: string, };
+
+export type CreateApiToken = { name: string, scopes: Array<TokenScope>, 
+/**
+ * Days until the token stops working; `None` keeps it working until it's revoked
+ */
+expires_in_days: number | null, };
+
+export type CreatedApiToken = { token: ApiToken, secret: string, };
+
+export type ApiTokenUse = { id: bigint, token_id: string, method: string, path: string, status: bigint, created_at: string, };
+
+export type TwoFactorEnrollment = { secret: string, otpauth_url: string, };
+
+export type RecoveryCodes = { codes: Array<string>, };
+
+export type TwoFactorCode = { code: string, };
+
+export type BranchStatus = { is_behind: boolean, commits_behind: number, commits_ahead: number, up_to_date: boolean, merged: boolean, has_uncommitted_changes: boolean, base_branch_name: string, 
+/**
+ * Files that would conflict if the branch were rebased onto its base now
+ */
+conflicted_files: Array<string>, };
+
+export type ExecutionState = "NotStarted" | "SetupRunning" | "SetupComplete" | "SetupFailed" | "SetupStopped" | "CodingAgentRunning" | "CodingAgentComplete" | "CodingAgentFailed" | "CodingAgentStopped" | "CodingAgentRetrying" | "Complete";
+
+export type TaskAttemptState = { execution_state: ExecutionState, has_changes: boolean, has_setup_script: boolean, setup_process_id: string | null, coding_agent_process_id: string | null, 
+/**
+ * What the attempt's executor supports; `None` if it isn't known
+ */
+capabilities: ExecutorCapabilities | null, };
+
+export type ExecutionProcess = { id: string, task_attempt_id: string, process_type: ExecutionProcessType, executor_type: string | null, status: ExecutionProcessStatus, command: string, args: string | null, working_directory: string, stdout: string | null, stderr: string | null, exit_code: bigint | null, 
+/**
+ * How many times a failed start has been retried
+ */
+retry_count: bigint, 
+/**
+ * Set while a failed start waits to be retried
+ */
+retry_at: string | null, 
+/**
+ * The worktree's commit once the run finished and its changes were committed
+ */
+commit_sha: string | null, 
+/**
+ * Why the run failed, where that could be told
+ */
+failure_reason: FailureReason | null, 
+/**
+ * The model a coding agent was told to run with; `None` leaves it to the agent
+ */
+model: string | null, started_at: string, completed_at: string | null, 
+/**
+ * Wall-clock milliseconds from start to finish, pauses included
+ */
+duration_ms: bigint | null, created_at: string, updated_at: string, };
+
+export type ExecutionProcessSummary = { id: string, task_attempt_id: string, process_type: ExecutionProcessType, executor_type: string | null, status: ExecutionProcessStatus, command: string, args: string | null, working_directory: string, exit_code: bigint | null, 
+/**
+ * How many times a failed start has been retried
+ */
+retry_count: bigint, 
+/**
+ * Set while a failed start waits to be retried
+ */
+retry_at: string | null, 
+/**
+ * The worktree's commit once the run finished and its changes were committed
+ */
+commit_sha: string | null, 
+/**
+ * Why the run failed, where that could be told
+ */
+failure_reason: FailureReason | null, 
+/**
+ * The model a coding agent was told to run with; `None` leaves it to the agent
+ */
+model: string | null, started_at: string, completed_at: string | null, 
+/**
+ * Wall-clock milliseconds from start to finish, pauses included
+ */
+duration_ms: bigint | null, created_at: string, updated_at: string, };
+
+export type ExecutionProcessStatus = "running" | "completed" | "failed" | "killed" | "timedout" | "paused" | "resourcelimitexceeded" | "ratelimited" | "interrupted";
+
+export type FailureReason = "missing_binary" | "auth_failure" | "rate_limited" | "context_length_exceeded" | "git_conflict" | "network_error" | "spawn_failed";
 
 export type ExecutionProcessType = "setupscript" | "cleanupscript" | "codingagent" | "devserver";
 
@@ -112,19 +1896,93 @@ export type CreateExecutionProcess = { task_attempt_id: string, process_type: Ex
 
 export type UpdateExecutionProcess = { status: ExecutionProcessStatus | null, exit_code: bigint | null, completed_at: string | null, };
 
+export type QueueEntry = { task_attempt_id: string, task_id: string, task_title: string, project_id: string, project_name: string, priority: TaskPriority | null, executor: string | null, 
+/**
+ * Paused to make room for a more urgent attempt
+ */
+paused: boolean, queued_at: string, 
+/**
+ * Why its project's guardrails hold it back, if they do; filled in by
+ * `services::guardrails`, not stored
+ */
+hold: GuardrailHold | null, };
+
+export type ReorderQueue = { 
+/**
+ * Queued attempts in the order they should go first; the rest keep their order after
+ */
+task_attempt_ids: Array<string>, };
+
+export type DevServerPort = { execution_process_id: string, port: bigint, created_at: string, };
+
 export type ExecutorSession = { id: string, task_attempt_id: string, execution_process_id: string, session_id: string | null, prompt: string | null, summary: string | null, created_at: string, updated_at: string, };
 
 export type CreateExecutorSession = { task_attempt_id: string, execution_process_id: string, prompt: string | null, };
 
 export type UpdateExecutorSession = { session_id: string | null, prompt: string | null, summary: string | null, };
 
-export type NormalizedConversation = { entries: Array<NormalizedEntry>, session_id: string | null, executor_type: string, prompt: string | null, summary: string | null, };
-
-export type NormalizedEntry = { timestamp: string | null, entry_type: NormalizedEntryType, content: string, };
-
-export type NormalizedEntryType = { "type": "user_message" } | { "type": "assistant_message" } | { "type": "tool_use", tool_name: string, action_type: ActionType, } | { "type": "system_message" } | { "type": "error_message" } | { "type": "thinking" };
-
-export type ActionType = { "action": "file_read", path: string, } | { "action": "file_write", path: string, } | { "action": "command_run", command: string, } | { "action": "search", query: string, } | { "action": "web_fetch", url: string, } | { "action": "task_create", description: string, } | { "action": "plan_presentation", plan: string, } | { "action": "other", description: string, };
+export type NormalizedConversation = { entries: Array<NormalizedEntry>, session_id: string | null, executor_type: string, prompt: string | null, summary: string | null, 
+/**
+ * What the run changed, once it has finished
+ */
+diff_summary: DiffSummary | null, };
+
+export type NormalizedEntry = { 
+/**
+ * Identifies the entry among its run's, however often the run's output is normalized
+ * again: where it came from and its place there. `None` for entries not from a run.
+ */
+id: string | null, 
+/**
+ * When the output the entry came from was received, RFC 3339
+ */
+timestamp: string | null, 
+/**
+ * For a tool call, how long from its entry to the one after it, which is how long the
+ * agent spent on it
+ */
+duration_ms: number | null, entry_type: NormalizedEntryType, content: string, };
+
+export type NormalizedEntryType = { "type": "user_message" } | { "type": "assistant_message" } | { "type": "tool_use", tool_name: string, action_type: ActionType, } | { "type": "system_message" } | { "type": "error_message" } | { "type": "thinking" } | { "type": "attachment", attachment_id: string, content_type: string, } | { "type": "plan_entry" } | { "type": "verification", command: string, passed: boolean, 
+/**
+ * `None` when the command was stopped or couldn't start
+ */
+exit_code: number | null, duration_ms: number, } | { "type": "file_edit", path: string, 
+/**
+ * Unified diff of the change
+ */
+diff: string, };
+
+export type ActionType = { "action": "file_read", path: string, 
+/**
+ * Filled in once the conversation is normalized
+ */
+location: FileLocation | null, } | { "action": "file_write", path: string, 
+/**
+ * Filled in once the conversation is normalized
+ */
+location: FileLocation | null, } | { "action": "command_run", command: string, } | { "action": "search", query: string, } | { "action": "web_fetch", url: string, } | { "action": "task_create", description: string, } | { "action": "plan_presentation", plan: string, } | { "action": "other", description: string, };
+
+export type FileLocation = { absolute_path: string, 
+/**
+ * Whether the file is still there; it may have been deleted or renamed since
+ */
+exists: boolean, 
+/**
+ * The file on the project's git host at the execution's commit, for files in the
+ * worktree of a repository with a known host
+ */
+url: string | null, };
+
+export type TruncatedContent = { 
+/**
+ * Length of the full content in bytes
+ */
+original_bytes: number, 
+/**
+ * The content looked like binary data, so none of it was kept
+ */
+binary: boolean, };
 
 // Generated constants
 export const EXECUTOR_TYPES: string[] = [
@@ -139,6 +1997,7 @@ export const EXECUTOR_TYPES: string[] = [
     "aider",
     "codex",
     "aaa",
+    "goose",
 ];
 
 export const EDITOR_TYPES: EditorType[] = [
@@ -161,7 +2020,8 @@ export const EXECUTOR_LABELS: Record<string, string> = {
     "sst-opencode": "SST Opencode",
     "aider": "Aider",
     "codex": "Codex",
-    "aaa": "AAA (Assistant Agent)"
+    "aaa": "AAA (Assistant Agent)",
+    "goose": "Goose"
 };
 
 export const EDITOR_LABELS: Record<string, string> = {
@@ -183,6 +2043,22 @@ export const MCP_SUPPORTED_EXECUTORS: string[] = [
     "aaa"
 ];
 
+export const MODEL_SUPPORTED_EXECUTORS: string[] = [
+    "claude",
+    "claude-plan",
+    "gemini",
+    "sst-opencode",
+    "aider",
+    "codex",
+    "goose"
+];
+
+export const CONTEXT_REPLAY_EXECUTORS: string[] = [
+    "gemini",
+    "charm-opencode",
+    "aaa"
+];
+
 export const SOUND_FILES: SoundFile[] = [
     "abstract-sound1",
     "abstract-sound2",